@@ -0,0 +1,80 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::str::FromStr;
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_semver::npm::NpmPackageReqReference;
+use deno_semver::package::PackageReq;
+
+use super::ConfigFileExt;
+use super::Section;
+
+impl ConfigFileExt {
+  /// The npm package requirements of the `"allowScripts"` array, whose
+  /// install scripts are run when a matching version is set up in a local
+  /// node_modules directory.
+  pub fn to_allow_scripts(&self) -> Result<Vec<PackageReq>, AnyError> {
+    let Some(section) = self.section(|json| json.allow_scripts.as_ref()) else {
+      return Ok(Vec::new());
+    };
+    parse_allow_scripts(&section).with_context(|| {
+      format!("Invalid allowScripts in '{}'", section.display())
+    })
+  }
+}
+
+fn parse_allow_scripts(section: &Section) -> Result<Vec<PackageReq>, AnyError> {
+  let allow_scripts: Vec<String> = section.deserialize()?;
+  allow_scripts
+    .iter()
+    .map(|entry| {
+      let req_ref = NpmPackageReqReference::from_str(entry).with_context(|| {
+        format!(
+          "Invalid \"allowScripts\" entry \"{}\". Use an npm specifier like \"npm:{}\".",
+          entry, entry
+        )
+      })?;
+      let req = req_ref.into_inner().req;
+      if let Some(tag) = req.version_req.tag() {
+        bail!(
+          "Invalid \"allowScripts\" entry \"{}\". Use a version range instead of the tag \"{}\".",
+          entry,
+          tag
+        );
+      }
+      Ok(req)
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::args::config_file_ext::test::ext;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_to_allow_scripts() {
+    assert_eq!(
+      ext(r#"{ "allowScripts": ["npm:esbuild", "npm:@scope/pkg@1"] }"#)
+        .to_allow_scripts()
+        .unwrap(),
+      vec![
+        PackageReq::from_str("esbuild").unwrap(),
+        PackageReq::from_str("@scope/pkg@1").unwrap()
+      ]
+    );
+    assert_eq!(
+      ext(r#"{}"#).to_allow_scripts().unwrap(),
+      Vec::<PackageReq>::new()
+    );
+    assert!(ext(r#"{ "allowScripts": ["esbuild"] }"#)
+      .to_allow_scripts()
+      .is_err());
+    assert!(ext(r#"{ "allowScripts": ["npm:esbuild@latest"] }"#)
+      .to_allow_scripts()
+      .is_err());
+  }
+}
@@ -0,0 +1,107 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use super::ConfigFileExt;
+use super::Section;
+use crate::auth_tokens::AuthTokenHelper;
+
+impl ConfigFileExt {
+  /// The credential helpers of the `"auth": { "helpers": ... }` object, which
+  /// are run in the directory of the configuration file.
+  pub fn to_auth_helpers(&self) -> Result<Vec<AuthTokenHelper>, AnyError> {
+    let Some(section) = self.section(|json| json.auth.as_ref()) else {
+      return Ok(Vec::new());
+    };
+    parse_auth_helpers(&section).with_context(|| {
+      format!("Invalid auth configuration in '{}'", section.display())
+    })
+  }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+struct SerializedAuthConfig {
+  #[serde(default)]
+  helpers: IndexMap<String, SerializedAuthHelperCommand>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum SerializedAuthHelperCommand {
+  Executable(String),
+  Command(Vec<String>),
+}
+
+fn parse_auth_helpers(
+  section: &Section,
+) -> Result<Vec<AuthTokenHelper>, AnyError> {
+  let auth: SerializedAuthConfig = section.deserialize()?;
+  let config_dir = section.dir();
+  let mut helpers = Vec::with_capacity(auth.helpers.len());
+  for (host, command) in auth.helpers {
+    let command = match command {
+      SerializedAuthHelperCommand::Executable(executable) => vec![executable],
+      SerializedAuthHelperCommand::Command(command) => command,
+    };
+    if command.is_empty() {
+      bail!(
+        "The credential helper of \"{}\" has an empty command.",
+        host
+      );
+    }
+    helpers
+      .push(AuthTokenHelper::new(&host, command).with_cwd(config_dir.clone()));
+  }
+  Ok(helpers)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::args::config_file_ext::test::ext;
+  use pretty_assertions::assert_eq;
+  use std::path::PathBuf;
+
+  #[test]
+  fn test_to_auth_helpers() {
+    let config_dir = PathBuf::from("/project");
+    assert_eq!(
+      ext(
+        r#"{
+          "auth": {
+            "helpers": {
+              "npm.pkg.github.com": ["gh", "auth", "token"],
+              "deno.example.com": "./get-token.sh"
+            }
+          }
+        }"#
+      )
+      .to_auth_helpers()
+      .unwrap(),
+      vec![
+        AuthTokenHelper::new(
+          "npm.pkg.github.com",
+          vec!["gh".to_string(), "auth".to_string(), "token".to_string()],
+        )
+        .with_cwd(config_dir.clone()),
+        AuthTokenHelper::new(
+          "deno.example.com",
+          vec!["./get-token.sh".to_string()],
+        )
+        .with_cwd(config_dir),
+      ]
+    );
+    assert_eq!(ext(r#"{ "auth": {} }"#).to_auth_helpers().unwrap(), vec![]);
+    assert!(ext(r#"{ "auth": { "helpers": { "deno.land": [] } } }"#)
+      .to_auth_helpers()
+      .is_err());
+    assert!(ext(r#"{ "auth": { "tokens": {} } }"#)
+      .to_auth_helpers()
+      .is_err());
+  }
+}
@@ -0,0 +1,78 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use serde::Deserialize;
+
+use super::ConfigFileExt;
+
+impl ConfigFileExt {
+  /// The URL and whether the remote cache is read only of the
+  /// `"cache": { "remote": ... }` object.
+  pub fn to_remote_cache(&self) -> Result<Option<(String, bool)>, AnyError> {
+    let Some(section) = self
+      .section(|json| json.cache.as_ref())
+      .and_then(|cache| cache.get("remote"))
+    else {
+      return Ok(None);
+    };
+    let remote: SerializedRemoteCache =
+      section.deserialize().with_context(|| {
+        format!("Invalid remote cache in '{}'", section.display())
+      })?;
+    Ok(Some(match remote {
+      SerializedRemoteCache::Url(url) => (url, false),
+      SerializedRemoteCache::Object(remote) => (remote.url, remote.read_only),
+    }))
+  }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum SerializedRemoteCache {
+  Url(String),
+  Object(SerializedRemoteCacheObject),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+struct SerializedRemoteCacheObject {
+  url: String,
+  #[serde(default)]
+  read_only: bool,
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::args::config_file_ext::test::ext;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_to_remote_cache() {
+    assert_eq!(
+      ext(r#"{ "cache": { "remote": "https://cache.example.com/deno" } }"#)
+        .to_remote_cache()
+        .unwrap(),
+      Some(("https://cache.example.com/deno".to_string(), false))
+    );
+    assert_eq!(
+      ext(
+        r#"{
+          "cache": {
+            "remote": { "url": "https://cache.example.com", "readOnly": true }
+          }
+        }"#
+      )
+      .to_remote_cache()
+      .unwrap(),
+      Some(("https://cache.example.com".to_string(), true))
+    );
+    assert_eq!(ext(r#"{ "cache": {} }"#).to_remote_cache().unwrap(), None);
+    assert!(ext(
+      r#"{ "cache": { "remote": { "url": "https://a", "token": "t" } } }"#
+    )
+    .to_remote_cache()
+    .is_err());
+  }
+}
@@ -0,0 +1,94 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use serde::Deserialize;
+
+use super::ConfigFileExt;
+use super::Section;
+use crate::args::CoverageThreshold;
+
+impl ConfigFileExt {
+  /// The `"coverage": { "threshold": { ... } }` object.
+  pub fn to_coverage_threshold(
+    &self,
+  ) -> Result<Option<CoverageThreshold>, AnyError> {
+    let Some(section) = self
+      .section(|json| json.coverage.as_ref())
+      .and_then(|coverage| coverage.get("threshold"))
+    else {
+      return Ok(None);
+    };
+    parse_coverage_threshold(&section)
+      .map(Some)
+      .with_context(|| {
+        format!("Invalid coverage threshold in '{}'", section.display())
+      })
+  }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct SerializedCoverageThreshold {
+  lines: Option<u8>,
+  branches: Option<u8>,
+  functions: Option<u8>,
+}
+
+fn parse_coverage_threshold(
+  section: &Section,
+) -> Result<CoverageThreshold, AnyError> {
+  let threshold: SerializedCoverageThreshold = section.deserialize()?;
+  for percent in [threshold.lines, threshold.branches, threshold.functions]
+    .into_iter()
+    .flatten()
+  {
+    if percent > 100 {
+      bail!("Coverage percentage {} is above 100.", percent);
+    }
+  }
+  Ok(CoverageThreshold {
+    lines: threshold.lines,
+    branches: threshold.branches,
+    functions: threshold.functions,
+  })
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::args::config_file_ext::test::ext;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_to_coverage_threshold() {
+    assert_eq!(
+      ext(
+        r#"{
+          // comments are allowed
+          "coverage": { "threshold": { "lines": 90, "branches": 80 } }
+        }"#
+      )
+      .to_coverage_threshold()
+      .unwrap(),
+      Some(CoverageThreshold {
+        lines: Some(90),
+        branches: Some(80),
+        functions: None,
+      })
+    );
+    assert_eq!(
+      ext(r#"{ "tasks": {} }"#).to_coverage_threshold().unwrap(),
+      None
+    );
+    assert!(ext(r#"{ "coverage": { "threshold": { "lines": 101 } } }"#)
+      .to_coverage_threshold()
+      .is_err());
+    assert!(
+      ext(r#"{ "coverage": { "threshold": { "statements": 50 } } }"#)
+        .to_coverage_threshold()
+        .is_err()
+    );
+  }
+}
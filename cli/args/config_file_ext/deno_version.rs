@@ -0,0 +1,96 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_semver::Version;
+use deno_semver::VersionReq;
+
+use super::parse_exact_version;
+use super::ConfigFileExt;
+
+impl ConfigFileExt {
+  /// The `"minDenoVersion"`, which is the oldest version of Deno the project
+  /// supports, like `"1.40.0"`.
+  pub fn to_min_deno_version(&self) -> Result<Option<Version>, AnyError> {
+    let Some(section) = self.section(|json| json.min_deno_version.as_ref())
+    else {
+      return Ok(None);
+    };
+    parse_exact_version(&section, "minDenoVersion", "1.40.0")
+      .map(Some)
+      .with_context(|| {
+        format!("Invalid minDenoVersion in '{}'", section.display())
+      })
+  }
+
+  /// The `"deno"` version requirement, which is the range of the versions of
+  /// Deno the project runs on, like `">=1.44 <2"`, along with the path of the
+  /// configuration file which sets it.
+  pub fn to_deno_version_req(
+    &self,
+  ) -> Result<Option<(String, VersionReq, String)>, AnyError> {
+    let Some(section) = self.section(|json| json.deno.as_ref()) else {
+      return Ok(None);
+    };
+    let parse = || -> Result<_, AnyError> {
+      let version_req: String = section.deserialize()?;
+      let parsed = VersionReq::parse_from_npm(&version_req).with_context(|| {
+        format!(
+          "Invalid \"deno\" version requirement \"{}\". Use a range like \">=1.44 <2\".",
+          version_req
+        )
+      })?;
+      Ok((version_req, parsed))
+    };
+    let (version_req, parsed) = parse().with_context(|| {
+      format!(
+        "Invalid deno version requirement in '{}'",
+        section.display()
+      )
+    })?;
+    Ok(Some((version_req, parsed, section.display())))
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::args::config_file_ext::test::ext;
+  use crate::args::satisfies_deno_version_req;
+  use pretty_assertions::assert_eq;
+  use std::path::PathBuf;
+
+  #[test]
+  fn test_to_min_deno_version() {
+    assert_eq!(
+      ext(r#"{ "minDenoVersion": "1.40.0" }"#)
+        .to_min_deno_version()
+        .unwrap(),
+      Some(Version::parse_standard("1.40.0").unwrap())
+    );
+    assert_eq!(ext(r#"{}"#).to_min_deno_version().unwrap(), None);
+    assert!(ext(r#"{ "minDenoVersion": ">=1.40" }"#)
+      .to_min_deno_version()
+      .is_err());
+  }
+  #[test]
+  fn test_to_deno_version_req() {
+    let (text, version_req, path) = ext(r#"{ "deno": ">=1.0 <100" }"#)
+      .to_deno_version_req()
+      .unwrap()
+      .unwrap();
+    assert_eq!(text, ">=1.0 <100");
+    assert_eq!(
+      path,
+      PathBuf::from("/project/deno.json").display().to_string()
+    );
+    assert!(satisfies_deno_version_req(&version_req));
+    let (_, version_req, _) = ext(r#"{ "deno": "<1" }"#)
+      .to_deno_version_req()
+      .unwrap()
+      .unwrap();
+    assert!(!satisfies_deno_version_req(&version_req));
+    assert!(ext(r#"{}"#).to_deno_version_req().unwrap().is_none());
+    assert!(ext(r#"{ "deno": 1 }"#).to_deno_version_req().is_err());
+  }
+}
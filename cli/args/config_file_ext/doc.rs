@@ -0,0 +1,112 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use serde::Deserialize;
+
+use super::ConfigFileExt;
+use crate::args::DocHtmlConfig;
+use crate::args::DocLintRulesConfig;
+
+impl ConfigFileExt {
+  /// The `"doc": { "lint": { "rules": { ... } } }` object.
+  pub fn to_doc_lint_rules(
+    &self,
+  ) -> Result<Option<DocLintRulesConfig>, AnyError> {
+    let Some(section) = self
+      .section(|json| json.doc.as_ref())
+      .and_then(|doc| doc.get("lint"))
+      .and_then(|lint| lint.get("rules"))
+    else {
+      return Ok(None);
+    };
+    section.deserialize().map(Some).with_context(|| {
+      format!("Invalid doc lint rules in '{}'", section.display())
+    })
+  }
+
+  /// The `"doc": { "html": { ... } }` object, whose paths are relative to the
+  /// configuration file.
+  pub fn to_doc_html_config(&self) -> Result<Option<DocHtmlConfig>, AnyError> {
+    let Some(section) = self
+      .section(|json| json.doc.as_ref())
+      .and_then(|doc| doc.get("html"))
+    else {
+      return Ok(None);
+    };
+    let html: SerializedDocHtmlConfig =
+      section.deserialize().with_context(|| {
+        format!("Invalid doc html options in '{}'", section.display())
+      })?;
+    let config_dir = section.dir();
+    Ok(Some(DocHtmlConfig {
+      logo: html.logo.map(|p| config_dir.join(p)),
+      stylesheet: html.stylesheet.map(|p| config_dir.join(p)),
+    }))
+  }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct SerializedDocHtmlConfig {
+  logo: Option<String>,
+  stylesheet: Option<String>,
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::args::config_file_ext::test::ext;
+  use pretty_assertions::assert_eq;
+  use std::path::Path;
+
+  #[test]
+  fn test_to_doc_lint_rules() {
+    assert_eq!(
+      ext(
+        r#"{
+          "doc": {
+            "lint": {
+              "rules": {
+                "include": ["missing-example"],
+                "exclude": ["private-type-ref"]
+              }
+            }
+          }
+        }"#
+      )
+      .to_doc_lint_rules()
+      .unwrap(),
+      Some(DocLintRulesConfig {
+        include: vec!["missing-example".to_string()],
+        exclude: vec!["private-type-ref".to_string()],
+      })
+    );
+    assert_eq!(
+      ext(r#"{ "doc": { "lint": {} } }"#)
+        .to_doc_lint_rules()
+        .unwrap(),
+      None
+    );
+    assert!(ext(r#"{ "doc": { "lint": { "rules": { "tags": [] } } } }"#)
+      .to_doc_lint_rules()
+      .is_err());
+  }
+  #[test]
+  fn test_to_doc_html_config() {
+    let config_dir = Path::new("/project");
+    assert_eq!(
+      ext(r#"{ "doc": { "html": { "logo": "./logo.svg" } } }"#)
+        .to_doc_html_config()
+        .unwrap(),
+      Some(DocHtmlConfig {
+        logo: Some(config_dir.join("./logo.svg")),
+        stylesheet: None,
+      })
+    );
+    assert_eq!(ext(r#"{ "doc": {} }"#).to_doc_html_config().unwrap(), None);
+    assert!(ext(r#"{ "doc": { "html": { "theme": "dark" } } }"#)
+      .to_doc_html_config()
+      .is_err());
+  }
+}
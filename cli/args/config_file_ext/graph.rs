@@ -0,0 +1,99 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use serde::Deserialize;
+
+use super::ConfigFileExt;
+use super::Section;
+use crate::args::GraphBudget;
+
+impl ConfigFileExt {
+  /// The `"graph": { "budget": { ... } }` object.
+  pub fn to_graph_budget(&self) -> Result<Option<GraphBudget>, AnyError> {
+    let Some(section) = self
+      .section(|json| json.graph.as_ref())
+      .and_then(|graph| graph.get("budget"))
+    else {
+      return Ok(None);
+    };
+    parse_graph_budget(&section).map(Some).with_context(|| {
+      format!("Invalid graph budget in '{}'", section.display())
+    })
+  }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+struct SerializedGraphBudget {
+  max_total_bytes: Option<u64>,
+  max_dependencies: Option<usize>,
+  banned: Vec<String>,
+}
+
+fn parse_graph_budget(section: &Section) -> Result<GraphBudget, AnyError> {
+  let budget: SerializedGraphBudget = section.deserialize()?;
+  let banned = budget
+    .banned
+    .iter()
+    .map(|pattern| {
+      glob::Pattern::new(pattern)
+        .with_context(|| format!("Invalid banned pattern \"{}\"", pattern))
+    })
+    .collect::<Result<Vec<_>, _>>()?;
+  Ok(GraphBudget {
+    max_total_bytes: budget.max_total_bytes,
+    max_dependencies: budget.max_dependencies,
+    banned,
+  })
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::args::config_file_ext::test::ext;
+  use deno_core::ModuleSpecifier;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_to_graph_budget() {
+    let budget = ext(
+      r#"{
+        "graph": {
+          "budget": {
+            "maxTotalBytes": 1000,
+            "banned": ["npm:left-pad", "https://esm.sh/*"]
+          }
+        }
+      }"#,
+    )
+    .to_graph_budget()
+    .unwrap()
+    .unwrap();
+    assert_eq!(budget.max_total_bytes, Some(1000));
+    assert_eq!(budget.max_dependencies, None);
+    let banned_pattern = |specifier: &str| {
+      budget
+        .banned_pattern(&ModuleSpecifier::parse(specifier).unwrap())
+        .map(|p| p.to_string())
+    };
+    assert_eq!(
+      banned_pattern("npm:left-pad@1.3.0/index.js"),
+      Some("npm:left-pad".to_string())
+    );
+    assert_eq!(
+      banned_pattern("https://esm.sh/preact@10/hooks"),
+      Some("https://esm.sh/*".to_string())
+    );
+    assert_eq!(banned_pattern("npm:left-pad-extra@1"), None);
+    assert_eq!(banned_pattern("https://deno.land/std/mod.ts"), None);
+
+    assert!(ext(r#"{ "graph": {} }"#)
+      .to_graph_budget()
+      .unwrap()
+      .is_none());
+    assert!(ext(r#"{ "graph": { "budget": { "maxBytes": 1 } } }"#)
+      .to_graph_budget()
+      .is_err());
+  }
+}
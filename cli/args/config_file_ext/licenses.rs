@@ -0,0 +1,49 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use serde::Deserialize;
+
+use super::ConfigFileExt;
+
+impl ConfigFileExt {
+  /// The `"licenses": { "allow": [...] }` array, which `deno info --licenses`
+  /// checks the dependencies against.
+  pub fn to_license_allowlist(&self) -> Result<Option<Vec<String>>, AnyError> {
+    let Some(section) = self.section(|json| json.licenses.as_ref()) else {
+      return Ok(None);
+    };
+    let licenses: SerializedLicenses =
+      section.deserialize().with_context(|| {
+        format!("Invalid licenses in '{}'", section.display())
+      })?;
+    Ok(licenses.allow)
+  }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct SerializedLicenses {
+  allow: Option<Vec<String>>,
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::args::config_file_ext::test::ext;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_to_license_allowlist() {
+    assert_eq!(
+      ext(r#"{ "licenses": { "allow": ["MIT", "Apache-2.0"] } }"#)
+        .to_license_allowlist()
+        .unwrap(),
+      Some(vec!["MIT".to_string(), "Apache-2.0".to_string()])
+    );
+    assert_eq!(ext(r#"{}"#).to_license_allowlist().unwrap(), None);
+    assert!(ext(r#"{ "licenses": { "deny": ["GPL-3.0"] } }"#)
+      .to_license_allowlist()
+      .is_err());
+  }
+}
@@ -0,0 +1,343 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+mod allow_scripts;
+mod auth;
+mod cache;
+mod coverage;
+mod deno_version;
+mod doc;
+mod graph;
+mod licenses;
+mod permissions;
+mod proxy;
+mod tls;
+mod typescript;
+
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+
+use deno_config::ConfigFile;
+use deno_config::ConfigFlag;
+use deno_core::anyhow::anyhow;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::serde_json::Value;
+use deno_core::url::Url;
+use deno_semver::Version;
+use serde::Deserialize;
+
+use crate::util::path::specifier_to_file_path;
+
+pub use self::proxy::ConfigProxy;
+
+/// The sections of a configuration file which `deno_config` doesn't know
+/// about. Like the sections of `deno_config::ConfigFileJson`, each of them is
+/// only deserialized when it's used, so an invalid section only fails the
+/// subcommands which use it.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ConfigFileExtJson {
+  pub coverage: Option<Value>,
+  pub graph: Option<Value>,
+  pub doc: Option<Value>,
+  pub licenses: Option<Value>,
+  pub allow_scripts: Option<Value>,
+  pub typescript_version: Option<Value>,
+  pub min_deno_version: Option<Value>,
+  pub deno: Option<Value>,
+  pub permissions: Option<Value>,
+  pub auth: Option<Value>,
+  pub tls: Option<Value>,
+  pub proxy: Option<Value>,
+  pub cache: Option<Value>,
+}
+
+/// The sections of a configuration file which `deno_config` doesn't know
+/// about, which are read once along with the `ConfigFile`. A member of a
+/// workspace inherits the sections it doesn't set from the workspace root,
+/// whose relative paths stay relative to the root.
+#[derive(Clone, Debug)]
+pub struct ConfigFileExt {
+  pub specifier: Url,
+  pub json: ConfigFileExtJson,
+  maybe_workspace_root: Option<Box<ConfigFileExt>>,
+}
+
+/// A section of a configuration file along with the configuration file which
+/// sets it, which its relative paths are resolved against.
+struct Section<'a> {
+  value: &'a Value,
+  specifier: &'a Url,
+}
+
+impl<'a> Section<'a> {
+  fn get(&self, key: &str) -> Option<Section<'a>> {
+    Some(Section {
+      value: self.value.get(key)?,
+      specifier: self.specifier,
+    })
+  }
+
+  fn deserialize<T: serde::de::DeserializeOwned>(&self) -> Result<T, AnyError> {
+    Ok(serde_json::from_value(self.value.clone())?)
+  }
+
+  fn dir(&self) -> PathBuf {
+    let path = self.specifier.to_file_path().unwrap_or_default();
+    path.parent().map(Path::to_path_buf).unwrap_or(path)
+  }
+
+  fn display(&self) -> String {
+    display_specifier(self.specifier)
+  }
+}
+
+/// Parses a configuration file along with the sections of it which
+/// `deno_config` doesn't know about from the same text.
+pub fn parse_config_file(
+  text: &str,
+  specifier: Url,
+) -> Result<(ConfigFile, ConfigFileExt), AnyError> {
+  let config_file = ConfigFile::new(text, specifier.clone())?;
+  let config_file_ext = ConfigFileExt::new(text, specifier)?;
+  Ok((config_file, config_file_ext))
+}
+
+/// Like `ConfigFile::read`, but also parses the sections which `deno_config`
+/// doesn't know about, so the file is only read once.
+pub fn read_config_file(
+  config_path: &Path,
+) -> Result<(ConfigFile, ConfigFileExt), AnyError> {
+  debug_assert!(config_path.is_absolute());
+  let specifier = Url::from_file_path(config_path).map_err(|_| {
+    anyhow!(
+      "Could not convert config file path to specifier. Path: {}",
+      config_path.display()
+    )
+  })?;
+  read_config_file_from_path(specifier, config_path)
+}
+
+/// Like `ConfigFile::from_specifier`, but also parses the sections which
+/// `deno_config` doesn't know about, so the file is only read once.
+pub fn read_config_file_from_specifier(
+  specifier: Url,
+) -> Result<(ConfigFile, ConfigFileExt), AnyError> {
+  let config_path = specifier_to_file_path(&specifier).with_context(|| {
+    format!("Invalid config file path for '{}'.", specifier)
+  })?;
+  read_config_file_from_path(specifier, &config_path)
+}
+
+fn read_config_file_from_path(
+  specifier: Url,
+  config_path: &Path,
+) -> Result<(ConfigFile, ConfigFileExt), AnyError> {
+  let text = std::fs::read_to_string(config_path)
+    .with_context(|| format!("Error reading config file '{}'.", specifier))?;
+  parse_config_file(&text, specifier)
+}
+
+/// Like `ConfigFile::discover`, but reads the configuration file with
+/// `read_config_file`.
+pub fn discover_config_file(
+  config_flag: &ConfigFlag,
+  maybe_config_path_args: Option<Vec<PathBuf>>,
+  cwd: &Path,
+) -> Result<Option<(ConfigFile, ConfigFileExt)>, AnyError> {
+  match config_flag {
+    ConfigFlag::Disabled => Ok(None),
+    ConfigFlag::Path(config_path) => {
+      let config_path = PathBuf::from(config_path);
+      let config_path = if config_path.is_absolute() {
+        config_path
+      } else {
+        cwd.join(config_path)
+      };
+      Ok(Some(read_config_file(&config_path)?))
+    }
+    ConfigFlag::Discover => {
+      if let Some(config_path_args) = maybe_config_path_args {
+        let mut checked = HashSet::new();
+        for f in config_path_args {
+          if let Some(config_file) =
+            discover_config_file_from(&f, &mut checked)?
+          {
+            return Ok(Some(config_file));
+          }
+        }
+        // From CWD walk up to root looking for deno.json or deno.jsonc
+        discover_config_file_from(cwd, &mut checked)
+      } else {
+        Ok(None)
+      }
+    }
+  }
+}
+
+/// Like `ConfigFile::discover_from`, but reads the configuration file with
+/// `read_config_file`.
+pub fn discover_config_file_from(
+  start: &Path,
+  checked: &mut HashSet<PathBuf>,
+) -> Result<Option<(ConfigFile, ConfigFileExt)>, AnyError> {
+  fn is_skippable_err(e: &AnyError) -> bool {
+    if let Some(ioerr) = e.downcast_ref::<std::io::Error>() {
+      use std::io::ErrorKind::*;
+      match ioerr.kind() {
+        InvalidInput | PermissionDenied | NotFound => true,
+        _ => {
+          const NOT_A_DIRECTORY: i32 = 20;
+          cfg!(unix) && ioerr.raw_os_error() == Some(NOT_A_DIRECTORY)
+        }
+      }
+    } else {
+      false
+    }
+  }
+
+  let start = if start.is_absolute() {
+    Cow::Borrowed(start)
+  } else {
+    Cow::Owned(std::env::current_dir()?.join(start))
+  };
+  for ancestor in start.ancestors() {
+    if checked.insert(ancestor.to_path_buf()) {
+      for config_filename in ["deno.json", "deno.jsonc"] {
+        let f = ancestor.join(config_filename);
+        match read_config_file(&f) {
+          Ok(config_file) => {
+            log::debug!("Config file found at '{}'", f.display());
+            return Ok(Some(config_file));
+          }
+          Err(e) if is_skippable_err(&e) => {}
+          Err(e) => return Err(e),
+        }
+      }
+    }
+  }
+  Ok(None)
+}
+
+fn display_specifier(specifier: &Url) -> String {
+  match specifier.to_file_path() {
+    Ok(path) => path.display().to_string(),
+    Err(()) => specifier.to_string(),
+  }
+}
+
+impl ConfigFileExt {
+  pub fn new(text: &str, specifier: Url) -> Result<Self, AnyError> {
+    let json =
+      match jsonc_parser::parse_to_serde_value(text, &Default::default())? {
+        Some(value @ Value::Object(_)) => serde_json::from_value(value)?,
+        _ => ConfigFileExtJson::default(),
+      };
+    Ok(Self {
+      specifier,
+      json,
+      maybe_workspace_root: None,
+    })
+  }
+
+  /// Sets the sections of the configuration file of the workspace root,
+  /// which the configuration file of a member inherits.
+  pub fn set_workspace_root(&mut self, workspace_root: ConfigFileExt) {
+    self.maybe_workspace_root = Some(Box::new(workspace_root));
+  }
+
+  fn section(
+    &self,
+    get: fn(&ConfigFileExtJson) -> Option<&Value>,
+  ) -> Option<Section> {
+    match get(&self.json) {
+      Some(value) => Some(Section {
+        value,
+        specifier: &self.specifier,
+      }),
+      None => self.maybe_workspace_root.as_ref()?.section(get),
+    }
+  }
+}
+
+fn parse_exact_version(
+  section: &Section,
+  key: &str,
+  example: &str,
+) -> Result<Version, AnyError> {
+  let version: String = section.deserialize()?;
+  Version::parse_standard(&version).with_context(|| {
+    format!(
+      "Invalid \"{}\" \"{}\". Use an exact version like \"{}\".",
+      key, version, example
+    )
+  })
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::args::DocHtmlConfig;
+  use deno_semver::package::PackageReq;
+  use pretty_assertions::assert_eq;
+  use std::str::FromStr;
+
+  pub(super) fn ext(text: &str) -> ConfigFileExt {
+    ConfigFileExt::new(text, Url::parse("file:///project/deno.json").unwrap())
+      .unwrap()
+  }
+
+  #[test]
+  fn test_parse_config_file() {
+    let (config_file, config_file_ext) = parse_config_file(
+      r#"{ "lock": false, "licenses": { "allow": ["MIT"] } }"#,
+      Url::parse("file:///project/deno.json").unwrap(),
+    )
+    .unwrap();
+    assert_eq!(config_file.json.lock, Some(Value::Bool(false)));
+    assert_eq!(
+      config_file_ext.to_license_allowlist().unwrap(),
+      Some(vec!["MIT".to_string()])
+    );
+    assert!(parse_config_file(
+      "[]",
+      Url::parse("file:///project/deno.json").unwrap()
+    )
+    .is_err());
+  }
+
+  #[test]
+  fn test_workspace_root_sections() {
+    let mut member = ConfigFileExt::new(
+      r#"{ "tls": { "clientCerts": {} } }"#,
+      Url::parse("file:///project/member/deno.json").unwrap(),
+    )
+    .unwrap();
+    member.set_workspace_root(ext(
+      r#"{
+        "allowScripts": ["npm:esbuild"],
+        "tls": {
+          "clientCerts": { "corp.com": { "cert": "a.crt", "key": "a.key" } }
+        },
+        "doc": { "html": { "logo": "./logo.svg" } }
+      }"#,
+    ));
+    // the member inherits the sections it doesn't set, whose paths stay
+    // relative to the workspace root
+    assert_eq!(
+      member.to_allow_scripts().unwrap(),
+      vec![PackageReq::from_str("esbuild").unwrap()]
+    );
+    assert_eq!(member.to_client_certs().unwrap(), vec![]);
+    assert_eq!(
+      member.to_doc_html_config().unwrap(),
+      Some(DocHtmlConfig {
+        logo: Some(Path::new("/project").join("./logo.svg")),
+        stylesheet: None,
+      })
+    );
+  }
+}
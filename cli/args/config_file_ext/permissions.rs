@@ -0,0 +1,94 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+
+use super::display_specifier;
+use super::ConfigFileExt;
+use super::Section;
+use crate::args::PermissionSet;
+
+impl ConfigFileExt {
+  /// A permission set of the `"permissions"` object, which maps the names of
+  /// the sets to the permissions they grant, like
+  /// `{ "serve": { "net": true, "read": ["./static"] } }`, whose paths are
+  /// relative to the configuration file.
+  pub fn to_permission_set(
+    &self,
+    name: &str,
+  ) -> Result<PermissionSet, AnyError> {
+    let Some(section) = self.section(|json| json.permissions.as_ref()) else {
+      bail!(
+        "There is no \"permissions\" object in '{}'.",
+        display_specifier(&self.specifier)
+      );
+    };
+    parse_permission_set(&section, name).with_context(|| {
+      format!("Invalid permissions in '{}'", section.display())
+    })
+  }
+}
+
+fn parse_permission_set(
+  section: &Section,
+  name: &str,
+) -> Result<PermissionSet, AnyError> {
+  let Some(set) = section.value.get(name) else {
+    let names = section
+      .value
+      .as_object()
+      .map(|sets| sets.keys().map(|name| format!("\"{name}\"")).collect())
+      .unwrap_or_else(Vec::new);
+    bail!(
+      "Unknown permission set \"{}\". Use one of {}.",
+      name,
+      names.join(", ")
+    );
+  };
+  PermissionSet::parse(section.dir(), set.clone())
+    .with_context(|| format!("Invalid permission set \"{}\"", name))
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::args::config_file_ext::test::ext;
+  use deno_runtime::permissions::PermissionsOptions;
+  use pretty_assertions::assert_eq;
+  use std::path::PathBuf;
+
+  #[test]
+  fn test_to_permission_set() {
+    let text = r#"{
+      // the permissions of the tasks
+      "permissions": {
+        "serve": { "net": true, "read": ["./static"] },
+        "test": { "env": ["AWS_*"], "hrtime": true },
+        "invalid": { "disk": true }
+      }
+    }"#;
+    let set = ext(text).to_permission_set("serve").unwrap();
+    let mut options = PermissionsOptions::default();
+    set.merge_into(&mut options);
+    assert_eq!(options.allow_net, Some(vec![]));
+    assert_eq!(
+      options.allow_read,
+      Some(vec![PathBuf::from("/project/static")])
+    );
+    assert_eq!(options.allow_env, None);
+
+    let set = ext(text).to_permission_set("test").unwrap();
+    let mut options = PermissionsOptions::default();
+    set.merge_into(&mut options);
+    assert_eq!(options.allow_env, None);
+    assert_eq!(options.allow_env_prefixes, vec!["AWS_".to_string()]);
+    assert!(options.allow_hrtime);
+
+    let err = format!("{:#}", ext(text).to_permission_set("dev").unwrap_err());
+    assert!(err.contains("Unknown permission set \"dev\". Use one of"));
+    assert!(err.contains("\"serve\""));
+    assert!(ext(text).to_permission_set("invalid").is_err());
+    assert!(ext("{}").to_permission_set("serve").is_err());
+  }
+}
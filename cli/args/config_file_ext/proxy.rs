@@ -0,0 +1,116 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::url::Url;
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use super::ConfigFileExt;
+use super::Section;
+use crate::proxy::HostPattern;
+use crate::proxy::ProxyTarget;
+
+impl ConfigFileExt {
+  /// The proxy rules of the hosts and the URL of the proxy auto-config file
+  /// of the `"proxy": { ... }` object, where the URL is relative to the
+  /// configuration file.
+  pub fn to_proxy_config(&self) -> Result<Option<ConfigProxy>, AnyError> {
+    let Some(section) = self.section(|json| json.proxy.as_ref()) else {
+      return Ok(None);
+    };
+    parse_proxy(&section).map(Some).with_context(|| {
+      format!("Invalid proxy configuration in '{}'", section.display())
+    })
+  }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+struct SerializedProxyConfig {
+  #[serde(default)]
+  hosts: IndexMap<String, String>,
+  pac: Option<String>,
+}
+
+/// The proxy rules of the `"proxy": { ... }` object of a configuration file.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ConfigProxy {
+  pub hosts: Vec<(HostPattern, ProxyTarget)>,
+  pub maybe_pac_url: Option<Url>,
+}
+
+fn parse_proxy(section: &Section) -> Result<ConfigProxy, AnyError> {
+  let proxy: SerializedProxyConfig = section.deserialize()?;
+  let mut hosts = Vec::with_capacity(proxy.hosts.len());
+  for (host, target) in proxy.hosts {
+    hosts.push((HostPattern::parse(&host)?, ProxyTarget::parse(&target)?));
+  }
+  let maybe_pac_url =
+    match proxy.pac {
+      Some(pac) => Some(section.specifier.join(&pac).with_context(|| {
+        format!("Invalid proxy auto-config URL \"{}\"", pac)
+      })?),
+      None => None,
+    };
+  Ok(ConfigProxy {
+    hosts,
+    maybe_pac_url,
+  })
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::args::config_file_ext::test::ext;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_to_proxy_config() {
+    assert_eq!(
+      ext(
+        r#"{
+          "proxy": {
+            "hosts": {
+              "jsr.io": "http://jsr-proxy:3128",
+              "*.corp.com": "DIRECT"
+            },
+            "pac": "./proxy.pac"
+          }
+        }"#
+      )
+      .to_proxy_config()
+      .unwrap(),
+      Some(ConfigProxy {
+        hosts: vec![
+          (
+            HostPattern::parse("jsr.io").unwrap(),
+            ProxyTarget::parse("http://jsr-proxy:3128").unwrap(),
+          ),
+          (
+            HostPattern::parse("*.corp.com").unwrap(),
+            ProxyTarget::Direct,
+          ),
+        ],
+        maybe_pac_url: Some(Url::parse("file:///project/proxy.pac").unwrap()),
+      })
+    );
+    assert_eq!(
+      ext(r#"{ "proxy": { "pac": "http://wpad.corp.com/wpad.dat" } }"#)
+        .to_proxy_config()
+        .unwrap(),
+      Some(ConfigProxy {
+        hosts: vec![],
+        maybe_pac_url: Some(
+          Url::parse("http://wpad.corp.com/wpad.dat").unwrap()
+        ),
+      })
+    );
+    assert_eq!(ext(r#"{ "lint": {} }"#).to_proxy_config().unwrap(), None);
+    assert!(
+      ext(r#"{ "proxy": { "hosts": { "jsr.io": "ftp://proxy" } } }"#)
+        .to_proxy_config()
+        .is_err()
+    );
+  }
+}
@@ -0,0 +1,97 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::path::PathBuf;
+
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::normalize_path;
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use super::ConfigFileExt;
+use super::Section;
+use crate::proxy::HostPattern;
+
+impl ConfigFileExt {
+  /// The hosts along with the paths of the certificate chain and the private
+  /// key of the `"tls": { "clientCerts": ... }` object, where the paths are
+  /// relative to the configuration file.
+  pub fn to_client_certs(
+    &self,
+  ) -> Result<Vec<(HostPattern, PathBuf, PathBuf)>, AnyError> {
+    let Some(section) = self.section(|json| json.tls.as_ref()) else {
+      return Ok(Vec::new());
+    };
+    parse_client_certs(&section).with_context(|| {
+      format!("Invalid TLS configuration in '{}'", section.display())
+    })
+  }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+struct SerializedTlsConfig {
+  #[serde(default)]
+  client_certs: IndexMap<String, SerializedClientCert>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SerializedClientCert {
+  cert: String,
+  key: String,
+}
+
+fn parse_client_certs(
+  section: &Section,
+) -> Result<Vec<(HostPattern, PathBuf, PathBuf)>, AnyError> {
+  let tls: SerializedTlsConfig = section.deserialize()?;
+  let config_dir = section.dir();
+  let mut client_certs = Vec::with_capacity(tls.client_certs.len());
+  for (host, client_cert) in tls.client_certs {
+    client_certs.push((
+      HostPattern::parse(&host)?,
+      normalize_path(config_dir.join(client_cert.cert)),
+      normalize_path(config_dir.join(client_cert.key)),
+    ));
+  }
+  Ok(client_certs)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::args::config_file_ext::test::ext;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_to_client_certs() {
+    assert_eq!(
+      ext(
+        r#"{
+          "tls": {
+            "clientCerts": {
+              "registry.corp.com": {
+                "cert": "./certs/client.crt",
+                "key": "../keys/client.key"
+              }
+            }
+          }
+        }"#
+      )
+      .to_client_certs()
+      .unwrap(),
+      vec![(
+        HostPattern::parse("registry.corp.com").unwrap(),
+        PathBuf::from("/project/certs/client.crt"),
+        PathBuf::from("/keys/client.key"),
+      )]
+    );
+    assert_eq!(ext(r#"{ "lint": {} }"#).to_client_certs().unwrap(), vec![]);
+    assert!(ext(
+      r#"{ "tls": { "clientCerts": { "corp.com": { "cert": "a.crt" } } } }"#
+    )
+    .to_client_certs()
+    .is_err());
+  }
+}
@@ -0,0 +1,45 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_semver::Version;
+
+use super::parse_exact_version;
+use super::ConfigFileExt;
+
+impl ConfigFileExt {
+  /// The `"typescriptVersion"`, which is an exact version of the `typescript`
+  /// npm package like `"5.4.5"`.
+  pub fn to_typescript_version(&self) -> Result<Option<Version>, AnyError> {
+    let Some(section) = self.section(|json| json.typescript_version.as_ref())
+    else {
+      return Ok(None);
+    };
+    parse_exact_version(&section, "typescriptVersion", "5.4.5")
+      .map(Some)
+      .with_context(|| {
+        format!("Invalid typescriptVersion in '{}'", section.display())
+      })
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::args::config_file_ext::test::ext;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_to_typescript_version() {
+    assert_eq!(
+      ext(r#"{ "typescriptVersion": "5.4.5" }"#)
+        .to_typescript_version()
+        .unwrap(),
+      Some(Version::parse_standard("5.4.5").unwrap())
+    );
+    assert_eq!(ext(r#"{}"#).to_typescript_version().unwrap(), None);
+    assert!(ext(r#"{ "typescriptVersion": "^5.4.0" }"#)
+      .to_typescript_version()
+      .is_err());
+  }
+}
@@ -2,9 +2,11 @@
 
 use clap::builder::styling::AnsiColor;
 use clap::builder::FalseyValueParser;
+use clap::builder::PossibleValuesParser;
 use clap::value_parser;
 use clap::Arg;
 use clap::ArgAction;
+use clap::ArgGroup;
 use clap::ArgMatches;
 use clap::ColorChoice;
 use clap::Command;
@@ -35,6 +37,91 @@ pub struct FileFlags {
   pub include: Vec<String>,
 }
 
+/// The severity of an npm advisory. `deno audit --level` fails when there
+/// are vulnerabilities of the level or above.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd)]
+pub enum AuditSeverity {
+  Info,
+  #[default]
+  Low,
+  Moderate,
+  High,
+  Critical,
+}
+
+impl AuditSeverity {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::Info => "info",
+      Self::Low => "low",
+      Self::Moderate => "moderate",
+      Self::High => "high",
+      Self::Critical => "critical",
+    }
+  }
+}
+
+impl FromStr for AuditSeverity {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "info" => Ok(Self::Info),
+      "low" => Ok(Self::Low),
+      "moderate" => Ok(Self::Moderate),
+      "high" => Ok(Self::High),
+      "critical" => Ok(Self::Critical),
+      _ => Err(format!("Unknown severity: {}", s)),
+    }
+  }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AuditFlags {
+  pub json: bool,
+  pub level: AuditSeverity,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LockFlags {
+  pub verify: bool,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TypesFlags {
+  /// The C header to generate the `Deno.dlopen()` symbols of, instead of
+  /// printing the declarations of the runtime.
+  pub ffi: Option<String>,
+  /// The bindings which have to be the ones generated from the header.
+  pub check: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct OutdatedFlags {
+  pub json: bool,
+  pub update: bool,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AddFlags {
+  pub packages: Vec<String>,
+  pub dev: bool,
+  pub cache: bool,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RemoveFlags {
+  pub packages: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum BenchReporterConfig {
+  #[default]
+  Pretty,
+  Json,
+  Junit,
+}
+
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct BenchFlags {
   pub files: FileFlags,
@@ -42,6 +129,10 @@ pub struct BenchFlags {
   pub json: bool,
   pub no_run: bool,
   pub watch: Option<WatchFlags>,
+  pub reporter: BenchReporterConfig,
+  pub baseline: Option<String>,
+  /// The regression threshold in basis points (hundredths of a percent).
+  pub regression_threshold_bps: Option<u32>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -49,26 +140,66 @@ pub struct BundleFlags {
   pub source_file: String,
   pub out_file: Option<String>,
   pub watch: Option<WatchFlags>,
+  pub minify: bool,
+  pub source_map: bool,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct CacheFlags {
   pub files: Vec<String>,
+  pub prune: Option<CachePruneFlags>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CachePruneFlags {
+  /// Evict the entries which weren't used for longer, in seconds.
+  pub max_age: Option<u64>,
+  /// Evict the least recently used entries until the cache is smaller, in
+  /// bytes.
+  pub max_size: Option<u64>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct CheckFlags {
   pub files: Vec<String>,
+  /// Type-check the exports of all the members of the workspace.
+  pub workspace: bool,
+  /// The file to write a trace of the type checking to.
+  pub trace: Option<String>,
 }
 
+/// The targets `deno compile` can cross-compile to.
+pub const COMPILE_TARGETS: [&str; 4] = [
+  "x86_64-unknown-linux-gnu",
+  "x86_64-pc-windows-msvc",
+  "x86_64-apple-darwin",
+  "aarch64-apple-darwin",
+];
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct CompileFlags {
   pub source_file: String,
   pub output: Option<PathBuf>,
   pub args: Vec<String>,
+  /// A target of `COMPILE_TARGETS` or "all" for every target.
   pub target: Option<String>,
   pub no_terminal: bool,
   pub include: Vec<String>,
+  pub list_targets: bool,
+  /// The identity to sign the executable with, using `codesign` for macOS
+  /// targets and `signtool` for Windows targets.
+  pub sign_identity: Option<String>,
+  /// A program which signs the executable instead, which is run with the
+  /// `sign_args` and the path of the executable as its last argument.
+  pub sign_command: Option<String>,
+  pub sign_args: Vec<String>,
+  pub icon: Option<PathBuf>,
+  pub version_info: Option<String>,
+  /// Excludes the source maps to reduce the size of the executable.
+  pub strip: bool,
+  /// Embeds the source maps explicitly, which is the default unless
+  /// `strip` is set, so that stack traces point at the original files.
+  pub include_source_maps: bool,
 }
 
 impl CompileFlags {
@@ -92,6 +223,50 @@ pub enum CoverageType {
   Detailed,
   Lcov,
   Html,
+  Cobertura,
+  JsonSummary,
+}
+
+/// The minimum coverage percentages, which fail the command when they aren't
+/// met.
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct CoverageThreshold {
+  pub lines: Option<u8>,
+  pub branches: Option<u8>,
+  pub functions: Option<u8>,
+}
+
+impl FromStr for CoverageThreshold {
+  type Err = String;
+
+  /// Parse a threshold like `lines:90,branches:80`.
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let mut threshold = CoverageThreshold::default();
+    for part in s.split(',') {
+      let (kind, percent) = part.split_once(':').ok_or_else(|| {
+        format!(
+          "Invalid coverage threshold \"{part}\", expected <kind>:<percent>"
+        )
+      })?;
+      let percent = percent
+        .trim()
+        .parse::<u8>()
+        .ok()
+        .filter(|p| *p <= 100)
+        .ok_or_else(|| format!("Invalid coverage percentage \"{percent}\""))?;
+      match kind.trim() {
+        "lines" => threshold.lines = Some(percent),
+        "branches" => threshold.branches = Some(percent),
+        "functions" => threshold.functions = Some(percent),
+        kind => {
+          return Err(format!(
+            "Unknown coverage threshold \"{kind}\", expected lines, branches or functions"
+          ))
+        }
+      }
+    }
+    Ok(threshold)
+  }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
@@ -101,12 +276,15 @@ pub struct CoverageFlags {
   pub include: Vec<String>,
   pub exclude: Vec<String>,
   pub r#type: CoverageType,
+  pub threshold: Option<CoverageThreshold>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum DocSourceFileFlag {
   Builtin,
   Paths(Vec<String>),
+  /// The exports of the members of the workspace.
+  Workspace,
 }
 
 impl Default for DocSourceFileFlag {
@@ -121,12 +299,19 @@ pub struct DocHtmlFlag {
   pub output: PathBuf,
 }
 
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DocMarkdownFlag {
+  pub name: Option<String>,
+  pub output: PathBuf,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct DocFlags {
   pub private: bool,
   pub json: bool,
   pub lint: bool,
   pub html: Option<DocHtmlFlag>,
+  pub markdown: Option<DocMarkdownFlag>,
   pub source_files: DocSourceFileFlag,
   pub filter: Option<String>,
 }
@@ -147,6 +332,7 @@ pub struct FmtFlags {
   pub single_quote: Option<bool>,
   pub prose_wrap: Option<String>,
   pub no_semicolons: Option<bool>,
+  pub organize_imports: bool,
   pub watch: Option<WatchFlags>,
 }
 
@@ -160,11 +346,22 @@ impl FmtFlags {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct InitFlags {
   pub dir: Option<String>,
+  /// The name of a built-in template, or a `jsr:` or `https:` URL of a
+  /// community template.
+  pub template: Option<String>,
+  /// Adds a GitHub Actions workflow.
+  pub ci: bool,
+  /// Uses the defaults instead of prompting.
+  pub yes: bool,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct InfoFlags {
   pub json: bool,
+  pub dot: bool,
+  /// A module graph saved with `deno info --json`, to compare against.
+  pub diff: Option<String>,
+  pub licenses: bool,
   pub file: Option<String>,
 }
 
@@ -177,6 +374,12 @@ pub struct InstallFlags {
   pub force: bool,
 }
 
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LspFlags {
+  pub log_file: Option<PathBuf>,
+  pub otlp_endpoint: Option<Url>,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct JupyterFlags {
   pub install: bool,
@@ -236,6 +439,19 @@ impl RunFlags {
   }
 }
 
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ServeFlags {
+  pub script: String,
+  pub watch: Option<WatchFlagsWithPaths>,
+  pub port: u16,
+  pub host: String,
+  /// Serve an HTML error overlay when the handler throws.
+  pub dev: bool,
+  /// The template of the links of the error overlay which open a file in an
+  /// editor, like `vscode://file{path}:{line}:{column}`.
+  pub editor_url: Option<String>,
+}
+
 #[derive(Clone, Default, Debug, Eq, PartialEq)]
 pub struct WatchFlags {
   pub hmr: bool,
@@ -253,6 +469,9 @@ pub struct WatchFlagsWithPaths {
 pub struct TaskFlags {
   pub cwd: Option<String>,
   pub task: Option<String>,
+  pub recursive: bool,
+  pub filter: Option<String>,
+  pub no_watch: bool,
 }
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
@@ -264,11 +483,36 @@ pub enum TestReporterConfig {
   Tap,
 }
 
+/// A 1-based shard of the test modules, parsed from `--shard=<index>/<count>`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TestShard {
+  pub index: usize,
+  pub count: usize,
+}
+
+impl FromStr for TestShard {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let invalid = || format!("Invalid shard \"{s}\", expected <index>/<count>");
+    let (index, count) = s.split_once('/').ok_or_else(invalid)?;
+    let index = index.trim().parse::<usize>().map_err(|_| invalid())?;
+    let count = count.trim().parse::<usize>().map_err(|_| invalid())?;
+    if count == 0 || index == 0 || index > count {
+      return Err(format!(
+        "Invalid shard \"{s}\", the index must be between 1 and the shard count"
+      ));
+    }
+    Ok(TestShard { index, count })
+  }
+}
+
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct TestFlags {
   pub doc: bool,
   pub no_run: bool,
   pub coverage_dir: Option<String>,
+  pub coverage_threshold: Option<CoverageThreshold>,
   pub fail_fast: Option<NonZeroUsize>,
   pub files: FileFlags,
   pub allow_none: bool,
@@ -279,15 +523,32 @@ pub struct TestFlags {
   pub watch: Option<WatchFlags>,
   pub reporter: TestReporterConfig,
   pub junit_path: Option<String>,
+  pub shard: Option<TestShard>,
+  pub shard_timings: Option<String>,
+  pub retry: Option<usize>,
+  pub update_snapshots: bool,
+  pub changed: Option<String>,
+}
+
+/// The channel of the releases which `deno upgrade` installs.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum UpgradeChannel {
+  #[default]
+  Stable,
+  Rc,
+  Canary,
+  Lts,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct UpgradeFlags {
   pub dry_run: bool,
   pub force: bool,
-  pub canary: bool,
+  pub channel: UpgradeChannel,
   pub version: Option<String>,
   pub output: Option<PathBuf>,
+  pub rollback: bool,
+  pub archive: Option<PathBuf>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -300,12 +561,29 @@ pub struct VendorFlags {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct PublishFlags {
   pub token: Option<String>,
+  /// The URL of a jsr compatible registry to publish to instead of the
+  /// default registry.
+  pub registry: Option<Url>,
   pub dry_run: bool,
   pub no_zap: bool,
+  /// Bumps the versions of the packages in their config files before
+  /// publishing.
+  pub bump: Option<VersionBump>,
+  /// Commits the bumped versions and tags the commit.
+  pub git: bool,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum VersionBump {
+  Patch,
+  Minor,
+  Major,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum DenoSubcommand {
+  Add(AddFlags),
+  Audit(AuditFlags),
   Bench(BenchFlags),
   Bundle(BundleFlags),
   Cache(CacheFlags),
@@ -321,13 +599,17 @@ pub enum DenoSubcommand {
   Install(InstallFlags),
   Jupyter(JupyterFlags),
   Uninstall(UninstallFlags),
-  Lsp,
+  Lsp(LspFlags),
   Lint(LintFlags),
+  Lock(LockFlags),
+  Outdated(OutdatedFlags),
+  Remove(RemoveFlags),
   Repl(ReplFlags),
   Run(RunFlags),
+  Serve(ServeFlags),
   Task(TaskFlags),
   Test(TestFlags),
-  Types,
+  Types(TypesFlags),
   Upgrade(UpgradeFlags),
   Vendor(VendorFlags),
   Publish(PublishFlags),
@@ -346,7 +628,7 @@ impl DenoSubcommand {
         | Self::Jupyter(_)
         | Self::Repl(_)
         | Self::Bench(_)
-        | Self::Lsp
+        | Self::Lsp(_)
     )
   }
 }
@@ -361,6 +643,18 @@ impl Default for DenoSubcommand {
   }
 }
 
+/// How the npm packages are set up in a local node_modules directory.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum NodeModulesDirMode {
+  /// Copy the files of the packages out of the global npm cache.
+  #[default]
+  Copy,
+  /// Hard link the files of the packages to the global npm cache like the
+  /// store of pnpm, which falls back to copying them on file systems that
+  /// don't support hard links.
+  Linked,
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum TypeCheckMode {
   /// Type-check all modules.
@@ -415,6 +709,7 @@ pub struct UnstableConfig {
   pub bare_node_builtins: bool,  // --unstable-bare-node-builts
   pub byonm: bool,
   pub sloppy_imports: bool,
+  pub test_snapshot: bool,
   pub features: Vec<String>, // --unstabe-kv --unstable-cron
 }
 
@@ -442,6 +737,13 @@ pub struct Flags {
   pub deny_sys: Option<Vec<String>>,
   pub allow_write: Option<Vec<PathBuf>>,
   pub deny_write: Option<Vec<PathBuf>>,
+  /// The file with the permissions which are granted at startup and where
+  /// the permissions granted in prompts are saved.
+  pub permissions_file: Option<String>,
+  /// The name of a set of the `"permissions"` of the configuration file.
+  pub permission_set: Option<String>,
+  /// The JSONL file where every check of a permission is recorded.
+  pub permission_log: Option<String>,
   pub ca_stores: Option<Vec<String>>,
   pub ca_data: Option<CaData>,
   pub cache_blocklist: Vec<String>,
@@ -452,13 +754,24 @@ pub struct Flags {
   pub type_check_mode: TypeCheckMode,
   pub config_flag: ConfigFlag,
   pub node_modules_dir: Option<bool>,
+  pub node_modules_dir_mode: NodeModulesDirMode,
   pub vendor: Option<bool>,
+  /// The number of times a failed download is retried.
+  pub download_retries: Option<u32>,
   pub enable_op_summary_metrics: bool,
   pub enable_testing_features: bool,
   pub ext: Option<String>,
+  /// Exclude the files ignored by the `.gitignore` files from the files of
+  /// the subcommand.
+  pub gitignore: bool,
+  /// Include the files ignored by the `.gitignore` files in the files of the
+  /// subcommands which exclude them by default.
+  pub no_gitignore: bool,
+  /// The max number of remote modules to download at the same time.
+  pub fetch_concurrency: Option<NonZeroUsize>,
   pub ignore: Vec<PathBuf>,
   pub import_map_path: Option<String>,
-  pub env_file: Option<String>,
+  pub env_file: Option<Vec<String>>,
   pub inspect_brk: Option<SocketAddr>,
   pub inspect_wait: Option<SocketAddr>,
   pub inspect: Option<SocketAddr>,
@@ -470,6 +783,8 @@ pub struct Flags {
   pub no_lock: bool,
   pub no_npm: bool,
   pub no_prompt: bool,
+  /// Only use cached data and fail fast instead of accessing the network.
+  pub offline: bool,
   pub reload: bool,
   pub seed: Option<u64>,
   pub strace_ops: Option<Vec<String>>,
@@ -673,6 +988,18 @@ impl Flags {
       args.push("--deny-hrtime".to_string());
     }
 
+    if let Some(permissions_file) = &self.permissions_file {
+      args.push(format!("--permissions-file={permissions_file}"));
+    }
+
+    if let Some(permission_set) = &self.permission_set {
+      args.push(format!("--permission-set={permission_set}"));
+    }
+
+    if let Some(permission_log) = &self.permission_log {
+      args.push(format!("--permission-log={permission_log}"));
+    }
+
     args
   }
 
@@ -691,7 +1018,7 @@ impl Flags {
       Lint(LintFlags { files, .. }) => {
         Some(files.include.iter().map(|p| current_dir.join(p)).collect())
       }
-      Run(RunFlags { script, .. }) => {
+      Run(RunFlags { script, .. }) | Serve(ServeFlags { script, .. }) => {
         if let Ok(module_specifier) = resolve_url_or_path(script, current_dir) {
           if module_specifier.scheme() == "file"
             || module_specifier.scheme() == "npm"
@@ -734,7 +1061,7 @@ impl Flags {
     use DenoSubcommand::*;
 
     match &self.subcommand {
-      Run(RunFlags { script, .. }) => {
+      Run(RunFlags { script, .. }) | Serve(ServeFlags { script, .. }) => {
         let module_specifier = resolve_url_or_path(script, current_dir).ok()?;
         if module_specifier.scheme() == "file" {
           let p = module_specifier
@@ -755,13 +1082,12 @@ impl Flags {
           .to_file_path()
           .ok()
       }
-      Task(_) | Check(_) | Coverage(_) | Cache(_) | Info(_) | Eval(_)
-      | Test(_) | Bench(_) | Repl(_) | Compile(_) => {
-        std::env::current_dir().ok()
-      }
+      Add(_) | Audit(_) | Task(_) | Check(_) | Coverage(_) | Cache(_)
+      | Info(_) | Lock(_) | Outdated(_) | Remove(_) | Eval(_) | Test(_)
+      | Bench(_) | Repl(_) | Compile(_) => std::env::current_dir().ok(),
       Bundle(_) | Completions(_) | Doc(_) | Fmt(_) | Init(_) | Install(_)
-      | Uninstall(_) | Jupyter(_) | Lsp | Lint(_) | Types | Upgrade(_)
-      | Vendor(_) | Publish(_) => None,
+      | Uninstall(_) | Jupyter(_) | Lsp(_) | Lint(_) | Types(_)
+      | Upgrade(_) | Vendor(_) | Publish(_) => None,
     }
   }
 
@@ -783,6 +1109,8 @@ impl Flags {
       || self.deny_sys.is_some()
       || self.allow_write.is_some()
       || self.deny_write.is_some()
+      || self.permissions_file.is_some()
+      || self.permission_set.is_some()
   }
 
   pub fn has_permission_in_argv(&self) -> bool {
@@ -804,6 +1132,9 @@ impl Flags {
         || arg.starts_with("--deny-sys")
         || arg.starts_with("--allow-write")
         || arg.starts_with("--deny-write")
+        || arg.starts_with("--permissions-file")
+        || arg.starts_with("--permission-set")
+        || arg.starts_with("--permission-log")
     })
   }
 }
@@ -815,6 +1146,12 @@ static ENV_VARIABLES_HELP: &str = color_print::cstr!(
                          private repositories
                          (e.g. "abcde12345@deno.land;54321edcba@github.com")
 
+    <g>DENO_AUTH_HELPERS</>    A semi-colon separated list of hostnames and the
+                         commands which print their auth tokens, which are
+                         used for the hosts without a token instead of the
+                         "auth.helpers" of the configuration file
+                         (e.g. "npm.pkg.github.com=gh auth token")
+
     <g>DENO_TLS_CA_STORE</>    Comma-separated list of order dependent certificate
                          stores. Possible values: "system", "mozilla".
                          Defaults to "mozilla".
@@ -828,7 +1165,11 @@ static ENV_VARIABLES_HELP: &str = color_print::cstr!(
 
     <g>DENO_REPL_HISTORY</>    Set REPL history file path
                          History file is disabled when the value is empty
-                         (defaults to $DENO_DIR/deno_history.txt)
+                         (defaults to $DENO_DIR/repl_history/ per project
+                         with a config file, or $DENO_DIR/deno_history.txt)
+
+    <g>DENO_REPL_HISTORY_SIZE</> Set the max number of entries of the REPL history
+                         (defaults to 100)
 
     <g>DENO_NO_PACKAGE_JSON</> Disables auto-resolution of package.json
 
@@ -838,13 +1179,31 @@ static ENV_VARIABLES_HELP: &str = color_print::cstr!(
     <g>DENO_NO_UPDATE_CHECK</> Set to disable checking if a newer Deno version is
                          available
 
+    <g>DENO_REMOTE_CACHE_URL</>
+                         URL of a cache of remote modules, npm tarballs and
+                         type checking results shared between machines,
+                         which is read with GET and written with PUT
+                         requests or a file URL of a directory
+                         (overrides "cache.remote" of deno.json)
+
+    <g>DENO_REMOTE_CACHE_TOKEN</>
+                         Bearer token to use for the remote cache
+
+    <g>DENO_REMOTE_CACHE_S3_REGION</>
+                         Set to sign the requests to the remote cache for S3
+                         in this region with AWS_ACCESS_KEY_ID,
+                         AWS_SECRET_ACCESS_KEY and AWS_SESSION_TOKEN
+
+    <g>DENO_REMOTE_CACHE_READ_ONLY</>
+                         Set to only read from the remote cache
+
     <g>DENO_V8_FLAGS</>        Set V8 command line options
 
     <g>DENO_WEBGPU_TRACE</>    Directory to use for wgpu traces
 
-    <g>DENO_JOBS</>            Number of parallel workers used for the --parallel
-                         flag with the test subcommand. Defaults to number
-                         of available CPUs.
+    <g>DENO_JOBS</>            Number of parallel workers used for the --jobs and
+                         --parallel flags with the test subcommand. Defaults
+                         to number of available CPUs.
 
     <g>HTTP_PROXY</>           Proxy address for HTTP requests
                          (module downloads, fetch)
@@ -856,7 +1215,8 @@ static ENV_VARIABLES_HELP: &str = color_print::cstr!(
 
     <g>NO_COLOR</>             Set to disable color
 
-    <g>NO_PROXY</>             Comma-separated list of hosts which do not use a proxy
+    <g>NO_PROXY</>             Comma-separated list of hosts which do not use a proxy,
+                         which may use wildcards like "*.example.com"
                          (module downloads, fetch)"#
 );
 
@@ -908,6 +1268,8 @@ pub fn flags_from_vec(args: Vec<String>) -> clap::error::Result<Flags> {
   flags.unstable_config.byonm = matches.get_flag("unstable-byonm");
   flags.unstable_config.sloppy_imports =
     matches.get_flag("unstable-sloppy-imports");
+  flags.unstable_config.test_snapshot =
+    matches.get_flag("unstable-test-snapshot");
 
   if matches.get_flag("quiet") {
     flags.log_level = Some(Level::Error);
@@ -919,9 +1281,15 @@ pub fn flags_from_vec(args: Vec<String>) -> clap::error::Result<Flags> {
       _ => unreachable!(),
     };
   }
+  flags.offline = matches.get_flag("offline");
+  flags.fetch_concurrency =
+    matches.remove_one::<NonZeroUsize>("fetch-concurrency");
+  flags.download_retries = matches.remove_one::<u32>("download-retries");
 
   if let Some((subcommand, mut m)) = matches.remove_subcommand() {
     match subcommand.as_str() {
+      "add" => add_parse(&mut flags, &mut m),
+      "audit" => audit_parse(&mut flags, &mut m),
       "bench" => bench_parse(&mut flags, &mut m),
       "bundle" => bundle_parse(&mut flags, &mut m),
       "cache" => cache_parse(&mut flags, &mut m),
@@ -938,8 +1306,12 @@ pub fn flags_from_vec(args: Vec<String>) -> clap::error::Result<Flags> {
       "jupyter" => jupyter_parse(&mut flags, &mut m),
       "lint" => lint_parse(&mut flags, &mut m),
       "lsp" => lsp_parse(&mut flags, &mut m),
+      "lock" => lock_parse(&mut flags, &mut m),
+      "outdated" => outdated_parse(&mut flags, &mut m),
+      "remove" => remove_parse(&mut flags, &mut m),
       "repl" => repl_parse(&mut flags, &mut m),
       "run" => run_parse(&mut flags, &mut m, app)?,
+      "serve" => serve_parse(&mut flags, &mut m),
       "task" => task_parse(&mut flags, &mut m),
       "test" => test_parse(&mut flags, &mut m),
       "types" => types_parse(&mut flags, &mut m),
@@ -1042,6 +1414,15 @@ fn clap_root() -> Command {
         .value_parser(FalseyValueParser::new())
         .action(ArgAction::SetTrue)
         .global(true),
+    )
+    .arg(
+      Arg::new("unstable-test-snapshot")
+        .long("unstable-test-snapshot")
+        .help("Enable unstable 't.assertSnapshot()' API of the test runner")
+        .env("DENO_UNSTABLE_TEST_SNAPSHOT")
+        .value_parser(FalseyValueParser::new())
+        .action(ArgAction::SetTrue)
+        .global(true),
     );
 
   for (flag_name, help, _) in crate::UNSTABLE_GRANULAR_FLAGS {
@@ -1074,9 +1455,50 @@ fn clap_root() -> Command {
         .action(ArgAction::SetTrue)
         .global(true),
     )
+    .arg(
+      Arg::new("offline")
+        .long("offline")
+        .help("Only use cached data and fail instead of accessing the network")
+        .env("DENO_OFFLINE")
+        .value_parser(FalseyValueParser::new())
+        .action(ArgAction::SetTrue)
+        .global(true),
+    )
+    .arg(
+      Arg::new("fetch-concurrency")
+        .long("fetch-concurrency")
+        .help(
+          "Set the max number of remote modules to download at the same time",
+        )
+        .long_help(
+          "Set the max number of remote modules to download at the same \
+          time. Defaults to a number based on the available CPUs. The \
+          downloads reuse the pooled connections of the HTTP client, which \
+          negotiates HTTP/2 with the servers that support it.",
+        )
+        .value_name("N")
+        .value_parser(value_parser!(NonZeroUsize))
+        .global(true),
+    )
+    .arg(
+      Arg::new("download-retries")
+        .long("download-retries")
+        .help("Set the number of times a failed download is retried")
+        .long_help(
+          "Set the number of times a download is retried after the connection \
+          dropped or the server errored. Interrupted downloads are resumed \
+          where they stopped when the server supports range requests. \
+          Defaults to 2.",
+        )
+        .value_name("N")
+        .value_parser(value_parser!(u32))
+        .global(true),
+    )
     .subcommand(run_subcommand())
     .defer(|cmd| {
       cmd
+        .subcommand(add_subcommand())
+        .subcommand(audit_subcommand())
         .subcommand(bench_subcommand())
         .subcommand(bundle_subcommand())
         .subcommand(cache_subcommand())
@@ -1094,7 +1516,11 @@ fn clap_root() -> Command {
         .subcommand(uninstall_subcommand())
         .subcommand(lsp_subcommand())
         .subcommand(lint_subcommand())
+        .subcommand(lock_subcommand())
+        .subcommand(outdated_subcommand())
         .subcommand(publish_subcommand())
+        .subcommand(remove_subcommand())
+        .subcommand(serve_subcommand())
         .subcommand(repl_subcommand())
         .subcommand(task_subcommand())
         .subcommand(test_subcommand())
@@ -1106,6 +1532,106 @@ fn clap_root() -> Command {
     .after_help(ENV_VARIABLES_HELP)
 }
 
+fn add_subcommand() -> Command {
+  Command::new("add")
+    .about("Add dependencies to the import map")
+    .long_about(
+      "Add jsr and npm packages to the \"imports\" of the configuration file, or
+of the import map file it points to, resolving the highest version matching
+the version requirement, or the latest version when there's none.
+
+  deno add jsr:@std/http npm:express@^4
+
+The packages are added with a caret requirement of the resolved version,
+unless an exact version or a tilde requirement is given, and the lockfile is
+updated to resolve them. Packages without a prefix are looked up on jsr.
+
+Add npm packages to the \"devDependencies\" of the package.json instead:
+
+  deno add --dev npm:typescript
+
+Download the added packages along with their dependencies:
+
+  deno add --cache jsr:@std/http",
+    )
+    .defer(|cmd| {
+      cmd
+        .arg(
+          Arg::new("packages")
+            .help("The packages to add")
+            .num_args(1..)
+            .action(ArgAction::Append)
+            .required(true),
+        )
+        .arg(
+          Arg::new("dev")
+            .long("dev")
+            .short('D')
+            .help("Add the npm packages as dev dependencies of the package.json")
+            .action(ArgAction::SetTrue),
+        )
+        .arg(
+          Arg::new("cache")
+            .long("cache")
+            .help("Download the added packages and their dependencies")
+            .action(ArgAction::SetTrue),
+        )
+        .arg(ca_file_arg())
+        .arg(cached_only_arg())
+        .arg(config_arg())
+        .arg(no_config_arg())
+        .arg(import_map_arg())
+        .arg(lock_arg())
+        .arg(no_lock_arg())
+    })
+}
+
+fn audit_subcommand() -> Command {
+  Command::new("audit")
+    .about("Audit the npm packages for known vulnerabilities")
+    .long_about(
+      "Audit the npm packages of the lockfile for known vulnerabilities.
+
+The packages are checked against the advisory database of the npm registry:
+
+  deno audit
+
+Every vulnerable package is reported with the severity of the advisory, the
+paths through the dependency tree to the package and the lowest version which
+isn't affected. The results are cached in DENO_DIR, so '--cached-only' audits
+without network access and the language server reports the cached
+vulnerabilities on the npm specifiers of open documents.
+
+Only fail on vulnerabilities with a severity of high or critical:
+
+  deno audit --level=high",
+    )
+    .defer(|cmd| {
+      cmd
+        .arg(
+          Arg::new("json")
+            .long("json")
+            .help("Output the vulnerabilities in JSON format")
+            .action(ArgAction::SetTrue),
+        )
+        .arg(
+          Arg::new("level")
+            .long("level")
+            .value_name("SEVERITY")
+            .help("Fail on vulnerabilities with this severity or above")
+            .value_parser(["info", "low", "moderate", "high", "critical"]),
+        )
+        .arg(ca_file_arg())
+        .arg(cached_only_arg())
+        .arg(config_arg())
+        .arg(no_config_arg())
+        .arg(lock_arg())
+        .arg(no_lock_arg())
+        .arg(node_modules_dir_arg())
+        .arg(vendor_arg())
+    })
+}
+
 fn bench_subcommand() -> Command {
   Command::new("bench")
     .about("Run benchmarks")
@@ -1131,6 +1657,7 @@ glob {*_,*.,}bench.{js,mjs,ts,mts,jsx,tsx}:
             .action(ArgAction::SetTrue)
             .help("UNSTABLE: Output benchmark result in JSON format"),
         )
+        .arg(gitignore_arg())
         .arg(
           Arg::new("ignore")
             .long("ignore")
@@ -1159,6 +1686,51 @@ glob {*_,*.,}bench.{js,mjs,ts,mts,jsx,tsx}:
             .help("Cache bench modules, but don't run benchmarks")
             .action(ArgAction::SetTrue),
         )
+        .arg(
+          Arg::new("reporter")
+            .long("reporter")
+            .conflicts_with("json")
+            .help("Select reporter to use. Default to 'pretty'.")
+            .long_help(
+              "Select reporter to use. Default to 'pretty'. The 'json' and \
+'junit' reporters write a report with a stable schema to standard output, \
+containing the iterations, percentiles and throughput of every benchmark.",
+            )
+            .value_parser(["pretty", "json", "junit"]),
+        )
+        .arg(
+          Arg::new("baseline")
+            .long("baseline")
+            .value_name("PATH")
+            .require_equals(true)
+            .conflicts_with("watch")
+            .value_hint(ValueHint::FilePath)
+            .help("Fail on regressions compared to a previous JSON report")
+            .long_help(
+              "Compare the results to a report written by a previous run of \
+'deno bench --reporter=json' and fail if a benchmark got slower. A benchmark \
+regressed when its average is slower than the baseline by more than \
+'--regression-threshold' and the difference is statistically significant.",
+            ),
+        )
+        .arg(
+          Arg::new("regression-threshold")
+            .long("regression-threshold")
+            .value_name("PERCENT")
+            .require_equals(true)
+            .requires("baseline")
+            .value_parser(|percent: &str| {
+              percent
+                .parse::<f64>()
+                .ok()
+                .filter(|p| p.is_finite() && *p >= 0.0)
+                .map(|p| (p * 100.0).round())
+                .filter(|bps| *bps <= u32::MAX as f64)
+                .map(|bps| bps as u32)
+                .ok_or_else(|| format!("Invalid percentage \"{percent}\""))
+            })
+            .help("The slowdown in percent which counts as a regression. Defaults to 5."),
+        )
         .arg(watch_arg(false))
         .arg(no_clear_screen_arg())
         .arg(script_arg().last(true))
@@ -1170,13 +1742,13 @@ fn bundle_subcommand() -> Command {
   Command::new("bundle")
     .about("Bundle module and dependencies into single file")
     .long_about(
-      "⚠️ Warning: `deno bundle` is deprecated and will be removed in Deno 2.0.
-Use an alternative bundler like \"deno_emit\", \"esbuild\" or \"rollup\" instead.
-
-Output a single JavaScript file with all dependencies.
+      "Output a single JavaScript file with all dependencies.
 
   deno bundle https://deno.land/std/http/file_server.ts file_server.bundle.js
 
+The modules which are imported dynamically are bundled into separate chunks
+next to the output file, which are only loaded once they're imported.
+
 If no output file is given, the output is written to standard output:
 
   deno bundle https://deno.land/std/http/file_server.ts",
@@ -1191,6 +1763,18 @@ If no output file is given, the output is written to standard output:
             .value_hint(ValueHint::FilePath),
         )
         .arg(Arg::new("out_file").value_hint(ValueHint::FilePath))
+        .arg(
+          Arg::new("minify")
+            .long("minify")
+            .help("Minify the output")
+            .action(ArgAction::SetTrue),
+        )
+        .arg(
+          Arg::new("source-map")
+            .long("source-map")
+            .help("Emit a source map next to the output, or inline it when writing to standard output")
+            .action(ArgAction::SetTrue),
+        )
         .arg(watch_arg(false))
         .arg(no_clear_screen_arg())
         .arg(executable_ext_arg())
@@ -1209,15 +1793,46 @@ them in the local cache, without running any code:
   deno cache https://deno.land/std/http/file_server.ts
 
 Future runs of this module will trigger no downloads or compilation unless
---reload is specified.",
+--reload is specified.
+
+Evict the entries of the global HTTP cache which weren't used for 30 days,
+or until the cache is smaller than 5GB:
+
+  deno cache --prune
+  deno cache --prune --max-age=30d --max-size=5GB",
     )
     .defer(|cmd| {
-      compile_args(cmd).arg(check_arg(false)).arg(
-        Arg::new("file")
-          .num_args(1..)
-          .required(true)
-          .value_hint(ValueHint::FilePath),
-      )
+      compile_args(cmd)
+        .arg(check_arg(false))
+        .arg(
+          Arg::new("file")
+            .num_args(1..)
+            .required_unless_present("prune")
+            .value_hint(ValueHint::FilePath),
+        )
+        .arg(
+          Arg::new("prune")
+            .long("prune")
+            .help("Evict the stale entries of the global HTTP cache instead of caching modules")
+            .action(ArgAction::SetTrue)
+            .conflicts_with("file"),
+        )
+        .arg(
+          Arg::new("max-age")
+            .long("max-age")
+            .help("Evict the entries which weren't used for longer than this, like 30d or 12h [default: 30d]")
+            .value_name("DURATION")
+            .value_parser(parse_duration_secs)
+            .requires("prune"),
+        )
+        .arg(
+          Arg::new("max-size")
+            .long("max-size")
+            .help("Evict the least recently used entries until the cache is smaller than this, like 5GB or 500MB")
+            .value_name("SIZE")
+            .value_parser(parse_size_bytes)
+            .requires("prune"),
+        )
     })
 }
 
@@ -1229,6 +1844,14 @@ fn check_subcommand() -> Command {
 
   deno check https://deno.land/std/http/file_server.ts
 
+Type-check the exports of all the members of a workspace in the order of their dependencies:
+
+  deno check --workspace
+
+Find out why type checking is slow with a trace, which can be opened in chrome://tracing or https://ui.perfetto.dev:
+
+  deno check --trace=trace.json main.ts
+
 Unless --reload is specified, this command will not re-download already cached dependencies.",
       )
     .defer(|cmd| compile_args_without_check_args(cmd).arg(
@@ -1238,6 +1861,7 @@ Unless --reload is specified, this command will not re-download already cached d
         .action(ArgAction::SetTrue)
         .conflicts_with("no-remote")
     )
+      .arg(gitignore_arg())
       .arg(
         // past alias for --all
         Arg::new("remote")
@@ -1247,10 +1871,24 @@ Unless --reload is specified, this command will not re-download already cached d
           .conflicts_with("no-remote")
           .hide(true)
       )
+      .arg(
+        Arg::new("workspace")
+          .long("workspace")
+          .help("Type-check the exports of all the members of the workspace, where every member is checked against the types of the members it depends on")
+          .action(ArgAction::SetTrue)
+          .conflicts_with("file")
+      )
+      .arg(
+        Arg::new("trace")
+          .long("trace")
+          .value_name("FILE")
+          .help("Type-check without the cache and write the time spent parsing, resolving, binding and checking every file to the file in the Chrome trace format, printing the statistics of the compiler and the slowest files")
+          .value_hint(ValueHint::FilePath)
+      )
       .arg(
         Arg::new("file")
           .num_args(1..)
-          .required(true)
+          .required_unless_present("workspace")
           .value_hint(ValueHint::FilePath),
       )
     )
@@ -1279,6 +1917,18 @@ Cross-compiling to different target architectures is supported using the
 `--target` flag. On the first invocation with deno will download proper
 binary and cache it in $DENO_DIR. The aarch64-apple-darwin target is not
 supported in canary.
+
+Use `--target all` to compile an executable for every target at once, which
+appends the target to the name of each executable, and `--list-targets` to
+show the available targets:
+
+  deno compile --target all --output dist/file_server https://deno.land/std/http/file_server.ts
+  deno compile --list-targets
+
+Directories and globs passed to `--include` are embedded in the executable,
+so they can be read at the same paths relative to the main module:
+
+  deno compile --include static --include \"templates/*.html\" main.ts
 ",
     )
     .defer(|cmd| {
@@ -1287,12 +1937,17 @@ supported in canary.
       .arg(
         Arg::new("include")
           .long("include")
-          .help("Additional module to include in the module graph")
+          .help("Additional module or files to include in the executable")
           .long_help(
             "Includes an additional module in the compiled executable's module
     graph. Use this flag if a dynamically imported module or a web worker main
     module fails to load in the executable. This flag can be passed multiple
-    times, to include multiple additional modules.",
+    times, to include multiple additional modules.
+
+    Directories, globs and files which aren't modules are embedded in the
+    executable's virtual file system instead, at the same paths relative to
+    the main module, so they can be read with `Deno.readFile`. The paths of
+    the embedded files are listed in `Deno.embeddedFiles`.",
           )
           .action(ArgAction::Append)
           .value_hint(ValueHint::FilePath),
@@ -1309,13 +1964,17 @@ supported in canary.
       .arg(
         Arg::new("target")
           .long("target")
-          .help("Target OS architecture")
-          .value_parser([
-            "x86_64-unknown-linux-gnu",
-            "x86_64-pc-windows-msvc",
-            "x86_64-apple-darwin",
-            "aarch64-apple-darwin",
-          ]),
+          .help("Target OS architecture, or \"all\" for every target")
+          .value_parser(PossibleValuesParser::new(
+            COMPILE_TARGETS.into_iter().chain(["all"]),
+          )),
+      )
+      .arg(
+        Arg::new("list-targets")
+          .long("list-targets")
+          .help("List the targets which can be compiled to")
+          .action(ArgAction::SetTrue)
+          .conflicts_with_all(["target", "output", "script_arg"]),
       )
       .arg(
         Arg::new("no-terminal")
@@ -1323,9 +1982,85 @@ supported in canary.
           .help("Hide terminal on Windows")
           .action(ArgAction::SetTrue),
       )
+      .arg(
+        Arg::new("sign-identity")
+          .long("sign-identity")
+          .value_name("IDENTITY")
+          .help("Sign the executable with codesign on macOS or signtool on Windows")
+          .long_help(
+            "Signs the executable with the identity, using `codesign` when
+    targeting macOS and `signtool` when targeting Windows, so the executable
+    doesn't trigger the security warnings of the OS. The signing tool needs
+    to be in the PATH. Notarizing the signed executable for macOS still needs
+    to be done separately, for example with `xcrun notarytool`.",
+          ),
+      )
+      .arg(
+        Arg::new("sign-command")
+          .long("sign-command")
+          .value_name("PROGRAM")
+          .help("Sign the executable with a program, which is passed the path of the executable")
+          .long_help(
+            "Signs the executable with a program instead, which is run with the
+    arguments of `--sign-arg` followed by the path of the executable. The
+    program isn't run in a shell, so a path with spaces doesn't need to be
+    quoted.",
+          )
+          .value_hint(ValueHint::FilePath)
+          .conflicts_with("sign-identity"),
+      )
+      .arg(
+        Arg::new("sign-arg")
+          .long("sign-arg")
+          .value_name("ARG")
+          .help("Pass an argument to the program of --sign-command, which can be repeated")
+          .allow_hyphen_values(true)
+          .action(ArgAction::Append)
+          .requires("sign-command"),
+      )
+      .arg(
+        Arg::new("icon")
+          .long("icon")
+          .value_parser(value_parser!(PathBuf))
+          .help("Set the icon of the executable on Windows, which requires rcedit in the PATH")
+          .value_hint(ValueHint::FilePath),
+      )
+      .arg(
+        Arg::new("version-info")
+          .long("version-info")
+          .value_name("VERSION")
+          .help("Set the file and product version of the executable on Windows, which requires rcedit in the PATH"),
+      )
+      .arg(
+        Arg::new("strip")
+          .long("strip")
+          .help("Exclude source maps from the executable")
+          .long_help(
+            "Reduces the size of the executable by not embedding the source maps
+    of the transpiled modules, so stack traces point at the emitted
+    JavaScript.",
+          )
+          .action(ArgAction::SetTrue)
+          .conflicts_with("include-source-maps"),
+      )
+      .arg(
+        Arg::new("include-source-maps")
+          .long("include-source-maps")
+          .help("Embed source maps in the executable, which is the default without --strip")
+          .long_help(
+            "Embeds the source maps of the transpiled modules in the executable,
+    so uncaught errors show the original file, line and column. The source
+    maps are embedded unless --strip is passed, which this conflicts with.",
+          )
+          .action(ArgAction::SetTrue),
+      )
       .arg(executable_ext_arg())
       .arg(env_file_arg())
-      .arg(script_arg().required(true).trailing_var_arg(true))
+      .arg(
+        script_arg()
+          .required_unless_present("list-targets")
+          .trailing_var_arg(true),
+      )
     })
 }
 
@@ -1382,10 +2117,17 @@ Write a report using the lcov format:
 Generate html reports from lcov:
 
   genhtml -o html_cov cov.lcov
+
+Write a report using the Cobertura XML format, or a JSON summary of the
+line, branch and function coverage of each file:
+
+  deno coverage --cobertura --output=cobertura.xml cov_profile/
+  deno coverage --json-summary --output=coverage-summary.json cov_profile/
 ",
     )
     .defer(|cmd| {
       cmd
+        .arg(gitignore_arg())
         .arg(
           Arg::new("ignore")
             .long("ignore")
@@ -1421,15 +2163,33 @@ Generate html reports from lcov:
             .help("Output coverage report in lcov format")
             .action(ArgAction::SetTrue),
         )
+        .arg(
+          Arg::new("cobertura")
+            .long("cobertura")
+            .help("Output coverage report in Cobertura XML format")
+            .action(ArgAction::SetTrue),
+        )
+        .arg(
+          Arg::new("json-summary")
+            .long("json-summary")
+            .help("Output a summary of the coverage in JSON format")
+            .action(ArgAction::SetTrue),
+        )
+        .group(
+          ArgGroup::new("output-format")
+            .args(["lcov", "cobertura", "json-summary"]),
+        )
         .arg(
           Arg::new("output")
-            .requires("lcov")
+            .requires("output-format")
             .long("output")
             // todo(dsherret): remove value_parser!(PathBuf) and instead parse as string
             .value_parser(value_parser!(PathBuf))
-            .help("Output file (defaults to stdout) for lcov")
+            .help(
+              "Output file (defaults to stdout) for lcov, cobertura or json-summary",
+            )
             .long_help(
-              "Exports the coverage report in lcov format to the given file.
+              "Exports the lcov, cobertura or json-summary coverage report to the given file.
     Filename should be passed along with '=' For example '--output=foo.lcov'
     If no --output arg is specified then the report is written to stdout.",
             )
@@ -1450,6 +2210,7 @@ Generate html reports from lcov:
             .help("Output coverage report in detailed format in the terminal.")
             .action(ArgAction::SetTrue),
         )
+        .arg(coverage_threshold_arg())
         .arg(
           Arg::new("files")
             .num_args(0..)
@@ -1475,6 +2236,16 @@ Output documentation in HTML format:
     deno doc --html --name=\"My library\" ./main.ts ./dev.ts
     deno doc --html --name=\"My library\" --output=./documentation/ ./path/to/module.ts
 
+Output documentation of all the members of a workspace in HTML format, with an
+index of the members and a search across them:
+
+    deno doc --html --name=\"My workspace\" --workspace
+
+Output documentation in Markdown format, with a file per module and an index
+of the modules:
+
+    deno doc --markdown --output=./docs/ ./mod.ts ./path/to/module.ts
+
 Output private documentation to standard output:
 
     deno doc --private ./path/to/module.ts
@@ -1487,6 +2258,10 @@ Lint a module for documentation diagnostics:
 
     deno doc --lint ./path/to/module.ts
 
+Output documentation diagnostics in JSON format:
+
+    deno doc --lint --json ./path/to/module.ts
+
 Target a specific symbol:
 
     deno doc ./path/to/module.ts MyClass.someField
@@ -1507,7 +2282,7 @@ Show documentation for runtime built-ins:
         .arg(
           Arg::new("json")
             .long("json")
-            .help("Output documentation in JSON format")
+            .help("Output documentation in JSON format, or documentation diagnostics with --lint")
             .action(ArgAction::SetTrue),
         )
         .arg(
@@ -1517,6 +2292,13 @@ Show documentation for runtime built-ins:
             .action(ArgAction::SetTrue)
             .conflicts_with("json")
         )
+        .arg(
+          Arg::new("markdown")
+            .long("markdown")
+            .help("Output documentation in Markdown format")
+            .action(ArgAction::SetTrue)
+            .conflicts_with_all(["json", "html", "filter"])
+        )
         .arg(
           Arg::new("name")
             .long("name")
@@ -1528,7 +2310,7 @@ Show documentation for runtime built-ins:
         .arg(
           Arg::new("output")
             .long("output")
-            .help("Directory for HTML documentation output")
+            .help("Directory for HTML or Markdown documentation output")
             .action(ArgAction::Set)
             .require_equals(true)
             .value_hint(ValueHint::DirPath)
@@ -1556,6 +2338,14 @@ Show documentation for runtime built-ins:
             .help("Output documentation diagnostics.")
             .action(ArgAction::SetTrue),
         )
+        .arg(
+          Arg::new("workspace")
+            .long("workspace")
+            .help("Output HTML documentation of the exports of all the members of the workspace")
+            .action(ArgAction::SetTrue)
+            .requires("html")
+            .conflicts_with_all(["source_file", "lint"]),
+        )
         // TODO(nayeemrmn): Make `--builtin` a proper option. Blocked by
         // https://github.com/clap-rs/clap/issues/1794. Currently `--builtin` is
         // just a possible value of `source_file` so leading hyphens must be
@@ -1566,7 +2356,9 @@ Show documentation for runtime built-ins:
             .num_args(1..)
             .action(ArgAction::Append)
             .value_hint(ValueHint::FilePath)
-            .required_if_eq_any([("html", "true"), ("lint", "true")]),
+            .required_if_eq_all([("html", "true"), ("workspace", "false")])
+            .required_if_eq("markdown", "true")
+            .required_if_eq("lint", "true"),
         )
     })
 }
@@ -1660,6 +2452,7 @@ Ignore formatting a file by adding an ignore comment at the top of the file:
               "ts", "tsx", "js", "jsx", "md", "json", "jsonc", "ipynb",
             ]),
         )
+        .arg(no_gitignore_arg())
         .arg(
           Arg::new("ignore")
             .long("ignore")
@@ -1733,18 +2526,61 @@ Ignore formatting a file by adding an ignore comment at the top of the file:
               "Don't use semicolons except where necessary. Defaults to false.",
             ),
         )
+        .arg(
+          Arg::new("organize-imports")
+            .long("organize-imports")
+            .help("Sort and group imports and remove unused ones")
+            .action(ArgAction::SetTrue),
+        )
     })
 }
 
 fn init_subcommand() -> Command {
   Command::new("init")
     .about("Initialize a new project")
+    .long_about(
+      "Initialize a new project from a template.
+
+  deno init
+  deno init --template=server my_server
+
+The built-in templates are default, server, cli, library and worker.
+Community templates are fetched from a jsr package, whose files are copied,
+or from a URL of a JSON file with a \"files\" object which maps the paths of
+the files to their contents:
+
+  deno init --template=jsr:@scope/template
+  deno init --template=https://example.com/template.json
+
+When no template is passed in an interactive terminal, the template and
+whether to add a GitHub Actions workflow are prompted, unless --yes is passed.",
+    )
     .defer(|cmd| {
-      cmd.arg(
-        Arg::new("dir")
-          .required(false)
-          .value_hint(ValueHint::DirPath),
-      )
+      cmd
+        .arg(
+          Arg::new("dir")
+            .required(false)
+            .value_hint(ValueHint::DirPath),
+        )
+        .arg(
+          Arg::new("template")
+            .long("template")
+            .value_name("TEMPLATE")
+            .help("The template to initialize the project with: default, server, cli, library, worker, or a jsr: or https: URL of a community template"),
+        )
+        .arg(
+          Arg::new("ci")
+            .long("ci")
+            .help("Add a GitHub Actions workflow which checks the formatting, lints and tests the project")
+            .action(ArgAction::SetTrue),
+        )
+        .arg(
+          Arg::new("yes")
+            .long("yes")
+            .short('y')
+            .help("Don't prompt, using the defaults for anything not passed as a flag")
+            .action(ArgAction::SetTrue),
+        )
     })
 }
 
@@ -1765,6 +2601,15 @@ type: JavaScript, TypeScript, or JSON.
 emit: Local path of compiled source code. (TypeScript only.)
 dependencies: Dependency tree of the source file.
 
+Compare the dependencies against a module graph which was saved before:
+
+  deno info --json main.ts > graph.json
+  deno info --diff=graph.json main.ts
+
+List the licenses of the npm and jsr packages and remote modules:
+
+  deno info --licenses main.ts
+
 Without any additional arguments, 'deno info' shows:
 
 DENO_DIR: Directory containing Deno-managed files.
@@ -1795,6 +2640,34 @@ TypeScript compiler cache: Subdirectory containing TS compiler output.",
           .long("json")
           .help("UNSTABLE: Outputs the information in JSON format")
           .action(ArgAction::SetTrue),
+      )
+      .arg(
+        Arg::new("format")
+          .long("format")
+          .help("UNSTABLE: Outputs the dependency graph in the given format")
+          .long_help("UNSTABLE: Outputs the dependency graph in the given format. 'dot' produces a Graphviz graph of the modules and their imports, 'json' is the same as --json.")
+          .requires("file")
+          .conflicts_with("json")
+          .value_parser(["dot", "json"]),
+      )
+      .arg(
+        Arg::new("diff")
+          .long("diff")
+          .value_name("OLD_GRAPH")
+          .help("UNSTABLE: Compare the dependencies against a module graph saved with 'deno info --json'")
+          .long_help("UNSTABLE: Compare the dependencies against a module graph saved with 'deno info --json', reporting the added, removed and updated modules and packages with their size changes. Combine with --json to output the changes as JSON.")
+          .requires("file")
+          .conflicts_with("format")
+          .value_hint(ValueHint::FilePath),
+      )
+      .arg(
+        Arg::new("licenses")
+          .long("licenses")
+          .help("UNSTABLE: List the licenses of the dependencies")
+          .long_help("UNSTABLE: List the licenses of the npm and jsr packages and remote modules of the module graph. When the configuration file has a \"licenses\": { \"allow\": [...] } list, dependencies with other licenses are errors. Combine with --json to output the licenses as JSON.")
+          .requires("file")
+          .conflicts_with_all(["format", "diff"])
+          .action(ArgAction::SetTrue),
       ))
 }
 
@@ -1915,12 +2788,42 @@ How to connect various editors and IDEs to 'deno lsp':
 https://deno.land/manual@v",
   env!("CARGO_PKG_VERSION"),
   "/getting_started/setup_your_environment#editors-and-ides",
+  "
+
+To diagnose a slow workspace, write the log of the language server to a file
+and log the debug messages as well:
+
+  deno lsp --log-file=lsp.log --log-level=debug
+
+Or export the durations of the requests, the diagnostics and the calls to tsc
+as OpenTelemetry spans and metrics to an OTLP/HTTP collector:
+
+  deno lsp --otlp-endpoint=http://localhost:4318",
 );
 
 fn lsp_subcommand() -> Command {
   Command::new("lsp")
     .about("Start the language server")
     .long_about(LSP_HELP)
+    .arg(
+      Arg::new("log-file")
+        .long("log-file")
+        .help("Write the log of the language server to a file")
+        .value_parser(value_parser!(PathBuf))
+        .value_hint(ValueHint::FilePath),
+    )
+    .arg(
+      Arg::new("otlp-endpoint")
+        .long("otlp-endpoint")
+        .value_name("URL")
+        .help(
+          "Export OpenTelemetry traces and metrics to an OTLP/HTTP collector",
+        )
+        .value_parser(|url: &str| {
+          Url::parse(url).map_err(|_| "Failed to parse URL".to_string())
+        })
+        .value_hint(ValueHint::Url),
+    )
 }
 
 fn lint_subcommand() -> Command {
@@ -1995,6 +2898,7 @@ Ignore linting a file by adding an ignore comment at the top of the file:
         )
         .arg(no_config_arg())
         .arg(config_arg())
+        .arg(no_gitignore_arg())
         .arg(
           Arg::new("ignore")
             .long("ignore")
@@ -2092,13 +2996,94 @@ Specifying the filename '-' to read the file from stdin.
     )
 }
 
+fn serve_subcommand() -> Command {
+  runtime_args(Command::new("serve"), true, true)
+    .arg(check_arg(false))
+    .arg(watch_arg(true))
+    .arg(hmr_arg(true))
+    .arg(no_clear_screen_arg())
+    .arg(executable_ext_arg())
+    .arg(
+      Arg::new("port")
+        .long("port")
+        .help("The port to listen on")
+        .value_parser(value_parser!(u16))
+        .default_value("8000"),
+    )
+    .arg(
+      Arg::new("host")
+        .long("host")
+        .help("The hostname to listen on")
+        .default_value("0.0.0.0"),
+    )
+    .arg(
+      Arg::new("dev")
+        .long("dev")
+        .help("Serve an error overlay when the handler throws")
+        .action(ArgAction::SetTrue),
+    )
+    .arg(
+      Arg::new("editor-url")
+        .long("editor-url")
+        .help("The URL template of the links which open the editor")
+        .long_help(
+          "The URL template of the links of the error overlay which open a file
+in the editor, where {path}, {line} and {column} are replaced by the location.
+Defaults to vscode://file{path}:{line}:{column}",
+        )
+        .value_name("TEMPLATE")
+        .requires("dev"),
+    )
+    .arg(script_arg().required(true).trailing_var_arg(true))
+    .arg(env_file_arg())
+    .about("Serve the default export of a module with Deno.serve")
+    .long_about(
+      "Serves the `fetch` handler of the default export of a module, which is
+like calling `Deno.serve` with it:
+
+  export default {
+    fetch(request) {
+      return new Response(\"Hello world\");
+    },
+  };
+
+  deno serve --allow-net --port 3000 server.ts
+
+With `--unstable-hmr` (or `--watch-hmr`) the changed modules are hot replaced
+without restarting the server, so in-flight connections are kept. After every
+replacement, the `onHmr` method of the default export is called with the path
+of the changed module, so frameworks can register their routes again:
+
+  export default {
+    fetch(request) { ... },
+    onHmr(path) { ... },
+  };
+
+With `--dev`, an error which is thrown by the handler is served as an HTML page
+with its stack and the code around the lines of the local files in it, which
+link to the editor, instead of a bare 500 response:
+
+  deno serve --dev --watch --allow-net --allow-read server.ts",
+    )
+}
+
 fn task_subcommand() -> Command {
   Command::new("task")
     .about("Run a task defined in the configuration file")
     .long_about(
       "Run a task defined in the configuration file
 
-  deno task build",
+  deno task build
+
+Run a task in every workspace member which defines it, in the order of the
+dependencies between the members:
+
+  deno task --recursive build
+
+Only run the task in the members whose name or directory match a glob:
+
+  deno task --filter \"@scope/*\" build
+  deno task --filter \"packages/*\" build",
     )
     .defer(|cmd| {
       cmd
@@ -2112,6 +3097,28 @@ fn task_subcommand() -> Command {
             .help("Specify the directory to run the task in")
             .value_hint(ValueHint::DirPath),
         )
+        .arg(
+          Arg::new("recursive")
+            .long("recursive")
+            .short('r')
+            .help("Run the task in every workspace member which defines it")
+            .action(ArgAction::SetTrue)
+            .conflicts_with("cwd"),
+        )
+        .arg(
+          Arg::new("filter")
+            .long("filter")
+            .value_name("PATTERN")
+            .help("Run the task in the workspace members whose name or directory match the glob, implies '--recursive'")
+            .conflicts_with("cwd"),
+        )
+        .arg(
+          Arg::new("no-watch")
+            .long("no-watch")
+            .help("Run the task once, even if it declares files to watch")
+            .action(ArgAction::SetTrue),
+        )
+        .arg(env_file_arg())
     })
 }
 
@@ -2133,6 +3140,7 @@ Directory arguments are expanded to all contained files matching the glob
     )
   .defer(|cmd| runtime_args(cmd, true, true)
     .arg(check_arg(true))
+    .arg(no_gitignore_arg())
     .arg(
       Arg::new("ignore")
         .long("ignore")
@@ -2203,10 +3211,11 @@ Directory arguments are expanded to all contained files matching the glob
         .conflicts_with("inspect-brk")
         .help("Collect coverage profile data into DIR. If DIR is not specified, it uses 'coverage/'."),
     )
+    .arg(coverage_threshold_arg().requires("coverage"))
     .arg(
       Arg::new("parallel")
         .long("parallel")
-        .help("Run test modules in parallel. Parallelism defaults to the number of available CPUs or the value in the DENO_JOBS environment variable.")
+        .help("Run test modules in parallel. Same as '--jobs' without a value.")
         .conflicts_with("jobs")
         .action(ArgAction::SetTrue)
     )
@@ -2214,8 +3223,11 @@ Directory arguments are expanded to all contained files matching the glob
       Arg::new("jobs")
         .short('j')
         .long("jobs")
-        .help("deprecated: The `--jobs` flag is deprecated and will be removed in Deno 2.0. Use the `--parallel` flag with possibly the `DENO_JOBS` environment variable instead.")
-        .hide(true)
+        .value_name("N")
+        .help("Run up to N test modules in parallel, each in its own isolate. Defaults to the number of available CPUs or the value in the DENO_JOBS environment variable.")
+        .long_help("Run up to N test modules in parallel, each in its own isolate. If N is not specified, it defaults to the value in the DENO_JOBS environment variable or otherwise the number of available CPUs.
+
+The output of a test is collected separately for every module and is reported together with the result of the test, so the output of modules running at the same time is not interleaved.")
         .num_args(0..=1)
         .value_parser(value_parser!(NonZeroUsize)),
     )
@@ -2246,6 +3258,74 @@ Directory arguments are expanded to all contained files matching the glob
         .help("Select reporter to use. Default to 'pretty'.")
         .value_parser(["pretty", "dot", "junit", "tap"])
     )
+    .arg(
+      Arg::new("retry")
+        .long("retry")
+        .value_name("N")
+        .require_equals(true)
+        .value_parser(value_parser!(usize))
+        .help("Run failing tests up to N more times, reporting tests which pass on a retry as flaky"),
+    )
+    .arg(
+      Arg::new("changed")
+        .long("changed")
+        .value_name("REF")
+        .num_args(0..=1)
+        .require_equals(true)
+        .default_missing_value("HEAD")
+        .conflicts_with("watch")
+        .help("Only run test modules affected by files changed since a git ref. Defaults to 'HEAD'")
+        .long_help("Only run test modules affected by files changed since a git ref, which defaults to 'HEAD'. Uncommitted and untracked files are considered changed.
+
+A test module is affected when the module itself or one of the local modules it depends on changed. All test modules are run when the configuration file or the import map changed.
+
+  deno test --changed=origin/main"),
+    )
+    .arg(
+      Arg::new("update-snapshots")
+        .long("update-snapshots")
+        .action(ArgAction::SetTrue)
+        .help("Write the values of 't.assertSnapshot()' calls to the snapshot files instead of comparing against them")
+        .long_help("Write the values of 't.assertSnapshot()' calls to the snapshot files instead of comparing against them.
+
+Snapshots are stored in a '__snapshots__' directory next to the test module. Snapshots which are no longer asserted are removed when all tests of the module ran."),
+    )
+    .arg(
+      Arg::new("shard")
+        .long("shard")
+        .value_name("INDEX/COUNT")
+        .require_equals(true)
+        .conflicts_with("watch")
+        .value_parser(|shard: &str| shard.parse::<TestShard>())
+        .help("Only run the test modules of a shard, like '--shard=2/5'")
+        .long_help(
+          "Split the test modules into COUNT shards and only run the modules of \
+the shard INDEX, which is 1-based. Modules are assigned to shards by a stable \
+hash of their path relative to the current directory, so every machine of a CI \
+job selects the same split.
+
+  deno test --shard=2/5",
+        ),
+    )
+    .arg(
+      Arg::new("shard-timings")
+        .long("shard-timings")
+        .value_name("PATH")
+        .require_equals(true)
+        .requires("shard")
+        .value_hint(ValueHint::FilePath)
+        .help("Balance shards by the JUnit report of a previous run")
+        .long_help(
+          "Balance the shards of '--shard' by the durations of a previous run \
+instead of hashing the paths. PATH is the JUnit report of that run, where the \
+duration of a test module is the sum of the durations of its test cases:
+
+  deno test --reporter=junit --junit-path=report.xml
+  deno test --shard=2/5 --shard-timings=report.xml
+
+Modules which aren't in the report are assumed to take the average duration.",
+        ),
+    )
     .arg(env_file_arg())
   )
 }
@@ -2258,8 +3338,37 @@ fn types_subcommand() -> Command {
 
   deno types > lib.deno.d.ts
 
-The declaration file could be saved and used for typing information.",
+The declaration file could be saved and used for typing information.
+
+Generate the Deno.dlopen() symbols of the functions, structs and extern
+variables of a C header, with an open() function which returns the typed
+library:
+
+  deno types --ffi ./mylib.h > mylib.ts
+
+Check that the bindings are still the ones generated from the header, which
+fails when a signature of the header has changed:
+
+  deno types --ffi ./mylib.h --check ./mylib.ts",
     )
+    .defer(|cmd| {
+      cmd
+        .arg(
+          Arg::new("ffi")
+            .long("ffi")
+            .help("Generate the Deno.dlopen() symbols of a C header")
+            .value_name("HEADER")
+            .value_hint(ValueHint::FilePath),
+        )
+        .arg(
+          Arg::new("check")
+            .long("check")
+            .help("Check that the bindings in the file are the ones generated from the header")
+            .value_name("FILE")
+            .requires("ffi")
+            .value_hint(ValueHint::FilePath),
+        )
+    })
 }
 
 fn upgrade_subcommand() -> Command {
@@ -2276,7 +3385,23 @@ and is used to replace the current executable.
 If you want to not replace the current Deno executable but instead download an
 update to a different location, use the --output flag
 
-  deno upgrade --output $HOME/my_deno",
+  deno upgrade --output $HOME/my_deno
+
+Release candidates, canary builds and long term support releases are installed
+from their channels:
+
+  deno upgrade --channel rc
+  deno upgrade --channel lts
+
+Without --version, the version of a .dvmrc file in the current directory or one
+of its parents is installed. The executable which is replaced is kept, so that
+it can be restored:
+
+  deno upgrade --rollback
+
+An archive which was downloaded before can be installed offline:
+
+  deno upgrade --archive ./deno-x86_64-unknown-linux-gnu.zip",
     )
     .hide(cfg!(not(feature = "upgrade")))
     .defer(|cmd| {
@@ -2311,7 +3436,31 @@ update to a different location, use the --output flag
           Arg::new("canary")
             .long("canary")
             .help("Upgrade to canary builds")
-            .action(ArgAction::SetTrue),
+            .action(ArgAction::SetTrue)
+            .conflicts_with("channel"),
+        )
+        .arg(
+          Arg::new("channel")
+            .long("channel")
+            .help("The channel of the release to upgrade to")
+            .value_parser(["stable", "rc", "canary", "lts"]),
+        )
+        .arg(
+          Arg::new("rollback")
+            .long("rollback")
+            .help("Restore the version which was replaced by the last upgrade")
+            .action(ArgAction::SetTrue)
+            .conflicts_with_all([
+              "version", "output", "canary", "channel", "archive",
+            ]),
+        )
+        .arg(
+          Arg::new("archive")
+            .long("archive")
+            .help("Install from a downloaded release archive instead")
+            .value_parser(value_parser!(PathBuf))
+            .value_hint(ValueHint::FilePath)
+            .conflicts_with_all(["version", "canary", "channel"]),
         )
         .arg(ca_file_arg())
     })
@@ -2325,7 +3474,9 @@ fn vendor_subcommand() -> Command {
 
 Analyzes the provided modules along with their dependencies, downloads
 remote modules to the output directory, and produces an import map that
-maps remote specifiers to the downloaded files.
+maps remote specifiers to the downloaded files. npm packages are vendored
+into a local node_modules directory along with the registry information
+they need, so that it can be committed and used offline.
 
   deno vendor main.ts
   deno run --import-map vendor/import_map.json main.ts
@@ -2368,6 +3519,114 @@ Remote modules and multiple modules may also be specified:
       .arg(ca_file_arg()))
 }
 
+fn lock_subcommand() -> Command {
+  Command::new("lock")
+    .about("Verify the lockfile")
+    .long_about(
+      "Verify the checksums of every remote module and npm package in the
+lockfile, without running or type checking any code:
+
+  deno lock --verify
+
+The cached copies of remote modules are hashed again and the ones which aren't
+cached are downloaded. Pass '--reload' to download every remote module and
+'--cached-only' to only check what is cached. The tarballs of npm packages are
+always downloaded, because only their extracted contents are cached.
+
+Modified or unavailable dependencies, along with entries which are missing
+from the lockfile, are reported and make the command fail.",
+    )
+    .defer(|cmd| {
+      cmd
+        .arg(
+          Arg::new("verify")
+            .long("verify")
+            .help("Verify the checksums of the lockfile")
+            .action(ArgAction::SetTrue)
+            .required(true),
+        )
+        .arg(ca_file_arg())
+        .arg(cached_only_arg())
+        .arg(config_arg())
+        .arg(no_config_arg())
+        .arg(lock_arg())
+        .arg(reload_arg())
+    })
+}
+
+fn outdated_subcommand() -> Command {
+  Command::new("outdated")
+    .about("Check for newer versions of the dependencies")
+    .long_about(
+      "Check the registries for newer versions of the jsr, npm and deno.land
+dependencies of the import map and the lockfile.
+
+  deno outdated
+
+The dependencies which aren't on the latest version are listed with the
+version of the lockfile, the highest version matching the version
+requirement and the latest version. Versions in the URLs of deno.land modules
+are treated like caret requirements.
+
+Update the specifiers of the import map to the wanted versions, keeping
+their version requirements, and update the lockfile:
+
+  deno outdated --update",
+    )
+    .defer(|cmd| {
+      cmd
+        .arg(
+          Arg::new("json")
+            .long("json")
+            .help("Output the outdated dependencies in JSON format")
+            .action(ArgAction::SetTrue)
+            .conflicts_with("update"),
+        )
+        .arg(
+          Arg::new("update")
+            .long("update")
+            .help("Update the dependencies to the wanted versions")
+            .action(ArgAction::SetTrue),
+        )
+        .arg(ca_file_arg())
+        .arg(cached_only_arg())
+        .arg(config_arg())
+        .arg(no_config_arg())
+        .arg(import_map_arg())
+        .arg(lock_arg())
+        .arg(no_lock_arg())
+    })
+}
+
+fn remove_subcommand() -> Command {
+  Command::new("remove")
+    .about("Remove dependencies from the import map")
+    .long_about(
+      "Remove packages from the \"imports\" of the configuration file, or of the
+import map file it points to, along with their entries of the lockfile.
+
+  deno remove @std/http express
+
+A name without a prefix removes the jsr and npm packages with that name. The
+package is also removed from the dependencies of the package.json.",
+    )
+    .defer(|cmd| {
+      cmd
+        .arg(
+          Arg::new("packages")
+            .help("The packages to remove")
+            .num_args(1..)
+            .action(ArgAction::Append)
+            .required(true),
+        )
+        .arg(config_arg())
+        .arg(no_config_arg())
+        .arg(import_map_arg())
+        .arg(lock_arg())
+        .arg(no_lock_arg())
+    })
+}
+
 fn publish_subcommand() -> Command {
   Command::new("publish")
     .hide(true)
@@ -2379,6 +3638,16 @@ fn publish_subcommand() -> Command {
           .long("token")
           .help("The API token to use when publishing. If unset, interactive authentication is be used")
       )
+      .arg(
+        Arg::new("registry")
+          .long("registry")
+          .value_name("URL")
+          .help("The URL of the jsr compatible registry to publish to. The token of the host in DENO_AUTH_TOKENS is used to authenticate, unless --token is passed")
+          .value_parser(|url: &str| {
+            Url::parse(url).map_err(|_| "Failed to parse URL".to_string())
+          })
+          .value_hint(ValueHint::Url),
+      )
       .arg(
         Arg::new("dry-run")
           .long("dry-run")
@@ -2391,6 +3660,21 @@ fn publish_subcommand() -> Command {
           .help("Skip Zap compatibility validation")
           .action(ArgAction::SetTrue),
       )
+      .arg(
+        Arg::new("bump")
+          .long("bump")
+          .value_name("KIND")
+          .help("Bump the version of the packages in their configuration files before publishing. Fails if the new version is already published")
+          .value_parser(["patch", "minor", "major"]),
+      )
+      .arg(
+        Arg::new("git")
+          .long("git")
+          .help("Commit the bumped versions and create a git tag for each package")
+          .requires("bump")
+          .action(ArgAction::SetTrue),
+      )
+      .arg(gitignore_arg())
     })
 }
 
@@ -2481,7 +3765,31 @@ static ALLOW_ENV_HELP: &str = concat!(
   "/basics/permissions\n",
   "Examples:\n",
   "  --allow-env\n",
-  "  --allow-env=\"PORT,HOME,PATH\""
+  "  --allow-env=\"PORT,HOME,PATH\"\n",
+  "  --allow-env=\"AWS_*\""
+);
+
+static PERMISSIONS_FILE_HELP: &str = concat!(
+  "Grant the permissions of a JSON file, which maps the names of the permissions to true or to a list of values.\n",
+  "The file is created when it doesn't exist, and the permissions allowed in prompts are saved to it, so they\n",
+  "aren't prompted for again in the next runs.\n",
+  "Examples:\n",
+  "  --permissions-file=permissions.json\n",
+  "  { \"read\": [\"./data\"], \"net\": [\"deno.land\"], \"env\": true }"
+);
+
+static PERMISSION_SET_HELP: &str = concat!(
+  "Grant the permissions of a set of the \"permissions\" object of the configuration file.\n",
+  "Examples:\n",
+  "  --permission-set=serve\n",
+  "  { \"permissions\": { \"serve\": { \"net\": true, \"read\": [\"./static\"] } } }"
+);
+
+static PERMISSION_LOG_HELP: &str = concat!(
+  "Record every check of a permission to a file, with one JSON object per line, to find the permissions a program uses.\n",
+  "Examples:\n",
+  "  --permission-log=permissions.jsonl\n",
+  "  {\"permission\":\"read\",\"resource\":\"/app/data.json\",\"apiName\":\"Deno.readFile()\",\"granted\":true,\"prompted\":false}"
 );
 
 static DENY_ENV_HELP: &str = concat!(
@@ -2773,6 +4081,29 @@ fn permission_args(app: Command) -> Command {
         .action(ArgAction::SetTrue)
         .help("Always throw if required permission wasn't passed"),
     )
+    .arg(
+      Arg::new("permissions-file")
+        .long("permissions-file")
+        .value_name("FILE")
+        .require_equals(true)
+        .help(PERMISSIONS_FILE_HELP)
+        .value_hint(ValueHint::FilePath),
+    )
+    .arg(
+      Arg::new("permission-set")
+        .long("permission-set")
+        .value_name("NAME")
+        .require_equals(true)
+        .help(PERMISSION_SET_HELP),
+    )
+    .arg(
+      Arg::new("permission-log")
+        .long("permission-log")
+        .value_name("FILE")
+        .require_equals(true)
+        .help(PERMISSION_LOG_HELP)
+        .value_hint(ValueHint::FilePath),
+    )
 }
 
 fn runtime_args(
@@ -2842,6 +4173,21 @@ Specification: https://wicg.github.io/import-maps/
 Examples: https://github.com/WICG/import-maps#the-import-map",
 );
 
+fn coverage_threshold_arg() -> Arg {
+  Arg::new("coverage-threshold")
+    .long("coverage-threshold")
+    .value_name("THRESHOLD")
+    .require_equals(true)
+    .value_parser(|threshold: &str| threshold.parse::<CoverageThreshold>())
+    .help("Fail if the line, branch or function coverage is below a percentage")
+    .long_help(
+      "Fail if the total line, branch or function coverage is below a \
+percentage, printing the files which are below it. For example \
+'--coverage-threshold=lines:90,branches:80'. This overrides \
+\"coverage.threshold\" in the configuration file.",
+    )
+}
+
 fn import_map_arg() -> Arg {
   Arg::new("import-map")
     .long("import-map")
@@ -2853,15 +4199,17 @@ fn import_map_arg() -> Arg {
 }
 
 fn env_file_arg() -> Arg {
-  Arg::new("env")
-    .long("env")
+  Arg::new("env-file")
+    .long("env-file")
+    .alias("env")
     .value_name("FILE")
     .help("Load .env file")
-    .long_help("UNSTABLE: Load environment variables from local file. Only the first environment variable with a given key is used. Existing process environment variables are not overwritten.")
+    .long_help("UNSTABLE: Load environment variables from local file. Can be used multiple times, in which case the variables of later files take precedence over the ones of earlier files. Only the first environment variable with a given key in a file is used. Existing process environment variables are not overwritten.")
     .value_hint(ValueHint::FilePath)
     .default_missing_value(".env")
     .require_equals(true)
     .num_args(0..=1)
+    .action(ArgAction::Append)
 }
 
 fn reload_arg() -> Arg {
@@ -2978,6 +4326,7 @@ fn seed_arg() -> Arg {
 fn hmr_arg(takes_files: bool) -> Arg {
   let arg = Arg::new("hmr")
     .long("unstable-hmr")
+    .visible_alias("watch-hmr")
     .help("UNSTABLE: Watch for file changes and hot replace modules")
     .conflicts_with("watch");
 
@@ -3145,6 +4494,20 @@ fn config_arg() -> Arg {
     .value_hint(ValueHint::FilePath)
 }
 
+fn gitignore_arg() -> Arg {
+  Arg::new("gitignore")
+    .long("gitignore")
+    .action(ArgAction::SetTrue)
+    .help("Exclude the files ignored by the .gitignore files")
+}
+
+fn no_gitignore_arg() -> Arg {
+  Arg::new("no-gitignore")
+    .long("no-gitignore")
+    .action(ArgAction::SetTrue)
+    .help("Don't exclude the files ignored by the .gitignore files")
+}
+
 fn no_config_arg() -> Arg {
   Arg::new("no-config")
     .long("no-config")
@@ -3171,10 +4534,10 @@ fn node_modules_dir_arg() -> Arg {
   Arg::new("node-modules-dir")
     .long("node-modules-dir")
     .num_args(0..=1)
-    .value_parser(value_parser!(bool))
+    .value_parser(["true", "false", "linked"])
     .default_missing_value("true")
     .require_equals(true)
-    .help("Enables or disables the use of a local node_modules folder for npm packages")
+    .help("Enables or disables the use of a local node_modules folder for npm packages. Use \"linked\" to hard link the packages to the global npm cache instead of copying them")
 }
 
 fn vendor_arg() -> Arg {
@@ -3198,10 +4561,43 @@ fn unsafely_ignore_certificate_errors_arg() -> Arg {
     .value_parser(flags_net::validator)
 }
 
+fn add_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  ca_file_arg_parse(flags, matches);
+  cached_only_arg_parse(flags, matches);
+  config_args_parse(flags, matches);
+  import_map_arg_parse(flags, matches);
+  lock_arg_parse(flags, matches);
+  no_lock_arg_parse(flags, matches);
+
+  flags.subcommand = DenoSubcommand::Add(AddFlags {
+    packages: matches.remove_many::<String>("packages").unwrap().collect(),
+    dev: matches.get_flag("dev"),
+    cache: matches.get_flag("cache"),
+  });
+}
+
+fn audit_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  ca_file_arg_parse(flags, matches);
+  cached_only_arg_parse(flags, matches);
+  config_args_parse(flags, matches);
+  lock_arg_parse(flags, matches);
+  no_lock_arg_parse(flags, matches);
+  node_modules_and_vendor_dir_arg_parse(flags, matches);
+
+  flags.subcommand = DenoSubcommand::Audit(AuditFlags {
+    json: matches.get_flag("json"),
+    level: matches
+      .remove_one::<String>("level")
+      .map(|level| level.parse().unwrap())
+      .unwrap_or_default(),
+  });
+}
+
 fn bench_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   flags.type_check_mode = TypeCheckMode::Local;
 
   runtime_args_parse(flags, matches, true, false);
+  gitignore_arg_parse(flags, matches);
 
   // NOTE: `deno bench` always uses `--no-prompt`, tests shouldn't ever do
   // interactive prompts, unless done by user code
@@ -3230,12 +4626,27 @@ fn bench_parse(flags: &mut Flags, matches: &mut ArgMatches) {
 
   let no_run = matches.get_flag("no-run");
 
+  let reporter =
+    if let Some(reporter) = matches.remove_one::<String>("reporter") {
+      match reporter.as_str() {
+        "pretty" => BenchReporterConfig::Pretty,
+        "json" => BenchReporterConfig::Json,
+        "junit" => BenchReporterConfig::Junit,
+        _ => unreachable!(),
+      }
+    } else {
+      BenchReporterConfig::Pretty
+    };
+
   flags.subcommand = DenoSubcommand::Bench(BenchFlags {
     files: FileFlags { include, ignore },
     filter,
     json,
     no_run,
     watch: watch_arg_parse(matches),
+    reporter,
+    baseline: matches.remove_one::<String>("baseline"),
+    regression_threshold_bps: matches.remove_one::<u32>("regression-threshold"),
   });
 }
 
@@ -3260,35 +4671,67 @@ fn bundle_parse(flags: &mut Flags, matches: &mut ArgMatches) {
     source_file,
     out_file,
     watch: watch_arg_parse(matches),
+    minify: matches.get_flag("minify"),
+    source_map: matches.get_flag("source-map"),
   });
 }
 
 fn cache_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   compile_args_parse(flags, matches);
-  let files = matches.remove_many::<String>("file").unwrap().collect();
-  flags.subcommand = DenoSubcommand::Cache(CacheFlags { files });
+  let prune = matches.get_flag("prune").then(|| CachePruneFlags {
+    max_age: matches.remove_one::<u64>("max-age"),
+    max_size: matches.remove_one::<u64>("max-size"),
+  });
+  let files = match matches.remove_many::<String>("file") {
+    Some(files) => files.collect(),
+    None => Vec::new(),
+  };
+  flags.subcommand = DenoSubcommand::Cache(CacheFlags { files, prune });
 }
 
 fn check_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   flags.type_check_mode = TypeCheckMode::Local;
   compile_args_without_check_parse(flags, matches);
-  let files = matches.remove_many::<String>("file").unwrap().collect();
+  gitignore_arg_parse(flags, matches);
+  let files = match matches.remove_many::<String>("file") {
+    Some(files) => files.collect(),
+    None => Vec::new(),
+  };
   if matches.get_flag("all") || matches.get_flag("remote") {
     flags.type_check_mode = TypeCheckMode::All;
   }
-  flags.subcommand = DenoSubcommand::Check(CheckFlags { files });
+  flags.subcommand = DenoSubcommand::Check(CheckFlags {
+    files,
+    workspace: matches.get_flag("workspace"),
+    trace: matches.remove_one::<String>("trace"),
+  });
 }
 
 fn compile_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   flags.type_check_mode = TypeCheckMode::Local;
   runtime_args_parse(flags, matches, true, false);
 
-  let mut script = matches.remove_many::<String>("script_arg").unwrap();
-  let source_file = script.next().unwrap();
+  let mut script = matches
+    .remove_many::<String>("script_arg")
+    .into_iter()
+    .flatten();
+  // the script is only optional when listing the targets
+  let source_file = script.next().unwrap_or_default();
   let args = script.collect();
   let output = matches.remove_one::<PathBuf>("output");
   let target = matches.remove_one::<String>("target");
   let no_terminal = matches.get_flag("no-terminal");
+  let list_targets = matches.get_flag("list-targets");
+  let sign_identity = matches.remove_one::<String>("sign-identity");
+  let sign_command = matches.remove_one::<String>("sign-command");
+  let sign_args = match matches.remove_many::<String>("sign-arg") {
+    Some(f) => f.collect(),
+    None => vec![],
+  };
+  let icon = matches.remove_one::<PathBuf>("icon");
+  let version_info = matches.remove_one::<String>("version-info");
+  let strip = matches.get_flag("strip");
+  let include_source_maps = matches.get_flag("include-source-maps");
   let include = match matches.remove_many::<String>("include") {
     Some(f) => f.collect(),
     None => vec![],
@@ -3302,6 +4745,14 @@ fn compile_parse(flags: &mut Flags, matches: &mut ArgMatches) {
     target,
     no_terminal,
     include,
+    list_targets,
+    sign_identity,
+    sign_command,
+    sign_args,
+    icon,
+    version_info,
+    strip,
+    include_source_maps,
   });
 }
 
@@ -3335,6 +4786,7 @@ fn completions_parse(
 }
 
 fn coverage_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  gitignore_arg_parse(flags, matches);
   let files = match matches.remove_many::<String>("files") {
     Some(f) => f.collect(),
     None => vec!["coverage".to_string()], // default
@@ -3353,6 +4805,10 @@ fn coverage_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   };
   let r#type = if matches.get_flag("lcov") {
     CoverageType::Lcov
+  } else if matches.get_flag("cobertura") {
+    CoverageType::Cobertura
+  } else if matches.get_flag("json-summary") {
+    CoverageType::JsonSummary
   } else if matches.get_flag("html") {
     CoverageType::Html
   } else if matches.get_flag("detailed") {
@@ -3361,6 +4817,7 @@ fn coverage_parse(flags: &mut Flags, matches: &mut ArgMatches) {
     CoverageType::Summary
   };
   let output = matches.remove_one::<PathBuf>("output");
+  let threshold = matches.remove_one::<CoverageThreshold>("coverage-threshold");
   flags.subcommand = DenoSubcommand::Coverage(CoverageFlags {
     files: FileFlags {
       include: files,
@@ -3370,6 +4827,7 @@ fn coverage_parse(flags: &mut Flags, matches: &mut ArgMatches) {
     include,
     exclude,
     r#type,
+    threshold,
   });
 }
 
@@ -3382,7 +4840,9 @@ fn doc_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   no_remote_arg_parse(flags, matches);
 
   let source_files_val = matches.remove_many::<String>("source_file");
-  let source_files = if let Some(val) = source_files_val {
+  let source_files = if matches.get_flag("workspace") {
+    DocSourceFileFlag::Workspace
+  } else if let Some(val) = source_files_val {
     let vals: Vec<String> = val.collect();
 
     if vals.len() == 1 {
@@ -3412,12 +4872,22 @@ fn doc_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   } else {
     None
   };
+  let markdown = if matches.get_flag("markdown") {
+    let name = matches.remove_one::<String>("name");
+    let output = matches
+      .remove_one::<PathBuf>("output")
+      .unwrap_or(PathBuf::from("./docs/"));
+    Some(DocMarkdownFlag { name, output })
+  } else {
+    None
+  };
 
   flags.subcommand = DenoSubcommand::Doc(DocFlags {
     source_files,
     json,
     lint,
     html,
+    markdown,
     filter,
     private,
   });
@@ -3461,6 +4931,7 @@ fn eval_parse(flags: &mut Flags, matches: &mut ArgMatches) {
 fn fmt_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   config_args_parse(flags, matches);
   ext_arg_parse(flags, matches);
+  no_gitignore_arg_parse(flags, matches);
 
   let include = match matches.remove_many::<String>("files") {
     Some(f) => f.collect(),
@@ -3487,6 +4958,7 @@ fn fmt_parse(flags: &mut Flags, matches: &mut ArgMatches) {
     single_quote,
     prose_wrap,
     no_semicolons,
+    organize_imports: matches.get_flag("organize-imports"),
     watch: watch_arg_parse(matches),
   });
 }
@@ -3494,6 +4966,9 @@ fn fmt_parse(flags: &mut Flags, matches: &mut ArgMatches) {
 fn init_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   flags.subcommand = DenoSubcommand::Init(InitFlags {
     dir: matches.remove_one::<String>("dir"),
+    template: matches.remove_one::<String>("template"),
+    ci: matches.get_flag("ci"),
+    yes: matches.get_flag("yes"),
   });
 }
 
@@ -3508,10 +4983,15 @@ fn info_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   no_lock_arg_parse(flags, matches);
   no_remote_arg_parse(flags, matches);
   no_npm_arg_parse(flags, matches);
-  let json = matches.get_flag("json");
+  let format = matches.remove_one::<String>("format");
+  let json = matches.get_flag("json") || format.as_deref() == Some("json");
+  let dot = format.as_deref() == Some("dot");
   flags.subcommand = DenoSubcommand::Info(InfoFlags {
     file: matches.remove_one::<String>("file"),
     json,
+    dot,
+    diff: matches.remove_one::<String>("diff"),
+    licenses: matches.get_flag("licenses"),
   });
 }
 
@@ -3555,12 +5035,16 @@ fn uninstall_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   flags.subcommand = DenoSubcommand::Uninstall(UninstallFlags { name, root });
 }
 
-fn lsp_parse(flags: &mut Flags, _matches: &mut ArgMatches) {
-  flags.subcommand = DenoSubcommand::Lsp;
+fn lsp_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  flags.subcommand = DenoSubcommand::Lsp(LspFlags {
+    log_file: matches.remove_one::<PathBuf>("log-file"),
+    otlp_endpoint: matches.remove_one::<Url>("otlp-endpoint"),
+  });
 }
 
 fn lint_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   config_args_parse(flags, matches);
+  no_gitignore_arg_parse(flags, matches);
   let files = match matches.remove_many::<String>("files") {
     Some(f) => f.collect(),
     None => vec![],
@@ -3647,15 +5131,44 @@ fn run_parse(
   Ok(())
 }
 
+fn serve_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  runtime_args_parse(flags, matches, true, true);
+
+  let mut script_arg = matches.remove_many::<String>("script_arg").unwrap();
+  let script = script_arg.next().unwrap();
+  flags.argv.extend(script_arg);
+  let port = matches.remove_one::<u16>("port").unwrap();
+  let host = matches.remove_one::<String>("host").unwrap();
+  let dev = matches.get_flag("dev");
+  let editor_url = matches.remove_one::<String>("editor-url");
+
+  ext_arg_parse(flags, matches);
+
+  flags.subcommand = DenoSubcommand::Serve(ServeFlags {
+    script,
+    watch: watch_arg_parse_with_paths(matches),
+    port,
+    host,
+    dev,
+    editor_url,
+  });
+}
+
 fn task_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   flags.config_flag = matches
     .remove_one::<String>("config")
     .map(ConfigFlag::Path)
     .unwrap_or(ConfigFlag::Discover);
 
+  env_file_arg_parse(flags, matches);
+
+  let filter = matches.remove_one::<String>("filter");
   let mut task_flags = TaskFlags {
     cwd: matches.remove_one::<String>("cwd"),
     task: None,
+    recursive: matches.get_flag("recursive") || filter.is_some(),
+    filter,
+    no_watch: matches.get_flag("no-watch"),
   };
 
   if let Some((task, mut matches)) = matches.remove_subcommand() {
@@ -3676,6 +5189,7 @@ fn task_parse(flags: &mut Flags, matches: &mut ArgMatches) {
 fn test_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   flags.type_check_mode = TypeCheckMode::Local;
   runtime_args_parse(flags, matches, true, true);
+  no_gitignore_arg_parse(flags, matches);
   // NOTE: `deno test` always uses `--no-prompt`, tests shouldn't ever do
   // interactive prompts, unless done by user code
   flags.no_prompt = true;
@@ -3715,29 +5229,18 @@ fn test_parse(flags: &mut Flags, matches: &mut ArgMatches) {
     flags.argv.extend(script_arg);
   }
 
-  let concurrent_jobs = if matches.get_flag("parallel") {
-    if let Ok(value) = env::var("DENO_JOBS") {
-      value.parse::<NonZeroUsize>().ok()
-    } else {
-      std::thread::available_parallelism().ok()
-    }
-  } else if matches.contains_id("jobs") {
-    // We can't change this to use the log crate because its not configured
-    // yet at this point since the flags haven't been parsed. This flag is
-    // deprecated though so it's not worth changing the code to use the log
-    // crate here and this is only done for testing anyway.
-    eprintln!(
-      "⚠️ {}",
-      crate::colors::yellow("The `--jobs` flag is deprecated and will be removed in Deno 2.0.\nUse the `--parallel` flag with possibly the `DENO_JOBS` environment variable instead.\nLearn more at: https://docs.deno.com/runtime/manual/basics/env_variables"),
-    );
-    if let Some(value) = matches.remove_one::<NonZeroUsize>("jobs") {
-      Some(value)
+  let concurrent_jobs =
+    if matches.get_flag("parallel") || matches.contains_id("jobs") {
+      if let Some(value) = matches.remove_one::<NonZeroUsize>("jobs") {
+        Some(value)
+      } else if let Ok(value) = env::var("DENO_JOBS") {
+        value.parse::<NonZeroUsize>().ok()
+      } else {
+        std::thread::available_parallelism().ok()
+      }
     } else {
-      std::thread::available_parallelism().ok()
-    }
-  } else {
-    None
-  };
+      None
+    };
 
   let include = if let Some(files) = matches.remove_many::<String>("files") {
     files.collect()
@@ -3768,6 +5271,8 @@ fn test_parse(flags: &mut Flags, matches: &mut ArgMatches) {
     no_run,
     doc,
     coverage_dir: matches.remove_one::<String>("coverage"),
+    coverage_threshold: matches
+      .remove_one::<CoverageThreshold>("coverage-threshold"),
     fail_fast,
     files: FileFlags { include, ignore },
     filter,
@@ -3778,11 +5283,19 @@ fn test_parse(flags: &mut Flags, matches: &mut ArgMatches) {
     watch: watch_arg_parse(matches),
     reporter,
     junit_path,
+    shard: matches.remove_one::<TestShard>("shard"),
+    retry: matches.remove_one::<usize>("retry"),
+    update_snapshots: matches.get_flag("update-snapshots"),
+    changed: matches.remove_one::<String>("changed"),
+    shard_timings: matches.remove_one::<String>("shard-timings"),
   });
 }
 
-fn types_parse(flags: &mut Flags, _matches: &mut ArgMatches) {
-  flags.subcommand = DenoSubcommand::Types;
+fn types_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  flags.subcommand = DenoSubcommand::Types(TypesFlags {
+    ffi: matches.remove_one::<String>("ffi"),
+    check: matches.remove_one::<String>("check"),
+  });
 }
 
 fn upgrade_parse(flags: &mut Flags, matches: &mut ArgMatches) {
@@ -3790,15 +5303,29 @@ fn upgrade_parse(flags: &mut Flags, matches: &mut ArgMatches) {
 
   let dry_run = matches.get_flag("dry-run");
   let force = matches.get_flag("force");
-  let canary = matches.get_flag("canary");
+  let channel = if matches.get_flag("canary") {
+    UpgradeChannel::Canary
+  } else {
+    match matches.remove_one::<String>("channel").as_deref() {
+      Some("rc") => UpgradeChannel::Rc,
+      Some("canary") => UpgradeChannel::Canary,
+      Some("lts") => UpgradeChannel::Lts,
+      Some("stable") | None => UpgradeChannel::Stable,
+      _ => unreachable!(),
+    }
+  };
   let version = matches.remove_one::<String>("version");
   let output = matches.remove_one::<PathBuf>("output");
+  let rollback = matches.get_flag("rollback");
+  let archive = matches.remove_one::<PathBuf>("archive");
   flags.subcommand = DenoSubcommand::Upgrade(UpgradeFlags {
     dry_run,
     force,
-    canary,
+    channel,
     version,
     output,
+    rollback,
+    archive,
   });
 }
 
@@ -3820,11 +5347,59 @@ fn vendor_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   });
 }
 
+fn lock_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  ca_file_arg_parse(flags, matches);
+  cached_only_arg_parse(flags, matches);
+  config_args_parse(flags, matches);
+  lock_arg_parse(flags, matches);
+  reload_arg_parse(flags, matches);
+
+  flags.subcommand = DenoSubcommand::Lock(LockFlags {
+    verify: matches.get_flag("verify"),
+  });
+}
+
+fn outdated_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  ca_file_arg_parse(flags, matches);
+  cached_only_arg_parse(flags, matches);
+  config_args_parse(flags, matches);
+  import_map_arg_parse(flags, matches);
+  lock_arg_parse(flags, matches);
+  no_lock_arg_parse(flags, matches);
+
+  flags.subcommand = DenoSubcommand::Outdated(OutdatedFlags {
+    json: matches.get_flag("json"),
+    update: matches.get_flag("update"),
+  });
+}
+
+fn remove_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  config_args_parse(flags, matches);
+  import_map_arg_parse(flags, matches);
+  lock_arg_parse(flags, matches);
+  no_lock_arg_parse(flags, matches);
+
+  flags.subcommand = DenoSubcommand::Remove(RemoveFlags {
+    packages: matches.remove_many::<String>("packages").unwrap().collect(),
+  });
+}
+
 fn publish_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  gitignore_arg_parse(flags, matches);
   flags.subcommand = DenoSubcommand::Publish(PublishFlags {
     token: matches.remove_one("token"),
+    registry: matches.remove_one::<Url>("registry"),
     dry_run: matches.get_flag("dry-run"),
     no_zap: matches.get_flag("no-zap"),
+    bump: matches.remove_one::<String>("bump").map(|bump| {
+      match bump.as_str() {
+        "patch" => VersionBump::Patch,
+        "minor" => VersionBump::Minor,
+        "major" => VersionBump::Major,
+        _ => unreachable!(),
+      }
+    }),
+    git: matches.get_flag("git"),
   });
 }
 
@@ -3939,6 +5514,10 @@ fn permission_args_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   if matches.get_flag("no-prompt") {
     flags.no_prompt = true;
   }
+
+  flags.permissions_file = matches.remove_one::<String>("permissions-file");
+  flags.permission_set = matches.remove_one::<String>("permission-set");
+  flags.permission_log = matches.remove_one::<String>("permission-log");
 }
 
 fn unsafely_ignore_certificate_errors_parse(
@@ -4011,7 +5590,9 @@ fn import_map_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
 }
 
 fn env_file_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
-  flags.env_file = matches.remove_one::<String>("env");
+  flags.env_file = matches
+    .remove_many::<String>("env-file")
+    .map(|files| files.collect());
 }
 
 fn reload_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
@@ -4135,6 +5716,14 @@ fn config_args_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   };
 }
 
+fn gitignore_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  flags.gitignore = matches.get_flag("gitignore");
+}
+
+fn no_gitignore_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  flags.no_gitignore = matches.get_flag("no-gitignore");
+}
+
 fn no_remote_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   if matches.get_flag("no-remote") {
     flags.no_remote = true;
@@ -4151,10 +5740,65 @@ fn node_modules_and_vendor_dir_arg_parse(
   flags: &mut Flags,
   matches: &mut ArgMatches,
 ) {
-  flags.node_modules_dir = matches.remove_one::<bool>("node-modules-dir");
+  match matches.remove_one::<String>("node-modules-dir").as_deref() {
+    Some("linked") => {
+      flags.node_modules_dir = Some(true);
+      flags.node_modules_dir_mode = NodeModulesDirMode::Linked;
+    }
+    Some(value) => flags.node_modules_dir = Some(value == "true"),
+    None => {}
+  }
   flags.vendor = matches.remove_one::<bool>("vendor");
 }
 
+/// Parses a duration like "30d", "12h", "90m" or "45s" into seconds.
+fn parse_duration_secs(value: &str) -> Result<u64, String> {
+  let index = value.find(|c: char| !c.is_ascii_digit()).ok_or_else(|| {
+    format!("Missing unit in '{value}'. Use s, m, h, d or w.")
+  })?;
+  let (number, unit) = value.split_at(index);
+  let number = number
+    .parse::<u64>()
+    .map_err(|_| format!("Invalid duration '{value}'."))?;
+  let multiplier = match unit {
+    "s" => 1,
+    "m" => 60,
+    "h" => 60 * 60,
+    "d" => 24 * 60 * 60,
+    "w" => 7 * 24 * 60 * 60,
+    _ => {
+      return Err(format!(
+        "Invalid unit '{unit}' in '{value}'. Use s, m, h, d or w."
+      ))
+    }
+  };
+  Ok(number * multiplier)
+}
+
+/// Parses a size like "5GB", "500MB", "64KB" or "100B" into bytes.
+fn parse_size_bytes(value: &str) -> Result<u64, String> {
+  let index = value.find(|c: char| !c.is_ascii_digit()).ok_or_else(|| {
+    format!("Missing unit in '{value}'. Use B, KB, MB, GB or TB.")
+  })?;
+  let (number, unit) = value.split_at(index);
+  let number = number
+    .parse::<u64>()
+    .map_err(|_| format!("Invalid size '{value}'."))?;
+  let multiplier: u64 = match unit.to_ascii_uppercase().as_str() {
+    "B" => 1,
+    "KB" => 1024,
+    "MB" => 1024 * 1024,
+    "GB" => 1024 * 1024 * 1024,
+    "TB" => 1024 * 1024 * 1024 * 1024,
+    _ => {
+      return Err(format!(
+        "Invalid unit '{unit}' in '{value}'. Use B, KB, MB, GB or TB."
+      ))
+    }
+  };
+  Ok(number * multiplier)
+}
+
 fn reload_arg_validate(urlstr: &str) -> Result<String, String> {
   if urlstr.is_empty() {
     return Err(String::from("Missing url. Check for extra commas."));
@@ -4261,13 +5905,100 @@ mod tests {
         subcommand: DenoSubcommand::Upgrade(UpgradeFlags {
           force: true,
           dry_run: true,
-          canary: false,
+          channel: UpgradeChannel::Stable,
+          version: None,
+          output: None,
+          rollback: false,
+          archive: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "upgrade", "--canary"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Upgrade(UpgradeFlags {
+          force: false,
+          dry_run: false,
+          channel: UpgradeChannel::Canary,
+          version: None,
+          output: None,
+          rollback: false,
+          archive: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "upgrade", "--channel", "lts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Upgrade(UpgradeFlags {
+          force: false,
+          dry_run: false,
+          channel: UpgradeChannel::Lts,
+          version: None,
+          output: None,
+          rollback: false,
+          archive: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "upgrade",
+      "--archive",
+      "deno-x86_64-unknown-linux-gnu.zip"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Upgrade(UpgradeFlags {
+          force: false,
+          dry_run: false,
+          channel: UpgradeChannel::Stable,
+          version: None,
+          output: None,
+          rollback: false,
+          archive: Some(PathBuf::from("deno-x86_64-unknown-linux-gnu.zip")),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "upgrade", "--rollback"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Upgrade(UpgradeFlags {
+          force: false,
+          dry_run: false,
+          channel: UpgradeChannel::Stable,
           version: None,
           output: None,
+          rollback: true,
+          archive: None,
         }),
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "upgrade",
+      "--rollback",
+      "--version",
+      "1.40.0"
+    ]);
+    assert!(r.is_err());
+    let r =
+      flags_from_vec(svec!["deno", "upgrade", "--canary", "--channel", "rc"]);
+    assert!(r.is_err());
   }
 
   #[test]
@@ -4441,6 +6172,90 @@ mod tests {
     );
   }
 
+  #[test]
+  fn serve() {
+    let r = flags_from_vec(svec!["deno", "serve", "main.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Serve(ServeFlags {
+          script: "main.ts".to_string(),
+          watch: None,
+          port: 8000,
+          host: "0.0.0.0".to_string(),
+          dev: false,
+          editor_url: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "serve",
+      "--watch-hmr",
+      "--port",
+      "3000",
+      "--host",
+      "127.0.0.1",
+      "main.ts",
+      "arg"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Serve(ServeFlags {
+          script: "main.ts".to_string(),
+          watch: Some(WatchFlagsWithPaths {
+            hmr: true,
+            paths: vec![],
+            no_clear_screen: false,
+          }),
+          port: 3000,
+          host: "127.0.0.1".to_string(),
+          dev: false,
+          editor_url: None,
+        }),
+        argv: svec!["arg"],
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "serve",
+      "--dev",
+      "--editor-url",
+      "idea://open?file={path}&line={line}",
+      "main.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Serve(ServeFlags {
+          script: "main.ts".to_string(),
+          watch: None,
+          port: 8000,
+          host: "0.0.0.0".to_string(),
+          dev: true,
+          editor_url: Some("idea://open?file={path}&line={line}".to_string()),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "serve", "--port", "foo", "main.ts"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec![
+      "deno",
+      "serve",
+      "--editor-url",
+      "vscode://file{path}",
+      "main.ts"
+    ]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn run_reload_allow_write() {
     let r =
@@ -4610,12 +6425,81 @@ mod tests {
   }
 
   #[test]
-  fn deny_hrtime() {
-    let r = flags_from_vec(svec!["deno", "run", "--deny-hrtime", "gist.ts"]);
-    assert_eq!(
-      r.unwrap(),
-      Flags {
-        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+  fn permissions_file() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--permissions-file=permissions.json",
+      "gist.ts"
+    ]);
+    let flags = r.unwrap();
+    assert_eq!(
+      flags,
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "gist.ts".to_string(),
+        )),
+        permissions_file: Some("permissions.json".to_string()),
+        ..Flags::default()
+      }
+    );
+    assert!(flags.has_permission());
+    assert_eq!(
+      flags.to_permission_args(),
+      svec!["--permissions-file=permissions.json"]
+    );
+  }
+
+  #[test]
+  fn permission_set() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "test",
+      "--permission-set=test",
+      "--allow-net"
+    ]);
+    let flags = r.unwrap();
+    assert_eq!(flags.permission_set, Some("test".to_string()));
+    assert!(flags.has_permission());
+    assert_eq!(
+      flags.to_permission_args(),
+      svec!["--allow-net", "--permission-set=test"]
+    );
+  }
+
+  #[test]
+  fn permission_log() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--permission-log=permissions.jsonl",
+      "gist.ts"
+    ]);
+    let flags = r.unwrap();
+    assert_eq!(
+      flags,
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "gist.ts".to_string(),
+        )),
+        permission_log: Some("permissions.jsonl".to_string()),
+        ..Flags::default()
+      }
+    );
+    assert!(!flags.has_permission());
+    assert_eq!(
+      flags.to_permission_args(),
+      svec!["--permission-log=permissions.jsonl"]
+    );
+  }
+
+  #[test]
+  fn deny_hrtime() {
+    let r = flags_from_vec(svec!["deno", "run", "--deny-hrtime", "gist.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
           "gist.ts".to_string(),
         )),
         deny_hrtime: true,
@@ -4669,6 +6553,7 @@ mod tests {
           single_quote: None,
           prose_wrap: None,
           no_semicolons: None,
+          organize_imports: false,
           watch: Default::default(),
         }),
         ext: Some("ts".to_string()),
@@ -4692,6 +6577,7 @@ mod tests {
           single_quote: None,
           prose_wrap: None,
           no_semicolons: None,
+          organize_imports: false,
           watch: Default::default(),
         }),
         ext: Some("ts".to_string()),
@@ -4715,6 +6601,7 @@ mod tests {
           single_quote: None,
           prose_wrap: None,
           no_semicolons: None,
+          organize_imports: false,
           watch: Default::default(),
         }),
         ext: Some("ts".to_string()),
@@ -4738,6 +6625,7 @@ mod tests {
           single_quote: None,
           prose_wrap: None,
           no_semicolons: None,
+          organize_imports: false,
           watch: Some(Default::default()),
         }),
         ext: Some("ts".to_string()),
@@ -4762,6 +6650,7 @@ mod tests {
           single_quote: None,
           prose_wrap: None,
           no_semicolons: None,
+          organize_imports: false,
           watch: Some(WatchFlags {
             hmr: false,
             no_clear_screen: true,
@@ -4795,6 +6684,7 @@ mod tests {
           single_quote: None,
           prose_wrap: None,
           no_semicolons: None,
+          organize_imports: false,
           watch: Some(Default::default()),
         }),
         ext: Some("ts".to_string()),
@@ -4818,6 +6708,7 @@ mod tests {
           single_quote: None,
           prose_wrap: None,
           no_semicolons: None,
+          organize_imports: false,
           watch: Default::default(),
         }),
         ext: Some("ts".to_string()),
@@ -4849,6 +6740,7 @@ mod tests {
           single_quote: None,
           prose_wrap: None,
           no_semicolons: None,
+          organize_imports: false,
           watch: Some(Default::default()),
         }),
         config_flag: ConfigFlag::Path("deno.jsonc".to_string()),
@@ -4885,6 +6777,7 @@ mod tests {
           single_quote: Some(true),
           prose_wrap: Some("never".to_string()),
           no_semicolons: Some(true),
+          organize_imports: false,
           watch: Default::default(),
         }),
         ext: Some("ts".to_string()),
@@ -4915,6 +6808,34 @@ mod tests {
           single_quote: Some(false),
           prose_wrap: None,
           no_semicolons: Some(false),
+          organize_imports: false,
+          watch: Default::default(),
+        }),
+        ext: Some("ts".to_string()),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn fmt_organize_imports() {
+    let r = flags_from_vec(svec!["deno", "fmt", "--organize-imports"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Fmt(FmtFlags {
+          check: false,
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+          },
+          use_tabs: None,
+          line_width: None,
+          indent_width: None,
+          single_quote: None,
+          prose_wrap: None,
+          no_semicolons: None,
+          organize_imports: true,
           watch: Default::default(),
         }),
         ext: Some("ts".to_string()),
@@ -4923,6 +6844,28 @@ mod tests {
     );
   }
 
+  #[test]
+  fn gitignore() {
+    for args in [
+      svec!["deno", "bench", "--gitignore"],
+      svec!["deno", "check", "--gitignore", "mod.ts"],
+      svec!["deno", "coverage", "--gitignore", "cov"],
+      svec!["deno", "publish", "--gitignore"],
+    ] {
+      assert!(flags_from_vec(args).unwrap().gitignore);
+    }
+    assert!(!flags_from_vec(svec!["deno", "bench"]).unwrap().gitignore);
+    for args in [
+      svec!["deno", "fmt", "--no-gitignore"],
+      svec!["deno", "lint", "--no-gitignore"],
+      svec!["deno", "test", "--no-gitignore"],
+    ] {
+      assert!(flags_from_vec(args).unwrap().no_gitignore);
+    }
+    assert!(!flags_from_vec(svec!["deno", "fmt"]).unwrap().no_gitignore);
+    assert!(flags_from_vec(svec!["deno", "fmt", "--gitignore"]).is_err());
+  }
+
   #[test]
   fn lint() {
     let r = flags_from_vec(svec!["deno", "lint", "script_1.ts", "script_2.ts"]);
@@ -5186,10 +7129,27 @@ mod tests {
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Types,
+        subcommand: DenoSubcommand::Types(TypesFlags::default()),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno", "types", "--ffi", "mylib.h", "--check", "mylib.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Types(TypesFlags {
+          ffi: Some("mylib.h".to_string()),
+          check: Some("mylib.ts".to_string()),
+        }),
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec!["deno", "types", "--check", "mylib.ts"]);
+    assert!(r.is_err());
   }
 
   #[test]
@@ -5200,12 +7160,61 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Cache(CacheFlags {
           files: svec!["script.ts"],
+          prune: None,
         }),
         ..Flags::default()
       }
     );
   }
 
+  #[test]
+  fn cache_prune() {
+    let r = flags_from_vec(svec!["deno", "cache", "--prune"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Cache(CacheFlags {
+          files: vec![],
+          prune: Some(CachePruneFlags {
+            max_age: None,
+            max_size: None,
+          }),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "cache",
+      "--prune",
+      "--max-age=30d",
+      "--max-size=5GB"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Cache(CacheFlags {
+          files: vec![],
+          prune: Some(CachePruneFlags {
+            max_age: Some(30 * 24 * 60 * 60),
+            max_size: Some(5 * 1024 * 1024 * 1024),
+          }),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "cache", "--prune", "script.ts"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "cache", "--max-age=30d", "a.ts"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "cache", "--prune", "--max-age=30"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "cache", "--prune", "--max-size=5PB"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn check() {
     let r = flags_from_vec(svec!["deno", "check", "script.ts"]);
@@ -5214,6 +7223,8 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Check(CheckFlags {
           files: svec!["script.ts"],
+          workspace: false,
+          trace: None,
         }),
         type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
@@ -5227,6 +7238,8 @@ mod tests {
         Flags {
           subcommand: DenoSubcommand::Check(CheckFlags {
             files: svec!["script.ts"],
+            workspace: false,
+            trace: None,
           }),
           type_check_mode: TypeCheckMode::All,
           ..Flags::default()
@@ -5245,6 +7258,46 @@ mod tests {
         clap::error::ErrorKind::ArgumentConflict
       );
     }
+
+    let r = flags_from_vec(svec!["deno", "check", "--workspace"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Check(CheckFlags {
+          files: vec![],
+          workspace: true,
+          trace: None,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "check", "--workspace", "script.ts"]);
+    assert_eq!(
+      r.unwrap_err().kind(),
+      clap::error::ErrorKind::ArgumentConflict
+    );
+    let r = flags_from_vec(svec!["deno", "check"]);
+    assert_eq!(
+      r.unwrap_err().kind(),
+      clap::error::ErrorKind::MissingRequiredArgument
+    );
+
+    let r =
+      flags_from_vec(svec!["deno", "check", "--trace=trace.json", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Check(CheckFlags {
+          files: svec!["script.ts"],
+          workspace: false,
+          trace: Some("trace.json".to_string()),
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
   }
 
   #[test]
@@ -5255,6 +7308,9 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Info(InfoFlags {
           json: false,
+          dot: false,
+          diff: None,
+          licenses: false,
           file: Some("script.ts".to_string()),
         }),
         ..Flags::default()
@@ -5267,6 +7323,9 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Info(InfoFlags {
           json: false,
+          dot: false,
+          diff: None,
+          licenses: false,
           file: Some("script.ts".to_string()),
         }),
         reload: true,
@@ -5280,11 +7339,110 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Info(InfoFlags {
           json: true,
+          dot: false,
+          diff: None,
+          licenses: false,
+          file: Some("script.ts".to_string()),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "info", "--format=dot", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: false,
+          dot: true,
+          diff: None,
+          licenses: false,
+          file: Some("script.ts".to_string()),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "info", "--format=json", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: true,
+          dot: false,
+          diff: None,
+          licenses: false,
+          file: Some("script.ts".to_string()),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "info",
+      "--json",
+      "--format=dot",
+      "script.ts"
+    ]);
+    assert!(r.is_err());
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "info",
+      "--json",
+      "--diff=graph.json",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: true,
+          dot: false,
+          diff: Some("graph.json".to_string()),
+          licenses: false,
+          file: Some("script.ts".to_string()),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "info",
+      "--format=dot",
+      "--diff=graph.json",
+      "script.ts"
+    ]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "info", "--diff=graph.json"]);
+    assert!(r.is_err());
+
+    let r = flags_from_vec(svec!["deno", "info", "--licenses", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: false,
+          dot: false,
+          diff: None,
+          licenses: true,
           file: Some("script.ts".to_string()),
         }),
         ..Flags::default()
       }
     );
+    let r = flags_from_vec(svec![
+      "deno",
+      "info",
+      "--licenses",
+      "--diff=graph.json",
+      "script.ts"
+    ]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "info", "--licenses"]);
+    assert!(r.is_err());
 
     let r = flags_from_vec(svec!["deno", "info"]);
     assert_eq!(
@@ -5292,6 +7450,9 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Info(InfoFlags {
           json: false,
+          dot: false,
+          diff: None,
+          licenses: false,
           file: None
         }),
         ..Flags::default()
@@ -5304,6 +7465,9 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Info(InfoFlags {
           json: true,
+          dot: false,
+          diff: None,
+          licenses: false,
           file: None
         }),
         ..Flags::default()
@@ -5323,6 +7487,9 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Info(InfoFlags {
           json: false,
+          dot: false,
+          diff: None,
+          licenses: false,
           file: None
         }),
         config_flag: ConfigFlag::Path("tsconfig.json".to_owned()),
@@ -5452,7 +7619,7 @@ mod tests {
         allow_write: Some(vec![]),
         allow_ffi: Some(vec![]),
         allow_hrtime: true,
-        env_file: Some(".example.env".to_owned()),
+        env_file: Some(vec![".example.env".to_owned()]),
         ..Flags::default()
       }
     );
@@ -5566,7 +7733,7 @@ mod tests {
         allow_write: Some(vec![]),
         allow_ffi: Some(vec![]),
         allow_hrtime: true,
-        env_file: Some(".example.env".to_owned()),
+        env_file: Some(vec![".example.env".to_owned()]),
         unsafely_ignore_certificate_errors: Some(vec![]),
         ..Flags::default()
       }
@@ -6071,7 +8238,36 @@ mod tests {
           source_file: "source.ts".to_string(),
           out_file: None,
           watch: Default::default(),
+          minify: false,
+          source_map: false,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn bundle_with_minify_and_source_map() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "bundle",
+      "--minify",
+      "--source-map",
+      "source.ts",
+      "bundle.js"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Bundle(BundleFlags {
+          source_file: "source.ts".to_string(),
+          out_file: Some("bundle.js".to_string()),
+          watch: Default::default(),
+          minify: true,
+          source_map: true,
         }),
+        allow_write: Some(vec![]),
         type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
       }
@@ -6096,6 +8292,8 @@ mod tests {
           source_file: "source.ts".to_string(),
           out_file: Some("bundle.js".to_string()),
           watch: Default::default(),
+          minify: false,
+          source_map: false,
         }),
         allow_write: Some(vec![]),
         no_remote: true,
@@ -6116,6 +8314,8 @@ mod tests {
           source_file: "source.ts".to_string(),
           out_file: Some("bundle.js".to_string()),
           watch: Default::default(),
+          minify: false,
+          source_map: false,
         }),
         type_check_mode: TypeCheckMode::Local,
         allow_write: Some(vec![]),
@@ -6140,6 +8340,8 @@ mod tests {
           source_file: "source.ts".to_string(),
           out_file: None,
           watch: Default::default(),
+          minify: false,
+          source_map: false,
         }),
         type_check_mode: TypeCheckMode::Local,
         lock_write: true,
@@ -6160,6 +8362,8 @@ mod tests {
           source_file: "source.ts".to_string(),
           out_file: None,
           watch: Default::default(),
+          minify: false,
+          source_map: false,
         }),
         type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
@@ -6178,6 +8382,8 @@ mod tests {
           source_file: "script.ts".to_string(),
           out_file: None,
           watch: Default::default(),
+          minify: false,
+          source_map: false,
         }),
         type_check_mode: TypeCheckMode::None,
         ..Flags::default()
@@ -6195,6 +8401,8 @@ mod tests {
           source_file: "source.ts".to_string(),
           out_file: None,
           watch: Some(Default::default()),
+          minify: false,
+          source_map: false,
         }),
         type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
@@ -6220,6 +8428,8 @@ mod tests {
           watch: Some(WatchFlags {
             hmr: false,
             no_clear_screen: true,
+            minify: false,
+            source_map: false,
           }),
         }),
         type_check_mode: TypeCheckMode::Local,
@@ -6262,6 +8472,9 @@ mod tests {
         subcommand: DenoSubcommand::Info(InfoFlags {
           file: Some("script.ts".to_string()),
           json: false,
+          dot: false,
+          diff: None,
+          licenses: false,
         }),
         import_map_path: Some("import_map.json".to_owned()),
         ..Flags::default()
@@ -6282,6 +8495,7 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Cache(CacheFlags {
           files: svec!["script.ts"],
+          prune: None,
         }),
         import_map_path: Some("import_map.json".to_owned()),
         ..Flags::default()
@@ -6301,6 +8515,7 @@ mod tests {
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Doc(DocFlags {
+          markdown: None,
           source_files: DocSourceFileFlag::Paths(vec!["script.ts".to_owned()]),
           private: false,
           json: false,
@@ -6323,7 +8538,7 @@ mod tests {
         subcommand: DenoSubcommand::Run(RunFlags::new_default(
           "script.ts".to_string(),
         )),
-        env_file: Some(".env".to_owned()),
+        env_file: Some(vec![".env".to_owned()]),
         ..Flags::default()
       }
     );
@@ -6339,21 +8554,43 @@ mod tests {
         subcommand: DenoSubcommand::Run(RunFlags::new_default(
           "script.ts".to_string(),
         )),
-        env_file: Some(".another_env".to_owned()),
+        env_file: Some(vec![".another_env".to_owned()]),
         ..Flags::default()
       }
     );
   }
 
   #[test]
-  fn cache_multiple() {
-    let r =
-      flags_from_vec(svec!["deno", "cache", "script.ts", "script_two.ts"]);
-    assert_eq!(
-      r.unwrap(),
+  fn run_env_file_multiple() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--env-file=.env",
+      "--env-file=.env.local",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        env_file: Some(svec![".env", ".env.local"]),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn cache_multiple() {
+    let r =
+      flags_from_vec(svec!["deno", "cache", "script.ts", "script_two.ts"]);
+    assert_eq!(
+      r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Cache(CacheFlags {
           files: svec!["script.ts", "script_two.ts"],
+          prune: None,
         }),
         ..Flags::default()
       }
@@ -6450,7 +8687,7 @@ mod tests {
         allow_net: Some(vec![]),
         unsafely_ignore_certificate_errors: Some(vec![]),
         allow_read: Some(vec![]),
-        env_file: Some(".example.env".to_owned()),
+        env_file: Some(vec![".example.env".to_owned()]),
         ..Flags::default()
       }
     );
@@ -6793,6 +9030,76 @@ mod tests {
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--node-modules-dir=linked",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        node_modules_dir: Some(true),
+        node_modules_dir_mode: NodeModulesDirMode::Linked,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn publish_registry() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "publish",
+      "--registry=https://jsr.example.com",
+      "--dry-run"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Publish(PublishFlags {
+          token: None,
+          registry: Some(Url::parse("https://jsr.example.com").unwrap()),
+          dry_run: true,
+          no_zap: false,
+          bump: None,
+          git: false,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "publish", "--registry=jsr"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn publish_bump() {
+    let r =
+      flags_from_vec(svec!["deno", "publish", "--bump", "minor", "--git"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Publish(PublishFlags {
+          token: None,
+          registry: None,
+          dry_run: false,
+          no_zap: false,
+          bump: Some(VersionBump::Minor),
+          git: true,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "publish", "--bump=prerelease"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "publish", "--git"]);
+    assert!(r.is_err());
   }
 
   #[test]
@@ -6837,6 +9144,101 @@ mod tests {
     );
   }
 
+  #[test]
+  fn offline() {
+    let r = flags_from_vec(svec!["deno", "run", "--offline", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        offline: true,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "--offline", "lsp"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lsp(LspFlags::default()),
+        offline: true,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn lsp() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "lsp",
+      "--log-file",
+      "lsp.log",
+      "--log-level",
+      "debug",
+      "--otlp-endpoint",
+      "http://localhost:4318"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lsp(LspFlags {
+          log_file: Some(PathBuf::from("lsp.log")),
+          otlp_endpoint: Some(Url::parse("http://localhost:4318").unwrap()),
+        }),
+        log_level: Some(Level::Debug),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "lsp", "--otlp-endpoint", "foo"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn fetch_concurrency() {
+    let r =
+      flags_from_vec(svec!["deno", "cache", "--fetch-concurrency=32", "a.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Cache(CacheFlags {
+          files: svec!["a.ts"],
+          prune: None,
+        }),
+        fetch_concurrency: Some(NonZeroUsize::new(32).unwrap()),
+        ..Flags::default()
+      }
+    );
+
+    let r =
+      flags_from_vec(svec!["deno", "cache", "--fetch-concurrency=0", "a.ts"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn download_retries() {
+    let r =
+      flags_from_vec(svec!["deno", "cache", "--download-retries=5", "a.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Cache(CacheFlags {
+          files: svec!["a.ts"],
+          prune: None,
+        }),
+        download_retries: Some(5),
+        ..Flags::default()
+      }
+    );
+
+    let r =
+      flags_from_vec(svec!["deno", "cache", "--download-retries=-1", "a.ts"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn allow_net_allowlist_with_ports() {
     let r = flags_from_vec(svec![
@@ -7091,9 +9493,15 @@ mod tests {
           concurrent_jobs: None,
           trace_ops: true,
           coverage_dir: Some("cov".to_string()),
+          coverage_threshold: None,
           watch: Default::default(),
           reporter: Default::default(),
           junit_path: None,
+          shard: None,
+          shard_timings: None,
+          retry: None,
+          update_snapshots: false,
+          changed: None,
         }),
         unstable_config: UnstableConfig {
           legacy_flag_enabled: true,
@@ -7173,8 +9581,14 @@ mod tests {
           concurrent_jobs: Some(NonZeroUsize::new(4).unwrap()),
           trace_ops: false,
           coverage_dir: None,
+          coverage_threshold: None,
           watch: Default::default(),
           junit_path: None,
+          shard: None,
+          shard_timings: None,
+          retry: None,
+          update_snapshots: false,
+          changed: None,
         }),
         type_check_mode: TypeCheckMode::Local,
         no_prompt: true,
@@ -7184,6 +9598,15 @@ mod tests {
 
     let r = flags_from_vec(svec!["deno", "test", "--jobs=0"]);
     assert!(r.is_err());
+
+    let r = flags_from_vec(svec!["deno", "test", "--jobs"]).unwrap();
+    let DenoSubcommand::Test(test_flags) = r.subcommand else {
+      unreachable!()
+    };
+    assert!(test_flags.concurrent_jobs.is_some());
+
+    let r = flags_from_vec(svec!["deno", "test", "--parallel", "--jobs=2"]);
+    assert!(r.is_err());
   }
 
   #[test]
@@ -7206,9 +9629,15 @@ mod tests {
           concurrent_jobs: None,
           trace_ops: false,
           coverage_dir: None,
+          coverage_threshold: None,
           watch: Default::default(),
           reporter: Default::default(),
           junit_path: None,
+          shard: None,
+          shard_timings: None,
+          retry: None,
+          update_snapshots: false,
+          changed: None,
         }),
         type_check_mode: TypeCheckMode::Local,
         no_prompt: true,
@@ -7244,9 +9673,15 @@ mod tests {
           concurrent_jobs: None,
           trace_ops: false,
           coverage_dir: None,
+          coverage_threshold: None,
           watch: Default::default(),
           reporter: Default::default(),
           junit_path: None,
+          shard: None,
+          shard_timings: None,
+          retry: None,
+          update_snapshots: false,
+          changed: None,
         }),
         no_prompt: true,
         type_check_mode: TypeCheckMode::Local,
@@ -7361,9 +9796,15 @@ mod tests {
           concurrent_jobs: None,
           trace_ops: false,
           coverage_dir: None,
+          coverage_threshold: None,
           watch: Default::default(),
           reporter: Default::default(),
           junit_path: None,
+          shard: None,
+          shard_timings: None,
+          retry: None,
+          update_snapshots: false,
+          changed: None,
         }),
         no_prompt: true,
         type_check_mode: TypeCheckMode::Local,
@@ -7392,9 +9833,15 @@ mod tests {
           concurrent_jobs: None,
           trace_ops: false,
           coverage_dir: None,
+          coverage_threshold: None,
           watch: Some(Default::default()),
           reporter: Default::default(),
           junit_path: None,
+          shard: None,
+          shard_timings: None,
+          retry: None,
+          update_snapshots: false,
+          changed: None,
         }),
         no_prompt: true,
         type_check_mode: TypeCheckMode::Local,
@@ -7422,9 +9869,15 @@ mod tests {
           concurrent_jobs: None,
           trace_ops: false,
           coverage_dir: None,
+          coverage_threshold: None,
           watch: Some(Default::default()),
           reporter: Default::default(),
           junit_path: None,
+          shard: None,
+          shard_timings: None,
+          retry: None,
+          update_snapshots: false,
+          changed: None,
         }),
         no_prompt: true,
         type_check_mode: TypeCheckMode::Local,
@@ -7454,12 +9907,18 @@ mod tests {
           concurrent_jobs: None,
           trace_ops: false,
           coverage_dir: None,
+          coverage_threshold: None,
           watch: Some(WatchFlags {
             hmr: false,
             no_clear_screen: true,
           }),
           reporter: Default::default(),
           junit_path: None,
+          shard: None,
+          shard_timings: None,
+          retry: None,
+          update_snapshots: false,
+          changed: None,
         }),
         type_check_mode: TypeCheckMode::Local,
         no_prompt: true,
@@ -7485,6 +9944,149 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_coverage_threshold() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "test",
+      "--coverage",
+      "--coverage-threshold=lines:90,branches:80"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          coverage_dir: Some("coverage".to_string()),
+          coverage_threshold: Some(CoverageThreshold {
+            lines: Some(90),
+            branches: Some(80),
+            functions: None,
+          }),
+          ..TestFlags::default()
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        no_prompt: true,
+        ..Flags::default()
+      }
+    );
+
+    let r =
+      flags_from_vec(svec!["deno", "test", "--coverage-threshold=lines:90"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec![
+      "deno",
+      "coverage",
+      "--coverage-threshold=lines:101"
+    ]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec![
+      "deno",
+      "coverage",
+      "--coverage-threshold=statements:50"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn test_retry() {
+    let r = flags_from_vec(svec!["deno", "test", "--retry=2"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          retry: Some(2),
+          ..TestFlags::default()
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        no_prompt: true,
+        ..Flags::default()
+      }
+    );
+    let r = flags_from_vec(svec!["deno", "test", "--retry=-1"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn test_changed() {
+    let r = flags_from_vec(svec!["deno", "test", "--changed=origin/main"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          changed: Some("origin/main".to_string()),
+          ..TestFlags::default()
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        no_prompt: true,
+        ..Flags::default()
+      }
+    );
+    let r = flags_from_vec(svec!["deno", "test", "--changed"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          changed: Some("HEAD".to_string()),
+          ..TestFlags::default()
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        no_prompt: true,
+        ..Flags::default()
+      }
+    );
+    let r = flags_from_vec(svec!["deno", "test", "--changed", "--watch"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn test_update_snapshots() {
+    let r = flags_from_vec(svec!["deno", "test", "--update-snapshots"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          update_snapshots: true,
+          ..TestFlags::default()
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        no_prompt: true,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn test_shard() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "test",
+      "--shard=2/5",
+      "--shard-timings=report.xml"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          shard: Some(TestShard { index: 2, count: 5 }),
+          shard_timings: Some("report.xml".to_string()),
+          ..TestFlags::default()
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        no_prompt: true,
+        ..Flags::default()
+      }
+    );
+
+    for shard in ["0/5", "6/5", "1/0", "2", "a/b"] {
+      let r = flags_from_vec(svec!["deno", "test", format!("--shard={shard}")]);
+      assert!(r.is_err(), "{shard}");
+    }
+    let r = flags_from_vec(svec!["deno", "test", "--shard-timings=report.xml"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "test", "--shard=1/2", "--watch"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn bundle_with_cafile() {
     let r = flags_from_vec(svec![
@@ -7501,6 +10103,8 @@ mod tests {
           source_file: "source.ts".to_string(),
           out_file: None,
           watch: Default::default(),
+          minify: false,
+          source_map: false,
         }),
         type_check_mode: TypeCheckMode::Local,
         ca_data: Some(CaData::File("example.crt".to_owned())),
@@ -7518,9 +10122,11 @@ mod tests {
         subcommand: DenoSubcommand::Upgrade(UpgradeFlags {
           force: false,
           dry_run: false,
-          canary: false,
+          channel: UpgradeChannel::Stable,
           version: None,
           output: None,
+          rollback: false,
+          archive: None,
         }),
         ca_data: Some(CaData::File("example.crt".to_owned())),
         ..Flags::default()
@@ -7543,6 +10149,7 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Cache(CacheFlags {
           files: svec!["script.ts", "script_two.ts"],
+          prune: None,
         }),
         ca_data: Some(CaData::File("example.crt".to_owned())),
         ..Flags::default()
@@ -7564,6 +10171,9 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Info(InfoFlags {
           json: false,
+          dot: false,
+          diff: None,
+          licenses: false,
           file: Some("https://example.com".to_string()),
         }),
         ca_data: Some(CaData::File("example.crt".to_owned())),
@@ -7583,6 +10193,7 @@ mod tests {
           json: true,
           html: None,
           lint: false,
+          markdown: None,
           source_files: DocSourceFileFlag::Paths(svec!["path/to/module.ts"]),
           filter: None,
         }),
@@ -7611,6 +10222,7 @@ mod tests {
             name: "My library".to_string(),
             output: PathBuf::from("./docs/"),
           }),
+          markdown: None,
           source_files: DocSourceFileFlag::Paths(svec!["path/to/module.ts"]),
           filter: None,
         }),
@@ -7638,6 +10250,7 @@ mod tests {
             output: PathBuf::from("./foo"),
           }),
           lint: true,
+          markdown: None,
           source_files: DocSourceFileFlag::Paths(svec!["path/to/module.ts"]),
           filter: None,
         }),
@@ -7652,9 +10265,9 @@ mod tests {
     let r = flags_from_vec(svec![
       "deno",
       "doc",
-      "--filter",
-      "SomeClass.someField",
-      "path/to/module.ts",
+      "--html",
+      "--name=My workspace",
+      "--workspace"
     ]);
     assert_eq!(
       r.unwrap(),
@@ -7662,11 +10275,88 @@ mod tests {
         subcommand: DenoSubcommand::Doc(DocFlags {
           private: false,
           json: false,
-          html: None,
           lint: false,
-          source_files: DocSourceFileFlag::Paths(vec![
-            "path/to/module.ts".to_string()
-          ]),
+          html: Some(DocHtmlFlag {
+            name: "My workspace".to_string(),
+            output: PathBuf::from("./docs/"),
+          }),
+          markdown: None,
+          source_files: DocSourceFileFlag::Workspace,
+          filter: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "doc", "--workspace"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec![
+      "deno",
+      "doc",
+      "--html",
+      "--name=My workspace",
+      "--workspace",
+      "path/to/module.ts"
+    ]);
+    assert!(r.is_err());
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "doc",
+      "--markdown",
+      "--output=./foo",
+      "path/to/module.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Doc(DocFlags {
+          private: false,
+          json: false,
+          lint: false,
+          html: None,
+          markdown: Some(DocMarkdownFlag {
+            name: None,
+            output: PathBuf::from("./foo"),
+          }),
+          source_files: DocSourceFileFlag::Paths(svec!["path/to/module.ts"]),
+          filter: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "doc", "--markdown"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec![
+      "deno",
+      "doc",
+      "--markdown",
+      "--html",
+      "--name=My library",
+      "path/to/module.ts"
+    ]);
+    assert!(r.is_err());
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "doc",
+      "--filter",
+      "SomeClass.someField",
+      "path/to/module.ts",
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Doc(DocFlags {
+          private: false,
+          json: false,
+          html: None,
+          lint: false,
+          markdown: None,
+          source_files: DocSourceFileFlag::Paths(vec![
+            "path/to/module.ts".to_string()
+          ]),
           filter: Some("SomeClass.someField".to_string()),
         }),
         ..Flags::default()
@@ -7682,6 +10372,7 @@ mod tests {
           json: false,
           html: None,
           lint: false,
+          markdown: None,
           source_files: Default::default(),
           filter: None,
         }),
@@ -7704,6 +10395,7 @@ mod tests {
           lint: false,
           json: false,
           html: None,
+          markdown: None,
           source_files: DocSourceFileFlag::Builtin,
           filter: Some("Deno.Listener".to_string()),
         }),
@@ -7727,6 +10419,7 @@ mod tests {
           lint: false,
           json: false,
           html: None,
+          markdown: None,
           source_files: DocSourceFileFlag::Paths(svec!["path/to/module.js"]),
           filter: None,
         }),
@@ -7750,6 +10443,7 @@ mod tests {
           lint: false,
           json: false,
           html: None,
+          markdown: None,
           source_files: DocSourceFileFlag::Paths(vec![
             "path/to/module.js".to_string(),
             "path/to/module2.js".to_string()
@@ -7775,6 +10469,7 @@ mod tests {
           json: false,
           html: None,
           lint: false,
+          markdown: None,
           source_files: DocSourceFileFlag::Paths(vec![
             "path/to/module.js".to_string(),
             "path/to/module2.js".to_string()
@@ -7803,6 +10498,7 @@ mod tests {
           lint: true,
           json: false,
           html: None,
+          markdown: None,
           source_files: DocSourceFileFlag::Paths(vec![
             "path/to/module.js".to_string(),
             "path/to/module2.js".to_string()
@@ -7878,12 +10574,242 @@ mod tests {
           args: vec![],
           target: None,
           no_terminal: false,
-          include: vec![]
+          include: vec![],
+          list_targets: false,
+          sign_identity: None,
+          sign_command: None,
+          sign_args: vec![],
+          icon: None,
+          version_info: None,
+          strip: false,
+          include_source_maps: false,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn compile_target_all() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "compile",
+      "--target",
+      "all",
+      "--output",
+      "dist/colors",
+      "https://examples.deno.land/color-logging.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Compile(CompileFlags {
+          source_file: "https://examples.deno.land/color-logging.ts"
+            .to_string(),
+          output: Some(PathBuf::from("dist/colors")),
+          args: vec![],
+          target: Some("all".to_string()),
+          no_terminal: false,
+          include: vec![],
+          list_targets: false,
+          sign_identity: None,
+          sign_command: None,
+          sign_args: vec![],
+          icon: None,
+          version_info: None,
+          strip: false,
+          include_source_maps: false,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn compile_list_targets() {
+    let r = flags_from_vec(svec!["deno", "compile", "--list-targets"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Compile(CompileFlags {
+          source_file: "".to_string(),
+          output: None,
+          args: vec![],
+          target: None,
+          no_terminal: false,
+          include: vec![],
+          list_targets: true,
+          sign_identity: None,
+          sign_command: None,
+          sign_args: vec![],
+          icon: None,
+          version_info: None,
+          strip: false,
+          include_source_maps: false,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    let r =
+      flags_from_vec(svec!["deno", "compile", "--list-targets", "mod.ts"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "compile", "--target", "linux"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn compile_with_strip() {
+    let r = flags_from_vec(svec!["deno", "compile", "--strip", "main.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Compile(CompileFlags {
+          source_file: "main.ts".to_string(),
+          output: None,
+          args: vec![],
+          target: None,
+          no_terminal: false,
+          include: vec![],
+          list_targets: false,
+          sign_identity: None,
+          sign_command: None,
+          sign_args: vec![],
+          icon: None,
+          version_info: None,
+          strip: true,
+          include_source_maps: false,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn compile_with_include_source_maps() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "compile",
+      "--include-source-maps",
+      "main.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Compile(CompileFlags {
+          source_file: "main.ts".to_string(),
+          output: None,
+          args: vec![],
+          target: None,
+          no_terminal: false,
+          include: vec![],
+          list_targets: false,
+          sign_identity: None,
+          sign_command: None,
+          sign_args: vec![],
+          icon: None,
+          version_info: None,
+          strip: false,
+          include_source_maps: true,
         }),
         type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "compile",
+      "--strip",
+      "--include-source-maps",
+      "main.ts"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn compile_with_signing() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "compile",
+      "--target",
+      "x86_64-pc-windows-msvc",
+      "--sign-identity",
+      "My Company",
+      "--icon",
+      "icon.ico",
+      "--version-info",
+      "1.2.3",
+      "main.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Compile(CompileFlags {
+          source_file: "main.ts".to_string(),
+          output: None,
+          args: vec![],
+          target: Some("x86_64-pc-windows-msvc".to_string()),
+          no_terminal: false,
+          include: vec![],
+          list_targets: false,
+          sign_identity: Some("My Company".to_string()),
+          sign_command: None,
+          sign_args: vec![],
+          icon: Some(PathBuf::from("icon.ico")),
+          version_info: Some("1.2.3".to_string()),
+          strip: false,
+          include_source_maps: false,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "compile",
+      "--sign-identity",
+      "My Company",
+      "--sign-command",
+      "sign.sh",
+      "main.ts"
+    ]);
+    assert!(r.is_err());
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "compile",
+      "--sign-command",
+      "/opt/My Tools/sign.sh",
+      "--sign-arg",
+      "--key",
+      "--sign-arg",
+      "My Key.pem",
+      "main.ts"
+    ]);
+    let DenoSubcommand::Compile(compile_flags) = r.unwrap().subcommand else {
+      unreachable!();
+    };
+    assert_eq!(
+      compile_flags.sign_command,
+      Some("/opt/My Tools/sign.sh".to_string())
+    );
+    assert_eq!(compile_flags.sign_args, svec!["--key", "My Key.pem"]);
+
+    // the arguments are for the signing program
+    let r = flags_from_vec(svec![
+      "deno",
+      "compile",
+      "--sign-arg",
+      "--key",
+      "main.ts"
+    ]);
+    assert!(r.is_err());
   }
 
   #[test]
@@ -7900,7 +10826,15 @@ mod tests {
           args: svec!["foo", "bar", "-p", "8080"],
           target: None,
           no_terminal: true,
-          include: vec![]
+          include: vec![],
+          list_targets: false,
+          sign_identity: None,
+          sign_command: None,
+          sign_args: vec![],
+          icon: None,
+          version_info: None,
+          strip: false,
+          include_source_maps: false,
         }),
         import_map_path: Some("import_map.json".to_string()),
         no_remote: true,
@@ -7917,7 +10851,7 @@ mod tests {
         allow_net: Some(vec![]),
         v8_flags: svec!["--help", "--random-seed=1"],
         seed: Some(1),
-        env_file: Some(".example.env".to_owned()),
+        env_file: Some(vec![".example.env".to_owned()]),
         ..Flags::default()
       }
     );
@@ -7964,12 +10898,64 @@ mod tests {
           exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
           r#type: CoverageType::Lcov,
           output: Some(PathBuf::from("foo.lcov")),
+          threshold: None,
         }),
         ..Flags::default()
       }
     );
   }
 
+  #[test]
+  fn coverage_with_cobertura_and_json_summary() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "coverage",
+      "--cobertura",
+      "--output=cobertura.xml",
+      "foo.json"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Coverage(CoverageFlags {
+          files: FileFlags {
+            include: vec!["foo.json".to_string()],
+            ignore: vec![],
+          },
+          include: vec![r"^file:".to_string()],
+          exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
+          r#type: CoverageType::Cobertura,
+          output: Some(PathBuf::from("cobertura.xml")),
+          threshold: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "coverage", "--json-summary"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Coverage(CoverageFlags {
+          files: FileFlags {
+            include: vec!["coverage".to_string()],
+            ignore: vec![],
+          },
+          include: vec![r"^file:".to_string()],
+          exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
+          r#type: CoverageType::JsonSummary,
+          ..CoverageFlags::default()
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "coverage", "--lcov", "--cobertura"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "coverage", "--output=foo.lcov"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn coverage_with_default_files() {
     let r = flags_from_vec(svec!["deno", "coverage",]);
@@ -8100,6 +11086,9 @@ mod tests {
         subcommand: DenoSubcommand::Task(TaskFlags {
           cwd: None,
           task: Some("build".to_string()),
+          recursive: false,
+          filter: None,
+          no_watch: false,
         }),
         argv: svec!["hello", "world"],
         ..Flags::default()
@@ -8113,6 +11102,9 @@ mod tests {
         subcommand: DenoSubcommand::Task(TaskFlags {
           cwd: None,
           task: Some("build".to_string()),
+          recursive: false,
+          filter: None,
+          no_watch: false,
         }),
         ..Flags::default()
       }
@@ -8125,7 +11117,84 @@ mod tests {
         subcommand: DenoSubcommand::Task(TaskFlags {
           cwd: Some("foo".to_string()),
           task: Some("build".to_string()),
+          recursive: false,
+          filter: None,
+          no_watch: false,
+        }),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn task_subcommand_recursive() {
+    let r = flags_from_vec(svec!["deno", "task", "-r", "build"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Task(TaskFlags {
+          cwd: None,
+          task: Some("build".to_string()),
+          recursive: true,
+          filter: None,
+          no_watch: false,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "task", "--filter", "pkg/*", "build"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Task(TaskFlags {
+          cwd: None,
+          task: Some("build".to_string()),
+          recursive: true,
+          filter: Some("pkg/*".to_string()),
+          no_watch: false,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r =
+      flags_from_vec(svec!["deno", "task", "-r", "--cwd", "foo", "build"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn task_subcommand_no_watch() {
+    let r = flags_from_vec(svec!["deno", "task", "--no-watch", "dev"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Task(TaskFlags {
+          cwd: None,
+          task: Some("dev".to_string()),
+          recursive: false,
+          filter: None,
+          no_watch: true,
+        }),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn task_subcommand_env_file() {
+    let r = flags_from_vec(svec!["deno", "task", "--env-file", "build"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Task(TaskFlags {
+          cwd: None,
+          task: Some("build".to_string()),
+          recursive: false,
+          filter: None,
+          no_watch: false,
         }),
+        env_file: Some(svec![".env"]),
         ..Flags::default()
       }
     );
@@ -8149,6 +11218,9 @@ mod tests {
         subcommand: DenoSubcommand::Task(TaskFlags {
           cwd: None,
           task: Some("build".to_string()),
+          recursive: false,
+          filter: None,
+          no_watch: false,
         }),
         argv: svec!["--", "hello", "world"],
         config_flag: ConfigFlag::Path("deno.json".to_owned()),
@@ -8165,6 +11237,9 @@ mod tests {
         subcommand: DenoSubcommand::Task(TaskFlags {
           cwd: Some("foo".to_string()),
           task: Some("build".to_string()),
+          recursive: false,
+          filter: None,
+          no_watch: false,
         }),
         argv: svec!["--", "hello", "world"],
         ..Flags::default()
@@ -8182,6 +11257,9 @@ mod tests {
         subcommand: DenoSubcommand::Task(TaskFlags {
           cwd: None,
           task: Some("build".to_string()),
+          recursive: false,
+          filter: None,
+          no_watch: false,
         }),
         argv: svec!["--"],
         ..Flags::default()
@@ -8198,6 +11276,9 @@ mod tests {
         subcommand: DenoSubcommand::Task(TaskFlags {
           cwd: None,
           task: Some("build".to_string()),
+          recursive: false,
+          filter: None,
+          no_watch: false,
         }),
         argv: svec!["-1", "--test"],
         ..Flags::default()
@@ -8214,6 +11295,9 @@ mod tests {
         subcommand: DenoSubcommand::Task(TaskFlags {
           cwd: None,
           task: Some("build".to_string()),
+          recursive: false,
+          filter: None,
+          no_watch: false,
         }),
         argv: svec!["--test"],
         ..Flags::default()
@@ -8232,6 +11316,9 @@ mod tests {
         subcommand: DenoSubcommand::Task(TaskFlags {
           cwd: None,
           task: Some("build".to_string()),
+          recursive: false,
+          filter: None,
+          no_watch: false,
         }),
         unstable_config: UnstableConfig {
           legacy_flag_enabled: true,
@@ -8252,6 +11339,9 @@ mod tests {
         subcommand: DenoSubcommand::Task(TaskFlags {
           cwd: None,
           task: None,
+          recursive: false,
+          filter: None,
+          no_watch: false,
         }),
         ..Flags::default()
       }
@@ -8267,6 +11357,9 @@ mod tests {
         subcommand: DenoSubcommand::Task(TaskFlags {
           cwd: None,
           task: None,
+          recursive: false,
+          filter: None,
+          no_watch: false,
         }),
         config_flag: ConfigFlag::Path("deno.jsonc".to_string()),
         ..Flags::default()
@@ -8283,6 +11376,9 @@ mod tests {
         subcommand: DenoSubcommand::Task(TaskFlags {
           cwd: None,
           task: None,
+          recursive: false,
+          filter: None,
+          no_watch: false,
         }),
         config_flag: ConfigFlag::Path("deno.jsonc".to_string()),
         ..Flags::default()
@@ -8299,6 +11395,162 @@ mod tests {
     );
   }
 
+  #[test]
+  fn audit() {
+    let r = flags_from_vec(svec!["deno", "audit"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Audit(AuditFlags {
+          json: false,
+          level: AuditSeverity::Low,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "audit",
+      "--json",
+      "--level=high",
+      "--cached-only",
+      "--lock=deno.lock"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Audit(AuditFlags {
+          json: true,
+          level: AuditSeverity::High,
+        }),
+        cached_only: true,
+        lock: Some(PathBuf::from("deno.lock")),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "audit", "--level=severe"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn lock() {
+    let r = flags_from_vec(svec!["deno", "lock", "--verify"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lock(LockFlags { verify: true }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "lock",
+      "--verify",
+      "--reload",
+      "--lock=deno.lock"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lock(LockFlags { verify: true }),
+        reload: true,
+        lock: Some(PathBuf::from("deno.lock")),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "lock"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn outdated() {
+    let r = flags_from_vec(svec!["deno", "outdated"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Outdated(OutdatedFlags {
+          json: false,
+          update: false,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "outdated",
+      "--update",
+      "--config=deno.jsonc",
+      "--lock=deno.lock"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Outdated(OutdatedFlags {
+          json: false,
+          update: true,
+        }),
+        config_flag: ConfigFlag::Path("deno.jsonc".to_string()),
+        lock: Some(PathBuf::from("deno.lock")),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "outdated", "--json", "--update"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn add() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "add",
+      "npm:express@^4",
+      "jsr:@std/http",
+      "--dev",
+      "--cache"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Add(AddFlags {
+          packages: svec!["npm:express@^4", "jsr:@std/http"],
+          dev: true,
+          cache: true,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "add"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn remove() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "remove",
+      "@std/http",
+      "express",
+      "--config=deno.jsonc"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Remove(RemoveFlags {
+          packages: svec!["@std/http", "express"],
+        }),
+        config_flag: ConfigFlag::Path("deno.jsonc".to_string()),
+        ..Flags::default()
+      }
+    );
+  }
+
   #[test]
   fn bench_with_flags() {
     let r = flags_from_vec(svec![
@@ -8332,6 +11584,9 @@ mod tests {
             ignore: vec![],
           },
           watch: Default::default(),
+          reporter: Default::default(),
+          baseline: None,
+          regression_threshold_bps: None,
         }),
         unstable_config: UnstableConfig {
           legacy_flag_enabled: true,
@@ -8364,6 +11619,9 @@ mod tests {
             ignore: vec![],
           },
           watch: Some(Default::default()),
+          reporter: Default::default(),
+          baseline: None,
+          regression_threshold_bps: None,
         }),
         no_prompt: true,
         type_check_mode: TypeCheckMode::Local,
@@ -8372,6 +11630,69 @@ mod tests {
     );
   }
 
+  #[test]
+  fn bench_reporter() {
+    for (value, reporter) in [
+      ("pretty", BenchReporterConfig::Pretty),
+      ("json", BenchReporterConfig::Json),
+      ("junit", BenchReporterConfig::Junit),
+    ] {
+      let r =
+        flags_from_vec(svec!["deno", "bench", format!("--reporter={value}")]);
+      assert_eq!(
+        r.unwrap(),
+        Flags {
+          subcommand: DenoSubcommand::Bench(BenchFlags {
+            reporter,
+            ..BenchFlags::default()
+          }),
+          no_prompt: true,
+          type_check_mode: TypeCheckMode::Local,
+          ..Flags::default()
+        }
+      );
+    }
+
+    let r =
+      flags_from_vec(svec!["deno", "bench", "--reporter=junit", "--json"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "bench", "--reporter=tap"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn bench_baseline() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "bench",
+      "--baseline=baseline.json",
+      "--regression-threshold=10"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Bench(BenchFlags {
+          baseline: Some("baseline.json".to_string()),
+          regression_threshold_bps: Some(1000),
+          ..BenchFlags::default()
+        }),
+        no_prompt: true,
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "bench", "--regression-threshold=10"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec![
+      "deno",
+      "bench",
+      "--baseline=baseline.json",
+      "--regression-threshold=-1"
+    ]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn run_with_check() {
     let r = flags_from_vec(svec!["deno", "run", "--check", "script.ts",]);
@@ -8451,7 +11772,12 @@ mod tests {
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Init(InitFlags { dir: None }),
+        subcommand: DenoSubcommand::Init(InitFlags {
+          dir: None,
+          template: None,
+          ci: false,
+          yes: false,
+        }),
         ..Flags::default()
       }
     );
@@ -8462,6 +11788,9 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Init(InitFlags {
           dir: Some(String::from("foo")),
+          template: None,
+          ci: false,
+          yes: false,
         }),
         ..Flags::default()
       }
@@ -8471,11 +11800,37 @@ mod tests {
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Init(InitFlags { dir: None }),
+        subcommand: DenoSubcommand::Init(InitFlags {
+          dir: None,
+          template: None,
+          ci: false,
+          yes: false,
+        }),
         log_level: Some(Level::Error),
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "init",
+      "--template=jsr:@foo/template",
+      "--ci",
+      "-y",
+      "foo"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Init(InitFlags {
+          dir: Some(String::from("foo")),
+          template: Some(String::from("jsr:@foo/template")),
+          ci: true,
+          yes: true,
+        }),
+        ..Flags::default()
+      }
+    );
   }
 
   #[test]
@@ -1,19 +1,35 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+mod config_file_ext;
 mod flags;
 mod flags_net;
 mod import_map;
 mod lockfile;
 pub mod package_json;
-
+mod permission_log;
+mod permissions_file;
+
+use self::config_file_ext::discover_config_file;
+pub use self::config_file_ext::discover_config_file_from;
+pub use self::config_file_ext::parse_config_file;
+use self::config_file_ext::read_config_file;
+pub use self::config_file_ext::read_config_file_from_specifier;
+pub use self::config_file_ext::ConfigFileExt;
+use self::config_file_ext::ConfigProxy;
 pub use self::import_map::resolve_import_map_from_specifier;
 use self::package_json::PackageJsonDeps;
+use self::package_json::WorkspaceNpmPackage;
 use ::import_map::ImportMap;
 use deno_core::resolve_url_or_path;
 use deno_npm::resolution::ValidSerializedNpmResolutionSnapshot;
 use deno_npm::NpmSystemInfo;
 use deno_runtime::deno_tls::RootCertStoreProvider;
+use deno_semver::jsr::JsrPackageReqReference;
 use deno_semver::npm::NpmPackageReqReference;
+use deno_semver::package::PackageNv;
+use deno_semver::package::PackageReq;
+use deno_semver::Version;
+use deno_semver::VersionReq;
 use indexmap::IndexMap;
 
 pub use deno_config::glob::FilePatterns;
@@ -32,6 +48,9 @@ pub use flags::*;
 pub use lockfile::Lockfile;
 pub use lockfile::LockfileError;
 pub use package_json::PackageJsonDepsProvider;
+use permission_log::install_permission_log;
+pub use permissions_file::PermissionSet;
+pub use permissions_file::PermissionsFile;
 
 use deno_ast::ModuleSpecifier;
 use deno_core::anyhow::anyhow;
@@ -51,12 +70,13 @@ use deno_runtime::deno_tls::webpki_roots;
 use deno_runtime::inspector_server::InspectorServer;
 use deno_runtime::permissions::PermissionsOptions;
 use deno_terminal::colors;
-use dotenvy::from_filename;
+use dotenvy::from_filename_iter;
 use once_cell::sync::Lazy;
 use once_cell::sync::OnceCell;
 use serde::Deserialize;
 use serde::Serialize;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::env;
 use std::io::BufReader;
 use std::io::Cursor;
@@ -67,8 +87,17 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use thiserror::Error;
 
+use crate::auth_tokens::AuthTokenHelper;
+use crate::auth_tokens::AuthTokens;
 use crate::file_fetcher::FileFetcher;
+use crate::http_util::ClientCert;
+use crate::http_util::DEFAULT_DOWNLOAD_RETRIES;
+use crate::proxy::PacEvaluator;
+use crate::proxy::ProxyEnv;
+use crate::proxy::ProxyRules;
+use crate::util::file_selection::FileSelection;
 use crate::util::fs::canonicalize_path_maybe_not_exists;
+use crate::util::path::specifier_to_file_path;
 use crate::version;
 
 use deno_config::glob::PathOrPatternSet;
@@ -223,6 +252,10 @@ pub struct BenchOptions {
   pub filter: Option<String>,
   pub json: bool,
   pub no_run: bool,
+  pub reporter: BenchReporterConfig,
+  pub baseline: Option<PathBuf>,
+  /// The regression threshold in basis points (hundredths of a percent).
+  pub regression_threshold_bps: u32,
 }
 
 impl BenchOptions {
@@ -241,6 +274,11 @@ impl BenchOptions {
       filter: bench_flags.filter,
       json: bench_flags.json,
       no_run: bench_flags.no_run,
+      reporter: bench_flags.reporter,
+      baseline: bench_flags.baseline.map(|p| initial_cwd.join(p)),
+      regression_threshold_bps: bench_flags
+        .regression_threshold_bps
+        .unwrap_or(500),
     })
   }
 }
@@ -248,6 +286,7 @@ impl BenchOptions {
 #[derive(Clone, Debug)]
 pub struct FmtOptions {
   pub check: bool,
+  pub organize_imports: bool,
   pub options: FmtOptionsConfig,
   pub files: FilePatterns,
 }
@@ -256,6 +295,7 @@ impl FmtOptions {
   pub fn new_with_base(base: PathBuf) -> Self {
     Self {
       check: false,
+      organize_imports: false,
       options: FmtOptionsConfig::default(),
       files: FilePatterns::new_with_base(base),
     }
@@ -271,6 +311,10 @@ impl FmtOptions {
 
     Ok(Self {
       check: maybe_fmt_flags.as_ref().map(|f| f.check).unwrap_or(false),
+      organize_imports: maybe_fmt_flags
+        .as_ref()
+        .map(|f| f.organize_imports)
+        .unwrap_or(false),
       options: resolve_fmt_options(
         maybe_fmt_flags.as_ref(),
         maybe_config_options,
@@ -282,6 +326,33 @@ impl FmtOptions {
       )?,
     })
   }
+
+  /// Resolves the options of a workspace member's config file. Options not
+  /// set by the member fall back to the root config file, while flags still
+  /// take precedence over both.
+  fn resolve_for_member(
+    maybe_root_fmt_config: Option<&FmtConfig>,
+    member_fmt_config: FmtConfig,
+    maybe_fmt_flags: Option<&FmtFlags>,
+  ) -> Self {
+    let mut options = member_fmt_config.options;
+    if let Some(root_options) = maybe_root_fmt_config.map(|c| &c.options) {
+      options.use_tabs = options.use_tabs.or(root_options.use_tabs);
+      options.line_width = options.line_width.or(root_options.line_width);
+      options.indent_width = options.indent_width.or(root_options.indent_width);
+      options.single_quote = options.single_quote.or(root_options.single_quote);
+      options.prose_wrap = options.prose_wrap.or(root_options.prose_wrap);
+      options.semi_colons = options.semi_colons.or(root_options.semi_colons);
+    }
+    Self {
+      check: maybe_fmt_flags.map(|f| f.check).unwrap_or(false),
+      organize_imports: maybe_fmt_flags
+        .map(|f| f.organize_imports)
+        .unwrap_or(false),
+      options: resolve_fmt_options(maybe_fmt_flags, Some(options)),
+      files: member_fmt_config.files,
+    }
+  }
 }
 
 fn resolve_fmt_options(
@@ -325,6 +396,118 @@ fn resolve_fmt_options(
   options
 }
 
+/// A task of the "tasks" configuration, which is either a command or an
+/// object with a command and the tasks that need to run before it.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TaskDefinition {
+  pub command: String,
+  pub depends_on: Vec<String>,
+  /// Globs relative to the configuration file of the files which restart
+  /// the task when they change.
+  pub watch: Vec<String>,
+}
+
+impl<'de> Deserialize<'de> for TaskDefinition {
+  fn deserialize<D: serde::Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<Self, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum TaskDefinitionValue {
+      Command(String),
+      #[serde(rename_all = "camelCase")]
+      Object {
+        #[serde(default)]
+        command: String,
+        #[serde(default)]
+        depends_on: Vec<String>,
+        #[serde(default)]
+        watch: Vec<String>,
+      },
+    }
+    Ok(match TaskDefinitionValue::deserialize(deserializer)? {
+      TaskDefinitionValue::Command(command) => TaskDefinition {
+        command,
+        depends_on: Vec::new(),
+        watch: Vec::new(),
+      },
+      TaskDefinitionValue::Object {
+        command,
+        depends_on,
+        watch,
+      } => TaskDefinition {
+        command,
+        depends_on,
+        watch,
+      },
+    })
+  }
+}
+
+/// Load the variables of the env files into the environment of the process,
+/// so they are seen by the program and by the commands of tasks, which also
+/// expand them. The variables of later files take precedence over the ones of
+/// earlier files, but variables which were set before aren't overwritten.
+fn load_env_variables_from_env_files(env_file_names: &[String]) {
+  let existing_keys = std::env::vars_os()
+    .map(|(key, _)| key)
+    .collect::<HashSet<_>>();
+  for env_file_name in env_file_names {
+    let iter = match from_filename_iter(env_file_name) {
+      Ok(iter) => iter,
+      Err(err) => {
+        if err.not_found() {
+          log::info!(
+            "{} The `--env-file` flag was used, but the dotenv file '{}' was not found.",
+            colors::yellow("Warning"),
+            env_file_name
+          );
+        } else {
+          log::info!(
+            "{} Failed to read the dotenv file '{}': {}",
+            colors::yellow("Warning"),
+            env_file_name,
+            err
+          );
+        }
+        continue;
+      }
+    };
+    let mut file_keys = HashSet::new();
+    for item in iter {
+      match item {
+        Ok((key, value)) => {
+          // only the first variable with a key in a file is used
+          if file_keys.insert(key.clone())
+            && !existing_keys.contains(std::ffi::OsStr::new(&key))
+          {
+            std::env::set_var(key, value);
+          }
+        }
+        Err(err) => {
+          log::info!(
+            "{} Failed to parse the dotenv file '{}': {}",
+            colors::yellow("Warning"),
+            env_file_name,
+            err
+          );
+          break;
+        }
+      }
+    }
+  }
+}
+
+pub fn parse_tasks_config(
+  maybe_tasks: Option<&serde_json::Value>,
+) -> Result<IndexMap<String, TaskDefinition>, AnyError> {
+  match maybe_tasks {
+    Some(tasks) => serde_json::from_value(tasks.clone())
+      .context("Failed to parse \"tasks\" configuration"),
+    None => Ok(Default::default()),
+  }
+}
+
 #[derive(Clone)]
 pub struct TestOptions {
   pub files: FilePatterns,
@@ -338,6 +521,11 @@ pub struct TestOptions {
   pub trace_ops: bool,
   pub reporter: TestReporterConfig,
   pub junit_path: Option<String>,
+  pub shard: Option<TestShard>,
+  pub shard_timings: Option<PathBuf>,
+  pub retry: usize,
+  pub update_snapshots: bool,
+  pub changed: Option<String>,
 }
 
 impl TestOptions {
@@ -366,6 +554,11 @@ impl TestOptions {
       trace_ops: test_flags.trace_ops,
       reporter: test_flags.reporter,
       junit_path: test_flags.junit_path,
+      shard: test_flags.shard,
+      shard_timings: test_flags.shard_timings.map(|p| initial_cwd.join(p)),
+      retry: test_flags.retry.unwrap_or(0),
+      update_snapshots: test_flags.update_snapshots,
+      changed: test_flags.changed,
     })
   }
 }
@@ -458,6 +651,174 @@ impl LintOptions {
       ),
     })
   }
+
+  /// Resolves the options of a workspace member's config file. Rules not
+  /// configured by the member fall back to the root config file, while flags
+  /// still take precedence over both.
+  fn resolve_for_member(
+    maybe_root_lint_config: Option<&LintConfig>,
+    member_lint_config: LintConfig,
+    maybe_lint_flags: Option<&LintFlags>,
+  ) -> Self {
+    let mut rules = member_lint_config.rules;
+    if let Some(root_rules) = maybe_root_lint_config.map(|c| &c.rules) {
+      rules.tags = rules.tags.or_else(|| root_rules.tags.clone());
+      rules.include = rules.include.or_else(|| root_rules.include.clone());
+      rules.exclude = rules.exclude.or_else(|| root_rules.exclude.clone());
+    }
+    let (maybe_rules_tags, maybe_rules_include, maybe_rules_exclude) =
+      maybe_lint_flags
+        .map(|f| {
+          (
+            f.maybe_rules_tags.clone(),
+            f.maybe_rules_include.clone(),
+            f.maybe_rules_exclude.clone(),
+          )
+        })
+        .unwrap_or_default();
+    Self {
+      // the reporter is shared by all the linted files, so it's always
+      // resolved from the root config file
+      reporter_kind: Default::default(),
+      files: member_lint_config.files,
+      rules: resolve_lint_rules_options(
+        Some(rules),
+        maybe_rules_tags,
+        maybe_rules_include,
+        maybe_rules_exclude,
+      ),
+    }
+  }
+}
+
+/// Options resolved from the root config file along with the options of
+/// workspace members, which override them for the files in the member's
+/// directory.
+#[derive(Clone, Debug, Serialize)]
+pub struct ScopedOptions<T> {
+  pub root: T,
+  /// Options keyed by the directory of the member's config file, sorted so
+  /// that nested directories come after their parents.
+  pub members: Vec<(PathBuf, T)>,
+}
+
+impl<T> ScopedOptions<T> {
+  pub fn new(root: T) -> Self {
+    Self {
+      root,
+      members: Vec::new(),
+    }
+  }
+
+  pub fn with_members(root: T, mut members: Vec<(PathBuf, T)>) -> Self {
+    members.sort_by(|a, b| a.0.cmp(&b.0));
+    Self { root, members }
+  }
+
+  /// Gets the options of the nearest config file for the provided path.
+  pub fn for_path(&self, path: &Path) -> &T {
+    self
+      .members
+      .iter()
+      .rev()
+      .find(|(dir, _)| path.starts_with(dir))
+      .map(|(_, options)| options)
+      .unwrap_or(&self.root)
+  }
+
+  /// Gets the options of the nearest config file for the provided specifier.
+  /// Non-file specifiers always use the root options.
+  pub fn for_specifier(&self, specifier: &ModuleSpecifier) -> &T {
+    match specifier_to_file_path(specifier) {
+      Ok(path) => self.for_path(&path),
+      Err(_) => &self.root,
+    }
+  }
+
+  pub fn map<R>(&self, mut f: impl FnMut(&T) -> R) -> ScopedOptions<R> {
+    ScopedOptions {
+      root: f(&self.root),
+      members: self
+        .members
+        .iter()
+        .map(|(dir, options)| (dir.clone(), f(options)))
+        .collect(),
+    }
+  }
+}
+
+/// Resolves the lint options of the root config file and of any workspace
+/// members that have a `lint` configuration.
+pub fn resolve_scoped_lint_options(
+  maybe_config_file: Option<&ConfigFile>,
+  member_config_files: &[ConfigFile],
+  maybe_lint_flags: Option<LintFlags>,
+  initial_cwd: &Path,
+) -> Result<ScopedOptions<LintOptions>, AnyError> {
+  let maybe_root_lint_config = match maybe_config_file {
+    Some(config_file) => config_file.to_lint_config()?,
+    None => None,
+  };
+  let mut members = Vec::new();
+  for config_file in member_config_files {
+    let Some(dir) = config_file_dir(config_file) else {
+      continue;
+    };
+    if let Some(lint_config) = config_file.to_lint_config()? {
+      members.push((
+        dir,
+        LintOptions::resolve_for_member(
+          maybe_root_lint_config.as_ref(),
+          lint_config,
+          maybe_lint_flags.as_ref(),
+        ),
+      ));
+    }
+  }
+  let root = LintOptions::resolve(
+    maybe_root_lint_config,
+    maybe_lint_flags,
+    initial_cwd,
+  )?;
+  Ok(ScopedOptions::with_members(root, members))
+}
+
+/// Resolves the fmt options of the root config file and of any workspace
+/// members that have a `fmt` configuration.
+pub fn resolve_scoped_fmt_options(
+  maybe_config_file: Option<&ConfigFile>,
+  member_config_files: &[ConfigFile],
+  maybe_fmt_flags: Option<FmtFlags>,
+  initial_cwd: &Path,
+) -> Result<ScopedOptions<FmtOptions>, AnyError> {
+  let maybe_root_fmt_config = match maybe_config_file {
+    Some(config_file) => config_file.to_fmt_config()?,
+    None => None,
+  };
+  let mut members = Vec::new();
+  for config_file in member_config_files {
+    let Some(dir) = config_file_dir(config_file) else {
+      continue;
+    };
+    if let Some(fmt_config) = config_file.to_fmt_config()? {
+      members.push((
+        dir,
+        FmtOptions::resolve_for_member(
+          maybe_root_fmt_config.as_ref(),
+          fmt_config,
+          maybe_fmt_flags.as_ref(),
+        ),
+      ));
+    }
+  }
+  let root =
+    FmtOptions::resolve(maybe_root_fmt_config, maybe_fmt_flags, initial_cwd)?;
+  Ok(ScopedOptions::with_members(root, members))
+}
+
+fn config_file_dir(config_file: &ConfigFile) -> Option<PathBuf> {
+  let path = specifier_to_file_path(&config_file.specifier).ok()?;
+  path.parent().map(|p| p.to_path_buf())
 }
 
 fn resolve_lint_rules_options(
@@ -670,6 +1031,440 @@ struct CliOptionOverrides {
   import_map_specifier: Option<Option<ModuleSpecifier>>,
 }
 
+/// The limits of the module graph of the `"graph": { "budget": { ... } }`
+/// object of a configuration file, which `deno check`, `deno cache` and the
+/// LSP enforce.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GraphBudget {
+  pub max_total_bytes: Option<u64>,
+  pub max_dependencies: Option<usize>,
+  /// Specifiers or glob patterns of specifiers which may not be imported.
+  pub banned: Vec<glob::Pattern>,
+}
+
+impl GraphBudget {
+  /// Get the banned pattern which matches the specifier. For npm and jsr
+  /// specifiers, the name of the package is matched as well so a pattern of
+  /// "npm:chalk" bans every version and sub path of the package.
+  pub fn banned_pattern(&self, specifier: &ModuleSpecifier) -> Option<&str> {
+    let package_specifier =
+      if let Ok(req_ref) = NpmPackageReqReference::from_specifier(specifier) {
+        Some(format!("npm:{}", req_ref.req().name))
+      } else if let Ok(req_ref) =
+        JsrPackageReqReference::from_specifier(specifier)
+      {
+        Some(format!("jsr:{}", req_ref.req().name))
+      } else {
+        None
+      };
+    self
+      .banned
+      .iter()
+      .find(|pattern| {
+        pattern.matches(specifier.as_str())
+          || package_specifier
+            .as_ref()
+            .map(|s| pattern.matches(s))
+            .unwrap_or(false)
+      })
+      .map(|pattern| pattern.as_str())
+  }
+}
+
+/// The rules of `deno doc --lint` which are enabled or disabled by the
+/// `"doc": { "lint": { "rules": { ... } } }` object of a configuration file.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DocLintRulesConfig {
+  /// Rules which are run, even when they are also excluded.
+  pub include: Vec<String>,
+  /// Rules which aren't run.
+  pub exclude: Vec<String>,
+}
+
+/// The theme of the HTML output of `deno doc` of the
+/// `"doc": { "html": { ... } }` object of a configuration file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DocHtmlConfig {
+  /// An image which is shown at the top of every page.
+  pub logo: Option<PathBuf>,
+  /// A stylesheet which is added to every page after the default styles.
+  pub stylesheet: Option<PathBuf>,
+}
+
+/// The entries of the top-level `"exclude"` array of a configuration file and
+/// of the `"exclude"` array of a section like `"fmt"`, which includes the
+/// deprecated `"files": { "exclude": [...] }` object. These are deserialized
+/// again from the JSON of the configuration file, because `deno_config`
+/// doesn't know about negated entries.
+fn config_file_excludes(
+  config_file: &ConfigFile,
+  maybe_section: Option<&str>,
+) -> Result<Vec<String>, AnyError> {
+  let json = &config_file.json;
+  let maybe_section = maybe_section.and_then(|section| match section {
+    "fmt" => json.fmt.as_ref(),
+    "lint" => json.lint.as_ref(),
+    "test" => json.test.as_ref(),
+    "bench" => json.bench.as_ref(),
+    "publish" => json.publish.as_ref(),
+    _ => None,
+  });
+  let excludes = [
+    json.exclude.as_ref(),
+    maybe_section.and_then(|section| section.get("exclude")),
+    maybe_section
+      .and_then(|section| section.get("files"))
+      .and_then(|files| files.get("exclude")),
+  ];
+  let mut result = Vec::new();
+  for exclude in excludes.into_iter().flatten() {
+    let exclude: Vec<String> = serde_json::from_value(exclude.clone())?;
+    result.extend(exclude);
+  }
+  Ok(result)
+}
+
+/// Resolve how the files of a subcommand are selected on top of the file
+/// patterns of its section of the configuration file, which are the negated
+/// entries of the `"exclude"` arrays and, with `use_gitignore`, the
+/// `.gitignore` files.
+pub fn resolve_file_selection(
+  maybe_config_file: Option<&ConfigFile>,
+  maybe_section: Option<&str>,
+  use_gitignore: bool,
+) -> Result<FileSelection, AnyError> {
+  let Some((config_file, path)) = maybe_config_file.and_then(|config_file| {
+    Some((config_file, config_file.specifier.to_file_path().ok()?))
+  }) else {
+    return FileSelection::new(Path::new(""), &[], use_gitignore);
+  };
+  let excludes = config_file_excludes(config_file, maybe_section)
+    .with_context(|| format!("Invalid exclude in '{}'", path.display()))?;
+  let base = path.parent().unwrap_or(&path);
+  FileSelection::new(base, &excludes, use_gitignore)
+}
+
+/// Resolve the npm packages which are allowed to run install scripts by the
+/// `"allowScripts"` array of the configuration file.
+pub fn resolve_allow_scripts(
+  maybe_config_file_ext: Option<&ConfigFileExt>,
+) -> Result<Vec<PackageReq>, AnyError> {
+  match maybe_config_file_ext {
+    Some(config_file_ext) => config_file_ext.to_allow_scripts(),
+    None => Ok(Vec::new()),
+  }
+}
+
+/// Gets if the `"allowScripts"` of the configuration file allow a package to
+/// run its install scripts, where an entry without a version allows every
+/// version of the package.
+pub fn is_allowed_to_run_scripts(
+  allow_scripts: &[PackageReq],
+  package: &PackageNv,
+) -> bool {
+  allow_scripts.iter().any(|req| {
+    req.name == package.name && req.version_req.matches(&package.version)
+  })
+}
+
+/// Resolve the version of the TypeScript compiler which is pinned by the
+/// `"typescriptVersion"` of the configuration file, if it isn't the version
+/// which is built into Deno.
+pub fn resolve_typescript_version(
+  maybe_config_file_ext: Option<&ConfigFileExt>,
+) -> Result<Option<Version>, AnyError> {
+  let Some(config_file_ext) = maybe_config_file_ext else {
+    return Ok(None);
+  };
+  let maybe_version = config_file_ext.to_typescript_version()?;
+  Ok(maybe_version.filter(|version| version.to_string() != version::TYPESCRIPT))
+}
+
+/// Resolve the `"minDenoVersion"` of the configuration file, which
+/// `deno upgrade` doesn't install older versions than.
+pub fn resolve_min_deno_version(
+  maybe_config_file_ext: Option<&ConfigFileExt>,
+) -> Result<Option<Version>, AnyError> {
+  match maybe_config_file_ext {
+    Some(config_file_ext) => config_file_ext.to_min_deno_version(),
+    None => Ok(None),
+  }
+}
+
+/// Whether the running version of Deno satisfies a version requirement,
+/// where a canary build is the version of the release it's built after.
+pub fn satisfies_deno_version_req(version_req: &VersionReq) -> bool {
+  let version = version::deno();
+  let version = version
+    .split_once('+')
+    .map_or(version, |(version, _)| version);
+  match Version::parse_standard(version) {
+    Ok(version) => version_req.matches(&version),
+    Err(_) => true,
+  }
+}
+
+/// Fail when the running version of Deno doesn't satisfy the `"deno"` version
+/// requirement of the configuration file, instead of failing in confusing
+/// ways on the APIs the project needs.
+pub fn check_deno_version_req(
+  maybe_config_file_ext: Option<&ConfigFileExt>,
+) -> Result<(), AnyError> {
+  let Some(config_file_ext) = maybe_config_file_ext else {
+    return Ok(());
+  };
+  match config_file_ext.to_deno_version_req()? {
+    Some((text, version_req, path))
+      if !satisfies_deno_version_req(&version_req) =>
+    {
+      bail!(
+        "Deno {} doesn't satisfy the version requirement \"{}\" of '{}'. Run `deno upgrade` to install a version which does.",
+        version::deno(),
+        text,
+        path
+      )
+    }
+    _ => Ok(()),
+  }
+}
+
+/// A TypeScript language service plugin of the `"plugins"` compiler option,
+/// like `{ "name": "typescript-plugin-css-modules", "classnameTransform": "camelCase" }`,
+/// where the options other than the name are passed to the plugin.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TsPluginConfig {
+  pub name: String,
+  #[serde(flatten)]
+  pub options: serde_json::Map<String, serde_json::Value>,
+}
+
+fn parse_config_ts_plugins(
+  maybe_compiler_options: Option<&serde_json::Value>,
+) -> Result<Vec<TsPluginConfig>, AnyError> {
+  let Some(plugins) =
+    maybe_compiler_options.and_then(|options| options.get("plugins"))
+  else {
+    return Ok(vec![]);
+  };
+  let plugins: Vec<TsPluginConfig> = serde_json::from_value(plugins.clone())
+    .context("Each plugin must be an object with a \"name\".")?;
+  Ok(plugins)
+}
+
+/// Resolve the TypeScript language service plugins of the `"plugins"`
+/// compiler option of the configuration file, which are only loaded by the
+/// language server.
+pub fn resolve_ts_plugins(
+  maybe_config_file: Option<&ConfigFile>,
+) -> Result<Vec<TsPluginConfig>, AnyError> {
+  let Some(config_file) = maybe_config_file else {
+    return Ok(vec![]);
+  };
+  parse_config_ts_plugins(config_file.json.compiler_options.as_ref())
+    .with_context(|| {
+      format!(
+        "Invalid compilerOptions.plugins in '{}'",
+        config_file.specifier
+      )
+    })
+}
+
+/// Resolve the permission set of `--permission-set`, whose paths are
+/// relative to the configuration file.
+pub fn resolve_permission_set(
+  maybe_config_file_ext: Option<&ConfigFileExt>,
+  name: &str,
+) -> Result<PermissionSet, AnyError> {
+  let Some(config_file_ext) = maybe_config_file_ext else {
+    bail!(
+      "Couldn't find a configuration file for the permission set \"{}\".",
+      name
+    );
+  };
+  config_file_ext.to_permission_set(name)
+}
+
+/// The cache of remote modules, npm tarballs and type checking results which
+/// is shared between machines, configured by the `"cache": { "remote": ... }`
+/// object of a configuration file or the `DENO_REMOTE_CACHE_*` environment
+/// variables.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RemoteCacheOptions {
+  pub url: Url,
+  /// The bearer token of the `DENO_REMOTE_CACHE_TOKEN` environment variable,
+  /// which is never read from the configuration file so it isn't committed.
+  pub maybe_token: Option<String>,
+  /// The credentials to sign the requests with for S3, which doesn't accept
+  /// bearer tokens.
+  pub maybe_s3_credentials: Option<RemoteCacheS3Credentials>,
+  /// Only pull from the remote cache without pushing to it.
+  pub read_only: bool,
+}
+
+/// The AWS credentials of the environment, which are only used for the remote
+/// cache when the `DENO_REMOTE_CACHE_S3_REGION` environment variable is set.
+#[derive(Clone, PartialEq, Eq)]
+pub struct RemoteCacheS3Credentials {
+  pub region: String,
+  pub access_key_id: String,
+  pub secret_access_key: String,
+  pub maybe_session_token: Option<String>,
+}
+
+impl std::fmt::Debug for RemoteCacheS3Credentials {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    // don't print the secrets in the logs
+    f.debug_struct("RemoteCacheS3Credentials")
+      .field("region", &self.region)
+      .field("access_key_id", &self.access_key_id)
+      .finish_non_exhaustive()
+  }
+}
+
+/// Resolve the tokens of the `DENO_AUTH_TOKENS` environment variable along
+/// with the credential helpers of the configuration file, which the
+/// `DENO_AUTH_HELPERS` environment variable overrides when it's set.
+pub fn resolve_auth_tokens(
+  maybe_config_file_ext: Option<&ConfigFileExt>,
+) -> Result<AuthTokens, AnyError> {
+  let auth_tokens = AuthTokens::new(env::var("DENO_AUTH_TOKENS").ok());
+  let helpers = match env::var("DENO_AUTH_HELPERS").ok() {
+    Some(helpers_str) => AuthTokenHelper::parse_list(Some(helpers_str)),
+    None => match maybe_config_file_ext {
+      Some(config_file_ext) => config_file_ext.to_auth_helpers()?,
+      None => Vec::new(),
+    },
+  };
+  Ok(auth_tokens.with_helpers(helpers))
+}
+
+/// Resolve the client certificates of the configuration file, which are
+/// presented to the hosts they're configured for.
+pub fn resolve_client_certs(
+  maybe_config_file_ext: Option<&ConfigFileExt>,
+) -> Result<Vec<ClientCert>, AnyError> {
+  let Some(config_file_ext) = maybe_config_file_ext else {
+    return Ok(Vec::new());
+  };
+  let read = |path: &Path| {
+    std::fs::read_to_string(path).with_context(|| {
+      format!("Failed reading the client certificate '{}'", path.display())
+    })
+  };
+  config_file_ext
+    .to_client_certs()?
+    .into_iter()
+    .map(|(host, cert_path, key_path)| {
+      Ok(ClientCert {
+        host,
+        cert_chain: read(&cert_path)?,
+        private_key: read(&key_path)?,
+      })
+    })
+    .collect()
+}
+
+/// Resolve the proxy rules of the configuration file along with the proxies
+/// of the environment. There are none when the HTTP client can use the
+/// proxies of the environment by itself.
+pub fn resolve_proxy_rules(
+  maybe_config_file_ext: Option<&ConfigFileExt>,
+) -> Result<Option<Arc<ProxyRules>>, AnyError> {
+  let maybe_config_proxy = match maybe_config_file_ext {
+    Some(config_file_ext) => config_file_ext.to_proxy_config()?,
+    None => None,
+  };
+  let env = ProxyEnv::from_env();
+  let config_proxy = match maybe_config_proxy {
+    Some(config_proxy) => config_proxy,
+    None if env.has_no_proxy_wildcards() => ConfigProxy::default(),
+    None => return Ok(None),
+  };
+  Ok(Some(Arc::new(ProxyRules::new(
+    config_proxy.hosts,
+    config_proxy.maybe_pac_url.map(PacEvaluator::new),
+    env,
+  ))))
+}
+
+/// Resolve the remote cache, where the environment variables take precedence
+/// over the configuration file.
+fn resolve_remote_cache_options(
+  maybe_config_remote_cache: Option<(String, bool)>,
+  maybe_env_url: Option<String>,
+  maybe_env_token: Option<String>,
+  maybe_env_read_only: Option<String>,
+  maybe_s3_credentials: Option<RemoteCacheS3Credentials>,
+) -> Result<Option<RemoteCacheOptions>, AnyError> {
+  let (url, config_read_only) = match (
+    maybe_env_url.filter(|url| !url.is_empty()),
+    maybe_config_remote_cache,
+  ) {
+    (Some(url), _) => (url, false),
+    (None, Some(config_remote_cache)) => config_remote_cache,
+    (None, None) => return Ok(None),
+  };
+  let url = Url::parse(&url)
+    .with_context(|| format!("Invalid remote cache URL \"{}\"", url))?;
+  if !matches!(url.scheme(), "http" | "https" | "file") {
+    bail!(
+      "The remote cache URL \"{}\" must be an http, https or file URL.",
+      url
+    );
+  }
+  let read_only = match maybe_env_read_only.as_deref() {
+    Some(value) => !matches!(value, "" | "0" | "false"),
+    None => config_read_only,
+  };
+  Ok(Some(RemoteCacheOptions {
+    url,
+    maybe_token: maybe_env_token.filter(|token| !token.is_empty()),
+    maybe_s3_credentials,
+    read_only,
+  }))
+}
+
+/// Resolve the AWS credentials to sign the requests to the remote cache
+/// with, which requires the access key when the S3 region is set.
+fn resolve_remote_cache_s3_credentials(
+  maybe_env_region: Option<String>,
+  maybe_env_access_key_id: Option<String>,
+  maybe_env_secret_access_key: Option<String>,
+  maybe_env_session_token: Option<String>,
+) -> Result<Option<RemoteCacheS3Credentials>, AnyError> {
+  let non_empty = |value: Option<String>| value.filter(|v| !v.is_empty());
+  let Some(region) = non_empty(maybe_env_region) else {
+    return Ok(None);
+  };
+  let (Some(access_key_id), Some(secret_access_key)) = (
+    non_empty(maybe_env_access_key_id),
+    non_empty(maybe_env_secret_access_key),
+  ) else {
+    bail!(concat!(
+      "The DENO_REMOTE_CACHE_S3_REGION environment variable requires the ",
+      "AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY environment variables ",
+      "to sign the requests to the remote cache."
+    ));
+  };
+  Ok(Some(RemoteCacheS3Credentials {
+    region,
+    access_key_id,
+    secret_access_key,
+    maybe_session_token: non_empty(maybe_env_session_token),
+  }))
+}
+
+/// The default max number of remote modules which are downloaded at the same
+/// time. Downloading is bound by the network rather than the CPU, so this
+/// allows a few downloads per CPU within limits that servers tolerate.
+pub fn default_fetch_concurrency() -> NonZeroUsize {
+  let cpus = std::thread::available_parallelism()
+    .map(|n| n.get())
+    .unwrap_or(1);
+  NonZeroUsize::new((cpus * 4).clamp(16, 64)).unwrap()
+}
+
 /// Holds the resolved options of many sources used by subcommands
 /// and provides some helper function for creating common objects.
 pub struct CliOptions {
@@ -680,10 +1475,17 @@ pub struct CliOptions {
   maybe_node_modules_folder: Option<PathBuf>,
   maybe_vendor_folder: Option<PathBuf>,
   maybe_config_file: Option<ConfigFile>,
+  maybe_config_file_ext: Option<ConfigFileExt>,
   maybe_package_json: Option<PackageJson>,
   maybe_lockfile: Option<Arc<Mutex<Lockfile>>>,
   overrides: CliOptionOverrides,
   maybe_workspace_config: Option<WorkspaceConfig>,
+  workspace_npm_packages: Vec<WorkspaceNpmPackage>,
+  allow_scripts: Vec<PackageReq>,
+  typescript_version: Option<Version>,
+  auth_tokens: AuthTokens,
+  maybe_proxy_rules: Option<Arc<ProxyRules>>,
+  client_certs: Vec<ClientCert>,
   pub disable_deprecated_api_warning: bool,
   pub verbose_deprecated_api_warning: bool,
 }
@@ -693,6 +1495,7 @@ impl CliOptions {
     flags: Flags,
     initial_cwd: PathBuf,
     maybe_config_file: Option<ConfigFile>,
+    mut maybe_config_file_ext: Option<ConfigFileExt>,
     maybe_lockfile: Option<Arc<Mutex<Lockfile>>>,
     maybe_package_json: Option<PackageJson>,
   ) -> Result<Self, AnyError> {
@@ -710,31 +1513,73 @@ impl CliOptions {
       eprintln!("{}", colors::yellow(msg));
     }
 
+    let maybe_workspace_config =
+      if let Some(config_file) = maybe_config_file.as_ref() {
+        config_file.to_workspace_config()?
+      } else {
+        None
+      };
+    // the members of a workspace share the node_modules directory of the
+    // workspace root, where the members with a package.json are linked
+    let (maybe_workspace_root_dir, workspace_npm_packages) =
+      match (maybe_config_file.as_ref(), maybe_workspace_config.as_ref()) {
+        (Some(config_file), Some(workspace_config)) => (
+          config_file_dir(config_file),
+          package_json::resolve_workspace_npm_packages(workspace_config)?,
+        ),
+        (Some(config_file), None) => {
+          match discover_workspace_root(config_file) {
+            Some((
+              root_config_file,
+              root_config_file_ext,
+              workspace_config,
+            )) => {
+              if let Some(config_file_ext) = maybe_config_file_ext.as_mut() {
+                config_file_ext.set_workspace_root(root_config_file_ext);
+              }
+              (
+                config_file_dir(&root_config_file),
+                package_json::resolve_workspace_npm_packages(
+                  &workspace_config,
+                )?,
+              )
+            }
+            None => (None, Vec::new()),
+          }
+        }
+        (None, _) => (None, Vec::new()),
+      };
     let maybe_node_modules_folder = resolve_node_modules_folder(
       &initial_cwd,
       &flags,
       maybe_config_file.as_ref(),
       maybe_package_json.as_ref(),
+      maybe_workspace_root_dir.as_deref(),
     )
     .with_context(|| "Resolving node_modules folder.")?;
     let maybe_vendor_folder =
       resolve_vendor_folder(&initial_cwd, &flags, maybe_config_file.as_ref());
-    let maybe_workspace_config =
-      if let Some(config_file) = maybe_config_file.as_ref() {
-        config_file.to_workspace_config()?
-      } else {
-        None
-      };
 
-    if let Some(env_file_name) = &flags.env_file {
-      if (from_filename(env_file_name)).is_err() {
-        log::info!(
-          "{} The `--env` flag was used, but the dotenv file '{}' was not found.",
-          colors::yellow("Warning"),
-          env_file_name
-        );
-      }
+    if let Some(env_file_names) = &flags.env_file {
+      load_env_variables_from_env_files(env_file_names);
+    }
+
+    // other subcommands like `deno upgrade` have to work with any version
+    if matches!(
+      flags.subcommand,
+      DenoSubcommand::Run(_)
+        | DenoSubcommand::Serve(_)
+        | DenoSubcommand::Check(_)
+    ) {
+      check_deno_version_req(maybe_config_file_ext.as_ref())?;
     }
+    let auth_tokens = resolve_auth_tokens(maybe_config_file_ext.as_ref())?;
+    let maybe_proxy_rules =
+      resolve_proxy_rules(maybe_config_file_ext.as_ref())?;
+    let client_certs = resolve_client_certs(maybe_config_file_ext.as_ref())?;
+    let allow_scripts = resolve_allow_scripts(maybe_config_file_ext.as_ref())?;
+    let typescript_version =
+      resolve_typescript_version(maybe_config_file_ext.as_ref())?;
 
     let disable_deprecated_api_warning = flags.log_level
       == Some(log::Level::Error)
@@ -747,12 +1592,19 @@ impl CliOptions {
       flags,
       initial_cwd,
       maybe_config_file,
+      maybe_config_file_ext,
       maybe_lockfile,
       maybe_package_json,
       maybe_node_modules_folder,
       maybe_vendor_folder,
       overrides: Default::default(),
       maybe_workspace_config,
+      workspace_npm_packages,
+      allow_scripts,
+      typescript_version,
+      auth_tokens,
+      maybe_proxy_rules,
+      client_certs,
       disable_deprecated_api_warning,
       verbose_deprecated_api_warning,
     })
@@ -761,11 +1613,12 @@ impl CliOptions {
   pub fn from_flags(flags: Flags) -> Result<Self, AnyError> {
     let initial_cwd =
       std::env::current_dir().with_context(|| "Failed getting cwd.")?;
-    let maybe_config_file = ConfigFile::discover(
+    let (maybe_config_file, maybe_config_file_ext) = discover_config_file(
       &flags.config_flag,
       flags.config_path_args(&initial_cwd),
       &initial_cwd,
-    )?;
+    )?
+    .unzip();
 
     let mut maybe_package_json = None;
     if flags.config_flag == deno_config::ConfigFlag::Disabled
@@ -795,6 +1648,7 @@ impl CliOptions {
       flags,
       initial_cwd,
       maybe_config_file,
+      maybe_config_file_ext,
       maybe_lock_file.map(|l| Arc::new(Mutex::new(l))),
       maybe_package_json,
     )
@@ -818,7 +1672,7 @@ impl CliOptions {
   }
 
   pub fn cache_setting(&self) -> CacheSetting {
-    if self.flags.cached_only {
+    if self.flags.cached_only || self.flags.offline {
       CacheSetting::Only
     } else if !self.flags.cache_blocklist.is_empty() {
       CacheSetting::ReloadSome(self.flags.cache_blocklist.clone())
@@ -1023,6 +1877,10 @@ impl CliOptions {
             .map_err(AnyError::from)
         }
       }
+      DenoSubcommand::Serve(serve_flags) => {
+        resolve_url_or_path(&serve_flags.script, self.initial_cwd())
+          .map_err(AnyError::from)
+      }
       _ => {
         bail!("No main module.")
       }
@@ -1096,10 +1954,17 @@ impl CliOptions {
       maybe_node_modules_folder: Some(path),
       maybe_vendor_folder: self.maybe_vendor_folder.clone(),
       maybe_config_file: self.maybe_config_file.clone(),
+      maybe_config_file_ext: self.maybe_config_file_ext.clone(),
       maybe_package_json: self.maybe_package_json.clone(),
       maybe_lockfile: self.maybe_lockfile.clone(),
       maybe_workspace_config: self.maybe_workspace_config.clone(),
+      workspace_npm_packages: self.workspace_npm_packages.clone(),
+      allow_scripts: self.allow_scripts.clone(),
+      auth_tokens: self.auth_tokens.clone(),
+      maybe_proxy_rules: self.maybe_proxy_rules.clone(),
+      client_certs: self.client_certs.clone(),
       overrides: self.overrides.clone(),
+      typescript_version: self.typescript_version.clone(),
       disable_deprecated_api_warning: self.disable_deprecated_api_warning,
       verbose_deprecated_api_warning: self.verbose_deprecated_api_warning,
     }
@@ -1114,6 +1979,10 @@ impl CliOptions {
     })
   }
 
+  pub fn node_modules_dir_mode(&self) -> NodeModulesDirMode {
+    self.flags.node_modules_dir_mode
+  }
+
   pub fn vendor_dir_path(&self) -> Option<&PathBuf> {
     self.maybe_vendor_folder.as_ref()
   }
@@ -1170,9 +2039,9 @@ impl CliOptions {
 
   pub fn resolve_tasks_config(
     &self,
-  ) -> Result<IndexMap<String, String>, AnyError> {
+  ) -> Result<IndexMap<String, TaskDefinition>, AnyError> {
     if let Some(config_file) = &self.maybe_config_file {
-      config_file.resolve_tasks_config()
+      parse_tasks_config(config_file.json.tasks.as_ref())
     } else if self.maybe_package_json.is_some() {
       Ok(Default::default())
     } else {
@@ -1214,6 +2083,12 @@ impl CliOptions {
     &self.maybe_config_file
   }
 
+  /// The sections of the configuration file which `deno_config` doesn't know
+  /// about.
+  pub fn maybe_config_file_ext(&self) -> Option<&ConfigFileExt> {
+    self.maybe_config_file_ext.as_ref()
+  }
+
   pub fn maybe_workspace_config(&self) -> &Option<WorkspaceConfig> {
     &self.maybe_workspace_config
   }
@@ -1228,37 +2103,118 @@ impl CliOptions {
       DenoSubcommand::Task(TaskFlags { task: None, .. })
     ) {
       // don't have any package json dependencies for deno task with no args
-      None
-    } else {
-      self
-        .maybe_package_json()
-        .as_ref()
-        .map(package_json::get_local_package_json_version_reqs)
+      return None;
     }
+    let mut maybe_deps = self
+      .maybe_package_json()
+      .as_ref()
+      .map(package_json::get_local_package_json_version_reqs);
+    // the dependencies of the members are installed in the node_modules
+    // directory of the workspace root, favoring the ones of the root
+    for package in &self.workspace_npm_packages {
+      let deps = maybe_deps.get_or_insert_with(Default::default);
+      for (name, dep) in &package.deps {
+        deps.entry(name.clone()).or_insert_with(|| dep.clone());
+      }
+    }
+    maybe_deps
+  }
+
+  /// The workspace members with a package.json, which are linked into the
+  /// node_modules directory of the workspace root.
+  pub fn workspace_npm_packages(&self) -> &[WorkspaceNpmPackage] {
+    &self.workspace_npm_packages
+  }
+
+  /// The npm packages whose install scripts are run.
+  pub fn allow_scripts(&self) -> &[PackageReq] {
+    &self.allow_scripts
+  }
+
+  /// The version of the TypeScript compiler pinned by the configuration
+  /// file, when it's another version than the one built into Deno.
+  pub fn typescript_version(&self) -> Option<&Version> {
+    self.typescript_version.as_ref()
+  }
+
+  /// Config files of the workspace members, if any.
+  pub fn workspace_member_config_files(&self) -> Vec<ConfigFile> {
+    self
+      .maybe_workspace_config
+      .as_ref()
+      .map(|workspace_config| {
+        workspace_config
+          .members
+          .iter()
+          .map(|member| member.config_file.clone())
+          .collect()
+      })
+      .unwrap_or_default()
   }
 
   pub fn resolve_fmt_options(
     &self,
     fmt_flags: FmtFlags,
-  ) -> Result<FmtOptions, AnyError> {
-    let maybe_fmt_config = if let Some(config_file) = &self.maybe_config_file {
-      config_file.to_fmt_config()?
-    } else {
-      None
-    };
-    FmtOptions::resolve(maybe_fmt_config, Some(fmt_flags), &self.initial_cwd)
+  ) -> Result<ScopedOptions<FmtOptions>, AnyError> {
+    resolve_scoped_fmt_options(
+      self.maybe_config_file.as_ref(),
+      &self.workspace_member_config_files(),
+      Some(fmt_flags),
+      &self.initial_cwd,
+    )
   }
 
   pub fn resolve_lint_options(
     &self,
     lint_flags: LintFlags,
-  ) -> Result<LintOptions, AnyError> {
-    let maybe_lint_config = if let Some(config_file) = &self.maybe_config_file {
-      config_file.to_lint_config()?
+  ) -> Result<ScopedOptions<LintOptions>, AnyError> {
+    resolve_scoped_lint_options(
+      self.maybe_config_file.as_ref(),
+      &self.workspace_member_config_files(),
+      Some(lint_flags),
+      &self.initial_cwd,
+    )
+  }
+
+  /// Resolve how the files of a subcommand are selected, where the negated
+  /// entries of the `--ignore` flag take precedence over the ones of the
+  /// configuration file, like the other entries.
+  pub fn resolve_file_selection(
+    &self,
+    maybe_section: Option<&str>,
+    ignore_flags: &[String],
+  ) -> Result<FileSelection, AnyError> {
+    if ignore_flags.is_empty() {
+      resolve_file_selection(
+        self.maybe_config_file.as_ref(),
+        maybe_section,
+        self.use_gitignore(),
+      )
     } else {
-      None
-    };
-    LintOptions::resolve(maybe_lint_config, Some(lint_flags), &self.initial_cwd)
+      FileSelection::new(&self.initial_cwd, ignore_flags, self.use_gitignore())
+    }
+  }
+
+  /// Whether the files ignored by the `.gitignore` files are excluded, which
+  /// is the default for `deno fmt`, `deno lint` and `deno test` and opted
+  /// into with `--gitignore` for the other subcommands.
+  fn use_gitignore(&self) -> bool {
+    match self.flags.subcommand {
+      DenoSubcommand::Fmt(_)
+      | DenoSubcommand::Lint(_)
+      | DenoSubcommand::Test(_) => !self.flags.no_gitignore,
+      _ => self.flags.gitignore,
+    }
+  }
+
+  /// Resolve the file patterns of the top-level `exclude` of the
+  /// configuration file, for the subcommands without a section of their own.
+  pub fn resolve_config_file_patterns(&self) -> Result<FilePatterns, AnyError> {
+    Ok(FilePatterns {
+      base: self.initial_cwd.clone(),
+      include: None,
+      exclude: self.resolve_config_excludes()?,
+    })
   }
 
   pub fn resolve_config_excludes(&self) -> Result<PathOrPatternSet, AnyError> {
@@ -1283,6 +2239,88 @@ impl CliOptions {
     TestOptions::resolve(maybe_test_config, Some(test_flags), &self.initial_cwd)
   }
 
+  /// Resolve the coverage threshold from the flag, falling back to the
+  /// `"coverage": { "threshold": { ... } }` object of the configuration file.
+  pub fn resolve_coverage_threshold(
+    &self,
+    maybe_threshold: Option<CoverageThreshold>,
+  ) -> Result<Option<CoverageThreshold>, AnyError> {
+    if maybe_threshold.is_some() {
+      return Ok(maybe_threshold);
+    }
+    match &self.maybe_config_file_ext {
+      Some(config_file_ext) => config_file_ext.to_coverage_threshold(),
+      None => Ok(None),
+    }
+  }
+
+  /// Resolve the `"graph": { "budget": { ... } }` object of the
+  /// configuration file.
+  pub fn resolve_graph_budget(&self) -> Result<Option<GraphBudget>, AnyError> {
+    match &self.maybe_config_file_ext {
+      Some(config_file_ext) => config_file_ext.to_graph_budget(),
+      None => Ok(None),
+    }
+  }
+
+  /// Resolve the `"doc": { "lint": { "rules": { ... } } }` object of the
+  /// configuration file.
+  pub fn resolve_doc_lint_rules(
+    &self,
+  ) -> Result<Option<DocLintRulesConfig>, AnyError> {
+    match &self.maybe_config_file_ext {
+      Some(config_file_ext) => config_file_ext.to_doc_lint_rules(),
+      None => Ok(None),
+    }
+  }
+
+  /// Resolve the `"doc": { "html": { ... } }` object of the configuration
+  /// file.
+  pub fn resolve_doc_html_config(
+    &self,
+  ) -> Result<Option<DocHtmlConfig>, AnyError> {
+    match &self.maybe_config_file_ext {
+      Some(config_file_ext) => config_file_ext.to_doc_html_config(),
+      None => Ok(None),
+    }
+  }
+
+  /// Resolve the `"licenses": { "allow": [...] }` array of the
+  /// configuration file.
+  pub fn resolve_license_allowlist(
+    &self,
+  ) -> Result<Option<Vec<String>>, AnyError> {
+    match &self.maybe_config_file_ext {
+      Some(config_file_ext) => config_file_ext.to_license_allowlist(),
+      None => Ok(None),
+    }
+  }
+
+  /// Resolve the remote cache of the `"cache": { "remote": ... }` object of
+  /// the configuration file and the `DENO_REMOTE_CACHE_URL`,
+  /// `DENO_REMOTE_CACHE_TOKEN`, `DENO_REMOTE_CACHE_READ_ONLY` and
+  /// `DENO_REMOTE_CACHE_S3_REGION` environment variables.
+  pub fn resolve_remote_cache_options(
+    &self,
+  ) -> Result<Option<RemoteCacheOptions>, AnyError> {
+    let maybe_config_remote_cache = match &self.maybe_config_file_ext {
+      Some(config_file_ext) => config_file_ext.to_remote_cache()?,
+      None => None,
+    };
+    resolve_remote_cache_options(
+      maybe_config_remote_cache,
+      env::var("DENO_REMOTE_CACHE_URL").ok(),
+      env::var("DENO_REMOTE_CACHE_TOKEN").ok(),
+      env::var("DENO_REMOTE_CACHE_READ_ONLY").ok(),
+      resolve_remote_cache_s3_credentials(
+        env::var("DENO_REMOTE_CACHE_S3_REGION").ok(),
+        env::var("AWS_ACCESS_KEY_ID").ok(),
+        env::var("AWS_SECRET_ACCESS_KEY").ok(),
+        env::var("AWS_SESSION_TOKEN").ok(),
+      )?,
+    )
+  }
+
   pub fn resolve_bench_options(
     &self,
     bench_flags: BenchFlags,
@@ -1351,14 +2389,16 @@ impl CliOptions {
   }
 
   pub fn has_hmr(&self) -> bool {
-    if let DenoSubcommand::Run(RunFlags {
-      watch: Some(WatchFlagsWithPaths { hmr, .. }),
-      ..
-    }) = &self.flags.subcommand
-    {
-      *hmr
-    } else {
-      false
+    match &self.flags.subcommand {
+      DenoSubcommand::Run(RunFlags {
+        watch: Some(WatchFlagsWithPaths { hmr, .. }),
+        ..
+      })
+      | DenoSubcommand::Serve(ServeFlags {
+        watch: Some(WatchFlagsWithPaths { hmr, .. }),
+        ..
+      }) => *hmr,
+      _ => false,
     }
   }
 
@@ -1404,6 +2444,43 @@ impl CliOptions {
     self.flags.no_remote
   }
 
+  pub fn offline(&self) -> bool {
+    self.flags.offline
+  }
+
+  /// The max number of remote modules which are downloaded at the same time.
+  pub fn fetch_concurrency(&self) -> NonZeroUsize {
+    self
+      .flags
+      .fetch_concurrency
+      .unwrap_or_else(default_fetch_concurrency)
+  }
+
+  /// The tokens which authorize the downloads of private resources.
+  pub fn auth_tokens(&self) -> &AuthTokens {
+    &self.auth_tokens
+  }
+
+  /// The rules which resolve the proxies of the HTTP client, when the
+  /// proxies of the environment aren't enough.
+  pub fn maybe_proxy_rules(&self) -> Option<&Arc<ProxyRules>> {
+    self.maybe_proxy_rules.as_ref()
+  }
+
+  /// The client certificates which are presented to the hosts they're
+  /// configured for.
+  pub fn client_certs(&self) -> &[ClientCert] {
+    &self.client_certs
+  }
+
+  /// The number of times a failed download is retried.
+  pub fn download_retries(&self) -> u32 {
+    self
+      .flags
+      .download_retries
+      .unwrap_or(DEFAULT_DOWNLOAD_RETRIES)
+  }
+
   pub fn no_npm(&self) -> bool {
     self.flags.no_npm
   }
@@ -1412,9 +2489,14 @@ impl CliOptions {
     self.flags.config_flag == deno_config::ConfigFlag::Disabled
   }
 
-  pub fn permissions_options(&self) -> PermissionsOptions {
-    PermissionsOptions {
+  /// Gets the permissions of the flags, of the permission set of the
+  /// configuration file and of the permissions file, where the permissions
+  /// granted in prompts are saved from then on. The checks of the
+  /// permissions are recorded in the permission log from then on too.
+  pub fn permissions_options(&self) -> Result<PermissionsOptions, AnyError> {
+    let mut options = PermissionsOptions {
       allow_env: self.flags.allow_env.clone(),
+      allow_env_prefixes: vec![],
       deny_env: self.flags.deny_env.clone(),
       allow_hrtime: self.flags.allow_hrtime,
       deny_hrtime: self.flags.deny_hrtime,
@@ -1431,17 +2513,60 @@ impl CliOptions {
       allow_write: self.flags.allow_write.clone(),
       deny_write: self.flags.deny_write.clone(),
       prompt: !self.no_prompt(),
+    };
+    if let Some(permission_log) = &self.flags.permission_log {
+      install_permission_log(&self.initial_cwd.join(permission_log))?;
+    }
+    if let Some(name) = &self.flags.permission_set {
+      resolve_permission_set(self.maybe_config_file_ext.as_ref(), name)?
+        .merge_into(&mut options);
     }
+    if let Some(permissions_file) = &self.flags.permissions_file {
+      let path = self.initial_cwd.join(permissions_file);
+      PermissionsFile::read(path.clone())?.merge_into(&mut options);
+      deno_runtime::permissions::set_prompt_grant_callback(Box::new(
+        move |name, maybe_value| {
+          // read the file again, since another run may have changed it
+          let result =
+            PermissionsFile::read(path.clone()).and_then(|mut file| {
+              file.insert_granted(name, maybe_value);
+              file.write()
+            });
+          if let Err(err) = result {
+            log::warn!(
+              "{} Failed saving the granted permission: {:#}",
+              colors::yellow("Warning"),
+              err
+            );
+          }
+        },
+      ));
+    }
+    Ok(options)
   }
 
   pub fn reload_flag(&self) -> bool {
     self.flags.reload
   }
 
+  pub fn gitignore_flag(&self) -> bool {
+    self.flags.gitignore
+  }
+
   pub fn seed(&self) -> Option<u64> {
     self.flags.seed
   }
 
+  /// The file of `deno check --trace`.
+  pub fn check_trace_path(&self) -> Option<PathBuf> {
+    match &self.flags.subcommand {
+      DenoSubcommand::Check(CheckFlags {
+        trace: Some(trace), ..
+      }) => Some(self.initial_cwd.join(trace)),
+      _ => None,
+    }
+  }
+
   pub fn sub_command(&self) -> &DenoSubcommand {
     &self.flags.subcommand
   }
@@ -1512,6 +2637,15 @@ impl CliOptions {
         .unwrap_or(false)
   }
 
+  pub fn unstable_test_snapshot(&self) -> bool {
+    self.flags.unstable_config.test_snapshot
+      || self
+        .maybe_config_file()
+        .as_ref()
+        .map(|c| c.has_unstable("test-snapshot"))
+        .unwrap_or(false)
+  }
+
   pub fn unstable_features(&self) -> Vec<String> {
     let mut from_config_file = self
       .maybe_config_file()
@@ -1528,14 +2662,16 @@ impl CliOptions {
   }
 
   pub fn watch_paths(&self) -> Vec<PathBuf> {
-    let mut paths = if let DenoSubcommand::Run(RunFlags {
-      watch: Some(WatchFlagsWithPaths { paths, .. }),
-      ..
-    }) = &self.flags.subcommand
-    {
-      paths.clone()
-    } else {
-      Vec::with_capacity(2)
+    let mut paths = match &self.flags.subcommand {
+      DenoSubcommand::Run(RunFlags {
+        watch: Some(WatchFlagsWithPaths { paths, .. }),
+        ..
+      })
+      | DenoSubcommand::Serve(ServeFlags {
+        watch: Some(WatchFlagsWithPaths { paths, .. }),
+        ..
+      }) => paths.clone(),
+      _ => Vec::with_capacity(2),
     };
     if let Ok(Some(import_map_path)) = self
       .resolve_import_map_specifier()
@@ -1554,12 +2690,51 @@ impl CliOptions {
   }
 }
 
+/// Finds the configuration file of the workspace which has the configuration
+/// file as one of its members, looking for a "workspaces" setting in the
+/// configuration files of the ancestor directories.
+pub fn discover_workspace_root(
+  config_file: &ConfigFile,
+) -> Option<(ConfigFile, ConfigFileExt, WorkspaceConfig)> {
+  let config_path = specifier_to_file_path(&config_file.specifier).ok()?;
+  for ancestor in config_path.parent()?.ancestors().skip(1) {
+    for file_name in ["deno.json", "deno.jsonc"] {
+      let path = ancestor.join(file_name);
+      if !path.is_file() {
+        continue;
+      }
+      let Ok((root_config_file, root_config_file_ext)) =
+        read_config_file(&path)
+      else {
+        continue;
+      };
+      let Ok(Some(workspace_config)) = root_config_file.to_workspace_config()
+      else {
+        continue;
+      };
+      let is_member = workspace_config
+        .members
+        .iter()
+        .any(|member| member.config_file.specifier == config_file.specifier);
+      if is_member {
+        return Some((
+          root_config_file,
+          root_config_file_ext,
+          workspace_config,
+        ));
+      }
+    }
+  }
+  None
+}
+
 /// Resolves the path to use for a local node_modules folder.
 fn resolve_node_modules_folder(
   cwd: &Path,
   flags: &Flags,
   maybe_config_file: Option<&ConfigFile>,
   maybe_package_json: Option<&PackageJson>,
+  maybe_workspace_root_dir: Option<&Path>,
 ) -> Result<Option<PathBuf>, AnyError> {
   let use_node_modules_dir = flags
     .node_modules_dir
@@ -1570,11 +2745,14 @@ fn resolve_node_modules_folder(
     return Ok(None);
   } else if let Some(state) = &*NPM_PROCESS_STATE {
     return Ok(state.local_node_modules_path.as_ref().map(PathBuf::from));
+  } else if use_node_modules_dir.is_none() && maybe_package_json.is_none() {
+    return Ok(None);
+  } else if let Some(workspace_root_dir) = maybe_workspace_root_dir {
+    // a single node_modules directory is set up for the whole workspace
+    workspace_root_dir.join("node_modules")
   } else if let Some(package_json_path) = maybe_package_json.map(|c| &c.path) {
     // always auto-discover the local_node_modules_folder when a package.json exists
     package_json_path.parent().unwrap().join("node_modules")
-  } else if use_node_modules_dir.is_none() {
-    return Ok(None);
   } else if let Some(config_path) = maybe_config_file
     .as_ref()
     .and_then(|c| c.specifier.to_file_path().ok())
@@ -1772,6 +2950,61 @@ mod test {
   use super::*;
   use pretty_assertions::assert_eq;
 
+  #[test]
+  fn test_parse_tasks_config() {
+    let tasks = serde_json::json!({
+      "codegen": "deno run gen.ts",
+      "build": {
+        "command": "deno run build.ts",
+        "dependsOn": ["codegen"]
+      },
+      "all": { "dependsOn": ["build"] },
+      "dev": {
+        "command": "deno run main.ts",
+        "watch": ["src/**/*.ts"]
+      }
+    });
+    let tasks = parse_tasks_config(Some(&tasks)).unwrap();
+    assert_eq!(
+      tasks.into_iter().collect::<Vec<_>>(),
+      vec![
+        (
+          "codegen".to_string(),
+          TaskDefinition {
+            command: "deno run gen.ts".to_string(),
+            depends_on: vec![],
+            watch: vec![],
+          }
+        ),
+        (
+          "build".to_string(),
+          TaskDefinition {
+            command: "deno run build.ts".to_string(),
+            depends_on: vec!["codegen".to_string()],
+            watch: vec![],
+          }
+        ),
+        (
+          "all".to_string(),
+          TaskDefinition {
+            command: "".to_string(),
+            depends_on: vec!["build".to_string()],
+            watch: vec![],
+          }
+        ),
+        (
+          "dev".to_string(),
+          TaskDefinition {
+            command: "deno run main.ts".to_string(),
+            depends_on: vec![],
+            watch: vec!["src/**/*.ts".to_string()],
+          }
+        ),
+      ]
+    );
+    assert!(parse_tasks_config(Some(&serde_json::json!({ "a": 1 }))).is_err());
+  }
+
   #[cfg(not(windows))]
   #[test]
   fn resolve_import_map_config_file() {
@@ -2024,4 +3257,209 @@ mod test {
     let reg_api_url = deno_registry_api_url();
     assert!(reg_api_url.as_str().ends_with('/'));
   }
+
+  #[test]
+  fn scoped_options_nearest_member() {
+    let root = PathBuf::from(if cfg!(windows) { "C:\\root" } else { "/root" });
+    let options = ScopedOptions::with_members(
+      "root",
+      vec![
+        (root.join("a/b"), "b"),
+        (root.join("a"), "a"),
+        (root.join("c"), "c"),
+      ],
+    );
+    assert_eq!(*options.for_path(&root.join("mod.ts")), "root");
+    assert_eq!(*options.for_path(&root.join("a/mod.ts")), "a");
+    assert_eq!(*options.for_path(&root.join("a/b/mod.ts")), "b");
+    assert_eq!(*options.for_path(&root.join("a/bc/mod.ts")), "a");
+    assert_eq!(*options.for_path(&root.join("c/d/mod.ts")), "c");
+    let specifier =
+      ModuleSpecifier::parse("https://deno.land/x/mod.ts").unwrap();
+    assert_eq!(*options.for_specifier(&specifier), "root");
+  }
+
+  #[test]
+  fn test_resolve_remote_cache_options() {
+    let config = Some(("https://config.example.com".to_string(), true));
+    assert_eq!(
+      resolve_remote_cache_options(
+        None,
+        None,
+        Some("token".to_string()),
+        None,
+        None
+      )
+      .unwrap(),
+      None
+    );
+    assert_eq!(
+      resolve_remote_cache_options(config.clone(), None, None, None, None)
+        .unwrap(),
+      Some(RemoteCacheOptions {
+        url: Url::parse("https://config.example.com").unwrap(),
+        maybe_token: None,
+        maybe_s3_credentials: None,
+        read_only: true,
+      })
+    );
+    assert_eq!(
+      resolve_remote_cache_options(
+        config,
+        Some("https://env.example.com".to_string()),
+        Some("token".to_string()),
+        Some("1".to_string()),
+        None,
+      )
+      .unwrap(),
+      Some(RemoteCacheOptions {
+        url: Url::parse("https://env.example.com").unwrap(),
+        maybe_token: Some("token".to_string()),
+        maybe_s3_credentials: None,
+        read_only: true,
+      })
+    );
+    assert!(resolve_remote_cache_options(
+      None,
+      Some("ftp://cache.example.com".to_string()),
+      None,
+      None,
+      None,
+    )
+    .is_err());
+  }
+
+  #[test]
+  fn test_resolve_remote_cache_s3_credentials() {
+    assert_eq!(
+      resolve_remote_cache_s3_credentials(
+        None,
+        Some("key".to_string()),
+        Some("secret".to_string()),
+        None,
+      )
+      .unwrap(),
+      None
+    );
+    assert_eq!(
+      resolve_remote_cache_s3_credentials(
+        Some("us-east-1".to_string()),
+        Some("key".to_string()),
+        Some("secret".to_string()),
+        Some("".to_string()),
+      )
+      .unwrap(),
+      Some(RemoteCacheS3Credentials {
+        region: "us-east-1".to_string(),
+        access_key_id: "key".to_string(),
+        secret_access_key: "secret".to_string(),
+        maybe_session_token: None,
+      })
+    );
+    assert!(resolve_remote_cache_s3_credentials(
+      Some("us-east-1".to_string()),
+      Some("key".to_string()),
+      None,
+      None,
+    )
+    .is_err());
+  }
+
+  #[test]
+  fn test_is_allowed_to_run_scripts() {
+    let allow_scripts = vec![
+      PackageReq::from_str("esbuild").unwrap(),
+      PackageReq::from_str("sharp@^0.33").unwrap(),
+    ];
+    let nv = |text: &str| PackageNv::from_str(text).unwrap();
+    assert!(is_allowed_to_run_scripts(
+      &allow_scripts,
+      &nv("esbuild@0.19.2")
+    ));
+    assert!(is_allowed_to_run_scripts(
+      &allow_scripts,
+      &nv("sharp@0.33.1")
+    ));
+    assert!(!is_allowed_to_run_scripts(
+      &allow_scripts,
+      &nv("sharp@0.32.6")
+    ));
+    assert!(!is_allowed_to_run_scripts(
+      &allow_scripts,
+      &nv("canvas@2.11.2")
+    ));
+  }
+
+  #[test]
+  fn test_resolve_ts_plugins() {
+    let config_file = |text: &str| {
+      ConfigFile::new(text, Url::parse("file:///project/deno.json").unwrap())
+        .unwrap()
+    };
+    let plugins = resolve_ts_plugins(Some(&config_file(
+      r#"{
+        "compilerOptions": {
+          "plugins": [
+            { "name": "typescript-plugin-css-modules", "classnameTransform": "camelCase" }
+          ]
+        }
+      }"#,
+    )))
+    .unwrap();
+    assert_eq!(plugins.len(), 1);
+    assert_eq!(plugins[0].name, "typescript-plugin-css-modules");
+    assert_eq!(
+      plugins[0].options.get("classnameTransform"),
+      Some(&serde_json::json!("camelCase"))
+    );
+    assert_eq!(
+      serde_json::to_value(&plugins[0]).unwrap(),
+      serde_json::json!({
+        "name": "typescript-plugin-css-modules",
+        "classnameTransform": "camelCase",
+      })
+    );
+    assert!(resolve_ts_plugins(Some(&config_file(
+      r#"{ "compilerOptions": {} }"#
+    )))
+    .unwrap()
+    .is_empty());
+    assert!(resolve_ts_plugins(Some(&config_file(
+      r#"{ "compilerOptions": { "plugins": [{ "classnameTransform": "camelCase" }] } }"#
+    )))
+    .is_err());
+  }
+
+  #[test]
+  fn test_config_file_excludes() {
+    let config_file = ConfigFile::new(
+      r#"{
+        "exclude": ["dist", "!dist/keep.ts"],
+        "fmt": { "exclude": ["!dist/fmt.ts"] },
+        "lint": { "files": { "exclude": ["!dist/lint.ts"] } }
+      }"#,
+      Url::parse("file:///project/deno.json").unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+      config_file_excludes(&config_file, None).unwrap(),
+      vec!["dist".to_string(), "!dist/keep.ts".to_string()]
+    );
+    assert_eq!(
+      config_file_excludes(&config_file, Some("fmt")).unwrap(),
+      vec![
+        "dist".to_string(),
+        "!dist/keep.ts".to_string(),
+        "!dist/fmt.ts".to_string()
+      ]
+    );
+    assert_eq!(
+      config_file_excludes(&config_file, Some("lint")).unwrap(),
+      vec![
+        "dist".to_string(),
+        "!dist/keep.ts".to_string(),
+        "!dist/lint.ts".to_string()
+      ]
+    );
+  }
 }
@@ -3,7 +3,9 @@
 use std::path::Path;
 use std::path::PathBuf;
 
+use deno_config::WorkspaceConfig;
 use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
 use deno_npm::registry::parse_dep_entry_name_and_raw_version;
 use deno_runtime::deno_node::PackageJson;
@@ -109,6 +111,107 @@ pub fn get_local_package_json_version_reqs(
   result
 }
 
+/// The scripts which npm runs when installing a package.
+const LIFECYCLE_SCRIPTS: [&str; 4] =
+  ["preinstall", "install", "postinstall", "prepare"];
+
+/// The scripts which npm runs when installing a package of the registry,
+/// in the order they're run.
+const INSTALL_SCRIPTS: [&str; 3] = ["preinstall", "install", "postinstall"];
+
+/// Reads the names and commands of the install scripts of the package.json of
+/// an npm package, which has none when the package.json can't be read.
+pub fn read_install_scripts(package_dir: &Path) -> Vec<(String, String)> {
+  let path = package_dir.join("package.json");
+  let Ok(source) = std::fs::read_to_string(&path) else {
+    return Vec::new();
+  };
+  let Ok(package_json) = PackageJson::load_from_string(path, source) else {
+    return Vec::new();
+  };
+  let Some(scripts) = &package_json.scripts else {
+    return Vec::new();
+  };
+  INSTALL_SCRIPTS
+    .iter()
+    .filter_map(|name| {
+      let command = scripts.get(*name)?;
+      Some((name.to_string(), command.to_string()))
+    })
+    .collect()
+}
+
+/// A workspace member with a package.json, which is linked into the
+/// node_modules directory of the workspace root like a package of a pnpm
+/// workspace, so the npm packages and the other members can import it by
+/// its name.
+#[derive(Debug, Clone)]
+pub struct WorkspaceNpmPackage {
+  pub name: String,
+  /// The directory of the member.
+  pub path: PathBuf,
+  /// The dependencies of the package.json, which are installed in the
+  /// node_modules directory of the workspace root, without the dependencies
+  /// on the other members.
+  pub deps: PackageJsonDeps,
+  /// The lifecycle scripts of the package.json, which aren't run when the
+  /// node_modules directory is set up.
+  pub lifecycle_scripts: Vec<String>,
+}
+
+/// Loads the package.json files of the workspace members which have one with
+/// a name.
+pub fn resolve_workspace_npm_packages(
+  workspace_config: &WorkspaceConfig,
+) -> Result<Vec<WorkspaceNpmPackage>, AnyError> {
+  let mut packages = Vec::new();
+  for member in &workspace_config.members {
+    let Ok(config_path) = member.config_file.specifier.to_file_path() else {
+      continue;
+    };
+    let Some(dir) = config_path.parent() else {
+      continue;
+    };
+    let path = dir.join("package.json");
+    let source = match std::fs::read_to_string(&path) {
+      Ok(source) => source,
+      Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+      Err(err) => {
+        return Err(err).with_context(|| {
+          format!("Error loading package.json at {}.", path.display())
+        })
+      }
+    };
+    let package_json = PackageJson::load_from_string(path, source)?;
+    let Some(name) = package_json.name.clone() else {
+      continue;
+    };
+    let lifecycle_scripts = match &package_json.scripts {
+      Some(scripts) => LIFECYCLE_SCRIPTS
+        .iter()
+        .filter(|script| scripts.contains_key(**script))
+        .map(|script| script.to_string())
+        .collect(),
+      None => Vec::new(),
+    };
+    packages.push(WorkspaceNpmPackage {
+      name,
+      path: dir.to_path_buf(),
+      deps: get_local_package_json_version_reqs(&package_json),
+      lifecycle_scripts,
+    });
+  }
+  // the members are linked instead of installed from the registry
+  let names = packages
+    .iter()
+    .map(|package| package.name.clone())
+    .collect::<Vec<_>>();
+  for package in &mut packages {
+    package.deps.retain(|name, _| !names.contains(name));
+  }
+  Ok(packages)
+}
+
 /// Attempts to discover the package.json file, maybe stopping when it
 /// reaches the specified `maybe_stop_at` directory.
 pub fn discover_from(
@@ -149,6 +252,7 @@ pub fn discover_from(
 
 #[cfg(test)]
 mod test {
+  use deno_config::ConfigFile;
   use pretty_assertions::assert_eq;
   use std::path::PathBuf;
 
@@ -269,4 +373,64 @@ mod test {
       ])
     );
   }
+
+  #[test]
+  fn test_resolve_workspace_npm_packages() {
+    let temp_dir = test_util::TempDir::new();
+    temp_dir.write("deno.json", r#"{ "workspaces": ["a", "b", "c"] }"#);
+    for member in ["a", "b", "c"] {
+      temp_dir.create_dir_all(member);
+      temp_dir.write(format!("{}/deno.json", member), "{}");
+    }
+    temp_dir.write(
+      "a/package.json",
+      r#"{
+  "name": "a",
+  "scripts": { "build": "tsc", "postinstall": "node setup.js" },
+  "dependencies": { "b": "^1.0.0", "chalk": "^5.0.0" }
+}"#,
+    );
+    temp_dir.write("b/package.json", r#"{ "name": "b" }"#);
+    // a member without a package.json isn't linked
+    let config_file =
+      ConfigFile::read(temp_dir.target_path().join("deno.json").as_path())
+        .unwrap();
+    let workspace_config = config_file.to_workspace_config().unwrap().unwrap();
+
+    let packages = resolve_workspace_npm_packages(&workspace_config).unwrap();
+    assert_eq!(
+      packages.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(),
+      vec!["a", "b"]
+    );
+    assert_eq!(
+      packages[0].path,
+      temp_dir.target_path().join("a").to_path_buf()
+    );
+    assert_eq!(packages[0].lifecycle_scripts, vec!["postinstall"]);
+    assert_eq!(packages[0].deps.keys().collect::<Vec<_>>(), vec!["chalk"]);
+    assert!(packages[1].deps.is_empty());
+  }
+
+  #[test]
+  fn test_read_install_scripts() {
+    let temp_dir = test_util::TempDir::new();
+    temp_dir.write(
+      "package.json",
+      r#"{
+  "name": "a",
+  "scripts": { "postinstall": "node b.js", "test": "c", "preinstall": "d" }
+}"#,
+    );
+    assert_eq!(
+      read_install_scripts(temp_dir.target_path().as_path()),
+      vec![
+        ("preinstall".to_string(), "d".to_string()),
+        ("postinstall".to_string(), "node b.js".to_string()),
+      ]
+    );
+    assert_eq!(
+      read_install_scripts(temp_dir.target_path().join("missing").as_path()),
+      Vec::new()
+    );
+  }
 }
@@ -0,0 +1,76 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_runtime::permissions::set_permission_check_callback;
+use deno_runtime::permissions::PermissionCheck;
+use deno_terminal::colors;
+use once_cell::sync::OnceCell;
+
+/// Records every permission check of the process to the file of
+/// `--permission-log`, one JSON object per line like
+/// `{"permission":"read","resource":"/app/data.json","apiName":"Deno.readFile()","granted":true,"prompted":false}`.
+/// The file is created again once per process, so it only has the checks of
+/// the last run.
+pub fn install_permission_log(path: &Path) -> Result<(), AnyError> {
+  static INSTALLED: OnceCell<()> = OnceCell::new();
+  INSTALLED.get_or_try_init(|| {
+    let mut file = File::create(path).with_context(|| {
+      format!("Failed creating permission log \"{}\"", path.display())
+    })?;
+    let path = path.to_path_buf();
+    set_permission_check_callback(Box::new(move |check| {
+      if let Err(err) = file.write_all(permission_log_line(check).as_bytes()) {
+        log::warn!(
+          "{} Failed writing permission log \"{}\": {:#}",
+          colors::yellow("Warning"),
+          path.display(),
+          err
+        );
+      }
+    }));
+    Ok::<_, AnyError>(())
+  })?;
+  Ok(())
+}
+
+fn permission_log_line(check: &PermissionCheck) -> String {
+  let mut line = serde_json::to_string(check).unwrap();
+  line.push('\n');
+  line
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use std::borrow::Cow;
+
+  #[test]
+  fn test_permission_log_line() {
+    assert_eq!(
+      permission_log_line(&PermissionCheck {
+        permission: "read",
+        resource: Some(Cow::Borrowed("/app/data.json")),
+        api_name: Some("Deno.readFile()"),
+        granted: false,
+        prompted: true,
+      }),
+      "{\"permission\":\"read\",\"resource\":\"/app/data.json\",\"apiName\":\"Deno.readFile()\",\"granted\":false,\"prompted\":true}\n"
+    );
+    assert_eq!(
+      permission_log_line(&PermissionCheck {
+        permission: "hrtime",
+        resource: None,
+        api_name: None,
+        granted: true,
+        prompted: false,
+      }),
+      "{\"permission\":\"hrtime\",\"resource\":null,\"apiName\":null,\"granted\":true,\"prompted\":false}\n"
+    );
+  }
+}
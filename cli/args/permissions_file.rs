@@ -0,0 +1,285 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde::Deserialize;
+use deno_core::serde::Serialize;
+use deno_core::serde_json;
+use deno_runtime::permissions::PermissionsOptions;
+
+const PERMISSION_NAMES: [&str; 8] =
+  ["read", "write", "net", "env", "sys", "run", "ffi", "hrtime"];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+enum PermissionValue {
+  All(bool),
+  List(Vec<String>),
+}
+
+/// Permissions which map the names of the permissions to `true` or to a list
+/// of values, like `{ "read": ["./data"], "env": true }`, where the paths are
+/// relative to a directory. These are the contents of the file of
+/// `--permissions-file` and of the sets of the `"permissions"` object of a
+/// configuration file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PermissionSet {
+  base_dir: PathBuf,
+  permissions: BTreeMap<String, PermissionValue>,
+}
+
+impl PermissionSet {
+  pub fn parse(
+    base_dir: PathBuf,
+    value: serde_json::Value,
+  ) -> Result<Self, AnyError> {
+    let permissions: BTreeMap<String, PermissionValue> =
+      serde_json::from_value(value)?;
+    for name in permissions.keys() {
+      if !PERMISSION_NAMES.contains(&name.as_str()) {
+        bail!("Unknown permission \"{}\".", name);
+      }
+    }
+    Ok(Self {
+      base_dir,
+      permissions,
+    })
+  }
+
+  /// Adds the permissions to the ones of the flags.
+  pub fn merge_into(&self, options: &mut PermissionsOptions) {
+    merge_list(&mut options.allow_read, self.paths("read"));
+    merge_list(&mut options.allow_write, self.paths("write"));
+    merge_list(&mut options.allow_ffi, self.paths("ffi"));
+    merge_list(&mut options.allow_net, self.values("net"));
+    match self.values("env") {
+      // the names ending with `*` are the groups granted in prompts
+      Some(values) if !values.is_empty() => {
+        let (prefixes, names): (Vec<_>, Vec<_>) =
+          values.into_iter().partition(|value| value.ends_with('*'));
+        for prefix in prefixes {
+          let prefix = prefix.trim_end_matches('*').to_string();
+          if !prefix.is_empty() && !options.allow_env_prefixes.contains(&prefix)
+          {
+            options.allow_env_prefixes.push(prefix);
+          }
+        }
+        if !names.is_empty() {
+          merge_list(&mut options.allow_env, Some(names));
+        }
+      }
+      values => merge_list(&mut options.allow_env, values),
+    }
+    merge_list(&mut options.allow_sys, self.values("sys"));
+    merge_list(&mut options.allow_run, self.values("run"));
+    if self.values("hrtime").is_some() {
+      options.allow_hrtime = true;
+    }
+  }
+
+  fn values(&self, name: &str) -> Option<Vec<String>> {
+    match self.permissions.get(name)? {
+      PermissionValue::All(true) => Some(vec![]),
+      PermissionValue::All(false) => None,
+      PermissionValue::List(values) => Some(values.clone()),
+    }
+  }
+
+  fn paths(&self, name: &str) -> Option<Vec<PathBuf>> {
+    self.values(name).map(|values| {
+      values
+        .iter()
+        .map(|value| self.base_dir.join(value))
+        .collect()
+    })
+  }
+}
+
+/// The file of `--permissions-file`, where the permissions allowed in
+/// prompts are added, so they aren't prompted for again in the next runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PermissionsFile {
+  path: PathBuf,
+  set: PermissionSet,
+}
+
+impl PermissionsFile {
+  /// Reads the file, which has no permissions when it doesn't exist yet.
+  pub fn read(path: PathBuf) -> Result<Self, AnyError> {
+    let value = match std::fs::read_to_string(&path) {
+      Ok(text) => serde_json::from_str(&text).with_context(|| {
+        format!("Failed parsing permissions file \"{}\"", path.display())
+      })?,
+      Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+        serde_json::Value::Object(Default::default())
+      }
+      Err(err) => {
+        return Err(err).with_context(|| {
+          format!("Failed reading permissions file \"{}\"", path.display())
+        })
+      }
+    };
+    let base_dir = path.parent().unwrap_or_else(|| Path::new("")).to_path_buf();
+    let set = PermissionSet::parse(base_dir, value).with_context(|| {
+      format!("Invalid permissions file \"{}\"", path.display())
+    })?;
+    Ok(Self { path, set })
+  }
+
+  pub fn merge_into(&self, options: &mut PermissionsOptions) {
+    self.set.merge_into(options);
+  }
+
+  /// Adds a permission which was granted in a prompt, where no value means
+  /// all of the permission.
+  pub fn insert_granted(&mut self, name: &str, maybe_value: Option<&str>) {
+    let maybe_value = maybe_value.map(|value| match name {
+      "read" | "write" | "ffi" => self.relative_path_text(value),
+      _ => value.to_string(),
+    });
+    let entry = self
+      .set
+      .permissions
+      .entry(name.to_string())
+      .or_insert_with(|| PermissionValue::List(vec![]));
+    match (entry, maybe_value) {
+      (PermissionValue::All(true), _) => {}
+      (PermissionValue::List(values), Some(value)) => {
+        if !values.contains(&value) {
+          values.push(value);
+        }
+      }
+      (entry, maybe_value) => {
+        *entry = match maybe_value {
+          Some(value) => PermissionValue::List(vec![value]),
+          None => PermissionValue::All(true),
+        };
+      }
+    }
+  }
+
+  pub fn write(&self) -> Result<(), AnyError> {
+    let text = serde_json::to_string_pretty(&self.set.permissions)?;
+    std::fs::write(&self.path, format!("{text}\n")).with_context(|| {
+      format!(
+        "Failed writing permissions file \"{}\"",
+        self.path.display()
+      )
+    })
+  }
+
+  /// Gets the text of a granted path relative to the directory of the file,
+  /// so the file keeps working when the project is moved.
+  fn relative_path_text(&self, path: &str) -> String {
+    match Path::new(path).strip_prefix(&self.set.base_dir) {
+      Ok(relative_path) if relative_path.as_os_str().is_empty() => {
+        ".".to_string()
+      }
+      Ok(relative_path) => {
+        format!("./{}", relative_path.to_string_lossy().replace('\\', "/"))
+      }
+      Err(_) => path.to_string(),
+    }
+  }
+}
+
+/// Merges the values of a permission, where an empty list allows all of it.
+fn merge_list<T: PartialEq>(list: &mut Option<Vec<T>>, other: Option<Vec<T>>) {
+  let Some(other) = other else {
+    return;
+  };
+  match list {
+    Some(list) if list.is_empty() => {}
+    Some(list) if other.is_empty() => list.clear(),
+    Some(list) => {
+      for value in other {
+        if !list.contains(&value) {
+          list.push(value);
+        }
+      }
+    }
+    None => *list = Some(other),
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use test_util::TempDir;
+
+  #[test]
+  fn test_permissions_file() {
+    let temp_dir = TempDir::new();
+    let path = temp_dir.path().join("permissions.json").to_path_buf();
+    let file = PermissionsFile::read(path.clone()).unwrap();
+    assert!(file.set.permissions.is_empty());
+
+    temp_dir.write(
+      "permissions.json",
+      r#"{ "read": ["./data"], "net": ["deno.land"], "env": true }"#,
+    );
+    let mut file = PermissionsFile::read(path.clone()).unwrap();
+    let mut options = PermissionsOptions {
+      allow_net: Some(vec!["example.com".to_string()]),
+      allow_env: Some(vec!["HOME".to_string()]),
+      allow_write: Some(vec![]),
+      ..Default::default()
+    };
+    file.merge_into(&mut options);
+    assert_eq!(
+      options.allow_read,
+      Some(vec![temp_dir.path().join("data").to_path_buf()])
+    );
+    assert_eq!(
+      options.allow_net,
+      Some(vec!["example.com".to_string(), "deno.land".to_string()])
+    );
+    assert_eq!(options.allow_env, Some(vec![]));
+    assert_eq!(options.allow_write, Some(vec![]));
+    assert_eq!(options.allow_run, None);
+    assert!(!options.allow_hrtime);
+
+    let cache_path = temp_dir.path().join("cache").to_path_buf();
+    file.insert_granted("read", Some(&cache_path.to_string_lossy()));
+    file.insert_granted("read", Some("/etc/hosts"));
+    file.insert_granted("env", Some("AWS_*"));
+    file.insert_granted("net", None);
+    file.write().unwrap();
+    let file = PermissionsFile::read(path.clone()).unwrap();
+    assert_eq!(
+      file.set.values("read"),
+      Some(vec![
+        "./data".to_string(),
+        "./cache".to_string(),
+        "/etc/hosts".to_string()
+      ])
+    );
+    assert_eq!(file.set.values("env"), Some(vec![]));
+    assert_eq!(file.set.values("net"), Some(vec![]));
+
+    temp_dir.write("permissions.json", r#"{ "env": ["AWS_*", "HOME"] }"#);
+    let file = PermissionsFile::read(path.clone()).unwrap();
+    let mut options = PermissionsOptions::default();
+    file.merge_into(&mut options);
+    assert_eq!(options.allow_env, Some(vec!["HOME".to_string()]));
+    assert_eq!(options.allow_env_prefixes, vec!["AWS_".to_string()]);
+
+    temp_dir.write("permissions.json", r#"{ "env": ["AWS_*"] }"#);
+    let file = PermissionsFile::read(path).unwrap();
+    let mut options = PermissionsOptions::default();
+    file.merge_into(&mut options);
+    assert_eq!(options.allow_env, None);
+    assert_eq!(options.allow_env_prefixes, vec!["AWS_".to_string()]);
+
+    temp_dir.write("permissions.json", r#"{ "disk": true }"#);
+    assert!(PermissionsFile::read(
+      temp_dir.path().join("permissions.json").to_path_buf()
+    )
+    .is_err());
+  }
+}
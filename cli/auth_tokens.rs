@@ -2,16 +2,25 @@
 
 use base64::prelude::BASE64_STANDARD;
 use base64::Engine;
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+use deno_core::parking_lot::Mutex;
 use deno_core::ModuleSpecifier;
 use log::debug;
 use log::error;
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::fmt;
 use std::net::IpAddr;
 use std::net::Ipv4Addr;
 use std::net::Ipv6Addr;
 use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::process::Stdio;
 use std::str::FromStr;
+use std::sync::Arc;
+use tokio::process::Command;
+use tokio::sync::OnceCell;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum AuthTokenData {
@@ -19,6 +28,22 @@ pub enum AuthTokenData {
   Basic { username: String, password: String },
 }
 
+impl AuthTokenData {
+  /// Parses either a bearer token or `{username}:{password}` for basic
+  /// authentication.
+  fn parse(token: &str) -> Self {
+    if token.contains(':') {
+      let pair: Vec<&str> = token.rsplitn(2, ':').collect();
+      Self::Basic {
+        username: pair[1].to_string(),
+        password: pair[0].to_string(),
+      }
+    } else {
+      Self::Bearer(token.to_string())
+    }
+  }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AuthToken {
   host: AuthDomain,
@@ -37,11 +62,103 @@ impl fmt::Display for AuthToken {
   }
 }
 
+/// An executable which prints the token of a host, similar to the credential
+/// helpers of git. They're configured in the `"auth": { "helpers": ... }`
+/// object of the configuration file, which the `DENO_AUTH_HELPERS`
+/// environment variable overrides.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthTokenHelper {
+  host: AuthDomain,
+  command: Vec<String>,
+  /// The directory the command is run in, which is the directory of the
+  /// configuration file for the helpers configured there.
+  maybe_cwd: Option<PathBuf>,
+}
+
+impl AuthTokenHelper {
+  pub fn new(host: &str, command: Vec<String>) -> Self {
+    Self {
+      host: AuthDomain::from(host),
+      command,
+      maybe_cwd: None,
+    }
+  }
+
+  pub fn with_cwd(mut self, cwd: PathBuf) -> Self {
+    self.maybe_cwd = Some(cwd);
+    self
+  }
+
+  /// Parses the value of the `DENO_AUTH_HELPERS` environment variable, which
+  /// is a semi-colon separated string, where each value is
+  /// `{hostname}={command}` and the arguments of the command are separated by
+  /// whitespace.
+  pub fn parse_list(maybe_helpers_str: Option<String>) -> Vec<Self> {
+    let mut helpers = Vec::new();
+    if let Some(helpers_str) = maybe_helpers_str {
+      for helper_str in helpers_str.split(';') {
+        if helper_str.trim().is_empty() {
+          continue;
+        }
+        let Some((host, command)) = helper_str.split_once('=') else {
+          error!("Badly formed auth helper discarded.");
+          continue;
+        };
+        let host = host.trim();
+        let command: Vec<String> =
+          command.split_whitespace().map(String::from).collect();
+        if host.is_empty() || command.is_empty() {
+          error!("Badly formed auth helper discarded.");
+          continue;
+        }
+        helpers.push(Self::new(host, command));
+      }
+      debug!("Parsed {} auth helper(s).", helpers.len());
+    }
+    helpers
+  }
+
+  /// Runs the command with the host as the last argument. It prints either a
+  /// bearer token or `{username}:{password}` for basic authentication.
+  async fn run(&self, host: &str) -> Result<AuthTokenData, AnyError> {
+    let Some((executable, args)) = self.command.split_first() else {
+      bail!("The command is empty.");
+    };
+    let mut command = Command::new(executable);
+    if let Some(cwd) = &self.maybe_cwd {
+      command.current_dir(cwd);
+    }
+    let output = command
+      .args(args)
+      .arg(host)
+      .stdin(Stdio::null())
+      .stderr(Stdio::inherit())
+      .kill_on_drop(true)
+      .output()
+      .await?;
+    if !output.status.success() {
+      bail!("The command failed with {}.", output.status);
+    }
+    let stdout = String::from_utf8(output.stdout)?;
+    let token = stdout.trim();
+    if token.is_empty() {
+      bail!("The command didn't print a token.");
+    }
+    Ok(AuthTokenData::parse(token))
+  }
+}
+
 /// A structure which contains bearer tokens that can be used when sending
 /// requests to websites, intended to authorize access to private resources
 /// such as remote modules.
 #[derive(Debug, Clone)]
-pub struct AuthTokens(Vec<AuthToken>);
+pub struct AuthTokens {
+  tokens: Vec<AuthToken>,
+  helpers: Vec<AuthTokenHelper>,
+  /// The tokens the helpers printed by host, so every helper runs at most
+  /// once per host.
+  helper_tokens: Arc<Mutex<HashMap<String, Arc<OnceCell<Option<AuthToken>>>>>>,
+}
 
 /// An authorization domain, either an exact or suffix match.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -128,20 +245,10 @@ impl AuthTokens {
           let pair: Vec<&str> = token_str.rsplitn(2, '@').collect();
           let token = pair[1];
           let host = AuthDomain::from(pair[0]);
-          if token.contains(':') {
-            let pair: Vec<&str> = token.rsplitn(2, ':').collect();
-            let username = pair[1].to_string();
-            let password = pair[0].to_string();
-            tokens.push(AuthToken {
-              host,
-              token: AuthTokenData::Basic { username, password },
-            })
-          } else {
-            tokens.push(AuthToken {
-              host,
-              token: AuthTokenData::Bearer(token.to_string()),
-            });
-          }
+          tokens.push(AuthToken {
+            host,
+            token: AuthTokenData::parse(token),
+          });
         } else {
           error!("Badly formed auth token discarded.");
         }
@@ -149,7 +256,18 @@ impl AuthTokens {
       debug!("Parsed {} auth token(s).", tokens.len());
     }
 
-    Self(tokens)
+    Self {
+      tokens,
+      helpers: Vec::new(),
+      helper_tokens: Default::default(),
+    }
+  }
+
+  /// Adds the credential helpers which provide the tokens of the hosts
+  /// without a token in the set.
+  pub fn with_helpers(mut self, helpers: Vec<AuthTokenHelper>) -> Self {
+    self.helpers = helpers;
+    self
   }
 
   /// Attempt to match the provided specifier to the tokens in the set.  The
@@ -158,7 +276,7 @@ impl AuthTokens {
   /// with a host value of `deno.land:8080` but not match `www.deno.land`.  The
   /// matching is case insensitive.
   pub fn get(&self, specifier: &ModuleSpecifier) -> Option<AuthToken> {
-    self.0.iter().find_map(|t| {
+    self.tokens.iter().find_map(|t| {
       if t.host.matches(specifier) {
         Some(t.clone())
       } else {
@@ -166,6 +284,55 @@ impl AuthTokens {
       }
     })
   }
+
+  /// Like `get`, but hosts without a token are looked up with the matching
+  /// credential helper.
+  pub async fn resolve(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> Option<AuthToken> {
+    let maybe_token = self.get(specifier);
+    if maybe_token.is_some() {
+      return maybe_token;
+    }
+    let helper = self.helpers.iter().find(|h| h.host.matches(specifier))?;
+    let host = match specifier.port() {
+      Some(port) => format!("{}:{}", specifier.host_str()?, port),
+      None => specifier.host_str()?.to_string(),
+    };
+    // the cell makes concurrent requests to the same host wait for a single
+    // run of the helper, without holding the lock while it runs
+    let cell = self
+      .helper_tokens
+      .lock()
+      .entry(host.clone())
+      .or_default()
+      .clone();
+    cell
+      .get_or_init(|| async {
+        match helper.run(&host).await {
+          Ok(token) => {
+            debug!(
+              "Got the auth token of '{}' from the credential helper.",
+              host
+            );
+            Some(AuthToken {
+              host: AuthDomain::from(&host),
+              token,
+            })
+          }
+          Err(err) => {
+            error!(
+              "Failed getting the auth token of '{}' from the credential helper: {:#}",
+              host, err
+            );
+            None
+          }
+        }
+      })
+      .await
+      .clone()
+  }
 }
 
 #[cfg(test)]
@@ -173,6 +340,105 @@ mod tests {
   use super::*;
   use deno_core::resolve_url;
 
+  #[cfg(unix)]
+  #[tokio::test]
+  async fn test_auth_token_helpers() {
+    let auth_tokens = AuthTokens::new(Some("abc123@deno.land".to_string()))
+      .with_helpers(vec![
+        AuthTokenHelper::new(
+          "deno.land",
+          vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            "echo unused".to_string(),
+          ],
+        ),
+        AuthTokenHelper::new(
+          "example.com",
+          vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            "echo user:pass-$0".to_string(),
+          ],
+        ),
+        AuthTokenHelper::new(
+          "failing.com",
+          vec!["sh".to_string(), "-c".to_string(), "exit 1".to_string()],
+        ),
+      ]);
+    // the tokens take precedence over the helpers
+    let fixture = resolve_url("https://deno.land/x/mod.ts").unwrap();
+    assert_eq!(
+      auth_tokens.resolve(&fixture).await.unwrap().to_string(),
+      "Bearer abc123"
+    );
+    let fixture = resolve_url("https://npm.example.com:8080/pkg").unwrap();
+    assert_eq!(auth_tokens.get(&fixture), None);
+    assert_eq!(
+      auth_tokens.resolve(&fixture).await.unwrap().token,
+      AuthTokenData::Basic {
+        username: "user".to_string(),
+        password: "pass-npm.example.com:8080".to_string(),
+      }
+    );
+    let fixture = resolve_url("https://failing.com/x/mod.ts").unwrap();
+    assert_eq!(auth_tokens.resolve(&fixture).await, None);
+    let fixture = resolve_url("https://other.com/x/mod.ts").unwrap();
+    assert_eq!(auth_tokens.resolve(&fixture).await, None);
+  }
+
+  #[cfg(unix)]
+  #[tokio::test]
+  async fn test_config_auth_token_helper_runs_once() {
+    let temp_dir = test_util::TempDir::new();
+    let (_, config_file_ext) = crate::args::parse_config_file(
+      r#"{
+        "auth": {
+          "helpers": {
+            "example.com": ["sh", "-c", "echo run >> runs.txt && echo token-$0"]
+          }
+        }
+      }"#,
+      temp_dir.uri().join("deno.json").unwrap(),
+    )
+    .unwrap();
+    let auth_tokens = AuthTokens::new(None)
+      .with_helpers(config_file_ext.to_auth_helpers().unwrap());
+    let fixture = resolve_url("https://example.com/a.ts").unwrap();
+    let other_fixture = resolve_url("https://example.com/b.ts").unwrap();
+    let (token, other_token) = tokio::join!(
+      auth_tokens.resolve(&fixture),
+      auth_tokens.resolve(&other_fixture)
+    );
+    assert_eq!(token.unwrap().to_string(), "Bearer token-example.com");
+    assert_eq!(other_token.unwrap().to_string(), "Bearer token-example.com");
+    assert_eq!(
+      auth_tokens.resolve(&fixture).await.unwrap().to_string(),
+      "Bearer token-example.com"
+    );
+    // the helper ran in the directory of the configuration file and only
+    // once for the host
+    assert_eq!(temp_dir.read_to_string("runs.txt"), "run\n");
+  }
+
+  #[test]
+  fn test_parse_auth_token_helpers() {
+    assert_eq!(
+      AuthTokenHelper::parse_list(Some(
+        "deno.land=helper;example.com = /bin/helper --flag ; ;broken"
+          .to_string()
+      )),
+      vec![
+        AuthTokenHelper::new("deno.land", vec!["helper".to_string()]),
+        AuthTokenHelper::new(
+          "example.com",
+          vec!["/bin/helper".to_string(), "--flag".to_string()]
+        ),
+      ]
+    );
+    assert_eq!(AuthTokenHelper::parse_list(None), vec![]);
+  }
+
   #[test]
   fn test_auth_token() {
     let auth_tokens = AuthTokens::new(Some("abc123@deno.land".to_string()));
@@ -9,6 +9,7 @@ use super::cache_db::CacheDB;
 use super::cache_db::CacheDBConfiguration;
 use super::check::TYPE_CHECK_CACHE_DB;
 use super::deno_dir::DenoDirProvider;
+use super::http_cache_access::HTTP_CACHE_ACCESS_DB;
 use super::incremental::INCREMENTAL_CACHE_DB;
 use super::module_info::MODULE_INFO_CACHE_DB;
 use super::node::NODE_ANALYSIS_CACHE_DB;
@@ -20,6 +21,7 @@ pub struct Caches {
   dep_analysis_db: OnceCell<CacheDB>,
   node_analysis_db: OnceCell<CacheDB>,
   type_checking_cache_db: OnceCell<CacheDB>,
+  http_cache_access_db: OnceCell<CacheDB>,
 }
 
 impl Caches {
@@ -31,6 +33,7 @@ impl Caches {
       dep_analysis_db: Default::default(),
       node_analysis_db: Default::default(),
       type_checking_cache_db: Default::default(),
+      http_cache_access_db: Default::default(),
     }
   }
 
@@ -109,4 +112,16 @@ impl Caches {
         .map(|dir| dir.type_checking_cache_db_file_path()),
     )
   }
+
+  pub fn http_cache_access_db(&self) -> CacheDB {
+    Self::make_db(
+      &self.http_cache_access_db,
+      &HTTP_CACHE_ACCESS_DB,
+      self
+        .dir_provider
+        .get_or_create()
+        .ok()
+        .map(|dir| dir.http_cache_access_db_file_path()),
+    )
+  }
 }
@@ -3,8 +3,10 @@
 use super::cache_db::CacheDB;
 use super::cache_db::CacheDBConfiguration;
 use super::cache_db::CacheFailure;
+use crate::tsc::Diagnostics;
 use deno_ast::ModuleSpecifier;
 use deno_core::error::AnyError;
+use deno_core::serde_json;
 use deno_runtime::deno_webstorage::rusqlite::params;
 
 pub static TYPE_CHECK_CACHE_DB: CacheDBConfiguration = CacheDBConfiguration {
@@ -16,10 +18,15 @@ pub static TYPE_CHECK_CACHE_DB: CacheDBConfiguration = CacheDBConfiguration {
       specifier TEXT PRIMARY KEY,
       text TEXT NOT NULL
     );",
+    "CREATE TABLE IF NOT EXISTS checkdiagnostics (
+      check_hash TEXT PRIMARY KEY,
+      diagnostics TEXT NOT NULL
+    );",
   ),
   on_version_change: concat!(
     "DELETE FROM checkcache;",
-    "DELETE FROM tsbuildinfo;"
+    "DELETE FROM tsbuildinfo;",
+    "DELETE FROM checkdiagnostics;"
   ),
   preheat_queries: &[],
   // If the cache fails, just ignore all caching attempts
@@ -28,8 +35,10 @@ pub static TYPE_CHECK_CACHE_DB: CacheDBConfiguration = CacheDBConfiguration {
 
 /// The cache used to tell whether type checking should occur again.
 ///
-/// This simply stores a hash of the inputs of each successful type check
-/// and only clears them out when changing CLI versions.
+/// This simply stores a hash of the inputs of each successful type check,
+/// along with the diagnostics of each failed type check so they can be
+/// reported again without type checking, and only clears them out when
+/// changing CLI versions.
 pub struct TypeCheckCache(CacheDB);
 
 impl TypeCheckCache {
@@ -79,6 +88,52 @@ impl TypeCheckCache {
     Ok(())
   }
 
+  /// Gets the diagnostics of a previous type check of the same inputs.
+  pub fn get_check_diagnostics(&self, check_hash: u64) -> Option<Diagnostics> {
+    let text = self
+      .0
+      .query_row(
+        "SELECT diagnostics FROM checkdiagnostics WHERE check_hash=?1 LIMIT 1",
+        params![check_hash.to_string()],
+        |row| Ok(row.get::<_, String>(0)?),
+      )
+      .ok()??;
+    match serde_json::from_str(&text) {
+      Ok(diagnostics) => Some(diagnostics),
+      Err(err) => {
+        log::debug!("Error deserializing check diagnostics: {}", err);
+        None
+      }
+    }
+  }
+
+  pub fn set_check_diagnostics(
+    &self,
+    check_hash: u64,
+    diagnostics: &Diagnostics,
+  ) {
+    if let Err(err) = self.set_check_diagnostics_result(check_hash, diagnostics)
+    {
+      if cfg!(debug_assertions) {
+        panic!("Error saving check diagnostics: {err}");
+      } else {
+        log::debug!("Error saving check diagnostics: {}", err);
+      }
+    }
+  }
+
+  fn set_check_diagnostics_result(
+    &self,
+    check_hash: u64,
+    diagnostics: &Diagnostics,
+  ) -> Result<(), AnyError> {
+    self.0.execute(
+      "INSERT OR REPLACE INTO checkdiagnostics (check_hash, diagnostics) VALUES (?1, ?2)",
+      params![check_hash.to_string(), serde_json::to_string(diagnostics)?],
+    )?;
+    Ok(())
+  }
+
   pub fn get_tsbuildinfo(&self, specifier: &ModuleSpecifier) -> Option<String> {
     self
       .0
@@ -161,4 +216,31 @@ mod test {
       Some("other".to_string())
     );
   }
+
+  #[test]
+  pub fn check_cache_diagnostics() {
+    let conn = CacheDB::in_memory(&TYPE_CHECK_CACHE_DB, "1.0.0");
+    let cache = TypeCheckCache::new(conn);
+
+    let diagnostics: Diagnostics = serde_json::from_value(serde_json::json!([
+      {
+        "messageText": "Type 'string' is not assignable to type 'number'.",
+        "code": 2322,
+        "category": 1,
+        "fileName": "file:///a/main.ts",
+        "start": { "line": 0, "character": 6 },
+        "end": { "line": 0, "character": 7 },
+      }
+    ]))
+    .unwrap();
+    assert_eq!(cache.get_check_diagnostics(1), None);
+    cache.set_check_diagnostics(1, &diagnostics);
+    assert_eq!(cache.get_check_diagnostics(1), Some(diagnostics.clone()));
+    assert_eq!(cache.get_check_diagnostics(2), None);
+
+    // try changing the cli version (should clear)
+    let conn = cache.0.recreate_with_version("2.0.0");
+    let cache = TypeCheckCache::new(conn);
+    assert_eq!(cache.get_check_diagnostics(1), None);
+  }
 }
@@ -3,8 +3,10 @@
 use once_cell::sync::OnceCell;
 
 use super::DiskCache;
+use super::FastInsecureHasher;
 
 use std::env;
+use std::path::Path;
 use std::path::PathBuf;
 
 /// Lazily creates the deno dir which might be useful in scenarios
@@ -110,6 +112,12 @@ impl DenoDir {
     self.root.join("check_cache_v1")
   }
 
+  /// Path for the last access times of the entries of the HTTP cache.
+  pub fn http_cache_access_db_file_path(&self) -> PathBuf {
+    // bump this version name to invalidate the entire cache
+    self.root.join("http_cache_access_v1")
+  }
+
   /// Path to the registries cache, used for the lps.
   pub fn registries_folder_path(&self) -> PathBuf {
     self.root.join("registries")
@@ -131,25 +139,61 @@ impl DenoDir {
     self.root.join("latest.txt")
   }
 
+  /// File used for the advisories of `deno audit`.
+  pub fn npm_audit_file_path(&self) -> PathBuf {
+    self.root.join("npm_audit.json")
+  }
+
   /// Folder used for the npm cache.
   pub fn npm_folder_path(&self) -> PathBuf {
     self.root.join("npm")
   }
 
-  /// Path used for the REPL history file.
+  /// Folder used for the snapshots of the TypeScript compilers pinned by
+  /// `"typescriptVersion"`.
+  pub fn tsc_snapshots_folder_path(&self) -> PathBuf {
+    self.root.join("tsc_snapshots")
+  }
+
+  /// Path used for the REPL history file, which is a file per project when
+  /// the REPL is started in a project with a config file.
   /// Can be overridden or disabled by setting `DENO_REPL_HISTORY` environment variable.
-  pub fn repl_history_file_path(&self) -> Option<PathBuf> {
+  pub fn repl_history_file_path(
+    &self,
+    maybe_project_dir: Option<&Path>,
+  ) -> Option<PathBuf> {
     if let Some(deno_repl_history) = env::var_os("DENO_REPL_HISTORY") {
       if deno_repl_history.is_empty() {
         None
       } else {
         Some(PathBuf::from(deno_repl_history))
       }
+    } else if let Some(project_dir) = maybe_project_dir {
+      let hash = FastInsecureHasher::new()
+        .write_str(&project_dir.to_string_lossy())
+        .finish();
+      Some(
+        self
+          .root
+          .join("repl_history")
+          .join(format!("{:016x}.txt", hash)),
+      )
     } else {
       Some(self.root.join("deno_history.txt"))
     }
   }
 
+  /// Folder with the control sockets of the programs running with HMR.
+  pub fn hmr_control_folder_path(&self) -> PathBuf {
+    self.root.join("hmr")
+  }
+
+  /// Folder with the executable which was replaced by the last
+  /// `deno upgrade`, which `deno upgrade --rollback` restores.
+  pub fn upgrade_backup_folder_path(&self) -> PathBuf {
+    self.root.join("upgrade_backup")
+  }
+
   /// Folder path used for downloading new versions of deno.
   pub fn dl_folder_path(&self) -> PathBuf {
     self.root.join("dl")
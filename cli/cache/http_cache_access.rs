@@ -0,0 +1,115 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use deno_core::error::AnyError;
+use deno_core::ModuleSpecifier;
+use deno_runtime::deno_webstorage::rusqlite::params;
+
+use super::cache_db::CacheDB;
+use super::cache_db::CacheDBConfiguration;
+use super::cache_db::CacheFailure;
+
+pub static HTTP_CACHE_ACCESS_DB: CacheDBConfiguration = CacheDBConfiguration {
+  table_initializer: "CREATE TABLE IF NOT EXISTS httpcacheaccess (
+      url TEXT PRIMARY KEY,
+      last_access INTEGER NOT NULL
+    );",
+  // the access times are still valid for a different version of deno
+  on_version_change: "",
+  preheat_queries: &[],
+  on_failure: CacheFailure::Blackhole,
+};
+
+/// The time when the remote modules of the global HTTP cache were last
+/// accessed, which is used to evict the stale entries of the cache.
+pub struct HttpCacheAccessCache {
+  conn: CacheDB,
+}
+
+impl HttpCacheAccessCache {
+  #[cfg(test)]
+  pub fn new_in_memory() -> Self {
+    Self::new(CacheDB::in_memory(
+      &HTTP_CACHE_ACCESS_DB,
+      crate::version::deno(),
+    ))
+  }
+
+  pub fn new(conn: CacheDB) -> Self {
+    Self { conn }
+  }
+
+  /// Records that the remote module was accessed now.
+  pub fn set_accessed(&self, specifier: &ModuleSpecifier) {
+    if !matches!(specifier.scheme(), "http" | "https") {
+      return;
+    }
+    if let Err(err) = self.set_last_access(specifier.as_str(), now_secs()) {
+      log::debug!("Error saving the access time of {}. {:#}", specifier, err);
+    }
+  }
+
+  /// Gets the time in seconds since the epoch when the URL was last accessed.
+  pub fn get_last_access(&self, url: &str) -> Result<Option<u64>, AnyError> {
+    let query = "SELECT last_access FROM httpcacheaccess WHERE url=?1 LIMIT 1";
+    self.conn.query_row(query, params![url], |row| {
+      let last_access: i64 = row.get(0)?;
+      Ok(last_access as u64)
+    })
+  }
+
+  pub fn set_last_access(
+    &self,
+    url: &str,
+    last_access: u64,
+  ) -> Result<(), AnyError> {
+    let sql = "
+      INSERT OR REPLACE INTO
+        httpcacheaccess (url, last_access)
+      VALUES
+        (?1, ?2)";
+    self.conn.execute(sql, params![url, last_access as i64])?;
+    Ok(())
+  }
+
+  pub fn remove(&self, url: &str) -> Result<(), AnyError> {
+    let sql = "DELETE FROM httpcacheaccess WHERE url=?1";
+    self.conn.execute(sql, params![url])?;
+    Ok(())
+  }
+}
+
+fn now_secs() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_secs())
+    .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  pub fn http_cache_access_general_use() {
+    let cache = HttpCacheAccessCache::new_in_memory();
+    let url = "https://deno.land/x/mod.ts";
+    assert_eq!(cache.get_last_access(url).unwrap(), None);
+    cache.set_last_access(url, 10).unwrap();
+    assert_eq!(cache.get_last_access(url).unwrap(), Some(10));
+    cache.set_last_access(url, 20).unwrap();
+    assert_eq!(cache.get_last_access(url).unwrap(), Some(20));
+    cache.remove(url).unwrap();
+    assert_eq!(cache.get_last_access(url).unwrap(), None);
+
+    // only remote modules are recorded
+    let specifier = ModuleSpecifier::parse("file:///mod.ts").unwrap();
+    cache.set_accessed(&specifier);
+    assert_eq!(cache.get_last_access(specifier.as_str()).unwrap(), None);
+    let specifier = ModuleSpecifier::parse(url).unwrap();
+    cache.set_accessed(&specifier);
+    assert!(cache.get_last_access(url).unwrap().unwrap() > 20);
+  }
+}
@@ -6,6 +6,7 @@ use crate::errors::get_error_class_name;
 use crate::file_fetcher::FetchOptions;
 use crate::file_fetcher::FileFetcher;
 use crate::npm::CliNpmResolver;
+use crate::util::checksum;
 use crate::util::fs::atomic_write_file;
 
 use deno_ast::MediaType;
@@ -31,10 +32,12 @@ mod common;
 mod deno_dir;
 mod disk_cache;
 mod emit;
+mod http_cache_access;
 mod incremental;
 mod module_info;
 mod node;
 mod parsed_source;
+mod remote;
 
 pub use caches::Caches;
 pub use check::TypeCheckCache;
@@ -43,11 +46,16 @@ pub use deno_dir::DenoDir;
 pub use deno_dir::DenoDirProvider;
 pub use disk_cache::DiskCache;
 pub use emit::EmitCache;
+pub use http_cache_access::HttpCacheAccessCache;
 pub use incremental::IncrementalCache;
 pub use module_info::ModuleInfoCache;
 pub use node::NodeAnalysisCache;
 pub use parsed_source::LazyGraphSourceParser;
 pub use parsed_source::ParsedSourceCache;
+pub use remote::FsRemoteCacheBackend;
+pub use remote::HttpRemoteCacheBackend;
+pub use remote::RemoteCache;
+pub use remote::RemoteCacheBackend;
 
 /// Permissions used to save a file in the disk caches.
 pub const CACHE_PERM: u32 = 0o644;
@@ -69,7 +77,7 @@ impl deno_cache_dir::DenoCacheEnv for RealDenoCacheEnv {
     path: &Path,
     bytes: &[u8],
   ) -> std::io::Result<()> {
-    atomic_write_file(path, bytes, CACHE_PERM)
+    atomic_write_cache_file(path, bytes)
   }
 
   fn modified(&self, path: &Path) -> std::io::Result<Option<SystemTime>> {
@@ -91,6 +99,60 @@ impl deno_cache_dir::DenoCacheEnv for RealDenoCacheEnv {
   }
 }
 
+/// Writes a cache entry through a sibling temp file that's named after a
+/// checksum of its content and then renamed into place, so processes sharing
+/// a DENO_DIR only ever observe complete entries. An entry that already holds
+/// the same content is left untouched.
+fn atomic_write_cache_file(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+  if let Ok(metadata) = std::fs::metadata(path) {
+    if metadata.len() == bytes.len() as u64
+      && std::fs::read(path).ok().as_deref() == Some(bytes)
+    {
+      return Ok(());
+    }
+  }
+
+  let checksum = checksum::gen(&[bytes]);
+  let mut temp_file_name = path.file_name().unwrap().to_os_string();
+  temp_file_name.push(format!(".{}.tmp", &checksum[..16]));
+  let temp_file_path = path.with_file_name(temp_file_name);
+  let mut file = match std::fs::OpenOptions::new()
+    .write(true)
+    .create_new(true)
+    .open(&temp_file_path)
+  {
+    Ok(file) => file,
+    // Either another writer is currently saving this same content or the
+    // directory doesn't exist yet. Fall back to a randomly named temp file,
+    // which also creates any missing directories.
+    Err(err)
+      if matches!(
+        err.kind(),
+        std::io::ErrorKind::AlreadyExists | std::io::ErrorKind::NotFound
+      ) =>
+    {
+      return atomic_write_file(path, bytes, CACHE_PERM);
+    }
+    Err(err) => return Err(err),
+  };
+
+  let result = (|| {
+    use std::io::Write;
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::PermissionsExt;
+      file.set_permissions(std::fs::Permissions::from_mode(CACHE_PERM))?;
+    }
+    file.write_all(bytes)?;
+    drop(file);
+    std::fs::rename(&temp_file_path, path)
+  })();
+  if result.is_err() {
+    let _ = std::fs::remove_file(&temp_file_path);
+  }
+  result
+}
+
 pub type GlobalHttpCache = deno_cache_dir::GlobalHttpCache<RealDenoCacheEnv>;
 pub type LocalHttpCache = deno_cache_dir::LocalHttpCache<RealDenoCacheEnv>;
 pub type LocalLspHttpCache =
@@ -106,6 +168,7 @@ pub struct FetchCacher {
   file_fetcher: Arc<FileFetcher>,
   file_header_overrides: HashMap<ModuleSpecifier, HashMap<String, String>>,
   global_http_cache: Arc<GlobalHttpCache>,
+  http_cache_access_cache: Arc<HttpCacheAccessCache>,
   npm_resolver: Arc<dyn CliNpmResolver>,
   module_info_cache: Arc<ModuleInfoCache>,
   permissions: PermissionsContainer,
@@ -113,11 +176,13 @@ pub struct FetchCacher {
 }
 
 impl FetchCacher {
+  #[allow(clippy::too_many_arguments)]
   pub fn new(
     emit_cache: EmitCache,
     file_fetcher: Arc<FileFetcher>,
     file_header_overrides: HashMap<ModuleSpecifier, HashMap<String, String>>,
     global_http_cache: Arc<GlobalHttpCache>,
+    http_cache_access_cache: Arc<HttpCacheAccessCache>,
     npm_resolver: Arc<dyn CliNpmResolver>,
     module_info_cache: Arc<ModuleInfoCache>,
     permissions: PermissionsContainer,
@@ -127,6 +192,7 @@ impl FetchCacher {
       file_fetcher,
       file_header_overrides,
       global_http_cache,
+      http_cache_access_cache,
       npm_resolver,
       module_info_cache,
       permissions,
@@ -218,6 +284,7 @@ impl Loader for FetchCacher {
 
     let file_fetcher = self.file_fetcher.clone();
     let file_header_overrides = self.file_header_overrides.clone();
+    let http_cache_access_cache = self.http_cache_access_cache.clone();
     let permissions = self.permissions.clone();
     let specifier = specifier.clone();
 
@@ -243,6 +310,12 @@ impl Loader for FetchCacher {
         })
         .await
         .map(|file| {
+          // keep track of when the modules were last used so that stale
+          // entries of the cache can be evicted
+          http_cache_access_cache.set_accessed(&specifier);
+          if file.specifier != specifier {
+            http_cache_access_cache.set_accessed(&file.specifier);
+          }
           let maybe_headers =
             match (file.maybe_headers, file_header_overrides.get(&specifier)) {
               (Some(headers), Some(overrides)) => {
@@ -299,3 +372,54 @@ impl Loader for FetchCacher {
     }
   }
 }
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use deno_cache_dir::DenoCacheEnv;
+  use test_util::TempDir;
+
+  #[test]
+  fn atomic_write_cache_file_concurrent() {
+    let temp_dir = TempDir::new();
+    let path = temp_dir.path().join("entry").to_path_buf();
+    let contents = [b"a".repeat(64 * 1024), b"b".repeat(64 * 1024)];
+    let contents = &contents;
+    std::thread::scope(|scope| {
+      for i in 0..16 {
+        let path = &path;
+        let content = &contents[i % 2];
+        scope.spawn(move || {
+          for _ in 0..20 {
+            RealDenoCacheEnv.atomic_write_file(path, content).unwrap();
+            // readers only ever see a complete entry
+            let read = RealDenoCacheEnv.read_file_bytes(path).unwrap().unwrap();
+            assert!(contents.contains(&read));
+          }
+        });
+      }
+    });
+
+    let read = RealDenoCacheEnv.read_file_bytes(&path).unwrap().unwrap();
+    assert!(contents.contains(&read));
+    // no temp files were left behind
+    let entries = std::fs::read_dir(temp_dir.path())
+      .unwrap()
+      .map(|entry| entry.unwrap().file_name())
+      .collect::<Vec<_>>();
+    assert_eq!(entries, vec!["entry"]);
+  }
+
+  #[test]
+  fn atomic_write_cache_file_creates_dirs() {
+    let temp_dir = TempDir::new();
+    let path = temp_dir.path().join("a/b/entry").to_path_buf();
+    RealDenoCacheEnv.atomic_write_file(&path, b"data").unwrap();
+    // writing the same content again leaves the entry in place
+    RealDenoCacheEnv.atomic_write_file(&path, b"data").unwrap();
+    assert_eq!(
+      RealDenoCacheEnv.read_file_bytes(&path).unwrap().unwrap(),
+      b"data"
+    );
+  }
+}
@@ -11,12 +11,17 @@ use crate::cache::Caches;
 use crate::cache::DenoDir;
 use crate::cache::DenoDirProvider;
 use crate::cache::EmitCache;
+use crate::cache::FsRemoteCacheBackend;
 use crate::cache::GlobalHttpCache;
 use crate::cache::HttpCache;
+use crate::cache::HttpCacheAccessCache;
+use crate::cache::HttpRemoteCacheBackend;
 use crate::cache::LocalHttpCache;
 use crate::cache::ModuleInfoCache;
 use crate::cache::NodeAnalysisCache;
 use crate::cache::ParsedSourceCache;
+use crate::cache::RemoteCache;
+use crate::cache::RemoteCacheBackend;
 use crate::emit::Emitter;
 use crate::file_fetcher::FileFetcher;
 use crate::graph_util::FileWatcherReporter;
@@ -42,10 +47,12 @@ use crate::resolver::NpmModuleLoader;
 use crate::resolver::SloppyImportsResolver;
 use crate::standalone::DenoCompileBinaryWriter;
 use crate::tools::check::TypeChecker;
+use crate::tsc::PinnedTypeScript;
 use crate::util::file_watcher::WatcherCommunicator;
 use crate::util::fs::canonicalize_path_maybe_not_exists;
 use crate::util::import_map::deno_json_deps;
 use crate::util::import_map::import_map_deps;
+use crate::util::path::specifier_to_file_path;
 use crate::util::progress_bar::ProgressBar;
 use crate::util::progress_bar::ProgressBarStyle;
 use crate::worker::CliMainWorkerFactory;
@@ -158,6 +165,8 @@ struct CliFactoryServices {
   root_cert_store_provider: Deferred<Arc<dyn RootCertStoreProvider>>,
   blob_store: Deferred<Arc<BlobStore>>,
   module_info_cache: Deferred<Arc<ModuleInfoCache>>,
+  http_cache_access_cache: Deferred<Arc<HttpCacheAccessCache>>,
+  remote_cache: Deferred<Option<Arc<RemoteCache>>>,
   parsed_source_cache: Deferred<Arc<ParsedSourceCache>>,
   resolver: Deferred<Arc<CliGraphResolver>>,
   maybe_file_watcher_reporter: Deferred<Option<FileWatcherReporter>>,
@@ -210,7 +219,7 @@ impl CliFactory {
       let caches = Arc::new(Caches::new(self.deno_dir_provider().clone()));
       // Warm up the caches we know we'll likely need based on the CLI mode
       match self.options.sub_command() {
-        DenoSubcommand::Run(_) => {
+        DenoSubcommand::Run(_) | DenoSubcommand::Serve(_) => {
           _ = caches.dep_analysis_db();
           _ = caches.node_analysis_db();
         }
@@ -268,23 +277,68 @@ impl CliFactory {
 
   pub fn http_client(&self) -> &Arc<HttpClient> {
     self.services.http_client.get_or_init(|| {
-      Arc::new(HttpClient::new(
-        Some(self.root_cert_store_provider().clone()),
-        self.options.unsafely_ignore_certificate_errors().clone(),
-      ))
+      Arc::new(
+        HttpClient::new(
+          Some(self.root_cert_store_provider().clone()),
+          self.options.unsafely_ignore_certificate_errors().clone(),
+        )
+        .with_offline(self.options.offline())
+        .with_download_retries(self.options.download_retries())
+        .with_auth_tokens(self.options.auth_tokens().clone())
+        .with_proxy_rules(self.options.maybe_proxy_rules().cloned())
+        .with_client_certs(self.options.client_certs().to_vec()),
+      )
+    })
+  }
+
+  pub fn pinned_typescript(
+    &self,
+  ) -> Result<Option<Arc<PinnedTypeScript>>, AnyError> {
+    let Some(version) = self.options.typescript_version() else {
+      return Ok(None);
+    };
+    Ok(Some(Arc::new(PinnedTypeScript::new(
+      &self.deno_dir()?.tsc_snapshots_folder_path(),
+      version.clone(),
+    ))))
+  }
+
+  pub fn remote_cache(&self) -> Result<&Option<Arc<RemoteCache>>, AnyError> {
+    self.services.remote_cache.get_or_try_init(|| {
+      let Some(options) = self.options.resolve_remote_cache_options()? else {
+        return Ok(None);
+      };
+      let backend: Arc<dyn RemoteCacheBackend> =
+        if options.url.scheme() == "file" {
+          let root_path = specifier_to_file_path(&options.url)?;
+          Arc::new(FsRemoteCacheBackend::new(root_path))
+        } else if self.options.offline() {
+          return Ok(None);
+        } else {
+          Arc::new(HttpRemoteCacheBackend::new(
+            options.url,
+            options.maybe_token,
+            options.maybe_s3_credentials,
+            self.http_client().clone(),
+          ))
+        };
+      Ok(Some(Arc::new(RemoteCache::new(backend, options.read_only))))
     })
   }
 
   pub fn file_fetcher(&self) -> Result<&Arc<FileFetcher>, AnyError> {
     self.services.file_fetcher.get_or_try_init(|| {
-      Ok(Arc::new(FileFetcher::new(
+      let mut file_fetcher = FileFetcher::new(
         self.http_cache()?.clone(),
         self.options.cache_setting(),
         !self.options.no_remote(),
         self.http_client().clone(),
         self.blob_store().clone(),
         Some(self.text_only_progress_bar().clone()),
-      )))
+      );
+      file_fetcher.set_fetch_concurrency(self.options.fetch_concurrency());
+      file_fetcher.set_remote_cache(self.remote_cache()?.clone());
+      Ok(Arc::new(file_fetcher))
     })
   }
 
@@ -437,12 +491,19 @@ impl CliFactory {
             cache_setting: self.options.cache_setting(),
             text_only_progress_bar: self.text_only_progress_bar().clone(),
             maybe_node_modules_path: self.options.node_modules_dir_path(),
+            workspace_npm_packages: self
+              .options
+              .workspace_npm_packages()
+              .to_vec(),
+            node_modules_dir_mode: self.options.node_modules_dir_mode(),
+            allow_scripts: self.options.allow_scripts().to_vec(),
             package_json_installer:
               CliNpmResolverManagedPackageJsonInstallerOption::ConditionalInstall(
                 self.package_json_deps_provider().clone(),
               ),
             npm_system_info: self.options.npm_system_info(),
             npm_registry_url: crate::args::npm_registry_default_url().to_owned(),
+            maybe_remote_cache: self.remote_cache()?.clone(),
           })
         }).await
       })
@@ -533,6 +594,16 @@ impl CliFactory {
     })
   }
 
+  pub fn http_cache_access_cache(
+    &self,
+  ) -> Result<&Arc<HttpCacheAccessCache>, AnyError> {
+    self.services.http_cache_access_cache.get_or_try_init(|| {
+      Ok(Arc::new(HttpCacheAccessCache::new(
+        self.caches()?.http_cache_access_db(),
+      )))
+    })
+  }
+
   pub fn parsed_source_cache(&self) -> &Arc<ParsedSourceCache> {
     self
       .services
@@ -604,6 +675,8 @@ impl CliFactory {
           self.options.clone(),
           self.node_resolver().await?.clone(),
           self.npm_resolver().await?.clone(),
+          self.remote_cache()?.clone(),
+          self.pinned_typescript()?,
         )))
       })
       .await
@@ -628,6 +701,7 @@ impl CliFactory {
           self.emit_cache()?.clone(),
           self.file_fetcher()?.clone(),
           self.global_http_cache()?.clone(),
+          self.http_cache_access_cache()?.clone(),
           self.type_checker().await?.clone(),
         )))
       })
@@ -668,6 +742,7 @@ impl CliFactory {
           self.maybe_file_watcher_reporter().clone(),
           self.module_graph_builder().await?.clone(),
           self.module_info_cache()?.clone(),
+          self.npm_resolver().await?.clone(),
           self.parsed_source_cache().clone(),
           self.text_only_progress_bar().clone(),
           self.resolver().await?.clone(),
@@ -712,6 +787,10 @@ impl CliFactory {
           checker.enable_feature(flag_name);
         }
       }
+      if self.options.unstable_test_snapshot() {
+        checker
+          .enable_feature(crate::tools::test::snapshot::UNSTABLE_FEATURE_NAME);
+      }
 
       Arc::new(checker)
     })
@@ -795,6 +874,7 @@ impl CliFactory {
       enable_testing_features: self.options.enable_testing_features(),
       has_node_modules_dir: self.options.has_node_modules_dir(),
       hmr: self.options.has_hmr(),
+      hmr_control_dir: Some(self.deno_dir()?.hmr_control_folder_path()),
       inspect_brk: self.options.inspect_brk().is_some(),
       inspect_wait: self.options.inspect_wait().is_some(),
       strace_ops: self.options.strace_ops().clone(),
@@ -1,15 +1,17 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use crate::args::default_fetch_concurrency;
 use crate::args::CacheSetting;
 use crate::auth_tokens::AuthToken;
-use crate::auth_tokens::AuthTokens;
 use crate::cache::HttpCache;
+use crate::cache::RemoteCache;
 use crate::colors;
 use crate::http_util;
 use crate::http_util::resolve_redirect_from_response;
 use crate::http_util::CacheSemantics;
 use crate::http_util::HeadersMap;
 use crate::http_util::HttpClient;
+use crate::http_util::PartialBody;
 use crate::util::progress_bar::ProgressBar;
 use crate::util::progress_bar::UpdateGuard;
 
@@ -28,18 +30,21 @@ use deno_runtime::deno_fetch::reqwest::header::HeaderValue;
 use deno_runtime::deno_fetch::reqwest::header::ACCEPT;
 use deno_runtime::deno_fetch::reqwest::header::AUTHORIZATION;
 use deno_runtime::deno_fetch::reqwest::header::IF_NONE_MATCH;
+use deno_runtime::deno_fetch::reqwest::header::IF_RANGE;
+use deno_runtime::deno_fetch::reqwest::header::RANGE;
 use deno_runtime::deno_fetch::reqwest::StatusCode;
 use deno_runtime::deno_web::BlobStore;
 use deno_runtime::permissions::PermissionsContainer;
 use log::debug;
 use std::collections::HashMap;
-use std::env;
 use std::fs;
 use std::future::Future;
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::time::SystemTime;
+use tokio::sync::Semaphore;
 
 pub const SUPPORTED_SCHEMES: [&str; 5] =
   ["data", "blob", "file", "http", "https"];
@@ -151,7 +156,6 @@ pub struct FetchOptions<'a> {
 /// A structure for resolving, fetching and caching source files.
 #[derive(Debug, Clone)]
 pub struct FileFetcher {
-  auth_tokens: AuthTokens,
   allow_remote: bool,
   memory_files: MemoryFiles,
   cache_setting: CacheSetting,
@@ -160,6 +164,10 @@ pub struct FileFetcher {
   blob_store: Arc<BlobStore>,
   download_log_level: log::Level,
   progress_bar: Option<ProgressBar>,
+  remote_cache: Option<Arc<RemoteCache>>,
+  /// limits the number of remote modules downloaded at the same time, whose
+  /// requests share the connection pool of `http_client`
+  download_semaphore: Arc<Semaphore>,
 }
 
 impl FileFetcher {
@@ -172,7 +180,6 @@ impl FileFetcher {
     progress_bar: Option<ProgressBar>,
   ) -> Self {
     Self {
-      auth_tokens: AuthTokens::new(env::var("DENO_AUTH_TOKENS").ok()),
       allow_remote,
       memory_files: Default::default(),
       cache_setting,
@@ -181,6 +188,10 @@ impl FileFetcher {
       blob_store,
       download_log_level: log::Level::Info,
       progress_bar,
+      remote_cache: None,
+      download_semaphore: Arc::new(Semaphore::new(
+        default_fetch_concurrency().get(),
+      )),
     }
   }
 
@@ -193,6 +204,17 @@ impl FileFetcher {
     self.download_log_level = level;
   }
 
+  /// Sets the max number of remote modules downloaded at the same time.
+  pub fn set_fetch_concurrency(&mut self, concurrency: NonZeroUsize) {
+    self.download_semaphore = Arc::new(Semaphore::new(concurrency.get()));
+  }
+
+  /// Sets the remote cache which is tried before downloading a remote module
+  /// and which downloaded remote modules are pushed to.
+  pub fn set_remote_cache(&mut self, remote_cache: Option<Arc<RemoteCache>>) {
+    self.remote_cache = remote_cache;
+  }
+
   /// Fetch cached remote file.
   ///
   /// This is a recursive operation if source file has redirections.
@@ -309,48 +331,49 @@ impl FileFetcher {
       return futures::future::err(custom_error(
         "NotCached",
         format!(
-          "Specifier not found in cache: \"{specifier}\", --cached-only is specified."
+          "Specifier not found in cache: \"{specifier}\", {} is specified.",
+          if self.http_client.is_offline() {
+            "--offline"
+          } else {
+            "--cached-only"
+          }
         ),
       ))
       .boxed();
     }
 
-    let mut maybe_progress_guard = None;
-    if let Some(pb) = self.progress_bar.as_ref() {
-      maybe_progress_guard = Some(pb.update(specifier.as_str()));
-    } else {
-      log::log!(
-        self.download_log_level,
-        "{} {}",
-        colors::green("Download"),
-        specifier
-      );
-    }
-
+    // the remote cache is only pulled from when the local cache would be used
+    let maybe_remote_cache = match cache_setting {
+      CacheSetting::Use | CacheSetting::RespectHeaders => {
+        self.remote_cache.clone()
+      }
+      _ => None,
+    };
     let maybe_etag = self
       .http_cache
       .cache_item_key(specifier)
       .ok()
       .and_then(|key| self.http_cache.read_metadata(&key).ok().flatten())
       .and_then(|metadata| metadata.headers.get("etag").cloned());
-    let maybe_auth_token = self.auth_tokens.get(specifier);
     let specifier = specifier.clone();
     let client = self.http_client.clone();
     let file_fetcher = self.clone();
     let cache_setting = cache_setting.clone();
-    // A single pass of fetch either yields code or yields a redirect, server
-    // error causes a single retry to avoid crashing hard on intermittent failures.
+    // A single pass of fetch either yields code or yields a redirect, request
+    // and server errors are retried with an exponential backoff to avoid
+    // crashing hard on intermittent failures.
 
     async fn handle_request_or_server_error(
-      retried: &mut bool,
+      retries: &mut u32,
+      max_retries: u32,
       specifier: &Url,
       err_str: String,
     ) -> Result<(), AnyError> {
-      // Retry once, and bail otherwise.
-      if !*retried {
-        *retried = true;
+      // Retry up to the max retries, and bail otherwise.
+      if *retries < max_retries {
+        *retries += 1;
         log::debug!("Import '{}' failed: {}. Retrying...", specifier, err_str);
-        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        tokio::time::sleep(http_util::download_retry_delay(*retries)).await;
         Ok(())
       } else {
         Err(generic_error(format!(
@@ -361,7 +384,66 @@ impl FileFetcher {
     }
 
     async move {
-      let mut retried = false;
+      let maybe_auth_token = client.auth_tokens().resolve(&specifier).await;
+      // modules fetched with a token are private, so they're never shared
+      // with the other users of the remote cache
+      let maybe_remote_cache =
+        maybe_remote_cache.filter(|_| maybe_auth_token.is_none());
+      let maybe_push_remote_cache = file_fetcher
+        .remote_cache
+        .as_ref()
+        .filter(|_| maybe_auth_token.is_none());
+      if let Some(remote_cache) = &maybe_remote_cache {
+        if let Some(module) = remote_cache.get_module(&specifier).await {
+          file_fetcher.http_cache.set(
+            &specifier,
+            module.headers.clone(),
+            &module.content,
+          )?;
+          if let Some(redirect_to) = module.headers.get("location") {
+            let redirect_url =
+              deno_core::resolve_import(redirect_to, specifier.as_str())?;
+            return file_fetcher
+              .fetch_remote(
+                &redirect_url,
+                permissions,
+                redirect_limit - 1,
+                maybe_accept,
+                &cache_setting,
+              )
+              .await;
+          }
+          return Ok(File {
+            specifier,
+            maybe_headers: Some(module.headers),
+            source: Arc::from(module.content),
+          });
+        }
+      }
+
+      // wait for other downloads instead of opening too many connections
+      let download_permit = file_fetcher
+        .download_semaphore
+        .clone()
+        .acquire_owned()
+        .await?;
+
+      let mut maybe_progress_guard = None;
+      if let Some(pb) = file_fetcher.progress_bar.as_ref() {
+        maybe_progress_guard = Some(pb.update(specifier.as_str()));
+      } else {
+        log::log!(
+          file_fetcher.download_log_level,
+          "{} {}",
+          colors::green("Download"),
+          specifier
+        );
+      }
+
+      let mut retries = 0;
+      // the body read before the connection dropped, which is resumed with a
+      // range request
+      let mut partial_body = PartialBody::default();
       let result = loop {
         let result = match fetch_once(
           &client,
@@ -371,6 +453,7 @@ impl FileFetcher {
             maybe_etag: maybe_etag.clone(),
             maybe_auth_token: maybe_auth_token.clone(),
             maybe_progress_guard: maybe_progress_guard.as_ref(),
+            maybe_partial_body: Some(&mut partial_body),
           },
         )
         .await?
@@ -380,7 +463,12 @@ impl FileFetcher {
             Ok(file)
           }
           FetchOnceResult::Redirect(redirect_url, headers) => {
+            if let Some(remote_cache) = maybe_push_remote_cache {
+              remote_cache.put_module(&specifier, &headers, &[]).await;
+            }
             file_fetcher.http_cache.set(&specifier, headers, &[])?;
+            // the redirected module waits for its own permit
+            drop(download_permit);
             file_fetcher
               .fetch_remote(
                 &redirect_url,
@@ -395,6 +483,9 @@ impl FileFetcher {
             file_fetcher
               .http_cache
               .set(&specifier, headers.clone(), &bytes)?;
+            if let Some(remote_cache) = maybe_push_remote_cache {
+              remote_cache.put_module(&specifier, &headers, &bytes).await;
+            }
             Ok(File {
               specifier,
               maybe_headers: Some(headers),
@@ -402,13 +493,19 @@ impl FileFetcher {
             })
           }
           FetchOnceResult::RequestError(err) => {
-            handle_request_or_server_error(&mut retried, &specifier, err)
-              .await?;
+            handle_request_or_server_error(
+              &mut retries,
+              client.download_retries(),
+              &specifier,
+              err,
+            )
+            .await?;
             continue;
           }
           FetchOnceResult::ServerError(status) => {
             handle_request_or_server_error(
-              &mut retried,
+              &mut retries,
+              client.download_retries(),
               &specifier,
               status.to_string(),
             )
@@ -510,13 +607,20 @@ impl FileFetcher {
         format!("A remote specifier was requested: \"{specifier}\", but --no-remote is specified."),
       ))
     } else {
+      let cache_setting = if self.http_client.is_offline() {
+        // use the cache regardless of the setting instead of failing when
+        // accessing the network
+        &CacheSetting::Only
+      } else {
+        options.maybe_cache_setting.unwrap_or(&self.cache_setting)
+      };
       self
         .fetch_remote(
           specifier,
           options.permissions,
           10,
           options.maybe_accept.map(String::from),
-          options.maybe_cache_setting.unwrap_or(&self.cache_setting),
+          cache_setting,
         )
         .await
     }
@@ -562,6 +666,9 @@ struct FetchOnceArgs<'a> {
   pub maybe_etag: Option<String>,
   pub maybe_auth_token: Option<AuthToken>,
   pub maybe_progress_guard: Option<&'a UpdateGuard>,
+  /// The body read by a previous pass, which is continued with a range
+  /// request when it can be resumed.
+  pub maybe_partial_body: Option<&'a mut PartialBody>,
 }
 
 /// Asynchronously fetches the given HTTP URL one pass only.
@@ -573,7 +680,11 @@ async fn fetch_once<'a>(
   http_client: &HttpClient,
   args: FetchOnceArgs<'a>,
 ) -> Result<FetchOnceResult, AnyError> {
+  http_client.prepare_proxy(&args.url).await;
   let mut request = http_client.get_no_redirect(args.url.clone())?;
+  let mut empty_body = PartialBody::default();
+  let partial_body = args.maybe_partial_body.unwrap_or(&mut empty_body);
+  let maybe_range = partial_body.range_headers();
 
   if let Some(etag) = args.maybe_etag {
     let if_none_match_val = HeaderValue::from_str(&etag)?;
@@ -587,6 +698,9 @@ async fn fetch_once<'a>(
     let accepts_val = HeaderValue::from_str(&accept)?;
     request = request.header(ACCEPT, accepts_val);
   }
+  if let Some((range, if_range)) = &maybe_range {
+    request = request.header(RANGE, range).header(IF_RANGE, if_range);
+  }
   let response = match request.send().await {
     Ok(resp) => resp,
     Err(err) => {
@@ -634,6 +748,25 @@ async fn fetch_once<'a>(
     return Ok(FetchOnceResult::ServerError(status));
   }
 
+  if status == StatusCode::RANGE_NOT_SATISFIABLE && maybe_range.is_some() {
+    // the module changed since the previous pass, so start over
+    partial_body.clear();
+    return Ok(FetchOnceResult::RequestError(status.to_string()));
+  }
+
+  if status.is_success() {
+    if let Err(err) = partial_body.start_response(&response) {
+      return Ok(FetchOnceResult::RequestError(err.to_string()));
+    }
+    if status == StatusCode::PARTIAL_CONTENT {
+      // the headers describe the range instead of the whole module
+      result_headers.remove("content-range");
+      result_headers.remove("content-length");
+    }
+  } else {
+    partial_body.clear();
+  }
+
   if status.is_client_error() {
     let err = if response.status() == StatusCode::NOT_FOUND {
       custom_error(
@@ -650,13 +783,20 @@ async fn fetch_once<'a>(
     return Err(err);
   }
 
-  let body = http_util::get_response_body_with_progress(
+  if let Err(err) = http_util::read_response_body_into(
     response,
+    partial_body,
     args.maybe_progress_guard,
   )
-  .await?;
+  .await
+  {
+    return Ok(FetchOnceResult::RequestError(err.to_string()));
+  }
 
-  Ok(FetchOnceResult::Code(body, result_headers))
+  Ok(FetchOnceResult::Code(
+    partial_body.take_data(),
+    result_headers,
+  ))
 }
 
 #[cfg(test)]
@@ -1511,6 +1651,7 @@ mod tests {
         maybe_etag: None,
         maybe_auth_token: None,
         maybe_progress_guard: None,
+        maybe_partial_body: None,
       },
     )
     .await;
@@ -1539,6 +1680,7 @@ mod tests {
         maybe_etag: None,
         maybe_auth_token: None,
         maybe_progress_guard: None,
+        maybe_partial_body: None,
       },
     )
     .await;
@@ -1568,6 +1710,7 @@ mod tests {
         maybe_etag: None,
         maybe_auth_token: None,
         maybe_progress_guard: None,
+        maybe_partial_body: None,
       },
     )
     .await;
@@ -1591,6 +1734,7 @@ mod tests {
         maybe_etag: Some("33a64df551425fcc55e".to_string()),
         maybe_auth_token: None,
         maybe_progress_guard: None,
+        maybe_partial_body: None,
       },
     )
     .await;
@@ -1612,6 +1756,7 @@ mod tests {
         maybe_etag: None,
         maybe_auth_token: None,
         maybe_progress_guard: None,
+        maybe_partial_body: None,
       },
     )
     .await;
@@ -1643,6 +1788,7 @@ mod tests {
         maybe_etag: None,
         maybe_auth_token: None,
         maybe_progress_guard: None,
+        maybe_partial_body: None,
       },
     )
     .await;
@@ -1670,6 +1816,7 @@ mod tests {
         maybe_etag: None,
         maybe_auth_token: None,
         maybe_progress_guard: None,
+        maybe_partial_body: None,
       },
     )
     .await;
@@ -1707,6 +1854,7 @@ mod tests {
         maybe_etag: None,
         maybe_auth_token: None,
         maybe_progress_guard: None,
+        maybe_partial_body: None,
       },
     )
     .await;
@@ -1756,6 +1904,7 @@ mod tests {
           maybe_etag: None,
           maybe_auth_token: None,
           maybe_progress_guard: None,
+          maybe_partial_body: None,
         },
       )
       .await;
@@ -1820,6 +1969,7 @@ mod tests {
         maybe_etag: None,
         maybe_auth_token: None,
         maybe_progress_guard: None,
+        maybe_partial_body: None,
       },
     )
     .await;
@@ -1875,6 +2025,7 @@ mod tests {
         maybe_etag: None,
         maybe_auth_token: None,
         maybe_progress_guard: None,
+        maybe_partial_body: None,
       },
     )
     .await;
@@ -1918,6 +2069,7 @@ mod tests {
         maybe_etag: None,
         maybe_auth_token: None,
         maybe_progress_guard: None,
+        maybe_partial_body: None,
       },
     )
     .await;
@@ -1942,6 +2094,7 @@ mod tests {
         maybe_etag: Some("33a64df551425fcc55e".to_string()),
         maybe_auth_token: None,
         maybe_progress_guard: None,
+        maybe_partial_body: None,
       },
     )
     .await;
@@ -1978,6 +2131,7 @@ mod tests {
         maybe_etag: None,
         maybe_auth_token: None,
         maybe_progress_guard: None,
+        maybe_partial_body: None,
       },
     )
     .await;
@@ -2009,6 +2163,7 @@ mod tests {
         maybe_etag: None,
         maybe_auth_token: None,
         maybe_progress_guard: None,
+        maybe_partial_body: None,
       },
     )
     .await;
@@ -2032,6 +2187,7 @@ mod tests {
         maybe_etag: None,
         maybe_auth_token: None,
         maybe_progress_guard: None,
+        maybe_partial_body: None,
       },
     )
     .await;
@@ -2057,6 +2213,7 @@ mod tests {
         maybe_etag: None,
         maybe_auth_token: None,
         maybe_progress_guard: None,
+        maybe_partial_body: None,
       },
     )
     .await;
@@ -2092,4 +2249,70 @@ mod tests {
       .headers
       .remove("location")
   }
+
+  #[tokio::test]
+  async fn test_fetch_concurrency_limits_downloads() {
+    use std::io::Read;
+    use std::io::Write;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+
+    // a server which tracks how many requests it handles at the same time
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let max_in_flight = Arc::new(AtomicUsize::new(0));
+    std::thread::spawn({
+      let in_flight = in_flight.clone();
+      let max_in_flight = max_in_flight.clone();
+      move || {
+        for stream in listener.incoming() {
+          let mut stream = stream.unwrap();
+          let in_flight = in_flight.clone();
+          let max_in_flight = max_in_flight.clone();
+          std::thread::spawn(move || {
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+              let read = stream.read(&mut buf).unwrap();
+              if read == 0 {
+                return;
+              }
+              request.extend_from_slice(&buf[..read]);
+            }
+            let count = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            max_in_flight.fetch_max(count, Ordering::SeqCst);
+            std::thread::sleep(std::time::Duration::from_millis(100));
+            in_flight.fetch_sub(1, Ordering::SeqCst);
+            let body = "export {};";
+            write!(
+              stream,
+              "HTTP/1.1 200 OK\r\ncontent-type: application/javascript\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+              body.len(),
+              body
+            )
+            .unwrap();
+          });
+        }
+      }
+    });
+
+    let (mut file_fetcher, _temp_dir) = setup(CacheSetting::ReloadAll, None);
+    file_fetcher.set_fetch_concurrency(NonZeroUsize::new(2).unwrap());
+    let specifiers = (0..6)
+      .map(|i| {
+        ModuleSpecifier::parse(&format!("http://127.0.0.1:{port}/mod{i}.js"))
+          .unwrap()
+      })
+      .collect::<Vec<_>>();
+    let results =
+      futures::future::join_all(specifiers.iter().map(|specifier| {
+        file_fetcher.fetch(specifier, PermissionsContainer::allow_all())
+      }))
+      .await;
+    for result in results {
+      assert_eq!(&*result.unwrap().source, "export {};".as_bytes());
+    }
+    assert_eq!(max_in_flight.load(Ordering::SeqCst), 2);
+  }
 }
@@ -1,10 +1,12 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
 use crate::args::CliOptions;
+use crate::args::GraphBudget;
 use crate::args::Lockfile;
 use crate::args::TsTypeLib;
 use crate::cache;
 use crate::cache::GlobalHttpCache;
+use crate::cache::HttpCacheAccessCache;
 use crate::cache::ModuleInfoCache;
 use crate::cache::ParsedSourceCache;
 use crate::colors;
@@ -15,6 +17,7 @@ use crate::resolver::CliGraphResolver;
 use crate::resolver::SloppyImportsResolver;
 use crate::tools::check;
 use crate::tools::check::TypeChecker;
+use crate::util::display::human_size;
 use crate::util::file_watcher::WatcherCommunicator;
 use crate::util::fs::canonicalize_path;
 use crate::util::path::specifier_to_file_path;
@@ -190,6 +193,100 @@ pub fn graph_lock_or_exit(graph: &ModuleGraph, lockfile: &mut Lockfile) {
   }
 }
 
+/// Checks the graph against the `"graph": { "budget": { ... } }` of the
+/// configuration file, erroring with every way the budget is exceeded.
+pub fn check_graph_budget(
+  graph: &ModuleGraph,
+  budget: &GraphBudget,
+  npm_resolver: &dyn CliNpmResolver,
+) -> Result<(), AnyError> {
+  let mut violations = Vec::new();
+  // npm packages are sized by their folders along with their dependencies
+  let mut total_bytes = match npm_resolver.as_managed() {
+    Some(npm_resolver) if !graph.npm_packages.is_empty() => {
+      let snapshot = npm_resolver.snapshot();
+      let package_ids = graph
+        .modules()
+        .filter_map(|module| module.npm())
+        .filter_map(|module| {
+          snapshot
+            .resolve_package_from_deno_module(module.nv_reference.nv())
+            .ok()
+            .map(|package| package.id.clone())
+        })
+        .collect();
+      npm_resolver.packages_size_with_deps(
+        &snapshot,
+        package_ids,
+        &mut HashSet::new(),
+      )
+    }
+    _ => 0,
+  };
+  let mut dependency_count = 0;
+  for module in graph.modules() {
+    total_bytes += match module {
+      Module::Js(module) => module.source.len() as u64,
+      Module::Json(module) => module.source.len() as u64,
+      Module::Node(_) | Module::Npm(_) | Module::External(_) => 0,
+    };
+    if !matches!(module, Module::Node(_))
+      && !graph.roots.contains(module.specifier())
+    {
+      dependency_count += 1;
+    }
+    let Module::Js(module) = module else {
+      continue;
+    };
+    for dependency in module.dependencies.values() {
+      let maybe_code = dependency.maybe_code.ok();
+      let maybe_type = dependency.maybe_type.ok().filter(|t| {
+        maybe_code
+          .map(|c| c.specifier != t.specifier)
+          .unwrap_or(true)
+      });
+      for resolved in maybe_code.into_iter().chain(maybe_type) {
+        if let Some(pattern) = budget.banned_pattern(&resolved.specifier) {
+          violations.push(format!(
+            "\"{}\" is banned by \"{}\"\n      at {}",
+            resolved.specifier,
+            pattern,
+            format_range_with_colors(&resolved.range)
+          ));
+        }
+      }
+    }
+  }
+  if let Some(max_total_bytes) = budget.max_total_bytes {
+    if total_bytes > max_total_bytes {
+      violations.push(format!(
+        "The total size of {} is over the maximum of {} (maxTotalBytes)",
+        human_size(total_bytes as f64),
+        human_size(max_total_bytes as f64)
+      ));
+    }
+  }
+  if let Some(max_dependencies) = budget.max_dependencies {
+    if dependency_count > max_dependencies {
+      violations.push(format!(
+        "The {} dependencies are more than the maximum of {} (maxDependencies)",
+        dependency_count, max_dependencies
+      ));
+    }
+  }
+  if violations.is_empty() {
+    return Ok(());
+  }
+  bail!(
+    "The module graph exceeds the budget of the configuration file:\n{}",
+    violations
+      .iter()
+      .map(|v| format!("  - {}", v))
+      .collect::<Vec<_>>()
+      .join("\n")
+  )
+}
+
 pub struct CreateGraphOptions<'a> {
   pub graph_kind: GraphKind,
   pub roots: Vec<ModuleSpecifier>,
@@ -198,6 +295,9 @@ pub struct CreateGraphOptions<'a> {
   pub workspace_fast_check: bool,
   /// Specify `None` to use the default CLI loader.
   pub loader: Option<&'a mut dyn Loader>,
+  /// Specify `None` to use the members of the workspace or the package of
+  /// the configuration file.
+  pub workspace_members: Option<Vec<deno_graph::WorkspaceMember>>,
 }
 
 pub struct ModuleGraphBuilder {
@@ -212,6 +312,7 @@ pub struct ModuleGraphBuilder {
   emit_cache: cache::EmitCache,
   file_fetcher: Arc<FileFetcher>,
   global_http_cache: Arc<GlobalHttpCache>,
+  http_cache_access_cache: Arc<HttpCacheAccessCache>,
   type_checker: Arc<TypeChecker>,
 }
 
@@ -229,6 +330,7 @@ impl ModuleGraphBuilder {
     emit_cache: cache::EmitCache,
     file_fetcher: Arc<FileFetcher>,
     global_http_cache: Arc<GlobalHttpCache>,
+    http_cache_access_cache: Arc<HttpCacheAccessCache>,
     type_checker: Arc<TypeChecker>,
   ) -> Self {
     Self {
@@ -243,6 +345,7 @@ impl ModuleGraphBuilder {
       emit_cache,
       file_fetcher,
       global_http_cache,
+      http_cache_access_cache,
       type_checker,
     }
   }
@@ -270,6 +373,7 @@ impl ModuleGraphBuilder {
         roots,
         loader: Some(loader),
         workspace_fast_check: false,
+        workspace_members: None,
       })
       .await
   }
@@ -307,6 +411,11 @@ impl ModuleGraphBuilder {
       None => MutLoaderRef::Owned(self.create_graph_loader()),
     };
 
+    let workspace_members = match options.workspace_members {
+      Some(workspace_members) => workspace_members,
+      None => self.get_deno_graph_workspace_members()?,
+    };
+
     let mut graph = ModuleGraph::new(options.graph_kind);
     self
       .build_graph_with_npm_resolution(
@@ -323,7 +432,7 @@ impl ModuleGraphBuilder {
           module_parser: Some(&parser),
           reporter: maybe_file_watcher_reporter,
           workspace_fast_check: options.workspace_fast_check,
-          workspace_members: self.get_deno_graph_workspace_members()?,
+          workspace_members,
         },
       )
       .await?;
@@ -540,6 +649,7 @@ impl ModuleGraphBuilder {
       self.file_fetcher.clone(),
       self.options.resolve_file_header_overrides(),
       self.global_http_cache.clone(),
+      self.http_cache_access_cache.clone(),
       self.npm_resolver.clone(),
       self.module_info_cache.clone(),
       permissions,
@@ -828,7 +938,7 @@ pub fn workspace_config_to_workspace_members(
     .collect()
 }
 
-fn config_to_workspace_member(
+pub fn config_to_workspace_member(
   config: &ConfigFile,
 ) -> Result<deno_graph::WorkspaceMember, AnyError> {
   let nv = deno_semver::package::PackageNv {
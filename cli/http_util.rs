@@ -1,4 +1,8 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+use crate::auth_tokens::AuthToken;
+use crate::auth_tokens::AuthTokens;
+use crate::proxy::HostPattern;
+use crate::proxy::ProxyRules;
 use crate::util::progress_bar::UpdateGuard;
 use crate::version::get_user_agent;
 
@@ -13,11 +17,23 @@ use deno_core::futures::StreamExt;
 use deno_core::url::Url;
 use deno_runtime::deno_fetch::create_http_client;
 use deno_runtime::deno_fetch::reqwest;
+use deno_runtime::deno_fetch::reqwest::header::HeaderMap;
+use deno_runtime::deno_fetch::reqwest::header::AUTHORIZATION;
+use deno_runtime::deno_fetch::reqwest::header::CONTENT_ENCODING;
+use deno_runtime::deno_fetch::reqwest::header::CONTENT_LENGTH;
+use deno_runtime::deno_fetch::reqwest::header::CONTENT_RANGE;
+use deno_runtime::deno_fetch::reqwest::header::ETAG;
+use deno_runtime::deno_fetch::reqwest::header::IF_RANGE;
+use deno_runtime::deno_fetch::reqwest::header::LAST_MODIFIED;
 use deno_runtime::deno_fetch::reqwest::header::LOCATION;
+use deno_runtime::deno_fetch::reqwest::header::RANGE;
 use deno_runtime::deno_fetch::reqwest::Response;
+use deno_runtime::deno_fetch::reqwest::StatusCode;
 use deno_runtime::deno_fetch::CreateHttpClientOptions;
+use deno_runtime::deno_fetch::ProxyResolver;
 use deno_runtime::deno_tls::RootCertStoreProvider;
 use std::collections::HashMap;
+use std::env;
 use std::sync::Arc;
 use std::time::Duration;
 use std::time::SystemTime;
@@ -219,16 +235,70 @@ impl CacheSemantics {
   }
 }
 
+/// The number of times a failed download is retried by default.
+pub const DEFAULT_DOWNLOAD_RETRIES: u32 = 2;
+
+/// The time to wait before the given retry of a download, which
+/// doubles with every retry.
+pub fn download_retry_delay(retry: u32) -> Duration {
+  let ms = 100u64.saturating_mul(2u64.saturating_pow(retry.saturating_sub(1)));
+  Duration::from_millis(std::cmp::min(ms, 5_000))
+}
+
+/// An error of an attempt to download something.
+enum DownloadAttemptError {
+  /// The connection dropped or the server errored, so it's worth trying
+  /// again.
+  Retryable(AnyError),
+  Fatal(AnyError),
+}
+
+impl From<reqwest::Error> for DownloadAttemptError {
+  fn from(err: reqwest::Error) -> Self {
+    if err.is_connect() || err.is_timeout() || err.is_body() || err.is_request()
+    {
+      Self::Retryable(err.into())
+    } else {
+      Self::Fatal(err.into())
+    }
+  }
+}
+
+/// A client certificate which is presented to the hosts matching the pattern,
+/// for example to an internal registry protected by mutual TLS.
+#[derive(Debug, Clone)]
+pub struct ClientCert {
+  pub host: HostPattern,
+  pub cert_chain: String,
+  pub private_key: String,
+}
+
 pub struct HttpClient {
   options: CreateHttpClientOptions,
   root_cert_store_provider: Option<Arc<dyn RootCertStoreProvider>>,
+  offline: bool,
+  download_retries: u32,
+  auth_tokens: AuthTokens,
+  maybe_proxy_rules: Option<Arc<ProxyRules>>,
   cell: once_cell::sync::OnceCell<reqwest::Client>,
+  /// The client certificates along with the clients which present them.
+  client_certs: Vec<(ClientCert, once_cell::sync::OnceCell<reqwest::Client>)>,
 }
 
 impl std::fmt::Debug for HttpClient {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     f.debug_struct("HttpClient")
       .field("options", &self.options)
+      .field("offline", &self.offline)
+      .field("download_retries", &self.download_retries)
+      .field(
+        "client_certs",
+        &self
+          .client_certs
+          .iter()
+          .map(|(cert, _)| &cert.host)
+          .collect::<Vec<_>>(),
+      )
       .finish()
   }
 }
@@ -244,7 +314,76 @@ impl HttpClient {
         ..Default::default()
       },
       root_cert_store_provider,
+      offline: false,
+      download_retries: DEFAULT_DOWNLOAD_RETRIES,
+      auth_tokens: AuthTokens::new(env::var("DENO_AUTH_TOKENS").ok()),
+      maybe_proxy_rules: None,
       cell: Default::default(),
+      client_certs: Vec::new(),
+    }
+  }
+
+  /// Makes every request fail immediately instead of accessing the network.
+  pub fn with_offline(mut self, offline: bool) -> Self {
+    self.offline = offline;
+    self
+  }
+
+  pub fn is_offline(&self) -> bool {
+    self.offline
+  }
+
+  /// Sets the number of times a download is retried after the connection
+  /// dropped or the server errored.
+  pub fn with_download_retries(mut self, download_retries: u32) -> Self {
+    self.download_retries = download_retries;
+    self
+  }
+
+  pub fn download_retries(&self) -> u32 {
+    self.download_retries
+  }
+
+  /// Sets the tokens which authorize the downloads of private resources,
+  /// which default to the ones of the `DENO_AUTH_TOKENS` environment variable.
+  pub fn with_auth_tokens(mut self, auth_tokens: AuthTokens) -> Self {
+    self.auth_tokens = auth_tokens;
+    self
+  }
+
+  pub fn auth_tokens(&self) -> &AuthTokens {
+    &self.auth_tokens
+  }
+
+  /// Presents the client certificates to the hosts they're configured for.
+  pub fn with_client_certs(mut self, client_certs: Vec<ClientCert>) -> Self {
+    self.client_certs = client_certs
+      .into_iter()
+      .map(|cert| (cert, Default::default()))
+      .collect();
+    self
+  }
+
+  /// Resolves the proxies of the requests with the rules instead of the
+  /// proxies of the environment.
+  pub fn with_proxy_rules(
+    mut self,
+    maybe_proxy_rules: Option<Arc<ProxyRules>>,
+  ) -> Self {
+    self.options.proxy_resolver = maybe_proxy_rules
+      .clone()
+      .map(|rules| ProxyResolver::new(move |url| rules.resolve(url)));
+    self.maybe_proxy_rules = maybe_proxy_rules;
+    self
+  }
+
+  /// Evaluates the proxy auto-config file for the URL before it's requested,
+  /// because the proxies are resolved synchronously while connecting.
+  pub async fn prepare_proxy(&self, url: &Url) {
+    if let Some(proxy_rules) = &self.maybe_proxy_rules {
+      proxy_rules
+        .prepare(url, self.root_cert_store_provider.as_deref())
+        .await;
     }
   }
 
@@ -253,25 +392,74 @@ impl HttpClient {
     let result = Self {
       options: Default::default(),
       root_cert_store_provider: Default::default(),
+      offline: false,
+      download_retries: DEFAULT_DOWNLOAD_RETRIES,
+      auth_tokens: AuthTokens::new(env::var("DENO_AUTH_TOKENS").ok()),
+      maybe_proxy_rules: None,
       cell: Default::default(),
+      client_certs: Vec::new(),
     };
     result.cell.set(client).unwrap();
     result
   }
 
   pub(crate) fn client(&self) -> Result<&reqwest::Client, AnyError> {
-    self.cell.get_or_try_init(|| {
-      create_http_client(
-        get_user_agent(),
-        CreateHttpClientOptions {
-          root_cert_store: match &self.root_cert_store_provider {
-            Some(provider) => Some(provider.get_or_try_init()?.clone()),
-            None => None,
-          },
-          ..self.options.clone()
+    self.ensure_online()?;
+    self.cell.get_or_try_init(|| self.create_client(None))
+  }
+
+  /// Gets the client for the URL, which presents the client certificate of
+  /// its host if there's one.
+  pub(crate) fn client_for_url(
+    &self,
+    url: &Url,
+  ) -> Result<&reqwest::Client, AnyError> {
+    let maybe_client_cert = url.host_str().and_then(|host| {
+      let port = url.port_or_known_default();
+      self
+        .client_certs
+        .iter()
+        .find(|(cert, _)| cert.host.matches(host, port))
+    });
+    match maybe_client_cert {
+      Some((cert, cell)) => {
+        self.ensure_online()?;
+        cell.get_or_try_init(|| self.create_client(Some(cert)))
+      }
+      None => self.client(),
+    }
+  }
+
+  fn ensure_online(&self) -> Result<(), AnyError> {
+    if self.offline {
+      return Err(custom_error(
+        "Http",
+        "Network access is disabled, because --offline is specified.",
+      ));
+    }
+    Ok(())
+  }
+
+  fn create_client(
+    &self,
+    maybe_client_cert: Option<&ClientCert>,
+  ) -> Result<reqwest::Client, AnyError> {
+    create_http_client(
+      get_user_agent(),
+      CreateHttpClientOptions {
+        root_cert_store: match &self.root_cert_store_provider {
+          Some(provider) => Some(provider.get_or_try_init()?.clone()),
+          None => None,
         },
-      )
-    })
+        client_cert_chain_and_key: match maybe_client_cert {
+          Some(cert) => {
+            Some((cert.cert_chain.clone(), cert.private_key.clone()))
+          }
+          None => self.options.client_cert_chain_and_key.clone(),
+        },
+        ..self.options.clone()
+      },
+    )
   }
 
   /// Do a GET request without following redirects.
@@ -279,7 +467,8 @@ impl HttpClient {
     &self,
     url: U,
   ) -> Result<reqwest::RequestBuilder, AnyError> {
-    Ok(self.client()?.get(url))
+    let url = url.into_url()?;
+    Ok(self.client_for_url(&url)?.get(url))
   }
 
   pub async fn download_text<U: reqwest::IntoUrl>(
@@ -314,40 +503,131 @@ impl HttpClient {
     url: U,
     progress_guard: Option<&UpdateGuard>,
   ) -> Result<Option<Vec<u8>>, AnyError> {
-    let response = self.get_redirected_response(url).await?;
+    let url = url.into_url()?;
+    let mut partial_body = PartialBody::default();
+    let mut retries = 0;
+    loop {
+      match self
+        .download_attempt(&url, &mut partial_body, progress_guard)
+        .await
+      {
+        Ok(true) => return Ok(Some(partial_body.take_data())),
+        Ok(false) => return Ok(None),
+        Err(DownloadAttemptError::Retryable(err))
+          if retries < self.download_retries =>
+        {
+          retries += 1;
+          log::debug!(
+            "Download of '{}' failed after {} bytes: {:#}. Retrying...",
+            url,
+            partial_body.len(),
+            err
+          );
+          tokio::time::sleep(download_retry_delay(retries)).await;
+        }
+        Err(
+          DownloadAttemptError::Retryable(err)
+          | DownloadAttemptError::Fatal(err),
+        ) => return Err(err),
+      }
+    }
+  }
 
-    if response.status() == 404 {
-      return Ok(None);
-    } else if !response.status().is_success() {
-      let status = response.status();
+  /// Downloads the body of the URL into the partial body, continuing after
+  /// the data of a previous attempt when it can be resumed. Returns `false`
+  /// when the URL wasn't found.
+  async fn download_attempt(
+    &self,
+    url: &Url,
+    partial_body: &mut PartialBody,
+    progress_guard: Option<&UpdateGuard>,
+  ) -> Result<bool, DownloadAttemptError> {
+    let response = self
+      .get_redirected_response_inner(url.clone(), partial_body.range_headers())
+      .await?;
+
+    let status = response.status();
+    if status == StatusCode::NOT_FOUND {
+      return Ok(false);
+    } else if status == StatusCode::RANGE_NOT_SATISFIABLE {
+      // the resource changed, so start over
+      partial_body.clear();
+      return Err(DownloadAttemptError::Retryable(generic_error(
+        "The resumed download is out of range.",
+      )));
+    } else if status.is_success() {
+      partial_body
+        .start_response(&response)
+        .map_err(DownloadAttemptError::Retryable)?;
+    } else {
       let maybe_response_text = response.text().await.ok();
-      bail!(
+      let err = generic_error(format!(
         "Bad response: {:?}{}",
         status,
         match maybe_response_text {
           Some(text) => format!("\n\n{text}"),
           None => String::new(),
         }
-      );
+      ));
+      return Err(if status.is_server_error() {
+        DownloadAttemptError::Retryable(err)
+      } else {
+        DownloadAttemptError::Fatal(err)
+      });
     }
 
-    get_response_body_with_progress(response, progress_guard)
-      .await
-      .map(Some)
+    read_response_body_into(response, partial_body, progress_guard).await?;
+    Ok(true)
   }
 
   pub async fn get_redirected_response<U: reqwest::IntoUrl>(
     &self,
     url: U,
   ) -> Result<Response, AnyError> {
-    let mut url = url.into_url()?;
-    let mut response = self.get_no_redirect(url.clone())?.send().await?;
+    match self
+      .get_redirected_response_inner(url.into_url()?, None)
+      .await
+    {
+      Ok(response) => Ok(response),
+      Err(
+        DownloadAttemptError::Retryable(err) | DownloadAttemptError::Fatal(err),
+      ) => Err(err),
+    }
+  }
+
+  /// Follows the redirects of the URL, sending the `Range` and `If-Range`
+  /// headers of `maybe_range` when a download is resumed. Every request is
+  /// authorized with the auth token of its own URL.
+  async fn get_redirected_response_inner(
+    &self,
+    mut url: Url,
+    maybe_range: Option<(String, String)>,
+  ) -> Result<Response, DownloadAttemptError> {
+    let send = |url: Url,
+                maybe_auth_token: Option<AuthToken>|
+     -> Result<_, DownloadAttemptError> {
+      let mut request = self
+        .get_no_redirect(url)
+        .map_err(DownloadAttemptError::Fatal)?;
+      if let Some(auth_token) = maybe_auth_token {
+        request = request.header(AUTHORIZATION, auth_token.to_string());
+      }
+      if let Some((range, if_range)) = &maybe_range {
+        request = request.header(RANGE, range).header(IF_RANGE, if_range);
+      }
+      Ok(request.send())
+    };
+    self.prepare_proxy(&url).await;
+    let maybe_auth_token = self.auth_tokens.resolve(&url).await;
+    let mut response = send(url.clone(), maybe_auth_token)?.await?;
     let status = response.status();
     if status.is_redirection() {
       for _ in 0..5 {
-        let new_url = resolve_redirect_from_response(&url, &response)?;
-        let new_response =
-          self.get_no_redirect(new_url.clone())?.send().await?;
+        let new_url = resolve_redirect_from_response(&url, &response)
+          .map_err(DownloadAttemptError::Fatal)?;
+        self.prepare_proxy(&new_url).await;
+        let maybe_auth_token = self.auth_tokens.resolve(&new_url).await;
+        let new_response = send(new_url.clone(), maybe_auth_token)?.await?;
         let status = new_response.status();
         if status.is_redirection() {
           response = new_response;
@@ -356,34 +636,126 @@ impl HttpClient {
           return Ok(new_response);
         }
       }
-      Err(custom_error("Http", "Too many redirects."))
+      Err(DownloadAttemptError::Fatal(custom_error(
+        "Http",
+        "Too many redirects.",
+      )))
     } else {
       Ok(response)
     }
   }
 }
 
-pub async fn get_response_body_with_progress(
+/// The body of a download which was read before the connection dropped, so
+/// the download can be resumed with a range request.
+///
+/// Only bodies without a `Content-Encoding` are resumed, because the HTTP
+/// client decodes the others, so their length isn't an offset of the
+/// resource. The `ETag` or `Last-Modified` of the response is sent as
+/// `If-Range`, so a resource which changed is downloaded from the start.
+#[derive(Debug, Default)]
+pub struct PartialBody {
+  data: Vec<u8>,
+  /// The `If-Range` of the resumed request, which is `None` when the body
+  /// can't be resumed.
+  maybe_validator: Option<String>,
+}
+
+impl PartialBody {
+  pub fn len(&self) -> usize {
+    self.data.len()
+  }
+
+  pub fn take_data(&mut self) -> Vec<u8> {
+    self.maybe_validator = None;
+    std::mem::take(&mut self.data)
+  }
+
+  pub fn clear(&mut self) {
+    self.data.clear();
+    self.maybe_validator = None;
+  }
+
+  /// The `Range` and `If-Range` headers of the request which resumes the
+  /// download, which is `None` when it starts from the beginning.
+  pub fn range_headers(&self) -> Option<(String, String)> {
+    if self.data.is_empty() {
+      return None;
+    }
+    let validator = self.maybe_validator.clone()?;
+    Some((format!("bytes={}-", self.data.len()), validator))
+  }
+
+  /// Prepares the body for the successful response, which either continues
+  /// the body or replaces it when the response is complete, for example when
+  /// the server ignored the range or the resource changed.
+  pub fn start_response(
+    &mut self,
+    response: &Response,
+  ) -> Result<(), AnyError> {
+    if response.status() != StatusCode::PARTIAL_CONTENT {
+      self.data.clear();
+      self.maybe_validator = resume_validator(response.headers());
+      return Ok(());
+    }
+    let expected_range = format!("bytes {}-", self.data.len());
+    let is_expected_range = self.range_headers().is_some()
+      && response
+        .headers()
+        .get(CONTENT_RANGE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.starts_with(&expected_range))
+        .unwrap_or(false);
+    if !is_expected_range {
+      self.clear();
+      bail!("Unexpected content range of the resumed download.");
+    }
+    Ok(())
+  }
+}
+
+/// Gets the validator of a response which can be resumed. The HTTP client
+/// removes the `Content-Encoding` and `Content-Length` of the responses it
+/// decodes, so both are checked.
+fn resume_validator(headers: &HeaderMap) -> Option<String> {
+  if headers.contains_key(CONTENT_ENCODING)
+    || !headers.contains_key(CONTENT_LENGTH)
+  {
+    return None;
+  }
+  let header_str = |name| headers.get(name).and_then(|v| v.to_str().ok());
+  header_str(ETAG)
+    // weak entity tags can't be used with If-Range
+    .filter(|etag| !etag.starts_with("W/"))
+    .or_else(|| header_str(LAST_MODIFIED))
+    .map(ToString::to_string)
+}
+
+/// Appends the body of the response to the partial body, which keeps what
+/// was read before the connection dropped so the download can be resumed.
+pub async fn read_response_body_into(
   response: reqwest::Response,
+  partial_body: &mut PartialBody,
   progress_guard: Option<&UpdateGuard>,
-) -> Result<Vec<u8>, AnyError> {
-  if let Some(progress_guard) = progress_guard {
-    if let Some(total_size) = response.content_length() {
-      progress_guard.set_total_size(total_size);
-      let mut current_size = 0;
-      let mut data = Vec::with_capacity(total_size as usize);
-      let mut stream = response.bytes_stream();
-      while let Some(item) = stream.next().await {
-        let bytes = item?;
-        current_size += bytes.len() as u64;
-        progress_guard.set_position(current_size);
-        data.extend(bytes.into_iter());
-      }
-      return Ok(data);
+) -> Result<(), reqwest::Error> {
+  let data = &mut partial_body.data;
+  let initial_size = data.len() as u64;
+  if let Some(content_length) = response.content_length() {
+    data.reserve(content_length as usize);
+    if let Some(progress_guard) = progress_guard {
+      progress_guard.set_total_size(initial_size + content_length);
+      progress_guard.set_position(initial_size);
+    }
+  }
+  let mut stream = response.bytes_stream();
+  while let Some(item) = stream.next().await {
+    let bytes = item?;
+    data.extend_from_slice(&bytes);
+    if let Some(progress_guard) = progress_guard {
+      progress_guard.set_position(data.len() as u64);
     }
   }
-  let bytes = response.bytes().await?;
-  Ok(bytes.into())
+  Ok(())
 }
 
 #[cfg(test)]
@@ -411,6 +783,70 @@ mod test {
     assert_eq!(err.to_string(), "Too many redirects.");
   }
 
+  #[test]
+  fn test_resume_validator() {
+    let headers = |pairs: &[(&str, &str)]| {
+      let mut headers = HeaderMap::new();
+      for (name, value) in pairs {
+        headers.insert(
+          reqwest::header::HeaderName::from_bytes(name.as_bytes()).unwrap(),
+          value.parse().unwrap(),
+        );
+      }
+      headers
+    };
+    assert_eq!(
+      resume_validator(&headers(&[
+        ("content-length", "10"),
+        ("etag", "\"abc\""),
+        ("last-modified", "Wed, 21 Oct 2015 07:28:00 GMT"),
+      ])),
+      Some("\"abc\"".to_string())
+    );
+    assert_eq!(
+      resume_validator(&headers(&[
+        ("content-length", "10"),
+        ("etag", "W/\"abc\""),
+        ("last-modified", "Wed, 21 Oct 2015 07:28:00 GMT"),
+      ])),
+      Some("Wed, 21 Oct 2015 07:28:00 GMT".to_string())
+    );
+    assert_eq!(
+      resume_validator(&headers(&[
+        ("content-length", "10"),
+        ("content-encoding", "gzip"),
+        ("etag", "\"abc\""),
+      ])),
+      None
+    );
+    // decoded by the HTTP client
+    assert_eq!(resume_validator(&headers(&[("etag", "\"abc\"")])), None);
+    assert_eq!(
+      resume_validator(&headers(&[("content-length", "10")])),
+      None
+    );
+
+    let mut partial_body = PartialBody {
+      data: b"abc".to_vec(),
+      maybe_validator: Some("\"abc\"".to_string()),
+    };
+    assert_eq!(
+      partial_body.range_headers(),
+      Some(("bytes=3-".to_string(), "\"abc\"".to_string()))
+    );
+    partial_body.maybe_validator = None;
+    assert_eq!(partial_body.range_headers(), None);
+  }
+
+  #[test]
+  fn test_download_retry_delay() {
+    assert_eq!(download_retry_delay(1), Duration::from_millis(100));
+    assert_eq!(download_retry_delay(2), Duration::from_millis(200));
+    assert_eq!(download_retry_delay(3), Duration::from_millis(400));
+    assert_eq!(download_retry_delay(10), Duration::from_millis(5_000));
+    assert_eq!(download_retry_delay(100), Duration::from_millis(5_000));
+  }
+
   #[test]
   fn test_resolve_url_from_location_full_1() {
     let url = "http://deno.land".parse::<Url>().unwrap();
@@ -14,6 +14,7 @@ const {
   ArrayPrototypePush,
   Error,
   MathCeil,
+  MathSqrt,
   SymbolToStringTag,
   TypeError,
 } = primordials;
@@ -161,6 +162,12 @@ function benchStats(
   max,
   all,
 ) {
+  const mean = avg / n;
+  let variance = 0;
+  for (let i = 0; i < all.length; i++) {
+    variance += (all[i] - mean) ** 2;
+  }
+  variance /= n > 1 ? n - 1 : 1;
   return {
     n,
     min,
@@ -170,6 +177,7 @@ function benchStats(
     p995: all[MathCeil(n * (99.5 / 100)) - 1],
     p999: all[MathCeil(n * (99.9 / 100)) - 1],
     avg: !highPrecision ? (avg / n) : MathCeil(avg / n),
+    sd: MathSqrt(variance),
     highPrecision,
     usedExplicitTimers,
   };
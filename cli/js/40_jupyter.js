@@ -313,6 +313,37 @@ const html = createTaggedTemplateDisplayable("text/html");
  *    </svg>`
  */
 const svg = createTaggedTemplateDisplayable("image/svg+xml");
+/**
+ * LaTeX Tagged Template Function.
+ *
+ * Takes a template string and returns a displayable object for Jupyter frontends.
+ *
+ * Example usage:
+ *
+ * latex`$$\int_0^1 x^2 \, dx = \frac{1}{3}$$`
+ */
+const latex = createTaggedTemplateDisplayable("text/latex");
+
+/** The width of the bar of the plain text view of a progress bar. */
+const PROGRESS_BAR_WIDTH = 30;
+
+/**
+ * Format a progress bar as a media bundle, with a `<progress>` element for
+ * HTML frontends and a text bar for the others.
+ */
+function formatProgress(value, total, label) {
+  const ratio = total > 0 ? Math.min(Math.max(value / total, 0), 1) : 0;
+  const percent = Math.floor(ratio * 100);
+  const filled = Math.round(ratio * PROGRESS_BAR_WIDTH);
+  const bar = "#".repeat(filled) + " ".repeat(PROGRESS_BAR_WIDTH - filled);
+  const prefix = label ? `${label} ` : "";
+  const htmlPrefix = label ? `<span>${escapeHTML(String(label))}</span> ` : "";
+  return {
+    "text/plain": `${prefix}[${bar}] ${value}/${total} (${percent}%)`,
+    "text/html":
+      `<div>${htmlPrefix}<progress value="${value}" max="${total}"></progress> ${value}/${total} (${percent}%)</div>`,
+  };
+}
 
 function isMediaBundle(obj) {
   if (obj == null || typeof obj !== "object" || Array.isArray(obj)) {
@@ -334,7 +365,7 @@ async function formatInner(obj, raw) {
   }
 }
 
-internals.jupyter = { formatInner };
+internals.jupyter = { formatInner, formatProgress };
 
 function enableJupyter() {
   const {
@@ -393,6 +424,9 @@ function enableJupyter() {
    * Mimics the behavior of IPython's `display(obj, raw=True)` function to allow
    * asynchronous displaying of objects in Jupyter.
    *
+   * Returns a handle of the display, which updates the displayed object in
+   * place.
+   *
    * @param obj - The object to be displayed
    * @param options - Display options
    */
@@ -402,18 +436,236 @@ function enableJupyter() {
     if (options.update) {
       messageType = "update_display_data";
     }
-    let transient = {};
-    if (options.display_id) {
-      transient = { display_id: options.display_id };
-    }
+    // a display always has an id, so it can be updated with the handle
+    const displayId = options.display_id ?? crypto.randomUUID();
     await broadcast(messageType, {
       data: bundle,
       metadata: {},
-      transient,
+      transient: { display_id: displayId },
     });
-    return;
+    return new DisplayHandle(displayId);
+  }
+
+  class DisplayHandle {
+    constructor(displayId) {
+      this.displayId = displayId;
+    }
+
+    async update(obj, options = { raw: false }) {
+      await display(obj, {
+        raw: options.raw,
+        update: true,
+        display_id: this.displayId,
+      });
+    }
+  }
+
+  /** A progress bar, which is updated in place in the output of the cell. */
+  class ProgressBar {
+    #handle;
+    #label;
+
+    constructor(handle, total, label) {
+      this.#handle = handle;
+      this.#label = label;
+      this.total = total;
+      this.value = 0;
+    }
+
+    async update(value) {
+      this.value = value;
+      await this.#handle.update(
+        formatProgress(this.value, this.total, this.#label),
+        { raw: true },
+      );
+    }
+
+    async increment(step = 1) {
+      await this.update(this.value + step);
+    }
+  }
+
+  async function progress(total, { label = "" } = {}) {
+    const handle = await display(formatProgress(0, total, label), {
+      raw: true,
+    });
+    return new ProgressBar(handle, total, label);
+  }
+
+  /** The comms opened by the kernel, by their ids. */
+  const comms = new Map();
+
+  /**
+   * A comm of the Jupyter messaging protocol, which is a channel with the
+   * frontend for custom messages like the ones of widgets.
+   */
+  class Comm {
+    #listeners = [];
+
+    constructor(id, targetName) {
+      this.id = id;
+      this.targetName = targetName;
+    }
+
+    async send(data, { metadata = {}, buffers = [] } = {}) {
+      await broadcast("comm_msg", { comm_id: this.id, data }, {
+        metadata,
+        buffers,
+      });
+    }
+
+    onMessage(listener) {
+      this.#listeners.push(listener);
+    }
+
+    async close(data = {}) {
+      comms.delete(this.id);
+      await broadcast("comm_close", { comm_id: this.id, data });
+    }
+
+    async dispatch(data) {
+      for (const listener of this.#listeners) {
+        await listener(data);
+      }
+    }
+  }
+
+  async function openComm(
+    targetName,
+    data = {},
+    { metadata = {}, buffers = [] } = {},
+  ) {
+    const comm = new Comm(crypto.randomUUID(), targetName);
+    comms.set(comm.id, comm);
+    await broadcast("comm_open", {
+      comm_id: comm.id,
+      target_name: targetName,
+      data,
+    }, { metadata, buffers });
+    return comm;
   }
 
+  /** The version of the widget protocol of ipywidgets 8. */
+  const WIDGET_PROTOCOL_VERSION = "2.1.0";
+
+  /** The widgets created in the kernel, by their model ids. */
+  const widgets = new Map();
+
+  /**
+   * A widget of ipywidgets, whose state is synced with the frontend with the
+   * widget protocol.
+   */
+  class Widget {
+    #comm;
+    #listeners = [];
+
+    constructor(comm, state) {
+      this.#comm = comm;
+      this.state = state;
+      comm.onMessage(async (data) => {
+        if (data?.method === "update") {
+          Object.assign(this.state, data.state);
+          for (const listener of this.#listeners) {
+            await listener(data.state);
+          }
+        } else if (data?.method === "request_state") {
+          await this.#comm.send({
+            method: "update",
+            state: this.state,
+            buffer_paths: [],
+          });
+        }
+      });
+    }
+
+    get id() {
+      return this.#comm.id;
+    }
+
+    async set(state) {
+      Object.assign(this.state, state);
+      await this.#comm.send({ method: "update", state, buffer_paths: [] });
+    }
+
+    onChange(listener) {
+      this.#listeners.push(listener);
+    }
+
+    async close() {
+      widgets.delete(this.id);
+      await this.#comm.close();
+    }
+
+    [$display]() {
+      const [major, minor] = WIDGET_PROTOCOL_VERSION.split(".");
+      return {
+        "application/vnd.jupyter.widget-view+json": {
+          model_id: this.id,
+          version_major: Number(major),
+          version_minor: Number(minor),
+        },
+        "text/plain": `${this.state._model_name}(${
+          JSON.stringify(this.state.value ?? "")
+        })`,
+      };
+    }
+  }
+
+  /**
+   * Create a widget of `@jupyter-widgets/controls`, like an `IntSliderModel`.
+   */
+  async function widget(modelName, state = {}) {
+    const fullState = {
+      _model_module: "@jupyter-widgets/controls",
+      _model_module_version: "2.0.0",
+      _model_name: modelName,
+      _view_module: "@jupyter-widgets/controls",
+      _view_module_version: "2.0.0",
+      _view_name: modelName.replace(/Model$/, "View"),
+      ...state,
+    };
+    const comm = await openComm("jupyter.widget", {
+      state: fullState,
+      buffer_paths: [],
+    }, { metadata: { version: WIDGET_PROTOCOL_VERSION } });
+    const created = new Widget(comm, fullState);
+    widgets.set(created.id, created);
+    return created;
+  }
+
+  /** Handle a `comm_msg` of the frontend. */
+  async function handleCommMessage(commId, data) {
+    const comm = comms.get(commId);
+    if (comm) {
+      await comm.dispatch(data);
+    } else if (data?.method === "request_states") {
+      // the widget manager of the frontend asks for every widget on its
+      // "jupyter.widget.control" comm when it starts
+      const states = {};
+      for (const [id, widget] of widgets) {
+        states[id] = {
+          model_name: widget.state._model_name,
+          model_module: widget.state._model_module,
+          model_module_version: widget.state._model_module_version,
+          state: widget.state,
+        };
+      }
+      await broadcast("comm_msg", {
+        comm_id: commId,
+        data: { method: "update_states", states, buffer_paths: {} },
+      });
+    }
+  }
+
+  /** Handle a `comm_close` of the frontend. */
+  function handleCommClose(commId) {
+    comms.delete(commId);
+    widgets.delete(commId);
+  }
+
+  internals.jupyter.handleCommMessage = handleCommMessage;
+  internals.jupyter.handleCommClose = handleCommClose;
+
   globalThis.Deno.jupyter = {
     broadcast,
     display,
@@ -421,6 +673,10 @@ function enableJupyter() {
     md,
     html,
     svg,
+    latex,
+    progress,
+    openComm,
+    widget,
     $display,
   };
 }
@@ -11,6 +11,7 @@ const {
   op_test_event_step_result_ignored,
   op_test_event_step_result_ok,
   op_test_event_step_wait,
+  op_test_snapshot_assert,
   op_test_op_sanitizer_collect,
   op_test_op_sanitizer_finish,
   op_test_op_sanitizer_get_async_message,
@@ -27,12 +28,16 @@ const {
   MapPrototypeGet,
   MapPrototypeHas,
   MapPrototypeSet,
+  NumberIsInteger,
   Promise,
   SafeArrayIterator,
+  StringPrototypeIncludes,
+  StringPrototypeReplaceAll,
   SymbolToStringTag,
   TypeError,
 } = primordials;
 
+import { inspect } from "ext:deno_console/01_console.js";
 import { setExitHandler } from "ext:runtime/30_os.js";
 import { setTimeout } from "ext:deno_web/02_timers.js";
 
@@ -294,6 +299,11 @@ function assertExit(fn, isTest) {
 
 function wrapOuter(fn, desc) {
   return async function outerWrapped() {
+    // A test may be run again when it's retried.
+    const initialState = MapPrototypeGet(testStates, desc.id);
+    initialState.children = [];
+    initialState.completed = false;
+    initialState.snapshotCount = 0;
     try {
       if (desc.ignore) {
         return "ignored";
@@ -387,6 +397,7 @@ function testInner(
     sanitizeResources: true,
     sanitizeExit: true,
     permissions: null,
+    retry: 0,
   };
 
   if (typeof nameOrFnOrOptions === "string") {
@@ -464,6 +475,13 @@ function testInner(
   // Delete this prop in case the user passed it. It's used to detect steps.
   delete testDesc.parent;
 
+  if (
+    typeof testDesc.retry !== "number" || !NumberIsInteger(testDesc.retry) ||
+    testDesc.retry < 0
+  ) {
+    throw new TypeError("The 'retry' option must be a non-negative integer");
+  }
+
   testDesc.location = core.currentUserCallSite();
   testDesc.fn = wrapTest(testDesc);
   testDesc.name = escapeName(testDesc.name);
@@ -478,6 +496,7 @@ function testInner(
     testDesc.location.fileName,
     testDesc.location.lineNumber,
     testDesc.location.columnNumber,
+    testDesc.retry,
     registerTestIdRetBufU8,
   );
   testDesc.id = registerTestIdRetBuf[0];
@@ -486,6 +505,7 @@ function testInner(
     context: createTestContext(testDesc),
     children: [],
     completed: false,
+    snapshotCount: 0,
   });
 }
 
@@ -635,6 +655,7 @@ function createTestContext(desc) {
         children: [],
         failed: false,
         completed: false,
+        snapshotCount: 0,
       };
       MapPrototypeSet(testStates, stepDesc.id, state);
       ArrayPrototypePush(
@@ -650,9 +671,49 @@ function createTestContext(desc) {
       stepReportResult(stepDesc, result, elapsed);
       return result == "ok";
     },
+    /**
+     * @param actual {unknown}
+     */
+    assertSnapshot(actual) {
+      const state = MapPrototypeGet(testStates, desc.id);
+      state.snapshotCount++;
+      op_test_snapshot_assert(
+        desc.origin,
+        `${getSnapshotName(desc)} ${state.snapshotCount}`,
+        serializeSnapshot(actual),
+      );
+    },
   };
 }
 
+/** Snapshots are named like the ones of the std snapshot module. */
+function getSnapshotName(desc) {
+  if ("parent" in desc) {
+    return `${getSnapshotName(desc.parent)} > ${desc.name}`;
+  }
+  return desc.name;
+}
+
+function serializeSnapshot(actual) {
+  const serialized = StringPrototypeReplaceAll(
+    inspect(actual, {
+      depth: Infinity,
+      sorted: true,
+      trailingComma: true,
+      compact: false,
+      iterableLimit: Infinity,
+      strAbbreviateSize: Infinity,
+      breakLength: Infinity,
+      escapeSequences: false,
+    }),
+    "\r",
+    "\\r",
+  );
+  return StringPrototypeIncludes(serialized, "\n")
+    ? `\n${serialized}\n`
+    : serialized;
+}
+
 /**
  * Wrap a user test function in one which returns a structured result.
  * @template T {Function}
@@ -265,6 +265,26 @@ impl<'a> TsResponseImportMapper<'a> {
       if let Some(result) = import_map.lookup(specifier, referrer) {
         return Some(result);
       }
+      // jsr packages in the import map are only resolved through the cache
+      for entry in import_map.entries_for_referrer(referrer) {
+        if entry.raw_key.ends_with('/') {
+          continue;
+        }
+        if let Some(value) = entry.value {
+          if self.documents.resolve_cached_jsr_specifier(value).as_ref()
+            == Some(specifier)
+          {
+            return Some(entry.raw_key.to_string());
+          }
+        }
+      }
+    }
+
+    // modules of cached jsr packages are imported with a `jsr:` specifier
+    if let Some(result) =
+      self.documents.cached_jsr_module_to_specifier(specifier)
+    {
+      return Some(result);
     }
 
     None
@@ -604,8 +624,10 @@ impl CodeActionCollection {
     &mut self,
     specifier: &ModuleSpecifier,
     diagnostic: &lsp::Diagnostic,
+    documents: &Documents,
   ) -> Result<(), AnyError> {
-    let code_action = DenoDiagnostic::get_code_action(specifier, diagnostic)?;
+    let code_action =
+      DenoDiagnostic::get_code_action(specifier, diagnostic, documents)?;
     self.actions.push(CodeActionKind::Deno(code_action));
     Ok(())
   }
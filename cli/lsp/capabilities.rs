@@ -20,8 +20,11 @@ fn code_action_capabilities(
     .and_then(|it| it.code_action.as_ref())
     .and_then(|it| it.code_action_literal_support.as_ref())
     .map(|_| {
-      let mut code_action_kinds =
-        vec![CodeActionKind::QUICKFIX, CodeActionKind::REFACTOR];
+      let mut code_action_kinds = vec![
+        CodeActionKind::QUICKFIX,
+        CodeActionKind::REFACTOR,
+        CodeActionKind::SOURCE_ORGANIZE_IMPORTS,
+      ];
       code_action_kinds.extend(
         ALL_KNOWN_REFACTOR_ACTION_KINDS
           .iter()
@@ -109,8 +112,13 @@ pub fn server_capabilities(
       resolve_provider: Some(true),
     }),
     document_formatting_provider: Some(OneOf::Left(true)),
-    document_range_formatting_provider: None,
-    document_on_type_formatting_provider: None,
+    document_range_formatting_provider: Some(OneOf::Left(true)),
+    document_on_type_formatting_provider: Some(
+      DocumentOnTypeFormattingOptions {
+        first_trigger_character: ";".to_string(),
+        more_trigger_character: Some(vec!["}".to_string()]),
+      },
+    ),
     selection_range_provider: Some(SelectionRangeProviderCapability::Simple(
       true,
     )),
@@ -121,7 +129,12 @@ pub fn server_capabilities(
     execute_command_provider: Some(ExecuteCommandOptions {
       commands: vec![
         "deno.cache".to_string(),
+        "deno.coverage.show".to_string(),
+        "deno.debugTest".to_string(),
         "deno.reloadImportRegistries".to_string(),
+        "deno.reloadModule".to_string(),
+        "deno.showDependencyGraph".to_string(),
+        "deno.upgrade".to_string(),
       ],
       ..Default::default()
     }),
@@ -24,6 +24,9 @@ pub enum TestingNotification {
   Module(testing_lsp_custom::TestModuleNotificationParams),
   DeleteModule(testing_lsp_custom::TestModuleDeleteNotificationParams),
   Progress(testing_lsp_custom::TestRunProgressParams),
+  DebugSession(testing_lsp_custom::TestDebugSessionParams),
+  RunStart(testing_lsp_custom::TestRunStartParams),
+  Coverage(testing_lsp_custom::TestCoverageParams),
 }
 
 #[derive(Clone)]
@@ -249,6 +252,28 @@ impl ClientTrait for TowerClient {
           )
           .await
       }
+      TestingNotification::DebugSession(params) => self
+        .0
+        .send_notification::<testing_lsp_custom::TestDebugSessionNotification>(
+          params,
+        )
+        .await,
+      TestingNotification::RunStart(params) => {
+        self
+          .0
+          .send_notification::<testing_lsp_custom::TestRunStartNotification>(
+            params,
+          )
+          .await
+      }
+      TestingNotification::Coverage(params) => {
+        self
+          .0
+          .send_notification::<testing_lsp_custom::TestCoverageNotification>(
+            params,
+          )
+          .await
+      }
     }
   }
 
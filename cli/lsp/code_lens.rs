@@ -3,6 +3,7 @@
 use super::analysis::source_range_to_lsp_range;
 use super::config::CodeLensSettings;
 use super::language_server;
+use super::testing::TestRunResult;
 use super::text::LineIndex;
 use super::tsc;
 use super::tsc::NavigationTree;
@@ -24,6 +25,7 @@ use lazy_regex::lazy_regex;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::rc::Rc;
 use std::sync::Arc;
@@ -53,23 +55,48 @@ struct DenoTestCollector {
   parsed_source: ParsedSource,
   specifier: ModuleSpecifier,
   test_vars: HashSet<String>,
+  /// The results of the last run of the tests, only available when the tests
+  /// are run by the language server itself.
+  maybe_test_results: Option<HashMap<String, TestRunResult>>,
 }
 
 impl DenoTestCollector {
-  pub fn new(specifier: ModuleSpecifier, parsed_source: ParsedSource) -> Self {
+  pub fn new(
+    specifier: ModuleSpecifier,
+    parsed_source: ParsedSource,
+    maybe_test_results: Option<HashMap<String, TestRunResult>>,
+  ) -> Self {
     Self {
       code_lenses: Vec::new(),
       parsed_source,
       specifier,
       test_vars: HashSet::new(),
+      maybe_test_results,
     }
   }
 
   fn add_code_lenses<N: AsRef<str>>(&mut self, name: N, range: &SourceRange) {
     let range =
       source_range_to_lsp_range(range, self.parsed_source.text_info());
-    self.add_code_lens(&name, range, "▶\u{fe0e} Run Test", false);
-    self.add_code_lens(&name, range, "Debug", true);
+    let Some(test_results) = &self.maybe_test_results else {
+      self.add_code_lens(&name, range, "▶\u{fe0e} Run Test", false);
+      self.add_code_lens(&name, range, "Debug", true);
+      return;
+    };
+    let title = test_results
+      .get(name.as_ref())
+      .map(|r| r.to_code_lens_title())
+      .unwrap_or_else(|| "▶\u{fe0e} Run Test".to_string());
+    self.add_code_lens(&name, range, &title, false);
+    self.code_lenses.push(lsp::CodeLens {
+      range,
+      command: Some(lsp::Command {
+        title: "Debug Test".to_string(),
+        command: "deno.debugTest".to_string(),
+        arguments: Some(vec![json!(self.specifier), json!(name.as_ref())]),
+      }),
+      data: None,
+    });
   }
 
   fn add_code_lens<N: AsRef<str>>(
@@ -404,12 +431,19 @@ pub async fn resolve_code_lens(
   }
 }
 
+/// Return the test code lenses of a module. When the tests are run by the
+/// language server, the results of the last run are shown on the lenses and
+/// debugging is started by the language server.
 pub fn collect_test(
   specifier: &ModuleSpecifier,
   parsed_source: ParsedSource,
+  maybe_test_results: Option<HashMap<String, TestRunResult>>,
 ) -> Result<Vec<lsp::CodeLens>, AnyError> {
-  let mut collector =
-    DenoTestCollector::new(specifier.clone(), parsed_source.clone());
+  let mut collector = DenoTestCollector::new(
+    specifier.clone(),
+    parsed_source.clone(),
+    maybe_test_results,
+  );
   parsed_source.module().visit_with(&mut collector);
   Ok(collector.take())
 }
@@ -569,7 +603,7 @@ mod tests {
     })
     .unwrap();
     let mut collector =
-      DenoTestCollector::new(specifier, parsed_module.clone());
+      DenoTestCollector::new(specifier, parsed_module.clone(), None);
     parsed_module.module().visit_with(&mut collector);
     assert_eq!(
       collector.take(),
@@ -865,4 +899,65 @@ mod tests {
       ]
     );
   }
+
+  #[test]
+  fn test_deno_test_collector_with_results() {
+    use super::super::testing::TestResultState;
+
+    let specifier = resolve_url("file:///a/mod_test.ts").unwrap();
+    let source = r#"
+      Deno.test("test a", () => {});
+      Deno.test("test b", () => {});
+      Deno.test("test c", () => {});
+    "#;
+    let parsed_module = deno_ast::parse_module(deno_ast::ParseParams {
+      specifier: specifier.clone(),
+      text_info: SourceTextInfo::new(source.into()),
+      media_type: MediaType::TypeScript,
+      capture_tokens: true,
+      scope_analysis: true,
+      maybe_syntax: None,
+    })
+    .unwrap();
+    let test_results = HashMap::from([
+      (
+        "test a".to_string(),
+        TestRunResult {
+          state: TestResultState::Passed,
+          duration: Some(12),
+        },
+      ),
+      (
+        "test b".to_string(),
+        TestRunResult {
+          state: TestResultState::Failed,
+          duration: Some(3),
+        },
+      ),
+    ]);
+    let code_lenses =
+      collect_test(&specifier, parsed_module, Some(test_results)).unwrap();
+    let commands = code_lenses
+      .iter()
+      .map(|l| {
+        let command = l.command.as_ref().unwrap();
+        (command.title.as_str(), command.command.as_str())
+      })
+      .collect::<Vec<_>>();
+    assert_eq!(
+      commands,
+      vec![
+        ("\u{2714}\u{fe0e} Run Test (12ms)", "deno.client.test"),
+        ("Debug Test", "deno.debugTest"),
+        ("\u{2718}\u{fe0e} Run Test (3ms)", "deno.client.test"),
+        ("Debug Test", "deno.debugTest"),
+        ("▶\u{fe0e} Run Test", "deno.client.test"),
+        ("Debug Test", "deno.debugTest"),
+      ]
+    );
+    assert_eq!(
+      code_lenses[1].command.as_ref().unwrap().arguments,
+      Some(vec![json!("file:///a/mod_test.ts"), json!("test a")])
+    );
+  }
 }
@@ -5,12 +5,15 @@ use super::config::ConfigSnapshot;
 use super::config::WorkspaceSettings;
 use super::documents::Documents;
 use super::documents::DocumentsFilter;
+use super::jsr::CliJsrSearchApi;
+use super::jsr::JsrSearchApi;
 use super::lsp_custom;
 use super::npm::CliNpmSearchApi;
 use super::npm::NpmSearchApi;
 use super::registries::ModuleRegistry;
 use super::tsc;
 
+use crate::npm::CliNpmResolver;
 use crate::util::path::is_importable_ext;
 use crate::util::path::relative_specifier;
 use crate::util::path::specifier_to_file_path;
@@ -22,12 +25,17 @@ use deno_core::resolve_path;
 use deno_core::resolve_url;
 use deno_core::serde::Deserialize;
 use deno_core::serde::Serialize;
+use deno_core::serde_json;
 use deno_core::serde_json::json;
 use deno_core::url::Position;
 use deno_core::ModuleSpecifier;
+use deno_semver::npm::NpmPackageReqReference;
+use deno_semver::Version;
+use deno_semver::VersionReq;
 use import_map::ImportMap;
 use once_cell::sync::Lazy;
 use regex::Regex;
+use std::path::Path;
 use std::sync::Arc;
 use tower_lsp::lsp_types as lsp;
 
@@ -149,6 +157,8 @@ pub async fn get_import_completions(
   client: &Client,
   module_registries: &ModuleRegistry,
   npm_search_api: &CliNpmSearchApi,
+  jsr_search_api: &CliJsrSearchApi,
+  npm_resolver: Option<&dyn CliNpmResolver>,
   documents: &Documents,
   maybe_import_map: Option<Arc<ImportMap>>,
 ) -> Option<lsp::CompletionResponse> {
@@ -171,8 +181,21 @@ pub async fn get_import_completions(
       items: get_local_completions(specifier, &text, &range)?,
     }))
   } else if text.starts_with("npm:") {
+    let items = get_npm_completions(
+      specifier,
+      &text,
+      &range,
+      npm_search_api,
+      npm_resolver,
+    )
+    .await?;
+    Some(lsp::CompletionResponse::List(lsp::CompletionList {
+      is_incomplete: !items.is_empty(),
+      items,
+    }))
+  } else if text.starts_with("jsr:") {
     let items =
-      get_npm_completions(specifier, &text, &range, npm_search_api).await?;
+      get_jsr_completions(specifier, &text, &range, jsr_search_api).await?;
     Some(lsp::CompletionResponse::List(lsp::CompletionList {
       is_incomplete: !items.is_empty(),
       items,
@@ -497,14 +520,95 @@ fn parse_npm_specifier_version_index(specifier: &str) -> Option<usize> {
   }
 }
 
+/// Find the index of the '/' delimiting the package name (and version) and the
+/// subpath, if any.
+fn parse_npm_specifier_subpath_index(specifier: &str) -> Option<usize> {
+  let bare_specifier = specifier.strip_prefix("npm:")?;
+  let name_start = if bare_specifier.starts_with('@') {
+    bare_specifier
+      .find('/')
+      .filter(|idx| !bare_specifier[1..*idx].is_empty())?
+      + 1
+  } else {
+    0
+  };
+  let idx = bare_specifier[name_start..].find('/')? + name_start;
+  let name = &bare_specifier[name_start..idx];
+  let name = name.split_once('@').map(|(name, _)| name).unwrap_or(name);
+  if name.is_empty() {
+    return None;
+  }
+  Some(4 + idx)
+}
+
+/// Get the subpaths which are exported by the package in the folder, excluding
+/// subpath patterns.
+fn get_package_export_subpaths(package_folder: &Path) -> Option<Vec<String>> {
+  let text =
+    std::fs::read_to_string(package_folder.join("package.json")).ok()?;
+  let package_json: serde_json::Value = serde_json::from_str(&text).ok()?;
+  let exports = package_json.get("exports")?.as_object()?;
+  let mut subpaths = exports
+    .keys()
+    .filter_map(|key| key.strip_prefix("./"))
+    .filter(|subpath| !subpath.is_empty() && !subpath.contains('*'))
+    .map(|subpath| subpath.to_string())
+    .collect::<Vec<_>>();
+  subpaths.sort();
+  Some(subpaths)
+}
+
 /// Get completions for `npm:` specifiers.
 async fn get_npm_completions(
   referrer: &ModuleSpecifier,
   specifier: &str,
   range: &lsp::Range,
   npm_search_api: &impl NpmSearchApi,
+  npm_resolver: Option<&dyn CliNpmResolver>,
 ) -> Option<Vec<lsp::CompletionItem>> {
-  // First try to match `npm:some-package@<version-to-complete>`.
+  // First try to match `npm:some-package@version/<subpath-to-complete>` from
+  // the exports of the package, once it's cached.
+  if let Some(s_index) = parse_npm_specifier_subpath_index(specifier) {
+    let package_specifier = &specifier[..s_index];
+    let subpath_prefix = &specifier[(s_index + 1)..];
+    let req_ref = NpmPackageReqReference::from_str(package_specifier).ok()?;
+    let package_folder = npm_resolver?
+      .resolve_pkg_folder_from_deno_module_req(req_ref.req(), referrer)
+      .ok()?;
+    let subpaths = get_package_export_subpaths(&package_folder)?;
+    let items = subpaths
+      .into_iter()
+      .filter(|subpath| subpath.starts_with(subpath_prefix))
+      .enumerate()
+      .map(|(idx, subpath)| {
+        let specifier = format!("{}/{}", package_specifier, subpath);
+        let command = Some(lsp::Command {
+          title: "".to_string(),
+          command: "deno.cache".to_string(),
+          arguments: Some(vec![json!([&specifier]), json!(referrer)]),
+        });
+        let text_edit = Some(lsp::CompletionTextEdit::Edit(lsp::TextEdit {
+          range: *range,
+          new_text: specifier.clone(),
+        }));
+        lsp::CompletionItem {
+          label: specifier,
+          kind: Some(lsp::CompletionItemKind::FILE),
+          detail: Some("(npm)".to_string()),
+          sort_text: Some(format!("{:0>10}", idx + 1)),
+          text_edit,
+          command,
+          commit_characters: Some(
+            IMPORT_COMMIT_CHARS.iter().map(|&c| c.into()).collect(),
+          ),
+          ..Default::default()
+        }
+      })
+      .collect();
+    return Some(items);
+  }
+
+  // Then try to match `npm:some-package@<version-to-complete>`.
   if let Some(v_index) = parse_npm_specifier_version_index(specifier) {
     let package_name = &specifier[..v_index].strip_prefix("npm:")?;
     let v_prefix = &specifier[(v_index + 1)..];
@@ -585,6 +689,120 @@ async fn get_npm_completions(
   Some(items)
 }
 
+fn get_jsr_completion_item(
+  referrer: &ModuleSpecifier,
+  specifier: String,
+  range: &lsp::Range,
+  idx: usize,
+) -> lsp::CompletionItem {
+  let command = Some(lsp::Command {
+    title: "".to_string(),
+    command: "deno.cache".to_string(),
+    arguments: Some(vec![json!([&specifier]), json!(referrer)]),
+  });
+  let text_edit = Some(lsp::CompletionTextEdit::Edit(lsp::TextEdit {
+    range: *range,
+    new_text: specifier.clone(),
+  }));
+  lsp::CompletionItem {
+    label: specifier,
+    kind: Some(lsp::CompletionItemKind::FILE),
+    detail: Some("(jsr)".to_string()),
+    sort_text: Some(format!("{:0>10}", idx + 1)),
+    text_edit,
+    command,
+    commit_characters: Some(
+      IMPORT_COMMIT_CHARS.iter().map(|&c| c.into()).collect(),
+    ),
+    ..Default::default()
+  }
+}
+
+/// Get completions for `jsr:` specifiers.
+async fn get_jsr_completions(
+  referrer: &ModuleSpecifier,
+  specifier: &str,
+  range: &lsp::Range,
+  jsr_search_api: &impl JsrSearchApi,
+) -> Option<Vec<lsp::CompletionItem>> {
+  let bare_specifier = specifier.strip_prefix("jsr:")?;
+  let maybe_version_index = bare_specifier
+    .strip_prefix('@')
+    .and_then(|s| s.find('/'))
+    .and_then(|scope_end| {
+      bare_specifier[scope_end + 1..]
+        .find('@')
+        .map(|idx| scope_end + 1 + idx)
+    });
+  if let Some(v_index) = maybe_version_index {
+    let package_name = &bare_specifier[..v_index];
+    let rest = &bare_specifier[(v_index + 1)..];
+    let versions = jsr_search_api.versions(package_name).await.ok()?;
+
+    // First try to match `jsr:@scope/name@version/<export-to-complete>`.
+    if let Some(e_index) = rest.find('/') {
+      let version_text = &rest[..e_index];
+      let export_prefix = &rest[(e_index + 1)..];
+      let version = match Version::parse_standard(version_text) {
+        Ok(version) => version,
+        Err(_) => {
+          let version_req =
+            VersionReq::parse_from_specifier(version_text).ok()?;
+          versions
+            .iter()
+            .find(|version| version_req.matches(version))?
+            .clone()
+        }
+      };
+      let exports =
+        jsr_search_api.exports(package_name, &version).await.ok()?;
+      let items = exports
+        .iter()
+        .filter_map(|export| export.strip_prefix("./"))
+        .filter(|export| export.starts_with(export_prefix))
+        .enumerate()
+        .map(|(idx, export)| {
+          get_jsr_completion_item(
+            referrer,
+            format!("jsr:{}@{}/{}", package_name, version_text, export),
+            range,
+            idx,
+          )
+        })
+        .collect();
+      return Some(items);
+    }
+
+    // Then try to match `jsr:@scope/name@<version-to-complete>`.
+    let items = versions
+      .iter()
+      .map(|version| version.to_string())
+      .filter(|version| version.starts_with(rest))
+      .enumerate()
+      .map(|(idx, version)| {
+        get_jsr_completion_item(
+          referrer,
+          format!("jsr:{}@{}", package_name, version),
+          range,
+          idx,
+        )
+      })
+      .collect();
+    return Some(items);
+  }
+
+  // Otherwise match `jsr:<package-to-complete>`.
+  let names = jsr_search_api.search(bare_specifier).await.ok()?;
+  let items = names
+    .iter()
+    .enumerate()
+    .map(|(idx, name)| {
+      get_jsr_completion_item(referrer, format!("jsr:{}", name), range, idx)
+    })
+    .collect();
+  Some(items)
+}
+
 /// Get workspace completions that include modules in the Deno cache which match
 /// the current specifier string.
 fn get_workspace_completions(
@@ -642,6 +860,7 @@ mod tests {
   use crate::cache::HttpCache;
   use crate::lsp::documents::Documents;
   use crate::lsp::documents::LanguageId;
+  use crate::lsp::jsr::JsrSearchApi;
   use crate::lsp::npm::NpmSearchApi;
   use crate::AnyError;
   use async_trait::async_trait;
@@ -677,6 +896,41 @@ mod tests {
     }
   }
 
+  #[derive(Default)]
+  struct TestJsrSearchApi {
+    names: HashMap<String, Arc<Vec<String>>>,
+    versions: HashMap<String, Arc<Vec<Version>>>,
+    exports: HashMap<String, Arc<Vec<String>>>,
+  }
+
+  #[async_trait]
+  impl JsrSearchApi for TestJsrSearchApi {
+    async fn search(&self, query: &str) -> Result<Arc<Vec<String>>, AnyError> {
+      Ok(self.names.get(query).cloned().unwrap_or_default())
+    }
+
+    async fn versions(
+      &self,
+      name: &str,
+    ) -> Result<Arc<Vec<Version>>, AnyError> {
+      Ok(self.versions.get(name).cloned().unwrap_or_default())
+    }
+
+    async fn exports(
+      &self,
+      name: &str,
+      version: &Version,
+    ) -> Result<Arc<Vec<String>>, AnyError> {
+      Ok(
+        self
+          .exports
+          .get(&format!("{}@{}", name, version))
+          .cloned()
+          .unwrap_or_default(),
+      )
+    }
+  }
+
   fn mock_documents(
     fixtures: &[(&str, &str, i32, LanguageId)],
     source_fixtures: &[(&str, &str)],
@@ -876,6 +1130,47 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_parse_npm_specifier_subpath_index() {
+    assert_eq!(parse_npm_specifier_subpath_index("npm:"), None);
+    assert_eq!(parse_npm_specifier_subpath_index("npm:/"), None);
+    assert_eq!(parse_npm_specifier_subpath_index("npm:foo"), None);
+    assert_eq!(parse_npm_specifier_subpath_index("npm:foo@1"), None);
+    assert_eq!(parse_npm_specifier_subpath_index("npm:@org/"), None);
+    assert_eq!(parse_npm_specifier_subpath_index("npm:@org/foo"), None);
+    assert_eq!(parse_npm_specifier_subpath_index("npm:@org//"), None);
+    assert_eq!(parse_npm_specifier_subpath_index("npm:@1/"), None);
+
+    assert_eq!(parse_npm_specifier_subpath_index("npm:foo/"), Some(7));
+    assert_eq!(parse_npm_specifier_subpath_index("npm:foo@1/bar"), Some(9));
+    assert_eq!(parse_npm_specifier_subpath_index("npm:@org/foo/"), Some(12));
+    assert_eq!(
+      parse_npm_specifier_subpath_index("npm:@org/foo@^1.0/b"),
+      Some(17)
+    );
+  }
+
+  #[test]
+  fn test_get_package_export_subpaths() {
+    let temp_dir = TempDir::new();
+    temp_dir.write(
+      "package.json",
+      r#"{
+        "name": "foo",
+        "exports": {
+          ".": "./index.js",
+          "./utils": "./utils.js",
+          "./client": { "types": "./client.d.ts", "default": "./client.js" },
+          "./features/*": "./features/*.js"
+        }
+      }"#,
+    );
+    assert_eq!(
+      get_package_export_subpaths(temp_dir.path().as_path()),
+      Some(vec!["client".to_string(), "utils".to_string()])
+    );
+  }
+
   #[tokio::test]
   async fn test_get_npm_completions() {
     let npm_search_api = TestNpmSearchApi(
@@ -903,10 +1198,15 @@ mod tests {
       },
     };
     let referrer = ModuleSpecifier::parse("file:///referrer.ts").unwrap();
-    let actual =
-      get_npm_completions(&referrer, "npm:puppe", &range, &npm_search_api)
-        .await
-        .unwrap();
+    let actual = get_npm_completions(
+      &referrer,
+      "npm:puppe",
+      &range,
+      &npm_search_api,
+      None,
+    )
+    .await
+    .unwrap();
     assert_eq!(
       actual,
       vec![
@@ -1025,10 +1325,15 @@ mod tests {
       },
     };
     let referrer = ModuleSpecifier::parse("file:///referrer.ts").unwrap();
-    let actual =
-      get_npm_completions(&referrer, "npm:puppeteer@", &range, &npm_search_api)
-        .await
-        .unwrap();
+    let actual = get_npm_completions(
+      &referrer,
+      "npm:puppeteer@",
+      &range,
+      &npm_search_api,
+      None,
+    )
+    .await
+    .unwrap();
     assert_eq!(
       actual,
       vec![
@@ -1124,6 +1429,99 @@ mod tests {
     );
   }
 
+  #[tokio::test]
+  async fn test_get_jsr_completions() {
+    let mut jsr_search_api = TestJsrSearchApi::default();
+    jsr_search_api.names.insert(
+      "@std/".to_string(),
+      Arc::new(vec!["@std/path".to_string(), "@std/fs".to_string()]),
+    );
+    jsr_search_api.versions.insert(
+      "@std/path".to_string(),
+      Arc::new(vec![
+        Version::parse_standard("0.211.0").unwrap(),
+        Version::parse_standard("0.210.0").unwrap(),
+        Version::parse_standard("0.1.0").unwrap(),
+      ]),
+    );
+    jsr_search_api.exports.insert(
+      "@std/path@0.211.0".to_string(),
+      Arc::new(vec![
+        ".".to_string(),
+        "./join".to_string(),
+        "./posix".to_string(),
+      ]),
+    );
+    let range = lsp::Range {
+      start: lsp::Position {
+        line: 0,
+        character: 23,
+      },
+      end: lsp::Position {
+        line: 0,
+        character: 32,
+      },
+    };
+    let referrer = ModuleSpecifier::parse("file:///referrer.ts").unwrap();
+    let labels = |items: Vec<lsp::CompletionItem>| {
+      items.into_iter().map(|i| i.label).collect::<Vec<_>>()
+    };
+
+    let actual =
+      get_jsr_completions(&referrer, "jsr:@std/", &range, &jsr_search_api)
+        .await
+        .unwrap();
+    assert_eq!(
+      actual[0],
+      lsp::CompletionItem {
+        label: "jsr:@std/path".to_string(),
+        kind: Some(lsp::CompletionItemKind::FILE),
+        detail: Some("(jsr)".to_string()),
+        sort_text: Some("0000000001".to_string()),
+        text_edit: Some(lsp::CompletionTextEdit::Edit(lsp::TextEdit {
+          range,
+          new_text: "jsr:@std/path".to_string(),
+        })),
+        command: Some(lsp::Command {
+          title: "".to_string(),
+          command: "deno.cache".to_string(),
+          arguments: Some(vec![json!(["jsr:@std/path"]), json!(&referrer)])
+        }),
+        commit_characters: Some(
+          IMPORT_COMMIT_CHARS.iter().map(|&c| c.into()).collect()
+        ),
+        ..Default::default()
+      }
+    );
+    assert_eq!(labels(actual), vec!["jsr:@std/path", "jsr:@std/fs"]);
+
+    let actual = get_jsr_completions(
+      &referrer,
+      "jsr:@std/path@0.2",
+      &range,
+      &jsr_search_api,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+      labels(actual),
+      vec!["jsr:@std/path@0.211.0", "jsr:@std/path@0.210.0"]
+    );
+
+    let actual = get_jsr_completions(
+      &referrer,
+      "jsr:@std/path@^0.211/",
+      &range,
+      &jsr_search_api,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+      labels(actual),
+      vec!["jsr:@std/path@^0.211/join", "jsr:@std/path@^0.211/posix"]
+    );
+  }
+
   #[test]
   fn test_to_narrow_lsp_range() {
     let text_info = SourceTextInfo::from_string(r#""te""#.to_string());
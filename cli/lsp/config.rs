@@ -1,8 +1,14 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
 use super::logging::lsp_log;
+use super::preprocessors::DocumentPreprocessorKind;
+use crate::args::resolve_allow_scripts;
+use crate::args::resolve_file_selection;
 use crate::args::ConfigFile;
+use crate::args::ConfigFileExt;
+use crate::args::GraphBudget;
 use crate::lsp::logging::lsp_warn;
+use crate::util::file_selection::FileSelection;
 use crate::util::fs::canonicalize_path_maybe_not_exists;
 use crate::util::path::specifier_to_file_path;
 use deno_ast::MediaType;
@@ -17,7 +23,9 @@ use deno_core::serde_json;
 use deno_core::serde_json::Value;
 use deno_core::ModuleSpecifier;
 use deno_lockfile::Lockfile;
+use deno_semver::package::PackageReq;
 use lsp::Url;
+use once_cell::sync::Lazy;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -36,6 +44,9 @@ pub struct ClientCapabilities {
   /// built around VSCode's testing API. It indicates that the server should
   /// send notifications about tests discovered in modules.
   pub testing_api: bool,
+  /// The client supports dynamically registering the type hierarchy
+  /// requests.
+  pub type_hierarchy: bool,
   pub workspace_configuration: bool,
   pub workspace_did_change_watched_files: bool,
   pub workspace_will_rename_files: bool,
@@ -271,16 +282,40 @@ pub struct TestingSettings {
   /// a workspace.
   #[serde(default)]
   pub args: Vec<String>,
+  /// Run the tests which depend on a module whenever it is saved.
+  #[serde(default)]
+  pub run_on_save: bool,
+  /// Additional identifiers of functions which declare bdd style test suites,
+  /// like `describe` from `@std/testing/bdd`.
+  #[serde(default)]
+  pub describe_identifiers: Vec<String>,
+  /// Additional identifiers of functions which declare bdd style test cases,
+  /// like `it` from `@std/testing/bdd`.
+  #[serde(default)]
+  pub it_identifiers: Vec<String>,
 }
 
 impl Default for TestingSettings {
   fn default() -> Self {
     Self {
       args: vec!["--allow-all".to_string(), "--no-check".to_string()],
+      run_on_save: false,
+      describe_identifiers: vec![],
+      it_identifiers: vec![],
     }
   }
 }
 
+#[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceSymbolsSettings {
+  /// Include the exported symbols of the workspace's direct dependencies
+  /// (remote modules, jsr packages and npm packages) in workspace symbol
+  /// results, ranked after symbols from the workspace itself.
+  #[serde(default)]
+  pub include_dependencies: bool,
+}
+
 fn default_to_true() -> bool {
   true
 }
@@ -472,6 +507,13 @@ pub struct WorkspaceSettings {
   #[serde(default, deserialize_with = "empty_string_none")]
   pub import_map: Option<String>,
 
+  /// A path string, relative to the root of the workspace, of the directory
+  /// which unsaved `untitled:` documents and documents of virtual file systems
+  /// of the editor are assumed to be in. Their relative imports are resolved
+  /// against it. Defaults to the root of the workspace.
+  #[serde(default, deserialize_with = "empty_string_none")]
+  pub untitled_root: Option<String>,
+
   /// Code lens specific settings for the workspace.
   #[serde(default)]
   pub code_lens: CodeLensSettings,
@@ -495,6 +537,18 @@ pub struct WorkspaceSettings {
   #[serde(default = "default_document_preload_limit")]
   pub document_preload_limit: usize,
 
+  /// The preprocessors which map the documents of the file extensions to
+  /// virtual documents, like `{ "vue": "scriptBlocks" }` to analyze the
+  /// `<script>` blocks of the single file components of Vue.
+  #[serde(default)]
+  pub document_preprocessors: BTreeMap<String, DocumentPreprocessorKind>,
+
+  /// The memory budget in megabytes of the documents which aren't open. When
+  /// it's exceeded, the least recently used documents are dropped and read
+  /// again when they're needed. It's unbounded when not set.
+  #[serde(default)]
+  pub max_document_cache_mb: Option<usize>,
+
   #[serde(default)]
   pub suggest: DenoCompletionSettings,
 
@@ -515,6 +569,15 @@ pub struct WorkspaceSettings {
   #[serde(default)]
   pub unstable: bool,
 
+  /// Skip the paths ignored by the `.gitignore` files when discovering the
+  /// documents of the workspace.
+  #[serde(rename = "useGitIgnore", default = "default_to_true")]
+  pub use_gitignore: bool,
+
+  /// Workspace symbol settings for the workspace.
+  #[serde(default)]
+  pub workspace_symbols: WorkspaceSymbolsSettings,
+
   #[serde(default)]
   pub javascript: LanguageWorkspaceSettings,
 
@@ -533,17 +596,22 @@ impl Default for WorkspaceSettings {
       certificate_stores: None,
       config: None,
       import_map: None,
+      untitled_root: None,
       code_lens: Default::default(),
       internal_debug: false,
       internal_inspect: Default::default(),
       log_file: false,
       lint: true,
       document_preload_limit: default_document_preload_limit(),
+      document_preprocessors: Default::default(),
+      max_document_cache_mb: None,
       suggest: Default::default(),
       testing: Default::default(),
       tls_certificate: None,
       unsafely_ignore_certificate_errors: None,
       unstable: false,
+      use_gitignore: true,
+      workspace_symbols: Default::default(),
       javascript: Default::default(),
       typescript: Default::default(),
     }
@@ -719,8 +787,16 @@ impl WorkspaceSettings {
 pub struct ConfigSnapshot {
   pub client_capabilities: ClientCapabilities,
   pub config_file: Option<ConfigFile>,
+  pub member_config_files: Vec<ConfigFile>,
+  pub graph_budget: Option<GraphBudget>,
+  /// The npm packages of the `"allowScripts"` of the config file.
+  pub allow_scripts: Vec<PackageReq>,
+  pub file_selections: ConfigFileSelections,
   pub settings: Settings,
   pub workspace_folders: Vec<(ModuleSpecifier, lsp::WorkspaceFolder)>,
+  /// The config files of the workspace folders other than the root, keyed by
+  /// the URI of the folder.
+  pub folder_config_files: BTreeMap<ModuleSpecifier, FolderConfigFiles>,
 }
 
 impl ConfigSnapshot {
@@ -731,11 +807,44 @@ impl ConfigSnapshot {
     self.settings.get_for_specifier(specifier).0
   }
 
+  /// Gets the config file which applies to the specifier and the config files
+  /// of its workspace members, which is the one of the innermost workspace
+  /// folder with its own config file or else the one of the root.
+  pub fn config_files_for_specifier(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> (Option<&ConfigFile>, &[ConfigFile]) {
+    match find_scope(&self.folder_config_files, specifier) {
+      Some((_, files)) => (
+        Some(&files.config_file),
+        files.member_config_files.as_slice(),
+      ),
+      None => (
+        self.config_file.as_ref(),
+        self.member_config_files.as_slice(),
+      ),
+    }
+  }
+
+  pub fn file_selections_for_specifier(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> &ConfigFileSelections {
+    match find_scope(&self.folder_config_files, specifier) {
+      Some((_, files)) => &files.file_selections,
+      None => &self.file_selections,
+    }
+  }
+
   /// Determine if the provided specifier is enabled or not.
   pub fn specifier_enabled(&self, specifier: &ModuleSpecifier) -> bool {
+    let (config_file, member_config_files) =
+      self.config_files_for_specifier(specifier);
     specifier_enabled(
       specifier,
-      self.config_file.as_ref(),
+      config_file,
+      member_config_files,
+      self.file_selections_for_specifier(specifier),
       &self.settings,
       &self.workspace_folders,
     )
@@ -745,9 +854,11 @@ impl ConfigSnapshot {
     &self,
     specifier: &ModuleSpecifier,
   ) -> bool {
-    if let Some(cf) = &self.config_file {
+    if let (Some(cf), _) = self.config_files_for_specifier(specifier) {
       if let Some(options) = cf.to_test_config().ok().flatten() {
-        if !options.files.matches_specifier(specifier) {
+        let file_selection =
+          &self.file_selections_for_specifier(specifier).test;
+        if !file_selection.matches_specifier(&options.files, specifier) {
           return false;
         }
       }
@@ -771,17 +882,11 @@ impl Settings {
     &self,
     specifier: &ModuleSpecifier,
   ) -> (&WorkspaceSettings, Option<&ModuleSpecifier>) {
-    let Ok(path) = specifier_to_file_path(specifier) else {
-      return (&self.unscoped, None);
-    };
     if let Some(by_workspace_folder) = &self.by_workspace_folder {
-      for (folder_uri, settings) in by_workspace_folder.iter().rev() {
-        let Ok(folder_path) = specifier_to_file_path(folder_uri) else {
-          continue;
-        };
-        if path.starts_with(folder_path) {
-          return (settings, Some(folder_uri));
-        }
+      if let Some((folder_uri, settings)) =
+        find_scope(by_workspace_folder, specifier)
+      {
+        return (settings, Some(folder_uri));
       }
     }
     (&self.unscoped, None)
@@ -813,6 +918,46 @@ impl Settings {
   }
 }
 
+/// Gets the entry of the innermost folder containing the specifier, where the
+/// keys are the URIs of the folders.
+pub fn find_scope<'a, T>(
+  scopes: &'a BTreeMap<ModuleSpecifier, T>,
+  specifier: &ModuleSpecifier,
+) -> Option<(&'a ModuleSpecifier, &'a T)> {
+  if scopes.is_empty() {
+    return None;
+  }
+  let path = specifier_to_file_path(specifier).ok()?;
+  // an inner folder sorts after the folders containing it
+  scopes.iter().rev().find(|(folder_uri, _)| {
+    specifier_to_file_path(folder_uri)
+      .map(|folder_path| path.starts_with(folder_path))
+      .unwrap_or(false)
+  })
+}
+
+/// The config file of a workspace folder and the config files of its
+/// workspace members.
+#[derive(Debug, Clone)]
+pub struct FolderConfigFiles {
+  pub config_file: ConfigFile,
+  pub member_config_files: Vec<ConfigFile>,
+  pub file_selections: ConfigFileSelections,
+}
+
+/// The selections of files of a config file by the negated entries of its
+/// `"exclude"` arrays, for the sections used by the language server.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigFileSelections {
+  pub files: FileSelection,
+  pub fmt: FileSelection,
+  pub lint: FileSelection,
+  pub test: FileSelection,
+}
+
+static EMPTY_FILE_SELECTIONS: Lazy<ConfigFileSelections> =
+  Lazy::new(Default::default);
+
 #[derive(Debug)]
 struct WithCanonicalizedSpecifier<T> {
   /// Stored canonicalized specifier, which is used for file watcher events.
@@ -824,10 +969,63 @@ struct WithCanonicalizedSpecifier<T> {
 #[derive(Debug)]
 struct LspConfigFileInfo {
   config_file: WithCanonicalizedSpecifier<ConfigFile>,
+  /// The sections of the config file which `deno_config` doesn't know about.
+  config_file_ext: ConfigFileExt,
+  /// Config files of the workspace members, which override the lint and fmt
+  /// configuration for their directory.
+  member_config_files: Vec<ConfigFile>,
   /// An optional deno.lock file, which is resolved relative to the config file.
   maybe_lockfile: Option<WithCanonicalizedSpecifier<Arc<Mutex<Lockfile>>>>,
   /// The canonicalized node_modules directory, which is found relative to the config file.
   maybe_node_modules_dir: Option<PathBuf>,
+  /// The `"graph": { "budget": { ... } }` of the config file.
+  maybe_graph_budget: Option<GraphBudget>,
+  /// The npm packages of the `"allowScripts"` of the config file.
+  allow_scripts: Vec<PackageReq>,
+  file_selections: ConfigFileSelections,
+}
+
+impl LspConfigFileInfo {
+  fn new(config_file: ConfigFile, mut config_file_ext: ConfigFileExt) -> Self {
+    if config_file.json.workspaces.is_empty() {
+      if let Some((_, root_config_file_ext, _)) =
+        crate::args::discover_workspace_root(&config_file)
+      {
+        config_file_ext.set_workspace_root(root_config_file_ext);
+      }
+    }
+    Self {
+      maybe_lockfile: resolve_lockfile_from_config(&config_file).map(
+        |lockfile| {
+          let path = canonicalize_path_maybe_not_exists(&lockfile.filename)
+            .unwrap_or_else(|_| lockfile.filename.clone());
+          WithCanonicalizedSpecifier {
+            canonicalized_specifier: ModuleSpecifier::from_file_path(path)
+              .unwrap(),
+            file: Arc::new(Mutex::new(lockfile)),
+          }
+        },
+      ),
+      maybe_node_modules_dir: resolve_node_modules_dir(&config_file),
+      maybe_graph_budget: resolve_graph_budget_from_config(Some(
+        &config_file_ext,
+      )),
+      allow_scripts: resolve_allow_scripts_from_config(Some(&config_file_ext)),
+      config_file_ext,
+      file_selections: resolve_file_selections_from_config(&config_file),
+      member_config_files: resolve_member_config_files(&config_file),
+      config_file: WithCanonicalizedSpecifier {
+        canonicalized_specifier: config_file
+          .specifier
+          .to_file_path()
+          .ok()
+          .and_then(|p| canonicalize_path_maybe_not_exists(&p).ok())
+          .and_then(|p| ModuleSpecifier::from_file_path(p).ok())
+          .unwrap_or_else(|| config_file.specifier.clone()),
+        file: config_file,
+      },
+    }
+  }
 }
 
 #[derive(Debug)]
@@ -838,6 +1036,11 @@ pub struct Config {
   /// An optional configuration file which has been specified in the client
   /// options along with some data that is computed after the config file is set.
   maybe_config_file_info: Option<LspConfigFileInfo>,
+  /// The config files of the workspace folders other than the root, keyed by
+  /// the URI of the folder. They're used instead of the config file of the
+  /// root for the specifiers in their folder, so unrelated projects opened
+  /// in the same window don't share their configuration.
+  folder_config_file_infos: BTreeMap<ModuleSpecifier, LspConfigFileInfo>,
 }
 
 impl Config {
@@ -848,6 +1051,7 @@ impl Config {
       settings: Default::default(),
       workspace_folders: vec![],
       maybe_config_file_info: None,
+      folder_config_file_infos: BTreeMap::new(),
     }
   }
 
@@ -879,6 +1083,15 @@ impl Config {
     self.settings.get_unscoped()
   }
 
+  /// Gets the settings which were set for the workspace folder itself, if
+  /// any.
+  pub fn folder_workspace_settings(
+    &self,
+    folder_uri: &ModuleSpecifier,
+  ) -> Option<&WorkspaceSettings> {
+    self.settings.by_workspace_folder.as_ref()?.get(folder_uri)
+  }
+
   pub fn workspace_settings_for_specifier(
     &self,
     specifier: &ModuleSpecifier,
@@ -952,6 +1165,158 @@ impl Config {
       .map(|c| &c.config_file.file)
   }
 
+  pub fn maybe_config_file_ext(&self) -> Option<&ConfigFileExt> {
+    self
+      .maybe_config_file_info
+      .as_ref()
+      .map(|c| &c.config_file_ext)
+  }
+
+  pub fn member_config_files(&self) -> &[ConfigFile] {
+    self
+      .maybe_config_file_info
+      .as_ref()
+      .map(|c| c.member_config_files.as_slice())
+      .unwrap_or_default()
+  }
+
+  /// Whether the specifier is the config file or the config file of one of
+  /// its workspace members.
+  pub fn is_config_file(&self, specifier: &ModuleSpecifier) -> bool {
+    self
+      .maybe_config_file_info
+      .iter()
+      .chain(self.folder_config_file_infos.values())
+      .flat_map(|info| {
+        std::iter::once(&info.config_file.file)
+          .chain(info.member_config_files.iter())
+      })
+      .any(|config_file| config_file.specifier == *specifier)
+  }
+
+  fn config_file_info_for_specifier(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> Option<&LspConfigFileInfo> {
+    match find_scope(&self.folder_config_file_infos, specifier) {
+      Some((_, info)) => Some(info),
+      None => self.maybe_config_file_info.as_ref(),
+    }
+  }
+
+  /// Gets the config file which applies to the specifier, which is the one
+  /// of the innermost workspace folder with its own config file or else the
+  /// one of the root.
+  pub fn config_file_for_specifier(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> Option<&ConfigFile> {
+    self
+      .config_file_info_for_specifier(specifier)
+      .map(|c| &c.config_file.file)
+  }
+
+  /// Gets the sections unknown to `deno_config` of the config file which
+  /// applies to the specifier.
+  pub fn config_file_ext_for_specifier(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> Option<&ConfigFileExt> {
+    self
+      .config_file_info_for_specifier(specifier)
+      .map(|c| &c.config_file_ext)
+  }
+
+  pub fn member_config_files_for_specifier(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> &[ConfigFile] {
+    self
+      .config_file_info_for_specifier(specifier)
+      .map(|c| c.member_config_files.as_slice())
+      .unwrap_or_default()
+  }
+
+  pub fn file_selections_for_specifier(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> &ConfigFileSelections {
+    self
+      .config_file_info_for_specifier(specifier)
+      .map(|c| &c.file_selections)
+      .unwrap_or(&EMPTY_FILE_SELECTIONS)
+  }
+
+  pub fn lockfile_for_specifier(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> Option<&Arc<Mutex<Lockfile>>> {
+    self
+      .config_file_info_for_specifier(specifier)
+      .and_then(|c| c.maybe_lockfile.as_ref().map(|l| &l.file))
+  }
+
+  pub fn node_modules_dir_path_for_specifier(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> Option<&PathBuf> {
+    self
+      .config_file_info_for_specifier(specifier)
+      .and_then(|c| c.maybe_node_modules_dir.as_ref())
+  }
+
+  /// Gets the URI of the innermost workspace folder with its own config file
+  /// which contains the specifier.
+  pub fn folder_uri_for_specifier(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> Option<&ModuleSpecifier> {
+    find_scope(&self.folder_config_file_infos, specifier)
+      .map(|(folder_uri, _)| folder_uri)
+  }
+
+  /// The config files of the workspace folders other than the root by the
+  /// URI of the folder.
+  pub fn folder_config_files(
+    &self,
+  ) -> impl Iterator<Item = (&ModuleSpecifier, &ConfigFile)> {
+    self
+      .folder_config_file_infos
+      .iter()
+      .map(|(folder_uri, info)| (folder_uri, &info.config_file.file))
+  }
+
+  /// Canonicalized specifiers of the config files and lockfiles of the
+  /// workspace folders other than the root, which should only be used for
+  /// file watcher events.
+  pub fn folder_canonicalized_specifiers(
+    &self,
+  ) -> impl Iterator<Item = &ModuleSpecifier> {
+    self.folder_config_file_infos.values().flat_map(|info| {
+      std::iter::once(&info.config_file.canonicalized_specifier).chain(
+        info
+          .maybe_lockfile
+          .as_ref()
+          .map(|l| &l.canonicalized_specifier),
+      )
+    })
+  }
+
+  pub fn set_folder_config_files(
+    &mut self,
+    config_files: Vec<(ModuleSpecifier, ConfigFile, ConfigFileExt)>,
+  ) {
+    self.folder_config_file_infos = config_files
+      .into_iter()
+      .map(|(folder_uri, config_file, config_file_ext)| {
+        (
+          folder_uri,
+          LspConfigFileInfo::new(config_file, config_file_ext),
+        )
+      })
+      .collect();
+  }
+
   /// Canonicalized specifier of the config file, which should only be used for
   /// file watcher events. Otherwise, prefer using the non-canonicalized path
   /// as the rest of the CLI does for config files.
@@ -986,52 +1351,64 @@ impl Config {
 
   pub fn clear_config_file(&mut self) {
     self.maybe_config_file_info = None;
+    self.folder_config_file_infos.clear();
   }
 
   pub fn has_config_file(&self) -> bool {
     self.maybe_config_file_info.is_some()
   }
 
-  pub fn set_config_file(&mut self, config_file: ConfigFile) {
-    self.maybe_config_file_info = Some(LspConfigFileInfo {
-      maybe_lockfile: resolve_lockfile_from_config(&config_file).map(
-        |lockfile| {
-          let path = canonicalize_path_maybe_not_exists(&lockfile.filename)
-            .unwrap_or_else(|_| lockfile.filename.clone());
-          WithCanonicalizedSpecifier {
-            canonicalized_specifier: ModuleSpecifier::from_file_path(path)
-              .unwrap(),
-            file: Arc::new(Mutex::new(lockfile)),
-          }
-        },
-      ),
-      maybe_node_modules_dir: resolve_node_modules_dir(&config_file),
-      config_file: WithCanonicalizedSpecifier {
-        canonicalized_specifier: config_file
-          .specifier
-          .to_file_path()
-          .ok()
-          .and_then(|p| canonicalize_path_maybe_not_exists(&p).ok())
-          .and_then(|p| ModuleSpecifier::from_file_path(p).ok())
-          .unwrap_or_else(|| config_file.specifier.clone()),
-        file: config_file,
-      },
-    });
+  pub fn set_config_file(
+    &mut self,
+    config_file: ConfigFile,
+    config_file_ext: ConfigFileExt,
+  ) {
+    self.maybe_config_file_info =
+      Some(LspConfigFileInfo::new(config_file, config_file_ext));
   }
 
   pub fn snapshot(&self) -> Arc<ConfigSnapshot> {
     Arc::new(ConfigSnapshot {
       client_capabilities: self.client_capabilities.clone(),
       config_file: self.maybe_config_file().cloned(),
+      member_config_files: self.member_config_files().to_vec(),
+      graph_budget: self
+        .maybe_config_file_info
+        .as_ref()
+        .and_then(|c| c.maybe_graph_budget.clone()),
+      allow_scripts: self
+        .maybe_config_file_info
+        .as_ref()
+        .map(|c| c.allow_scripts.clone())
+        .unwrap_or_default(),
+      file_selections: self
+        .maybe_config_file_info
+        .as_ref()
+        .map(|c| c.file_selections.clone())
+        .unwrap_or_default(),
       settings: self.settings.clone(),
       workspace_folders: self.workspace_folders.clone(),
+      folder_config_files: self
+        .folder_config_file_infos
+        .iter()
+        .map(|(folder_uri, info)| {
+          let files = FolderConfigFiles {
+            config_file: info.config_file.file.clone(),
+            member_config_files: info.member_config_files.clone(),
+            file_selections: info.file_selections.clone(),
+          };
+          (folder_uri.clone(), files)
+        })
+        .collect(),
     })
   }
 
   pub fn specifier_enabled(&self, specifier: &ModuleSpecifier) -> bool {
     specifier_enabled(
       specifier,
-      self.maybe_config_file(),
+      self.config_file_for_specifier(specifier),
+      self.member_config_files_for_specifier(specifier),
+      self.file_selections_for_specifier(specifier),
       &self.settings,
       &self.workspace_folders,
     )
@@ -1041,9 +1418,11 @@ impl Config {
     &self,
     specifier: &ModuleSpecifier,
   ) -> bool {
-    if let Some(cf) = self.maybe_config_file() {
+    if let Some(cf) = self.config_file_for_specifier(specifier) {
       if let Some(options) = cf.to_test_config().ok().flatten() {
-        if !options.files.matches_specifier(specifier) {
+        let file_selection =
+          &self.file_selections_for_specifier(specifier).test;
+        if !file_selection.matches_specifier(&options.files, specifier) {
           return false;
         }
       }
@@ -1082,7 +1461,11 @@ impl Config {
 
   pub fn get_disabled_paths(&self) -> PathOrPatternSet {
     let mut path_or_patterns = vec![];
-    if let Some(cf) = self.maybe_config_file() {
+    for cf in self
+      .maybe_config_file()
+      .into_iter()
+      .chain(self.folder_config_files().map(|(_, cf)| cf))
+    {
       if let Some(files) = cf.to_files_config().ok().flatten() {
         for path in files.exclude.into_path_or_patterns() {
           path_or_patterns.push(path);
@@ -1095,7 +1478,9 @@ impl Config {
         continue;
       };
       let settings = self.workspace_settings_for_specifier(workspace_uri);
-      if settings.enable.unwrap_or_else(|| self.has_config_file()) {
+      let has_config_file =
+        self.config_file_for_specifier(workspace_uri).is_some();
+      if settings.enable.unwrap_or(has_config_file) {
         for path in &settings.disable_paths {
           path_or_patterns.push(PathOrPattern::Path(workspace_path.join(path)));
         }
@@ -1146,6 +1531,11 @@ impl Config {
     }
 
     if let Some(text_document) = &capabilities.text_document {
+      self.client_capabilities.type_hierarchy = text_document
+        .type_hierarchy
+        .as_ref()
+        .and_then(|it| it.dynamic_registration)
+        .unwrap_or(false);
       self.client_capabilities.line_folding_only = text_document
         .folding_range
         .as_ref()
@@ -1170,17 +1560,48 @@ impl Config {
   }
 }
 
+/// Gets the config file of the innermost workspace member containing the
+/// specifier.
+fn member_config_file_for_specifier<'a>(
+  specifier: &Url,
+  member_config_files: &'a [ConfigFile],
+) -> Option<&'a ConfigFile> {
+  let path = specifier_to_file_path(specifier).ok()?;
+  member_config_files
+    .iter()
+    .filter_map(|member| {
+      let member_path = specifier_to_file_path(&member.specifier).ok()?;
+      let member_dir = member_path.parent()?.to_path_buf();
+      path
+        .starts_with(&member_dir)
+        .then_some((member_dir, member))
+    })
+    .max_by(|a, b| a.0.cmp(&b.0))
+    .map(|(_, member)| member)
+}
+
 fn specifier_enabled(
   specifier: &Url,
   config_file: Option<&ConfigFile>,
+  member_config_files: &[ConfigFile],
+  file_selections: &ConfigFileSelections,
   settings: &Settings,
   workspace_folders: &[(Url, lsp::WorkspaceFolder)],
 ) -> bool {
-  if let Some(cf) = config_file {
-    if let Some(files) = cf.to_files_config().ok().flatten() {
-      if !files.matches_specifier(specifier) {
-        return false;
-      }
+  if let Some(files) =
+    config_file.and_then(|cf| cf.to_files_config().ok().flatten())
+  {
+    if !file_selections.files.matches_specifier(&files, specifier) {
+      return false;
+    }
+  }
+  let maybe_member_config_file =
+    member_config_file_for_specifier(specifier, member_config_files);
+  if let Some(files) =
+    maybe_member_config_file.and_then(|cf| cf.to_files_config().ok().flatten())
+  {
+    if !files.matches_specifier(specifier) {
+      return false;
     }
   }
   let Ok(path) = specifier_to_file_path(specifier) else {
@@ -1221,6 +1642,74 @@ fn specifier_enabled(
   }
 }
 
+fn resolve_member_config_files(config_file: &ConfigFile) -> Vec<ConfigFile> {
+  match config_file.to_workspace_config() {
+    Ok(Some(workspace_config)) => workspace_config
+      .members
+      .into_iter()
+      .map(|member| {
+        lsp_log!(
+          "  Resolved workspace member config file: \"{}\"",
+          member.config_file.specifier
+        );
+        member.config_file
+      })
+      .collect(),
+    Ok(None) => Vec::new(),
+    Err(err) => {
+      lsp_warn!("Error resolving workspace members: {:#}", err);
+      Vec::new()
+    }
+  }
+}
+
+fn resolve_graph_budget_from_config(
+  maybe_config_file_ext: Option<&ConfigFileExt>,
+) -> Option<GraphBudget> {
+  let config_file_ext = maybe_config_file_ext?;
+  match config_file_ext.to_graph_budget() {
+    Ok(maybe_graph_budget) => maybe_graph_budget,
+    Err(err) => {
+      lsp_warn!("Error resolving graph budget: {:#}", err);
+      None
+    }
+  }
+}
+
+fn resolve_allow_scripts_from_config(
+  maybe_config_file_ext: Option<&ConfigFileExt>,
+) -> Vec<PackageReq> {
+  match resolve_allow_scripts(maybe_config_file_ext) {
+    Ok(allow_scripts) => allow_scripts,
+    Err(err) => {
+      lsp_warn!("Error resolving allowScripts: {:#}", err);
+      Vec::new()
+    }
+  }
+}
+
+fn resolve_file_selections_from_config(
+  config_file: &ConfigFile,
+) -> ConfigFileSelections {
+  let resolve = |maybe_section| match resolve_file_selection(
+    Some(config_file),
+    maybe_section,
+    false,
+  ) {
+    Ok(file_selection) => file_selection,
+    Err(err) => {
+      lsp_warn!("Error resolving exclude: {:#}", err);
+      Default::default()
+    }
+  };
+  ConfigFileSelections {
+    files: resolve(None),
+    fmt: resolve(Some("fmt")),
+    lint: resolve(Some("lint")),
+    test: resolve(Some("test")),
+  }
+}
+
 fn resolve_lockfile_from_config(config_file: &ConfigFile) -> Option<Lockfile> {
   let lockfile_path = match config_file.resolve_lockfile_path() {
     Ok(Some(value)) => value,
@@ -1242,11 +1731,20 @@ fn resolve_node_modules_dir(config_file: &ConfigFile) -> Option<PathBuf> {
   if explicitly_disabled {
     return None;
   }
-  let enabled = config_file.node_modules_dir_flag() == Some(true)
-    || config_file.vendor_dir_flag() == Some(true);
+  // the members of a workspace share the node_modules directory of the root
+  let maybe_root_config_file =
+    crate::args::discover_workspace_root(config_file).map(|(c, _, _)| c);
+  let enabled = [Some(config_file), maybe_root_config_file.as_ref()]
+    .into_iter()
+    .flatten()
+    .any(|c| {
+      c.node_modules_dir_flag() == Some(true)
+        || c.vendor_dir_flag() == Some(true)
+    });
   if !enabled {
     return None;
   }
+  let config_file = maybe_root_config_file.as_ref().unwrap_or(config_file);
   if config_file.specifier.scheme() != "file" {
     return None;
   }
@@ -1273,6 +1771,7 @@ fn resolve_lockfile_from_path(lockfile_path: PathBuf) -> Option<Lockfile> {
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::args::parse_config_file;
   use deno_core::resolve_url;
   use deno_core::serde_json;
   use deno_core::serde_json::json;
@@ -1359,6 +1858,7 @@ mod tests {
         certificate_stores: None,
         config: None,
         import_map: None,
+        untitled_root: None,
         code_lens: CodeLensSettings {
           implementations: false,
           references: false,
@@ -1370,6 +1870,8 @@ mod tests {
         log_file: false,
         lint: true,
         document_preload_limit: 1_000,
+        document_preprocessors: Default::default(),
+        max_document_cache_mb: None,
         suggest: DenoCompletionSettings {
           imports: ImportCompletionSettings {
             auto_discover: true,
@@ -1378,10 +1880,17 @@ mod tests {
         },
         testing: TestingSettings {
           args: vec!["--allow-all".to_string(), "--no-check".to_string()],
+          run_on_save: false,
+          describe_identifiers: vec![],
+          it_identifiers: vec![],
         },
         tls_certificate: None,
         unsafely_ignore_certificate_errors: None,
         unstable: false,
+        use_gitignore: true,
+        workspace_symbols: WorkspaceSymbolsSettings {
+          include_dependencies: false,
+        },
         javascript: LanguageWorkspaceSettings {
           inlay_hints: InlayHintsSettings {
             parameter_names: InlayHintsParamNamesOptions {
@@ -1528,6 +2037,61 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_inlay_hints_language_settings() {
+    let mut config = Config::new();
+    config.set_workspace_settings(
+      WorkspaceSettings::from_raw_settings(
+        json!({
+          "inlayHints": {
+            "enumMemberValues": { "enabled": true },
+          },
+        }),
+        json!({}),
+        json!({
+          "inlayHints": {
+            "parameterNames": {
+              "enabled": "literals",
+              "suppressWhenArgumentMatchesName": false,
+            },
+            "functionLikeReturnTypes": { "enabled": true },
+          },
+        }),
+      ),
+      None,
+    );
+    let js_specifier = resolve_url("file:///a/mod.js").unwrap();
+    let ts_specifier = resolve_url("file:///a/mod.ts").unwrap();
+    let js_inlay_hints = &config
+      .language_settings_for_specifier(&js_specifier)
+      .unwrap()
+      .inlay_hints;
+    assert!(js_inlay_hints.enum_member_values.enabled);
+    assert!(!js_inlay_hints.function_like_return_types.enabled);
+    assert!(
+      js_inlay_hints
+        .parameter_names
+        .suppress_when_argument_matches_name
+    );
+    let ts_inlay_hints = &config
+      .language_settings_for_specifier(&ts_specifier)
+      .unwrap()
+      .inlay_hints;
+    assert_eq!(
+      ts_inlay_hints.parameter_names,
+      InlayHintsParamNamesOptions {
+        enabled: InlayHintsParamNamesEnabled::Literals,
+        suppress_when_argument_matches_name: false,
+      }
+    );
+    assert!(ts_inlay_hints.function_like_return_types.enabled);
+    assert!(ts_inlay_hints.enum_member_values.enabled);
+    assert!(config.enabled_inlay_hints_for_specifier(&js_specifier));
+    assert!(!config.enabled_inlay_hints_for_specifier(
+      &resolve_url("file:///a/data.json").unwrap()
+    ));
+  }
+
   #[test]
   fn config_get_enabled_paths() {
     let mut config = Config::new();
@@ -1608,9 +2172,9 @@ mod tests {
     config.settings.unscoped.enable = None;
     assert!(!config.specifier_enabled(&root_uri));
 
-    config.set_config_file(
-      ConfigFile::new("{}", root_uri.join("deno.json").unwrap()).unwrap(),
-    );
+    let (config_file, config_file_ext) =
+      parse_config_file("{}", root_uri.join("deno.json").unwrap()).unwrap();
+    config.set_config_file(config_file, config_file_ext);
     assert!(config.specifier_enabled(&root_uri));
   }
 
@@ -1643,19 +2207,18 @@ mod tests {
     );
     config.settings.unscoped.enable_paths = None;
 
-    config.set_config_file(
-      ConfigFile::new(
-        &json!({
-          "exclude": ["mod2.ts"],
-          "test": {
-            "exclude": ["mod3.ts"],
-          },
-        })
-        .to_string(),
-        root_uri.join("deno.json").unwrap(),
-      )
-      .unwrap(),
-    );
+    let (config_file, config_file_ext) = parse_config_file(
+      &json!({
+        "exclude": ["mod2.ts"],
+        "test": {
+          "exclude": ["mod3.ts"],
+        },
+      })
+      .to_string(),
+      root_uri.join("deno.json").unwrap(),
+    )
+    .unwrap();
+    config.set_config_file(config_file, config_file_ext);
     assert!(
       config.specifier_enabled_for_test(&root_uri.join("mod1.ts").unwrap())
     );
@@ -1666,18 +2229,17 @@ mod tests {
       !config.specifier_enabled_for_test(&root_uri.join("mod3.ts").unwrap())
     );
 
-    config.set_config_file(
-      ConfigFile::new(
-        &json!({
-          "test": {
-            "include": ["mod1.ts"],
-          },
-        })
-        .to_string(),
-        root_uri.join("deno.json").unwrap(),
-      )
-      .unwrap(),
-    );
+    let (config_file, config_file_ext) = parse_config_file(
+      &json!({
+        "test": {
+          "include": ["mod1.ts"],
+        },
+      })
+      .to_string(),
+      root_uri.join("deno.json").unwrap(),
+    )
+    .unwrap();
+    config.set_config_file(config_file, config_file_ext);
     assert!(
       config.specifier_enabled_for_test(&root_uri.join("mod1.ts").unwrap())
     );
@@ -1685,19 +2247,18 @@ mod tests {
       !config.specifier_enabled_for_test(&root_uri.join("mod2.ts").unwrap())
     );
 
-    config.set_config_file(
-      ConfigFile::new(
-        &json!({
-          "test": {
-            "exclude": ["mod2.ts"],
-            "include": ["mod2.ts"],
-          },
-        })
-        .to_string(),
-        root_uri.join("deno.json").unwrap(),
-      )
-      .unwrap(),
-    );
+    let (config_file, config_file_ext) = parse_config_file(
+      &json!({
+        "test": {
+          "exclude": ["mod2.ts"],
+          "include": ["mod2.ts"],
+        },
+      })
+      .to_string(),
+      root_uri.join("deno.json").unwrap(),
+    )
+    .unwrap();
+    config.set_config_file(config_file, config_file_ext);
     assert!(
       !config.specifier_enabled_for_test(&root_uri.join("mod1.ts").unwrap())
     );
@@ -1711,19 +2272,18 @@ mod tests {
     let root_uri = resolve_url("file:///root/").unwrap();
     let mut config = Config::new_with_root(root_uri.clone());
     config.settings.unscoped.enable = Some(true);
-    config.set_config_file(
-      ConfigFile::new(
-        &json!({
-          "exclude": ["mod2.ts"],
-          "test": {
-            "exclude": ["mod3.ts"],
-          },
-        })
-        .to_string(),
-        root_uri.join("deno.json").unwrap(),
-      )
-      .unwrap(),
-    );
+    let (config_file, config_file_ext) = parse_config_file(
+      &json!({
+        "exclude": ["mod2.ts"],
+        "test": {
+          "exclude": ["mod3.ts"],
+        },
+      })
+      .to_string(),
+      root_uri.join("deno.json").unwrap(),
+    )
+    .unwrap();
+    config.set_config_file(config_file, config_file_ext);
     let config_snapshot = config.snapshot();
     assert!(config_snapshot
       .specifier_enabled_for_test(&root_uri.join("mod1.ts").unwrap()));
@@ -1732,4 +2292,91 @@ mod tests {
     assert!(!config_snapshot
       .specifier_enabled_for_test(&root_uri.join("mod3.ts").unwrap()));
   }
+
+  #[test]
+  fn config_folder_config_files() {
+    let root_uri = resolve_url("file:///root/a/").unwrap();
+    let folder_uri = resolve_url("file:///root/b/").unwrap();
+    let mut config = Config::new_with_root(root_uri.clone());
+    config.workspace_folders.push((
+      folder_uri.clone(),
+      lsp::WorkspaceFolder {
+        uri: folder_uri.clone(),
+        name: "b".to_string(),
+      },
+    ));
+    let (config_file, config_file_ext) = parse_config_file(
+      &json!({ "exclude": ["mod2.ts"] }).to_string(),
+      root_uri.join("deno.json").unwrap(),
+    )
+    .unwrap();
+    config.set_config_file(config_file, config_file_ext);
+    let (folder_config_file, folder_config_file_ext) = parse_config_file(
+      &json!({ "exclude": ["mod1.ts"] }).to_string(),
+      folder_uri.join("deno.json").unwrap(),
+    )
+    .unwrap();
+    config.set_folder_config_files(vec![(
+      folder_uri.clone(),
+      folder_config_file,
+      folder_config_file_ext,
+    )]);
+
+    let root_mod = root_uri.join("mod1.ts").unwrap();
+    let folder_mod = folder_uri.join("mod1.ts").unwrap();
+    assert_eq!(
+      config
+        .config_file_for_specifier(&root_mod)
+        .unwrap()
+        .specifier,
+      root_uri.join("deno.json").unwrap()
+    );
+    assert_eq!(
+      config
+        .config_file_for_specifier(&folder_mod)
+        .unwrap()
+        .specifier,
+      folder_uri.join("deno.json").unwrap()
+    );
+    assert_eq!(config.folder_uri_for_specifier(&root_mod), None);
+    assert_eq!(
+      config.folder_uri_for_specifier(&folder_mod),
+      Some(&folder_uri)
+    );
+    assert!(config.is_config_file(&folder_uri.join("deno.json").unwrap()));
+
+    // the excluded files of one folder don't apply to the other one
+    let config_snapshot = config.snapshot();
+    for (path, enabled) in [
+      ("a/mod1.ts", true),
+      ("a/mod2.ts", false),
+      ("b/mod1.ts", false),
+      ("b/mod2.ts", true),
+    ] {
+      let specifier = resolve_url(&format!("file:///root/{path}")).unwrap();
+      assert_eq!(config.specifier_enabled(&specifier), enabled, "{path}");
+      assert_eq!(
+        config_snapshot.specifier_enabled(&specifier),
+        enabled,
+        "{path}"
+      );
+    }
+  }
+
+  #[test]
+  fn test_find_scope() {
+    let scopes = BTreeMap::from([
+      (resolve_url("file:///a/").unwrap(), 1),
+      (resolve_url("file:///a/b/").unwrap(), 2),
+      (resolve_url("file:///ab/").unwrap(), 3),
+    ]);
+    let scope = |specifier: &str| {
+      find_scope(&scopes, &resolve_url(specifier).unwrap()).map(|(_, v)| *v)
+    };
+    assert_eq!(scope("file:///a/mod.ts"), Some(1));
+    assert_eq!(scope("file:///a/b/mod.ts"), Some(2));
+    assert_eq!(scope("file:///ab/mod.ts"), Some(3));
+    assert_eq!(scope("file:///abc/mod.ts"), None);
+    assert_eq!(scope("https://deno.land/x/mod.ts"), None);
+  }
 }
@@ -0,0 +1,737 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::HashSet;
+use std::ops::Range;
+
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
+use deno_semver::VersionReq;
+use jsonc_parser::ast::Array;
+use jsonc_parser::ast::Object;
+use jsonc_parser::ast::ObjectPropName;
+use jsonc_parser::ast::Value as JsonValue;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use tower_lsp::lsp_types as lsp;
+
+use super::diagnostics::DiagnosticSource;
+use super::text::LineIndex;
+use crate::args::satisfies_deno_version_req;
+use crate::version;
+
+const PERMISSION_SET_FLAG: &str = "--permission-set=";
+
+static CONFIG_FILE_SCHEMA: Lazy<Value> = Lazy::new(|| {
+  serde_json::from_str(include_str!("../schemas/config-file.v1.json")).unwrap()
+});
+
+/// A task of the "tasks" of a config file, with the ranges of its parts in
+/// the text.
+struct TaskNode<'a> {
+  name: &'a str,
+  command: Option<(&'a str, Range<usize>)>,
+  depends_on: Vec<(&'a str, Range<usize>)>,
+}
+
+fn parse_config_text(text: &str) -> Option<JsonValue> {
+  jsonc_parser::parse_to_ast(text, &Default::default(), &Default::default())
+    .ok()?
+    .value
+}
+
+fn prop_name<'a>(name: &'a ObjectPropName) -> (&'a str, Range<usize>) {
+  match name {
+    ObjectPropName::String(lit) => {
+      (&*lit.value, lit.range.start..lit.range.end)
+    }
+    ObjectPropName::Word(word) => {
+      (word.value, word.range.start..word.range.end)
+    }
+  }
+}
+
+fn value_range(value: &JsonValue) -> Range<usize> {
+  let range = match value {
+    JsonValue::StringLit(lit) => &lit.range,
+    JsonValue::NumberLit(lit) => &lit.range,
+    JsonValue::BooleanLit(lit) => &lit.range,
+    JsonValue::Object(object) => &object.range,
+    JsonValue::Array(array) => &array.range,
+    JsonValue::NullKeyword(keyword) => &keyword.range,
+  };
+  range.start..range.end
+}
+
+fn tasks_object<'a>(root: &'a JsonValue) -> Option<&'a Object<'a>> {
+  let JsonValue::Object(root) = root else {
+    return None;
+  };
+  match &root.get("tasks")?.value {
+    JsonValue::Object(tasks) => Some(tasks),
+    _ => None,
+  }
+}
+
+fn task_nodes<'a>(tasks: &'a Object<'a>) -> Vec<TaskNode<'a>> {
+  let mut nodes = Vec::with_capacity(tasks.properties.len());
+  for prop in &tasks.properties {
+    let (name, _) = prop_name(&prop.name);
+    let mut node = TaskNode {
+      name,
+      command: None,
+      depends_on: Vec::new(),
+    };
+    match &prop.value {
+      JsonValue::StringLit(lit) => {
+        node.command = Some((&*lit.value, lit.range.start..lit.range.end));
+      }
+      JsonValue::Object(object) => {
+        if let Some(JsonValue::StringLit(lit)) =
+          object.get("command").map(|p| &p.value)
+        {
+          node.command = Some((&*lit.value, lit.range.start..lit.range.end));
+        }
+        if let Some(JsonValue::Array(array)) =
+          object.get("dependsOn").map(|p| &p.value)
+        {
+          node.depends_on = string_elements(array);
+        }
+      }
+      _ => {}
+    }
+    nodes.push(node);
+  }
+  nodes
+}
+
+/// The names of the permission sets of the "permissions" of a config file.
+fn permission_set_names<'a>(root: &'a JsonValue) -> Vec<&'a str> {
+  let JsonValue::Object(root) = root else {
+    return Vec::new();
+  };
+  let Some(JsonValue::Object(sets)) = root.get("permissions").map(|p| &p.value)
+  else {
+    return Vec::new();
+  };
+  sets
+    .properties
+    .iter()
+    .filter(|prop| matches!(prop.value, JsonValue::Object(_)))
+    .map(|prop| prop_name(&prop.name).0)
+    .collect()
+}
+
+/// Find the names of the permission sets of the `--permission-set=<NAME>`
+/// flags of a task command, with their ranges in the text. The ranges are
+/// only known when the command has no escapes in the text, since the
+/// offsets of the command differ from the ones of the text otherwise.
+fn permission_set_references<'a>(
+  text: &str,
+  command: &'a str,
+  range: &Range<usize>,
+) -> Vec<(&'a str, Range<usize>)> {
+  let command_start = range.start + 1;
+  if text.get(command_start..range.end - 1) != Some(command) {
+    return Vec::new();
+  }
+  command
+    .match_indices(PERMISSION_SET_FLAG)
+    .map(|(index, _)| {
+      let start = index + PERMISSION_SET_FLAG.len();
+      let end = command[start..]
+        .find(|c: char| {
+          c.is_whitespace() || matches!(c, '\'' | '"' | ';' | '&' | '|' | ')')
+        })
+        .map(|end| start + end)
+        .unwrap_or(command.len());
+      (
+        &command[start..end],
+        command_start + start..command_start + end,
+      )
+    })
+    .collect()
+}
+
+fn string_elements<'a>(array: &'a Array<'a>) -> Vec<(&'a str, Range<usize>)> {
+  array
+    .elements
+    .iter()
+    .filter_map(|element| match element {
+      JsonValue::StringLit(lit) => {
+        Some((&*lit.value, lit.range.start..lit.range.end))
+      }
+      _ => None,
+    })
+    .collect()
+}
+
+fn to_lsp_range(
+  text: &str,
+  line_index: &LineIndex,
+  range: &Range<usize>,
+) -> lsp::Range {
+  let position = |offset: usize| {
+    let utf16_offset = text[..offset].encode_utf16().count() as u32;
+    line_index.position_tsc(utf16_offset.into())
+  };
+  lsp::Range {
+    start: position(range.start),
+    end: position(range.end),
+  }
+}
+
+/// Validate the `"deno"` version requirement of a config file against the
+/// running version of Deno.
+fn get_deno_version_diagnostic(
+  text: &str,
+  line_index: &LineIndex,
+  root: &JsonValue,
+) -> Option<lsp::Diagnostic> {
+  let JsonValue::Object(root) = root else {
+    return None;
+  };
+  let JsonValue::StringLit(lit) = &root.get("deno")?.value else {
+    return None;
+  };
+  let (code, message, data) = match VersionReq::parse_from_npm(&lit.value) {
+    Ok(version_req) if satisfies_deno_version_req(&version_req) => {
+      return None;
+    }
+    Ok(_) => (
+      "deno-version-unsatisfied",
+      format!(
+        "Deno {} doesn't satisfy the version requirement \"{}\" of the configuration file.",
+        version::deno(),
+        lit.value
+      ),
+      Some(json!({ "versionReq": lit.value })),
+    ),
+    Err(err) => (
+      "invalid-deno-version-req",
+      format!(
+        "Invalid Deno version requirement \"{}\": {:#}",
+        lit.value, err
+      ),
+      None,
+    ),
+  };
+  Some(lsp::Diagnostic {
+    range: to_lsp_range(text, line_index, &(lit.range.start..lit.range.end)),
+    severity: Some(lsp::DiagnosticSeverity::ERROR),
+    code: Some(lsp::NumberOrString::String(code.to_string())),
+    source: Some(DiagnosticSource::Config.as_lsp_source().to_string()),
+    message,
+    data,
+    ..Default::default()
+  })
+}
+
+/// Validate the `"deno"` version requirement of a config file and its tasks,
+/// which are the commands that are run by the cross-platform shell of
+/// `deno task`, the tasks which they depend on and the permission sets of
+/// their `--permission-set` flags. Syntax errors of the file itself are left
+/// to the JSON support of the editor.
+pub fn get_config_file_diagnostics(
+  text: &str,
+  line_index: &LineIndex,
+) -> Vec<lsp::Diagnostic> {
+  let Some(root) = parse_config_text(text) else {
+    return Vec::new();
+  };
+  let mut diagnostics = Vec::new();
+  diagnostics.extend(get_deno_version_diagnostic(text, line_index, &root));
+  let Some(tasks) = tasks_object(&root) else {
+    return diagnostics;
+  };
+  let nodes = task_nodes(tasks);
+  let task_names = nodes.iter().map(|n| n.name).collect::<HashSet<_>>();
+  let permission_set_names = permission_set_names(&root);
+  let mut push = |range: &Range<usize>, code: &str, message: String| {
+    diagnostics.push(lsp::Diagnostic {
+      range: to_lsp_range(text, line_index, range),
+      severity: Some(lsp::DiagnosticSeverity::ERROR),
+      code: Some(lsp::NumberOrString::String(code.to_string())),
+      source: Some(DiagnosticSource::Config.as_lsp_source().to_string()),
+      message,
+      ..Default::default()
+    });
+  };
+  for node in &nodes {
+    if let Some((command, range)) = &node.command {
+      if !command.trim().is_empty() {
+        if let Err(err) = deno_task_shell::parser::parse(command) {
+          push(
+            range,
+            "invalid-task-command",
+            format!("Invalid command of task \"{}\": {:#}", node.name, err),
+          );
+        }
+      }
+      for (name, range) in permission_set_references(text, command, range) {
+        // an empty name is still being typed
+        if !name.is_empty() && !permission_set_names.contains(&name) {
+          push(
+            &range,
+            "unknown-permission-set",
+            format!(
+              "Task \"{}\" uses unknown permission set \"{}\".",
+              node.name, name
+            ),
+          );
+        }
+      }
+    }
+    for (dependency, range) in &node.depends_on {
+      if *dependency == node.name {
+        push(
+          range,
+          "unknown-task-dependency",
+          format!("Task \"{}\" can't depend on itself.", node.name),
+        );
+      } else if !task_names.contains(dependency) {
+        push(
+          range,
+          "unknown-task-dependency",
+          format!(
+            "Task \"{}\" depends on unknown task \"{}\".",
+            node.name, dependency
+          ),
+        );
+      }
+    }
+  }
+  diagnostics
+}
+
+/// Get the quick fixes of the diagnostics of a config file, which is running
+/// `deno upgrade` when the `"deno"` version requirement isn't satisfied.
+pub fn get_config_file_code_actions(
+  diagnostics: &[lsp::Diagnostic],
+) -> Option<lsp::CodeActionResponse> {
+  let actions = diagnostics
+    .iter()
+    .filter(|d| {
+      d.source.as_deref() == Some(DiagnosticSource::Config.as_lsp_source())
+        && d.code
+          == Some(lsp::NumberOrString::String(
+            "deno-version-unsatisfied".to_string(),
+          ))
+    })
+    .map(|d| {
+      lsp::CodeActionOrCommand::CodeAction(lsp::CodeAction {
+        title: "Upgrade Deno".to_string(),
+        kind: Some(lsp::CodeActionKind::QUICKFIX),
+        diagnostics: Some(vec![d.clone()]),
+        command: Some(lsp::Command {
+          title: "Upgrade Deno".to_string(),
+          command: "deno.upgrade".to_string(),
+          arguments: None,
+        }),
+        ..Default::default()
+      })
+    })
+    .collect::<Vec<_>>();
+  if actions.is_empty() {
+    None
+  } else {
+    Some(actions)
+  }
+}
+
+/// Complete the names of the tasks in the "dependsOn" of a task and the
+/// names of the permission sets of the `--permission-set` flags of a task
+/// command.
+pub fn get_config_file_completions(
+  text: &str,
+  line_index: &LineIndex,
+  position: lsp::Position,
+) -> Option<lsp::CompletionResponse> {
+  let offset = usize::from(line_index.offset(position).ok()?);
+  let root = parse_config_text(text)?;
+  let nodes = task_nodes(tasks_object(&root)?);
+  let items = complete_task_dependencies(text, line_index, offset, &nodes)
+    .or_else(|| {
+      complete_permission_sets(text, line_index, offset, &root, &nodes)
+    })?;
+  Some(lsp::CompletionResponse::List(lsp::CompletionList {
+    is_incomplete: false,
+    items,
+  }))
+}
+
+fn complete_task_dependencies(
+  text: &str,
+  line_index: &LineIndex,
+  offset: usize,
+  nodes: &[TaskNode],
+) -> Option<Vec<lsp::CompletionItem>> {
+  let (node, range) = nodes.iter().find_map(|node| {
+    node
+      .depends_on
+      .iter()
+      .find(|(_, range)| range.start < offset && offset < range.end)
+      .map(|(_, range)| (node, range))
+  })?;
+  // leave out the task itself and its other dependencies
+  let is_other_dependency = |name: &str| {
+    node
+      .depends_on
+      .iter()
+      .any(|(other, other_range)| other_range != range && *other == name)
+  };
+  let items = nodes
+    .iter()
+    .filter(|other| other.name != node.name && !is_other_dependency(other.name))
+    .map(|other| lsp::CompletionItem {
+      label: other.name.to_string(),
+      kind: Some(lsp::CompletionItemKind::VALUE),
+      detail: other
+        .command
+        .as_ref()
+        .map(|(command, _)| command.to_string()),
+      text_edit: Some(lsp::CompletionTextEdit::Edit(lsp::TextEdit {
+        // replace the contents of the string, without the quotes
+        range: to_lsp_range(
+          text,
+          line_index,
+          &(range.start + 1..range.end - 1),
+        ),
+        new_text: other.name.to_string(),
+      })),
+      ..Default::default()
+    })
+    .collect();
+  Some(items)
+}
+
+fn complete_permission_sets(
+  text: &str,
+  line_index: &LineIndex,
+  offset: usize,
+  root: &JsonValue,
+  nodes: &[TaskNode],
+) -> Option<Vec<lsp::CompletionItem>> {
+  let range = nodes.iter().find_map(|node| {
+    let (command, range) = node.command.as_ref()?;
+    permission_set_references(text, command, range)
+      .into_iter()
+      .map(|(_, range)| range)
+      .find(|range| range.start <= offset && offset <= range.end)
+  })?;
+  let items = permission_set_names(root)
+    .into_iter()
+    .map(|name| lsp::CompletionItem {
+      label: name.to_string(),
+      kind: Some(lsp::CompletionItemKind::VALUE),
+      text_edit: Some(lsp::CompletionTextEdit::Edit(lsp::TextEdit {
+        range: to_lsp_range(text, line_index, &range),
+        new_text: name.to_string(),
+      })),
+      ..Default::default()
+    })
+    .collect();
+  Some(items)
+}
+
+/// Get the documentation of the property of a config file under the
+/// position from the config file schema.
+pub fn get_config_file_hover(
+  text: &str,
+  line_index: &LineIndex,
+  position: lsp::Position,
+) -> Option<lsp::Hover> {
+  let offset = usize::from(line_index.offset(position).ok()?);
+  let root = parse_config_text(text)?;
+  let mut path = Vec::new();
+  let range = find_property_at_offset(&root, offset, &mut path)?;
+  let mut schema = &*CONFIG_FILE_SCHEMA;
+  for key in &path {
+    schema = property_schema(schema, key)?;
+  }
+  let description = schema_description(schema)?;
+  Some(lsp::Hover {
+    contents: lsp::HoverContents::Markup(lsp::MarkupContent {
+      kind: lsp::MarkupKind::Markdown,
+      value: description.to_string(),
+    }),
+    range: Some(to_lsp_range(text, line_index, &range)),
+  })
+}
+
+/// Find the name of the property which contains the offset, collecting the
+/// names of the properties on the way there.
+fn find_property_at_offset<'a>(
+  value: &'a JsonValue,
+  offset: usize,
+  path: &mut Vec<&'a str>,
+) -> Option<Range<usize>> {
+  match value {
+    JsonValue::Object(object) => {
+      for prop in &object.properties {
+        let (name, name_range) = prop_name(&prop.name);
+        if name_range.start <= offset && offset < name_range.end {
+          path.push(name);
+          return Some(name_range);
+        }
+        let range = value_range(&prop.value);
+        if range.start <= offset && offset < range.end {
+          path.push(name);
+          return find_property_at_offset(&prop.value, offset, path);
+        }
+      }
+      None
+    }
+    _ => None,
+  }
+}
+
+fn property_schema<'a>(schema: &'a Value, key: &str) -> Option<&'a Value> {
+  if let Some(property) = schema.get("properties").and_then(|p| p.get(key)) {
+    return Some(property);
+  }
+  if let Some(patterns) =
+    schema.get("patternProperties").and_then(|p| p.as_object())
+  {
+    for (pattern, property) in patterns {
+      if Regex::new(pattern)
+        .map(|re| re.is_match(key))
+        .unwrap_or(false)
+      {
+        return Some(property);
+      }
+    }
+  }
+  ["oneOf", "anyOf", "allOf"]
+    .iter()
+    .filter_map(|name| schema.get(name).and_then(|s| s.as_array()))
+    .flatten()
+    .find_map(|alternative| property_schema(alternative, key))
+}
+
+fn schema_description(schema: &Value) -> Option<&str> {
+  schema
+    .get("markdownDescription")
+    .or_else(|| schema.get("description"))
+    .and_then(|d| d.as_str())
+    .or_else(|| {
+      schema
+        .get("oneOf")
+        .and_then(|s| s.as_array())?
+        .iter()
+        .find_map(schema_description)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  const CONFIG_TEXT: &str = r#"{
+  "tasks": {
+    "build": "deno run build.ts &&",
+    "dev": {
+      "command": "deno run main.ts",
+      "dependsOn": ["build", "fetch", ""]
+    }
+  },
+  "lint": {
+    "rules": {}
+  }
+}
+"#;
+
+  fn position_of(text: &str, needle: &str) -> lsp::Position {
+    let line_index = LineIndex::new(text);
+    let offset = text.find(needle).unwrap() as u32;
+    line_index.position_tsc(offset.into())
+  }
+
+  #[test]
+  fn test_config_file_diagnostics() {
+    let line_index = LineIndex::new(CONFIG_TEXT);
+    let diagnostics = get_config_file_diagnostics(CONFIG_TEXT, &line_index)
+      .into_iter()
+      .map(|d| {
+        let code = match d.code {
+          Some(lsp::NumberOrString::String(code)) => code,
+          _ => unreachable!(),
+        };
+        (d.range.start.line, code)
+      })
+      .collect::<Vec<_>>();
+    assert_eq!(
+      diagnostics,
+      vec![
+        (2, "invalid-task-command".to_string()),
+        (5, "unknown-task-dependency".to_string()),
+        (5, "unknown-task-dependency".to_string()),
+      ]
+    );
+    assert!(get_config_file_diagnostics("{", &LineIndex::new("{")).is_empty());
+
+    // the syntax of the shell of `deno task` beyond plain commands
+    let text = r#"{
+  "tasks": {
+    "version": "export VERSION=$(git describe --tags) && echo $VERSION",
+    "build": "(cd dist && ls ~/dist) || echo failed | cat"
+  }
+}"#;
+    assert!(get_config_file_diagnostics(text, &LineIndex::new(text)).is_empty());
+  }
+
+  #[test]
+  fn test_config_file_deno_version() {
+    let diagnostics = |text: &str| {
+      get_config_file_diagnostics(text, &LineIndex::new(text))
+        .into_iter()
+        .map(|d| match d.code {
+          Some(lsp::NumberOrString::String(code)) => (d.range.start, code),
+          _ => unreachable!(),
+        })
+        .collect::<Vec<_>>()
+    };
+    assert!(diagnostics(r#"{ "deno": ">=1.0 <100" }"#).is_empty());
+    assert_eq!(
+      diagnostics(r#"{ "deno": "<1" }"#),
+      vec![(
+        lsp::Position::new(0, 10),
+        "deno-version-unsatisfied".to_string()
+      )]
+    );
+
+    let text = r#"{ "deno": "<1" }"#;
+    let all_diagnostics =
+      get_config_file_diagnostics(text, &LineIndex::new(text));
+    let actions = get_config_file_code_actions(&all_diagnostics).unwrap();
+    let lsp::CodeActionOrCommand::CodeAction(action) = &actions[0] else {
+      panic!("expected a code action");
+    };
+    assert_eq!(action.title, "Upgrade Deno");
+    assert_eq!(action.command.as_ref().unwrap().command, "deno.upgrade");
+    assert!(get_config_file_code_actions(&[]).is_none());
+  }
+
+  #[test]
+  fn test_config_file_completions() {
+    let line_index = LineIndex::new(CONFIG_TEXT);
+    let mut position = position_of(CONFIG_TEXT, r#"""]"#);
+    position.character += 1;
+    let Some(lsp::CompletionResponse::List(list)) =
+      get_config_file_completions(CONFIG_TEXT, &line_index, position)
+    else {
+      panic!("expected completions");
+    };
+    // "build" is already a dependency
+    assert_eq!(list.items.len(), 0);
+
+    let text = CONFIG_TEXT.replace(r#"["build", "fetch", ""]"#, r#"["b"]"#);
+    let line_index = LineIndex::new(&text);
+    let mut position = position_of(&text, r#""b"]"#);
+    position.character += 1;
+    let Some(lsp::CompletionResponse::List(list)) =
+      get_config_file_completions(&text, &line_index, position)
+    else {
+      panic!("expected completions");
+    };
+    assert_eq!(
+      list
+        .items
+        .iter()
+        .map(|i| i.label.as_str())
+        .collect::<Vec<_>>(),
+      vec!["build"]
+    );
+
+    let line_index = LineIndex::new(CONFIG_TEXT);
+    let position = position_of(CONFIG_TEXT, "deno run main.ts");
+    assert!(
+      get_config_file_completions(CONFIG_TEXT, &line_index, position).is_none()
+    );
+  }
+
+  #[test]
+  fn test_config_file_permission_sets() {
+    let text = r#"{
+  "permissions": {
+    "serve": { "net": true },
+    "test": { "read": true }
+  },
+  "tasks": {
+    "start": "deno run --permission-set=serve main.ts",
+    "dev": "deno run --permission-set=dev main.ts && deno test --permission-set="
+  }
+}"#;
+    let line_index = LineIndex::new(text);
+    let diagnostics = get_config_file_diagnostics(text, &line_index);
+    assert_eq!(
+      diagnostics
+        .iter()
+        .map(|d| (d.range.start, d.message.as_str()))
+        .collect::<Vec<_>>(),
+      vec![(
+        position_of(text, "dev main.ts"),
+        "Task \"dev\" uses unknown permission set \"dev\"."
+      )]
+    );
+
+    let mut position = position_of(text, "dev main.ts");
+    position.character += 1;
+    let Some(lsp::CompletionResponse::List(list)) =
+      get_config_file_completions(text, &line_index, position)
+    else {
+      panic!("expected completions");
+    };
+    assert_eq!(
+      list
+        .items
+        .iter()
+        .map(|i| i.label.as_str())
+        .collect::<Vec<_>>(),
+      vec!["serve", "test"]
+    );
+    let Some(lsp::CompletionTextEdit::Edit(edit)) = &list.items[0].text_edit
+    else {
+      panic!("expected a text edit");
+    };
+    assert_eq!(edit.range.start, position_of(text, "dev main.ts"));
+    assert_eq!(edit.range.end.character, position.character + 2);
+
+    let position = position_of(text, "\"\n  }\n}");
+    assert!(get_config_file_completions(text, &line_index, position).is_some());
+    let position = position_of(text, "main.ts\"");
+    assert!(get_config_file_completions(text, &line_index, position).is_none());
+  }
+
+  #[test]
+  fn test_config_file_hover() {
+    let line_index = LineIndex::new(CONFIG_TEXT);
+    let hover_text = |needle: &str| {
+      let hover = get_config_file_hover(
+        CONFIG_TEXT,
+        &line_index,
+        position_of(CONFIG_TEXT, needle),
+      )?;
+      match hover.contents {
+        lsp::HoverContents::Markup(content) => Some(content.value),
+        _ => unreachable!(),
+      }
+    };
+    assert_eq!(
+      hover_text("\"tasks\"").unwrap(),
+      "Configuration for deno task"
+    );
+    assert_eq!(
+      hover_text("\"dependsOn\"").unwrap(),
+      "Tasks to run before this task. Tasks which don't depend on each other run in parallel."
+    );
+    assert!(hover_text("\"command\"")
+      .unwrap()
+      .contains("the cross-platform shell of `deno task`"));
+    assert_eq!(hover_text("\"lint\"").unwrap(), "Configuration for linter");
+    // "rules" has no documentation of its own
+    assert!(hover_text("\"rules\"").is_none());
+    assert!(hover_text("deno run main.ts").is_none());
+  }
+}
@@ -4,26 +4,47 @@ use super::analysis;
 use super::cache;
 use super::client::Client;
 use super::config::ConfigSnapshot;
+use super::deno_json;
 use super::documents;
 use super::documents::Document;
+use super::documents::Documents;
 use super::documents::DocumentsFilter;
+use super::ffi;
+use super::ffi::FfiCallProblem;
+use super::import_attributes::ModuleAttributeType;
 use super::language_server;
 use super::language_server::StateSnapshot;
 use super::performance::Performance;
+use super::text::get_edits;
+use super::text::LineIndex;
 use super::tsc;
 use super::tsc::TsServer;
 use super::urls::LspClientUrl;
 use super::urls::LspUrlMap;
 
+use crate::args::is_allowed_to_run_scripts;
+use crate::args::package_json::read_install_scripts;
+use crate::args::ConfigFile;
+use crate::args::GraphBudget;
 use crate::args::LintOptions;
+use crate::args::ScopedOptions;
 use crate::graph_util;
 use crate::graph_util::enhanced_resolution_error_message;
 use crate::lsp::lsp_custom::DiagnosticBatchNotificationParams;
 use crate::resolver::SloppyImportsResolution;
 use crate::resolver::SloppyImportsResolver;
+use crate::tools::audit::NpmAdvisory;
 use crate::tools::lint::get_configured_rules;
-
-use deno_ast::MediaType;
+use crate::tools::pm::add_allow_scripts_text;
+use crate::tools::registry;
+use crate::util::display::human_size;
+use crate::util::path::specifier_to_file_path;
+
+use deno_ast::diagnostics::Diagnostic as _;
+use deno_ast::diagnostics::DiagnosticLevel;
+use deno_ast::diagnostics::DiagnosticLocation;
+use deno_ast::diagnostics::DiagnosticSourcePos;
+use deno_ast::SourceTextInfo;
 use deno_core::anyhow::anyhow;
 use deno_core::error::AnyError;
 use deno_core::parking_lot::RwLock;
@@ -35,19 +56,23 @@ use deno_core::unsync::spawn;
 use deno_core::unsync::spawn_blocking;
 use deno_core::unsync::JoinHandle;
 use deno_core::ModuleSpecifier;
+use deno_graph::GraphKind;
 use deno_graph::Resolution;
 use deno_graph::ResolutionError;
 use deno_graph::SpecifierError;
 use deno_lint::rules::LintRule;
+use deno_npm::NpmPackageId;
 use deno_runtime::deno_fs;
 use deno_runtime::deno_node;
 use deno_runtime::tokio_util::create_basic_runtime;
 use deno_semver::npm::NpmPackageReqReference;
+use deno_semver::package::PackageNv;
 use deno_semver::package::PackageReq;
 use log::error;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::thread;
 use tokio::sync::mpsc;
@@ -60,7 +85,7 @@ use tower_lsp::lsp_types as lsp;
 pub struct DiagnosticServerUpdateMessage {
   pub snapshot: Arc<StateSnapshot>,
   pub config: Arc<ConfigSnapshot>,
-  pub lint_options: LintOptions,
+  pub lint_options: ScopedOptions<LintOptions>,
   pub url_map: LspUrlMap,
 }
 
@@ -79,16 +104,20 @@ type DiagnosticVec = Vec<DiagnosticRecord>;
 
 #[derive(Debug, Hash, PartialEq, Eq, Copy, Clone)]
 pub enum DiagnosticSource {
+  Config,
   Deno,
   Lint,
+  Publish,
   Ts,
 }
 
 impl DiagnosticSource {
   pub fn as_lsp_source(&self) -> &'static str {
     match self {
+      Self::Config => "deno-config",
       Self::Deno => "deno",
       Self::Lint => "deno-lint",
+      Self::Publish => "deno-publish",
       Self::Ts => "deno-ts",
     }
   }
@@ -372,6 +401,8 @@ impl DiagnosticsState {
 #[derive(Debug)]
 pub struct DiagnosticsServer {
   channel: Option<mpsc::UnboundedSender<ChannelMessage>>,
+  /// The number of messages of the channel which weren't received yet.
+  pending_messages: Arc<AtomicUsize>,
   ts_diagnostics: TsDiagnosticsStore,
   client: Client,
   performance: Arc<Performance>,
@@ -389,6 +420,7 @@ impl DiagnosticsServer {
   ) -> Self {
     DiagnosticsServer {
       channel: Default::default(),
+      pending_messages: Default::default(),
       ts_diagnostics: Default::default(),
       client,
       performance,
@@ -413,10 +445,17 @@ impl DiagnosticsServer {
   pub fn invalidate_all(&self) {
     self.ts_diagnostics.invalidate_all();
     if let Some(tx) = &self.channel {
-      let _ = tx.send(ChannelMessage::Clear);
+      self.pending_messages.fetch_add(1, Ordering::SeqCst);
+      if tx.send(ChannelMessage::Clear).is_err() {
+        self.pending_messages.fetch_sub(1, Ordering::SeqCst);
+      }
     }
   }
 
+  pub fn pending_messages(&self) -> usize {
+    self.pending_messages.load(Ordering::SeqCst)
+  }
+
   #[allow(unused_must_use)]
   pub fn start(&mut self) {
     let (tx, mut rx) = mpsc::unbounded_channel::<ChannelMessage>();
@@ -426,6 +465,7 @@ impl DiagnosticsServer {
     let performance = self.performance.clone();
     let ts_diagnostics_store = self.ts_diagnostics.clone();
     let ts_server = self.ts_server.clone();
+    let pending_messages = self.pending_messages.clone();
 
     let _join_handle = thread::spawn(move || {
       let runtime = create_basic_runtime();
@@ -435,14 +475,18 @@ impl DiagnosticsServer {
         let mut ts_handle: Option<JoinHandle<()>> = None;
         let mut lint_handle: Option<JoinHandle<()>> = None;
         let mut deps_handle: Option<JoinHandle<()>> = None;
+        let mut config_handle: Option<JoinHandle<()>> = None;
+        let mut publish_handle: Option<JoinHandle<()>> = None;
         let diagnostics_publisher =
           Arc::new(DiagnosticsPublisher::new(client.clone(), state.clone()));
+        let graph_budget_cache = Arc::new(GraphBudgetCache::default());
 
         loop {
           match rx.recv().await {
             // channel has closed
             None => break,
             Some(message) => {
+              pending_messages.fetch_sub(1, Ordering::SeqCst);
               let message = match message {
                 ChannelMessage::Update(message) => message,
                 ChannelMessage::Clear => {
@@ -547,6 +591,7 @@ impl DiagnosticsServer {
               let previous_deps_handle = deps_handle.take();
               deps_handle = Some(spawn({
                 let performance = performance.clone();
+                let graph_budget_cache = graph_budget_cache.clone();
                 let diagnostics_publisher = diagnostics_publisher.clone();
                 let token = token.clone();
                 let snapshot = snapshot.clone();
@@ -559,7 +604,14 @@ impl DiagnosticsServer {
                   let mark = performance.mark("lsp.update_diagnostics_deps");
                   let diagnostics = spawn_blocking({
                     let token = token.clone();
-                    move || generate_deno_diagnostics(&snapshot, &config, token)
+                    move || {
+                      generate_deno_diagnostics(
+                        &snapshot,
+                        &config,
+                        &graph_budget_cache,
+                        token,
+                      )
+                    }
                   })
                   .await
                   .unwrap();
@@ -648,6 +700,103 @@ impl DiagnosticsServer {
                   }
                 }
               }));
+
+              let previous_config_handle = config_handle.take();
+              config_handle = Some(spawn({
+                let performance = performance.clone();
+                let diagnostics_publisher = diagnostics_publisher.clone();
+                let token = token.clone();
+                let snapshot = snapshot.clone();
+                let config = config.clone();
+                let url_map = url_map.clone();
+                async move {
+                  if let Some(previous_handle) = previous_config_handle {
+                    previous_handle.await;
+                  }
+                  let mark = performance.mark("lsp.update_diagnostics_config");
+                  let diagnostics = spawn_blocking({
+                    let token = token.clone();
+                    move || {
+                      generate_config_diagnostics(&snapshot, &config, token)
+                    }
+                  })
+                  .await
+                  .unwrap();
+
+                  let mut messages_len = 0;
+                  if !token.is_cancelled() {
+                    messages_len = diagnostics_publisher
+                      .publish(
+                        DiagnosticSource::Config,
+                        diagnostics,
+                        &url_map,
+                        &token,
+                      )
+                      .await;
+
+                    if !token.is_cancelled() {
+                      performance.measure(mark);
+                    }
+                  }
+
+                  if let Some(batch_index) = batch_index {
+                    diagnostics_publisher
+                      .client
+                      .send_diagnostic_batch_notification(
+                        DiagnosticBatchNotificationParams {
+                          batch_index,
+                          messages_len,
+                        },
+                      );
+                  }
+                }
+              }));
+
+              let previous_publish_handle = publish_handle.take();
+              publish_handle = Some(spawn({
+                let performance = performance.clone();
+                let diagnostics_publisher = diagnostics_publisher.clone();
+                let token = token.clone();
+                let snapshot = snapshot.clone();
+                let config = config.clone();
+                let url_map = url_map.clone();
+                async move {
+                  if let Some(previous_handle) = previous_publish_handle {
+                    previous_handle.await;
+                  }
+                  let mark = performance.mark("lsp.update_diagnostics_publish");
+                  let diagnostics =
+                    generate_publish_diagnostics(&snapshot, &config, &token)
+                      .await;
+
+                  let mut messages_len = 0;
+                  if !token.is_cancelled() {
+                    messages_len = diagnostics_publisher
+                      .publish(
+                        DiagnosticSource::Publish,
+                        diagnostics,
+                        &url_map,
+                        &token,
+                      )
+                      .await;
+
+                    if !token.is_cancelled() {
+                      performance.measure(mark);
+                    }
+                  }
+
+                  if let Some(batch_index) = batch_index {
+                    diagnostics_publisher
+                      .client
+                      .send_diagnostic_batch_notification(
+                        DiagnosticBatchNotificationParams {
+                          batch_index,
+                          messages_len,
+                        },
+                      );
+                  }
+                }
+              }));
             }
           }
         }
@@ -667,11 +816,16 @@ impl DiagnosticsServer {
     // instead only store the latest message (ex. maybe using a
     // tokio::sync::watch::channel)
     if let Some(tx) = &self.channel {
+      // counted before sending, so it's received after being counted
+      self.pending_messages.fetch_add(1, Ordering::SeqCst);
       tx.send(ChannelMessage::Update(ChannelUpdateMessage {
         message,
         batch_index: self.batch_counter.inc(),
       }))
-      .map_err(|err| err.into())
+      .map_err(|err| {
+        self.pending_messages.fetch_sub(1, Ordering::SeqCst);
+        err.into()
+      })
     } else {
       Err(anyhow!("diagnostics server not started"))
     }
@@ -787,13 +941,14 @@ fn ts_json_to_diagnostics(
 fn generate_lint_diagnostics(
   snapshot: &language_server::StateSnapshot,
   config: &ConfigSnapshot,
-  lint_options: &LintOptions,
+  lint_options: &ScopedOptions<LintOptions>,
   token: CancellationToken,
 ) -> DiagnosticVec {
   let documents = snapshot
     .documents
     .documents(DocumentsFilter::OpenDiagnosable);
-  let lint_rules = get_configured_rules(lint_options.rules.clone());
+  let lint_rules =
+    lint_options.map(|options| get_configured_rules(options.rules.clone()));
   let mut diagnostics_vec = Vec::new();
   for document in documents {
     let settings =
@@ -818,8 +973,8 @@ fn generate_lint_diagnostics(
         version,
         diagnostics: generate_document_lint_diagnostics(
           config,
-          lint_options,
-          lint_rules.clone(),
+          lint_options.for_specifier(document.specifier()),
+          lint_rules.for_specifier(document.specifier()).clone(),
           &document,
         ),
       },
@@ -828,6 +983,218 @@ fn generate_lint_diagnostics(
   diagnostics_vec
 }
 
+/// Generate the diagnostics of the tasks of the open config files.
+fn generate_config_diagnostics(
+  snapshot: &language_server::StateSnapshot,
+  config: &ConfigSnapshot,
+  token: CancellationToken,
+) -> DiagnosticVec {
+  let mut diagnostics_vec = Vec::new();
+  let folder_config_files = config.folder_config_files.values().flat_map(|f| {
+    std::iter::once(&f.config_file).chain(f.member_config_files.iter())
+  });
+  for config_file in config
+    .config_file
+    .iter()
+    .chain(config.member_config_files.iter())
+    .chain(folder_config_files)
+  {
+    // exit early if cancelled
+    if token.is_cancelled() {
+      break;
+    }
+    let Some(document) = snapshot.documents.get(&config_file.specifier) else {
+      continue;
+    };
+    if !document.is_open() {
+      continue;
+    }
+    diagnostics_vec.push(DiagnosticRecord {
+      specifier: document.specifier().clone(),
+      versioned: VersionedDiagnostics {
+        version: document.maybe_lsp_version(),
+        diagnostics: deno_json::get_config_file_diagnostics(
+          &document.content(),
+          &document.line_index(),
+        ),
+      },
+    });
+  }
+  diagnostics_vec
+}
+
+/// Generate the diagnostics `deno publish` would report, like slow types, for
+/// the config files which are packages, meaning they have a `name`, a
+/// `version` and `exports`. Only the diagnostics of open documents are
+/// reported.
+async fn generate_publish_diagnostics(
+  snapshot: &language_server::StateSnapshot,
+  config: &ConfigSnapshot,
+  token: &CancellationToken,
+) -> DiagnosticVec {
+  let mut diagnostics_by_specifier: HashMap<
+    ModuleSpecifier,
+    Vec<lsp::Diagnostic>,
+  > = HashMap::new();
+  let folder_config_files = config.folder_config_files.values().flat_map(|f| {
+    std::iter::once(&f.config_file).chain(f.member_config_files.iter())
+  });
+  for config_file in config
+    .config_file
+    .iter()
+    .chain(config.member_config_files.iter())
+    .chain(folder_config_files)
+  {
+    // exit early if cancelled
+    if token.is_cancelled() {
+      return Vec::new();
+    }
+    let Ok(member) = graph_util::config_to_workspace_member(config_file) else {
+      continue;
+    };
+    let exports = member
+      .exports
+      .values()
+      .filter_map(|export| member.base.join(export).ok())
+      .collect::<Vec<_>>();
+    let Some(first_export) = exports.first() else {
+      continue;
+    };
+    let mut loader = documents::DocumentsGraphLoader {
+      documents: &snapshot.documents,
+    };
+    let mut graph = deno_graph::ModuleGraph::new(GraphKind::TypesOnly);
+    graph
+      .build(
+        exports.clone(),
+        &mut loader,
+        deno_graph::BuildOptions {
+          resolver: Some(snapshot.documents.get_resolver(first_export)),
+          npm_resolver: Some(snapshot.documents.get_npm_resolver(first_export)),
+          workspace_fast_check: true,
+          workspace_members: vec![member.clone()],
+          ..Default::default()
+        },
+      )
+      .await;
+    let packages = [registry::MemberRoots {
+      name: member.nv.name.clone(),
+      dir_url: member.base.clone(),
+      exports,
+    }];
+    for diagnostic in registry::collect_publish_diagnostics(&graph, &packages) {
+      if let Some((specifier, diagnostic)) =
+        publish_diagnostic_to_lsp(&diagnostic)
+      {
+        diagnostics_by_specifier
+          .entry(specifier)
+          .or_default()
+          .push(diagnostic);
+      }
+    }
+  }
+
+  let mut diagnostics_vec = Vec::new();
+  for document in snapshot
+    .documents
+    .documents(DocumentsFilter::OpenDiagnosable)
+  {
+    if !config.specifier_enabled(document.specifier()) {
+      continue;
+    }
+    diagnostics_vec.push(DiagnosticRecord {
+      specifier: document.specifier().clone(),
+      versioned: VersionedDiagnostics {
+        version: document.maybe_lsp_version(),
+        diagnostics: diagnostics_by_specifier
+          .remove(document.specifier())
+          .unwrap_or_default(),
+      },
+    });
+  }
+  diagnostics_vec
+}
+
+/// Converts a diagnostic of `deno publish` to an LSP diagnostic of the module
+/// it's in. Diagnostics of paths, which aren't modules, are skipped.
+fn publish_diagnostic_to_lsp(
+  diagnostic: &registry::PublishDiagnostic,
+) -> Option<(ModuleSpecifier, lsp::Diagnostic)> {
+  let (specifier, range) = match diagnostic.location() {
+    DiagnosticLocation::ModulePosition {
+      specifier,
+      text_info,
+      source_pos,
+    } => {
+      let range = match diagnostic.snippet() {
+        Some(snippet) => lsp::Range {
+          start: to_lsp_position(
+            &snippet.source,
+            &snippet.highlight.range.start,
+          ),
+          end: to_lsp_position(&snippet.source, &snippet.highlight.range.end),
+        },
+        None => {
+          let position = to_lsp_position(&text_info, &source_pos);
+          lsp::Range {
+            start: position,
+            end: position,
+          }
+        }
+      };
+      (specifier.into_owned(), range)
+    }
+    DiagnosticLocation::Module { specifier } => {
+      (specifier.into_owned(), lsp::Range::default())
+    }
+    DiagnosticLocation::Path { .. } => return None,
+  };
+  let message = match diagnostic.hint() {
+    Some(hint) => format!("{}\n\nhint: {}", diagnostic.message(), hint),
+    None => diagnostic.message().into_owned(),
+  };
+  let severity = if matches!(diagnostic.level(), DiagnosticLevel::Warning) {
+    lsp::DiagnosticSeverity::WARNING
+  } else {
+    lsp::DiagnosticSeverity::ERROR
+  };
+  Some((
+    specifier,
+    lsp::Diagnostic {
+      range,
+      severity: Some(severity),
+      code: Some(lsp::NumberOrString::String(diagnostic.code().into_owned())),
+      source: Some(DiagnosticSource::Publish.as_lsp_source().to_string()),
+      message,
+      ..Default::default()
+    },
+  ))
+}
+
+fn to_lsp_position(
+  text_info: &SourceTextInfo,
+  pos: &DiagnosticSourcePos,
+) -> lsp::Position {
+  let line_and_column = match pos {
+    DiagnosticSourcePos::SourcePos(pos) => {
+      text_info.line_and_column_index(*pos)
+    }
+    DiagnosticSourcePos::ByteIndex(index) => {
+      text_info.line_and_column_index(text_info.range().start + *index)
+    }
+    DiagnosticSourcePos::LineAndCol { line, column } => {
+      return lsp::Position {
+        line: *line as u32,
+        character: *column as u32,
+      };
+    }
+  };
+  lsp::Position {
+    line: line_and_column.line_index as u32,
+    character: line_and_column.column_index as u32,
+  }
+}
+
 fn generate_document_lint_diagnostics(
   config: &ConfigSnapshot,
   lint_options: &LintOptions,
@@ -837,7 +1204,9 @@ fn generate_document_lint_diagnostics(
   if !config.specifier_enabled(document.specifier()) {
     return Vec::new();
   }
-  if !lint_options.files.matches_specifier(document.specifier()) {
+  let specifier = document.specifier();
+  let file_selection = &config.file_selections_for_specifier(specifier).lint;
+  if !file_selection.matches_specifier(&lint_options.files, specifier) {
     return Vec::new();
   }
   match document.maybe_parsed_source() {
@@ -947,6 +1316,14 @@ struct DiagnosticDataNoLocal {
   pub message: String,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DiagnosticDataNpmInstallScripts {
+  pub specifier: ModuleSpecifier,
+  pub name: String,
+  pub config: ModuleSpecifier,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct DiagnosticDataImportMapRemap {
@@ -962,10 +1339,13 @@ pub enum DenoDiagnostic {
   /// An informational diagnostic that indicates an existing specifier can be
   /// remapped to an import map import specifier.
   ImportMapRemap { from: String, to: String },
-  /// The import assertion type is incorrect.
-  InvalidAttributeType(String),
+  /// The import attribute type is incorrect.
+  InvalidAttributeType {
+    expected: ModuleAttributeType,
+    actual: String,
+  },
   /// A module requires an attribute type to be a valid import.
-  NoAttributeType,
+  NoAttributeType(ModuleAttributeType),
   /// A remote module was not found in the cache.
   NoCache(ModuleSpecifier),
   /// A remote npm package reference was not found in the cache.
@@ -984,6 +1364,30 @@ pub enum DenoDiagnostic {
   InvalidNodeSpecifier(ModuleSpecifier),
   /// Bare specifier is used for `node:` specifier
   BareNodeSpecifier(String),
+  /// The specifier is banned by the graph budget of the config file.
+  BannedSpecifier {
+    specifier: ModuleSpecifier,
+    pattern: String,
+  },
+  /// The import adds the most to a module graph which exceeds the graph
+  /// budget of the config file.
+  GraphBudgetExceeded(String),
+  /// `deno audit` found a vulnerability of the npm package.
+  NpmVulnerability {
+    package: PackageNv,
+    advisory: NpmAdvisory,
+  },
+  /// The npm package has install scripts, which aren't run because it isn't
+  /// in the `"allowScripts"` of the config file.
+  NpmInstallScripts {
+    package: PackageNv,
+    scripts: Vec<String>,
+    specifier: ModuleSpecifier,
+    maybe_config_specifier: Option<ModuleSpecifier>,
+  },
+  /// A symbol of a library which is opened by `Deno.dlopen()` is used in
+  /// another way than it's declared.
+  FfiCall(FfiCallProblem),
 }
 
 impl DenoDiagnostic {
@@ -991,8 +1395,8 @@ impl DenoDiagnostic {
     match self {
       Self::DenoWarn(_) => "deno-warn",
       Self::ImportMapRemap { .. } => "import-map-remap",
-      Self::InvalidAttributeType(_) => "invalid-attribute-type",
-      Self::NoAttributeType => "no-attribute-type",
+      Self::InvalidAttributeType { .. } => "invalid-attribute-type",
+      Self::NoAttributeType(_) => "no-attribute-type",
       Self::NoCache(_) => "no-cache",
       Self::NoCacheNpm(_, _) => "no-cache-npm",
       Self::NoLocal(_) => "no-local",
@@ -1018,6 +1422,15 @@ impl DenoDiagnostic {
       }
       Self::InvalidNodeSpecifier(_) => "resolver-error",
       Self::BareNodeSpecifier(_) => "import-node-prefix-missing",
+      Self::BannedSpecifier { .. } => "banned-specifier",
+      Self::GraphBudgetExceeded(_) => "graph-budget-exceeded",
+      Self::NpmVulnerability { .. } => "npm-vulnerability",
+      Self::NpmInstallScripts { .. } => "npm-install-scripts",
+      Self::FfiCall(problem) => match problem {
+        FfiCallProblem::Arity { .. } => "ffi-arity",
+        FfiCallProblem::StringArgument { .. } => "ffi-string-argument",
+        FfiCallProblem::UnknownSymbol(_) => "ffi-unknown-symbol",
+      },
     }
   }
 
@@ -1026,6 +1439,7 @@ impl DenoDiagnostic {
   pub fn get_code_action(
     specifier: &ModuleSpecifier,
     diagnostic: &lsp::Diagnostic,
+    documents: &Documents,
   ) -> Result<lsp::CodeAction, AnyError> {
     if let Some(lsp::NumberOrString::String(code)) = &diagnostic.code {
       let code_action = match code.as_str() {
@@ -1053,25 +1467,34 @@ impl DenoDiagnostic {
             ..Default::default()
           }
         }
-        "no-attribute-type" => lsp::CodeAction {
-          title: "Insert import attribute.".to_string(),
-          kind: Some(lsp::CodeActionKind::QUICKFIX),
-          diagnostics: Some(vec![diagnostic.clone()]),
-          edit: Some(lsp::WorkspaceEdit {
-            changes: Some(HashMap::from([(
-              specifier.clone(),
-              vec![lsp::TextEdit {
-                new_text: " with { type: \"json\" }".to_string(),
-                range: lsp::Range {
-                  start: diagnostic.range.end,
-                  end: diagnostic.range.end,
-                },
-              }],
-            )])),
+        "no-attribute-type" => {
+          // diagnostics of older versions only were of JSON modules
+          let attribute_type = diagnostic
+            .data
+            .as_ref()
+            .and_then(|data| data.get("type"))
+            .and_then(|value| value.as_str())
+            .unwrap_or("json");
+          lsp::CodeAction {
+            title: "Insert import attribute.".to_string(),
+            kind: Some(lsp::CodeActionKind::QUICKFIX),
+            diagnostics: Some(vec![diagnostic.clone()]),
+            edit: Some(lsp::WorkspaceEdit {
+              changes: Some(HashMap::from([(
+                specifier.clone(),
+                vec![lsp::TextEdit {
+                  new_text: format!(" with {{ type: \"{attribute_type}\" }}"),
+                  range: lsp::Range {
+                    start: diagnostic.range.end,
+                    end: diagnostic.range.end,
+                  },
+                }],
+              )])),
+              ..Default::default()
+            }),
             ..Default::default()
-          }),
-          ..Default::default()
-        },
+          }
+        }
         "no-cache" | "no-cache-npm" => {
           let data = diagnostic
             .data
@@ -1145,6 +1568,50 @@ impl DenoDiagnostic {
             ..Default::default()
           }
         }
+        "npm-install-scripts" => {
+          let data = diagnostic
+            .data
+            .clone()
+            .ok_or_else(|| anyhow!("Diagnostic is missing data"))?;
+          let data: DiagnosticDataNpmInstallScripts =
+            serde_json::from_value(data)?;
+          // prefer the open document so the edit applies to what the user
+          // is currently looking at, not what was last saved
+          let text = match documents.get(&data.config) {
+            Some(document) => document.content().to_string(),
+            None => {
+              std::fs::read_to_string(specifier_to_file_path(&data.config)?)?
+            }
+          };
+          let fmt_options = ConfigFile::new(&text, data.config.clone())?
+            .to_fmt_config()?
+            .map(|config| config.options)
+            .unwrap_or_default();
+          let new_text =
+            add_allow_scripts_text(&text, &data.name, &fmt_options)?
+              .unwrap_or_else(|| text.clone());
+          lsp::CodeAction {
+            title: format!(
+              "Add \"npm:{}\" to \"allowScripts\" and cache it.",
+              data.name
+            ),
+            kind: Some(lsp::CodeActionKind::QUICKFIX),
+            diagnostics: Some(vec![diagnostic.clone()]),
+            edit: Some(lsp::WorkspaceEdit {
+              changes: Some(HashMap::from([(
+                data.config.clone(),
+                get_edits(&text, &new_text, &LineIndex::new(&text)),
+              )])),
+              ..Default::default()
+            }),
+            command: Some(lsp::Command {
+              title: "".to_string(),
+              command: "deno.cache".to_string(),
+              arguments: Some(vec![json!([data.specifier]), json!(&specifier)]),
+            }),
+            ..Default::default()
+          }
+        }
         "import-node-prefix-missing" => {
           let data = diagnostic
             .data
@@ -1192,7 +1659,7 @@ impl DenoDiagnostic {
         | "no-attribute-type"
         | "redirect"
         | "import-node-prefix-missing" => true,
-        "no-local" => diagnostic.data.is_some(),
+        "no-local" | "npm-install-scripts" => diagnostic.data.is_some(),
         _ => false,
       }
     } else {
@@ -1223,8 +1690,8 @@ impl DenoDiagnostic {
     let (severity, message, data) = match self {
       Self::DenoWarn(message) => (lsp::DiagnosticSeverity::WARNING, message.to_string(), None),
       Self::ImportMapRemap { from, to } => (lsp::DiagnosticSeverity::HINT, format!("The import specifier can be remapped to \"{to}\" which will resolve it via the active import map."), Some(json!({ "from": from, "to": to }))),
-      Self::InvalidAttributeType(assert_type) => (lsp::DiagnosticSeverity::ERROR, format!("The module is a JSON module and expected an attribute type of \"json\". Instead got \"{assert_type}\"."), None),
-      Self::NoAttributeType => (lsp::DiagnosticSeverity::ERROR, "The module is a JSON module and not being imported with an import attribute. Consider adding `with { type: \"json\" }` to the import statement.".to_string(), None),
+      Self::InvalidAttributeType { expected, actual } => (lsp::DiagnosticSeverity::ERROR, format!("The module is {} and expected an attribute type of \"{}\". Instead got \"{actual}\".", expected.description(), expected.as_str()), None),
+      Self::NoAttributeType(expected) => (lsp::DiagnosticSeverity::ERROR, format!("The module is {} and not being imported with an import attribute. Consider adding `with {{ type: \"{}\" }}` to the import statement.", expected.description(), expected.as_str()), Some(json!({ "type": expected.as_str() }))),
       Self::NoCache(specifier) => (lsp::DiagnosticSeverity::ERROR, format!("Uncached or missing remote URL: {specifier}"), Some(json!({ "specifier": specifier }))),
       Self::NoCacheNpm(pkg_req, specifier) => (lsp::DiagnosticSeverity::ERROR, format!("Uncached or missing npm package: {}", pkg_req), Some(json!({ "specifier": specifier }))),
       Self::NoLocal(specifier) => {
@@ -1247,6 +1714,20 @@ impl DenoDiagnostic {
       ),
       Self::InvalidNodeSpecifier(specifier) => (lsp::DiagnosticSeverity::ERROR, format!("Unknown Node built-in module: {}", specifier.path()), None),
       Self::BareNodeSpecifier(specifier) => (lsp::DiagnosticSeverity::WARNING, format!("\"{}\" is resolved to \"node:{}\". If you want to use a built-in Node module, add a \"node:\" prefix.", specifier, specifier), Some(json!({ "specifier": specifier }))),
+      Self::BannedSpecifier { specifier, pattern } => (lsp::DiagnosticSeverity::ERROR, format!("\"{specifier}\" is banned by \"{pattern}\" in the graph budget of the configuration file."), None),
+      Self::GraphBudgetExceeded(message) => (lsp::DiagnosticSeverity::ERROR, message.to_string(), None),
+      Self::NpmVulnerability { package, advisory } => (lsp::DiagnosticSeverity::WARNING, format!("\"{package}\" has a {} severity vulnerability: {} ({})", advisory.severity().as_str(), advisory.title, advisory.url), None),
+      Self::NpmInstallScripts { package, scripts, specifier, maybe_config_specifier } => (lsp::DiagnosticSeverity::WARNING, format!("\"{package}\" has install scripts ({}), which aren't run unless \"npm:{}\" is in the \"allowScripts\" of the configuration file.", scripts.join(", "), package.name), maybe_config_specifier.as_ref().map(|config| json!({ "specifier": specifier, "name": package.name, "config": config }))),
+      Self::FfiCall(FfiCallProblem::Arity { symbol, expected, actual }) => (lsp::DiagnosticSeverity::ERROR, format!("The FFI symbol \"{symbol}\" expects {expected} {}, but got {actual}.", if *expected == 1 { "argument" } else { "arguments" }), None),
+      Self::FfiCall(FfiCallProblem::StringArgument { symbol, index, native_type }) => {
+        let hint = match native_type.as_str() {
+          "buffer" => " Encode the string into a buffer, like `new TextEncoder().encode(\"text\\0\")`.",
+          "pointer" => " Pass the pointer of a buffer of the encoded string, like `Deno.UnsafePointer.of(new TextEncoder().encode(\"text\\0\"))`.",
+          _ => "",
+        };
+        (lsp::DiagnosticSeverity::ERROR, format!("Argument {index} of the FFI symbol \"{symbol}\" is of the native type \"{native_type}\", which can't be a string.{hint}"), None)
+      }
+      Self::FfiCall(FfiCallProblem::UnknownSymbol(symbol)) => (lsp::DiagnosticSeverity::ERROR, format!("The FFI symbol \"{symbol}\" is not declared in the symbols which are passed to `Deno.dlopen()`."), None),
     };
     lsp::Diagnostic {
       range: *range,
@@ -1316,20 +1797,25 @@ fn diagnose_resolution(
             to: doc_specifier.clone(),
           });
         }
-        if doc.media_type() == MediaType::Json {
+        if let Some(expected) =
+          ModuleAttributeType::from_specifier(doc_specifier, doc.media_type())
+        {
           match maybe_assert_type {
             // The module has the correct assertion type, no diagnostic
-            Some("json") => (),
+            Some(assert_type) if assert_type == expected.as_str() => (),
             // The dynamic import statement is missing an attribute type, which
             // we might not be able to statically detect, therefore we will
             // not provide a potentially incorrect diagnostic.
             None if is_dynamic => (),
             // The module has an incorrect assertion type, diagnostic
-            Some(assert_type) => diagnostics.push(
-              DenoDiagnostic::InvalidAttributeType(assert_type.to_string()),
-            ),
+            Some(assert_type) => {
+              diagnostics.push(DenoDiagnostic::InvalidAttributeType {
+                expected,
+                actual: assert_type.to_string(),
+              })
+            }
             // The module is missing an attribute type, diagnostic
-            None => diagnostics.push(DenoDiagnostic::NoAttributeType),
+            None => diagnostics.push(DenoDiagnostic::NoAttributeType(expected)),
           }
         }
       } else if specifier.scheme() == "jsr" {
@@ -1337,16 +1823,50 @@ fn diagnose_resolution(
       } else if let Ok(pkg_ref) =
         NpmPackageReqReference::from_specifier(specifier)
       {
-        if let Some(npm_resolver) = snapshot
-          .npm
-          .as_ref()
-          .and_then(|n| n.npm_resolver.as_managed())
-        {
-          // show diagnostics for npm package references that aren't cached
-          let req = pkg_ref.into_inner().req;
-          if !npm_resolver.is_pkg_req_folder_cached(&req) {
-            diagnostics
-              .push(DenoDiagnostic::NoCacheNpm(req, specifier.clone()));
+        if let Some(npm) = &snapshot.npm {
+          if let Some(npm_resolver) = npm.npm_resolver.as_managed() {
+            // show diagnostics for npm package references that aren't cached
+            let req = pkg_ref.into_inner().req;
+            if !npm_resolver.is_pkg_req_folder_cached(&req) {
+              diagnostics
+                .push(DenoDiagnostic::NoCacheNpm(req, specifier.clone()));
+            } else if let Ok(pkg_id) =
+              npm_resolver.resolve_pkg_id_from_pkg_req(&req)
+            {
+              // show the vulnerabilities which `deno audit` found
+              for advisory in npm.advisories.get(&pkg_id.nv).unwrap_or_default()
+              {
+                diagnostics.push(DenoDiagnostic::NpmVulnerability {
+                  package: pkg_id.nv.clone(),
+                  advisory: advisory.clone(),
+                });
+              }
+              // install scripts are only run when setting up a local
+              // node_modules directory
+              let config = &snapshot.config;
+              if npm.npm_resolver.root_node_modules_path().is_some()
+                && !is_allowed_to_run_scripts(&config.allow_scripts, &pkg_id.nv)
+              {
+                let scripts = npm_resolver
+                  .resolve_pkg_folder_from_pkg_id(&pkg_id)
+                  .map(|path| read_install_scripts(&path))
+                  .unwrap_or_default();
+                if !scripts.is_empty() {
+                  diagnostics.push(DenoDiagnostic::NpmInstallScripts {
+                    package: pkg_id.nv.clone(),
+                    scripts: scripts
+                      .into_iter()
+                      .map(|(name, _)| name)
+                      .collect(),
+                    specifier: specifier.clone(),
+                    maybe_config_specifier: config
+                      .config_file
+                      .as_ref()
+                      .map(|c| c.specifier.clone()),
+                  });
+                }
+              }
+            }
           }
         }
       } else if let Some(module_name) = specifier.as_str().strip_prefix("node:")
@@ -1489,15 +2009,314 @@ fn diagnose_dependency(
   }
 }
 
+/// A module of the graph of the graph budget, which is looked up once for
+/// every document diagnosed in a pass.
+#[derive(Debug, Default)]
+struct BudgetModule {
+  bytes: u64,
+  maybe_npm_package_id: Option<NpmPackageId>,
+  dependencies: Vec<ModuleSpecifier>,
+}
+
+/// The modules reachable from the documents of a diagnostics pass, used to
+/// measure their module graphs against the graph budget.
+struct BudgetGraph<'a> {
+  snapshot: &'a language_server::StateSnapshot,
+  modules: HashMap<ModuleSpecifier, BudgetModule>,
+}
+
+impl<'a> BudgetGraph<'a> {
+  fn new(snapshot: &'a language_server::StateSnapshot) -> Self {
+    Self {
+      snapshot,
+      modules: Default::default(),
+    }
+  }
+
+  fn load_module(&self, specifier: &ModuleSpecifier) -> BudgetModule {
+    if let Ok(req_ref) = NpmPackageReqReference::from_specifier(specifier) {
+      let maybe_npm_package_id = self
+        .snapshot
+        .npm
+        .as_ref()
+        .and_then(|npm| npm.npm_resolver.as_managed())
+        .and_then(|npm_resolver| {
+          npm_resolver.resolve_pkg_id_from_pkg_req(req_ref.req()).ok()
+        });
+      return BudgetModule {
+        maybe_npm_package_id,
+        ..Default::default()
+      };
+    }
+    // missing modules don't add to the size
+    let Some(document) = self.snapshot.documents.get(specifier) else {
+      return BudgetModule::default();
+    };
+    let mut dependencies = Vec::new();
+    for dependency in document.dependencies().values() {
+      dependencies.extend(dependency.maybe_code.maybe_specifier().cloned());
+      dependencies.extend(dependency.maybe_type.maybe_specifier().cloned());
+    }
+    BudgetModule {
+      bytes: document.content().len() as u64,
+      maybe_npm_package_id: None,
+      dependencies,
+    }
+  }
+
+  /// Walk the modules which are reachable from the specifiers and aren't
+  /// visited yet, returning their total size and count. The size of npm
+  /// packages includes their dependencies.
+  fn walk(
+    &mut self,
+    mut pending: Vec<ModuleSpecifier>,
+    visited: &mut HashSet<ModuleSpecifier>,
+  ) -> (u64, usize) {
+    let mut total_bytes = 0;
+    let mut count = 0;
+    let mut npm_package_ids = Vec::new();
+    while let Some(specifier) = pending.pop() {
+      if specifier.scheme() == "node" || !visited.insert(specifier.clone()) {
+        continue;
+      }
+      count += 1;
+      if !self.modules.contains_key(&specifier) {
+        let module = self.load_module(&specifier);
+        self.modules.insert(specifier.clone(), module);
+      }
+      let module = &self.modules[&specifier];
+      total_bytes += module.bytes;
+      npm_package_ids.extend(module.maybe_npm_package_id.clone());
+      pending.extend(module.dependencies.iter().cloned());
+    }
+    if !npm_package_ids.is_empty() {
+      if let Some(npm_resolver) = self
+        .snapshot
+        .npm
+        .as_ref()
+        .and_then(|npm| npm.npm_resolver.as_managed())
+      {
+        total_bytes += npm_resolver.packages_size_with_deps(
+          &npm_resolver.snapshot(),
+          npm_package_ids,
+          &mut HashSet::new(),
+        );
+      }
+    }
+    (total_bytes, count)
+  }
+}
+
+/// How much of the graph budget the module graph of a document uses.
+#[derive(Debug)]
+struct GraphBudgetUsage {
+  total_bytes: u64,
+  count: usize,
+  /// The size and count which the imports add by their dependency keys,
+  /// which are only measured when the budget is exceeded.
+  additions: HashMap<String, (u64, usize)>,
+}
+
+impl GraphBudgetUsage {
+  fn measure(
+    graph: &mut BudgetGraph,
+    document: &Document,
+    budget: &GraphBudget,
+  ) -> Self {
+    let import_specifiers = |dependency: &deno_graph::Dependency| {
+      dependency
+        .maybe_code
+        .maybe_specifier()
+        .into_iter()
+        .chain(dependency.maybe_type.maybe_specifier())
+        .cloned()
+        .collect::<Vec<_>>()
+    };
+    let document_only = || HashSet::from([document.specifier().clone()]);
+    let (total_bytes, count) = graph.walk(
+      document
+        .dependencies()
+        .values()
+        .flat_map(import_specifiers)
+        .collect(),
+      &mut document_only(),
+    );
+    let total_bytes = total_bytes + document.content().len() as u64;
+    let is_exceeded = budget
+      .max_total_bytes
+      .map(|max| total_bytes > max)
+      .unwrap_or(false)
+      || budget
+        .max_dependencies
+        .map(|max| count > max)
+        .unwrap_or(false);
+    let mut additions = HashMap::new();
+    if is_exceeded {
+      for (dependency_key, dependency) in document.dependencies() {
+        let added =
+          graph.walk(import_specifiers(dependency), &mut document_only());
+        additions.insert(dependency_key.clone(), added);
+      }
+    }
+    Self {
+      total_bytes,
+      count,
+      additions,
+    }
+  }
+}
+
+/// The graph budget usage of the documents, which is kept until the
+/// documents or the graph budget of the config change.
+#[derive(Debug, Default)]
+struct GraphBudgetCache(deno_core::parking_lot::Mutex<GraphBudgetCacheInner>);
+
+#[derive(Debug, Default)]
+struct GraphBudgetCacheInner {
+  project_version: String,
+  budget: GraphBudget,
+  usages: HashMap<ModuleSpecifier, Arc<GraphBudgetUsage>>,
+}
+
+impl GraphBudgetCache {
+  fn get_or_measure(
+    &self,
+    graph: &mut BudgetGraph,
+    document: &Document,
+    budget: &GraphBudget,
+  ) -> Arc<GraphBudgetUsage> {
+    let project_version = graph.snapshot.documents.project_version();
+    let mut inner = self.0.lock();
+    if inner.project_version != project_version || inner.budget != *budget {
+      *inner = GraphBudgetCacheInner {
+        project_version,
+        budget: budget.clone(),
+        usages: Default::default(),
+      };
+    }
+    inner
+      .usages
+      .entry(document.specifier().clone())
+      .or_insert_with(|| {
+        Arc::new(GraphBudgetUsage::measure(graph, document, budget))
+      })
+      .clone()
+  }
+}
+
+/// Check the module graph of a document against the graph budget of the
+/// config file. Banned specifiers are reported on their imports, while an
+/// exceeded size or dependency count is reported on the import which adds
+/// the most to it.
+fn diagnose_graph_budget(
+  diagnostics: &mut Vec<lsp::Diagnostic>,
+  graph: &mut BudgetGraph,
+  cache: &GraphBudgetCache,
+  document: &Document,
+  budget: &GraphBudget,
+) {
+  fn import_ranges(dependency: &deno_graph::Dependency) -> Vec<lsp::Range> {
+    dependency
+      .imports
+      .iter()
+      .map(|i| documents::to_lsp_range(&i.range))
+      .collect()
+  }
+
+  for dependency in document.dependencies().values() {
+    if let Some((specifier, pattern)) = dependency
+      .maybe_code
+      .maybe_specifier()
+      .into_iter()
+      .chain(dependency.maybe_type.maybe_specifier())
+      .find_map(|s| {
+        budget
+          .banned_pattern(s)
+          .map(|pattern| (s, pattern.to_string()))
+      })
+    {
+      for range in import_ranges(dependency) {
+        diagnostics.push(
+          DenoDiagnostic::BannedSpecifier {
+            specifier: specifier.clone(),
+            pattern: pattern.clone(),
+          }
+          .to_lsp_diagnostic(&range),
+        );
+      }
+    }
+  }
+
+  if budget.max_total_bytes.is_none() && budget.max_dependencies.is_none() {
+    return;
+  }
+  let usage = cache.get_or_measure(graph, document, budget);
+  let mut report = |max_by: &dyn Fn(&(u64, usize)) -> u64, message: String| {
+    let maybe_dependency = document
+      .dependencies()
+      .iter()
+      .filter_map(|(key, dependency)| {
+        usage.additions.get(key).map(|added| (dependency, added))
+      })
+      .max_by_key(|(_, added)| max_by(added));
+    if let Some((dependency, _)) = maybe_dependency {
+      for range in import_ranges(dependency) {
+        diagnostics.push(
+          DenoDiagnostic::GraphBudgetExceeded(message.clone())
+            .to_lsp_diagnostic(&range),
+        );
+      }
+    }
+  };
+  if let Some(max_total_bytes) = budget.max_total_bytes {
+    if usage.total_bytes > max_total_bytes {
+      let added = usage
+        .additions
+        .values()
+        .map(|(bytes, _)| *bytes)
+        .max()
+        .unwrap_or(0);
+      report(
+        &|(bytes, _)| *bytes,
+        format!(
+          "The module graph of {} is over the maximum of {} in the graph budget of the configuration file. This import adds {}.",
+          human_size(usage.total_bytes as f64),
+          human_size(max_total_bytes as f64),
+          human_size(added as f64)
+        ),
+      );
+    }
+  }
+  if let Some(max_dependencies) = budget.max_dependencies {
+    if usage.count > max_dependencies {
+      let added = usage
+        .additions
+        .values()
+        .map(|(_, count)| *count)
+        .max()
+        .unwrap_or(0);
+      report(
+        &|(_, count)| *count as u64,
+        format!(
+          "The {} dependencies of the module graph are more than the maximum of {} in the graph budget of the configuration file. This import adds {}.",
+          usage.count, max_dependencies, added
+        ),
+      );
+    }
+  }
+}
+
 /// Generate diagnostics that come from Deno module resolution logic (like
 /// dependencies) or other Deno specific diagnostics, like the ability to use
 /// an import map to shorten an URL.
 fn generate_deno_diagnostics(
   snapshot: &language_server::StateSnapshot,
   config: &ConfigSnapshot,
+  graph_budget_cache: &GraphBudgetCache,
   token: CancellationToken,
 ) -> DiagnosticVec {
   let mut diagnostics_vec = Vec::new();
+  let mut budget_graph = BudgetGraph::new(snapshot);
 
   for document in snapshot
     .documents
@@ -1518,6 +2337,21 @@ fn generate_deno_diagnostics(
           dependency,
         );
       }
+      if let Some(budget) = &config.graph_budget {
+        diagnose_graph_budget(
+          &mut diagnostics,
+          &mut budget_graph,
+          graph_budget_cache,
+          &document,
+          budget,
+        );
+      }
+      if let Some(Ok(parsed_source)) = document.maybe_parsed_source() {
+        for (range, problem) in ffi::check_ffi_calls(&parsed_source) {
+          diagnostics
+            .push(DenoDiagnostic::FfiCall(problem).to_lsp_diagnostic(&range));
+        }
+      }
     }
     diagnostics_vec.push(DiagnosticRecord {
       specifier: specifier.clone(),
@@ -1539,7 +2373,6 @@ mod tests {
   use crate::lsp::config::ConfigSnapshot;
   use crate::lsp::config::Settings;
   use crate::lsp::config::WorkspaceSettings;
-  use crate::lsp::documents::Documents;
   use crate::lsp::documents::LanguageId;
   use crate::lsp::language_server::StateSnapshot;
   use deno_config::glob::FilePatterns;
@@ -1642,12 +2475,12 @@ let c: number = "a";
     let cache =
       Arc::new(GlobalHttpCache::new(cache_location, RealDenoCacheEnv));
     let ts_server = TsServer::new(Default::default(), cache);
-    ts_server.start(None);
-    let lint_options = LintOptions {
+    ts_server.start(None, None);
+    let lint_options = ScopedOptions::new(LintOptions {
       rules: Default::default(),
       files: FilePatterns::new_with_base(temp_dir.path().to_path_buf()),
       reporter_kind: Default::default(),
-    };
+    });
 
     // test enabled
     {
@@ -1671,6 +2504,7 @@ let c: number = "a";
       let diagnostics = generate_deno_diagnostics(
         &snapshot,
         &enabled_config,
+        &Default::default(),
         Default::default(),
       );
       assert_eq!(get_diagnostics_for_single(diagnostics).len(), 1);
@@ -1703,6 +2537,7 @@ let c: number = "a";
       let diagnostics = generate_deno_diagnostics(
         &snapshot,
         &disabled_config,
+        &Default::default(),
         Default::default(),
       );
       assert_eq!(get_diagnostics_for_single(diagnostics).len(), 0);
@@ -1751,7 +2586,8 @@ let c: number = "a";
     );
     let config = mock_config();
     let token = CancellationToken::new();
-    let actual = generate_deno_diagnostics(&snapshot, &config, token);
+    let actual =
+      generate_deno_diagnostics(&snapshot, &config, &Default::default(), token);
     assert_eq!(actual.len(), 2);
     for record in actual {
       match record.specifier.as_str() {
@@ -1791,7 +2627,12 @@ let c: number = "a";
   #[test]
   fn test_get_code_action_import_map_remap() {
     let specifier = ModuleSpecifier::parse("file:///a/file.ts").unwrap();
-    let result = DenoDiagnostic::get_code_action(&specifier, &lsp::Diagnostic {
+    let temp_dir = TempDir::new();
+    let documents = Documents::new(Arc::new(GlobalHttpCache::new(
+      temp_dir.path().to_path_buf(),
+      RealDenoCacheEnv,
+    )));
+    let diagnostic = lsp::Diagnostic {
       range: lsp::Range {
         start: lsp::Position { line: 0, character: 23 },
         end: lsp::Position { line: 0, character: 50 },
@@ -1805,7 +2646,9 @@ let c: number = "a";
         "to": "/~/std/assert/mod.ts"
       })),
       ..Default::default()
-    });
+    };
+    let result =
+      DenoDiagnostic::get_code_action(&specifier, &diagnostic, &documents);
     assert!(result.is_ok());
     let actual = result.unwrap();
     assert_eq!(
@@ -1877,7 +2720,8 @@ let c: number = "a";
     );
     let config = mock_config();
     let token = CancellationToken::new();
-    let actual = generate_deno_diagnostics(&snapshot, &config, token);
+    let actual =
+      generate_deno_diagnostics(&snapshot, &config, &Default::default(), token);
     assert_eq!(actual.len(), 1);
     let record = actual.first().unwrap();
     assert_eq!(
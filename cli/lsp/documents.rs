@@ -2,10 +2,16 @@
 
 use super::cache::calculate_fs_version;
 use super::cache::calculate_fs_version_at_path;
+use super::config::find_scope;
+use super::import_attributes::ModuleAttributeType;
+use super::jsr;
 use super::language_server::StateNpmSnapshot;
+use super::preprocessors::DocumentPreprocessor;
+use super::preprocessors::DocumentPreprocessors;
 use super::text::LineIndex;
 use super::tsc;
 use super::tsc::AssetDocument;
+use super::wasm::get_wasm_declarations;
 
 use crate::args::package_json;
 use crate::args::package_json::PackageJsonDeps;
@@ -20,6 +26,7 @@ use crate::resolver::CliGraphResolverOptions;
 use crate::resolver::SloppyImportsFsEntry;
 use crate::resolver::SloppyImportsResolution;
 use crate::resolver::SloppyImportsResolver;
+use crate::util::gitignore::GitIgnoreTree;
 use crate::util::path::specifier_to_file_path;
 
 use deno_ast::MediaType;
@@ -33,6 +40,7 @@ use deno_core::error::AnyError;
 use deno_core::futures::future;
 use deno_core::futures::FutureExt;
 use deno_core::parking_lot::Mutex;
+use deno_core::serde::Serialize;
 use deno_core::url;
 use deno_core::ModuleSpecifier;
 use deno_graph::source::ResolutionMode;
@@ -52,6 +60,7 @@ use lsp::Url;
 use once_cell::sync::Lazy;
 use package_json::PackageJsonDepsProvider;
 use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::collections::VecDeque;
@@ -95,6 +104,49 @@ static TSX_HEADERS: Lazy<HashMap<String, String>> = Lazy::new(|| {
     .collect()
 });
 
+/// The headers which a virtual document of the media type is parsed with.
+fn virtual_document_headers(
+  media_type: MediaType,
+) -> &'static HashMap<String, String> {
+  match media_type {
+    MediaType::TypeScript => &TS_HEADERS,
+    MediaType::Tsx => &TSX_HEADERS,
+    MediaType::Jsx => &JSX_HEADERS,
+    _ => &JS_HEADERS,
+  }
+}
+
+/// Creates the document of a Wasm module, which is parsed as the declarations
+/// of its exports.
+fn new_wasm_document(
+  specifier: &ModuleSpecifier,
+  fs_version: String,
+  bytes: &[u8],
+  resolver: &dyn deno_graph::source::Resolver,
+  npm_resolver: &dyn deno_graph::source::NpmResolver,
+) -> Option<Document> {
+  let declarations = match get_wasm_declarations(bytes) {
+    Ok(declarations) => declarations,
+    Err(err) => {
+      lsp_warn!(
+        "Failed reading the Wasm module \"{}\": {:#}",
+        specifier,
+        err
+      );
+      return None;
+    }
+  };
+  Some(Document::new(
+    specifier.clone(),
+    fs_version,
+    Some(TS_HEADERS.clone()),
+    SourceTextInfo::from_string(declarations),
+    None,
+    resolver,
+    npm_resolver,
+  ))
+}
+
 pub const DOCUMENT_SCHEMES: [&str; 5] =
   ["data", "blob", "file", "http", "https"];
 
@@ -107,6 +159,9 @@ pub enum LanguageId {
   Json,
   JsonC,
   Markdown,
+  Css,
+  Scss,
+  Html,
   Unknown,
 }
 
@@ -119,7 +174,11 @@ impl LanguageId {
       LanguageId::Tsx => MediaType::Tsx,
       LanguageId::Json => MediaType::Json,
       LanguageId::JsonC => MediaType::Json,
-      LanguageId::Markdown | LanguageId::Unknown => MediaType::Unknown,
+      LanguageId::Markdown
+      | LanguageId::Css
+      | LanguageId::Scss
+      | LanguageId::Html
+      | LanguageId::Unknown => MediaType::Unknown,
     }
   }
 
@@ -132,6 +191,9 @@ impl LanguageId {
       LanguageId::Json => Some("json"),
       LanguageId::JsonC => Some("jsonc"),
       LanguageId::Markdown => Some("md"),
+      LanguageId::Css => Some("css"),
+      LanguageId::Scss => Some("scss"),
+      LanguageId::Html => Some("html"),
       LanguageId::Unknown => None,
     }
   }
@@ -166,6 +228,9 @@ impl FromStr for LanguageId {
       "json" => Ok(Self::Json),
       "jsonc" => Ok(Self::JsonC),
       "markdown" => Ok(Self::Markdown),
+      "css" => Ok(Self::Css),
+      "scss" => Ok(Self::Scss),
+      "html" => Ok(Self::Html),
       _ => Ok(Self::Unknown),
     }
   }
@@ -285,6 +350,24 @@ impl DocumentDependencies {
 type ModuleResult = Result<deno_graph::JsModule, deno_graph::ModuleGraphError>;
 type ParsedSourceResult = Result<ParsedSource, deno_ast::ParseDiagnostic>;
 
+/// The text of the editor or the file system of a virtual document, which the
+/// text of the document is mapped from by the preprocessor.
+#[derive(Debug, Clone)]
+struct VirtualSource {
+  preprocessor: Arc<dyn DocumentPreprocessor>,
+  text: Arc<str>,
+}
+
+impl VirtualSource {
+  fn preprocess(&self) -> (SourceTextInfo, &'static HashMap<String, String>) {
+    let virtual_document = self.preprocessor.preprocess(&self.text);
+    (
+      SourceTextInfo::from_string(virtual_document.text),
+      virtual_document_headers(virtual_document.media_type),
+    )
+  }
+}
+
 #[derive(Debug)]
 struct DocumentInner {
   /// Contains the last-known-good set of dependencies from parsing the module.
@@ -299,6 +382,7 @@ struct DocumentInner {
   // so having a mutex to hold it is ok
   maybe_navigation_tree: Mutex<Option<Arc<tsc::NavigationTree>>>,
   maybe_parsed_source: Option<ParsedSourceResult>,
+  maybe_virtual_source: Option<VirtualSource>,
   specifier: ModuleSpecifier,
   text_info: SourceTextInfo,
 }
@@ -312,9 +396,22 @@ impl Document {
     fs_version: String,
     maybe_headers: Option<HashMap<String, String>>,
     text_info: SourceTextInfo,
+    maybe_preprocessor: Option<&Arc<dyn DocumentPreprocessor>>,
     resolver: &dyn deno_graph::source::Resolver,
     npm_resolver: &dyn deno_graph::source::NpmResolver,
   ) -> Self {
+    let maybe_virtual_source =
+      maybe_preprocessor.map(|preprocessor| VirtualSource {
+        preprocessor: preprocessor.clone(),
+        text: text_info.text(),
+      });
+    let (text_info, maybe_headers) = match &maybe_virtual_source {
+      Some(virtual_source) => {
+        let (text_info, headers) = virtual_source.preprocess();
+        (text_info, Some(headers.clone()))
+      }
+      None => (text_info, maybe_headers),
+    };
     // we only ever do `Document::new` on on disk resources that are supposed to
     // be diagnosable, unlike `Document::open`, so it is safe to unconditionally
     // parse the module.
@@ -338,6 +435,7 @@ impl Document {
       maybe_module,
       maybe_navigation_tree: Mutex::new(None),
       maybe_parsed_source,
+      maybe_virtual_source,
       text_info,
       specifier,
     }))
@@ -373,6 +471,7 @@ impl Document {
       maybe_headers: self.0.maybe_headers.clone(),
       maybe_language_id: self.0.maybe_language_id,
       maybe_lsp_version: self.0.maybe_lsp_version,
+      maybe_virtual_source: self.0.maybe_virtual_source.clone(),
       text_info: self.0.text_info.clone(),
       specifier: self.0.specifier.clone(),
     })))
@@ -384,22 +483,34 @@ impl Document {
     language_id: LanguageId,
     content: Arc<str>,
     cache: &Arc<dyn HttpCache>,
+    maybe_preprocessor: Option<&Arc<dyn DocumentPreprocessor>>,
     resolver: &dyn deno_graph::source::Resolver,
     npm_resolver: &dyn deno_graph::source::NpmResolver,
   ) -> Self {
-    let maybe_headers = language_id.as_headers();
-    let text_info = SourceTextInfo::new(content);
-    let (maybe_parsed_source, maybe_module) = if language_id.is_diagnosable() {
-      parse_and_analyze_module(
-        &specifier,
-        text_info.clone(),
-        maybe_headers,
-        resolver,
-        npm_resolver,
-      )
-    } else {
-      (None, None)
+    let maybe_virtual_source =
+      maybe_preprocessor.map(|preprocessor| VirtualSource {
+        preprocessor: preprocessor.clone(),
+        text: content.clone(),
+      });
+    let (text_info, maybe_headers) = match &maybe_virtual_source {
+      Some(virtual_source) => {
+        let (text_info, headers) = virtual_source.preprocess();
+        (text_info, Some(headers))
+      }
+      None => (SourceTextInfo::new(content), language_id.as_headers()),
     };
+    let (maybe_parsed_source, maybe_module) =
+      if maybe_virtual_source.is_some() || language_id.is_diagnosable() {
+        parse_and_analyze_module(
+          &specifier,
+          text_info.clone(),
+          maybe_headers,
+          resolver,
+          npm_resolver,
+        )
+      } else {
+        (None, None)
+      };
     let dependencies =
       Arc::new(DocumentDependencies::from_maybe_module(&maybe_module));
     let line_index = Arc::new(LineIndex::new(text_info.text_str()));
@@ -414,6 +525,7 @@ impl Document {
       maybe_module,
       maybe_navigation_tree: Mutex::new(None),
       maybe_parsed_source,
+      maybe_virtual_source,
       text_info,
       specifier,
     }))
@@ -426,8 +538,17 @@ impl Document {
     resolver: &dyn deno_graph::source::Resolver,
     npm_resolver: &dyn deno_graph::source::NpmResolver,
   ) -> Result<Document, AnyError> {
-    let mut content = self.0.text_info.text_str().to_string();
-    let mut line_index = self.0.line_index.clone();
+    // the changes of a virtual document are applied to the text of the editor
+    let (mut content, mut line_index) = match &self.0.maybe_virtual_source {
+      Some(virtual_source) => (
+        virtual_source.text.to_string(),
+        Arc::new(LineIndex::new(&virtual_source.text)),
+      ),
+      None => (
+        self.0.text_info.text_str().to_string(),
+        self.0.line_index.clone(),
+      ),
+    };
     let mut index_valid = IndexValid::All;
     for change in changes {
       if let Some(range) = change.range {
@@ -442,19 +563,35 @@ impl Document {
         index_valid = IndexValid::UpTo(0);
       }
     }
-    let text_info = SourceTextInfo::from_string(content);
-    let (maybe_parsed_source, maybe_module) = if self
-      .0
-      .maybe_language_id
-      .as_ref()
-      .map(|li| li.is_diagnosable())
-      .unwrap_or(false)
-    {
-      let maybe_headers = self
+    let maybe_virtual_source =
+      self.0.maybe_virtual_source.as_ref().map(|virtual_source| {
+        VirtualSource {
+          preprocessor: virtual_source.preprocessor.clone(),
+          text: content.clone().into(),
+        }
+      });
+    let (text_info, maybe_virtual_headers) = match &maybe_virtual_source {
+      Some(virtual_source) => {
+        let (text_info, headers) = virtual_source.preprocess();
+        (text_info, Some(headers))
+      }
+      None => (SourceTextInfo::from_string(content), None),
+    };
+    let (maybe_parsed_source, maybe_module) = if maybe_virtual_headers.is_some()
+      || self
         .0
         .maybe_language_id
         .as_ref()
-        .and_then(|li| li.as_headers());
+        .map(|li| li.is_diagnosable())
+        .unwrap_or(false)
+    {
+      let maybe_headers = maybe_virtual_headers.or_else(|| {
+        self
+          .0
+          .maybe_language_id
+          .as_ref()
+          .and_then(|li| li.as_headers())
+      });
       parse_and_analyze_module(
         &self.0.specifier,
         text_info.clone(),
@@ -470,11 +607,12 @@ impl Document {
     } else {
       self.0.dependencies.clone() // use the last known good
     };
-    let line_index = if index_valid == IndexValid::All {
-      line_index
-    } else {
-      Arc::new(LineIndex::new(text_info.text_str()))
-    };
+    let line_index =
+      if index_valid == IndexValid::All && maybe_virtual_source.is_none() {
+        line_index
+      } else {
+        Arc::new(LineIndex::new(text_info.text_str()))
+      };
     Ok(Document(Arc::new(DocumentInner {
       specifier: self.0.specifier.clone(),
       fs_version: self.0.fs_version.clone(),
@@ -487,6 +625,7 @@ impl Document {
       maybe_parsed_source,
       maybe_lsp_version: Some(version),
       maybe_navigation_tree: Mutex::new(None),
+      maybe_virtual_source,
     })))
   }
 
@@ -504,6 +643,7 @@ impl Document {
       maybe_parsed_source: self.0.maybe_parsed_source.clone(),
       maybe_lsp_version: self.0.maybe_lsp_version,
       maybe_navigation_tree: Mutex::new(None),
+      maybe_virtual_source: self.0.maybe_virtual_source.clone(),
     }))
   }
 
@@ -511,10 +651,18 @@ impl Document {
     &self.0.specifier
   }
 
+  /// The text of the document, which is the one of the virtual document
+  /// when the document is mapped by a preprocessor.
   pub fn content(&self) -> Arc<str> {
     self.0.text_info.text()
   }
 
+  /// Checks if the document is a virtual document of a preprocessor, whose
+  /// text isn't the one of the editor or the file system.
+  pub fn is_virtual(&self) -> bool {
+    self.0.maybe_virtual_source.is_some()
+  }
+
   pub fn text_info(&self) -> SourceTextInfo {
     self.0.text_info.clone()
   }
@@ -523,6 +671,17 @@ impl Document {
     self.0.line_index.clone()
   }
 
+  /// A rough estimate of the memory used by the text and the parsed source
+  /// of the document, where the AST is assumed to be a multiple of the size
+  /// of the text.
+  fn estimated_size(&self) -> usize {
+    let text_size = self.0.text_info.text_str().len();
+    match &self.0.maybe_parsed_source {
+      Some(Ok(_)) => text_size * (1 + PARSED_SOURCE_SIZE_FACTOR),
+      _ => text_size,
+    }
+  }
+
   fn fs_version(&self) -> &str {
     self.0.fs_version.as_str()
   }
@@ -753,10 +912,41 @@ impl RedirectResolver {
   }
 }
 
+/// How many times the size of the text the parsed source of a document is
+/// estimated to be.
+const PARSED_SOURCE_SIZE_FACTOR: usize = 10;
+
+#[derive(Debug, Default, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentCacheStats {
+  /// The number of documents which aren't open.
+  pub count: usize,
+  /// The estimated size in bytes of the text and the parsed sources of the
+  /// documents which aren't open.
+  pub estimated_size: usize,
+  pub max_size: Option<usize>,
+  /// The number of documents which were evicted to stay under the max size.
+  pub evictions: usize,
+}
+
 #[derive(Debug, Default)]
 struct FileSystemDocuments {
   docs: HashMap<ModuleSpecifier, Document>,
+  /// The sum of the estimated sizes of the documents, which is updated as
+  /// they're inserted and removed, so evicting doesn't go over all of them.
+  estimated_size: usize,
   dirty: bool,
+  /// The max estimated size in bytes of the documents. When it's exceeded,
+  /// the least recently used documents are evicted, which are read again from
+  /// the file system or the cache the next time they're requested.
+  maybe_max_size: Option<usize>,
+  /// The tick of the last access of the documents, which is only tracked
+  /// when there is a max size. This may contain specifiers which were
+  /// removed from the documents since.
+  last_accesses: HashMap<ModuleSpecifier, u64>,
+  access_tick: u64,
+  evictions: usize,
+  preprocessors: DocumentPreprocessors,
 }
 
 impl FileSystemDocuments {
@@ -772,6 +962,7 @@ impl FileSystemDocuments {
     } else {
       calculate_fs_version(cache, specifier)
     };
+    self.mark_accessed(specifier);
     let file_system_doc = self.docs.get(specifier);
     if file_system_doc.map(|d| d.fs_version().to_string()) != fs_version {
       // attempt to update the file on the file system
@@ -781,6 +972,89 @@ impl FileSystemDocuments {
     }
   }
 
+  /// Inserts or replaces a document, which all the changes of the documents
+  /// go through to keep the estimated size up to date.
+  fn insert(
+    &mut self,
+    specifier: ModuleSpecifier,
+    doc: Document,
+  ) -> Option<Document> {
+    self.estimated_size += doc.estimated_size();
+    let maybe_old_doc = self.docs.insert(specifier, doc);
+    if let Some(old_doc) = &maybe_old_doc {
+      self.estimated_size -= old_doc.estimated_size();
+    }
+    maybe_old_doc
+  }
+
+  fn remove(&mut self, specifier: &ModuleSpecifier) -> Option<Document> {
+    let maybe_doc = self.docs.remove(specifier);
+    if let Some(doc) = &maybe_doc {
+      self.estimated_size -= doc.estimated_size();
+    }
+    maybe_doc
+  }
+
+  fn mark_accessed(&mut self, specifier: &ModuleSpecifier) {
+    if self.maybe_max_size.is_some() {
+      self.access_tick += 1;
+      self
+        .last_accesses
+        .insert(specifier.clone(), self.access_tick);
+    }
+  }
+
+  pub fn set_max_size(&mut self, maybe_max_size: Option<usize>) {
+    self.maybe_max_size = maybe_max_size;
+    if maybe_max_size.is_none() {
+      self.last_accesses.clear();
+    }
+    self.evict_least_recently_used(None);
+  }
+
+  /// Evicts the least recently used documents, except for the kept one,
+  /// until the estimated size of the documents is within the max size.
+  fn evict_least_recently_used(
+    &mut self,
+    maybe_keep: Option<&ModuleSpecifier>,
+  ) {
+    let Some(max_size) = self.maybe_max_size else {
+      return;
+    };
+    if self.estimated_size <= max_size {
+      return;
+    }
+    self.last_accesses.retain(|s, _| self.docs.contains_key(s));
+    let mut candidates = self
+      .docs
+      .iter()
+      .filter(|(s, _)| Some(*s) != maybe_keep)
+      .map(|(s, d)| {
+        let last_access = self.last_accesses.get(s).copied().unwrap_or(0);
+        (last_access, s.clone())
+      })
+      .collect::<Vec<_>>();
+    candidates.sort_unstable_by_key(|(last_access, _)| *last_access);
+    for (_, specifier) in candidates {
+      if self.estimated_size <= max_size {
+        break;
+      }
+      self.remove(&specifier);
+      self.last_accesses.remove(&specifier);
+      self.evictions += 1;
+    }
+    self.dirty = true;
+  }
+
+  fn stats(&self) -> DocumentCacheStats {
+    DocumentCacheStats {
+      count: self.docs.len(),
+      estimated_size: self.estimated_size,
+      max_size: self.maybe_max_size,
+      evictions: self.evictions,
+    }
+  }
+
   /// Adds or updates a document by reading the document from the file system
   /// returning the document.
   fn refresh_document(
@@ -790,10 +1064,23 @@ impl FileSystemDocuments {
     specifier: &ModuleSpecifier,
     npm_resolver: &dyn deno_graph::source::NpmResolver,
   ) -> Option<Document> {
+    let maybe_preprocessor = self.preprocessors.get(specifier).cloned();
     let doc = if specifier.scheme() == "file" {
       let path = specifier_to_file_path(specifier).ok()?;
       let fs_version = calculate_fs_version_at_path(&path)?;
       let bytes = fs::read(path).ok()?;
+      if MediaType::from_specifier(specifier) == MediaType::Wasm {
+        return self.insert_document(
+          specifier,
+          new_wasm_document(
+            specifier,
+            fs_version,
+            &bytes,
+            resolver,
+            npm_resolver,
+          )?,
+        );
+      }
       let content =
         deno_graph::source::decode_owned_source(specifier, bytes, None).ok()?;
       Document::new(
@@ -801,6 +1088,7 @@ impl FileSystemDocuments {
         fs_version,
         None,
         SourceTextInfo::from_string(content),
+        maybe_preprocessor.as_ref(),
         resolver,
         npm_resolver,
       )
@@ -814,6 +1102,7 @@ impl FileSystemDocuments {
         "1".to_string(),
         None,
         SourceTextInfo::from_string(source),
+        maybe_preprocessor.as_ref(),
         resolver,
         npm_resolver,
       )
@@ -822,11 +1111,23 @@ impl FileSystemDocuments {
       let cache_key = cache.cache_item_key(specifier).ok()?;
       let bytes = cache.read_file_bytes(&cache_key).ok()??;
       let specifier_metadata = cache.read_metadata(&cache_key).ok()??;
-      let (_, maybe_charset) =
+      let (media_type, maybe_charset) =
         deno_graph::source::resolve_media_type_and_charset_from_headers(
           specifier,
           Some(&specifier_metadata.headers),
         );
+      if media_type == MediaType::Wasm {
+        return self.insert_document(
+          specifier,
+          new_wasm_document(
+            specifier,
+            fs_version,
+            &bytes,
+            resolver,
+            npm_resolver,
+          )?,
+        );
+      }
       let content = deno_graph::source::decode_owned_source(
         specifier,
         bytes,
@@ -839,12 +1140,23 @@ impl FileSystemDocuments {
         fs_version,
         maybe_headers,
         SourceTextInfo::from_string(content),
+        maybe_preprocessor.as_ref(),
         resolver,
         npm_resolver,
       )
     };
+    self.insert_document(specifier, doc)
+  }
+
+  fn insert_document(
+    &mut self,
+    specifier: &ModuleSpecifier,
+    doc: Document,
+  ) -> Option<Document> {
     self.dirty = true;
-    self.docs.insert(specifier.clone(), doc.clone());
+    self.insert(specifier.clone(), doc.clone());
+    self.mark_accessed(specifier);
+    self.evict_least_recently_used(Some(specifier));
     Some(doc)
   }
 }
@@ -852,11 +1164,29 @@ impl FileSystemDocuments {
 pub struct UpdateDocumentConfigOptions<'a> {
   pub file_patterns: FilePatterns,
   pub document_preload_limit: usize,
+  /// Skip the paths ignored by the `.gitignore` files when preloading.
+  pub use_gitignore: bool,
+  /// The max estimated size in bytes of the documents which aren't open.
+  pub max_document_cache_size: Option<usize>,
   pub maybe_import_map: Option<Arc<import_map::ImportMap>>,
   pub maybe_config_file: Option<&'a ConfigFile>,
   pub maybe_package_json: Option<&'a PackageJson>,
   pub node_resolver: Option<Arc<NodeResolver>>,
   pub npm_resolver: Option<Arc<dyn CliNpmResolver>>,
+  /// The preprocessors of the documents which are mapped to virtual
+  /// documents, which are used for the documents read or opened afterwards.
+  pub preprocessors: DocumentPreprocessors,
+  /// The resolution of the workspace folders with their own configuration
+  /// by the URI of the folder, which is used instead of the one above for
+  /// the documents in the folder.
+  pub scopes: BTreeMap<ModuleSpecifier, ScopedDocumentConfigOptions<'a>>,
+}
+
+pub struct ScopedDocumentConfigOptions<'a> {
+  pub maybe_import_map: Option<Arc<import_map::ImportMap>>,
+  pub maybe_config_file: Option<&'a ConfigFile>,
+  pub node_resolver: Option<Arc<NodeResolver>>,
+  pub npm_resolver: Option<Arc<dyn CliNpmResolver>>,
 }
 
 /// Specify the documents to include on a `documents.documents(...)` call.
@@ -893,6 +1223,10 @@ pub struct Documents {
   /// A resolver that takes into account currently loaded import map and JSX
   /// settings.
   resolver: Arc<CliGraphResolver>,
+  /// The resolvers of the workspace folders with their own configuration by
+  /// the URI of the folder, so the documents of unrelated projects are
+  /// resolved with their own import map and npm resolver.
+  scoped_resolvers: Arc<BTreeMap<ModuleSpecifier, Arc<CliGraphResolver>>>,
   /// The npm package requirements found in npm specifiers.
   npm_specifier_reqs: Arc<Vec<PackageReq>>,
   /// Gets if any document had a node: specifier such that a @types/node package
@@ -903,6 +1237,9 @@ pub struct Documents {
   /// If --unstable-sloppy-imports is enabled.
   unstable_sloppy_imports: bool,
   project_version: usize,
+  /// The preprocessors which map the documents which aren't JavaScript or
+  /// TypeScript to virtual documents by their file extensions.
+  preprocessors: DocumentPreprocessors,
 }
 
 impl Documents {
@@ -927,11 +1264,13 @@ impl Documents {
         bare_node_builtins_enabled: false,
         sloppy_imports_resolver: None,
       })),
+      scoped_resolvers: Default::default(),
       npm_specifier_reqs: Default::default(),
       has_injected_types_node_package: false,
       redirect_resolver: Arc::new(RedirectResolver::new(cache)),
       unstable_sloppy_imports: false,
       project_version: 0,
+      preprocessors: Default::default(),
     }
   }
 
@@ -962,20 +1301,21 @@ impl Documents {
     language_id: LanguageId,
     content: Arc<str>,
   ) -> Document {
-    let resolver = self.get_resolver();
-    let npm_resolver = self.get_npm_resolver();
+    let resolver = self.get_resolver(&specifier);
+    let npm_resolver = self.get_npm_resolver(&specifier);
     let document = Document::open(
       specifier.clone(),
       version,
       language_id,
       content,
       &self.cache,
+      self.preprocessors.get(&specifier),
       resolver,
       npm_resolver,
     );
     {
       let mut file_system_docs = self.file_system_docs.lock();
-      file_system_docs.docs.remove(&specifier);
+      file_system_docs.remove(&specifier);
       file_system_docs.dirty = true;
     }
     self.open_docs.insert(specifier, document.clone());
@@ -997,7 +1337,7 @@ impl Documents {
       .cloned()
       .or_else(|| {
         let mut file_system_docs = self.file_system_docs.lock();
-        file_system_docs.docs.remove(specifier)
+        file_system_docs.remove(specifier)
       })
       .map(Ok)
       .unwrap_or_else(|| {
@@ -1010,8 +1350,8 @@ impl Documents {
     let doc = doc.with_change(
       version,
       changes,
-      self.get_resolver(),
-      self.get_npm_resolver(),
+      self.get_resolver(specifier),
+      self.get_npm_resolver(specifier),
     )?;
     self.open_docs.insert(doc.specifier().clone(), doc.clone());
     self.increment_project_version();
@@ -1021,7 +1361,7 @@ impl Documents {
   pub fn save(&mut self, specifier: &ModuleSpecifier) {
     let doc = self.open_docs.get(specifier).cloned().or_else(|| {
       let mut file_system_docs = self.file_system_docs.lock();
-      file_system_docs.docs.remove(specifier)
+      file_system_docs.remove(specifier)
     });
     let Some(doc) = doc else {
       return;
@@ -1038,7 +1378,9 @@ impl Documents {
     if let Some(document) = self.open_docs.remove(specifier) {
       {
         let mut file_system_docs = self.file_system_docs.lock();
-        file_system_docs.docs.insert(specifier.clone(), document);
+        file_system_docs.insert(specifier.clone(), document);
+        file_system_docs.mark_accessed(specifier);
+        file_system_docs.evict_least_recently_used(Some(specifier));
       }
       self.increment_project_version();
       self.dirty = true;
@@ -1054,7 +1396,7 @@ impl Documents {
     referrer: &ModuleSpecifier,
   ) -> bool {
     let maybe_specifier = self
-      .get_resolver()
+      .get_resolver(referrer)
       .resolve(
         specifier,
         &deno_graph::Range {
@@ -1112,6 +1454,23 @@ impl Documents {
     })
   }
 
+  /// Resolve a `jsr:` specifier to a module of a package which is already in
+  /// the cache.
+  pub fn resolve_cached_jsr_specifier(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> Option<ModuleSpecifier> {
+    jsr::resolve_cached_jsr_specifier(self.cache.as_ref(), specifier)
+  }
+
+  /// Get a `jsr:` specifier for a module of a jsr package in the cache.
+  pub fn cached_jsr_module_to_specifier(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> Option<String> {
+    jsr::cached_jsr_module_to_specifier(self.cache.as_ref(), specifier)
+  }
+
   /// Return `true` if the specifier can be resolved to a document.
   pub fn exists(&self, specifier: &ModuleSpecifier) -> bool {
     let specifier = self.resolve_specifier(specifier);
@@ -1163,6 +1522,12 @@ impl Documents {
     self.has_injected_types_node_package
   }
 
+  /// Checks if the document of the specifier is mapped to a virtual document
+  /// by a preprocessor.
+  pub fn has_preprocessor(&self, specifier: &ModuleSpecifier) -> bool {
+    self.preprocessors.get(specifier).is_some()
+  }
+
   /// Return a document for the specifier.
   pub fn get(&self, original_specifier: &ModuleSpecifier) -> Option<Document> {
     let specifier = self.resolve_specifier(original_specifier)?;
@@ -1172,9 +1537,9 @@ impl Documents {
       let mut file_system_docs = self.file_system_docs.lock();
       file_system_docs.get(
         &self.cache,
-        self.get_resolver(),
+        self.get_resolver(&specifier),
         &specifier,
-        self.get_npm_resolver(),
+        self.get_npm_resolver(&specifier),
       )
     }
   }
@@ -1230,6 +1595,7 @@ impl Documents {
     maybe_npm: Option<&StateNpmSnapshot>,
   ) -> Vec<Option<(ModuleSpecifier, MediaType)>> {
     let referrer = referrer_doc.specifier();
+    let maybe_npm = maybe_npm.map(|npm| npm.for_specifier(referrer));
     let dependencies = match referrer_doc {
       AssetOrDocument::Asset(_) => None,
       AssetOrDocument::Document(doc) => Some(doc.0.dependencies.clone()),
@@ -1323,16 +1689,22 @@ impl Documents {
     Ok(())
   }
 
+  pub fn document_cache_stats(&self) -> DocumentCacheStats {
+    self.file_system_docs.lock().stats()
+  }
+
   pub fn update_config(&mut self, options: UpdateDocumentConfigOptions) {
     #[allow(clippy::too_many_arguments)]
     fn calculate_resolver_config_hash(
       file_patterns: &FilePatterns,
       document_preload_limit: usize,
+      use_gitignore: bool,
       maybe_import_map: Option<&import_map::ImportMap>,
       maybe_jsx_config: Option<&JsxImportSourceConfig>,
       maybe_vendor_dir: Option<bool>,
       maybe_package_json_deps: Option<&PackageJsonDeps>,
       maybe_unstable_flags: Option<&Vec<String>>,
+      scopes: &BTreeMap<ModuleSpecifier, ScopedDocumentConfigOptions>,
     ) -> u64 {
       fn get_pattern_set_vec(set: &PathOrPatternSet) -> Vec<Cow<'_, str>> {
         let mut paths = set
@@ -1353,6 +1725,7 @@ impl Documents {
 
       let mut hasher = FastInsecureHasher::default();
       hasher.write_hashable(document_preload_limit);
+      hasher.write_hashable(use_gitignore);
       hasher.write_hashable(
         &file_patterns.include.as_ref().map(get_pattern_set_vec),
       );
@@ -1381,6 +1754,24 @@ impl Documents {
           }
         }
       }
+      for (folder_uri, scope) in scopes {
+        hasher.write_str(folder_uri.as_str());
+        if let Some(import_map) = &scope.maybe_import_map {
+          hasher.write_str(&import_map.to_json());
+          hasher.write_str(import_map.base_url().as_str());
+        }
+        if let Some(config_file) = scope.maybe_config_file {
+          hasher.write_str(config_file.specifier.as_str());
+          hasher.write_hashable(config_file.vendor_dir_flag());
+          hasher.write_hashable(
+            config_file
+              .to_maybe_jsx_import_source_config()
+              .ok()
+              .flatten(),
+          );
+          hasher.write_hashable(&config_file.json.unstable);
+        }
+      }
 
       hasher.finish()
     }
@@ -1395,68 +1786,84 @@ impl Documents {
     let new_resolver_config_hash = calculate_resolver_config_hash(
       &options.file_patterns,
       options.document_preload_limit,
+      options.use_gitignore,
       options.maybe_import_map.as_deref(),
       maybe_jsx_config.as_ref(),
       options.maybe_config_file.and_then(|c| c.vendor_dir_flag()),
       maybe_package_json_deps.as_ref(),
       options.maybe_config_file.map(|c| &c.json.unstable),
+      &options.scopes,
     );
     let deps_provider =
       Arc::new(PackageJsonDepsProvider::new(maybe_package_json_deps));
-    let fs = Arc::new(RealFs);
-    self.resolver = Arc::new(CliGraphResolver::new(CliGraphResolverOptions {
-      fs: fs.clone(),
-      node_resolver: options.node_resolver,
-      npm_resolver: options.npm_resolver,
-      cjs_resolutions: None, // only used for runtime
-      package_json_deps_provider: deps_provider,
-      maybe_jsx_import_source_config: maybe_jsx_config,
-      maybe_import_map: options.maybe_import_map,
-      maybe_vendor_dir: options
-        .maybe_config_file
-        .and_then(|c| c.vendor_dir_path())
-        .as_ref(),
-      bare_node_builtins_enabled: options
-        .maybe_config_file
-        .map(|config| config.has_unstable("bare-node-builtins"))
-        .unwrap_or(false),
-      // Don't set this for the LSP because instead we'll use the OpenDocumentsLoader
-      // because it's much easier and we get diagnostics/quick fixes about a redirected
-      // specifier for free.
-      sloppy_imports_resolver: None,
-    }));
+    self.resolver = Arc::new(create_graph_resolver(
+      options.maybe_config_file,
+      options.maybe_import_map,
+      deps_provider,
+      options.node_resolver,
+      options.npm_resolver,
+    ));
+    self.scoped_resolvers = Arc::new(
+      options
+        .scopes
+        .iter()
+        .map(|(folder_uri, scope)| {
+          let resolver = create_graph_resolver(
+            scope.maybe_config_file,
+            scope.maybe_import_map.clone(),
+            // the package.json is only discovered for the root
+            Default::default(),
+            scope.node_resolver.clone(),
+            scope.npm_resolver.clone(),
+          );
+          (folder_uri.clone(), Arc::new(resolver))
+        })
+        .collect(),
+    );
     self.redirect_resolver =
       Arc::new(RedirectResolver::new(self.cache.clone()));
-    self.imports = Arc::new(
-      if let Some(Ok(imports)) =
-        options.maybe_config_file.map(|cf| cf.to_maybe_imports())
-      {
-        imports
-          .into_iter()
-          .map(|(referrer, imports)| {
-            let graph_import = GraphImport::new(
-              &referrer,
-              imports,
-              Some(self.get_resolver()),
-              Some(self.get_npm_resolver()),
-            );
-            (referrer, graph_import)
-          })
-          .collect()
-      } else {
-        IndexMap::new()
-      },
+    let config_files = options.maybe_config_file.into_iter().chain(
+      options
+        .scopes
+        .values()
+        .filter_map(|scope| scope.maybe_config_file),
     );
+    let mut imports = IndexMap::new();
+    for config_file in config_files {
+      let Ok(config_imports) = config_file.to_maybe_imports() else {
+        continue;
+      };
+      let resolver = self.get_resolver(&config_file.specifier);
+      let npm_resolver = self.get_npm_resolver(&config_file.specifier);
+      for (referrer, config_imports) in config_imports {
+        let graph_import = GraphImport::new(
+          &referrer,
+          config_imports,
+          Some(resolver),
+          Some(npm_resolver),
+        );
+        imports.insert(referrer, graph_import);
+      }
+    }
+    self.imports = Arc::new(imports);
     self.unstable_sloppy_imports = options
       .maybe_config_file
       .map(|c| c.has_unstable("sloppy-imports"))
       .unwrap_or(false);
 
+    {
+      let mut file_system_docs = self.file_system_docs.lock();
+      file_system_docs.set_max_size(options.max_document_cache_size);
+      file_system_docs.preprocessors = options.preprocessors.clone();
+    }
+    self.preprocessors = options.preprocessors;
+
     // only refresh the dependencies if the underlying configuration has changed
     if self.resolver_config_hash != new_resolver_config_hash {
       self.refresh_dependencies(
         options.file_patterns,
         options.document_preload_limit,
+        options.use_gitignore,
       );
       self.resolver_config_hash = new_resolver_config_hash;
 
@@ -1470,12 +1877,17 @@ impl Documents {
     &mut self,
     file_patterns: FilePatterns,
     document_preload_limit: usize,
+    use_gitignore: bool,
   ) {
-    let resolver = self.resolver.as_graph_resolver();
-    let npm_resolver = self.resolver.as_graph_npm_resolver();
-    for doc in self.open_docs.values_mut() {
-      if let Some(new_doc) = doc.maybe_with_new_resolver(resolver, npm_resolver)
-      {
+    let root_resolver = self.resolver.clone();
+    let scoped_resolvers = self.scoped_resolvers.clone();
+    for (specifier, doc) in self.open_docs.iter_mut() {
+      let resolver =
+        resolver_for_specifier(&root_resolver, &scoped_resolvers, specifier);
+      if let Some(new_doc) = doc.maybe_with_new_resolver(
+        resolver.as_graph_resolver(),
+        resolver.as_graph_npm_resolver(),
+      ) {
         *doc = new_doc;
       }
     }
@@ -1492,28 +1904,33 @@ impl Documents {
         PreloadDocumentFinder::new(PreloadDocumentFinderOptions {
           file_patterns,
           limit: document_preload_limit,
+          use_gitignore,
         });
       for specifier in finder.by_ref() {
         // mark this document as having been found
         not_found_docs.remove(&specifier);
 
+        let resolver =
+          resolver_for_specifier(&root_resolver, &scoped_resolvers, &specifier);
         if !open_docs.contains_key(&specifier)
           && !fs_docs.docs.contains_key(&specifier)
         {
           fs_docs.refresh_document(
             &self.cache,
-            resolver,
+            resolver.as_graph_resolver(),
             &specifier,
-            npm_resolver,
+            resolver.as_graph_npm_resolver(),
           );
         } else {
           // update the existing entry to have the new resolver
-          if let Some(doc) = fs_docs.docs.get_mut(&specifier) {
-            if let Some(new_doc) =
-              doc.maybe_with_new_resolver(resolver, npm_resolver)
-            {
-              *doc = new_doc;
-            }
+          let maybe_new_doc = fs_docs.docs.get(&specifier).and_then(|doc| {
+            doc.maybe_with_new_resolver(
+              resolver.as_graph_resolver(),
+              resolver.as_graph_npm_resolver(),
+            )
+          });
+          if let Some(new_doc) = maybe_new_doc {
+            fs_docs.insert(specifier, new_doc);
           }
         }
       }
@@ -1531,18 +1948,22 @@ impl Documents {
 
         // since we hit the limit, just update everything to use the new resolver
         for uri in not_found_docs {
-          if let Some(doc) = fs_docs.docs.get_mut(&uri) {
-            if let Some(new_doc) =
-              doc.maybe_with_new_resolver(resolver, npm_resolver)
-            {
-              *doc = new_doc;
-            }
+          let resolver =
+            resolver_for_specifier(&root_resolver, &scoped_resolvers, &uri);
+          let maybe_new_doc = fs_docs.docs.get(&uri).and_then(|doc| {
+            doc.maybe_with_new_resolver(
+              resolver.as_graph_resolver(),
+              resolver.as_graph_npm_resolver(),
+            )
+          });
+          if let Some(new_doc) = maybe_new_doc {
+            fs_docs.insert(uri, new_doc);
           }
         }
       } else {
         // clean up and remove any documents that weren't found
         for uri in not_found_docs {
-          fs_docs.docs.remove(&uri);
+          fs_docs.remove(&uri);
         }
       }
     } else {
@@ -1552,12 +1973,24 @@ impl Documents {
       log::debug!("Skipping document preload.");
 
       // just update to use the new resolver
-      for doc in fs_docs.docs.values_mut() {
-        if let Some(new_doc) =
-          doc.maybe_with_new_resolver(resolver, npm_resolver)
-        {
-          *doc = new_doc;
-        }
+      let new_docs = fs_docs
+        .docs
+        .iter()
+        .filter_map(|(specifier, doc)| {
+          let resolver = resolver_for_specifier(
+            &root_resolver,
+            &scoped_resolvers,
+            specifier,
+          );
+          let new_doc = doc.maybe_with_new_resolver(
+            resolver.as_graph_resolver(),
+            resolver.as_graph_npm_resolver(),
+          )?;
+          Some((specifier.clone(), new_doc))
+        })
+        .collect::<Vec<_>>();
+      for (specifier, new_doc) in new_docs {
+        fs_docs.insert(specifier, new_doc);
       }
     }
 
@@ -1627,14 +2060,15 @@ impl Documents {
       doc_analyzer.analyze_doc(specifier, doc);
     }
 
-    let resolver = self.get_resolver();
-    let npm_resolver = self.get_npm_resolver();
     while let Some(specifier) = doc_analyzer.pending_specifiers.pop_front() {
       if let Some(doc) = self.open_docs.get(&specifier) {
         doc_analyzer.analyze_doc(&specifier, doc);
-      } else if let Some(doc) =
-        file_system_docs.get(&self.cache, resolver, &specifier, npm_resolver)
-      {
+      } else if let Some(doc) = file_system_docs.get(
+        &self.cache,
+        self.get_resolver(&specifier),
+        &specifier,
+        self.get_npm_resolver(&specifier),
+      ) {
         doc_analyzer.analyze_doc(&specifier, &doc);
       }
     }
@@ -1660,12 +2094,20 @@ impl Documents {
     file_system_docs.dirty = false;
   }
 
-  fn get_resolver(&self) -> &dyn deno_graph::source::Resolver {
-    self.resolver.as_graph_resolver()
+  pub fn get_resolver(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> &dyn deno_graph::source::Resolver {
+    resolver_for_specifier(&self.resolver, &self.scoped_resolvers, specifier)
+      .as_graph_resolver()
   }
 
-  fn get_npm_resolver(&self) -> &dyn deno_graph::source::NpmResolver {
-    self.resolver.as_graph_npm_resolver()
+  pub fn get_npm_resolver(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> &dyn deno_graph::source::NpmResolver {
+    resolver_for_specifier(&self.resolver, &self.scoped_resolvers, specifier)
+      .as_graph_npm_resolver()
   }
 
   fn resolve_dependency(
@@ -1696,6 +2138,13 @@ impl Documents {
       self.resolve_dependency(specifier, maybe_npm, referrer)
     } else {
       let media_type = doc.media_type();
+      // modules like CSS and SVG are typed by generated declarations
+      if let Some(types_specifier) =
+        ModuleAttributeType::from_specifier(doc.specifier(), media_type)
+          .and_then(|attribute_type| attribute_type.types_specifier())
+      {
+        return Some((types_specifier, MediaType::Dts));
+      }
       Some((doc.specifier().clone(), media_type))
     }
   }
@@ -1714,6 +2163,47 @@ impl Documents {
   }
 }
 
+fn create_graph_resolver(
+  maybe_config_file: Option<&ConfigFile>,
+  maybe_import_map: Option<Arc<import_map::ImportMap>>,
+  package_json_deps_provider: Arc<PackageJsonDepsProvider>,
+  node_resolver: Option<Arc<NodeResolver>>,
+  npm_resolver: Option<Arc<dyn CliNpmResolver>>,
+) -> CliGraphResolver {
+  CliGraphResolver::new(CliGraphResolverOptions {
+    fs: Arc::new(RealFs),
+    node_resolver,
+    npm_resolver,
+    cjs_resolutions: None, // only used for runtime
+    package_json_deps_provider,
+    maybe_jsx_import_source_config: maybe_config_file
+      .and_then(|cf| cf.to_maybe_jsx_import_source_config().ok().flatten()),
+    maybe_import_map,
+    maybe_vendor_dir: maybe_config_file
+      .and_then(|c| c.vendor_dir_path())
+      .as_ref(),
+    bare_node_builtins_enabled: maybe_config_file
+      .map(|config| config.has_unstable("bare-node-builtins"))
+      .unwrap_or(false),
+    // Don't set this for the LSP because instead we'll use the OpenDocumentsLoader
+    // because it's much easier and we get diagnostics/quick fixes about a redirected
+    // specifier for free.
+    sloppy_imports_resolver: None,
+  })
+}
+
+/// Gets the resolver of the innermost workspace folder with its own
+/// configuration which contains the specifier, or else the root resolver.
+fn resolver_for_specifier<'a>(
+  resolver: &'a Arc<CliGraphResolver>,
+  scoped_resolvers: &'a BTreeMap<ModuleSpecifier, Arc<CliGraphResolver>>,
+  specifier: &ModuleSpecifier,
+) -> &'a CliGraphResolver {
+  find_scope(scoped_resolvers, specifier)
+    .map(|(_, resolver)| resolver)
+    .unwrap_or(resolver)
+}
+
 fn node_resolve_npm_req_ref(
   npm_req_ref: NpmPackageReqReference,
   maybe_npm: Option<&StateNpmSnapshot>,
@@ -1742,6 +2232,34 @@ fn node_resolve_npm_req_ref(
   })
 }
 
+/// Loader that only loads the documents, which are the open documents and
+/// the files and remote modules in the cache, without fetching anything.
+pub struct DocumentsGraphLoader<'a> {
+  pub documents: &'a Documents,
+}
+
+impl<'a> deno_graph::source::Loader for DocumentsGraphLoader<'a> {
+  fn registry_url(&self) -> &Url {
+    crate::args::deno_registry_url()
+  }
+
+  fn load(
+    &mut self,
+    specifier: &ModuleSpecifier,
+    _is_dynamic: bool,
+    _cache_setting: deno_graph::source::CacheSetting,
+  ) -> deno_graph::source::LoadFuture {
+    let response = self.documents.get(specifier).map(|doc| {
+      deno_graph::source::LoadResponse::Module {
+        content: Arc::from(doc.content()),
+        specifier: doc.specifier().clone(),
+        maybe_headers: None,
+      }
+    });
+    future::ready(Ok(response)).boxed_local()
+  }
+}
+
 /// Loader that will look at the open documents.
 pub struct OpenDocumentsGraphLoader<'a> {
   pub inner_loader: &'a mut dyn deno_graph::source::Loader,
@@ -1904,6 +2422,7 @@ enum PendingEntry {
 struct PreloadDocumentFinderOptions {
   file_patterns: FilePatterns,
   limit: usize,
+  use_gitignore: bool,
 }
 
 /// Iterator that finds documents that can be preloaded into
@@ -1914,6 +2433,7 @@ struct PreloadDocumentFinder {
   pending_entries: VecDeque<PendingEntry>,
   root_dir_entries: Vec<PendingEntry>,
   visited_paths: HashSet<PathBuf>,
+  maybe_gitignore: Option<GitIgnoreTree>,
 }
 
 impl PreloadDocumentFinder {
@@ -1932,6 +2452,7 @@ impl PreloadDocumentFinder {
       pending_entries: Default::default(),
       root_dir_entries: Default::default(),
       visited_paths: Default::default(),
+      maybe_gitignore: options.use_gitignore.then(Default::default),
     };
 
     let file_patterns_by_base = options.file_patterns.split_by_base();
@@ -1958,6 +2479,14 @@ impl PreloadDocumentFinder {
     self.entry_count >= self.limit
   }
 
+  fn is_gitignored(&self, path: &Path, is_dir: bool) -> bool {
+    self
+      .maybe_gitignore
+      .as_ref()
+      .map(|gitignore| gitignore.is_ignored(path, is_dir))
+      .unwrap_or(false)
+  }
+
   fn get_valid_specifier(path: &Path) -> Option<ModuleSpecifier> {
     fn is_allowed_media_type(media_type: MediaType) -> bool {
       match media_type {
@@ -2069,7 +2598,9 @@ impl Iterator for PreloadDocumentFinder {
               if let Ok(entry) = entry {
                 let path = entry.path();
                 if let Ok(file_type) = entry.file_type() {
-                  if file_patterns.matches_path(&path) {
+                  if file_patterns.matches_path(&path)
+                    && !self.is_gitignored(&path, file_type.is_dir())
+                  {
                     if file_type.is_dir() && is_discoverable_dir(&path) {
                       self.pending_entries.push_back(PendingEntry::Dir(
                         path.to_path_buf(),
@@ -2185,6 +2716,83 @@ console.log(b, "hello deno");
     );
   }
 
+  #[test]
+  fn test_documents_virtual() {
+    use crate::lsp::preprocessors::DocumentPreprocessorKind;
+
+    let temp_dir = TempDir::new();
+    let (mut documents, _) = setup(&temp_dir);
+    let preprocessors = DocumentPreprocessors::new(&BTreeMap::from([(
+      "vue".to_string(),
+      DocumentPreprocessorKind::ScriptBlocks,
+    )]));
+    documents.preprocessors = preprocessors.clone();
+    documents.file_system_docs.lock().preprocessors = preprocessors;
+    let specifier = ModuleSpecifier::parse("file:///App.vue").unwrap();
+    let content = r#"<template>
+  <p>Hé</p>
+</template>
+<script setup lang="ts">
+import { b } from "./b.ts";
+</script>
+"#;
+    let document = documents.open(
+      specifier.clone(),
+      1,
+      "vue".parse().unwrap(),
+      content.into(),
+    );
+    assert!(document.is_virtual());
+    assert!(document.is_diagnosable());
+    assert_eq!(document.media_type(), MediaType::TypeScript);
+    assert!(document.dependencies().contains_key("./b.ts"));
+    let document = documents
+      .change(
+        &specifier,
+        2,
+        vec![lsp::TextDocumentContentChangeEvent {
+          range: Some(lsp::Range {
+            start: lsp::Position {
+              line: 1,
+              character: 7,
+            },
+            end: lsp::Position {
+              line: 1,
+              character: 7,
+            },
+          }),
+          range_length: None,
+          text: "llo".to_string(),
+        }],
+      )
+      .unwrap();
+    assert_eq!(
+      document
+        .0
+        .maybe_virtual_source
+        .as_ref()
+        .unwrap()
+        .text
+        .as_ref(),
+      content.replace("Hé", "Héllo")
+    );
+    assert_eq!(
+      document.content().lines().nth(4),
+      Some(r#"import { b } from "./b.ts";"#)
+    );
+    assert!(document.dependencies().contains_key("./b.ts"));
+
+    temp_dir.write(
+      "Counter.vue",
+      "<script>\nexport const count = 1;\n</script>\n",
+    );
+    let document = documents
+      .get(&temp_dir.uri().join("Counter.vue").unwrap())
+      .unwrap();
+    assert!(document.is_virtual());
+    assert_eq!(document.media_type(), MediaType::JavaScript);
+  }
+
   #[test]
   fn test_documents_ensure_no_duplicates() {
     // it should never happen that a user of this API causes this to happen,
@@ -2213,6 +2821,120 @@ console.log(b, "hello deno");
     assert_eq!(documents.documents(DocumentsFilter::All).len(), 1);
   }
 
+  #[test]
+  fn test_documents_evict_least_recently_used() {
+    let temp_dir = TempDir::new();
+    let (documents, documents_path) = setup(&temp_dir);
+    documents_path.create_dir_all();
+    let specifiers = ["a.ts", "b.ts", "c.ts"].map(|name| {
+      let file_path = documents_path.join(name);
+      file_path.write("export const a = 1;\n");
+      ModuleSpecifier::from_file_path(&file_path).unwrap()
+    });
+    let doc_size = documents.get(&specifiers[0]).unwrap().estimated_size();
+    assert_eq!(doc_size, 20 * (1 + PARSED_SOURCE_SIZE_FACTOR));
+
+    // only two of the documents fit
+    documents
+      .file_system_docs
+      .lock()
+      .set_max_size(Some(doc_size * 2 + 1));
+    for specifier in &specifiers {
+      documents.get(specifier).unwrap();
+    }
+    let stats = documents.document_cache_stats();
+    assert_eq!(stats.count, 2);
+    assert_eq!(stats.estimated_size, doc_size * 2);
+    assert_eq!(stats.evictions, 1);
+    assert!(!documents
+      .file_system_docs
+      .lock()
+      .docs
+      .contains_key(&specifiers[0]));
+
+    // the evicted document is read again, which evicts the next one
+    assert_eq!(
+      &*documents.get(&specifiers[0]).unwrap().content(),
+      "export const a = 1;\n"
+    );
+    let file_system_docs = documents.file_system_docs.lock();
+    assert!(!file_system_docs.docs.contains_key(&specifiers[1]));
+    assert!(file_system_docs.docs.contains_key(&specifiers[2]));
+    assert_eq!(file_system_docs.evictions, 2);
+    assert_eq!(
+      file_system_docs.estimated_size,
+      file_system_docs
+        .docs
+        .values()
+        .map(|d| d.estimated_size())
+        .sum::<usize>()
+    );
+  }
+
+  #[test]
+  fn test_documents_scoped_import_maps() {
+    let temp_dir = TempDir::new();
+    let (mut documents, documents_path) = setup(&temp_dir);
+    let folder_a = documents_path.join("a");
+    let folder_b = documents_path.join("b");
+    fs::create_dir_all(&folder_a).unwrap();
+    fs::create_dir_all(&folder_b).unwrap();
+
+    let import_map_for = |folder: &PathRef| {
+      let mut import_map = ImportMap::new(
+        ModuleSpecifier::from_file_path(folder.join("deno.json")).unwrap(),
+      );
+      import_map
+        .imports_mut()
+        .append("foo".to_string(), "./foo.ts".to_string())
+        .unwrap();
+      Arc::new(import_map)
+    };
+    let folder_b_uri =
+      ModuleSpecifier::from_directory_path(folder_b.as_path()).unwrap();
+    documents.update_config(UpdateDocumentConfigOptions {
+      file_patterns: FilePatterns::new_with_base(documents_path.to_path_buf()),
+      document_preload_limit: 1_000,
+      use_gitignore: false,
+      max_document_cache_size: None,
+      maybe_import_map: Some(import_map_for(&folder_a)),
+      maybe_config_file: None,
+      maybe_package_json: None,
+      node_resolver: None,
+      npm_resolver: None,
+      preprocessors: Default::default(),
+      scopes: BTreeMap::from([(
+        folder_b_uri,
+        ScopedDocumentConfigOptions {
+          maybe_import_map: Some(import_map_for(&folder_b)),
+          maybe_config_file: None,
+          node_resolver: None,
+          npm_resolver: None,
+        },
+      )]),
+    });
+
+    // the same specifier resolves differently in each folder
+    for folder in [&folder_a, &folder_b] {
+      let document = documents.open(
+        ModuleSpecifier::from_file_path(folder.join("mod.ts")).unwrap(),
+        1,
+        LanguageId::TypeScript,
+        "import {} from 'foo';".into(),
+      );
+      assert_eq!(
+        document
+          .dependencies()
+          .get("foo")
+          .unwrap()
+          .maybe_code
+          .maybe_specifier()
+          .map(ToOwned::to_owned),
+        Some(ModuleSpecifier::from_file_path(folder.join("foo.ts")).unwrap()),
+      );
+    }
+  }
+
   #[test]
   fn test_documents_refresh_dependencies_config_change() {
     // it should never happen that a user of this API causes this to happen,
@@ -2249,11 +2971,15 @@ console.log(b, "hello deno");
           documents_path.to_path_buf(),
         ),
         document_preload_limit: 1_000,
+        use_gitignore: false,
+        max_document_cache_size: None,
         maybe_import_map: Some(Arc::new(import_map)),
         maybe_config_file: None,
         maybe_package_json: None,
         node_resolver: None,
         npm_resolver: None,
+        preprocessors: Default::default(),
+        scopes: Default::default(),
       });
 
       // open the document
@@ -2292,11 +3018,15 @@ console.log(b, "hello deno");
           documents_path.to_path_buf(),
         ),
         document_preload_limit: 1_000,
+        use_gitignore: false,
+        max_document_cache_size: None,
         maybe_import_map: Some(Arc::new(import_map)),
         maybe_config_file: None,
         maybe_package_json: None,
         node_resolver: None,
         npm_resolver: None,
+        preprocessors: Default::default(),
+        scopes: Default::default(),
       });
 
       // check the document's dependencies
@@ -2374,6 +3104,7 @@ console.log(b, "hello deno");
         exclude: Default::default(),
       },
       limit: 1_000,
+      use_gitignore: false,
     })
     .collect::<Vec<_>>();
 
@@ -2408,6 +3139,7 @@ console.log(b, "hello deno");
         exclude: Default::default(),
       },
       limit: 10, // entries and not results
+      use_gitignore: false,
     })
     .collect::<Vec<_>>();
 
@@ -2432,6 +3164,7 @@ console.log(b, "hello deno");
         .unwrap(),
       },
       limit: 1_000,
+      use_gitignore: false,
     })
     .collect::<Vec<_>>();
     urls.sort();
@@ -2446,6 +3179,45 @@ console.log(b, "hello deno");
     );
   }
 
+  #[test]
+  pub fn test_pre_load_document_finder_gitignore() {
+    let temp_dir = TempDir::new();
+    temp_dir.create_dir_all(".git");
+    temp_dir.write(".gitignore", "dist/\n*.gen.ts\n");
+    temp_dir.create_dir_all("dist");
+    temp_dir.create_dir_all("src");
+    temp_dir.write("dist/mod.ts", "");
+    temp_dir.write("src/mod.ts", "");
+    temp_dir.write("src/mod.gen.ts", "");
+
+    let find_urls = |use_gitignore: bool| {
+      let mut urls = PreloadDocumentFinder::new(PreloadDocumentFinderOptions {
+        file_patterns: FilePatterns {
+          base: temp_dir.path().to_path_buf(),
+          include: Default::default(),
+          exclude: Default::default(),
+        },
+        limit: 1_000,
+        use_gitignore,
+      })
+      .collect::<Vec<_>>();
+      urls.sort();
+      urls
+    };
+    assert_eq!(
+      find_urls(true),
+      vec![temp_dir.uri().join("src/mod.ts").unwrap()]
+    );
+    assert_eq!(
+      find_urls(false),
+      vec![
+        temp_dir.uri().join("dist/mod.ts").unwrap(),
+        temp_dir.uri().join("src/mod.gen.ts").unwrap(),
+        temp_dir.uri().join("src/mod.ts").unwrap(),
+      ]
+    );
+  }
+
   #[test]
   pub fn test_pre_load_document_finder_disallowed_dirs() {
     if cfg!(windows) {
@@ -2456,6 +3228,7 @@ console.log(b, "hello deno");
           exclude: Default::default(),
         },
         limit: 1_000,
+        use_gitignore: false,
       })
       .collect::<Vec<_>>();
       assert_eq!(paths, vec![]);
@@ -2467,6 +3240,7 @@ console.log(b, "hello deno");
           exclude: Default::default(),
         },
         limit: 1_000,
+        use_gitignore: false,
       })
       .collect::<Vec<_>>();
       assert_eq!(paths, vec![]);
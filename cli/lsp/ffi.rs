@@ -0,0 +1,491 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use super::analysis::source_range_to_lsp_range;
+
+use deno_ast::swc::ast;
+use deno_ast::swc::visit::Visit;
+use deno_ast::swc::visit::VisitWith;
+use deno_ast::ParsedSource;
+use deno_ast::SourceRange;
+use deno_ast::SourceRangedForSpanned;
+use deno_ast::SourceTextInfo;
+use std::collections::HashMap;
+use tower_lsp::lsp_types as lsp;
+
+/// A mismatch between the symbols which are passed to `Deno.dlopen()` and how
+/// the library it returns is used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FfiCallProblem {
+  /// A symbol is called with another number of arguments than the
+  /// parameters it's declared with.
+  Arity {
+    symbol: String,
+    expected: usize,
+    actual: usize,
+  },
+  /// A string is passed to a parameter of a native type, which can't be a
+  /// JavaScript string, where the index of the argument starts at 1.
+  StringArgument {
+    symbol: String,
+    index: usize,
+    native_type: String,
+  },
+  /// A symbol is used which isn't declared.
+  UnknownSymbol(String),
+}
+
+#[derive(Debug, Clone)]
+enum ForeignSymbol {
+  /// A function with the native types of its parameters, where parameters
+  /// which aren't string literals, like structs, are `None`. The parameters
+  /// are `None` when they aren't an array literal.
+  Function(Option<Vec<Option<String>>>),
+  Static,
+}
+
+#[derive(Debug, Default)]
+struct ForeignSymbols {
+  symbols: HashMap<String, ForeignSymbol>,
+  /// Whether all of the symbols are known, so that other names are unknown
+  /// symbols instead of ones which are declared by a spread for example.
+  is_complete: bool,
+}
+
+/// Skips the parentheses and type assertions of an expression, like the
+/// ones of `{ ... } as const`.
+fn unwrap_expr(expr: &ast::Expr) -> &ast::Expr {
+  match expr {
+    ast::Expr::Paren(expr) => unwrap_expr(&expr.expr),
+    ast::Expr::TsAs(expr) => unwrap_expr(&expr.expr),
+    ast::Expr::TsConstAssertion(expr) => unwrap_expr(&expr.expr),
+    ast::Expr::TsSatisfies(expr) => unwrap_expr(&expr.expr),
+    ast::Expr::TsNonNull(expr) => unwrap_expr(&expr.expr),
+    expr => expr,
+  }
+}
+
+fn prop_name(name: &ast::PropName) -> Option<String> {
+  match name {
+    ast::PropName::Ident(ident) => Some(ident.sym.to_string()),
+    ast::PropName::Str(str) => Some(str.value.to_string()),
+    _ => None,
+  }
+}
+
+/// Resolves an identifier to the object literal of the variable it's
+/// declared by.
+fn resolve_expr<'a>(
+  expr: &'a ast::Expr,
+  objects: &'a HashMap<String, Box<ast::Expr>>,
+) -> &'a ast::Expr {
+  match unwrap_expr(expr) {
+    ast::Expr::Ident(ident) => objects
+      .get(ident.sym.as_ref())
+      .map(|expr| unwrap_expr(expr))
+      .unwrap_or(expr),
+    expr => expr,
+  }
+}
+
+fn parse_foreign_symbol(
+  expr: &ast::Expr,
+  objects: &HashMap<String, Box<ast::Expr>>,
+) -> ForeignSymbol {
+  let ast::Expr::Object(object) = resolve_expr(expr, objects) else {
+    return ForeignSymbol::Function(None);
+  };
+  let mut parameters = None;
+  for prop in &object.props {
+    let ast::PropOrSpread::Prop(prop) = prop else {
+      return ForeignSymbol::Function(None);
+    };
+    let ast::Prop::KeyValue(prop) = prop.as_ref() else {
+      continue;
+    };
+    match prop_name(&prop.key).as_deref() {
+      Some("parameters") => {
+        let ast::Expr::Array(array) = resolve_expr(&prop.value, objects) else {
+          return ForeignSymbol::Function(None);
+        };
+        parameters = array
+          .elems
+          .iter()
+          .map(|elem| match elem {
+            Some(ast::ExprOrSpread { spread: None, expr }) => {
+              Some(match unwrap_expr(expr) {
+                ast::Expr::Lit(ast::Lit::Str(str)) => {
+                  Some(str.value.to_string())
+                }
+                _ => None,
+              })
+            }
+            _ => None,
+          })
+          .collect::<Option<Vec<_>>>();
+      }
+      Some("type") => return ForeignSymbol::Static,
+      _ => {}
+    }
+  }
+  ForeignSymbol::Function(parameters)
+}
+
+fn parse_foreign_symbols(
+  expr: &ast::Expr,
+  objects: &HashMap<String, Box<ast::Expr>>,
+) -> Option<ForeignSymbols> {
+  let ast::Expr::Object(object) = resolve_expr(expr, objects) else {
+    return None;
+  };
+  let mut symbols = ForeignSymbols {
+    symbols: HashMap::new(),
+    is_complete: true,
+  };
+  for prop in &object.props {
+    let ast::PropOrSpread::Prop(prop) = prop else {
+      symbols.is_complete = false;
+      continue;
+    };
+    match prop.as_ref() {
+      ast::Prop::KeyValue(prop) => match prop_name(&prop.key) {
+        Some(name) => {
+          let symbol = parse_foreign_symbol(&prop.value, objects);
+          symbols.symbols.insert(name, symbol);
+        }
+        None => symbols.is_complete = false,
+      },
+      ast::Prop::Shorthand(ident) => {
+        let symbol =
+          parse_foreign_symbol(&ast::Expr::Ident(ident.clone()), objects);
+        symbols.symbols.insert(ident.sym.to_string(), symbol);
+      }
+      _ => symbols.is_complete = false,
+    }
+  }
+  Some(symbols)
+}
+
+/// Gets the symbols argument of a call like `Deno.dlopen(path, symbols)`.
+fn get_dlopen_symbols(expr: &ast::Expr) -> Option<&ast::Expr> {
+  let ast::Expr::Call(call) = unwrap_expr(expr) else {
+    return None;
+  };
+  let ast::Callee::Expr(callee) = &call.callee else {
+    return None;
+  };
+  let ast::Expr::Member(member) = unwrap_expr(callee) else {
+    return None;
+  };
+  let ast::Expr::Ident(obj) = unwrap_expr(&member.obj) else {
+    return None;
+  };
+  let ast::MemberProp::Ident(prop) = &member.prop else {
+    return None;
+  };
+  if obj.sym != "Deno" || prop.sym != "dlopen" {
+    return None;
+  }
+  match call.args.get(1) {
+    Some(ast::ExprOrSpread { spread: None, expr }) => Some(expr.as_ref()),
+    _ => None,
+  }
+}
+
+/// Collects the libraries which are opened by `Deno.dlopen()` and the object
+/// literals of variables, which their symbols can be declared by.
+#[derive(Default)]
+struct LibraryCollector {
+  objects: HashMap<String, Box<ast::Expr>>,
+  libraries: Vec<(String, Box<ast::Expr>)>,
+}
+
+impl Visit for LibraryCollector {
+  fn visit_var_declarator(&mut self, node: &ast::VarDeclarator) {
+    if let (ast::Pat::Ident(ident), Some(init)) = (&node.name, &node.init) {
+      let name = ident.id.sym.to_string();
+      if let Some(symbols) = get_dlopen_symbols(init) {
+        self.libraries.push((name, Box::new(symbols.clone())));
+      } else if matches!(unwrap_expr(init), ast::Expr::Object(_)) {
+        self.objects.insert(name, init.clone());
+      }
+    }
+    node.visit_children_with(self);
+  }
+}
+
+struct FfiCallChecker<'a> {
+  text_info: &'a SourceTextInfo,
+  libraries: HashMap<String, ForeignSymbols>,
+  problems: Vec<(lsp::Range, FfiCallProblem)>,
+}
+
+impl FfiCallChecker<'_> {
+  /// Looks up the symbol of a member expression like `lib.symbols.add`,
+  /// which is `Some(None)` when the library doesn't declare it.
+  fn get_symbol(
+    &self,
+    member: &ast::MemberExpr,
+  ) -> Option<(String, Option<ForeignSymbol>)> {
+    let ast::Expr::Member(symbols_member) = unwrap_expr(&member.obj) else {
+      return None;
+    };
+    let ast::Expr::Ident(library) = unwrap_expr(&symbols_member.obj) else {
+      return None;
+    };
+    let ast::MemberProp::Ident(prop) = &symbols_member.prop else {
+      return None;
+    };
+    if prop.sym != "symbols" {
+      return None;
+    }
+    let symbols = self.libraries.get(library.sym.as_ref())?;
+    let name = match &member.prop {
+      ast::MemberProp::Ident(ident) => ident.sym.to_string(),
+      ast::MemberProp::Computed(computed) => {
+        match unwrap_expr(&computed.expr) {
+          ast::Expr::Lit(ast::Lit::Str(str)) => str.value.to_string(),
+          _ => return None,
+        }
+      }
+      ast::MemberProp::PrivateName(_) => return None,
+    };
+    match symbols.symbols.get(&name) {
+      Some(symbol) => Some((name, Some(symbol.clone()))),
+      None if symbols.is_complete => Some((name, None)),
+      None => None,
+    }
+  }
+
+  fn add_problem(&mut self, range: SourceRange, problem: FfiCallProblem) {
+    let range = source_range_to_lsp_range(&range, self.text_info);
+    self.problems.push((range, problem));
+  }
+
+  fn check_call(
+    &mut self,
+    call: &ast::CallExpr,
+    symbol_range: SourceRange,
+    symbol: &str,
+    parameters: &[Option<String>],
+  ) {
+    let has_spread = call.args.iter().any(|arg| arg.spread.is_some());
+    if !has_spread && call.args.len() != parameters.len() {
+      self.add_problem(
+        symbol_range,
+        FfiCallProblem::Arity {
+          symbol: symbol.to_string(),
+          expected: parameters.len(),
+          actual: call.args.len(),
+        },
+      );
+    }
+    for (index, (arg, parameter)) in
+      call.args.iter().zip(parameters).enumerate()
+    {
+      if arg.spread.is_some() {
+        break;
+      }
+      let Some(native_type) = parameter else {
+        continue;
+      };
+      if matches!(
+        unwrap_expr(&arg.expr),
+        ast::Expr::Lit(ast::Lit::Str(_)) | ast::Expr::Tpl(_)
+      ) {
+        self.add_problem(
+          arg.expr.range(),
+          FfiCallProblem::StringArgument {
+            symbol: symbol.to_string(),
+            index: index + 1,
+            native_type: native_type.clone(),
+          },
+        );
+      }
+    }
+  }
+}
+
+impl Visit for FfiCallChecker<'_> {
+  fn visit_call_expr(&mut self, node: &ast::CallExpr) {
+    if let ast::Callee::Expr(callee) = &node.callee {
+      if let ast::Expr::Member(member) = unwrap_expr(callee) {
+        if let Some((name, Some(ForeignSymbol::Function(Some(parameters))))) =
+          self.get_symbol(member)
+        {
+          self.check_call(node, member.prop.range(), &name, &parameters);
+        }
+      }
+    }
+    node.visit_children_with(self);
+  }
+
+  fn visit_member_expr(&mut self, node: &ast::MemberExpr) {
+    if let Some((name, None)) = self.get_symbol(node) {
+      self.add_problem(node.prop.range(), FfiCallProblem::UnknownSymbol(name));
+    }
+    node.visit_children_with(self);
+  }
+}
+
+/// Checks the calls of the symbols of libraries which are opened by
+/// `Deno.dlopen()` in a module against the symbols they're declared with.
+/// Libraries and symbol objects are matched by the names of their variables,
+/// so shadowing of them isn't taken into account.
+pub fn check_ffi_calls(
+  parsed_source: &ParsedSource,
+) -> Vec<(lsp::Range, FfiCallProblem)> {
+  if !parsed_source.text_info().text_str().contains("dlopen") {
+    return Vec::new();
+  }
+  let mut collector = LibraryCollector::default();
+  parsed_source.module().visit_with(&mut collector);
+  let libraries = collector
+    .libraries
+    .iter()
+    .filter_map(|(name, symbols)| {
+      let symbols = parse_foreign_symbols(symbols, &collector.objects)?;
+      Some((name.clone(), symbols))
+    })
+    .collect::<HashMap<_, _>>();
+  if libraries.is_empty() {
+    return Vec::new();
+  }
+  let mut checker = FfiCallChecker {
+    text_info: parsed_source.text_info(),
+    libraries,
+    problems: Vec::new(),
+  };
+  parsed_source.module().visit_with(&mut checker);
+  checker.problems
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use deno_ast::MediaType;
+  use deno_core::ModuleSpecifier;
+  use pretty_assertions::assert_eq;
+
+  fn check(source: &str) -> Vec<(lsp::Range, FfiCallProblem)> {
+    let parsed_source = deno_ast::parse_module(deno_ast::ParseParams {
+      specifier: ModuleSpecifier::parse("file:///a/mod.ts").unwrap(),
+      text_info: SourceTextInfo::from_string(source.to_string()),
+      media_type: MediaType::TypeScript,
+      capture_tokens: false,
+      scope_analysis: false,
+      maybe_syntax: None,
+    })
+    .unwrap();
+    check_ffi_calls(&parsed_source)
+  }
+
+  fn range(
+    start_line: u32,
+    start_character: u32,
+    end_line: u32,
+    end_character: u32,
+  ) -> lsp::Range {
+    lsp::Range {
+      start: lsp::Position::new(start_line, start_character),
+      end: lsp::Position::new(end_line, end_character),
+    }
+  }
+
+  #[test]
+  fn test_check_ffi_calls() {
+    let problems = check(
+      r#"const symbols = {
+  add: { parameters: ["i32", "i32"], result: "i32" },
+  puts: { parameters: ["buffer"], result: "void" },
+  counter: { type: "u32" },
+} as const;
+const lib = Deno.dlopen("./libmath.so", symbols);
+lib.symbols.add(1, 2);
+lib.symbols.add(1);
+lib.symbols.puts("hello");
+lib.symbols.puts(`hello ${1}`);
+lib.symbols.sub(1, 2);
+lib.symbols["add"](1, 2, 3);
+console.log(lib.symbols.counter);
+"#,
+    );
+    assert_eq!(
+      problems,
+      vec![
+        (
+          range(7, 12, 7, 15),
+          FfiCallProblem::Arity {
+            symbol: "add".to_string(),
+            expected: 2,
+            actual: 1,
+          }
+        ),
+        (
+          range(8, 17, 8, 24),
+          FfiCallProblem::StringArgument {
+            symbol: "puts".to_string(),
+            index: 1,
+            native_type: "buffer".to_string(),
+          }
+        ),
+        (
+          range(9, 17, 9, 29),
+          FfiCallProblem::StringArgument {
+            symbol: "puts".to_string(),
+            index: 1,
+            native_type: "buffer".to_string(),
+          }
+        ),
+        (
+          range(10, 12, 10, 15),
+          FfiCallProblem::UnknownSymbol("sub".to_string())
+        ),
+        (
+          range(11, 11, 11, 18),
+          FfiCallProblem::Arity {
+            symbol: "add".to_string(),
+            expected: 2,
+            actual: 3,
+          }
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_check_ffi_calls_unknown_parameters() {
+    let problems = check(
+      r#"const base = { parameters: ["pointer"], result: "void" } as const;
+const extra = {};
+const lib = Deno.dlopen("./lib.so", {
+  free: base,
+  spread: { parameters: [...base.parameters], result: "void" },
+  ...extra,
+});
+lib.symbols.free("text");
+lib.symbols.free(...[]);
+lib.symbols.spread(1, 2, 3);
+lib.symbols.other();
+const other = Deno.dlopen("./other.so", {});
+other.symbols.free();
+"#,
+    );
+    assert_eq!(
+      problems,
+      vec![
+        (
+          range(7, 17, 7, 23),
+          FfiCallProblem::StringArgument {
+            symbol: "free".to_string(),
+            index: 1,
+            native_type: "pointer".to_string(),
+          }
+        ),
+        (
+          range(12, 14, 12, 18),
+          FfiCallProblem::UnknownSymbol("free".to_string())
+        ),
+      ]
+    );
+  }
+}
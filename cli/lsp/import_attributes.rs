@@ -0,0 +1,138 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use deno_ast::MediaType;
+use deno_core::ModuleSpecifier;
+
+const CSS_MODULE_TYPES_SPECIFIER: &str = "internal:///css_module.d.ts";
+const TEXT_MODULE_TYPES_SPECIFIER: &str = "internal:///text_module.d.ts";
+
+/// The type of a CSS module is the `CSSStyleSheet` of the "dom" library, or
+/// the parts of it which are used the most when the library isn't used.
+const CSS_MODULE_TYPES: &str = r#"declare const sheet: typeof globalThis extends {
+  CSSStyleSheet: { prototype: infer T };
+} ? T
+  : {
+    readonly cssRules: ArrayLike<unknown>;
+    insertRule(rule: string, index?: number): number;
+    deleteRule(index: number): void;
+    replace(text: string): Promise<unknown>;
+    replaceSync(text: string): void;
+  };
+export default sheet;
+"#;
+
+const TEXT_MODULE_TYPES: &str = r#"declare const text: string;
+export default text;
+"#;
+
+/// The modules which aren't JavaScript or TypeScript and have to be imported
+/// with the type of an import attribute, like `with { type: "css" }`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModuleAttributeType {
+  Json,
+  Css,
+  /// An SVG image, which is imported as the text of its markup.
+  Text,
+}
+
+impl ModuleAttributeType {
+  pub fn from_specifier(
+    specifier: &ModuleSpecifier,
+    media_type: MediaType,
+  ) -> Option<Self> {
+    if media_type == MediaType::Json {
+      return Some(Self::Json);
+    }
+    if media_type != MediaType::Unknown {
+      return None;
+    }
+    let (_, extension) = specifier.path().rsplit_once('.')?;
+    match extension.to_lowercase().as_str() {
+      "css" => Some(Self::Css),
+      "svg" => Some(Self::Text),
+      _ => None,
+    }
+  }
+
+  /// The type of the import attribute.
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::Json => "json",
+      Self::Css => "css",
+      Self::Text => "text",
+    }
+  }
+
+  /// The description of the module in diagnostics.
+  pub fn description(&self) -> &'static str {
+    match self {
+      Self::Json => "a JSON module",
+      Self::Css => "a CSS module",
+      Self::Text => "an SVG image",
+    }
+  }
+
+  /// The specifier of the generated declarations which the type checker
+  /// uses for the module, where JSON modules have their own types.
+  pub fn types_specifier(&self) -> Option<ModuleSpecifier> {
+    let specifier = match self {
+      Self::Json => return None,
+      Self::Css => CSS_MODULE_TYPES_SPECIFIER,
+      Self::Text => TEXT_MODULE_TYPES_SPECIFIER,
+    };
+    Some(ModuleSpecifier::parse(specifier).unwrap())
+  }
+}
+
+/// Gets the generated declarations of a specifier of
+/// [`ModuleAttributeType::types_specifier`].
+pub fn get_module_types(specifier: &ModuleSpecifier) -> Option<&'static str> {
+  match specifier.as_str() {
+    CSS_MODULE_TYPES_SPECIFIER => Some(CSS_MODULE_TYPES),
+    TEXT_MODULE_TYPES_SPECIFIER => Some(TEXT_MODULE_TYPES),
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_module_attribute_type() {
+    let from_specifier = |specifier: &str, media_type: MediaType| {
+      ModuleAttributeType::from_specifier(
+        &ModuleSpecifier::parse(specifier).unwrap(),
+        media_type,
+      )
+    };
+    assert_eq!(
+      from_specifier("file:///data.json", MediaType::Json),
+      Some(ModuleAttributeType::Json)
+    );
+    assert_eq!(
+      from_specifier("file:///styles.css", MediaType::Unknown),
+      Some(ModuleAttributeType::Css)
+    );
+    assert_eq!(
+      from_specifier("https://example.com/logo.SVG", MediaType::Unknown),
+      Some(ModuleAttributeType::Text)
+    );
+    assert_eq!(
+      from_specifier("file:///mod.ts", MediaType::TypeScript),
+      None
+    );
+    assert_eq!(
+      from_specifier("file:///notes.txt", MediaType::Unknown),
+      None
+    );
+
+    for attribute_type in [ModuleAttributeType::Css, ModuleAttributeType::Text]
+    {
+      let specifier = attribute_type.types_specifier().unwrap();
+      assert_eq!(MediaType::from_specifier(&specifier), MediaType::Dts);
+      assert!(get_module_types(&specifier).is_some());
+    }
+    assert_eq!(ModuleAttributeType::Json.types_specifier(), None);
+  }
+}
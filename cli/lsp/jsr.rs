@@ -0,0 +1,329 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use deno_core::anyhow::anyhow;
+use deno_core::error::AnyError;
+use deno_core::parking_lot::Mutex;
+use deno_core::serde::de::DeserializeOwned;
+use deno_core::serde::Deserialize;
+use deno_core::serde_json;
+use deno_core::url::Url;
+use deno_core::ModuleSpecifier;
+use deno_runtime::permissions::PermissionsContainer;
+use deno_semver::jsr::JsrPackageReqReference;
+use deno_semver::Version;
+
+use crate::args::deno_registry_api_url;
+use crate::args::deno_registry_url;
+use crate::cache::HttpCache;
+use crate::file_fetcher::FileFetcher;
+
+#[async_trait::async_trait]
+pub trait JsrSearchApi {
+  async fn search(&self, query: &str) -> Result<Arc<Vec<String>>, AnyError>;
+  async fn versions(&self, name: &str) -> Result<Arc<Vec<Version>>, AnyError>;
+  async fn exports(
+    &self,
+    name: &str,
+    version: &Version,
+  ) -> Result<Arc<Vec<String>>, AnyError>;
+}
+
+/// Searches the jsr registry. Package metadata which can't be fetched, for
+/// example when offline, is read from the DENO_DIR cache instead.
+#[derive(Debug, Clone)]
+pub struct CliJsrSearchApi {
+  file_fetcher: FileFetcher,
+  cache: Arc<dyn HttpCache>,
+  search_cache: Arc<Mutex<HashMap<String, Arc<Vec<String>>>>>,
+  versions_cache: Arc<Mutex<HashMap<String, Arc<Vec<Version>>>>>,
+  exports_cache: Arc<Mutex<HashMap<String, Arc<Vec<String>>>>>,
+}
+
+impl CliJsrSearchApi {
+  pub fn new(file_fetcher: FileFetcher, cache: Arc<dyn HttpCache>) -> Self {
+    Self {
+      file_fetcher,
+      cache,
+      search_cache: Default::default(),
+      versions_cache: Default::default(),
+      exports_cache: Default::default(),
+    }
+  }
+
+  async fn fetch_json<T: DeserializeOwned>(
+    &self,
+    url: &Url,
+  ) -> Result<T, AnyError> {
+    match self
+      .file_fetcher
+      .fetch(url, PermissionsContainer::allow_all())
+      .await
+    {
+      Ok(file) => Ok(serde_json::from_slice(&file.source)?),
+      Err(err) => read_cached_json(self.cache.as_ref(), url).ok_or(err),
+    }
+  }
+}
+
+#[async_trait::async_trait]
+impl JsrSearchApi for CliJsrSearchApi {
+  async fn search(&self, query: &str) -> Result<Arc<Vec<String>>, AnyError> {
+    if let Some(names) = self.search_cache.lock().get(query) {
+      return Ok(names.clone());
+    }
+    let mut search_url = deno_registry_api_url().join("packages")?;
+    search_url.query_pairs_mut().append_pair("query", query);
+    let file = self
+      .file_fetcher
+      .fetch(&search_url, PermissionsContainer::allow_all())
+      .await?
+      .into_text_decoded()?;
+    let names = Arc::new(parse_jsr_search_response(&file.source)?);
+    self
+      .search_cache
+      .lock()
+      .insert(query.to_string(), names.clone());
+    Ok(names)
+  }
+
+  async fn versions(&self, name: &str) -> Result<Arc<Vec<Version>>, AnyError> {
+    if let Some(versions) = self.versions_cache.lock().get(name) {
+      return Ok(versions.clone());
+    }
+    let info_url = deno_registry_url().join(&format!("{}/meta.json", name))?;
+    let info = self.fetch_json::<JsrPackageInfo>(&info_url).await?;
+    let mut versions = info
+      .versions
+      .iter()
+      .filter(|(_, info)| !info.yanked)
+      .filter_map(|(version, _)| Version::parse_standard(version).ok())
+      .collect::<Vec<_>>();
+    versions.sort();
+    versions.reverse();
+    let versions = Arc::new(versions);
+    self
+      .versions_cache
+      .lock()
+      .insert(name.to_string(), versions.clone());
+    Ok(versions)
+  }
+
+  async fn exports(
+    &self,
+    name: &str,
+    version: &Version,
+  ) -> Result<Arc<Vec<String>>, AnyError> {
+    let nv = format!("{}@{}", name, version);
+    if let Some(exports) = self.exports_cache.lock().get(&nv) {
+      return Ok(exports.clone());
+    }
+    let version_info_url =
+      deno_registry_url().join(&format!("{}/{}_meta.json", name, version))?;
+    let version_info = self
+      .fetch_json::<JsrPackageVersionInfo>(&version_info_url)
+      .await?;
+    let mut exports = version_info.exports.into_keys().collect::<Vec<_>>();
+    exports.sort();
+    let exports = Arc::new(exports);
+    self.exports_cache.lock().insert(nv, exports.clone());
+    Ok(exports)
+  }
+}
+
+fn parse_jsr_search_response(source: &str) -> Result<Vec<String>, AnyError> {
+  #[derive(Debug, Deserialize)]
+  struct Item {
+    scope: String,
+    name: String,
+  }
+  #[derive(Debug, Deserialize)]
+  struct Response {
+    items: Vec<Item>,
+  }
+  let items = serde_json::from_str::<Response>(source)
+    .map_err(|err| anyhow!("Invalid jsr search response: {}", err))?
+    .items;
+  Ok(
+    items
+      .into_iter()
+      .map(|i| format!("@{}/{}", i.scope, i.name))
+      .collect(),
+  )
+}
+
+#[derive(Debug, Deserialize)]
+struct JsrPackageInfo {
+  versions: HashMap<String, JsrPackageInfoVersion>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsrPackageInfoVersion {
+  #[serde(default)]
+  yanked: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsrPackageVersionInfo {
+  exports: HashMap<String, String>,
+}
+
+fn read_cached_json<T: DeserializeOwned>(
+  cache: &dyn HttpCache,
+  specifier: &ModuleSpecifier,
+) -> Option<T> {
+  let cache_key = cache.cache_item_key(specifier).ok()?;
+  let bytes = cache.read_file_bytes(&cache_key).ok()??;
+  serde_json::from_slice(&bytes).ok()
+}
+
+fn read_cached_version_info(
+  cache: &dyn HttpCache,
+  name: &str,
+  version: &Version,
+) -> Option<JsrPackageVersionInfo> {
+  let url = deno_registry_url()
+    .join(&format!("{}/{}_meta.json", name, version))
+    .ok()?;
+  read_cached_json(cache, &url)
+}
+
+/// Resolve a `jsr:` specifier to the module of the highest matching package
+/// version, using only the package metadata which is already in the cache.
+pub fn resolve_cached_jsr_specifier(
+  cache: &dyn HttpCache,
+  specifier: &ModuleSpecifier,
+) -> Option<ModuleSpecifier> {
+  let req_ref = JsrPackageReqReference::from_specifier(specifier).ok()?;
+  let req = req_ref.req();
+  let registry_url = deno_registry_url();
+  let info_url = registry_url.join(&format!("{}/meta.json", req.name)).ok()?;
+  let info: JsrPackageInfo = read_cached_json(cache, &info_url)?;
+  let version = info
+    .versions
+    .iter()
+    .filter(|(_, info)| !info.yanked)
+    .filter_map(|(version, _)| Version::parse_standard(version).ok())
+    .filter(|version| req.version_req.matches(version))
+    .max()?;
+  let version_info = read_cached_version_info(cache, &req.name, &version)?;
+  let export_name = match req_ref.sub_path() {
+    Some(sub_path) => format!("./{}", sub_path),
+    None => ".".to_string(),
+  };
+  let path = version_info.exports.get(&export_name)?;
+  registry_url
+    .join(&format!(
+      "{}/{}/{}",
+      req.name,
+      version,
+      path.trim_start_matches("./")
+    ))
+    .ok()
+}
+
+/// Get a `jsr:` specifier for a module of a cached jsr package, if the module
+/// is exported by the package.
+pub fn cached_jsr_module_to_specifier(
+  cache: &dyn HttpCache,
+  specifier: &ModuleSpecifier,
+) -> Option<String> {
+  let path = specifier
+    .as_str()
+    .strip_prefix(deno_registry_url().as_str())?;
+  let mut parts = path.splitn(4, '/');
+  let scope = parts.next().filter(|scope| scope.starts_with('@'))?;
+  let name = format!("{}/{}", scope, parts.next()?);
+  let version = Version::parse_standard(parts.next()?).ok()?;
+  let module_path = format!("./{}", parts.next()?);
+  let version_info = read_cached_version_info(cache, &name, &version)?;
+  let (export_name, _) = version_info
+    .exports
+    .iter()
+    .filter(|(_, path)| **path == module_path)
+    .min_by_key(|(export_name, _)| export_name.len())?;
+  Some(format!(
+    "jsr:{}@^{}{}",
+    name,
+    version,
+    export_name.trim_start_matches('.')
+  ))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::cache::GlobalHttpCache;
+  use crate::cache::RealDenoCacheEnv;
+  use pretty_assertions::assert_eq;
+  use test_util::TempDir;
+
+  #[test]
+  fn test_parse_jsr_search_response() {
+    // This is a subset of a realistic response only containing data currently
+    // used by our parser.
+    let names = parse_jsr_search_response(r#"{"items":[{"scope":"std","name":"path","description":""},{"scope":"std","name":"fs"}],"total":2}"#).unwrap();
+    assert_eq!(names, vec!["@std/path".to_string(), "@std/fs".to_string()]);
+  }
+
+  #[test]
+  fn test_cached_jsr_resolution() {
+    let temp_dir = TempDir::new();
+    let cache = GlobalHttpCache::new(
+      temp_dir.path().join("deps").to_path_buf(),
+      RealDenoCacheEnv,
+    );
+    let registry_url = deno_registry_url();
+    for (path, content) in [
+      (
+        "@std/path/meta.json",
+        r#"{ "versions": { "0.1.0": {}, "0.2.0": {}, "0.3.0": { "yanked": true } } }"#,
+      ),
+      (
+        "@std/path/0.2.0_meta.json",
+        r#"{ "exports": { ".": "./mod.ts", "./join": "./join.ts" } }"#,
+      ),
+    ] {
+      cache
+        .set(
+          &registry_url.join(path).unwrap(),
+          HashMap::default(),
+          content.as_bytes(),
+        )
+        .unwrap();
+    }
+    let resolve = |specifier: &str| {
+      resolve_cached_jsr_specifier(
+        &cache,
+        &ModuleSpecifier::parse(specifier).unwrap(),
+      )
+      .map(|s| s.to_string())
+    };
+    assert_eq!(
+      resolve("jsr:@std/path"),
+      Some(format!("{}@std/path/0.2.0/mod.ts", registry_url))
+    );
+    assert_eq!(
+      resolve("jsr:@std/path@0.2/join"),
+      Some(format!("{}@std/path/0.2.0/join.ts", registry_url))
+    );
+    assert_eq!(resolve("jsr:@std/path@0.1"), None);
+    assert_eq!(resolve("jsr:@std/path/unknown"), None);
+    assert_eq!(resolve("jsr:@std/fs"), None);
+
+    let to_specifier = |path: &str| {
+      cached_jsr_module_to_specifier(&cache, &registry_url.join(path).unwrap())
+    };
+    assert_eq!(
+      to_specifier("@std/path/0.2.0/mod.ts"),
+      Some("jsr:@std/path@^0.2.0".to_string())
+    );
+    assert_eq!(
+      to_specifier("@std/path/0.2.0/join.ts"),
+      Some("jsr:@std/path@^0.2.0/join".to_string())
+    );
+    assert_eq!(to_specifier("@std/path/0.2.0/_util.ts"), None);
+  }
+}
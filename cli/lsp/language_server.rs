@@ -7,19 +7,23 @@ use deno_core::anyhow::anyhow;
 use deno_core::error::AnyError;
 use deno_core::parking_lot::Mutex;
 use deno_core::resolve_url;
+use deno_core::serde::Serialize;
 use deno_core::serde_json;
 use deno_core::serde_json::json;
 use deno_core::serde_json::Value;
 use deno_core::unsync::spawn;
 use deno_core::ModuleSpecifier;
 use deno_graph::GraphKind;
+use deno_graph::Resolution;
 use deno_lockfile::Lockfile;
 use deno_npm::NpmSystemInfo;
 use deno_runtime::deno_fs;
 use deno_runtime::deno_node::NodeResolver;
+use deno_runtime::deno_node::NpmResolver;
 use deno_runtime::deno_node::PackageJson;
 use deno_runtime::deno_tls::rustls::RootCertStore;
 use deno_runtime::deno_tls::RootCertStoreProvider;
+use deno_semver::npm::NpmPackageReqReference;
 use import_map::ImportMap;
 use indexmap::IndexSet;
 use log::error;
@@ -27,6 +31,7 @@ use serde_json::from_value;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::collections::VecDeque;
 use std::env;
 use std::fmt::Write as _;
 use std::path::Path;
@@ -51,11 +56,13 @@ use super::capabilities;
 use super::client::Client;
 use super::code_lens;
 use super::completions;
+use super::config::find_scope;
 use super::config::Config;
 use super::config::ConfigSnapshot;
 use super::config::UpdateImportsOnFileMoveEnabled;
 use super::config::WorkspaceSettings;
 use super::config::SETTINGS_SECTION;
+use super::deno_json;
 use super::diagnostics;
 use super::diagnostics::DiagnosticDataSpecifier;
 use super::diagnostics::DiagnosticServerUpdateMessage;
@@ -68,15 +75,21 @@ use super::documents::Document;
 use super::documents::Documents;
 use super::documents::DocumentsFilter;
 use super::documents::LanguageId;
+use super::documents::ScopedDocumentConfigOptions;
 use super::documents::UpdateDocumentConfigOptions;
+use super::jsr::CliJsrSearchApi;
 use super::logging::lsp_log;
 use super::logging::lsp_warn;
 use super::lsp_custom;
 use super::lsp_custom::TaskDefinition;
 use super::npm::CliNpmSearchApi;
+use super::package_metadata::to_hover_markdown;
+use super::package_metadata::PackageDependency;
+use super::package_metadata::PackageMetadataCache;
 use super::parent_process_checker;
 use super::performance::Performance;
 use super::performance::PerformanceMark;
+use super::preprocessors::DocumentPreprocessors;
 use super::refactor;
 use super::registries::ModuleRegistry;
 use super::testing;
@@ -85,19 +98,34 @@ use super::tsc;
 use super::tsc::Assets;
 use super::tsc::AssetsSnapshot;
 use super::tsc::GetCompletionDetailsArgs;
+use super::tsc::TsPlugins;
 use super::tsc::TsServer;
+use super::type_hierarchy;
 use super::urls;
+use crate::args::deno_registry_url;
+use crate::args::discover_config_file_from;
 use crate::args::get_root_cert_store;
 use crate::args::package_json;
+use crate::args::read_config_file_from_specifier;
+use crate::args::resolve_auth_tokens;
+use crate::args::resolve_client_certs;
 use crate::args::resolve_import_map_from_specifier;
+use crate::args::resolve_proxy_rules;
+use crate::args::resolve_scoped_fmt_options;
+use crate::args::resolve_scoped_lint_options;
+use crate::args::resolve_ts_plugins;
+use crate::args::resolve_typescript_version;
 use crate::args::CaData;
 use crate::args::CacheSetting;
 use crate::args::CliOptions;
 use crate::args::ConfigFile;
+use crate::args::ConfigFileExt;
 use crate::args::Flags;
 use crate::args::FmtOptions;
 use crate::args::LintOptions;
+use crate::args::ScopedOptions;
 use crate::args::TsConfig;
+use crate::auth_tokens::AuthTokens;
 use crate::cache::DenoDir;
 use crate::cache::FastInsecureHasher;
 use crate::cache::GlobalHttpCache;
@@ -117,12 +145,23 @@ use crate::npm::CliNpmResolverCreateOptions;
 use crate::npm::CliNpmResolverManagedCreateOptions;
 use crate::npm::CliNpmResolverManagedPackageJsonInstallerOption;
 use crate::npm::CliNpmResolverManagedSnapshotOption;
+use crate::tools::audit::NpmAdvisoryCache;
+use crate::tools::audit::NpmAdvisoryCacheFile;
+use crate::tools::cache::http_cache_stats;
 use crate::tools::fmt::format_file;
 use crate::tools::fmt::format_parsed_source;
+use crate::tools::info::DependencyEdgeKind;
+use crate::tools::info::DependencyGraphEdge;
+use crate::tools::info::DependencyGraphNode;
+use crate::tools::info::DependencyGraphView;
+use crate::tools::organize_imports::organize_imports;
+use crate::tools::run::hmr::send_reload_module_request;
 use crate::tools::upgrade::check_for_upgrades_for_lsp;
 use crate::tools::upgrade::upgrade_check_enabled;
+use crate::util::display::human_size;
 use crate::util::fs::remove_dir_all_if_exists;
 use crate::util::path::is_importable_ext;
+use crate::util::path::relative_specifier;
 use crate::util::path::specifier_to_file_path;
 use crate::util::progress_bar::ProgressBar;
 use crate::util::progress_bar::ProgressBarStyle;
@@ -145,6 +184,18 @@ struct LspNpmServices {
   node_resolver: Option<Arc<NodeResolver>>,
   /// Resolver for npm packages.
   resolver: Option<Arc<dyn CliNpmResolver>>,
+  /// The advisories which `deno audit` cached.
+  advisories: Option<NpmAdvisoryCacheFile>,
+  /// The resolvers of the workspace folders with their own config file by
+  /// the URI of the folder, which use the lockfile and the node_modules
+  /// directory of that config file.
+  scoped: BTreeMap<ModuleSpecifier, ScopedNpmResolvers>,
+}
+
+#[derive(Debug, Clone)]
+struct ScopedNpmResolvers {
+  node_resolver: Arc<NodeResolver>,
+  resolver: Arc<dyn CliNpmResolver>,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -158,6 +209,15 @@ impl LspNpmConfigHash {
     if let Some(lockfile) = inner.config.maybe_lockfile() {
       hasher.write_hashable(&*lockfile.lock());
     }
+    for (folder_uri, _) in inner.config.folder_config_files() {
+      hasher.write_str(folder_uri.as_str());
+      hasher.write_hashable(
+        inner.config.node_modules_dir_path_for_specifier(folder_uri),
+      );
+      if let Some(lockfile) = inner.config.lockfile_for_specifier(folder_uri) {
+        hasher.write_hashable(&*lockfile.lock());
+      }
+    }
     Self(hasher.finish())
   }
 }
@@ -169,6 +229,38 @@ pub struct LanguageServer(Arc<tokio::sync::RwLock<Inner>>, CancellationToken);
 pub struct StateNpmSnapshot {
   pub node_resolver: Arc<NodeResolver>,
   pub npm_resolver: Arc<dyn CliNpmResolver>,
+  pub advisories: Arc<NpmAdvisoryCache>,
+  /// The snapshots of the workspace folders with their own config file by the
+  /// URI of the folder.
+  pub scoped: BTreeMap<ModuleSpecifier, StateNpmSnapshot>,
+}
+
+impl StateNpmSnapshot {
+  fn new(
+    npm_resolver: &Arc<dyn CliNpmResolver>,
+    advisories: Arc<NpmAdvisoryCache>,
+  ) -> Self {
+    let npm_resolver = npm_resolver.clone_snapshotted();
+    let fs = Arc::new(deno_fs::RealFs);
+    let node_resolver = Arc::new(NodeResolver::new(
+      fs,
+      npm_resolver.clone().into_npm_resolver(),
+    ));
+    Self {
+      node_resolver,
+      npm_resolver,
+      advisories,
+      scoped: BTreeMap::new(),
+    }
+  }
+
+  /// Gets the snapshot of the innermost workspace folder with its own config
+  /// file which contains the specifier, or else the one of the root.
+  pub fn for_specifier(&self, specifier: &ModuleSpecifier) -> &Self {
+    find_scope(&self.scoped, specifier)
+      .map(|(_, snapshot)| snapshot)
+      .unwrap_or(self)
+  }
 }
 
 /// Snapshot of the state used by TSC.
@@ -241,10 +333,16 @@ pub struct Inner {
   initial_cwd: PathBuf,
   http_client: Arc<HttpClient>,
   task_queue: LanguageServerTaskQueue,
+  /// Jsr's search api.
+  jsr_search_api: CliJsrSearchApi,
   /// Handles module registries, which allow discovery of modules
   module_registries: ModuleRegistry,
   /// The path to the module registries cache
   module_registries_location: PathBuf,
+  /// Registry metadata of packages, shown when hovering their specifiers.
+  package_metadata: PackageMetadataCache,
+  /// Whether `--offline` was specified, so the network is never accessed.
+  offline: bool,
   /// An optional path to the DENO_DIR which has been specified in the client
   /// options.
   maybe_global_cache_path: Option<PathBuf>,
@@ -252,12 +350,18 @@ pub struct Inner {
   maybe_import_map: Option<Arc<ImportMap>>,
   /// The URL for the import map which is used to determine relative imports.
   maybe_import_map_uri: Option<Url>,
+  /// The import maps of the workspace folders other than the root by the URI
+  /// of the folder, which are used instead of the one above for the
+  /// specifiers in their folder.
+  scoped_import_maps: BTreeMap<ModuleSpecifier, Arc<ImportMap>>,
   /// An optional package.json configuration file.
   maybe_package_json: Option<PackageJson>,
-  /// Configuration for formatter which has been taken from specified config file.
-  fmt_options: FmtOptions,
-  /// An optional configuration for linter which has been taken from specified config file.
-  lint_options: LintOptions,
+  /// Configuration for formatter which has been taken from specified config
+  /// file and the config files of its workspace members.
+  fmt_options: ScopedOptions<FmtOptions>,
+  /// An optional configuration for linter which has been taken from specified
+  /// config file and the config files of its workspace members.
+  lint_options: ScopedOptions<LintOptions>,
   /// A lazily create "server" for handling test run requests.
   maybe_testing_server: Option<testing::TestServer>,
   /// Services used for dealing with npm related functionality.
@@ -273,9 +377,18 @@ pub struct Inner {
 }
 
 impl LanguageServer {
-  pub fn new(client: Client, token: CancellationToken) -> Self {
+  pub fn new(
+    client: Client,
+    token: CancellationToken,
+    offline: bool,
+    performance: Arc<Performance>,
+  ) -> Self {
     Self(
-      Arc::new(tokio::sync::RwLock::new(Inner::new(client))),
+      Arc::new(tokio::sync::RwLock::new(Inner::new(
+        client,
+        offline,
+        performance,
+      ))),
       token,
     )
   }
@@ -391,6 +504,12 @@ impl LanguageServer {
     Ok(Some(self.0.read().await.get_performance()))
   }
 
+  pub async fn performance_report_request(
+    &self,
+  ) -> LspResult<lsp_custom::PerformanceReport> {
+    Ok(self.0.read().await.get_performance_report())
+  }
+
   pub async fn task_definitions(&self) -> LspResult<Vec<TaskDefinition>> {
     self.0.read().await.task_definitions()
   }
@@ -489,24 +608,30 @@ impl LanguageServer {
 }
 
 impl Inner {
-  fn new(client: Client) -> Self {
+  fn new(client: Client, offline: bool, performance: Arc<Performance>) -> Self {
     let dir = DenoDir::new(None).expect("could not access DENO_DIR");
     let module_registries_location = dir.registries_folder_path();
-    let http_client = Arc::new(HttpClient::new(None, None));
+    let http_client =
+      Arc::new(HttpClient::new(None, None).with_offline(offline));
     let module_registries = ModuleRegistry::new(
       module_registries_location.clone(),
       http_client.clone(),
     );
     let npm_search_api =
       CliNpmSearchApi::new(module_registries.file_fetcher.clone(), None);
+    let package_metadata =
+      PackageMetadataCache::new(module_registries.file_fetcher.clone());
     let location = dir.deps_folder_path();
     let deps_http_cache = Arc::new(GlobalHttpCache::new(
       location,
       crate::cache::RealDenoCacheEnv,
     ));
+    let jsr_search_api = CliJsrSearchApi::new(
+      module_registries.file_fetcher.clone(),
+      deps_http_cache.clone(),
+    );
     let documents = Documents::new(deps_http_cache.clone());
     let cache_metadata = cache::CacheMetadata::new(deps_http_cache.clone());
-    let performance = Arc::new(Performance::default());
     let ts_server =
       Arc::new(TsServer::new(performance.clone(), deps_http_cache.clone()));
     let config = Config::new();
@@ -533,13 +658,17 @@ impl Inner {
       documents,
       http_client,
       initial_cwd: initial_cwd.clone(),
+      jsr_search_api,
       maybe_global_cache_path: None,
       maybe_import_map: None,
       maybe_import_map_uri: None,
+      scoped_import_maps: BTreeMap::new(),
       maybe_package_json: None,
-      fmt_options: FmtOptions::new_with_base(initial_cwd.clone()),
+      fmt_options: ScopedOptions::new(FmtOptions::new_with_base(
+        initial_cwd.clone(),
+      )),
       task_queue: Default::default(),
-      lint_options: LintOptions::new_with_base(initial_cwd),
+      lint_options: ScopedOptions::new(LintOptions::new_with_base(initial_cwd)),
       maybe_testing_server: None,
       module_registries,
       module_registries_location,
@@ -548,7 +677,11 @@ impl Inner {
         search_api: npm_search_api,
         node_resolver: None,
         resolver: None,
+        advisories: None,
+        scoped: BTreeMap::new(),
       },
+      package_metadata,
+      offline,
       performance,
       ts_fixable_diagnostics: Default::default(),
       ts_server,
@@ -620,7 +753,9 @@ impl Inner {
     Ok(navigation_tree)
   }
 
-  fn get_config_file(&self) -> Result<Option<ConfigFile>, AnyError> {
+  fn get_config_file(
+    &self,
+  ) -> Result<Option<(ConfigFile, ConfigFileExt)>, AnyError> {
     let workspace_settings = self.config.workspace_settings();
     let maybe_config = &workspace_settings.config;
     if let Some(config_str) = maybe_config {
@@ -640,7 +775,7 @@ impl Inner {
         }?;
         lsp_log!("  Resolved configuration file: \"{}\"", config_url);
 
-        let config_file = ConfigFile::from_specifier(config_url)?;
+        let config_file = read_config_file_from_specifier(config_url)?;
         return Ok(Some(config_file));
       }
     }
@@ -653,16 +788,70 @@ impl Inner {
     if let Some(root_uri) = self.config.root_uri() {
       let root_path = specifier_to_file_path(root_uri)?;
       let mut checked = std::collections::HashSet::new();
-      let maybe_config = ConfigFile::discover_from(&root_path, &mut checked)?;
-      Ok(maybe_config.map(|c| {
+      let maybe_config = discover_config_file_from(&root_path, &mut checked)?;
+      Ok(maybe_config.map(|(c, ext)| {
         lsp_log!("  Auto-resolved configuration file: \"{}\"", c.specifier);
-        c
+        (c, ext)
       }))
     } else {
       Ok(None)
     }
   }
 
+  /// Gets the config files of the workspace folders other than the root,
+  /// which are set in the settings of the folder or else auto-discovered from
+  /// it. A folder which only finds the config file of the root shares the
+  /// configuration of the root.
+  fn get_folder_config_files(
+    &self,
+    maybe_root_config_file: Option<&ConfigFile>,
+  ) -> Vec<(ModuleSpecifier, ConfigFile, ConfigFileExt)> {
+    let mut config_files = Vec::new();
+    for (folder_uri, _) in self.config.workspace_folders.iter().skip(1) {
+      let maybe_config_str = self
+        .config
+        .folder_workspace_settings(folder_uri)
+        .and_then(|s| s.config.as_deref())
+        .filter(|s| !s.is_empty());
+      let result = if let Some(config_str) = maybe_config_str {
+        Url::from_file_path(config_str)
+          .or_else(|_| folder_uri.join(config_str))
+          .map_err(|_| {
+            anyhow!("Bad file path for configuration file: \"{}\"", config_str)
+          })
+          .and_then(read_config_file_from_specifier)
+          .map(Some)
+      } else {
+        specifier_to_file_path(folder_uri).and_then(|folder_path| {
+          let mut checked = std::collections::HashSet::new();
+          discover_config_file_from(&folder_path, &mut checked)
+        })
+      };
+      match result {
+        Ok(Some((config_file, config_file_ext))) => {
+          if maybe_root_config_file
+            .is_some_and(|root| root.specifier == config_file.specifier)
+          {
+            continue;
+          }
+          lsp_log!(
+            "  Resolved configuration file of \"{}\": \"{}\"",
+            folder_uri,
+            config_file.specifier
+          );
+          config_files.push((folder_uri.clone(), config_file, config_file_ext));
+        }
+        Ok(None) => {}
+        Err(err) => lsp_warn!(
+          "Unable to load the configuration file of \"{}\": {:#}",
+          folder_uri,
+          err
+        ),
+      }
+    }
+    config_files
+  }
+
   fn get_package_json(
     &self,
     maybe_config_file: Option<&ConfigFile>,
@@ -689,6 +878,18 @@ impl Inner {
     }
   }
 
+  /// Modules within npm packages (including the types of the node built-ins)
+  /// are dependencies of enabled modules, so navigating into them should work
+  /// even though they're outside of the enabled paths.
+  fn is_enabled_or_npm_package(&self, specifier: &ModuleSpecifier) -> bool {
+    self.config.specifier_enabled(specifier)
+      || self
+        .npm
+        .resolver
+        .as_ref()
+        .is_some_and(|resolver| resolver.in_npm_package(specifier))
+  }
+
   fn is_diagnosable(&self, specifier: &ModuleSpecifier) -> bool {
     if specifier.scheme() == "asset" {
       matches!(
@@ -732,20 +933,26 @@ impl Inner {
   }
 
   pub fn snapshot(&self) -> Arc<StateSnapshot> {
-    let maybe_state_npm_snapshot = self
-      .npm
-      .resolver
-      .as_ref()
-      .map(|resolver| resolver.clone_snapshotted())
-      .map(|resolver| {
-        let fs = Arc::new(deno_fs::RealFs);
-        let node_resolver =
-          Arc::new(NodeResolver::new(fs, resolver.clone().into_npm_resolver()));
-        StateNpmSnapshot {
-          node_resolver,
-          npm_resolver: resolver,
-        }
-      });
+    let maybe_state_npm_snapshot = self.npm.resolver.as_ref().map(|resolver| {
+      let advisories = self
+        .npm
+        .advisories
+        .as_ref()
+        .map(|advisories| advisories.get())
+        .unwrap_or_default();
+      let mut snapshot = StateNpmSnapshot::new(resolver, advisories.clone());
+      snapshot.scoped = self
+        .npm
+        .scoped
+        .iter()
+        .map(|(folder_uri, scoped)| {
+          let scoped_snapshot =
+            StateNpmSnapshot::new(&scoped.resolver, advisories.clone());
+          (folder_uri.clone(), scoped_snapshot)
+        })
+        .collect();
+      snapshot
+    });
     Arc::new(StateSnapshot {
       assets: self.assets.snapshot(),
       cache_metadata: self.cache_metadata.clone(),
@@ -820,18 +1027,50 @@ impl Inner {
     let root_cert_store_provider =
       Arc::new(LspRootCertStoreProvider(root_cert_store));
     let module_registries_location = dir.registries_folder_path();
-    self.http_client = Arc::new(HttpClient::new(
-      Some(root_cert_store_provider),
-      workspace_settings
-        .unsafely_ignore_certificate_errors
-        .clone(),
-    ));
+    let auth_tokens =
+      match resolve_auth_tokens(self.config.maybe_config_file_ext()) {
+        Ok(auth_tokens) => auth_tokens,
+        Err(err) => {
+          lsp_warn!("{:#}", err);
+          AuthTokens::new(std::env::var("DENO_AUTH_TOKENS").ok())
+        }
+      };
+    let maybe_proxy_rules =
+      match resolve_proxy_rules(self.config.maybe_config_file_ext()) {
+        Ok(maybe_proxy_rules) => maybe_proxy_rules,
+        Err(err) => {
+          lsp_warn!("{:#}", err);
+          None
+        }
+      };
+    let client_certs =
+      match resolve_client_certs(self.config.maybe_config_file_ext()) {
+        Ok(client_certs) => client_certs,
+        Err(err) => {
+          lsp_warn!("{:#}", err);
+          Vec::new()
+        }
+      };
+    self.http_client = Arc::new(
+      HttpClient::new(
+        Some(root_cert_store_provider),
+        workspace_settings
+          .unsafely_ignore_certificate_errors
+          .clone(),
+      )
+      .with_offline(self.offline)
+      .with_auth_tokens(auth_tokens)
+      .with_proxy_rules(maybe_proxy_rules)
+      .with_client_certs(client_certs),
+    );
     self.module_registries = ModuleRegistry::new(
       module_registries_location.clone(),
       self.http_client.clone(),
     );
     self.npm.search_api =
       CliNpmSearchApi::new(self.module_registries.file_fetcher.clone(), None);
+    self.package_metadata =
+      PackageMetadataCache::new(self.module_registries.file_fetcher.clone());
     self.module_registries_location = module_registries_location;
     // update the cache path
     let global_cache = Arc::new(GlobalHttpCache::new(
@@ -847,13 +1086,42 @@ impl Inner {
       .map(|c| c as Arc<dyn HttpCache>)
       .unwrap_or(global_cache);
     self.deps_http_cache = cache.clone();
+    self.jsr_search_api = CliJsrSearchApi::new(
+      self.module_registries.file_fetcher.clone(),
+      cache.clone(),
+    );
     self.documents.set_cache(cache.clone());
     self.cache_metadata.set_cache(cache);
     self.url_map.set_cache(maybe_local_cache);
+    self.url_map.set_npm_cache_dir(&dir.npm_folder_path());
     self.maybe_global_cache_path = new_cache_path;
     Ok(())
   }
 
+  /// Resolves the compiler pinned by the `"typescriptVersion"` of the
+  /// configuration file, downloading and snapshotting it on first use. A
+  /// change of the version is only used after a restart of the server.
+  async fn resolve_pinned_typescript(
+    &self,
+  ) -> Result<Option<Arc<crate::tsc::PinnedTypeScript>>, AnyError> {
+    let Some(version) =
+      resolve_typescript_version(self.config.maybe_config_file_ext())?
+    else {
+      return Ok(None);
+    };
+    let deno_dir = DenoDir::new(self.maybe_global_cache_path.clone())?;
+    let pinned_typescript = crate::tsc::PinnedTypeScript::new(
+      &deno_dir.tsc_snapshots_folder_path(),
+      version,
+    );
+    if let Some(npm_resolver) = &self.npm.resolver {
+      pinned_typescript
+        .ensure_cached(npm_resolver.as_ref())
+        .await?;
+    }
+    Ok(Some(Arc::new(pinned_typescript)))
+  }
+
   async fn recreate_npm_services_if_necessary(&mut self) {
     let deno_dir = match DenoDir::new(self.maybe_global_cache_path.clone()) {
       Ok(deno_dir) => deno_dir,
@@ -881,6 +1149,53 @@ impl Inner {
       npm_resolver.clone().into_npm_resolver(),
     )));
     self.npm.resolver = Some(npm_resolver);
+    self.npm.advisories =
+      Some(NpmAdvisoryCacheFile::new(deno_dir.npm_audit_file_path()));
+
+    let folder_configs = self
+      .config
+      .folder_config_files()
+      .map(|(folder_uri, config_file)| {
+        (
+          folder_uri.clone(),
+          config_file.clone(),
+          self.config.lockfile_for_specifier(folder_uri).cloned(),
+          self
+            .config
+            .node_modules_dir_path_for_specifier(folder_uri)
+            .cloned(),
+        )
+      })
+      .collect::<Vec<_>>();
+    let mut scoped = BTreeMap::new();
+    for (folder_uri, config_file, maybe_lockfile, maybe_node_modules_dir) in
+      folder_configs
+    {
+      let Ok(folder_path) = specifier_to_file_path(&folder_uri) else {
+        continue;
+      };
+      let resolver = create_npm_resolver(
+        &deno_dir,
+        &folder_path,
+        &self.http_client,
+        Some(&config_file),
+        maybe_lockfile.as_ref(),
+        maybe_node_modules_dir,
+      )
+      .await;
+      let node_resolver = Arc::new(NodeResolver::new(
+        Arc::new(deno_fs::RealFs),
+        resolver.clone().into_npm_resolver(),
+      ));
+      scoped.insert(
+        folder_uri,
+        ScopedNpmResolvers {
+          node_resolver,
+          resolver,
+        },
+      );
+    }
+    self.npm.scoped = scoped;
 
     // update the hash
     self.npm.config_hash = config_hash;
@@ -889,14 +1204,63 @@ impl Inner {
   pub async fn update_import_map(&mut self) -> Result<(), AnyError> {
     let mark = self.performance.mark("lsp.update_import_map");
 
-    let maybe_import_map_url = self.resolve_import_map_specifier()?;
+    // the import maps of the other workspace folders are only loaded from
+    // their own settings and config file
+    let mut scoped_import_maps = BTreeMap::new();
+    for (folder_uri, _) in self.config.workspace_folders.iter().skip(1) {
+      let maybe_config_file = self
+        .config
+        .folder_config_files()
+        .find(|(uri, _)| *uri == folder_uri)
+        .map(|(_, config_file)| config_file);
+      let maybe_import_map_setting = self
+        .config
+        .folder_workspace_settings(folder_uri)
+        .and_then(|s| s.import_map.as_deref());
+      let import_map = async {
+        let Some(import_map_url) = Self::resolve_import_map_specifier(
+          maybe_import_map_setting,
+          maybe_config_file,
+          Some(folder_uri),
+        )?
+        else {
+          return Ok(None);
+        };
+        self
+          .fetch_import_map(
+            &import_map_url,
+            maybe_config_file,
+            CacheSetting::RespectHeaders,
+          )
+          .await
+          .map(Some)
+      };
+      match import_map.await {
+        Ok(Some(import_map)) => {
+          scoped_import_maps.insert(folder_uri.clone(), Arc::new(import_map));
+        }
+        Ok(None) => {}
+        Err(err) => lsp_warn!("{:#}", err),
+      }
+    }
+    self.scoped_import_maps = scoped_import_maps;
+
+    let maybe_import_map_url = Self::resolve_import_map_specifier(
+      self.config.workspace_settings().import_map.as_deref(),
+      self.config.maybe_config_file(),
+      self.config.root_uri(),
+    )?;
     if let Some(import_map_url) = maybe_import_map_url {
       if import_map_url.scheme() != "data" {
         lsp_log!("  Resolved import map: \"{}\"", import_map_url);
       }
 
       let import_map = self
-        .fetch_import_map(&import_map_url, CacheSetting::RespectHeaders)
+        .fetch_import_map(
+          &import_map_url,
+          self.config.maybe_config_file(),
+          CacheSetting::RespectHeaders,
+        )
         .await?;
       self.maybe_import_map_uri = Some(import_map_url);
       self.maybe_import_map = Some(Arc::new(import_map));
@@ -911,11 +1275,12 @@ impl Inner {
   async fn fetch_import_map(
     &self,
     import_map_url: &ModuleSpecifier,
+    maybe_config_file: Option<&ConfigFile>,
     cache_setting: CacheSetting,
   ) -> Result<ImportMap, AnyError> {
     resolve_import_map_from_specifier(
       import_map_url,
-      self.config.maybe_config_file(),
+      maybe_config_file,
       &self.create_file_fetcher(cache_setting),
     )
     .await
@@ -941,31 +1306,31 @@ impl Inner {
     file_fetcher
   }
 
+  /// Resolves the import map of the `"deno.importMap"` setting, which is
+  /// relative to the base URI, or else the one of the config file.
   fn resolve_import_map_specifier(
-    &self,
+    maybe_import_map_setting: Option<&str>,
+    maybe_config_file: Option<&ConfigFile>,
+    maybe_base_uri: Option<&Url>,
   ) -> Result<Option<ModuleSpecifier>, AnyError> {
     Ok(
-      if let Some(import_map_str) = self
-        .config
-        .workspace_settings()
-        .import_map
-        .clone()
-        .and_then(|s| if s.is_empty() { None } else { Some(s) })
+      if let Some(import_map_str) =
+        maybe_import_map_setting.filter(|s| !s.is_empty())
       {
         lsp_log!(
           "Setting import map from workspace settings: \"{}\"",
           import_map_str
         );
-        if let Some(config_file) = self.config.maybe_config_file() {
+        if let Some(config_file) = maybe_config_file {
           if let Some(import_map_path) = config_file.to_import_map_path() {
             lsp_log!("Warning: Import map \"{}\" configured in \"{}\" being ignored due to an import map being explicitly configured in workspace settings.", import_map_path, config_file.specifier);
           }
         }
-        if let Ok(url) = Url::parse(&import_map_str) {
+        if let Ok(url) = Url::parse(import_map_str) {
           Some(url)
-        } else if let Some(root_uri) = self.config.root_uri() {
-          let root_path = specifier_to_file_path(root_uri)?;
-          let import_map_path = root_path.join(&import_map_str);
+        } else if let Some(base_uri) = maybe_base_uri {
+          let base_path = specifier_to_file_path(base_uri)?;
+          let import_map_path = base_path.join(import_map_str);
           let import_map_url =
             Url::from_file_path(import_map_path).map_err(|_| {
               anyhow!("Bad file path for import map: {}", import_map_str)
@@ -977,7 +1342,7 @@ impl Inner {
             import_map_str
           ));
         }
-      } else if let Some(config_file) = self.config.maybe_config_file() {
+      } else if let Some(config_file) = maybe_config_file {
         if config_file.is_an_import_map() {
           lsp_log!(
             "Setting import map defined in configuration file: \"{}\"",
@@ -1018,7 +1383,39 @@ impl Inner {
 
   pub fn update_debug_flag(&self) {
     let internal_debug = self.config.workspace_settings().internal_debug;
-    super::logging::set_lsp_debug_flag(internal_debug)
+    // `deno lsp --log-level=debug` logs the debug messages as well
+    let debug_log_level = log::max_level() >= log::LevelFilter::Debug;
+    super::logging::set_lsp_debug_flag(internal_debug || debug_log_level)
+  }
+
+  /// Updates the directory in which untitled and other virtual documents are
+  /// assumed to be, which is `deno.untitledRoot` or the root of the
+  /// workspace.
+  fn update_untitled_root(&mut self) {
+    let Ok(root_path) = self
+      .config
+      .root_uri()
+      .map(specifier_to_file_path)
+      .unwrap_or_else(|| Ok(self.initial_cwd.clone()))
+    else {
+      self.url_map.set_untitled_root(None);
+      return;
+    };
+    let root_path = match &self.config.workspace_settings().untitled_root {
+      Some(untitled_root) => {
+        lsp_log!(
+          "Setting untitled root from workspace settings: \"{}\"",
+          untitled_root
+        );
+        root_path.join(untitled_root)
+      }
+      None => root_path,
+    };
+    let untitled_root = Url::from_directory_path(&root_path).ok();
+    if untitled_root.is_none() {
+      lsp_warn!("Bad path for the untitled root: {}", root_path.display());
+    }
+    self.url_map.set_untitled_root(untitled_root);
   }
 
   async fn update_registries(&mut self) -> Result<(), AnyError> {
@@ -1039,27 +1436,53 @@ impl Inner {
 
   async fn update_config_file(&mut self) -> Result<(), AnyError> {
     self.config.clear_config_file();
-    self.fmt_options = FmtOptions::new_with_base(self.initial_cwd.clone());
-    self.lint_options = LintOptions::new_with_base(self.initial_cwd.clone());
-    if let Some(config_file) = self.get_config_file()? {
-      let lint_options = config_file
-        .to_lint_config()
-        .and_then(|maybe_lint_config| {
-          LintOptions::resolve(maybe_lint_config, None, &self.initial_cwd)
+    self.fmt_options =
+      ScopedOptions::new(FmtOptions::new_with_base(self.initial_cwd.clone()));
+    self.lint_options =
+      ScopedOptions::new(LintOptions::new_with_base(self.initial_cwd.clone()));
+    let maybe_config_file = self.get_config_file()?;
+    let folder_config_files =
+      self.get_folder_config_files(maybe_config_file.as_ref().map(|(c, _)| c));
+    self.config.set_folder_config_files(folder_config_files);
+    if let Some((config_file, config_file_ext)) = maybe_config_file {
+      self.config.set_config_file(config_file, config_file_ext);
+    }
+    if self.config.has_config_file()
+      || self.config.folder_config_files().next().is_some()
+    {
+      let lint_options = resolve_scoped_lint_options(
+        self.config.maybe_config_file(),
+        self.config.member_config_files(),
+        None,
+        &self.initial_cwd,
+      )
+      .and_then(|options| {
+        with_folder_options(options, &self.config, |cf, members, dir| {
+          resolve_scoped_lint_options(Some(cf), members, None, dir)
         })
-        .map_err(|err| {
-          anyhow!("Unable to update lint configuration: {:?}", err)
-        })?;
-      let fmt_options = config_file
-        .to_fmt_config()
-        .and_then(|maybe_fmt_config| {
-          FmtOptions::resolve(maybe_fmt_config, None, &self.initial_cwd)
+      })
+      .map_err(|err| anyhow!("Unable to update lint configuration: {:?}", err));
+      let fmt_options = resolve_scoped_fmt_options(
+        self.config.maybe_config_file(),
+        self.config.member_config_files(),
+        None,
+        &self.initial_cwd,
+      )
+      .and_then(|options| {
+        with_folder_options(options, &self.config, |cf, members, dir| {
+          resolve_scoped_fmt_options(Some(cf), members, None, dir)
         })
-        .map_err(|err| {
-          anyhow!("Unable to update formatter configuration: {:?}", err)
-        })?;
-
-      self.config.set_config_file(config_file);
+      })
+      .map_err(|err| {
+        anyhow!("Unable to update formatter configuration: {:?}", err)
+      });
+      let (lint_options, fmt_options) = match (lint_options, fmt_options) {
+        (Ok(lint_options), Ok(fmt_options)) => (lint_options, fmt_options),
+        (Err(err), _) | (_, Err(err)) => {
+          self.config.clear_config_file();
+          return Err(err);
+        }
+      };
       self.lint_options = lint_options;
       self.fmt_options = fmt_options;
       self.recreate_http_client_and_dependents().await?;
@@ -1128,12 +1551,50 @@ impl Inner {
     if let Err(err) = self.merge_user_tsconfig(&mut tsconfig) {
       self.client.show_message(MessageType::WARNING, err);
     }
-    let _ok = self.ts_server.configure(self.snapshot(), tsconfig).await?;
+    let maybe_config_file = self.config.maybe_config_file();
+    let plugins = TsPlugins {
+      referrer: maybe_config_file
+        .and_then(|config_file| config_file.specifier.to_file_path().ok()),
+      plugins: resolve_ts_plugins(maybe_config_file).unwrap_or_else(|err| {
+        self.client.show_message(MessageType::WARNING, err);
+        vec![]
+      }),
+    };
+    let _ok = self
+      .ts_server
+      .configure(self.snapshot(), tsconfig, plugins)
+      .await?;
     self.performance.measure(mark);
     Ok(())
   }
 }
 
+/// Adds the options of the config files of the workspace folders other than
+/// the root, which are resolved as roots themselves so they don't inherit the
+/// options of the root config file.
+fn with_folder_options<T>(
+  options: ScopedOptions<T>,
+  config: &Config,
+  resolve: impl Fn(
+    &ConfigFile,
+    &[ConfigFile],
+    &Path,
+  ) -> Result<ScopedOptions<T>, AnyError>,
+) -> Result<ScopedOptions<T>, AnyError> {
+  let mut members = options.members;
+  for (folder_uri, config_file) in config.folder_config_files() {
+    let folder_path = specifier_to_file_path(folder_uri)?;
+    let folder_options = resolve(
+      config_file,
+      config.member_config_files_for_specifier(folder_uri),
+      &folder_path,
+    )?;
+    members.push((folder_path, folder_options.root));
+    members.extend(folder_options.members);
+  }
+  Ok(ScopedOptions::with_members(options.root, members))
+}
+
 async fn create_npm_resolver(
   deno_dir: &DenoDir,
   initial_cwd: &Path,
@@ -1175,10 +1636,14 @@ async fn create_npm_resolver(
       cache_setting: CacheSetting::Only,
       text_only_progress_bar: ProgressBar::new(ProgressBarStyle::TextOnly),
       maybe_node_modules_path: maybe_node_modules_dir_path,
+      workspace_npm_packages: Vec::new(),
+      node_modules_dir_mode: Default::default(),
+      allow_scripts: Vec::new(),
       // do not install while resolving in the lsp—leave that to the cache command
       package_json_installer:
         CliNpmResolverManagedPackageJsonInstallerOption::NoInstall,
       npm_registry_url: crate::args::npm_registry_default_url().to_owned(),
+      maybe_remote_cache: None,
       npm_system_info: NpmSystemInfo::default(),
     })
   })
@@ -1269,10 +1734,6 @@ impl Inner {
       self.config.update_capabilities(&params.capabilities);
     }
 
-    self
-      .ts_server
-      .start(self.config.internal_inspect().to_address());
-
     self.update_debug_flag();
     // Check to see if we need to change the cache path
     if let Err(err) = self.update_cache().await {
@@ -1284,6 +1745,19 @@ impl Inner {
     if let Err(err) = self.update_package_json() {
       self.client.show_message(MessageType::WARNING, err);
     }
+    // the server is started once the configuration file is known, since it
+    // decides which compiler is used
+    let maybe_pinned_typescript = match self.resolve_pinned_typescript().await {
+      Ok(maybe_pinned_typescript) => maybe_pinned_typescript,
+      Err(err) => {
+        self.client.show_message(MessageType::WARNING, err);
+        None
+      }
+    };
+    self.ts_server.start(
+      self.config.internal_inspect().to_address(),
+      maybe_pinned_typescript,
+    );
     if let Err(err) = self.update_tsconfig().await {
       self.client.show_message(MessageType::WARNING, err);
     }
@@ -1317,6 +1791,34 @@ impl Inner {
   }
 
   async fn refresh_documents_config(&mut self) {
+    self.update_untitled_root();
+    let folder_config_files =
+      self.config.folder_config_files().collect::<Vec<_>>();
+    let mut scopes = BTreeMap::new();
+    let folder_uris = folder_config_files
+      .iter()
+      .map(|(folder_uri, _)| *folder_uri)
+      .chain(self.scoped_import_maps.keys());
+    for folder_uri in folder_uris {
+      let maybe_config_file = folder_config_files
+        .iter()
+        .find(|(uri, _)| *uri == folder_uri)
+        .map(|(_, config_file)| *config_file);
+      let maybe_npm = self.npm.scoped.get(folder_uri);
+      scopes.insert(
+        folder_uri.clone(),
+        ScopedDocumentConfigOptions {
+          maybe_import_map: self.scoped_import_maps.get(folder_uri).cloned(),
+          maybe_config_file,
+          node_resolver: maybe_npm
+            .map(|npm| npm.node_resolver.clone())
+            .or_else(|| self.npm.node_resolver.clone()),
+          npm_resolver: maybe_npm
+            .map(|npm| npm.resolver.clone())
+            .or_else(|| self.npm.resolver.clone()),
+        },
+      );
+    }
     self.documents.update_config(UpdateDocumentConfigOptions {
       file_patterns: FilePatterns {
         base: self.initial_cwd.clone(),
@@ -1327,11 +1829,21 @@ impl Inner {
         .config
         .workspace_settings()
         .document_preload_limit,
+      use_gitignore: self.config.workspace_settings().use_gitignore,
+      max_document_cache_size: self
+        .config
+        .workspace_settings()
+        .max_document_cache_mb
+        .map(|mb| mb * 1024 * 1024),
       maybe_import_map: self.maybe_import_map.clone(),
       maybe_config_file: self.config.maybe_config_file(),
       maybe_package_json: self.maybe_package_json.as_ref(),
       node_resolver: self.npm.node_resolver.clone(),
       npm_resolver: self.npm.resolver.clone(),
+      preprocessors: DocumentPreprocessors::new(
+        &self.config.workspace_settings().document_preprocessors,
+      ),
+      scopes,
     });
 
     // refresh the npm specifiers because it might have discovered
@@ -1358,7 +1870,9 @@ impl Inner {
           error!("{}", err);
           LanguageId::Unknown
         });
-    if language_id == LanguageId::Unknown {
+    if language_id == LanguageId::Unknown
+      && !self.documents.has_preprocessor(specifier)
+    {
       lsp_warn!(
         "Unsupported language id \"{}\" received for document \"{}\".",
         params.text_document.language_id,
@@ -1394,6 +1908,8 @@ impl Inner {
             .invalidate(&self.documents.dependents(&specifier));
           self.send_diagnostics_update();
           self.send_testing_update();
+        } else if self.config.is_config_file(&specifier) {
+          self.send_diagnostics_update();
         }
       }
       Err(err) => error!("{}", err),
@@ -1403,16 +1919,21 @@ impl Inner {
 
   async fn refresh_npm_specifiers(&mut self) {
     let package_reqs = self.documents.npm_package_reqs();
-    let npm_resolver = self.npm.resolver.clone();
+    let npm_resolvers = self
+      .npm
+      .resolver
+      .iter()
+      .chain(self.npm.scoped.values().map(|s| &s.resolver))
+      .cloned()
+      .collect::<Vec<_>>();
     // spawn to avoid the LSP's Send requirements
     let handle = spawn(async move {
-      if let Some(npm_resolver) =
-        npm_resolver.as_ref().and_then(|r| r.as_managed())
-      {
-        npm_resolver.set_package_reqs(&package_reqs).await
-      } else {
-        Ok(())
+      for npm_resolver in &npm_resolvers {
+        if let Some(npm_resolver) = npm_resolver.as_managed() {
+          npm_resolver.set_package_reqs(&package_reqs).await?;
+        }
       }
+      Ok::<_, AnyError>(())
     });
     if let Err(err) = handle.await.unwrap() {
       lsp_warn!("Could not set npm package requirements. {:#}", err);
@@ -1522,6 +2043,23 @@ impl Inner {
       {
         return true;
       }
+      // the config files of the other workspace folders are auto-discovered
+      // in each folder
+      if config
+        .folder_canonicalized_specifiers()
+        .any(|s| changes.contains(s))
+        || config
+          .folder_config_files()
+          .any(|(_, file)| changes.contains(&file.specifier))
+        || (config.workspace_folders.len() > 1
+          && changes.iter().any(|url| {
+            url.path().ends_with("/deno.json")
+              || url.path().ends_with("/deno.jsonc")
+              || url.path().ends_with("/deno.lock")
+          }))
+      {
+        return true;
+      }
       match config.maybe_config_file() {
         Some(file) => {
           if changes.contains(&file.specifier) {
@@ -1669,7 +2207,11 @@ impl Inner {
       .maybe_import_map_uri
       .as_ref()
       .map(|uri| changes.contains(uri))
-      .unwrap_or(false);
+      .unwrap_or(false)
+      || self
+        .scoped_import_maps
+        .values()
+        .any(|import_map| changes.contains(import_map.base_url()));
     if touched || import_map_changed {
       if let Err(err) = self.update_import_map().await {
         self.client.show_message(MessageType::WARNING, err);
@@ -1684,6 +2226,12 @@ impl Inner {
       self.ts_server.restart(self.snapshot()).await;
       self.send_diagnostics_update();
       self.send_testing_update();
+    } else if changes.iter().any(|url| url.path().ends_with(".wasm")) {
+      // the modules which import a Wasm module are checked against the
+      // declarations of its exports again
+      self.documents.increment_project_version();
+      self.diagnostics_server.invalidate_all();
+      self.send_diagnostics_update();
     }
     self.performance.measure(mark);
   }
@@ -1761,34 +2309,91 @@ impl Inner {
     &self,
     params: DocumentFormattingParams,
   ) -> LspResult<Option<Vec<TextEdit>>> {
-    let mut specifier = self
-      .url_map
-      .normalize_url(&params.text_document.uri, LspUrlKind::File);
+    self
+      .format_document(
+        &params.text_document.uri,
+        None,
+        "lsp.formatting",
+        &params,
+      )
+      .await
+  }
+
+  async fn range_formatting(
+    &self,
+    params: DocumentRangeFormattingParams,
+  ) -> LspResult<Option<Vec<TextEdit>>> {
+    self
+      .format_document(
+        &params.text_document.uri,
+        Some(params.range),
+        "lsp.range_formatting",
+        &params,
+      )
+      .await
+  }
+
+  async fn on_type_formatting(
+    &self,
+    params: DocumentOnTypeFormattingParams,
+  ) -> LspResult<Option<Vec<TextEdit>>> {
+    // only touch the line the trigger character was typed on, so the rest of
+    // the file isn't reformatted while the user is typing
+    let line = params.text_document_position.position.line;
+    let range = Range {
+      start: Position { line, character: 0 },
+      end: Position {
+        line: line + 1,
+        character: 0,
+      },
+    };
+    self
+      .format_document(
+        &params.text_document_position.text_document.uri,
+        Some(range),
+        "lsp.on_type_formatting",
+        &params,
+      )
+      .await
+  }
+
+  /// Format the document at `uri`. When `maybe_range` is provided, only the
+  /// edits which overlap that range are returned.
+  async fn format_document(
+    &self,
+    uri: &ModuleSpecifier,
+    maybe_range: Option<Range>,
+    mark_name: &str,
+    args: impl Serialize,
+  ) -> LspResult<Option<Vec<TextEdit>>> {
+    let mut specifier = self.url_map.normalize_url(uri, LspUrlKind::File);
     // skip formatting any files ignored by the config file
-    if !self.fmt_options.files.matches_specifier(&specifier) {
+    let fmt_options = self.fmt_options.for_specifier(&specifier);
+    let file_selection =
+      &self.config.file_selections_for_specifier(&specifier).fmt;
+    if !file_selection.matches_specifier(&fmt_options.files, &specifier) {
       return Ok(None);
     }
     let document = match self.documents.get(&specifier) {
-      Some(doc) if doc.is_open() => doc,
+      // the edits of a virtual document would replace the text of the editor
+      Some(doc) if doc.is_open() && !doc.is_virtual() => doc,
       _ => return Ok(None),
     };
     // Detect vendored paths. Vendor file URLs will normalize to their remote
     // counterparts, but for formatting we want to favour the file URL.
     // TODO(nayeemrmn): Implement `Document::file_resource_path()` or similar.
-    if specifier.scheme() != "file"
-      && params.text_document.uri.scheme() == "file"
-    {
-      specifier = params.text_document.uri.clone();
+    if specifier.scheme() != "file" && uri.scheme() == "file" {
+      specifier = uri.clone();
     }
     let file_path = specifier_to_file_path(&specifier).map_err(|err| {
       error!("{}", err);
       LspError::invalid_request()
     })?;
-    let mark = self.performance.mark_with_args("lsp.formatting", &params);
+    let mark = self.performance.mark_with_args(mark_name, args);
 
     // spawn a blocking task to allow doing other work while this is occurring
     let text_edits = deno_core::unsync::spawn_blocking({
-      let fmt_options = self.fmt_options.options.clone();
+      let fmt_options = fmt_options.options.clone();
       let document = document.clone();
       move || {
         let format_result = match document.maybe_parsed_source() {
@@ -1810,11 +2415,19 @@ impl Inner {
           }
         };
         match format_result {
-          Ok(Some(new_text)) => Some(text::get_edits(
-            &document.content(),
-            &new_text,
-            document.line_index().as_ref(),
-          )),
+          Ok(Some(new_text)) => {
+            let text_edits = text::get_edits(
+              &document.content(),
+              &new_text,
+              document.line_index().as_ref(),
+            );
+            match maybe_range {
+              Some(range) => {
+                Some(text::filter_edits_in_range(text_edits, &range))
+              }
+              None => Some(text_edits),
+            }
+          }
           Ok(None) => Some(Vec::new()),
           Err(err) => {
             lsp_warn!("Format error: {:#}", err);
@@ -1844,6 +2457,16 @@ impl Inner {
       &params.text_document_position_params.text_document.uri,
       LspUrlKind::File,
     );
+    if self.config.is_config_file(&specifier) {
+      let Some(document) = self.documents.get(&specifier) else {
+        return Ok(None);
+      };
+      return Ok(deno_json::get_config_file_hover(
+        &document.content(),
+        &document.line_index(),
+        params.text_document_position_params.position,
+      ));
+    }
     if !self.is_diagnosable(&specifier)
       || !self.config.specifier_enabled(&specifier)
     {
@@ -1852,7 +2475,7 @@ impl Inner {
 
     let mark = self.performance.mark_with_args("lsp.hover", &params);
     let asset_or_doc = self.get_asset_or_document(&specifier)?;
-    let hover = if let Some((_, dep, range)) = asset_or_doc
+    let hover = if let Some((dep_specifier, dep, range)) = asset_or_doc
       .get_maybe_dependency(&params.text_document_position_params.position)
     {
       let dep_maybe_types_dependency = dep
@@ -1897,6 +2520,15 @@ impl Inner {
         } else {
           value
         };
+      let value = if let Some(package) =
+        PackageDependency::from_specifier(&dep_specifier)
+      {
+        let metadata = self.package_metadata.get(&package).await;
+        let docs = to_hover_markdown(&package, metadata.as_deref());
+        format!("{value}\n\n---\n\n{docs}")
+      } else {
+        value
+      };
       Some(Hover {
         contents: HoverContents::Markup(MarkupContent {
           kind: MarkupKind::Markdown,
@@ -1925,6 +2557,11 @@ impl Inner {
     let specifier = self
       .url_map
       .normalize_url(&params.text_document.uri, LspUrlKind::File);
+    if self.config.is_config_file(&specifier) {
+      return Ok(deno_json::get_config_file_code_actions(
+        &params.context.diagnostics,
+      ));
+    }
     if !self.is_diagnosable(&specifier)
       || !self.config.specifier_enabled(&specifier)
     {
@@ -1981,10 +2618,10 @@ impl Inner {
                 line_index.offset_tsc(diagnostic.range.start)?
                   ..line_index.offset_tsc(diagnostic.range.end)?,
                 codes,
-                (&self.fmt_options.options).into(),
+                (&self.fmt_options.for_specifier(&specifier).options).into(),
                 tsc::UserPreferences::from_config_for_specifier(
                   &self.config,
-                  &self.fmt_options.options,
+                  &self.fmt_options.for_specifier(&specifier).options,
                   &specifier,
                 ),
               )
@@ -2015,7 +2652,7 @@ impl Inner {
               includes_no_cache = true;
             }
             code_actions
-              .add_deno_fix_action(&specifier, diagnostic)
+              .add_deno_fix_action(&specifier, diagnostic, &self.documents)
               .map_err(|err| {
                 error!("{}", err);
                 LspError::internal_error()
@@ -2073,7 +2710,7 @@ impl Inner {
           ..line_index.offset_tsc(params.range.end)?,
         Some(tsc::UserPreferences::from_config_for_specifier(
           &self.config,
-          &self.fmt_options.options,
+          &self.fmt_options.for_specifier(&specifier).options,
           &specifier,
         )),
         only,
@@ -2090,6 +2727,38 @@ impl Inner {
         .map(CodeActionOrCommand::CodeAction),
     );
 
+    // Source
+    let organize_imports_requested =
+      params.context.only.as_ref().is_some_and(|only| {
+        only.iter().any(|kind| {
+          CodeActionKind::SOURCE_ORGANIZE_IMPORTS
+            .as_str()
+            .starts_with(kind.as_str())
+        })
+      });
+    if organize_imports_requested {
+      if let Some(Ok(parsed_source)) = asset_or_doc.maybe_parsed_source() {
+        if let Some(new_text) =
+          organize_imports(&parsed_source, self.maybe_import_map.as_deref())
+        {
+          let text_edits =
+            text::get_edits(&asset_or_doc.text(), &new_text, &line_index);
+          all_actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+            title: "Organize imports".to_string(),
+            kind: Some(CodeActionKind::SOURCE_ORGANIZE_IMPORTS),
+            edit: Some(WorkspaceEdit {
+              changes: Some(HashMap::from([(
+                params.text_document.uri.clone(),
+                text_edits,
+              )])),
+              ..Default::default()
+            }),
+            ..Default::default()
+          }));
+        }
+      }
+    }
+
     let code_action_disabled_support =
       self.config.client_capabilities.code_action_disabled_support;
     let actions: Vec<CodeActionOrCommand> = all_actions.into_iter().filter(|ca| {
@@ -2127,15 +2796,17 @@ impl Inner {
           error!("Unable to decode code action data: {}", err);
           LspError::invalid_params("The CodeAction's data is invalid.")
         })?;
+      let fmt_options =
+        self.fmt_options.for_specifier(&code_action_data.specifier);
       let combined_code_actions = self
         .ts_server
         .get_combined_code_fix(
           self.snapshot(),
           &code_action_data,
-          (&self.fmt_options.options).into(),
+          (&fmt_options.options).into(),
           tsc::UserPreferences::from_config_for_specifier(
             &self.config,
-            &self.fmt_options.options,
+            &fmt_options.options,
             &code_action_data.specifier,
           ),
         )
@@ -2173,19 +2844,20 @@ impl Inner {
         })?;
       let asset_or_doc = self.get_asset_or_document(&action_data.specifier)?;
       let line_index = asset_or_doc.line_index();
+      let fmt_options = self.fmt_options.for_specifier(&action_data.specifier);
       let refactor_edit_info = self
         .ts_server
         .get_edits_for_refactor(
           self.snapshot(),
           action_data.specifier.clone(),
-          (&self.fmt_options.options).into(),
+          (&fmt_options.options).into(),
           line_index.offset_tsc(action_data.range.start)?
             ..line_index.offset_tsc(action_data.range.end)?,
           action_data.refactor_name,
           action_data.action_name,
           Some(tsc::UserPreferences::from_config_for_specifier(
             &self.config,
-            &self.fmt_options.options,
+            &fmt_options.options,
             &action_data.specifier,
           )),
         )
@@ -2232,15 +2904,21 @@ impl Inner {
     {
       if let Some(Ok(parsed_source)) = asset_or_doc.maybe_parsed_source() {
         code_lenses.extend(
-          code_lens::collect_test(&specifier, parsed_source).map_err(
-            |err| {
-              error!(
-                "Error getting test code lenses for \"{}\": {}",
-                &specifier, err
-              );
-              LspError::internal_error()
-            },
-          )?,
+          code_lens::collect_test(
+            &specifier,
+            parsed_source,
+            self
+              .maybe_testing_server
+              .as_ref()
+              .map(|s| s.results_for_specifier(&specifier)),
+          )
+          .map_err(|err| {
+            error!(
+              "Error getting test code lenses for \"{}\": {}",
+              &specifier, err
+            );
+            LspError::internal_error()
+          })?,
         );
       }
     }
@@ -2379,15 +3057,24 @@ impl Inner {
         let reference_line_index = if reference_specifier == specifier {
           line_index.clone()
         } else {
-          let asset_or_doc =
-            self.get_asset_or_document(&reference_specifier)?;
-          asset_or_doc.line_index()
+          // references may point into cached remote modules, so don't fail
+          // the whole request when one of them can no longer be loaded
+          match self.get_asset_or_document(&reference_specifier) {
+            Ok(asset_or_doc) => asset_or_doc.line_index(),
+            Err(err) => {
+              lsp_warn!("Unable to get reference location: {}", err);
+              continue;
+            }
+          }
         };
-        results.push(
-          reference
-            .entry
-            .to_location(reference_line_index, &self.url_map),
-        );
+        let location = reference
+          .entry
+          .to_location(reference_line_index, &self.url_map);
+        // the same reference is reported for each symbol it's re-exported
+        // as, for example through the `mod.ts` of a remote module
+        if !results.contains(&location) {
+          results.push(location);
+        }
       }
 
       self.performance.measure(mark);
@@ -2489,6 +3176,16 @@ impl Inner {
       &params.text_document_position.text_document.uri,
       LspUrlKind::File,
     );
+    if self.config.is_config_file(&specifier) {
+      let Some(document) = self.documents.get(&specifier) else {
+        return Ok(None);
+      };
+      return Ok(deno_json::get_config_file_completions(
+        &document.content(),
+        &document.line_index(),
+        params.text_document_position.position,
+      ));
+    }
     let language_settings =
       self.config.language_settings_for_specifier(&specifier);
     if !self.is_diagnosable(&specifier)
@@ -2516,6 +3213,8 @@ impl Inner {
         &self.client,
         &self.module_registries,
         &self.npm.search_api,
+        &self.jsr_search_api,
+        self.npm.resolver.as_deref(),
         &self.documents,
         self.maybe_import_map.clone(),
       )
@@ -2543,13 +3242,13 @@ impl Inner {
           tsc::GetCompletionsAtPositionOptions {
             user_preferences: tsc::UserPreferences::from_config_for_specifier(
               &self.config,
-              &self.fmt_options.options,
+              &self.fmt_options.for_specifier(&specifier).options,
               &specifier,
             ),
             trigger_character,
             trigger_kind,
           },
-          (&self.fmt_options.options).into(),
+          (&self.fmt_options.for_specifier(&specifier).options).into(),
         )
         .await;
 
@@ -2596,11 +3295,13 @@ impl Inner {
           .get_completion_details(
             self.snapshot(),
             GetCompletionDetailsArgs {
-              format_code_settings: Some((&self.fmt_options.options).into()),
+              format_code_settings: Some(
+                (&self.fmt_options.for_specifier(specifier).options).into(),
+              ),
               preferences: Some(
                 tsc::UserPreferences::from_config_for_specifier(
                   &self.config,
-                  &self.fmt_options.options,
+                  &self.fmt_options.for_specifier(specifier).options,
                   specifier,
                 ),
               ),
@@ -2744,7 +3445,7 @@ impl Inner {
       .url_map
       .normalize_url(&params.item.uri, LspUrlKind::File);
     if !self.is_diagnosable(&specifier)
-      || !self.config.specifier_enabled(&specifier)
+      || !self.is_enabled_or_npm_package(&specifier)
     {
       return Ok(None);
     }
@@ -2789,7 +3490,7 @@ impl Inner {
       .url_map
       .normalize_url(&params.item.uri, LspUrlKind::File);
     if !self.is_diagnosable(&specifier)
-      || !self.config.specifier_enabled(&specifier)
+      || !self.is_enabled_or_npm_package(&specifier)
     {
       return Ok(None);
     }
@@ -2836,7 +3537,7 @@ impl Inner {
       LspUrlKind::File,
     );
     if !self.is_diagnosable(&specifier)
-      || !self.config.specifier_enabled(&specifier)
+      || !self.is_enabled_or_npm_package(&specifier)
     {
       return Ok(None);
     }
@@ -2890,23 +3591,171 @@ impl Inner {
     Ok(response)
   }
 
-  async fn rename(
+  async fn prepare_type_hierarchy(
     &self,
-    params: RenameParams,
-  ) -> LspResult<Option<WorkspaceEdit>> {
+    params: TypeHierarchyPrepareParams,
+  ) -> LspResult<Option<Vec<TypeHierarchyItem>>> {
     let specifier = self.url_map.normalize_url(
-      &params.text_document_position.text_document.uri,
+      &params.text_document_position_params.text_document.uri,
       LspUrlKind::File,
     );
     if !self.is_diagnosable(&specifier)
-      || !self.config.specifier_enabled(&specifier)
+      || !self.is_enabled_or_npm_package(&specifier)
     {
       return Ok(None);
     }
 
-    let mark = self.performance.mark_with_args("lsp.rename", &params);
-    let asset_or_doc = self.get_asset_or_document(&specifier)?;
-    let line_index = asset_or_doc.line_index();
+    let mark = self
+      .performance
+      .mark_with_args("lsp.prepare_type_hierarchy", &params);
+    let maybe_items = self
+      .type_hierarchy_item_at(
+        &specifier,
+        params.text_document_position_params.position,
+      )
+      .map(|item| vec![item]);
+    self.performance.measure(mark);
+    Ok(maybe_items)
+  }
+
+  async fn supertypes(
+    &self,
+    params: TypeHierarchySupertypesParams,
+  ) -> LspResult<Option<Vec<TypeHierarchyItem>>> {
+    let specifier = self
+      .url_map
+      .normalize_url(&params.item.uri, LspUrlKind::File);
+    if !self.is_diagnosable(&specifier)
+      || !self.is_enabled_or_npm_package(&specifier)
+    {
+      return Ok(None);
+    }
+
+    let mark = self.performance.mark_with_args("lsp.supertypes", &params);
+    let asset_or_doc = self.get_asset_or_document(&specifier)?;
+    let line_index = asset_or_doc.line_index();
+    let Some(declaration) = asset_or_doc
+      .maybe_parsed_source()
+      .and_then(|r| r.ok())
+      .and_then(|parsed_source| {
+        type_hierarchy::find_type_declaration(
+          type_hierarchy::collect_type_declarations(&parsed_source),
+          params.item.selection_range.start,
+        )
+      })
+    else {
+      self.performance.measure(mark);
+      return Ok(None);
+    };
+
+    let mut items = Vec::new();
+    for position in declaration.supertype_positions {
+      let maybe_definition_info = self
+        .ts_server
+        .get_definition(
+          self.snapshot(),
+          specifier.clone(),
+          line_index.offset_tsc(position)?,
+        )
+        .await?;
+      let definitions = maybe_definition_info
+        .and_then(|info| info.definitions)
+        .unwrap_or_default();
+      for definition in definitions {
+        if let Some(item) =
+          self.type_hierarchy_item_for_span(&definition.document_span)
+        {
+          items.push(item);
+        }
+      }
+    }
+    self.performance.measure(mark);
+    Ok(Some(items))
+  }
+
+  async fn subtypes(
+    &self,
+    params: TypeHierarchySubtypesParams,
+  ) -> LspResult<Option<Vec<TypeHierarchyItem>>> {
+    let specifier = self
+      .url_map
+      .normalize_url(&params.item.uri, LspUrlKind::File);
+    if !self.is_diagnosable(&specifier)
+      || !self.is_enabled_or_npm_package(&specifier)
+    {
+      return Ok(None);
+    }
+
+    let mark = self.performance.mark_with_args("lsp.subtypes", &params);
+    let asset_or_doc = self.get_asset_or_document(&specifier)?;
+    let line_index = asset_or_doc.line_index();
+    let implementations = self
+      .ts_server
+      .get_implementations(
+        self.snapshot(),
+        specifier,
+        line_index.offset_tsc(params.item.selection_range.start)?,
+      )
+      .await?
+      .unwrap_or_default();
+    let items = implementations
+      .iter()
+      .filter_map(|implementation| {
+        self.type_hierarchy_item_for_span(&implementation.document_span)
+      })
+      .filter(|item| {
+        item.uri != params.item.uri
+          || item.selection_range != params.item.selection_range
+      })
+      .collect();
+    self.performance.measure(mark);
+    Ok(Some(items))
+  }
+
+  fn type_hierarchy_item_at(
+    &self,
+    specifier: &ModuleSpecifier,
+    position: Position,
+  ) -> Option<TypeHierarchyItem> {
+    let asset_or_doc = self.get_maybe_asset_or_document(specifier)?;
+    let parsed_source = asset_or_doc.maybe_parsed_source()?.ok()?;
+    let declaration = type_hierarchy::find_type_declaration(
+      type_hierarchy::collect_type_declarations(&parsed_source),
+      position,
+    )?;
+    let uri = self.url_map.normalize_specifier(specifier).ok()?.into_url();
+    let mut item = declaration.to_type_hierarchy_item(uri);
+    item.detail = type_hierarchy::detail_for_specifier(specifier);
+    Some(item)
+  }
+
+  fn type_hierarchy_item_for_span(
+    &self,
+    document_span: &tsc::DocumentSpan,
+  ) -> Option<TypeHierarchyItem> {
+    let specifier = resolve_url(&document_span.file_name).ok()?;
+    let asset_or_doc = self.get_maybe_asset_or_document(&specifier)?;
+    let range = document_span.text_span.to_range(asset_or_doc.line_index());
+    self.type_hierarchy_item_at(&specifier, range.start)
+  }
+
+  async fn rename(
+    &self,
+    params: RenameParams,
+  ) -> LspResult<Option<WorkspaceEdit>> {
+    let specifier = self.url_map.normalize_url(
+      &params.text_document_position.text_document.uri,
+      LspUrlKind::File,
+    );
+    if !self.is_diagnosable(&specifier)
+      || !self.config.specifier_enabled(&specifier)
+    {
+      return Ok(None);
+    }
+
+    let mark = self.performance.mark_with_args("lsp.rename", &params);
+    let asset_or_doc = self.get_asset_or_document(&specifier)?;
+    let line_index = asset_or_doc.line_index();
 
     let maybe_locations = self
       .ts_server
@@ -2997,8 +3846,11 @@ impl Inner {
       )
       .await?;
 
-    let semantic_tokens =
-      semantic_classification.to_semantic_tokens(&asset_or_doc, line_index)?;
+    let semantic_tokens = semantic_classification.to_semantic_tokens(
+      &asset_or_doc,
+      line_index,
+      None,
+    )?;
     let response = if !semantic_tokens.data.is_empty() {
       Some(SemanticTokensResult::Tokens(semantic_tokens))
     } else {
@@ -3035,8 +3887,11 @@ impl Inner {
       )
       .await?;
 
-    let semantic_tokens =
-      semantic_classification.to_semantic_tokens(&asset_or_doc, line_index)?;
+    let semantic_tokens = semantic_classification.to_semantic_tokens(
+      &asset_or_doc,
+      line_index,
+      Some(&params.range),
+    )?;
     let response = if !semantic_tokens.data.is_empty() {
       Some(SemanticTokensRangeResult::Tokens(semantic_tokens))
     } else {
@@ -3117,17 +3972,19 @@ impl Inner {
       if options.enabled == UpdateImportsOnFileMoveEnabled::Never {
         continue;
       }
+      let new_specifier = self.url_map.normalize_url(
+        &resolve_url(&rename.new_uri).unwrap(),
+        LspUrlKind::File,
+      );
+      let fmt_options = self.fmt_options.for_specifier(&old_specifier);
       changes.extend(
         self
           .ts_server
           .get_edits_for_file_rename(
             self.snapshot(),
-            old_specifier,
-            self.url_map.normalize_url(
-              &resolve_url(&rename.new_uri).unwrap(),
-              LspUrlKind::File,
-            ),
-            (&self.fmt_options.options).into(),
+            old_specifier.clone(),
+            new_specifier.clone(),
+            (&fmt_options.options).into(),
             tsc::UserPreferences {
               allow_text_changes_in_new_files: Some(true),
               ..Default::default()
@@ -3135,15 +3992,110 @@ impl Inner {
           )
           .await?,
       );
+      for deno_types_changes in
+        self.deno_types_edits_for_file_rename(&old_specifier, &new_specifier)?
+      {
+        match changes
+          .iter_mut()
+          .find(|c| c.file_name == deno_types_changes.file_name)
+        {
+          Some(file_changes) => file_changes
+            .text_changes
+            .extend(deno_types_changes.text_changes),
+          None => changes.push(deno_types_changes),
+        }
+      }
     }
     file_text_changes_to_workspace_edit(&changes, self)
   }
 
+  /// TypeScript doesn't know about `@deno-types` comments, so the edits for
+  /// those are derived from the dependencies of the documents instead.
+  fn deno_types_edits_for_file_rename(
+    &self,
+    old_specifier: &ModuleSpecifier,
+    new_specifier: &ModuleSpecifier,
+  ) -> LspResult<Vec<tsc::FileTextChanges>> {
+    let mut changes = Vec::new();
+    for document in self.documents.documents(DocumentsFilter::AllDiagnosable) {
+      let referrer = if document.specifier() == old_specifier {
+        new_specifier
+      } else {
+        document.specifier()
+      };
+      let line_index = document.line_index();
+      let content = document.content();
+      let mut text_changes = Vec::new();
+      for dependency in document.dependencies().values() {
+        let Resolution::Ok(resolved) = &dependency.maybe_type else {
+          continue;
+        };
+        // the types of a dependency which come from the import itself are
+        // already handled by TypeScript
+        if dependency
+          .imports
+          .iter()
+          .any(|i| dependency.maybe_type.includes(&i.range.start).is_some())
+        {
+          continue;
+        }
+        let target = if &resolved.specifier == old_specifier {
+          new_specifier
+        } else {
+          &resolved.specifier
+        };
+        if referrer == document.specifier() && target == &resolved.specifier {
+          continue;
+        }
+        let range = to_lsp_range(&resolved.range);
+        let text_range = line_index.get_text_range(range).map_err(|err| {
+          error!("Unable to get the range of a @deno-types comment: {}", err);
+          LspError::internal_error()
+        })?;
+        let text = &content[text_range];
+        let quote = text.chars().next().filter(|c| *c == '"' || *c == '\'');
+        let unquoted = quote.map(|q| text.trim_matches(q)).unwrap_or(text);
+        if !unquoted.starts_with("./") && !unquoted.starts_with("../") {
+          continue;
+        }
+        let Some(new_text) = relative_specifier(referrer, target) else {
+          continue;
+        };
+        let new_text = match quote {
+          Some(quote) => format!("{quote}{new_text}{quote}"),
+          None => new_text,
+        };
+        text_changes.push(tsc::TextChange {
+          span: tsc::TextSpan::from_range(&range, line_index.clone()).map_err(
+            |err| {
+              error!("Unable to convert range: {}", err);
+              LspError::internal_error()
+            },
+          )?,
+          new_text,
+        });
+      }
+      if !text_changes.is_empty() {
+        changes.push(tsc::FileTextChanges {
+          file_name: document.specifier().to_string(),
+          text_changes,
+          is_new_file: None,
+        });
+      }
+    }
+    Ok(changes)
+  }
+
   async fn symbol(
     &self,
     params: WorkspaceSymbolParams,
   ) -> LspResult<Option<Vec<SymbolInformation>>> {
     let mark = self.performance.mark_with_args("lsp.symbol", &params);
+    let include_dependencies = self
+      .config
+      .workspace_settings()
+      .workspace_symbols
+      .include_dependencies;
 
     let navigate_to_items = self
       .ts_server
@@ -3158,15 +4110,33 @@ impl Inner {
       )
       .await?;
 
-    let maybe_symbol_information = if navigate_to_items.is_empty() {
-      None
-    } else {
-      let mut symbol_information = Vec::new();
-      for item in navigate_to_items {
+    let maybe_direct_dependencies =
+      include_dependencies.then(|| self.direct_dependencies());
+    let mut symbol_information = Vec::new();
+    let mut dependency_symbol_information = Vec::new();
+    for item in navigate_to_items {
+      let Some(specifier) = item.specifier() else {
+        continue;
+      };
+      if self.is_dependency_specifier(&specifier) {
+        let Some(direct_dependencies) = &maybe_direct_dependencies else {
+          continue;
+        };
+        if !item.is_exported() || !direct_dependencies.contains(&specifier) {
+          continue;
+        }
         if let Some(info) = item.to_symbol_information(self) {
-          symbol_information.push(info);
+          dependency_symbol_information.push(info);
         }
+      } else if let Some(info) = item.to_symbol_information(self) {
+        symbol_information.push(info);
       }
+    }
+    // symbols of dependencies are ranked below the workspace's own symbols
+    symbol_information.extend(dependency_symbol_information);
+    let maybe_symbol_information = if symbol_information.is_empty() {
+      None
+    } else {
       Some(symbol_information)
     };
 
@@ -3174,6 +4144,53 @@ impl Inner {
     Ok(maybe_symbol_information)
   }
 
+  /// Determine if a specifier is a module of a dependency, as opposed to a
+  /// module of the workspace.
+  fn is_dependency_specifier(&self, specifier: &ModuleSpecifier) -> bool {
+    matches!(specifier.scheme(), "http" | "https")
+      || self
+        .npm
+        .resolver
+        .as_ref()
+        .is_some_and(|resolver| resolver.in_npm_package(specifier))
+  }
+
+  /// Collect the dependencies which are directly imported by the modules of
+  /// the workspace.
+  fn direct_dependencies(&self) -> DirectDependencies {
+    let mut direct_dependencies = DirectDependencies::default();
+    for document in self.documents.documents(DocumentsFilter::AllDiagnosable) {
+      let referrer = document.specifier();
+      if self.is_dependency_specifier(referrer) {
+        continue;
+      }
+      for dependency in document.dependencies().values() {
+        for resolution in [&dependency.maybe_code, &dependency.maybe_type] {
+          let Resolution::Ok(resolved) = resolution else {
+            continue;
+          };
+          let specifier = &resolved.specifier;
+          if let Ok(req_ref) = NpmPackageReqReference::from_specifier(specifier)
+          {
+            let Some(npm_resolver) = &self.npm.resolver else {
+              continue;
+            };
+            if let Ok(package_folder) = npm_resolver
+              .resolve_pkg_folder_from_deno_module_req(req_ref.req(), referrer)
+            {
+              direct_dependencies.npm_package_folders.push(package_folder);
+            }
+          } else if let Some(package_root) = jsr_package_root(specifier) {
+            direct_dependencies.jsr_package_roots.insert(package_root);
+          } else if self.is_dependency_specifier(specifier) {
+            direct_dependencies.modules.insert(specifier.clone());
+          }
+        }
+      }
+    }
+    direct_dependencies
+  }
+
   fn send_diagnostics_update(&self) {
     let snapshot = DiagnosticServerUpdateMessage {
       snapshot: self.snapshot(),
@@ -3197,6 +4214,43 @@ impl Inner {
   }
 }
 
+/// Run `deno upgrade` for the quick fix of a `"deno"` version requirement of
+/// a config file which isn't satisfied. The upgraded executable is only used
+/// once the language server is restarted.
+async fn upgrade_deno(client: Client) -> LspResult<Option<Value>> {
+  let result = deno_core::unsync::spawn_blocking(|| {
+    std::process::Command::new(std::env::current_exe()?)
+      .arg("upgrade")
+      .env("NO_COLOR", "1")
+      .output()
+  })
+  .await
+  .map_err(|err| {
+    error!("{}", err);
+    LspError::internal_error()
+  })?;
+  match result {
+    Ok(output) if output.status.success() => client.show_message(
+      MessageType::INFO,
+      "Deno was upgraded. Restart the language server to use the new version.",
+    ),
+    Ok(output) => client.show_message(
+      MessageType::ERROR,
+      format!(
+        "Failed upgrading Deno: {}",
+        String::from_utf8_lossy(&output.stderr).trim()
+      ),
+    ),
+    Err(err) => {
+      client.show_message(
+        MessageType::ERROR,
+        format!("Failed running `deno upgrade`: {err}"),
+      );
+    }
+  }
+  Ok(None)
+}
+
 #[tower_lsp::async_trait]
 impl tower_lsp::LanguageServer for LanguageServer {
   async fn execute_command(
@@ -3214,6 +4268,42 @@ impl tower_lsp::LanguageServer for LanguageServer {
       self.cache_request(specifiers, referrer).await
     } else if params.command == "deno.reloadImportRegistries" {
       self.0.write().await.reload_import_registries().await
+    } else if params.command == "deno.reloadModule" {
+      let mut arguments = params.arguments.into_iter();
+      let uri = serde_json::to_value(arguments.next()).unwrap();
+      let uri: Url = serde_json::from_value(uri)
+        .map_err(|err| LspError::invalid_params(err.to_string()))?;
+      let (control_dir, path) =
+        self.0.read().await.resolve_reload_module(&uri)?;
+      let count = send_reload_module_request(&control_dir, &path).await;
+      Ok(Some(json!(count)))
+    } else if params.command == "deno.coverage.show" {
+      self.0.read().await.toggle_coverage()
+    } else if params.command == "deno.debugTest" {
+      let mut arguments = params.arguments.into_iter();
+      let uri = serde_json::to_value(arguments.next()).unwrap();
+      let uri: Url = serde_json::from_value(uri)
+        .map_err(|err| LspError::invalid_params(err.to_string()))?;
+      let name = serde_json::to_value(arguments.next()).unwrap();
+      let name: String = serde_json::from_value(name)
+        .map_err(|err| LspError::invalid_params(err.to_string()))?;
+      self.0.read().await.debug_test(&uri, &name)
+    } else if params.command == "deno.showDependencyGraph" {
+      let mut arguments = params.arguments.into_iter();
+      let uri = serde_json::to_value(arguments.next()).unwrap();
+      let uri: Url = serde_json::from_value(uri)
+        .map_err(|err| LspError::invalid_params(err.to_string()))?;
+      let format = serde_json::to_value(arguments.next()).unwrap();
+      let format: Option<String> = serde_json::from_value(format)
+        .map_err(|err| LspError::invalid_params(err.to_string()))?;
+      self
+        .0
+        .read()
+        .await
+        .show_dependency_graph(&uri, format.as_deref())
+    } else if params.command == "deno.upgrade" {
+      let client = self.0.read().await.client.clone();
+      upgrade_deno(client).await
     } else {
       Ok(None)
     }
@@ -3229,7 +4319,7 @@ impl tower_lsp::LanguageServer for LanguageServer {
   }
 
   async fn initialized(&self, _: InitializedParams) {
-    let mut registrations = Vec::with_capacity(2);
+    let mut registrations = Vec::with_capacity(3);
     let (client, http_client) = {
       let mut ls = self.0.write().await;
       if ls
@@ -3243,7 +4333,7 @@ impl tower_lsp::LanguageServer for LanguageServer {
         let options = DidChangeWatchedFilesRegistrationOptions {
           watchers: vec![FileSystemWatcher {
             glob_pattern: GlobPattern::String(
-              "**/*.{json,jsonc,lock}".to_string(),
+              "**/*.{json,jsonc,lock,wasm}".to_string(),
             ),
             kind: None,
           }],
@@ -3272,6 +4362,21 @@ impl tower_lsp::LanguageServer for LanguageServer {
         });
       }
 
+      // type hierarchy isn't part of the static server capabilities we can
+      // provide yet, so it has to be registered dynamically
+      if ls.config.client_capabilities.type_hierarchy {
+        registrations.push(Registration {
+          id: "textDocument/prepareTypeHierarchy".to_string(),
+          method: "textDocument/prepareTypeHierarchy".to_string(),
+          register_options: Some(json!({
+            "documentSelector": [
+              { "scheme": "file" },
+              { "scheme": "deno" },
+            ],
+          })),
+        });
+      }
+
       if ls.config.client_capabilities.testing_api {
         let test_server = testing::TestServer::new(
           ls.client.clone(),
@@ -3291,6 +4396,14 @@ impl tower_lsp::LanguageServer for LanguageServer {
             configuration_type: lsp_custom::DenoConfigurationType::DenoJson,
           });
         }
+        for (folder_uri, config_file) in ls.config.folder_config_files() {
+          config_events.push(lsp_custom::DenoConfigurationChangeEvent {
+            scope_uri: folder_uri.clone(),
+            file_uri: config_file.specifier.clone(),
+            typ: lsp_custom::DenoConfigurationChangeType::Added,
+            configuration_type: lsp_custom::DenoConfigurationType::DenoJson,
+          });
+        }
         if let Some(package_json) = &ls.maybe_package_json {
           config_events.push(lsp_custom::DenoConfigurationChangeEvent {
             scope_uri: root_uri.clone(),
@@ -3384,6 +4497,8 @@ impl tower_lsp::LanguageServer for LanguageServer {
       inner.diagnostics_server.invalidate(&specifiers);
       inner.send_diagnostics_update();
       inner.send_testing_update();
+    } else if inner.config.is_config_file(&specifier) {
+      inner.send_diagnostics_update();
     }
   }
 
@@ -3393,25 +4508,28 @@ impl tower_lsp::LanguageServer for LanguageServer {
 
   async fn did_save(&self, params: DidSaveTextDocumentParams) {
     let uri = &params.text_document.uri;
-    let specifier = {
+    let (specifier, should_cache) = {
       let mut inner = self.0.write().await;
       let specifier = inner.url_map.normalize_url(uri, LspUrlKind::File);
       inner.documents.save(&specifier);
-      if !inner
+      let should_cache = inner
         .config
         .workspace_settings_for_specifier(&specifier)
         .cache_on_save
-        || !inner.config.specifier_enabled(&specifier)
-        || !inner.diagnostics_state.has_no_cache_diagnostics(&specifier)
-      {
-        return;
-      }
-      match specifier_to_file_path(&specifier) {
-        Ok(path) if is_importable_ext(&path) => {}
-        _ => return,
-      }
-      specifier
+        && inner.config.specifier_enabled(&specifier)
+        && inner.diagnostics_state.has_no_cache_diagnostics(&specifier)
+        && matches!(
+          specifier_to_file_path(&specifier),
+          Ok(path) if is_importable_ext(&path)
+        );
+      (specifier, should_cache)
     };
+    // finding the dependent tests walks the module graph, so don't block
+    // other requests by holding the write lock while doing so
+    self.0.read().await.run_tests_on_save(&specifier);
+    if !should_cache {
+      return;
+    }
     if let Err(err) = self.cache_request(vec![], specifier.clone()).await {
       lsp_warn!("Failed to cache \"{}\" on save: {}", &specifier, err);
     }
@@ -3462,6 +4580,14 @@ impl tower_lsp::LanguageServer for LanguageServer {
     self.refresh_configuration().await;
     {
       let mut ls = self.0.write().await;
+      // the config files and import maps are scoped to the workspace folders
+      if let Err(err) = ls.update_config_file().await {
+        ls.client.show_message(MessageType::WARNING, err);
+      }
+      if let Err(err) = ls.update_import_map().await {
+        ls.client.show_message(MessageType::WARNING, err);
+      }
+      ls.recreate_npm_services_if_necessary().await;
       ls.refresh_documents_config().await;
       ls.diagnostics_server.invalidate_all();
       ls.send_diagnostics_update();
@@ -3483,6 +4609,20 @@ impl tower_lsp::LanguageServer for LanguageServer {
     self.0.read().await.formatting(params).await
   }
 
+  async fn range_formatting(
+    &self,
+    params: DocumentRangeFormattingParams,
+  ) -> LspResult<Option<Vec<TextEdit>>> {
+    self.0.read().await.range_formatting(params).await
+  }
+
+  async fn on_type_formatting(
+    &self,
+    params: DocumentOnTypeFormattingParams,
+  ) -> LspResult<Option<Vec<TextEdit>>> {
+    self.0.read().await.on_type_formatting(params).await
+  }
+
   async fn hover(&self, params: HoverParams) -> LspResult<Option<Hover>> {
     self.0.read().await.hover(params).await
   }
@@ -3596,6 +4736,27 @@ impl tower_lsp::LanguageServer for LanguageServer {
     self.0.read().await.prepare_call_hierarchy(params).await
   }
 
+  async fn prepare_type_hierarchy(
+    &self,
+    params: TypeHierarchyPrepareParams,
+  ) -> LspResult<Option<Vec<TypeHierarchyItem>>> {
+    self.0.read().await.prepare_type_hierarchy(params).await
+  }
+
+  async fn supertypes(
+    &self,
+    params: TypeHierarchySupertypesParams,
+  ) -> LspResult<Option<Vec<TypeHierarchyItem>>> {
+    self.0.read().await.supertypes(params).await
+  }
+
+  async fn subtypes(
+    &self,
+    params: TypeHierarchySubtypesParams,
+  ) -> LspResult<Option<Vec<TypeHierarchyItem>>> {
+    self.0.read().await.subtypes(params).await
+  }
+
   async fn rename(
     &self,
     params: RenameParams,
@@ -3646,6 +4807,43 @@ impl tower_lsp::LanguageServer for LanguageServer {
   }
 }
 
+#[derive(Debug, Default)]
+struct DirectDependencies {
+  modules: HashSet<ModuleSpecifier>,
+  jsr_package_roots: HashSet<String>,
+  npm_package_folders: Vec<PathBuf>,
+}
+
+impl DirectDependencies {
+  fn contains(&self, specifier: &ModuleSpecifier) -> bool {
+    if self.modules.contains(specifier) {
+      return true;
+    }
+    if let Some(package_root) = jsr_package_root(specifier) {
+      return self.jsr_package_roots.contains(&package_root);
+    }
+    specifier_to_file_path(specifier).is_ok_and(|path| {
+      self
+        .npm_package_folders
+        .iter()
+        .any(|folder| path.starts_with(folder))
+    })
+  }
+}
+
+/// Get the root of the jsr package which the specifier is a module of
+/// (ex. `https://jsr.io/@std/path/0.210.0/`).
+fn jsr_package_root(specifier: &ModuleSpecifier) -> Option<String> {
+  let registry_url = deno_registry_url();
+  let path = specifier.as_str().strip_prefix(registry_url.as_str())?;
+  let mut parts = path.splitn(4, '/');
+  let scope = parts.next().filter(|scope| scope.starts_with('@'))?;
+  let name = parts.next()?;
+  let version = parts.next()?;
+  parts.next()?;
+  Some(format!("{registry_url}{scope}/{name}/{version}/"))
+}
+
 struct PrepareCacheResult {
   cli_options: CliOptions,
   roots: Vec<ModuleSpecifier>,
@@ -3663,6 +4861,34 @@ impl Inner {
     let mark = self
       .performance
       .mark_with_args("lsp.cache", (&specifiers, &referrer));
+    // cache with the configuration of the workspace folder of the referrer
+    let (initial_cwd, maybe_import_map_uri, maybe_package_json) =
+      match self.config.folder_uri_for_specifier(&referrer) {
+        Some(folder_uri) => (
+          specifier_to_file_path(folder_uri)?,
+          self
+            .scoped_import_maps
+            .get(folder_uri)
+            .map(|import_map| import_map.base_url().clone()),
+          None,
+        ),
+        None => (
+          self.initial_cwd.clone(),
+          self.maybe_import_map_uri.clone(),
+          self.maybe_package_json.clone(),
+        ),
+      };
+    let maybe_config_file =
+      self.config.config_file_for_specifier(&referrer).cloned();
+    let maybe_config_file_ext = self
+      .config
+      .config_file_ext_for_specifier(&referrer)
+      .cloned();
+    let maybe_lockfile = self.config.lockfile_for_specifier(&referrer).cloned();
+    let has_node_modules_dir = self
+      .config
+      .node_modules_dir_path_for_specifier(&referrer)
+      .is_some();
     let roots = if !specifiers.is_empty() {
       specifiers
     } else {
@@ -3677,19 +4903,19 @@ impl Inner {
         unsafely_ignore_certificate_errors: workspace_settings
           .unsafely_ignore_certificate_errors
           .clone(),
-        node_modules_dir: Some(
-          self.config.maybe_node_modules_dir_path().is_some(),
-        ),
+        node_modules_dir: Some(has_node_modules_dir),
+        offline: self.offline,
         // bit of a hack to force the lsp to cache the @types/node package
         type_check_mode: crate::args::TypeCheckMode::Local,
         ..Default::default()
       },
-      self.initial_cwd.clone(),
-      self.config.maybe_config_file().cloned(),
-      self.config.maybe_lockfile().cloned(),
-      self.maybe_package_json.clone(),
+      initial_cwd,
+      maybe_config_file,
+      maybe_config_file_ext,
+      maybe_lockfile,
+      maybe_package_json,
     )?;
-    cli_options.set_import_map_specifier(self.maybe_import_map_uri.clone());
+    cli_options.set_import_map_specifier(maybe_import_map_uri);
 
     let open_docs = self.documents.documents(DocumentsFilter::OpenDiagnosable);
     Ok(Some(PrepareCacheResult {
@@ -3718,12 +4944,42 @@ impl Inner {
     json!({ "averages": averages })
   }
 
+  fn get_performance_report(&self) -> lsp_custom::PerformanceReport {
+    let (requests, operations) = self
+      .performance
+      .percentiles()
+      .into_iter()
+      .partition(|p| p.name.starts_with("tsc."));
+    let open_documents = self
+      .documents
+      .documents(DocumentsFilter::All)
+      .into_iter()
+      .filter(|d| d.is_open())
+      .collect::<Vec<_>>();
+    lsp_custom::PerformanceReport {
+      operations,
+      diagnostics_queue_depth: self.diagnostics_server.pending_messages(),
+      open_documents: lsp_custom::OpenDocumentsReport {
+        count: open_documents.len(),
+        text_size: open_documents.iter().map(|d| d.content().len()).sum(),
+      },
+      document_cache: self.documents.document_cache_stats(),
+      tsc: lsp_custom::TscReport {
+        pending_requests: self.ts_server.pending_requests(),
+        requests,
+      },
+    }
+  }
+
   fn task_definitions(&self) -> LspResult<Vec<TaskDefinition>> {
     let mut result = vec![];
     if let Some(config_file) = self.config.maybe_config_file() {
       if let Some(tasks) = json!(&config_file.json.tasks).as_object() {
         for (name, value) in tasks {
-          let Some(command) = value.as_str() else {
+          let Some(command) = value
+            .as_str()
+            .or_else(|| value.get("command").and_then(|c| c.as_str()))
+          else {
             continue;
           };
           result.push(TaskDefinition {
@@ -3757,7 +5013,7 @@ impl Inner {
       .url_map
       .normalize_url(&params.text_document.uri, LspUrlKind::File);
     if !self.is_diagnosable(&specifier)
-      || !self.config.specifier_enabled(&specifier)
+      || !self.is_enabled_or_npm_package(&specifier)
       || !self.config.enabled_inlay_hints_for_specifier(&specifier)
     {
       return Ok(None);
@@ -3781,7 +5037,7 @@ impl Inner {
         text_span,
         tsc::UserPreferences::from_config_for_specifier(
           &self.config,
-          &self.fmt_options.options,
+          &self.fmt_options.for_specifier(&specifier).options,
           &specifier,
         ),
       )
@@ -3810,6 +5066,130 @@ impl Inner {
     Ok(Some(json!(true)))
   }
 
+  fn run_tests_on_save(&self, specifier: &ModuleSpecifier) {
+    let Some(testing_server) = &self.maybe_testing_server else {
+      return;
+    };
+    if !self
+      .config
+      .workspace_settings_for_specifier(specifier)
+      .testing
+      .run_on_save
+    {
+      return;
+    }
+    if let Err(err) = testing_server.run_on_save(
+      specifier,
+      &self.documents,
+      self.config.workspace_settings().clone(),
+    ) {
+      lsp_warn!("Failed to run tests on save of \"{}\": {}", specifier, err);
+    }
+  }
+
+  /// Resolves the control directory of the programs running with HMR and the
+  /// path of the document to replace in them, which editors request when the
+  /// document is saved.
+  fn resolve_reload_module(&self, uri: &Url) -> LspResult<(PathBuf, PathBuf)> {
+    let specifier = self.url_map.normalize_url(uri, LspUrlKind::File);
+    let Ok(path) = specifier_to_file_path(&specifier) else {
+      return Err(LspError::invalid_params(format!(
+        "Not a local module: {specifier}"
+      )));
+    };
+    let deno_dir = DenoDir::new(self.maybe_global_cache_path.clone())
+      .map_err(|_| LspError::internal_error())?;
+    Ok((deno_dir.hmr_control_folder_path(), path))
+  }
+
+  fn toggle_coverage(&self) -> LspResult<Option<Value>> {
+    let Some(testing_server) = &self.maybe_testing_server else {
+      return Err(LspError::invalid_request());
+    };
+    testing_server.toggle_coverage()
+  }
+
+  fn debug_test(&self, uri: &Url, name: &str) -> LspResult<Option<Value>> {
+    let Some(testing_server) = &self.maybe_testing_server else {
+      return Err(LspError::invalid_request());
+    };
+    let specifier = self.url_map.normalize_url(uri, LspUrlKind::File);
+    testing_server.debug_request(
+      &specifier,
+      name,
+      self.config.workspace_settings().clone(),
+    )
+  }
+
+  /// Walk the dependencies of a document, as they are known to the language
+  /// server, and return the graph as JSON or as a DOT string.
+  fn show_dependency_graph(
+    &self,
+    uri: &Url,
+    format: Option<&str>,
+  ) -> LspResult<Option<Value>> {
+    let mark = self
+      .performance
+      .mark_with_args("lsp.show_dependency_graph", (uri, format));
+    let specifier = self.url_map.normalize_url(uri, LspUrlKind::File);
+    let Some(document) = self.documents.get(&specifier) else {
+      return Err(LspError::invalid_params(format!(
+        "Unable to find document: {specifier}"
+      )));
+    };
+    let root = document.specifier().clone();
+    let mut view = DependencyGraphView {
+      roots: vec![root.to_string()],
+      ..Default::default()
+    };
+    let mut seen = HashSet::from([root.clone()]);
+    let mut pending = VecDeque::from([root]);
+    while let Some(specifier) = pending.pop_front() {
+      let Some(document) = self.documents.get(&specifier) else {
+        // npm packages, node built-ins and modules which aren't cached
+        view.nodes.push(DependencyGraphNode {
+          specifier: specifier.to_string(),
+          size: None,
+        });
+        continue;
+      };
+      view.nodes.push(DependencyGraphNode {
+        specifier: specifier.to_string(),
+        size: Some(document.content().len() as u64),
+      });
+      for dependency in document.dependencies().values() {
+        for (dep_specifier, kind) in
+          DependencyEdgeKind::from_dependency(dependency)
+        {
+          let dep_specifier = self
+            .documents
+            .get(dep_specifier)
+            .map(|d| d.specifier().clone())
+            .unwrap_or_else(|| dep_specifier.clone());
+          view.edges.push(DependencyGraphEdge {
+            from: specifier.to_string(),
+            to: dep_specifier.to_string(),
+            kind,
+          });
+          if seen.insert(dep_specifier.clone()) {
+            pending.push_back(dep_specifier);
+          }
+        }
+      }
+    }
+    let result = match format {
+      Some("dot") => json!(view.to_dot()),
+      Some("json") | None => json!(view),
+      Some(format) => {
+        return Err(LspError::invalid_params(format!(
+          "Unsupported dependency graph format: {format}"
+        )));
+      }
+    };
+    self.performance.measure(mark);
+    Ok(Some(result))
+  }
+
   fn virtual_text_document(
     &self,
     params: lsp_custom::VirtualTextDocumentParams,
@@ -3833,6 +5213,10 @@ impl Inner {
       documents_specifiers.sort();
       let measures = self.performance.to_vec();
       let workspace_settings = self.config.workspace_settings();
+      let http_cache_stats = DenoDir::new(self.maybe_global_cache_path.clone())
+        .map(|dir| http_cache_stats(&dir.deps_folder_path()))
+        .unwrap_or_default();
+      let report = self.get_performance_report();
 
       write!(
         contents,
@@ -3857,6 +5241,11 @@ impl Inner {
     - {}
 
   </details>
+
+  - HTTP cache: {} {}, {}
+  - Open documents: {} ({})
+  - Diagnostics queue depth: {}
+  - Pending tsc requests: {}
 "#,
         serde_json::to_string_pretty(&workspace_settings).unwrap(),
         documents_specifiers.len(),
@@ -3870,10 +5259,33 @@ impl Inner {
           .iter()
           .map(|m| m.to_string())
           .collect::<Vec<String>>()
-          .join("\n    - ")
+          .join("\n    - "),
+        http_cache_stats.entries,
+        if http_cache_stats.entries == 1 {
+          "entry"
+        } else {
+          "entries"
+        },
+        human_size(http_cache_stats.size as f64),
+        report.open_documents.count,
+        human_size(report.open_documents.text_size as f64),
+        report.diagnostics_queue_depth,
+        report.tsc.pending_requests,
       )
       .unwrap();
 
+      contents.push_str(
+        "\n## Performance (percentiles of the last 500 entries of each type)\n\n|Name|Count|p50|p95|Max|\n|---|---|---|---|---|\n",
+      );
+      for p in report.operations.iter().chain(report.tsc.requests.iter()) {
+        writeln!(
+          contents,
+          "|{}|{}|{}ms|{}ms|{}ms|",
+          p.name, p.count, p.p50, p.p95, p.max
+        )
+        .unwrap();
+      }
+
       contents
         .push_str("\n## Performance (last 3 000 entries)\n\n|Name|Count|Duration|\n|---|---|---|\n");
       let mut averages = self.performance.averages_as_f64();
@@ -6,6 +6,7 @@ use deno_core::parking_lot::Mutex;
 use std::fs;
 use std::io::prelude::*;
 use std::path::Path;
+use std::path::PathBuf;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
@@ -16,6 +17,9 @@ static LSP_DEBUG_FLAG: AtomicBool = AtomicBool::new(false);
 static LSP_LOG_LEVEL: AtomicUsize = AtomicUsize::new(log::Level::Info as usize);
 static LSP_WARN_LEVEL: AtomicUsize =
   AtomicUsize::new(log::Level::Warn as usize);
+/// If the log file was set with `deno lsp --log-file`, which takes precedence
+/// over the "deno.logFile" setting.
+static LOG_FILE_FROM_FLAG: AtomicBool = AtomicBool::new(false);
 static LOG_FILE: LogFile = LogFile {
   enabled: AtomicBool::new(true),
   buffer: Mutex::new(String::new()),
@@ -53,6 +57,9 @@ impl LogFile {
 }
 
 pub fn init_log_file(enabled: bool) {
+  if LOG_FILE_FROM_FLAG.load(Ordering::Relaxed) {
+    return;
+  }
   let prepare_path = || {
     if !enabled {
       return None;
@@ -71,6 +78,25 @@ pub fn init_log_file(enabled: bool) {
     LOG_FILE.buffer.lock().clear();
     return;
   };
+  spawn_commit_thread(path);
+}
+
+/// Writes the log to the file of `deno lsp --log-file`, which is truncated.
+pub fn init_log_file_at(path: PathBuf) {
+  LOG_FILE_FROM_FLAG.store(true, Ordering::Relaxed);
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).ok();
+  }
+  if let Err(err) = fs::write(&path, "") {
+    log::warn!("Failed creating the log file {}. {:#}", path.display(), err);
+    LOG_FILE.enabled.store(false, Ordering::Relaxed);
+    LOG_FILE.buffer.lock().clear();
+    return;
+  }
+  spawn_commit_thread(path);
+}
+
+fn spawn_commit_thread(path: PathBuf) {
   thread::spawn(move || loop {
     LOG_FILE.commit(&path);
     thread::sleep(std::time::Duration::from_secs(1));
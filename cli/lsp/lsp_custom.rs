@@ -4,7 +4,11 @@ use deno_core::serde::Deserialize;
 use deno_core::serde::Serialize;
 use tower_lsp::lsp_types as lsp;
 
+use super::documents::DocumentCacheStats;
+use super::performance::PerformancePercentiles;
+
 pub const PERFORMANCE_REQUEST: &str = "deno/performance";
+pub const PERFORMANCE_REPORT_REQUEST: &str = "deno/performanceReport";
 pub const TASK_REQUEST: &str = "deno/taskDefinitions";
 pub const VIRTUAL_TEXT_DOCUMENT: &str = "deno/virtualTextDocument";
 pub const LATEST_DIAGNOSTIC_BATCH_INDEX: &str =
@@ -20,6 +24,37 @@ pub struct TaskDefinition {
   pub source_uri: lsp::Url,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PerformanceReport {
+  /// The percentiles of the durations of the latest measurements of each
+  /// operation, except for the ones of tsc.
+  pub operations: Vec<PerformancePercentiles>,
+  /// The number of updates queued for the diagnostics server.
+  pub diagnostics_queue_depth: usize,
+  pub open_documents: OpenDocumentsReport,
+  pub document_cache: DocumentCacheStats,
+  pub tsc: TscReport,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenDocumentsReport {
+  pub count: usize,
+  /// The size in bytes of the text of the open documents.
+  pub text_size: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TscReport {
+  /// The number of requests which the tsc thread didn't respond to yet.
+  pub pending_requests: usize,
+  /// The percentiles of the requests to tsc, the calls of the host and the
+  /// ops.
+  pub requests: Vec<PerformancePercentiles>,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct RegistryStateNotificationParams {
   pub origin: String,
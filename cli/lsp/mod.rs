@@ -1,11 +1,15 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use std::sync::Arc;
+
 use deno_core::error::AnyError;
 use deno_core::unsync::spawn;
 use tokio_util::sync::CancellationToken;
 use tower_lsp::LspService;
 use tower_lsp::Server;
 
+use crate::args::LspFlags;
+use crate::http_util::HttpClient;
 use crate::lsp::language_server::LanguageServer;
 pub use repl::ReplCompletionItem;
 pub use repl::ReplLanguageServer;
@@ -19,15 +23,22 @@ mod client;
 mod code_lens;
 mod completions;
 mod config;
+mod deno_json;
 mod diagnostics;
 mod documents;
+mod ffi;
+mod import_attributes;
+mod jsr;
 pub mod language_server;
 mod logging;
 mod lsp_custom;
 mod npm;
-mod parent_process_checker;
+mod otlp;
+mod package_metadata;
+pub mod parent_process_checker;
 mod path_to_regex;
 mod performance;
+mod preprocessors;
 mod refactor;
 mod registries;
 mod repl;
@@ -35,23 +46,48 @@ mod semantic_tokens;
 mod testing;
 mod text;
 mod tsc;
+mod type_hierarchy;
 mod urls;
+mod wasm;
 
-pub async fn start() -> Result<(), AnyError> {
+pub async fn start(offline: bool, lsp_flags: LspFlags) -> Result<(), AnyError> {
   let stdin = tokio::io::stdin();
   let stdout = tokio::io::stdout();
 
+  if let Some(log_file) = lsp_flags.log_file {
+    logging::init_log_file_at(log_file);
+  }
+  let performance = match lsp_flags.otlp_endpoint {
+    Some(endpoint) => {
+      let exporter = Arc::new(otlp::OtlpExporter::new(
+        endpoint,
+        Arc::new(HttpClient::new(None, None)),
+      ));
+      let performance =
+        Arc::new(performance::Performance::with_exporter(exporter.clone()));
+      otlp::start_export_loop(performance.clone(), exporter);
+      performance
+    }
+    None => Default::default(),
+  };
+
   let token = CancellationToken::new();
   let builder = LspService::build(|client| {
     language_server::LanguageServer::new(
       client::Client::from_tower(client),
       token.clone(),
+      offline,
+      performance,
     )
   })
   .custom_method(
     lsp_custom::PERFORMANCE_REQUEST,
     LanguageServer::performance_request,
   )
+  .custom_method(
+    lsp_custom::PERFORMANCE_REPORT_REQUEST,
+    LanguageServer::performance_report_request,
+  )
   .custom_method(lsp_custom::TASK_REQUEST, LanguageServer::task_definitions)
   // TODO(nayeemrmn): Rename this to `deno/taskDefinitions` in vscode_deno and
   // remove this alias.
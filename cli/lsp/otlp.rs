@@ -0,0 +1,294 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+use deno_core::parking_lot::Mutex;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
+use deno_core::unsync::spawn;
+use deno_core::url::Url;
+use deno_runtime::deno_fetch::reqwest::header::CONTENT_TYPE;
+use rand::Rng;
+
+use super::logging::lsp_debug;
+use super::performance::Performance;
+use super::performance::PerformanceMeasure;
+use crate::http_util::HttpClient;
+use crate::version;
+
+/// How often the spans and metrics are exported.
+const EXPORT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The max number of spans kept until they are exported, so they are dropped
+/// instead of piling up when the collector is down.
+const MAX_PENDING_SPANS: usize = 2_048;
+
+/// `SPAN_KIND_INTERNAL` of the OTLP protocol.
+const SPAN_KIND_INTERNAL: u8 = 1;
+
+/// `AGGREGATION_TEMPORALITY_CUMULATIVE` of the OTLP protocol.
+const AGGREGATION_TEMPORALITY_CUMULATIVE: u8 = 2;
+
+/// Exports the measures of the language server, like the requests, the
+/// generation of diagnostics and the calls to tsc, as OpenTelemetry spans to a
+/// collector which accepts OTLP over HTTP with the JSON encoding. The totals
+/// of the measures are exported as metrics.
+#[derive(Debug)]
+pub struct OtlpExporter {
+  endpoint: Url,
+  http_client: Arc<HttpClient>,
+  start_time: SystemTime,
+  pending_spans: Mutex<Vec<Value>>,
+}
+
+impl OtlpExporter {
+  pub fn new(mut endpoint: Url, http_client: Arc<HttpClient>) -> Self {
+    // the signals are posted to paths relative to the endpoint
+    if !endpoint.path().ends_with('/') {
+      endpoint.set_path(&format!("{}/", endpoint.path()));
+    }
+    Self {
+      endpoint,
+      http_client,
+      start_time: SystemTime::now(),
+      pending_spans: Default::default(),
+    }
+  }
+
+  /// Records a span of a measure which just ended.
+  pub fn record_span(
+    &self,
+    measure: &PerformanceMeasure,
+    maybe_args: Option<&Value>,
+  ) {
+    let end_time = SystemTime::now();
+    let start_time = end_time.checked_sub(measure.duration).unwrap_or(end_time);
+    let mut attributes =
+      vec![int_attribute("deno.lsp.count", measure.count as u64)];
+    if let Some(args) = maybe_args {
+      attributes.push(string_attribute("deno.lsp.args", &args.to_string()));
+    }
+    let mut rng = rand::thread_rng();
+    let span = json!({
+      "traceId": format!("{:032x}", rng.gen::<u128>()),
+      "spanId": format!("{:016x}", rng.gen::<u64>()),
+      "name": measure.name,
+      "kind": SPAN_KIND_INTERNAL,
+      "startTimeUnixNano": unix_nanos(start_time),
+      "endTimeUnixNano": unix_nanos(end_time),
+      "attributes": attributes,
+    });
+    let mut pending_spans = self.pending_spans.lock();
+    if pending_spans.len() < MAX_PENDING_SPANS {
+      pending_spans.push(span);
+    }
+  }
+
+  /// Exports the spans recorded since the last export and the totals of the
+  /// measurements by type, which are the count and the duration in ms.
+  pub async fn export(
+    &self,
+    measurements_by_type: &[(String, u32, f64)],
+  ) -> Result<(), AnyError> {
+    let spans = std::mem::take(&mut *self.pending_spans.lock());
+    if !spans.is_empty() {
+      self.post("v1/traces", traces_body(spans)).await?;
+    }
+    if !measurements_by_type.is_empty() {
+      let body = metrics_body(self.start_time, measurements_by_type);
+      self.post("v1/metrics", body).await?;
+    }
+    Ok(())
+  }
+
+  async fn post(&self, path: &str, body: Value) -> Result<(), AnyError> {
+    let url = self.endpoint.join(path)?;
+    let response = self
+      .http_client
+      .client_for_url(&url)?
+      .post(url)
+      .header(CONTENT_TYPE, "application/json")
+      .body(serde_json::to_vec(&body)?)
+      .send()
+      .await?;
+    let status = response.status();
+    if !status.is_success() {
+      bail!("Bad response: {:?}", status);
+    }
+    Ok(())
+  }
+}
+
+/// Exports the measures of the performance periodically for as long as the
+/// language server runs.
+pub fn start_export_loop(
+  performance: Arc<Performance>,
+  exporter: Arc<OtlpExporter>,
+) {
+  spawn(async move {
+    loop {
+      tokio::time::sleep(EXPORT_INTERVAL).await;
+      let measurements_by_type = performance.measurements_by_type();
+      if let Err(err) = exporter.export(&measurements_by_type).await {
+        lsp_debug!("Failed exporting to the OTLP collector. {:#}", err);
+      }
+    }
+  });
+}
+
+fn unix_nanos(time: SystemTime) -> String {
+  // 64-bit integers are strings in the JSON encoding of OTLP
+  time
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_nanos())
+    .unwrap_or(0)
+    .to_string()
+}
+
+fn string_attribute(key: &str, value: &str) -> Value {
+  json!({ "key": key, "value": { "stringValue": value } })
+}
+
+fn int_attribute(key: &str, value: u64) -> Value {
+  json!({ "key": key, "value": { "intValue": value.to_string() } })
+}
+
+fn resource() -> Value {
+  json!({
+    "attributes": [
+      string_attribute("service.name", "deno-lsp"),
+      string_attribute("service.version", version::deno()),
+    ],
+  })
+}
+
+fn scope() -> Value {
+  json!({ "name": "deno.lsp", "version": version::deno() })
+}
+
+fn traces_body(spans: Vec<Value>) -> Value {
+  json!({
+    "resourceSpans": [{
+      "resource": resource(),
+      "scopeSpans": [{ "scope": scope(), "spans": spans }],
+    }],
+  })
+}
+
+fn metrics_body(
+  start_time: SystemTime,
+  measurements_by_type: &[(String, u32, f64)],
+) -> Value {
+  let start_time = unix_nanos(start_time);
+  let time = unix_nanos(SystemTime::now());
+  // the value of a data point is either "asInt" or "asDouble"
+  let data_points = |to_value: fn(u32, f64) -> (&'static str, Value)| {
+    measurements_by_type
+      .iter()
+      .map(|(name, count, duration)| {
+        let mut data_point = json!({
+          "attributes": [string_attribute("deno.lsp.name", name)],
+          "startTimeUnixNano": start_time,
+          "timeUnixNano": time,
+        });
+        let (key, value) = to_value(*count, *duration);
+        data_point[key] = value;
+        data_point
+      })
+      .collect::<Vec<_>>()
+  };
+  let sum = |data_points: Vec<Value>| {
+    json!({
+      "aggregationTemporality": AGGREGATION_TEMPORALITY_CUMULATIVE,
+      "isMonotonic": true,
+      "dataPoints": data_points,
+    })
+  };
+  json!({
+    "resourceMetrics": [{
+      "resource": resource(),
+      "scopeMetrics": [{
+        "scope": scope(),
+        "metrics": [
+          {
+            "name": "deno.lsp.measure.count",
+            "unit": "1",
+            "sum": sum(data_points(|count, _| ("asInt", json!(count.to_string())))),
+          },
+          {
+            "name": "deno.lsp.measure.duration",
+            "unit": "ms",
+            "sum": sum(data_points(|_, duration| ("asDouble", json!(duration)))),
+          },
+        ],
+      }],
+    }],
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_record_span() {
+    let exporter = OtlpExporter::new(
+      Url::parse("http://localhost:4318/otlp").unwrap(),
+      Arc::new(HttpClient::new(None, None)),
+    );
+    assert_eq!(exporter.endpoint.as_str(), "http://localhost:4318/otlp/");
+    exporter.record_span(
+      &PerformanceMeasure {
+        name: "lsp.hover".to_string(),
+        count: 2,
+        duration: Duration::from_millis(5),
+      },
+      Some(&json!({ "line": 1 })),
+    );
+    let spans = exporter.pending_spans.lock();
+    assert_eq!(spans.len(), 1);
+    let span = &spans[0];
+    assert_eq!(span["name"], "lsp.hover");
+    assert_eq!(span["traceId"].as_str().unwrap().len(), 32);
+    assert_eq!(span["spanId"].as_str().unwrap().len(), 16);
+    let start = span["startTimeUnixNano"].as_str().unwrap();
+    let end = span["endTimeUnixNano"].as_str().unwrap();
+    assert_eq!(
+      end.parse::<u128>().unwrap() - start.parse::<u128>().unwrap(),
+      5_000_000
+    );
+    assert_eq!(
+      span["attributes"],
+      json!([
+        { "key": "deno.lsp.count", "value": { "intValue": "2" } },
+        { "key": "deno.lsp.args", "value": { "stringValue": "{\"line\":1}" } },
+      ])
+    );
+  }
+
+  #[test]
+  fn test_metrics_body() {
+    let body = metrics_body(
+      UNIX_EPOCH + Duration::from_secs(1),
+      &[("lsp.hover".to_string(), 3, 1.5)],
+    );
+    let metrics = &body["resourceMetrics"][0]["scopeMetrics"][0]["metrics"];
+    assert_eq!(metrics[0]["name"], "deno.lsp.measure.count");
+    let data_point = &metrics[0]["sum"]["dataPoints"][0];
+    assert_eq!(data_point["asInt"], "3");
+    assert_eq!(data_point["startTimeUnixNano"], "1000000000");
+    assert_eq!(
+      data_point["attributes"],
+      json!([{ "key": "deno.lsp.name", "value": { "stringValue": "lsp.hover" } }])
+    );
+    assert_eq!(metrics[1]["name"], "deno.lsp.measure.duration");
+    assert_eq!(metrics[1]["sum"]["dataPoints"][0]["asDouble"], 1.5);
+  }
+}
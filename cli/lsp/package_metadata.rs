@@ -0,0 +1,396 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use deno_core::anyhow::anyhow;
+use deno_core::error::AnyError;
+use deno_core::parking_lot::Mutex;
+use deno_core::serde::de::DeserializeOwned;
+use deno_core::serde::Deserialize;
+use deno_core::serde_json;
+use deno_core::unsync::spawn;
+use deno_core::url::Url;
+use deno_runtime::permissions::PermissionsContainer;
+
+use crate::args::deno_registry_api_url;
+use crate::args::deno_registry_url;
+use crate::args::npm_registry_default_url;
+use crate::args::CacheSetting;
+use crate::file_fetcher::FetchOptions;
+use crate::file_fetcher::FileFetcher;
+
+/// Metadata older than this is still shown, but refreshed in the background
+/// so the next hover is up to date.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PackageRegistry {
+  Jsr,
+  Npm,
+}
+
+/// A package which is referenced by an import specifier.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PackageDependency {
+  pub registry: PackageRegistry,
+  pub name: String,
+  /// The version or version requirement in the specifier, if any.
+  pub version: Option<String>,
+}
+
+impl PackageDependency {
+  /// Parse `jsr:` and `npm:` specifiers, along with `https:` specifiers which
+  /// point into the jsr registry.
+  pub fn from_specifier(specifier: &str) -> Option<Self> {
+    let (registry, rest) = if let Some(rest) = specifier.strip_prefix("jsr:") {
+      (PackageRegistry::Jsr, rest.trim_start_matches('/'))
+    } else if let Some(rest) = specifier.strip_prefix("npm:") {
+      (PackageRegistry::Npm, rest.trim_start_matches('/'))
+    } else {
+      let rest = specifier.strip_prefix(deno_registry_url().as_str())?;
+      // remote jsr modules always include the scope, name and version
+      let mut parts = rest.splitn(4, '/');
+      let scope = parts.next().filter(|s| s.starts_with('@'))?;
+      let name = parts.next().filter(|s| !s.is_empty())?;
+      let version = parts.next().filter(|s| !s.is_empty())?;
+      return Some(Self {
+        registry: PackageRegistry::Jsr,
+        name: format!("{}/{}", scope, name),
+        version: Some(version.to_string()),
+      });
+    };
+    let (scope, rest) = if let Some(rest) = rest.strip_prefix('@') {
+      let (scope, rest) = rest.split_once('/')?;
+      (Some(scope), rest)
+    } else if registry == PackageRegistry::Jsr {
+      // jsr packages are always scoped
+      return None;
+    } else {
+      (None, rest)
+    };
+    let name_and_version = rest.split('/').next()?;
+    let (name, version) = match name_and_version.split_once('@') {
+      Some((name, version)) => (name, Some(version)),
+      None => (name_and_version, None),
+    };
+    if name.is_empty() || scope.map(|s| s.is_empty()).unwrap_or(false) {
+      return None;
+    }
+    Some(Self {
+      registry,
+      name: match scope {
+        Some(scope) => format!("@{}/{}", scope, name),
+        None => name.to_string(),
+      },
+      version: version.filter(|v| !v.is_empty()).map(|v| v.to_string()),
+    })
+  }
+
+  /// The page of the package on the registry website.
+  pub fn registry_page_url(&self) -> String {
+    match self.registry {
+      PackageRegistry::Jsr => format!("{}{}", deno_registry_url(), self.name),
+      PackageRegistry::Npm => {
+        format!("https://www.npmjs.com/package/{}", self.name)
+      }
+    }
+  }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackageMetadata {
+  pub description: Option<String>,
+  pub latest_version: Option<String>,
+  pub license: Option<String>,
+}
+
+/// Render the hover markdown of a package dependency.
+pub fn to_hover_markdown(
+  dependency: &PackageDependency,
+  metadata: Option<&PackageMetadata>,
+) -> String {
+  let mut value = format!("**{}**", dependency.name);
+  let metadata = metadata.cloned().unwrap_or_default();
+  if let Some(description) = &metadata.description {
+    value.push_str(&format!("\n\n{}", description));
+  }
+  let mut details = Vec::new();
+  if let Some(version) = &dependency.version {
+    details.push(format!("**Version**: `{}`", version));
+  }
+  if let Some(latest_version) = &metadata.latest_version {
+    details.push(format!("**Latest**: `{}`", latest_version));
+  }
+  if let Some(license) = &metadata.license {
+    details.push(format!("**License**: {}", license));
+  }
+  if !details.is_empty() {
+    value.push_str(&format!("\n\n{}", details.join("  \n")));
+  }
+  let url = dependency.registry_page_url();
+  value.push_str(&format!("\n\n[{}]({})", url, url));
+  value
+}
+
+#[derive(Debug, Clone)]
+struct CacheEntry {
+  fetched_at: Instant,
+  metadata: Arc<PackageMetadata>,
+}
+
+/// Package metadata for dependency hovers. Metadata is read from the
+/// registries cache first, and stale entries are refreshed in the background.
+#[derive(Debug, Clone)]
+pub struct PackageMetadataCache {
+  file_fetcher: FileFetcher,
+  entries: Arc<Mutex<HashMap<(PackageRegistry, String), CacheEntry>>>,
+}
+
+impl PackageMetadataCache {
+  pub fn new(file_fetcher: FileFetcher) -> Self {
+    Self {
+      file_fetcher,
+      entries: Default::default(),
+    }
+  }
+
+  pub async fn get(
+    &self,
+    dependency: &PackageDependency,
+  ) -> Option<Arc<PackageMetadata>> {
+    let key = (dependency.registry, dependency.name.clone());
+    let maybe_entry = self.entries.lock().get(&key).cloned();
+    if let Some(entry) = maybe_entry {
+      if entry.fetched_at.elapsed() > REFRESH_INTERVAL {
+        let cache = self.clone();
+        let dependency = dependency.clone();
+        // mark the entry as fresh so only one refresh is in flight at a time
+        cache.insert(&dependency, entry.metadata.clone());
+        spawn(async move {
+          if let Ok(metadata) = cache
+            .fetch(&dependency, Some(&CacheSetting::ReloadAll))
+            .await
+          {
+            cache.insert(&dependency, Arc::new(metadata));
+          }
+        });
+      }
+      return Some(entry.metadata);
+    }
+    let metadata = Arc::new(self.fetch(dependency, None).await.ok()?);
+    self.insert(dependency, metadata.clone());
+    Some(metadata)
+  }
+
+  fn insert(
+    &self,
+    dependency: &PackageDependency,
+    metadata: Arc<PackageMetadata>,
+  ) {
+    self.entries.lock().insert(
+      (dependency.registry, dependency.name.clone()),
+      CacheEntry {
+        fetched_at: Instant::now(),
+        metadata,
+      },
+    );
+  }
+
+  async fn fetch(
+    &self,
+    dependency: &PackageDependency,
+    maybe_cache_setting: Option<&CacheSetting>,
+  ) -> Result<PackageMetadata, AnyError> {
+    match dependency.registry {
+      PackageRegistry::Jsr => {
+        let (scope, name) = dependency
+          .name
+          .strip_prefix('@')
+          .and_then(|n| n.split_once('/'))
+          .ok_or_else(|| anyhow!("Invalid jsr package name."))?;
+        let meta_url = deno_registry_url()
+          .join(&format!("{}/meta.json", dependency.name))?;
+        let meta: JsrPackageMeta =
+          self.fetch_json(&meta_url, maybe_cache_setting).await?;
+        let api_url = deno_registry_api_url()
+          .join(&format!("scopes/{}/packages/{}", scope, name))?;
+        // the description is only available from the api, so the metadata is
+        // still useful without it
+        let details = self
+          .fetch_json::<JsrPackageDetails>(&api_url, maybe_cache_setting)
+          .await
+          .ok();
+        Ok(PackageMetadata {
+          description: details
+            .and_then(|d| d.description)
+            .filter(|d| !d.is_empty()),
+          latest_version: meta.latest,
+          license: None,
+        })
+      }
+      PackageRegistry::Npm => {
+        let mut info_url = npm_registry_default_url().clone();
+        info_url
+          .path_segments_mut()
+          .map_err(|_| anyhow!("Custom npm registry URL cannot be a base."))?
+          .pop_if_empty()
+          .push(&dependency.name);
+        let info: NpmPackageMeta =
+          self.fetch_json(&info_url, maybe_cache_setting).await?;
+        Ok(info.into())
+      }
+    }
+  }
+
+  async fn fetch_json<T: DeserializeOwned>(
+    &self,
+    url: &Url,
+    maybe_cache_setting: Option<&CacheSetting>,
+  ) -> Result<T, AnyError> {
+    let file = self
+      .file_fetcher
+      .fetch_with_options(FetchOptions {
+        specifier: url,
+        permissions: PermissionsContainer::allow_all(),
+        maybe_accept: Some("application/json"),
+        maybe_cache_setting,
+      })
+      .await?;
+    Ok(serde_json::from_slice(&file.source)?)
+  }
+}
+
+#[derive(Debug, Deserialize)]
+struct JsrPackageMeta {
+  latest: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsrPackageDetails {
+  description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum NpmLicense {
+  Name(String),
+  Object {
+    #[serde(rename = "type")]
+    kind: String,
+  },
+}
+
+#[derive(Debug, Deserialize)]
+struct NpmPackageMeta {
+  description: Option<String>,
+  #[serde(rename = "dist-tags", default)]
+  dist_tags: HashMap<String, String>,
+  license: Option<NpmLicense>,
+}
+
+impl From<NpmPackageMeta> for PackageMetadata {
+  fn from(mut meta: NpmPackageMeta) -> Self {
+    Self {
+      description: meta.description.filter(|d| !d.is_empty()),
+      latest_version: meta.dist_tags.remove("latest"),
+      license: meta.license.map(|license| match license {
+        NpmLicense::Name(name) => name,
+        NpmLicense::Object { kind } => kind,
+      }),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_package_dependency_from_specifier() {
+    let dependency = |registry, name: &str, version: Option<&str>| {
+      Some(PackageDependency {
+        registry,
+        name: name.to_string(),
+        version: version.map(|v| v.to_string()),
+      })
+    };
+    let fixtures = [
+      (
+        "jsr:@std/path@^0.2.0/join",
+        dependency(PackageRegistry::Jsr, "@std/path", Some("^0.2.0")),
+      ),
+      (
+        "jsr:/@std/path",
+        dependency(PackageRegistry::Jsr, "@std/path", None),
+      ),
+      ("jsr:path", None),
+      (
+        "npm:chalk@5",
+        dependency(PackageRegistry::Npm, "chalk", Some("5")),
+      ),
+      (
+        "npm:@types/node/fs",
+        dependency(PackageRegistry::Npm, "@types/node", None),
+      ),
+      ("npm:@types", None),
+      ("https://deno.land/x/oak/mod.ts", None),
+    ];
+    for (specifier, expected) in fixtures {
+      assert_eq!(
+        PackageDependency::from_specifier(specifier),
+        expected,
+        "{specifier}"
+      );
+    }
+    assert_eq!(
+      PackageDependency::from_specifier(&format!(
+        "{}@std/path/0.2.0/mod.ts",
+        deno_registry_url()
+      )),
+      dependency(PackageRegistry::Jsr, "@std/path", Some("0.2.0")),
+    );
+  }
+
+  #[test]
+  fn test_npm_package_meta() {
+    let meta: NpmPackageMeta = serde_json::from_str(
+      r#"{
+        "name": "chalk",
+        "description": "Terminal string styling done right",
+        "dist-tags": { "latest": "5.3.0" },
+        "license": { "type": "MIT" }
+      }"#,
+    )
+    .unwrap();
+    assert_eq!(
+      PackageMetadata::from(meta),
+      PackageMetadata {
+        description: Some("Terminal string styling done right".to_string()),
+        latest_version: Some("5.3.0".to_string()),
+        license: Some("MIT".to_string()),
+      }
+    );
+  }
+
+  #[test]
+  fn test_to_hover_markdown() {
+    let dependency = PackageDependency::from_specifier("npm:chalk@5").unwrap();
+    let metadata = PackageMetadata {
+      description: Some("Terminal string styling done right".to_string()),
+      latest_version: Some("5.3.0".to_string()),
+      license: Some("MIT".to_string()),
+    };
+    assert_eq!(
+      to_hover_markdown(&dependency, Some(&metadata)),
+      "**chalk**\n\nTerminal string styling done right\n\n**Version**: `5`  \n**Latest**: `5.3.0`  \n**License**: MIT\n\n[https://www.npmjs.com/package/chalk](https://www.npmjs.com/package/chalk)"
+    );
+    assert_eq!(
+      to_hover_markdown(&dependency, None),
+      "**chalk**\n\n**Version**: `5`\n\n[https://www.npmjs.com/package/chalk](https://www.npmjs.com/package/chalk)"
+    );
+  }
+}
@@ -17,7 +17,7 @@ pub fn start(parent_process_id: u32) {
 }
 
 #[cfg(unix)]
-fn is_process_active(process_id: u32) -> bool {
+pub fn is_process_active(process_id: u32) -> bool {
   // TODO(bartlomieju):
   #[allow(clippy::undocumented_unsafe_blocks)]
   unsafe {
@@ -27,7 +27,7 @@ fn is_process_active(process_id: u32) -> bool {
 }
 
 #[cfg(windows)]
-fn is_process_active(process_id: u32) -> bool {
+pub fn is_process_active(process_id: u32) -> bool {
   use winapi::shared::minwindef::DWORD;
   use winapi::shared::minwindef::FALSE;
   use winapi::shared::ntdef::NULL;
@@ -4,14 +4,17 @@ use deno_core::parking_lot::Mutex;
 use deno_core::serde::Deserialize;
 use deno_core::serde::Serialize;
 use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
 use std::cmp;
 use std::collections::HashMap;
 use std::collections::VecDeque;
 use std::fmt;
+use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
 
 use super::logging::lsp_debug;
+use super::otlp::OtlpExporter;
 
 #[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -33,12 +36,61 @@ impl Ord for PerformanceAverage {
   }
 }
 
+/// The percentiles of the durations in ms of the latest measurements of a
+/// type.
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PerformancePercentiles {
+  pub name: String,
+  pub count: usize,
+  pub p50: f64,
+  pub p95: f64,
+  pub max: f64,
+}
+
+/// The durations of the latest measurements of a type, which roll off when
+/// the limit is reached.
+#[derive(Debug, Default)]
+struct PerformanceHistogram {
+  durations: VecDeque<Duration>,
+}
+
+impl PerformanceHistogram {
+  const MAX_SIZE: usize = 500;
+
+  fn record(&mut self, duration: Duration) {
+    self.durations.push_back(duration);
+    while self.durations.len() > Self::MAX_SIZE {
+      self.durations.pop_front();
+    }
+  }
+
+  fn percentiles(&self, name: &str) -> PerformancePercentiles {
+    let mut durations = self.durations.iter().copied().collect::<Vec<_>>();
+    durations.sort();
+    // nearest-rank percentile
+    let percentile = |p: usize| {
+      let rank = (p * durations.len()).div_ceil(100).max(1);
+      durations[rank - 1].as_micros() as f64 / 1000.0
+    };
+    PerformancePercentiles {
+      name: name.to_string(),
+      count: durations.len(),
+      p50: percentile(50),
+      p95: percentile(95),
+      max: percentile(100),
+    }
+  }
+}
+
 /// A structure which serves as a start of a measurement span.
 #[derive(Debug)]
 pub struct PerformanceMark {
   name: String,
   count: u32,
   start: Instant,
+  /// The args of the mark, which are only kept for the OTLP exporter.
+  maybe_args: Option<Value>,
 }
 
 /// A structure which holds the information about the measured span.
@@ -76,6 +128,8 @@ impl From<PerformanceMark> for PerformanceMeasure {
 ///
 /// The structure will limit the size of measurements to the most recent 1000,
 /// and will roll off when that limit is reached.
+///
+/// When there's an OTLP exporter, every measurement is recorded as a span.
 #[derive(Debug)]
 pub struct Performance {
   counts: Mutex<HashMap<String, u32>>,
@@ -83,6 +137,8 @@ pub struct Performance {
     Mutex<HashMap<String, (/* count */ u32, /* duration */ f64)>>,
   max_size: usize,
   measures: Mutex<VecDeque<PerformanceMeasure>>,
+  histograms: Mutex<HashMap<String, PerformanceHistogram>>,
+  maybe_exporter: Option<Arc<OtlpExporter>>,
 }
 
 impl Default for Performance {
@@ -92,11 +148,20 @@ impl Default for Performance {
       measurements_by_type: Default::default(),
       max_size: 3_000,
       measures: Default::default(),
+      histograms: Default::default(),
+      maybe_exporter: None,
     }
   }
 }
 
 impl Performance {
+  pub fn with_exporter(exporter: Arc<OtlpExporter>) -> Self {
+    Self {
+      maybe_exporter: Some(exporter),
+      ..Default::default()
+    }
+  }
+
   /// Return the count and average duration of a measurement identified by name.
   #[cfg(test)]
   pub fn average(&self, name: &str) -> Option<(usize, Duration)> {
@@ -166,6 +231,19 @@ impl Performance {
       .collect()
   }
 
+  /// Return the percentiles of the latest measurements of each type, sorted
+  /// by name.
+  pub fn percentiles(&self) -> Vec<PerformancePercentiles> {
+    let mut percentiles = self
+      .histograms
+      .lock()
+      .iter()
+      .map(|(name, histogram)| histogram.percentiles(name))
+      .collect::<Vec<_>>();
+    percentiles.sort_by(|a, b| a.name.cmp(&b.name));
+    percentiles
+  }
+
   fn mark_inner<S: AsRef<str>, V: Serialize>(
     &self,
     name: S,
@@ -196,10 +274,15 @@ impl Performance {
       })
     };
     lsp_debug!("{},", msg);
+    let maybe_args = match &self.maybe_exporter {
+      Some(_) => msg.get("args").cloned(),
+      None => None,
+    };
     PerformanceMark {
       name: name.to_string(),
       count: *count,
       start: Instant::now(),
+      maybe_args,
     }
   }
 
@@ -224,8 +307,12 @@ impl Performance {
   /// A function which accepts a previously created performance mark which will
   /// be used to finalize the duration of the span being measured, and add the
   /// measurement to the internal buffer.
-  pub fn measure(&self, mark: PerformanceMark) -> Duration {
+  pub fn measure(&self, mut mark: PerformanceMark) -> Duration {
+    let maybe_args = mark.maybe_args.take();
     let measure = PerformanceMeasure::from(mark);
+    if let Some(exporter) = &self.maybe_exporter {
+      exporter.record_span(&measure, maybe_args.as_ref());
+    }
     lsp_debug!(
       "{},",
       json!({
@@ -243,6 +330,12 @@ impl Performance {
         .or_insert((0, 0.0));
       measurement.1 += duration.as_micros() as f64 / 1000.0;
     }
+    self
+      .histograms
+      .lock()
+      .entry(measure.name.to_string())
+      .or_default()
+      .record(duration);
     let mut measures = self.measures.lock();
     measures.push_front(measure);
     while measures.len() > self.max_size {
@@ -277,6 +370,40 @@ mod tests {
     assert!(performance.average("c").is_none());
   }
 
+  #[test]
+  fn test_percentiles() {
+    let mut histogram = PerformanceHistogram::default();
+    for ms in (1..=20).rev() {
+      histogram.record(Duration::from_millis(ms));
+    }
+    assert_eq!(
+      histogram.percentiles("a"),
+      PerformancePercentiles {
+        name: "a".to_string(),
+        count: 20,
+        p50: 10.0,
+        p95: 19.0,
+        max: 20.0,
+      }
+    );
+
+    // the oldest durations roll off
+    for _ in 0..PerformanceHistogram::MAX_SIZE {
+      histogram.record(Duration::from_millis(1));
+    }
+    let percentiles = histogram.percentiles("a");
+    assert_eq!(percentiles.count, PerformanceHistogram::MAX_SIZE);
+    assert_eq!(percentiles.max, 1.0);
+
+    let performance = Performance::default();
+    let mark = performance.mark("b");
+    performance.measure(mark);
+    let percentiles = performance.percentiles();
+    assert_eq!(percentiles.len(), 1);
+    assert_eq!(percentiles[0].name, "b");
+    assert_eq!(percentiles[0].count, 1);
+  }
+
   #[test]
   fn test_averages() {
     let performance = Performance::default();
@@ -0,0 +1,245 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use deno_ast::MediaType;
+use deno_core::serde::Deserialize;
+use deno_core::serde::Serialize;
+use deno_core::ModuleSpecifier;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// The virtual document of the code in a document which isn't JavaScript or
+/// TypeScript, which is what the language server analyzes instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VirtualDocument {
+  pub text: String,
+  pub media_type: MediaType,
+}
+
+/// Maps the text of a document which isn't JavaScript or TypeScript, like a
+/// single file component of Vue or Svelte, to a virtual document. The virtual
+/// document takes part in the module graph, diagnostics, go to definition and
+/// so on as if it was the document itself.
+///
+/// The text of the virtual document must have the same lines and UTF-16
+/// columns as the text of the document, so the positions in one of them are
+/// the same in the other one.
+pub trait DocumentPreprocessor: std::fmt::Debug + Send + Sync {
+  fn preprocess(&self, text: &str) -> VirtualDocument;
+}
+
+/// The preprocessors of the `"deno.documentPreprocessors"` setting, which
+/// maps the file extensions to them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DocumentPreprocessorKind {
+  /// The [`ScriptBlockPreprocessor`].
+  ScriptBlocks,
+}
+
+/// Keeps the text of the `<script>` blocks of an HTML like document, like
+/// `<script setup lang="ts">` of Vue and `<script lang="ts">` of Svelte, and
+/// blanks out everything else. The virtual document is TypeScript when a
+/// block has `lang="ts"`.
+#[derive(Debug)]
+pub struct ScriptBlockPreprocessor;
+
+impl DocumentPreprocessor for ScriptBlockPreprocessor {
+  fn preprocess(&self, text: &str) -> VirtualDocument {
+    // the lowercase text has the same byte offsets
+    let lowercase_text = text.to_ascii_lowercase();
+    let mut virtual_text = String::with_capacity(text.len());
+    let mut media_type = MediaType::JavaScript;
+    let mut pos = 0;
+    while let Some(start) = lowercase_text[pos..].find("<script") {
+      let start = pos + start;
+      let name_end = start + "<script".len();
+      // skip tags like `<scripts>`
+      if !lowercase_text[name_end..]
+        .starts_with(|c: char| c.is_ascii_whitespace() || c == '>' || c == '/')
+      {
+        push_blank(&mut virtual_text, &text[pos..name_end]);
+        pos = name_end;
+        continue;
+      }
+      let Some(tag_end) = lowercase_text[start..].find('>') else {
+        break;
+      };
+      let tag_end = start + tag_end + 1;
+      let tag = &lowercase_text[start..tag_end];
+      push_blank(&mut virtual_text, &text[pos..tag_end]);
+      pos = tag_end;
+      if tag.ends_with("/>") {
+        continue;
+      }
+      let Some(end) = lowercase_text[tag_end..].find("</script") else {
+        break;
+      };
+      let end = tag_end + end;
+      virtual_text.push_str(&text[tag_end..end]);
+      pos = end;
+      let block_media_type = match get_lang(tag) {
+        Some("ts") => MediaType::TypeScript,
+        Some("tsx") => MediaType::Tsx,
+        Some("jsx") => MediaType::Jsx,
+        _ => MediaType::JavaScript,
+      };
+      if media_type == MediaType::JavaScript
+        || block_media_type == MediaType::Tsx
+      {
+        media_type = block_media_type;
+      }
+    }
+    push_blank(&mut virtual_text, &text[pos..]);
+    VirtualDocument {
+      text: virtual_text,
+      media_type,
+    }
+  }
+}
+
+/// Gets the value of the `lang` attribute of a lowercase tag.
+fn get_lang(tag: &str) -> Option<&str> {
+  let index =
+    tag
+      .match_indices("lang")
+      .map(|(index, _)| index)
+      .find(|index| {
+        tag[..*index].ends_with(|c: char| c.is_ascii_whitespace())
+          && tag[index + "lang".len()..].trim_start().starts_with('=')
+      })?;
+  let value = tag[index + "lang".len()..].trim_start()[1..].trim_start();
+  match value.chars().next()? {
+    quote @ ('"' | '\'') => value[1..].split(quote).next(),
+    _ => value
+      .split(|c: char| c.is_ascii_whitespace() || c == '>' || c == '/')
+      .next(),
+  }
+}
+
+/// Pushes the text with every character other than the line breaks replaced
+/// by as many spaces as its UTF-16 length.
+fn push_blank(virtual_text: &mut String, text: &str) {
+  for c in text.chars() {
+    match c {
+      '\n' | '\r' => virtual_text.push(c),
+      _ => {
+        for _ in 0..c.len_utf16() {
+          virtual_text.push(' ');
+        }
+      }
+    }
+  }
+}
+
+/// The document preprocessors by the file extensions they're used for.
+#[derive(Debug, Clone, Default)]
+pub struct DocumentPreprocessors(
+  HashMap<String, Arc<dyn DocumentPreprocessor>>,
+);
+
+impl DocumentPreprocessors {
+  pub fn new(kinds: &BTreeMap<String, DocumentPreprocessorKind>) -> Self {
+    Self(
+      kinds
+        .iter()
+        .map(|(extension, kind)| {
+          let preprocessor: Arc<dyn DocumentPreprocessor> = match kind {
+            DocumentPreprocessorKind::ScriptBlocks => {
+              Arc::new(ScriptBlockPreprocessor)
+            }
+          };
+          let extension = extension.trim_start_matches('.').to_lowercase();
+          (extension, preprocessor)
+        })
+        .collect(),
+    )
+  }
+
+  /// Gets the preprocessor of a document by the extension of its path.
+  pub fn get(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> Option<&Arc<dyn DocumentPreprocessor>> {
+    if self.0.is_empty() {
+      return None;
+    }
+    let file_name = specifier.path().rsplit('/').next()?;
+    let (_, extension) = file_name.rsplit_once('.')?;
+    self.0.get(&extension.to_lowercase())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_script_block_preprocessor() {
+    let text = r#"<template>
+  <p>Hé {{ count }}</p>
+</template>
+
+<script setup lang="ts">
+const count: number = 1;
+</script>
+"#;
+    let virtual_document = ScriptBlockPreprocessor.preprocess(text);
+    assert_eq!(virtual_document.media_type, MediaType::TypeScript);
+    assert_eq!(
+      virtual_document
+        .text
+        .lines()
+        .map(|line| line.trim_end())
+        .collect::<Vec<_>>(),
+      vec!["", "", "", "", "", "const count: number = 1;", ""]
+    );
+    let utf16_lengths = |text: &str| {
+      text
+        .lines()
+        .map(|line| line.encode_utf16().count())
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(utf16_lengths(&virtual_document.text), utf16_lengths(text));
+
+    let text = "<script>\nexport let name;\n</script>\n<h1>{name}</h1>\n<script context=\"module\" lang=tsx>let a = <div />;</script>";
+    let virtual_document = ScriptBlockPreprocessor.preprocess(text);
+    assert_eq!(virtual_document.media_type, MediaType::Tsx);
+    assert_eq!(
+      virtual_document.text,
+      "        \nexport let name;\n         \n               \n                                  let a = <div />;         "
+    );
+
+    let virtual_document = ScriptBlockPreprocessor
+      .preprocess("<scripts></scripts><script src=\"./a.js\" />");
+    assert_eq!(virtual_document.media_type, MediaType::JavaScript);
+    assert_eq!(virtual_document.text, " ".repeat(42));
+  }
+
+  #[test]
+  fn test_get_lang() {
+    assert_eq!(get_lang(r#"<script lang="ts">"#), Some("ts"));
+    assert_eq!(get_lang("<script setup lang='tsx'>"), Some("tsx"));
+    assert_eq!(get_lang("<script lang = ts>"), Some("ts"));
+    assert_eq!(get_lang(r#"<script data-lang="ts">"#), None);
+    assert_eq!(get_lang("<script>"), None);
+  }
+
+  #[test]
+  fn test_document_preprocessors() {
+    let preprocessors = DocumentPreprocessors::new(&BTreeMap::from([
+      (".vue".to_string(), DocumentPreprocessorKind::ScriptBlocks),
+      ("svelte".to_string(), DocumentPreprocessorKind::ScriptBlocks),
+    ]));
+    let get = |specifier: &str| {
+      preprocessors
+        .get(&ModuleSpecifier::parse(specifier).unwrap())
+        .is_some()
+    };
+    assert!(get("file:///project/App.vue"));
+    assert!(get("file:///project/Counter.SVELTE"));
+    assert!(!get("file:///project/main.ts"));
+    assert!(!get("file:///project.vue/main"));
+  }
+}
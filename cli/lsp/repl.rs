@@ -11,6 +11,7 @@ use deno_core::serde_json;
 use tower_lsp::lsp_types::ClientCapabilities;
 use tower_lsp::lsp_types::ClientInfo;
 use tower_lsp::lsp_types::CompletionContext;
+use tower_lsp::lsp_types::CompletionItemKind;
 use tower_lsp::lsp_types::CompletionParams;
 use tower_lsp::lsp_types::CompletionResponse;
 use tower_lsp::lsp_types::CompletionTextEdit;
@@ -18,8 +19,11 @@ use tower_lsp::lsp_types::CompletionTriggerKind;
 use tower_lsp::lsp_types::DidChangeTextDocumentParams;
 use tower_lsp::lsp_types::DidCloseTextDocumentParams;
 use tower_lsp::lsp_types::DidOpenTextDocumentParams;
+use tower_lsp::lsp_types::HoverContents;
+use tower_lsp::lsp_types::HoverParams;
 use tower_lsp::lsp_types::InitializeParams;
 use tower_lsp::lsp_types::InitializedParams;
+use tower_lsp::lsp_types::MarkedString;
 use tower_lsp::lsp_types::PartialResultParams;
 use tower_lsp::lsp_types::Position;
 use tower_lsp::lsp_types::Range;
@@ -45,6 +49,7 @@ use super::config::WorkspaceSettings;
 pub struct ReplCompletionItem {
   pub new_text: String,
   pub range: std::ops::Range<usize>,
+  pub kind: Option<CompletionItemKind>,
 }
 
 pub struct ReplLanguageServer {
@@ -56,7 +61,9 @@ pub struct ReplLanguageServer {
 }
 
 impl ReplLanguageServer {
-  pub async fn new_initialized() -> Result<ReplLanguageServer, AnyError> {
+  pub async fn new_initialized(
+    offline: bool,
+  ) -> Result<ReplLanguageServer, AnyError> {
     // downgrade info and warn lsp logging to debug
     super::logging::set_lsp_log_level(log::Level::Debug);
     super::logging::set_lsp_warn_level(log::Level::Debug);
@@ -64,6 +71,8 @@ impl ReplLanguageServer {
     let language_server = super::language_server::LanguageServer::new(
       Client::new_for_repl(),
       Default::default(),
+      offline,
+      Default::default(),
     );
 
     let cwd_uri = get_cwd_uri()?;
@@ -170,10 +179,12 @@ impl ReplLanguageServer {
     items
       .into_iter()
       .filter_map(|item| {
+        let kind = item.kind;
         item.text_edit.and_then(|edit| match edit {
           CompletionTextEdit::Edit(edit) => Some(ReplCompletionItem {
             new_text: edit.new_text,
             range: lsp_range_to_std_range(&text_info, &edit.range),
+            kind,
           }),
           CompletionTextEdit::InsertAndReplace(_) => None,
         })
@@ -192,6 +203,57 @@ impl ReplLanguageServer {
       .collect()
   }
 
+  /// Gets the hover of tsc at the position of the line, which is markdown
+  /// with the type and the documentation of the expression.
+  pub async fn hover(
+    &mut self,
+    line_text: &str,
+    position: usize,
+  ) -> Option<String> {
+    self.did_change(line_text).await;
+    let text_info = deno_ast::SourceTextInfo::from_string(format!(
+      "{}{}",
+      self.document_text, self.pending_text
+    ));
+    let position =
+      text_info.range().start + self.document_text.len() + position;
+    let line_and_column = text_info.line_and_column_index(position);
+    let hover = self
+      .language_server
+      .hover(HoverParams {
+        text_document_position_params: TextDocumentPositionParams {
+          text_document: TextDocumentIdentifier {
+            uri: self.get_document_specifier(),
+          },
+          position: Position {
+            line: line_and_column.line_index as u32,
+            character: line_and_column.column_index as u32,
+          },
+        },
+        work_done_progress_params: WorkDoneProgressParams {
+          work_done_token: None,
+        },
+      })
+      .await
+      .ok()??;
+    let marked_string_text = |marked_string: MarkedString| match marked_string {
+      MarkedString::String(text) => text,
+      MarkedString::LanguageString(code) => {
+        format!("```{}\n{}\n```", code.language, code.value)
+      }
+    };
+    let text = match hover.contents {
+      HoverContents::Scalar(marked_string) => marked_string_text(marked_string),
+      HoverContents::Array(marked_strings) => marked_strings
+        .into_iter()
+        .map(marked_string_text)
+        .collect::<Vec<_>>()
+        .join("\n\n"),
+      HoverContents::Markup(markup) => markup.value,
+    };
+    Some(text)
+  }
+
   async fn did_change(&mut self, new_text: &str) {
     self.check_cwd_change().await;
     let new_text = if new_text.ends_with('\n') {
@@ -298,12 +360,14 @@ pub fn get_repl_workspace_settings() -> WorkspaceSettings {
     cache: None,
     cache_on_save: false,
     import_map: None,
+    untitled_root: None,
     code_lens: Default::default(),
     internal_debug: false,
     internal_inspect: Default::default(),
     log_file: false,
     lint: false,
     document_preload_limit: 0, // don't pre-load any modules as it's expensive and not useful for the repl
+    max_document_cache_mb: None,
     tls_certificate: None,
     unsafely_ignore_certificate_errors: None,
     unstable: false,
@@ -313,7 +377,14 @@ pub fn get_repl_workspace_settings() -> WorkspaceSettings {
         hosts: HashMap::from([("https://deno.land".to_string(), true)]),
       },
     },
-    testing: TestingSettings { args: vec![] },
+    testing: TestingSettings {
+      args: vec![],
+      run_on_save: false,
+      describe_identifiers: vec![],
+      it_identifiers: vec![],
+    },
+    use_gitignore: false,
+    workspace_symbols: Default::default(),
     javascript: LanguageWorkspaceSettings {
       suggest: CompletionSettings {
         auto_imports: false,
@@ -29,6 +29,9 @@ enum TokenType {
   Property = 9,
   Function = 10,
   Method = 11,
+  ImportScheme = 12,
+  ImportPackage = 13,
+  ImportVersion = 14,
 }
 
 impl<T> Index<TokenType> for Vec<T> {
@@ -51,6 +54,8 @@ enum TokenModifier {
   Readonly = 3,
   DefaultLibrary = 4,
   Local = 5,
+  Unpinned = 6,
+  Unknown = 7,
 }
 
 impl<T> Index<TokenModifier> for Vec<T> {
@@ -67,7 +72,7 @@ impl<T> IndexMut<TokenModifier> for Vec<T> {
 }
 
 pub fn get_legend() -> SemanticTokensLegend {
-  let mut token_types = vec![SemanticTokenType::from(""); 12];
+  let mut token_types = vec![SemanticTokenType::from(""); 15];
   token_types[TokenType::Class] = "class".into();
   token_types[TokenType::Enum] = "enum".into();
   token_types[TokenType::Interface] = "interface".into();
@@ -80,14 +85,19 @@ pub fn get_legend() -> SemanticTokensLegend {
   token_types[TokenType::Property] = "property".into();
   token_types[TokenType::Function] = "function".into();
   token_types[TokenType::Method] = "method".into();
+  token_types[TokenType::ImportScheme] = "importScheme".into();
+  token_types[TokenType::ImportPackage] = "importPackage".into();
+  token_types[TokenType::ImportVersion] = "importVersion".into();
 
-  let mut token_modifiers = vec![SemanticTokenModifier::from(""); 6];
+  let mut token_modifiers = vec![SemanticTokenModifier::from(""); 8];
   token_modifiers[TokenModifier::Async] = "async".into();
   token_modifiers[TokenModifier::Declaration] = "declaration".into();
   token_modifiers[TokenModifier::Readonly] = "readonly".into();
   token_modifiers[TokenModifier::Static] = "static".into();
   token_modifiers[TokenModifier::Local] = "local".into();
   token_modifiers[TokenModifier::DefaultLibrary] = "defaultLibrary".into();
+  token_modifiers[TokenModifier::Unpinned] = "unpinned".into();
+  token_modifiers[TokenModifier::Unknown] = "unknown".into();
 
   SemanticTokensLegend {
     token_types,
@@ -247,6 +257,135 @@ impl SemanticTokensBuilder {
   }
 }
 
+const KNOWN_SCHEMES: &[&str] = &[
+  "blob", "data", "file", "http", "https", "jsr", "node", "npm",
+];
+
+/// A token for a portion of an import specifier, where `start` and `length`
+/// are in UTF-16 code units relative to the start of the specifier.
+#[derive(Debug, PartialEq, Eq)]
+struct ImportSpecifierToken {
+  start: u32,
+  length: u32,
+  token_type: u32,
+  token_modifiers: u32,
+}
+
+fn utf16_len(text: &str) -> u32 {
+  text.encode_utf16().count() as u32
+}
+
+fn import_specifier_tokens(specifier: &str) -> Vec<ImportSpecifierToken> {
+  let mut tokens = Vec::new();
+  let Some(scheme_end) = specifier.find(':') else {
+    return tokens;
+  };
+  let scheme = &specifier[..scheme_end];
+  // a single letter is a windows drive letter and not a scheme
+  if scheme.len() < 2
+    || !scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+    || !scheme
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+  {
+    return tokens;
+  }
+  let scheme_modifiers = if KNOWN_SCHEMES.contains(&scheme) {
+    0
+  } else {
+    1 << TokenModifier::Unknown as u32
+  };
+  tokens.push(ImportSpecifierToken {
+    start: 0,
+    length: utf16_len(&specifier[..scheme_end + 1]),
+    token_type: TokenType::ImportScheme as u32,
+    token_modifiers: scheme_modifiers,
+  });
+  let mut offset = scheme_end + 1;
+  match scheme {
+    "node" => {
+      if offset < specifier.len() {
+        tokens.push(ImportSpecifierToken {
+          start: utf16_len(&specifier[..offset]),
+          length: utf16_len(&specifier[offset..]),
+          token_type: TokenType::ImportPackage as u32,
+          token_modifiers: 0,
+        });
+      }
+    }
+    "npm" | "jsr" => {
+      if specifier[offset..].starts_with('/') {
+        offset += 1;
+      }
+      let rest = &specifier[offset..];
+      // skip past the scope of a scoped package
+      let name_search_start = if rest.starts_with('@') {
+        rest.find('/').map(|i| i + 1).unwrap_or(rest.len())
+      } else {
+        0
+      };
+      let name_end = rest[name_search_start..]
+        .find(|c| c == '@' || c == '/')
+        .map(|i| i + name_search_start)
+        .unwrap_or(rest.len());
+      if name_end == 0 {
+        return tokens;
+      }
+      let version = rest[name_end..].strip_prefix('@').map(|version| {
+        let version_end = version.find('/').unwrap_or(version.len());
+        &version[..version_end]
+      });
+      let is_pinned = version
+        .map(|v| deno_semver::Version::parse_standard(v).is_ok())
+        .unwrap_or(false);
+      tokens.push(ImportSpecifierToken {
+        start: utf16_len(&specifier[..offset]),
+        length: utf16_len(&rest[..name_end]),
+        token_type: TokenType::ImportPackage as u32,
+        token_modifiers: if version.is_none() {
+          1 << TokenModifier::Unpinned as u32
+        } else {
+          0
+        },
+      });
+      if let Some(version) = version.filter(|v| !v.is_empty()) {
+        tokens.push(ImportSpecifierToken {
+          start: utf16_len(&specifier[..offset + name_end + 1]),
+          length: utf16_len(version),
+          token_type: TokenType::ImportVersion as u32,
+          token_modifiers: if is_pinned {
+            0
+          } else {
+            1 << TokenModifier::Unpinned as u32
+          },
+        });
+      }
+    }
+    _ => {}
+  }
+  tokens
+}
+
+/// Push tokens for the scheme, package name and version constraint of an
+/// import specifier. The `line` and `character` are the position of the first
+/// character of the specifier, excluding any quotes.
+pub fn push_import_specifier_tokens(
+  builder: &mut SemanticTokensBuilder,
+  specifier: &str,
+  line: u32,
+  character: u32,
+) {
+  for token in import_specifier_tokens(specifier) {
+    builder.push(
+      line,
+      character + token.start,
+      token.length,
+      token.token_type,
+      token.token_modifiers,
+    );
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -352,4 +491,54 @@ mod tests {
       ]
     );
   }
+
+  #[test]
+  fn test_import_specifier_tokens() {
+    fn tokens(specifier: &str) -> Vec<(&str, u32, u32)> {
+      import_specifier_tokens(specifier)
+        .into_iter()
+        .map(|t| {
+          let start = t.start as usize;
+          let end = start + t.length as usize;
+          (&specifier[start..end], t.token_type, t.token_modifiers)
+        })
+        .collect()
+    }
+    let scheme = TokenType::ImportScheme as u32;
+    let package = TokenType::ImportPackage as u32;
+    let version = TokenType::ImportVersion as u32;
+    let unpinned = 1 << TokenModifier::Unpinned as u32;
+    let unknown = 1 << TokenModifier::Unknown as u32;
+    assert_eq!(
+      tokens("npm:chalk@5.0.1"),
+      vec![
+        ("npm:", scheme, 0),
+        ("chalk", package, 0),
+        ("5.0.1", version, 0)
+      ]
+    );
+    assert_eq!(
+      tokens("jsr:@std/path@^0.210/join"),
+      vec![
+        ("jsr:", scheme, 0),
+        ("@std/path", package, 0),
+        ("^0.210", version, unpinned),
+      ]
+    );
+    assert_eq!(
+      tokens("npm:/preact/hooks"),
+      vec![("npm:", scheme, 0), ("preact", package, unpinned)]
+    );
+    assert_eq!(
+      tokens("node:fs/promises"),
+      vec![("node:", scheme, 0), ("fs/promises", package, 0)]
+    );
+    assert_eq!(
+      tokens("https://deno.land/x/mod.ts"),
+      vec![("https:", scheme, 0)]
+    );
+    assert_eq!(tokens("foo:bar"), vec![("foo:", scheme, unknown)]);
+    assert_eq!(tokens("./mod.ts"), vec![]);
+    assert_eq!(tokens("C:/mod.ts"), vec![]);
+  }
 }
@@ -1,12 +1,14 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
 use crate::lsp::analysis::source_range_to_lsp_range;
+use crate::lsp::config::TestingSettings;
 
 use super::definitions::TestModule;
 
 use deno_ast::swc::ast;
 use deno_ast::swc::visit::Visit;
 use deno_ast::swc::visit::VisitWith;
+use deno_ast::SourceRange;
 use deno_ast::SourceRangedForSpanned;
 use deno_ast::SourceTextInfo;
 use deno_core::ModuleSpecifier;
@@ -442,11 +444,225 @@ impl Visit for TestStepCollector<'_> {
   }
 }
 
-/// Walk an AST and determine if it contains any `Deno.test` tests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BddKind {
+  Describe,
+  It,
+}
+
+/// The identifiers of the bdd style test functions of a module, which are
+/// either imported from `@std/testing/bdd` or configured in the testing
+/// settings.
+#[derive(Debug, Default)]
+struct BddIdentifiers {
+  describe: HashSet<String>,
+  it: HashSet<String>,
+}
+
+impl BddIdentifiers {
+  fn new(testing_settings: &TestingSettings) -> Self {
+    Self {
+      describe: testing_settings
+        .describe_identifiers
+        .iter()
+        .cloned()
+        .collect(),
+      it: testing_settings.it_identifiers.iter().cloned().collect(),
+    }
+  }
+
+  fn get(&self, name: &str) -> Option<BddKind> {
+    if self.describe.contains(name) {
+      Some(BddKind::Describe)
+    } else if self.it.contains(name) {
+      Some(BddKind::It)
+    } else {
+      None
+    }
+  }
+
+  /// Determine if a call is a call to `describe` or `it`, including the
+  /// `.only()`, `.ignore()` and `.skip()` variants, returning the kind and the
+  /// range of the identifier.
+  fn match_callee(
+    &self,
+    callee: &ast::Callee,
+  ) -> Option<(BddKind, SourceRange)> {
+    let ast::Callee::Expr(callee_expr) = callee else {
+      return None;
+    };
+    let ident = match callee_expr.as_ref() {
+      ast::Expr::Ident(ident) => ident,
+      ast::Expr::Member(member_expr) => {
+        let ast::MemberProp::Ident(prop_ident) = &member_expr.prop else {
+          return None;
+        };
+        if !matches!(&*prop_ident.sym, "only" | "ignore" | "skip") {
+          return None;
+        }
+        let ast::Expr::Ident(ident) = member_expr.obj.as_ref() else {
+          return None;
+        };
+        ident
+      }
+      _ => return None,
+    };
+    self.get(&ident.sym).map(|kind| (kind, ident.range()))
+  }
+}
+
+/// Determine if an import specifier refers to the bdd module of the testing
+/// part of the standard library, for example `jsr:@std/testing/bdd` or
+/// `https://deno.land/std/testing/bdd.ts`.
+fn is_bdd_module(specifier: &str) -> bool {
+  let path = specifier.split(['?', '#']).next().unwrap_or(specifier);
+  let path = path.strip_suffix(".ts").unwrap_or(path);
+  path.ends_with("/bdd") && path.contains("testing")
+}
+
+enum BddFn<'a> {
+  Arrow(&'a ast::ArrowExpr),
+  Fn(&'a ast::Function),
+}
+
+/// Check a call expression of `describe` or `it` to determine the name of the
+/// suite or test case, as well as any nested suites and test cases of a suite.
+/// These are registered the way `@std/testing/bdd` registers them at runtime,
+/// where top level suites and test cases are tests and anything nested in a
+/// suite is a test step of it.
+fn visit_bdd_call_expr(
+  node: &ast::CallExpr,
+  kind: BddKind,
+  range: Range,
+  parent_id: Option<&str>,
+  bdd: &BddIdentifiers,
+  text_info: &SourceTextInfo,
+  test_module: &mut TestModule,
+) {
+  let mut maybe_name = None;
+  let mut maybe_fn = None;
+  for arg in &node.args {
+    match arg.expr.as_ref() {
+      ast::Expr::Lit(ast::Lit::Str(lit_str)) => {
+        maybe_name.get_or_insert_with(|| lit_str.value.to_string());
+      }
+      ast::Expr::Tpl(tpl) if tpl.quasis.len() == 1 => {
+        maybe_name.get_or_insert_with(|| tpl.quasis[0].raw.to_string());
+      }
+      ast::Expr::Arrow(arrow_expr) => maybe_fn = Some(BddFn::Arrow(arrow_expr)),
+      ast::Expr::Fn(fn_expr) => maybe_fn = Some(BddFn::Fn(&fn_expr.function)),
+      ast::Expr::Object(obj_lit) => {
+        for prop in &obj_lit.props {
+          let ast::PropOrSpread::Prop(prop) = prop else {
+            continue;
+          };
+          match prop.as_ref() {
+            ast::Prop::KeyValue(key_value_prop) => {
+              let ast::PropName::Ident(ast::Ident { sym, .. }) =
+                &key_value_prop.key
+              else {
+                continue;
+              };
+              match (&**sym, key_value_prop.value.as_ref()) {
+                ("name", ast::Expr::Lit(ast::Lit::Str(lit_str))) => {
+                  maybe_name = Some(lit_str.value.to_string());
+                }
+                ("name", ast::Expr::Tpl(tpl)) if tpl.quasis.len() == 1 => {
+                  maybe_name = Some(tpl.quasis[0].raw.to_string());
+                }
+                ("fn", ast::Expr::Arrow(arrow_expr)) => {
+                  maybe_fn = Some(BddFn::Arrow(arrow_expr));
+                }
+                ("fn", ast::Expr::Fn(fn_expr)) => {
+                  maybe_fn = Some(BddFn::Fn(&fn_expr.function));
+                }
+                _ => {}
+              }
+            }
+            ast::Prop::Method(method_prop) => {
+              if let ast::PropName::Ident(ast::Ident { sym, .. }) =
+                &method_prop.key
+              {
+                if sym == "fn" {
+                  maybe_fn = Some(BddFn::Fn(&method_prop.function));
+                }
+              }
+            }
+            _ => {}
+          }
+        }
+      }
+      _ => {}
+    }
+  }
+  let Some(name) = maybe_name else {
+    return;
+  };
+  let (id, _) = test_module.register(
+    name,
+    Some(range),
+    false,
+    parent_id.map(str::to_owned),
+  );
+  let Some(bdd_fn) = maybe_fn else {
+    return;
+  };
+  match kind {
+    BddKind::Describe => {
+      let mut collector = BddSuiteCollector {
+        bdd,
+        parent_id: &id,
+        text_info,
+        test_module,
+      };
+      match bdd_fn {
+        BddFn::Arrow(arrow_expr) => arrow_expr.body.visit_with(&mut collector),
+        BddFn::Fn(function) => function.body.visit_with(&mut collector),
+      }
+    }
+    // test cases receive a test context, so they can contain test steps
+    BddKind::It => match bdd_fn {
+      BddFn::Arrow(arrow_expr) => {
+        visit_arrow(arrow_expr, &id, text_info, test_module)
+      }
+      BddFn::Fn(function) => visit_fn(function, &id, text_info, test_module),
+    },
+  }
+}
+
+/// Walks the body of a `describe` call for nested suites and test cases.
+struct BddSuiteCollector<'a> {
+  bdd: &'a BddIdentifiers,
+  parent_id: &'a str,
+  text_info: &'a SourceTextInfo,
+  test_module: &'a mut TestModule,
+}
+
+impl Visit for BddSuiteCollector<'_> {
+  fn visit_call_expr(&mut self, node: &ast::CallExpr) {
+    if let Some((kind, range)) = self.bdd.match_callee(&node.callee) {
+      visit_bdd_call_expr(
+        node,
+        kind,
+        source_range_to_lsp_range(&range, self.text_info),
+        Some(self.parent_id),
+        self.bdd,
+        self.text_info,
+        self.test_module,
+      );
+    } else {
+      node.visit_children_with(self);
+    }
+  }
+}
+
+/// Walk an AST and determine if it contains any `Deno.test` tests, or any bdd
+/// style `describe` and `it` tests.
 pub struct TestCollector {
   test_module: TestModule,
   vars: HashSet<String>,
   fns: HashMap<String, ast::Function>,
+  bdd: BddIdentifiers,
   text_info: SourceTextInfo,
 }
 
@@ -455,11 +671,13 @@ impl TestCollector {
     specifier: ModuleSpecifier,
     script_version: String,
     text_info: SourceTextInfo,
+    testing_settings: &TestingSettings,
   ) -> Self {
     Self {
       test_module: TestModule::new(specifier, script_version),
       vars: HashSet::new(),
       fns: HashMap::new(),
+      bdd: BddIdentifiers::new(testing_settings),
       text_info,
     }
   }
@@ -499,6 +717,19 @@ impl Visit for TestCollector {
       }
     }
 
+    if let Some((kind, range)) = self.bdd.match_callee(&node.callee) {
+      visit_bdd_call_expr(
+        node,
+        kind,
+        source_range_to_lsp_range(&range, &self.text_info),
+        None,
+        &self.bdd,
+        &self.text_info,
+        &mut self.test_module,
+      );
+      return;
+    }
+
     let ast::Callee::Expr(callee_expr) = &node.callee else {
       return;
     };
@@ -626,6 +857,32 @@ impl Visit for TestCollector {
       .fns
       .insert(n.ident.sym.to_string(), *n.function.clone());
   }
+
+  fn visit_import_decl(&mut self, node: &ast::ImportDecl) {
+    if !is_bdd_module(&node.src.value) {
+      return;
+    }
+    for specifier in &node.specifiers {
+      let ast::ImportSpecifier::Named(named) = specifier else {
+        continue;
+      };
+      let imported = match &named.imported {
+        Some(ast::ModuleExportName::Ident(ident)) => ident.sym.to_string(),
+        Some(ast::ModuleExportName::Str(lit_str)) => lit_str.value.to_string(),
+        None => named.local.sym.to_string(),
+      };
+      let local = named.local.sym.to_string();
+      match imported.as_str() {
+        "describe" => {
+          self.bdd.describe.insert(local);
+        }
+        "it" | "test" => {
+          self.bdd.it.insert(local);
+        }
+        _ => {}
+      }
+    }
+  }
 }
 
 #[cfg(test)]
@@ -653,12 +910,76 @@ pub mod tests {
     })
     .unwrap();
     let text_info = parsed_module.text_info().clone();
-    let mut collector =
-      TestCollector::new(specifier, "1".to_string(), text_info);
+    let mut collector = TestCollector::new(
+      specifier,
+      "1".to_string(),
+      text_info,
+      &Default::default(),
+    );
     parsed_module.module().visit_with(&mut collector);
     collector.take()
   }
 
+  #[test]
+  fn test_test_collector_bdd() {
+    let test_module = collect(
+      r#"
+      import { describe, it as test } from "jsr:@std/testing/bdd";
+      describe("suite", () => {
+        describe.only({
+          name: "nested suite",
+          fn() {
+            test("case", async (t) => {
+              await t.step("step", () => {});
+            });
+          },
+        });
+        test(`other case`, () => {});
+      });
+      test.ignore("top level case", () => {});
+      it("not imported", () => {});
+    "#,
+    );
+
+    let mut tests = test_module
+      .defs
+      .values()
+      .map(|def| {
+        (
+          def.name.as_str(),
+          def.range,
+          def
+            .parent_id
+            .as_ref()
+            .map(|id| test_module.defs.get(id).unwrap().name.as_str()),
+        )
+      })
+      .collect::<Vec<_>>();
+    tests.sort_by_key(|(_, range, _)| range.unwrap().start);
+    assert_eq!(
+      tests,
+      vec![
+        ("suite", Some(new_range(2, 6, 2, 14)), None),
+        ("nested suite", Some(new_range(3, 8, 3, 16)), Some("suite")),
+        ("case", Some(new_range(6, 12, 6, 16)), Some("nested suite")),
+        ("step", Some(new_range(7, 22, 7, 26)), Some("case")),
+        ("other case", Some(new_range(11, 8, 11, 12)), Some("suite")),
+        ("top level case", Some(new_range(13, 6, 13, 10)), None),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_is_bdd_module() {
+    assert!(is_bdd_module("jsr:@std/testing/bdd"));
+    assert!(is_bdd_module("jsr:@std/testing@^0.218.0/bdd"));
+    assert!(is_bdd_module(
+      "https://deno.land/std@0.218.0/testing/bdd.ts"
+    ));
+    assert!(!is_bdd_module("jsr:@std/testing/mock"));
+    assert!(!is_bdd_module("./bdd.ts"));
+  }
+
   #[test]
   fn test_test_collector_test() {
     let test_module = collect(
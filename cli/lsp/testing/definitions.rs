@@ -25,6 +25,47 @@ pub struct TestDefinition {
   pub step_ids: HashSet<String>,
 }
 
+/// The outcome of a test the last time it was run by the language server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestResultState {
+  Passed,
+  Failed,
+  Skipped,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TestRunResult {
+  pub state: TestResultState,
+  /// The duration of the test in milliseconds.
+  pub duration: Option<u64>,
+}
+
+impl TestRunResult {
+  /// The title of the "Run Test" code lens, which shows the outcome of the
+  /// last run in place of the run icon.
+  pub fn to_code_lens_title(&self) -> String {
+    let icon = match self.state {
+      TestResultState::Passed => "\u{2714}\u{fe0e}",
+      TestResultState::Failed => "\u{2718}\u{fe0e}",
+      TestResultState::Skipped => "\u{2298}\u{fe0e}",
+    };
+    match self.duration {
+      Some(duration) if self.state != TestResultState::Skipped => {
+        format!("{icon} Run Test ({duration}ms)")
+      }
+      _ => format!("{icon} Run Test"),
+    }
+  }
+}
+
+/// The line coverage of each module covered by the last test run which
+/// collected coverage.
+pub type TestCoverage =
+  HashMap<ModuleSpecifier, Vec<lsp_custom::TestCoverageLine>>;
+
+/// The results of the last run of the tests of each module, by test name.
+pub type TestResults = HashMap<ModuleSpecifier, HashMap<String, TestRunResult>>;
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct TestModule {
   pub specifier: ModuleSpecifier,
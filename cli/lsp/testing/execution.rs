@@ -1,7 +1,11 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use super::definitions::TestCoverage;
 use super::definitions::TestDefinition;
 use super::definitions::TestModule;
+use super::definitions::TestResultState;
+use super::definitions::TestResults;
+use super::definitions::TestRunResult;
 use super::lsp_custom;
 
 use crate::args::flags_from_vec;
@@ -11,6 +15,8 @@ use crate::lsp::client::Client;
 use crate::lsp::client::TestingNotification;
 use crate::lsp::config;
 use crate::lsp::logging::lsp_log;
+use crate::lsp::logging::lsp_warn;
+use crate::tools::coverage;
 use crate::tools::test;
 use crate::tools::test::FailFastTracker;
 use crate::tools::test::TestEventSender;
@@ -146,17 +152,26 @@ impl LspTestFilter {
 pub struct TestRun {
   id: u32,
   kind: lsp_custom::TestRunKind,
+  collects_coverage: bool,
   filters: HashMap<ModuleSpecifier, LspTestFilter>,
   queue: HashSet<ModuleSpecifier>,
   tests: Arc<Mutex<HashMap<ModuleSpecifier, TestModule>>>,
+  results: Arc<Mutex<TestResults>>,
+  coverage: Arc<Mutex<TestCoverage>>,
   token: CancellationToken,
   workspace_settings: config::WorkspaceSettings,
+  /// When set, the tests are started with `--inspect-brk` and the client is
+  /// notified once the inspector is available.
+  maybe_debug_session: Option<lsp_custom::TestDebugSessionParams>,
+  maybe_inspect_brk_arg: Option<String>,
 }
 
 impl TestRun {
   pub fn new(
     params: &lsp_custom::TestRunRequestParams,
     tests: Arc<Mutex<HashMap<ModuleSpecifier, TestModule>>>,
+    results: Arc<Mutex<TestResults>>,
+    coverage: Arc<Mutex<TestCoverage>>,
     workspace_settings: config::WorkspaceSettings,
   ) -> Self {
     let (queue, filters) = {
@@ -167,14 +182,33 @@ impl TestRun {
     Self {
       id: params.id,
       kind: params.kind.clone(),
+      collects_coverage: params.kind == lsp_custom::TestRunKind::Coverage
+        || params.coverage,
       filters,
       queue,
       tests,
+      results,
+      coverage,
       token: CancellationToken::new(),
       workspace_settings,
+      maybe_debug_session: None,
+      maybe_inspect_brk_arg: None,
     }
   }
 
+  /// Break on the first statement of the tests, waiting for a debugger to
+  /// attach to the inspector described by the debug session.
+  pub fn set_debug_session(
+    &mut self,
+    debug_session: lsp_custom::TestDebugSessionParams,
+  ) {
+    self.maybe_inspect_brk_arg = Some(format!(
+      "--inspect-brk={}:{}",
+      debug_session.host, debug_session.port
+    ));
+    self.maybe_debug_session = Some(debug_session);
+  }
+
   /// Provide the tests of a test run as an enqueued module which can be sent
   /// to the client to indicate tests are enqueued for testing.
   pub fn as_enqueued(&self) -> Vec<lsp_custom::EnqueuedTestModule> {
@@ -200,6 +234,16 @@ impl TestRun {
       .collect()
   }
 
+  pub fn kind(&self) -> &lsp_custom::TestRunKind {
+    &self.kind
+  }
+
+  /// Gets if the coverage of the run is collected, either because of its
+  /// kind or because it was requested explicitly.
+  pub fn collects_coverage(&self) -> bool {
+    self.collects_coverage
+  }
+
   /// If being executed, cancel the test.
   pub fn cancel(&self) {
     self.token.cancel();
@@ -211,7 +255,18 @@ impl TestRun {
     client: &Client,
     maybe_root_uri: Option<&ModuleSpecifier>,
   ) -> Result<(), AnyError> {
-    let args = self.get_args();
+    let mut args = self.get_args();
+    let maybe_coverage_dir = if self.collects_coverage {
+      Some(tempfile::tempdir()?)
+    } else {
+      None
+    };
+    let coverage_arg;
+    if let Some(coverage_dir) = &maybe_coverage_dir {
+      args.retain(|a| *a != "--coverage" && !a.starts_with("--coverage="));
+      coverage_arg = format!("--coverage={}", coverage_dir.path().display());
+      args.push(&coverage_arg);
+    }
     lsp_log!("Executing test run with arguments: {}", args.join(" "));
     let flags = flags_from_vec(args.into_iter().map(String::from).collect())?;
     let factory = CliFactory::from_flags(flags).await?;
@@ -219,7 +274,7 @@ impl TestRun {
     // `PermissionsContainer` - otherwise granting/revoking permissions in one
     // file would have impact on other files, which is undesirable.
     let permissions =
-      Permissions::from_options(&factory.cli_options().permissions_options())?;
+      Permissions::from_options(&factory.cli_options().permissions_options()?)?;
     test::check_specifiers(
       factory.cli_options(),
       factory.file_fetcher()?,
@@ -258,6 +313,19 @@ impl TestRun {
     let mut test_steps = IndexMap::new();
     let worker_factory =
       Arc::new(factory.create_cli_main_worker_factory().await?);
+    // the inspector server is listening once the worker factory is created,
+    // and it holds the port it was bound to
+    if let Some(debug_session) = &self.maybe_debug_session {
+      let Some(inspector_server) = factory.maybe_inspector_server() else {
+        return Err(anyhow!("The inspector server failed to start."));
+      };
+      client.send_test_notification(TestingNotification::DebugSession(
+        lsp_custom::TestDebugSessionParams {
+          port: inspector_server.host.port(),
+          ..debug_session.clone()
+        },
+      ));
+    }
 
     let join_handles = queue.into_iter().map(move |specifier| {
       let specifier = specifier.clone();
@@ -298,6 +366,8 @@ impl TestRun {
               filter,
               shuffle: None,
               trace_ops: false,
+              retry: 0,
+              update_snapshots: false,
             },
           ))
         };
@@ -352,14 +422,18 @@ impl TestRun {
             test::TestEvent::Wait(id) => {
               reporter.report_wait(tests.read().get(&id).unwrap());
             }
-            test::TestEvent::Output(output) => {
-              reporter.report_output(&output);
+            test::TestEvent::Output(stream, output) => {
+              reporter.report_output(stream, &output);
             }
             test::TestEvent::Result(id, result, elapsed) => {
               if tests_with_result.insert(id) {
                 let description = tests.read().get(&id).unwrap().clone();
                 match &result {
                   test::TestResult::Ok => summary.passed += 1,
+                  test::TestResult::Flaky(_) => {
+                    summary.passed += 1;
+                    summary.flaky += 1;
+                  }
                   test::TestResult::Ignored => summary.ignored += 1,
                   test::TestResult::Failed(error) => {
                     summary.failed += 1;
@@ -430,6 +504,31 @@ impl TestRun {
 
     let (join_results, result) = future::join(join_stream, handler).await;
 
+    // the coverage is also collected when tests fail
+    if let Some(coverage_dir) = &maybe_coverage_dir {
+      match coverage::collect_line_coverage(&factory, coverage_dir.path()).await
+      {
+        Ok(line_coverages) => {
+          let mut coverage = self.coverage.lock();
+          coverage.clear();
+          for line_coverage in line_coverages {
+            coverage.insert(
+              line_coverage.specifier,
+              line_coverage
+                .lines
+                .into_iter()
+                .map(|(line, hits)| lsp_custom::TestCoverageLine {
+                  line: line as u32,
+                  hits,
+                })
+                .collect(),
+            );
+          }
+        }
+        Err(err) => lsp_warn!("Unable to collect test coverage: {}", err),
+      }
+    }
+
     // propagate any errors
     for join_result in join_results {
       join_result??;
@@ -466,7 +565,15 @@ impl TestRun {
         args.push(import_map.as_str());
       }
     }
-    if self.kind == lsp_custom::TestRunKind::Debug
+    if let Some(inspect_brk_arg) = &self.maybe_inspect_brk_arg {
+      args.retain(|a| {
+        *a != "--inspect"
+          && *a != "--inspect-brk"
+          && !a.starts_with("--inspect=")
+          && !a.starts_with("--inspect-brk=")
+      });
+      args.push(inspect_brk_arg.as_str());
+    } else if self.kind == lsp_custom::TestRunKind::Debug
       && !args.contains(&"--inspect")
       && !args.contains(&"--inspect-brk")
     {
@@ -541,6 +648,7 @@ struct LspTestReporter {
   id: u32,
   maybe_root_uri: Option<ModuleSpecifier>,
   files: Arc<Mutex<HashMap<ModuleSpecifier, TestModule>>>,
+  results: Arc<Mutex<TestResults>>,
   tests: IndexMap<usize, LspTestDescription>,
   current_test: Option<usize>,
 }
@@ -557,6 +665,7 @@ impl LspTestReporter {
       id: run.id,
       maybe_root_uri: maybe_root_uri.cloned(),
       files,
+      results: run.results.clone(),
       tests: Default::default(),
       current_test: Default::default(),
     }
@@ -573,6 +682,24 @@ impl LspTestReporter {
       ));
   }
 
+  /// Remember the outcome of a test for the test code lenses.
+  fn record_result(
+    &self,
+    desc: &test::TestDescription,
+    state: TestResultState,
+    duration: Option<u64>,
+  ) {
+    let Ok(specifier) = ModuleSpecifier::parse(&desc.location.file_name) else {
+      return;
+    };
+    self
+      .results
+      .lock()
+      .entry(specifier)
+      .or_default()
+      .insert(desc.name.clone(), TestRunResult { state, duration });
+  }
+
   fn report_plan(&mut self, _plan: &test::TestPlan) {}
 
   fn report_register(&mut self, desc: &test::TestDescription) {
@@ -609,7 +736,7 @@ impl LspTestReporter {
     self.progress(lsp_custom::TestRunProgressMessage::Started { test });
   }
 
-  fn report_output(&mut self, output: &[u8]) {
+  fn report_output(&mut self, stream: test::TestStdioStream, output: &[u8]) {
     let test = self
       .current_test
       .as_ref()
@@ -617,6 +744,7 @@ impl LspTestReporter {
     let value = String::from_utf8_lossy(output).replace('\n', "\r\n");
     self.progress(lsp_custom::TestRunProgressMessage::Output {
       value,
+      stream,
       test,
       // TODO(@kitsonk) test output should include a location
       location: None,
@@ -630,8 +758,18 @@ impl LspTestReporter {
     elapsed: u64,
   ) {
     self.current_test = None;
+    let state = match result {
+      test::TestResult::Ok | test::TestResult::Flaky(_) => {
+        TestResultState::Passed
+      }
+      test::TestResult::Ignored => TestResultState::Skipped,
+      test::TestResult::Failed(_) | test::TestResult::Cancelled => {
+        TestResultState::Failed
+      }
+    };
+    self.record_result(desc, state, Some(elapsed));
     match result {
-      test::TestResult::Ok => {
+      test::TestResult::Ok | test::TestResult::Flaky(_) => {
         let desc = self.tests.get(&desc.id).unwrap();
         self.progress(lsp_custom::TestRunProgressMessage::Passed {
           test: desc.as_test_identifier(&self.tests),
@@ -672,6 +810,9 @@ impl LspTestReporter {
     );
     let messages = as_test_messages(err_string, false);
     for desc in self.tests.values().filter(|d| d.origin() == origin) {
+      if let LspTestDescription::TestDescription(desc, _) = desc {
+        self.record_result(desc, TestResultState::Failed, None);
+      }
       self.progress(lsp_custom::TestRunProgressMessage::Failed {
         test: desc.as_test_identifier(&self.tests),
         messages: messages.clone(),
@@ -775,6 +916,7 @@ mod tests {
     let params = lsp_custom::TestRunRequestParams {
       id: 1,
       kind: lsp_custom::TestRunKind::Run,
+      coverage: false,
       include: Some(vec![
         lsp_custom::TestIdentifier {
           text_document: lsp::TextDocumentIdentifier {
@@ -1,5 +1,6 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use crate::tools::test::TestStdioStream;
 use deno_core::serde::Deserialize;
 use deno_core::serde::Serialize;
 use tower_lsp::lsp_types as lsp;
@@ -81,10 +82,10 @@ impl lsp::notification::Notification for TestModuleDeleteNotification {
 pub enum TestRunKind {
   // The run profile is just to execute the tests
   Run,
-  // The tests should be run and debugged, currently not implemented
+  // The tests should be run with the inspector enabled
   Debug,
-  // The tests should be run, collecting and reporting coverage information,
-  // currently not implemented
+  // The tests should be run, collecting coverage information which is sent to
+  // the client with `deno/testCoverage` notifications
   Coverage,
 }
 
@@ -98,6 +99,10 @@ pub struct TestRunRequestParams {
   pub exclude: Vec<TestIdentifier>,
   #[serde(skip_serializing_if = "Option::is_none")]
   pub include: Option<Vec<TestIdentifier>>,
+  /// Collect coverage information during the run even when the kind of the
+  /// run isn't `coverage`, for example to debug a test with coverage.
+  #[serde(default)]
+  pub coverage: bool,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -159,6 +164,9 @@ pub enum TestRunProgressMessage {
   },
   Output {
     value: String,
+    /// The stream the test wrote the output to.
+    stream: TestStdioStream,
+    /// The test which was running when the output was written, if any.
     #[serde(skip_serializing_if = "Option::is_none")]
     test: Option<TestIdentifier>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -179,6 +187,76 @@ pub struct TestMessage {
   pub location: Option<lsp::Location>,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestDebugSessionParams {
+  /// The id of the test run which is being debugged.
+  pub id: u32,
+  /// The test which is being debugged.
+  pub test: TestIdentifier,
+  /// The host of the inspector the client should attach to.
+  pub host: String,
+  /// The port of the inspector the client should attach to.
+  pub port: u16,
+}
+
+pub enum TestDebugSessionNotification {}
+
+impl lsp::notification::Notification for TestDebugSessionNotification {
+  type Params = TestDebugSessionParams;
+
+  const METHOD: &'static str = "deno/testDebugSession";
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestRunStartParams {
+  /// The id of the test run, which is used by the following
+  /// `deno/testRunProgress` notifications.
+  pub id: u32,
+  pub kind: TestRunKind,
+  /// The tests which are enqueued by the run.
+  pub enqueued: Vec<EnqueuedTestModule>,
+}
+
+/// Sent when the server starts a test run on its own, for example when a
+/// module is saved and `deno.testing.runOnSave` is enabled.
+pub enum TestRunStartNotification {}
+
+impl lsp::notification::Notification for TestRunStartNotification {
+  type Params = TestRunStartParams;
+
+  const METHOD: &'static str = "deno/testRunStart";
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TestCoverageLine {
+  /// The zero based index of the line.
+  pub line: u32,
+  /// The number of times the line was executed.
+  pub hits: i64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestCoverageParams {
+  pub text_document: lsp::TextDocumentIdentifier,
+  /// The coverage of the lines of the document, which is empty when coverage
+  /// is hidden.
+  pub lines: Vec<TestCoverageLine>,
+}
+
+/// Sent for the open documents after a test run which collected coverage and
+/// when coverage is toggled with the `deno.coverage.show` command.
+pub enum TestCoverageNotification {}
+
+impl lsp::notification::Notification for TestCoverageNotification {
+  type Params = TestCoverageParams;
+
+  const METHOD: &'static str = "deno/testCoverage";
+}
+
 pub enum TestRunProgressNotification {}
 
 impl lsp::notification::Notification for TestRunProgressNotification {
@@ -6,6 +6,8 @@ mod execution;
 pub mod lsp_custom;
 mod server;
 
+pub use definitions::TestResultState;
+pub use definitions::TestRunResult;
 pub use lsp_custom::TEST_RUN_CANCEL_REQUEST;
 pub use lsp_custom::TEST_RUN_REQUEST;
 pub use server::TestServer;
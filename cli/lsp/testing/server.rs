@@ -1,13 +1,17 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
 use super::collectors::TestCollector;
+use super::definitions::TestCoverage;
 use super::definitions::TestModule;
+use super::definitions::TestResults;
+use super::definitions::TestRunResult;
 use super::execution::TestRun;
 use super::lsp_custom;
 
 use crate::lsp::client::Client;
 use crate::lsp::client::TestingNotification;
 use crate::lsp::config;
+use crate::lsp::documents::Documents;
 use crate::lsp::documents::DocumentsFilter;
 use crate::lsp::language_server::StateSnapshot;
 use crate::lsp::performance::Performance;
@@ -21,6 +25,10 @@ use deno_core::ModuleSpecifier;
 use deno_runtime::tokio_util::create_basic_runtime;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::thread;
 use tokio::sync::mpsc;
@@ -46,8 +54,22 @@ pub struct TestServer {
   run_channel: mpsc::UnboundedSender<u32>,
   /// A map of run ids to test runs
   runs: Arc<Mutex<HashMap<u32, TestRun>>>,
+  /// The id of the next run which is started by the server instead of the
+  /// client. These count down, to not collide with the ids of the client.
+  next_server_run_id: AtomicU32,
+  /// The id of the last run which was started by saving a module, which is
+  /// cancelled when another module is saved.
+  maybe_run_on_save_id: Mutex<Option<u32>>,
   /// Tests that are discovered from a versioned document
   tests: Arc<Mutex<HashMap<ModuleSpecifier, TestModule>>>,
+  /// The outcome of the tests the last time they were run
+  results: Arc<Mutex<TestResults>>,
+  /// The line coverage of the last test run which collected coverage
+  coverage: Arc<Mutex<TestCoverage>>,
+  /// If coverage is shown in the editor, toggled with `deno.coverage.show`
+  coverage_shown: Arc<AtomicBool>,
+  /// The documents which are open in the editor, as of the last update
+  open_documents: Arc<Mutex<HashSet<ModuleSpecifier>>>,
   /// A channel for requesting that changes to documents be statically analyzed
   /// for tests
   update_channel: mpsc::UnboundedSender<Arc<StateSnapshot>>,
@@ -71,11 +93,20 @@ impl TestServer {
       performance,
       run_channel,
       runs: Default::default(),
+      next_server_run_id: AtomicU32::new(u32::MAX),
+      maybe_run_on_save_id: Default::default(),
       tests,
+      results: Default::default(),
+      coverage: Default::default(),
+      coverage_shown: Arc::new(AtomicBool::new(true)),
+      open_documents: Default::default(),
       update_channel,
     };
 
     let tests = server.tests.clone();
+    let coverage = server.coverage.clone();
+    let coverage_shown = server.coverage_shown.clone();
+    let open_documents = server.open_documents.clone();
     let client = server.client.clone();
     let performance = server.performance.clone();
     let mru = maybe_root_uri.clone();
@@ -120,6 +151,10 @@ impl TestServer {
                       specifier.clone(),
                       script_version,
                       parsed_source.text_info().clone(),
+                      &snapshot
+                        .config
+                        .workspace_settings_for_specifier(specifier)
+                        .testing,
                     );
                     parsed_source.module().visit_with(&mut collector);
                     let test_module = collector.take();
@@ -139,6 +174,23 @@ impl TestServer {
               for key in keys {
                 client.send_test_notification(as_delete_notification(key));
               }
+              let current_open_documents = snapshot
+                .documents
+                .documents(DocumentsFilter::OpenDiagnosable)
+                .into_iter()
+                .map(|d| d.specifier().clone())
+                .collect::<HashSet<_>>();
+              let coverage = coverage.lock();
+              let mut open_documents = open_documents.lock();
+              if coverage_shown.load(Ordering::Relaxed) {
+                send_coverage_notifications(
+                  &client,
+                  &coverage,
+                  current_open_documents.difference(&open_documents),
+                  true,
+                );
+              }
+              *open_documents = current_open_documents;
               performance.measure(mark);
             }
           }
@@ -148,6 +200,9 @@ impl TestServer {
 
     let client = server.client.clone();
     let runs = server.runs.clone();
+    let coverage = server.coverage.clone();
+    let coverage_shown = server.coverage_shown.clone();
+    let open_documents = server.open_documents.clone();
     let _run_join_handle = thread::spawn(move || {
       let runtime = create_basic_runtime();
 
@@ -167,6 +222,16 @@ impl TestServer {
                     client.show_message(lsp::MessageType::ERROR, err);
                   }
                 }
+                if run.collects_coverage()
+                  && coverage_shown.load(Ordering::Relaxed)
+                {
+                  send_coverage_notifications(
+                    &client,
+                    &coverage.lock(),
+                    open_documents.lock().iter(),
+                    true,
+                  );
+                }
                 client.send_test_notification(TestingNotification::Progress(
                   lsp_custom::TestRunProgressParams {
                     id,
@@ -207,8 +272,15 @@ impl TestServer {
     params: lsp_custom::TestRunRequestParams,
     workspace_settings: config::WorkspaceSettings,
   ) -> LspResult<Option<Value>> {
-    let test_run =
-      { TestRun::new(&params, self.tests.clone(), workspace_settings) };
+    let test_run = {
+      TestRun::new(
+        &params,
+        self.tests.clone(),
+        self.results.clone(),
+        self.coverage.clone(),
+        workspace_settings,
+      )
+    };
     let enqueued = test_run.as_enqueued();
     {
       let mut runs = self.runs.lock();
@@ -221,6 +293,157 @@ impl TestServer {
     Ok(Some(json!({ "enqueued": enqueued })))
   }
 
+  /// A request from a test code lens to debug a single test. The test is
+  /// started with `--inspect-brk` and the address of the inspector is sent to
+  /// the client with a `deno/testDebugSession` notification, so it can attach.
+  pub fn debug_request(
+    &self,
+    specifier: &ModuleSpecifier,
+    name: &str,
+    workspace_settings: config::WorkspaceSettings,
+  ) -> LspResult<Option<Value>> {
+    let maybe_test_id = self.tests.lock().get(specifier).and_then(|module| {
+      module
+        .defs
+        .values()
+        .find(|d| d.parent_id.is_none() && d.name == name)
+        .map(|d| d.id.clone())
+    });
+    let Some(test_id) = maybe_test_id else {
+      return Err(LspError::invalid_params(format!(
+        "Unable to find test \"{name}\" in \"{specifier}\"."
+      )));
+    };
+    let id = self.next_server_run_id.fetch_sub(1, Ordering::Relaxed);
+    let test = lsp_custom::TestIdentifier {
+      text_document: lsp::TextDocumentIdentifier {
+        uri: specifier.clone(),
+      },
+      id: Some(test_id),
+      step_id: None,
+    };
+    let params = lsp_custom::TestRunRequestParams {
+      id,
+      kind: lsp_custom::TestRunKind::Debug,
+      exclude: Vec::new(),
+      include: Some(vec![test.clone()]),
+      coverage: false,
+    };
+    let mut test_run = TestRun::new(
+      &params,
+      self.tests.clone(),
+      self.results.clone(),
+      self.coverage.clone(),
+      workspace_settings,
+    );
+    // the inspector picks a free port itself, which is reported to the
+    // client with the debug session notification
+    test_run.set_debug_session(lsp_custom::TestDebugSessionParams {
+      id,
+      test,
+      host: "127.0.0.1".to_string(),
+      port: 0,
+    });
+    self.runs.lock().insert(id, test_run);
+    self.enqueue_run(id).map_err(|err| {
+      log::error!("cannot enqueue run: {}", err);
+      LspError::internal_error()
+    })?;
+    Ok(Some(json!({ "id": id })))
+  }
+
+  /// Run the tests of the test modules which depend on a saved module, either
+  /// directly or transitively, replacing any run which was started by a
+  /// previous save. Returns the id of the run, if any tests depend on the
+  /// module.
+  pub fn run_on_save(
+    &self,
+    specifier: &ModuleSpecifier,
+    documents: &Documents,
+    workspace_settings: config::WorkspaceSettings,
+  ) -> Result<Option<u32>, AnyError> {
+    let test_specifiers = self
+      .tests
+      .lock()
+      .iter()
+      .filter(|(_, test_module)| !test_module.is_empty())
+      .map(|(test_specifier, _)| test_specifier.clone())
+      .filter(|test_specifier| depends_on(test_specifier, specifier, documents))
+      .collect::<Vec<_>>();
+    if test_specifiers.is_empty() {
+      return Ok(None);
+    }
+    if let Some(id) = self.maybe_run_on_save_id.lock().take() {
+      if let Some(run) = self.runs.lock().get(&id) {
+        run.cancel();
+      }
+    }
+    let id = self.next_server_run_id.fetch_sub(1, Ordering::Relaxed);
+    let params = lsp_custom::TestRunRequestParams {
+      id,
+      kind: lsp_custom::TestRunKind::Run,
+      exclude: Vec::new(),
+      include: Some(
+        test_specifiers
+          .into_iter()
+          .map(|uri| lsp_custom::TestIdentifier {
+            text_document: lsp::TextDocumentIdentifier { uri },
+            id: None,
+            step_id: None,
+          })
+          .collect(),
+      ),
+      coverage: false,
+    };
+    let test_run = TestRun::new(
+      &params,
+      self.tests.clone(),
+      self.results.clone(),
+      self.coverage.clone(),
+      workspace_settings,
+    );
+    self
+      .client
+      .send_test_notification(TestingNotification::RunStart(
+        lsp_custom::TestRunStartParams {
+          id,
+          kind: params.kind.clone(),
+          enqueued: test_run.as_enqueued(),
+        },
+      ));
+    self.runs.lock().insert(id, test_run);
+    *self.maybe_run_on_save_id.lock() = Some(id);
+    self.enqueue_run(id)?;
+    Ok(Some(id))
+  }
+
+  /// Toggle if the coverage of the last test run which collected coverage is
+  /// shown, sending the coverage of the open documents to the client, or
+  /// clearing it. Returns if coverage is now shown.
+  pub fn toggle_coverage(&self) -> LspResult<Option<Value>> {
+    let shown = !self.coverage_shown.fetch_xor(true, Ordering::Relaxed);
+    send_coverage_notifications(
+      &self.client,
+      &self.coverage.lock(),
+      self.open_documents.lock().iter(),
+      shown,
+    );
+    Ok(Some(json!(shown)))
+  }
+
+  /// The outcome of the tests of a module the last time they were run.
+  pub fn results_for_specifier(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> HashMap<String, TestRunResult> {
+    self
+      .results
+      .lock()
+      .get(specifier)
+      .cloned()
+      .unwrap_or_default()
+  }
+
   pub(crate) fn update(
     &self,
     snapshot: Arc<StateSnapshot>,
@@ -228,3 +451,57 @@ impl TestServer {
     self.update_channel.send(snapshot).map_err(|err| err.into())
   }
 }
+
+/// Send the line coverage of documents to the client. When coverage isn't
+/// shown, the coverage of the documents is cleared instead.
+fn send_coverage_notifications<'a>(
+  client: &Client,
+  coverage: &TestCoverage,
+  specifiers: impl Iterator<Item = &'a ModuleSpecifier>,
+  shown: bool,
+) {
+  for specifier in specifiers {
+    let Some(lines) = coverage.get(specifier) else {
+      continue;
+    };
+    client.send_test_notification(TestingNotification::Coverage(
+      lsp_custom::TestCoverageParams {
+        text_document: lsp::TextDocumentIdentifier {
+          uri: specifier.clone(),
+        },
+        lines: if shown { lines.clone() } else { Vec::new() },
+      },
+    ));
+  }
+}
+
+/// Determine if a module depends on another module, by walking the
+/// dependencies of the documents.
+fn depends_on(
+  specifier: &ModuleSpecifier,
+  dependency: &ModuleSpecifier,
+  documents: &Documents,
+) -> bool {
+  let mut seen = HashSet::from([specifier.clone()]);
+  let mut pending = VecDeque::from([specifier.clone()]);
+  while let Some(specifier) = pending.pop_front() {
+    if specifier == *dependency {
+      return true;
+    }
+    let Some(document) = documents.get(&specifier) else {
+      continue;
+    };
+    for dep in document.dependencies().values() {
+      for dep_specifier in dep.get_code().into_iter().chain(dep.get_type()) {
+        let dep_specifier = documents
+          .get(dep_specifier)
+          .map(|d| d.specifier().clone())
+          .unwrap_or_else(|| dep_specifier.clone());
+        if seen.insert(dep_specifier.clone()) {
+          pending.push_back(dep_specifier);
+        }
+      }
+    }
+  }
+  false
+}
@@ -269,6 +269,28 @@ pub fn get_edits(a: &str, b: &str, line_index: &LineIndex) -> Vec<TextEdit> {
   text_edits
 }
 
+/// Only keep the text edits which overlap with `range`. Insertions are kept
+/// when they are positioned within or on the boundary of the range.
+pub fn filter_edits_in_range(
+  text_edits: Vec<TextEdit>,
+  range: &lsp::Range,
+) -> Vec<TextEdit> {
+  let key = |pos: &lsp::Position| (pos.line, pos.character);
+  let (start, end) = (key(&range.start), key(&range.end));
+  text_edits
+    .into_iter()
+    .filter(|edit| {
+      let (edit_start, edit_end) =
+        (key(&edit.range.start), key(&edit.range.end));
+      if edit_start == edit_end {
+        start <= edit_start && edit_start <= end
+      } else {
+        edit_start < end && edit_end > start
+      }
+    })
+    .collect()
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -502,6 +524,68 @@ const C: char = \"メ メ\";
     );
   }
 
+  #[test]
+  fn test_filter_edits_in_range() {
+    let a = "const a=1\nconst b=2\nconst c=3\n";
+    let b = "const a = 1;\nconst b = 2;\nconst c = 3;\n";
+    let edits = get_edits(a, b, &LineIndex::new(a));
+    let actual = filter_edits_in_range(
+      edits,
+      &lsp::Range {
+        start: lsp::Position::new(1, 0),
+        end: lsp::Position::new(1, 9),
+      },
+    );
+    assert!(!actual.is_empty());
+    assert!(actual
+      .iter()
+      .all(|edit| edit.range.start.line == 1 && edit.range.end.line == 1));
+  }
+
+  #[test]
+  fn test_filter_edits_in_range_boundaries() {
+    let edit = |start: (u32, u32), end: (u32, u32)| TextEdit {
+      range: lsp::Range {
+        start: lsp::Position::new(start.0, start.1),
+        end: lsp::Position::new(end.0, end.1),
+      },
+      new_text: " ".to_string(),
+    };
+    let edits = vec![
+      // before the range
+      edit((0, 0), (0, 3)),
+      // crossing the start of the range, which can't be split
+      edit((0, 5), (1, 2)),
+      // an insertion on the start of the range
+      edit((1, 0), (1, 0)),
+      // crossing the end of the range
+      edit((2, 4), (3, 1)),
+      // an insertion on the end of the range
+      edit((2, 6), (2, 6)),
+      // an edit ending on the start of the range
+      edit((0, 8), (1, 0)),
+      // after the range
+      edit((2, 7), (2, 7)),
+      edit((2, 6), (2, 9)),
+    ];
+    let actual = filter_edits_in_range(
+      edits.clone(),
+      &lsp::Range {
+        start: lsp::Position::new(1, 0),
+        end: lsp::Position::new(2, 6),
+      },
+    );
+    assert_eq!(
+      actual,
+      vec![
+        edits[1].clone(),
+        edits[2].clone(),
+        edits[3].clone(),
+        edits[4].clone(),
+      ]
+    );
+  }
+
   #[test]
   fn test_get_edits_mbc() {
     let a = "const bar = \"👍🇺🇸😃\";\nconsole.log('hello deno')\n";
@@ -4,7 +4,9 @@ use super::analysis::CodeActionData;
 use super::code_lens;
 use super::config;
 use super::documents::AssetOrDocument;
+use super::documents::Document;
 use super::documents::DocumentsFilter;
+use super::import_attributes::get_module_types;
 use super::language_server;
 use super::language_server::StateSnapshot;
 use super::performance::Performance;
@@ -22,6 +24,7 @@ use super::urls::INVALID_SPECIFIER;
 
 use crate::args::FmtOptionsConfig;
 use crate::args::TsConfig;
+use crate::args::TsPluginConfig;
 use crate::cache::HttpCache;
 use crate::lsp::cache::CacheMetadata;
 use crate::lsp::documents::Documents;
@@ -56,6 +59,7 @@ use deno_core::PollEventLoopOptions;
 use deno_core::RuntimeOptions;
 use deno_runtime::inspector_server::InspectorServer;
 use deno_runtime::tokio_util::create_basic_runtime;
+use import_map::ImportMap;
 use lazy_regex::lazy_regex;
 use log::error;
 use once_cell::sync::Lazy;
@@ -69,7 +73,10 @@ use std::collections::HashSet;
 use std::net::SocketAddr;
 use std::ops::Range;
 use std::path::Path;
+use std::path::PathBuf;
 use std::rc::Rc;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::thread;
 use text_size::TextRange;
@@ -221,6 +228,9 @@ pub struct TsServer {
   receiver: Mutex<Option<mpsc::UnboundedReceiver<Request>>>,
   specifier_map: Arc<TscSpecifierMap>,
   inspector_server: Mutex<Option<Arc<InspectorServer>>>,
+  /// The number of requests which were sent to the tsc thread and weren't
+  /// responded to or cancelled yet.
+  pending_requests: AtomicUsize,
 }
 
 impl std::fmt::Debug for TsServer {
@@ -246,10 +256,21 @@ impl TsServer {
       receiver: Mutex::new(Some(request_rx)),
       specifier_map: Arc::new(TscSpecifierMap::new()),
       inspector_server: Mutex::new(None),
+      pending_requests: Default::default(),
     }
   }
 
-  pub fn start(&self, inspector_server_addr: Option<String>) {
+  pub fn pending_requests(&self) -> usize {
+    self.pending_requests.load(Ordering::SeqCst)
+  }
+
+  /// Starts the server with the compiler pinned by the configuration file or
+  /// with the built-in one.
+  pub fn start(
+    &self,
+    inspector_server_addr: Option<String>,
+    maybe_pinned_typescript: Option<Arc<tsc::PinnedTypeScript>>,
+  ) {
     let maybe_inspector_server = inspector_server_addr.and_then(|addr| {
       let addr: SocketAddr = match addr.parse() {
         Ok(addr) => addr,
@@ -274,6 +295,7 @@ impl TsServer {
         cache.clone(),
         specifier_map.clone(),
         maybe_inspector_server,
+        maybe_pinned_typescript,
       )
     });
   }
@@ -346,10 +368,11 @@ impl TsServer {
     &self,
     snapshot: Arc<StateSnapshot>,
     tsconfig: TsConfig,
+    plugins: TsPlugins,
   ) -> Result<bool, AnyError> {
     let req = TscRequest {
       method: "$configure",
-      args: json!([tsconfig]),
+      args: json!([tsconfig, plugins]),
     };
     self.request(snapshot, req).await
   }
@@ -1008,8 +1031,16 @@ impl TsServer {
         self.0.cancel();
       }
     }
+    struct PendingRequest<'a>(&'a AtomicUsize);
+    impl Drop for PendingRequest<'_> {
+      fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+      }
+    }
     let token = token.child_token();
     let droppable_token = DroppableToken(token.clone());
+    self.pending_requests.fetch_add(1, Ordering::SeqCst);
+    let _pending_request = PendingRequest(&self.pending_requests);
     let (tx, rx) = oneshot::channel::<Result<Value, AnyError>>();
     if self.sender.send((req, snapshot, tx, token)).is_err() {
       return Err(anyhow!("failed to send request to tsc thread"));
@@ -1667,7 +1698,7 @@ impl QuickInfo {
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DocumentSpan {
-  text_span: TextSpan,
+  pub text_span: TextSpan,
   pub file_name: String,
   original_text_span: Option<TextSpan>,
   // original_file_name: Option<String>,
@@ -1783,6 +1814,14 @@ pub struct NavigateToItem {
 }
 
 impl NavigateToItem {
+  pub fn specifier(&self) -> Option<ModuleSpecifier> {
+    resolve_url(&self.file_name).ok()
+  }
+
+  pub fn is_exported(&self) -> bool {
+    parse_kind_modifier(&self.kind_modifiers).contains("export")
+  }
+
   fn normalize(
     &mut self,
     specifier_map: &TscSpecifierMap,
@@ -2388,6 +2427,7 @@ impl Classifications {
     &self,
     asset_or_doc: &AssetOrDocument,
     line_index: Arc<LineIndex>,
+    maybe_range: Option<&lsp::Range>,
   ) -> LspResult<lsp::SemanticTokens> {
     let token_count = self.spans.len() / 3;
     let mut builder = SemanticTokensBuilder::new();
@@ -2428,6 +2468,36 @@ impl Classifications {
         return Err(LspError::internal_error());
       }
     }
+    // TypeScript doesn't classify string literals, so the segments of import
+    // specifiers are tokenized separately
+    if let Some(document) = asset_or_doc.document() {
+      for (specifier, dependency) in document.dependencies() {
+        for import in &dependency.imports {
+          let start = &import.range.start;
+          let end = &import.range.end;
+          // only quoted specifiers on a single line can be tokenized
+          if start.line != end.line
+            || end.character.saturating_sub(start.character)
+              != specifier.encode_utf16().count() + 2
+          {
+            continue;
+          }
+          if let Some(range) = maybe_range {
+            if (start.line as u32) < range.start.line
+              || (start.line as u32) > range.end.line
+            {
+              continue;
+            }
+          }
+          semantic_tokens::push_import_specifier_tokens(
+            &mut builder,
+            specifier,
+            start.line as u32,
+            start.character as u32 + 1,
+          );
+        }
+      }
+    }
     Ok(builder.build(None))
   }
 
@@ -3878,6 +3948,8 @@ impl State {
       } else {
         None
       }
+    } else if get_module_types(specifier).is_some() {
+      Some("1".to_string())
     } else {
       self
         .state_snapshot
@@ -3894,6 +3966,107 @@ fn op_is_cancelled(state: &mut OpState) -> bool {
   state.token.is_cancelled()
 }
 
+/// The TypeScript language service plugins of the `"plugins"` compiler
+/// option, which wrap the language service of the server. Their names are
+/// resolved like `require()` of Node.js from the configuration file.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TsPlugins {
+  pub referrer: Option<PathBuf>,
+  pub plugins: Vec<TsPluginConfig>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PluginModule {
+  file_name: String,
+  source: String,
+}
+
+/// Loads a CommonJS module of a language service plugin, which is required
+/// by the file of `referrer`.
+#[op2]
+#[serde]
+fn op_load_plugin_module(
+  #[string] specifier: &str,
+  #[string] referrer: &str,
+) -> Result<Option<PluginModule>, AnyError> {
+  let Some(path) = resolve_plugin_module(specifier, Path::new(referrer)) else {
+    return Ok(None);
+  };
+  let source = std::fs::read_to_string(&path)?;
+  Ok(Some(PluginModule {
+    file_name: path.to_string_lossy().to_string(),
+    source,
+  }))
+}
+
+/// Resolves a module like `require()` of Node.js, where a bare specifier is
+/// looked up in the `node_modules` directories of the ancestors of the
+/// referrer. The built-in modules of Node.js aren't supported.
+fn resolve_plugin_module(specifier: &str, referrer: &Path) -> Option<PathBuf> {
+  let dir = referrer.parent()?;
+  if specifier.starts_with("./")
+    || specifier.starts_with("../")
+    || Path::new(specifier).is_absolute()
+  {
+    return resolve_plugin_path(&dir.join(specifier));
+  }
+  let name_end = specifier
+    .match_indices('/')
+    .nth(if specifier.starts_with('@') { 1 } else { 0 })
+    .map(|(index, _)| index);
+  let (name, maybe_sub_path) = match name_end {
+    Some(index) => (&specifier[..index], Some(&specifier[index + 1..])),
+    None => (specifier, None),
+  };
+  let package_dir = dir
+    .ancestors()
+    .map(|ancestor| ancestor.join("node_modules").join(name))
+    .find(|package_dir| package_dir.is_dir())?;
+  match maybe_sub_path {
+    Some(sub_path) => resolve_plugin_path(&package_dir.join(sub_path)),
+    None => resolve_plugin_path(&package_dir),
+  }
+}
+
+fn resolve_plugin_path(path: &Path) -> Option<PathBuf> {
+  if let Some(path) = resolve_plugin_file(path) {
+    return Some(path);
+  }
+  if !path.is_dir() {
+    return None;
+  }
+  let maybe_main = std::fs::read_to_string(path.join("package.json"))
+    .ok()
+    .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+    .and_then(|package_json| {
+      Some(package_json.get("main")?.as_str()?.to_string())
+    });
+  if let Some(main) = maybe_main {
+    let main = path.join(main);
+    if let Some(path) = resolve_plugin_file(&main)
+      .or_else(|| resolve_plugin_file(&main.join("index.js")))
+    {
+      return Some(path);
+    }
+  }
+  resolve_plugin_file(&path.join("index.js"))
+}
+
+fn resolve_plugin_file(path: &Path) -> Option<PathBuf> {
+  if path.is_file() {
+    return Some(path.to_path_buf());
+  }
+  ["js", "cjs", "json"].into_iter().find_map(|extension| {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".");
+    path.push(extension);
+    let path = PathBuf::from(path);
+    path.is_file().then_some(path)
+  })
+}
+
 #[op2(fast)]
 fn op_is_node_file(state: &mut OpState, #[string] path: String) -> bool {
   let state = state.borrow::<State>();
@@ -3933,6 +4106,12 @@ fn op_load<'s>(
   let maybe_load_response =
     if specifier.as_str() == "internal:///missing_dependency.d.ts" {
       None
+    } else if let Some(module_types) = get_module_types(&specifier) {
+      Some(LoadResponse {
+        data: module_types.into(),
+        script_kind: crate::tsc::as_ts_script_kind(MediaType::Dts),
+        version: state.script_version(&specifier),
+      })
     } else {
       let asset_or_document = state.get_asset_or_document(&specifier);
       asset_or_document.map(|doc| LoadResponse {
@@ -3996,6 +4175,41 @@ fn op_respond(state: &mut OpState, #[serde] args: Response) {
   state.response = Some(args);
 }
 
+/// Get the modules which are mapped by the import map and the modules of the
+/// jsr packages which are imported by the documents and already cached.
+fn auto_import_specifiers(
+  documents: &Documents,
+  all_docs: &[Document],
+  maybe_import_map: Option<&ImportMap>,
+) -> Vec<ModuleSpecifier> {
+  let mut specifiers = Vec::new();
+  if let Some(import_map) = maybe_import_map {
+    for entry in import_map.imports().entries() {
+      // entries for a directory can't be enumerated
+      if entry.raw_key.ends_with('/') {
+        continue;
+      }
+      if let Some(value) = entry.value {
+        specifiers.push(
+          documents
+            .resolve_cached_jsr_specifier(value)
+            .unwrap_or_else(|| value.clone()),
+        );
+      }
+    }
+  }
+  for doc in all_docs {
+    for dependency in doc.dependencies().values() {
+      if let Some(specifier) = dependency.get_code() {
+        if specifier.scheme() == "jsr" {
+          specifiers.extend(documents.resolve_cached_jsr_specifier(specifier));
+        }
+      }
+    }
+  }
+  specifiers
+}
+
 #[op2]
 #[serde]
 fn op_script_names(state: &mut OpState) -> Vec<String> {
@@ -4003,6 +4217,11 @@ fn op_script_names(state: &mut OpState) -> Vec<String> {
   let mark = state.performance.mark("tsc.op.op_script_names");
   let documents = &state.state_snapshot.documents;
   let all_docs = documents.documents(DocumentsFilter::AllDiagnosable);
+  let auto_import_specifiers = auto_import_specifiers(
+    documents,
+    &all_docs,
+    state.state_snapshot.maybe_import_map.as_deref(),
+  );
   let mut seen = HashSet::new();
   let mut result = Vec::new();
 
@@ -4040,6 +4259,14 @@ fn op_script_names(state: &mut OpState) -> Vec<String> {
     }
   }
 
+  // include modules which aren't imported yet, so their exports are offered
+  // as auto-imports
+  for specifier in &auto_import_specifiers {
+    if seen.insert(specifier.as_str()) && documents.exists(specifier) {
+      result.push(specifier.to_string());
+    }
+  }
+
   let r = result
     .into_iter()
     .map(|s| match ModuleSpecifier::parse(&s) {
@@ -4081,14 +4308,25 @@ fn run_tsc_thread(
   cache: Arc<dyn HttpCache>,
   specifier_map: Arc<TscSpecifierMap>,
   maybe_inspector_server: Option<Arc<InspectorServer>>,
+  maybe_pinned_typescript: Option<Arc<tsc::PinnedTypeScript>>,
 ) {
   let has_inspector_server = maybe_inspector_server.is_some();
+  let startup_snapshot = match maybe_pinned_typescript
+    .map(|pinned_typescript| pinned_typescript.load_snapshot())
+  {
+    Some(Ok(snapshot)) => snapshot,
+    Some(Err(err)) => {
+      lsp_warn!("{:#}", err);
+      tsc::compiler_snapshot()
+    }
+    None => tsc::compiler_snapshot(),
+  };
   // Create and setup a JsRuntime based on a snapshot. It is expected that the
   // supplied snapshot is an isolate that contains the TypeScript language
   // server.
   let mut tsc_runtime = JsRuntime::new(RuntimeOptions {
     extensions: vec![deno_tsc::init_ops(performance, cache, specifier_map)],
-    startup_snapshot: Some(tsc::compiler_snapshot()),
+    startup_snapshot: Some(startup_snapshot),
     inspector: maybe_inspector_server.is_some(),
     ..Default::default()
   });
@@ -4150,6 +4388,7 @@ deno_core::extension!(deno_tsc,
     op_is_cancelled,
     op_is_node_file,
     op_load,
+    op_load_plugin_module,
     op_resolve,
     op_respond,
     op_script_names,
@@ -4634,15 +4873,59 @@ mod tests {
     let snapshot = Arc::new(mock_state_snapshot(sources, &location));
     let performance = Arc::new(Performance::default());
     let ts_server = TsServer::new(performance, cache.clone());
-    ts_server.start(None);
+    ts_server.start(None, None);
     let ts_config = TsConfig::new(config);
     assert!(ts_server
-      .configure(snapshot.clone(), ts_config,)
+      .configure(snapshot.clone(), ts_config, Default::default())
       .await
       .unwrap());
     (ts_server, snapshot, cache)
   }
 
+  #[test]
+  fn test_resolve_plugin_module() {
+    let temp_dir = TempDir::new();
+    temp_dir.create_dir_all("node_modules/plugin/lib");
+    temp_dir.write(
+      "node_modules/plugin/package.json",
+      r#"{ "name": "plugin", "main": "./lib/main" }"#,
+    );
+    temp_dir.write("node_modules/plugin/lib/main.js", "");
+    temp_dir.write("node_modules/plugin/lib/util.cjs", "");
+    temp_dir.create_dir_all("node_modules/@scope/plugin");
+    temp_dir.write("node_modules/@scope/plugin/index.js", "");
+    temp_dir.create_dir_all("project");
+    temp_dir.write("project/deno.json", "{}");
+    temp_dir.write("project/local.js", "");
+    let path = |name: &str| temp_dir.path().join(name).to_path_buf();
+    let referrer = path("project/deno.json");
+    assert_eq!(
+      resolve_plugin_module("plugin", &referrer),
+      Some(path("node_modules/plugin/lib/main.js"))
+    );
+    assert_eq!(
+      resolve_plugin_module("plugin/lib/util", &referrer),
+      Some(path("node_modules/plugin/lib/util.cjs"))
+    );
+    assert_eq!(
+      resolve_plugin_module("@scope/plugin", &referrer),
+      Some(path("node_modules/@scope/plugin/index.js"))
+    );
+    assert_eq!(
+      resolve_plugin_module("./local", &referrer),
+      Some(path("project/local.js"))
+    );
+    assert_eq!(
+      resolve_plugin_module(
+        "./util.cjs",
+        &path("node_modules/plugin/lib/main.js")
+      ),
+      Some(path("node_modules/plugin/lib/util.cjs"))
+    );
+    assert_eq!(resolve_plugin_module("missing", &referrer), None);
+    assert_eq!(resolve_plugin_module("fs", &referrer), None);
+  }
+
   #[test]
   fn test_replace_links() {
     let actual = replace_links(r"test {@link http://deno.land/x/mod.ts} test");
@@ -4692,7 +4975,10 @@ mod tests {
       "noEmit": true,
       "lib": ["deno.ns", "deno.worker"]
     }));
-    assert!(ts_server.configure(snapshot, ts_config).await.unwrap());
+    assert!(ts_server
+      .configure(snapshot, ts_config, Default::default())
+      .await
+      .unwrap());
   }
 
   #[tokio::test]
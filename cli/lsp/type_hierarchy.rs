@@ -0,0 +1,187 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use super::analysis::source_range_to_lsp_range;
+
+use deno_ast::swc::ast;
+use deno_ast::swc::visit::Visit;
+use deno_ast::swc::visit::VisitWith;
+use deno_ast::ParsedSource;
+use deno_ast::SourceRange;
+use deno_ast::SourceRangedForSpanned;
+use deno_ast::SourceTextInfo;
+use deno_core::ModuleSpecifier;
+use tower_lsp::lsp_types as lsp;
+
+/// A class or interface declaration which can be part of a type hierarchy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeDeclaration {
+  pub name: String,
+  pub kind: lsp::SymbolKind,
+  pub range: lsp::Range,
+  pub selection_range: lsp::Range,
+  /// The positions of the types which are extended or implemented by the
+  /// declaration. These are meant to be resolved by TypeScript, so they point
+  /// at the last identifier of the expression (ex. `Base` in `ns.Base`).
+  pub supertype_positions: Vec<lsp::Position>,
+}
+
+impl TypeDeclaration {
+  pub fn to_type_hierarchy_item(
+    &self,
+    uri: lsp::Url,
+  ) -> lsp::TypeHierarchyItem {
+    lsp::TypeHierarchyItem {
+      name: self.name.clone(),
+      kind: self.kind,
+      tags: None,
+      detail: None,
+      uri,
+      range: self.range,
+      selection_range: self.selection_range,
+      data: None,
+    }
+  }
+}
+
+struct TypeDeclarationCollector<'a> {
+  text_info: &'a SourceTextInfo,
+  declarations: Vec<TypeDeclaration>,
+}
+
+impl TypeDeclarationCollector<'_> {
+  fn to_lsp_range(&self, range: SourceRange) -> lsp::Range {
+    source_range_to_lsp_range(&range, self.text_info)
+  }
+
+  fn heritage_position(&self, expr: &ast::Expr) -> lsp::Position {
+    let range = match expr {
+      ast::Expr::Member(member_expr) => match &member_expr.prop {
+        ast::MemberProp::Ident(ident) => ident.range(),
+        _ => member_expr.range(),
+      },
+      expr => expr.range(),
+    };
+    self.to_lsp_range(range).start
+  }
+}
+
+impl Visit for TypeDeclarationCollector<'_> {
+  fn visit_class_decl(&mut self, node: &ast::ClassDecl) {
+    let mut supertype_positions = Vec::new();
+    if let Some(super_class) = &node.class.super_class {
+      supertype_positions.push(self.heritage_position(super_class));
+    }
+    for implements in &node.class.implements {
+      supertype_positions.push(self.heritage_position(&implements.expr));
+    }
+    self.declarations.push(TypeDeclaration {
+      name: node.ident.sym.to_string(),
+      kind: lsp::SymbolKind::CLASS,
+      range: self.to_lsp_range(node.range()),
+      selection_range: self.to_lsp_range(node.ident.range()),
+      supertype_positions,
+    });
+    node.visit_children_with(self);
+  }
+
+  fn visit_ts_interface_decl(&mut self, node: &ast::TsInterfaceDecl) {
+    let supertype_positions = node
+      .extends
+      .iter()
+      .map(|extends| self.heritage_position(&extends.expr))
+      .collect();
+    self.declarations.push(TypeDeclaration {
+      name: node.id.sym.to_string(),
+      kind: lsp::SymbolKind::INTERFACE,
+      range: self.to_lsp_range(node.range()),
+      selection_range: self.to_lsp_range(node.id.range()),
+      supertype_positions,
+    });
+    node.visit_children_with(self);
+  }
+}
+
+/// Collect the class and interface declarations of a module.
+pub fn collect_type_declarations(
+  parsed_source: &ParsedSource,
+) -> Vec<TypeDeclaration> {
+  let mut collector = TypeDeclarationCollector {
+    text_info: parsed_source.text_info(),
+    declarations: Vec::new(),
+  };
+  parsed_source.module().visit_with(&mut collector);
+  collector.declarations
+}
+
+/// Find the innermost declaration whose range contains the position.
+pub fn find_type_declaration(
+  declarations: Vec<TypeDeclaration>,
+  position: lsp::Position,
+) -> Option<TypeDeclaration> {
+  let contains = |range: &lsp::Range| {
+    (range.start.line, range.start.character)
+      <= (position.line, position.character)
+      && (position.line, position.character)
+        <= (range.end.line, range.end.character)
+  };
+  declarations
+    .into_iter()
+    .filter(|d| contains(&d.range))
+    .last()
+}
+
+/// Type hierarchy items which are in remote modules or npm packages are shown
+/// with the specifier as the detail, so it's clear where the type came from.
+pub fn detail_for_specifier(specifier: &ModuleSpecifier) -> Option<String> {
+  if specifier.scheme() == "file" {
+    None
+  } else {
+    Some(specifier.to_string())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use deno_ast::MediaType;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_collect_type_declarations() {
+    let parsed_source = deno_ast::parse_module(deno_ast::ParseParams {
+      specifier: ModuleSpecifier::parse("file:///a/mod.ts").unwrap(),
+      text_info: SourceTextInfo::from_string(
+        r#"import * as events from "node:events";
+interface A {}
+interface B extends A {}
+export class C extends events.EventEmitter implements B {}
+"#
+        .to_string(),
+      ),
+      media_type: MediaType::TypeScript,
+      capture_tokens: false,
+      scope_analysis: false,
+      maybe_syntax: None,
+    })
+    .unwrap();
+    let declarations = collect_type_declarations(&parsed_source);
+    assert_eq!(
+      declarations
+        .iter()
+        .map(|d| (d.name.as_str(), d.supertype_positions.clone()))
+        .collect::<Vec<_>>(),
+      vec![
+        ("A", vec![]),
+        ("B", vec![lsp::Position::new(2, 20)]),
+        (
+          "C",
+          vec![lsp::Position::new(3, 30), lsp::Position::new(3, 54)]
+        ),
+      ]
+    );
+    let declaration =
+      find_type_declaration(declarations, lsp::Position::new(3, 14)).unwrap();
+    assert_eq!(declaration.name, "C");
+    assert_eq!(declaration.kind, lsp::SymbolKind::CLASS);
+  }
+}
@@ -10,6 +10,7 @@ use deno_core::url::Url;
 use deno_core::ModuleSpecifier;
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
 
 /// Used in situations where a default URL needs to be used where otherwise a
@@ -151,12 +152,22 @@ pub enum LspUrlKind {
   Folder,
 }
 
+/// The path prefix of `deno:` URLs for files in the global npm cache.
+const NPM_CACHE_PATH_PREFIX: &str = "/npm/";
+
 /// A bi-directional map of URLs sent to the LSP client and internal module
 /// specifiers. We need to map internal specifiers into `deno:` schema URLs
 /// to allow the Deno language server to manage these as virtual documents.
 #[derive(Debug, Default, Clone)]
 pub struct LspUrlMap {
   local_http_cache: Option<Arc<LocalLspHttpCache>>,
+  /// The directory URL of the global npm cache. Files of npm packages in it
+  /// are exposed as read-only `deno:/npm/` virtual documents, because they
+  /// aren't part of the workspace when there is no local `node_modules`.
+  npm_cache_url: Option<Url>,
+  /// The directory URL which virtual documents of the client, like unsaved
+  /// `untitled:` buffers, are assumed to be in.
+  untitled_root: Option<Url>,
   inner: Arc<Mutex<LspUrlMapInner>>,
 }
 
@@ -165,6 +176,38 @@ impl LspUrlMap {
     self.local_http_cache = http_cache;
   }
 
+  pub fn set_npm_cache_dir(&mut self, npm_cache_dir: &Path) {
+    self.npm_cache_url = Url::from_directory_path(npm_cache_dir).ok();
+  }
+
+  /// Sets the directory which virtual documents are assumed to be in, so
+  /// their relative imports are resolved against it. Documents which were
+  /// already mapped keep their specifier.
+  pub fn set_untitled_root(&mut self, untitled_root: Option<Url>) {
+    self.untitled_root = untitled_root;
+  }
+
+  fn npm_cache_file_to_deno_url(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> Option<Url> {
+    if specifier.scheme() != "file" {
+      return None;
+    }
+    let relative_path = specifier
+      .as_str()
+      .strip_prefix(self.npm_cache_url.as_ref()?.as_str())?;
+    Url::parse(&format!("deno:{}{}", NPM_CACHE_PATH_PREFIX, relative_path)).ok()
+  }
+
+  fn deno_url_to_npm_cache_file(&self, url: &Url) -> Option<ModuleSpecifier> {
+    if url.scheme() != "deno" {
+      return None;
+    }
+    let relative_path = url.path().strip_prefix(NPM_CACHE_PATH_PREFIX)?;
+    self.npm_cache_url.as_ref()?.join(relative_path).ok()
+  }
+
   /// Normalize a specifier that is used internally within Deno (or tsc) to a
   /// URL that can be handled as a "virtual" document by an LSP client.
   pub fn normalize_specifier(
@@ -178,6 +221,9 @@ impl LspUrlMap {
         }
       }
     }
+    if let Some(url) = self.npm_cache_file_to_deno_url(specifier) {
+      return Ok(LspClientUrl(url));
+    }
     let mut inner = self.inner.lock();
     if let Some(url) = inner.get_url(specifier).cloned() {
       Ok(url)
@@ -229,6 +275,9 @@ impl LspUrlMap {
         }
       }
     }
+    if let Some(specifier) = self.deno_url_to_npm_cache_file(url) {
+      return specifier;
+    }
     let mut inner = self.inner.lock();
     if let Some(specifier) = inner.get_specifier(url).cloned() {
       return specifier;
@@ -245,6 +294,8 @@ impl LspUrlMap {
       specifier = Some(s);
     } else if let Some(s) = from_deno_url(url) {
       specifier = Some(s);
+    } else if let Some(root) = &self.untitled_root {
+      specifier = virtual_to_file_specifier(url, root);
     }
     let specifier = specifier.unwrap_or_else(|| url.clone());
     inner.put(specifier.clone(), LspClientUrl(url.clone()));
@@ -261,7 +312,9 @@ impl LspUrlMap {
 ///   Some(Url::parse("file:///path/to/file.ipynb.ts?scheme=deno-notebook-cell#abc").unwrap()),
 /// );
 fn file_like_to_file_specifier(specifier: &Url) -> Option<Url> {
-  if matches!(specifier.scheme(), "untitled" | "deno-notebook-cell") {
+  if matches!(specifier.scheme(), "untitled" | "deno-notebook-cell")
+    && !specifier.cannot_be_a_base()
+  {
     if let Ok(mut s) = ModuleSpecifier::parse(&format!(
       "file://{}",
       &specifier.as_str()[deno_core::url::quirks::internal_components(specifier)
@@ -276,6 +329,54 @@ fn file_like_to_file_specifier(specifier: &Url) -> Option<Url> {
   None
 }
 
+/// The schemes which are understood by Deno itself. Documents of any other
+/// scheme come from a virtual file system of the client.
+const DENO_SCHEMES: &[&str] = &[
+  "asset", "blob", "data", "deno", "file", "http", "https", "jsr", "node",
+  "npm",
+];
+
+/// Convert a document of a virtual file system of the client, like an unsaved
+/// `untitled:Untitled-1` buffer or a `vscode-vfs:` file, to a `file:`
+/// specifier in the root, so the relative imports of the document are
+/// resolved against the root.
+/// ```rust
+/// assert_eq!(
+///   virtual_to_file_specifier(
+///     &Url::parse("untitled:Untitled-1").unwrap(),
+///     &Url::parse("file:///project/").unwrap(),
+///   ),
+///   Some(Url::parse("file:///project/Untitled-1.ts?scheme=untitled").unwrap()),
+/// );
+/// ```
+fn virtual_to_file_specifier(url: &Url, root: &Url) -> Option<Url> {
+  if DENO_SCHEMES.contains(&url.scheme()) || root.scheme() != "file" {
+    return None;
+  }
+  let name = if url.cannot_be_a_base() {
+    url.path()
+  } else {
+    url.path_segments()?.filter(|s| !s.is_empty()).last()?
+  };
+  let mut specifier = root
+    .join(&format!("./{}", name.replace(['/', '\\'], "_")))
+    .ok()?;
+  if MediaType::from_specifier(&specifier) == MediaType::Unknown {
+    specifier.set_path(&format!("{}.ts", specifier.path()));
+  }
+  {
+    let mut query_pairs = specifier.query_pairs_mut();
+    query_pairs.append_pair("scheme", url.scheme());
+    // documents in directories of a virtual file system can have the same
+    // name, so the original URL is kept to tell them apart
+    if !url.cannot_be_a_base() {
+      query_pairs.append_pair("uri", url.as_str());
+    }
+  }
+  specifier.set_fragment(url.fragment());
+  Some(specifier)
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -341,6 +442,35 @@ mod tests {
     assert_eq!(actual_specifier, fixture);
   }
 
+  #[cfg(not(windows))]
+  #[test]
+  fn test_lsp_url_map_npm_cache() {
+    let mut map = LspUrlMap::default();
+    map.set_npm_cache_dir(Path::new("/deno_dir/npm"));
+    let fixture = resolve_url(
+      "file:///deno_dir/npm/registry.npmjs.org/@types/node/18.0.0/fs.d.ts",
+    )
+    .unwrap();
+    let actual_url = map
+      .normalize_specifier(&fixture)
+      .expect("could not handle specifier");
+    let expected_url =
+      Url::parse("deno:/npm/registry.npmjs.org/@types/node/18.0.0/fs.d.ts")
+        .unwrap();
+    assert_eq!(actual_url.as_url(), &expected_url);
+
+    let actual_specifier =
+      map.normalize_url(actual_url.as_url(), LspUrlKind::File);
+    assert_eq!(actual_specifier, fixture);
+
+    // files outside of the npm cache, like a local `node_modules`, are real
+    // files for the client
+    let fixture =
+      resolve_url("file:///project/node_modules/chalk/index.d.ts").unwrap();
+    let actual_url = map.normalize_specifier(&fixture).unwrap();
+    assert_eq!(actual_url.as_url(), &fixture);
+  }
+
   #[test]
   fn test_lsp_url_map_data() {
     let map = LspUrlMap::default();
@@ -433,4 +563,53 @@ mod tests {
       ),
     );
   }
+
+  #[test]
+  fn test_virtual_to_file_specifier() {
+    let root = Url::parse("file:///project/").unwrap();
+    let cases = [
+      (
+        "untitled:Untitled-1",
+        Some("file:///project/Untitled-1.ts?scheme=untitled"),
+      ),
+      (
+        "vscode-vfs://github/owner/repo/src/mod.js",
+        Some("file:///project/mod.js?scheme=vscode-vfs&uri=vscode-vfs%3A%2F%2Fgithub%2Fowner%2Frepo%2Fsrc%2Fmod.js"),
+      ),
+      ("https://deno.land/x/mod.ts", None),
+      ("file:///project/mod.ts", None),
+      ("vscode-vfs://github/", None),
+    ];
+    for (url, expected) in cases {
+      assert_eq!(
+        virtual_to_file_specifier(&Url::parse(url).unwrap(), &root)
+          .as_ref()
+          .map(|s| s.as_str()),
+        expected,
+        "{}",
+        url
+      );
+    }
+  }
+
+  #[test]
+  fn test_lsp_url_map_untitled_root() {
+    let fixture = Url::parse("untitled:Untitled-1").unwrap();
+    // without a root the URL is kept as is
+    let map = LspUrlMap::default();
+    assert_eq!(map.normalize_url(&fixture, LspUrlKind::File), fixture);
+
+    let mut map = LspUrlMap::default();
+    map.set_untitled_root(Some(Url::parse("file:///project/").unwrap()));
+    let actual_specifier = map.normalize_url(&fixture, LspUrlKind::File);
+    assert_eq!(
+      actual_specifier,
+      Url::parse("file:///project/Untitled-1.ts?scheme=untitled").unwrap()
+    );
+    let relative = actual_specifier.join("./mod.ts").unwrap();
+    assert_eq!(relative.as_str(), "file:///project/mod.ts");
+
+    let actual_url = map.normalize_specifier(&actual_specifier).unwrap();
+    assert_eq!(actual_url.as_url(), &fixture);
+  }
 }
@@ -0,0 +1,441 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::anyhow::anyhow;
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+
+const SECTION_TYPE: u8 = 1;
+const SECTION_IMPORT: u8 = 2;
+const SECTION_FUNCTION: u8 = 3;
+const SECTION_EXPORT: u8 = 7;
+
+const RESERVED_WORDS: &[&str] = &[
+  "break",
+  "case",
+  "catch",
+  "class",
+  "const",
+  "continue",
+  "debugger",
+  "default",
+  "delete",
+  "do",
+  "else",
+  "enum",
+  "export",
+  "extends",
+  "false",
+  "finally",
+  "for",
+  "function",
+  "if",
+  "import",
+  "in",
+  "instanceof",
+  "new",
+  "null",
+  "return",
+  "super",
+  "switch",
+  "this",
+  "throw",
+  "true",
+  "try",
+  "typeof",
+  "var",
+  "void",
+  "while",
+  "with",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ValueType {
+  I32,
+  I64,
+  F32,
+  F64,
+  V128,
+  FuncRef,
+  ExternRef,
+}
+
+impl ValueType {
+  fn as_ts_type(&self) -> &'static str {
+    match self {
+      Self::I32 | Self::F32 | Self::F64 => "number",
+      Self::I64 => "bigint",
+      // these can't be passed between JavaScript and Wasm
+      Self::V128 => "never",
+      Self::FuncRef => "((...args: unknown[]) => unknown) | null",
+      Self::ExternRef => "unknown",
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FunctionType {
+  params: Vec<ValueType>,
+  results: Vec<ValueType>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum WasmExportKind {
+  Function(FunctionType),
+  Table,
+  Memory,
+  Global,
+  Tag,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct WasmExport {
+  name: String,
+  kind: WasmExportKind,
+}
+
+struct Reader<'a> {
+  bytes: &'a [u8],
+  pos: usize,
+}
+
+impl<'a> Reader<'a> {
+  fn new(bytes: &'a [u8]) -> Self {
+    Self { bytes, pos: 0 }
+  }
+
+  fn is_empty(&self) -> bool {
+    self.pos >= self.bytes.len()
+  }
+
+  fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], AnyError> {
+    let end = self
+      .pos
+      .checked_add(len)
+      .filter(|end| *end <= self.bytes.len())
+      .ok_or_else(|| anyhow!("Unexpected end of the binary."))?;
+    let bytes = &self.bytes[self.pos..end];
+    self.pos = end;
+    Ok(bytes)
+  }
+
+  fn read_u8(&mut self) -> Result<u8, AnyError> {
+    Ok(self.read_bytes(1)?[0])
+  }
+
+  /// Reads an unsigned LEB128 integer.
+  fn read_u64(&mut self) -> Result<u64, AnyError> {
+    let mut value = 0u64;
+    let mut shift = 0;
+    loop {
+      let byte = self.read_u8()?;
+      if shift >= 64 {
+        bail!("Invalid integer in the binary.");
+      }
+      value |= ((byte & 0x7f) as u64) << shift;
+      if byte & 0x80 == 0 {
+        return Ok(value);
+      }
+      shift += 7;
+    }
+  }
+
+  fn read_u32(&mut self) -> Result<u32, AnyError> {
+    u32::try_from(self.read_u64()?)
+      .map_err(|_| anyhow!("Invalid integer in the binary."))
+  }
+
+  fn read_name(&mut self) -> Result<String, AnyError> {
+    let len = self.read_u32()? as usize;
+    let bytes = self.read_bytes(len)?;
+    String::from_utf8(bytes.to_vec())
+      .map_err(|_| anyhow!("Invalid name in the binary."))
+  }
+
+  fn read_value_type(&mut self) -> Result<ValueType, AnyError> {
+    match self.read_u8()? {
+      0x7f => Ok(ValueType::I32),
+      0x7e => Ok(ValueType::I64),
+      0x7d => Ok(ValueType::F32),
+      0x7c => Ok(ValueType::F64),
+      0x7b => Ok(ValueType::V128),
+      0x70 => Ok(ValueType::FuncRef),
+      0x6f => Ok(ValueType::ExternRef),
+      byte => bail!("Unsupported value type 0x{byte:02x} in the binary."),
+    }
+  }
+
+  fn read_value_types(&mut self) -> Result<Vec<ValueType>, AnyError> {
+    let count = self.read_u32()?;
+    (0..count).map(|_| self.read_value_type()).collect()
+  }
+
+  fn skip_limits(&mut self) -> Result<(), AnyError> {
+    let flags = self.read_u8()?;
+    self.read_u64()?;
+    if flags & 0x01 != 0 {
+      self.read_u64()?;
+    }
+    Ok(())
+  }
+}
+
+/// Reads the exports of a Wasm module with the types of its functions out of
+/// the type, import, function and export sections of the binary.
+fn parse_exports(bytes: &[u8]) -> Result<Vec<WasmExport>, AnyError> {
+  let mut reader = Reader::new(bytes);
+  if reader.read_bytes(4).ok() != Some(b"\0asm".as_slice()) {
+    bail!("The file is not a Wasm module.");
+  }
+  let version = reader.read_bytes(4)?;
+  if version != [1, 0, 0, 0] {
+    bail!("Unsupported version of the Wasm binary format.");
+  }
+  let mut types = Vec::new();
+  // the type indexes of the imported and then the defined functions
+  let mut function_types = Vec::new();
+  let mut exports = Vec::new();
+  while !reader.is_empty() {
+    let id = reader.read_u8()?;
+    let size = reader.read_u32()? as usize;
+    let mut section = Reader::new(reader.read_bytes(size)?);
+    match id {
+      SECTION_TYPE => {
+        for _ in 0..section.read_u32()? {
+          if section.read_u8()? != 0x60 {
+            bail!("Invalid function type in the binary.");
+          }
+          types.push(FunctionType {
+            params: section.read_value_types()?,
+            results: section.read_value_types()?,
+          });
+        }
+      }
+      SECTION_IMPORT => {
+        for _ in 0..section.read_u32()? {
+          section.read_name()?;
+          section.read_name()?;
+          match section.read_u8()? {
+            0x00 => function_types.push(section.read_u32()?),
+            0x01 => {
+              section.read_value_type()?;
+              section.skip_limits()?;
+            }
+            0x02 => section.skip_limits()?,
+            0x03 => {
+              section.read_value_type()?;
+              section.read_u8()?;
+            }
+            0x04 => {
+              section.read_u8()?;
+              section.read_u32()?;
+            }
+            kind => {
+              bail!("Unsupported import kind 0x{kind:02x} in the binary.")
+            }
+          }
+        }
+      }
+      SECTION_FUNCTION => {
+        for _ in 0..section.read_u32()? {
+          function_types.push(section.read_u32()?);
+        }
+      }
+      SECTION_EXPORT => {
+        for _ in 0..section.read_u32()? {
+          let name = section.read_name()?;
+          let kind = section.read_u8()?;
+          let index = section.read_u32()?;
+          let kind = match kind {
+            0x00 => {
+              let function_type = function_types
+                .get(index as usize)
+                .and_then(|type_index| types.get(*type_index as usize))
+                .ok_or_else(|| {
+                  anyhow!("Invalid function of the export \"{name}\".")
+                })?;
+              WasmExportKind::Function(function_type.clone())
+            }
+            0x01 => WasmExportKind::Table,
+            0x02 => WasmExportKind::Memory,
+            0x03 => WasmExportKind::Global,
+            0x04 => WasmExportKind::Tag,
+            kind => {
+              bail!("Unsupported export kind 0x{kind:02x} in the binary.")
+            }
+          };
+          exports.push(WasmExport { name, kind });
+        }
+      }
+      _ => {}
+    }
+  }
+  Ok(exports)
+}
+
+fn is_identifier(name: &str) -> bool {
+  let mut chars = name.chars();
+  chars
+    .next()
+    .map(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+    .unwrap_or(false)
+    && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+    && !RESERVED_WORDS.contains(&name)
+}
+
+/// Generates the TypeScript declarations of the exports of a Wasm module,
+/// which are what the module is type checked as when it's imported.
+pub fn get_wasm_declarations(bytes: &[u8]) -> Result<String, AnyError> {
+  let mut declarations =
+    "// The declarations of the exports of the Wasm module.\n".to_string();
+  for export in parse_exports(bytes)? {
+    let is_default = export.name == "default";
+    let local_name = if is_default {
+      "_default"
+    } else if is_identifier(&export.name) {
+      export.name.as_str()
+    } else {
+      // these can only be accessed by a namespace import
+      declarations.push_str(&format!(
+        "// The export \"{}\" is not an identifier.\n",
+        export.name.escape_default()
+      ));
+      continue;
+    };
+    let export_keyword = if is_default { "" } else { "export " };
+    let declaration = match &export.kind {
+      WasmExportKind::Function(function_type) => {
+        let params = function_type
+          .params
+          .iter()
+          .enumerate()
+          .map(|(index, param)| format!("p{index}: {}", param.as_ts_type()))
+          .collect::<Vec<_>>()
+          .join(", ");
+        let result = match function_type.results.as_slice() {
+          [] => "void".to_string(),
+          [result] => result.as_ts_type().to_string(),
+          results => format!(
+            "[{}]",
+            results
+              .iter()
+              .map(|result| result.as_ts_type())
+              .collect::<Vec<_>>()
+              .join(", ")
+          ),
+        };
+        format!("declare function {local_name}({params}): {result};")
+      }
+      WasmExportKind::Table => {
+        format!("declare const {local_name}: WebAssembly.Table;")
+      }
+      WasmExportKind::Memory => {
+        format!("declare const {local_name}: WebAssembly.Memory;")
+      }
+      WasmExportKind::Global => {
+        format!("declare const {local_name}: WebAssembly.Global;")
+      }
+      WasmExportKind::Tag => format!("declare const {local_name}: unknown;"),
+    };
+    declarations.push_str(export_keyword);
+    declarations.push_str(&declaration);
+    declarations.push('\n');
+    if is_default {
+      declarations.push_str("export default _default;\n");
+    }
+  }
+  Ok(declarations)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  fn section(id: u8, payload: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![id, payload.len() as u8];
+    bytes.extend_from_slice(payload);
+    bytes
+  }
+
+  fn name(name: &str) -> Vec<u8> {
+    let mut bytes = vec![name.len() as u8];
+    bytes.extend_from_slice(name.as_bytes());
+    bytes
+  }
+
+  fn test_module() -> Vec<u8> {
+    let mut bytes = b"\0asm\x01\0\0\0".to_vec();
+    // (i32, i32) -> i32 and (i64) -> (f64, f32)
+    bytes.extend(section(
+      SECTION_TYPE,
+      &[
+        2, 0x60, 2, 0x7f, 0x7f, 1, 0x7f, 0x60, 1, 0x7e, 2, 0x7c, 0x7d,
+      ],
+    ));
+    // an imported function shifts the indexes of the defined ones
+    let mut imports = vec![1];
+    imports.extend(name("env"));
+    imports.extend(name("log"));
+    imports.extend([0x00, 1]);
+    bytes.extend(section(SECTION_IMPORT, &imports));
+    bytes.extend(section(SECTION_FUNCTION, &[2, 0, 1]));
+    let mut exports = vec![5];
+    for (export_name, kind, index) in [
+      ("add", 0x00, 1),
+      ("split", 0x00, 2),
+      ("memory", 0x02, 0),
+      ("default", 0x00, 0),
+      ("to-string", 0x00, 1),
+    ] {
+      exports.extend(name(export_name));
+      exports.extend([kind, index]);
+    }
+    bytes.extend(section(SECTION_EXPORT, &exports));
+    bytes
+  }
+
+  #[test]
+  fn test_parse_exports() {
+    let exports = parse_exports(&test_module()).unwrap();
+    assert_eq!(
+      exports[0],
+      WasmExport {
+        name: "add".to_string(),
+        kind: WasmExportKind::Function(FunctionType {
+          params: vec![ValueType::I32, ValueType::I32],
+          results: vec![ValueType::I32],
+        }),
+      }
+    );
+    assert_eq!(exports[2].kind, WasmExportKind::Memory);
+    assert_eq!(exports.len(), 5);
+
+    assert!(parse_exports(b"export {}").is_err());
+    assert!(parse_exports(&test_module()[..20]).is_err());
+  }
+
+  #[test]
+  fn test_get_wasm_declarations() {
+    assert_eq!(
+      get_wasm_declarations(&test_module()).unwrap(),
+      r#"// The declarations of the exports of the Wasm module.
+export declare function add(p0: number, p1: number): number;
+export declare function split(p0: bigint): [number, number];
+export declare const memory: WebAssembly.Memory;
+declare function _default(p0: bigint): [number, number];
+export default _default;
+// The export "to-string" is not an identifier.
+"#
+    );
+  }
+
+  #[test]
+  fn test_is_identifier() {
+    assert!(is_identifier("add"));
+    assert!(is_identifier("$_add2"));
+    assert!(!is_identifier("2add"));
+    assert!(!is_identifier("to-string"));
+    assert!(!is_identifier("delete"));
+    assert!(!is_identifier(""));
+  }
+}
@@ -18,6 +18,7 @@ mod napi;
 mod node;
 mod npm;
 mod ops;
+mod proxy;
 mod resolver;
 mod standalone;
 mod tools;
@@ -88,6 +89,12 @@ fn spawn_subcommand<F: Future<Output = T> + 'static, T: SubcommandOutput>(
 
 async fn run_subcommand(flags: Flags) -> Result<i32, AnyError> {
   let handle = match flags.subcommand.clone() {
+    DenoSubcommand::Add(add_flags) => spawn_subcommand(async {
+      tools::pm::add(flags, add_flags).await
+    }),
+    DenoSubcommand::Audit(audit_flags) => spawn_subcommand(async {
+      tools::audit::audit(flags, audit_flags).await
+    }),
     DenoSubcommand::Bench(bench_flags) => spawn_subcommand(async {
       if bench_flags.watch.is_some() {
         tools::bench::run_benchmarks_with_watch(flags, bench_flags).await
@@ -105,6 +112,9 @@ async fn run_subcommand(flags: Flags) -> Result<i32, AnyError> {
       tools::run::eval_command(flags, eval_flags).await
     }),
     DenoSubcommand::Cache(cache_flags) => spawn_subcommand(async move {
+      if let Some(prune_flags) = cache_flags.prune {
+        return tools::cache::prune(flags, prune_flags).await;
+      }
       let factory = CliFactory::from_flags(flags).await?;
       let module_load_preparer = factory.module_load_preparer().await?;
       let emitter = factory.emitter()?;
@@ -116,6 +126,9 @@ async fn run_subcommand(flags: Flags) -> Result<i32, AnyError> {
     }),
     DenoSubcommand::Check(check_flags) => spawn_subcommand(async move {
       let factory = CliFactory::from_flags(flags).await?;
+      if check_flags.workspace {
+        return tools::check::check_workspace(&factory).await;
+      }
       let module_load_preparer = factory.module_load_preparer().await?;
       module_load_preparer
         .load_and_type_check_files(&check_flags.files)
@@ -133,7 +146,9 @@ async fn run_subcommand(flags: Flags) -> Result<i32, AnyError> {
       )
     }
     DenoSubcommand::Init(init_flags) => {
-      spawn_subcommand(async { tools::init::init_project(init_flags).await })
+      spawn_subcommand(async {
+        tools::init::init_project(flags, init_flags).await
+      })
     }
     DenoSubcommand::Info(info_flags) => {
       spawn_subcommand(async { tools::info::info(flags, info_flags).await })
@@ -147,7 +162,9 @@ async fn run_subcommand(flags: Flags) -> Result<i32, AnyError> {
     DenoSubcommand::Uninstall(uninstall_flags) => spawn_subcommand(async {
       tools::installer::uninstall(uninstall_flags.name, uninstall_flags.root)
     }),
-    DenoSubcommand::Lsp => spawn_subcommand(async { lsp::start().await }),
+    DenoSubcommand::Lsp(lsp_flags) => spawn_subcommand(async move {
+      lsp::start(flags.offline, lsp_flags).await
+    }),
     DenoSubcommand::Lint(lint_flags) => spawn_subcommand(async {
       if lint_flags.rules {
         tools::lint::print_rules_list(
@@ -159,6 +176,15 @@ async fn run_subcommand(flags: Flags) -> Result<i32, AnyError> {
         tools::lint::lint(flags, lint_flags).await
       }
     }),
+    DenoSubcommand::Lock(lock_flags) => spawn_subcommand(async {
+      tools::lock::verify(flags, lock_flags).await
+    }),
+    DenoSubcommand::Outdated(outdated_flags) => spawn_subcommand(async {
+      tools::outdated::outdated(flags, outdated_flags).await
+    }),
+    DenoSubcommand::Remove(remove_flags) => spawn_subcommand(async {
+      tools::pm::remove(flags, remove_flags).await
+    }),
     DenoSubcommand::Repl(repl_flags) => {
       spawn_subcommand(async move { tools::repl::run(flags, repl_flags).await })
     }
@@ -169,6 +195,9 @@ async fn run_subcommand(flags: Flags) -> Result<i32, AnyError> {
         tools::run::run_script(flags, run_flags).await
       }
     }),
+    DenoSubcommand::Serve(serve_flags) => spawn_subcommand(async move {
+      tools::run::serve(flags, serve_flags).await
+    }),
     DenoSubcommand::Task(task_flags) => spawn_subcommand(async {
       tools::task::execute_script(flags, task_flags).await
     }),
@@ -197,9 +226,13 @@ async fn run_subcommand(flags: Flags) -> Result<i32, AnyError> {
         display::write_to_stdout_ignore_sigpipe(&completions_flags.buf)
       })
     }
-    DenoSubcommand::Types => spawn_subcommand(async move {
+    DenoSubcommand::Types(types_flags) => spawn_subcommand(async move {
+      if types_flags.ffi.is_some() {
+        return tools::ffi_types::generate(types_flags);
+      }
       let types = tsc::get_types_declaration_file_text();
-      display::write_to_stdout_ignore_sigpipe(types.as_bytes())
+      display::write_to_stdout_ignore_sigpipe(types.as_bytes())?;
+      Ok(())
     }),
     #[cfg(feature = "upgrade")]
     DenoSubcommand::Upgrade(upgrade_flags) => spawn_subcommand(async {
@@ -365,7 +398,7 @@ pub fn main() {
     let default_v8_flags = match flags.subcommand {
       // Using same default as VSCode:
       // https://github.com/microsoft/vscode/blob/48d4ba271686e8072fc6674137415bc80d936bc7/extensions/typescript-language-features/src/configuration/configuration.ts#L213-L214
-      DenoSubcommand::Lsp => vec!["--max-old-space-size=3072".to_string()],
+      DenoSubcommand::Lsp(_) => vec!["--max-old-space-size=3072".to_string()],
       _ => {
         if flags.unstable_config.legacy_flag_enabled
           || flags
@@ -2,10 +2,12 @@
 
 use crate::args::CliOptions;
 use crate::args::DenoSubcommand;
+use crate::args::GraphBudget;
 use crate::args::TsTypeLib;
 use crate::cache::ModuleInfoCache;
 use crate::cache::ParsedSourceCache;
 use crate::emit::Emitter;
+use crate::graph_util::check_graph_budget;
 use crate::graph_util::graph_lock_or_exit;
 use crate::graph_util::graph_valid_with_cli_options;
 use crate::graph_util::workspace_config_to_workspace_members;
@@ -14,6 +16,7 @@ use crate::graph_util::FileWatcherReporter;
 use crate::graph_util::ModuleGraphBuilder;
 use crate::graph_util::ModuleGraphContainer;
 use crate::node;
+use crate::npm::CliNpmResolver;
 use crate::resolver::CliGraphResolver;
 use crate::resolver::CliNodeResolver;
 use crate::resolver::ModuleCodeStringSource;
@@ -71,6 +74,7 @@ pub struct ModuleLoadPreparer {
   maybe_file_watcher_reporter: Option<FileWatcherReporter>,
   module_graph_builder: Arc<ModuleGraphBuilder>,
   module_info_cache: Arc<ModuleInfoCache>,
+  npm_resolver: Arc<dyn CliNpmResolver>,
   parsed_source_cache: Arc<ParsedSourceCache>,
   progress_bar: ProgressBar,
   resolver: Arc<CliGraphResolver>,
@@ -87,6 +91,7 @@ impl ModuleLoadPreparer {
     maybe_file_watcher_reporter: Option<FileWatcherReporter>,
     module_graph_builder: Arc<ModuleGraphBuilder>,
     module_info_cache: Arc<ModuleInfoCache>,
+    npm_resolver: Arc<dyn CliNpmResolver>,
     parsed_source_cache: Arc<ParsedSourceCache>,
     progress_bar: ProgressBar,
     resolver: Arc<CliGraphResolver>,
@@ -100,6 +105,7 @@ impl ModuleLoadPreparer {
       maybe_file_watcher_reporter,
       module_graph_builder,
       module_info_cache,
+      npm_resolver,
       parsed_source_cache,
       progress_bar,
       resolver,
@@ -118,6 +124,27 @@ impl ModuleLoadPreparer {
     is_dynamic: bool,
     lib: TsTypeLib,
     permissions: PermissionsContainer,
+  ) -> Result<(), AnyError> {
+    self
+      .prepare_module_load_with_budget(
+        roots,
+        is_dynamic,
+        lib,
+        permissions,
+        None,
+      )
+      .await
+  }
+
+  /// Same as `prepare_module_load`, but also checks the module graph against
+  /// the graph budget of the config file before type checking it.
+  async fn prepare_module_load_with_budget(
+    &self,
+    roots: Vec<ModuleSpecifier>,
+    is_dynamic: bool,
+    lib: TsTypeLib,
+    permissions: PermissionsContainer,
+    maybe_budget: Option<&GraphBudget>,
   ) -> Result<(), AnyError> {
     log::debug!("Preparing module load.");
     let _pb_clear_guard = self.progress_bar.clear_guard();
@@ -192,6 +219,10 @@ impl ModuleLoadPreparer {
 
     drop(_pb_clear_guard);
 
+    if let Some(budget) = maybe_budget {
+      check_graph_budget(&graph, budget, self.npm_resolver.as_ref())?;
+    }
+
     // type check if necessary
     if self.options.type_check_mode().is_true()
       && !self.graph_container.is_type_checked(&roots, lib)
@@ -231,12 +262,14 @@ impl ModuleLoadPreparer {
       log::warn!("{} No matching files found.", colors::yellow("Warning"));
     }
 
+    let maybe_budget = self.options.resolve_graph_budget()?;
     self
-      .prepare_module_load(
+      .prepare_module_load_with_budget(
         specifiers,
         false,
         lib,
         PermissionsContainer::allow_all(),
+        maybe_budget.as_ref(),
       )
       .await
   }
@@ -245,7 +278,8 @@ impl ModuleLoadPreparer {
     &self,
     files: &[String],
   ) -> Result<Vec<ModuleSpecifier>, AnyError> {
-    let excludes = self.options.resolve_config_excludes()?;
+    let file_patterns = self.options.resolve_config_file_patterns()?;
+    let file_selection = self.options.resolve_file_selection(None, &[])?;
     Ok(
       files
         .iter()
@@ -257,7 +291,7 @@ impl ModuleLoadPreparer {
           }
           // ignore local files that match any of files listed in `exclude` option
           let file_path = file_url.to_file_path().ok()?;
-          if excludes.matches_path(&file_path) {
+          if file_selection.is_excluded(&file_patterns, &file_path, false) {
             None
           } else {
             Some(file_url)
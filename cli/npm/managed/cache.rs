@@ -19,12 +19,15 @@ use deno_runtime::deno_fs;
 use deno_semver::package::PackageNv;
 
 use crate::args::CacheSetting;
+use crate::cache::RemoteCache;
 use crate::http_util::HttpClient;
 use crate::npm::NpmCacheDir;
 use crate::util::fs::hard_link_dir_recursive;
+use crate::util::fs::LaxSingleProcessFsFlag;
 use crate::util::progress_bar::ProgressBar;
 
 use super::tarball::verify_and_extract_tarball;
+use super::tarball::TarballExtractionMode;
 
 /// Stores a single copy of npm packages in a cache.
 #[derive(Debug)]
@@ -34,6 +37,7 @@ pub struct NpmCache {
   fs: Arc<dyn deno_fs::FileSystem>,
   http_client: Arc<HttpClient>,
   progress_bar: ProgressBar,
+  maybe_remote_cache: Option<Arc<RemoteCache>>,
   /// ensures a package is only downloaded once per run
   previously_reloaded_packages: Mutex<HashSet<PackageNv>>,
 }
@@ -45,6 +49,7 @@ impl NpmCache {
     fs: Arc<dyn deno_fs::FileSystem>,
     http_client: Arc<HttpClient>,
     progress_bar: ProgressBar,
+    maybe_remote_cache: Option<Arc<RemoteCache>>,
   ) -> Self {
     Self {
       cache_dir,
@@ -52,6 +57,7 @@ impl NpmCache {
       fs,
       http_client,
       progress_bar,
+      maybe_remote_cache,
       previously_reloaded_packages: Default::default(),
     }
   }
@@ -98,36 +104,93 @@ impl NpmCache {
     let package_folder = self
       .cache_dir
       .package_folder_for_name_and_version(package, registry_url);
-    if self.should_use_global_cache_for_package(package)
-      && self.fs.exists_sync(&package_folder)
-      // if this file exists, then the package didn't successfully extract
-      // the first time, or another process is currently extracting the zip file
-      && !self.fs.exists_sync(&package_folder.join(NPM_PACKAGE_SYNC_LOCK_FILENAME))
-    {
+    let use_global_cache = self.should_use_global_cache_for_package(package);
+    let is_cached = || {
+      self.fs.exists_sync(&package_folder)
+        // if this file exists, then the package didn't successfully extract
+        // the first time, or another process is currently extracting the zip file
+        && !self.fs.exists_sync(&package_folder.join(NPM_PACKAGE_SYNC_LOCK_FILENAME))
+    };
+    if use_global_cache && is_cached() {
       return Ok(());
     } else if self.cache_setting == CacheSetting::Only {
       return Err(custom_error(
         "NotCached",
         format!(
-          "An npm specifier not found in cache: \"{}\", --cached-only is specified.",
-          &package.name
-        )
-      )
-      );
+          "An npm specifier not found in cache: \"{}\", {} is specified.",
+          &package.name,
+          if self.http_client.is_offline() {
+            "--offline"
+          } else {
+            "--cached-only"
+          }
+        ),
+      ));
     }
 
     if dist.tarball.is_empty() {
       bail!("Tarball URL was empty.");
     }
 
-    let guard = self.progress_bar.update(&dist.tarball);
-    let maybe_bytes = self
-      .http_client
-      .download_with_progress(&dist.tarball, &guard)
-      .await?;
+    // only let one process download and extract the package at a time
+    let _lock = LaxSingleProcessFsFlag::lock(
+      package_lock_path(&package_folder),
+      &format!(
+        "Blocking waiting for another process to cache npm package '{}'",
+        package
+      ),
+    )
+    .await;
+    if use_global_cache && is_cached() {
+      // another process cached it while we were waiting on the lock
+      return Ok(());
+    }
+
+    // the remote cache isn't used when reloading the package
+    let maybe_remote_cache = self
+      .maybe_remote_cache
+      .as_ref()
+      .filter(|_| self.cache_setting.should_use_for_npm_package(&package.name));
+    let maybe_remote_bytes = match maybe_remote_cache {
+      Some(remote_cache) => remote_cache.get_npm_tarball(&dist.tarball).await,
+      None => None,
+    };
+    let is_from_remote_cache = maybe_remote_bytes.is_some();
+    let maybe_bytes = match maybe_remote_bytes {
+      Some(bytes) => Some(bytes),
+      None => {
+        let guard = self.progress_bar.update(&dist.tarball);
+        self
+          .http_client
+          .download_with_progress(&dist.tarball, &guard)
+          .await?
+      }
+    };
     match maybe_bytes {
       Some(bytes) => {
-        verify_and_extract_tarball(package, &bytes, dist, &package_folder)
+        let extraction_mode = if self.fs.exists_sync(&package_folder) {
+          // the package is being reloaded or a previous extraction failed,
+          // so overwrite the existing folder
+          TarballExtractionMode::Overwrite
+        } else {
+          // extract to a temp folder then rename it, so that other
+          // processes sharing the cache never see a partial package
+          TarballExtractionMode::SiblingTempDir
+        };
+        verify_and_extract_tarball(
+          package,
+          &bytes,
+          dist,
+          &package_folder,
+          extraction_mode,
+        )?;
+        // only push tarballs which were verified against the registry
+        if !is_from_remote_cache {
+          if let Some(remote_cache) = &self.maybe_remote_cache {
+            remote_cache.put_npm_tarball(&dist.tarball, &bytes).await;
+          }
+        }
+        Ok(())
       }
       None => {
         bail!("Could not find npm package tarball at: {}", dist.tarball);
@@ -135,6 +198,22 @@ impl NpmCache {
     }
   }
 
+  /// Downloads the prebuilt native addon of a package, which is `None` when
+  /// it doesn't exist or downloading isn't allowed.
+  pub async fn download_prebuild(
+    &self,
+    url: &Url,
+  ) -> Result<Option<Vec<u8>>, AnyError> {
+    if self.cache_setting == CacheSetting::Only {
+      return Ok(None);
+    }
+    let guard = self.progress_bar.update(url.as_str());
+    self
+      .http_client
+      .download_with_progress(url.as_str(), &guard)
+      .await
+  }
+
   /// Ensures a copy of the package exists in the global cache.
   ///
   /// This assumes that the original package folder being hard linked
@@ -206,6 +285,16 @@ impl NpmCache {
 
 const NPM_PACKAGE_SYNC_LOCK_FILENAME: &str = ".deno_sync_lock";
 
+/// Gets the path of the advisory lock file that guards downloading and
+/// extracting a package, which lives beside the package folder so that it
+/// survives the folder being replaced.
+fn package_lock_path(package_folder: &Path) -> PathBuf {
+  // don't use `with_extension` because versions contain periods
+  let mut file_name = package_folder.file_name().unwrap().to_os_string();
+  file_name.push(".lock");
+  package_folder.with_file_name(file_name)
+}
+
 pub fn with_folder_sync_lock(
   package: &PackageNv,
   output_folder: &Path,
@@ -1,16 +1,19 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use std::collections::HashSet;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 
 use deno_ast::ModuleSpecifier;
+use deno_core::anyhow::bail;
 use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
 use deno_core::parking_lot::Mutex;
 use deno_core::serde_json;
 use deno_core::url::Url;
 use deno_graph::NpmPackageReqResolution;
+use deno_npm::registry::NpmPackageInfo;
 use deno_npm::registry::NpmRegistryApi;
 use deno_npm::resolution::NpmResolutionSnapshot;
 use deno_npm::resolution::PackageReqNotFoundError;
@@ -25,7 +28,9 @@ use deno_runtime::deno_node::NpmResolver;
 use deno_semver::package::PackageNv;
 use deno_semver::package::PackageReq;
 
+use crate::args::package_json::WorkspaceNpmPackage;
 use crate::args::Lockfile;
+use crate::args::NodeModulesDirMode;
 use crate::args::NpmProcessState;
 use crate::args::NpmProcessStateKind;
 use crate::args::PackageJsonDepsProvider;
@@ -46,11 +51,15 @@ use super::NpmCacheDir;
 
 mod cache;
 mod installer;
+mod prebuild;
 mod registry;
 mod resolution;
 mod resolvers;
 mod tarball;
 
+pub use self::registry::vendored_registry_file_path;
+pub use self::tarball::verify_tarball_integrity;
+
 pub enum CliNpmResolverManagedSnapshotOption {
   ResolveFromLockfile(Arc<Mutex<Lockfile>>),
   Specified(Option<ValidSerializedNpmResolutionSnapshot>),
@@ -70,9 +79,15 @@ pub struct CliNpmResolverManagedCreateOptions {
   pub cache_setting: crate::args::CacheSetting,
   pub text_only_progress_bar: crate::util::progress_bar::ProgressBar,
   pub maybe_node_modules_path: Option<PathBuf>,
+  /// The workspace members to link into the local node_modules directory.
+  pub workspace_npm_packages: Vec<WorkspaceNpmPackage>,
+  pub node_modules_dir_mode: NodeModulesDirMode,
+  /// The npm packages whose install scripts are run.
+  pub allow_scripts: Vec<PackageReq>,
   pub npm_system_info: NpmSystemInfo,
   pub package_json_installer: CliNpmResolverManagedPackageJsonInstallerOption,
   pub npm_registry_url: Url,
+  pub maybe_remote_cache: Option<Arc<crate::cache::RemoteCache>>,
 }
 
 pub async fn create_managed_npm_resolver_for_lsp(
@@ -95,6 +110,9 @@ pub async fn create_managed_npm_resolver_for_lsp(
     options.fs,
     options.text_only_progress_bar,
     options.maybe_node_modules_path,
+    options.workspace_npm_packages,
+    options.node_modules_dir_mode,
+    options.allow_scripts,
     options.package_json_installer,
     options.npm_registry_url,
     options.npm_system_info,
@@ -115,6 +133,9 @@ pub async fn create_managed_npm_resolver(
     options.fs,
     options.text_only_progress_bar,
     options.maybe_node_modules_path,
+    options.workspace_npm_packages,
+    options.node_modules_dir_mode,
+    options.allow_scripts,
     options.package_json_installer,
     options.npm_registry_url,
     options.npm_system_info,
@@ -130,6 +151,9 @@ fn create_inner(
   fs: Arc<dyn deno_runtime::deno_fs::FileSystem>,
   text_only_progress_bar: crate::util::progress_bar::ProgressBar,
   node_modules_dir_path: Option<PathBuf>,
+  workspace_npm_packages: Vec<WorkspaceNpmPackage>,
+  node_modules_dir_mode: NodeModulesDirMode,
+  allow_scripts: Vec<PackageReq>,
   package_json_installer: CliNpmResolverManagedPackageJsonInstallerOption,
   npm_registry_url: Url,
   npm_system_info: NpmSystemInfo,
@@ -145,7 +169,11 @@ fn create_inner(
     &text_only_progress_bar,
     npm_registry_url,
     resolution.clone(),
+    maybe_lockfile.clone(),
     node_modules_dir_path,
+    workspace_npm_packages.clone(),
+    node_modules_dir_mode,
+    allow_scripts.clone(),
     npm_system_info.clone(),
   );
   let package_json_deps_installer = match package_json_installer {
@@ -170,6 +198,9 @@ fn create_inner(
     package_json_deps_installer,
     text_only_progress_bar,
     npm_system_info,
+    workspace_npm_packages,
+    node_modules_dir_mode,
+    allow_scripts,
   ))
 }
 
@@ -180,6 +211,7 @@ fn create_cache(options: &CliNpmResolverManagedCreateOptions) -> Arc<NpmCache> {
     options.fs.clone(),
     options.http_client.clone(),
     options.text_only_progress_bar.clone(),
+    options.maybe_remote_cache.clone(),
   ))
 }
 
@@ -192,6 +224,7 @@ fn create_api(
     npm_cache.clone(),
     options.http_client.clone(),
     options.text_only_progress_bar.clone(),
+    options.maybe_node_modules_path.as_deref(),
   ))
 }
 
@@ -255,6 +288,9 @@ pub struct ManagedCliNpmResolver {
   npm_system_info: NpmSystemInfo,
   progress_bar: ProgressBar,
   package_json_deps_installer: Arc<PackageJsonDepsInstaller>,
+  workspace_npm_packages: Vec<WorkspaceNpmPackage>,
+  node_modules_dir_mode: NodeModulesDirMode,
+  allow_scripts: Vec<PackageReq>,
 }
 
 impl std::fmt::Debug for ManagedCliNpmResolver {
@@ -277,6 +313,9 @@ impl ManagedCliNpmResolver {
     package_json_deps_installer: Arc<PackageJsonDepsInstaller>,
     progress_bar: ProgressBar,
     npm_system_info: NpmSystemInfo,
+    workspace_npm_packages: Vec<WorkspaceNpmPackage>,
+    node_modules_dir_mode: NodeModulesDirMode,
+    allow_scripts: Vec<PackageReq>,
   ) -> Self {
     Self {
       api,
@@ -288,6 +327,9 @@ impl ManagedCliNpmResolver {
       package_json_deps_installer,
       progress_bar,
       npm_system_info,
+      workspace_npm_packages,
+      node_modules_dir_mode,
+      allow_scripts,
     }
   }
 
@@ -340,6 +382,28 @@ impl ManagedCliNpmResolver {
     Ok(crate::util::fs::dir_size(&package_folder)?)
   }
 
+  /// Gets the total size in bytes of the packages and their dependencies
+  /// which weren't visited yet, where packages that aren't cached don't add
+  /// to the size.
+  pub fn packages_size_with_deps(
+    &self,
+    snapshot: &NpmResolutionSnapshot,
+    mut pending: Vec<NpmPackageId>,
+    visited: &mut HashSet<NpmPackageId>,
+  ) -> u64 {
+    let mut total_size = 0;
+    while let Some(id) = pending.pop() {
+      if !visited.insert(id.clone()) {
+        continue;
+      }
+      total_size += self.package_size(&id).unwrap_or(0);
+      if let Some(package) = snapshot.package_from_id(&id) {
+        pending.extend(package.dependencies.values().cloned());
+      }
+    }
+    total_size
+  }
+
   pub fn all_system_packages(
     &self,
     system_info: &NpmSystemInfo,
@@ -454,7 +518,7 @@ impl ManagedCliNpmResolver {
     self.resolve_pkg_folder_from_pkg_id(&pkg_id)
   }
 
-  fn resolve_pkg_id_from_pkg_req(
+  pub fn resolve_pkg_id_from_pkg_req(
     &self,
     req: &PackageReq,
   ) -> Result<NpmPackageId, PackageReqNotFoundError> {
@@ -483,10 +547,43 @@ impl ManagedCliNpmResolver {
       .map_err(|err| err.into())
   }
 
+  pub async fn package_info(
+    &self,
+    package_name: &str,
+  ) -> Result<Arc<NpmPackageInfo>, AnyError> {
+    self
+      .api
+      .package_info(package_name)
+      .await
+      .map_err(|err| err.into())
+  }
+
   pub fn registry_base_url(&self) -> &ModuleSpecifier {
     self.api.base_url()
   }
 
+  /// Caches a version of a package in the global cache without adding it to
+  /// the resolution, returning the folder of the package.
+  pub async fn cache_package_version(
+    &self,
+    nv: &PackageNv,
+  ) -> Result<PathBuf, AnyError> {
+    let package_info = self.package_info(&nv.name).await?;
+    let Some(version_info) = package_info.versions.get(&nv.version) else {
+      bail!("Could not find npm package '{}'.", nv);
+    };
+    let registry_url = self.api.base_url();
+    self
+      .global_npm_cache
+      .ensure_package(nv, &version_info.dist, registry_url)
+      .await?;
+    Ok(
+      self
+        .global_npm_cache
+        .package_folder_for_name_and_version(nv, registry_url),
+    )
+  }
+
   pub fn registry_folder_in_global_cache(
     &self,
     registry_url: &ModuleSpecifier,
@@ -564,7 +661,11 @@ impl CliNpmResolver for ManagedCliNpmResolver {
         &self.progress_bar,
         self.api.base_url().clone(),
         npm_resolution,
+        self.maybe_lockfile.clone(),
         self.root_node_modules_path().map(ToOwned::to_owned),
+        self.workspace_npm_packages.clone(),
+        self.node_modules_dir_mode,
+        self.allow_scripts.clone(),
         self.npm_system_info.clone(),
       ),
       self.global_npm_cache.clone(),
@@ -572,6 +673,9 @@ impl CliNpmResolver for ManagedCliNpmResolver {
       self.package_json_deps_installer.clone(),
       self.progress_bar.clone(),
       self.npm_system_info.clone(),
+      self.workspace_npm_packages.clone(),
+      self.node_modules_dir_mode,
+      self.allow_scripts.clone(),
     ))
   }
 
@@ -0,0 +1,246 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Support for the native addons of npm packages which download a prebuilt
+//! N-API binary with `prebuild-install --runtime napi` in their install
+//! script. Binaries built against the ABI of node aren't compatible with
+//! deno, so their install script runs instead.
+//!
+//! Packages built with napi-rs don't need this, because they publish the
+//! binary of every platform as an optional dependency, which is already
+//! filtered by the `os` and `cpu` of the package.
+
+use std::path::Path;
+
+use deno_core::serde_json;
+use deno_core::url::Url;
+use deno_npm::NpmSystemInfo;
+use deno_semver::package::PackageNv;
+
+use crate::napi::js_native_api::NAPI_VERSION;
+
+/// Gets if the install script downloads a prebuilt binary, for example
+/// `prebuild-install || node-gyp rebuild`.
+pub fn is_prebuild_install_script(command: &str) -> bool {
+  command.split_whitespace().next() == Some("prebuild-install")
+}
+
+/// Resolves the URL of the prebuilt binary the same way as the defaults of
+/// `prebuild-install`, which is the asset of the GitHub release of the
+/// version. Returns `None` when the repository isn't on GitHub or the script
+/// doesn't download an N-API binary of a version supported by deno.
+pub fn resolve_prebuild_url(
+  package_nv: &PackageNv,
+  package_json: &serde_json::Value,
+  command: &str,
+  system_info: &NpmSystemInfo,
+) -> Option<Url> {
+  let args = command.split_whitespace().collect::<Vec<_>>();
+  let is_napi = args
+    .windows(2)
+    .any(|w| matches!(w[0], "-r" | "--runtime") && w[1] == "napi")
+    || args.contains(&"--runtime=napi");
+  if !is_napi {
+    return None;
+  }
+  let repository_url = github_repository_url(package_json.get("repository")?)?;
+  // use the highest N-API version supported by deno
+  let napi_version = package_json
+    .get("binary")?
+    .get("napi_versions")?
+    .as_array()?
+    .iter()
+    .filter_map(|v| v.as_u64())
+    .filter(|v| *v <= NAPI_VERSION as u64)
+    .max()?;
+  // the scope isn't part of the file name
+  let name = match package_nv.name.split_once('/') {
+    Some((scope, name)) if scope.starts_with('@') => name,
+    _ => package_nv.name.as_str(),
+  };
+  let version = &package_nv.version;
+  Url::parse(&format!(
+    "{}/releases/download/v{}/{}-v{}-napi-v{}-{}-{}.tar.gz",
+    repository_url,
+    version,
+    name,
+    version,
+    napi_version,
+    system_info.os,
+    system_info.cpu,
+  ))
+  .ok()
+}
+
+/// Gets the URL of the GitHub repository of the "repository" field of a
+/// package.json, which is either a string or an object with a "url".
+fn github_repository_url(repository: &serde_json::Value) -> Option<String> {
+  let url = match repository {
+    serde_json::Value::String(url) => url.as_str(),
+    serde_json::Value::Object(obj) => obj.get("url")?.as_str()?,
+    _ => return None,
+  };
+  let url = url.strip_prefix("git+").unwrap_or(url);
+  let path = if let Some(path) = url.strip_prefix("github:") {
+    path
+  } else if let Some((scheme, rest)) = url.split_once("://") {
+    if !matches!(scheme, "https" | "http" | "git" | "ssh") {
+      return None;
+    }
+    // remove the user of ssh urls like ssh://git@github.com/owner/repo
+    let rest = rest.split_once('@').map(|(_, r)| r).unwrap_or(rest);
+    rest.strip_prefix("github.com/")?
+  } else if !url.contains(':') {
+    url // shorthand like owner/repo
+  } else {
+    return None;
+  };
+  let path = path.trim_end_matches('/');
+  let path = path.strip_suffix(".git").unwrap_or(path);
+  let mut parts = path.split('/');
+  let owner = parts.next().filter(|p| !p.is_empty())?;
+  let repo = parts.next().filter(|p| !p.is_empty())?;
+  if parts.next().is_some() {
+    return None;
+  }
+  Some(format!("https://github.com/{}/{}", owner, repo))
+}
+
+/// Reads the package.json of the package directory.
+pub fn read_package_json_value(
+  package_dir: &Path,
+) -> Option<serde_json::Value> {
+  let text = std::fs::read_to_string(package_dir.join("package.json")).ok()?;
+  serde_json::from_str(&text).ok()
+}
+
+#[cfg(test)]
+mod test {
+  use deno_core::serde_json::json;
+  use deno_semver::Version;
+
+  use super::*;
+
+  fn nv(name: &str, version: &str) -> PackageNv {
+    PackageNv {
+      name: name.to_string(),
+      version: Version::parse_from_npm(version).unwrap(),
+    }
+  }
+
+  fn linux_x64() -> NpmSystemInfo {
+    NpmSystemInfo {
+      os: "linux".to_string(),
+      cpu: "x64".to_string(),
+    }
+  }
+
+  #[test]
+  fn test_is_prebuild_install_script() {
+    assert!(is_prebuild_install_script(
+      "prebuild-install || node-gyp rebuild"
+    ));
+    assert!(is_prebuild_install_script("prebuild-install -r napi"));
+    assert!(!is_prebuild_install_script("node-gyp rebuild"));
+    assert!(!is_prebuild_install_script(
+      "node install.js prebuild-install"
+    ));
+  }
+
+  #[test]
+  fn test_github_repository_url() {
+    let cases = [
+      (
+        json!("github:owner/repo"),
+        Some("https://github.com/owner/repo"),
+      ),
+      (json!("owner/repo"), Some("https://github.com/owner/repo")),
+      (
+        json!("https://github.com/owner/repo"),
+        Some("https://github.com/owner/repo"),
+      ),
+      (
+        json!({ "type": "git", "url": "git+https://github.com/owner/repo.git" }),
+        Some("https://github.com/owner/repo"),
+      ),
+      (
+        json!({ "url": "git+ssh://git@github.com/owner/repo.git" }),
+        Some("https://github.com/owner/repo"),
+      ),
+      (json!("https://gitlab.com/owner/repo"), None),
+      (json!("gitlab:owner/repo"), None),
+      (json!("https://github.com/owner"), None),
+      (json!(1), None),
+    ];
+    for (repository, expected) in cases {
+      assert_eq!(
+        github_repository_url(&repository).as_deref(),
+        expected,
+        "{}",
+        repository
+      );
+    }
+  }
+
+  #[test]
+  fn test_resolve_prebuild_url() {
+    // binaries for the ABI of node aren't supported
+    let package_json = json!({
+      "repository": {
+        "type": "git",
+        "url": "git+https://github.com/WiseLibs/better-sqlite3.git",
+      },
+    });
+    assert_eq!(
+      resolve_prebuild_url(
+        &nv("better-sqlite3", "9.4.0"),
+        &package_json,
+        "prebuild-install || node-gyp rebuild --release",
+        &linux_x64(),
+      ),
+      None,
+    );
+
+    // the highest supported N-API version is used
+    let package_json = json!({
+      "repository": "github:owner/addon",
+      "binary": { "napi_versions": [3, 6, 100] },
+    });
+    assert_eq!(
+      resolve_prebuild_url(
+        &nv("@scope/addon", "1.2.3"),
+        &package_json,
+        "prebuild-install --runtime napi || node-gyp rebuild",
+        &linux_x64(),
+      )
+      .unwrap()
+      .as_str(),
+      "https://github.com/owner/addon/releases/download/v1.2.3/addon-v1.2.3-napi-v6-linux-x64.tar.gz",
+    );
+
+    // no supported N-API version
+    let package_json = json!({
+      "repository": "github:owner/addon",
+      "binary": { "napi_versions": [100] },
+    });
+    assert_eq!(
+      resolve_prebuild_url(
+        &nv("addon", "1.0.0"),
+        &package_json,
+        "prebuild-install -r napi",
+        &linux_x64(),
+      ),
+      None,
+    );
+
+    // no repository
+    assert_eq!(
+      resolve_prebuild_url(
+        &nv("addon", "1.0.0"),
+        &json!({ "binary": { "napi_versions": [3] } }),
+        "prebuild-install --runtime=napi",
+        &linux_x64(),
+      ),
+      None,
+    );
+  }
+}
@@ -4,6 +4,7 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fs;
 use std::io::ErrorKind;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -21,6 +22,7 @@ use deno_core::url::Url;
 use deno_npm::registry::NpmPackageInfo;
 use deno_npm::registry::NpmRegistryApi;
 use deno_npm::registry::NpmRegistryPackageInfoLoadError;
+use once_cell::sync::OnceCell;
 
 use crate::args::CacheSetting;
 use crate::cache::CACHE_PERM;
@@ -31,6 +33,13 @@ use crate::util::sync::AtomicFlag;
 
 use super::cache::NpmCache;
 
+/// Gets the path of the file with the registry information of the packages
+/// of a local node_modules directory, which `deno vendor` writes so that the
+/// packages can be resolved without access to the registry.
+pub fn vendored_registry_file_path(node_modules_path: &Path) -> PathBuf {
+  node_modules_path.join(".deno").join(".registry.json")
+}
+
 #[derive(Debug)]
 pub struct CliNpmRegistryApi(Option<Arc<CliNpmRegistryApiInner>>);
 
@@ -40,6 +49,7 @@ impl CliNpmRegistryApi {
     cache: Arc<NpmCache>,
     http_client: Arc<HttpClient>,
     progress_bar: ProgressBar,
+    maybe_node_modules_path: Option<&Path>,
   ) -> Self {
     Self(Some(Arc::new(CliNpmRegistryApiInner {
       base_url,
@@ -49,6 +59,9 @@ impl CliNpmRegistryApi {
       previously_reloaded_packages: Default::default(),
       http_client,
       progress_bar,
+      maybe_vendored_registry_file: maybe_node_modules_path
+        .map(vendored_registry_file_path),
+      vendored_package_infos: Default::default(),
     })))
   }
 
@@ -128,6 +141,8 @@ struct CliNpmRegistryApiInner {
   previously_reloaded_packages: Mutex<HashSet<String>>,
   http_client: Arc<HttpClient>,
   progress_bar: ProgressBar,
+  maybe_vendored_registry_file: Option<PathBuf>,
+  vendored_package_infos: OnceCell<HashMap<String, NpmPackageInfo>>,
 }
 
 impl CliNpmRegistryApiInner {
@@ -273,16 +288,48 @@ impl CliNpmRegistryApiInner {
     &self,
     name: &str,
   ) -> Result<Option<NpmPackageInfo>, AnyError> {
-    self
-      .load_package_info_from_registry_inner(name)
-      .await
-      .with_context(|| {
-        format!(
-          "Error getting response at {} for package \"{}\"",
-          self.get_package_url(name),
-          name
-        )
-      })
+    let result = self.load_package_info_from_registry_inner(name).await;
+    if let Err(err) = &result {
+      // fall back to the registry information vendored into the local
+      // node_modules directory, so it works offline
+      if let Some(info) = self.load_vendored_package_info(name) {
+        log::debug!(
+          "Using the vendored registry information of '{}'. {:#}",
+          name,
+          err
+        );
+        return Ok(Some(info));
+      }
+    }
+    result.with_context(|| {
+      format!(
+        "Error getting response at {} for package \"{}\"",
+        self.get_package_url(name),
+        name
+      )
+    })
+  }
+
+  fn load_vendored_package_info(&self, name: &str) -> Option<NpmPackageInfo> {
+    let file_path = self.maybe_vendored_registry_file.as_ref()?;
+    let infos = self.vendored_package_infos.get_or_init(|| {
+      let file_text = match fs::read_to_string(file_path) {
+        Ok(file_text) => file_text,
+        Err(_) => return HashMap::new(),
+      };
+      match serde_json::from_str(&file_text) {
+        Ok(infos) => infos,
+        Err(err) => {
+          log::debug!(
+            "error deserializing '{}'. Ignoring. {:?}",
+            file_path.display(),
+            err
+          );
+          HashMap::new()
+        }
+      }
+    });
+    infos.get(name).cloned()
   }
 
   async fn load_package_info_from_registry_inner(
@@ -293,8 +340,13 @@ impl CliNpmRegistryApiInner {
       return Err(custom_error(
         "NotCached",
         format!(
-          "An npm specifier not found in cache: \"{name}\", --cached-only is specified."
-        )
+          "An npm specifier not found in cache: \"{name}\", {} is specified.",
+          if self.http_client.is_offline() {
+            "--offline"
+          } else {
+            "--cached-only"
+          }
+        ),
       ));
     }
 
@@ -9,10 +9,18 @@ use std::collections::HashSet;
 use std::fs;
 use std::path::Path;
 use std::path::PathBuf;
+use std::rc::Rc;
 use std::sync::Arc;
 
+use crate::args::is_allowed_to_run_scripts;
+use crate::args::package_json::read_install_scripts;
+use crate::args::package_json::WorkspaceNpmPackage;
+use crate::args::Lockfile;
+use crate::args::NodeModulesDirMode;
 use crate::cache::CACHE_PERM;
+use crate::colors;
 use crate::npm::cache_dir::mixed_case_package_name_decode;
+use crate::util::checksum;
 use crate::util::fs::atomic_write_file;
 use crate::util::fs::canonicalize_path_maybe_not_exists_with_fs;
 use crate::util::fs::symlink_dir;
@@ -24,7 +32,10 @@ use deno_ast::ModuleSpecifier;
 use deno_core::anyhow::bail;
 use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
+use deno_core::futures::future::LocalBoxFuture;
+use deno_core::parking_lot::Mutex;
 use deno_core::unsync::spawn;
+use deno_core::unsync::spawn_blocking;
 use deno_core::unsync::JoinHandle;
 use deno_core::url::Url;
 use deno_npm::resolution::NpmResolutionSnapshot;
@@ -36,17 +47,28 @@ use deno_runtime::deno_core::futures;
 use deno_runtime::deno_fs;
 use deno_runtime::deno_node::NodePermissions;
 use deno_runtime::deno_node::NodeResolutionMode;
+use deno_runtime::tokio_util::create_and_run_current_thread;
 use deno_semver::package::PackageNv;
+use deno_semver::package::PackageReq;
+use deno_task_shell::ExecuteResult;
+use deno_task_shell::ShellCommand;
+use deno_task_shell::ShellCommandContext;
 use serde::Deserialize;
 use serde::Serialize;
+use tokio::task::LocalSet;
 
 use crate::npm::cache_dir::mixed_case_package_name_encode;
 use crate::util::fs::copy_dir_recursive;
 use crate::util::fs::hard_link_dir_recursive;
+use crate::util::fs::hard_link_or_copy_dir_recursive;
 
 use super::super::super::common::types_package_name;
 use super::super::cache::NpmCache;
+use super::super::prebuild::is_prebuild_install_script;
+use super::super::prebuild::read_package_json_value;
+use super::super::prebuild::resolve_prebuild_url;
 use super::super::resolution::NpmResolution;
+use super::super::tarball::extract_prebuild_tarball;
 use super::common::NpmPackageFsResolver;
 use super::common::RegistryReadPermissionChecker;
 
@@ -61,6 +83,10 @@ pub struct LocalNpmPackageResolver {
   registry_url: Url,
   root_node_modules_path: PathBuf,
   root_node_modules_url: Url,
+  workspace_npm_packages: Vec<WorkspaceNpmPackage>,
+  node_modules_dir_mode: NodeModulesDirMode,
+  allow_scripts: Vec<PackageReq>,
+  maybe_lockfile: Option<Arc<Mutex<Lockfile>>>,
   system_info: NpmSystemInfo,
   registry_read_permission_checker: RegistryReadPermissionChecker,
 }
@@ -72,7 +98,11 @@ impl LocalNpmPackageResolver {
     progress_bar: ProgressBar,
     registry_url: Url,
     node_modules_folder: PathBuf,
+    workspace_npm_packages: Vec<WorkspaceNpmPackage>,
+    node_modules_dir_mode: NodeModulesDirMode,
+    allow_scripts: Vec<PackageReq>,
     resolution: Arc<NpmResolution>,
+    maybe_lockfile: Option<Arc<Mutex<Lockfile>>>,
     system_info: NpmSystemInfo,
   ) -> Self {
     Self {
@@ -84,6 +114,10 @@ impl LocalNpmPackageResolver {
       root_node_modules_url: Url::from_directory_path(&node_modules_folder)
         .unwrap(),
       root_node_modules_path: node_modules_folder.clone(),
+      workspace_npm_packages,
+      node_modules_dir_mode,
+      allow_scripts,
+      maybe_lockfile,
       system_info,
       registry_read_permission_checker: RegistryReadPermissionChecker::new(
         fs,
@@ -231,6 +265,10 @@ impl NpmPackageFsResolver for LocalNpmPackageResolver {
       &self.progress_bar,
       &self.registry_url,
       &self.root_node_modules_path,
+      &self.workspace_npm_packages,
+      self.node_modules_dir_mode,
+      &self.allow_scripts,
+      self.maybe_lockfile.as_ref(),
       &self.system_info,
     )
     .await
@@ -254,9 +292,13 @@ async fn sync_resolution_with_fs(
   progress_bar: &ProgressBar,
   registry_url: &Url,
   root_node_modules_dir_path: &Path,
+  workspace_npm_packages: &[WorkspaceNpmPackage],
+  node_modules_dir_mode: NodeModulesDirMode,
+  allow_scripts: &[PackageReq],
+  maybe_lockfile: Option<&Arc<Mutex<Lockfile>>>,
   system_info: &NpmSystemInfo,
 ) -> Result<(), AnyError> {
-  if snapshot.is_empty() {
+  if snapshot.is_empty() && workspace_npm_packages.is_empty() {
     return Ok(()); // don't create the directory
   }
 
@@ -281,7 +323,7 @@ async fn sync_resolution_with_fs(
 
   // 1. Write all the packages out the .deno directory.
   //
-  // Copy (hardlink in future) <global_registry_cache>/<package_id>/ to
+  // Copy or hard link <global_registry_cache>/<package_id>/ to
   // node_modules/.deno/<package_folder_id_folder_name>/node_modules/<package_name>
   let package_partitions =
     snapshot.all_system_packages_partitioned(system_info);
@@ -289,6 +331,7 @@ async fn sync_resolution_with_fs(
     Vec::with_capacity(package_partitions.packages.len());
   let mut newest_packages_by_name: HashMap<&String, &NpmResolutionPackage> =
     HashMap::with_capacity(package_partitions.packages.len());
+  let mut newly_initialized_packages = HashSet::new();
   for package in &package_partitions.packages {
     if let Some(current_pkg) =
       newest_packages_by_name.get_mut(&package.id.nv.name)
@@ -312,6 +355,7 @@ async fn sync_resolution_with_fs(
       // cache bust the dep from the dep setup cache so the symlinks
       // are forced to be recreated
       setup_cache.remove_dep(&package_folder_name);
+      newly_initialized_packages.insert(&package.id);
 
       let pb = progress_bar.clone();
       let cache = cache.clone();
@@ -332,8 +376,14 @@ async fn sync_resolution_with_fs(
           .with_context(|| format!("Creating '{}'", folder_path.display()))?;
         let cache_folder = cache
           .package_folder_for_name_and_version(&package.id.nv, &registry_url);
-        // for now copy, but in the future consider hard linking
-        copy_dir_recursive(&cache_folder, &package_path)?;
+        match node_modules_dir_mode {
+          NodeModulesDirMode::Copy => {
+            copy_dir_recursive(&cache_folder, &package_path)?
+          }
+          NodeModulesDirMode::Linked => {
+            hard_link_or_copy_dir_recursive(&cache_folder, &package_path)?
+          }
+        }
         // write out a file that indicates this folder has been initialized
         fs::write(initialized_file, "")?;
         // finally stop showing the progress bar
@@ -412,11 +462,39 @@ async fn sync_resolution_with_fs(
     }
   }
 
-  // 4. Create all the top level packages in the node_modules folder, which are symlinks.
+  // 4. Symlink the members of the workspace into the node_modules folder,
+  // which take precedence over the packages of the registry.
+  //
+  // Symlink node_modules/<package_name> to <member_dir>
+  let mut found_names = HashSet::new();
+  for package in workspace_npm_packages {
+    if !found_names.insert(&package.name) {
+      continue; // skip, already handled
+    }
+    let target_path = package.path.to_string_lossy();
+    if setup_cache.insert_root_symlink(&package.name, &target_path) {
+      symlink_package_dir(
+        &package.path,
+        &join_package_name(root_node_modules_dir_path, &package.name),
+      )?;
+      // lifecycle scripts aren't supported, so at least surface them
+      for script_name in &package.lifecycle_scripts {
+        log::warn!(
+          "{} The \"{}\" script of the workspace member \"{}\" was not run. Run it with `deno task {}` in {}",
+          colors::yellow("Warning"),
+          script_name,
+          package.name,
+          script_name,
+          package.path.display(),
+        );
+      }
+    }
+  }
+
+  // 5. Create all the top level packages in the node_modules folder, which are symlinks.
   //
   // Symlink node_modules/<package_name> to
   // node_modules/.deno/<package_id>/node_modules/<package_name>
-  let mut found_names = HashSet::new();
   let mut ids = snapshot.top_level_packages().collect::<Vec<_>>();
   ids.sort_by(|a, b| b.cmp(a)); // create determinism and only include the latest version
   for id in ids {
@@ -441,7 +519,7 @@ async fn sync_resolution_with_fs(
     }
   }
 
-  // 5. Create a node_modules/.deno/node_modules/<package-name> directory with
+  // 6. Create a node_modules/.deno/node_modules/<package-name> directory with
   // the remaining packages
   for package in newest_packages_by_name.values() {
     if !found_names.insert(&package.id.nv.name) {
@@ -466,13 +544,230 @@ async fn sync_resolution_with_fs(
     }
   }
 
+  // 7. Find the install scripts of the packages in "allowScripts" to run once
+  // their dependencies are set up and warn about the scripts of the other
+  // packages which were newly set up.
+  let mut pending_scripts = Vec::new();
+  for package in &package_partitions.packages {
+    let is_allowed = is_allowed_to_run_scripts(allow_scripts, &package.id.nv);
+    if !is_allowed && !newly_initialized_packages.contains(&package.id) {
+      continue;
+    }
+    let folder_path = deno_local_registry_dir.join(
+      get_package_folder_id_folder_name(&package.get_package_cache_folder_id()),
+    );
+    let scripts_run_file = folder_path.join(".scripts-run");
+    if is_allowed && scripts_run_file.exists() {
+      continue;
+    }
+    let package_path =
+      join_package_name(&folder_path.join("node_modules"), &package.id.nv.name);
+    let mut scripts = read_install_scripts(&package_path);
+    if scripts.is_empty() {
+      continue;
+    }
+    if is_allowed {
+      // the prebuilt N-API binary of a native addon is downloaded without
+      // running `prebuild-install`
+      if let Some(index) = scripts
+        .iter()
+        .position(|(_, command)| is_prebuild_install_script(command))
+      {
+        let installed = install_prebuild(
+          cache,
+          maybe_lockfile,
+          &package.id.nv,
+          &scripts[index].1,
+          &package_path,
+          &folder_path.join(".prebuild"),
+          system_info,
+        )
+        .await?;
+        if installed {
+          scripts.remove(index);
+        }
+      }
+      if scripts.is_empty() {
+        fs::write(&scripts_run_file, "")?;
+      } else {
+        pending_scripts.push((
+          package,
+          scripts,
+          package_path,
+          scripts_run_file,
+        ));
+      }
+    } else {
+      let names = scripts
+        .iter()
+        .map(|(name, _)| format!("\"{}\"", name))
+        .collect::<Vec<_>>();
+      log::warn!(
+        "{} The {} {} of npm:{} {} not run. Add \"npm:{}\" to \"allowScripts\" in the configuration file to run {}.",
+        colors::yellow("Warning"),
+        names.join(", "),
+        if names.len() == 1 { "script" } else { "scripts" },
+        package.id.nv,
+        if names.len() == 1 { "was" } else { "were" },
+        package.id.nv.name,
+        if names.len() == 1 { "it" } else { "them" },
+      );
+    }
+  }
+
   setup_cache.save();
+  // the scripts may run deno, which would wait on this lock
   drop(single_process_lock);
   drop(pb_clear_guard);
 
+  for (package, scripts, package_path, scripts_run_file) in pending_scripts {
+    // mark the scripts as run first so the deno processes of the scripts
+    // don't run them again
+    fs::write(&scripts_run_file, "")?;
+    let result = run_install_scripts(
+      &package.id.nv,
+      scripts,
+      &package_path,
+      root_node_modules_dir_path,
+    )
+    .await;
+    if result.is_err() {
+      let _ = fs::remove_file(&scripts_run_file);
+    }
+    result?;
+  }
+
+  Ok(())
+}
+
+/// Downloads and extracts the prebuilt binary of a package which uses
+/// `prebuild-install`, which returns `false` when it isn't available. The
+/// checksum of the binary is checked against the lockfile.
+async fn install_prebuild(
+  cache: &NpmCache,
+  maybe_lockfile: Option<&Arc<Mutex<Lockfile>>>,
+  package_nv: &PackageNv,
+  command: &str,
+  package_path: &Path,
+  temp_path: &Path,
+  system_info: &NpmSystemInfo,
+) -> Result<bool, AnyError> {
+  let Some(url) = read_package_json_value(package_path).and_then(|value| {
+    resolve_prebuild_url(package_nv, &value, command, system_info)
+  }) else {
+    return Ok(false);
+  };
+  let result = match cache.download_prebuild(&url).await {
+    Ok(Some(data)) => {
+      if let Some(lockfile) = maybe_lockfile {
+        let checksum = checksum::gen(&[&data]);
+        let mut lockfile = lockfile.lock();
+        if !lockfile.check_or_insert_remote(url.as_str(), &checksum) {
+          bail!(
+            "The prebuilt binary of npm:{} does not match the expected hash in the lock file.\n  URL: {}\n  Lock file: {}",
+            package_nv,
+            url,
+            lockfile.filename.display(),
+          );
+        }
+      }
+      extract_prebuild_tarball(&data, package_path, temp_path)
+    }
+    Ok(None) => {
+      log::debug!("No prebuilt binary of npm:{} at {}", package_nv, url);
+      return Ok(false);
+    }
+    Err(err) => Err(err),
+  };
+  Ok(match result {
+    Ok(()) => true,
+    Err(err) => {
+      log::debug!(
+        "Failed installing the prebuilt binary of npm:{} from {}. {:#}",
+        package_nv,
+        url,
+        err
+      );
+      false
+    }
+  })
+}
+
+/// Runs the install scripts of an npm package in its directory with the
+/// shell of `deno task`, where `node` runs the scripts with deno.
+async fn run_install_scripts(
+  package_nv: &PackageNv,
+  scripts: Vec<(String, String)>,
+  package_path: &Path,
+  root_node_modules_dir_path: &Path,
+) -> Result<(), AnyError> {
+  let mut env_vars = std::env::vars().collect::<HashMap<_, _>>();
+  let bin_dir = root_node_modules_dir_path.join(".bin");
+  let path = match env_vars.get("PATH") {
+    Some(path) if !path.is_empty() => format!(
+      "{}{}{}",
+      bin_dir.display(),
+      if cfg!(windows) { ";" } else { ":" },
+      path
+    ),
+    _ => bin_dir.display().to_string(),
+  };
+  env_vars.insert("PATH".to_string(), path);
+  for (name, command) in scripts {
+    log::info!(
+      "{} {} script of npm:{}: {}",
+      colors::green("Running"),
+      name,
+      package_nv,
+      command
+    );
+    let mut env_vars = env_vars.clone();
+    env_vars.insert("npm_lifecycle_event".to_string(), name.clone());
+    let cwd = package_path.to_path_buf();
+    let exit_code = spawn_blocking(move || {
+      let seq_list = deno_task_shell::parser::parse(&command)?;
+      let custom_commands = HashMap::from([(
+        "node".to_string(),
+        Rc::new(NodeCommand) as Rc<dyn ShellCommand>,
+      )]);
+      Ok::<_, AnyError>(create_and_run_current_thread(async move {
+        let future =
+          deno_task_shell::execute(seq_list, env_vars, &cwd, custom_commands);
+        LocalSet::new().run_until(future).await
+      }))
+    })
+    .await?
+    .with_context(|| {
+      format!("Error parsing the {} script of npm:{}.", name, package_nv)
+    })?;
+    if exit_code != 0 {
+      bail!(
+        "The {} script of npm:{} failed with exit code {}.",
+        name,
+        package_nv,
+        exit_code
+      );
+    }
+  }
   Ok(())
 }
 
+/// Runs the `node` command of an install script with deno.
+struct NodeCommand;
+
+impl ShellCommand for NodeCommand {
+  fn execute(
+    &self,
+    context: ShellCommandContext,
+  ) -> LocalBoxFuture<'static, ExecuteResult> {
+    let mut args = vec!["run".to_string(), "-A".to_string()];
+    args.extend(context.args);
+    let executable_command =
+      deno_task_shell::ExecutableCommand::new("deno".to_string());
+    executable_command.execute(ShellCommandContext { args, ..context })
+  }
+}
+
 /// Represents a dependency at `node_modules/.deno/<package_id>/`
 struct SetupCacheDep<'a> {
   previous: Option<&'a HashMap<String, String>>,
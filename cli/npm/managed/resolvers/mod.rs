@@ -7,10 +7,15 @@ mod local;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use deno_core::parking_lot::Mutex;
 use deno_core::url::Url;
 use deno_npm::NpmSystemInfo;
 use deno_runtime::deno_fs::FileSystem;
+use deno_semver::package::PackageReq;
 
+use crate::args::package_json::WorkspaceNpmPackage;
+use crate::args::Lockfile;
+use crate::args::NodeModulesDirMode;
 use crate::util::progress_bar::ProgressBar;
 
 pub use self::common::NpmPackageFsResolver;
@@ -27,7 +32,11 @@ pub fn create_npm_fs_resolver(
   progress_bar: &ProgressBar,
   registry_url: Url,
   resolution: Arc<NpmResolution>,
+  maybe_lockfile: Option<Arc<Mutex<Lockfile>>>,
   maybe_node_modules_path: Option<PathBuf>,
+  workspace_npm_packages: Vec<WorkspaceNpmPackage>,
+  node_modules_dir_mode: NodeModulesDirMode,
+  allow_scripts: Vec<PackageReq>,
   system_info: NpmSystemInfo,
 ) -> Arc<dyn NpmPackageFsResolver> {
   match maybe_node_modules_path {
@@ -37,7 +46,11 @@ pub fn create_npm_fs_resolver(
       progress_bar.clone(),
       registry_url,
       node_modules_folder,
+      workspace_npm_packages,
+      node_modules_dir_mode,
+      allow_scripts,
       resolution,
+      maybe_lockfile,
       system_info,
     )),
     None => Arc::new(GlobalNpmPackageResolver::new(
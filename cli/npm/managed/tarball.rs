@@ -2,8 +2,10 @@
 
 use std::collections::HashSet;
 use std::fs;
+use std::io::ErrorKind;
 use std::path::Path;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use base64::prelude::BASE64_STANDARD;
 use base64::Engine;
@@ -11,6 +13,7 @@ use deno_core::anyhow::bail;
 use deno_core::error::AnyError;
 use deno_npm::registry::NpmPackageVersionDistInfo;
 use deno_npm::registry::NpmPackageVersionDistInfoIntegrity;
+use deno_runtime::deno_crypto::rand;
 use deno_semver::package::PackageNv;
 use flate2::read::GzDecoder;
 use tar::Archive;
@@ -18,20 +21,96 @@ use tar::EntryType;
 
 use super::cache::with_folder_sync_lock;
 
+#[derive(Debug, Copy, Clone)]
+pub enum TarballExtractionMode {
+  /// Overwrites the destination directory without deleting any files.
+  Overwrite,
+  /// Creates and writes to a sibling temporary directory. When done, moves
+  /// it to the final destination.
+  ///
+  /// This is more robust than `Overwrite` as it better handles multiple
+  /// processes writing to the directory at the same time.
+  SiblingTempDir,
+}
+
 pub fn verify_and_extract_tarball(
   package: &PackageNv,
   data: &[u8],
   dist_info: &NpmPackageVersionDistInfo,
   output_folder: &Path,
+  extraction_mode: TarballExtractionMode,
 ) -> Result<(), AnyError> {
   verify_tarball_integrity(package, data, &dist_info.integrity())?;
 
-  with_folder_sync_lock(package, output_folder, || {
-    extract_tarball(data, output_folder)
-  })
+  match extraction_mode {
+    TarballExtractionMode::Overwrite => {
+      with_folder_sync_lock(package, output_folder, || {
+        extract_tarball(data, output_folder)
+      })
+    }
+    TarballExtractionMode::SiblingTempDir => {
+      let temp_dir = get_atomic_dir_path(output_folder);
+      if let Err(err) = extract_tarball(data, &temp_dir) {
+        let _ = fs::remove_dir_all(&temp_dir);
+        return Err(err);
+      }
+      rename_with_retries(&temp_dir, output_folder)?;
+      Ok(())
+    }
+  }
+}
+
+/// Gets a path next to the provided one, which is unique to this process.
+fn get_atomic_dir_path(file_path: &Path) -> PathBuf {
+  let rand = format!("{:016x}", rand::random::<u64>());
+  let new_file_name = format!(
+    ".{}_{}",
+    file_path.file_name().unwrap_or_default().to_string_lossy(),
+    rand
+  );
+  file_path.with_file_name(new_file_name)
+}
+
+/// Moves the extracted directory to its final destination. If another
+/// process got there first, its directory is kept and ours is deleted.
+fn rename_with_retries(
+  temp_dir: &Path,
+  output_folder: &Path,
+) -> Result<(), std::io::Error> {
+  fn already_exists(err: &std::io::Error, output_folder: &Path) -> bool {
+    // Windows will do an "Access is denied" error
+    err.kind() == ErrorKind::AlreadyExists || output_folder.exists()
+  }
+
+  let mut count = 0;
+  // renaming might be flaky if a lot of processes are trying
+  // to do this, so retry a few times
+  loop {
+    match fs::rename(temp_dir, output_folder) {
+      Ok(_) => return Ok(()),
+      Err(err) if already_exists(&err, output_folder) => {
+        // another process copied here, just cleanup
+        let _ = fs::remove_dir_all(temp_dir);
+        return Ok(());
+      }
+      Err(err) => {
+        count += 1;
+        if count > 5 {
+          // too many retries, cleanup and return the error
+          let _ = fs::remove_dir_all(temp_dir);
+          return Err(err);
+        }
+
+        // wait a bit before retrying... this should be very rare or only
+        // in error cases, so ok to sleep a bit
+        let sleep_ms = std::cmp::min(100, 20 * count);
+        std::thread::sleep(Duration::from_millis(sleep_ms));
+      }
+    }
+  }
 }
 
-fn verify_tarball_integrity(
+pub fn verify_tarball_integrity(
   package: &PackageNv,
   data: &[u8],
   npm_integrity: &NpmPackageVersionDistInfoIntegrity,
@@ -84,7 +163,61 @@ fn verify_tarball_integrity(
   Ok(())
 }
 
+/// Extracts the tarball of a prebuilt native addon, where its paths are
+/// relative to the package folder. The tarball is extracted into a temporary
+/// folder outside of the package folder first and only the native addons of
+/// its "build" folder are moved into the package folder, so the tarball can't
+/// replace the code of the package.
+pub fn extract_prebuild_tarball(
+  data: &[u8],
+  package_folder: &Path,
+  temp_folder: &Path,
+) -> Result<(), AnyError> {
+  let _ = fs::remove_dir_all(temp_folder);
+  let result = extract_tarball_with_components_skipped(data, temp_folder, 0)
+    .and_then(|()| move_native_addons(temp_folder, package_folder));
+  let _ = fs::remove_dir_all(temp_folder);
+  result
+}
+
+fn move_native_addons(
+  from_folder: &Path,
+  package_folder: &Path,
+) -> Result<(), AnyError> {
+  let mut addon_paths = Vec::new();
+  for entry in walkdir::WalkDir::new(from_folder.join("build")) {
+    let entry = entry?;
+    if entry.file_type().is_file()
+      && entry
+        .path()
+        .extension()
+        .map(|e| e == "node")
+        .unwrap_or(false)
+    {
+      addon_paths.push(entry.path().strip_prefix(from_folder)?.to_path_buf());
+    }
+  }
+  if addon_paths.is_empty() {
+    bail!("The prebuilt binary doesn't contain a native addon.");
+  }
+  for relative_path in addon_paths {
+    let to_path = package_folder.join(&relative_path);
+    fs::create_dir_all(to_path.parent().unwrap())?;
+    fs::rename(from_folder.join(&relative_path), &to_path)?;
+  }
+  Ok(())
+}
+
 fn extract_tarball(data: &[u8], output_folder: &Path) -> Result<(), AnyError> {
+  // skip the first component which will be either "package" or the name of the package
+  extract_tarball_with_components_skipped(data, output_folder, 1)
+}
+
+fn extract_tarball_with_components_skipped(
+  data: &[u8],
+  output_folder: &Path,
+  skipped_components: usize,
+) -> Result<(), AnyError> {
   fs::create_dir_all(output_folder)?;
   let output_folder = fs::canonicalize(output_folder)?;
   let tar = GzDecoder::new(data);
@@ -104,8 +237,13 @@ fn extract_tarball(data: &[u8], output_folder: &Path) -> Result<(), AnyError> {
       continue;
     }
 
-    // skip the first component which will be either "package" or the name of the package
-    let relative_path = path.components().skip(1).collect::<PathBuf>();
+    let relative_path = path
+      .components()
+      .skip(skipped_components)
+      .collect::<PathBuf>();
+    if relative_path.as_os_str().is_empty() {
+      continue;
+    }
     let absolute_path = output_folder.join(relative_path);
     let dir_path = if entry_type == EntryType::Directory {
       absolute_path.as_path()
@@ -150,9 +288,73 @@ fn extract_tarball(data: &[u8], output_folder: &Path) -> Result<(), AnyError> {
 #[cfg(test)]
 mod test {
   use deno_semver::Version;
+  use test_util::TempDir;
 
   use super::*;
 
+  #[test]
+  fn test_extract_prebuild_tarball() {
+    let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+      Vec::new(),
+      flate2::Compression::default(),
+    ));
+    for (path, data) in [
+      ("build/Release/addon.node", "addon"),
+      ("build/Release/obj/addon.o", "object"),
+      ("package.json", "replaced"),
+    ] {
+      let mut header = tar::Header::new_gnu();
+      header.set_size(data.len() as u64);
+      header.set_mode(0o644);
+      header.set_cksum();
+      builder
+        .append_data(&mut header, path, data.as_bytes())
+        .unwrap();
+    }
+    let data = builder.into_inner().unwrap().finish().unwrap();
+
+    let temp_dir = TempDir::new();
+    let package_folder = temp_dir.path().join("node_modules/addon");
+    let temp_folder = temp_dir.path().join(".prebuild");
+    package_folder.create_dir_all();
+    package_folder.join("package.json").write("{}");
+    extract_prebuild_tarball(
+      &data,
+      package_folder.as_path(),
+      temp_folder.as_path(),
+    )
+    .unwrap();
+    package_folder
+      .join("build/Release/addon.node")
+      .assert_matches_text("addon");
+    package_folder
+      .join("package.json")
+      .assert_matches_text("{}");
+    assert!(!package_folder.join("build/Release/obj").exists());
+    assert!(!temp_folder.exists());
+
+    // a tarball without a native addon fails
+    let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+      Vec::new(),
+      flate2::Compression::default(),
+    ));
+    let mut header = tar::Header::new_gnu();
+    header.set_size(0);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder
+      .append_data(&mut header, "index.js", &[] as &[u8])
+      .unwrap();
+    let data = builder.into_inner().unwrap().finish().unwrap();
+    assert!(extract_prebuild_tarball(
+      &data,
+      package_folder.as_path(),
+      temp_folder.as_path(),
+    )
+    .is_err());
+    assert!(!package_folder.join("index.js").exists());
+  }
+
   #[test]
   pub fn test_verify_tarball() {
     let package = PackageNv {
@@ -240,4 +442,27 @@ mod test {
     )
     .is_ok());
   }
+
+  #[test]
+  fn rename_with_retries_succeeds_exists() {
+    let temp_dir = TempDir::new();
+    let folder_1 = temp_dir.path().join("folder_1");
+    let folder_2 = temp_dir.path().join("folder_2");
+
+    folder_1.create_dir_all();
+    folder_1.join("a.txt").write("test");
+    folder_2.create_dir_all();
+    // this will not end up in the output as rename_with_retries assumes
+    // the folders ending up at the destination are the same
+    folder_2.join("b.txt").write("test2");
+
+    let dest_folder = temp_dir.path().join("dest_folder");
+
+    rename_with_retries(folder_1.as_path(), dest_folder.as_path()).unwrap();
+    rename_with_retries(folder_2.as_path(), dest_folder.as_path()).unwrap();
+    assert!(dest_folder.join("a.txt").exists());
+    assert!(!dest_folder.join("b.txt").exists());
+    assert!(!folder_1.exists());
+    assert!(!folder_2.exists());
+  }
 }
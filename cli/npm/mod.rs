@@ -16,6 +16,8 @@ use deno_semver::package::PackageReq;
 pub use self::byonm::ByonmCliNpmResolver;
 pub use self::byonm::CliNpmResolverByonmCreateOptions;
 pub use self::cache_dir::NpmCacheDir;
+pub use self::managed::vendored_registry_file_path;
+pub use self::managed::verify_tarball_integrity;
 pub use self::managed::CliNpmResolverManagedCreateOptions;
 pub use self::managed::CliNpmResolverManagedPackageJsonInstallerOption;
 pub use self::managed::CliNpmResolverManagedSnapshotOption;
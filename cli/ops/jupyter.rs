@@ -6,6 +6,7 @@ use std::sync::Arc;
 
 use crate::tools::jupyter::jupyter_msg::Connection;
 use crate::tools::jupyter::jupyter_msg::JupyterMessage;
+use crate::tools::jupyter::server::CommTargets;
 use crate::tools::jupyter::server::StdioMsg;
 use deno_core::error::AnyError;
 use deno_core::op2;
@@ -39,16 +40,31 @@ pub async fn op_jupyter_broadcast(
   #[serde] metadata: serde_json::Value,
   #[serde] buffers: Vec<deno_core::JsBuffer>,
 ) -> Result<(), AnyError> {
-  let (iopub_socket, last_execution_request) = {
+  let (iopub_socket, last_execution_request, comm_targets) = {
     let s = state.borrow();
 
     (
       s.borrow::<Arc<Mutex<Connection<zeromq::PubSocket>>>>()
         .clone(),
       s.borrow::<Rc<RefCell<Option<JupyterMessage>>>>().clone(),
+      s.borrow::<Rc<RefCell<CommTargets>>>().clone(),
     )
   };
 
+  // keep track of the comms opened by the kernel for "comm_info_request"
+  let comm_id = content["comm_id"].as_str().unwrap_or_default();
+  match message_type.as_str() {
+    "comm_open" => comm_targets.borrow_mut().insert(
+      comm_id.to_string(),
+      content["target_name"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string(),
+    ),
+    "comm_close" => comm_targets.borrow_mut().remove(comm_id),
+    _ => {}
+  }
+
   let maybe_last_request = last_execution_request.borrow().clone();
   if let Some(last_request) = maybe_last_request {
     last_request
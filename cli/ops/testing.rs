@@ -1,6 +1,8 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
 use crate::tools::test::fmt::OP_DETAILS;
+use crate::tools::test::snapshot::TestSnapshots;
+use crate::tools::test::snapshot::UNSTABLE_FEATURE_NAME;
 use crate::tools::test::TestDescription;
 use crate::tools::test::TestEvent;
 use crate::tools::test::TestEventSender;
@@ -45,6 +47,7 @@ deno_core::extension!(deno_test,
     op_test_event_step_result_ok,
     op_test_event_step_result_ignored,
     op_test_event_step_result_failed,
+    op_test_snapshot_assert,
     op_test_op_sanitizer_collect,
     op_test_op_sanitizer_finish,
     op_test_op_sanitizer_report,
@@ -57,6 +60,7 @@ deno_core::extension!(deno_test,
     state.put(options.sender);
     state.put(TestContainer::default());
     state.put(TestOpSanitizers::default());
+    state.put(TestSnapshots::default());
   },
 );
 
@@ -130,6 +134,7 @@ fn op_register_test(
   #[string] file_name: String,
   #[smi] line_number: u32,
   #[smi] column_number: u32,
+  #[smi] retry: u32,
   #[buffer] ret_buf: &mut [u8],
 ) -> Result<String, AnyError> {
   if ret_buf.len() != 4 {
@@ -147,6 +152,7 @@ fn op_register_test(
     only,
     sanitize_ops,
     sanitize_resources,
+    retry: retry as usize,
     origin: origin.clone(),
     location: TestLocation {
       file_name,
@@ -205,6 +211,22 @@ fn op_test_event_step_wait(state: &mut OpState, #[smi] id: usize) {
   sender.send(TestEvent::StepWait(id)).ok();
 }
 
+#[op2]
+fn op_test_snapshot_assert(
+  state: &mut OpState,
+  #[string] origin: &str,
+  #[string] name: &str,
+  #[string] actual: &str,
+) -> Result<(), AnyError> {
+  state
+    .feature_checker
+    .check_or_exit(UNSTABLE_FEATURE_NAME, "t.assertSnapshot");
+  let origin = ModuleSpecifier::parse(origin)?;
+  state
+    .borrow_mut::<TestSnapshots>()
+    .assert(&origin, name, actual)
+}
+
 #[op2(fast)]
 fn op_test_event_step_result_ok(
   state: &mut OpState,
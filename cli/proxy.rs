@@ -0,0 +1,751 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::HashMap;
+use std::env;
+use std::net::IpAddr;
+use std::net::ToSocketAddrs;
+use std::net::UdpSocket;
+use std::sync::mpsc;
+
+use deno_core::anyhow::anyhow;
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+use deno_core::op2;
+use deno_core::parking_lot::Mutex;
+use deno_core::serde_json;
+use deno_core::serde_v8;
+use deno_core::url::Url;
+use deno_core::v8;
+use deno_core::JsRuntime;
+use deno_core::RuntimeOptions;
+use deno_runtime::deno_fetch::create_http_client;
+use deno_runtime::deno_fetch::CreateHttpClientOptions;
+use deno_runtime::deno_tls::RootCertStoreProvider;
+use tokio::sync::oneshot;
+use tokio::sync::OnceCell;
+
+use crate::version::get_user_agent;
+
+/// A pattern of hosts, which is used by the `NO_PROXY` environment variable
+/// and by the proxy rules of the configuration file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HostPattern {
+  /// `*`, which matches every host.
+  Any,
+  /// A host which matches itself and its subdomains, like `example.com` or
+  /// `.example.com`, or a pattern with `*` wildcards like `*.example.com`. It
+  /// only matches the port when one is specified.
+  Host { pattern: String, port: Option<u16> },
+  /// An IP address or a range of them, like `10.0.0.0/8`.
+  Ip { ip: IpAddr, prefix_len: u8 },
+}
+
+impl HostPattern {
+  pub fn parse(text: &str) -> Result<Self, AnyError> {
+    let text = text.trim().to_lowercase();
+    if text.is_empty() {
+      bail!("The host pattern is empty.");
+    }
+    if text == "*" {
+      return Ok(Self::Any);
+    }
+    if let Some((ip, prefix_len)) = text.split_once('/') {
+      let ip = ip.trim_start_matches('[').trim_end_matches(']');
+      let ip = ip
+        .parse::<IpAddr>()
+        .map_err(|_| anyhow!("Invalid IP address range \"{}\".", text))?;
+      let max_prefix_len = if ip.is_ipv4() { 32 } else { 128 };
+      let prefix_len = prefix_len
+        .parse::<u8>()
+        .ok()
+        .filter(|prefix_len| *prefix_len <= max_prefix_len)
+        .ok_or_else(|| anyhow!("Invalid IP address range \"{}\".", text))?;
+      return Ok(Self::Ip { ip, prefix_len });
+    }
+    let unbracketed = text.trim_start_matches('[').trim_end_matches(']');
+    if let Ok(ip) = unbracketed.parse::<IpAddr>() {
+      let prefix_len = if ip.is_ipv4() { 32 } else { 128 };
+      return Ok(Self::Ip { ip, prefix_len });
+    }
+    let (host, port) = match text.rsplit_once(':') {
+      Some((host, port)) => {
+        let port = port
+          .parse::<u16>()
+          .map_err(|_| anyhow!("Invalid port of the host \"{}\".", text))?;
+        (host, Some(port))
+      }
+      None => (text.as_str(), None),
+    };
+    Ok(Self::Host {
+      pattern: host.trim_start_matches('.').to_string(),
+      port,
+    })
+  }
+
+  /// Parses the comma separated patterns of `NO_PROXY`, skipping the invalid
+  /// ones like curl does.
+  pub fn parse_list(text: &str) -> Vec<Self> {
+    text
+      .split(',')
+      .filter(|part| !part.trim().is_empty())
+      .filter_map(|part| Self::parse(part).ok())
+      .collect()
+  }
+
+  pub fn matches(&self, host: &str, port: Option<u16>) -> bool {
+    let host = host.trim_end_matches('.').to_lowercase();
+    match self {
+      Self::Any => true,
+      Self::Ip { ip, prefix_len } => {
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        match host.parse::<IpAddr>() {
+          Ok(host_ip) => ip_in_range(host_ip, *ip, *prefix_len),
+          Err(_) => false,
+        }
+      }
+      Self::Host {
+        pattern,
+        port: pattern_port,
+      } => {
+        if pattern_port.is_some() && *pattern_port != port {
+          return false;
+        }
+        if pattern.contains('*') {
+          wildcard_match(pattern, &host)
+        } else {
+          host == *pattern || host.ends_with(&format!(".{}", pattern))
+        }
+      }
+    }
+  }
+}
+
+fn ip_in_range(ip: IpAddr, range_ip: IpAddr, prefix_len: u8) -> bool {
+  match (ip, range_ip) {
+    (IpAddr::V4(ip), IpAddr::V4(range_ip)) => {
+      let mask = u32::MAX.checked_shl(32 - prefix_len as u32).unwrap_or(0);
+      u32::from(ip) & mask == u32::from(range_ip) & mask
+    }
+    (IpAddr::V6(ip), IpAddr::V6(range_ip)) => {
+      let mask = u128::MAX.checked_shl(128 - prefix_len as u32).unwrap_or(0);
+      u128::from(ip) & mask == u128::from(range_ip) & mask
+    }
+    _ => false,
+  }
+}
+
+/// Matches the text against a pattern where `*` matches any characters.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+  let mut parts = pattern.split('*');
+  let first = parts.next().unwrap_or("");
+  let Some(mut rest) = text.strip_prefix(first) else {
+    return false;
+  };
+  let mut parts = parts.collect::<Vec<_>>();
+  let last = parts.pop();
+  for part in parts {
+    match rest.find(part) {
+      Some(index) => rest = &rest[index + part.len()..],
+      None => return false,
+    }
+  }
+  match last {
+    Some(last) => rest.ends_with(last),
+    None => rest.is_empty(),
+  }
+}
+
+/// Where the requests to a host go.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProxyTarget {
+  Direct,
+  Proxy(Url),
+}
+
+impl ProxyTarget {
+  /// Parses either `DIRECT` or the URL of a proxy, where the scheme defaults
+  /// to `http`.
+  pub fn parse(text: &str) -> Result<Self, AnyError> {
+    let text = text.trim();
+    if text.eq_ignore_ascii_case("direct") {
+      return Ok(Self::Direct);
+    }
+    let url = parse_proxy_url(text)
+      .ok_or_else(|| anyhow!("Invalid proxy \"{}\".", text))?;
+    Ok(Self::Proxy(url))
+  }
+
+  fn into_url(self) -> Option<Url> {
+    match self {
+      Self::Direct => None,
+      Self::Proxy(url) => Some(url),
+    }
+  }
+}
+
+fn parse_proxy_url(text: &str) -> Option<Url> {
+  let url = if text.contains("://") {
+    Url::parse(text).ok()?
+  } else {
+    Url::parse(&format!("http://{}", text)).ok()?
+  };
+  match url.scheme() {
+    "http" | "https" | "socks5" | "socks5h" => Some(url),
+    _ => None,
+  }
+}
+
+/// The proxies of the `HTTP_PROXY`, `HTTPS_PROXY`, `ALL_PROXY` and `NO_PROXY`
+/// environment variables, which are also read in lower case.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProxyEnv {
+  pub http_proxy: Option<Url>,
+  pub https_proxy: Option<Url>,
+  pub no_proxy: Vec<HostPattern>,
+}
+
+impl ProxyEnv {
+  pub fn from_env() -> Self {
+    fn var(name: &str) -> Option<String> {
+      env::var(name)
+        .ok()
+        .or_else(|| env::var(name.to_lowercase()).ok())
+        .filter(|value| !value.trim().is_empty())
+    }
+    let all_proxy = var("ALL_PROXY").and_then(|text| parse_proxy_url(&text));
+    Self {
+      http_proxy: var("HTTP_PROXY")
+        .and_then(|text| parse_proxy_url(&text))
+        .or_else(|| all_proxy.clone()),
+      https_proxy: var("HTTPS_PROXY")
+        .and_then(|text| parse_proxy_url(&text))
+        .or(all_proxy),
+      no_proxy: var("NO_PROXY")
+        .map(|text| HostPattern::parse_list(&text))
+        .unwrap_or_default(),
+    }
+  }
+
+  /// Gets if `NO_PROXY` has a pattern with wildcards, which the HTTP client
+  /// doesn't support by itself.
+  pub fn has_no_proxy_wildcards(&self) -> bool {
+    self.no_proxy.iter().any(|no_proxy| {
+      matches!(no_proxy, HostPattern::Host { pattern, .. } if pattern.contains('*'))
+    })
+  }
+}
+
+/// Resolves the proxy of every request of the HTTP client. The proxy rules of
+/// the hosts in the configuration file come first, then the hosts of
+/// `NO_PROXY`, the proxy auto-config file and finally the proxies of the
+/// environment.
+#[derive(Debug)]
+pub struct ProxyRules {
+  hosts: Vec<(HostPattern, ProxyTarget)>,
+  maybe_pac: Option<PacEvaluator>,
+  env: ProxyEnv,
+}
+
+impl ProxyRules {
+  pub fn new(
+    hosts: Vec<(HostPattern, ProxyTarget)>,
+    maybe_pac: Option<PacEvaluator>,
+    env: ProxyEnv,
+  ) -> Self {
+    Self {
+      hosts,
+      maybe_pac,
+      env,
+    }
+  }
+
+  /// Gets the proxy of the URL, where `None` connects directly. The proxy
+  /// auto-config file is only used once `prepare` evaluated it for the origin
+  /// of the URL, because this runs while the HTTP client connects.
+  pub fn resolve(&self, url: &Url) -> Option<Url> {
+    let host = url.host_str()?;
+    let port = url.port_or_known_default();
+    for (pattern, target) in &self.hosts {
+      if pattern.matches(host, port) {
+        return target.clone().into_url();
+      }
+    }
+    if self.env.no_proxy.iter().any(|p| p.matches(host, port)) {
+      return None;
+    }
+    if let Some(pac) = &self.maybe_pac {
+      match pac.cached_proxy(url) {
+        Some(target) => return target.into_url(),
+        None => {
+          log::debug!(
+            "The proxy auto-config wasn't evaluated for {}.",
+            url.origin().ascii_serialization()
+          );
+        }
+      }
+    }
+    match url.scheme() {
+      "http" => self.env.http_proxy.clone(),
+      "https" => self.env.https_proxy.clone(),
+      _ => None,
+    }
+  }
+
+  /// Evaluates the proxy auto-config file for the origin of the URL before
+  /// it's requested, when neither the rules of the hosts nor `NO_PROXY`
+  /// apply to it. The file is downloaded with the root certificates of the
+  /// provider.
+  pub async fn prepare(
+    &self,
+    url: &Url,
+    root_cert_store_provider: Option<&dyn RootCertStoreProvider>,
+  ) {
+    let Some(pac) = &self.maybe_pac else {
+      return;
+    };
+    let Some(host) = url.host_str() else {
+      return;
+    };
+    let port = url.port_or_known_default();
+    if self.hosts.iter().any(|(p, _)| p.matches(host, port))
+      || self.env.no_proxy.iter().any(|p| p.matches(host, port))
+    {
+      return;
+    }
+    if let Err(err) = pac.find_proxy(url, root_cert_store_provider).await {
+      log::debug!("Failed evaluating the proxy auto-config. {:#}", err);
+    }
+  }
+}
+
+type PacRequest = (Url, oneshot::Sender<Result<String, AnyError>>);
+
+/// A proxy auto-config file, whose `FindProxyForURL` function is evaluated in
+/// a separate isolate on its own thread. The thread is started and the file
+/// is loaded when the first proxy is evaluated.
+#[derive(Debug)]
+pub struct PacEvaluator {
+  url: Url,
+  sender: OnceCell<mpsc::Sender<PacRequest>>,
+  /// The proxies by origin, because the file is only evaluated once per
+  /// origin.
+  cache: Mutex<HashMap<String, ProxyTarget>>,
+}
+
+impl PacEvaluator {
+  /// Creates an evaluator of the file at the URL, which is either a `file:`
+  /// URL or a remote URL which is downloaded without a proxy.
+  pub fn new(url: Url) -> Self {
+    Self {
+      url,
+      sender: Default::default(),
+      cache: Default::default(),
+    }
+  }
+
+  #[cfg(test)]
+  fn from_script(script: String) -> Self {
+    let evaluator = Self::new(Url::parse("data:,").unwrap());
+    evaluator.sender.set(spawn_pac_thread(Ok(script))).unwrap();
+    evaluator
+  }
+
+  /// Gets the proxy of the origin of the URL, if it was already evaluated.
+  fn cached_proxy(&self, url: &Url) -> Option<ProxyTarget> {
+    let origin = url.origin().ascii_serialization();
+    self.cache.lock().get(&origin).cloned()
+  }
+
+  async fn find_proxy(
+    &self,
+    url: &Url,
+    root_cert_store_provider: Option<&dyn RootCertStoreProvider>,
+  ) -> Result<ProxyTarget, AnyError> {
+    if let Some(target) = self.cached_proxy(url) {
+      return Ok(target);
+    }
+    let sender = self
+      .sender
+      .get_or_init(|| async {
+        spawn_pac_thread(
+          load_pac_script(&self.url, root_cert_store_provider).await,
+        )
+      })
+      .await;
+    let (reply_sender, reply_receiver) = oneshot::channel();
+    sender
+      .send((url.clone(), reply_sender))
+      .map_err(|_| anyhow!("The proxy auto-config thread stopped."))?;
+    let result = reply_receiver
+      .await
+      .map_err(|_| anyhow!("The proxy auto-config thread stopped."))??;
+    let target = parse_pac_result(&result);
+    let origin = url.origin().ascii_serialization();
+    self.cache.lock().insert(origin, target.clone());
+    Ok(target)
+  }
+}
+
+async fn load_pac_script(
+  url: &Url,
+  root_cert_store_provider: Option<&dyn RootCertStoreProvider>,
+) -> Result<String, AnyError> {
+  if url.scheme() == "file" {
+    let path = url
+      .to_file_path()
+      .map_err(|_| anyhow!("Invalid file URL \"{}\".", url))?;
+    return tokio::fs::read_to_string(&path).await.map_err(|err| {
+      anyhow!("Failed reading '{}'. {:#}", path.display(), err)
+    });
+  }
+  let client = create_http_client(
+    get_user_agent(),
+    CreateHttpClientOptions {
+      root_cert_store: match root_cert_store_provider {
+        Some(provider) => Some(provider.get_or_try_init()?.clone()),
+        None => None,
+      },
+      ..Default::default()
+    },
+  )?;
+  let response = client.get(url.clone()).send().await?;
+  if !response.status().is_success() {
+    bail!("Bad response: {:?}", response.status());
+  }
+  Ok(response.text().await?)
+}
+
+fn spawn_pac_thread(
+  script: Result<String, AnyError>,
+) -> mpsc::Sender<PacRequest> {
+  let (sender, receiver) = mpsc::channel::<PacRequest>();
+  std::thread::spawn(move || {
+    let mut runtime = JsRuntime::new(RuntimeOptions {
+      extensions: vec![deno_pac::init_ops()],
+      ..Default::default()
+    });
+    let init_result = script.and_then(|script| {
+      runtime.execute_script(
+        "ext:deno_pac/pac.js",
+        format!("{}\n{}", PAC_PRELUDE, script).into(),
+      )?;
+      Ok(())
+    });
+    for (url, reply_sender) in receiver {
+      let result = match &init_result {
+        Ok(()) => evaluate_find_proxy_for_url(&mut runtime, &url),
+        Err(err) => Err(anyhow!("{:#}", err)),
+      };
+      let _ = reply_sender.send(result);
+    }
+  });
+  sender
+}
+
+fn evaluate_find_proxy_for_url(
+  runtime: &mut JsRuntime,
+  url: &Url,
+) -> Result<String, AnyError> {
+  let host = url.host_str().unwrap_or("");
+  let code = format!(
+    "FindProxyForURL({}, {})",
+    serde_json::to_string(url.as_str())?,
+    serde_json::to_string(host)?
+  );
+  let value = runtime.execute_script("ext:deno_pac/find.js", code.into())?;
+  let scope = &mut runtime.handle_scope();
+  let local = v8::Local::new(scope, value);
+  serde_v8::from_v8::<String>(scope, local)
+    .map_err(|_| anyhow!("FindProxyForURL didn't return a string."))
+}
+
+/// Parses the first supported proxy of a result of `FindProxyForURL`, like
+/// `PROXY proxy.example.com:8080; DIRECT`.
+fn parse_pac_result(result: &str) -> ProxyTarget {
+  for entry in result.split(';') {
+    let mut parts = entry.split_whitespace();
+    let Some(kind) = parts.next() else {
+      continue;
+    };
+    let scheme = match kind.to_uppercase().as_str() {
+      "DIRECT" => return ProxyTarget::Direct,
+      "PROXY" | "HTTP" => "http",
+      "HTTPS" => "https",
+      "SOCKS" | "SOCKS5" => "socks5",
+      _ => continue,
+    };
+    let Some(host) = parts.next() else {
+      continue;
+    };
+    if let Ok(url) = Url::parse(&format!("{}://{}", scheme, host)) {
+      return ProxyTarget::Proxy(url);
+    }
+  }
+  ProxyTarget::Direct
+}
+
+/// The functions which proxy auto-config files may use, except the ones
+/// depending on the time.
+const PAC_PRELUDE: &str = r#"
+function isPlainHostName(host) {
+  return host.indexOf(".") === -1;
+}
+function dnsDomainIs(host, domain) {
+  return host.length >= domain.length &&
+    host.substring(host.length - domain.length) === domain;
+}
+function localHostOrDomainIs(host, hostdom) {
+  return host === hostdom || hostdom.lastIndexOf(host + ".", 0) === 0;
+}
+function dnsResolve(host) {
+  return Deno.core.ops.op_pac_dns_resolve(host);
+}
+function isResolvable(host) {
+  return dnsResolve(host) !== null;
+}
+function myIpAddress() {
+  return Deno.core.ops.op_pac_my_ip_address();
+}
+function dnsDomainLevels(host) {
+  return host.split(".").length - 1;
+}
+function shExpMatch(str, shexp) {
+  const source = shexp
+    .replace(/[.+^${}()|[\]\\]/g, "\\$&")
+    .replace(/\*/g, ".*")
+    .replace(/\?/g, ".");
+  return new RegExp("^" + source + "$").test(str);
+}
+function isInNet(host, pattern, mask) {
+  const ip = /^\d+\.\d+\.\d+\.\d+$/.test(host) ? host : dnsResolve(host);
+  if (ip === null) {
+    return false;
+  }
+  const toInt = (ip) =>
+    ip.split(".").reduce((n, part) => (n << 8) + Number(part), 0) >>> 0;
+  const maskInt = toInt(mask);
+  return ((toInt(ip) & maskInt) >>> 0) === ((toInt(pattern) & maskInt) >>> 0);
+}
+"#;
+
+#[op2]
+#[serde]
+fn op_pac_dns_resolve(#[string] host: String) -> Option<String> {
+  (host.as_str(), 0)
+    .to_socket_addrs()
+    .ok()?
+    .find(|addr| addr.is_ipv4())
+    .map(|addr| addr.ip().to_string())
+}
+
+#[op2]
+#[string]
+fn op_pac_my_ip_address() -> String {
+  // connecting a UDP socket doesn't send anything, but selects the local
+  // address of the default route
+  UdpSocket::bind("0.0.0.0:0")
+    .and_then(|socket| {
+      socket.connect("8.8.8.8:80")?;
+      socket.local_addr()
+    })
+    .map(|addr| addr.ip().to_string())
+    .unwrap_or_else(|_| "127.0.0.1".to_string())
+}
+
+deno_core::extension!(
+  deno_pac,
+  ops = [op_pac_dns_resolve, op_pac_my_ip_address]
+);
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  fn url(text: &str) -> Url {
+    Url::parse(text).unwrap()
+  }
+
+  #[test]
+  fn host_pattern_matches() {
+    let cases = [
+      ("*", "deno.land", None, true),
+      ("deno.land", "deno.land", None, true),
+      ("deno.land", "www.deno.land", None, true),
+      (".deno.land", "www.deno.land", None, true),
+      ("deno.land", "notdeno.land", None, false),
+      ("*.deno.land", "www.deno.land", None, true),
+      ("*.deno.land", "deno.land", None, false),
+      ("registry-*.corp.com", "registry-eu.corp.com", None, true),
+      ("registry-*.corp.com", "cdn.corp.com", None, false),
+      ("deno.land:8080", "deno.land", Some(8080), true),
+      ("deno.land:8080", "deno.land", Some(443), false),
+      ("10.0.0.0/8", "10.1.2.3", None, true),
+      ("10.0.0.0/8", "11.1.2.3", None, false),
+      ("127.0.0.1", "127.0.0.1", None, true),
+      ("::1", "[::1]", None, true),
+      ("fd00::/8", "[fd12::1]", None, true),
+    ];
+    for (pattern, host, port, expected) in cases {
+      assert_eq!(
+        HostPattern::parse(pattern).unwrap().matches(host, port),
+        expected,
+        "{} {}",
+        pattern,
+        host
+      );
+    }
+    assert!(HostPattern::parse("10.0.0.0/33").is_err());
+    assert!(HostPattern::parse("deno.land:port").is_err());
+    assert_eq!(
+      HostPattern::parse_list("localhost, ,*.corp.com,bad:port"),
+      vec![
+        HostPattern::parse("localhost").unwrap(),
+        HostPattern::parse("*.corp.com").unwrap(),
+      ]
+    );
+  }
+
+  #[test]
+  fn proxy_rules_resolve() {
+    let rules = ProxyRules::new(
+      vec![
+        (
+          HostPattern::parse("jsr.io").unwrap(),
+          ProxyTarget::parse("jsr-proxy:3128").unwrap(),
+        ),
+        (
+          HostPattern::parse("*.npmjs.org").unwrap(),
+          ProxyTarget::parse("socks5://npm-proxy:1080").unwrap(),
+        ),
+        (
+          HostPattern::parse("deno.land").unwrap(),
+          ProxyTarget::parse("DIRECT").unwrap(),
+        ),
+      ],
+      None,
+      ProxyEnv {
+        http_proxy: Some(url("http://http-proxy:3128")),
+        https_proxy: Some(url("http://https-proxy:3128")),
+        no_proxy: HostPattern::parse_list("*.internal,registry.npmjs.org"),
+      },
+    );
+    assert_eq!(
+      rules.resolve(&url("https://jsr.io/@std/path/meta.json")),
+      Some(url("http://jsr-proxy:3128"))
+    );
+    // the rules of the hosts take precedence over NO_PROXY
+    assert_eq!(
+      rules.resolve(&url("https://registry.npmjs.org/chalk")),
+      Some(url("socks5://npm-proxy:1080"))
+    );
+    assert_eq!(rules.resolve(&url("https://deno.land/x/mod.ts")), None);
+    assert_eq!(
+      rules.resolve(&url("https://git.corp.internal/mod.ts")),
+      None
+    );
+    assert_eq!(
+      rules.resolve(&url("https://example.com/mod.ts")),
+      Some(url("http://https-proxy:3128"))
+    );
+    assert_eq!(
+      rules.resolve(&url("http://example.com/mod.ts")),
+      Some(url("http://http-proxy:3128"))
+    );
+    assert!(ProxyTarget::parse("ftp://proxy").is_err());
+  }
+
+  #[tokio::test]
+  async fn proxy_rules_prepare() {
+    let rules = ProxyRules::new(
+      vec![(
+        HostPattern::parse("jsr.io").unwrap(),
+        ProxyTarget::parse("jsr-proxy:3128").unwrap(),
+      )],
+      Some(PacEvaluator::from_script(
+        r#"function FindProxyForURL(url, host) {
+          return "PROXY pac-proxy:3128";
+        }"#
+          .to_string(),
+      )),
+      ProxyEnv {
+        http_proxy: None,
+        https_proxy: Some(url("http://https-proxy:3128")),
+        no_proxy: Vec::new(),
+      },
+    );
+    let fixture = url("https://deno.land/x/mod.ts");
+    // the environment is used until the auto-config is evaluated
+    assert_eq!(
+      rules.resolve(&fixture),
+      Some(url("http://https-proxy:3128"))
+    );
+    rules.prepare(&fixture, None).await;
+    assert_eq!(rules.resolve(&fixture), Some(url("http://pac-proxy:3128")));
+    let fixture = url("https://jsr.io/@std/path/meta.json");
+    rules.prepare(&fixture, None).await;
+    assert_eq!(rules.resolve(&fixture), Some(url("http://jsr-proxy:3128")));
+  }
+
+  #[test]
+  fn pac_result_parse() {
+    assert_eq!(parse_pac_result("DIRECT"), ProxyTarget::Direct);
+    assert_eq!(
+      parse_pac_result("PROXY proxy.corp.com:8080; DIRECT"),
+      ProxyTarget::Proxy(url("http://proxy.corp.com:8080"))
+    );
+    assert_eq!(
+      parse_pac_result("SOCKS4 old:1080; SOCKS5 socks:1080"),
+      ProxyTarget::Proxy(url("socks5://socks:1080"))
+    );
+    assert_eq!(parse_pac_result(""), ProxyTarget::Direct);
+  }
+
+  #[tokio::test]
+  async fn pac_evaluator_find_proxy() {
+    let pac = PacEvaluator::from_script(
+      r#"function FindProxyForURL(url, host) {
+        if (isPlainHostName(host) || shExpMatch(host, "*.corp.com")) {
+          return "DIRECT";
+        }
+        if (dnsDomainIs(host, ".npmjs.org")) {
+          return "PROXY npm-proxy:3128";
+        }
+        return "PROXY default-proxy:3128; DIRECT";
+      }"#
+        .to_string(),
+    );
+    assert_eq!(
+      pac
+        .find_proxy(&url("https://registry.npmjs.org/chalk"), None)
+        .await
+        .unwrap(),
+      ProxyTarget::Proxy(url("http://npm-proxy:3128"))
+    );
+    assert_eq!(
+      pac
+        .find_proxy(&url("https://git.corp.com/mod.ts"), None)
+        .await
+        .unwrap(),
+      ProxyTarget::Direct
+    );
+    assert_eq!(
+      pac
+        .find_proxy(&url("http://localhost/mod.ts"), None)
+        .await
+        .unwrap(),
+      ProxyTarget::Direct
+    );
+    assert_eq!(
+      pac
+        .find_proxy(&url("https://jsr.io/@std/path"), None)
+        .await
+        .unwrap(),
+      ProxyTarget::Proxy(url("http://default-proxy:3128"))
+    );
+
+    let pac = PacEvaluator::from_script("syntax error(".to_string());
+    assert!(pac.find_proxy(&url("https://jsr.io"), None).await.is_err());
+    assert_eq!(pac.cached_proxy(&url("https://jsr.io")), None);
+  }
+}
@@ -141,15 +141,54 @@ pub struct Metadata {
   pub node_modules: Option<NodeModules>,
   pub disable_deprecated_api_warning: bool,
   pub unstable_config: UnstableConfig,
+  /// The target the executable was compiled for.
+  pub target: String,
+  pub assets: Option<EmbeddedAssets>,
 }
 
-pub fn load_npm_vfs(root_dir_path: PathBuf) -> Result<FileBackedVfs, AnyError> {
+/// The files embedded in the executable with `--include`, which are mounted
+/// at the directory they were in when compiling, so they can be read at the
+/// same paths relative to the main module.
+#[derive(Deserialize, Serialize)]
+pub struct EmbeddedAssets {
+  pub root_path: PathBuf,
+  pub dir: VirtualDirectory,
+  /// The offset of the files of the assets from the start of the npm files.
+  pub files_offset: u64,
+}
+
+/// The files to embed in the executable from the `--include` flag.
+pub struct CompileAssets {
+  /// The directory the files are mounted at, which may not be canonicalized.
+  pub root_dir: PathBuf,
+  /// The canonicalized paths of the files within the root directory.
+  pub files: Vec<PathBuf>,
+}
+
+/// Gets the path of the archive of the base binary of a target in the
+/// download folder of the DENO_DIR, which is separate for every version.
+pub fn base_binary_path_suffix(target: &str) -> String {
+  let binary_name = format!("deno-{target}.zip");
+  if crate::version::is_canary() {
+    format!("canary/{}/{}", crate::version::GIT_COMMIT_HASH, binary_name)
+  } else {
+    format!("release/v{}/{}", env!("CARGO_PKG_VERSION"), binary_name)
+  }
+}
+
+fn open_current_exe_with_trailer() -> Result<(std::fs::File, Trailer), AnyError>
+{
   let file_path = current_exe().unwrap();
   let mut file = std::fs::File::open(file_path)?;
   file.seek(SeekFrom::End(-(TRAILER_SIZE as i64)))?;
   let mut trailer = [0; TRAILER_SIZE];
   file.read_exact(&mut trailer)?;
   let trailer = Trailer::parse(&trailer)?.unwrap();
+  Ok((file, trailer))
+}
+
+pub fn load_npm_vfs(root_dir_path: PathBuf) -> Result<FileBackedVfs, AnyError> {
+  let (mut file, trailer) = open_current_exe_with_trailer()?;
   file.seek(SeekFrom::Start(trailer.npm_vfs_pos))?;
   let mut vfs_data = vec![0; trailer.npm_vfs_len() as usize];
   file.read_exact(&mut vfs_data)?;
@@ -170,6 +209,23 @@ pub fn load_npm_vfs(root_dir_path: PathBuf) -> Result<FileBackedVfs, AnyError> {
   Ok(FileBackedVfs::new(file, fs_root))
 }
 
+pub fn load_assets_vfs(
+  assets: EmbeddedAssets,
+) -> Result<FileBackedVfs, AnyError> {
+  let (file, trailer) = open_current_exe_with_trailer()?;
+  let mut dir = assets.dir;
+  // align the name of the directory with the root dir
+  if let Some(name) = assets.root_path.file_name() {
+    dir.name = name.to_string_lossy().to_string();
+  }
+  let fs_root = VfsRoot {
+    dir,
+    root_path: assets.root_path,
+    start_file_offset: trailer.npm_files_pos + assets.files_offset,
+  };
+  Ok(FileBackedVfs::new(file, fs_root))
+}
+
 fn write_binary_bytes(
   writer: &mut impl Write,
   original_bin: Vec<u8>,
@@ -177,6 +233,7 @@ fn write_binary_bytes(
   eszip: eszip::EszipV2,
   npm_vfs: Option<&VirtualDirectory>,
   npm_files: &Vec<Vec<u8>>,
+  asset_files: &Vec<Vec<u8>>,
 ) -> Result<(), AnyError> {
   let metadata = serde_json::to_string(metadata)?.as_bytes().to_vec();
   let npm_vfs = serde_json::to_string(&npm_vfs)?.as_bytes().to_vec();
@@ -189,6 +246,10 @@ fn write_binary_bytes(
   for file in npm_files {
     writer.write_all(file)?;
   }
+  // the files of the assets directly follow the npm files
+  for file in asset_files {
+    writer.write_all(file)?;
+  }
 
   // write the trailer, which includes the positions
   // of the data blocks in the file
@@ -373,6 +434,7 @@ impl<'a> DenoCompileBinaryWriter<'a> {
     module_specifier: &ModuleSpecifier,
     compile_flags: &CompileFlags,
     cli_options: &CliOptions,
+    maybe_assets: Option<&CompileAssets>,
   ) -> Result<(), AnyError> {
     // Select base binary based on target
     let mut original_binary = self.get_base_binary(compile_flags).await?;
@@ -396,6 +458,7 @@ impl<'a> DenoCompileBinaryWriter<'a> {
         module_specifier,
         cli_options,
         compile_flags,
+        maybe_assets,
       )
       .await
   }
@@ -410,13 +473,7 @@ impl<'a> DenoCompileBinaryWriter<'a> {
     }
 
     let target = compile_flags.resolve_target();
-    let binary_name = format!("deno-{target}.zip");
-
-    let binary_path_suffix = if crate::version::is_canary() {
-      format!("canary/{}/{}", crate::version::GIT_COMMIT_HASH, binary_name)
-    } else {
-      format!("release/v{}/{}", env!("CARGO_PKG_VERSION"), binary_name)
-    };
+    let binary_path_suffix = base_binary_path_suffix(&target);
 
     let download_directory = self.deno_dir.dl_folder_path();
     let binary_path = download_directory.join(&binary_path_suffix);
@@ -471,6 +528,7 @@ impl<'a> DenoCompileBinaryWriter<'a> {
 
   /// This functions creates a standalone deno binary by appending a bundle
   /// and magic trailer to the currently executing binary.
+  #[allow(clippy::too_many_arguments)]
   async fn write_standalone_binary(
     &self,
     writer: &mut impl Write,
@@ -479,6 +537,7 @@ impl<'a> DenoCompileBinaryWriter<'a> {
     entrypoint: &ModuleSpecifier,
     cli_options: &CliOptions,
     compile_flags: &CompileFlags,
+    maybe_assets: Option<&CompileAssets>,
   ) -> Result<(), AnyError> {
     let ca_data = match cli_options.ca_data() {
       Some(CaData::File(ca_file)) => Some(
@@ -530,12 +589,28 @@ impl<'a> DenoCompileBinaryWriter<'a> {
           )
         }
       };
+    let (assets, asset_files) = match maybe_assets {
+      Some(assets) => {
+        let mut builder = VfsBuilder::new(assets.root_dir.clone())?;
+        for file in &assets.files {
+          builder.add_file_at_path(file)?;
+        }
+        let (dir, files) = builder.into_dir_and_files();
+        let embedded_assets = EmbeddedAssets {
+          root_path: assets.root_dir.clone(),
+          dir,
+          files_offset: npm_files.iter().map(|f| f.len() as u64).sum(),
+        };
+        (Some(embedded_assets), files)
+      }
+      None => (None, Vec::new()),
+    };
 
     let metadata = Metadata {
       argv: compile_flags.args.clone(),
       seed: cli_options.seed(),
       location: cli_options.location_flag().clone(),
-      permissions: cli_options.permissions_options(),
+      permissions: cli_options.permissions_options()?,
       v8_flags: cli_options.v8_flags().clone(),
       unsafely_ignore_certificate_errors: cli_options
         .unsafely_ignore_certificate_errors()
@@ -555,6 +630,8 @@ impl<'a> DenoCompileBinaryWriter<'a> {
         sloppy_imports: cli_options.unstable_sloppy_imports(),
         features: cli_options.unstable_features(),
       },
+      target: compile_flags.resolve_target(),
+      assets,
     };
 
     write_binary_bytes(
@@ -564,6 +641,7 @@ impl<'a> DenoCompileBinaryWriter<'a> {
       eszip,
       npm_vfs.as_ref(),
       &npm_files,
+      &asset_files,
     )
   }
 
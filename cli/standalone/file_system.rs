@@ -18,15 +18,32 @@ use deno_runtime::deno_io::fs::FsStat;
 use super::virtual_fs::FileBackedVfs;
 
 #[derive(Debug, Clone)]
-pub struct DenoCompileFileSystem(Arc<FileBackedVfs>);
+pub struct DenoCompileFileSystem {
+  vfs: Arc<FileBackedVfs>,
+  /// The file system of the paths outside the vfs.
+  fallback: Arc<dyn FileSystem>,
+}
 
 impl DenoCompileFileSystem {
   pub fn new(vfs: FileBackedVfs) -> Self {
-    Self(Arc::new(vfs))
+    Self::with_fallback(vfs, Arc::new(RealFs))
+  }
+
+  /// Creates a file system which uses another file system outside the vfs,
+  /// for example to layer the vfs of the assets over the vfs of the npm
+  /// packages.
+  pub fn with_fallback(
+    vfs: FileBackedVfs,
+    fallback: Arc<dyn FileSystem>,
+  ) -> Self {
+    Self {
+      vfs: Arc::new(vfs),
+      fallback,
+    }
   }
 
   fn error_if_in_vfs(&self, path: &Path) -> FsResult<()> {
-    if self.0.is_path_within(path) {
+    if self.vfs.is_path_within(path) {
       Err(FsError::NotSupported)
     } else {
       Ok(())
@@ -34,9 +51,9 @@ impl DenoCompileFileSystem {
   }
 
   fn copy_to_real_path(&self, oldpath: &Path, newpath: &Path) -> FsResult<()> {
-    let old_file = self.0.file_entry(oldpath)?;
-    let old_file_bytes = self.0.read_file_all(old_file)?;
-    RealFs.write_file_sync(
+    let old_file = self.vfs.file_entry(oldpath)?;
+    let old_file_bytes = self.vfs.read_file_all(old_file)?;
+    self.fallback.write_file_sync(
       newpath,
       OpenOptions {
         read: false,
@@ -55,20 +72,20 @@ impl DenoCompileFileSystem {
 #[async_trait::async_trait(?Send)]
 impl FileSystem for DenoCompileFileSystem {
   fn cwd(&self) -> FsResult<PathBuf> {
-    RealFs.cwd()
+    self.fallback.cwd()
   }
 
   fn tmp_dir(&self) -> FsResult<PathBuf> {
-    RealFs.tmp_dir()
+    self.fallback.tmp_dir()
   }
 
   fn chdir(&self, path: &Path) -> FsResult<()> {
     self.error_if_in_vfs(path)?;
-    RealFs.chdir(path)
+    self.fallback.chdir(path)
   }
 
   fn umask(&self, mask: Option<u32>) -> FsResult<u32> {
-    RealFs.umask(mask)
+    self.fallback.umask(mask)
   }
 
   fn open_sync(
@@ -76,10 +93,10 @@ impl FileSystem for DenoCompileFileSystem {
     path: &Path,
     options: OpenOptions,
   ) -> FsResult<Rc<dyn File>> {
-    if self.0.is_path_within(path) {
-      Ok(self.0.open_file(path)?)
+    if self.vfs.is_path_within(path) {
+      Ok(self.vfs.open_file(path)?)
     } else {
-      RealFs.open_sync(path, options)
+      self.fallback.open_sync(path, options)
     }
   }
   async fn open_async(
@@ -87,10 +104,10 @@ impl FileSystem for DenoCompileFileSystem {
     path: PathBuf,
     options: OpenOptions,
   ) -> FsResult<Rc<dyn File>> {
-    if self.0.is_path_within(&path) {
-      Ok(self.0.open_file(&path)?)
+    if self.vfs.is_path_within(&path) {
+      Ok(self.vfs.open_file(&path)?)
     } else {
-      RealFs.open_async(path, options).await
+      self.fallback.open_async(path, options).await
     }
   }
 
@@ -101,7 +118,7 @@ impl FileSystem for DenoCompileFileSystem {
     mode: u32,
   ) -> FsResult<()> {
     self.error_if_in_vfs(path)?;
-    RealFs.mkdir_sync(path, recursive, mode)
+    self.fallback.mkdir_sync(path, recursive, mode)
   }
   async fn mkdir_async(
     &self,
@@ -110,16 +127,16 @@ impl FileSystem for DenoCompileFileSystem {
     mode: u32,
   ) -> FsResult<()> {
     self.error_if_in_vfs(&path)?;
-    RealFs.mkdir_async(path, recursive, mode).await
+    self.fallback.mkdir_async(path, recursive, mode).await
   }
 
   fn chmod_sync(&self, path: &Path, mode: u32) -> FsResult<()> {
     self.error_if_in_vfs(path)?;
-    RealFs.chmod_sync(path, mode)
+    self.fallback.chmod_sync(path, mode)
   }
   async fn chmod_async(&self, path: PathBuf, mode: u32) -> FsResult<()> {
     self.error_if_in_vfs(&path)?;
-    RealFs.chmod_async(path, mode).await
+    self.fallback.chmod_async(path, mode).await
   }
 
   fn chown_sync(
@@ -129,7 +146,7 @@ impl FileSystem for DenoCompileFileSystem {
     gid: Option<u32>,
   ) -> FsResult<()> {
     self.error_if_in_vfs(path)?;
-    RealFs.chown_sync(path, uid, gid)
+    self.fallback.chown_sync(path, uid, gid)
   }
   async fn chown_async(
     &self,
@@ -138,24 +155,24 @@ impl FileSystem for DenoCompileFileSystem {
     gid: Option<u32>,
   ) -> FsResult<()> {
     self.error_if_in_vfs(&path)?;
-    RealFs.chown_async(path, uid, gid).await
+    self.fallback.chown_async(path, uid, gid).await
   }
 
   fn remove_sync(&self, path: &Path, recursive: bool) -> FsResult<()> {
     self.error_if_in_vfs(path)?;
-    RealFs.remove_sync(path, recursive)
+    self.fallback.remove_sync(path, recursive)
   }
   async fn remove_async(&self, path: PathBuf, recursive: bool) -> FsResult<()> {
     self.error_if_in_vfs(&path)?;
-    RealFs.remove_async(path, recursive).await
+    self.fallback.remove_async(path, recursive).await
   }
 
   fn copy_file_sync(&self, oldpath: &Path, newpath: &Path) -> FsResult<()> {
     self.error_if_in_vfs(newpath)?;
-    if self.0.is_path_within(oldpath) {
+    if self.vfs.is_path_within(oldpath) {
       self.copy_to_real_path(oldpath, newpath)
     } else {
-      RealFs.copy_file_sync(oldpath, newpath)
+      self.fallback.copy_file_sync(oldpath, newpath)
     }
   }
   async fn copy_file_async(
@@ -164,92 +181,92 @@ impl FileSystem for DenoCompileFileSystem {
     newpath: PathBuf,
   ) -> FsResult<()> {
     self.error_if_in_vfs(&newpath)?;
-    if self.0.is_path_within(&oldpath) {
+    if self.vfs.is_path_within(&oldpath) {
       let fs = self.clone();
       tokio::task::spawn_blocking(move || {
         fs.copy_to_real_path(&oldpath, &newpath)
       })
       .await?
     } else {
-      RealFs.copy_file_async(oldpath, newpath).await
+      self.fallback.copy_file_async(oldpath, newpath).await
     }
   }
 
   fn cp_sync(&self, from: &Path, to: &Path) -> FsResult<()> {
     self.error_if_in_vfs(to)?;
 
-    RealFs.cp_sync(from, to)
+    self.fallback.cp_sync(from, to)
   }
   async fn cp_async(&self, from: PathBuf, to: PathBuf) -> FsResult<()> {
     self.error_if_in_vfs(&to)?;
 
-    RealFs.cp_async(from, to).await
+    self.fallback.cp_async(from, to).await
   }
 
   fn stat_sync(&self, path: &Path) -> FsResult<FsStat> {
-    if self.0.is_path_within(path) {
-      Ok(self.0.stat(path)?)
+    if self.vfs.is_path_within(path) {
+      Ok(self.vfs.stat(path)?)
     } else {
-      RealFs.stat_sync(path)
+      self.fallback.stat_sync(path)
     }
   }
   async fn stat_async(&self, path: PathBuf) -> FsResult<FsStat> {
-    if self.0.is_path_within(&path) {
-      Ok(self.0.stat(&path)?)
+    if self.vfs.is_path_within(&path) {
+      Ok(self.vfs.stat(&path)?)
     } else {
-      RealFs.stat_async(path).await
+      self.fallback.stat_async(path).await
     }
   }
 
   fn lstat_sync(&self, path: &Path) -> FsResult<FsStat> {
-    if self.0.is_path_within(path) {
-      Ok(self.0.lstat(path)?)
+    if self.vfs.is_path_within(path) {
+      Ok(self.vfs.lstat(path)?)
     } else {
-      RealFs.lstat_sync(path)
+      self.fallback.lstat_sync(path)
     }
   }
   async fn lstat_async(&self, path: PathBuf) -> FsResult<FsStat> {
-    if self.0.is_path_within(&path) {
-      Ok(self.0.lstat(&path)?)
+    if self.vfs.is_path_within(&path) {
+      Ok(self.vfs.lstat(&path)?)
     } else {
-      RealFs.lstat_async(path).await
+      self.fallback.lstat_async(path).await
     }
   }
 
   fn realpath_sync(&self, path: &Path) -> FsResult<PathBuf> {
-    if self.0.is_path_within(path) {
-      Ok(self.0.canonicalize(path)?)
+    if self.vfs.is_path_within(path) {
+      Ok(self.vfs.canonicalize(path)?)
     } else {
-      RealFs.realpath_sync(path)
+      self.fallback.realpath_sync(path)
     }
   }
   async fn realpath_async(&self, path: PathBuf) -> FsResult<PathBuf> {
-    if self.0.is_path_within(&path) {
-      Ok(self.0.canonicalize(&path)?)
+    if self.vfs.is_path_within(&path) {
+      Ok(self.vfs.canonicalize(&path)?)
     } else {
-      RealFs.realpath_async(path).await
+      self.fallback.realpath_async(path).await
     }
   }
 
   fn read_dir_sync(&self, path: &Path) -> FsResult<Vec<FsDirEntry>> {
-    if self.0.is_path_within(path) {
-      Ok(self.0.read_dir(path)?)
+    if self.vfs.is_path_within(path) {
+      Ok(self.vfs.read_dir(path)?)
     } else {
-      RealFs.read_dir_sync(path)
+      self.fallback.read_dir_sync(path)
     }
   }
   async fn read_dir_async(&self, path: PathBuf) -> FsResult<Vec<FsDirEntry>> {
-    if self.0.is_path_within(&path) {
-      Ok(self.0.read_dir(&path)?)
+    if self.vfs.is_path_within(&path) {
+      Ok(self.vfs.read_dir(&path)?)
     } else {
-      RealFs.read_dir_async(path).await
+      self.fallback.read_dir_async(path).await
     }
   }
 
   fn rename_sync(&self, oldpath: &Path, newpath: &Path) -> FsResult<()> {
     self.error_if_in_vfs(oldpath)?;
     self.error_if_in_vfs(newpath)?;
-    RealFs.rename_sync(oldpath, newpath)
+    self.fallback.rename_sync(oldpath, newpath)
   }
   async fn rename_async(
     &self,
@@ -258,13 +275,13 @@ impl FileSystem for DenoCompileFileSystem {
   ) -> FsResult<()> {
     self.error_if_in_vfs(&oldpath)?;
     self.error_if_in_vfs(&newpath)?;
-    RealFs.rename_async(oldpath, newpath).await
+    self.fallback.rename_async(oldpath, newpath).await
   }
 
   fn link_sync(&self, oldpath: &Path, newpath: &Path) -> FsResult<()> {
     self.error_if_in_vfs(oldpath)?;
     self.error_if_in_vfs(newpath)?;
-    RealFs.link_sync(oldpath, newpath)
+    self.fallback.link_sync(oldpath, newpath)
   }
   async fn link_async(
     &self,
@@ -273,7 +290,7 @@ impl FileSystem for DenoCompileFileSystem {
   ) -> FsResult<()> {
     self.error_if_in_vfs(&oldpath)?;
     self.error_if_in_vfs(&newpath)?;
-    RealFs.link_async(oldpath, newpath).await
+    self.fallback.link_async(oldpath, newpath).await
   }
 
   fn symlink_sync(
@@ -284,7 +301,7 @@ impl FileSystem for DenoCompileFileSystem {
   ) -> FsResult<()> {
     self.error_if_in_vfs(oldpath)?;
     self.error_if_in_vfs(newpath)?;
-    RealFs.symlink_sync(oldpath, newpath, file_type)
+    self.fallback.symlink_sync(oldpath, newpath, file_type)
   }
   async fn symlink_async(
     &self,
@@ -294,31 +311,34 @@ impl FileSystem for DenoCompileFileSystem {
   ) -> FsResult<()> {
     self.error_if_in_vfs(&oldpath)?;
     self.error_if_in_vfs(&newpath)?;
-    RealFs.symlink_async(oldpath, newpath, file_type).await
+    self
+      .fallback
+      .symlink_async(oldpath, newpath, file_type)
+      .await
   }
 
   fn read_link_sync(&self, path: &Path) -> FsResult<PathBuf> {
-    if self.0.is_path_within(path) {
-      Ok(self.0.read_link(path)?)
+    if self.vfs.is_path_within(path) {
+      Ok(self.vfs.read_link(path)?)
     } else {
-      RealFs.read_link_sync(path)
+      self.fallback.read_link_sync(path)
     }
   }
   async fn read_link_async(&self, path: PathBuf) -> FsResult<PathBuf> {
-    if self.0.is_path_within(&path) {
-      Ok(self.0.read_link(&path)?)
+    if self.vfs.is_path_within(&path) {
+      Ok(self.vfs.read_link(&path)?)
     } else {
-      RealFs.read_link_async(path).await
+      self.fallback.read_link_async(path).await
     }
   }
 
   fn truncate_sync(&self, path: &Path, len: u64) -> FsResult<()> {
     self.error_if_in_vfs(path)?;
-    RealFs.truncate_sync(path, len)
+    self.fallback.truncate_sync(path, len)
   }
   async fn truncate_async(&self, path: PathBuf, len: u64) -> FsResult<()> {
     self.error_if_in_vfs(&path)?;
-    RealFs.truncate_async(path, len).await
+    self.fallback.truncate_async(path, len).await
   }
 
   fn utime_sync(
@@ -330,7 +350,13 @@ impl FileSystem for DenoCompileFileSystem {
     mtime_nanos: u32,
   ) -> FsResult<()> {
     self.error_if_in_vfs(path)?;
-    RealFs.utime_sync(path, atime_secs, atime_nanos, mtime_secs, mtime_nanos)
+    self.fallback.utime_sync(
+      path,
+      atime_secs,
+      atime_nanos,
+      mtime_secs,
+      mtime_nanos,
+    )
   }
   async fn utime_async(
     &self,
@@ -341,7 +367,8 @@ impl FileSystem for DenoCompileFileSystem {
     mtime_nanos: u32,
   ) -> FsResult<()> {
     self.error_if_in_vfs(&path)?;
-    RealFs
+    self
+      .fallback
       .utime_async(path, atime_secs, atime_nanos, mtime_secs, mtime_nanos)
       .await
   }
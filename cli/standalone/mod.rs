@@ -24,6 +24,7 @@ use crate::resolver::MappedSpecifierResolver;
 use crate::resolver::NpmModuleLoader;
 use crate::util::progress_bar::ProgressBar;
 use crate::util::progress_bar::ProgressBarStyle;
+use crate::util::text_encoding::source_map_from_code;
 use crate::util::v8::construct_v8_flags;
 use crate::worker::CliMainWorkerFactory;
 use crate::worker::CliMainWorkerOptions;
@@ -34,8 +35,11 @@ use deno_core::error::generic_error;
 use deno_core::error::type_error;
 use deno_core::error::AnyError;
 use deno_core::futures::FutureExt;
+use deno_core::serde_json;
+use deno_core::sourcemap::SourceMap;
 use deno_core::v8_set_flags;
 use deno_core::FeatureChecker;
+use deno_core::ModuleCodeString;
 use deno_core::ModuleLoader;
 use deno_core::ModuleSourceCode;
 use deno_core::ModuleSpecifier;
@@ -52,6 +56,7 @@ use deno_runtime::permissions::PermissionsContainer;
 use deno_runtime::WorkerLogLevel;
 use deno_semver::npm::NpmPackageReqReference;
 use import_map::parse_from_json;
+use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::Arc;
 
@@ -59,10 +64,13 @@ mod binary;
 mod file_system;
 mod virtual_fs;
 
+pub use binary::base_binary_path_suffix;
 pub use binary::extract_standalone;
 pub use binary::is_standalone_binary;
+pub use binary::CompileAssets;
 pub use binary::DenoCompileBinaryWriter;
 
+use self::binary::load_assets_vfs;
 use self::binary::load_npm_vfs;
 use self::binary::Metadata;
 use self::file_system::DenoCompileFileSystem;
@@ -281,7 +289,56 @@ impl ModuleLoaderFactory for StandaloneModuleLoaderFactory {
   fn create_source_map_getter(
     &self,
   ) -> Option<Rc<dyn deno_core::SourceMapGetter>> {
-    None
+    Some(Rc::new(EmbeddedSourceMapGetter {
+      shared: self.shared.clone(),
+    }))
+  }
+}
+
+/// Reads the source maps which are inlined in the transpiled modules of the
+/// eszip, unless they were excluded with `deno compile --strip`.
+struct EmbeddedSourceMapGetter {
+  shared: Arc<SharedModuleLoaderState>,
+}
+
+impl EmbeddedSourceMapGetter {
+  fn module_code(&self, specifier: &str) -> Option<ModuleCodeString> {
+    let module = self.shared.eszip.get_module(specifier)?;
+    // the eszip is fully loaded before the worker runs, so the source is
+    // already available
+    let code = module.source().now_or_never()??;
+    let code = arc_u8_to_arc_str(code).ok()?;
+    Some(code.into())
+  }
+}
+
+impl deno_core::SourceMapGetter for EmbeddedSourceMapGetter {
+  fn get_source_map(&self, file_name: &str) -> Option<Vec<u8>> {
+    source_map_from_code(&self.module_code(file_name)?)
+  }
+
+  fn get_source_line(
+    &self,
+    file_name: &str,
+    line_number: usize,
+  ) -> Option<String> {
+    let code = self.module_code(file_name)?;
+    // the original source of a transpiled module is only available from the
+    // contents of its source map
+    let source = match source_map_from_code(&code) {
+      Some(source_map) => {
+        let source_map = SourceMap::from_slice(&source_map).ok()?;
+        let index = (0..source_map.get_source_count())
+          .find(|index| source_map.get_source(*index) == Some(file_name))
+          .unwrap_or(0);
+        source_map.get_source_contents(index)?.to_string()
+      }
+      None => code.as_str().to_string(),
+    };
+    source
+      .split('\n')
+      .nth(line_number)
+      .map(|line| line.to_string())
   }
 }
 
@@ -304,6 +361,7 @@ pub async fn run(
   mut eszip: eszip::EszipV2,
   metadata: Metadata,
 ) -> Result<(), AnyError> {
+  log::debug!("Standalone executable compiled for {}", metadata.target);
   let main_module = &metadata.entrypoint;
   let current_exe_path = std::env::current_exe().unwrap();
   let current_exe_name =
@@ -327,7 +385,7 @@ pub async fn run(
   let npm_cache_dir = NpmCacheDir::new(root_path.clone());
   let npm_global_cache_dir = npm_cache_dir.get_cache_location();
   let cache_setting = CacheSetting::Only;
-  let (package_json_deps_provider, fs, npm_resolver, maybe_vfs_root) =
+  let (package_json_deps_provider, npm_fs, npm_resolver, maybe_vfs_root) =
     match metadata.node_modules {
       Some(binary::NodeModules::Managed {
         node_modules_dir,
@@ -363,11 +421,15 @@ pub async fn run(
             cache_setting,
             text_only_progress_bar: progress_bar,
             maybe_node_modules_path,
+            workspace_npm_packages: Vec::new(),
+            node_modules_dir_mode: Default::default(),
+            allow_scripts: Vec::new(),
             package_json_installer:
               CliNpmResolverManagedPackageJsonInstallerOption::ConditionalInstall(
                 package_json_deps_provider.clone(),
               ),
             npm_registry_url,
+            maybe_remote_cache: None,
             npm_system_info: Default::default(),
           }),
         )
@@ -419,11 +481,15 @@ pub async fn run(
             cache_setting,
             text_only_progress_bar: progress_bar,
             maybe_node_modules_path: None,
+            workspace_npm_packages: Vec::new(),
+            node_modules_dir_mode: Default::default(),
+            allow_scripts: Vec::new(),
             package_json_installer:
               CliNpmResolverManagedPackageJsonInstallerOption::ConditionalInstall(
                 package_json_deps_provider.clone(),
               ),
             npm_registry_url,
+            maybe_remote_cache: None,
             npm_system_info: Default::default(),
           }),
         )
@@ -431,6 +497,19 @@ pub async fn run(
         (package_json_deps_provider, fs, npm_resolver, None)
       }
     };
+  let mut vfs_roots = maybe_vfs_root.into_iter().collect::<Vec<_>>();
+  let mut embedded_files = Vec::new();
+  let fs = match metadata.assets {
+    Some(assets) => {
+      let vfs =
+        load_assets_vfs(assets).context("Failed to load assets vfs.")?;
+      vfs_roots.push(vfs.root().to_path_buf());
+      embedded_files = vfs.file_paths();
+      Arc::new(DenoCompileFileSystem::with_fallback(vfs, npm_fs))
+        as Arc<dyn deno_fs::FileSystem>
+    }
+    None => npm_fs,
+  };
 
   let has_node_modules_dir = npm_resolver.root_node_modules_path().is_some();
   let node_resolver = Arc::new(NodeResolver::new(
@@ -475,8 +554,8 @@ pub async fn run(
 
   let permissions = {
     let mut permissions = metadata.permissions;
-    // if running with an npm vfs, grant read access to it
-    if let Some(vfs_root) = maybe_vfs_root {
+    // if running with an npm or assets vfs, grant read access to it
+    for vfs_root in vfs_roots {
       match &mut permissions.allow_read {
         Some(vec) if vec.is_empty() => {
           // do nothing, already granted
@@ -530,6 +609,7 @@ pub async fn run(
       enable_testing_features: false,
       has_node_modules_dir,
       hmr: false,
+      hmr_control_dir: None,
       inspect_brk: false,
       inspect_wait: false,
       strace_ops: None,
@@ -561,7 +641,24 @@ pub async fn run(
   let mut worker = worker_factory
     .create_main_worker(main_module.clone(), permissions)
     .await?;
+  worker.execute_script(
+    "ext:deno_compile/embedded_files.js",
+    embedded_files_script(&embedded_files),
+  )?;
 
   let exit_code = worker.run().await?;
   std::process::exit(exit_code)
 }
+
+/// Creates the script which exposes the paths of the files embedded with
+/// `--include` as `Deno.embeddedFiles`.
+fn embedded_files_script(embedded_files: &[PathBuf]) -> String {
+  let paths = embedded_files
+    .iter()
+    .map(|path| path.to_string_lossy())
+    .collect::<Vec<_>>();
+  format!(
+    "Object.defineProperty(Deno, \"embeddedFiles\", {{ value: Object.freeze({}), enumerable: true }});",
+    serde_json::to_string(&paths).unwrap(),
+  )
+}
@@ -745,6 +745,23 @@ impl FileBackedVfs {
     path.starts_with(&self.fs_root.root_path)
   }
 
+  /// Gets the paths of all the files in the vfs, sorted by path.
+  pub fn file_paths(&self) -> Vec<PathBuf> {
+    fn collect(dir: &VirtualDirectory, path: &Path, paths: &mut Vec<PathBuf>) {
+      for entry in &dir.entries {
+        match entry {
+          VfsEntry::Dir(dir) => collect(dir, &path.join(&dir.name), paths),
+          VfsEntry::File(file) => paths.push(path.join(&file.name)),
+          VfsEntry::Symlink(_) => {}
+        }
+      }
+    }
+
+    let mut paths = Vec::new();
+    collect(&self.fs_root.dir, &self.fs_root.root_path, &mut paths);
+    paths
+  }
+
   pub fn open_file(
     self: &Arc<Self>,
     path: &Path,
@@ -930,6 +947,18 @@ mod test {
         .is_directory,
     );
     assert!(virtual_fs.stat(&dest_path.join("e.txt")).unwrap().is_file,);
+
+    // symlinks aren't listed as files
+    assert_eq!(
+      virtual_fs.file_paths(),
+      vec![
+        dest_path.join("a.txt"),
+        dest_path.join("b.txt"),
+        dest_path.join("c.txt"),
+        dest_path.join("e.txt"),
+        dest_path.join("sub_dir").join("d.txt"),
+      ]
+    );
   }
 
   #[test]
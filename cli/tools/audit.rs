@@ -0,0 +1,492 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+use deno_core::parking_lot::Mutex;
+use deno_core::serde_json;
+use deno_core::url::Url;
+use deno_npm::resolution::NpmResolutionSnapshot;
+use deno_semver::package::PackageNv;
+use deno_semver::Version;
+use deno_semver::VersionReq;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::args::AuditFlags;
+use crate::args::AuditSeverity;
+use crate::args::CacheSetting;
+use crate::args::Flags;
+use crate::cache::CACHE_PERM;
+use crate::colors;
+use crate::display;
+use crate::factory::CliFactory;
+use crate::http_util::HttpClient;
+use crate::util::fs::atomic_write_file;
+
+/// An advisory of the advisory database of the npm registry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NpmAdvisory {
+  pub id: u64,
+  pub title: String,
+  pub severity: String,
+  pub url: String,
+  #[serde(alias = "vulnerable_versions")]
+  pub vulnerable_versions: String,
+}
+
+impl NpmAdvisory {
+  pub fn severity(&self) -> AuditSeverity {
+    self.severity.parse().unwrap_or(AuditSeverity::Info)
+  }
+
+  fn affects(&self, version: &Version) -> bool {
+    match VersionReq::parse_from_npm(&self.vulnerable_versions) {
+      Ok(req) => req.matches(version),
+      // be conservative with ranges which can't be parsed
+      Err(_) => true,
+    }
+  }
+}
+
+/// The advisories of the npm packages which `deno audit` checked, by
+/// "name@version". This is stored in the DENO_DIR for auditing offline and
+/// for the warnings of the LSP.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct NpmAdvisoryCache {
+  packages: BTreeMap<String, Vec<NpmAdvisory>>,
+}
+
+impl NpmAdvisoryCache {
+  pub fn load(path: &Path) -> Self {
+    std::fs::read_to_string(path)
+      .ok()
+      .and_then(|text| serde_json::from_str(&text).ok())
+      .unwrap_or_default()
+  }
+
+  fn save(&self, path: &Path) -> Result<(), AnyError> {
+    if let Some(parent) = path.parent() {
+      std::fs::create_dir_all(parent)?;
+    }
+    atomic_write_file(path, serde_json::to_string(self)?, CACHE_PERM)?;
+    Ok(())
+  }
+
+  /// Get the advisories which affect the package, or `None` when the package
+  /// wasn't audited.
+  pub fn get(&self, nv: &PackageNv) -> Option<&[NpmAdvisory]> {
+    self.packages.get(&nv.to_string()).map(|a| a.as_slice())
+  }
+}
+
+/// The advisory cache of the DENO_DIR for the LSP, which is reloaded when
+/// `deno audit` updates it.
+#[derive(Debug)]
+pub struct NpmAdvisoryCacheFile {
+  path: PathBuf,
+  state: Mutex<(Option<SystemTime>, Arc<NpmAdvisoryCache>)>,
+}
+
+impl NpmAdvisoryCacheFile {
+  pub fn new(path: PathBuf) -> Self {
+    Self {
+      path,
+      state: Default::default(),
+    }
+  }
+
+  pub fn get(&self) -> Arc<NpmAdvisoryCache> {
+    let modified = std::fs::metadata(&self.path)
+      .and_then(|m| m.modified())
+      .ok();
+    let mut state = self.state.lock();
+    if state.0 != modified {
+      *state = (modified, Arc::new(NpmAdvisoryCache::load(&self.path)));
+    }
+    state.1.clone()
+  }
+}
+
+/// Query the bulk advisory endpoint of the npm registry with the versions of
+/// every package.
+async fn fetch_advisories(
+  http_client: &HttpClient,
+  registry_url: &Url,
+  packages: &BTreeMap<String, Vec<String>>,
+) -> Result<HashMap<String, Vec<NpmAdvisory>>, AnyError> {
+  let url = registry_url.join("-/npm/v1/security/advisories/bulk")?;
+  let response = http_client
+    .client()?
+    .post(url)
+    .json(packages)
+    .send()
+    .await?;
+  let status = response.status();
+  let text = response.text().await?;
+  if !status.is_success() {
+    bail!("{} {}", status, text.trim());
+  }
+  Ok(serde_json::from_str(&text)?)
+}
+
+/// A vulnerable package of the npm resolution.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Vulnerability {
+  package: String,
+  version: String,
+  advisory: NpmAdvisory,
+  /// The paths from the top level packages to the package.
+  paths: Vec<String>,
+  /// The lowest version above the current one which the advisory doesn't
+  /// affect.
+  fix_version: Option<String>,
+}
+
+/// Get the shortest path from each top level package to the packages of the
+/// snapshot, like "express@4.18.2 > qs@6.11.0".
+fn dependency_paths(
+  snapshot: &NpmResolutionSnapshot,
+) -> HashMap<PackageNv, Vec<String>> {
+  let mut paths: HashMap<PackageNv, Vec<String>> = HashMap::new();
+  let mut roots = snapshot.top_level_packages().collect::<Vec<_>>();
+  roots.sort();
+  for root in roots {
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::from([vec![root]]);
+    while let Some(path) = queue.pop_front() {
+      let id = *path.last().unwrap();
+      if !visited.insert(id) {
+        continue;
+      }
+      let text = path
+        .iter()
+        .map(|id| id.nv.to_string())
+        .collect::<Vec<_>>()
+        .join(" > ");
+      let nv_paths = paths.entry(id.nv.clone()).or_default();
+      if !nv_paths.contains(&text) {
+        nv_paths.push(text);
+      }
+      let Some(package) = snapshot.package_from_id(id) else {
+        continue;
+      };
+      let mut dependencies = package.dependencies.values().collect::<Vec<_>>();
+      dependencies.sort();
+      for dependency in dependencies {
+        if !visited.contains(&dependency) {
+          let mut path = path.clone();
+          path.push(dependency);
+          queue.push_back(path);
+        }
+      }
+    }
+  }
+  paths
+}
+
+fn fix_version<'a>(
+  versions: impl Iterator<Item = &'a Version>,
+  current: &Version,
+  advisory: &NpmAdvisory,
+) -> Option<Version> {
+  versions
+    .filter(|v| *v > current && v.pre.is_empty() && !advisory.affects(v))
+    .min()
+    .cloned()
+}
+
+fn colored_severity(severity: AuditSeverity) -> String {
+  let text = severity.as_str();
+  match severity {
+    AuditSeverity::Critical => colors::red_bold(text).to_string(),
+    AuditSeverity::High => colors::red(text).to_string(),
+    AuditSeverity::Moderate => colors::yellow(text).to_string(),
+    AuditSeverity::Low | AuditSeverity::Info => colors::gray(text).to_string(),
+  }
+}
+
+fn write_vulnerabilities(
+  output: &mut String,
+  vulnerabilities: &[Vulnerability],
+  package_count: usize,
+) -> std::fmt::Result {
+  const MAX_PATHS: usize = 3;
+  for vulnerability in vulnerabilities {
+    let nv = format!("{}@{}", vulnerability.package, vulnerability.version);
+    let advisory = &vulnerability.advisory;
+    writeln!(
+      output,
+      "{} {}",
+      colored_severity(advisory.severity()),
+      colors::bold(&advisory.title)
+    )?;
+    writeln!(output, "  Package: {}", nv)?;
+    for (i, path) in vulnerability.paths.iter().take(MAX_PATHS).enumerate() {
+      let label = if i == 0 { "Path:   " } else { "        " };
+      writeln!(output, "  {} {}", label, path)?;
+    }
+    if vulnerability.paths.len() > MAX_PATHS {
+      writeln!(
+        output,
+        "           {}",
+        colors::gray(format!(
+          "and {} more",
+          vulnerability.paths.len() - MAX_PATHS
+        ))
+      )?;
+    }
+    match &vulnerability.fix_version {
+      Some(version) => writeln!(
+        output,
+        "  Fix:     {}",
+        colors::green(format!("{}@{}", vulnerability.package, version))
+      )?,
+      None => {
+        writeln!(output, "  Fix:     {}", colors::gray("none available"))?
+      }
+    }
+    writeln!(output, "  Info:    {}", advisory.url)?;
+    writeln!(output)?;
+  }
+
+  if vulnerabilities.is_empty() {
+    writeln!(
+      output,
+      "{}",
+      colors::green(format!(
+        "No vulnerabilities found in {} npm packages.",
+        package_count
+      ))
+    )
+  } else {
+    let mut counts = BTreeMap::new();
+    for vulnerability in vulnerabilities {
+      *counts
+        .entry(std::cmp::Reverse(vulnerability.advisory.severity()))
+        .or_insert(0) += 1;
+    }
+    let counts = counts
+      .into_iter()
+      .map(|(severity, count)| format!("{} {}", count, severity.0.as_str()))
+      .collect::<Vec<_>>();
+    writeln!(
+      output,
+      "Found {} {} ({}) in {} npm packages.",
+      vulnerabilities.len(),
+      if vulnerabilities.len() == 1 {
+        "vulnerability"
+      } else {
+        "vulnerabilities"
+      },
+      counts.join(", "),
+      package_count
+    )
+  }
+}
+
+pub async fn audit(
+  flags: Flags,
+  audit_flags: AuditFlags,
+) -> Result<(), AnyError> {
+  let factory = CliFactory::from_flags(flags).await?;
+  let cli_options = factory.cli_options();
+  let npm_resolver = factory.npm_resolver().await?;
+  let Some(npm_resolver) = npm_resolver.as_managed() else {
+    bail!("deno audit is not supported with --unstable-byonm");
+  };
+  npm_resolver.ensure_top_level_package_json_install().await?;
+  let snapshot = npm_resolver.snapshot();
+  let packages = snapshot
+    .all_packages_for_every_system()
+    .map(|package| package.id.nv.clone())
+    .collect::<BTreeSet<_>>();
+
+  let cache_path = factory.deno_dir()?.npm_audit_file_path();
+  let mut cache = NpmAdvisoryCache::load(&cache_path);
+  if !packages.is_empty()
+    && !matches!(cli_options.cache_setting(), CacheSetting::Only)
+  {
+    let mut query: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for nv in &packages {
+      query
+        .entry(nv.name.clone())
+        .or_default()
+        .push(nv.version.to_string());
+    }
+    match fetch_advisories(
+      factory.http_client(),
+      npm_resolver.registry_base_url(),
+      &query,
+    )
+    .await
+    {
+      Ok(advisories) => {
+        for nv in &packages {
+          let affecting = advisories
+            .get(&nv.name)
+            .map(|advisories| {
+              advisories
+                .iter()
+                .filter(|advisory| advisory.affects(&nv.version))
+                .cloned()
+                .collect()
+            })
+            .unwrap_or_default();
+          cache.packages.insert(nv.to_string(), affecting);
+        }
+        if let Err(err) = cache.save(&cache_path) {
+          log::debug!("Failed caching npm advisories: {:#}", err);
+        }
+      }
+      Err(err) => {
+        log::warn!(
+          "{} Failed to query the advisory database, using the cached advisories: {:#}",
+          colors::yellow("Warning"),
+          err
+        );
+      }
+    }
+  }
+
+  let paths = dependency_paths(&snapshot);
+  let mut vulnerabilities = Vec::new();
+  let mut unchecked = Vec::new();
+  for nv in &packages {
+    let Some(advisories) = cache.get(nv) else {
+      unchecked.push(nv.to_string());
+      continue;
+    };
+    if advisories.is_empty() {
+      continue;
+    }
+    let maybe_info = npm_resolver.package_info(&nv.name).await.ok();
+    for advisory in advisories {
+      let maybe_fix_version = maybe_info.as_ref().and_then(|info| {
+        fix_version(info.versions.keys(), &nv.version, advisory)
+      });
+      vulnerabilities.push(Vulnerability {
+        package: nv.name.clone(),
+        version: nv.version.to_string(),
+        advisory: advisory.clone(),
+        paths: paths.get(nv).cloned().unwrap_or_default(),
+        fix_version: maybe_fix_version.map(|v| v.to_string()),
+      });
+    }
+  }
+  vulnerabilities.sort_by(|a, b| {
+    b.advisory
+      .severity()
+      .cmp(&a.advisory.severity())
+      .then_with(|| a.package.cmp(&b.package))
+  });
+
+  if !unchecked.is_empty() {
+    log::warn!(
+      "{} {} npm packages weren't audited, because there are no cached advisories for them: {}",
+      colors::yellow("Warning"),
+      unchecked.len(),
+      unchecked.join(", ")
+    );
+  }
+
+  if audit_flags.json {
+    #[derive(Serialize)]
+    struct JsonReport<'a> {
+      vulnerabilities: &'a [Vulnerability],
+      unchecked: &'a [String],
+    }
+    display::write_json_to_stdout(&JsonReport {
+      vulnerabilities: &vulnerabilities,
+      unchecked: &unchecked,
+    })?;
+  } else {
+    let mut output = String::new();
+    write_vulnerabilities(&mut output, &vulnerabilities, packages.len())?;
+    display::write_to_stdout_ignore_sigpipe(output.as_bytes())?;
+  }
+
+  let failing = vulnerabilities
+    .iter()
+    .filter(|v| v.advisory.severity() >= audit_flags.level)
+    .count();
+  if failing > 0 {
+    bail!(
+      "Found {} {} with a severity of {} or above.",
+      failing,
+      if failing == 1 {
+        "vulnerability"
+      } else {
+        "vulnerabilities"
+      },
+      audit_flags.level.as_str()
+    );
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use deno_core::serde_json::json;
+  use pretty_assertions::assert_eq;
+
+  fn advisory(vulnerable_versions: &str) -> NpmAdvisory {
+    NpmAdvisory {
+      id: 1,
+      title: "Prototype Pollution".to_string(),
+      severity: "high".to_string(),
+      url: "https://github.com/advisories/GHSA-0000-0000-0000".to_string(),
+      vulnerable_versions: vulnerable_versions.to_string(),
+    }
+  }
+
+  #[test]
+  fn test_parse_advisories() {
+    let advisories: HashMap<String, Vec<NpmAdvisory>> =
+      serde_json::from_value(json!({
+        "lodash": [{
+          "id": 1,
+          "url": "https://github.com/advisories/GHSA-0000-0000-0000",
+          "title": "Prototype Pollution",
+          "severity": "high",
+          "vulnerable_versions": "<4.17.21",
+          "cwe": ["CWE-1321"],
+          "cvss": { "score": 7.4, "vectorString": null },
+        }],
+      }))
+      .unwrap();
+    assert_eq!(advisories["lodash"], vec![advisory("<4.17.21")]);
+    assert_eq!(advisories["lodash"][0].severity(), AuditSeverity::High);
+  }
+
+  #[test]
+  fn test_fix_version() {
+    let versions = ["4.17.19", "4.17.20", "4.17.21", "4.17.22-rc.1", "5.0.0"]
+      .iter()
+      .map(|v| Version::parse_from_npm(v).unwrap())
+      .collect::<Vec<_>>();
+    let current = Version::parse_from_npm("4.17.20").unwrap();
+    assert_eq!(
+      fix_version(versions.iter(), &current, &advisory("<4.17.21")),
+      Some(Version::parse_from_npm("4.17.21").unwrap())
+    );
+    assert_eq!(
+      fix_version(versions.iter(), &current, &advisory(">=4.0.0 <5.0.0")),
+      Some(Version::parse_from_npm("5.0.0").unwrap())
+    );
+    assert_eq!(fix_version(versions.iter(), &current, &advisory("*")), None);
+  }
+}
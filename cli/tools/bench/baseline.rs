@@ -0,0 +1,316 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::path::Path;
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use serde::Deserialize;
+
+use super::BenchDescription;
+use super::BenchStats;
+use crate::colors;
+
+/// A benchmark of a report written by `deno bench --reporter=json`.
+#[derive(Debug, Clone, Deserialize)]
+struct BaselineBench {
+  origin: String,
+  group: Option<String>,
+  name: String,
+  stats: Option<BaselineStats>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct BaselineStats {
+  iterations: u64,
+  avg: f64,
+  #[serde(default)]
+  sd: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct BaselineReport {
+  version: u32,
+  benches: Vec<BaselineBench>,
+}
+
+/// The benchmarks of a previous run, which `deno bench --baseline` compares
+/// the current measurements against.
+#[derive(Debug, Clone)]
+pub struct BenchBaseline {
+  benches: Vec<BaselineBench>,
+  regression_threshold: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchRegression {
+  pub name: String,
+  pub group: Option<String>,
+  pub baseline_avg: f64,
+  pub current_avg: f64,
+  pub delta_percent: f64,
+}
+
+impl BenchBaseline {
+  pub fn load(
+    path: &Path,
+    regression_threshold: f64,
+  ) -> Result<Self, AnyError> {
+    let text = std::fs::read_to_string(path).with_context(|| {
+      format!("Failed to read bench baseline \"{}\"", path.display())
+    })?;
+    Self::parse(&text, regression_threshold)
+      .with_context(|| format!("Invalid bench baseline \"{}\"", path.display()))
+  }
+
+  fn parse(text: &str, regression_threshold: f64) -> Result<Self, AnyError> {
+    let report: BaselineReport = serde_json::from_str(text)?;
+    if report.version != 1 {
+      bail!(
+        "Unsupported report version {}, expected a report of 'deno bench --reporter=json'",
+        report.version
+      );
+    }
+    Ok(Self {
+      benches: report.benches,
+      regression_threshold,
+    })
+  }
+
+  /// Find the stats of a benchmark in the baseline. Modules are matched by
+  /// their specifier, or otherwise by a common path suffix, so a baseline
+  /// which was written in another checkout still applies.
+  fn find(&self, desc: &BenchDescription) -> Option<&BaselineStats> {
+    let candidates = self
+      .benches
+      .iter()
+      .filter(|b| b.name == desc.name && b.group == desc.group);
+    let mut best: Option<(usize, &BaselineBench)> = None;
+    for bench in candidates {
+      let suffix_len = common_suffix_len(&bench.origin, &desc.origin);
+      if best.map(|(len, _)| suffix_len > len).unwrap_or(true) {
+        best = Some((suffix_len, bench));
+      }
+    }
+    let (suffix_len, bench) = best?;
+    // require at least the file name to match
+    let file_name_len = desc.origin.rsplit('/').next()?.len() + 1;
+    if suffix_len < file_name_len.min(desc.origin.len()) {
+      return None;
+    }
+    bench.stats.as_ref()
+  }
+
+  /// Get the benchmarks which are slower than the baseline by more than the
+  /// regression threshold, where the difference is statistically
+  /// significant.
+  pub fn regressions(
+    &self,
+    measurements: &[(BenchDescription, BenchStats)],
+  ) -> Vec<BenchRegression> {
+    measurements
+      .iter()
+      .filter_map(|(desc, stats)| {
+        let baseline = self.find(desc)?;
+        if baseline.avg <= 0.0 {
+          return None;
+        }
+        let delta_percent = (stats.avg - baseline.avg) / baseline.avg * 100.0;
+        if delta_percent <= self.regression_threshold {
+          return None;
+        }
+        if !is_significant(
+          (baseline.avg, baseline.sd, baseline.iterations),
+          (stats.avg, stats.sd, stats.n),
+        ) {
+          return None;
+        }
+        Some(BenchRegression {
+          name: desc.name.clone(),
+          group: desc.group.clone(),
+          baseline_avg: baseline.avg,
+          current_avg: stats.avg,
+          delta_percent,
+        })
+      })
+      .collect()
+  }
+}
+
+fn common_suffix_len(a: &str, b: &str) -> usize {
+  a.bytes()
+    .rev()
+    .zip(b.bytes().rev())
+    .take_while(|(a, b)| a == b)
+    .count()
+}
+
+/// The critical values of a two-sided Student's t-test with a significance
+/// level of 0.05, by degrees of freedom.
+const T_CRITICAL_VALUES: [f64; 30] = [
+  12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201,
+  2.179, 2.160, 2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074,
+  2.069, 2.064, 2.060, 2.056, 2.052, 2.048, 2.045, 2.042,
+];
+
+/// Welch's t-test of two samples given as (mean, standard deviation, size).
+fn is_significant(a: (f64, f64, u64), b: (f64, f64, u64)) -> bool {
+  let (mean_a, sd_a, n_a) = a;
+  let (mean_b, sd_b, n_b) = b;
+  if n_a < 2 || n_b < 2 {
+    return false;
+  }
+  let (n_a, n_b) = (n_a as f64, n_b as f64);
+  let var_a = sd_a * sd_a / n_a;
+  let var_b = sd_b * sd_b / n_b;
+  let standard_error = (var_a + var_b).sqrt();
+  if standard_error == 0.0 {
+    // without any variance, every difference is significant
+    return mean_a != mean_b;
+  }
+  let t = (mean_a - mean_b).abs() / standard_error;
+  let df = (var_a + var_b).powi(2)
+    / (var_a.powi(2) / (n_a - 1.0) + var_b.powi(2) / (n_b - 1.0));
+  let critical_value = if df.is_finite() && df < 30.0 {
+    T_CRITICAL_VALUES[(df.floor() as usize).max(1) - 1]
+  } else {
+    1.96
+  };
+  t > critical_value
+}
+
+pub fn report_regressions(regressions: &[BenchRegression], compared: usize) {
+  if regressions.is_empty() {
+    eprintln!(
+      "{}",
+      colors::green(format!(
+        "No regressions found in {} benchmarks compared to the baseline",
+        compared
+      ))
+    );
+    return;
+  }
+  eprintln!(
+    "{}",
+    colors::red_bold(format!(
+      "{} of {} benchmarks regressed compared to the baseline:",
+      regressions.len(),
+      compared
+    ))
+  );
+  for regression in regressions {
+    let name = match &regression.group {
+      Some(group) => format!("{} ({})", regression.name, group),
+      None => regression.name.clone(),
+    };
+    eprintln!(
+      "  {} {} -> {} {}",
+      name,
+      colors::gray(super::mitata::fmt_duration(regression.baseline_avg)),
+      super::mitata::fmt_duration(regression.current_avg),
+      colors::red(format!("(+{:.1}%)", regression.delta_percent)),
+    );
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  fn desc(origin: &str, name: &str) -> BenchDescription {
+    BenchDescription {
+      id: 0,
+      name: name.to_string(),
+      origin: origin.to_string(),
+      baseline: false,
+      group: None,
+      ignore: false,
+      only: false,
+      warmup: false,
+    }
+  }
+
+  fn stats(avg: f64, sd: f64, n: u64) -> BenchStats {
+    BenchStats {
+      n,
+      min: avg,
+      max: avg,
+      avg,
+      sd,
+      p75: avg,
+      p99: avg,
+      p995: avg,
+      p999: avg,
+      high_precision: true,
+      used_explicit_timers: false,
+    }
+  }
+
+  #[test]
+  fn test_regressions() {
+    let baseline = BenchBaseline::parse(
+      r#"{
+        "version": 1,
+        "benches": [
+          { "origin": "file:///ci/a_bench.ts", "group": null, "name": "fast", "stats": { "iterations": 1000, "avg": 100, "sd": 5 } },
+          { "origin": "file:///ci/a_bench.ts", "group": null, "name": "noisy", "stats": { "iterations": 1000, "avg": 100, "sd": 5000 } },
+          { "origin": "file:///ci/a_bench.ts", "group": null, "name": "same", "stats": { "iterations": 1000, "avg": 100, "sd": 5 } },
+          { "origin": "file:///ci/a_bench.ts", "group": null, "name": "failed", "stats": null }
+        ]
+      }"#,
+      5.0,
+    )
+    .unwrap();
+    let measurements = vec![
+      (
+        desc("file:///home/a_bench.ts", "fast"),
+        stats(150.0, 5.0, 1000),
+      ),
+      (
+        desc("file:///home/a_bench.ts", "noisy"),
+        stats(150.0, 5000.0, 1000),
+      ),
+      (
+        desc("file:///home/a_bench.ts", "same"),
+        stats(102.0, 5.0, 1000),
+      ),
+      (
+        desc("file:///home/a_bench.ts", "failed"),
+        stats(150.0, 5.0, 1000),
+      ),
+      (
+        desc("file:///home/b_bench.ts", "fast"),
+        stats(150.0, 5.0, 1000),
+      ),
+    ];
+    assert_eq!(
+      baseline.regressions(&measurements),
+      vec![BenchRegression {
+        name: "fast".to_string(),
+        group: None,
+        baseline_avg: 100.0,
+        current_avg: 150.0,
+        delta_percent: 50.0,
+      }]
+    );
+  }
+
+  #[test]
+  fn test_unsupported_baseline_version() {
+    assert!(
+      BenchBaseline::parse(r#"{ "version": 2, "benches": [] }"#, 5.0).is_err()
+    );
+    assert!(
+      BenchBaseline::parse(r#"{ "runtime": "", "benches": [] }"#, 5.0).is_err()
+    );
+  }
+
+  #[test]
+  fn test_is_significant() {
+    assert!(is_significant((100.0, 1.0, 100), (110.0, 1.0, 100)));
+    assert!(!is_significant((100.0, 50.0, 10), (110.0, 50.0, 10)));
+    assert!(!is_significant((100.0, 0.0, 1), (110.0, 0.0, 1)));
+  }
+}
@@ -1,6 +1,7 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
 use crate::args::BenchFlags;
+use crate::args::BenchReporterConfig;
 use crate::args::CliOptions;
 use crate::args::Flags;
 use crate::colors;
@@ -49,17 +50,23 @@ use std::time::Duration;
 use tokio::sync::mpsc::unbounded_channel;
 use tokio::sync::mpsc::UnboundedSender;
 
+mod baseline;
 mod mitata;
 mod reporters;
 
+use baseline::BenchBaseline;
 use reporters::BenchReporter;
 use reporters::ConsoleReporter;
 use reporters::JsonReporter;
+use reporters::JunitReporter;
+use reporters::StructuredJsonReporter;
 
 #[derive(Debug, Clone)]
 struct BenchSpecifierOptions {
   filter: TestFilter,
   json: bool,
+  reporter: BenchReporterConfig,
+  baseline: Option<Arc<BenchBaseline>>,
   log_level: Option<log::Level>,
 }
 
@@ -117,6 +124,10 @@ pub struct BenchStats {
   pub min: f64,
   pub max: f64,
   pub avg: f64,
+  /// The standard deviation of the iteration durations, which is missing from
+  /// the reports of older versions.
+  #[serde(default)]
+  pub sd: f64,
   pub p75: f64,
   pub p99: f64,
   pub p995: f64,
@@ -139,11 +150,16 @@ impl BenchReport {
 fn create_reporter(
   show_output: bool,
   json: bool,
+  reporter: BenchReporterConfig,
 ) -> Box<dyn BenchReporter + Send> {
   if json {
     return Box::new(JsonReporter::new());
   }
-  Box::new(ConsoleReporter::new(show_output))
+  match reporter {
+    BenchReporterConfig::Pretty => Box::new(ConsoleReporter::new(show_output)),
+    BenchReporterConfig::Json => Box::new(StructuredJsonReporter::new()),
+    BenchReporterConfig::Junit => Box::new(JunitReporter::new()),
+  }
 }
 
 /// Type check a collection of module and document specifiers.
@@ -316,8 +332,11 @@ async fn bench_specifiers(
     spawn(async move {
       let mut used_only = false;
       let mut report = BenchReport::new();
-      let mut reporter =
-        create_reporter(log_level != Some(Level::Error), options.json);
+      let mut reporter = create_reporter(
+        log_level != Some(Level::Error),
+        options.json,
+        options.reporter,
+      );
       let mut benches = IndexMap::new();
 
       while let Some(event) = receiver.recv().await {
@@ -368,6 +387,16 @@ async fn bench_specifiers(
 
       reporter.report_end(&report);
 
+      if let Some(baseline) = &options.baseline {
+        let regressions = baseline.regressions(&report.measurements);
+        baseline::report_regressions(&regressions, report.measurements.len());
+        if !regressions.is_empty() {
+          return Err(generic_error(
+            "Bench failed because of regressions compared to the baseline",
+          ));
+        }
+      }
+
       if used_only {
         return Err(generic_error(
           "Bench failed because the \"only\" option was used",
@@ -433,6 +462,8 @@ pub async fn run_benchmarks(
   bench_flags: BenchFlags,
 ) -> Result<(), AnyError> {
   let cli_options = CliOptions::from_flags(flags)?;
+  let file_selection = cli_options
+    .resolve_file_selection(Some("bench"), &bench_flags.files.ignore)?;
   let bench_options = cli_options.resolve_bench_options(bench_flags)?;
   let factory = CliFactory::from_cli_options(Arc::new(cli_options));
   let cli_options = factory.cli_options();
@@ -440,10 +471,13 @@ pub async fn run_benchmarks(
   // `PermissionsContainer` - otherwise granting/revoking permissions in one
   // file would have impact on other files, which is undesirable.
   let permissions =
-    Permissions::from_options(&cli_options.permissions_options())?;
+    Permissions::from_options(&cli_options.permissions_options()?)?;
 
-  let specifiers =
-    collect_specifiers(bench_options.files, is_supported_bench_path)?;
+  let specifiers = collect_specifiers(
+    bench_options.files,
+    &file_selection,
+    is_supported_bench_path,
+  )?;
 
   if specifiers.is_empty() {
     return Err(generic_error("No bench modules found"));
@@ -460,6 +494,18 @@ pub async fn run_benchmarks(
     return Ok(());
   }
 
+  let baseline = bench_options
+    .baseline
+    .as_deref()
+    .map(|path| {
+      BenchBaseline::load(
+        path,
+        bench_options.regression_threshold_bps as f64 / 100.0,
+      )
+      .map(Arc::new)
+    })
+    .transpose()?;
+
   let log_level = cli_options.log_level();
   let worker_factory =
     Arc::new(factory.create_cli_main_worker_factory().await?);
@@ -470,6 +516,8 @@ pub async fn run_benchmarks(
     BenchSpecifierOptions {
       filter: TestFilter::from_flag(&bench_options.filter),
       json: bench_options.json,
+      reporter: bench_options.reporter,
+      baseline,
       log_level,
     },
   )
@@ -500,6 +548,8 @@ pub async fn run_benchmarks_with_watch(
           .build_from_flags_for_watcher(flags, watcher_communicator.clone())
           .await?;
         let cli_options = factory.cli_options();
+        let file_selection = cli_options
+          .resolve_file_selection(Some("bench"), &bench_flags.files.ignore)?;
         let bench_options = cli_options.resolve_bench_options(bench_flags)?;
 
         let _ = watcher_communicator.watch_paths(cli_options.watch_paths());
@@ -516,6 +566,7 @@ pub async fn run_benchmarks_with_watch(
 
         let bench_modules = collect_specifiers(
           bench_options.files.clone(),
+          &file_selection,
           is_supported_bench_path,
         )?;
 
@@ -523,7 +574,7 @@ pub async fn run_benchmarks_with_watch(
         // `PermissionsContainer` - otherwise granting/revoking permissions in one
         // file would have impact on other files, which is undesirable.
         let permissions =
-          Permissions::from_options(&cli_options.permissions_options())?;
+          Permissions::from_options(&cli_options.permissions_options()?)?;
 
         let graph = module_graph_builder
           .create_graph(graph_kind, bench_modules.clone())
@@ -558,11 +609,14 @@ pub async fn run_benchmarks_with_watch(
 
         // todo(THIS PR): why are we collecting specifiers twice in a row?
         // Seems like a perf bug.
-        let specifiers =
-          collect_specifiers(bench_options.files, is_supported_bench_path)?
-            .into_iter()
-            .filter(|specifier| bench_modules_to_reload.contains(specifier))
-            .collect::<Vec<ModuleSpecifier>>();
+        let specifiers = collect_specifiers(
+          bench_options.files,
+          &file_selection,
+          is_supported_bench_path,
+        )?
+        .into_iter()
+        .filter(|specifier| bench_modules_to_reload.contains(specifier))
+        .collect::<Vec<ModuleSpecifier>>();
 
         check_specifiers(cli_options, module_load_preparer, specifiers.clone())
           .await?;
@@ -579,6 +633,8 @@ pub async fn run_benchmarks_with_watch(
           BenchSpecifierOptions {
             filter: TestFilter::from_flag(&bench_options.filter),
             json: bench_options.json,
+            reporter: bench_options.reporter,
+            baseline: None,
             log_level,
           },
         )
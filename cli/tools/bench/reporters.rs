@@ -96,6 +96,247 @@ impl BenchReporter for JsonReporter {
   fn report_uncaught_error(&mut self, _origin: &str, _error: Box<JsError>) {}
 }
 
+/// The number of iterations per second of a benchmark, from the average
+/// duration of an iteration in nanoseconds.
+fn throughput(stats: &BenchStats) -> Option<f64> {
+  (stats.avg > 0.0).then(|| 1e9 / stats.avg)
+}
+
+/// The output of `--reporter=json`. Unlike the `--json` output, this has a
+/// versioned schema and durations are in nanoseconds.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StructuredJsonOutput {
+  version: u32,
+  runtime: String,
+  cpu: String,
+  benches: Vec<StructuredJsonBench>,
+  uncaught_errors: Vec<StructuredJsonUncaughtError>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StructuredJsonBench {
+  origin: String,
+  group: Option<String>,
+  name: String,
+  baseline: bool,
+  ok: bool,
+  stats: Option<StructuredJsonStats>,
+  error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StructuredJsonStats {
+  iterations: u64,
+  avg: f64,
+  min: f64,
+  max: f64,
+  p75: f64,
+  p99: f64,
+  p995: f64,
+  p999: f64,
+  sd: f64,
+  throughput: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StructuredJsonUncaughtError {
+  origin: String,
+  error: String,
+}
+
+pub struct StructuredJsonReporter(StructuredJsonOutput);
+
+impl StructuredJsonReporter {
+  pub fn new() -> Self {
+    Self(StructuredJsonOutput {
+      version: 1,
+      runtime: format!("{} {}", get_user_agent(), env!("TARGET")),
+      cpu: mitata::cpu::name(),
+      benches: vec![],
+      uncaught_errors: vec![],
+    })
+  }
+}
+
+impl BenchReporter for StructuredJsonReporter {
+  fn report_group_summary(&mut self) {}
+  #[cold]
+  fn report_plan(&mut self, _plan: &BenchPlan) {}
+
+  fn report_end(&mut self, _report: &BenchReport) {
+    match write_json_to_stdout(&self.0) {
+      Ok(_) => (),
+      Err(e) => println!("{e}"),
+    }
+  }
+
+  fn report_register(&mut self, _desc: &BenchDescription) {}
+
+  fn report_wait(&mut self, _desc: &BenchDescription) {}
+
+  fn report_output(&mut self, _output: &str) {}
+
+  fn report_result(&mut self, desc: &BenchDescription, result: &BenchResult) {
+    if desc.warmup {
+      return;
+    }
+    let (stats, error) = match result {
+      BenchResult::Ok(stats) => (
+        Some(StructuredJsonStats {
+          iterations: stats.n,
+          avg: stats.avg,
+          min: stats.min,
+          max: stats.max,
+          p75: stats.p75,
+          p99: stats.p99,
+          p995: stats.p995,
+          p999: stats.p999,
+          sd: stats.sd,
+          throughput: throughput(stats),
+        }),
+        None,
+      ),
+      BenchResult::Failed(js_error) => {
+        (None, Some(format_test_error(js_error)))
+      }
+    };
+    self.0.benches.push(StructuredJsonBench {
+      origin: desc.origin.clone(),
+      group: desc.group.clone(),
+      name: desc.name.clone(),
+      baseline: desc.baseline,
+      ok: error.is_none(),
+      stats,
+      error,
+    });
+  }
+
+  fn report_uncaught_error(&mut self, origin: &str, error: Box<JsError>) {
+    self.0.uncaught_errors.push(StructuredJsonUncaughtError {
+      origin: origin.to_string(),
+      error: format_test_error(&error),
+    });
+  }
+}
+
+/// Writes a JUnit XML report to stdout, with a test suite per module and the
+/// measurements of every benchmark as properties of its test case.
+pub struct JunitReporter {
+  suites: IndexMap<String, quick_junit::TestSuite>,
+}
+
+impl JunitReporter {
+  pub fn new() -> Self {
+    Self {
+      suites: IndexMap::new(),
+    }
+  }
+
+  fn add_test_case(&mut self, origin: &str, case: quick_junit::TestCase) {
+    self
+      .suites
+      .entry(origin.to_string())
+      .or_insert_with(|| quick_junit::TestSuite::new(origin.to_string()))
+      .add_test_case(case);
+  }
+}
+
+impl BenchReporter for JunitReporter {
+  fn report_group_summary(&mut self) {}
+  #[cold]
+  fn report_plan(&mut self, _plan: &BenchPlan) {}
+
+  fn report_end(&mut self, _report: &BenchReport) {
+    let mut report = quick_junit::Report::new("deno bench");
+    report.add_test_suites(self.suites.values().cloned());
+    if let Err(e) = report.serialize(std::io::stdout()) {
+      println!("{e}");
+    }
+  }
+
+  fn report_register(&mut self, _desc: &BenchDescription) {}
+
+  fn report_wait(&mut self, _desc: &BenchDescription) {}
+
+  fn report_output(&mut self, _output: &str) {}
+
+  fn report_result(&mut self, desc: &BenchDescription, result: &BenchResult) {
+    if desc.warmup {
+      return;
+    }
+    let mut case = match result {
+      BenchResult::Ok(stats) => {
+        let mut case = quick_junit::TestCase::new(
+          desc.name.clone(),
+          quick_junit::TestCaseStatus::success(),
+        );
+        case
+          .set_time(Duration::from_nanos((stats.avg * stats.n as f64) as u64));
+        case.add_property(quick_junit::Property::new(
+          "iterations",
+          stats.n.to_string(),
+        ));
+        for (name, value) in [
+          ("avg", stats.avg),
+          ("min", stats.min),
+          ("max", stats.max),
+          ("p75", stats.p75),
+          ("p99", stats.p99),
+          ("p995", stats.p995),
+          ("p999", stats.p999),
+        ] {
+          case.add_property(quick_junit::Property::new(
+            format!("{name}_ns"),
+            value.to_string(),
+          ));
+        }
+        if let Some(throughput) = throughput(stats) {
+          case.add_property(quick_junit::Property::new(
+            "throughput_per_second",
+            throughput.to_string(),
+          ));
+        }
+        case
+      }
+      BenchResult::Failed(js_error) => quick_junit::TestCase::new(
+        desc.name.clone(),
+        quick_junit::TestCaseStatus::NonSuccess {
+          kind: quick_junit::NonSuccessKind::Failure,
+          message: Some(format_test_error(js_error)),
+          ty: None,
+          description: None,
+          reruns: vec![],
+        },
+      ),
+    };
+    if let Some(group) = &desc.group {
+      case.set_classname(group.clone());
+    }
+    if desc.baseline {
+      case.add_property(quick_junit::Property::new("baseline", "true"));
+    }
+    self.add_test_case(&desc.origin, case);
+  }
+
+  fn report_uncaught_error(&mut self, origin: &str, error: Box<JsError>) {
+    let case = quick_junit::TestCase::new(
+      "uncaught error",
+      quick_junit::TestCaseStatus::NonSuccess {
+        kind: quick_junit::NonSuccessKind::Error,
+        message: Some(format_test_error(&error)),
+        ty: None,
+        description: None,
+        reruns: vec![],
+      },
+    );
+    self.add_test_case(origin, case);
+  }
+}
+
 pub struct ConsoleReporter {
   name: String,
   show_output: bool,
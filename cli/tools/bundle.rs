@@ -1,10 +1,44 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt;
+use std::path::Path;
 use std::path::PathBuf;
+use std::sync::Arc;
 
+use deno_ast::MediaType;
+use deno_ast::ModuleSpecifier;
+use deno_ast::ParseParams;
+use deno_ast::ParsedSource;
+use deno_ast::SourceTextInfo;
+use deno_ast::TextChange;
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
+use deno_core::futures::FutureExt;
+use deno_core::sourcemap::SourceMap;
+use deno_core::sourcemap::SourceMapBuilder;
+use deno_graph::source::CacheSetting;
+use deno_graph::source::LoadFuture;
+use deno_graph::source::LoadResponse;
+use deno_graph::source::LoadResult;
+use deno_graph::source::Loader;
+use deno_graph::source::ResolutionMode;
+use deno_graph::source::ResolveError;
+use deno_graph::source::Resolver;
+use deno_graph::BuildOptions;
+use deno_graph::DefaultModuleAnalyzer;
+use deno_graph::DependencyDescriptor;
+use deno_graph::DynamicArgument;
+use deno_graph::GraphKind;
 use deno_graph::Module;
+use deno_graph::ModuleGraph;
+use deno_runtime::permissions::PermissionsContainer;
+use deno_semver::npm::NpmPackageReqReference;
 use deno_terminal::colors;
+use indexmap::IndexMap;
+use indexmap::IndexSet;
 
 use crate::args::BundleFlags;
 use crate::args::CliOptions;
@@ -12,19 +46,18 @@ use crate::args::Flags;
 use crate::args::TsConfigType;
 use crate::factory::CliFactory;
 use crate::factory::CliFactoryBuilder;
-use crate::graph_util::error_for_any_npm_specifier;
+use crate::resolver::CjsResolutionStore;
+use crate::resolver::CliGraphResolver;
+use crate::resolver::CliNodeResolver;
 use crate::util;
+use crate::util::checksum;
 use crate::util::display;
+use crate::util::path::specifier_to_file_path;
 
 pub async fn bundle(
   flags: Flags,
   bundle_flags: BundleFlags,
 ) -> Result<(), AnyError> {
-  log::info!(
-    "{}",
-    colors::yellow("⚠️ Warning: `deno bundle` is deprecated and will be removed in Deno 2.0.\nUse an alternative bundler like \"deno_emit\", \"esbuild\" or \"rollup\" instead."),
-  );
-
   if let Some(watch_flags) = &bundle_flags.watch {
     util::file_watcher::watch_func(
       flags,
@@ -88,50 +121,566 @@ async fn bundle_action(
     paths_to_watch.push(import_map_path);
   }
 
-  // at the moment, we don't support npm specifiers in deno bundle, so show an error
-  error_for_any_npm_specifier(&graph)?;
+  // the modules of npm packages are inlined, so they need to be in the cache
+  if graph.modules().any(|m| matches!(m, Module::Npm(_))) {
+    if let Some(npm_resolver) = factory.npm_resolver().await?.as_managed() {
+      npm_resolver.cache_packages().await?;
+    }
+  }
+  let graph_resolver = factory.resolver().await?;
+  let node_resolver = factory.cli_node_resolver().await?;
+  let root = graph.resolve(&graph.roots[0]).clone();
+  let resolver = BundleResolver {
+    graph: &graph,
+    graph_resolver,
+    node_resolver,
+    cjs_resolutions: factory.cjs_resolutions(),
+  };
+  let bundle_graph =
+    build_bundle_graph(&root, &graph, &resolver, HashSet::new()).await?;
+
+  let Some(out_file) = &bundle_flags.out_file else {
+    // the chunks can't be written to standard output, so the dynamic
+    // imports stay as they are
+    let bundle_output =
+      bundle_module_graph(&bundle_graph, cli_options, bundle_flags, true)?;
+    log::debug!(">>>>> bundle END");
+    println!("{}", bundle_output.code);
+    return Ok(());
+  };
 
-  let bundle_output = bundle_module_graph(graph.as_ref(), cli_options)?;
+  // the modules which are imported dynamically or shared between chunks are
+  // bundled into chunks next to the output file
+  let out_file = cli_options.initial_cwd().join(out_file);
+  let out_dir = out_file.parent().unwrap().to_path_buf();
+  let chunks = collect_chunks(&bundle_graph, &root)
+    .into_iter()
+    .map(|specifier| {
+      let file_name = if specifier == root {
+        out_file.file_name().unwrap().to_string_lossy().to_string()
+      } else {
+        chunk_file_name(&specifier)
+      };
+      (specifier, file_name)
+    })
+    .collect::<IndexMap<_, _>>();
+  let resolver = BundleResolver {
+    graph: &bundle_graph,
+    ..resolver
+  };
+  for (specifier, file_name) in &chunks {
+    let other_chunks = chunks
+      .keys()
+      .filter(|s| *s != specifier)
+      .cloned()
+      .collect::<HashSet<_>>();
+    let chunk_graph =
+      build_bundle_graph(specifier, &bundle_graph, &resolver, other_chunks)
+        .await?;
+    let bundle_output =
+      bundle_module_graph(&chunk_graph, cli_options, bundle_flags, false)?;
+    let bundle_output = link_chunks(specifier, &chunks, bundle_output)?;
+    write_bundle(&out_dir.join(file_name), bundle_output)?;
+  }
   log::debug!(">>>>> bundle END");
-  let out_file = &bundle_flags.out_file;
+  Ok(())
+}
+
+/// Resolves the dependencies of the modules of a bundle graph to the ones
+/// of the module graph it's built from. The npm specifiers are resolved to
+/// the modules of their packages, so they're inlined into the bundle.
+struct BundleResolver<'a> {
+  graph: &'a ModuleGraph,
+  graph_resolver: &'a CliGraphResolver,
+  node_resolver: &'a CliNodeResolver,
+  cjs_resolutions: &'a CjsResolutionStore,
+}
 
-  if let Some(out_file) = out_file {
-    let out_file = cli_options.initial_cwd().join(out_file);
-    let output_bytes = bundle_output.code.as_bytes();
-    let output_len = output_bytes.len();
-    util::fs::write_file(&out_file, output_bytes, 0o644)?;
+impl fmt::Debug for BundleResolver<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("BundleResolver").finish_non_exhaustive()
+  }
+}
+
+impl BundleResolver<'_> {
+  fn resolve_specifier(
+    &self,
+    specifier: &str,
+    referrer: &ModuleSpecifier,
+  ) -> Result<ModuleSpecifier, AnyError> {
+    let permissions = PermissionsContainer::allow_all();
+    let resolved = if let Some(resolved) =
+      self.graph.resolve_dependency(specifier, referrer, false)
+    {
+      let resolved = self.graph.resolve(&resolved).clone();
+      match NpmPackageReqReference::from_specifier(&resolved) {
+        Ok(req_ref) => self.node_resolver.resolve_req_reference(
+          &req_ref,
+          &permissions,
+          referrer,
+        )?,
+        Err(_) => return Ok(resolved),
+      }
+    } else if let Some(result) = self.node_resolver.resolve_if_in_npm_package(
+      specifier,
+      referrer,
+      &permissions,
+    ) {
+      result?
+    } else {
+      // the imports of other chunks were rewritten to absolute specifiers
+      return Ok(deno_graph::resolve_import(specifier, referrer)?);
+    };
+    if self.cjs_resolutions.contains(&resolved) {
+      bail!(
+        "CommonJS modules can't be bundled. Found: {}\n    at {}",
+        resolved,
+        referrer
+      );
+    }
+    Ok(resolved)
+  }
+}
+
+impl Resolver for BundleResolver<'_> {
+  fn default_jsx_import_source(&self) -> Option<String> {
+    self.graph_resolver.default_jsx_import_source()
+  }
+
+  fn jsx_import_source_module(&self) -> &str {
+    self.graph_resolver.jsx_import_source_module()
+  }
+
+  fn resolve(
+    &self,
+    specifier: &str,
+    referrer_range: &deno_graph::Range,
+    _mode: ResolutionMode,
+  ) -> Result<ModuleSpecifier, ResolveError> {
+    Ok(self.resolve_specifier(specifier, &referrer_range.specifier)?)
+  }
+}
+
+/// Loads the modules of a bundle graph from the module graph it's built
+/// from, or from the npm cache for the modules of npm packages.
+struct BundleLoader<'a> {
+  graph: &'a ModuleGraph,
+  resolver: &'a BundleResolver<'a>,
+  /// The roots of the chunks which the bundle imports instead of inlining.
+  external_chunks: HashSet<ModuleSpecifier>,
+}
+
+impl BundleLoader<'_> {
+  fn is_external(&self, specifier: &ModuleSpecifier) -> bool {
+    specifier.scheme() == "node" || self.external_chunks.contains(specifier)
+  }
+
+  fn load_module(&self, specifier: &ModuleSpecifier) -> LoadResult {
+    if self.is_external(specifier) {
+      return Ok(Some(LoadResponse::External {
+        specifier: specifier.clone(),
+      }));
+    }
+    let (specifier, source, media_type) = match self.graph.get(specifier) {
+      Some(Module::Js(module)) => (
+        module.specifier.clone(),
+        module.source.clone(),
+        module.media_type,
+      ),
+      Some(Module::Json(module)) => (
+        module.specifier.clone(),
+        module.source.clone(),
+        module.media_type,
+      ),
+      // the modules of npm packages are external to the module graph
+      _ if self.resolver.node_resolver.in_npm_package(specifier) => {
+        let path = specifier_to_file_path(specifier)?;
+        let source = std::fs::read_to_string(&path)
+          .with_context(|| format!("Failed reading '{}'.", path.display()))?;
+        (
+          specifier.clone(),
+          source.into(),
+          MediaType::from_specifier(specifier),
+        )
+      }
+      _ => return Ok(None),
+    };
+    let source = if media_type == MediaType::Json {
+      source
+    } else {
+      self.rewrite_external_imports(&specifier, source, media_type)?
+    };
+    let maybe_headers = if specifier.scheme() == "file" {
+      None
+    } else {
+      media_type.as_content_type().map(|content_type| {
+        HashMap::from([("content-type".to_string(), content_type.to_string())])
+      })
+    };
+    Ok(Some(LoadResponse::Module {
+      content: source.as_bytes().into(),
+      specifier,
+      maybe_headers,
+    }))
+  }
+
+  /// Rewrites the imports of external modules to their absolute specifiers,
+  /// because the bundler only leaves imports of external modules as they
+  /// are when they're written that way.
+  fn rewrite_external_imports(
+    &self,
+    specifier: &ModuleSpecifier,
+    source: Arc<str>,
+    media_type: MediaType,
+  ) -> Result<Arc<str>, AnyError> {
+    let parsed_source = deno_ast::parse_module(ParseParams {
+      specifier: specifier.clone(),
+      text_info: SourceTextInfo::new(source.clone()),
+      media_type,
+      capture_tokens: false,
+      scope_analysis: false,
+      maybe_syntax: None,
+    })?;
+    let text_changes = import_text_changes(&parsed_source, |text| {
+      self
+        .resolver
+        .resolve_specifier(text, specifier)
+        .ok()
+        .filter(|resolved| self.is_external(resolved))
+        .map(|resolved| resolved.to_string())
+    });
+    if text_changes.is_empty() {
+      return Ok(source);
+    }
+    Ok(deno_ast::apply_text_changes(&source, text_changes).into())
+  }
+}
+
+impl Loader for BundleLoader<'_> {
+  fn load(
+    &mut self,
+    specifier: &ModuleSpecifier,
+    _is_dynamic: bool,
+    _cache_setting: CacheSetting,
+  ) -> LoadFuture {
+    let result = self.load_module(specifier);
+    async move { result }.boxed_local()
+  }
+}
+
+/// Builds the graph of the code which is bundled from a root, where the
+/// roots of the other chunks are external modules.
+async fn build_bundle_graph(
+  root: &ModuleSpecifier,
+  graph: &ModuleGraph,
+  resolver: &BundleResolver<'_>,
+  external_chunks: HashSet<ModuleSpecifier>,
+) -> Result<ModuleGraph, AnyError> {
+  let mut loader = BundleLoader {
+    graph,
+    resolver,
+    external_chunks,
+  };
+  let mut bundle_graph = ModuleGraph::new(GraphKind::CodeOnly);
+  bundle_graph
+    .build(
+      vec![root.clone()],
+      &mut loader,
+      BuildOptions {
+        resolver: Some(resolver),
+        ..Default::default()
+      },
+    )
+    .await;
+  bundle_graph.valid()?;
+  Ok(bundle_graph)
+}
+
+fn write_bundle(
+  out_file: &Path,
+  bundle_output: deno_emit::BundleEmit,
+) -> Result<(), AnyError> {
+  let mut code = bundle_output.code;
+  let maybe_map_out_file = bundle_output.maybe_map.as_ref().map(|_| {
+    let ext = if let Some(curr_ext) = out_file.extension() {
+      format!("{}.map", curr_ext.to_string_lossy())
+    } else {
+      "map".to_string()
+    };
+    out_file.with_extension(ext)
+  });
+  if let Some(map_out_file) = &maybe_map_out_file {
+    if !code.ends_with('\n') {
+      code.push('\n');
+    }
+    code.push_str(&format!(
+      "//# sourceMappingURL={}\n",
+      map_out_file.file_name().unwrap().to_string_lossy()
+    ));
+  }
+  let output_bytes = code.as_bytes();
+  let output_len = output_bytes.len();
+  util::fs::write_file(out_file, output_bytes, 0o644)?;
+  log::info!(
+    "{} {:?} ({})",
+    colors::green("Emit"),
+    out_file,
+    colors::gray(display::human_size(output_len as f64))
+  );
+  if let (Some(bundle_map), Some(map_out_file)) =
+    (bundle_output.maybe_map, maybe_map_out_file)
+  {
+    let map_bytes = bundle_map.as_bytes();
+    let map_len = map_bytes.len();
+    util::fs::write_file(&map_out_file, map_bytes, 0o644)?;
     log::info!(
       "{} {:?} ({})",
       colors::green("Emit"),
-      out_file,
-      colors::gray(display::human_size(output_len as f64))
+      map_out_file,
+      colors::gray(display::human_size(map_len as f64))
     );
-    if let Some(bundle_map) = bundle_output.maybe_map {
-      let map_bytes = bundle_map.as_bytes();
-      let map_len = map_bytes.len();
-      let ext = if let Some(curr_ext) = out_file.extension() {
-        format!("{}.map", curr_ext.to_string_lossy())
-      } else {
-        "map".to_string()
+  }
+  Ok(())
+}
+
+/// Collects the roots of the chunks of a bundle, which are the modules that
+/// are imported dynamically and the modules that are shared between chunks.
+/// Every other module is only reached from one chunk, which it's bundled
+/// into, so no module ends up in two chunks.
+fn collect_chunks(
+  graph: &ModuleGraph,
+  root: &ModuleSpecifier,
+) -> IndexSet<ModuleSpecifier> {
+  let mut chunks = IndexSet::from([root.clone()]);
+  let mut imports: HashMap<&ModuleSpecifier, Vec<&ModuleSpecifier>> =
+    HashMap::new();
+  for module in graph.modules() {
+    let Some(module) = module.js() else {
+      continue;
+    };
+    for dep in module.dependencies.values() {
+      let Some(resolved) = dep.maybe_code.ok() else {
+        continue;
       };
-      let map_out_file = out_file.with_extension(ext);
-      util::fs::write_file(&map_out_file, map_bytes, 0o644)?;
-      log::info!(
-        "{} {:?} ({})",
-        colors::green("Emit"),
-        map_out_file,
-        colors::gray(display::human_size(map_len as f64))
-      );
+      let specifier = graph.resolve(&resolved.specifier);
+      // json modules need to be imported with an import attribute, so
+      // they're inlined into every chunk that imports them
+      let Some(Module::Js(imported)) = graph.get(specifier) else {
+        continue;
+      };
+      if dep.is_dynamic {
+        chunks.insert(imported.specifier.clone());
+      } else {
+        imports
+          .entry(&module.specifier)
+          .or_default()
+          .push(&imported.specifier);
+      }
     }
-  } else {
-    println!("{}", bundle_output.code);
   }
-  Ok(())
+  let mut importers: HashMap<&ModuleSpecifier, Vec<&ModuleSpecifier>> =
+    HashMap::new();
+  for (importer, imported) in &imports {
+    for specifier in imported {
+      importers.entry(*specifier).or_default().push(*importer);
+    }
+  }
+
+  loop {
+    let reached_by = chunks_reaching_modules(&imports, &chunks);
+    // only the topmost of the shared modules become chunks, so a module
+    // shared along with its importer stays in the importer's chunk
+    let shared = reached_by
+      .iter()
+      .filter(|(specifier, by)| {
+        by.len() > 1
+          && importers
+            .get(*specifier)
+            .into_iter()
+            .flatten()
+            .any(|importer| {
+              chunks.contains(*importer)
+                || reached_by.get(importer) != Some(*by)
+            })
+      })
+      .map(|(specifier, _)| (*specifier).clone())
+      .collect::<Vec<_>>();
+    if shared.is_empty() {
+      return chunks;
+    }
+    chunks.extend(shared);
+  }
+}
+
+/// Gets the chunks each module is reached from through static imports,
+/// without going through the roots of other chunks.
+fn chunks_reaching_modules<'a>(
+  imports: &HashMap<&'a ModuleSpecifier, Vec<&'a ModuleSpecifier>>,
+  chunks: &'a IndexSet<ModuleSpecifier>,
+) -> IndexMap<&'a ModuleSpecifier, IndexSet<&'a ModuleSpecifier>> {
+  let mut reached_by: IndexMap<_, IndexSet<_>> = IndexMap::new();
+  for chunk in chunks {
+    let mut pending = vec![chunk];
+    while let Some(specifier) = pending.pop() {
+      if !reached_by.entry(specifier).or_default().insert(chunk) {
+        continue;
+      }
+      for imported in imports.get(specifier).into_iter().flatten() {
+        if !chunks.contains(*imported) {
+          pending.push(*imported);
+        }
+      }
+    }
+  }
+  reached_by
+}
+
+/// Gets the file name of the chunk of a module, which is unique because it
+/// contains a hash of the specifier.
+fn chunk_file_name(specifier: &ModuleSpecifier) -> String {
+  let file_name = specifier
+    .path_segments()
+    .and_then(|mut segments| segments.next_back())
+    .unwrap_or("");
+  let stem = match file_name.split_once('.') {
+    Some((stem, _)) => stem,
+    None => file_name,
+  };
+  let stem = if stem.is_empty() { "chunk" } else { stem };
+  let hash = checksum::gen(&[specifier.as_str().as_bytes()]);
+  format!("{}-{}.js", stem, &hash[..8])
+}
+
+/// Points the imports of other chunks in the bundle of a chunk to their
+/// files. The bundle keeps the imports of other chunks as the absolute
+/// specifiers they were rewritten to when building its graph.
+fn link_chunks(
+  chunk_specifier: &ModuleSpecifier,
+  chunks: &IndexMap<ModuleSpecifier, String>,
+  bundle_output: deno_emit::BundleEmit,
+) -> Result<deno_emit::BundleEmit, AnyError> {
+  let parsed_source = deno_ast::parse_module(ParseParams {
+    specifier: chunk_specifier.clone(),
+    text_info: SourceTextInfo::new(bundle_output.code.into()),
+    media_type: MediaType::JavaScript,
+    capture_tokens: false,
+    scope_analysis: false,
+    maybe_syntax: None,
+  })?;
+  let text = parsed_source.text_info().text_str();
+  let text_changes = import_text_changes(&parsed_source, |value| {
+    let specifier = ModuleSpecifier::parse(value).ok()?;
+    chunks
+      .get(&specifier)
+      .map(|file_name| format!("./{}", file_name))
+  });
+  let maybe_map = match bundle_output.maybe_map {
+    Some(map) => Some(shift_source_map(&map, text, &text_changes)?),
+    None => None,
+  };
+  Ok(deno_emit::BundleEmit {
+    code: deno_ast::apply_text_changes(text, text_changes),
+    maybe_map,
+  })
+}
+
+/// Gets the text changes which replace the specifiers of the static imports
+/// and exports and of the dynamic imports of a module.
+fn import_text_changes(
+  parsed_source: &ParsedSource,
+  replace: impl Fn(&str) -> Option<String>,
+) -> Vec<TextChange> {
+  let text_info = parsed_source.text_info();
+  let text = text_info.text_str();
+  let module_info = DefaultModuleAnalyzer::module_info(parsed_source);
+  let mut text_changes = Vec::new();
+  for dep in &module_info.dependencies {
+    let (value, range) = match dep {
+      DependencyDescriptor::Static(dep) => {
+        (&dep.specifier, &dep.specifier_range)
+      }
+      DependencyDescriptor::Dynamic(dep) => match &dep.argument {
+        DynamicArgument::String(value) => (value, &dep.argument_range),
+        _ => continue,
+      },
+    };
+    let Some(new_text) = replace(value).filter(|new_text| new_text != value)
+    else {
+      continue;
+    };
+    let range = range
+      .as_source_range(text_info)
+      .as_byte_range(text_info.range().start);
+    let Some(relative_index) = text[range.start..].find(value.as_str()) else {
+      continue;
+    };
+    let start = range.start + relative_index;
+    text_changes.push(TextChange {
+      range: start..start + value.len(),
+      new_text,
+    });
+  }
+  text_changes
+}
+
+/// Shifts the generated columns of a source map after text changes within
+/// a line, which is the case for the specifiers of imports.
+fn shift_source_map(
+  map: &str,
+  text: &str,
+  text_changes: &[TextChange],
+) -> Result<String, AnyError> {
+  // the line and column of every change with the amount of columns it shifts
+  let shifts = text_changes
+    .iter()
+    .map(|change| {
+      let line_start = text[..change.range.start]
+        .rfind('\n')
+        .map(|i| i + 1)
+        .unwrap_or(0);
+      let line = text[..line_start].matches('\n').count() as u32;
+      let col = text[line_start..change.range.start].encode_utf16().count();
+      let old_len = text[change.range.clone()].encode_utf16().count();
+      let new_len = change.new_text.encode_utf16().count();
+      (line, col as u32, new_len as i64 - old_len as i64)
+    })
+    .collect::<Vec<_>>();
+  let source_map = SourceMap::from_slice(map.as_bytes())?;
+  let mut builder = SourceMapBuilder::new(source_map.get_file());
+  for (index, source) in source_map.sources().enumerate() {
+    let source_id = builder.add_source(source);
+    builder.set_source_contents(
+      source_id,
+      source_map.get_source_contents(index as u32),
+    );
+  }
+  for token in source_map.tokens() {
+    let shift = shifts
+      .iter()
+      .filter(|(line, col, _)| {
+        *line == token.get_dst_line() && *col < token.get_dst_col()
+      })
+      .map(|(_, _, shift)| shift)
+      .sum::<i64>();
+    builder.add(
+      token.get_dst_line(),
+      (token.get_dst_col() as i64 + shift) as u32,
+      token.get_src_line(),
+      token.get_src_col(),
+      token.get_source(),
+      token.get_name(),
+    );
+  }
+  let mut buf = Vec::new();
+  builder.into_sourcemap().to_writer(&mut buf)?;
+  Ok(String::from_utf8(buf)?)
 }
 
 fn bundle_module_graph(
   graph: &deno_graph::ModuleGraph,
   cli_options: &CliOptions,
+  bundle_flags: &BundleFlags,
+  inline_source_map: bool,
 ) -> Result<deno_emit::BundleEmit, AnyError> {
   log::info!("{} {}", colors::green("Bundle"), graph.roots[0]);
 
@@ -143,15 +692,120 @@ fn bundle_module_graph(
     }
   }
 
+  let mut emit_options =
+    crate::args::ts_config_to_emit_options(ts_config_result.ts_config);
+  if bundle_flags.source_map {
+    emit_options.inline_source_map = inline_source_map;
+    emit_options.source_map = !inline_source_map;
+  }
   deno_emit::bundle_graph(
     graph,
     deno_emit::BundleOptions {
-      minify: false,
+      minify: bundle_flags.minify,
       bundle_type: deno_emit::BundleType::Module,
-      emit_options: crate::args::ts_config_to_emit_options(
-        ts_config_result.ts_config,
-      ),
+      emit_options,
       emit_ignore_directives: true,
     },
   )
 }
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_chunk_file_name() {
+    let specifier = ModuleSpecifier::parse("file:///dir/mod.ts").unwrap();
+    let file_name = chunk_file_name(&specifier);
+    assert!(file_name.starts_with("mod-"), "{}", file_name);
+    assert!(file_name.ends_with(".js"), "{}", file_name);
+    assert_eq!(file_name.len(), "mod-".len() + 8 + ".js".len());
+    // different modules get different chunks
+    let other = ModuleSpecifier::parse("file:///other/mod.ts").unwrap();
+    assert_ne!(chunk_file_name(&other), file_name);
+    let specifier = ModuleSpecifier::parse("https://deno.land/").unwrap();
+    assert!(chunk_file_name(&specifier).starts_with("chunk-"));
+  }
+
+  #[tokio::test]
+  async fn test_collect_chunks() {
+    let sources = [
+      (
+        "file:///main.ts",
+        "import \"./util.ts\"; import(\"./a.ts\"); import(\"./b.ts\");",
+      ),
+      ("file:///a.ts", "import \"./shared.ts\";"),
+      (
+        "file:///b.ts",
+        "import \"./shared.ts\"; import \"./b_only.ts\";",
+      ),
+      (
+        "file:///shared.ts",
+        "import \"./util.ts\"; import \"./data.json\" with { type: \"json\" };",
+      ),
+      ("file:///util.ts", "export const a = 1;"),
+      ("file:///b_only.ts", "export const b = 1;"),
+      ("file:///data.json", "{}"),
+    ];
+    let mut loader = deno_graph::source::MemoryLoader::new(
+      sources
+        .iter()
+        .map(|(specifier, content)| {
+          (
+            *specifier,
+            deno_graph::source::Source::Module {
+              specifier: *specifier,
+              maybe_headers: None,
+              content: *content,
+            },
+          )
+        })
+        .collect(),
+      Vec::new(),
+    );
+    let root = ModuleSpecifier::parse("file:///main.ts").unwrap();
+    let mut graph = ModuleGraph::new(GraphKind::CodeOnly);
+    graph
+      .build(vec![root.clone()], &mut loader, Default::default())
+      .await;
+    let chunks = collect_chunks(&graph, &root)
+      .into_iter()
+      .map(|specifier| specifier.to_string())
+      .collect::<Vec<_>>();
+    // util.ts is shared by main.ts and shared.ts, while shared.ts is shared
+    // by a.ts and b.ts, and the json module is inlined
+    assert_eq!(
+      chunks,
+      vec![
+        "file:///main.ts",
+        "file:///a.ts",
+        "file:///b.ts",
+        "file:///util.ts",
+        "file:///shared.ts",
+      ]
+    );
+  }
+
+  #[test]
+  fn test_shift_source_map() {
+    let text = "import(\"./a.ts\");\nfoo();";
+    let mut builder = SourceMapBuilder::new(None);
+    builder.add(0, 0, 0, 0, Some("file:///main.ts"), None);
+    builder.add(0, 16, 0, 20, Some("file:///main.ts"), None);
+    builder.add(1, 0, 1, 0, Some("file:///main.ts"), None);
+    let mut buf = Vec::new();
+    builder.into_sourcemap().to_writer(&mut buf).unwrap();
+    let map = String::from_utf8(buf).unwrap();
+    let text_changes = vec![TextChange {
+      range: 8..14,
+      new_text: "./a-12345678.js".to_string(),
+    }];
+    let map = shift_source_map(&map, text, &text_changes).unwrap();
+    let source_map = SourceMap::from_slice(map.as_bytes()).unwrap();
+    let positions = source_map
+      .tokens()
+      .map(|t| (t.get_dst_line(), t.get_dst_col()))
+      .collect::<Vec<_>>();
+    assert_eq!(positions, vec![(0, 0), (0, 25), (1, 0)]);
+  }
+}
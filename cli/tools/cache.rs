@@ -0,0 +1,270 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+
+use crate::args::CachePruneFlags;
+use crate::args::Flags;
+use crate::cache::HttpCacheAccessCache;
+use crate::colors;
+use crate::factory::CliFactory;
+use crate::util::display::human_size;
+
+/// The max age of the entries when neither a max age nor a max size is
+/// specified, which is 30 days.
+const DEFAULT_MAX_AGE_SECS: u64 = 30 * 24 * 60 * 60;
+
+/// A remote module of the global HTTP cache along with its metadata file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HttpCacheEntry {
+  url: Option<String>,
+  paths: Vec<PathBuf>,
+  size: u64,
+  /// The time in seconds since the epoch when the entry was last used.
+  last_access: u64,
+}
+
+/// The size of the global HTTP cache, which is shown by the status page of
+/// the language server.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HttpCacheStats {
+  pub entries: usize,
+  pub size: u64,
+}
+
+pub fn http_cache_stats(deps_path: &Path) -> HttpCacheStats {
+  let entries = collect_http_cache_entries(deps_path, None);
+  HttpCacheStats {
+    entries: entries.len(),
+    size: entries.iter().map(|e| e.size).sum(),
+  }
+}
+
+fn secs_since_epoch(time: SystemTime) -> u64 {
+  time
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_secs())
+    .unwrap_or(0)
+}
+
+/// Walks the global HTTP cache, where every module is stored in a file next
+/// to a ".metadata.json" file with its URL and headers. Entries which were
+/// never recorded as accessed fall back to the time they were cached.
+fn collect_http_cache_entries(
+  deps_path: &Path,
+  maybe_access_cache: Option<&HttpCacheAccessCache>,
+) -> Vec<HttpCacheEntry> {
+  let mut entries = Vec::new();
+  let mut pending_dirs = vec![deps_path.to_path_buf()];
+  while let Some(dir) = pending_dirs.pop() {
+    let Ok(read_dir) = std::fs::read_dir(&dir) else {
+      continue;
+    };
+    for entry in read_dir.flatten() {
+      let path = entry.path();
+      let Ok(metadata) = entry.metadata() else {
+        continue;
+      };
+      if metadata.is_dir() {
+        pending_dirs.push(path);
+        continue;
+      }
+      if path.to_string_lossy().ends_with(".metadata.json") {
+        continue; // handled with the file of the module
+      }
+      let metadata_path = path.with_extension("metadata.json");
+      let mut size = metadata.len();
+      let mut paths = vec![path];
+      let mut url = None;
+      if let Ok(text) = std::fs::read_to_string(&metadata_path) {
+        size += text.len() as u64;
+        paths.push(metadata_path);
+        url = serde_json::from_str::<serde_json::Value>(&text)
+          .ok()
+          .and_then(|value| Some(value.get("url")?.as_str()?.to_string()));
+      }
+      let maybe_last_access = match (&url, maybe_access_cache) {
+        (Some(url), Some(cache)) => cache.get_last_access(url).ok().flatten(),
+        _ => None,
+      };
+      let last_access = maybe_last_access.unwrap_or_else(|| {
+        metadata.modified().map(secs_since_epoch).unwrap_or(0)
+      });
+      entries.push(HttpCacheEntry {
+        url,
+        paths,
+        size,
+        last_access,
+      });
+    }
+  }
+  entries
+}
+
+/// Selects the entries which weren't used for longer than the max age and
+/// then the least recently used entries until the rest fits in the max size.
+fn select_entries_to_evict(
+  entries: &[HttpCacheEntry],
+  now: u64,
+  maybe_max_age: Option<u64>,
+  maybe_max_size: Option<u64>,
+) -> Vec<usize> {
+  let mut indexes = (0..entries.len()).collect::<Vec<_>>();
+  // least recently used first
+  indexes.sort_by_key(|i| entries[*i].last_access);
+  let mut evicted = Vec::new();
+  let mut remaining_size = entries.iter().map(|e| e.size).sum::<u64>();
+  for index in indexes {
+    let entry = &entries[index];
+    let is_stale = maybe_max_age
+      .map(|max_age| now.saturating_sub(entry.last_access) > max_age)
+      .unwrap_or(false);
+    let is_too_large = maybe_max_size
+      .map(|max_size| remaining_size > max_size)
+      .unwrap_or(false);
+    if is_stale || is_too_large {
+      remaining_size -= entry.size;
+      evicted.push(index);
+    }
+  }
+  evicted
+}
+
+/// Evicts the stale entries of the global HTTP cache.
+pub async fn prune(
+  flags: Flags,
+  prune_flags: CachePruneFlags,
+) -> Result<(), AnyError> {
+  let factory = CliFactory::from_flags(flags).await?;
+  let deps_path = factory.deno_dir()?.deps_folder_path();
+  let access_cache = factory.http_cache_access_cache()?;
+  let maybe_max_age = match (prune_flags.max_age, prune_flags.max_size) {
+    (None, None) => Some(DEFAULT_MAX_AGE_SECS),
+    (max_age, _) => max_age,
+  };
+
+  let entries = collect_http_cache_entries(&deps_path, Some(access_cache));
+  let evicted = select_entries_to_evict(
+    &entries,
+    secs_since_epoch(SystemTime::now()),
+    maybe_max_age,
+    prune_flags.max_size,
+  );
+  let mut freed_size = 0;
+  for index in &evicted {
+    let entry = &entries[*index];
+    for path in &entry.paths {
+      match std::fs::remove_file(path) {
+        Ok(()) => {}
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+        Err(err) => return Err(err.into()),
+      }
+    }
+    // remove the directory of the host once it's empty
+    if let Some(parent) = entry.paths[0].parent() {
+      let _ = std::fs::remove_dir(parent);
+    }
+    if let Some(url) = &entry.url {
+      access_cache.remove(url)?;
+    }
+    freed_size += entry.size;
+  }
+
+  log::info!(
+    "{} {} of {} {} of the HTTP cache, freeing {}.",
+    colors::green("Removed"),
+    evicted.len(),
+    entries.len(),
+    if entries.len() == 1 {
+      "entry"
+    } else {
+      "entries"
+    },
+    human_size(freed_size as f64),
+  );
+  Ok(())
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use pretty_assertions::assert_eq;
+  use test_util::TempDir;
+
+  fn entry(last_access: u64, size: u64) -> HttpCacheEntry {
+    HttpCacheEntry {
+      url: None,
+      paths: vec![],
+      size,
+      last_access,
+    }
+  }
+
+  #[test]
+  fn test_select_entries_to_evict() {
+    let entries = vec![entry(100, 10), entry(10, 20), entry(50, 30)];
+    assert_eq!(
+      select_entries_to_evict(&entries, 100, Some(60), None),
+      vec![1]
+    );
+    assert_eq!(
+      select_entries_to_evict(&entries, 100, Some(30), None),
+      vec![1, 2]
+    );
+    assert_eq!(
+      select_entries_to_evict(&entries, 100, None, Some(40)),
+      vec![1]
+    );
+    assert_eq!(
+      select_entries_to_evict(&entries, 100, None, Some(30)),
+      vec![1, 2]
+    );
+    assert_eq!(
+      select_entries_to_evict(&entries, 100, None, Some(60)),
+      Vec::<usize>::new()
+    );
+    assert_eq!(
+      select_entries_to_evict(&entries, 100, Some(60), Some(10)),
+      vec![1, 2]
+    );
+  }
+
+  #[test]
+  fn test_collect_http_cache_entries() {
+    let temp_dir = TempDir::new();
+    let host_dir = temp_dir.path().join("https/deno.land");
+    host_dir.create_dir_all();
+    host_dir.join("a1b2").write("export {};");
+    host_dir
+      .join("a1b2.metadata.json")
+      .write(r#"{"headers":{},"url":"https://deno.land/x/mod.ts"}"#);
+    let access_cache = HttpCacheAccessCache::new_in_memory();
+    access_cache
+      .set_last_access("https://deno.land/x/mod.ts", 42)
+      .unwrap();
+
+    let entries = collect_http_cache_entries(
+      temp_dir.path().as_path(),
+      Some(&access_cache),
+    );
+    assert_eq!(entries.len(), 1);
+    assert_eq!(
+      entries[0].url.as_deref(),
+      Some("https://deno.land/x/mod.ts")
+    );
+    assert_eq!(entries[0].last_access, 42);
+    assert_eq!(entries[0].paths.len(), 2);
+    assert_eq!(
+      http_cache_stats(temp_dir.path().as_path()),
+      HttpCacheStats {
+        entries: 1,
+        size: entries[0].size,
+      }
+    );
+  }
+}
@@ -1,12 +1,17 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::collections::VecDeque;
 use std::sync::Arc;
 
 use deno_ast::MediaType;
 use deno_ast::ModuleSpecifier;
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_graph::GraphKind;
 use deno_graph::Module;
 use deno_graph::ModuleGraph;
 use deno_runtime::deno_node::NodeResolver;
@@ -21,10 +26,20 @@ use crate::args::TsTypeLib;
 use crate::args::TypeCheckMode;
 use crate::cache::Caches;
 use crate::cache::FastInsecureHasher;
+use crate::cache::RemoteCache;
 use crate::cache::TypeCheckCache;
+use crate::factory::CliFactory;
+use crate::graph_util::graph_lock_or_exit;
+use crate::graph_util::graph_valid_with_cli_options;
+use crate::graph_util::workspace_config_to_workspace_members;
+use crate::graph_util::CreateGraphOptions;
 use crate::npm::CliNpmResolver;
+use crate::tools::registry::get_workspace_member_roots;
+use crate::tools::registry::resolve_workspace_member_order;
 use crate::tsc;
 use crate::tsc::Diagnostics;
+use crate::tsc::PinnedTypeScript;
+use crate::util::checksum;
 use crate::version;
 
 /// Options for performing a check of a module graph. Note that the decision to
@@ -44,6 +59,8 @@ pub struct TypeChecker {
   cli_options: Arc<CliOptions>,
   node_resolver: Arc<NodeResolver>,
   npm_resolver: Arc<dyn CliNpmResolver>,
+  maybe_remote_cache: Option<Arc<RemoteCache>>,
+  maybe_pinned_typescript: Option<Arc<PinnedTypeScript>>,
 }
 
 impl TypeChecker {
@@ -52,12 +69,16 @@ impl TypeChecker {
     cli_options: Arc<CliOptions>,
     node_resolver: Arc<NodeResolver>,
     npm_resolver: Arc<dyn CliNpmResolver>,
+    maybe_remote_cache: Option<Arc<RemoteCache>>,
+    maybe_pinned_typescript: Option<Arc<PinnedTypeScript>>,
   ) -> Self {
     Self {
       caches,
       cli_options,
       node_resolver,
       npm_resolver,
+      maybe_remote_cache,
+      maybe_pinned_typescript,
     }
   }
 
@@ -111,10 +132,17 @@ impl TypeChecker {
     }
 
     let ts_config = ts_config_result.ts_config;
+    // a trace needs the files to be checked again
+    let maybe_trace_path = self.cli_options.check_trace_path();
+    let reload = options.reload || maybe_trace_path.is_some();
     let type_check_mode = self.cli_options.type_check_mode();
     let debug = self.cli_options.log_level() == Some(log::Level::Debug);
     let cache = TypeCheckCache::new(self.caches.type_checking_cache_db());
     let check_js = ts_config.get_check_js();
+    let typescript_version = match &self.maybe_pinned_typescript {
+      Some(pinned_typescript) => pinned_typescript.version().to_string(),
+      None => version::TYPESCRIPT.to_string(),
+    };
     let maybe_check_hash = match self.npm_resolver.check_state_hash() {
       Some(npm_check_hash) => {
         match get_check_hash(
@@ -122,6 +150,7 @@ impl TypeChecker {
           npm_check_hash,
           type_check_mode,
           &ts_config,
+          &typescript_version,
         ) {
           CheckHashResult::NoFiles => return Ok(Default::default()),
           CheckHashResult::Hash(hash) => Some(hash),
@@ -131,14 +160,37 @@ impl TypeChecker {
     };
 
     // do not type check if we know this is type checked
-    if !options.reload {
+    if !reload {
       if let Some(check_hash) = maybe_check_hash {
         if cache.has_check_hash(check_hash) {
           return Ok(Default::default());
         }
+        // the same inputs failed type checking before, so report the same
+        // diagnostics again
+        if let Some(mut diagnostics) = cache.get_check_diagnostics(check_hash) {
+          for root in &graph.roots {
+            log::info!("{} {}", colors::green("Check"), root);
+          }
+          diagnostics.apply_fast_check_source_maps(&graph);
+          return Ok(diagnostics);
+        }
+        // it might've been type checked on another machine
+        if let Some(remote_cache) = &self.maybe_remote_cache {
+          let digest = get_remote_check_digest(&graph, check_hash);
+          if remote_cache.has_check_hash(check_hash, &digest).await {
+            cache.add_check_hash(check_hash);
+            return Ok(Default::default());
+          }
+        }
       }
     }
 
+    if let Some(pinned_typescript) = &self.maybe_pinned_typescript {
+      pinned_typescript
+        .ensure_cached(self.npm_resolver.as_ref())
+        .await?;
+    }
+
     for root in &graph.roots {
       let root_str = root.as_str();
       log::info!("{} {}", colors::green("Check"), root_str);
@@ -148,7 +200,7 @@ impl TypeChecker {
     // while there might be multiple roots, we can't "merge" the build info, so we
     // try to retrieve the build info for first root, which is the most common use
     // case.
-    let maybe_tsbuildinfo = if options.reload {
+    let maybe_tsbuildinfo = if reload {
       None
     } else {
       cache.get_tsbuildinfo(&graph.roots[0])
@@ -159,6 +211,7 @@ impl TypeChecker {
     let hash_data = FastInsecureHasher::new()
       .write(&ts_config.as_bytes())
       .write_str(version::deno())
+      .write_str(&typescript_version)
       .finish();
 
     let response = tsc::exec(tsc::Request {
@@ -173,6 +226,8 @@ impl TypeChecker {
       maybe_tsbuildinfo,
       root_names,
       check_mode: type_check_mode,
+      trace: maybe_trace_path.is_some(),
+      maybe_pinned_typescript: self.maybe_pinned_typescript.clone(),
     })?;
 
     let mut diagnostics = if type_check_mode == TypeCheckMode::Local {
@@ -198,24 +253,121 @@ impl TypeChecker {
       response.diagnostics
     };
 
-    diagnostics.apply_fast_check_source_maps(&graph);
-
     if let Some(tsbuildinfo) = response.maybe_tsbuildinfo {
       cache.set_tsbuildinfo(&graph.roots[0], &tsbuildinfo);
     }
 
-    if diagnostics.is_empty() {
-      if let Some(check_hash) = maybe_check_hash {
+    if let Some(check_hash) = maybe_check_hash {
+      if diagnostics.is_empty() {
         cache.add_check_hash(check_hash);
+        if let Some(remote_cache) = &self.maybe_remote_cache {
+          let digest = get_remote_check_digest(&graph, check_hash);
+          remote_cache.add_check_hash(check_hash, &digest).await;
+        }
+      } else {
+        // store these before mapping them, which is done again when loaded
+        cache.set_check_diagnostics(check_hash, &diagnostics);
       }
     }
 
-    log::debug!("{}", response.stats);
+    diagnostics.apply_fast_check_source_maps(&graph);
+
+    match (maybe_trace_path, response.maybe_trace) {
+      (Some(trace_path), Some(trace)) => {
+        let text = serde_json::to_string(&trace.to_chrome_trace())?;
+        std::fs::write(&trace_path, text).with_context(|| {
+          format!("Failed writing trace \"{}\"", trace_path.display())
+        })?;
+        log::info!("{}", response.stats);
+        log::info!("{}", trace);
+        log::info!("{} {}", colors::green("Trace"), trace_path.display());
+      }
+      _ => log::debug!("{}", response.stats),
+    }
 
     Ok(diagnostics)
   }
 }
 
+/// Type checks the exports of every member of the workspace for
+/// `deno check --workspace`. The members are checked in the order of their
+/// dependencies, each one against the fast check types of the members it
+/// imports instead of their sources, so a member only gets checked again when
+/// its own modules or the public types of its dependencies change.
+pub async fn check_workspace(factory: &CliFactory) -> Result<(), AnyError> {
+  let cli_options = factory.cli_options();
+  let Some(workspace_config) = cli_options.maybe_workspace_config() else {
+    bail!("'--workspace' requires a configuration file with \"workspaces\"");
+  };
+  let module_graph_builder = factory.module_graph_builder().await?;
+  let type_checker = factory.type_checker().await?;
+  let member_order = resolve_workspace_member_order(
+    module_graph_builder,
+    workspace_config,
+    GraphKind::TypesOnly,
+  )
+  .await?;
+  let mut member_roots = get_workspace_member_roots(workspace_config)?
+    .into_iter()
+    .map(|roots| (roots.name.clone(), roots))
+    .collect::<HashMap<_, _>>();
+  let workspace_members =
+    workspace_config_to_workspace_members(workspace_config)?;
+
+  let mut diagnostics = Diagnostics::default();
+  for package_name in member_order {
+    let Some(roots) = member_roots.remove(&package_name) else {
+      continue;
+    };
+    log::info!("{} {}", colors::green("Check"), package_name);
+    // the other members are resolved with fast check, which is what makes
+    // their types work like the declaration files of project references
+    let other_members = workspace_config
+      .members
+      .iter()
+      .zip(workspace_members.iter())
+      .filter(|(member, _)| member.package_name != package_name)
+      .map(|(_, workspace_member)| workspace_member.clone())
+      .collect();
+    let graph = module_graph_builder
+      .create_graph_with_options(CreateGraphOptions {
+        graph_kind: GraphKind::TypesOnly,
+        roots: roots.exports,
+        workspace_fast_check: true,
+        workspace_members: Some(other_members),
+        loader: None,
+      })
+      .await?;
+    graph_valid_with_cli_options(
+      &graph,
+      factory.fs().as_ref(),
+      &graph.roots,
+      cli_options,
+    )?;
+    if let Some(lockfile) = factory.maybe_lockfile() {
+      graph_lock_or_exit(&graph, &mut lockfile.lock());
+    }
+    diagnostics.extend(
+      type_checker
+        .check_diagnostics(
+          Arc::new(graph),
+          CheckOptions {
+            lib: cli_options.ts_type_lib_window(),
+            log_ignored_options: false,
+            reload: cli_options.reload_flag(),
+          },
+        )
+        .await?,
+    );
+  }
+
+  if diagnostics.is_empty() {
+    Ok(())
+  } else {
+    Err(diagnostics.into())
+  }
+}
+
 enum CheckHashResult {
   Hash(u64),
   NoFiles,
@@ -228,6 +380,7 @@ fn get_check_hash(
   package_reqs_hash: u64,
   type_check_mode: TypeCheckMode,
   ts_config: &TsConfig,
+  typescript_version: &str,
 ) -> CheckHashResult {
   let mut hasher = FastInsecureHasher::new();
   hasher.write_u8(match type_check_mode {
@@ -236,6 +389,7 @@ fn get_check_hash(
     TypeCheckMode::None => 2,
   });
   hasher.write(&ts_config.as_bytes());
+  hasher.write_str(typescript_version);
 
   let check_js = ts_config.get_check_js();
   let mut has_file = false;
@@ -314,6 +468,37 @@ fn get_check_hash(
   }
 }
 
+/// Gets a SHA-256 digest of the inputs for type checking, which verifies the
+/// check hashes of the remote cache because the check hash can collide.
+fn get_remote_check_digest(graph: &ModuleGraph, check_hash: u64) -> String {
+  let check_hash = check_hash.to_le_bytes();
+  let mut parts: Vec<&[u8]> = vec![check_hash.as_slice()];
+  for module in graph.modules() {
+    match module {
+      Module::Js(module) => {
+        parts.push(module.specifier.as_str().as_bytes());
+        parts.push(
+          module
+            .fast_check_module()
+            .map(|s| s.source.as_ref())
+            .unwrap_or(&module.source)
+            .as_bytes(),
+        );
+      }
+      Module::Json(module) => {
+        parts.push(module.specifier.as_str().as_bytes());
+        parts.push(module.source.as_bytes());
+      }
+      Module::External(module) => {
+        parts.push(module.specifier.as_str().as_bytes());
+      }
+      // these are covered by the npm snapshot of the check hash
+      Module::Node(_) | Module::Npm(_) => {}
+    }
+  }
+  checksum::gen(&parts)
+}
+
 /// Transform the graph into root specifiers that we can feed `tsc`. We have to
 /// provide the media type for root modules because `tsc` does not "resolve" the
 /// media type like other modules, as well as a root specifier needs any
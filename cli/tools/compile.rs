@@ -1,17 +1,26 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
 use crate::args::CompileFlags;
+use crate::args::DenoSubcommand;
 use crate::args::Flags;
+use crate::args::COMPILE_TARGETS;
 use crate::factory::CliFactory;
+use crate::standalone::base_binary_path_suffix;
 use crate::standalone::is_standalone_binary;
+use crate::standalone::CompileAssets;
+use crate::util::fs::canonicalize_path;
 use crate::util::path::path_has_trailing_slash;
+use deno_ast::MediaType;
 use deno_core::anyhow::bail;
 use deno_core::anyhow::Context;
 use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::resolve_url_or_path;
+use deno_core::url::Url;
+use deno_core::ModuleSpecifier;
 use deno_graph::GraphKind;
 use deno_terminal::colors;
+use std::collections::BTreeSet;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -21,6 +30,84 @@ use super::installer::infer_name_from_url;
 pub async fn compile(
   flags: Flags,
   compile_flags: CompileFlags,
+) -> Result<(), AnyError> {
+  if compile_flags.list_targets {
+    return list_targets(flags).await;
+  }
+  if compile_flags.target.as_deref() != Some("all") {
+    return compile_for_target(flags, compile_flags).await;
+  }
+
+  // compile every target separately, so the npm packages are resolved for
+  // the system of each target
+  let output_path = resolve_compile_executable_output_path(
+    &compile_flags,
+    &std::env::current_dir()?,
+  )
+  .await?;
+  for target in COMPILE_TARGETS {
+    let compile_flags = CompileFlags {
+      output: Some(output_path_for_target(&output_path, target)),
+      target: Some(target.to_string()),
+      // hiding the terminal and setting the resources is only possible on
+      // windows and signing with an identity on windows and macos
+      no_terminal: compile_flags.no_terminal && target.contains("windows"),
+      icon: compile_flags
+        .icon
+        .clone()
+        .filter(|_| target.contains("windows")),
+      version_info: compile_flags
+        .version_info
+        .clone()
+        .filter(|_| target.contains("windows")),
+      sign_identity: compile_flags.sign_identity.clone().filter(|_| {
+        target.contains("windows") || target.contains("apple-darwin")
+      }),
+      ..compile_flags.clone()
+    };
+    let mut flags = flags.clone();
+    flags.subcommand = DenoSubcommand::Compile(compile_flags.clone());
+    compile_for_target(flags, compile_flags).await?;
+  }
+  Ok(())
+}
+
+/// Prints the targets along with whether their base binary still needs to
+/// be downloaded.
+async fn list_targets(flags: Flags) -> Result<(), AnyError> {
+  let factory = CliFactory::from_flags(flags).await?;
+  let download_directory = factory.deno_dir()?.dl_folder_path();
+  for target in COMPILE_TARGETS {
+    let status = if target == env!("TARGET") {
+      Some("current")
+    } else if download_directory
+      .join(base_binary_path_suffix(target))
+      .exists()
+    {
+      Some("downloaded")
+    } else {
+      None
+    };
+    match status {
+      Some(status) => {
+        println!("{} {}", target, colors::gray(format!("({})", status)))
+      }
+      None => println!("{}", target),
+    }
+  }
+  Ok(())
+}
+
+/// Gets the output path of the executable of a target when compiling for
+/// every target, like `file_server-x86_64-apple-darwin`.
+fn output_path_for_target(output_path: &Path, target: &str) -> PathBuf {
+  let file_name = output_path.file_name().unwrap().to_string_lossy();
+  output_path.with_file_name(format!("{}-{}", file_name, target))
+}
+
+async fn compile_for_target(
+  flags: Flags,
+  compile_flags: CompileFlags,
 ) -> Result<(), AnyError> {
   let factory = CliFactory::from_flags(flags).await?;
   let cli_options = factory.cli_options();
@@ -28,14 +115,23 @@ pub async fn compile(
   let parsed_source_cache = factory.parsed_source_cache();
   let binary_writer = factory.create_compile_binary_writer().await?;
   let module_specifier = cli_options.resolve_main_module()?;
+  let (asset_includes, module_includes): (Vec<_>, Vec<_>) = compile_flags
+    .include
+    .iter()
+    .partition(|include| is_asset_include(include, cli_options.initial_cwd()));
   let module_roots = {
-    let mut vec = Vec::with_capacity(compile_flags.include.len() + 1);
+    let mut vec = Vec::with_capacity(module_includes.len() + 1);
     vec.push(module_specifier.clone());
-    for side_module in &compile_flags.include {
+    for side_module in module_includes {
       vec.push(resolve_url_or_path(side_module, cli_options.initial_cwd())?);
     }
     vec
   };
+  let maybe_assets = resolve_compile_assets(
+    &asset_includes,
+    &module_specifier,
+    cli_options.initial_cwd(),
+  )?;
 
   // this is not supported, so show a warning about it, but don't error in order
   // to allow someone to still run `deno compile` when this is in a deno.json
@@ -54,6 +150,9 @@ pub async fn compile(
     cli_options.initial_cwd(),
   )
   .await?;
+  // resolve these before compiling to error early for unsupported targets
+  let finalize_commands =
+    resolve_finalize_commands(&output_path, &compile_flags)?;
 
   let graph = Arc::try_unwrap(
     module_graph_builder
@@ -72,14 +171,27 @@ pub async fn compile(
     graph
   };
 
+  // the source maps are inlined in the emitted modules by default, which is
+  // what the standalone binary reads them from
+  let emit_options = if compile_flags.strip {
+    deno_ast::EmitOptions {
+      inline_source_map: false,
+      inline_sources: false,
+      source_map: false,
+      ..Default::default()
+    }
+  } else {
+    Default::default()
+  };
   let parser = parsed_source_cache.as_capturing_parser();
-  let eszip = eszip::EszipV2::from_graph(graph, &parser, Default::default())?;
+  let eszip = eszip::EszipV2::from_graph(graph, &parser, emit_options)?;
 
   log::info!(
-    "{} {} to {}",
+    "{} {} to {} ({})",
     colors::green("Compile"),
     module_specifier.to_string(),
     output_path.display(),
+    compile_flags.resolve_target(),
   );
   validate_output_path(&output_path)?;
 
@@ -92,6 +204,7 @@ pub async fn compile(
       &module_specifier,
       &compile_flags,
       cli_options,
+      maybe_assets.as_ref(),
     )
     .await
     .with_context(|| format!("Writing {}", output_path.display()));
@@ -107,12 +220,206 @@ pub async fn compile(
   {
     use std::os::unix::fs::PermissionsExt;
     let perms = std::fs::Permissions::from_mode(0o777);
-    std::fs::set_permissions(output_path, perms)?;
+    std::fs::set_permissions(&output_path, perms)?;
+  }
+
+  for command in finalize_commands {
+    command.run()?;
   }
 
   Ok(())
 }
 
+/// An external command which modifies the compiled executable.
+#[derive(Debug, PartialEq, Eq)]
+struct FinalizeCommand {
+  program: String,
+  args: Vec<String>,
+}
+
+impl FinalizeCommand {
+  fn run(&self) -> Result<(), AnyError> {
+    log::debug!("Running `{} {}`", self.program, self.args.join(" "));
+    let status = std::process::Command::new(&self.program)
+      .args(&self.args)
+      .status()
+      .map_err(|err| {
+        if err.kind() == std::io::ErrorKind::NotFound {
+          generic_error(format!(
+            "`{}` was not found in your PATH",
+            self.program
+          ))
+        } else {
+          err.into()
+        }
+      })?;
+    if !status.success() {
+      bail!("`{}` failed with {}", self.program, status);
+    }
+    Ok(())
+  }
+}
+
+/// Resolves the commands which set the icon and version info and then sign
+/// the executable. The resources are set first, because changing them
+/// afterwards would invalidate the signature.
+fn resolve_finalize_commands(
+  output_path: &Path,
+  compile_flags: &CompileFlags,
+) -> Result<Vec<FinalizeCommand>, AnyError> {
+  let target = compile_flags.resolve_target();
+  let output_path = output_path.to_string_lossy().to_string();
+  let mut commands = Vec::new();
+
+  if compile_flags.icon.is_some() || compile_flags.version_info.is_some() {
+    if !target.contains("windows") {
+      bail!(
+        "The `--icon` and `--version-info` flags are only available when targeting Windows (current: {})",
+        target,
+      );
+    }
+    let mut args = vec![output_path.clone()];
+    if let Some(icon) = &compile_flags.icon {
+      args.push("--set-icon".to_string());
+      args.push(icon.to_string_lossy().to_string());
+    }
+    if let Some(version) = &compile_flags.version_info {
+      args.push("--set-file-version".to_string());
+      args.push(version.clone());
+      args.push("--set-product-version".to_string());
+      args.push(version.clone());
+    }
+    commands.push(FinalizeCommand {
+      program: "rcedit".to_string(),
+      args,
+    });
+  }
+
+  if let Some(program) = &compile_flags.sign_command {
+    if program.is_empty() {
+      bail!("The `--sign-command` flag must not be empty.");
+    }
+    let mut args = compile_flags.sign_args.clone();
+    args.push(output_path);
+    commands.push(FinalizeCommand {
+      program: program.clone(),
+      args,
+    });
+  } else if let Some(identity) = &compile_flags.sign_identity {
+    let (program, args) = if target.contains("apple-darwin") {
+      (
+        "codesign",
+        vec!["--sign", identity.as_str(), "--force", output_path.as_str()],
+      )
+    } else if target.contains("windows") {
+      (
+        "signtool",
+        vec![
+          "sign",
+          "/n",
+          identity.as_str(),
+          "/fd",
+          "SHA256",
+          output_path.as_str(),
+        ],
+      )
+    } else {
+      bail!(
+        concat!(
+          "The `--sign-identity` flag is only available when targeting macOS ",
+          "or Windows (current: {}). Use `--sign-command` to sign with ",
+          "another tool."
+        ),
+        target,
+      );
+    };
+    commands.push(FinalizeCommand {
+      program: program.to_string(),
+      args: args.into_iter().map(|a| a.to_string()).collect(),
+    });
+  }
+
+  Ok(commands)
+}
+
+/// Gets if an `--include` value is embedded in the virtual file system of the
+/// executable instead of being added to the module graph, which is the case
+/// for globs, directories and files which aren't modules.
+fn is_asset_include(include: &str, cwd: &Path) -> bool {
+  // windows paths like C:\ parse as urls with a single letter scheme
+  if Url::parse(include).is_ok_and(|url| url.scheme().len() > 1) {
+    return false;
+  }
+  if is_glob(include) {
+    return true;
+  }
+  let path = cwd.join(include);
+  path.is_dir()
+    || (path.is_file() && MediaType::from_path(&path) == MediaType::Unknown)
+}
+
+fn is_glob(include: &str) -> bool {
+  include.contains(['*', '?', '['])
+}
+
+/// Resolves the files of the assets, which are mounted at the directory of
+/// the main module so they keep the same paths relative to it.
+fn resolve_compile_assets(
+  asset_includes: &[&String],
+  main_module: &ModuleSpecifier,
+  cwd: &Path,
+) -> Result<Option<CompileAssets>, AnyError> {
+  if asset_includes.is_empty() {
+    return Ok(None);
+  }
+  let root_dir = match main_module.to_file_path() {
+    Ok(path) => path.parent().unwrap().to_path_buf(),
+    Err(()) => cwd.to_path_buf(),
+  };
+  // the paths of the files are canonicalized, but the assets are mounted at
+  // the directory as it's in the url of the main module
+  let canonical_root_dir = canonicalize_path(&root_dir)?;
+  let mut files = BTreeSet::new();
+  for include in asset_includes {
+    let paths = if is_glob(include) {
+      let pattern = cwd.join(include);
+      glob::glob(&pattern.to_string_lossy())
+        .with_context(|| format!("Failed to expand glob '{}'", include))?
+        .collect::<Result<Vec<_>, _>>()?
+    } else {
+      vec![cwd.join(include)]
+    };
+    if paths.is_empty() {
+      bail!("No files matched the included glob '{}'.", include);
+    }
+    for path in paths {
+      for entry in walkdir::WalkDir::new(&path).follow_links(true) {
+        let entry = entry?;
+        if entry.file_type().is_file() {
+          files.insert(canonicalize_path(entry.path())?);
+        }
+      }
+    }
+  }
+  if let Some(file) = files
+    .iter()
+    .find(|file| !file.starts_with(&canonical_root_dir))
+  {
+    bail!(
+      concat!(
+        "Could not include '{}' because it is outside the directory of the ",
+        "main module '{}'."
+      ),
+      file.display(),
+      canonical_root_dir.display(),
+    );
+  }
+  Ok(Some(CompileAssets {
+    root_dir,
+    files: files.into_iter().collect(),
+  }))
+}
+
 /// This function writes out a final binary to specified path. If output path
 /// is not already standalone binary it will return error instead.
 fn validate_output_path(output_path: &Path) -> Result<(), AnyError> {
@@ -232,6 +539,14 @@ mod test {
         target: Some("x86_64-unknown-linux-gnu".to_string()),
         no_terminal: false,
         include: vec![],
+        list_targets: false,
+        sign_identity: None,
+        sign_command: None,
+        sign_args: vec![],
+        icon: None,
+        version_info: None,
+        strip: false,
+        include_source_maps: false,
       },
       &std::env::current_dir().unwrap(),
     )
@@ -254,6 +569,14 @@ mod test {
         target: Some("x86_64-pc-windows-msvc".to_string()),
         include: vec![],
         no_terminal: false,
+        list_targets: false,
+        sign_identity: None,
+        sign_command: None,
+        sign_args: vec![],
+        icon: None,
+        version_info: None,
+        strip: false,
+        include_source_maps: false,
       },
       &std::env::current_dir().unwrap(),
     )
@@ -262,6 +585,28 @@ mod test {
     assert_eq!(path.file_name().unwrap(), "file.exe");
   }
 
+  #[test]
+  fn test_output_path_for_target() {
+    assert_eq!(
+      output_path_for_target(
+        &PathBuf::from("dist/file_server"),
+        "x86_64-apple-darwin"
+      ),
+      PathBuf::from("dist/file_server-x86_64-apple-darwin")
+    );
+    // the extension is added afterwards for windows
+    assert_eq!(
+      get_os_specific_filepath(
+        output_path_for_target(
+          &PathBuf::from("dist/file_server"),
+          "x86_64-pc-windows-msvc"
+        ),
+        &Some("x86_64-pc-windows-msvc".to_string()),
+      ),
+      PathBuf::from("dist/file_server-x86_64-pc-windows-msvc.exe")
+    );
+  }
+
   #[test]
   fn test_os_specific_file_path() {
     fn run_test(path: &str, target: Option<&str>, expected: &str) {
@@ -288,4 +633,180 @@ mod test {
     run_test("C:\\my-exe.0.1.2", Some("windows"), "C:\\my-exe.0.1.2.exe");
     run_test("my-exe-0.1.2", Some("linux"), "my-exe-0.1.2");
   }
+
+  #[test]
+  fn test_resolve_compile_assets() {
+    let temp_dir = test_util::TempDir::new();
+    temp_dir.create_dir_all("assets/nested");
+    temp_dir.create_dir_all("templates");
+    temp_dir.write("main.ts", "");
+    temp_dir.write("worker.ts", "");
+    temp_dir.write("data.bin", "");
+    temp_dir.write("assets/a.txt", "a");
+    temp_dir.write("assets/nested/b.txt", "b");
+    temp_dir.write("templates/index.html", "");
+    temp_dir.write("templates/post.html", "");
+    let cwd = temp_dir.path().as_path();
+
+    assert!(!is_asset_include("worker.ts", cwd));
+    assert!(!is_asset_include("https://deno.land/x/mod.ts?a=1", cwd));
+    assert!(is_asset_include("data.bin", cwd));
+    assert!(is_asset_include("./assets", cwd));
+    assert!(is_asset_include("templates/*.html", cwd));
+
+    let root_dir = temp_dir.path().canonicalize();
+    let main_module =
+      ModuleSpecifier::from_file_path(cwd.join("main.ts")).unwrap();
+    let includes = [
+      "./assets".to_string(),
+      "templates/*.html".to_string(),
+      // duplicates are only included once
+      "assets/a.txt".to_string(),
+    ];
+    let assets = resolve_compile_assets(
+      &includes.iter().collect::<Vec<_>>(),
+      &main_module,
+      cwd,
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(assets.root_dir, cwd.to_path_buf());
+    assert_eq!(
+      assets.files,
+      vec![
+        root_dir.join("assets/a.txt").to_path_buf(),
+        root_dir.join("assets/nested/b.txt").to_path_buf(),
+        root_dir.join("templates/index.html").to_path_buf(),
+        root_dir.join("templates/post.html").to_path_buf(),
+      ]
+    );
+
+    let includes = ["missing/*.txt".to_string()];
+    let err = resolve_compile_assets(
+      &includes.iter().collect::<Vec<_>>(),
+      &main_module,
+      cwd,
+    )
+    .err()
+    .unwrap();
+    assert_eq!(
+      err.to_string(),
+      "No files matched the included glob 'missing/*.txt'."
+    );
+
+    // the assets need to be inside the directory of the main module
+    let main_module =
+      ModuleSpecifier::from_file_path(root_dir.join("assets/main.ts")).unwrap();
+    let includes = ["templates".to_string()];
+    assert!(resolve_compile_assets(
+      &includes.iter().collect::<Vec<_>>(),
+      &main_module,
+      cwd,
+    )
+    .is_err());
+  }
+
+  #[test]
+  fn test_resolve_finalize_commands() {
+    fn flags(target: &str) -> CompileFlags {
+      CompileFlags {
+        source_file: "mod.ts".to_string(),
+        output: None,
+        args: Vec::new(),
+        target: Some(target.to_string()),
+        no_terminal: false,
+        include: vec![],
+        list_targets: false,
+        sign_identity: None,
+        sign_command: None,
+        sign_args: vec![],
+        icon: None,
+        version_info: None,
+        strip: false,
+        include_source_maps: false,
+      }
+    }
+    fn command(program: &str, args: &[&str]) -> FinalizeCommand {
+      FinalizeCommand {
+        program: program.to_string(),
+        args: args.iter().map(|a| a.to_string()).collect(),
+      }
+    }
+    let output_path = Path::new("out");
+
+    assert_eq!(
+      resolve_finalize_commands(
+        output_path,
+        &flags("x86_64-unknown-linux-gnu")
+      )
+      .unwrap(),
+      vec![]
+    );
+
+    // the resources are set before signing
+    let compile_flags = CompileFlags {
+      sign_identity: Some("Company".to_string()),
+      icon: Some(PathBuf::from("icon.ico")),
+      version_info: Some("1.2.3".to_string()),
+      ..flags("x86_64-pc-windows-msvc")
+    };
+    assert_eq!(
+      resolve_finalize_commands(output_path, &compile_flags).unwrap(),
+      vec![
+        command(
+          "rcedit",
+          &[
+            "out",
+            "--set-icon",
+            "icon.ico",
+            "--set-file-version",
+            "1.2.3",
+            "--set-product-version",
+            "1.2.3"
+          ]
+        ),
+        command(
+          "signtool",
+          &["sign", "/n", "Company", "/fd", "SHA256", "out"]
+        ),
+      ]
+    );
+
+    let compile_flags = CompileFlags {
+      sign_identity: Some("Developer ID".to_string()),
+      ..flags("aarch64-apple-darwin")
+    };
+    assert_eq!(
+      resolve_finalize_commands(output_path, &compile_flags).unwrap(),
+      vec![command(
+        "codesign",
+        &["--sign", "Developer ID", "--force", "out"]
+      )]
+    );
+
+    let compile_flags = CompileFlags {
+      sign_command: Some("/opt/My Tools/sign.sh".to_string()),
+      sign_args: vec!["--key".to_string(), "My Key.pem".to_string()],
+      ..flags("x86_64-unknown-linux-gnu")
+    };
+    assert_eq!(
+      resolve_finalize_commands(output_path, &compile_flags).unwrap(),
+      vec![command(
+        "/opt/My Tools/sign.sh",
+        &["--key", "My Key.pem", "out"]
+      )]
+    );
+
+    // unsupported targets
+    let compile_flags = CompileFlags {
+      sign_identity: Some("Company".to_string()),
+      ..flags("x86_64-unknown-linux-gnu")
+    };
+    assert!(resolve_finalize_commands(output_path, &compile_flags).is_err());
+    let compile_flags = CompileFlags {
+      icon: Some(PathBuf::from("icon.ico")),
+      ..flags("aarch64-apple-darwin")
+    };
+    assert!(resolve_finalize_commands(output_path, &compile_flags).is_err());
+  }
 }
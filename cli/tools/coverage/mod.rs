@@ -1,13 +1,18 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
 use crate::args::CoverageFlags;
+use crate::args::CoverageThreshold;
 use crate::args::FileFlags;
 use crate::args::Flags;
 use crate::cdp;
+use crate::colors;
+use crate::emit::Emitter;
 use crate::factory::CliFactory;
+use crate::file_fetcher::FileFetcher;
 use crate::npm::CliNpmResolver;
 use crate::tools::fmt::format_json;
 use crate::tools::test::is_supported_test_path;
+use crate::util::file_selection::FileSelection;
 use crate::util::fs::FileCollector;
 use crate::util::text_encoding::source_map_from_code;
 
@@ -32,6 +37,7 @@ use std::io::BufWriter;
 use std::io::Write;
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::Arc;
 use text_lines::TextLines;
 use uuid::Uuid;
 
@@ -425,6 +431,8 @@ fn filter_coverages(
   coverages: Vec<cdp::ScriptCoverage>,
   include: Vec<String>,
   exclude: Vec<String>,
+  file_patterns: &FilePatterns,
+  file_selection: &FileSelection,
   npm_resolver: &dyn CliNpmResolver,
 ) -> Vec<cdp::ScriptCoverage> {
   let include: Vec<Regex> =
@@ -448,12 +456,313 @@ fn filter_coverages(
 
       let is_included = include.iter().any(|p| p.is_match(&e.url));
       let is_excluded = exclude.iter().any(|p| p.is_match(&e.url));
-
-      (include.is_empty() || is_included) && !is_excluded && !is_internal
+      // the modules excluded by the configuration file aren't reported
+      let is_selected = Url::parse(&e.url)
+        .map(|url| file_selection.matches_specifier(file_patterns, &url))
+        .unwrap_or(true);
+
+      (include.is_empty() || is_included)
+        && !is_excluded
+        && is_selected
+        && !is_internal
     })
     .collect::<Vec<cdp::ScriptCoverage>>()
 }
 
+fn merge_coverages(
+  script_coverages: Vec<cdp::ScriptCoverage>,
+) -> Vec<cdp::ScriptCoverage> {
+  let proc_coverages: Vec<_> = script_coverages
+    .into_iter()
+    .map(|cov| ProcessCoverage { result: vec![cov] })
+    .collect();
+
+  if let Some(c) = merge::merge_processes(proc_coverages) {
+    c.result
+  } else {
+    vec![]
+  }
+}
+
+/// Generate the coverage report of a script, mapped back to the original
+/// source when the script was transpiled, returning it together with the
+/// original source.
+fn script_coverage_report(
+  script_coverage: &cdp::ScriptCoverage,
+  initial_cwd: &Path,
+  file_fetcher: &FileFetcher,
+  emitter: &Emitter,
+  output: &Option<PathBuf>,
+) -> Result<(CoverageReport, Arc<str>), AnyError> {
+  let module_specifier =
+    deno_core::resolve_url_or_path(&script_coverage.url, initial_cwd)?;
+
+  let maybe_file = if module_specifier.scheme() == "file" {
+    file_fetcher.get_source(&module_specifier)
+  } else {
+    file_fetcher
+      .fetch_cached(&module_specifier, 10)
+      .with_context(|| {
+        format!("Failed to fetch \"{module_specifier}\" from cache.")
+      })?
+  };
+  let file = maybe_file.ok_or_else(|| {
+    anyhow!("Failed to fetch \"{}\" from cache.
+        Before generating coverage report, run `deno test --coverage` to ensure consistent state.",
+        module_specifier
+      )
+  })?.into_text_decoded()?;
+
+  let original_source = file.source.clone();
+  // Check if file was transpiled
+  let transpiled_code = match file.media_type {
+    MediaType::JavaScript
+    | MediaType::Unknown
+    | MediaType::Cjs
+    | MediaType::Mjs
+    | MediaType::Json => None,
+    MediaType::Dts | MediaType::Dmts | MediaType::Dcts => Some(String::new()),
+    MediaType::TypeScript
+    | MediaType::Jsx
+    | MediaType::Mts
+    | MediaType::Cts
+    | MediaType::Tsx => {
+      Some(match emitter.maybe_cached_emit(&file.specifier, &file.source) {
+        Some(code) => code,
+        None => {
+          return Err(anyhow!(
+            "Missing transpiled source code for: \"{}\".
+            Before generating coverage report, run `deno test --coverage` to ensure consistent state.",
+            file.specifier,
+          ))
+        }
+      })
+    }
+    MediaType::Wasm | MediaType::TsBuildInfo | MediaType::SourceMap => {
+      unreachable!()
+    }
+  };
+  let runtime_code: ModuleCodeString = transpiled_code
+    .map(|c| c.into())
+    .unwrap_or_else(|| original_source.clone().into());
+
+  let source_map = source_map_from_code(&runtime_code);
+  let coverage_report = generate_coverage_report(
+    script_coverage,
+    runtime_code.as_str().to_owned(),
+    &source_map,
+    output,
+  );
+  Ok((coverage_report, original_source))
+}
+
+/// The number of hits of the lines of a module.
+#[derive(Debug, Clone)]
+pub struct LineCoverage {
+  pub specifier: ModuleSpecifier,
+  /// (line_index, number_of_hits)
+  pub lines: Vec<(usize, i64)>,
+}
+
+/// Generate the coverage reports of the modules in the coverage profiles of a
+/// directory, as written by `deno test --coverage`.
+async fn collect_coverage_reports(
+  factory: &CliFactory,
+  dir: &Path,
+  include: Vec<String>,
+  exclude: Vec<String>,
+) -> Result<Vec<CoverageReport>, AnyError> {
+  let npm_resolver = factory.npm_resolver().await?;
+  let file_fetcher = factory.file_fetcher()?;
+  let emitter = factory.emitter()?;
+  let initial_cwd = factory.cli_options().initial_cwd();
+  let files = FileFlags {
+    include: vec![dir.to_string_lossy().to_string()],
+    ignore: vec![],
+  };
+  let script_coverages = filter_coverages(
+    collect_coverages(files, initial_cwd)?,
+    include,
+    exclude,
+    &factory.cli_options().resolve_config_file_patterns()?,
+    &factory.cli_options().resolve_file_selection(None, &[])?,
+    npm_resolver.as_ref(),
+  );
+  let mut coverage_reports = Vec::new();
+  for script_coverage in merge_coverages(script_coverages) {
+    let (coverage_report, _) = script_coverage_report(
+      &script_coverage,
+      initial_cwd,
+      file_fetcher,
+      emitter,
+      &None,
+    )?;
+    if !coverage_report.found_lines.is_empty() {
+      coverage_reports.push(coverage_report);
+    }
+  }
+  Ok(coverage_reports)
+}
+
+/// Collect the line coverage of the modules in the coverage profiles of a
+/// directory, as written by `deno test --coverage`. This is used by the
+/// language server to show coverage in the editor.
+pub async fn collect_line_coverage(
+  factory: &CliFactory,
+  dir: &Path,
+) -> Result<Vec<LineCoverage>, AnyError> {
+  let coverage_reports =
+    collect_coverage_reports(factory, dir, vec![], vec![]).await?;
+  Ok(
+    coverage_reports
+      .into_iter()
+      .map(|coverage_report| LineCoverage {
+        specifier: coverage_report.url,
+        lines: coverage_report.found_lines,
+      })
+      .collect(),
+  )
+}
+
+#[derive(Debug, Default)]
+struct CoverageTotals {
+  line_hit: usize,
+  line_total: usize,
+  branch_hit: usize,
+  branch_total: usize,
+  function_hit: usize,
+  function_total: usize,
+}
+
+impl CoverageTotals {
+  fn add(&mut self, report: &CoverageReport) {
+    self.line_hit += report.found_lines.iter().filter(|(_, c)| *c > 0).count();
+    self.line_total += report.found_lines.len();
+    self.branch_hit += report.branches.iter().filter(|b| b.is_hit).count();
+    self.branch_total += report.branches.len();
+    self.function_hit += report
+      .named_functions
+      .iter()
+      .filter(|f| f.execution_count > 0)
+      .count();
+    self.function_total += report.named_functions.len();
+  }
+
+  /// The line, branch and function percentages.
+  fn percentages(&self) -> [f32; 3] {
+    let percent = |hit: usize, total: usize| {
+      if total == 0 {
+        100.0
+      } else {
+        hit as f32 / total as f32 * 100.0
+      }
+    };
+    [
+      percent(self.line_hit, self.line_total),
+      percent(self.branch_hit, self.branch_total),
+      percent(self.function_hit, self.function_total),
+    ]
+  }
+
+  /// Describe the percentages which are below the threshold.
+  fn below_threshold(&self, threshold: &CoverageThreshold) -> Vec<String> {
+    let minimums = [threshold.lines, threshold.branches, threshold.functions];
+    ["lines", "branches", "functions"]
+      .into_iter()
+      .zip(self.percentages())
+      .zip(minimums)
+      .filter_map(|((kind, percent), minimum)| {
+        let minimum = minimum?;
+        (percent < minimum as f32)
+          .then(|| format!("{kind} {percent:.1}% < {minimum}%"))
+      })
+      .collect()
+  }
+}
+
+/// Check the total coverage of the reports against a threshold. When it isn't
+/// met, the files which are below the threshold are printed and an error is
+/// returned.
+fn check_coverage_threshold(
+  coverage_reports: &[CoverageReport],
+  threshold: &CoverageThreshold,
+) -> Result<(), AnyError> {
+  let mut totals = CoverageTotals::default();
+  for report in coverage_reports {
+    totals.add(report);
+  }
+  let failures = totals.below_threshold(threshold);
+  if failures.is_empty() {
+    return Ok(());
+  }
+
+  let maybe_root =
+    util::find_root(coverage_reports.iter().map(|r| &r.url).collect());
+  let mut offenders = coverage_reports
+    .iter()
+    .filter_map(|report| {
+      let mut file_totals = CoverageTotals::default();
+      file_totals.add(report);
+      if file_totals.below_threshold(threshold).is_empty() {
+        return None;
+      }
+      let name = maybe_root
+        .as_ref()
+        .and_then(|root| report.url.as_str().strip_prefix(root.as_str()))
+        .unwrap_or(report.url.as_str())
+        .to_string();
+      Some((name, file_totals.percentages()))
+    })
+    .collect::<Vec<_>>();
+  offenders.sort_by(|a, b| a.0.cmp(&b.0));
+  let node_max = offenders
+    .iter()
+    .map(|(name, _)| name.len())
+    .max()
+    .unwrap_or(0)
+    .max("File".len());
+  let header = format!(
+    "{node:node_max$} | Branch % | Function % | Line % |",
+    node = "File"
+  );
+  let separator = "-".repeat(header.len());
+  println!("Files below the coverage threshold:");
+  println!("{}", separator);
+  println!("{}", header);
+  println!("{}", separator);
+  for (name, [line_percent, branch_percent, function_percent]) in &offenders {
+    println!(
+      "{}",
+      colors::red(&format!(
+        "{name:node_max$} | {branch_percent:>8.1} | {function_percent:>10.1} | {line_percent:>6.1} |"
+      ))
+    );
+  }
+  println!("{}", separator);
+
+  Err(generic_error(format!(
+    "Coverage threshold not met: {}",
+    failures.join(", ")
+  )))
+}
+
+/// Check the coverage of the profiles written by `deno test --coverage`
+/// against a threshold, with the default filters of `deno coverage`.
+pub async fn check_coverage_threshold_of_dir(
+  factory: &CliFactory,
+  dir: &Path,
+  threshold: &CoverageThreshold,
+) -> Result<(), AnyError> {
+  let coverage_reports = collect_coverage_reports(
+    factory,
+    dir,
+    vec![r"^file:".to_string()],
+    vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
+  )
+  .await?;
+  check_coverage_threshold(&coverage_reports, threshold)
+}
+
 pub async fn cover_files(
   flags: Flags,
   coverage_flags: CoverageFlags,
@@ -467,6 +776,8 @@ pub async fn cover_files(
   let file_fetcher = factory.file_fetcher()?;
   let cli_options = factory.cli_options();
   let emitter = factory.emitter()?;
+  let maybe_threshold =
+    cli_options.resolve_coverage_threshold(coverage_flags.threshold.clone())?;
 
   assert!(!coverage_flags.files.include.is_empty());
 
@@ -483,20 +794,12 @@ pub async fn cover_files(
     script_coverages,
     coverage_flags.include,
     coverage_flags.exclude,
+    &cli_options.resolve_config_file_patterns()?,
+    &cli_options.resolve_file_selection(None, &[])?,
     npm_resolver.as_ref(),
   );
 
-  let proc_coverages: Vec<_> = script_coverages
-    .into_iter()
-    .map(|cov| ProcessCoverage { result: vec![cov] })
-    .collect();
-
-  let script_coverages = if let Some(c) = merge::merge_processes(proc_coverages)
-  {
-    c.result
-  } else {
-    vec![]
-  };
+  let script_coverages = merge_coverages(script_coverages);
 
   let mut reporter = reporter::create(coverage_flags.r#type);
 
@@ -510,75 +813,27 @@ pub async fn cover_files(
     None => None,
   };
 
+  let mut coverage_reports = Vec::new();
   for script_coverage in script_coverages {
-    let module_specifier = deno_core::resolve_url_or_path(
-      &script_coverage.url,
-      cli_options.initial_cwd(),
-    )?;
-
-    let maybe_file = if module_specifier.scheme() == "file" {
-      file_fetcher.get_source(&module_specifier)
-    } else {
-      file_fetcher
-        .fetch_cached(&module_specifier, 10)
-        .with_context(|| {
-          format!("Failed to fetch \"{module_specifier}\" from cache.")
-        })?
-    };
-    let file = maybe_file.ok_or_else(|| {
-      anyhow!("Failed to fetch \"{}\" from cache.
-          Before generating coverage report, run `deno test --coverage` to ensure consistent state.",
-          module_specifier
-        )
-    })?.into_text_decoded()?;
-
-    let original_source = file.source.clone();
-    // Check if file was transpiled
-    let transpiled_code = match file.media_type {
-      MediaType::JavaScript
-      | MediaType::Unknown
-      | MediaType::Cjs
-      | MediaType::Mjs
-      | MediaType::Json => None,
-      MediaType::Dts | MediaType::Dmts | MediaType::Dcts => Some(String::new()),
-      MediaType::TypeScript
-      | MediaType::Jsx
-      | MediaType::Mts
-      | MediaType::Cts
-      | MediaType::Tsx => {
-        Some(match emitter.maybe_cached_emit(&file.specifier, &file.source) {
-          Some(code) => code,
-          None => {
-            return Err(anyhow!(
-              "Missing transpiled source code for: \"{}\".
-              Before generating coverage report, run `deno test --coverage` to ensure consistent state.",
-              file.specifier,
-            ))
-          }
-        })
-      }
-      MediaType::Wasm | MediaType::TsBuildInfo | MediaType::SourceMap => {
-        unreachable!()
-      }
-    };
-    let runtime_code: ModuleCodeString = transpiled_code
-      .map(|c| c.into())
-      .unwrap_or_else(|| original_source.clone().into());
-
-    let source_map = source_map_from_code(&runtime_code);
-    let coverage_report = generate_coverage_report(
+    let (coverage_report, original_source) = script_coverage_report(
       &script_coverage,
-      runtime_code.as_str().to_owned(),
-      &source_map,
+      cli_options.initial_cwd(),
+      file_fetcher,
+      emitter,
       &out_mode,
-    );
+    )?;
 
     if !coverage_report.found_lines.is_empty() {
       reporter.report(&coverage_report, &original_source)?;
+      coverage_reports.push(coverage_report);
     }
   }
 
   reporter.done(&coverage_root);
 
+  if let Some(threshold) = &maybe_threshold {
+    check_coverage_threshold(&coverage_reports, threshold)?;
+  }
+
   Ok(())
 }
@@ -5,6 +5,8 @@ use super::CoverageReport;
 use crate::args::CoverageType;
 use crate::colors;
 use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
 use deno_core::url::Url;
 use std::collections::HashMap;
 use std::fs;
@@ -34,6 +36,8 @@ pub fn create(kind: CoverageType) -> Box<dyn CoverageReporter + Send> {
     CoverageType::Lcov => Box::new(LcovCoverageReporter::new()),
     CoverageType::Detailed => Box::new(DetailedCoverageReporter::new()),
     CoverageType::Html => Box::new(HtmlCoverageReporter::new()),
+    CoverageType::Cobertura => Box::new(CoberturaCoverageReporter::new()),
+    CoverageType::JsonSummary => Box::new(JsonSummaryCoverageReporter::new()),
   }
 }
 
@@ -304,6 +308,283 @@ impl CoverageReporter for LcovCoverageReporter {
   }
 }
 
+/// Writes a report to the output file of the coverage reports, or to stdout
+/// if no file is specified.
+fn write_report_output(
+  output: &Option<PathBuf>,
+  content: &str,
+) -> Result<(), AnyError> {
+  match output {
+    Some(path) => fs::write(path, content)?,
+    None => {
+      let mut stdout = io::stdout();
+      stdout.write_all(content.as_bytes())?;
+      stdout.flush()?;
+    }
+  }
+  Ok(())
+}
+
+fn xml_escape(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+}
+
+/// The ratio of hit items, which is 1 when there are no items.
+fn coverage_rate(hit: usize, total: usize) -> f64 {
+  if total == 0 {
+    1.0
+  } else {
+    hit as f64 / total as f64
+  }
+}
+
+/// Writes a report in the Cobertura XML format, which is supported by many CI
+/// systems. Files are grouped into packages by their directory.
+struct CoberturaCoverageReporter {
+  file_reports: Vec<CoverageReport>,
+}
+
+impl CoberturaCoverageReporter {
+  pub fn new() -> CoberturaCoverageReporter {
+    CoberturaCoverageReporter {
+      file_reports: Vec::new(),
+    }
+  }
+
+  fn create_class_xml(
+    &self,
+    report: &CoverageReport,
+    filename: &str,
+  ) -> String {
+    let lines_valid = report.found_lines.len();
+    let lines_covered =
+      report.found_lines.iter().filter(|(_, c)| *c > 0).count();
+    let branches_valid = report.branches.len();
+    let branches_covered = report.branches.iter().filter(|b| b.is_hit).count();
+
+    let methods = report
+      .named_functions
+      .iter()
+      .map(|function| {
+        format!(
+          "            <method name=\"{name}\" signature=\"\" line-rate=\"{rate}\" branch-rate=\"1\">\n              <lines>\n                <line number=\"{line}\" hits=\"{hits}\"/>\n              </lines>\n            </method>\n",
+          name = xml_escape(&function.name),
+          rate = u8::from(function.execution_count > 0),
+          line = function.line_index + 1,
+          hits = function.execution_count,
+        )
+      })
+      .collect::<String>();
+
+    let lines = report
+      .found_lines
+      .iter()
+      .map(|(index, count)| {
+        let branches = report
+          .branches
+          .iter()
+          .filter(|b| b.line_index == *index)
+          .collect::<Vec<_>>();
+        if branches.is_empty() {
+          format!(
+            "            <line number=\"{}\" hits=\"{}\" branch=\"false\"/>\n",
+            index + 1,
+            count
+          )
+        } else {
+          let hit = branches.iter().filter(|b| b.is_hit).count();
+          format!(
+            "            <line number=\"{}\" hits=\"{}\" branch=\"true\" condition-coverage=\"{}% ({}/{})\"/>\n",
+            index + 1,
+            count,
+            (coverage_rate(hit, branches.len()) * 100.0).round(),
+            hit,
+            branches.len()
+          )
+        }
+      })
+      .collect::<String>();
+
+    format!(
+      "        <class name=\"{name}\" filename=\"{name}\" line-rate=\"{line_rate}\" branch-rate=\"{branch_rate}\" complexity=\"0\">\n          <methods>\n{methods}          </methods>\n          <lines>\n{lines}          </lines>\n        </class>\n",
+      name = xml_escape(filename),
+      line_rate = coverage_rate(lines_covered, lines_valid),
+      branch_rate = coverage_rate(branches_covered, branches_valid),
+    )
+  }
+}
+
+impl CoverageReporter for CoberturaCoverageReporter {
+  fn report(
+    &mut self,
+    coverage_report: &CoverageReport,
+    _file_text: &str,
+  ) -> Result<(), AnyError> {
+    self.file_reports.push(coverage_report.clone());
+    Ok(())
+  }
+
+  fn done(&mut self, _coverage_root: &Path) {
+    let Some(output) = self.file_reports.first().map(|r| r.output.clone())
+    else {
+      return;
+    };
+    let urls = self.file_reports.iter().map(|r| &r.url).collect();
+    let root = util::find_root(urls).unwrap().to_file_path().unwrap();
+
+    // (package name, [lines valid, lines covered, branches valid, branches
+    // covered], classes)
+    let mut packages: Vec<(String, [usize; 4], String)> = Vec::new();
+    let mut totals = [0; 4];
+    let mut file_reports = self.file_reports.iter().collect::<Vec<_>>();
+    file_reports.sort_by_key(|r| r.url.clone());
+    for report in file_reports {
+      let path = report.url.to_file_path().unwrap();
+      let relative_path = path.strip_prefix(&root).unwrap();
+      let filename = relative_path.to_string_lossy().replace('\\', "/");
+      let package_name = match filename.rsplit_once('/') {
+        Some((dir, _)) => dir.replace('/', "."),
+        None => ".".to_string(),
+      };
+      let stats = [
+        report.found_lines.len(),
+        report.found_lines.iter().filter(|(_, c)| *c > 0).count(),
+        report.branches.len(),
+        report.branches.iter().filter(|b| b.is_hit).count(),
+      ];
+      let class_xml = self.create_class_xml(report, &filename);
+      let package = match packages.iter_mut().find(|p| p.0 == package_name) {
+        Some(package) => package,
+        None => {
+          packages.push((package_name, [0; 4], String::new()));
+          packages.last_mut().unwrap()
+        }
+      };
+      for (i, stat) in stats.into_iter().enumerate() {
+        package.1[i] += stat;
+        totals[i] += stat;
+      }
+      package.2.push_str(&class_xml);
+    }
+
+    let packages_xml = packages
+      .iter()
+      .map(|(name, stats, classes)| {
+        format!(
+          "    <package name=\"{name}\" line-rate=\"{line_rate}\" branch-rate=\"{branch_rate}\" complexity=\"0\">\n      <classes>\n{classes}      </classes>\n    </package>\n",
+          name = xml_escape(name),
+          line_rate = coverage_rate(stats[1], stats[0]),
+          branch_rate = coverage_rate(stats[3], stats[2]),
+        )
+      })
+      .collect::<String>();
+    let xml = format!(
+      "<?xml version=\"1.0\" ?>\n<!DOCTYPE coverage SYSTEM \"http://cobertura.sourceforge.net/xml/coverage-04.dtd\">\n<coverage lines-valid=\"{}\" lines-covered=\"{}\" line-rate=\"{}\" branches-valid=\"{}\" branches-covered=\"{}\" branch-rate=\"{}\" timestamp=\"{}\" complexity=\"0\" version=\"0.1\">\n  <sources>\n    <source>{}</source>\n  </sources>\n  <packages>\n{}  </packages>\n</coverage>\n",
+      totals[0],
+      totals[1],
+      coverage_rate(totals[1], totals[0]),
+      totals[2],
+      totals[3],
+      coverage_rate(totals[3], totals[2]),
+      crate::util::time::utc_now().timestamp_millis(),
+      xml_escape(&root.to_string_lossy()),
+      packages_xml,
+    );
+    if let Err(err) = write_report_output(&output, &xml) {
+      log::error!("Failed to write the Cobertura coverage report: {}", err);
+    }
+  }
+}
+
+/// Writes a JSON summary of the line, branch and function coverage of each
+/// file and of all files, in the format of the `json-summary` reporter of
+/// istanbul.
+struct JsonSummaryCoverageReporter {
+  file_reports: Vec<CoverageReport>,
+}
+
+impl JsonSummaryCoverageReporter {
+  pub fn new() -> JsonSummaryCoverageReporter {
+    JsonSummaryCoverageReporter {
+      file_reports: Vec::new(),
+    }
+  }
+}
+
+fn json_summary_metric(covered: usize, total: usize) -> serde_json::Value {
+  json!({
+    "total": total,
+    "covered": covered,
+    "skipped": 0,
+    "pct": (coverage_rate(covered, total) * 10000.0).round() / 100.0,
+  })
+}
+
+fn json_summary_entry(stats: &[usize; 6]) -> serde_json::Value {
+  json!({
+    "lines": json_summary_metric(stats[1], stats[0]),
+    "branches": json_summary_metric(stats[3], stats[2]),
+    "functions": json_summary_metric(stats[5], stats[4]),
+  })
+}
+
+impl CoverageReporter for JsonSummaryCoverageReporter {
+  fn report(
+    &mut self,
+    coverage_report: &CoverageReport,
+    _file_text: &str,
+  ) -> Result<(), AnyError> {
+    self.file_reports.push(coverage_report.clone());
+    Ok(())
+  }
+
+  fn done(&mut self, _coverage_root: &Path) {
+    let Some(output) = self.file_reports.first().map(|r| r.output.clone())
+    else {
+      return;
+    };
+    let mut totals = [0; 6];
+    let mut files = serde_json::Map::new();
+    let mut file_reports = self.file_reports.iter().collect::<Vec<_>>();
+    file_reports.sort_by_key(|r| r.url.clone());
+    for report in file_reports {
+      let stats = [
+        report.found_lines.len(),
+        report.found_lines.iter().filter(|(_, c)| *c > 0).count(),
+        report.branches.len(),
+        report.branches.iter().filter(|b| b.is_hit).count(),
+        report.named_functions.len(),
+        report
+          .named_functions
+          .iter()
+          .filter(|f| f.execution_count > 0)
+          .count(),
+      ];
+      for (total, stat) in totals.iter_mut().zip(stats) {
+        *total += stat;
+      }
+      let file_path = report
+        .url
+        .to_file_path()
+        .ok()
+        .and_then(|p| p.to_str().map(|p| p.to_string()))
+        .unwrap_or_else(|| report.url.to_string());
+      files.insert(file_path, json_summary_entry(&stats));
+    }
+    let mut summary = serde_json::Map::new();
+    summary.insert("total".to_string(), json_summary_entry(&totals));
+    summary.extend(files);
+    let json = serde_json::to_string_pretty(&summary).unwrap();
+    if let Err(err) = write_report_output(&output, &format!("{json}\n")) {
+      log::error!("Failed to write the JSON coverage summary: {}", err);
+    }
+  }
+}
+
 struct DetailedCoverageReporter {}
 
 impl DetailedCoverageReporter {
@@ -0,0 +1,514 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Renders the documentation of modules as a tree of Markdown files, with a
+//! file per module and an index of the modules.
+
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::Path;
+
+use deno_ast::swc::ast::VarDeclKind;
+use deno_doc as doc;
+use deno_graph::ModuleSpecifier;
+use doc::js_doc::JsDoc;
+use doc::js_doc::JsDocTag;
+use doc::DocNode;
+use doc::DocNodeKind;
+use indexmap::IndexMap;
+
+const INDEX_FILE_NAME: &str = "index.md";
+
+/// Get the Markdown files of the documentation of the modules, by their path
+/// relative to the output directory. The paths of the local modules mirror
+/// their paths relative to `base`.
+pub fn generate(
+  doc_nodes_by_url: &IndexMap<ModuleSpecifier, Vec<DocNode>>,
+  base: &ModuleSpecifier,
+  maybe_name: Option<&str>,
+) -> Vec<(String, String)> {
+  let mut files = Vec::with_capacity(doc_nodes_by_url.len() + 1);
+  let mut index = String::new();
+  writeln!(index, "# {}\n", maybe_name.unwrap_or("Documentation")).unwrap();
+  for (specifier, doc_nodes) in doc_nodes_by_url {
+    let path = module_file_path(specifier, base);
+    let title = module_title(specifier, base);
+    write!(index, "- [{}]({})", title, path).unwrap();
+    if let Some(summary) = module_doc(doc_nodes).and_then(first_paragraph) {
+      write!(index, ": {}", summary).unwrap();
+    }
+    index.push('\n');
+    files.push((path, render_module(&title, doc_nodes)));
+  }
+  files.insert(0, (INDEX_FILE_NAME.to_string(), index));
+  files
+}
+
+/// Get the path of the Markdown file of a module, which is the path relative
+/// to the base for local modules and the host and path for remote modules.
+fn module_file_path(
+  specifier: &ModuleSpecifier,
+  base: &ModuleSpecifier,
+) -> String {
+  let path = match base.make_relative(specifier) {
+    Some(path) if !path.starts_with("../") => path,
+    _ => format!(
+      "{}{}",
+      specifier.host_str().unwrap_or(specifier.scheme()),
+      specifier.path()
+    ),
+  };
+  Path::new(path.trim_start_matches('/'))
+    .with_extension("md")
+    .to_string_lossy()
+    .replace('\\', "/")
+}
+
+fn module_title(specifier: &ModuleSpecifier, base: &ModuleSpecifier) -> String {
+  match base.make_relative(specifier) {
+    Some(path) if !path.starts_with("../") => path,
+    _ => specifier.to_string(),
+  }
+}
+
+fn module_doc(doc_nodes: &[DocNode]) -> Option<&str> {
+  doc_nodes
+    .iter()
+    .find(|node| node.kind == DocNodeKind::ModuleDoc)
+    .and_then(|node| node.js_doc.doc.as_deref())
+}
+
+fn first_paragraph(text: &str) -> Option<String> {
+  let paragraph = text
+    .trim()
+    .split("\n\n")
+    .next()?
+    .lines()
+    .map(|line| line.trim())
+    .collect::<Vec<_>>()
+    .join(" ");
+  (!paragraph.is_empty()).then_some(paragraph)
+}
+
+fn render_module(title: &str, doc_nodes: &[DocNode]) -> String {
+  let mut out = String::new();
+  writeln!(out, "# {}\n", title).unwrap();
+  if let Some(doc) = module_doc(doc_nodes) {
+    writeln!(out, "{}\n", doc.trim()).unwrap();
+  }
+
+  let sections = collect_sections(doc_nodes, "");
+  if !sections.is_empty() {
+    let mut slugs = Slugs::default();
+    let anchors = sections
+      .iter()
+      .map(|section| slugs.slug(&section.title))
+      .collect::<Vec<_>>();
+    out.push_str("## Table of contents\n\n");
+    for (section, anchor) in sections.iter().zip(&anchors) {
+      let indent = "  ".repeat(section.depth);
+      writeln!(out, "{}- [{}](#{})", indent, section.title, anchor).unwrap();
+    }
+    out.push('\n');
+    for section in &sections {
+      render_section(&mut out, section);
+    }
+  }
+  out
+}
+
+/// The documentation of a symbol, which has multiple nodes when it's
+/// overloaded or declaration merged.
+struct Section<'a> {
+  title: String,
+  depth: usize,
+  nodes: Vec<&'a DocNode>,
+}
+
+fn collect_sections<'a>(
+  doc_nodes: impl IntoIterator<Item = &'a DocNode>,
+  prefix: &str,
+) -> Vec<Section<'a>> {
+  let mut nodes_by_name: IndexMap<&str, Vec<&DocNode>> = IndexMap::new();
+  for node in doc_nodes {
+    if !matches!(node.kind, DocNodeKind::Import | DocNodeKind::ModuleDoc) {
+      nodes_by_name
+        .entry(node.name.as_str())
+        .or_default()
+        .push(node);
+    }
+  }
+  let depth = prefix.matches('.').count();
+  let mut sections = Vec::with_capacity(nodes_by_name.len());
+  for (name, nodes) in nodes_by_name {
+    let title = format!("{}{}", prefix, name);
+    let elements = nodes
+      .iter()
+      .copied()
+      .filter_map(|node| node.namespace_def.as_ref())
+      .flat_map(|namespace_def| namespace_def.elements.iter())
+      .map(|element| -> &'a DocNode { element.borrow() })
+      .collect::<Vec<_>>();
+    sections.push(Section {
+      title: title.clone(),
+      depth,
+      nodes,
+    });
+    if !elements.is_empty() {
+      sections.extend(collect_sections(elements, &format!("{}.", title)));
+    }
+  }
+  sections
+}
+
+fn render_section(out: &mut String, section: &Section) {
+  writeln!(out, "{} {}\n", "#".repeat(section.depth + 2), section.title)
+    .unwrap();
+  out.push_str("```ts\n");
+  for node in &section.nodes {
+    writeln!(out, "{}", signature(node)).unwrap();
+  }
+  out.push_str("```\n\n");
+  for node in &section.nodes {
+    render_js_doc(out, &node.js_doc);
+  }
+  let members = section
+    .nodes
+    .iter()
+    .flat_map(|node| member_docs(node))
+    .collect::<Vec<_>>();
+  if !members.is_empty() {
+    out.push_str("**Members**\n\n");
+    for (name, doc) in members {
+      writeln!(out, "- `{}`: {}", name, doc).unwrap();
+    }
+    out.push('\n');
+  }
+}
+
+fn render_js_doc(out: &mut String, js_doc: &JsDoc) {
+  if let Some(doc) = &js_doc.doc {
+    writeln!(out, "{}\n", doc.trim()).unwrap();
+  }
+  let mut params = Vec::new();
+  for tag in &js_doc.tags {
+    match tag {
+      JsDocTag::Deprecated { doc } => {
+        let doc = doc.as_deref().map(str::trim).unwrap_or_default();
+        writeln!(out, "> **Deprecated**{}\n", prefixed(": ", doc)).unwrap();
+      }
+      JsDocTag::Param { name, doc, .. } => {
+        let doc = doc.as_deref().map(str::trim).unwrap_or_default();
+        params.push(format!("- `{}`{}", name, prefixed(": ", doc)));
+      }
+      JsDocTag::Return { doc, .. } => {
+        if let Some(doc) = doc {
+          writeln!(out, "**Returns**: {}\n", doc.trim()).unwrap();
+        }
+      }
+      JsDocTag::Example { doc } => {
+        let doc = doc.trim();
+        if doc.contains("```") {
+          writeln!(out, "**Example**\n\n{}\n", doc).unwrap();
+        } else {
+          writeln!(out, "**Example**\n\n```ts\n{}\n```\n", doc).unwrap();
+        }
+      }
+      _ => {}
+    }
+  }
+  if !params.is_empty() {
+    writeln!(out, "**Parameters**\n\n{}\n", params.join("\n")).unwrap();
+  }
+}
+
+fn prefixed(prefix: &str, text: &str) -> String {
+  if text.is_empty() {
+    String::new()
+  } else {
+    format!("{}{}", prefix, text)
+  }
+}
+
+/// Get the TypeScript declaration of a node.
+fn signature(node: &DocNode) -> String {
+  let name = &node.name;
+  match node.kind {
+    DocNodeKind::Function => {
+      let function_def = node.function_def.as_ref().unwrap();
+      format!(
+        "{}function {}{}",
+        if function_def.is_async { "async " } else { "" },
+        name,
+        function_signature(
+          &function_def.type_params,
+          &function_def.params,
+          function_def.return_type.as_ref()
+        )
+      )
+    }
+    DocNodeKind::Variable => {
+      let variable_def = node.variable_def.as_ref().unwrap();
+      let kind = match variable_def.kind {
+        VarDeclKind::Var => "var",
+        VarDeclKind::Let => "let",
+        VarDeclKind::Const => "const",
+      };
+      format!(
+        "{} {}{}",
+        kind,
+        name,
+        type_annotation(variable_def.ts_type.as_ref())
+      )
+    }
+    DocNodeKind::Class => {
+      let class_def = node.class_def.as_ref().unwrap();
+      let mut out = format!(
+        "{}class {}{}",
+        if class_def.is_abstract {
+          "abstract "
+        } else {
+          ""
+        },
+        name,
+        type_params(&class_def.type_params)
+      );
+      if let Some(extends) = &class_def.extends {
+        write!(out, " extends {}", extends).unwrap();
+      }
+      if !class_def.implements.is_empty() {
+        write!(out, " implements {}", join(&class_def.implements)).unwrap();
+      }
+      out.push_str(" {\n");
+      for constructor in &class_def.constructors {
+        writeln!(out, "  constructor({});", join(&constructor.params)).unwrap();
+      }
+      for property in &class_def.properties {
+        writeln!(
+          out,
+          "  {}{}{}{}{};",
+          if property.is_static { "static " } else { "" },
+          if property.readonly { "readonly " } else { "" },
+          property.name,
+          if property.optional { "?" } else { "" },
+          type_annotation(property.ts_type.as_ref())
+        )
+        .unwrap();
+      }
+      for method in &class_def.methods {
+        writeln!(
+          out,
+          "  {}{}{}{};",
+          if method.is_static { "static " } else { "" },
+          method.name,
+          if method.optional { "?" } else { "" },
+          function_signature(
+            &method.function_def.type_params,
+            &method.function_def.params,
+            method.function_def.return_type.as_ref()
+          )
+        )
+        .unwrap();
+      }
+      out.push('}');
+      out
+    }
+    DocNodeKind::Interface => {
+      let interface_def = node.interface_def.as_ref().unwrap();
+      let mut out = format!(
+        "interface {}{}",
+        name,
+        type_params(&interface_def.type_params)
+      );
+      if !interface_def.extends.is_empty() {
+        write!(out, " extends {}", join(&interface_def.extends)).unwrap();
+      }
+      out.push_str(" {\n");
+      for property in &interface_def.properties {
+        writeln!(
+          out,
+          "  {}{}{}{};",
+          if property.readonly { "readonly " } else { "" },
+          property.name,
+          if property.optional { "?" } else { "" },
+          type_annotation(property.ts_type.as_ref())
+        )
+        .unwrap();
+      }
+      for method in &interface_def.methods {
+        writeln!(
+          out,
+          "  {}{}{};",
+          method.name,
+          if method.optional { "?" } else { "" },
+          function_signature(
+            &method.type_params,
+            &method.params,
+            method.return_type.as_ref()
+          )
+        )
+        .unwrap();
+      }
+      out.push('}');
+      out
+    }
+    DocNodeKind::Enum => {
+      let enum_def = node.enum_def.as_ref().unwrap();
+      let members = enum_def
+        .members
+        .iter()
+        .map(|member| match &member.init {
+          Some(init) => format!("  {} = {},\n", member.name, init),
+          None => format!("  {},\n", member.name),
+        })
+        .collect::<String>();
+      format!("enum {} {{\n{}}}", name, members)
+    }
+    DocNodeKind::TypeAlias => {
+      let type_alias_def = node.type_alias_def.as_ref().unwrap();
+      format!(
+        "type {}{} = {};",
+        name,
+        type_params(&type_alias_def.type_params),
+        type_alias_def.ts_type
+      )
+    }
+    DocNodeKind::Namespace => format!("namespace {}", name),
+    DocNodeKind::Import | DocNodeKind::ModuleDoc => String::new(),
+  }
+}
+
+fn function_signature(
+  type_params_defs: &[doc::ts_type_param::TsTypeParamDef],
+  params: &[doc::params::ParamDef],
+  return_type: Option<&doc::ts_type::TsTypeDef>,
+) -> String {
+  format!(
+    "{}({}){}",
+    type_params(type_params_defs),
+    join(params),
+    type_annotation(return_type)
+  )
+}
+
+fn type_params(type_params: &[doc::ts_type_param::TsTypeParamDef]) -> String {
+  if type_params.is_empty() {
+    String::new()
+  } else {
+    format!("<{}>", join(type_params))
+  }
+}
+
+fn type_annotation(maybe_ts_type: Option<&doc::ts_type::TsTypeDef>) -> String {
+  maybe_ts_type
+    .map(|ts_type| format!(": {}", ts_type))
+    .unwrap_or_default()
+}
+
+fn join<T: std::fmt::Display>(items: &[T]) -> String {
+  items
+    .iter()
+    .map(|item| item.to_string())
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+/// Get the first paragraph of the documentation of the members of classes,
+/// interfaces and enums.
+fn member_docs(node: &DocNode) -> Vec<(String, String)> {
+  let mut members = Vec::new();
+  let mut push = |name: &str, js_doc: &JsDoc| {
+    if let Some(doc) = js_doc.doc.as_deref().and_then(first_paragraph) {
+      members.push((name.to_string(), doc));
+    }
+  };
+  if let Some(class_def) = &node.class_def {
+    for property in &class_def.properties {
+      push(&property.name, &property.js_doc);
+    }
+    for method in &class_def.methods {
+      push(&method.name, &method.js_doc);
+    }
+  }
+  if let Some(interface_def) = &node.interface_def {
+    for property in &interface_def.properties {
+      push(&property.name, &property.js_doc);
+    }
+    for method in &interface_def.methods {
+      push(&method.name, &method.js_doc);
+    }
+  }
+  if let Some(enum_def) = &node.enum_def {
+    for member in &enum_def.members {
+      push(&member.name, &member.js_doc);
+    }
+  }
+  members
+}
+
+/// Generates the anchors of headings the same way as GitHub, where
+/// duplicate headings get a numeric suffix.
+#[derive(Default)]
+struct Slugs {
+  counts: HashMap<String, usize>,
+}
+
+impl Slugs {
+  fn slug(&mut self, heading: &str) -> String {
+    let slug = heading
+      .to_lowercase()
+      .chars()
+      .filter_map(|c| match c {
+        ' ' => Some('-'),
+        c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+        _ => None,
+      })
+      .collect::<String>();
+    let count = self.counts.entry(slug.clone()).or_insert(0);
+    let slug = if *count == 0 {
+      slug
+    } else {
+      format!("{}-{}", slug, count)
+    };
+    *count += 1;
+    slug
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_module_file_path() {
+    let base = ModuleSpecifier::parse("file:///project/").unwrap();
+    let cases = [
+      ("file:///project/mod.ts", "mod.md"),
+      ("file:///project/src/util.d.ts", "src/util.d.md"),
+      ("file:///other/mod.ts", "file/other/mod.md"),
+      ("https://deno.land/x/mod/mod.ts", "deno.land/x/mod/mod.md"),
+    ];
+    for (specifier, expected) in cases {
+      let specifier = ModuleSpecifier::parse(specifier).unwrap();
+      assert_eq!(module_file_path(&specifier, &base), expected);
+    }
+  }
+
+  #[test]
+  fn test_first_paragraph() {
+    assert_eq!(
+      first_paragraph("  A module\n  of things.\n\nMore details.").as_deref(),
+      Some("A module of things.")
+    );
+    assert_eq!(first_paragraph("\n"), None);
+  }
+
+  #[test]
+  fn test_slugs() {
+    let mut slugs = Slugs::default();
+    assert_eq!(slugs.slug("MyClass"), "myclass");
+    assert_eq!(slugs.slug("Deno.Listener"), "denolistener");
+    assert_eq!(slugs.slug("MyClass"), "myclass-1");
+  }
+}
@@ -0,0 +1,996 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use crate::args::CliOptions;
+use crate::args::DocFlags;
+use crate::args::DocHtmlConfig;
+use crate::args::DocHtmlFlag;
+use crate::args::DocLintRulesConfig;
+use crate::args::DocMarkdownFlag;
+use crate::args::DocSourceFileFlag;
+use crate::args::Flags;
+use crate::colors;
+use crate::display::write_json_to_stdout;
+use crate::display::write_to_stdout_ignore_sigpipe;
+use crate::factory::CliFactory;
+use crate::graph_util::graph_lock_or_exit;
+use crate::tsc::get_types_declaration_file_text;
+use crate::util::fs::collect_specifiers;
+use deno_ast::diagnostics::Diagnostic;
+use deno_config::glob::FilePatterns;
+use deno_config::glob::PathOrPatternSet;
+use deno_core::anyhow::anyhow;
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::futures::FutureExt;
+use deno_core::serde_json;
+use deno_doc as doc;
+use deno_graph::GraphKind;
+use deno_graph::ModuleAnalyzer;
+use deno_graph::ModuleParser;
+use deno_graph::ModuleSpecifier;
+use doc::DocDiagnostic;
+use indexmap::IndexMap;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::Path;
+use std::rc::Rc;
+
+mod markdown;
+
+/// The rules of the documentation linter of `deno_doc`, which are run unless
+/// they're excluded.
+const DEFAULT_DOC_LINT_RULES: &[&str] = &[
+  "missing-jsdoc",
+  "missing-explicit-type",
+  "missing-return-type",
+  "private-type-ref",
+];
+
+/// Checks that exported symbols have an `@example` tag. It's only run when
+/// it's included.
+const MISSING_EXAMPLE_RULE: &str = "missing-example";
+
+/// The scheme of the placeholder links to the symbols of other workspace
+/// members, which are replaced with relative links once every member is
+/// generated.
+const WORKSPACE_SYMBOL_HREF_PREFIX: &str = "deno-doc-symbol:";
+
+/// The name of the stylesheet of the theme in the output directory.
+const THEME_STYLESHEET_FILE_NAME: &str = "theme.css";
+
+/// Filters the symbols of all the workspace members on the index page.
+const WORKSPACE_SEARCH_SCRIPT: &str = r#"const input = document.getElementById("workspaceSearch");
+const results = document.getElementById("workspaceSearchResults");
+const members = document.getElementById("workspaceMembers");
+input.addEventListener("input", () => {
+  const query = input.value.trim().toLowerCase();
+  results.replaceChildren();
+  for (const entry of window.DENO_WORKSPACE_SEARCH_INDEX) {
+    if (!query || !entry.name.toLowerCase().includes(query)) continue;
+    const item = document.createElement("li");
+    const link = document.createElement("a");
+    link.href = entry.href;
+    link.textContent = entry.name;
+    const details = document.createElement("span");
+    details.textContent = ` ${entry.kind} - ${entry.package}`;
+    item.append(link, details);
+    results.append(item);
+  }
+  results.hidden = !query;
+  members.hidden = !!query;
+});
+"#;
+
+async fn generate_doc_nodes_for_builtin_types(
+  doc_flags: DocFlags,
+  parser: &dyn ModuleParser,
+  analyzer: &dyn ModuleAnalyzer,
+) -> Result<IndexMap<ModuleSpecifier, Vec<doc::DocNode>>, AnyError> {
+  let source_file_specifier =
+    ModuleSpecifier::parse("internal://lib.deno.d.ts").unwrap();
+  let content = get_types_declaration_file_text();
+  let mut loader = deno_graph::source::MemoryLoader::new(
+    vec![(
+      source_file_specifier.to_string(),
+      deno_graph::source::Source::Module {
+        specifier: source_file_specifier.to_string(),
+        content,
+        maybe_headers: None,
+      },
+    )],
+    Vec::new(),
+  );
+  let mut graph = deno_graph::ModuleGraph::new(GraphKind::TypesOnly);
+  graph
+    .build(
+      vec![source_file_specifier.clone()],
+      &mut loader,
+      deno_graph::BuildOptions {
+        module_analyzer: Some(analyzer),
+        ..Default::default()
+      },
+    )
+    .await;
+  let doc_parser = doc::DocParser::new(
+    &graph,
+    parser,
+    doc::DocParserOptions {
+      diagnostics: false,
+      private: doc_flags.private,
+    },
+  )?;
+  let nodes = doc_parser.parse_module(&source_file_specifier)?.definitions;
+
+  Ok(IndexMap::from([(source_file_specifier, nodes)]))
+}
+
+pub async fn doc(flags: Flags, doc_flags: DocFlags) -> Result<(), AnyError> {
+  let factory = CliFactory::from_flags(flags).await?;
+  let cli_options = factory.cli_options();
+  let module_info_cache = factory.module_info_cache()?;
+  let parsed_source_cache = factory.parsed_source_cache();
+  let capturing_parser = parsed_source_cache.as_capturing_parser();
+  let analyzer = module_info_cache.as_module_analyzer(&capturing_parser);
+
+  let doc_nodes_by_url = match doc_flags.source_files {
+    DocSourceFileFlag::Workspace => {
+      // `--workspace` requires `--html`
+      let html_options = doc_flags.html.as_ref().unwrap();
+      let members = generate_doc_nodes_for_workspace(
+        &factory,
+        &capturing_parser,
+        doc_flags.private,
+      )
+      .await?;
+      let deno_ns =
+        generate_deno_ns(&doc_flags, &capturing_parser, &analyzer).await?;
+      return generate_workspace_docs_directory(
+        &members,
+        html_options,
+        cli_options.resolve_doc_html_config()?.unwrap_or_default(),
+        deno_ns,
+      )
+      .boxed_local()
+      .await;
+    }
+    DocSourceFileFlag::Builtin => {
+      generate_doc_nodes_for_builtin_types(
+        doc_flags.clone(),
+        &capturing_parser,
+        &analyzer,
+      )
+      .await?
+    }
+    DocSourceFileFlag::Paths(ref source_files) => {
+      let module_graph_builder = factory.module_graph_builder().await?;
+      let maybe_lockfile = factory.maybe_lockfile();
+
+      let module_specifiers = collect_specifiers(
+        FilePatterns {
+          base: cli_options.initial_cwd().to_path_buf(),
+          include: Some(PathOrPatternSet::from_relative_path_or_patterns(
+            cli_options.initial_cwd(),
+            source_files,
+          )?),
+          exclude: Default::default(),
+        },
+        &Default::default(),
+        |_, _| true,
+      )?;
+      let graph = module_graph_builder
+        .create_graph(GraphKind::TypesOnly, module_specifiers.clone())
+        .await?;
+
+      if let Some(lockfile) = maybe_lockfile {
+        graph_lock_or_exit(&graph, &mut lockfile.lock());
+      }
+
+      let doc_parser = doc::DocParser::new(
+        &graph,
+        &capturing_parser,
+        doc::DocParserOptions {
+          private: doc_flags.private,
+          diagnostics: doc_flags.lint,
+        },
+      )?;
+
+      let mut doc_nodes_by_url =
+        IndexMap::with_capacity(module_specifiers.len());
+
+      for module_specifier in module_specifiers {
+        let nodes = doc_parser.parse_with_reexports(&module_specifier)?;
+        doc_nodes_by_url.insert(module_specifier, nodes);
+      }
+
+      if doc_flags.lint {
+        let rules = resolve_doc_lint_rules(
+          cli_options.resolve_doc_lint_rules()?.unwrap_or_default(),
+        )?;
+        let mut diagnostics = doc_parser
+          .take_diagnostics()
+          .iter()
+          .filter(|d| rules.contains(&*d.code()))
+          .map(DocLintDiagnostic::from_doc_diagnostic)
+          .collect::<Vec<_>>();
+        if rules.contains(MISSING_EXAMPLE_RULE) {
+          diagnostics.extend(missing_example_diagnostics(&doc_nodes_by_url));
+        }
+        check_diagnostics(diagnostics, doc_flags.json)?;
+      }
+
+      doc_nodes_by_url
+    }
+  };
+
+  if let Some(html_options) = &doc_flags.html {
+    let deno_ns = if doc_flags.source_files != DocSourceFileFlag::Builtin {
+      generate_deno_ns(&doc_flags, &capturing_parser, &analyzer).await?
+    } else {
+      Default::default()
+    };
+
+    generate_docs_directory(
+      &doc_nodes_by_url,
+      html_options,
+      cli_options.resolve_doc_html_config()?.unwrap_or_default(),
+      deno_ns,
+    )
+    .boxed_local()
+    .await
+  } else if let Some(markdown_options) = &doc_flags.markdown {
+    generate_markdown_directory(
+      &doc_nodes_by_url,
+      markdown_options,
+      cli_options,
+    )
+  } else {
+    let modules_len = doc_nodes_by_url.len();
+    let doc_nodes =
+      doc_nodes_by_url.into_values().flatten().collect::<Vec<_>>();
+
+    if doc_flags.lint {
+      // don't output docs if running with the --lint flag, the diagnostics
+      // were already output in JSON format with --json
+      if !doc_flags.json {
+        log::info!(
+          "Checked {} file{}",
+          modules_len,
+          if modules_len == 1 { "" } else { "s" }
+        );
+      }
+      Ok(())
+    } else if doc_flags.json {
+      write_json_to_stdout(&doc_nodes)
+    } else {
+      print_docs_to_stdout(doc_flags, doc_nodes)
+    }
+  }
+}
+
+/// Get the symbols of the `Deno` namespace, which are linked to the API
+/// documentation of deno.land.
+async fn generate_deno_ns(
+  doc_flags: &DocFlags,
+  parser: &dyn ModuleParser,
+  analyzer: &dyn ModuleAnalyzer,
+) -> Result<HashSet<Vec<String>>, AnyError> {
+  let deno_ns =
+    generate_doc_nodes_for_builtin_types(doc_flags.clone(), parser, analyzer)
+      .await?;
+  let (_, deno_ns) = deno_ns.first().unwrap();
+
+  Ok(deno_doc::html::compute_namespaced_symbols(deno_ns, &[]))
+}
+
+/// The documentation of a member of a workspace.
+struct WorkspaceMemberDocs {
+  name: String,
+  version: Option<String>,
+  dir_url: ModuleSpecifier,
+  /// The directory of the pages of the member, relative to the output
+  /// directory.
+  output_dir: String,
+  main_entrypoint: Option<ModuleSpecifier>,
+  /// The name of the export of every entrypoint.
+  rewrite_map: IndexMap<ModuleSpecifier, String>,
+  doc_nodes_by_url: IndexMap<ModuleSpecifier, Vec<doc::DocNode>>,
+}
+
+/// Parse the documentation of the exports of every workspace member. The
+/// members share one module graph, so the imports between members are
+/// resolved to their pages.
+async fn generate_doc_nodes_for_workspace(
+  factory: &CliFactory,
+  parser: &dyn ModuleParser,
+  private: bool,
+) -> Result<Vec<WorkspaceMemberDocs>, AnyError> {
+  let cli_options = factory.cli_options();
+  let Some(workspace_config) = cli_options.maybe_workspace_config() else {
+    bail!("'--workspace' requires a configuration file with \"workspaces\"");
+  };
+
+  let mut members = Vec::with_capacity(workspace_config.members.len());
+  let mut seen_names = HashSet::with_capacity(workspace_config.members.len());
+  for member in &workspace_config.members {
+    if !seen_names.insert(&member.package_name) {
+      bail!(
+        "Cannot have two workspace packages with the same name ('{}' at {})",
+        member.package_name,
+        member.path.display(),
+      );
+    }
+    let config_file = &member.config_file;
+    let exports_config = config_file
+      .to_exports_config()
+      .with_context(|| {
+        format!("Failed to parse exports at {}", config_file.specifier)
+      })?
+      .into_map();
+    let mut rewrite_map = IndexMap::with_capacity(exports_config.len());
+    for (key, value) in exports_config {
+      let entry_point =
+        config_file.specifier.join(&value).with_context(|| {
+          format!("Failed to join {} with {}", config_file.specifier, value)
+        })?;
+      rewrite_map.insert(entry_point, key);
+    }
+    let main_entrypoint = rewrite_map
+      .iter()
+      .find(|(_, key)| key.as_str() == ".")
+      .map(|(specifier, _)| specifier.clone());
+    members.push(WorkspaceMemberDocs {
+      name: member.package_name.clone(),
+      version: config_file.json.version.clone(),
+      dir_url: config_file.specifier.join("./").unwrap(),
+      output_dir: member.package_name.clone(),
+      main_entrypoint,
+      rewrite_map,
+      doc_nodes_by_url: IndexMap::new(),
+    });
+  }
+
+  let module_graph_builder = factory.module_graph_builder().await?;
+  let roots = members
+    .iter()
+    .flat_map(|member| member.rewrite_map.keys().cloned())
+    .collect::<Vec<_>>();
+  let graph = module_graph_builder
+    .create_graph(GraphKind::TypesOnly, roots)
+    .await?;
+
+  if let Some(lockfile) = factory.maybe_lockfile() {
+    graph_lock_or_exit(&graph, &mut lockfile.lock());
+  }
+
+  let doc_parser = doc::DocParser::new(
+    &graph,
+    parser,
+    doc::DocParserOptions {
+      private,
+      diagnostics: false,
+    },
+  )?;
+  for member in &mut members {
+    for specifier in member.rewrite_map.keys() {
+      let nodes = doc_parser.parse_with_reexports(specifier)?;
+      member.doc_nodes_by_url.insert(specifier.clone(), nodes);
+    }
+  }
+
+  Ok(members)
+}
+
+struct DocResolver {
+  deno_ns: std::collections::HashSet<Vec<String>>,
+}
+
+impl deno_doc::html::HrefResolver for DocResolver {
+  fn resolve_global_symbol(&self, symbol: &[String]) -> Option<String> {
+    if self.deno_ns.contains(symbol) {
+      Some(format!(
+        "https://deno.land/api@{}?s={}",
+        env!("CARGO_PKG_VERSION"),
+        symbol.join(".")
+      ))
+    } else {
+      None
+    }
+  }
+
+  fn resolve_import_href(
+    &self,
+    symbol: &[String],
+    src: &str,
+  ) -> Option<String> {
+    let mut url = ModuleSpecifier::parse(src).ok()?;
+
+    if url.domain() == Some("deno.land") {
+      url.set_query(Some(&format!("s={}", symbol.join("."))));
+      return Some(url.to_string());
+    }
+
+    None
+  }
+
+  fn resolve_usage(
+    &self,
+    _current_specifier: &ModuleSpecifier,
+    current_file: &str,
+  ) -> Option<String> {
+    Some(current_file.to_string())
+  }
+
+  fn resolve_source(&self, location: &deno_doc::Location) -> Option<String> {
+    Some(location.filename.clone())
+  }
+}
+
+/// Links the imported symbols of other workspace members to a placeholder,
+/// because the relative link depends on the page which links to it.
+struct WorkspaceDocResolver {
+  inner: DocResolver,
+  member_dir_urls: Vec<ModuleSpecifier>,
+}
+
+impl deno_doc::html::HrefResolver for WorkspaceDocResolver {
+  fn resolve_global_symbol(&self, symbol: &[String]) -> Option<String> {
+    self.inner.resolve_global_symbol(symbol)
+  }
+
+  fn resolve_import_href(
+    &self,
+    symbol: &[String],
+    src: &str,
+  ) -> Option<String> {
+    if let Some(index) = self
+      .member_dir_urls
+      .iter()
+      .position(|dir_url| src.starts_with(dir_url.as_str()))
+    {
+      return Some(format!(
+        "{}{}/{}",
+        WORKSPACE_SYMBOL_HREF_PREFIX,
+        index,
+        symbol.join(".")
+      ));
+    }
+
+    self.inner.resolve_import_href(symbol, src)
+  }
+
+  fn resolve_usage(
+    &self,
+    current_specifier: &ModuleSpecifier,
+    current_file: &str,
+  ) -> Option<String> {
+    self.inner.resolve_usage(current_specifier, current_file)
+  }
+
+  fn resolve_source(&self, location: &deno_doc::Location) -> Option<String> {
+    self.inner.resolve_source(location)
+  }
+}
+
+async fn generate_docs_directory(
+  doc_nodes_by_url: &IndexMap<ModuleSpecifier, Vec<doc::DocNode>>,
+  html_options: &DocHtmlFlag,
+  html_config: DocHtmlConfig,
+  deno_ns: std::collections::HashSet<Vec<String>>,
+) -> Result<(), AnyError> {
+  let options = deno_doc::html::GenerateOptions {
+    package_name: Some(html_options.name.to_owned()),
+    main_entrypoint: None,
+    rewrite_map: None,
+    hide_module_doc_title: false,
+    href_resolver: Rc::new(DocResolver { deno_ns }),
+    sidebar_flatten_namespaces: false,
+    usage_composer: None,
+  };
+
+  let files = deno_doc::html::generate(options, doc_nodes_by_url)
+    .context("Failed to generate HTML documentation")?;
+
+  write_docs_directory(
+    &html_options.output,
+    apply_doc_html_theme(files.into_iter().collect(), &html_config)?,
+  )
+}
+
+fn generate_markdown_directory(
+  doc_nodes_by_url: &IndexMap<ModuleSpecifier, Vec<doc::DocNode>>,
+  markdown_options: &DocMarkdownFlag,
+  cli_options: &CliOptions,
+) -> Result<(), AnyError> {
+  let base = ModuleSpecifier::from_directory_path(cli_options.initial_cwd())
+    .map_err(|_| anyhow!("Invalid current directory"))?;
+  let files = markdown::generate(
+    doc_nodes_by_url,
+    &base,
+    markdown_options.name.as_deref(),
+  );
+  write_docs_directory(
+    &markdown_options.output,
+    files
+      .into_iter()
+      .map(|(name, content)| (name, content.into_bytes()))
+      .collect(),
+  )
+}
+
+/// Generate a site with the pages of every workspace member in its own
+/// directory, and an index page which lists the members and searches the
+/// symbols of all of them.
+async fn generate_workspace_docs_directory(
+  members: &[WorkspaceMemberDocs],
+  html_options: &DocHtmlFlag,
+  html_config: DocHtmlConfig,
+  deno_ns: HashSet<Vec<String>>,
+) -> Result<(), AnyError> {
+  let href_resolver = Rc::new(WorkspaceDocResolver {
+    inner: DocResolver { deno_ns },
+    member_dir_urls: members.iter().map(|m| m.dir_url.clone()).collect(),
+  });
+
+  let mut files = Vec::new();
+  let mut symbol_pages = Vec::with_capacity(members.len());
+  for member in members {
+    let options = deno_doc::html::GenerateOptions {
+      package_name: Some(member.name.clone()),
+      main_entrypoint: member.main_entrypoint.clone(),
+      rewrite_map: Some(member.rewrite_map.clone()),
+      hide_module_doc_title: false,
+      href_resolver: href_resolver.clone(),
+      sidebar_flatten_namespaces: false,
+      usage_composer: None,
+    };
+    let member_files =
+      deno_doc::html::generate(options, &member.doc_nodes_by_url)
+        .with_context(|| {
+          format!("Failed to generate HTML documentation of '{}'", member.name)
+        })?;
+    let mut member_files = member_files
+      .into_iter()
+      .map(|(name, content)| {
+        let name = name.trim_start_matches("./");
+        (format!("{}/{}", member.output_dir, name), content)
+      })
+      .collect::<Vec<_>>();
+    member_files.sort_by(|a, b| a.0.cmp(&b.0));
+    symbol_pages.push(collect_symbol_pages(&member_files));
+    files.extend(member_files);
+  }
+
+  resolve_workspace_symbol_hrefs(&mut files, members, &symbol_pages);
+  files.push((
+    "workspace_search_index.js".to_string(),
+    format!(
+      "window.DENO_WORKSPACE_SEARCH_INDEX = {};\n",
+      serde_json::to_string(&workspace_search_index(members, &symbol_pages))?
+    ),
+  ));
+  files.push((
+    "workspace_search.js".to_string(),
+    WORKSPACE_SEARCH_SCRIPT.to_string(),
+  ));
+  files.push((
+    "index.html".to_string(),
+    render_workspace_index(&html_options.name, members),
+  ));
+
+  write_docs_directory(
+    &html_options.output,
+    apply_doc_html_theme(files, &html_config)?,
+  )
+}
+
+/// Get the page of every symbol of a workspace member by the name of the
+/// symbol. The pages of the symbols are named `~/<symbol>.html`, and the
+/// first page wins when a symbol is exported by multiple entrypoints.
+fn collect_symbol_pages(files: &[(String, String)]) -> HashMap<String, String> {
+  let mut pages = HashMap::new();
+  for (name, _) in files {
+    let Some((_, file_name)) = name.rsplit_once("/~/") else {
+      continue;
+    };
+    let Some(symbol) = file_name.strip_suffix(".html") else {
+      continue;
+    };
+    if symbol != "index" {
+      pages
+        .entry(symbol.to_string())
+        .or_insert_with(|| name.clone());
+    }
+  }
+  pages
+}
+
+/// Get the prefix of the links from a page to the root of the output
+/// directory.
+fn relative_root_prefix(file_name: &str) -> String {
+  "../".repeat(file_name.matches('/').count())
+}
+
+/// Replace the placeholder links of `WorkspaceDocResolver` with links to the
+/// pages of the symbols, or to the index of the member when the symbol
+/// doesn't have a page.
+fn resolve_workspace_symbol_hrefs(
+  files: &mut [(String, String)],
+  members: &[WorkspaceMemberDocs],
+  symbol_pages: &[HashMap<String, String>],
+) {
+  let re = lazy_regex::regex!(r#"deno-doc-symbol:(\d+)/([^"'\s]*)"#);
+  for (name, content) in files.iter_mut() {
+    if !name.ends_with(".html")
+      || !content.contains(WORKSPACE_SYMBOL_HREF_PREFIX)
+    {
+      continue;
+    }
+    let prefix = relative_root_prefix(name);
+    *content = re
+      .replace_all(content, |caps: &regex::Captures| {
+        let index = caps[1].parse::<usize>().unwrap();
+        let page =
+          symbol_pages[index]
+            .get(&caps[2])
+            .cloned()
+            .unwrap_or_else(|| {
+              format!("{}/index.html", members[index].output_dir)
+            });
+        format!("{}{}", prefix, page)
+      })
+      .into_owned();
+  }
+}
+
+#[derive(Serialize)]
+struct WorkspaceSearchEntry<'a> {
+  name: &'a str,
+  kind: doc::DocNodeKind,
+  package: &'a str,
+  href: &'a str,
+}
+
+/// Get the symbols of every workspace member which have a page.
+fn workspace_search_index<'a>(
+  members: &'a [WorkspaceMemberDocs],
+  symbol_pages: &'a [HashMap<String, String>],
+) -> Vec<WorkspaceSearchEntry<'a>> {
+  let mut entries = Vec::new();
+  for (member, pages) in members.iter().zip(symbol_pages) {
+    let mut seen = HashSet::new();
+    for node in member.doc_nodes_by_url.values().flatten() {
+      let Some(href) = pages.get(&node.name) else {
+        continue;
+      };
+      if seen.insert(&node.name) {
+        entries.push(WorkspaceSearchEntry {
+          name: &node.name,
+          kind: node.kind,
+          package: &member.name,
+          href,
+        });
+      }
+    }
+  }
+  entries
+}
+
+fn escape_html(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+}
+
+fn render_workspace_index(
+  name: &str,
+  members: &[WorkspaceMemberDocs],
+) -> String {
+  let name = escape_html(name);
+  let members = members
+    .iter()
+    .map(|member| {
+      format!(
+        "<li><a href=\"{}/index.html\">{}</a> <span>{}</span></li>",
+        escape_html(&member.output_dir),
+        escape_html(&member.name),
+        escape_html(member.version.as_deref().unwrap_or_default())
+      )
+    })
+    .collect::<Vec<_>>()
+    .join("\n");
+  format!(
+    r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{name}</title>
+<script src="workspace_search_index.js" defer></script>
+<script src="workspace_search.js" defer></script>
+</head>
+<body>
+<main>
+<h1>{name}</h1>
+<input id="workspaceSearch" type="search" placeholder="Search for symbols" autocomplete="off">
+<ul id="workspaceSearchResults" hidden></ul>
+<ul id="workspaceMembers">
+{members}
+</ul>
+</main>
+</body>
+</html>
+"#
+  )
+}
+
+/// Add the stylesheet and the logo of the theme to every page, and add the
+/// files of the theme to the root of the output directory.
+fn apply_doc_html_theme(
+  mut files: Vec<(String, String)>,
+  html_config: &DocHtmlConfig,
+) -> Result<Vec<(String, Vec<u8>)>, AnyError> {
+  let mut assets = Vec::new();
+  if let Some(path) = &html_config.stylesheet {
+    let content = std::fs::read(path)
+      .with_context(|| format!("Failed reading '{}'", path.display()))?;
+    assets.push((THEME_STYLESHEET_FILE_NAME.to_string(), content));
+  }
+  let maybe_logo_name = match &html_config.logo {
+    Some(path) => {
+      let file_name = path
+        .file_name()
+        .with_context(|| format!("Invalid logo path '{}'", path.display()))?
+        .to_string_lossy()
+        .to_string();
+      let content = std::fs::read(path)
+        .with_context(|| format!("Failed reading '{}'", path.display()))?;
+      assets.push((file_name.clone(), content));
+      Some(file_name)
+    }
+    None => None,
+  };
+
+  for (name, content) in &mut files {
+    if !name.ends_with(".html") {
+      continue;
+    }
+    let prefix = relative_root_prefix(name);
+    if html_config.stylesheet.is_some() {
+      *content = content.replacen(
+        "</head>",
+        &format!(
+          "<link rel=\"stylesheet\" href=\"{}{}\"></head>",
+          prefix, THEME_STYLESHEET_FILE_NAME
+        ),
+        1,
+      );
+    }
+    if let Some(logo_name) = &maybe_logo_name {
+      let body = lazy_regex::regex!(r"<body[^>]*>");
+      if let Some(end) = body.find(content).map(|m| m.end()) {
+        content.insert_str(
+          end,
+          &format!(
+            "<a class=\"doc-logo\" href=\"{0}index.html\"><img src=\"{0}{1}\" alt=\"\"></a>",
+            prefix,
+            escape_html(logo_name)
+          ),
+        );
+      }
+    }
+  }
+
+  Ok(
+    files
+      .into_iter()
+      .map(|(name, content)| (name, content.into_bytes()))
+      .chain(assets)
+      .collect(),
+  )
+}
+
+/// Write the files to the output directory, replacing the previous output.
+fn write_docs_directory(
+  output: &Path,
+  files: Vec<(String, Vec<u8>)>,
+) -> Result<(), AnyError> {
+  let cwd = std::env::current_dir().context("Failed to get CWD")?;
+  let output_dir_resolved = cwd.join(output);
+
+  let path = &output_dir_resolved;
+  let _ = std::fs::remove_dir_all(path);
+  std::fs::create_dir(path)
+    .with_context(|| format!("Failed to create directory {:?}", path))?;
+
+  let no_of_files = files.len();
+  for (name, content) in files {
+    let this_path = path.join(name);
+    let prefix = this_path.parent().with_context(|| {
+      format!("Failed to get parent path for {:?}", this_path)
+    })?;
+    std::fs::create_dir_all(prefix)
+      .with_context(|| format!("Failed to create directory {:?}", prefix))?;
+    std::fs::write(&this_path, content)
+      .with_context(|| format!("Failed to write file {:?}", this_path))?;
+  }
+
+  log::info!(
+    "{}",
+    colors::green(format!("Written {} files to {:?}", no_of_files, output))
+  );
+  Ok(())
+}
+
+fn print_docs_to_stdout(
+  doc_flags: DocFlags,
+  mut doc_nodes: Vec<deno_doc::DocNode>,
+) -> Result<(), AnyError> {
+  doc_nodes.retain(|doc_node| doc_node.kind != doc::DocNodeKind::Import);
+  let details = if let Some(filter) = doc_flags.filter {
+    let nodes = doc::find_nodes_by_name_recursively(doc_nodes, filter.clone());
+    if nodes.is_empty() {
+      bail!("Node {} was not found!", filter);
+    }
+    format!(
+      "{}",
+      doc::DocPrinter::new(&nodes, colors::use_color(), doc_flags.private)
+    )
+  } else {
+    format!(
+      "{}",
+      doc::DocPrinter::new(&doc_nodes, colors::use_color(), doc_flags.private)
+    )
+  };
+
+  write_to_stdout_ignore_sigpipe(details.as_bytes()).map_err(AnyError::from)
+}
+
+/// Resolve the enabled rules of the documentation linter.
+fn resolve_doc_lint_rules(
+  config: DocLintRulesConfig,
+) -> Result<HashSet<&'static str>, AnyError> {
+  let all_rules = DEFAULT_DOC_LINT_RULES
+    .iter()
+    .copied()
+    .chain([MISSING_EXAMPLE_RULE]);
+  for rule in config.include.iter().chain(config.exclude.iter()) {
+    if !all_rules.clone().any(|r| r == rule) {
+      bail!("Unknown documentation lint rule \"{}\".", rule);
+    }
+  }
+  Ok(
+    all_rules
+      .filter(|rule| {
+        config.include.iter().any(|r| r == rule)
+          || (DEFAULT_DOC_LINT_RULES.contains(rule)
+            && !config.exclude.iter().any(|r| r == rule))
+      })
+      .collect(),
+  )
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DocLintDiagnostic {
+  code: String,
+  message: String,
+  filename: String,
+  /// The one based line of the symbol.
+  line: usize,
+  /// The zero based column of the symbol.
+  col: usize,
+  #[serde(skip)]
+  display: String,
+}
+
+impl DocLintDiagnostic {
+  fn from_doc_diagnostic(diagnostic: &DocDiagnostic) -> Self {
+    Self {
+      code: diagnostic.code().to_string(),
+      message: diagnostic.message().to_string(),
+      filename: diagnostic.location.filename.clone(),
+      line: diagnostic.location.line,
+      col: diagnostic.location.col,
+      display: diagnostic.display().to_string(),
+    }
+  }
+}
+
+/// Get the diagnostics of the exported symbols which don't have an
+/// `@example` in their JSDoc.
+fn missing_example_diagnostics(
+  doc_nodes_by_url: &IndexMap<ModuleSpecifier, Vec<doc::DocNode>>,
+) -> Vec<DocLintDiagnostic> {
+  doc_nodes_by_url
+    .values()
+    .flatten()
+    .filter(|node| {
+      !matches!(
+        node.kind,
+        doc::DocNodeKind::Import | doc::DocNodeKind::ModuleDoc
+      ) && matches!(node.declaration_kind, doc::node::DeclarationKind::Export)
+        && !node
+          .js_doc
+          .tags
+          .iter()
+          .any(|tag| matches!(tag, doc::js_doc::JsDocTag::Example { .. }))
+    })
+    .map(|node| {
+      let message =
+        format!("exported symbol \"{}\" is missing an @example", node.name);
+      let location = &node.location;
+      DocLintDiagnostic {
+        code: MISSING_EXAMPLE_RULE.to_string(),
+        display: format!(
+          "{}[{}]: {}\n    at {}:{}:{}",
+          colors::red_bold("error"),
+          MISSING_EXAMPLE_RULE,
+          message,
+          location.filename,
+          location.line,
+          location.col + 1
+        ),
+        message,
+        filename: location.filename.clone(),
+        line: location.line,
+        col: location.col,
+      }
+    })
+    .collect()
+}
+
+#[derive(Serialize)]
+struct JsonDocLintReport<'a> {
+  diagnostics: Vec<&'a DocLintDiagnostic>,
+}
+
+fn check_diagnostics(
+  diagnostics: Vec<DocLintDiagnostic>,
+  json: bool,
+) -> Result<(), AnyError> {
+  if diagnostics.is_empty() && !json {
+    return Ok(());
+  }
+
+  // group by location then by line (sorted) then column (sorted)
+  let mut diagnostic_groups = IndexMap::new();
+  for diagnostic in &diagnostics {
+    diagnostic_groups
+      .entry(diagnostic.filename.clone())
+      .or_insert_with(BTreeMap::new)
+      .entry(diagnostic.line)
+      .or_insert_with(BTreeMap::new)
+      .entry(diagnostic.col)
+      .or_insert_with(Vec::new)
+      .push(diagnostic);
+  }
+  let sorted_diagnostics = diagnostic_groups
+    .into_values()
+    .flat_map(|by_line| by_line.into_values())
+    .flat_map(|by_col| by_col.into_values())
+    .flatten()
+    .collect::<Vec<_>>();
+
+  if json {
+    write_json_to_stdout(&JsonDocLintReport {
+      diagnostics: sorted_diagnostics,
+    })?;
+    if diagnostics.is_empty() {
+      return Ok(());
+    }
+  } else {
+    for diagnostic in sorted_diagnostics {
+      log::error!("{}", diagnostic.display);
+    }
+  }
+  bail!(
+    "Found {} documentation lint error{}.",
+    colors::bold(diagnostics.len().to_string()),
+    if diagnostics.len() == 1 { "" } else { "s" }
+  );
+}
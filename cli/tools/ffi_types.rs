@@ -0,0 +1,1047 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use deno_core::anyhow::anyhow;
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+
+use crate::args::TypesFlags;
+use crate::colors;
+use crate::util::diff::diff;
+use crate::util::display;
+
+/// The words which don't change how a type is passed through FFI.
+const QUALIFIERS: &[&str] = &[
+  "const",
+  "volatile",
+  "restrict",
+  "__restrict",
+  "extern",
+  "inline",
+  "__inline",
+  "__extension__",
+  "register",
+];
+
+/// The words which are followed by a parenthesized group to leave out, like
+/// `__attribute__((visibility("default")))`.
+const ATTRIBUTES: &[&str] = &["__attribute__", "__declspec", "__asm__", "asm"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+  Ident(String),
+  Number(String),
+  Str,
+  Ellipsis,
+  Punct(char),
+}
+
+impl Token {
+  fn is_ident(&self, ident: &str) -> bool {
+    matches!(self, Token::Ident(value) if value == ident)
+  }
+
+  fn is_punct(&self, punct: char) -> bool {
+    matches!(self, Token::Punct(value) if *value == punct)
+  }
+}
+
+/// Tokenizes a C header, leaving out the comments and the preprocessor
+/// directives.
+fn tokenize(text: &str) -> Vec<Token> {
+  let chars = text.chars().collect::<Vec<_>>();
+  let mut tokens = Vec::new();
+  let mut i = 0;
+  let mut is_line_start = true;
+  while i < chars.len() {
+    let c = chars[i];
+    if c == '\n' {
+      is_line_start = true;
+      i += 1;
+      continue;
+    }
+    if c.is_whitespace() {
+      i += 1;
+      continue;
+    }
+    if c == '#' && is_line_start {
+      // the directive can be continued on the next lines
+      while i < chars.len() && chars[i] != '\n' {
+        if chars[i] == '\\' && chars.get(i + 1) == Some(&'\n') {
+          i += 1;
+        }
+        i += 1;
+      }
+      continue;
+    }
+    is_line_start = false;
+    if c == '/' && chars.get(i + 1) == Some(&'/') {
+      while i < chars.len() && chars[i] != '\n' {
+        i += 1;
+      }
+    } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+      i += 2;
+      while i < chars.len()
+        && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/'))
+      {
+        i += 1;
+      }
+      i += 2;
+    } else if c.is_ascii_alphanumeric() || c == '_' {
+      let start = i;
+      while i < chars.len()
+        && (chars[i].is_ascii_alphanumeric() || chars[i] == '_')
+      {
+        i += 1;
+      }
+      let word = chars[start..i].iter().collect::<String>();
+      tokens.push(if c.is_ascii_digit() {
+        Token::Number(word)
+      } else {
+        Token::Ident(word)
+      });
+    } else if c == '"' || c == '\'' {
+      i += 1;
+      while i < chars.len() && chars[i] != c {
+        if chars[i] == '\\' {
+          i += 1;
+        }
+        i += 1;
+      }
+      i += 1;
+      tokens.push(Token::Str);
+    } else if c == '.'
+      && chars.get(i + 1) == Some(&'.')
+      && chars.get(i + 2) == Some(&'.')
+    {
+      i += 3;
+      tokens.push(Token::Ellipsis);
+    } else {
+      tokens.push(Token::Punct(c));
+      i += 1;
+    }
+  }
+  tokens
+}
+
+/// Splits the tokens into the top level declarations without their `;`. The
+/// blocks of `extern "C" { ... }` are unwrapped and the definitions of
+/// functions, like the ones of `static inline` functions, are left out.
+fn split_declarations(tokens: Vec<Token>) -> Vec<Vec<Token>> {
+  let mut declarations = Vec::new();
+  let mut current = Vec::new();
+  let mut depth = 0;
+  let mut extern_blocks = 0;
+  for token in tokens {
+    match &token {
+      Token::Punct('{')
+        if depth == 0
+          && current.len() == 2
+          && current[0].is_ident("extern")
+          && current[1] == Token::Str =>
+      {
+        current.clear();
+        extern_blocks += 1;
+        continue;
+      }
+      Token::Punct('}') if depth == 0 && extern_blocks > 0 => {
+        current.clear();
+        extern_blocks -= 1;
+        continue;
+      }
+      Token::Punct('{') => depth += 1,
+      Token::Punct('}') => {
+        depth -= 1;
+        if depth == 0 && is_function_definition(&current) {
+          current.clear();
+          continue;
+        }
+      }
+      Token::Punct(';') if depth == 0 => {
+        if !current.is_empty() {
+          declarations.push(std::mem::take(&mut current));
+        }
+        continue;
+      }
+      _ => {}
+    }
+    current.push(token);
+  }
+  declarations
+}
+
+fn is_function_definition(tokens: &[Token]) -> bool {
+  let body_start = tokens.iter().position(|token| token.is_punct('{'));
+  matches!(body_start, Some(index) if index > 0 && tokens[index - 1].is_punct(')'))
+}
+
+/// Finds the index of the token which closes the group opened at the index.
+fn find_closing(tokens: &[Token], open_index: usize) -> Option<usize> {
+  let (open, close) = match tokens.get(open_index)? {
+    Token::Punct('(') => ('(', ')'),
+    Token::Punct('{') => ('{', '}'),
+    Token::Punct('[') => ('[', ']'),
+    _ => return None,
+  };
+  let mut depth = 0;
+  for (index, token) in tokens.iter().enumerate().skip(open_index) {
+    if token.is_punct(open) {
+      depth += 1;
+    } else if token.is_punct(close) {
+      depth -= 1;
+      if depth == 0 {
+        return Some(index);
+      }
+    }
+  }
+  None
+}
+
+/// Splits the tokens by the separator outside of any group.
+fn split_top_level(tokens: &[Token], separator: char) -> Vec<&[Token]> {
+  let mut parts = Vec::new();
+  let mut depth = 0;
+  let mut start = 0;
+  for (index, token) in tokens.iter().enumerate() {
+    match token {
+      Token::Punct('(' | '{' | '[') => depth += 1,
+      Token::Punct(')' | '}' | ']') => depth -= 1,
+      Token::Punct(c) if *c == separator && depth == 0 => {
+        parts.push(&tokens[start..index]);
+        start = index + 1;
+      }
+      _ => {}
+    }
+  }
+  parts.push(&tokens[start..]);
+  parts
+}
+
+/// Leaves out the attributes and the parenthesized groups which follow them.
+fn strip_attributes(tokens: &[Token]) -> Vec<Token> {
+  let mut stripped = Vec::with_capacity(tokens.len());
+  let mut index = 0;
+  while index < tokens.len() {
+    let is_attribute = matches!(
+      &tokens[index],
+      Token::Ident(ident) if ATTRIBUTES.contains(&ident.as_str())
+    );
+    if is_attribute {
+      index = find_closing(tokens, index + 1).unwrap_or(index) + 1;
+      continue;
+    }
+    stripped.push(tokens[index].clone());
+    index += 1;
+  }
+  stripped
+}
+
+fn declaration_text(tokens: &[Token]) -> String {
+  let text = tokens
+    .iter()
+    .map(|token| match token {
+      Token::Ident(value) | Token::Number(value) => value.to_string(),
+      Token::Str => "\"...\"".to_string(),
+      Token::Ellipsis => "...".to_string(),
+      Token::Punct(c) => c.to_string(),
+    })
+    .collect::<Vec<_>>()
+    .join(" ");
+  match text.char_indices().nth(60) {
+    Some((index, _)) => format!("{}...", &text[..index]),
+    None => text,
+  }
+}
+
+fn parse_array_length(token: Option<&Token>) -> Result<usize, AnyError> {
+  let Some(Token::Number(number)) = token else {
+    bail!("The length of an array has to be a number.");
+  };
+  let number = number.trim_end_matches(['u', 'U', 'l', 'L']);
+  let length = match number.strip_prefix("0x").or(number.strip_prefix("0X")) {
+    Some(hex) => usize::from_str_radix(hex, 16),
+    None => number.parse::<usize>(),
+  };
+  length.map_err(|_| anyhow!("Invalid length of an array \"{number}\"."))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CType {
+  Void,
+  /// The name of a native type of `Deno.dlopen()`.
+  Native(&'static str),
+  /// A struct by the name of its constant in the bindings.
+  Struct(String),
+  /// A struct which is only declared, so it can only be used by a pointer.
+  OpaqueStruct(String),
+  Union(String),
+  /// A pointer to a function.
+  Function,
+}
+
+impl CType {
+  fn pointer_to(self, pointers: usize) -> Self {
+    match (self, pointers) {
+      (ctype, 0) => ctype,
+      (Self::Function, 1) => Self::Function,
+      _ => Self::Native("pointer"),
+    }
+  }
+
+  /// The native type in the bindings of a parameter, a result or a field.
+  fn to_native_type(&self, is_result: bool) -> Result<String, AnyError> {
+    match self {
+      Self::Void if is_result => Ok("\"void\"".to_string()),
+      Self::Void => bail!("Only a result can be \"void\"."),
+      Self::Native(native) => Ok(format!("\"{native}\"")),
+      Self::Struct(name) => Ok(name.clone()),
+      Self::OpaqueStruct(name) => {
+        bail!("The fields of the struct \"{name}\" are unknown.")
+      }
+      Self::Union(name) => {
+        bail!("The union \"{name}\" can't be passed by value through FFI.")
+      }
+      Self::Function => Ok("\"function\"".to_string()),
+    }
+  }
+}
+
+fn get_fixed_width_type(name: &str) -> Option<&'static str> {
+  let native = match name {
+    "int8_t" => "i8",
+    "uint8_t" => "u8",
+    "int16_t" => "i16",
+    "uint16_t" => "u16",
+    "int32_t" => "i32",
+    "uint32_t" => "u32",
+    "int64_t" => "i64",
+    "uint64_t" => "u64",
+    "size_t" | "uintptr_t" => "usize",
+    "ssize_t" | "ptrdiff_t" | "intptr_t" => "isize",
+    _ => return None,
+  };
+  Some(native)
+}
+
+/// Gets the type of a combination of the built-in type words of C, like
+/// `unsigned long long int`.
+fn get_builtin_type(words: &[&str]) -> Option<CType> {
+  let mut base = None;
+  let mut is_unsigned = false;
+  let mut is_signed = false;
+  let mut has_int = false;
+  let mut longs = 0;
+  for word in words {
+    match *word {
+      "unsigned" => is_unsigned = true,
+      "signed" => is_signed = true,
+      "int" => has_int = true,
+      "long" => longs += 1,
+      "char" | "short" | "float" | "double" | "void" | "bool" | "_Bool"
+        if base.is_none() =>
+      {
+        base = Some(*word)
+      }
+      _ => return None,
+    }
+  }
+  if is_unsigned && is_signed {
+    return None;
+  }
+  let int = |signed, unsigned| {
+    Some(CType::Native(if is_unsigned { unsigned } else { signed }))
+  };
+  let is_plain = !is_unsigned && !is_signed && !has_int;
+  match (base, longs) {
+    (None, 0) if has_int || is_unsigned || is_signed => int("i32", "u32"),
+    // a long has the size of a pointer other than on Windows
+    (None, 1) => int("isize", "usize"),
+    (None, 2) => int("i64", "u64"),
+    (Some("short"), 0) => int("i16", "u16"),
+    (Some("char"), 0) if !has_int => int("i8", "u8"),
+    (Some("float"), 0) if is_plain => Some(CType::Native("f32")),
+    (Some("double"), 0) if is_plain => Some(CType::Native("f64")),
+    (Some("bool" | "_Bool"), 0) if is_plain => Some(CType::Native("bool")),
+    (Some("void"), 0) if is_plain => Some(CType::Void),
+    _ => None,
+  }
+}
+
+#[derive(Debug, Default)]
+struct HeaderParser {
+  typedefs: HashMap<String, CType>,
+  /// The types of the tags, like `struct point` or `enum color`.
+  tags: HashMap<String, CType>,
+  /// The fields of the structs by the names of their constants, in the order
+  /// they are defined in.
+  structs: Vec<(String, Vec<String>)>,
+  /// The definitions of the symbols by their names.
+  symbols: Vec<(String, String)>,
+}
+
+impl HeaderParser {
+  fn is_type_name(&self, name: &str) -> bool {
+    self.typedefs.contains_key(name)
+      || get_fixed_width_type(name).is_some()
+      || get_builtin_type(&[name]).is_some()
+      || matches!(name, "unsigned" | "signed" | "long" | "short")
+  }
+
+  fn parse_type(&self, tokens: &[Token]) -> Result<CType, AnyError> {
+    let mut words = Vec::new();
+    let mut pointers = 0;
+    for token in tokens {
+      match token {
+        Token::Ident(word) if QUALIFIERS.contains(&word.as_str()) => {}
+        Token::Ident(word) => words.push(word.as_str()),
+        Token::Punct('*') => pointers += 1,
+        _ => bail!("Unsupported type \"{}\".", declaration_text(tokens)),
+      }
+    }
+    let ctype = match words.as_slice() {
+      [] => bail!("Missing type."),
+      [kind @ ("struct" | "union" | "enum"), tag] => {
+        match self.tags.get(&format!("{kind} {tag}")) {
+          Some(ctype) => ctype.clone(),
+          None => match *kind {
+            "struct" => CType::OpaqueStruct(tag.to_string()),
+            "union" => CType::Union(tag.to_string()),
+            _ => CType::Native("i32"),
+          },
+        }
+      }
+      [name] if self.typedefs.contains_key(*name) => {
+        self.typedefs.get(*name).unwrap().clone()
+      }
+      [name] if get_fixed_width_type(name).is_some() => {
+        CType::Native(get_fixed_width_type(name).unwrap())
+      }
+      words => get_builtin_type(words)
+        .ok_or_else(|| anyhow!("Unknown type \"{}\".", words.join(" ")))?,
+    };
+    Ok(ctype.pointer_to(pointers))
+  }
+
+  /// Parses a declarator like `*name[4]` of the base type, returning its name,
+  /// its type and the length of the array it is.
+  fn parse_declarator<'a>(
+    &self,
+    base: &[Token],
+    declarator: &'a [Token],
+  ) -> Result<(&'a str, CType, usize), AnyError> {
+    if declarator.iter().any(|token| token.is_punct('(')) {
+      // only pointers to functions are declared with parentheses here
+      let name = declarator
+        .iter()
+        .find_map(|token| match token {
+          Token::Ident(name) if !QUALIFIERS.contains(&name.as_str()) => {
+            Some(name.as_str())
+          }
+          _ => None,
+        })
+        .ok_or_else(|| anyhow!("Missing name of a pointer to a function."))?;
+      return Ok((name, CType::Function, 1));
+    }
+    let array_start = declarator
+      .iter()
+      .position(|token| token.is_punct('['))
+      .unwrap_or(declarator.len());
+    let (name_tokens, array_tokens) = declarator.split_at(array_start);
+    let Some((Token::Ident(name), pointer_tokens)) = name_tokens.split_last()
+    else {
+      bail!("Missing name in \"{}\".", declaration_text(declarator));
+    };
+    let mut type_tokens = base.to_vec();
+    type_tokens.extend_from_slice(pointer_tokens);
+    let ctype = self.parse_type(&type_tokens)?;
+    let mut length = 1;
+    for (index, token) in array_tokens.iter().enumerate() {
+      if token.is_punct('[') {
+        length *= parse_array_length(array_tokens.get(index + 1))?;
+      }
+    }
+    Ok((name, ctype, length))
+  }
+
+  /// Splits a declaration like `const char *name, value` into its base type
+  /// and its declarators.
+  fn split_declarators<'a>(
+    &self,
+    tokens: &'a [Token],
+  ) -> Result<(&'a [Token], Vec<&'a [Token]>), AnyError> {
+    let parts = split_top_level(tokens, ',');
+    let first = parts[0];
+    // the base type ends before the pointers and the name of the first one,
+    // or the parentheses of a pointer to a function like `(*name)(int)`
+    let mut end = match first
+      .iter()
+      .position(|token| token.is_punct('(') || token.is_punct('['))
+    {
+      Some(index)
+        if first[index].is_punct('(')
+          && first
+            .get(index + 1)
+            .is_some_and(|token| token.is_punct('*')) =>
+      {
+        index
+      }
+      Some(index) => index.saturating_sub(1),
+      None => first.len().saturating_sub(1),
+    };
+    while end > 0 && first[end - 1].is_punct('*') {
+      end -= 1;
+    }
+    if end == 0 {
+      bail!("Missing type in \"{}\".", declaration_text(tokens));
+    }
+    let mut declarators = vec![&first[end..]];
+    declarators.extend(parts[1..].iter().copied());
+    Ok((&first[..end], declarators))
+  }
+
+  fn parse_fields(
+    &mut self,
+    struct_name: &str,
+    body: &[Token],
+  ) -> Result<Vec<String>, AnyError> {
+    let mut fields = Vec::new();
+    for field in split_top_level(body, ';') {
+      if field.is_empty() {
+        continue;
+      }
+      if field.iter().any(|token| token.is_punct('{')) {
+        bail!("The nested definitions of the struct \"{struct_name}\" are not supported.");
+      }
+      if field.iter().any(|token| token.is_punct(':')) {
+        bail!(
+          "The bit fields of the struct \"{struct_name}\" are not supported."
+        );
+      }
+      let (base, declarators) = self.split_declarators(field)?;
+      for declarator in declarators {
+        let (_, ctype, length) = self.parse_declarator(base, declarator)?;
+        let native_type = ctype.to_native_type(false)?;
+        for _ in 0..length {
+          fields.push(native_type.clone());
+        }
+      }
+    }
+    Ok(fields)
+  }
+
+  /// Parses a definition like `struct name { ... }`, returning its type and
+  /// the tokens after it.
+  fn parse_tag_definition<'a>(
+    &mut self,
+    tokens: &'a [Token],
+    maybe_alias: Option<&str>,
+  ) -> Result<(CType, &'a [Token]), AnyError> {
+    let Some(Token::Ident(kind)) = tokens.first() else {
+      bail!("Missing kind of a definition.");
+    };
+    let (maybe_tag, open_index) = match tokens.get(1) {
+      Some(Token::Ident(tag)) => (Some(tag.as_str()), 2),
+      _ => (None, 1),
+    };
+    let close_index = find_closing(tokens, open_index)
+      .ok_or_else(|| anyhow!("Missing body of the {kind}."))?;
+    let body = &tokens[open_index + 1..close_index];
+    let ctype = match kind.as_str() {
+      "struct" => {
+        let name = maybe_alias
+          .or(maybe_tag)
+          .map(|name| name.to_string())
+          .unwrap_or_else(|| format!("Struct{}", self.structs.len()));
+        // the struct can't contain itself, only pointers to itself
+        if let Some(tag) = maybe_tag {
+          self
+            .tags
+            .insert(format!("struct {tag}"), CType::OpaqueStruct(name.clone()));
+        }
+        let fields = self.parse_fields(&name, body)?;
+        self.structs.push((name.clone(), fields));
+        CType::Struct(name)
+      }
+      "union" => {
+        CType::Union(maybe_alias.or(maybe_tag).unwrap_or("union").to_string())
+      }
+      // the enumerators are ints
+      _ => CType::Native("i32"),
+    };
+    if let Some(tag) = maybe_tag {
+      self.tags.insert(format!("{kind} {tag}"), ctype.clone());
+    }
+    Ok((ctype, &tokens[close_index + 1..]))
+  }
+
+  fn parse_typedef(&mut self, tokens: &[Token]) -> Result<(), AnyError> {
+    let is_tag_definition =
+      tokens.first().is_some_and(|token| {
+        token.is_ident("struct")
+          || token.is_ident("union")
+          || token.is_ident("enum")
+      }) && tokens.iter().any(|token| token.is_punct('{'));
+    if is_tag_definition {
+      let close_index = tokens
+        .iter()
+        .rposition(|token| token.is_punct('}'))
+        .unwrap();
+      // the struct is named after the first alias which isn't a pointer
+      let maybe_alias =
+        tokens[close_index + 1..]
+          .first()
+          .and_then(|token| match token {
+            Token::Ident(alias) => Some(alias.as_str()),
+            _ => None,
+          });
+      let (ctype, rest) = self.parse_tag_definition(tokens, maybe_alias)?;
+      for alias in split_top_level(rest, ',') {
+        let Some((Token::Ident(name), pointers)) = alias.split_last() else {
+          bail!("Missing name of the typedef.");
+        };
+        let ctype = ctype.clone().pointer_to(pointers.len());
+        self.typedefs.insert(name.clone(), ctype);
+      }
+      return Ok(());
+    }
+    let (base, declarators) = self.split_declarators(tokens)?;
+    for declarator in declarators {
+      // a typedef of a function type is used like a pointer to a function
+      let is_function_type = declarator.iter().any(|token| token.is_punct('('))
+        && !declarator.iter().any(|token| token.is_punct('*'));
+      let (name, ctype, length) = if is_function_type {
+        match declarator.first() {
+          Some(Token::Ident(name)) => (name.as_str(), CType::Function, 1),
+          _ => bail!("Missing name of the typedef."),
+        }
+      } else {
+        self.parse_declarator(base, declarator)?
+      };
+      let ctype = if length > 1 {
+        CType::Native("pointer")
+      } else {
+        ctype
+      };
+      self.typedefs.insert(name.to_string(), ctype);
+    }
+    Ok(())
+  }
+
+  fn parse_function(&mut self, tokens: &[Token]) -> Result<(), AnyError> {
+    let open_index =
+      tokens.iter().position(|token| token.is_punct('(')).unwrap();
+    if tokens
+      .get(open_index + 1)
+      .is_some_and(|token| token.is_punct('*'))
+    {
+      // a variable of a pointer to a function
+      return self.parse_static(tokens);
+    }
+    let Some(Token::Ident(name)) = open_index
+      .checked_sub(1)
+      .and_then(|index| tokens.get(index))
+    else {
+      bail!("Missing name of the function.");
+    };
+    let close_index = find_closing(tokens, open_index)
+      .ok_or_else(|| anyhow!("Missing end of the parameters of \"{name}\"."))?;
+    let result = self
+      .parse_type(&tokens[..open_index - 1])
+      .and_then(|ctype| ctype.to_native_type(true))
+      .with_context(|| format!("Invalid result of \"{name}\""))?;
+    let parameter_tokens = &tokens[open_index + 1..close_index];
+    let mut parameters = Vec::new();
+    let is_void =
+      parameter_tokens.len() == 1 && parameter_tokens[0].is_ident("void");
+    if !parameter_tokens.is_empty() && !is_void {
+      for (index, parameter) in split_top_level(parameter_tokens, ',')
+        .into_iter()
+        .enumerate()
+      {
+        if parameter.iter().any(|token| *token == Token::Ellipsis) {
+          bail!(
+            "The function \"{name}\" is variadic, which FFI doesn't support."
+          );
+        }
+        parameters.push(
+          self
+            .parse_parameter(parameter)
+            .and_then(|ctype| ctype.to_native_type(false))
+            .with_context(|| {
+              format!("Invalid parameter {} of \"{name}\"", index + 1)
+            })?,
+        );
+      }
+    }
+    self.symbols.push((
+      name.clone(),
+      format!(
+        "{{ parameters: [{}], result: {result} }}",
+        parameters.join(", ")
+      ),
+    ));
+    Ok(())
+  }
+
+  fn parse_parameter(&self, tokens: &[Token]) -> Result<CType, AnyError> {
+    if tokens.iter().any(|token| token.is_punct('(')) {
+      return Ok(CType::Function);
+    }
+    // arrays are passed as pointers
+    let array_start = tokens.iter().position(|token| token.is_punct('['));
+    let tokens = &tokens[..array_start.unwrap_or(tokens.len())];
+    // leave out the name of the parameter
+    let tokens = match tokens.split_last() {
+      Some((Token::Ident(name), rest))
+        if !rest.is_empty()
+          && !self.is_type_name(name)
+          && !QUALIFIERS.contains(&name.as_str())
+          && !matches!(
+            rest.last(),
+            Some(Token::Ident(kind)) if matches!(kind.as_str(), "struct" | "union" | "enum")
+          ) =>
+      {
+        rest
+      }
+      _ => tokens,
+    };
+    let ctype = self.parse_type(tokens)?;
+    Ok(match array_start {
+      Some(_) => CType::Native("pointer"),
+      None => ctype,
+    })
+  }
+
+  /// Parses the declaration of a variable, like `extern int counter`.
+  fn parse_static(&mut self, tokens: &[Token]) -> Result<(), AnyError> {
+    let (base, declarators) = self.split_declarators(tokens)?;
+    for declarator in declarators {
+      let (name, ctype, length) = self.parse_declarator(base, declarator)?;
+      if length > 1 {
+        bail!("The array \"{name}\" can't be a static of FFI.");
+      }
+      let native_type = match ctype {
+        CType::Native(native) => format!("\"{native}\""),
+        CType::Function => "\"pointer\"".to_string(),
+        _ => bail!("The variable \"{name}\" can't be a static of FFI."),
+      };
+      self
+        .symbols
+        .push((name.to_string(), format!("{{ type: {native_type} }}")));
+    }
+    Ok(())
+  }
+
+  fn parse_declaration(&mut self, tokens: &[Token]) -> Result<(), AnyError> {
+    let tokens = strip_attributes(tokens);
+    let Some(first) = tokens.first() else {
+      return Ok(());
+    };
+    // static declarations aren't exported by the library
+    if first.is_ident("static") {
+      return Ok(());
+    }
+    if first.is_ident("typedef") {
+      return self.parse_typedef(&tokens[1..]);
+    }
+    let is_tag = first.is_ident("struct")
+      || first.is_ident("union")
+      || first.is_ident("enum");
+    if is_tag && tokens.iter().any(|token| token.is_punct('{')) {
+      let (_, rest) = self.parse_tag_definition(&tokens, None)?;
+      if !rest.is_empty() {
+        bail!("Variables of the definitions of structs are not supported.");
+      }
+      return Ok(());
+    }
+    if is_tag && tokens.len() == 2 {
+      // a forward declaration
+      return Ok(());
+    }
+    if tokens.iter().any(|token| token.is_punct('(')) {
+      self.parse_function(&tokens)
+    } else {
+      self.parse_static(&tokens)
+    }
+  }
+}
+
+/// Generates the bindings of the functions, structs and extern variables of
+/// a C header, which are the symbols of `Deno.dlopen()` and an `open()`
+/// function typed by them. The declarations which FFI doesn't support are
+/// skipped with a warning.
+fn generate_bindings(header_name: &str, text: &str) -> String {
+  let mut parser = HeaderParser::default();
+  for declaration in split_declarations(tokenize(text)) {
+    if let Err(err) = parser.parse_declaration(&declaration) {
+      log::warn!(
+        "{} Skipped \"{}\": {:#}",
+        colors::yellow("Warning"),
+        declaration_text(&declaration),
+        err
+      );
+    }
+  }
+
+  let mut bindings = format!(
+    "// Generated by `deno types --ffi {header_name}`. Do not edit.\n\n"
+  );
+  for (name, fields) in &parser.structs {
+    bindings.push_str(&format!(
+      "export const {name} = {{ struct: [{}] }} as const;\n\n",
+      fields.join(", ")
+    ));
+  }
+  bindings.push_str("export const symbols = {\n");
+  for (name, symbol) in &parser.symbols {
+    bindings.push_str(&format!("  {name}: {symbol},\n"));
+  }
+  bindings.push_str(
+    r#"} as const satisfies Deno.ForeignLibraryInterface;
+
+export type Symbols = typeof symbols;
+
+/** Opens the library at the path with the symbols of the header. */
+export function open(path: string | URL): Deno.DynamicLibrary<Symbols> {
+  return Deno.dlopen(path, symbols);
+}
+"#,
+  );
+  bindings
+}
+
+/// Leaves out the differences which formatting the bindings makes.
+fn normalize_bindings(text: &str) -> String {
+  let text = text
+    .chars()
+    .filter(|c| !c.is_whitespace())
+    .collect::<String>();
+  text.replace(",]", "]").replace(",}", "}")
+}
+
+pub fn generate(types_flags: TypesFlags) -> Result<(), AnyError> {
+  let Some(header) = &types_flags.ffi else {
+    bail!("Missing C header of the FFI bindings.");
+  };
+  let text = std::fs::read_to_string(header)
+    .with_context(|| format!("Failed reading \"{header}\""))?;
+  let header_name = Path::new(header)
+    .file_name()
+    .map(|name| name.to_string_lossy().to_string())
+    .unwrap_or_else(|| header.to_string());
+  let bindings = generate_bindings(&header_name, &text);
+  let Some(check) = &types_flags.check else {
+    display::write_to_stdout_ignore_sigpipe(bindings.as_bytes())?;
+    return Ok(());
+  };
+  let current_bindings = std::fs::read_to_string(check)
+    .with_context(|| format!("Failed reading \"{check}\""))?;
+  if normalize_bindings(&current_bindings) != normalize_bindings(&bindings) {
+    log::info!("{}", diff(&current_bindings, &bindings));
+    bail!(
+      "The FFI bindings in \"{check}\" are not the ones generated from \"{header}\". Generate them again with `deno types --ffi {header} > {check}`."
+    );
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  fn parse(text: &str) -> HeaderParser {
+    let mut parser = HeaderParser::default();
+    for declaration in split_declarations(tokenize(text)) {
+      parser.parse_declaration(&declaration).unwrap();
+    }
+    parser
+  }
+
+  fn symbol<'a>(parser: &'a HeaderParser, name: &str) -> &'a str {
+    &parser.symbols.iter().find(|(n, _)| n == name).unwrap().1
+  }
+
+  #[test]
+  fn test_tokenize() {
+    assert_eq!(
+      tokenize("#define MAX \\\n  10\n/* comment */ int a[0x10]; // end\nchar *s = \"a;b\"...;"),
+      vec![
+        Token::Ident("int".to_string()),
+        Token::Ident("a".to_string()),
+        Token::Punct('['),
+        Token::Number("0x10".to_string()),
+        Token::Punct(']'),
+        Token::Punct(';'),
+        Token::Ident("char".to_string()),
+        Token::Punct('*'),
+        Token::Ident("s".to_string()),
+        Token::Punct('='),
+        Token::Str,
+        Token::Ellipsis,
+        Token::Punct(';'),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_split_declarations() {
+    let declarations = split_declarations(tokenize(
+      r#"#ifdef __cplusplus
+extern "C" {
+#endif
+struct point { int x; int y; };
+static inline int twice(int a) { return a * 2; }
+int add(int a, int b);
+#ifdef __cplusplus
+}
+#endif"#,
+    ));
+    assert_eq!(
+      declarations
+        .iter()
+        .map(|declaration| declaration_text(declaration))
+        .collect::<Vec<_>>(),
+      vec![
+        "struct point { int x ; int y ; }",
+        "int add ( int a , int b )",
+      ]
+    );
+  }
+
+  #[test]
+  fn test_functions() {
+    let parser = parse(
+      r#"#include <stdint.h>
+#include <stddef.h>
+void reset(void);
+uint64_t hash(const uint8_t* data, size_t len);
+unsigned long long int big(long long value, unsigned short flags);
+double scale(float factor, double values[]);
+__attribute__((visibility("default"))) _Bool is_ready(const char *name);
+typedef void (*callback_t)(int32_t status);
+void on_done(callback_t callback, void (*on_error)(int code), void *data);
+"#,
+    );
+    assert_eq!(
+      symbol(&parser, "reset"),
+      "{ parameters: [], result: \"void\" }"
+    );
+    assert_eq!(
+      symbol(&parser, "hash"),
+      "{ parameters: [\"pointer\", \"usize\"], result: \"u64\" }"
+    );
+    assert_eq!(
+      symbol(&parser, "big"),
+      "{ parameters: [\"i64\", \"u16\"], result: \"u64\" }"
+    );
+    assert_eq!(
+      symbol(&parser, "scale"),
+      "{ parameters: [\"f32\", \"pointer\"], result: \"f64\" }"
+    );
+    assert_eq!(
+      symbol(&parser, "is_ready"),
+      "{ parameters: [\"pointer\"], result: \"bool\" }"
+    );
+    assert_eq!(
+      symbol(&parser, "on_done"),
+      "{ parameters: [\"function\", \"function\", \"pointer\"], result: \"void\" }"
+    );
+  }
+
+  #[test]
+  fn test_structs() {
+    let parser = parse(
+      r#"typedef struct { int32_t x, y; } Point;
+struct rect { Point origin; Point size; uint8_t flags[2]; struct rect *next; };
+typedef struct handle handle_t;
+typedef enum { RED, GREEN } color_t;
+Point translate(Point point, struct rect rect, color_t color);
+handle_t *open_handle(const char *path);
+"#,
+    );
+    assert_eq!(
+      parser.structs,
+      vec![
+        (
+          "Point".to_string(),
+          vec!["\"i32\"".to_string(), "\"i32\"".to_string()]
+        ),
+        (
+          "rect".to_string(),
+          vec![
+            "Point".to_string(),
+            "Point".to_string(),
+            "\"u8\"".to_string(),
+            "\"u8\"".to_string(),
+            "\"pointer\"".to_string(),
+          ]
+        ),
+      ]
+    );
+    assert_eq!(
+      symbol(&parser, "translate"),
+      "{ parameters: [Point, rect, \"i32\"], result: Point }"
+    );
+    assert_eq!(
+      symbol(&parser, "open_handle"),
+      "{ parameters: [\"pointer\"], result: \"pointer\" }"
+    );
+  }
+
+  #[test]
+  fn test_statics_and_unsupported() {
+    let mut parser = parse("extern const uint32_t version;\n");
+    assert_eq!(symbol(&parser, "version"), "{ type: \"u32\" }");
+    for text in [
+      "int printf(const char *format, ...);",
+      "typedef struct handle handle_t;\nvoid close(handle_t handle);",
+      "void take(my_type_t value);",
+      "struct flags { unsigned a : 1; };",
+      "long double precise(void);",
+    ] {
+      let declarations = split_declarations(tokenize(text));
+      let result = declarations
+        .iter()
+        .try_for_each(|declaration| parser.parse_declaration(declaration));
+      assert!(result.is_err(), "{text}");
+    }
+  }
+
+  #[test]
+  fn test_generate_bindings() {
+    let bindings = generate_bindings(
+      "mylib.h",
+      "typedef struct { double x; double y; } Vec2;\nint32_t add(int32_t a, int32_t b);\nVec2 vec2_add(Vec2 a, Vec2 b);\nint printf(const char *format, ...);\n",
+    );
+    assert_eq!(
+      bindings,
+      r#"// Generated by `deno types --ffi mylib.h`. Do not edit.
+
+export const Vec2 = { struct: ["f64", "f64"] } as const;
+
+export const symbols = {
+  add: { parameters: ["i32", "i32"], result: "i32" },
+  vec2_add: { parameters: [Vec2, Vec2], result: Vec2 },
+} as const satisfies Deno.ForeignLibraryInterface;
+
+export type Symbols = typeof symbols;
+
+/** Opens the library at the path with the symbols of the header. */
+export function open(path: string | URL): Deno.DynamicLibrary<Symbols> {
+  return Deno.dlopen(path, symbols);
+}
+"#
+    );
+    assert_eq!(
+      normalize_bindings(&bindings),
+      normalize_bindings(&bindings.replace(
+        "  add: { parameters: [\"i32\", \"i32\"], result: \"i32\" },",
+        "  add: {\n    parameters: [\n      \"i32\",\n      \"i32\",\n    ],\n    result: \"i32\",\n  },"
+      ))
+    );
+  }
+}
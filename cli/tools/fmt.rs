@@ -13,15 +13,17 @@ use crate::args::FmtFlags;
 use crate::args::FmtOptions;
 use crate::args::FmtOptionsConfig;
 use crate::args::ProseWrap;
+use crate::args::ScopedOptions;
 use crate::colors;
 use crate::factory::CliFactory;
+use crate::tools::organize_imports::organize_imports_in_file;
 use crate::util::diff::diff;
+use crate::util::file_selection::FileSelection;
 use crate::util::file_watcher;
 use crate::util::fs::canonicalize_path;
 use crate::util::fs::FileCollector;
 use crate::util::path::get_extension;
 use deno_ast::ParsedSource;
-use deno_config::glob::FilePatterns;
 use deno_core::anyhow::anyhow;
 use deno_core::anyhow::bail;
 use deno_core::anyhow::Context;
@@ -30,9 +32,12 @@ use deno_core::error::AnyError;
 use deno_core::futures;
 use deno_core::parking_lot::Mutex;
 use deno_core::unsync::spawn_blocking;
+use import_map::ImportMap;
 use log::debug;
 use log::info;
 use log::warn;
+use std::borrow::Cow;
+use std::collections::HashSet;
 use std::fs;
 use std::io::stdin;
 use std::io::stdout;
@@ -52,7 +57,7 @@ pub async fn format(flags: Flags, fmt_flags: FmtFlags) -> Result<(), AnyError> {
     let cli_options = CliOptions::from_flags(flags)?;
     let fmt_options = cli_options.resolve_fmt_options(fmt_flags)?;
     return format_stdin(
-      fmt_options,
+      fmt_options.root,
       cli_options
         .ext_flag()
         .as_ref()
@@ -62,17 +67,23 @@ pub async fn format(flags: Flags, fmt_flags: FmtFlags) -> Result<(), AnyError> {
   }
 
   if let Some(watch_flags) = &fmt_flags.watch {
+    // kept across the restarts, so only these and the changed files are
+    // checked again
+    let not_formatted_files = Arc::new(Mutex::new(HashSet::new()));
     file_watcher::watch_func(
       flags,
       file_watcher::PrintConfig::new("Fmt", !watch_flags.no_clear_screen),
       move |flags, watcher_communicator, changed_paths| {
         let fmt_flags = fmt_flags.clone();
+        let not_formatted_files = not_formatted_files.clone();
         Ok(async move {
           let factory = CliFactory::from_flags(flags).await?;
           let cli_options = factory.cli_options();
+          let file_selection = cli_options
+            .resolve_file_selection(Some("fmt"), &fmt_flags.files.ignore)?;
           let fmt_options = cli_options.resolve_fmt_options(fmt_flags)?;
-          let files =
-            collect_fmt_files(fmt_options.files.clone()).and_then(|files| {
+          let files = collect_fmt_files(&fmt_options, file_selection)
+            .and_then(|files| {
               if files.is_empty() {
                 Err(generic_error("No target files found."))
               } else {
@@ -81,17 +92,9 @@ pub async fn format(flags: Flags, fmt_flags: FmtFlags) -> Result<(), AnyError> {
             })?;
           let _ = watcher_communicator.watch_paths(files.clone());
           let refmt_files = if let Some(paths) = changed_paths {
-            if fmt_options.check {
-              // check all files on any changed (https://github.com/denoland/deno/issues/12446)
-              files
-                .iter()
-                .any(|path| {
-                  canonicalize_path(path)
-                    .map(|path| paths.contains(&path))
-                    .unwrap_or(false)
-                })
-                .then_some(files)
-                .unwrap_or_else(|| [].to_vec())
+            if fmt_options.root.check {
+              let not_formatted = not_formatted_files.lock().clone();
+              file_watcher::files_to_rerun(files, &paths, &not_formatted)
             } else {
               files
                 .into_iter()
@@ -105,7 +108,8 @@ pub async fn format(flags: Flags, fmt_flags: FmtFlags) -> Result<(), AnyError> {
           } else {
             files
           };
-          format_files(factory, fmt_options, refmt_files).await?;
+          format_files(factory, fmt_options, refmt_files, not_formatted_files)
+            .await?;
 
           Ok(())
         })
@@ -115,50 +119,99 @@ pub async fn format(flags: Flags, fmt_flags: FmtFlags) -> Result<(), AnyError> {
   } else {
     let factory = CliFactory::from_flags(flags).await?;
     let cli_options = factory.cli_options();
+    let file_selection = cli_options
+      .resolve_file_selection(Some("fmt"), &fmt_flags.files.ignore)?;
     let fmt_options = cli_options.resolve_fmt_options(fmt_flags)?;
     let files =
-      collect_fmt_files(fmt_options.files.clone()).and_then(|files| {
+      collect_fmt_files(&fmt_options, file_selection).and_then(|files| {
         if files.is_empty() {
           Err(generic_error("No target files found."))
         } else {
           Ok(files)
         }
       })?;
-    format_files(factory, fmt_options, files).await?;
+    format_files(factory, fmt_options, files, Default::default()).await?;
   }
 
   Ok(())
 }
 
+/// Formats the files, or checks them with `--check`, keeping track of the
+/// files which aren't formatted in `not_formatted_files`.
 async fn format_files(
   factory: CliFactory,
-  fmt_options: FmtOptions,
+  fmt_options: ScopedOptions<FmtOptions>,
   paths: Vec<PathBuf>,
+  not_formatted_files: Arc<Mutex<HashSet<PathBuf>>>,
 ) -> Result<(), AnyError> {
   let caches = factory.caches()?;
-  let check = fmt_options.check;
+  let check = fmt_options.root.check;
+  let organize_imports = fmt_options.root.organize_imports;
+  let fmt_options = fmt_options.map(|o| o.options.clone());
   let incremental_cache = Arc::new(IncrementalCache::new(
     caches.fmt_incremental_cache_db(),
-    &fmt_options.options,
+    &(&fmt_options, organize_imports),
     &paths,
   ));
+  let maybe_import_map = if organize_imports {
+    factory.maybe_import_map().await?.clone()
+  } else {
+    None
+  };
+  let fmt_func =
+    move |file_path: &Path, file_text: &str, fmt_options: &FmtOptionsConfig| {
+      if organize_imports {
+        format_file_with_organized_imports(
+          file_path,
+          file_text,
+          fmt_options,
+          maybe_import_map.as_deref(),
+        )
+      } else {
+        format_file(file_path, file_text, fmt_options)
+      }
+    };
   if check {
-    check_source_files(paths, fmt_options.options, incremental_cache.clone())
-      .await?;
+    check_source_files(
+      paths,
+      fmt_options,
+      fmt_func,
+      incremental_cache.clone(),
+      not_formatted_files,
+    )
+    .await?;
   } else {
-    format_source_files(paths, fmt_options.options, incremental_cache.clone())
-      .await?;
+    format_source_files(
+      paths,
+      fmt_options,
+      fmt_func,
+      incremental_cache.clone(),
+    )
+    .await?;
   }
   incremental_cache.wait_completion().await;
   Ok(())
 }
 
-fn collect_fmt_files(files: FilePatterns) -> Result<Vec<PathBuf>, AnyError> {
-  FileCollector::new(|path, _| is_supported_ext_fmt(path))
+fn collect_fmt_files(
+  fmt_options: &ScopedOptions<FmtOptions>,
+  file_selection: FileSelection,
+) -> Result<Vec<PathBuf>, AnyError> {
+  let files = FileCollector::new(|path, _| is_supported_ext_fmt(path))
+    .file_selection(file_selection.clone())
     .ignore_git_folder()
     .ignore_node_modules()
     .ignore_vendor_folder()
-    .collect_file_patterns(files)
+    .collect_file_patterns(fmt_options.root.files.clone())?;
+  // workspace members may exclude files within their own directory
+  Ok(
+    files
+      .into_iter()
+      .filter(|path| {
+        file_selection.matches_path(&fmt_options.for_path(path).files, path)
+      })
+      .collect(),
+  )
 }
 
 /// Formats markdown (using <https://github.com/dprint/dprint-plugin-markdown>) and its code blocks
@@ -231,7 +284,97 @@ pub fn format_json(
   dprint_plugin_json::format_text(file_path, file_text, &config)
 }
 
-/// Formats a single TS, TSX, JS, JSX, JSONC, JSON, MD, or IPYNB file.
+/// Formats CSS and SCSS using <https://github.com/g-plane/malva>.
+pub fn format_css(
+  file_path: &Path,
+  file_text: &str,
+  fmt_options: &FmtOptionsConfig,
+) -> Result<Option<String>, AnyError> {
+  let formatted_text = malva::format_text(
+    file_text,
+    malva::detect_syntax(file_path).unwrap_or(malva::Syntax::Css),
+    &get_resolved_malva_config(fmt_options),
+  )
+  .map_err(|err| generic_error(format!("CSS syntax error: {err}")))?;
+
+  Ok(if formatted_text == file_text {
+    None
+  } else {
+    Some(formatted_text)
+  })
+}
+
+/// Formats HTML using <https://github.com/g-plane/markup_fmt> along with its
+/// embedded `<script>` and `<style>` blocks.
+pub fn format_html(
+  file_path: &Path,
+  file_text: &str,
+  fmt_options: &FmtOptionsConfig,
+) -> Result<Option<String>, AnyError> {
+  let formatted_text = markup_fmt::format_text(
+    file_text,
+    markup_fmt::Language::Html,
+    &get_resolved_markup_fmt_config(fmt_options),
+    |text, hints| {
+      // It's important to tell the formatters the proper file extension,
+      // since it's what determines how the embedded code gets parsed.
+      let fake_filename = file_path.with_extension(hints.ext);
+      match hints.ext {
+        "css" | "scss" => {
+          let mut malva_config = get_resolved_malva_config(fmt_options);
+          malva_config.layout.print_width = hints.print_width;
+          malva::format_text(
+            text,
+            malva::detect_syntax(&fake_filename).unwrap_or(malva::Syntax::Css),
+            &malva_config,
+          )
+          .map(Cow::from)
+          .map_err(AnyError::from)
+        }
+        "json" | "jsonc" => {
+          let mut json_config = get_resolved_json_config(fmt_options);
+          json_config.line_width = hints.print_width as u32;
+          dprint_plugin_json::format_text(&fake_filename, text, &json_config)
+            .map(|formatted| {
+              formatted.map(Cow::from).unwrap_or(Cow::from(text))
+            })
+        }
+        _ => {
+          let mut typescript_config =
+            get_resolved_typescript_config(fmt_options);
+          typescript_config.line_width = hints.print_width as u32;
+          dprint_plugin_typescript::format_text(
+            &fake_filename,
+            text,
+            &typescript_config,
+          )
+          .map(|formatted| formatted.map(Cow::from).unwrap_or(Cow::from(text)))
+        }
+      }
+    },
+  )
+  .map_err(|err| match err {
+    markup_fmt::FormatError::Syntax(err) => {
+      generic_error(format!("HTML syntax error: {err}"))
+    }
+    markup_fmt::FormatError::External(errors) => generic_error(
+      errors
+        .into_iter()
+        .map(|err| format!("{err:#}"))
+        .collect::<Vec<_>>()
+        .join("\n\n"),
+    ),
+  })?;
+
+  Ok(if formatted_text == file_text {
+    None
+  } else {
+    Some(formatted_text)
+  })
+}
+
+/// Formats a single TS, TSX, JS, JSX, JSONC, JSON, MD, IPYNB, CSS, SCSS or
+/// HTML file.
 pub fn format_file(
   file_path: &Path,
   file_text: &str,
@@ -244,6 +387,8 @@ pub fn format_file(
       format_markdown(file_text, fmt_options)
     }
     "json" | "jsonc" => format_json(file_path, file_text, fmt_options),
+    "css" | "scss" => format_css(file_path, file_text, fmt_options),
+    "html" => format_html(file_path, file_text, fmt_options),
     "ipynb" => dprint_plugin_jupyter::format_text(
       file_text,
       |file_path: &Path, file_text: String| {
@@ -257,6 +402,20 @@ pub fn format_file(
   }
 }
 
+/// Organizes the imports of a JS/TS file before formatting it.
+fn format_file_with_organized_imports(
+  file_path: &Path,
+  file_text: &str,
+  fmt_options: &FmtOptionsConfig,
+  maybe_import_map: Option<&ImportMap>,
+) -> Result<Option<String>, AnyError> {
+  let maybe_organized_text =
+    organize_imports_in_file(file_path, file_text, maybe_import_map)?;
+  let file_text = maybe_organized_text.as_deref().unwrap_or(file_text);
+  let maybe_formatted_text = format_file(file_path, file_text, fmt_options)?;
+  Ok(maybe_formatted_text.or(maybe_organized_text))
+}
+
 pub fn format_parsed_source(
   parsed_source: &ParsedSource,
   fmt_options: &FmtOptionsConfig,
@@ -267,11 +426,19 @@ pub fn format_parsed_source(
   )
 }
 
-async fn check_source_files(
+async fn check_source_files<F>(
   paths: Vec<PathBuf>,
-  fmt_options: FmtOptionsConfig,
+  fmt_options: ScopedOptions<FmtOptionsConfig>,
+  fmt_func: F,
   incremental_cache: Arc<IncrementalCache>,
-) -> Result<(), AnyError> {
+  not_formatted_files: Arc<Mutex<HashSet<PathBuf>>>,
+) -> Result<(), AnyError>
+where
+  F: Fn(&Path, &str, &FmtOptionsConfig) -> Result<Option<String>, AnyError>
+    + Send
+    + Clone
+    + 'static,
+{
   let not_formatted_files_count = Arc::new(AtomicUsize::new(0));
   let checked_files_count = Arc::new(AtomicUsize::new(0));
 
@@ -287,12 +454,15 @@ async fn check_source_files(
 
       // skip checking the file if we know it's formatted
       if incremental_cache.is_file_same(&file_path, &file_text) {
+        not_formatted_files.lock().remove(&file_path);
         return Ok(());
       }
 
-      match format_file(&file_path, &file_text, &fmt_options) {
+      let fmt_options = fmt_options.for_path(&file_path);
+      match fmt_func(&file_path, &file_text, fmt_options) {
         Ok(Some(formatted_text)) => {
           not_formatted_files_count.fetch_add(1, Ordering::Relaxed);
+          not_formatted_files.lock().insert(file_path.clone());
           let _g = output_lock.lock();
           let diff = diff(&file_text, &formatted_text);
           info!("");
@@ -306,9 +476,11 @@ async fn check_source_files(
           // so that CIs that cache the DENO_DIR will get the benefit of
           // incremental formatting
           incremental_cache.update_file(&file_path, &file_text);
+          not_formatted_files.lock().remove(&file_path);
         }
         Err(e) => {
           not_formatted_files_count.fetch_add(1, Ordering::Relaxed);
+          not_formatted_files.lock().insert(file_path.clone());
           let _g = output_lock.lock();
           warn!("Error checking: {}", file_path.to_string_lossy());
           warn!(
@@ -348,11 +520,18 @@ async fn check_source_files(
   }
 }
 
-async fn format_source_files(
+async fn format_source_files<F>(
   paths: Vec<PathBuf>,
-  fmt_options: FmtOptionsConfig,
+  fmt_options: ScopedOptions<FmtOptionsConfig>,
+  fmt_func: F,
   incremental_cache: Arc<IncrementalCache>,
-) -> Result<(), AnyError> {
+) -> Result<(), AnyError>
+where
+  F: Fn(&Path, &str, &FmtOptionsConfig) -> Result<Option<String>, AnyError>
+    + Send
+    + Clone
+    + 'static,
+{
   let formatted_files_count = Arc::new(AtomicUsize::new(0));
   let checked_files_count = Arc::new(AtomicUsize::new(0));
   let output_lock = Arc::new(Mutex::new(0)); // prevent threads outputting at the same time
@@ -372,8 +551,8 @@ async fn format_source_files(
       match format_ensure_stable(
         &file_path,
         &file_contents.text,
-        &fmt_options,
-        format_file,
+        fmt_options.for_path(&file_path),
+        &fmt_func,
       ) {
         Ok(Some(formatted_text)) => {
           incremental_cache.update_file(&file_path, &formatted_text);
@@ -598,6 +777,52 @@ fn get_resolved_json_config(
   builder.build()
 }
 
+fn get_resolved_malva_config(
+  options: &FmtOptionsConfig,
+) -> malva::config::FormatOptions {
+  use malva::config::*;
+
+  FormatOptions {
+    layout: LayoutOptions {
+      print_width: options.line_width.unwrap_or(80) as usize,
+      use_tabs: options.use_tabs.unwrap_or_default(),
+      indent_width: options.indent_width.unwrap_or(2) as usize,
+      line_break: LineBreak::Lf,
+    },
+    language: LanguageOptions {
+      quotes: if let Some(true) = options.single_quote {
+        Quotes::PreferSingle
+      } else {
+        Quotes::PreferDouble
+      },
+      ignore_comment_directive: "deno-fmt-ignore".into(),
+      ..Default::default()
+    },
+  }
+}
+
+fn get_resolved_markup_fmt_config(
+  options: &FmtOptionsConfig,
+) -> markup_fmt::config::FormatOptions {
+  use markup_fmt::config::*;
+
+  FormatOptions {
+    layout: LayoutOptions {
+      print_width: options.line_width.unwrap_or(80) as usize,
+      use_tabs: options.use_tabs.unwrap_or_default(),
+      indent_width: options.indent_width.unwrap_or(2) as usize,
+      line_break: LineBreak::Lf,
+    },
+    language: LanguageOptions {
+      quotes: Quotes::Double,
+      script_indent: true,
+      style_indent: true,
+      ignore_comment_directive: "deno-fmt-ignore".into(),
+      ..Default::default()
+    },
+  }
+}
+
 struct FileContents {
   text: String,
   had_bom: bool,
@@ -697,6 +922,9 @@ fn is_supported_ext_fmt(path: &Path) -> bool {
         | "mdown"
         | "markdown"
         | "ipynb"
+        | "css"
+        | "scss"
+        | "html"
     )
   })
 }
@@ -731,6 +959,10 @@ mod test {
     assert!(is_supported_ext_fmt(Path::new("foo.json")));
     assert!(is_supported_ext_fmt(Path::new("foo.JsON")));
     assert!(is_supported_ext_fmt(Path::new("foo.ipynb")));
+    assert!(is_supported_ext_fmt(Path::new("foo.css")));
+    assert!(is_supported_ext_fmt(Path::new("foo.SCSS")));
+    assert!(is_supported_ext_fmt(Path::new("foo.html")));
+    assert!(!is_supported_ext_fmt(Path::new("foo.htmlx")));
   }
 
   #[test]
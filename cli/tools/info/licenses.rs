@@ -0,0 +1,316 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::BTreeMap;
+
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::ModuleSpecifier;
+use deno_graph::ModuleGraph;
+use deno_runtime::permissions::PermissionsContainer;
+use deno_terminal::colors;
+use serde::Serialize;
+
+use crate::args::deno_registry_url;
+use crate::display;
+use crate::file_fetcher::FileFetcher;
+use crate::npm::CliNpmResolver;
+
+/// The license of a dependency of the module graph.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyLicense {
+  /// The name of the package, like "npm:chalk" or "jsr:@std/path", or the
+  /// URL of the directory of remote modules.
+  pub name: String,
+  pub version: Option<String>,
+  /// The SPDX expression of the license, when it's known.
+  pub license: Option<String>,
+  /// Remote modules don't have metadata with a license, so they're out of
+  /// scope of the allowlist and always allowed.
+  pub remote: bool,
+  pub allowed: bool,
+}
+
+/// Get the license of the "license" field of a package.json or a jsr config
+/// file, including the deprecated object and "licenses" forms.
+fn license_from_manifest(manifest: &serde_json::Value) -> Option<String> {
+  fn license_type(value: &serde_json::Value) -> Option<String> {
+    match value {
+      serde_json::Value::String(license) => Some(license.to_string()),
+      serde_json::Value::Object(license) => {
+        Some(license.get("type")?.as_str()?.to_string())
+      }
+      _ => None,
+    }
+  }
+
+  if let Some(license) = manifest.get("license").and_then(license_type) {
+    return Some(license);
+  }
+  let licenses = manifest
+    .get("licenses")?
+    .as_array()?
+    .iter()
+    .filter_map(license_type)
+    .collect::<Vec<_>>();
+  if licenses.is_empty() {
+    None
+  } else {
+    Some(licenses.join(" OR "))
+  }
+}
+
+/// Check a license against the allowlist of the configuration file. Every
+/// identifier of one of the alternatives of an "OR" expression needs to be
+/// allowed, and an unknown license is only allowed by "UNKNOWN".
+fn is_license_allowed(license: Option<&str>, allow: &[String]) -> bool {
+  let is_allowed = |id: &str| allow.iter().any(|a| a.eq_ignore_ascii_case(id));
+  let Some(license) = license else {
+    return is_allowed("UNKNOWN");
+  };
+  let license = license.replace(['(', ')'], " ");
+  license.split(" OR ").any(|alternative| {
+    alternative
+      .split(" AND ")
+      .map(|id| id.trim())
+      .all(|id| !id.is_empty() && is_allowed(id))
+  })
+}
+
+/// The directory of a remote module up to the segment with a version, like
+/// "https://deno.land/std@0.200.0/", which is what remote modules are grouped
+/// by.
+fn remote_module_dir(specifier: &ModuleSpecifier) -> String {
+  let path = specifier.path();
+  let dir = match path.find('@') {
+    Some(index) => match path[index..].find('/') {
+      Some(end) => &path[..index + end + 1],
+      None => path,
+    },
+    None => path,
+  };
+  format!("{}{}", specifier.origin().ascii_serialization(), dir)
+}
+
+async fn jsr_package_license(
+  file_fetcher: &FileFetcher,
+  name: &str,
+  version: &str,
+) -> Option<String> {
+  async fn fetch_json(
+    file_fetcher: &FileFetcher,
+    path: &str,
+  ) -> Option<serde_json::Value> {
+    let specifier = deno_registry_url().join(path).ok()?;
+    let file = file_fetcher
+      .fetch(&specifier, PermissionsContainer::allow_all())
+      .await
+      .ok()?;
+    let text = String::from_utf8(file.source.to_vec()).ok()?;
+    jsonc_parser::parse_to_serde_value(&text, &Default::default()).ok()?
+  }
+
+  let version_info =
+    fetch_json(file_fetcher, &format!("{}/{}_meta.json", name, version))
+      .await?;
+  let manifest = version_info.get("manifest")?.as_object()?;
+  // only fetch the config files which were published
+  for file_name in ["/jsr.json", "/deno.json", "/deno.jsonc"] {
+    if !manifest.contains_key(file_name) {
+      continue;
+    }
+    let config =
+      fetch_json(file_fetcher, &format!("{}/{}{}", name, version, file_name))
+        .await;
+    if let Some(license) = config.as_ref().and_then(license_from_manifest) {
+      return Some(license);
+    }
+  }
+  None
+}
+
+/// Collect the licenses of the npm and jsr packages and the remote modules
+/// of the module graph, sorted by name. Only the package licenses are checked
+/// against the allowlist.
+pub async fn collect_licenses(
+  graph: &ModuleGraph,
+  npm_resolver: &dyn CliNpmResolver,
+  file_fetcher: &FileFetcher,
+  maybe_allow: Option<&[String]>,
+) -> Result<Vec<DependencyLicense>, AnyError> {
+  let mut licenses = BTreeMap::new();
+
+  if let Some(npm_resolver) = npm_resolver.as_managed() {
+    npm_resolver.cache_packages().await?;
+    let snapshot = npm_resolver.snapshot();
+    for package in snapshot.all_packages_for_every_system() {
+      let name = format!("npm:{}", package.id.nv.name);
+      let version = package.id.nv.version.to_string();
+      if licenses.contains_key(&(name.clone(), Some(version.clone()))) {
+        continue; // another copy of the package for different peer deps
+      }
+      let license = npm_resolver
+        .resolve_pkg_folder_from_pkg_id(&package.id)
+        .ok()
+        .and_then(|folder| {
+          std::fs::read_to_string(folder.join("package.json")).ok()
+        })
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .and_then(|manifest| license_from_manifest(&manifest));
+      licenses.insert((name, Some(version)), license);
+    }
+  }
+
+  for nv in graph.packages.mappings().values() {
+    let key = (format!("jsr:{}", nv.name), Some(nv.version.to_string()));
+    if licenses.contains_key(&key) {
+      continue;
+    }
+    let license =
+      jsr_package_license(file_fetcher, &nv.name, &nv.version.to_string())
+        .await;
+    licenses.insert(key, license);
+  }
+
+  let jsr_url = deno_registry_url().as_str();
+  for module in graph.modules() {
+    let specifier = module.specifier();
+    if !matches!(specifier.scheme(), "http" | "https")
+      || specifier.as_str().starts_with(jsr_url)
+    {
+      continue;
+    }
+    licenses
+      .entry((remote_module_dir(specifier), None))
+      .or_insert(None);
+  }
+
+  Ok(
+    licenses
+      .into_iter()
+      .map(|((name, version), license)| {
+        // only the packages have a version
+        let remote = version.is_none();
+        DependencyLicense {
+          allowed: remote
+            || maybe_allow
+              .map(|allow| is_license_allowed(license.as_deref(), allow))
+              .unwrap_or(true),
+          name,
+          version,
+          license,
+          remote,
+        }
+      })
+      .collect(),
+  )
+}
+
+/// Output the licenses as a table or JSON, erroring when there are licenses
+/// which the allowlist of the configuration file doesn't allow.
+pub fn print_licenses(
+  licenses: &[DependencyLicense],
+  json: bool,
+) -> Result<(), AnyError> {
+  if json {
+    display::write_json_to_stdout(&json!({ "dependencies": licenses }))?;
+  } else {
+    let names = licenses
+      .iter()
+      .map(|l| match &l.version {
+        Some(version) => format!("{}@{}", l.name, version),
+        None => l.name.clone(),
+      })
+      .collect::<Vec<_>>();
+    let width = names.iter().map(|n| n.len()).max().unwrap_or(0);
+    let mut output = String::new();
+    for (name, license) in names.iter().zip(licenses) {
+      let license_text = license.license.as_deref().unwrap_or("unknown");
+      let license_text = if license.remote {
+        colors::gray(format!("{} (remote module, not checked)", license_text))
+          .to_string()
+      } else if !license.allowed {
+        colors::red(format!("{} (not allowed)", license_text)).to_string()
+      } else if license.license.is_none() {
+        colors::gray(license_text).to_string()
+      } else {
+        license_text.to_string()
+      };
+      output.push_str(&format!("{:width$}  {}\n", name, license_text));
+    }
+    display::write_to_stdout_ignore_sigpipe(output.as_bytes())?;
+  }
+
+  let disallowed = licenses.iter().filter(|l| !l.allowed).count();
+  if disallowed > 0 {
+    bail!(
+      "The licenses of {} {} aren't allowed by the \"licenses\" of the configuration file.",
+      disallowed,
+      if disallowed == 1 {
+        "dependency"
+      } else {
+        "dependencies"
+      }
+    );
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_license_from_manifest() {
+    assert_eq!(
+      license_from_manifest(&json!({ "license": "MIT" })),
+      Some("MIT".to_string())
+    );
+    assert_eq!(
+      license_from_manifest(&json!({ "license": { "type": "ISC" } })),
+      Some("ISC".to_string())
+    );
+    assert_eq!(
+      license_from_manifest(&json!({
+        "licenses": [{ "type": "MIT" }, { "type": "Apache-2.0" }]
+      })),
+      Some("MIT OR Apache-2.0".to_string())
+    );
+    assert_eq!(license_from_manifest(&json!({ "name": "a" })), None);
+  }
+
+  #[test]
+  fn test_is_license_allowed() {
+    let allow = vec!["MIT".to_string(), "Apache-2.0".to_string()];
+    assert!(is_license_allowed(Some("MIT"), &allow));
+    assert!(is_license_allowed(Some("mit"), &allow));
+    assert!(is_license_allowed(Some("(MIT OR GPL-3.0)"), &allow));
+    assert!(is_license_allowed(Some("MIT AND Apache-2.0"), &allow));
+    assert!(!is_license_allowed(Some("MIT AND GPL-3.0"), &allow));
+    assert!(!is_license_allowed(Some("GPL-3.0"), &allow));
+    assert!(!is_license_allowed(None, &allow));
+    assert!(is_license_allowed(None, &["UNKNOWN".to_string()]));
+  }
+
+  #[test]
+  fn test_remote_module_dir() {
+    let dir = |specifier: &str| {
+      remote_module_dir(&ModuleSpecifier::parse(specifier).unwrap())
+    };
+    assert_eq!(
+      dir("https://deno.land/std@0.200.0/path/mod.ts"),
+      "https://deno.land/std@0.200.0/"
+    );
+    assert_eq!(
+      dir("https://esm.sh/preact@10.19.0"),
+      "https://esm.sh/preact@10.19.0"
+    );
+    assert_eq!(
+      dir("http://localhost:4545/mod.ts"),
+      "http://localhost:4545/mod.ts"
+    );
+  }
+}
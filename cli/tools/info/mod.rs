@@ -1,12 +1,15 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fmt;
 use std::fmt::Write;
+use std::path::Path;
 
 use deno_ast::ModuleSpecifier;
 use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
 use deno_core::resolve_url_or_path;
 use deno_core::serde_json;
@@ -24,6 +27,7 @@ use deno_semver::npm::NpmPackageNvReference;
 use deno_semver::npm::NpmPackageReqReference;
 use deno_semver::package::PackageNv;
 use deno_terminal::colors;
+use serde::Serialize;
 
 use crate::args::Flags;
 use crate::args::InfoFlags;
@@ -34,6 +38,8 @@ use crate::npm::CliNpmResolver;
 use crate::npm::ManagedCliNpmResolver;
 use crate::util::checksum;
 
+mod licenses;
+
 pub async fn info(flags: Flags, info_flags: InfoFlags) -> Result<(), AnyError> {
   let factory = CliFactory::from_flags(flags).await?;
   let cli_options = factory.cli_options();
@@ -70,7 +76,33 @@ pub async fn info(flags: Flags, info_flags: InfoFlags) -> Result<(), AnyError> {
       graph_lock_or_exit(&graph, &mut lockfile.lock());
     }
 
-    if info_flags.json {
+    if info_flags.licenses {
+      let maybe_allow = cli_options.resolve_license_allowlist()?;
+      let licenses = licenses::collect_licenses(
+        &graph,
+        npm_resolver.as_ref(),
+        factory.file_fetcher()?,
+        maybe_allow.as_deref(),
+      )
+      .await?;
+      licenses::print_licenses(&licenses, info_flags.json)?;
+    } else if let Some(old_graph_path) = &info_flags.diff {
+      let old_graph = GraphDependencies::load(Path::new(old_graph_path))?;
+      let mut json_graph = json!(graph);
+      add_npm_packages_to_json(&mut json_graph, npm_resolver.as_ref());
+      let new_graph = GraphDependencies::from_json(&json_graph)?;
+      let diff = GraphDiff::new(&old_graph, &new_graph);
+      if info_flags.json {
+        display::write_json_to_stdout(&diff)?;
+      } else {
+        let mut output = String::new();
+        diff.write(&mut output)?;
+        display::write_to_stdout_ignore_sigpipe(output.as_bytes())?;
+      }
+    } else if info_flags.dot {
+      let output = DependencyGraphView::from_module_graph(&graph).to_dot();
+      display::write_to_stdout_ignore_sigpipe(output.as_bytes())?;
+    } else if info_flags.json {
       let mut json_graph = json!(graph);
       add_npm_packages_to_json(&mut json_graph, npm_resolver.as_ref());
       display::write_json_to_stdout(&json_graph)?;
@@ -263,6 +295,293 @@ fn add_npm_packages_to_json(
   json.insert("npmPackages".to_string(), json_packages.into());
 }
 
+/// A dependency of a module graph, which is a module or a package.
+#[derive(Debug, Clone, PartialEq)]
+struct GraphDependency {
+  version: Option<String>,
+  size: Option<u64>,
+}
+
+/// The dependencies of a module graph in the format of `deno info --json`,
+/// by name. Modules of jsr and npm packages are grouped under names like
+/// "jsr:@std/path" and "npm:chalk". Local modules are named by their path
+/// relative to the directory of the first root, so a graph which was saved
+/// in another checkout still matches.
+#[derive(Debug, Default)]
+struct GraphDependencies {
+  dependencies: BTreeMap<String, GraphDependency>,
+}
+
+impl GraphDependencies {
+  fn load(path: &Path) -> Result<Self, AnyError> {
+    let text = std::fs::read_to_string(path).with_context(|| {
+      format!("Failed to read module graph \"{}\"", path.display())
+    })?;
+    serde_json::from_str(&text)
+      .map_err(AnyError::from)
+      .and_then(|json| Self::from_json(&json))
+      .with_context(|| format!("Invalid module graph \"{}\"", path.display()))
+  }
+
+  fn from_json(json: &serde_json::Value) -> Result<Self, AnyError> {
+    let Some(modules) = json.get("modules").and_then(|m| m.as_array()) else {
+      bail!("Expected the output of 'deno info --json <file>'");
+    };
+    let root_dir = json
+      .get("roots")
+      .and_then(|r| r.get(0))
+      .and_then(|r| r.as_str())
+      .and_then(|r| ModuleSpecifier::parse(r).ok())
+      .filter(|r| r.scheme() == "file")
+      .and_then(|r| r.join("./").ok());
+    let mut dependencies = BTreeMap::new();
+
+    // the modules of jsr packages are found by the "<name>/<version>/" in
+    // their specifier
+    let mut jsr_packages = json
+      .get("packages")
+      .and_then(|p| p.as_object())
+      .map(|packages| {
+        packages
+          .values()
+          .filter_map(|nv| nv.as_str()?.rsplit_once('@'))
+          .filter(|(name, _)| !name.is_empty())
+          .map(|(name, version)| {
+            (format!("/{}/{}/", name, version), (name, version, 0))
+          })
+          .collect::<BTreeMap<_, _>>()
+      })
+      .unwrap_or_default();
+    for module in modules {
+      let Some(specifier) = module.get("specifier").and_then(|s| s.as_str())
+      else {
+        continue;
+      };
+      let Some(size) = module.get("size").and_then(|s| s.as_u64()) else {
+        continue;
+      };
+      if let Some((_, (_, _, package_size))) = jsr_packages
+        .iter_mut()
+        .find(|(prefix, _)| specifier.contains(prefix.as_str()))
+      {
+        *package_size += size;
+        continue;
+      }
+      let name = root_dir
+        .as_ref()
+        .filter(|_| specifier.starts_with("file:"))
+        .and_then(|root_dir| {
+          let specifier = ModuleSpecifier::parse(specifier).ok()?;
+          root_dir.make_relative(&specifier)
+        })
+        .map(|path| {
+          if path.starts_with("../") {
+            path
+          } else {
+            format!("./{}", path)
+          }
+        })
+        .unwrap_or_else(|| specifier.to_string());
+      dependencies.insert(
+        name,
+        GraphDependency {
+          version: None,
+          size: Some(size),
+        },
+      );
+    }
+    for (name, version, size) in jsr_packages.into_values() {
+      dependencies.insert(
+        format!("jsr:{}", name),
+        GraphDependency {
+          version: Some(version.to_string()),
+          size: Some(size),
+        },
+      );
+    }
+
+    // there might be several versions of an npm package in the graph
+    let mut npm_versions: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    if let Some(packages) = json.get("npmPackages").and_then(|p| p.as_object())
+    {
+      for package in packages.values() {
+        let name = package.get("name").and_then(|n| n.as_str());
+        let version = package.get("version").and_then(|v| v.as_str());
+        if let (Some(name), Some(version)) = (name, version) {
+          npm_versions
+            .entry(format!("npm:{}", name))
+            .or_default()
+            .push(version);
+        }
+      }
+    }
+    for (name, mut versions) in npm_versions {
+      versions.sort();
+      versions.dedup();
+      dependencies.insert(
+        name,
+        GraphDependency {
+          version: Some(versions.join(", ")),
+          size: None,
+        },
+      );
+    }
+    Ok(Self { dependencies })
+  }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphDependencyChange {
+  name: String,
+  old_version: Option<String>,
+  new_version: Option<String>,
+  /// The difference in bytes, when the sizes of both sides are known.
+  size_delta: Option<i64>,
+}
+
+/// The changes of the dependencies of a module graph compared to a saved
+/// one, which is what `deno info --diff` reports.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphDiff {
+  added: Vec<GraphDependencyChange>,
+  removed: Vec<GraphDependencyChange>,
+  updated: Vec<GraphDependencyChange>,
+  size_delta: i64,
+}
+
+impl GraphDiff {
+  fn new(old: &GraphDependencies, new: &GraphDependencies) -> Self {
+    fn change(
+      name: &str,
+      old: Option<&GraphDependency>,
+      new: Option<&GraphDependency>,
+    ) -> GraphDependencyChange {
+      let old_size = old.map(|d| d.size);
+      let new_size = new.map(|d| d.size);
+      let size_delta = match (old_size, new_size) {
+        (Some(Some(old)), Some(Some(new))) => Some(new as i64 - old as i64),
+        (None, Some(Some(new))) => Some(new as i64),
+        (Some(Some(old)), None) => Some(-(old as i64)),
+        _ => None,
+      };
+      GraphDependencyChange {
+        name: name.to_string(),
+        old_version: old.and_then(|d| d.version.clone()),
+        new_version: new.and_then(|d| d.version.clone()),
+        size_delta,
+      }
+    }
+
+    let mut diff = GraphDiff {
+      added: Vec::new(),
+      removed: Vec::new(),
+      updated: Vec::new(),
+      size_delta: 0,
+    };
+    for (name, new_dep) in &new.dependencies {
+      match old.dependencies.get(name) {
+        None => diff.added.push(change(name, None, Some(new_dep))),
+        Some(old_dep) if old_dep != new_dep => {
+          diff
+            .updated
+            .push(change(name, Some(old_dep), Some(new_dep)))
+        }
+        Some(_) => {}
+      }
+    }
+    for (name, old_dep) in &old.dependencies {
+      if !new.dependencies.contains_key(name) {
+        diff.removed.push(change(name, Some(old_dep), None));
+      }
+    }
+    diff.size_delta = diff
+      .added
+      .iter()
+      .chain(&diff.removed)
+      .chain(&diff.updated)
+      .filter_map(|c| c.size_delta)
+      .sum();
+    diff
+  }
+
+  fn write<TWrite: Write>(&self, writer: &mut TWrite) -> fmt::Result {
+    fn size_delta_to_text(size_delta: i64) -> String {
+      let text = display::human_size(size_delta as f64);
+      if size_delta > 0 {
+        format!("+{}", text)
+      } else {
+        text
+      }
+    }
+
+    fn write_changes<TWrite: Write>(
+      writer: &mut TWrite,
+      title: &str,
+      marker: &str,
+      changes: &[GraphDependencyChange],
+    ) -> fmt::Result {
+      if changes.is_empty() {
+        return Ok(());
+      }
+      writeln!(writer, "{} ({}):", colors::bold(title), changes.len())?;
+      for change in changes {
+        write!(writer, "  {} {}", marker, change.name)?;
+        match (&change.old_version, &change.new_version) {
+          (Some(old), Some(new)) if old != new => {
+            write!(writer, " {} -> {}", old, new)?
+          }
+          (Some(version), _) | (_, Some(version)) => {
+            write!(writer, " {}", version)?
+          }
+          (None, None) => {}
+        }
+        if let Some(size_delta) = change.size_delta {
+          write!(
+            writer,
+            " {}",
+            colors::gray(format!("({})", size_delta_to_text(size_delta)))
+          )?;
+        }
+        writeln!(writer)?;
+      }
+      Ok(())
+    }
+
+    if self.added.is_empty()
+      && self.removed.is_empty()
+      && self.updated.is_empty()
+    {
+      return writeln!(writer, "No dependency changes");
+    }
+    write_changes(
+      writer,
+      "Added",
+      &colors::green("+").to_string(),
+      &self.added,
+    )?;
+    write_changes(
+      writer,
+      "Removed",
+      &colors::red("-").to_string(),
+      &self.removed,
+    )?;
+    write_changes(
+      writer,
+      "Updated",
+      &colors::yellow("~").to_string(),
+      &self.updated,
+    )?;
+    writeln!(
+      writer,
+      "{} {}",
+      colors::bold("Size change:"),
+      size_delta_to_text(self.size_delta)
+    )
+  }
+}
+
 struct TreeNode {
   text: String,
   children: Vec<TreeNode>,
@@ -727,3 +1046,140 @@ fn maybe_size_to_text(maybe_size: Option<u64>) -> String {
   ))
   .to_string()
 }
+
+/// How a module is imported, for dependency graph visualizations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DependencyEdgeKind {
+  Static,
+  Dynamic,
+  TypeOnly,
+}
+
+impl DependencyEdgeKind {
+  /// The resolved specifiers of a dependency along with how they are
+  /// imported. Types of a dependency are always imported as types only.
+  pub fn from_dependency(
+    dependency: &Dependency,
+  ) -> Vec<(&ModuleSpecifier, Self)> {
+    let mut edges = Vec::with_capacity(2);
+    if let Some(specifier) = dependency.maybe_code.maybe_specifier() {
+      let kind = if dependency.is_dynamic {
+        Self::Dynamic
+      } else {
+        Self::Static
+      };
+      edges.push((specifier, kind));
+    }
+    if let Some(specifier) = dependency.maybe_type.maybe_specifier() {
+      edges.push((specifier, Self::TypeOnly));
+    }
+    edges
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyGraphNode {
+  pub specifier: String,
+  pub size: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyGraphEdge {
+  pub from: String,
+  pub to: String,
+  pub kind: DependencyEdgeKind,
+}
+
+/// A flat representation of a module graph which is used to render import
+/// graphs, either by `deno info --format=dot` or by editors.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyGraphView {
+  pub roots: Vec<String>,
+  pub nodes: Vec<DependencyGraphNode>,
+  pub edges: Vec<DependencyGraphEdge>,
+}
+
+impl DependencyGraphView {
+  pub fn from_module_graph(graph: &ModuleGraph) -> Self {
+    let mut view = Self {
+      roots: graph
+        .roots
+        .iter()
+        .map(|s| graph.resolve(s).to_string())
+        .collect(),
+      ..Default::default()
+    };
+    for module in graph.modules() {
+      let size = match module {
+        Module::Js(module) => Some(module.size() as u64),
+        Module::Json(module) => Some(module.size() as u64),
+        Module::Node(_) | Module::Npm(_) | Module::External(_) => None,
+      };
+      view.nodes.push(DependencyGraphNode {
+        specifier: module.specifier().to_string(),
+        size,
+      });
+      let Some(module) = module.js() else {
+        continue;
+      };
+      for dependency in module.dependencies.values() {
+        for (specifier, kind) in DependencyEdgeKind::from_dependency(dependency)
+        {
+          view.edges.push(DependencyGraphEdge {
+            from: module.specifier.to_string(),
+            to: graph.resolve(specifier).to_string(),
+            kind,
+          });
+        }
+      }
+    }
+    view
+  }
+
+  /// Render the graph in the Graphviz DOT language.
+  pub fn to_dot(&self) -> String {
+    fn quote(text: &str) -> String {
+      format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+
+    let mut output = String::from("digraph {\n");
+    for node in &self.nodes {
+      let label = match node.size {
+        Some(size) => {
+          format!("{}\n{}", node.specifier, display::human_size(size as f64))
+        }
+        None => node.specifier.clone(),
+      };
+      let shape = if self.roots.contains(&node.specifier) {
+        ", shape=box"
+      } else {
+        ""
+      };
+      output.push_str(&format!(
+        "  {} [label={}{}];\n",
+        quote(&node.specifier),
+        quote(&label).replace('\n', "\\n"),
+        shape
+      ));
+    }
+    for edge in &self.edges {
+      let style = match edge.kind {
+        DependencyEdgeKind::Static => "",
+        DependencyEdgeKind::Dynamic => " [style=dashed]",
+        DependencyEdgeKind::TypeOnly => " [style=dotted]",
+      };
+      output.push_str(&format!(
+        "  {} -> {}{};\n",
+        quote(&edge.from),
+        quote(&edge.to),
+        style
+      ));
+    }
+    output.push_str("}\n");
+    output
+  }
+}
@@ -1,14 +1,129 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use crate::args::deno_registry_url;
+use crate::args::Flags;
 use crate::args::InitFlags;
 use crate::colors;
 use crate::deno_std;
+use crate::factory::CliFactory;
+use crate::file_fetcher::FileFetcher;
+use deno_core::anyhow::bail;
 use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::ModuleSpecifier;
+use deno_runtime::permissions::PermissionsContainer;
+use deno_semver::jsr::JsrPackageReqReference;
+use deno_semver::Version;
 use log::info;
+use std::io::IsTerminal;
 use std::io::Write;
+use std::path::Component;
 use std::path::Path;
 
+/// A template built into the binary, with the commands to get started which
+/// are shown after initializing the project.
+struct Template {
+  name: &'static str,
+  files: &'static [(&'static str, &'static str)],
+  commands: &'static [(&'static str, &'static str)],
+}
+
+static TEMPLATES: [Template; 5] = [
+  Template {
+    name: "default",
+    files: &[
+      ("main.ts", include_str!("./templates/main.ts")),
+      ("main_test.ts", include_str!("./templates/main_test.ts")),
+      ("deno.json", include_str!("./templates/deno.json")),
+    ],
+    commands: &[
+      ("Run the program", "deno run main.ts"),
+      (
+        "Run the program and watch for file changes",
+        "deno task dev",
+      ),
+      ("Run the tests", "deno test"),
+    ],
+  },
+  Template {
+    name: "server",
+    files: &[
+      ("main.ts", include_str!("./templates/server/main.ts")),
+      (
+        "main_test.ts",
+        include_str!("./templates/server/main_test.ts"),
+      ),
+      ("deno.json", include_str!("./templates/server/deno.json")),
+    ],
+    commands: &[
+      ("Start the server", "deno task start"),
+      (
+        "Start the server and watch for file changes",
+        "deno task dev",
+      ),
+      ("Run the tests", "deno test"),
+    ],
+  },
+  Template {
+    name: "cli",
+    files: &[
+      ("main.ts", include_str!("./templates/cli/main.ts")),
+      ("main_test.ts", include_str!("./templates/cli/main_test.ts")),
+      ("deno.json", include_str!("./templates/cli/deno.json")),
+    ],
+    commands: &[
+      ("Run the program", "deno run main.ts --name Deno"),
+      (
+        "Run the program and watch for file changes",
+        "deno task dev",
+      ),
+      ("Run the tests", "deno test"),
+    ],
+  },
+  Template {
+    name: "library",
+    files: &[
+      ("mod.ts", include_str!("./templates/library/mod.ts")),
+      (
+        "mod_test.ts",
+        include_str!("./templates/library/mod_test.ts"),
+      ),
+      ("deno.json", include_str!("./templates/library/deno.json")),
+    ],
+    commands: &[
+      ("Run the tests and watch for file changes", "deno task dev"),
+      (
+        "Check the package before publishing",
+        "deno publish --dry-run",
+      ),
+    ],
+  },
+  Template {
+    name: "worker",
+    files: &[
+      ("main.ts", include_str!("./templates/worker/main.ts")),
+      ("worker.ts", include_str!("./templates/worker/worker.ts")),
+      ("fib.ts", include_str!("./templates/worker/fib.ts")),
+      (
+        "fib_test.ts",
+        include_str!("./templates/worker/fib_test.ts"),
+      ),
+      ("deno.json", include_str!("./templates/worker/deno.json")),
+    ],
+    commands: &[
+      ("Run the program", "deno run --allow-read main.ts"),
+      (
+        "Run the program and watch for file changes",
+        "deno task dev",
+      ),
+      ("Run the tests", "deno test"),
+    ],
+  },
+];
+
+const CI_WORKFLOW_PATH: &str = ".github/workflows/ci.yml";
+
 fn create_file(
   dir: &Path,
   filename: &str,
@@ -22,6 +137,9 @@ fn create_file(
     );
     Ok(())
   } else {
+    if let Some(parent) = path.parent() {
+      std::fs::create_dir_all(parent)?;
+    }
     let mut file = std::fs::OpenOptions::new()
       .write(true)
       .create_new(true)
@@ -32,7 +150,155 @@ fn create_file(
   }
 }
 
-pub async fn init_project(init_flags: InitFlags) -> Result<(), AnyError> {
+/// Asks a question on stderr and reads the answer from stdin, which is the
+/// default when the answer is empty.
+fn prompt(question: &str, default: &str) -> Result<String, AnyError> {
+  eprint!("{} {} ", question, colors::gray(format!("({default})")));
+  std::io::stderr().flush()?;
+  let mut answer = String::new();
+  std::io::stdin().read_line(&mut answer)?;
+  let answer = answer.trim();
+  if answer.is_empty() {
+    Ok(default.to_string())
+  } else {
+    Ok(answer.to_string())
+  }
+}
+
+/// Gets the name of the package of the library template from the name of the
+/// directory, which can only contain lowercase letters, digits and dashes.
+fn package_name_for_dir(dir: &Path) -> String {
+  let name = dir
+    .file_name()
+    .map(|name| name.to_string_lossy().to_lowercase())
+    .unwrap_or_default()
+    .chars()
+    .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+    .collect::<String>();
+  let name = name.trim_matches('-');
+  if name.is_empty() {
+    "project".to_string()
+  } else {
+    name.to_string()
+  }
+}
+
+/// Checks that a path of a remote template stays in the directory of the
+/// project.
+fn is_valid_template_path(path: &str) -> bool {
+  !path.is_empty()
+    && Path::new(path)
+      .components()
+      .all(|component| matches!(component, Component::Normal(_)))
+}
+
+async fn fetch_text(
+  file_fetcher: &FileFetcher,
+  specifier: &ModuleSpecifier,
+) -> Result<String, AnyError> {
+  let file = file_fetcher
+    .fetch(specifier, PermissionsContainer::allow_all())
+    .await
+    .with_context(|| format!("Failed fetching {specifier}"))?;
+  String::from_utf8(file.source.to_vec())
+    .with_context(|| format!("{specifier} is not valid UTF-8"))
+}
+
+async fn fetch_json(
+  file_fetcher: &FileFetcher,
+  specifier: &ModuleSpecifier,
+) -> Result<serde_json::Value, AnyError> {
+  let text = fetch_text(file_fetcher, specifier).await?;
+  serde_json::from_str(&text)
+    .with_context(|| format!("{specifier} is not valid JSON"))
+}
+
+/// Fetches the files of a community template, which is either a `jsr:`
+/// package, whose published files are the files of the template, or a URL of
+/// a JSON file with a "files" object that maps the paths of the files to
+/// their contents.
+async fn fetch_remote_template(
+  file_fetcher: &FileFetcher,
+  template: &str,
+) -> Result<Vec<(String, String)>, AnyError> {
+  let mut files = Vec::new();
+  if let Ok(req_ref) = JsrPackageReqReference::from_str(template) {
+    let req = req_ref.req();
+    let registry_url = deno_registry_url();
+    let meta_url = registry_url.join(&format!("{}/meta.json", req.name))?;
+    let meta = fetch_json(file_fetcher, &meta_url).await?;
+    let Some(version) = meta
+      .get("versions")
+      .and_then(|versions| versions.as_object())
+      .into_iter()
+      .flatten()
+      .filter(|(_, info)| {
+        !info
+          .get("yanked")
+          .and_then(|y| y.as_bool())
+          .unwrap_or(false)
+      })
+      .filter_map(|(version, _)| Version::parse_standard(version).ok())
+      .filter(|version| req.version_req.matches(version))
+      .max()
+    else {
+      bail!("Couldn't find a version of the template {}", req);
+    };
+    let version_meta_url =
+      registry_url.join(&format!("{}/{}_meta.json", req.name, version))?;
+    let version_meta = fetch_json(file_fetcher, &version_meta_url).await?;
+    let Some(manifest) =
+      version_meta.get("manifest").and_then(|m| m.as_object())
+    else {
+      bail!("The template {}@{} has no files.", req.name, version);
+    };
+    for path in manifest.keys() {
+      let file_url =
+        registry_url.join(&format!("{}/{}{}", req.name, version, path))?;
+      let text = fetch_text(file_fetcher, &file_url).await?;
+      files.push((path.trim_start_matches('/').to_string(), text));
+    }
+  } else if template.starts_with("https:") || template.starts_with("http:") {
+    let template_url = ModuleSpecifier::parse(template)?;
+    let manifest = fetch_json(file_fetcher, &template_url).await?;
+    let Some(manifest_files) =
+      manifest.get("files").and_then(|files| files.as_object())
+    else {
+      bail!("The template at {} has no \"files\" object.", template_url);
+    };
+    for (path, content) in manifest_files {
+      let Some(content) = content.as_str() else {
+        bail!(
+          "The content of {} in {} is not a string.",
+          path,
+          template_url
+        );
+      };
+      files.push((path.clone(), content.to_string()));
+    }
+  } else {
+    bail!(
+      "Unknown template '{}'. Use one of {}, or a jsr: or https: URL.",
+      template,
+      TEMPLATES
+        .iter()
+        .map(|template| template.name)
+        .collect::<Vec<_>>()
+        .join(", ")
+    );
+  }
+  for (path, _) in &files {
+    if !is_valid_template_path(path) {
+      bail!("The template has an invalid path '{}'.", path);
+    }
+  }
+  Ok(files)
+}
+
+pub async fn init_project(
+  flags: Flags,
+  init_flags: InitFlags,
+) -> Result<(), AnyError> {
   let cwd =
     std::env::current_dir().context("Can't read current working directory.")?;
   let dir = if let Some(dir) = &init_flags.dir {
@@ -43,15 +309,56 @@ pub async fn init_project(init_flags: InitFlags) -> Result<(), AnyError> {
     cwd
   };
 
-  let main_ts = include_str!("./templates/main.ts");
-  create_file(&dir, "main.ts", main_ts)?;
+  let is_interactive = !init_flags.yes && std::io::stdin().is_terminal();
+  let template_name = match init_flags.template {
+    Some(template) => template,
+    None if is_interactive => prompt(
+      &format!(
+        "Which template do you want to use? {}",
+        TEMPLATES
+          .iter()
+          .map(|template| template.name)
+          .collect::<Vec<_>>()
+          .join(", ")
+      ),
+      "default",
+    )?,
+    None => "default".to_string(),
+  };
+  let add_ci = init_flags.ci
+    || (is_interactive
+      && prompt("Do you want to add a GitHub Actions workflow? [y/N]", "n")?
+        .eq_ignore_ascii_case("y"));
 
-  let main_test_ts = include_str!("./templates/main_test.ts")
-    .replace("{CURRENT_STD_URL}", deno_std::CURRENT_STD_URL_STR);
-  create_file(&dir, "main_test.ts", &main_test_ts)?;
-  create_file(&dir, "deno.json", include_str!("./templates/deno.json"))?;
+  let maybe_template = TEMPLATES.iter().find(|t| t.name == template_name);
+  match maybe_template {
+    Some(template) => {
+      let package_name = package_name_for_dir(&dir);
+      for (filename, content) in template.files {
+        let content = content
+          .replace("{CURRENT_STD_URL}", deno_std::CURRENT_STD_URL_STR)
+          .replace("{NAME}", &package_name);
+        create_file(&dir, filename, &content)?;
+      }
+    }
+    None => {
+      let factory = CliFactory::from_flags(flags).await?;
+      let files =
+        fetch_remote_template(factory.file_fetcher()?, &template_name).await?;
+      for (filename, content) in files {
+        create_file(&dir, &filename, &content)?;
+      }
+    }
+  }
+  if add_ci {
+    create_file(&dir, CI_WORKFLOW_PATH, include_str!("./templates/ci.yml"))?;
+  }
 
   info!("✅ {}", colors::green("Project initialized"));
+  let commands = maybe_template.map(|t| t.commands).unwrap_or_default();
+  if init_flags.dir.is_none() && commands.is_empty() {
+    return Ok(());
+  }
   info!("");
   info!("{}", colors::gray("Run these commands to get started"));
   info!("");
@@ -59,16 +366,34 @@ pub async fn init_project(init_flags: InitFlags) -> Result<(), AnyError> {
     info!("  cd {}", dir);
     info!("");
   }
-  info!("  {}", colors::gray("# Run the program"));
-  info!("  deno run main.ts");
-  info!("");
-  info!(
-    "  {}",
-    colors::gray("# Run the program and watch for file changes")
-  );
-  info!("  deno task dev");
-  info!("");
-  info!("  {}", colors::gray("# Run the tests"));
-  info!("  deno test");
+  for (index, (description, command)) in commands.iter().enumerate() {
+    if index > 0 {
+      info!("");
+    }
+    info!("  {}", colors::gray(format!("# {description}")));
+    info!("  {}", command);
+  }
   Ok(())
 }
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_package_name_for_dir() {
+    assert_eq!(package_name_for_dir(Path::new("/home/My_Lib")), "my-lib");
+    assert_eq!(package_name_for_dir(Path::new("/home/_lib_")), "lib");
+    assert_eq!(package_name_for_dir(Path::new("/")), "project");
+  }
+
+  #[test]
+  fn test_is_valid_template_path() {
+    assert!(is_valid_template_path("main.ts"));
+    assert!(is_valid_template_path(".github/workflows/ci.yml"));
+    assert!(!is_valid_template_path(""));
+    assert!(!is_valid_template_path("../main.ts"));
+    assert!(!is_valid_template_path("/etc/passwd"));
+    assert!(!is_valid_template_path("src/../../main.ts"));
+  }
+}
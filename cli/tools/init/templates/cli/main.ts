@@ -0,0 +1,18 @@
+import { parseArgs } from "$std/cli/parse_args.ts";
+
+export function greet(name: string): string {
+  return `Hello, ${name}!`;
+}
+
+if (import.meta.main) {
+  const args = parseArgs(Deno.args, {
+    string: ["name"],
+    boolean: ["help"],
+    alias: { h: "help", n: "name" },
+  });
+  if (args.help) {
+    console.log("Usage: main.ts [--name <name>]");
+    Deno.exit(0);
+  }
+  console.log(greet(args.name ?? "world"));
+}
@@ -0,0 +1,6 @@
+import { assertEquals } from "$std/assert/mod.ts";
+import { greet } from "./main.ts";
+
+Deno.test(function greetTest() {
+  assertEquals(greet("Deno"), "Hello, Deno!");
+});
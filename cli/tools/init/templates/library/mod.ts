@@ -0,0 +1,11 @@
+/**
+ * A short description of the library, which is shown on the page of the
+ * package.
+ *
+ * @module
+ */
+
+/** Adds two numbers. */
+export function add(a: number, b: number): number {
+  return a + b;
+}
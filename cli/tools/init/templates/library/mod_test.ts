@@ -0,0 +1,6 @@
+import { assertEquals } from "$std/assert/mod.ts";
+import { add } from "./mod.ts";
+
+Deno.test(function addTest() {
+  assertEquals(add(2, 3), 5);
+});
@@ -0,0 +1,11 @@
+export function handler(req: Request): Response {
+  const url = new URL(req.url);
+  if (url.pathname === "/") {
+    return new Response("Hello, world!");
+  }
+  return new Response("Not found", { status: 404 });
+}
+
+if (import.meta.main) {
+  Deno.serve({ port: 8000 }, handler);
+}
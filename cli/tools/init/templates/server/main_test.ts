@@ -0,0 +1,14 @@
+import { assertEquals } from "$std/assert/mod.ts";
+import { handler } from "./main.ts";
+
+Deno.test(async function helloTest() {
+  const res = handler(new Request("http://localhost:8000/"));
+  assertEquals(res.status, 200);
+  assertEquals(await res.text(), "Hello, world!");
+});
+
+Deno.test(async function notFoundTest() {
+  const res = handler(new Request("http://localhost:8000/missing"));
+  assertEquals(res.status, 404);
+  await res.body?.cancel();
+});
@@ -0,0 +1,6 @@
+import { assertEquals } from "$std/assert/mod.ts";
+import { fib } from "./fib.ts";
+
+Deno.test(function fibTest() {
+  assertEquals(fib(10), 55);
+});
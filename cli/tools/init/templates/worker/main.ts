@@ -0,0 +1,9 @@
+const worker = new Worker(new URL("./worker.ts", import.meta.url).href, {
+  type: "module",
+});
+
+worker.onmessage = (e: MessageEvent<number>) => {
+  console.log("fib(10) =", e.data);
+  worker.terminate();
+};
+worker.postMessage(10);
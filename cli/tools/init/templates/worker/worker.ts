@@ -0,0 +1,8 @@
+/// <reference no-default-lib="true" />
+/// <reference lib="deno.worker" />
+
+import { fib } from "./fib.ts";
+
+self.onmessage = (e: MessageEvent<number>) => {
+  self.postMessage(fib(e.data));
+};
@@ -402,6 +402,10 @@ async fn resolve_shim_data(
     executable_args.push("--cached-only".to_string());
   }
 
+  if flags.offline {
+    executable_args.push("--offline".to_string());
+  }
+
   if resolve_no_prompt(flags) {
     executable_args.push("--no-prompt".to_string());
   }
@@ -168,6 +168,10 @@ impl JupyterMessage {
     self.content["cursor_pos"].as_u64().unwrap_or(0) as usize
   }
 
+  pub(crate) fn content(&self) -> &serde_json::Value {
+    &self.content
+  }
+
   pub(crate) fn comm_id(&self) -> &str {
     self.content["comm_id"].as_str().unwrap_or("")
   }
@@ -18,6 +18,7 @@ use deno_core::CancelFuture;
 use deno_core::CancelHandle;
 use tokio::sync::mpsc;
 use tokio::sync::Mutex;
+use tower_lsp::lsp_types::CompletionItemKind;
 use zeromq::SocketRecv;
 use zeromq::SocketSend;
 
@@ -30,9 +31,58 @@ pub enum StdioMsg {
   Stderr(String),
 }
 
+/// The comm targets of the frontend which are accepted. Other comms opened by
+/// the frontend are closed right away.
+const ACCEPTED_COMM_TARGETS: &[&str] = &[
+  "jupyter.widget",
+  "jupyter.widget.control",
+  VARIABLES_COMM_TARGET,
+];
+
+/// The comm target of the variable explorer, which replies to a
+/// `{ "method": "request_variables" }` message with the variables of the
+/// global scope as `{ "method": "variables", "variables": [...] }`.
+const VARIABLES_COMM_TARGET: &str = "deno.variables";
+
+/// The target names of the open comms by their ids, which are opened either
+/// by the frontend or by the kernel with `Deno.jupyter.openComm()`.
+#[derive(Default)]
+pub struct CommTargets(HashMap<String, String>);
+
+impl CommTargets {
+  pub fn insert(&mut self, comm_id: String, target_name: String) {
+    self.0.insert(comm_id, target_name);
+  }
+
+  pub fn remove(&mut self, comm_id: &str) {
+    self.0.remove(comm_id);
+  }
+
+  pub fn target_name(&self, comm_id: &str) -> Option<&str> {
+    self.0.get(comm_id).map(|target_name| target_name.as_str())
+  }
+
+  /// Gets the content of a "comm_info_reply", which only has the comms of the
+  /// target when there's one.
+  pub fn info(&self, maybe_target_name: Option<&str>) -> serde_json::Value {
+    let comms = self
+      .0
+      .iter()
+      .filter(|(_, target_name)| {
+        maybe_target_name.map(|t| t == *target_name).unwrap_or(true)
+      })
+      .map(|(comm_id, target_name)| {
+        (comm_id.clone(), json!({ "target_name": target_name }))
+      })
+      .collect::<serde_json::Map<_, _>>();
+    json!({ "status": "ok", "comms": comms })
+  }
+}
+
 pub struct JupyterServer {
   execution_count: usize,
   last_execution_request: Rc<RefCell<Option<JupyterMessage>>>,
+  comm_targets: Rc<RefCell<CommTargets>>,
   // This is Arc<Mutex<>>, so we don't hold RefCell borrows across await
   // points.
   iopub_socket: Arc<Mutex<Connection<zeromq::PubSocket>>>,
@@ -57,6 +107,7 @@ impl JupyterServer {
       bind_socket::<zeromq::PubSocket>(&spec, spec.iopub_port).await?;
     let iopub_socket = Arc::new(Mutex::new(iopub_socket));
     let last_execution_request = Rc::new(RefCell::new(None));
+    let comm_targets = Rc::new(RefCell::new(CommTargets::default()));
 
     // Store `iopub_socket` in the op state so it's accessible to the runtime API.
     {
@@ -64,6 +115,7 @@ impl JupyterServer {
       let mut op_state = op_state_rc.borrow_mut();
       op_state.put(iopub_socket.clone());
       op_state.put(last_execution_request.clone());
+      op_state.put(comm_targets.clone());
     }
 
     let cancel_handle = CancelHandle::new_rc();
@@ -73,6 +125,7 @@ impl JupyterServer {
       execution_count: 0,
       iopub_socket: iopub_socket.clone(),
       last_execution_request: last_execution_request.clone(),
+      comm_targets,
       repl_session,
     };
 
@@ -228,9 +281,82 @@ impl JupyterServer {
           .await?;
       }
       "comm_open" => {
+        let target_name = msg.content()["target_name"].as_str().unwrap_or("");
+        if ACCEPTED_COMM_TARGETS.contains(&target_name) {
+          self
+            .comm_targets
+            .borrow_mut()
+            .insert(msg.comm_id().to_string(), target_name.to_string());
+        } else {
+          msg
+            .comm_close_message()
+            .send(&mut *self.iopub_socket.lock().await)
+            .await?;
+        }
+      }
+      "comm_msg"
+        if self.comm_targets.borrow().target_name(msg.comm_id())
+          == Some(VARIABLES_COMM_TARGET) =>
+      {
+        if msg.content()["data"]["method"] == "request_variables" {
+          let variables = get_variables(&mut self.repl_session).await;
+          msg
+            .new_message("comm_msg")
+            .with_content(json!({
+              "comm_id": msg.comm_id(),
+              "data": { "method": "variables", "variables": variables },
+            }))
+            .send(&mut *self.iopub_socket.lock().await)
+            .await?;
+        }
+      }
+      "comm_msg" => {
+        // the messages sent by the handlers of the comm are children of the
+        // comm message, the same as ipykernel
+        *self.last_execution_request.borrow_mut() = Some(msg.clone());
+        let args = [
+          json!(msg.comm_id()),
+          msg.content().get("data").cloned().unwrap_or(json!({})),
+        ];
+        call_jupyter_internal(
+          &mut self.repl_session,
+          "handleCommMessage",
+          &args,
+        )
+        .await?;
+      }
+      "comm_close" => {
+        self.comm_targets.borrow_mut().remove(msg.comm_id());
+        call_jupyter_internal(
+          &mut self.repl_session,
+          "handleCommClose",
+          &[json!(msg.comm_id())],
+        )
+        .await?;
+      }
+      "comm_info_request" => {
+        let content = self
+          .comm_targets
+          .borrow()
+          .info(msg.content()["target_name"].as_str());
         msg
-          .comm_close_message()
-          .send(&mut *self.iopub_socket.lock().await)
+          .new_reply()
+          .with_content(content)
+          .send(connection)
+          .await?;
+      }
+      "inspect_request" => {
+        let content = inspect(
+          &mut self.repl_session,
+          msg.code(),
+          msg.cursor_pos(),
+          msg.content()["detail_level"].as_u64().unwrap_or(0),
+        )
+        .await;
+        msg
+          .new_reply()
+          .with_content(content)
+          .send(connection)
           .await?;
       }
       "complete_request" => {
@@ -259,6 +385,19 @@ impl JupyterServer {
             .map(|item| item.range.end)
             .unwrap_or(cursor_pos);
 
+          // the types of the completions shown by JupyterLab
+          let types = lsp_completions
+            .iter()
+            .map(|item| {
+              json!({
+                "start": item.range.start,
+                "end": item.range.end,
+                "text": item.new_text,
+                "type": item.kind.map(completion_kind_name).unwrap_or("<unknown>"),
+              })
+            })
+            .collect::<Vec<_>>();
+
           msg
             .new_reply()
             .with_content(json!({
@@ -266,7 +405,7 @@ impl JupyterServer {
               "matches": matches,
               "cursor_start": cursor_start,
               "cursor_end": cursor_end,
-              "metadata": {},
+              "metadata": { "_jupyter_types_experimental": types },
             }))
             .send(connection)
             .await?;
@@ -320,7 +459,7 @@ impl JupyterServer {
             .await?;
         }
       }
-      "comm_msg" | "comm_info_request" | "history_request" => {
+      "history_request" => {
         // We don't handle these messages
       }
       _ => {
@@ -388,6 +527,12 @@ impl JupyterServer {
     } = evaluate_response.value;
 
     if exception_details.is_none() {
+      // make the declarations of the cell known to the completions of tsc
+      self
+        .repl_session
+        .language_server
+        .commit_text(&evaluate_response.ts_code)
+        .await;
       publish_result(&mut self.repl_session, &result, self.execution_count)
         .await?;
 
@@ -570,6 +715,146 @@ async fn publish_result(
   Ok(None)
 }
 
+/// Gets the name of the kind of a completion, which are the types of the
+/// completions of ipykernel.
+fn completion_kind_name(kind: CompletionItemKind) -> &'static str {
+  match kind {
+    CompletionItemKind::CLASS | CompletionItemKind::INTERFACE => "class",
+    CompletionItemKind::FUNCTION | CompletionItemKind::CONSTRUCTOR => {
+      "function"
+    }
+    CompletionItemKind::METHOD => "method",
+    CompletionItemKind::FIELD | CompletionItemKind::PROPERTY => "property",
+    CompletionItemKind::KEYWORD => "keyword",
+    CompletionItemKind::MODULE => "module",
+    CompletionItemKind::CONSTANT | CompletionItemKind::VARIABLE => "instance",
+    CompletionItemKind::ENUM | CompletionItemKind::ENUM_MEMBER => "enum",
+    CompletionItemKind::FILE | CompletionItemKind::FOLDER => "path",
+    _ => "<unknown>",
+  }
+}
+
+/// Gets the content of an "inspect_reply", which has the hover of tsc for the
+/// expression at the cursor and the inspected value of the expression when
+/// it can be evaluated without side effects. With a detail level above 0,
+/// the value is inspected completely instead of only its first line.
+async fn inspect(
+  session: &mut repl::ReplSession,
+  code: &str,
+  cursor_pos: usize,
+  detail_level: u64,
+) -> serde_json::Value {
+  let cursor_pos = cursor_pos.min(code.len());
+  let expr = get_expr_from_line_at_pos(code, cursor_pos);
+  let maybe_hover = session
+    .language_server
+    .hover(code, cursor_pos)
+    .await
+    .filter(|hover| !hover.is_empty());
+  let mut maybe_value = None;
+  if !expr.is_empty() {
+    if let Some(response) = evaluate_expression(session, expr).await {
+      maybe_value = session.get_eval_value(&response.result).await.ok();
+    }
+  }
+  let maybe_value = maybe_value.map(|value| {
+    if detail_level > 0 {
+      value
+    } else {
+      value.lines().next().unwrap_or_default().to_string()
+    }
+  });
+  if maybe_hover.is_none() && maybe_value.is_none() {
+    return json!({ "status": "ok", "found": false, "data": {}, "metadata": {} });
+  }
+  let plain = [maybe_hover.clone(), maybe_value.clone()]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join("\n\n");
+  let mut markdown = maybe_hover.unwrap_or_default();
+  if let Some(value) = maybe_value {
+    let value = console_static_text::ansi::strip_ansi_codes(&value);
+    markdown.push_str(&format!("\n\n```\n{value}\n```"));
+  }
+  json!({
+    "status": "ok",
+    "found": true,
+    "data": {
+      "text/plain": plain,
+      "text/markdown": markdown.trim_start(),
+    },
+    "metadata": {},
+  })
+}
+
+/// Gets the variables of the global scope for the variable explorer, which
+/// are summarized the same way as the previews of the inspector.
+async fn get_variables(
+  session: &mut repl::ReplSession,
+) -> Vec<serde_json::Value> {
+  let mut names = get_global_lexical_scope_names(session).await;
+  names.retain(|name| name != &*repl::REPL_INTERNALS_NAME);
+  names.sort();
+  let mut variables = Vec::with_capacity(names.len());
+  for name in names {
+    // uninitialized bindings, like in the temporal dead zone, fail to evaluate
+    let Some(response) = evaluate_expression(session, &name).await else {
+      continue;
+    };
+    let result = response.result;
+    let summary = result
+      .description
+      .clone()
+      .or_else(|| result.value.as_ref().map(|value| value.to_string()))
+      .unwrap_or_else(|| result.kind.clone());
+    variables.push(json!({
+      "name": name,
+      "type": result.class_name.clone().or(result.subtype.clone()).unwrap_or(result.kind),
+      "summary": summary,
+    }));
+  }
+  variables
+}
+
+/// Calls a function of `Deno[Deno.internal].jupyter` with JSON arguments,
+/// like the handlers of the comm messages.
+async fn call_jupyter_internal(
+  session: &mut repl::ReplSession,
+  name: &str,
+  args: &[serde_json::Value],
+) -> Result<(), AnyError> {
+  let arguments = args
+    .iter()
+    .map(|value| cdp::CallArgument {
+      value: Some(value.clone()),
+      unserializable_value: None,
+      object_id: None,
+    })
+    .collect::<Vec<_>>();
+  let response = session
+    .post_message_with_event_loop(
+      "Runtime.callFunctionOn",
+      Some(json!({
+        "functionDeclaration": format!(
+          "async function (...args) {{ await Deno[Deno.internal].jupyter.{name}(...args); }}"
+        ),
+        "arguments": arguments,
+        "executionContextId": session.context_id,
+        "awaitPromise": true,
+      })),
+    )
+    .await?;
+  let response: cdp::CallFunctionOnResponse = serde_json::from_value(response)?;
+  if let Some(exception_details) = &response.exception_details {
+    eprintln!(
+      "Exception encountered in {name}: {}",
+      exception_details.text
+    );
+  }
+  Ok(())
+}
+
 // TODO(bartlomieju): dedup with repl::editor
 fn get_expr_from_line_at_pos(line: &str, cursor_pos: usize) -> &str {
   let start = line[..cursor_pos].rfind(is_word_boundary).unwrap_or(0);
@@ -719,3 +1004,50 @@ async fn evaluate_expression(
     Some(evaluate_response)
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn comm_targets_info() {
+    let mut comm_targets = CommTargets::default();
+    comm_targets.insert("a".to_string(), "jupyter.widget".to_string());
+    comm_targets.insert("b".to_string(), "jupyter.widget.control".to_string());
+    comm_targets.insert("c".to_string(), "jupyter.widget".to_string());
+    comm_targets.remove("c");
+    assert_eq!(
+      comm_targets.info(Some("jupyter.widget")),
+      json!({
+        "status": "ok",
+        "comms": { "a": { "target_name": "jupyter.widget" } },
+      })
+    );
+    assert_eq!(
+      comm_targets.info(None),
+      json!({
+        "status": "ok",
+        "comms": {
+          "a": { "target_name": "jupyter.widget" },
+          "b": { "target_name": "jupyter.widget.control" },
+        },
+      })
+    );
+    assert_eq!(
+      comm_targets.target_name("b"),
+      Some("jupyter.widget.control")
+    );
+    assert_eq!(comm_targets.target_name("c"), None);
+  }
+
+  #[test]
+  fn test_completion_kind_name() {
+    assert_eq!(completion_kind_name(CompletionItemKind::METHOD), "method");
+    assert_eq!(completion_kind_name(CompletionItemKind::INTERFACE), "class");
+    assert_eq!(
+      completion_kind_name(CompletionItemKind::VARIABLE),
+      "instance"
+    );
+    assert_eq!(completion_kind_name(CompletionItemKind::TEXT), "<unknown>");
+  }
+}
@@ -7,11 +7,12 @@ use crate::args::LintFlags;
 use crate::args::LintOptions;
 use crate::args::LintReporterKind;
 use crate::args::LintRulesConfig;
+use crate::args::ScopedOptions;
 use crate::colors;
 use crate::factory::CliFactory;
 use crate::tools::fmt::run_parallelized;
+use crate::util::file_selection::FileSelection;
 use crate::util::file_watcher;
-use crate::util::fs::canonicalize_path;
 use crate::util::fs::specifier_from_file_path;
 use crate::util::fs::FileCollector;
 use crate::util::path::is_script_ext;
@@ -19,7 +20,6 @@ use crate::util::sync::AtomicFlag;
 use deno_ast::diagnostics::Diagnostic;
 use deno_ast::MediaType;
 use deno_ast::ParsedSource;
-use deno_config::glob::FilePatterns;
 use deno_core::anyhow::bail;
 use deno_core::error::generic_error;
 use deno_core::error::AnyError;
@@ -33,6 +33,7 @@ use deno_lint::rules::LintRule;
 use log::debug;
 use log::info;
 use serde::Serialize;
+use std::collections::HashSet;
 use std::fs;
 use std::io::stdin;
 use std::io::Read;
@@ -60,42 +61,40 @@ pub async fn lint(flags: Flags, lint_flags: LintFlags) -> Result<(), AnyError> {
         "Lint watch on standard input is not supported.",
       ));
     }
+    // kept across the restarts, so only these and the changed files are
+    // linted again
+    let files_with_problems = Arc::new(Mutex::new(HashSet::new()));
     file_watcher::watch_func(
       flags,
       file_watcher::PrintConfig::new("Lint", !watch_flags.no_clear_screen),
       move |flags, watcher_communicator, changed_paths| {
         let lint_flags = lint_flags.clone();
+        let files_with_problems = files_with_problems.clone();
         Ok(async move {
           let factory = CliFactory::from_flags(flags).await?;
           let cli_options = factory.cli_options();
+          let file_selection = cli_options
+            .resolve_file_selection(Some("lint"), &lint_flags.files.ignore)?;
           let lint_options = cli_options.resolve_lint_options(lint_flags)?;
-          let files = collect_lint_files(lint_options.files.clone()).and_then(
-            |files| {
+          let files = collect_lint_files(&lint_options, file_selection)
+            .and_then(|files| {
               if files.is_empty() {
                 Err(generic_error("No target files found."))
               } else {
                 Ok(files)
               }
-            },
-          )?;
+            })?;
           _ = watcher_communicator.watch_paths(files.clone());
 
           let lint_paths = if let Some(paths) = changed_paths {
-            // lint all files on any changed (https://github.com/denoland/deno/issues/12446)
-            files
-              .iter()
-              .any(|path| {
-                canonicalize_path(path)
-                  .map(|p| paths.contains(&p))
-                  .unwrap_or(false)
-              })
-              .then_some(files)
-              .unwrap_or_else(|| [].to_vec())
+            let problems = files_with_problems.lock().unwrap().clone();
+            file_watcher::files_to_rerun(files, &paths, &problems)
           } else {
             files
           };
 
-          lint_files(factory, lint_options, lint_paths).await?;
+          lint_files(factory, lint_options, lint_paths, files_with_problems)
+            .await?;
           Ok(())
         })
       },
@@ -105,9 +104,11 @@ pub async fn lint(flags: Flags, lint_flags: LintFlags) -> Result<(), AnyError> {
     let factory = CliFactory::from_flags(flags).await?;
     let cli_options = factory.cli_options();
     let is_stdin = lint_flags.is_stdin();
+    let file_selection = cli_options
+      .resolve_file_selection(Some("lint"), &lint_flags.files.ignore)?;
     let lint_options = cli_options.resolve_lint_options(lint_flags)?;
-    let files = &lint_options.files;
     let success = if is_stdin {
+      let lint_options = lint_options.root;
       let reporter_kind = lint_options.reporter_kind;
       let reporter_lock = Arc::new(Mutex::new(create_reporter(reporter_kind)));
       let lint_rules = get_config_rules_err_empty(lint_options.rules)?;
@@ -121,8 +122,8 @@ pub async fn lint(flags: Flags, lint_flags: LintFlags) -> Result<(), AnyError> {
       reporter_lock.lock().unwrap().close(1);
       success
     } else {
-      let target_files =
-        collect_lint_files(files.clone()).and_then(|files| {
+      let target_files = collect_lint_files(&lint_options, file_selection)
+        .and_then(|files| {
           if files.is_empty() {
             Err(generic_error("No target files found."))
           } else {
@@ -130,7 +131,8 @@ pub async fn lint(flags: Flags, lint_flags: LintFlags) -> Result<(), AnyError> {
           }
         })?;
       debug!("Found {} files", target_files.len());
-      lint_files(factory, lint_options, target_files).await?
+      lint_files(factory, lint_options, target_files, Default::default())
+        .await?
     };
     if !success {
       std::process::exit(1);
@@ -140,26 +142,54 @@ pub async fn lint(flags: Flags, lint_flags: LintFlags) -> Result<(), AnyError> {
   Ok(())
 }
 
+/// Lints the files, keeping track of the files which have problems in
+/// `files_with_problems`.
 async fn lint_files(
   factory: CliFactory,
-  lint_options: LintOptions,
+  lint_options: ScopedOptions<LintOptions>,
   paths: Vec<PathBuf>,
+  files_with_problems: Arc<Mutex<HashSet<PathBuf>>>,
 ) -> Result<bool, AnyError> {
   let caches = factory.caches()?;
-  let lint_rules = get_config_rules_err_empty(lint_options.rules)?;
+  let lint_rules = get_config_rules_err_empty(lint_options.root.rules.clone())?;
+  let lint_rules = ScopedOptions::with_members(
+    lint_rules,
+    lint_options
+      .members
+      .iter()
+      .map(|(dir, options)| {
+        Ok((
+          dir.clone(),
+          get_config_rules_err_empty(options.rules.clone())?,
+        ))
+      })
+      .collect::<Result<Vec<_>, AnyError>>()?,
+  );
   let incremental_cache = Arc::new(IncrementalCache::new(
     caches.lint_incremental_cache_db(),
     // use a hash of the rule names in order to bust the cache
     &{
       // ensure this is stable by sorting it
-      let mut names = lint_rules.iter().map(|r| r.code()).collect::<Vec<_>>();
+      let mut names = lint_rules
+        .root
+        .iter()
+        .map(|r| r.code().to_string())
+        .collect::<Vec<_>>();
       names.sort_unstable();
+      for (dir, rules) in &lint_rules.members {
+        let mut member_names = rules
+          .iter()
+          .map(|r| format!("{}:{}", dir.display(), r.code()))
+          .collect::<Vec<_>>();
+        member_names.sort_unstable();
+        names.extend(member_names);
+      }
       names
     },
     &paths,
   ));
   let target_files_len = paths.len();
-  let reporter_kind = lint_options.reporter_kind;
+  let reporter_kind = lint_options.root.reporter_kind;
   let reporter_lock =
     Arc::new(Mutex::new(create_reporter(reporter_kind.clone())));
   let has_error = Arc::new(AtomicFlag::default());
@@ -169,14 +199,17 @@ async fn lint_files(
     let lint_rules = lint_rules.clone();
     let reporter_lock = reporter_lock.clone();
     let incremental_cache = incremental_cache.clone();
+    let files_with_problems = files_with_problems.clone();
     move |file_path| {
       let file_text = fs::read_to_string(&file_path)?;
 
       // don't bother rechecking this file if it didn't have any diagnostics before
       if incremental_cache.is_file_same(&file_path, &file_text) {
+        files_with_problems.lock().unwrap().remove(&file_path);
         return Ok(());
       }
 
+      let lint_rules = lint_rules.for_path(&file_path).clone();
       let r = lint_file(&file_path, file_text, lint_rules);
       if let Ok((file_diagnostics, file_source)) = &r {
         if file_diagnostics.is_empty() {
@@ -191,8 +224,11 @@ async fn lint_files(
         r,
         reporter_lock.clone(),
       );
-      if !success {
+      if success {
+        files_with_problems.lock().unwrap().remove(&file_path);
+      } else {
         has_error.raise();
+        files_with_problems.lock().unwrap().insert(file_path);
       }
 
       Ok(())
@@ -205,12 +241,25 @@ async fn lint_files(
   Ok(!has_error.is_raised())
 }
 
-fn collect_lint_files(files: FilePatterns) -> Result<Vec<PathBuf>, AnyError> {
-  FileCollector::new(|path, _| is_script_ext(path))
+fn collect_lint_files(
+  lint_options: &ScopedOptions<LintOptions>,
+  file_selection: FileSelection,
+) -> Result<Vec<PathBuf>, AnyError> {
+  let files = FileCollector::new(|path, _| is_script_ext(path))
+    .file_selection(file_selection.clone())
     .ignore_git_folder()
     .ignore_node_modules()
     .ignore_vendor_folder()
-    .collect_file_patterns(files)
+    .collect_file_patterns(lint_options.root.files.clone())?;
+  // workspace members may exclude files within their own directory
+  Ok(
+    files
+      .into_iter()
+      .filter(|path| {
+        file_selection.matches_path(&lint_options.for_path(path).files, path)
+      })
+      .collect(),
+  )
 }
 
 pub fn print_rules_list(json: bool, maybe_rules_tags: Option<Vec<String>>) {
@@ -0,0 +1,309 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::BTreeMap;
+
+use deno_core::anyhow::anyhow;
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::url::Url;
+use deno_npm::registry::NpmPackageInfo;
+use deno_npm::NpmPackageId;
+use deno_runtime::permissions::PermissionsContainer;
+
+use crate::args::npm_registry_default_url;
+use crate::args::CacheSetting;
+use crate::args::Flags;
+use crate::args::LockFlags;
+use crate::colors;
+use crate::factory::CliFactory;
+use crate::file_fetcher::FileFetcher;
+use crate::http_util::HttpClient;
+use crate::npm::verify_tarball_integrity;
+use crate::util::checksum;
+
+/// The entries of the lockfile to verify, copied out of it so the lock isn't
+/// held while downloading.
+struct LockfileEntries {
+  remote: BTreeMap<String, String>,
+  /// The integrity of the npm packages by their serialized id.
+  npm: BTreeMap<String, String>,
+}
+
+/// Check that the packages which the specifiers and the npm packages of the
+/// lockfile resolve to are in the lockfile.
+fn find_missing_packages(
+  specifiers: &BTreeMap<String, String>,
+  jsr_packages: &[&String],
+  npm_dependencies: &BTreeMap<String, Vec<String>>,
+) -> Vec<String> {
+  let mut problems = Vec::new();
+  for (specifier, package) in specifiers {
+    let (kind, id) = if let Some(nv) = package.strip_prefix("jsr:") {
+      ("jsr", nv)
+    } else if let Some(id) = package.strip_prefix("npm:") {
+      ("npm", id)
+    } else {
+      continue;
+    };
+    let is_missing = match kind {
+      "jsr" => !jsr_packages.iter().any(|p| *p == id),
+      _ => !npm_dependencies.contains_key(id),
+    };
+    if is_missing {
+      problems.push(format!(
+        "The {} package \"{}\" which \"{}\" resolves to is missing from the lockfile.",
+        kind, id, specifier
+      ));
+    }
+  }
+  for (id, dependencies) in npm_dependencies {
+    for dependency in dependencies {
+      if !npm_dependencies.contains_key(dependency) {
+        problems.push(format!(
+          "The npm package \"{}\" which \"{}\" depends on is missing from the lockfile.",
+          dependency, id
+        ));
+      }
+    }
+  }
+  problems
+}
+
+async fn verify_remote_module(
+  file_fetcher: &FileFetcher,
+  specifier: &str,
+  expected: &str,
+) -> Option<String> {
+  let url = match Url::parse(specifier) {
+    Ok(url) => url,
+    Err(err) => {
+      return Some(format!(
+        "The remote module \"{}\" has an invalid URL: {:#}",
+        specifier, err
+      ))
+    }
+  };
+  let result = file_fetcher
+    .fetch(&url, PermissionsContainer::allow_all())
+    .await
+    .and_then(|file| file.into_text_decoded());
+  match result {
+    Ok(file) => {
+      let actual = checksum::gen(&[file.source.as_bytes()]);
+      (actual != expected).then(|| {
+        format!(
+          "The remote module \"{}\" doesn't match the checksum of the lockfile.\n  Expected: {}\n  Actual:   {}",
+          specifier, expected, actual
+        )
+      })
+    }
+    Err(err) => Some(format!(
+      "The remote module \"{}\" couldn't be loaded: {:#}",
+      specifier, err
+    )),
+  }
+}
+
+async fn fetch_npm_package_info(
+  file_fetcher: &FileFetcher,
+  name: &str,
+) -> Result<NpmPackageInfo, AnyError> {
+  let mut info_url = npm_registry_default_url().clone();
+  info_url
+    .path_segments_mut()
+    .map_err(|_| anyhow!("Custom npm registry URL cannot be a base."))?
+    .pop_if_empty()
+    .push(name);
+  let file = file_fetcher
+    .fetch(&info_url, PermissionsContainer::allow_all())
+    .await?;
+  Ok(serde_json::from_slice(&file.source)?)
+}
+
+/// Verify the integrity of an npm package against the registry, and unless
+/// only cached data may be used, against a download of its tarball.
+async fn verify_npm_package(
+  file_fetcher: &FileFetcher,
+  http_client: &HttpClient,
+  serialized_id: &str,
+  expected: &str,
+  download_tarball: bool,
+) -> Option<String> {
+  let id = match NpmPackageId::from_serialized(serialized_id) {
+    Ok(id) => id,
+    Err(err) => {
+      return Some(format!(
+        "The npm package \"{}\" has an invalid id: {:#}",
+        serialized_id, err
+      ))
+    }
+  };
+  let nv = &id.nv;
+  let info = match fetch_npm_package_info(file_fetcher, &nv.name).await {
+    Ok(info) => info,
+    Err(err) => {
+      return Some(format!(
+        "The npm package \"{}\" couldn't be found: {:#}",
+        nv, err
+      ))
+    }
+  };
+  let Some(version_info) = info.versions.get(&nv.version) else {
+    return Some(format!(
+      "The npm package \"{}\" doesn't exist in the npm registry.",
+      nv
+    ));
+  };
+  let dist = &version_info.dist;
+  let registry_integrity = dist.integrity().for_lockfile();
+  if registry_integrity != expected {
+    return Some(format!(
+      "The npm package \"{}\" doesn't match the integrity of the lockfile.\n  Expected: {}\n  Registry: {}",
+      nv, expected, registry_integrity
+    ));
+  }
+  if download_tarball {
+    let result = match http_client.download(&dist.tarball).await {
+      Ok(data) => verify_tarball_integrity(nv, &data, &dist.integrity()),
+      Err(err) => Err(err),
+    };
+    if let Err(err) = result {
+      return Some(format!(
+        "The tarball of the npm package \"{}\" couldn't be verified: {:#}",
+        nv, err
+      ));
+    }
+  }
+  None
+}
+
+pub async fn verify(
+  flags: Flags,
+  _lock_flags: LockFlags,
+) -> Result<(), AnyError> {
+  let factory = CliFactory::from_flags(flags).await?;
+  let cli_options = factory.cli_options();
+  let Some(lockfile) = factory.maybe_lockfile() else {
+    bail!("No lockfile to verify. Specify one with '--lock' or a configuration file next to a \"deno.lock\".");
+  };
+
+  let (entries, mut problems, lockfile_path) = {
+    let lockfile = lockfile.lock();
+    let packages = &lockfile.content.packages;
+    let npm_dependencies: BTreeMap<_, Vec<_>> = packages
+      .npm
+      .iter()
+      .map(|(id, info)| {
+        (id.clone(), info.dependencies.values().cloned().collect())
+      })
+      .collect();
+    let problems = find_missing_packages(
+      &packages.specifiers,
+      &packages.jsr.keys().collect::<Vec<_>>(),
+      &npm_dependencies,
+    );
+    let entries = LockfileEntries {
+      remote: lockfile.content.remote.clone(),
+      npm: packages
+        .npm
+        .iter()
+        .map(|(id, info)| (id.clone(), info.integrity.clone()))
+        .collect(),
+    };
+    (entries, problems, lockfile.filename.clone())
+  };
+
+  let file_fetcher = factory.file_fetcher()?;
+  for (specifier, expected) in &entries.remote {
+    if let Some(problem) =
+      verify_remote_module(file_fetcher, specifier, expected).await
+    {
+      problems.push(problem);
+    }
+  }
+  let download_tarballs =
+    !matches!(cli_options.cache_setting(), CacheSetting::Only);
+  for (id, expected) in &entries.npm {
+    if let Some(problem) = verify_npm_package(
+      file_fetcher,
+      factory.http_client(),
+      id,
+      expected,
+      download_tarballs,
+    )
+    .await
+    {
+      problems.push(problem);
+    }
+  }
+  if !download_tarballs && !entries.npm.is_empty() {
+    log::warn!(
+      "{} The tarballs of the npm packages weren't verified, because --cached-only is specified.",
+      colors::yellow("Warning"),
+    );
+  }
+
+  let count = entries.remote.len() + entries.npm.len();
+  if problems.is_empty() {
+    log::info!(
+      "{} {} {} of \"{}\".",
+      colors::green("Verified"),
+      count,
+      if count == 1 { "entry" } else { "entries" },
+      lockfile_path.display()
+    );
+    return Ok(());
+  }
+
+  for problem in &problems {
+    log::error!("{} {}", colors::red("error:"), problem);
+  }
+  bail!(
+    concat!(
+      "{} of the lockfile failed verification.\n\n",
+      "If the changes are expected, update the lockfile with ",
+      "'deno cache --reload --lock-write <files>'. Otherwise, find out which ",
+      "dependencies were modified before running any code which uses them.",
+    ),
+    if problems.len() == 1 {
+      "1 entry".to_string()
+    } else {
+      format!("{} entries", problems.len())
+    }
+  );
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_find_missing_packages() {
+    let specifiers = BTreeMap::from([
+      (
+        "jsr:@std/path@1".to_string(),
+        "jsr:@std/path@1.0.0".to_string(),
+      ),
+      ("jsr:@std/fs@1".to_string(), "jsr:@std/fs@1.0.0".to_string()),
+      ("npm:chalk@5".to_string(), "npm:chalk@5.3.0".to_string()),
+      ("npm:ms@2".to_string(), "npm:ms@2.1.3".to_string()),
+    ]);
+    let jsr_package = "@std/path@1.0.0".to_string();
+    let npm_dependencies = BTreeMap::from([(
+      "chalk@5.3.0".to_string(),
+      vec!["supports-color@9.4.0".to_string()],
+    )]);
+    let problems =
+      find_missing_packages(&specifiers, &[&jsr_package], &npm_dependencies);
+    assert_eq!(
+      problems,
+      vec![
+        "The jsr package \"@std/fs@1.0.0\" which \"jsr:@std/fs@1\" resolves to is missing from the lockfile.",
+        "The npm package \"ms@2.1.3\" which \"npm:ms@2\" resolves to is missing from the lockfile.",
+        "The npm package \"supports-color@9.4.0\" which \"chalk@5.3.0\" depends on is missing from the lockfile.",
+      ]
+    );
+  }
+}
@@ -1,17 +1,24 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+pub mod audit;
 pub mod bench;
 pub mod bundle;
+pub mod cache;
 pub mod check;
 pub mod compile;
 pub mod coverage;
 pub mod doc;
+pub mod ffi_types;
 pub mod fmt;
 pub mod info;
 pub mod init;
 pub mod installer;
 pub mod jupyter;
 pub mod lint;
+pub mod lock;
+pub mod organize_imports;
+pub mod outdated;
+pub mod pm;
 pub mod registry;
 pub mod repl;
 pub mod run;
@@ -0,0 +1,411 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Sorts and groups the import declarations at the top of a module, removes
+//! the ones which are unused and normalizes their specifiers through the
+//! import map. This is shared by `deno fmt --organize-imports` and the
+//! `source.organizeImports` code action of the language server.
+
+use std::collections::HashSet;
+use std::ops::Range;
+use std::path::Path;
+
+use deno_ast::swc::ast::Ident;
+use deno_ast::swc::ast::ImportDecl;
+use deno_ast::swc::ast::ImportSpecifier;
+use deno_ast::swc::ast::ModuleDecl;
+use deno_ast::swc::ast::ModuleItem;
+use deno_ast::swc::visit::Visit;
+use deno_ast::swc::visit::VisitWith;
+use deno_ast::MediaType;
+use deno_ast::ModuleSpecifier;
+use deno_ast::ParsedSource;
+use deno_ast::SourceRange;
+use deno_ast::SourceRangedForSpanned;
+use deno_core::error::AnyError;
+use deno_core::resolve_import;
+use import_map::ImportMap;
+
+/// The groups imports are sorted into, in the order they are emitted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum ImportGroup {
+  Std,
+  Jsr,
+  Npm,
+  Remote,
+  Relative,
+}
+
+impl ImportGroup {
+  fn from_specifier(
+    specifier: &str,
+    maybe_resolved: Option<&ModuleSpecifier>,
+  ) -> Self {
+    let Some(resolved) = maybe_resolved else {
+      return if specifier.starts_with('.') || specifier.starts_with('/') {
+        ImportGroup::Relative
+      } else {
+        ImportGroup::Remote
+      };
+    };
+    match resolved.scheme() {
+      "jsr" if resolved.path().starts_with("@std/") => ImportGroup::Std,
+      "jsr" => ImportGroup::Jsr,
+      "npm" | "node" => ImportGroup::Npm,
+      "file" => ImportGroup::Relative,
+      "https" | "http"
+        if resolved.host_str() == Some("deno.land")
+          && resolved.path().starts_with("/std") =>
+      {
+        ImportGroup::Std
+      }
+      _ => ImportGroup::Remote,
+    }
+  }
+}
+
+/// Collects all the identifiers referenced outside of import declarations.
+/// This over-approximates what is used (ex. property names are included),
+/// which means an import is never removed while it's still referenced.
+#[derive(Default)]
+struct UsedIdentCollector {
+  idents: HashSet<String>,
+}
+
+impl Visit for UsedIdentCollector {
+  fn visit_import_decl(&mut self, _import_decl: &ImportDecl) {}
+
+  fn visit_ident(&mut self, ident: &Ident) {
+    self.idents.insert(ident.sym.to_string());
+  }
+}
+
+struct OrganizedImport {
+  group: ImportGroup,
+  specifier: String,
+  text: String,
+}
+
+/// Parses the provided file and organizes its imports. Files which aren't
+/// JavaScript or TypeScript are left untouched.
+pub fn organize_imports_in_file(
+  file_path: &Path,
+  file_text: &str,
+  maybe_import_map: Option<&ImportMap>,
+) -> Result<Option<String>, AnyError> {
+  let Ok(specifier) = ModuleSpecifier::from_file_path(file_path) else {
+    return Ok(None);
+  };
+  let media_type = MediaType::from_specifier(&specifier);
+  if !matches!(
+    media_type,
+    MediaType::JavaScript
+      | MediaType::Jsx
+      | MediaType::Mjs
+      | MediaType::Cjs
+      | MediaType::TypeScript
+      | MediaType::Mts
+      | MediaType::Cts
+      | MediaType::Dts
+      | MediaType::Dmts
+      | MediaType::Dcts
+      | MediaType::Tsx
+  ) {
+    return Ok(None);
+  }
+  let parsed_source = deno_ast::parse_module(deno_ast::ParseParams {
+    specifier,
+    text_info: deno_ast::SourceTextInfo::from_string(file_text.to_string()),
+    media_type,
+    capture_tokens: false,
+    maybe_syntax: None,
+    scope_analysis: false,
+  })?;
+  Ok(organize_imports(&parsed_source, maybe_import_map))
+}
+
+/// Organizes the first contiguous run of import declarations in the module,
+/// returning the new text of the module or `None` if nothing changed.
+///
+/// The imports are left alone when comments are interspersed between them,
+/// since those can't be reliably moved along with the declarations. Unused
+/// imports are kept in JSX and TSX files because the JSX runtime may
+/// implicitly reference them.
+pub fn organize_imports(
+  parsed_source: &ParsedSource,
+  maybe_import_map: Option<&ImportMap>,
+) -> Option<String> {
+  let text_info = parsed_source.text_info();
+  let text = text_info.text_str();
+  let start_pos = text_info.range().start;
+  let byte_range = |range: SourceRange| -> Range<usize> {
+    range.start.as_byte_index(start_pos)..range.end.as_byte_index(start_pos)
+  };
+
+  let module = parsed_source.module();
+  let import_decls = module
+    .body
+    .iter()
+    .skip_while(|item| !is_import_decl(item))
+    .map_while(|item| match item {
+      ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)) => {
+        Some(import_decl)
+      }
+      _ => None,
+    })
+    .collect::<Vec<_>>();
+  let block_range = byte_range(SourceRange::new(
+    import_decls.first()?.start(),
+    import_decls.last()?.end(),
+  ));
+  let has_inner_comments =
+    parsed_source.comments().get_vec().iter().any(|comment| {
+      let range = byte_range(comment.range());
+      block_range.start <= range.start && range.end <= block_range.end
+    });
+  if has_inner_comments {
+    return None;
+  }
+
+  let maybe_used_idents =
+    if matches!(parsed_source.media_type(), MediaType::Jsx | MediaType::Tsx) {
+      None
+    } else {
+      let mut collector = UsedIdentCollector::default();
+      module.visit_with(&mut collector);
+      Some(collector.idents)
+    };
+  let referrer = parsed_source.specifier();
+
+  let mut imports = Vec::with_capacity(import_decls.len());
+  for import_decl in import_decls {
+    let kept_specifiers = import_decl
+      .specifiers
+      .iter()
+      .filter(|import_specifier| {
+        maybe_used_idents.as_ref().map_or(true, |used_idents| {
+          used_idents.contains(&*import_specifier_local(import_specifier).sym)
+        })
+      })
+      .collect::<Vec<_>>();
+    // side effect only imports are always kept
+    if kept_specifiers.is_empty() && !import_decl.specifiers.is_empty() {
+      continue;
+    }
+
+    let (specifier, maybe_resolved) =
+      normalize_specifier(&import_decl.src.value, referrer, maybe_import_map);
+    let decl_range = byte_range(import_decl.range());
+    let src_range = byte_range(import_decl.src.range());
+    let quote = &text[src_range.start..src_range.start + 1];
+    let suffix = &text[src_range.end..decl_range.end];
+    let import_text = if kept_specifiers.len() == import_decl.specifiers.len() {
+      format!(
+        "{}{quote}{specifier}{quote}{suffix}",
+        &text[decl_range.start..src_range.start]
+      )
+    } else {
+      let mut default_and_namespace = Vec::new();
+      let mut named = Vec::new();
+      for import_specifier in kept_specifiers {
+        let specifier_text = &text[byte_range(import_specifier.range())];
+        match import_specifier {
+          ImportSpecifier::Named(_) => named.push(specifier_text),
+          ImportSpecifier::Default(_) | ImportSpecifier::Namespace(_) => {
+            default_and_namespace.push(specifier_text)
+          }
+        }
+      }
+      let mut clauses = default_and_namespace
+        .into_iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<_>>();
+      if !named.is_empty() {
+        clauses.push(format!("{{ {} }}", named.join(", ")));
+      }
+      format!(
+        "import {}{} from {quote}{specifier}{quote}{suffix}",
+        if import_decl.type_only { "type " } else { "" },
+        clauses.join(", "),
+      )
+    };
+    imports.push(OrganizedImport {
+      group: ImportGroup::from_specifier(&specifier, maybe_resolved.as_ref()),
+      specifier,
+      text: import_text,
+    });
+  }
+  imports.sort_by(|a, b| {
+    a.group
+      .cmp(&b.group)
+      .then_with(|| a.specifier.cmp(&b.specifier))
+  });
+
+  let mut organized = String::new();
+  let mut last_group = None;
+  for import in imports {
+    if let Some(last_group) = last_group {
+      organized.push('\n');
+      if last_group != import.group {
+        organized.push('\n');
+      }
+    }
+    organized.push_str(&import.text);
+    last_group = Some(import.group);
+  }
+  let rest = if organized.is_empty() {
+    text[block_range.end..].trim_start()
+  } else {
+    &text[block_range.end..]
+  };
+  let new_text = format!("{}{}{}", &text[..block_range.start], organized, rest);
+  if new_text == text {
+    None
+  } else {
+    Some(new_text)
+  }
+}
+
+fn is_import_decl(item: &ModuleItem) -> bool {
+  matches!(item, ModuleItem::ModuleDecl(ModuleDecl::Import(_)))
+}
+
+fn import_specifier_local(import_specifier: &ImportSpecifier) -> &Ident {
+  match import_specifier {
+    ImportSpecifier::Named(named) => &named.local,
+    ImportSpecifier::Default(default) => &default.local,
+    ImportSpecifier::Namespace(namespace) => &namespace.local,
+  }
+}
+
+/// Resolves the specifier and, when an import map is in use, maps the
+/// resolved specifier back to the import map entry which addresses it.
+fn normalize_specifier(
+  specifier: &str,
+  referrer: &ModuleSpecifier,
+  maybe_import_map: Option<&ImportMap>,
+) -> (String, Option<ModuleSpecifier>) {
+  let maybe_resolved = match maybe_import_map {
+    Some(import_map) => import_map.resolve(specifier, referrer).ok(),
+    None => resolve_import(specifier, referrer.as_str()).ok(),
+  };
+  let maybe_mapped = maybe_import_map
+    .zip(maybe_resolved.as_ref())
+    .and_then(|(import_map, resolved)| import_map.lookup(resolved, referrer));
+  (
+    maybe_mapped.unwrap_or_else(|| specifier.to_string()),
+    maybe_resolved,
+  )
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  fn organize(source: &str, maybe_import_map: Option<&ImportMap>) -> String {
+    let parsed_source = deno_ast::parse_module(deno_ast::ParseParams {
+      specifier: ModuleSpecifier::parse("file:///project/mod.ts").unwrap(),
+      text_info: deno_ast::SourceTextInfo::from_string(source.to_string()),
+      media_type: MediaType::TypeScript,
+      capture_tokens: false,
+      maybe_syntax: None,
+      scope_analysis: false,
+    })
+    .unwrap();
+    organize_imports(&parsed_source, maybe_import_map)
+      .unwrap_or_else(|| source.to_string())
+  }
+
+  #[test]
+  fn sorts_and_groups_imports() {
+    let actual = organize(
+      r#"import { b } from "./b.ts";
+import { a } from "./a.ts";
+import express from "npm:express";
+import { assert } from "jsr:@std/assert";
+import { encodeHex } from "jsr:@luca/hex";
+import { z } from "https://example.com/z.ts";
+import "./polyfill.ts";
+
+console.log(a, b, express, assert, encodeHex, z);
+"#,
+      None,
+    );
+    assert_eq!(
+      actual,
+      r#"import { assert } from "jsr:@std/assert";
+
+import { encodeHex } from "jsr:@luca/hex";
+
+import express from "npm:express";
+
+import { z } from "https://example.com/z.ts";
+
+import { a } from "./a.ts";
+import { b } from "./b.ts";
+import "./polyfill.ts";
+
+console.log(a, b, express, assert, encodeHex, z);
+"#
+    );
+  }
+
+  #[test]
+  fn removes_unused_imports() {
+    let actual = organize(
+      r#"import { a, unused } from "./a.ts";
+import type { T, U } from "./types.ts";
+import other from "./other.ts";
+
+const value: T = a;
+"#,
+      None,
+    );
+    assert_eq!(
+      actual,
+      r#"import { a } from "./a.ts";
+import type { T } from "./types.ts";
+
+const value: T = a;
+"#
+    );
+  }
+
+  #[test]
+  fn normalizes_through_import_map() {
+    let import_map = import_map::parse_from_json(
+      &ModuleSpecifier::parse("file:///project/import_map.json").unwrap(),
+      r#"{ "imports": { "@std/assert": "jsr:@std/assert@^0.218.0", "@/": "./src/" } }"#,
+    )
+    .unwrap()
+    .import_map;
+    let actual = organize(
+      r#"import { util } from "./src/util.ts";
+import { assert } from "jsr:@std/assert@^0.218.0";
+
+assert(util);
+"#,
+      Some(&import_map),
+    );
+    assert_eq!(
+      actual,
+      r#"import { assert } from "@std/assert";
+
+import { util } from "@/util.ts";
+
+assert(util);
+"#
+    );
+  }
+
+  #[test]
+  fn skips_imports_with_comments() {
+    let source = r#"import { b } from "./b.ts";
+// comment
+import { a } from "./a.ts";
+
+console.log(a, b);
+"#;
+    assert_eq!(organize(source, None), source);
+  }
+}
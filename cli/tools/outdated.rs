@@ -0,0 +1,836 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use deno_core::anyhow::anyhow;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde::de::DeserializeOwned;
+use deno_core::serde::de::IgnoredAny;
+use deno_core::serde_json;
+use deno_core::url::Url;
+use deno_runtime::permissions::PermissionsContainer;
+use deno_semver::package::PackageReq;
+use deno_semver::Version;
+use deno_semver::VersionReq;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::args::deno_registry_url;
+use crate::args::npm_registry_default_url;
+use crate::args::CacheSetting;
+use crate::args::Flags;
+use crate::args::OutdatedFlags;
+use crate::colors;
+use crate::display;
+use crate::factory::CliFactory;
+use crate::file_fetcher::FetchOptions;
+use crate::file_fetcher::FileFetcher;
+use crate::util::path::specifier_to_file_path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(super) enum DependencyKind {
+  Jsr,
+  Npm,
+  DenoLand,
+}
+
+/// A dependency of the import map or the lockfile.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(super) struct Dependency {
+  pub kind: DependencyKind,
+  /// The name of the package, like "@std/path", or of the deno.land module,
+  /// which is "std" for the standard library.
+  pub name: String,
+  /// The version requirement as written in the specifier, or the version in
+  /// the URL of a deno.land module including any "v" prefix.
+  pub version_text: Option<String>,
+}
+
+impl Dependency {
+  /// Parse a `jsr:` or `npm:` specifier, or the URL of a deno.land module
+  /// with a version.
+  pub fn from_specifier(specifier: &str) -> Option<Self> {
+    let (kind, rest) = if let Some(rest) = specifier.strip_prefix("jsr:") {
+      (DependencyKind::Jsr, rest.trim_start_matches('/'))
+    } else if let Some(rest) = specifier.strip_prefix("npm:") {
+      (DependencyKind::Npm, rest.trim_start_matches('/'))
+    } else if let Some(rest) = specifier.strip_prefix("https://deno.land/x/") {
+      (DependencyKind::DenoLand, rest)
+    } else if let Some(rest) = specifier.strip_prefix("https://deno.land/") {
+      let rest = rest.strip_prefix("std@")?;
+      let version = rest.split('/').next().filter(|v| !v.is_empty())?;
+      return Some(Self {
+        kind: DependencyKind::DenoLand,
+        name: "std".to_string(),
+        version_text: Some(version.to_string()),
+      });
+    } else {
+      return None;
+    };
+    let name_len = match rest.strip_prefix('@') {
+      Some(scoped) => {
+        let (scope, name) = scoped.split_once('/')?;
+        let name_len = name.find(['@', '/']).unwrap_or(name.len());
+        if scope.is_empty() || name_len == 0 {
+          return None;
+        }
+        scope.len() + 2 + name_len
+      }
+      None if kind == DependencyKind::Jsr => return None,
+      None => rest.find(['@', '/']).unwrap_or(rest.len()),
+    };
+    if name_len == 0 {
+      return None;
+    }
+    let (name, rest) = rest.split_at(name_len);
+    let version_text = rest
+      .strip_prefix('@')
+      .and_then(|rest| rest.split('/').next())
+      .filter(|v| !v.is_empty())
+      .map(|v| v.to_string());
+    // the version is what the URL of a deno.land module is recognized by
+    if kind == DependencyKind::DenoLand && version_text.is_none() {
+      return None;
+    }
+    Some(Self {
+      kind,
+      name: name.to_string(),
+      version_text,
+    })
+  }
+
+  pub fn display_name(&self) -> String {
+    match self.kind {
+      DependencyKind::Jsr => format!("jsr:{}", self.name),
+      DependencyKind::Npm => format!("npm:{}", self.name),
+      DependencyKind::DenoLand if self.name == "std" => {
+        "deno.land/std".to_string()
+      }
+      DependencyKind::DenoLand => format!("deno.land/x/{}", self.name),
+    }
+  }
+
+  fn lockfile_prefix(&self) -> Option<&'static str> {
+    match self.kind {
+      DependencyKind::Jsr => Some("jsr:"),
+      DependencyKind::Npm => Some("npm:"),
+      DependencyKind::DenoLand => None,
+    }
+  }
+
+  /// The key of the specifier in the lockfile.
+  pub fn lockfile_key(&self) -> Option<String> {
+    let prefix = self.lockfile_prefix()?;
+    Some(match &self.version_text {
+      Some(version_text) => format!("{}{}@{}", prefix, self.name, version_text),
+      None => format!("{}{}", prefix, self.name),
+    })
+  }
+
+  /// The version of a package which the lockfile resolves the specifier to,
+  /// leaving out the peer dependencies of npm packages.
+  fn locked_version(&self, value: &str) -> Option<Version> {
+    let prefix = format!("{}{}@", self.lockfile_prefix()?, self.name);
+    let version = value.strip_prefix(&prefix)?.split('_').next()?;
+    Version::parse_standard(version).ok()
+  }
+
+  pub fn version_req(&self) -> Option<VersionReq> {
+    match (self.kind, &self.version_text) {
+      // a deno.land module may be updated to any compatible version
+      (DependencyKind::DenoLand, Some(version_text)) => {
+        VersionReq::parse_from_specifier(&format!(
+          "^{}",
+          version_text.trim_start_matches('v')
+        ))
+        .ok()
+      }
+      (_, Some(version_text)) => {
+        VersionReq::parse_from_specifier(version_text).ok()
+      }
+      (_, None) => VersionReq::parse_from_specifier("*").ok(),
+    }
+  }
+
+  /// The version which is pinned by the specifier itself.
+  fn exact_version(&self) -> Option<Version> {
+    let version_text = self.version_text.as_ref()?;
+    Version::parse_standard(version_text.trim_start_matches('v')).ok()
+  }
+
+  /// The version text for updating the specifier to the wanted version while
+  /// keeping the kind of version requirement. Requirements which aren't a
+  /// caret or tilde range of a version already allow the wanted version.
+  fn updated_version_text(&self, wanted: &Version) -> Option<String> {
+    let version_text = self.version_text.as_ref()?;
+    let updated = if self.kind == DependencyKind::DenoLand {
+      match version_text.strip_prefix('v') {
+        Some(_) => format!("v{}", wanted),
+        None => wanted.to_string(),
+      }
+    } else {
+      let (prefix, version) = match version_text.strip_prefix(['^', '~']) {
+        Some(version) => (&version_text[..1], version),
+        None => return None,
+      };
+      Version::parse_standard(version).ok()?;
+      format!("{}{}", prefix, wanted)
+    };
+    (updated != *version_text).then_some(updated)
+  }
+
+  /// The specifier with the version text replaced.
+  fn updated_specifier(&self, specifier: &str, version_text: &str) -> String {
+    let Some(old_version_text) = &self.version_text else {
+      return specifier.to_string();
+    };
+    let old = format!("{}@{}", self.name, old_version_text);
+    let new = format!("{}@{}", self.name, version_text);
+    specifier.replacen(&old, &new, 1)
+  }
+}
+
+/// The versions of a package or module which are published to its registry.
+#[derive(Debug, Default)]
+pub(super) struct PublishedVersions {
+  versions: Vec<Version>,
+  /// The version which the registry marks as the latest, if any.
+  latest: Option<Version>,
+}
+
+impl PublishedVersions {
+  pub fn wanted(&self, req: &VersionReq) -> Option<&Version> {
+    self.versions.iter().filter(|v| req.matches(v)).max()
+  }
+
+  pub fn latest(&self) -> Option<&Version> {
+    self
+      .latest
+      .as_ref()
+      .or_else(|| self.versions.iter().filter(|v| v.pre.is_empty()).max())
+  }
+}
+
+#[derive(Debug, Deserialize)]
+struct JsrPackageMeta {
+  latest: Option<String>,
+  versions: HashMap<String, JsrPackageMetaVersion>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsrPackageMetaVersion {
+  #[serde(default)]
+  yanked: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct NpmPackageMeta {
+  #[serde(rename = "dist-tags", default)]
+  dist_tags: HashMap<String, String>,
+  #[serde(default)]
+  versions: HashMap<String, IgnoredAny>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DenoLandModuleMeta {
+  latest: Option<String>,
+  versions: Vec<String>,
+}
+
+fn parse_versions<'a>(
+  versions: impl Iterator<Item = &'a str>,
+  latest: Option<&str>,
+) -> PublishedVersions {
+  let parse = |v: &str| Version::parse_standard(v.trim_start_matches('v')).ok();
+  PublishedVersions {
+    versions: versions.filter_map(parse).collect(),
+    latest: latest.and_then(parse),
+  }
+}
+
+pub(super) struct RegistryFetcher<'a> {
+  pub file_fetcher: &'a FileFetcher,
+  pub cache_setting: CacheSetting,
+}
+
+impl RegistryFetcher<'_> {
+  async fn fetch_json<T: DeserializeOwned>(
+    &self,
+    url: &Url,
+  ) -> Result<T, AnyError> {
+    let file = self
+      .file_fetcher
+      .fetch_with_options(FetchOptions {
+        specifier: url,
+        permissions: PermissionsContainer::allow_all(),
+        maybe_accept: Some("application/json"),
+        maybe_cache_setting: Some(&self.cache_setting),
+      })
+      .await?;
+    Ok(serde_json::from_slice(&file.source)?)
+  }
+
+  pub async fn versions(
+    &self,
+    dependency: &Dependency,
+  ) -> Result<PublishedVersions, AnyError> {
+    match dependency.kind {
+      DependencyKind::Jsr => {
+        let url = deno_registry_url()
+          .join(&format!("{}/meta.json", dependency.name))?;
+        let meta: JsrPackageMeta = self.fetch_json(&url).await?;
+        Ok(parse_versions(
+          meta
+            .versions
+            .iter()
+            .filter(|(_, info)| !info.yanked)
+            .map(|(version, _)| version.as_str()),
+          meta.latest.as_deref(),
+        ))
+      }
+      DependencyKind::Npm => {
+        let mut url = npm_registry_default_url().clone();
+        url
+          .path_segments_mut()
+          .map_err(|_| anyhow!("Custom npm registry URL cannot be a base."))?
+          .pop_if_empty()
+          .push(&dependency.name);
+        let meta: NpmPackageMeta = self.fetch_json(&url).await?;
+        Ok(parse_versions(
+          meta.versions.keys().map(|v| v.as_str()),
+          meta.dist_tags.get("latest").map(|v| v.as_str()),
+        ))
+      }
+      DependencyKind::DenoLand => {
+        let url = Url::parse(&format!(
+          "https://cdn.deno.land/{}/meta/versions.json",
+          dependency.name
+        ))?;
+        let meta: DenoLandModuleMeta = self.fetch_json(&url).await?;
+        Ok(parse_versions(
+          meta.versions.iter().map(|v| v.as_str()),
+          meta.latest.as_deref(),
+        ))
+      }
+    }
+  }
+}
+
+/// A dependency which isn't on the latest version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OutdatedDependency {
+  name: String,
+  /// The specifiers of the import map, or the key of the lockfile for the
+  /// dependencies which are only in the lockfile.
+  specifiers: Vec<String>,
+  current: Option<String>,
+  wanted: Option<String>,
+  latest: Option<String>,
+  #[serde(skip)]
+  dependency: Dependency,
+  #[serde(skip)]
+  in_import_map: bool,
+}
+
+/// A dependency is outdated when the current version, or the wanted version
+/// when there's no current version, isn't the latest or the wanted version.
+fn is_outdated(
+  current: Option<&Version>,
+  wanted: Option<&Version>,
+  latest: Option<&Version>,
+) -> bool {
+  let Some(version) = current.or(wanted) else {
+    return latest.is_some();
+  };
+  latest.map(|latest| version < latest).unwrap_or(false)
+    || wanted.map(|wanted| version < wanted).unwrap_or(false)
+}
+
+/// The "imports" and "scopes" values of an import map or a configuration
+/// file.
+fn import_map_specifiers(value: &serde_json::Value) -> Vec<String> {
+  let mut specifiers = Vec::new();
+  let mut add_values = |map: Option<&serde_json::Value>| {
+    let Some(map) = map.and_then(|map| map.as_object()) else {
+      return;
+    };
+    for value in map.values() {
+      if let Some(specifier) = value.as_str() {
+        specifiers.push(specifier.to_string());
+      }
+    }
+  };
+  add_values(value.get("imports"));
+  if let Some(scopes) = value.get("scopes").and_then(|s| s.as_object()) {
+    for scope in scopes.values() {
+      add_values(Some(scope));
+    }
+  }
+  specifiers
+}
+
+fn write_outdated(
+  output: &mut String,
+  outdated: &[OutdatedDependency],
+) -> std::fmt::Result {
+  if outdated.is_empty() {
+    return writeln!(
+      output,
+      "{}",
+      colors::green("All dependencies are up to date.")
+    );
+  }
+  let header = ["Package", "Current", "Wanted", "Latest"];
+  let rows = outdated
+    .iter()
+    .map(|d| {
+      [
+        d.name.clone(),
+        d.current.clone().unwrap_or_else(|| "-".to_string()),
+        d.wanted.clone().unwrap_or_else(|| "-".to_string()),
+        d.latest.clone().unwrap_or_else(|| "-".to_string()),
+      ]
+    })
+    .collect::<Vec<_>>();
+  let mut widths = header.map(|h| h.len());
+  for row in &rows {
+    for (width, cell) in widths.iter_mut().zip(row) {
+      *width = (*width).max(cell.len());
+    }
+  }
+  let header = header
+    .iter()
+    .zip(widths)
+    .map(|(h, width)| format!("{:width$}", h))
+    .collect::<Vec<_>>();
+  writeln!(output, "{}", colors::bold(header.join("  ").trim_end()))?;
+  for row in rows {
+    let cells = row
+      .iter()
+      .zip(widths)
+      .map(|(cell, width)| format!("{:width$}", cell))
+      .collect::<Vec<_>>();
+    writeln!(output, "{}", cells.join("  ").trim_end())?;
+  }
+  Ok(())
+}
+
+pub async fn outdated(
+  flags: Flags,
+  outdated_flags: OutdatedFlags,
+) -> Result<(), AnyError> {
+  let factory = CliFactory::from_flags(flags.clone()).await?;
+  let cli_options = factory.cli_options();
+
+  // the files with the import maps to scan, which are the configuration file
+  // and the import map file it or the flag points to
+  let mut import_map_paths = Vec::new();
+  if let Some(config_file) = cli_options.maybe_config_file() {
+    if let Ok(path) = specifier_to_file_path(&config_file.specifier) {
+      import_map_paths.push(path);
+    }
+  }
+  if let Some(specifier) = cli_options.resolve_import_map_specifier()? {
+    if let Ok(path) = specifier_to_file_path(&specifier) {
+      if !import_map_paths.contains(&path) {
+        import_map_paths.push(path);
+      }
+    }
+  }
+  let mut sources: Vec<(PathBuf, String)> = Vec::new();
+  let mut found = Vec::new();
+  for path in import_map_paths {
+    let text = std::fs::read_to_string(&path)
+      .with_context(|| format!("Failed reading \"{}\"", path.display()))?;
+    let value = jsonc_parser::parse_to_serde_value(&text, &Default::default())
+      .with_context(|| format!("Failed parsing \"{}\"", path.display()))?
+      .unwrap_or_default();
+    for specifier in import_map_specifiers(&value) {
+      if let Some(dependency) = Dependency::from_specifier(&specifier) {
+        found.push((specifier, dependency));
+      }
+    }
+    sources.push((path, text));
+  }
+
+  // the versions which the lockfile resolves the specifiers to, along with
+  // the specifiers which are only in the lockfile
+  let mut locked_versions = HashMap::new();
+  let mut lockfile_only = HashSet::new();
+  if let Some(lockfile) = factory.maybe_lockfile() {
+    let lockfile = lockfile.lock();
+    for (key, value) in &lockfile.content.packages.specifiers {
+      let Some(dependency) = Dependency::from_specifier(key) else {
+        continue;
+      };
+      let Some(version) = dependency.locked_version(value) else {
+        continue;
+      };
+      if !found.iter().any(|(_, d)| *d == dependency) {
+        lockfile_only.insert(dependency.clone());
+        found.push((key.clone(), dependency.clone()));
+      }
+      locked_versions.insert(dependency, version);
+    }
+  }
+
+  // the dependencies grouped by package, along with their specifiers
+  let mut dependencies: BTreeMap<(DependencyKind, String), Vec<Dependency>> =
+    BTreeMap::new();
+  let mut specifiers: HashMap<Dependency, Vec<String>> = HashMap::new();
+  for (specifier, dependency) in found {
+    let entry = specifiers.entry(dependency.clone()).or_default();
+    if entry.is_empty() {
+      dependencies
+        .entry((dependency.kind, dependency.name.clone()))
+        .or_default()
+        .push(dependency);
+    }
+    if !entry.contains(&specifier) {
+      entry.push(specifier);
+    }
+  }
+
+  let cache_setting = match cli_options.cache_setting() {
+    // the metadata needs to be up to date to find the newer versions
+    CacheSetting::Use => CacheSetting::RespectHeaders,
+    cache_setting => cache_setting,
+  };
+  let fetcher = RegistryFetcher {
+    file_fetcher: factory.file_fetcher()?,
+    cache_setting,
+  };
+  let mut outdated = Vec::new();
+  for dependencies in dependencies.into_values() {
+    let Some(first) = dependencies.first() else {
+      continue;
+    };
+    let published = match fetcher.versions(first).await {
+      Ok(published) => published,
+      Err(err) => {
+        log::warn!(
+          "{} Failed to get the versions of {}: {:#}",
+          colors::yellow("Warning"),
+          first.display_name(),
+          err
+        );
+        continue;
+      }
+    };
+    let latest = published.latest();
+    for dependency in dependencies {
+      let current = locked_versions
+        .get(&dependency)
+        .cloned()
+        .or_else(|| dependency.exact_version());
+      let wanted = dependency
+        .version_req()
+        .and_then(|req| published.wanted(&req).cloned());
+      if !is_outdated(current.as_ref(), wanted.as_ref(), latest) {
+        continue;
+      }
+      let in_import_map = !lockfile_only.contains(&dependency);
+      outdated.push(OutdatedDependency {
+        name: dependency.display_name(),
+        specifiers: specifiers.get(&dependency).cloned().unwrap_or_default(),
+        current: current.map(|v| v.to_string()),
+        wanted: wanted.map(|v| v.to_string()),
+        latest: latest.map(|v| v.to_string()),
+        dependency,
+        in_import_map,
+      });
+    }
+  }
+
+  if outdated_flags.json {
+    #[derive(Serialize)]
+    struct JsonReport<'a> {
+      outdated: &'a [OutdatedDependency],
+    }
+    display::write_json_to_stdout(&JsonReport {
+      outdated: &outdated,
+    })?;
+    return Ok(());
+  }
+
+  let mut output = String::new();
+  write_outdated(&mut output, &outdated)?;
+  display::write_to_stdout_ignore_sigpipe(output.as_bytes())?;
+
+  if outdated_flags.update && !outdated.is_empty() {
+    update(flags, sources, &outdated).await?;
+  }
+  Ok(())
+}
+
+/// Update the specifiers of the import maps to the wanted versions and the
+/// lockfile to resolve them to the wanted versions.
+async fn update(
+  flags: Flags,
+  sources: Vec<(PathBuf, String)>,
+  outdated: &[OutdatedDependency],
+) -> Result<(), AnyError> {
+  let mut replacements = Vec::new();
+  let mut jsr_specifiers = Vec::new();
+  let mut npm_reqs = Vec::new();
+  let mut messages = Vec::new();
+  for dependency in outdated {
+    // the version requirement doesn't allow the latest version
+    if dependency.current == dependency.wanted {
+      continue;
+    }
+    let Some(wanted) = dependency
+      .wanted
+      .as_deref()
+      .and_then(|v| Version::parse_standard(v).ok())
+    else {
+      continue;
+    };
+    let inner = &dependency.dependency;
+    let maybe_version_text = if dependency.in_import_map {
+      inner.updated_version_text(&wanted)
+    } else {
+      None
+    };
+    let updated = Dependency {
+      version_text: maybe_version_text
+        .clone()
+        .or_else(|| inner.version_text.clone()),
+      ..inner.clone()
+    };
+    match (inner.kind, &maybe_version_text) {
+      (DependencyKind::Jsr, _) => {
+        if let Some(key) = updated.lockfile_key() {
+          jsr_specifiers.push((key, format!("jsr:{}@{}", inner.name, wanted)));
+        }
+      }
+      // npm packages which are already in the lockfile are preferred by the
+      // resolution, so only the requirements which changed are resolved again
+      (DependencyKind::Npm, Some(version_text)) => {
+        let req = format!("{}@{}", inner.name, version_text);
+        if let Ok(req) = PackageReq::from_str(&req) {
+          npm_reqs.push(req);
+        }
+      }
+      (DependencyKind::Npm, None) | (DependencyKind::DenoLand, _) => {}
+    }
+    match (&inner.version_text, maybe_version_text) {
+      (Some(old), Some(new)) => {
+        for specifier in &dependency.specifiers {
+          replacements.push((
+            format!("\"{}\"", specifier),
+            format!("\"{}\"", inner.updated_specifier(specifier, &new)),
+          ));
+        }
+        messages.push((dependency.name.clone(), format!("{} -> {}", old, new)));
+      }
+      _ if inner.kind == DependencyKind::Jsr => {
+        messages.push((dependency.name.clone(), format!("-> {}", wanted)));
+      }
+      _ => {}
+    }
+  }
+
+  for (path, text) in sources {
+    let mut updated_text = text.clone();
+    for (old, new) in &replacements {
+      updated_text = updated_text.replace(old, new);
+    }
+    if updated_text != text {
+      std::fs::write(&path, updated_text)
+        .with_context(|| format!("Failed writing \"{}\"", path.display()))?;
+    }
+  }
+
+  // the lockfile is updated through a new factory, so it's reconciled with
+  // the updated configuration file first
+  let factory = CliFactory::from_flags(flags).await?;
+  if let Some(lockfile) = factory.maybe_lockfile() {
+    {
+      let mut lockfile = lockfile.lock();
+      for (key, value) in jsr_specifiers {
+        lockfile.insert_package_specifier(key, value);
+      }
+    }
+    if !npm_reqs.is_empty() {
+      if let Some(npm_resolver) = factory.npm_resolver().await?.as_managed() {
+        npm_resolver.add_package_reqs(&npm_reqs).await?;
+      }
+    }
+    lockfile
+      .lock()
+      .write()
+      .context("Failed writing lockfile.")?;
+  } else {
+    // without a lockfile, only the changed specifiers are updated
+    messages.retain(|(_, message)| !message.starts_with("->"));
+  }
+
+  for (name, message) in messages {
+    log::info!("{} {} {}", colors::green("Updated"), name, message);
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  fn dependency(
+    kind: DependencyKind,
+    name: &str,
+    version_text: Option<&str>,
+  ) -> Dependency {
+    Dependency {
+      kind,
+      name: name.to_string(),
+      version_text: version_text.map(|v| v.to_string()),
+    }
+  }
+
+  #[test]
+  fn test_dependency_from_specifier() {
+    use DependencyKind::*;
+    let cases = [
+      (
+        "jsr:@std/path@^0.218.0",
+        Some((Jsr, "@std/path", Some("^0.218.0"))),
+      ),
+      (
+        "jsr:/@std/path@1/posix",
+        Some((Jsr, "@std/path", Some("1"))),
+      ),
+      ("jsr:@std/path", Some((Jsr, "@std/path", None))),
+      ("jsr:path@1", None),
+      ("npm:chalk@5.3.0", Some((Npm, "chalk", Some("5.3.0")))),
+      (
+        "npm:@types/node@^20/fs",
+        Some((Npm, "@types/node", Some("^20"))),
+      ),
+      ("npm:chalk", Some((Npm, "chalk", None))),
+      (
+        "https://deno.land/std@0.218.0/path/mod.ts",
+        Some((DenoLand, "std", Some("0.218.0"))),
+      ),
+      (
+        "https://deno.land/x/oak@v12.6.1/mod.ts",
+        Some((DenoLand, "oak", Some("v12.6.1"))),
+      ),
+      ("https://deno.land/x/oak/mod.ts", None),
+      ("https://esm.sh/preact@10.19.0", None),
+      ("./mod.ts", None),
+    ];
+    for (specifier, expected) in cases {
+      assert_eq!(
+        Dependency::from_specifier(specifier),
+        expected.map(|(kind, name, version)| dependency(kind, name, version)),
+        "{}",
+        specifier
+      );
+    }
+  }
+
+  #[test]
+  fn test_updated_version_text() {
+    use DependencyKind::*;
+    let wanted = Version::parse_standard("0.2.3").unwrap();
+    let updated = |kind, version: &str| {
+      dependency(kind, "a", Some(version)).updated_version_text(&wanted)
+    };
+    assert_eq!(updated(Jsr, "^0.2.0"), Some("^0.2.3".to_string()));
+    assert_eq!(updated(Npm, "~0.2.1"), Some("~0.2.3".to_string()));
+    assert_eq!(updated(Npm, "^0.2.3"), None);
+    assert_eq!(updated(Npm, "0.2"), None);
+    assert_eq!(updated(Jsr, ">=0.1.0"), None);
+    assert_eq!(updated(DenoLand, "v0.2.0"), Some("v0.2.3".to_string()));
+    assert_eq!(updated(DenoLand, "0.2.0"), Some("0.2.3".to_string()));
+    assert_eq!(
+      dependency(Npm, "a", None).updated_version_text(&wanted),
+      None
+    );
+  }
+
+  #[test]
+  fn test_updated_specifier() {
+    let dependency = dependency(DependencyKind::Jsr, "@std/path", Some("^1"));
+    assert_eq!(
+      dependency.updated_specifier("jsr:@std/path@^1/posix", "^1.2.0"),
+      "jsr:@std/path@^1.2.0/posix"
+    );
+  }
+
+  #[test]
+  fn test_deno_land_version_req() {
+    let dependency =
+      dependency(DependencyKind::DenoLand, "oak", Some("v12.1.0"));
+    let req = dependency.version_req().unwrap();
+    assert!(req.matches(&Version::parse_standard("12.6.1").unwrap()));
+    assert!(!req.matches(&Version::parse_standard("13.0.0").unwrap()));
+    assert_eq!(
+      dependency.exact_version(),
+      Some(Version::parse_standard("12.1.0").unwrap())
+    );
+  }
+
+  #[test]
+  fn test_is_outdated() {
+    let v = |v: &str| Version::parse_standard(v).unwrap();
+    assert!(is_outdated(
+      Some(&v("1.0.0")),
+      Some(&v("1.1.0")),
+      Some(&v("1.1.0"))
+    ));
+    assert!(is_outdated(
+      Some(&v("1.0.0")),
+      Some(&v("1.0.0")),
+      Some(&v("2.0.0"))
+    ));
+    assert!(is_outdated(None, Some(&v("1.0.0")), Some(&v("2.0.0"))));
+    assert!(!is_outdated(None, Some(&v("2.0.0")), Some(&v("2.0.0"))));
+    assert!(!is_outdated(
+      Some(&v("2.0.0")),
+      Some(&v("2.0.0")),
+      Some(&v("2.0.0"))
+    ));
+    // prereleases newer than the latest version
+    assert!(!is_outdated(
+      Some(&v("3.0.0-beta")),
+      None,
+      Some(&v("2.0.0"))
+    ));
+  }
+
+  #[test]
+  fn test_published_versions() {
+    let published = parse_versions(
+      ["1.0.0", "1.1.0", "2.0.0", "3.0.0-rc.1"].into_iter(),
+      None,
+    );
+    let req = VersionReq::parse_from_specifier("^1.0.0").unwrap();
+    assert_eq!(published.wanted(&req).unwrap().to_string(), "1.1.0");
+    assert_eq!(published.latest().unwrap().to_string(), "2.0.0");
+
+    let published =
+      parse_versions(["v1.0.0", "v2.0.0"].into_iter(), Some("v1.0.0"));
+    assert_eq!(published.latest().unwrap().to_string(), "1.0.0");
+  }
+
+  #[test]
+  fn test_import_map_specifiers() {
+    let value = serde_json::json!({
+      "imports": { "a": "jsr:@std/path@1", "b": "./b.ts" },
+      "scopes": { "./x/": { "c": "npm:chalk@5" } },
+    });
+    assert_eq!(
+      import_map_specifiers(&value),
+      vec!["jsr:@std/path@1", "./b.ts", "npm:chalk@5"]
+    );
+  }
+}
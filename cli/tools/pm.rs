@@ -0,0 +1,779 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use deno_ast::TextChange;
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::ModuleSpecifier;
+use deno_graph::GraphKind;
+use deno_semver::package::PackageReq;
+use deno_semver::Version;
+use jsonc_parser::ast::ObjectProp;
+use jsonc_parser::ast::ObjectPropName;
+use jsonc_parser::ast::Value;
+
+use super::outdated::Dependency;
+use super::outdated::DependencyKind;
+use super::outdated::RegistryFetcher;
+use crate::args::AddFlags;
+use crate::args::CacheSetting;
+use crate::args::CliOptions;
+use crate::args::Flags;
+use crate::args::FmtOptionsConfig;
+use crate::args::RemoveFlags;
+use crate::colors;
+use crate::factory::CliFactory;
+use crate::graph_util::graph_lock_or_exit;
+use crate::graph_util::graph_valid_with_cli_options;
+use crate::tools::fmt::format_json;
+use crate::util::path::specifier_to_file_path;
+
+/// Parse a package of `deno add`, where packages without a prefix are jsr
+/// packages.
+fn parse_add_package(text: &str) -> Result<Dependency, AnyError> {
+  let specifier = if text.starts_with("jsr:") || text.starts_with("npm:") {
+    text.to_string()
+  } else if text.starts_with('@') {
+    format!("jsr:{}", text)
+  } else {
+    bail!(
+      "\"{}\" is missing a prefix. Did you mean \"jsr:{}\" or \"npm:{}\"?",
+      text,
+      text,
+      text
+    );
+  };
+  match Dependency::from_specifier(&specifier) {
+    Some(dependency) if dependency.kind != DependencyKind::DenoLand => {
+      Ok(dependency)
+    }
+    _ => bail!("Invalid package \"{}\".", text),
+  }
+}
+
+/// The version requirement which is written for the resolved version. Exact
+/// versions and tilde requirements are kept, while any other requirement is
+/// narrowed to a caret requirement of the resolved version.
+fn version_text_for(
+  maybe_version_text: Option<&str>,
+  version: &Version,
+) -> String {
+  match maybe_version_text {
+    Some(text) if text.starts_with('~') => format!("~{}", version),
+    Some(text) if Version::parse_standard(text).is_ok() => version.to_string(),
+    _ => format!("^{}", version),
+  }
+}
+
+/// The file with the import map to update, which is the import map file when
+/// the configuration file points to one, or the configuration file, which is
+/// created in the current directory when there's none.
+fn resolve_import_map_path(
+  cli_options: &CliOptions,
+) -> Result<(PathBuf, FmtOptionsConfig), AnyError> {
+  let maybe_config_file = cli_options.maybe_config_file().as_ref();
+  let fmt_options = maybe_config_file
+    .and_then(|config_file| config_file.to_fmt_config().ok().flatten())
+    .map(|config| config.options)
+    .unwrap_or_default();
+  if let Some(specifier) = cli_options.resolve_import_map_specifier()? {
+    let path = specifier_to_file_path(&specifier).with_context(|| {
+      format!("Cannot update the remote import map \"{}\".", specifier)
+    })?;
+    return Ok((path, fmt_options));
+  }
+  let path = match maybe_config_file {
+    Some(config_file) => specifier_to_file_path(&config_file.specifier)
+      .with_context(|| {
+        format!(
+          "Cannot update the remote configuration file \"{}\".",
+          config_file.specifier
+        )
+      })?,
+    None => cli_options.initial_cwd().join("deno.json"),
+  };
+  Ok((path, fmt_options))
+}
+
+fn read_json_file(path: &Path) -> Result<String, AnyError> {
+  match std::fs::read_to_string(path) {
+    Ok(text) => Ok(text),
+    Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+      Ok("{\n}\n".to_string())
+    }
+    Err(err) => {
+      Err(err).with_context(|| format!("Failed reading \"{}\"", path.display()))
+    }
+  }
+}
+
+fn prop_name<'a>(name: &'a ObjectPropName) -> &'a str {
+  match name {
+    ObjectPropName::String(lit) => &lit.value,
+    ObjectPropName::Word(word) => word.value,
+  }
+}
+
+fn value_range(value: &Value) -> std::ops::Range<usize> {
+  let range = match value {
+    Value::StringLit(lit) => &lit.range,
+    Value::NumberLit(lit) => &lit.range,
+    Value::BooleanLit(lit) => &lit.range,
+    Value::Object(object) => &object.range,
+    Value::Array(array) => &array.range,
+    Value::NullKeyword(keyword) => &keyword.range,
+  };
+  range.start..range.end
+}
+
+fn json_string(text: &str) -> String {
+  serde_json::Value::String(text.to_string()).to_string()
+}
+
+/// Sets the properties with a value and removes the properties without one
+/// of an object of the root object, like "imports", creating the object when
+/// it doesn't exist. The other properties and the comments are kept and the
+/// text is formatted after inserting properties.
+fn update_object_text(
+  text: &str,
+  object_key: &str,
+  changes: &[(String, Option<String>)],
+  fmt_options: &FmtOptionsConfig,
+) -> Result<Option<String>, AnyError> {
+  let ast =
+    jsonc_parser::parse_to_ast(text, &Default::default(), &Default::default())?;
+  let root = match ast.value {
+    Some(Value::Object(root)) => root,
+    _ => bail!("Failed updating \"{}\" due to no object.", object_key),
+  };
+  let mut text_changes = Vec::new();
+  let mut inserted = Vec::new();
+  let mut should_format = false;
+  match root.get(object_key) {
+    Some(ObjectProp {
+      value: Value::Object(object),
+      ..
+    }) => {
+      let props = &object.properties;
+      let mut removed = vec![false; props.len()];
+      for (key, maybe_value) in changes {
+        let index = props.iter().position(|p| prop_name(&p.name) == key);
+        match (index, maybe_value) {
+          (Some(index), Some(value)) => text_changes.push(TextChange {
+            range: value_range(&props[index].value),
+            new_text: json_string(value),
+          }),
+          (Some(index), None) => removed[index] = true,
+          (None, Some(value)) => inserted.push(format!(
+            "{}: {}",
+            json_string(key),
+            json_string(value)
+          )),
+          (None, None) => {}
+        }
+      }
+      // the removed properties after the last kept property are removed along
+      // with the comma after the last kept property and the others along with
+      // the comma after them, so the ranges don't overlap
+      let mut removed_ranges = Vec::new();
+      match removed.iter().rposition(|removed| !removed) {
+        _ if !removed.contains(&true) => {}
+        Some(last_kept) => {
+          for (pair, removed) in props.windows(2).zip(&removed).take(last_kept)
+          {
+            if *removed {
+              removed_ranges.push(pair[0].range.start..pair[1].range.start);
+            }
+          }
+          if last_kept + 1 < props.len() {
+            removed_ranges.push(
+              props[last_kept].range.end..props[props.len() - 1].range.end,
+            );
+          }
+        }
+        None => {
+          removed_ranges.push(object.range.start + 1..object.range.end - 1)
+        }
+      }
+      for range in removed_ranges {
+        text_changes.push(TextChange {
+          range,
+          new_text: String::new(),
+        });
+      }
+      if !inserted.is_empty() {
+        let (position, separator) = match props.last() {
+          Some(last) => (last.range.end, ",\n"),
+          None => (object.range.end - 1, "\n"),
+        };
+        text_changes.push(TextChange {
+          range: position..position,
+          new_text: format!("{}{}", separator, inserted.join(",\n")),
+        });
+        should_format = true;
+      }
+    }
+    Some(_) => {
+      bail!("Failed updating \"{}\" due to invalid type.", object_key)
+    }
+    None => {
+      for (key, maybe_value) in changes {
+        if let Some(value) = maybe_value {
+          inserted.push(format!(
+            "{}: {}",
+            json_string(key),
+            json_string(value)
+          ));
+        }
+      }
+      if !inserted.is_empty() {
+        let (position, separator) = match root.properties.last() {
+          Some(last) => (last.range.end, ",\n"),
+          None => (root.range.end - 1, "\n"),
+        };
+        text_changes.push(TextChange {
+          range: position..position,
+          new_text: format!(
+            "{}{}: {{\n{}\n}}",
+            separator,
+            json_string(object_key),
+            inserted.join(",\n")
+          ),
+        });
+        should_format = true;
+      }
+    }
+  }
+
+  if text_changes.is_empty() {
+    return Ok(None);
+  }
+  text_changes.sort_by_key(|change| change.range.start);
+  let new_text = deno_ast::apply_text_changes(text, text_changes);
+  let new_text = if should_format {
+    format_json(&PathBuf::from("deno.json"), &new_text, fmt_options)
+      .ok()
+      .and_then(|formatted_text| formatted_text)
+      .unwrap_or(new_text)
+  } else {
+    new_text
+  };
+  Ok(Some(new_text))
+}
+
+fn write_json_file(path: &Path, text: &str) -> Result<(), AnyError> {
+  std::fs::write(path, text)
+    .with_context(|| format!("Failed writing \"{}\"", path.display()))
+}
+
+/// Adds an npm package to the "allowScripts" array of the text of a
+/// configuration file, creating the array when it doesn't exist.
+pub fn add_allow_scripts_text(
+  text: &str,
+  package_name: &str,
+  fmt_options: &FmtOptionsConfig,
+) -> Result<Option<String>, AnyError> {
+  let entry = format!("npm:{}", package_name);
+  let ast =
+    jsonc_parser::parse_to_ast(text, &Default::default(), &Default::default())?;
+  let root = match ast.value {
+    Some(Value::Object(root)) => root,
+    _ => bail!("Failed updating \"allowScripts\" due to no object."),
+  };
+  let text_change = match root.get("allowScripts") {
+    Some(ObjectProp {
+      value: Value::Array(array),
+      ..
+    }) => {
+      let has_entry = array.elements.iter().any(|element| {
+        matches!(element, Value::StringLit(lit) if lit.value.as_ref() == entry)
+      });
+      if has_entry {
+        return Ok(None);
+      }
+      match array.elements.last() {
+        Some(last) => {
+          let position = value_range(last).end;
+          TextChange {
+            range: position..position,
+            new_text: format!(", {}", json_string(&entry)),
+          }
+        }
+        None => TextChange {
+          range: array.range.start + 1..array.range.end - 1,
+          new_text: json_string(&entry),
+        },
+      }
+    }
+    Some(_) => bail!("Failed updating \"allowScripts\" due to invalid type."),
+    None => {
+      let (position, separator) = match root.properties.last() {
+        Some(last) => (last.range.end, ",\n"),
+        None => (root.range.end - 1, "\n"),
+      };
+      TextChange {
+        range: position..position,
+        new_text: format!(
+          "{}\"allowScripts\": [{}]",
+          separator,
+          json_string(&entry)
+        ),
+      }
+    }
+  };
+  let new_text = deno_ast::apply_text_changes(text, vec![text_change]);
+  let new_text =
+    format_json(&PathBuf::from("deno.json"), &new_text, fmt_options)
+      .ok()
+      .and_then(|formatted_text| formatted_text)
+      .unwrap_or(new_text);
+  Ok(Some(new_text))
+}
+
+/// Add jsr and npm packages to the import map, or the npm packages to the dev
+/// dependencies of the package.json, and update the lockfile.
+pub async fn add(flags: Flags, add_flags: AddFlags) -> Result<(), AnyError> {
+  let dependencies = add_flags
+    .packages
+    .iter()
+    .map(|package| parse_add_package(package))
+    .collect::<Result<Vec<_>, _>>()?;
+
+  let factory = CliFactory::from_flags(flags.clone()).await?;
+  let cli_options = factory.cli_options();
+  let cache_setting = match cli_options.cache_setting() {
+    // the metadata needs to be up to date to find the latest versions
+    CacheSetting::Use => CacheSetting::RespectHeaders,
+    cache_setting => cache_setting,
+  };
+  let fetcher = RegistryFetcher {
+    file_fetcher: factory.file_fetcher()?,
+    cache_setting,
+  };
+  let maybe_package_json_path = cli_options
+    .maybe_package_json()
+    .as_ref()
+    .map(|package_json| package_json.path.clone());
+  if add_flags.dev && maybe_package_json_path.is_none() {
+    log::warn!(
+      "{} There's no package.json for the dev dependencies, so the packages are added to the import map.",
+      colors::yellow("Warning"),
+    );
+  }
+
+  let mut import_changes = Vec::new();
+  let mut dev_changes = Vec::new();
+  let mut jsr_specifiers = Vec::new();
+  let mut npm_reqs = Vec::new();
+  let mut roots = Vec::new();
+  let mut added = Vec::new();
+  for dependency in dependencies {
+    let published = fetcher.versions(&dependency).await.with_context(|| {
+      format!(
+        "Failed to get the versions of {}",
+        dependency.display_name()
+      )
+    })?;
+    let maybe_version = match &dependency.version_text {
+      Some(_) => dependency
+        .version_req()
+        .and_then(|req| published.wanted(&req).cloned()),
+      None => published.latest().cloned(),
+    };
+    let Some(version) = maybe_version else {
+      bail!(
+        "Could not find a version of {} matching \"{}\".",
+        dependency.display_name(),
+        dependency.version_text.as_deref().unwrap_or("latest")
+      );
+    };
+    let version_text =
+      version_text_for(dependency.version_text.as_deref(), &version);
+    let resolved = Dependency {
+      version_text: Some(version_text.clone()),
+      ..dependency.clone()
+    };
+    let Some(specifier) = resolved.lockfile_key() else {
+      continue;
+    };
+    match dependency.kind {
+      DependencyKind::Jsr => jsr_specifiers.push((
+        specifier.clone(),
+        format!("jsr:{}@{}", dependency.name, version),
+      )),
+      DependencyKind::Npm => {
+        let req = format!("{}@{}", dependency.name, version_text);
+        if let Ok(req) = PackageReq::from_str(&req) {
+          npm_reqs.push(req);
+        }
+      }
+      DependencyKind::DenoLand => {}
+    }
+    let is_dev = add_flags.dev
+      && dependency.kind == DependencyKind::Npm
+      && maybe_package_json_path.is_some();
+    if is_dev {
+      dev_changes.push((dependency.name.clone(), Some(version_text)));
+    } else {
+      import_changes.push((dependency.name.clone(), Some(specifier.clone())));
+    }
+    if let Ok(specifier) = ModuleSpecifier::parse(&specifier) {
+      roots.push(specifier);
+    }
+    added.push(specifier);
+  }
+
+  if !import_changes.is_empty() {
+    let (path, fmt_options) = resolve_import_map_path(cli_options)?;
+    let text = read_json_file(&path)?;
+    if let Some(new_text) =
+      update_object_text(&text, "imports", &import_changes, &fmt_options)?
+    {
+      write_json_file(&path, &new_text)?;
+    }
+  }
+  if !dev_changes.is_empty() {
+    if let Some(path) = &maybe_package_json_path {
+      let text = read_json_file(path)?;
+      if let Some(new_text) = update_object_text(
+        &text,
+        "devDependencies",
+        &dev_changes,
+        &Default::default(),
+      )? {
+        write_json_file(path, &new_text)?;
+      }
+    }
+  }
+
+  // the lockfile is updated through a new factory, so it's reconciled with
+  // the updated configuration file first
+  let factory = CliFactory::from_flags(flags).await?;
+  if let Some(lockfile) = factory.maybe_lockfile() {
+    let mut lockfile = lockfile.lock();
+    for (key, value) in jsr_specifiers {
+      lockfile.insert_package_specifier(key, value);
+    }
+  }
+  let npm_resolver = factory.npm_resolver().await?;
+  if let Some(npm_resolver) = npm_resolver.as_managed() {
+    if !npm_reqs.is_empty() {
+      npm_resolver.add_package_reqs(&npm_reqs).await?;
+    }
+  }
+  if add_flags.cache {
+    let cli_options = factory.cli_options();
+    let graph = factory
+      .module_graph_builder()
+      .await?
+      .create_graph(GraphKind::All, roots.clone())
+      .await?;
+    graph_valid_with_cli_options(
+      &graph,
+      factory.fs().as_ref(),
+      &roots,
+      cli_options,
+    )?;
+    if let Some(lockfile) = factory.maybe_lockfile() {
+      graph_lock_or_exit(&graph, &mut lockfile.lock());
+    }
+    if let Some(npm_resolver) = npm_resolver.as_managed() {
+      npm_resolver.cache_packages().await?;
+    }
+  }
+  if let Some(lockfile) = factory.maybe_lockfile() {
+    lockfile
+      .lock()
+      .write()
+      .context("Failed writing lockfile.")?;
+  }
+
+  for specifier in added {
+    log::info!("{} {}", colors::green("Added"), specifier);
+  }
+  Ok(())
+}
+
+/// A package of `deno remove`, which matches the packages of both registries
+/// when there's no prefix.
+fn parse_remove_package(text: &str) -> (Option<DependencyKind>, String) {
+  match Dependency::from_specifier(text) {
+    Some(dependency) if dependency.kind != DependencyKind::DenoLand => {
+      (Some(dependency.kind), dependency.name)
+    }
+    _ => (None, text.to_string()),
+  }
+}
+
+/// The keys of the "imports" which map to one of the packages, or which are
+/// the name of one of the packages, along with their specifiers.
+fn imports_to_remove(
+  imports: &serde_json::Map<String, serde_json::Value>,
+  packages: &[(Option<DependencyKind>, String)],
+) -> Vec<(String, String)> {
+  let mut found = Vec::new();
+  for (key, value) in imports {
+    let Some(specifier) = value.as_str() else {
+      continue;
+    };
+    let maybe_dependency = Dependency::from_specifier(specifier);
+    let is_match =
+      packages
+        .iter()
+        .any(|(maybe_kind, name)| match &maybe_dependency {
+          Some(dependency) => {
+            dependency.name == *name
+              && maybe_kind.map(|k| k == dependency.kind).unwrap_or(true)
+          }
+          None => maybe_kind.is_none() && key == name,
+        });
+    if is_match {
+      found.push((key.clone(), specifier.to_string()));
+    }
+  }
+  found
+}
+
+/// Remove packages from the import map and the dependencies of the
+/// package.json, along with the specifiers of the lockfile.
+pub async fn remove(
+  flags: Flags,
+  remove_flags: RemoveFlags,
+) -> Result<(), AnyError> {
+  let packages = remove_flags
+    .packages
+    .iter()
+    .map(|package| parse_remove_package(package))
+    .collect::<Vec<_>>();
+  let factory = CliFactory::from_flags(flags).await?;
+  let cli_options = factory.cli_options();
+
+  let mut removed = Vec::new();
+  let mut lockfile_keys = Vec::new();
+  let (path, fmt_options) = resolve_import_map_path(cli_options)?;
+  if path.exists() {
+    let text = read_json_file(&path)?;
+    let value = jsonc_parser::parse_to_serde_value(&text, &Default::default())
+      .with_context(|| format!("Failed parsing \"{}\"", path.display()))?
+      .unwrap_or_default();
+    if let Some(imports) = value.get("imports").and_then(|i| i.as_object()) {
+      let mut changes = Vec::new();
+      for (key, specifier) in imports_to_remove(imports, &packages) {
+        if let Some(dependency) = Dependency::from_specifier(&specifier) {
+          lockfile_keys.extend(dependency.lockfile_key());
+        }
+        changes.push((key, None));
+        removed.push(specifier);
+      }
+      if let Some(new_text) =
+        update_object_text(&text, "imports", &changes, &fmt_options)?
+      {
+        write_json_file(&path, &new_text)?;
+      }
+    }
+  }
+  if let Some(package_json) = cli_options.maybe_package_json() {
+    let text = read_json_file(&package_json.path)?;
+    let mut new_text = None;
+    let deps_by_key = [
+      ("dependencies", &package_json.dependencies),
+      ("devDependencies", &package_json.dev_dependencies),
+    ];
+    for (deps_key, maybe_deps) in deps_by_key {
+      let Some(deps) = maybe_deps else {
+        continue;
+      };
+      let mut changes = Vec::new();
+      for (name, version_text) in deps {
+        let is_match = packages.iter().any(|(maybe_kind, package)| {
+          package == name
+            && maybe_kind.map(|k| k == DependencyKind::Npm).unwrap_or(true)
+        });
+        if is_match {
+          let specifier = format!("npm:{}@{}", name, version_text);
+          lockfile_keys.push(specifier.clone());
+          changes.push((name.clone(), None));
+          removed.push(specifier);
+        }
+      }
+      let current_text = new_text.as_deref().unwrap_or(&text);
+      if let Some(updated_text) = update_object_text(
+        current_text,
+        deps_key,
+        &changes,
+        &Default::default(),
+      )? {
+        new_text = Some(updated_text);
+      }
+    }
+    if let Some(new_text) = new_text {
+      write_json_file(&package_json.path, &new_text)?;
+    }
+  }
+
+  if removed.is_empty() {
+    log::warn!(
+      "{} None of the packages were found in the import map or the package.json.",
+      colors::yellow("Warning"),
+    );
+    return Ok(());
+  }
+  if let Some(lockfile) = factory.maybe_lockfile() {
+    let mut lockfile = lockfile.lock();
+    for key in lockfile_keys {
+      if lockfile.content.packages.specifiers.remove(&key).is_some() {
+        lockfile.has_content_changed = true;
+      }
+    }
+    lockfile.write().context("Failed writing lockfile.")?;
+  }
+  for specifier in removed {
+    log::info!("{} {}", colors::green("Removed"), specifier);
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_parse_add_package() {
+    let dependency = parse_add_package("npm:express@^4").unwrap();
+    assert_eq!(dependency.kind, DependencyKind::Npm);
+    assert_eq!(dependency.name, "express");
+    assert_eq!(dependency.version_text.as_deref(), Some("^4"));
+    let dependency = parse_add_package("@std/http").unwrap();
+    assert_eq!(dependency.kind, DependencyKind::Jsr);
+    assert_eq!(dependency.name, "@std/http");
+    assert_eq!(dependency.version_text, None);
+    assert!(parse_add_package("express").is_err());
+    assert!(parse_add_package("jsr:http").is_err());
+  }
+
+  #[test]
+  fn test_version_text_for() {
+    let version = Version::parse_standard("4.19.2").unwrap();
+    assert_eq!(version_text_for(None, &version), "^4.19.2");
+    assert_eq!(version_text_for(Some("^4"), &version), "^4.19.2");
+    assert_eq!(version_text_for(Some("~4.19"), &version), "~4.19.2");
+    assert_eq!(version_text_for(Some("4.19.2"), &version), "4.19.2");
+  }
+
+  #[test]
+  fn test_update_object_text() {
+    let update = |text: &str, changes: &[(&str, Option<&str>)]| {
+      let changes = changes
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.map(|v| v.to_string())))
+        .collect::<Vec<_>>();
+      update_object_text(text, "imports", &changes, &Default::default())
+        .unwrap()
+    };
+    assert_eq!(
+      update("{\n}\n", &[("@std/http", Some("jsr:@std/http@^0.220.1"))]),
+      Some(
+        r#"{
+  "imports": {
+    "@std/http": "jsr:@std/http@^0.220.1"
+  }
+}
+"#
+        .to_string()
+      )
+    );
+    let text = r#"{
+  // the dependencies
+  "imports": {
+    "a": "npm:a@^1.0.0",
+    "b": "npm:b@^1.0.0",
+    "c": "npm:c@^1.0.0"
+  }
+}
+"#;
+    assert_eq!(
+      update(text, &[("b", Some("npm:b@^2.0.0"))]).unwrap(),
+      text.replace("npm:b@^1.0.0", "npm:b@^2.0.0")
+    );
+    assert_eq!(
+      update(text, &[("a", None), ("c", None)]).unwrap(),
+      r#"{
+  // the dependencies
+  "imports": {
+    "b": "npm:b@^1.0.0"
+  }
+}
+"#
+    );
+    assert_eq!(
+      update(text, &[("c", Some("npm:c@^1.0.0")), ("d", None)]),
+      Some(text.to_string())
+    );
+    assert_eq!(update(text, &[("d", None)]), None);
+  }
+
+  #[test]
+  fn test_add_allow_scripts_text() {
+    let add = |text: &str| {
+      add_allow_scripts_text(text, "esbuild", &Default::default()).unwrap()
+    };
+    assert_eq!(
+      add("{\n  \"nodeModulesDir\": true\n}\n").unwrap(),
+      r#"{
+  "nodeModulesDir": true,
+  "allowScripts": ["npm:esbuild"]
+}
+"#
+    );
+    assert_eq!(
+      add("{\n  \"allowScripts\": []\n}\n").unwrap(),
+      "{\n  \"allowScripts\": [\"npm:esbuild\"]\n}\n"
+    );
+    assert_eq!(
+      add("{\n  \"allowScripts\": [\"npm:sharp\"]\n}\n").unwrap(),
+      "{\n  \"allowScripts\": [\"npm:sharp\", \"npm:esbuild\"]\n}\n"
+    );
+    assert_eq!(add("{ \"allowScripts\": [\"npm:esbuild\"] }"), None);
+  }
+
+  #[test]
+  fn test_imports_to_remove() {
+    let imports = serde_json::json!({
+      "@std/http": "jsr:@std/http@^0.220.1",
+      "@std/http/": "jsr:/@std/http@^0.220.1/",
+      "express": "npm:express@^4.19.2",
+      "utils": "./utils.ts",
+    });
+    let imports = imports.as_object().unwrap();
+    assert_eq!(
+      imports_to_remove(
+        imports,
+        &[
+          parse_remove_package("@std/http"),
+          parse_remove_package("utils")
+        ]
+      ),
+      vec![
+        (
+          "@std/http".to_string(),
+          "jsr:@std/http@^0.220.1".to_string()
+        ),
+        (
+          "@std/http/".to_string(),
+          "jsr:/@std/http@^0.220.1/".to_string()
+        ),
+        ("utils".to_string(), "./utils.ts".to_string()),
+      ]
+    );
+    assert_eq!(
+      imports_to_remove(imports, &[parse_remove_package("jsr:express")]),
+      vec![]
+    );
+  }
+}
@@ -1,7 +1,11 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use std::collections::HashMap;
+
+use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
 use deno_core::serde_json;
+use deno_core::url::Url;
 use deno_runtime::deno_fetch::reqwest;
 use serde::de::DeserializeOwned;
 
@@ -33,6 +37,22 @@ pub struct OidcTokenResponse {
   pub value: String,
 }
 
+/// The manifest of a registry, which lets self-hosted registries serve their
+/// API from another URL than `api/` of the registry.
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryManifest {
+  /// The URL of the API, which is relative to the URL of the registry.
+  pub api_url: String,
+}
+
+/// The `meta.json` of a package in the registry.
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageMeta {
+  pub versions: HashMap<String, serde_json::Value>,
+}
+
 #[derive(serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PublishingTaskError {
@@ -114,6 +134,49 @@ pub async fn parse_response<T: DeserializeOwned>(
   })
 }
 
+/// Get the manifest of the registry at `.well-known/jsr.json`, or `None`
+/// when the registry doesn't have one.
+pub async fn get_registry_manifest(
+  client: &reqwest::Client,
+  registry_url: &Url,
+) -> Result<Option<RegistryManifest>, AnyError> {
+  let manifest_url = registry_url.join(".well-known/jsr.json")?;
+  let response = client.get(manifest_url.clone()).send().await?;
+  if response.status() == 404 {
+    return Ok(None);
+  }
+  let manifest = parse_response::<RegistryManifest>(response)
+    .await
+    .with_context(|| {
+      format!("Failed to get the registry manifest at {}", manifest_url)
+    })?;
+  Ok(Some(manifest))
+}
+
+/// Get the `meta.json` of a package, like `@scope/name`, from the registry,
+/// or `None` when the package was never published.
+pub async fn get_package_meta(
+  client: &reqwest::Client,
+  registry_url: &Url,
+  package_name: &str,
+) -> Result<Option<PackageMeta>, AnyError> {
+  let meta_url = registry_url.join(&format!("{}/meta.json", package_name))?;
+  let response = client.get(meta_url.clone()).send().await?;
+  if response.status() == 404 {
+    return Ok(None);
+  }
+  let meta =
+    parse_response::<PackageMeta>(response)
+      .await
+      .with_context(|| {
+        format!(
+          "Failed to get the versions of {} at {}",
+          package_name, meta_url
+        )
+      })?;
+  Ok(Some(meta))
+}
+
 pub async fn get_scope(
   client: &reqwest::Client,
   registry_api_url: &str,
@@ -9,6 +9,9 @@ use deno_core::error::AnyError;
 pub enum AuthMethod {
   Interactive,
   Token(String),
+  /// The authorization header of the token of the host of the registry in
+  /// DENO_AUTH_TOKENS.
+  Authorization(String),
   Oidc(OidcConfig),
 }
 
@@ -35,10 +38,14 @@ fn get_gh_oidc_env_vars() -> Option<Result<(String, String), AnyError>> {
 
 pub fn get_auth_method(
   maybe_token: Option<String>,
+  maybe_authorization: Option<String>,
 ) -> Result<AuthMethod, AnyError> {
   if let Some(token) = maybe_token {
     return Ok(AuthMethod::Token(token));
   }
+  if let Some(authorization) = maybe_authorization {
+    return Ok(AuthMethod::Authorization(authorization));
+  }
 
   match get_gh_oidc_env_vars() {
     Some(Ok((url, token))) => Ok(AuthMethod::Oidc(OidcConfig { url, token })),
@@ -0,0 +1,273 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+
+use deno_ast::TextChange;
+use deno_config::ConfigFile;
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::url::Url;
+use deno_semver::Version;
+use deno_terminal::colors;
+use jsonc_parser::ast::Value;
+
+use super::api;
+use crate::args::VersionBump;
+use crate::factory::CliFactory;
+use crate::util::path::specifier_to_file_path;
+
+struct BumpedPackage {
+  name: String,
+  version: Version,
+  config_path: PathBuf,
+  config_text: String,
+}
+
+/// Bumps the versions in the config files of the packages which are going to
+/// be published, after making sure the new versions aren't published yet.
+/// With `git`, the config files are committed and every new version is
+/// tagged, which is `v<version>` for a single package and `<name>@<version>`
+/// for the members of a workspace.
+pub async fn bump_versions(
+  cli_factory: &CliFactory,
+  bump: VersionBump,
+  registry_url: &Url,
+  git: bool,
+  dry_run: bool,
+) -> Result<(), AnyError> {
+  let cli_options = cli_factory.cli_options();
+  let Some(config_file) = cli_options.maybe_config_file() else {
+    bail!(
+      "Couldn't find a deno.json or a deno.jsonc configuration file in {}.",
+      cli_options.initial_cwd().display()
+    );
+  };
+  let maybe_workspace_config = cli_options.maybe_workspace_config();
+  let config_files = match maybe_workspace_config {
+    Some(workspace_config) => workspace_config
+      .members
+      .iter()
+      .map(|member| &member.config_file)
+      .collect::<Vec<_>>(),
+    None => vec![config_file],
+  };
+
+  let client = cli_factory.http_client().client_for_url(registry_url)?;
+  let mut packages = Vec::with_capacity(config_files.len());
+  for config_file in config_files {
+    let (name, version) = get_name_and_version(config_file)?;
+    let new_version = bump_version(&version, bump);
+    let maybe_meta = api::get_package_meta(client, registry_url, &name).await?;
+    if let Some(meta) = maybe_meta {
+      let new_version_text = new_version.to_string();
+      if meta.versions.contains_key(&new_version_text) {
+        bail!(
+          "Failed bumping {} to {}, because the version is already published.",
+          name,
+          new_version_text
+        );
+      }
+    }
+    let config_path = specifier_to_file_path(&config_file.specifier)?;
+    let text = std::fs::read_to_string(&config_path).with_context(|| {
+      format!("Failed reading \"{}\"", config_path.display())
+    })?;
+    let config_text = update_version_text(&text, &new_version.to_string())?;
+    log::info!(
+      "{} {} from {} to {}",
+      colors::green("Bump"),
+      name,
+      version,
+      new_version
+    );
+    packages.push(BumpedPackage {
+      name,
+      version: new_version,
+      config_path,
+      config_text,
+    });
+  }
+
+  if dry_run {
+    return Ok(());
+  }
+  for package in &packages {
+    std::fs::write(&package.config_path, &package.config_text).with_context(
+      || format!("Failed writing \"{}\"", package.config_path.display()),
+    )?;
+  }
+  if git {
+    let is_workspace = maybe_workspace_config.is_some();
+    commit_and_tag(cli_options.initial_cwd(), &packages, is_workspace)?;
+  }
+  Ok(())
+}
+
+fn get_name_and_version(
+  config_file: &ConfigFile,
+) -> Result<(String, Version), AnyError> {
+  let Some(name) = config_file.json.name.clone() else {
+    bail!("{} is missing 'name' field", config_file.specifier);
+  };
+  let Some(version) = &config_file.json.version else {
+    bail!("{} is missing 'version' field", config_file.specifier);
+  };
+  let version = Version::parse_standard(version).with_context(|| {
+    format!("Invalid version '{}' in {}", version, config_file.specifier)
+  })?;
+  Ok((name, version))
+}
+
+/// Bumps the version the same way as `npm version`, which releases a
+/// pre-release version when it's already the version of the bump, for
+/// example a minor bump of `1.1.0-beta.1` is `1.1.0`.
+fn bump_version(version: &Version, bump: VersionBump) -> Version {
+  let is_pre = !version.pre.is_empty();
+  let (major, minor, patch) = match bump {
+    VersionBump::Patch if is_pre => {
+      (version.major, version.minor, version.patch)
+    }
+    VersionBump::Patch => (version.major, version.minor, version.patch + 1),
+    VersionBump::Minor if is_pre && version.patch == 0 => {
+      (version.major, version.minor, 0)
+    }
+    VersionBump::Minor => (version.major, version.minor + 1, 0),
+    VersionBump::Major
+      if is_pre && version.minor == 0 && version.patch == 0 =>
+    {
+      (version.major, 0, 0)
+    }
+    VersionBump::Major => (version.major + 1, 0, 0),
+  };
+  Version {
+    major,
+    minor,
+    patch,
+    pre: Default::default(),
+    build: Default::default(),
+  }
+}
+
+/// Replaces the "version" of the text of a config file, keeping the rest of
+/// the text as is.
+fn update_version_text(text: &str, version: &str) -> Result<String, AnyError> {
+  let ast =
+    jsonc_parser::parse_to_ast(text, &Default::default(), &Default::default())?;
+  let Some(Value::Object(root)) = ast.value else {
+    bail!("Failed updating \"version\" due to no object.");
+  };
+  let Some(Value::StringLit(lit)) = root.get("version").map(|p| &p.value)
+  else {
+    bail!("Failed updating \"version\" due to no string.");
+  };
+  Ok(deno_ast::apply_text_changes(
+    text,
+    vec![TextChange {
+      range: lit.range.start..lit.range.end,
+      new_text: serde_json::Value::String(version.to_string()).to_string(),
+    }],
+  ))
+}
+
+fn run_git(cwd: &Path, args: &[&str]) -> Result<(), AnyError> {
+  let output = Command::new("git")
+    .args(args)
+    .current_dir(cwd)
+    .output()
+    .context("Failed to run git, which is required for '--git'")?;
+  if !output.status.success() {
+    bail!(
+      "'git {}' failed: {}",
+      args.join(" "),
+      String::from_utf8_lossy(&output.stderr).trim()
+    );
+  }
+  Ok(())
+}
+
+fn commit_and_tag(
+  cwd: &Path,
+  packages: &[BumpedPackage],
+  is_workspace: bool,
+) -> Result<(), AnyError> {
+  let tags = packages
+    .iter()
+    .map(|package| {
+      if is_workspace {
+        format!("{}@{}", package.name, package.version)
+      } else {
+        format!("v{}", package.version)
+      }
+    })
+    .collect::<Vec<_>>();
+  let paths = packages
+    .iter()
+    .map(|package| package.config_path.to_string_lossy().to_string())
+    .collect::<Vec<_>>();
+  let mut add_args = vec!["add", "--"];
+  add_args.extend(paths.iter().map(|path| path.as_str()));
+  run_git(cwd, &add_args)?;
+  let message = packages
+    .iter()
+    .map(|package| format!("{}@{}", package.name, package.version))
+    .collect::<Vec<_>>()
+    .join(", ");
+  run_git(
+    cwd,
+    &["commit", "-m", &format!("chore: release {}", message)],
+  )?;
+  for tag in &tags {
+    run_git(cwd, &["tag", tag])?;
+  }
+  log::info!("{} {}", colors::green("Tagged"), tags.join(", "));
+  Ok(())
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_bump_version() {
+    let cases = [
+      ("1.2.3", VersionBump::Patch, "1.2.4"),
+      ("1.2.3", VersionBump::Minor, "1.3.0"),
+      ("1.2.3", VersionBump::Major, "2.0.0"),
+      ("1.2.3-beta.1", VersionBump::Patch, "1.2.3"),
+      ("1.2.3-beta.1", VersionBump::Minor, "1.3.0"),
+      ("1.2.0-beta.1", VersionBump::Minor, "1.2.0"),
+      ("1.2.0-beta.1", VersionBump::Major, "2.0.0"),
+      ("2.0.0-rc.1", VersionBump::Major, "2.0.0"),
+    ];
+    for (version, bump, expected) in cases {
+      let version = Version::parse_standard(version).unwrap();
+      assert_eq!(bump_version(&version, bump).to_string(), expected);
+    }
+  }
+
+  #[test]
+  fn test_update_version_text() {
+    let text = r#"{
+  // the package
+  "name": "@foo/bar",
+  "version": "1.0.0",
+  "exports": "./mod.ts"
+}
+"#;
+    assert_eq!(
+      update_version_text(text, "1.1.0").unwrap(),
+      r#"{
+  // the package
+  "name": "@foo/bar",
+  "version": "1.1.0",
+  "exports": "./mod.ts"
+}
+"#
+    );
+    assert!(update_version_text(r#"{ "name": "@foo/bar" }"#, "1.0.0").is_err());
+  }
+}
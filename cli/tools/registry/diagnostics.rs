@@ -31,7 +31,7 @@ impl PublishDiagnosticsCollector {
   pub fn print_and_error(&self) -> Result<(), AnyError> {
     let mut errors = 0;
     let mut has_zap_errors = false;
-    let diagnostics = self.diagnostics.lock().unwrap().take();
+    let diagnostics = self.take_diagnostics();
     for diagnostic in diagnostics {
       eprint!("{}", diagnostic.display());
       if matches!(diagnostic.level(), DiagnosticLevel::Error) {
@@ -64,6 +64,10 @@ impl PublishDiagnosticsCollector {
   pub fn push(&self, diagnostic: PublishDiagnostic) {
     self.diagnostics.lock().unwrap().push(diagnostic);
   }
+
+  pub fn take_diagnostics(&self) -> Vec<PublishDiagnostic> {
+    self.diagnostics.lock().unwrap().take()
+  }
 }
 
 pub enum PublishDiagnostic {
@@ -8,6 +8,7 @@ use std::sync::Arc;
 use base64::prelude::BASE64_STANDARD;
 use base64::Engine;
 use deno_config::ConfigFile;
+use deno_config::WorkspaceConfig;
 use deno_core::anyhow::bail;
 use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
@@ -24,6 +25,7 @@ use sha2::Digest;
 
 use crate::args::deno_registry_api_url;
 use crate::args::deno_registry_url;
+use crate::args::resolve_file_selection;
 use crate::args::CliOptions;
 use crate::args::Flags;
 use crate::args::PublishFlags;
@@ -36,14 +38,13 @@ use crate::tools::check::CheckOptions;
 use crate::tools::registry::diagnostics::PublishDiagnosticsCollector;
 use crate::tools::registry::graph::collect_fast_check_type_graph_diagnostics;
 use crate::tools::registry::graph::collect_invalid_external_imports;
-use crate::tools::registry::graph::get_workspace_member_roots;
 use crate::tools::registry::graph::resolve_config_file_roots_from_exports;
-use crate::tools::registry::graph::MemberRoots;
 use crate::util::display::human_size;
 use crate::util::import_map::ImportMapUnfurler;
 
 mod api;
 mod auth;
+mod bump;
 mod diagnostics;
 mod graph;
 mod paths;
@@ -58,6 +59,10 @@ use super::check::TypeChecker;
 
 use self::tar::PublishableTarball;
 
+pub use diagnostics::PublishDiagnostic;
+pub use graph::get_workspace_member_roots;
+pub use graph::MemberRoots;
+
 fn ring_bell() {
   // ASCII code for the bell character.
   print!("\x07");
@@ -94,6 +99,7 @@ async fn prepare_publish(
   graph: Arc<deno_graph::ModuleGraph>,
   import_map: Arc<ImportMap>,
   diagnostics_collector: &PublishDiagnosticsCollector,
+  use_gitignore: bool,
 ) -> Result<Rc<PreparedPublishPackage>, AnyError> {
   let config_path = deno_json.specifier.to_file_path().unwrap();
   let dir_path = config_path.parent().unwrap().to_path_buf();
@@ -136,6 +142,8 @@ async fn prepare_publish(
     bail!("Invalid package name, use '@<scope_name>/<package_name> format");
   };
   let file_patterns = deno_json.to_publish_config()?.map(|c| c.files);
+  let file_selection =
+    resolve_file_selection(Some(deno_json), Some("publish"), use_gitignore)?;
 
   let diagnostics_collector = diagnostics_collector.clone();
   let tarball = deno_core::unsync::spawn_blocking(move || {
@@ -146,6 +154,7 @@ async fn prepare_publish(
       &diagnostics_collector,
       &unfurler,
       file_patterns,
+      &file_selection,
     )
     .context("Failed to create a tarball")
   })
@@ -277,6 +286,15 @@ async fn get_auth_headers(
         );
       }
     }
+    AuthMethod::Authorization(authorization) => {
+      let authorization: Rc<str> = authorization.into();
+      for pkg in &packages {
+        authorizations.insert(
+          (pkg.scope.clone(), pkg.package.clone(), pkg.version.clone()),
+          authorization.clone(),
+        );
+      }
+    }
     AuthMethod::Oidc(oidc_config) => {
       let mut chunked_packages = packages.chunks(16);
       for permissions in permissions.chunks(16) {
@@ -439,15 +457,60 @@ async fn ensure_scopes_and_packages_exist(
   Ok(())
 }
 
+/// Resolve the URL of the API of the registry, which is discovered from the
+/// manifest of registries passed with `--registry`.
+async fn resolve_registry_api_url(
+  client: &reqwest::Client,
+  registry_url: &Url,
+  is_default_registry: bool,
+) -> Result<Url, AnyError> {
+  if is_default_registry {
+    return Ok(deno_registry_api_url().clone());
+  }
+  let mut api_url =
+    match api::get_registry_manifest(client, registry_url).await? {
+      Some(manifest) => {
+        registry_url.join(&manifest.api_url).with_context(|| {
+          format!("Invalid API URL '{}' of the registry", manifest.api_url)
+        })?
+      }
+      None => registry_url.join("api/").unwrap(),
+    };
+  if !api_url.path().ends_with('/') {
+    api_url.set_path(&format!("{}/", api_url.path()));
+  }
+  Ok(api_url)
+}
+
+/// Resolve the URL of the registry of `--registry`, which otherwise is the
+/// default registry.
+fn resolve_registry_url(maybe_registry: Option<Url>) -> Url {
+  match maybe_registry {
+    Some(mut registry_url) => {
+      // ensure there is a trailing slash for the directory
+      if !registry_url.path().ends_with('/') {
+        registry_url.set_path(&format!("{}/", registry_url.path()));
+      }
+      registry_url
+    }
+    None => deno_registry_url().clone(),
+  }
+}
+
 async fn perform_publish(
   http_client: &Arc<HttpClient>,
   mut publish_order_graph: PublishOrderGraph,
   mut prepared_package_by_name: HashMap<String, Rc<PreparedPublishPackage>>,
   auth_method: AuthMethod,
+  registry_url: Url,
+  is_default_registry: bool,
 ) -> Result<(), AnyError> {
-  let client = http_client.client()?;
-  let registry_api_url = deno_registry_api_url().to_string();
-  let registry_url = deno_registry_url().to_string();
+  let client = http_client.client_for_url(&registry_url)?;
+  let registry_api_url =
+    resolve_registry_api_url(client, &registry_url, is_default_registry)
+      .await?
+      .to_string();
+  let registry_url = registry_url.to_string();
 
   let packages = prepared_package_by_name
     .values()
@@ -531,7 +594,7 @@ async fn publish_package(
   registry_url: &str,
   authorization: &str,
 ) -> Result<(), AnyError> {
-  let client = http_client.client()?;
+  let client = http_client.client_for_url(&Url::parse(registry_api_url)?)?;
   println!(
     "{} @{}/{}@{} ...",
     colors::intense_blue("Publishing"),
@@ -680,6 +743,7 @@ async fn prepare_packages_for_publishing(
       graph,
       import_map,
       diagnostics_collector,
+      cli_options.gitignore_flag(),
     )
     .await?;
     let package_name = format!("@{}/{}", package.scope, package.package);
@@ -724,6 +788,7 @@ async fn prepare_packages_for_publishing(
           graph,
           import_map,
           diagnostics_collector,
+          cli_options.gitignore_flag(),
         )
         .await
         .with_context(|| {
@@ -745,6 +810,62 @@ async fn prepare_packages_for_publishing(
   })
 }
 
+/// Get the names of the workspace members ordered so that every member comes
+/// after the members which it imports. The kind of the graph decides whether
+/// the imports of only types are taken into account.
+pub async fn resolve_workspace_member_order(
+  module_graph_builder: &ModuleGraphBuilder,
+  workspace_config: &WorkspaceConfig,
+  graph_kind: deno_graph::GraphKind,
+) -> Result<Vec<String>, AnyError> {
+  let roots = get_workspace_member_roots(workspace_config)?;
+  let graph = module_graph_builder
+    .create_graph_with_options(crate::graph_util::CreateGraphOptions {
+      graph_kind,
+      roots: roots
+        .iter()
+        .flat_map(|r| r.exports.iter())
+        .cloned()
+        .collect(),
+      workspace_fast_check: false,
+      workspace_members: None,
+      loader: None,
+    })
+    .await?;
+  let mut order_graph =
+    publish_order::build_publish_order_graph(&graph, &roots)?;
+  let mut order = Vec::with_capacity(roots.len());
+  loop {
+    let next_batch = order_graph.next();
+    if next_batch.is_empty() {
+      break;
+    }
+    for package_name in next_batch {
+      order_graph.finish_package(&package_name);
+      order.push(package_name);
+    }
+  }
+  order_graph.ensure_no_pending()?;
+  Ok(order)
+}
+
+/// Collects the diagnostics `deno publish` would report for the packages of a
+/// graph that was built with the fast check of the workspace members, so the
+/// language server can surface them while editing.
+pub fn collect_publish_diagnostics(
+  graph: &deno_graph::ModuleGraph,
+  packages: &[MemberRoots],
+) -> Vec<PublishDiagnostic> {
+  let diagnostics_collector = PublishDiagnosticsCollector::default();
+  collect_invalid_external_imports(graph, &diagnostics_collector);
+  collect_fast_check_type_graph_diagnostics(
+    graph,
+    packages,
+    &diagnostics_collector,
+  );
+  diagnostics_collector.take_diagnostics()
+}
+
 async fn build_and_check_graph_for_publish(
   module_graph_builder: &ModuleGraphBuilder,
   type_checker: &TypeChecker,
@@ -764,6 +885,7 @@ async fn build_and_check_graph_for_publish(
           .cloned()
           .collect(),
         workspace_fast_check: true,
+        workspace_members: None,
         loader: None,
       })
       .await?,
@@ -813,9 +935,35 @@ pub async fn publish(
   flags: Flags,
   publish_flags: PublishFlags,
 ) -> Result<(), AnyError> {
-  let cli_factory = CliFactory::from_flags(flags).await?;
+  let is_default_registry = publish_flags.registry.is_none();
+  let registry_url = resolve_registry_url(publish_flags.registry);
+
+  if let Some(bump) = publish_flags.bump {
+    // the config files are read again after bumping the versions
+    let cli_factory = CliFactory::from_flags(flags.clone()).await?;
+    bump::bump_versions(
+      &cli_factory,
+      bump,
+      &registry_url,
+      publish_flags.git,
+      publish_flags.dry_run,
+    )
+    .await?;
+  }
 
-  let auth_method = get_auth_method(publish_flags.token)?;
+  let cli_factory = CliFactory::from_flags(flags).await?;
+  // the tokens of DENO_AUTH_TOKENS are only used for the registries which are
+  // passed explicitly
+  let maybe_authorization = if is_default_registry {
+    None
+  } else {
+    cli_factory
+      .cli_options()
+      .auth_tokens()
+      .get(&registry_url)
+      .map(|token| token.to_string())
+  };
+  let auth_method = get_auth_method(publish_flags.token, maybe_authorization)?;
 
   let import_map = cli_factory
     .maybe_import_map()
@@ -865,6 +1013,8 @@ pub async fn publish(
     prepared_data.publish_order_graph,
     prepared_data.package_by_name,
     auth_method,
+    registry_url,
+    is_default_registry,
   )
   .await
 }
@@ -16,6 +16,7 @@ use tar::Header;
 
 use crate::cache::LazyGraphSourceParser;
 use crate::tools::registry::paths::PackagePath;
+use crate::util::file_selection::FileSelection;
 use crate::util::import_map::ImportMapUnfurler;
 
 use super::diagnostics::PublishDiagnostic;
@@ -40,7 +41,10 @@ pub fn create_gzipped_tarball(
   diagnostics_collector: &PublishDiagnosticsCollector,
   unfurler: &ImportMapUnfurler,
   file_patterns: Option<FilePatterns>,
+  file_selection: &FileSelection,
 ) -> Result<PublishableTarball, AnyError> {
+  let file_patterns = file_patterns
+    .unwrap_or_else(|| FilePatterns::new_with_base(dir.to_path_buf()));
   let mut tar = TarGzArchive::new();
   let mut files = vec![];
 
@@ -53,15 +57,13 @@ pub fn create_gzipped_tarball(
     let path = entry.path();
     let file_type = entry.file_type();
 
-    let matches_pattern = file_patterns
-      .as_ref()
-      .map(|p| p.matches_path(path))
-      .unwrap_or(true);
-    if !matches_pattern
-      || path.file_name() == Some(OsStr::new(".git"))
-      || path.file_name() == Some(OsStr::new("node_modules"))
-    {
-      if file_type.is_dir() {
+    let matches_pattern = file_selection.matches_path(&file_patterns, path);
+    let is_ignored_dir = path.file_name() == Some(OsStr::new(".git"))
+      || path.file_name() == Some(OsStr::new("node_modules"));
+    if !matches_pattern || is_ignored_dir {
+      if file_type.is_dir()
+        && (is_ignored_dir || file_selection.can_skip_excluded_dir(path))
+      {
         iterator.skip_current_dir();
       }
       continue;
@@ -0,0 +1,119 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::path::PathBuf;
+
+/// The commands of the REPL, which are lines starting with a dot like the
+/// commands of the Node.js REPL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplCommand {
+  /// Reads multiple lines which are evaluated at once.
+  Editor,
+  /// Saves the evaluated inputs of the session as a script.
+  Save(PathBuf),
+  /// Evaluates a script in the current session.
+  Load(PathBuf),
+  /// Starts recording the inputs and outputs to a file, or stops recording
+  /// without a file.
+  Transcript(Option<PathBuf>),
+  Help,
+}
+
+pub const REPL_COMMANDS_HELP: &str = r#".editor              Enter editor mode to evaluate multiple lines at once
+.help                Print this help message
+.load <file>         Evaluate a script in the current session
+.save <file>         Save the evaluated inputs of the session to a script
+.transcript [file]   Record the inputs and outputs to a file, or stop
+                     recording when no file is given"#;
+
+/// Parses a command of the REPL, which returns `None` for any other input,
+/// like `.5`, so it's evaluated as usual. Returns the usage of the command
+/// as the error when the arguments are wrong.
+pub fn parse_repl_command(line: &str) -> Option<Result<ReplCommand, String>> {
+  let line = line.trim();
+  let (name, arg) = match line.split_once(char::is_whitespace) {
+    Some((name, arg)) => (name, arg.trim()),
+    None => (line, ""),
+  };
+  let arg = (!arg.is_empty()).then(|| PathBuf::from(arg));
+  let command = match name {
+    ".editor" | ".help" if arg.is_some() => Err(format!("usage: {name}")),
+    ".editor" => Ok(ReplCommand::Editor),
+    ".help" => Ok(ReplCommand::Help),
+    ".save" => arg
+      .map(ReplCommand::Save)
+      .ok_or_else(|| "usage: .save <file>".to_string()),
+    ".load" => arg
+      .map(ReplCommand::Load)
+      .ok_or_else(|| "usage: .load <file>".to_string()),
+    ".transcript" => Ok(ReplCommand::Transcript(arg)),
+    _ => return None,
+  };
+  Some(command)
+}
+
+/// Formats an input and its output for a transcript, where the input is
+/// prefixed like it's shown in the REPL.
+pub fn format_transcript_entry(input: &str, output: &str) -> String {
+  let mut text = String::new();
+  for (index, line) in input.trim_end_matches('\n').split('\n').enumerate() {
+    text.push_str(if index == 0 { "> " } else { "... " });
+    text.push_str(line);
+    text.push('\n');
+  }
+  let output = console_static_text::ansi::strip_ansi_codes(output);
+  if !output.is_empty() {
+    text.push_str(&output);
+    text.push('\n');
+  }
+  text
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_parse_repl_command() {
+    assert_eq!(parse_repl_command(".editor"), Some(Ok(ReplCommand::Editor)));
+    assert_eq!(
+      parse_repl_command("  .save  session.ts "),
+      Some(Ok(ReplCommand::Save(PathBuf::from("session.ts"))))
+    );
+    assert_eq!(
+      parse_repl_command(".load my script.ts"),
+      Some(Ok(ReplCommand::Load(PathBuf::from("my script.ts"))))
+    );
+    assert_eq!(
+      parse_repl_command(".transcript"),
+      Some(Ok(ReplCommand::Transcript(None)))
+    );
+    assert_eq!(
+      parse_repl_command(".transcript out.txt"),
+      Some(Ok(ReplCommand::Transcript(Some(PathBuf::from("out.txt")))))
+    );
+    assert_eq!(
+      parse_repl_command(".save"),
+      Some(Err("usage: .save <file>".to_string()))
+    );
+    assert_eq!(
+      parse_repl_command(".editor foo"),
+      Some(Err("usage: .editor".to_string()))
+    );
+    assert_eq!(parse_repl_command(".5"), None);
+    assert_eq!(parse_repl_command("a.save"), None);
+    assert_eq!(parse_repl_command(".saved"), None);
+  }
+
+  #[test]
+  fn test_format_transcript_entry() {
+    assert_eq!(
+      format_transcript_entry("1 + 1", "\u{1b}[33m2\u{1b}[39m"),
+      "> 1 + 1\n2\n"
+    );
+    assert_eq!(
+      format_transcript_entry("function f() {\n  return 1;\n}", "undefined"),
+      "> function f() {\n...   return 1;\n... }\nundefined\n"
+    );
+    assert_eq!(format_transcript_entry("", ""), "> \n");
+  }
+}
@@ -29,6 +29,7 @@ use rustyline::EventHandler;
 use rustyline::KeyCode;
 use rustyline::KeyEvent;
 use rustyline::Modifiers;
+use rustyline::Movement;
 use rustyline::RepeatCount;
 use rustyline_derive::Helper;
 use rustyline_derive::Hinter;
@@ -421,11 +422,19 @@ impl Highlighter for EditorHelper {
 }
 
 #[derive(Clone)]
+/// The max number of entries of the history, unless it's set with the
+/// `DENO_REPL_HISTORY_SIZE` environment variable.
+const DEFAULT_HISTORY_SIZE: usize = 100;
+
+/// The indentation of each level of brackets in multi-line input.
+const INDENT: &str = "  ";
+
 pub struct ReplEditor {
   inner: Arc<Mutex<Editor<EditorHelper, rustyline::history::FileHistory>>>,
   history_file_path: Option<PathBuf>,
   errored_on_history_save: Arc<AtomicBool>,
   should_exit_on_interrupt: Arc<AtomicBool>,
+  is_editor_mode: Arc<AtomicBool>,
 }
 
 impl ReplEditor {
@@ -433,8 +442,14 @@ impl ReplEditor {
     helper: EditorHelper,
     history_file_path: Option<PathBuf>,
   ) -> Result<Self, AnyError> {
+    let history_size = std::env::var("DENO_REPL_HISTORY_SIZE")
+      .ok()
+      .and_then(|size| size.parse::<usize>().ok())
+      .unwrap_or(DEFAULT_HISTORY_SIZE);
     let editor_config = Config::builder()
       .completion_type(CompletionType::List)
+      .max_history_size(history_size)?
+      .history_ignore_dups(true)?
       .build();
 
     let mut editor =
@@ -458,6 +473,23 @@ impl ReplEditor {
         should_exit_on_interrupt: should_exit_on_interrupt.clone(),
       })),
     );
+    for enter in [
+      KeyEvent(KeyCode::Enter, Modifiers::NONE),
+      KeyEvent(KeyCode::Char('j'), Modifiers::CTRL),
+    ] {
+      editor.bind_sequence(
+        enter,
+        EventHandler::Conditional(Box::new(EnterEventHandler)),
+      );
+    }
+    for closing in ['}', ')', ']'] {
+      editor.bind_sequence(
+        KeyEvent(KeyCode::Char(closing), Modifiers::NONE),
+        EventHandler::Conditional(Box::new(ClosingBracketEventHandler(
+          closing,
+        ))),
+      );
+    }
 
     if let Some(history_file_path) = &history_file_path {
       let history_file_dir = history_file_path.parent().unwrap();
@@ -474,11 +506,14 @@ impl ReplEditor {
       history_file_path,
       errored_on_history_save: Arc::new(AtomicBool::new(false)),
       should_exit_on_interrupt,
+      is_editor_mode: Arc::new(AtomicBool::new(false)),
     })
   }
 
   pub fn readline(&self) -> Result<String, ReadlineError> {
-    self.inner.lock().readline("> ")
+    // the lines of the editor mode are pasted as they are, without a prompt
+    let prompt = if self.is_editor_mode() { "" } else { "> " };
+    self.inner.lock().readline(prompt)
   }
 
   pub fn update_history(&self, entry: String) {
@@ -502,6 +537,14 @@ impl ReplEditor {
   pub fn set_should_exit_on_interrupt(&self, yes: bool) {
     self.should_exit_on_interrupt.store(yes, Relaxed);
   }
+
+  pub fn is_editor_mode(&self) -> bool {
+    self.is_editor_mode.load(Relaxed)
+  }
+
+  pub fn set_editor_mode(&self, yes: bool) {
+    self.is_editor_mode.store(yes, Relaxed);
+  }
 }
 
 /// Command to reverse search history , same as rustyline default C-R but that resets repl should_exit flag to false
@@ -521,6 +564,84 @@ impl ConditionalEventHandler for ReverseSearchHistoryEventHandler {
   }
 }
 
+/// Inserts a new line indented by the depth of the open brackets when the
+/// input is incomplete and the cursor is at the end, otherwise it's the same
+/// as rustyline's default enter.
+struct EnterEventHandler;
+impl ConditionalEventHandler for EnterEventHandler {
+  fn handle(
+    &self,
+    _: &Event,
+    _: RepeatCount,
+    _: bool,
+    ctx: &EventContext,
+  ) -> Option<Cmd> {
+    let line = ctx.line();
+    if ctx.pos() != line.len()
+      || !matches!(validate(line), ValidationResult::Incomplete)
+    {
+      return None;
+    }
+    Some(Cmd::Insert(1, newline_with_indent(line)))
+  }
+}
+
+/// Dedents a closing bracket typed at the start of an indented line, so it
+/// lines up with the line of the opening bracket.
+struct ClosingBracketEventHandler(char);
+impl ConditionalEventHandler for ClosingBracketEventHandler {
+  fn handle(
+    &self,
+    _: &Event,
+    _: RepeatCount,
+    _: bool,
+    ctx: &EventContext,
+  ) -> Option<Cmd> {
+    if is_indented_line_start(&ctx.line()[..ctx.pos()]) {
+      Some(Cmd::Replace(
+        Movement::BackwardChar(INDENT.len()),
+        Some(self.0.to_string()),
+      ))
+    } else {
+      None // default insert
+    }
+  }
+}
+
+/// Gets the depth of the brackets which are still open at the end of the
+/// input.
+fn open_bracket_depth(input: &str) -> usize {
+  let mut depth: usize = 0;
+  for item in deno_ast::lex(input, deno_ast::MediaType::TypeScript) {
+    match item.inner {
+      deno_ast::TokenOrComment::Token(
+        Token::LParen | Token::LBracket | Token::LBrace | Token::DollarLBrace,
+      ) => depth += 1,
+      deno_ast::TokenOrComment::Token(
+        Token::RParen | Token::RBracket | Token::RBrace,
+      ) => depth = depth.saturating_sub(1),
+      _ => {}
+    }
+  }
+  depth
+}
+
+fn newline_with_indent(input: &str) -> String {
+  format!("\n{}", INDENT.repeat(open_bracket_depth(input)))
+}
+
+/// Gets if the text before the cursor ends with a line which only has the
+/// indentation so far.
+fn is_indented_line_start(text_before_cursor: &str) -> bool {
+  match text_before_cursor.rsplit_once('\n') {
+    Some((_, current_line)) => {
+      current_line.len() >= INDENT.len()
+        && current_line.chars().all(|c| c == ' ')
+    }
+    None => false,
+  }
+}
+
 /// A custom tab key event handler
 /// It uses a heuristic to determine if the user is requesting completion or if they want to insert an actual tab
 /// The heuristic goes like this:
@@ -563,6 +684,8 @@ impl ConditionalEventHandler for TabEventHandler {
 mod test {
   use rustyline::validate::ValidationResult;
 
+  use super::is_indented_line_start;
+  use super::newline_with_indent;
   use super::validate;
 
   #[test]
@@ -578,4 +701,21 @@ let left = test( arr.slice( 0 , arr.length/2 ) )"#;
     let code = r#"/testing/;"#;
     assert!(matches!(validate(code), ValidationResult::Valid(_)));
   }
+
+  #[test]
+  fn newline_indents_by_open_brackets() {
+    assert_eq!(newline_with_indent("function test() {"), "\n  ");
+    assert_eq!(newline_with_indent("if (a) {\n  foo([1,"), "\n      ");
+    assert_eq!(newline_with_indent("[\n  1,\n]; ({"), "\n    ");
+    assert_eq!(newline_with_indent("`${"), "\n  ");
+  }
+
+  #[test]
+  fn closing_bracket_dedents_indented_line_start() {
+    assert!(is_indented_line_start("{\n  "));
+    assert!(is_indented_line_start("{\n  {\n    "));
+    assert!(!is_indented_line_start("{\n  a"));
+    assert!(!is_indented_line_start("{\n"));
+    assert!(!is_indented_line_start("  "));
+  }
 }
@@ -1,5 +1,7 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use std::io::Write;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use crate::args::CliOptions;
@@ -9,6 +11,7 @@ use crate::cdp;
 use crate::colors;
 use crate::factory::CliFactory;
 use crate::file_fetcher::FileFetcher;
+use crate::util::path::specifier_to_file_path;
 use deno_core::error::AnyError;
 use deno_core::futures::StreamExt;
 use deno_core::serde_json;
@@ -19,6 +22,7 @@ use rustyline::error::ReadlineError;
 use tokio::sync::mpsc::unbounded_channel;
 
 mod channel;
+mod commands;
 mod editor;
 mod session;
 
@@ -26,6 +30,10 @@ use channel::rustyline_channel;
 use channel::RustylineSyncMessage;
 use channel::RustylineSyncMessageHandler;
 use channel::RustylineSyncResponse;
+use commands::format_transcript_entry;
+use commands::parse_repl_command;
+use commands::ReplCommand;
+use commands::REPL_COMMANDS_HELP;
 use editor::EditorHelper;
 use editor::ReplEditor;
 pub use session::EvaluationOutput;
@@ -35,10 +43,19 @@ pub use session::REPL_INTERNALS_NAME;
 use super::test::TestEvent;
 use super::test::TestEventSender;
 
+/// A file the inputs and outputs of the REPL are recorded to.
+struct Transcript {
+  path: PathBuf,
+  file: std::fs::File,
+}
+
 struct Repl {
   session: ReplSession,
   editor: ReplEditor,
   message_handler: RustylineSyncMessageHandler,
+  /// The inputs which have been evaluated without errors, for `.save`.
+  evaluated_sources: Vec<String>,
+  transcript: Option<Transcript>,
 }
 
 impl Repl {
@@ -50,11 +67,37 @@ impl Repl {
         self.editor.clone(),
       )
       .await;
+      let line = match line {
+        Ok(line) => match parse_repl_command(&line) {
+          Some(Ok(ReplCommand::Editor)) => {
+            match self.read_editor_mode().await {
+              Ok(Some(source)) => Ok(source),
+              Ok(None) => continue,
+              Err(err) => Err(err),
+            }
+          }
+          Some(Ok(command)) => {
+            self.editor.set_should_exit_on_interrupt(false);
+            self.editor.update_history(line);
+            self.run_command(command).await;
+            if self.session.closing().await? {
+              break;
+            }
+            continue;
+          }
+          Some(Err(usage)) => {
+            println!("{usage}");
+            continue;
+          }
+          None => Ok(line),
+        },
+        line => line,
+      };
       match line {
         Ok(line) => {
           self.editor.set_should_exit_on_interrupt(false);
           self.editor.update_history(line.clone());
-          let output = self.session.evaluate_line_and_get_output(&line).await;
+          let output = self.evaluate(&line).await;
 
           // We check for close and break here instead of making it a loop condition to get
           // consistent behavior in when the user evaluates a call to close().
@@ -84,6 +127,96 @@ impl Repl {
 
     Ok(())
   }
+
+  /// Evaluates the source, which is kept for `.save` unless it errors and
+  /// is recorded when there's a transcript.
+  async fn evaluate(&mut self, source: &str) -> EvaluationOutput {
+    let output = self.session.evaluate_line_and_get_output(source).await;
+    if !matches!(output, EvaluationOutput::Error(_)) {
+      self.evaluated_sources.push(source.to_string());
+    }
+    if let Some(transcript) = &mut self.transcript {
+      let entry = format_transcript_entry(source, &output.to_string());
+      if let Err(err) = transcript.file.write_all(entry.as_bytes()) {
+        println!(
+          "Error writing transcript \"{}\": {err}",
+          transcript.path.display()
+        );
+        self.transcript = None;
+      }
+    }
+    output
+  }
+
+  async fn run_command(&mut self, command: ReplCommand) {
+    match command {
+      ReplCommand::Editor => unreachable!(),
+      ReplCommand::Help => println!("{REPL_COMMANDS_HELP}"),
+      ReplCommand::Save(path) => {
+        let mut text = self.evaluated_sources.join("\n");
+        text.push('\n');
+        match std::fs::write(&path, text) {
+          Ok(()) => println!("Session saved to \"{}\"", path.display()),
+          Err(err) => {
+            println!("Error saving session to \"{}\": {err}", path.display())
+          }
+        }
+      }
+      ReplCommand::Load(path) => match std::fs::read_to_string(&path) {
+        Ok(source) => {
+          let output = self.evaluate(&source).await;
+          println!("{output}");
+        }
+        Err(err) => {
+          println!("Error loading \"{}\": {err}", path.display())
+        }
+      },
+      ReplCommand::Transcript(None) => match self.transcript.take() {
+        Some(transcript) => println!(
+          "Stopped recording transcript to \"{}\"",
+          transcript.path.display()
+        ),
+        None => println!("No transcript is being recorded"),
+      },
+      ReplCommand::Transcript(Some(path)) => match std::fs::File::create(&path)
+      {
+        Ok(file) => {
+          println!("Recording transcript to \"{}\"", path.display());
+          self.transcript = Some(Transcript { path, file });
+        }
+        Err(err) => {
+          println!("Error creating transcript \"{}\": {err}", path.display())
+        }
+      },
+    }
+  }
+
+  /// Reads lines until ctrl+d, which are evaluated all at once like the
+  /// `.editor` command of the Node.js REPL. Returns `None` when it's cancelled
+  /// with ctrl+c.
+  async fn read_editor_mode(
+    &mut self,
+  ) -> Result<Option<String>, ReadlineError> {
+    println!("// Entering editor mode (ctrl+d to finish, ctrl+c to cancel)");
+    self.editor.set_editor_mode(true);
+    let mut lines = Vec::new();
+    let result = loop {
+      let line = read_line_and_poll(
+        &mut self.session,
+        &mut self.message_handler,
+        self.editor.clone(),
+      )
+      .await;
+      match line {
+        Ok(line) => lines.push(line),
+        Err(ReadlineError::Eof) => break Ok(Some(lines.join("\n"))),
+        Err(ReadlineError::Interrupted) => break Ok(None),
+        Err(err) => break Err(err),
+      }
+    };
+    self.editor.set_editor_mode(false);
+    result
+  }
 }
 
 async fn read_line_and_poll(
@@ -158,16 +291,20 @@ pub async fn run(flags: Flags, repl_flags: ReplFlags) -> Result<i32, AnyError> {
   let cli_options = factory.cli_options();
   let main_module = cli_options.resolve_main_module()?;
   let permissions = PermissionsContainer::new(Permissions::from_options(
-    &cli_options.permissions_options(),
+    &cli_options.permissions_options()?,
   )?);
   let npm_resolver = factory.npm_resolver().await?.clone();
   let resolver = factory.resolver().await?.clone();
   let file_fetcher = factory.file_fetcher()?;
   let worker_factory = factory.create_cli_main_worker_factory().await?;
+  let maybe_project_dir = cli_options
+    .maybe_config_file_specifier()
+    .and_then(|specifier| specifier_to_file_path(&specifier).ok())
+    .and_then(|path| path.parent().map(ToOwned::to_owned));
   let history_file_path = factory
     .deno_dir()
     .ok()
-    .and_then(|dir| dir.repl_history_file_path());
+    .and_then(|dir| dir.repl_history_file_path(maybe_project_dir.as_deref()));
   let (test_event_sender, test_event_receiver) =
     unbounded_channel::<TestEvent>();
   let test_event_sender = TestEventSender::new(test_event_sender);
@@ -206,6 +343,8 @@ pub async fn run(flags: Flags, repl_flags: ReplFlags) -> Result<i32, AnyError> {
     session,
     editor,
     message_handler: rustyline_channel.1,
+    evaluated_sources: Vec::new(),
+    transcript: None,
   };
 
   if let Some(eval_files) = repl_flags.eval_files {
@@ -198,7 +198,8 @@ impl ReplSession {
     test_event_sender: TestEventSender,
     test_event_receiver: tokio::sync::mpsc::UnboundedReceiver<TestEvent>,
   ) -> Result<Self, AnyError> {
-    let language_server = ReplLanguageServer::new_initialized().await?;
+    let language_server =
+      ReplLanguageServer::new_initialized(cli_options.offline()).await?;
     let mut session = worker.create_inspector_session().await;
 
     worker
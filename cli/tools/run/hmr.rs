@@ -2,8 +2,12 @@
 
 use crate::cdp;
 use crate::emit::Emitter;
+use crate::lsp::parent_process_checker::is_process_active;
+use crate::util::checksum;
 use crate::util::file_watcher::WatcherCommunicator;
 use crate::util::file_watcher::WatcherRestartMode;
+use crate::util::fs::write_file;
+use deno_core::anyhow::bail;
 use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::futures::StreamExt;
@@ -12,10 +16,24 @@ use deno_core::serde_json::{self};
 use deno_core::url::Url;
 use deno_core::LocalInspectorSession;
 use deno_terminal::colors;
+use serde::Deserialize;
+use serde::Serialize;
 use std::collections::HashMap;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::io::BufReader;
+use tokio::net::TcpListener;
+use tokio::net::TcpStream;
 use tokio::select;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// How long a connection of the control socket may take to send its request.
+const CONTROL_SOCKET_TIMEOUT: Duration = Duration::from_secs(1);
 
 fn explain(status: &cdp::Status) -> &'static str {
   match status {
@@ -54,11 +72,20 @@ fn should_retry(status: &cdp::Status) -> bool {
 /// can refuse to perform hot replacement, eg. a top-level variable/function
 /// of an ES module cannot be hot-replaced. In such situation the runner will
 /// force a full restart of a program by notifying the `FileWatcher`.
+///
+/// The runner also listens on a `HmrControlSocket`, so the language server
+/// can replace the modules as soon as they are saved in the editor.
 pub struct HmrRunner {
   session: LocalInspectorSession,
   watcher_communicator: Arc<WatcherCommunicator>,
   script_ids: HashMap<String, String>,
+  /// The checksums of the sources last set for the scripts, so a module
+  /// reloaded through the control socket isn't replaced again when the file
+  /// watcher notices the change.
+  script_checksums: HashMap<String, String>,
   emitter: Arc<Emitter>,
+  maybe_control_dir: Option<PathBuf>,
+  maybe_control_socket: Option<HmrControlSocket>,
 }
 
 impl HmrRunner {
@@ -66,17 +93,30 @@ impl HmrRunner {
     emitter: Arc<Emitter>,
     session: LocalInspectorSession,
     watcher_communicator: Arc<WatcherCommunicator>,
+    maybe_control_dir: Option<PathBuf>,
   ) -> Self {
     Self {
       session,
       emitter,
       watcher_communicator,
       script_ids: HashMap::new(),
+      script_checksums: HashMap::new(),
+      maybe_control_dir,
+      maybe_control_socket: None,
     }
   }
 
   // TODO(bartlomieju): this code is duplicated in `cli/tools/coverage/mod.rs`
   pub async fn start(&mut self) -> Result<(), AnyError> {
+    if let Some(control_dir) = &self.maybe_control_dir {
+      // the file watcher still works without the control socket
+      match HmrControlSocket::bind(control_dir).await {
+        Ok(control_socket) => self.maybe_control_socket = Some(control_socket),
+        Err(err) => {
+          log::debug!("Failed to bind the HMR control socket. {:#}", err)
+        }
+      }
+    }
     self.enable_debugger().await
   }
 
@@ -159,6 +199,82 @@ impl HmrRunner {
     Ok(())
   }
 
+  async fn replace_changed_paths(
+    &mut self,
+    changed_paths: Vec<PathBuf>,
+  ) -> Result<(), AnyError> {
+    let filtered_paths: Vec<PathBuf> = changed_paths
+      .into_iter()
+      .filter(|p| {
+        p.extension().map_or(false, |ext| {
+          let ext_str = ext.to_str().unwrap();
+          matches!(ext_str, "js" | "ts" | "jsx" | "tsx")
+        })
+      })
+      .collect();
+
+    // If after filtering there are no paths it means it's either a file
+    // we can't HMR or an external file that was passed explicitly to
+    // `--unstable-hmr=<file>` path.
+    if filtered_paths.is_empty() {
+      let _ = self.watcher_communicator.force_restart();
+      return Ok(());
+    }
+
+    for path in filtered_paths {
+      let Some(path_str) = path.to_str() else {
+        let _ = self.watcher_communicator.force_restart();
+        continue;
+      };
+      let Ok(module_url) = Url::from_file_path(path_str) else {
+        let _ = self.watcher_communicator.force_restart();
+        continue;
+      };
+
+      let Some(id) = self.script_ids.get(module_url.as_str()).cloned() else {
+        let _ = self.watcher_communicator.force_restart();
+        continue;
+      };
+
+      let source_code = self.emitter.load_and_emit_for_hmr(&module_url).await?;
+      let source_checksum = checksum::gen(&[source_code.as_bytes()]);
+      if self.script_checksums.get(&id) == Some(&source_checksum) {
+        continue; // already replaced
+      }
+
+      let mut tries = 1;
+      loop {
+        let result = self.set_script_source(&id, source_code.as_str()).await?;
+
+        if matches!(result.status, cdp::Status::Ok) {
+          self
+            .script_checksums
+            .insert(id.clone(), source_checksum.clone());
+          self.dispatch_hmr_event(module_url.as_str()).await?;
+          self
+            .watcher_communicator
+            .print(format!("Replaced changed module {}", module_url.as_str()));
+          break;
+        }
+
+        self.watcher_communicator.print(format!(
+          "Failed to reload module {}: {}.",
+          module_url,
+          colors::gray(explain(&result.status))
+        ));
+        if should_retry(&result.status) && tries <= 2 {
+          tries += 1;
+          tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+          continue;
+        }
+
+        let _ = self.watcher_communicator.force_restart();
+        break;
+      }
+    }
+    Ok(())
+  }
+
   pub async fn run(&mut self) -> Result<(), AnyError> {
     self
       .watcher_communicator
@@ -186,69 +302,249 @@ impl HmrRunner {
           }
         }
         changed_paths = self.watcher_communicator.watch_for_changed_paths() => {
-          let changed_paths = changed_paths?;
-
-          let Some(changed_paths) = changed_paths else {
+          let Some(changed_paths) = changed_paths? else {
             let _ = self.watcher_communicator.force_restart();
             continue;
           };
-
-          let filtered_paths: Vec<PathBuf> = changed_paths.into_iter().filter(|p| p.extension().map_or(false, |ext| {
-            let ext_str = ext.to_str().unwrap();
-            matches!(ext_str, "js" | "ts" | "jsx" | "tsx")
-          })).collect();
-
-          // If after filtering there are no paths it means it's either a file
-          // we can't HMR or an external file that was passed explicitly to
-          // `--unstable-hmr=<file>` path.
-          if filtered_paths.is_empty() {
-            let _ = self.watcher_communicator.force_restart();
-            continue;
+          self.replace_changed_paths(changed_paths).await?;
+        }
+        Some(paths) = accept_reload_request(self.maybe_control_socket.as_mut()) => {
+          // only replace the modules of this program, which are requested
+          // for every program running with HMR
+          let paths = paths
+            .into_iter()
+            .filter_map(|path| path.canonicalize().ok())
+            .filter(|path| {
+              Url::from_file_path(path)
+                .map(|url| self.script_ids.contains_key(url.as_str()))
+                .unwrap_or(false)
+            })
+            .collect::<Vec<_>>();
+          if !paths.is_empty() {
+            self.replace_changed_paths(paths).await?;
           }
+        }
+        _ = self.session.receive_from_v8_session() => {}
+      }
+    }
+  }
+}
 
-          for path in filtered_paths {
-            let Some(path_str) = path.to_str() else {
-              let _ = self.watcher_communicator.force_restart();
-              continue;
-            };
-            let Ok(module_url) = Url::from_file_path(path_str) else {
-              let _ = self.watcher_communicator.force_restart();
-              continue;
-            };
-
-            let Some(id) = self.script_ids.get(module_url.as_str()).cloned() else {
-              let _ = self.watcher_communicator.force_restart();
-              continue;
-            };
-
-            let source_code = self.emitter.load_and_emit_for_hmr(
-              &module_url
-            ).await?;
-
-            let mut tries = 1;
-            loop {
-              let result = self.set_script_source(&id, source_code.as_str()).await?;
-
-              if matches!(result.status, cdp::Status::Ok) {
-                self.dispatch_hmr_event(module_url.as_str()).await?;
-                self.watcher_communicator.print(format!("Replaced changed module {}", module_url.as_str()));
-                break;
-              }
+/// The file of a program running with HMR in the control directory, which is
+/// `<DENO_DIR>/hmr/<pid>.json`, so other processes can find its socket. Only
+/// the user can read it, because the token authenticates the requests.
+#[derive(Debug, Serialize, Deserialize)]
+struct HmrControlFile {
+  port: u16,
+  token: String,
+}
 
-              self.watcher_communicator.print(format!("Failed to reload module {}: {}.", module_url, colors::gray(explain(&result.status))));
-              if should_retry(&result.status) && tries <= 2 {
-                tries += 1;
-                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-                continue;
-              }
+/// A request to replace the modules of the paths, which is sent as a line of
+/// JSON to the control socket.
+#[derive(Debug, Serialize, Deserialize)]
+struct ReloadModuleRequest {
+  token: String,
+  paths: Vec<PathBuf>,
+}
 
-              let _ = self.watcher_communicator.force_restart();
-              break;
+/// A socket on the loopback interface of a program running with HMR, which
+/// receives the paths to replace from other processes. The connections are
+/// read in their own tasks, so a connection that doesn't send its request
+/// doesn't hold up the others.
+struct HmrControlSocket {
+  accept_task: JoinHandle<()>,
+  requests_rx: mpsc::UnboundedReceiver<Vec<PathBuf>>,
+  file_path: PathBuf,
+}
+
+impl HmrControlSocket {
+  async fn bind(control_dir: &Path) -> Result<Self, AnyError> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let port = listener.local_addr()?.port();
+    let token = format!("{:032x}", rand::random::<u128>());
+    std::fs::create_dir_all(control_dir)?;
+    let file_path = control_dir.join(format!("{}.json", std::process::id()));
+    write_file(
+      &file_path,
+      serde_json::to_string(&HmrControlFile {
+        port,
+        token: token.clone(),
+      })?,
+      0o600,
+    )?;
+    let (requests_tx, requests_rx) = mpsc::unbounded_channel();
+    let accept_task = tokio::spawn(async move {
+      loop {
+        let stream = match listener.accept().await {
+          Ok((stream, _)) => stream,
+          Err(err) => {
+            log::debug!("Failed to accept an HMR control connection. {}", err);
+            tokio::time::sleep(CONTROL_SOCKET_TIMEOUT).await;
+            continue;
+          }
+        };
+        let requests_tx = requests_tx.clone();
+        let token = token.clone();
+        tokio::spawn(async move {
+          match read_reload_request(stream, &token).await {
+            Ok(paths) => {
+              let _ = requests_tx.send(paths);
             }
+            Err(err) => log::debug!("Invalid HMR control request. {:#}", err),
           }
-        }
-        _ = self.session.receive_from_v8_session() => {}
+        });
       }
+    });
+    Ok(Self {
+      accept_task,
+      requests_rx,
+      file_path,
+    })
+  }
+
+  async fn accept(&mut self) -> Option<Vec<PathBuf>> {
+    self.requests_rx.recv().await
+  }
+}
+
+impl Drop for HmrControlSocket {
+  fn drop(&mut self) {
+    self.accept_task.abort();
+    let _ = std::fs::remove_file(&self.file_path);
+  }
+}
+
+async fn read_reload_request(
+  stream: TcpStream,
+  token: &str,
+) -> Result<Vec<PathBuf>, AnyError> {
+  let mut line = String::new();
+  tokio::time::timeout(
+    CONTROL_SOCKET_TIMEOUT,
+    BufReader::new(stream).read_line(&mut line),
+  )
+  .await??;
+  let request = serde_json::from_str::<ReloadModuleRequest>(&line)?;
+  if request.token != token {
+    bail!("The token of the request doesn't match.");
+  }
+  Ok(request.paths)
+}
+
+/// Waits for the next request of the control socket, or forever when there's
+/// no control socket.
+async fn accept_reload_request(
+  maybe_control_socket: Option<&mut HmrControlSocket>,
+) -> Option<Vec<PathBuf>> {
+  let Some(control_socket) = maybe_control_socket else {
+    return std::future::pending().await;
+  };
+  control_socket.accept().await
+}
+
+/// Requests every program running with HMR to replace the module of the path,
+/// returning how many programs were reached. The files of the programs which
+/// aren't running anymore are removed.
+pub async fn send_reload_module_request(
+  control_dir: &Path,
+  path: &Path,
+) -> usize {
+  let Ok(read_dir) = std::fs::read_dir(control_dir) else {
+    return 0;
+  };
+  let mut count = 0;
+  for entry in read_dir.flatten() {
+    let file_path = entry.path();
+    let Some(pid) = file_path
+      .file_stem()
+      .and_then(|stem| stem.to_str())
+      .and_then(|stem| stem.parse::<u32>().ok())
+    else {
+      continue;
+    };
+    // another process may be listening on the port of a program which
+    // isn't running anymore
+    if !is_process_active(pid) {
+      let _ = std::fs::remove_file(&file_path);
+      continue;
     }
+    let Some(control_file) = std::fs::read_to_string(&file_path)
+      .ok()
+      .and_then(|text| serde_json::from_str::<HmrControlFile>(&text).ok())
+    else {
+      continue;
+    };
+    let mut request = serde_json::to_string(&ReloadModuleRequest {
+      token: control_file.token,
+      paths: vec![path.to_path_buf()],
+    })
+    .unwrap();
+    request.push('\n');
+    let result = tokio::time::timeout(CONTROL_SOCKET_TIMEOUT, async {
+      let mut stream =
+        TcpStream::connect(("127.0.0.1", control_file.port)).await?;
+      stream.write_all(request.as_bytes()).await?;
+      stream.shutdown().await
+    })
+    .await;
+    if let Ok(Ok(())) = result {
+      count += 1;
+    }
+  }
+  count
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use test_util::TempDir;
+
+  #[tokio::test]
+  async fn hmr_control_socket() {
+    let temp_dir = TempDir::new();
+    let control_dir = temp_dir.path().join("hmr");
+    let path = temp_dir.path().join("mod.ts");
+    assert_eq!(
+      send_reload_module_request(control_dir.as_path(), path.as_path()).await,
+      0
+    );
+
+    let mut control_socket =
+      HmrControlSocket::bind(control_dir.as_path()).await.unwrap();
+    let control_file = std::fs::read_dir(control_dir.as_path())
+      .unwrap()
+      .next()
+      .unwrap()
+      .unwrap()
+      .path();
+    let control_file = serde_json::from_str::<HmrControlFile>(
+      &std::fs::read_to_string(control_file).unwrap(),
+    )
+    .unwrap();
+    // an idle connection doesn't hold up the requests, and the requests with
+    // another token are ignored
+    let _idle_stream = TcpStream::connect(("127.0.0.1", control_file.port))
+      .await
+      .unwrap();
+    let mut stream = TcpStream::connect(("127.0.0.1", control_file.port))
+      .await
+      .unwrap();
+    stream
+      .write_all(b"{\"token\":\"invalid\",\"paths\":[\"/invalid.ts\"]}\n")
+      .await
+      .unwrap();
+    stream.shutdown().await.unwrap();
+    assert_eq!(
+      send_reload_module_request(control_dir.as_path(), path.as_path()).await,
+      1
+    );
+    assert_eq!(
+      accept_reload_request(Some(&mut control_socket)).await,
+      Some(vec![path.to_path_buf()])
+    );
+
+    // the file is removed with the socket
+    drop(control_socket);
+    assert_eq!(std::fs::read_dir(control_dir.as_path()).unwrap().count(), 0);
   }
 }
@@ -0,0 +1,133 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+// The error overlay of `deno serve --dev`, which is included in the module
+// serving the default export of the main module. The stacks of errors are
+// already source mapped, so their locations are the ones of the local files.
+
+const OVERLAY_CONTEXT_LINES = 3;
+const OVERLAY_MAX_FRAMES = 5;
+
+function escapeHtml(text) {
+  return String(text)
+    .replaceAll("&", "&amp;")
+    .replaceAll("<", "&lt;")
+    .replaceAll(">", "&gt;")
+    .replaceAll('"', "&quot;")
+    .replaceAll("'", "&#39;");
+}
+
+/** The frames of a stack in local files, like `file:///app/main.ts:4:11`. */
+function parseLocalFrames(stack) {
+  const frames = [];
+  const seen = new Set();
+  for (const match of stack.matchAll(/(file:\/\/[^\s()]+):(\d+):(\d+)/g)) {
+    const [location, url, line, column] = match;
+    if (seen.has(location) || url.includes("/node_modules/")) {
+      continue;
+    }
+    seen.add(location);
+    // the pathname of a URL like `file:///C:/app/main.ts` keeps its slash
+    const path = decodeURIComponent(new URL(url).pathname);
+    frames.push({ path, line: Number(line), column: Number(column) });
+  }
+  return frames.slice(0, OVERLAY_MAX_FRAMES);
+}
+
+/**
+ * The lines around the line of a frame, which are only read when the read
+ * permission is already granted, so that the overlay doesn't prompt for it.
+ */
+function readCodeFrame(frame) {
+  const path = /^\/[A-Za-z]:\//.test(frame.path)
+    ? frame.path.slice(1)
+    : frame.path;
+  try {
+    if (Deno.permissions.querySync({ name: "read", path }).state !== "granted") {
+      return null;
+    }
+    const lines = Deno.readTextFileSync(path).split("\n");
+    const start = Math.max(frame.line - 1 - OVERLAY_CONTEXT_LINES, 0);
+    const end = Math.min(frame.line + OVERLAY_CONTEXT_LINES, lines.length);
+    return lines.slice(start, end).map((text, index) => ({
+      number: start + index + 1,
+      text: text.replace(/\r$/, ""),
+    }));
+  } catch {
+    return null;
+  }
+}
+
+function renderFrame(frame, editorUrl) {
+  const location = `${frame.path}:${frame.line}:${frame.column}`;
+  const link = editorUrl
+    .replaceAll("{path}", encodeURI(frame.path))
+    .replaceAll("{line}", String(frame.line))
+    .replaceAll("{column}", String(frame.column));
+  const lines = readCodeFrame(frame);
+  let code = "";
+  if (lines !== null) {
+    const width = String(lines[lines.length - 1].number).length;
+    code = `<pre>${
+      lines.map((line) => {
+        const number = String(line.number).padStart(width);
+        const className = line.number === frame.line ? "line current" : "line";
+        return `<span class="${className}">${number} | ${
+          escapeHtml(line.text)
+        }</span>`;
+      }).join("\n")
+    }</pre>`;
+  }
+  return `<section><a href="${escapeHtml(link)}">${
+    escapeHtml(location)
+  }</a>${code}</section>`;
+}
+
+/**
+ * The response for an error which is thrown by the handler, which is an HTML
+ * page for browsers and the plain stack for other clients.
+ */
+function renderErrorOverlay(error, request, editorUrl) {
+  const stack = error instanceof Error
+    ? error.stack ?? `${error.name}: ${error.message}`
+    : `Uncaught ${Deno.inspect(error)}`;
+  const accept = request.headers.get("accept") ?? "";
+  if (!accept.includes("text/html")) {
+    return new Response(`${stack}\n`, {
+      status: 500,
+      headers: { "content-type": "text/plain; charset=utf-8" },
+    });
+  }
+  const title = error instanceof Error
+    ? `${error.name}: ${error.message}`
+    : stack;
+  const frames = parseLocalFrames(stack)
+    .map((frame) => renderFrame(frame, editorUrl))
+    .join("\n");
+  const html = `<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>${escapeHtml(title)}</title>
+<style>
+body { margin: 0; padding: 2rem; background: #1e1e1e; color: #d4d4d4; font-family: ui-monospace, monospace; }
+h1 { color: #f48771; font-size: 1.25rem; white-space: pre-wrap; }
+a { color: #4fc1ff; }
+section { margin: 1.5rem 0; }
+pre { margin: 0.5rem 0; padding: 1rem; background: #252526; overflow-x: auto; }
+.line.current { display: inline-block; width: 100%; background: #5a1d1d; }
+details pre { color: #9d9d9d; }
+</style>
+</head>
+<body>
+<h1>${escapeHtml(title)}</h1>
+<p>${escapeHtml(request.method)} ${escapeHtml(request.url)}</p>
+${frames}
+<details open><summary>Stack</summary><pre>${escapeHtml(stack)}</pre></details>
+</body>
+</html>
+`;
+  return new Response(html, {
+    status: 500,
+    headers: { "content-type": "text/html; charset=utf-8" },
+  });
+}
@@ -1,38 +1,56 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use crate::args::parse_tasks_config;
 use crate::args::CliOptions;
 use crate::args::Flags;
+use crate::args::TaskDefinition;
 use crate::args::TaskFlags;
 use crate::colors;
 use crate::factory::CliFactory;
 use crate::npm::CliNpmResolver;
 use crate::npm::InnerCliNpmResolverRef;
 use crate::npm::ManagedCliNpmResolver;
+use crate::util::file_watcher;
+use crate::util::file_watcher::WatcherRestartMode;
 use crate::util::fs::canonicalize_path;
+use deno_config::glob::PathOrPattern;
+use deno_config::glob::PathOrPatternSet;
+use deno_config::WorkspaceMemberConfig;
 use deno_core::anyhow::bail;
 use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
 use deno_core::futures;
 use deno_core::futures::future::LocalBoxFuture;
+use deno_core::futures::stream::FuturesUnordered;
+use deno_core::futures::FutureExt;
+use deno_core::futures::StreamExt;
 use deno_runtime::deno_node::NodeResolver;
+use deno_semver::jsr::JsrPackageReqReference;
 use deno_semver::package::PackageNv;
+use deno_task_shell::parser::SequentialList;
 use deno_task_shell::ExecuteResult;
 use deno_task_shell::ShellCommand;
 use deno_task_shell::ShellCommandContext;
+use deno_task_shell::ShellPipeReader;
+use deno_task_shell::ShellPipeWriter;
+use deno_task_shell::ShellState;
 use indexmap::IndexMap;
 use lazy_regex::Lazy;
 use regex::Regex;
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::io::Write;
 use std::path::Path;
 use std::path::PathBuf;
 use std::rc::Rc;
 use tokio::task::LocalSet;
+use tokio_util::sync::CancellationToken;
 
 pub async fn execute_script(
   flags: Flags,
   task_flags: TaskFlags,
 ) -> Result<i32, AnyError> {
-  let factory = CliFactory::from_flags(flags).await?;
+  let factory = CliFactory::from_flags(flags.clone()).await?;
   let cli_options = factory.cli_options();
   let tasks_config = cli_options.resolve_tasks_config()?;
   let maybe_package_json = cli_options.maybe_package_json();
@@ -49,27 +67,32 @@ pub async fn execute_script(
     }
   };
 
-  if let Some(script) = tasks_config.get(task_name) {
-    let config_file_url = cli_options.maybe_config_file_specifier().unwrap();
-    let config_file_path = if config_file_url.scheme() == "file" {
-      config_file_url.to_file_path().unwrap()
-    } else {
-      bail!("Only local configuration files are supported")
-    };
+  if task_flags.recursive {
+    return execute_workspace_task(
+      &factory,
+      task_name,
+      task_flags.filter.as_deref(),
+    )
+    .await;
+  }
+
+  if let Some(task) = tasks_config.get(task_name) {
+    if !task.watch.is_empty() && !task_flags.no_watch {
+      return watch_deno_json_task(flags, task_flags).await;
+    }
+    let config_file_path = local_config_file_path(cli_options)?;
     let cwd = match task_flags.cwd {
       Some(path) => canonicalize_path(&PathBuf::from(path))?,
       None => config_file_path.parent().unwrap().to_owned(),
     };
-    let script = get_script_with_args(script, cli_options);
-    output_task(task_name, &script);
-    let seq_list = deno_task_shell::parser::parse(&script)
-      .with_context(|| format!("Error parsing script '{task_name}'."))?;
-    let env_vars = collect_env_vars();
-    let local = LocalSet::new();
-    let future =
-      deno_task_shell::execute(seq_list, env_vars, &cwd, Default::default());
-    let exit_code = local.run_until(future).await;
-    Ok(exit_code)
+    execute_deno_json_task(
+      &tasks_config,
+      task_name,
+      cli_options,
+      &cwd,
+      &CancellationToken::new(),
+    )
+    .await
   } else if package_json_scripts.contains_key(task_name) {
     let package_json_deps_provider = factory.package_json_deps_provider();
     let npm_resolver = factory.npm_resolver().await?;
@@ -120,8 +143,7 @@ pub async fn execute_script(
       if let Some(script) = package_json_scripts.get(&task_name) {
         let script = get_script_with_args(script, cli_options);
         output_task(&task_name, &script);
-        let seq_list = deno_task_shell::parser::parse(&script)
-          .with_context(|| format!("Error parsing script '{task_name}'."))?;
+        let seq_list = parse_script(&task_name, &script)?;
         let npx_commands = match npm_resolver.as_inner() {
           InnerCliNpmResolverRef::Managed(npm_resolver) => {
             resolve_npm_commands(npm_resolver, node_resolver)?
@@ -154,6 +176,590 @@ pub async fn execute_script(
   }
 }
 
+fn local_config_file_path(
+  cli_options: &CliOptions,
+) -> Result<PathBuf, AnyError> {
+  let config_file_url = cli_options.maybe_config_file_specifier().unwrap();
+  if config_file_url.scheme() == "file" {
+    Ok(config_file_url.to_file_path().unwrap())
+  } else {
+    bail!("Only local configuration files are supported")
+  }
+}
+
+/// Run a task with its dependencies. The processes of the task are killed
+/// when the token is cancelled.
+async fn execute_deno_json_task(
+  tasks_config: &IndexMap<String, TaskDefinition>,
+  task_name: &str,
+  cli_options: &CliOptions,
+  cwd: &Path,
+  cancellation_token: &CancellationToken,
+) -> Result<i32, AnyError> {
+  let task_names = resolve_task_order(tasks_config, task_name)?;
+  let env_vars = collect_env_vars();
+  let local = LocalSet::new();
+  if task_names.len() > 1 {
+    let future = run_task_graph(
+      tasks_config,
+      task_name,
+      &task_names,
+      cli_options,
+      cwd,
+      env_vars,
+      cancellation_token,
+    );
+    return local.run_until(future).await;
+  }
+  let script =
+    get_script_with_args(&tasks_config[task_name].command, cli_options);
+  output_task(task_name, &script);
+  let seq_list = parse_script(task_name, &script)?;
+  let state = ShellState::new(env_vars, cwd, Default::default());
+  let shell_token = state.token().clone();
+  let future = deno_task_shell::execute_with_pipes(
+    seq_list,
+    state,
+    ShellPipeReader::stdin(),
+    ShellPipeWriter::stdout(),
+    ShellPipeWriter::stderr(),
+  );
+  let exit_code = local
+    .run_until(async {
+      tokio::pin!(future);
+      tokio::select! {
+        exit_code = &mut future => exit_code,
+        _ = cancellation_token.cancelled() => {
+          // cancelling the shell kills the processes of its commands
+          shell_token.cancel();
+          future.await
+        }
+      }
+    })
+    .await;
+  Ok(exit_code)
+}
+
+/// Run a task which declares files to watch and restart it when one of them
+/// or the configuration file changes. The directories of the globs are
+/// watched, so files created later restart the task too, and the processes
+/// of the task are killed before it restarts.
+async fn watch_deno_json_task(
+  flags: Flags,
+  task_flags: TaskFlags,
+) -> Result<i32, AnyError> {
+  file_watcher::watch_func(
+    flags,
+    file_watcher::PrintConfig::new("Task", true),
+    move |flags, watcher_communicator, _changed_paths| {
+      let task_flags = task_flags.clone();
+      Ok(async move {
+        // the task is only restarted for the changes of the watched files
+        watcher_communicator.change_restart_mode(WatcherRestartMode::Manual);
+        let factory = CliFactory::from_flags(flags).await?;
+        let cli_options = factory.cli_options();
+        let tasks_config = cli_options.resolve_tasks_config()?;
+        let task_name = task_flags.task.as_ref().unwrap();
+        let Some(task) = tasks_config.get(task_name) else {
+          bail!("Task not found: {task_name}");
+        };
+        let config_file_path =
+          canonicalize_path(&local_config_file_path(cli_options)?)?;
+        let config_dir = config_file_path.parent().unwrap();
+        let mut watch_patterns =
+          PathOrPatternSet::from_relative_path_or_patterns(
+            config_dir,
+            &task.watch,
+          )
+          .with_context(|| {
+            format!("Invalid \"watch\" globs of task '{task_name}'")
+          })?
+          .into_path_or_patterns();
+        watch_patterns.push(PathOrPattern::Path(config_file_path.clone()));
+        let watch_patterns = PathOrPatternSet::new(watch_patterns);
+        let _ = watcher_communicator.watch_paths(task_watch_paths(&watch_patterns));
+
+        let cwd = match &task_flags.cwd {
+          Some(path) => canonicalize_path(&PathBuf::from(path))?,
+          None => config_dir.to_owned(),
+        };
+        let cancellation_token = CancellationToken::new();
+        let task_future = execute_deno_json_task(
+          &tasks_config,
+          task_name,
+          cli_options,
+          &cwd,
+          &cancellation_token,
+        );
+        tokio::pin!(task_future);
+        let mut is_running = true;
+        loop {
+          tokio::select! {
+            result = &mut task_future, if is_running => {
+              is_running = false;
+              let message = match result {
+                Ok(0) => "finished",
+                Ok(exit_code) => {
+                  log::error!(
+                    "{} Task '{}' failed with exit code {}",
+                    colors::red_bold("error:"),
+                    task_name,
+                    exit_code
+                  );
+                  "failed"
+                }
+                Err(err) => {
+                  log::error!("{}: {:#}", colors::red_bold("error"), err);
+                  "failed"
+                }
+              };
+              watcher_communicator
+                .print(format!("Task {}. Restarting on file change...", message));
+            }
+            changed_paths = watcher_communicator.watch_for_changed_paths() => {
+              let Some(changed_paths) = changed_paths? else {
+                continue;
+              };
+              if !changed_paths
+                .iter()
+                .any(|path| watch_patterns.matches_path(path))
+              {
+                continue;
+              }
+              if is_running {
+                cancellation_token.cancel();
+                let _ = (&mut task_future).await;
+              }
+              watcher_communicator.force_restart()?;
+              // the watcher drops this future when it restarts
+              std::future::pending::<()>().await;
+            }
+          }
+        }
+      })
+    },
+  )
+  .await?;
+  Ok(0)
+}
+
+/// Gets the paths to watch for the globs of a task. These are the bases of
+/// the globs, or their closest existing ancestors when they don't exist yet.
+fn task_watch_paths(watch_patterns: &PathOrPatternSet) -> Vec<PathBuf> {
+  watch_patterns
+    .base_paths()
+    .into_iter()
+    .filter_map(|path| {
+      path
+        .ancestors()
+        .find(|ancestor| ancestor.exists())
+        .map(|ancestor| ancestor.to_path_buf())
+    })
+    .collect()
+}
+
+/// Run a task in every workspace member which defines it and matches the
+/// filter. The members run one after another, each in its own directory and
+/// after the members which its import map depends on.
+async fn execute_workspace_task(
+  factory: &CliFactory,
+  task_name: &str,
+  maybe_filter: Option<&str>,
+) -> Result<i32, AnyError> {
+  let cli_options = factory.cli_options();
+  let Some(workspace_config) = cli_options.maybe_workspace_config() else {
+    bail!(
+      "'--recursive' and '--filter' require a configuration file with \"workspaces\""
+    );
+  };
+  let filter = maybe_filter
+    .map(|filter| {
+      glob::Pattern::new(filter)
+        .with_context(|| format!("Invalid filter '{}'", filter))
+    })
+    .transpose()?;
+  let config_file_path = local_config_file_path(cli_options)?;
+  let root_dir = config_file_path.parent().unwrap();
+
+  let mut member_tasks = HashMap::new();
+  for (index, member) in workspace_config.members.iter().enumerate() {
+    let member_dir = member
+      .config_file
+      .specifier
+      .to_file_path()
+      .ok()
+      .and_then(|path| path.parent().map(|p| p.to_path_buf()))
+      .unwrap_or_else(|| member.path.clone());
+    if let Some(filter) = &filter {
+      let relative_dir = member_dir
+        .strip_prefix(root_dir)
+        .map(|dir| dir.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_else(|_| member.member_name.clone());
+      if !filter.matches(&member.package_name) && !filter.matches(&relative_dir)
+      {
+        continue;
+      }
+    }
+    let tasks_config =
+      parse_tasks_config(member.config_file.json.tasks.as_ref()).with_context(
+        || format!("Failed to resolve the tasks of '{}'", member.package_name),
+      )?;
+    if tasks_config.contains_key(task_name) {
+      member_tasks.insert(index, (member_dir, tasks_config));
+    }
+  }
+  if member_tasks.is_empty() {
+    eprintln!("Task not found in any workspace member: {task_name}");
+    return Ok(1);
+  }
+
+  let member_order = resolve_member_order(
+    &workspace_config
+      .members
+      .iter()
+      .map(|member| {
+        (
+          member.package_name.as_str(),
+          member_dependency_names(member),
+        )
+      })
+      .collect::<Vec<_>>(),
+  )?;
+  for index in member_order {
+    let Some((member_dir, tasks_config)) = member_tasks.remove(&index) else {
+      continue;
+    };
+    let package_name = &workspace_config.members[index].package_name;
+    log::info!(
+      "{} {}",
+      colors::green("Member"),
+      colors::cyan(&package_name)
+    );
+    let exit_code = execute_deno_json_task(
+      &tasks_config,
+      task_name,
+      cli_options,
+      &member_dir,
+      &CancellationToken::new(),
+    )
+    .await?;
+    if exit_code != 0 {
+      log::error!(
+        "{} Task '{}' failed in '{}' with exit code {}",
+        colors::red_bold("error:"),
+        task_name,
+        package_name,
+        exit_code
+      );
+      return Ok(exit_code);
+    }
+  }
+  Ok(0)
+}
+
+/// Get the names of the packages which a workspace member depends on, which
+/// are the jsr packages of the values of its import map.
+fn member_dependency_names(member: &WorkspaceMemberConfig) -> Vec<String> {
+  let json = &member.config_file.json;
+  let imports = json
+    .imports
+    .iter()
+    .filter_map(|imports| imports.as_object())
+    .flat_map(|imports| imports.values());
+  let scopes = json
+    .scopes
+    .iter()
+    .filter_map(|scopes| scopes.as_object())
+    .flat_map(|scopes| scopes.values())
+    .filter_map(|scope| scope.as_object())
+    .flat_map(|scope| scope.values());
+  imports
+    .chain(scopes)
+    .filter_map(|value| value.as_str())
+    .filter_map(|value| JsrPackageReqReference::from_str(value).ok())
+    .map(|req_ref| req_ref.req().name.clone())
+    .collect()
+}
+
+/// Get the indexes of the workspace members, given as their package names
+/// and the names of the packages which they depend on, ordered so that every
+/// member comes after the members which it depends on. Otherwise the members
+/// keep the order of the workspace.
+fn resolve_member_order(
+  members: &[(&str, Vec<String>)],
+) -> Result<Vec<usize>, AnyError> {
+  fn visit(
+    members: &[(&str, Vec<String>)],
+    index: usize,
+    path: &mut Vec<usize>,
+    order: &mut Vec<usize>,
+  ) -> Result<(), AnyError> {
+    if order.contains(&index) {
+      return Ok(());
+    }
+    if let Some(position) = path.iter().position(|i| *i == index) {
+      bail!(
+        "Workspace member '{}' can't be ordered because of a cyclic dependency: {} -> {}",
+        members[index].0,
+        path[position..]
+          .iter()
+          .map(|i| members[*i].0)
+          .collect::<Vec<_>>()
+          .join(" -> "),
+        members[index].0
+      );
+    }
+    path.push(index);
+    for dependency_name in &members[index].1 {
+      for (dependency_index, (package_name, _)) in members.iter().enumerate() {
+        if !package_name.is_empty()
+          && package_name == dependency_name
+          && dependency_index != index
+        {
+          visit(members, dependency_index, path, order)?;
+        }
+      }
+    }
+    path.pop();
+    order.push(index);
+    Ok(())
+  }
+
+  let mut order = Vec::with_capacity(members.len());
+  for index in 0..members.len() {
+    visit(members, index, &mut Vec::new(), &mut order)?;
+  }
+  Ok(order)
+}
+
+/// Get the task and the tasks which it transitively depends on, ordered so
+/// that every task comes after its dependencies.
+fn resolve_task_order(
+  tasks_config: &IndexMap<String, TaskDefinition>,
+  task_name: &str,
+) -> Result<Vec<String>, AnyError> {
+  fn visit(
+    tasks_config: &IndexMap<String, TaskDefinition>,
+    task_name: &str,
+    path: &mut Vec<String>,
+    order: &mut Vec<String>,
+  ) -> Result<(), AnyError> {
+    if order.iter().any(|name| name == task_name) {
+      return Ok(());
+    }
+    if let Some(index) = path.iter().position(|name| name == task_name) {
+      bail!(
+        "Task '{}' has a cyclic dependency: {} -> {}",
+        task_name,
+        path[index..].join(" -> "),
+        task_name
+      );
+    }
+    let Some(task) = tasks_config.get(task_name) else {
+      bail!(
+        "Task '{}' depends on unknown task '{}'",
+        path.last().unwrap(),
+        task_name
+      );
+    };
+    if task.command.trim().is_empty() {
+      bail!("Task '{}' has an empty command", task_name);
+    }
+    path.push(task_name.to_string());
+    for dependency in &task.depends_on {
+      visit(tasks_config, dependency, path, order)?;
+    }
+    path.pop();
+    order.push(task_name.to_string());
+    Ok(())
+  }
+
+  let mut order = Vec::new();
+  visit(tasks_config, task_name, &mut Vec::new(), &mut order)?;
+  Ok(order)
+}
+
+/// Run a task with its dependencies, starting every task as soon as the
+/// tasks it depends on succeeded, so independent tasks run in parallel.
+/// When a task fails, no more tasks are started and the processes of the
+/// running ones are killed.
+async fn run_task_graph(
+  tasks_config: &IndexMap<String, TaskDefinition>,
+  task_name: &str,
+  task_names: &[String],
+  cli_options: &CliOptions,
+  cwd: &Path,
+  env_vars: HashMap<String, String>,
+  cancellation_token: &CancellationToken,
+) -> Result<i32, AnyError> {
+  let prefix_width = task_names.iter().map(|name| name.len()).max().unwrap();
+  let mut pending = IndexMap::new();
+  for (index, name) in task_names.iter().enumerate() {
+    let command = &tasks_config[name].command;
+    // additional arguments only apply to the task which was asked for
+    let script = if name == task_name {
+      get_script_with_args(command, cli_options)
+    } else {
+      command.trim().to_string()
+    };
+    let seq_list = parse_script(name, &script)?;
+    let prefix = task_output_prefix(index, name, prefix_width);
+    pending.insert(name.clone(), (script, seq_list, prefix));
+  }
+
+  let mut completed = HashSet::new();
+  let mut running = FuturesUnordered::new();
+  let mut running_tokens = Vec::new();
+  loop {
+    let ready = pending
+      .keys()
+      .filter(|name| {
+        tasks_config[*name]
+          .depends_on
+          .iter()
+          .all(|dependency| completed.contains(dependency))
+      })
+      .cloned()
+      .collect::<Vec<_>>();
+    for name in ready {
+      let (script, seq_list, prefix) = pending.shift_remove(&name).unwrap();
+      output_task(&name, &script);
+      let state = ShellState::new(env_vars.clone(), cwd, Default::default());
+      running_tokens.push(state.token().clone());
+      let future = run_prefixed_task(seq_list, prefix, state);
+      running.push(future.map(move |exit_code| (name, exit_code)));
+    }
+    let maybe_next = tokio::select! {
+      next = running.next() => Some(next),
+      _ = cancellation_token.cancelled() => None,
+    };
+    let exit_code = match maybe_next {
+      Some(Some((name, 0))) => {
+        completed.insert(name);
+        continue;
+      }
+      Some(Some((name, exit_code))) => {
+        log::error!(
+          "{} Task '{}' failed with exit code {}",
+          colors::red_bold("error:"),
+          name,
+          exit_code
+        );
+        exit_code
+      }
+      Some(None) => return Ok(0),
+      // the tasks were stopped from outside, like by the file watcher
+      None => 1,
+    };
+    // cancelling the other running tasks kills their processes, which
+    // dropping their futures doesn't
+    for token in &running_tokens {
+      token.cancel();
+    }
+    while running.next().await.is_some() {}
+    return Ok(exit_code);
+  }
+}
+
+fn task_output_prefix(index: usize, name: &str, width: usize) -> String {
+  let label = format!("[{}]", name);
+  let label = match index % 5 {
+    0 => colors::cyan(&label).to_string(),
+    1 => colors::magenta(&label).to_string(),
+    2 => colors::yellow(&label).to_string(),
+    3 => colors::green(&label).to_string(),
+    _ => colors::intense_blue(&label).to_string(),
+  };
+  format!("{}{} ", label, " ".repeat(width - name.len()))
+}
+
+async fn run_prefixed_task(
+  seq_list: SequentialList,
+  prefix: String,
+  state: ShellState,
+) -> i32 {
+  let (stdout_reader, stdout_writer) = deno_task_shell::pipe();
+  let (stderr_reader, stderr_writer) = deno_task_shell::pipe();
+  let stdout_handle =
+    spawn_prefixed_output(stdout_reader, prefix.clone(), std::io::stdout());
+  let stderr_handle =
+    spawn_prefixed_output(stderr_reader, prefix, std::io::stderr());
+  let exit_code = deno_task_shell::execute_with_pipes(
+    seq_list,
+    state,
+    ShellPipeReader::stdin(),
+    stdout_writer,
+    stderr_writer,
+  )
+  .await;
+  let _ = stdout_handle.await;
+  let _ = stderr_handle.await;
+  exit_code
+}
+
+fn spawn_prefixed_output(
+  reader: ShellPipeReader,
+  prefix: String,
+  writer: impl Write + Send + 'static,
+) -> tokio::task::JoinHandle<()> {
+  tokio::task::spawn_blocking(move || {
+    let mut writer = PrefixedWriter::new(prefix, writer);
+    let _ = reader.pipe_to(&mut writer);
+    let _ = writer.finish();
+  })
+}
+
+/// Writes the output of a task line by line, with the name of the task in
+/// front of every line. Whole lines are written at once, so the lines of
+/// tasks which run at the same time don't get mixed up.
+struct PrefixedWriter<W: Write> {
+  prefix: String,
+  inner: W,
+  line: Vec<u8>,
+}
+
+impl<W: Write> PrefixedWriter<W> {
+  fn new(prefix: String, inner: W) -> Self {
+    Self {
+      prefix,
+      inner,
+      line: Vec::new(),
+    }
+  }
+
+  /// Write the last line, if it wasn't terminated by a newline.
+  fn finish(&mut self) -> std::io::Result<()> {
+    if !self.line.is_empty() {
+      self.line.push(b'\n');
+      self.write_line()?;
+    }
+    self.inner.flush()
+  }
+
+  fn write_line(&mut self) -> std::io::Result<()> {
+    let mut output = Vec::with_capacity(self.prefix.len() + self.line.len());
+    output.extend_from_slice(self.prefix.as_bytes());
+    output.append(&mut self.line);
+    self.inner.write_all(&output)
+  }
+}
+
+impl<W: Write> Write for PrefixedWriter<W> {
+  fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+    for &byte in buf {
+      self.line.push(byte);
+      if byte == b'\n' {
+        self.write_line()?;
+      }
+    }
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> std::io::Result<()> {
+    self.inner.flush()
+  }
+}
+
 fn get_script_with_args(script: &str, options: &CliOptions) -> String {
   let additional_args = options
     .argv()
@@ -167,6 +773,107 @@ fn get_script_with_args(script: &str, options: &CliOptions) -> String {
   script.trim().to_owned()
 }
 
+/// Parse the script of a task, after expanding its `~` to the home directory,
+/// which deno_task_shell doesn't do on its own.
+fn parse_script(
+  task_name: &str,
+  script: &str,
+) -> Result<SequentialList, AnyError> {
+  let home_var = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+  let script = match std::env::var(home_var) {
+    Ok(home_dir) if !home_dir.is_empty() => expand_tilde(script, &home_dir),
+    _ => script.to_string(),
+  };
+  deno_task_shell::parser::parse(&script)
+    .with_context(|| format!("Error parsing script '{task_name}'."))
+}
+
+/// Expand a `~` to the home directory, like a POSIX shell does, when it's a
+/// word on its own or the start of a path, including the values of
+/// assignments like `export DIR=~/dir` and `PATH=~/bin:$PATH`. A quoted or
+/// escaped `~` and a `~user` are left as is.
+fn expand_tilde(script: &str, home_dir: &str) -> String {
+  fn is_assignment(word: &str) -> bool {
+    let Some((name, _)) = word.split_once('=') else {
+      return false;
+    };
+    let mut chars = name.chars();
+    chars
+      .next()
+      .map(|c| c.is_ascii_alphabetic() || c == '_')
+      .unwrap_or(false)
+      && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+  }
+
+  fn is_word_end(c: char) -> bool {
+    c.is_whitespace() || matches!(c, ';' | '|' | '&' | '(' | ')')
+  }
+
+  // quote the home directory, so it stays a single word and its backslashes
+  // on Windows aren't treated as escapes
+  let quoted_home_dir = format!("'{}'", home_dir.replace('\'', r#"'"'"'"#));
+  let mut result = String::with_capacity(script.len());
+  // the text of the current word up to the current char
+  let mut word = String::new();
+  let mut quote = None;
+  let mut chars = script.chars().peekable();
+  while let Some(c) = chars.next() {
+    if let Some(quote_char) = quote {
+      if c == quote_char {
+        quote = None;
+      } else if c == '\\' && quote_char == '"' {
+        if let Some(next) = chars.next() {
+          result.push(c);
+          word.push(c);
+          result.push(next);
+          word.push(next);
+          continue;
+        }
+      }
+    } else {
+      match c {
+        '\'' | '"' => quote = Some(c),
+        '\\' => {
+          if let Some(next) = chars.next() {
+            result.push(c);
+            word.push(c);
+            result.push(next);
+            word.push(next);
+            continue;
+          }
+        }
+        '~' => {
+          let is_assignment = is_assignment(&word);
+          let is_start = word.is_empty()
+            || (is_assignment && (word.ends_with('=') || word.ends_with(':')));
+          let is_end = match chars.peek() {
+            Some(next) => {
+              *next == '/'
+                || is_word_end(*next)
+                || (is_assignment && *next == ':')
+            }
+            None => true,
+          };
+          if is_start && is_end {
+            result.push_str(&quoted_home_dir);
+            word.push(c);
+            continue;
+          }
+        }
+        c if is_word_end(c) => {
+          result.push(c);
+          word.clear();
+          continue;
+        }
+        _ => {}
+      }
+    }
+    result.push(c);
+    word.push(c);
+  }
+  result
+}
+
 fn output_task(task_name: &str, script: &str) {
   log::info!(
     "{} {} {}",
@@ -222,18 +929,22 @@ fn collect_env_vars() -> HashMap<String, String> {
 
 fn print_available_tasks(
   // order can be important, so these use an index map
-  tasks_config: &IndexMap<String, String>,
+  tasks_config: &IndexMap<String, TaskDefinition>,
   package_json_scripts: &IndexMap<String, String>,
 ) {
   eprintln!("{}", colors::green("Available tasks:"));
 
   let mut had_task = false;
-  for (is_deno, (key, value)) in tasks_config.iter().map(|e| (true, e)).chain(
-    package_json_scripts
-      .iter()
-      .filter(|(key, _)| !tasks_config.contains_key(*key))
-      .map(|e| (false, e)),
-  ) {
+  for (is_deno, (key, value), depends_on) in tasks_config
+    .iter()
+    .map(|(key, task)| (true, (key, &task.command), task.depends_on.as_slice()))
+    .chain(
+      package_json_scripts
+        .iter()
+        .filter(|(key, _)| !tasks_config.contains_key(*key))
+        .map(|e| (false, e, [].as_slice())),
+    )
+  {
     eprintln!(
       "- {}{}",
       colors::cyan(key),
@@ -243,7 +954,15 @@ fn print_available_tasks(
         format!(" {}", colors::italic_gray("(package.json)"))
       }
     );
-    eprintln!("    {value}");
+    if !value.is_empty() {
+      eprintln!("    {value}");
+    }
+    if !depends_on.is_empty() {
+      eprintln!(
+        "    {}",
+        colors::italic_gray(format!("depends on: {}", depends_on.join(", ")))
+      );
+    }
     had_task = true;
   }
   if !had_task {
@@ -442,6 +1161,29 @@ fn resolve_npm_commands(
 mod test {
   use super::*;
 
+  #[test]
+  fn test_resolve_member_order() {
+    let members = [
+      (
+        "@scope/a",
+        vec!["@scope/b".to_string(), "@std/path".to_string()],
+      ),
+      ("@scope/b", vec!["@scope/c".to_string()]),
+      ("@scope/c", vec![]),
+      ("", vec!["@scope/a".to_string()]),
+    ];
+    assert_eq!(resolve_member_order(&members).unwrap(), vec![2, 1, 0, 3]);
+
+    let members = [
+      ("@scope/a", vec!["@scope/b".to_string()]),
+      ("@scope/b", vec!["@scope/a".to_string()]),
+    ];
+    assert_eq!(
+      resolve_member_order(&members).unwrap_err().to_string(),
+      "Workspace member '@scope/a' can't be ordered because of a cyclic dependency: @scope/a -> @scope/b -> @scope/a"
+    );
+  }
+
   #[test]
   fn test_prepend_to_path() {
     let mut env_vars = HashMap::new();
@@ -470,6 +1212,97 @@ mod test {
     );
   }
 
+  #[test]
+  fn test_expand_tilde() {
+    let cases = [
+      ("echo ~", "echo '/home/deno'"),
+      ("cd ~/src && ls", "cd '/home/deno'/src && ls"),
+      ("(cd ~/a)|cat ~", "(cd '/home/deno'/a)|cat '/home/deno'"),
+      ("export DIR=~/dir", "export DIR='/home/deno'/dir"),
+      (
+        "PATH=~/bin:~/.local/bin:$PATH",
+        "PATH='/home/deno'/bin:'/home/deno'/.local/bin:$PATH",
+      ),
+      ("echo $(ls ~)", "echo $(ls '/home/deno')"),
+      ("echo --dir=~/dir", "echo --dir=~/dir"),
+      (
+        "echo '~' \"~/a\" \\~ a~ ~user",
+        "echo '~' \"~/a\" \\~ a~ ~user",
+      ),
+    ];
+    for (script, expected) in cases {
+      assert_eq!(expand_tilde(script, "/home/deno"), expected, "{script}");
+    }
+    assert_eq!(
+      expand_tilde("echo ~", "C:\\Users\\O'Neil"),
+      r#"echo 'C:\Users\O'"'"'Neil'"#
+    );
+  }
+
+  #[test]
+  fn test_resolve_task_order() {
+    let task = |depends_on: &[&str]| TaskDefinition {
+      command: "echo".to_string(),
+      depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+      watch: vec![],
+    };
+    let tasks_config = IndexMap::from([
+      ("build".to_string(), task(&["codegen", "lint"])),
+      ("codegen".to_string(), task(&["fetch"])),
+      ("lint".to_string(), task(&["fetch"])),
+      ("fetch".to_string(), task(&[])),
+      ("a".to_string(), task(&["b"])),
+      ("b".to_string(), task(&["a"])),
+      ("c".to_string(), task(&["unknown"])),
+      (
+        "d".to_string(),
+        TaskDefinition {
+          command: " ".to_string(),
+          depends_on: vec!["fetch".to_string()],
+          watch: vec![],
+        },
+      ),
+    ]);
+    assert_eq!(
+      resolve_task_order(&tasks_config, "build").unwrap(),
+      vec!["fetch", "codegen", "lint", "build"]
+    );
+    assert_eq!(
+      resolve_task_order(&tasks_config, "fetch").unwrap(),
+      vec!["fetch"]
+    );
+    assert_eq!(
+      resolve_task_order(&tasks_config, "a")
+        .unwrap_err()
+        .to_string(),
+      "Task 'a' has a cyclic dependency: a -> b -> a"
+    );
+    assert_eq!(
+      resolve_task_order(&tasks_config, "c")
+        .unwrap_err()
+        .to_string(),
+      "Task 'c' depends on unknown task 'unknown'"
+    );
+    assert_eq!(
+      resolve_task_order(&tasks_config, "d")
+        .unwrap_err()
+        .to_string(),
+      "Task 'd' has an empty command"
+    );
+  }
+
+  #[test]
+  fn test_prefixed_writer() {
+    let mut writer = PrefixedWriter::new("[a] ".to_string(), Vec::new());
+    writer.write_all(b"one\ntw").unwrap();
+    writer.write_all(b"o\nthree").unwrap();
+    writer.finish().unwrap();
+    assert_eq!(
+      String::from_utf8(writer.inner).unwrap(),
+      "[a] one\n[a] two\n[a] three\n"
+    );
+  }
+
   #[test]
   fn test_resolve_execution_path_from_npx_shim() {
     // example shim on unix
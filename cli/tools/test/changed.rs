@@ -0,0 +1,93 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::ModuleSpecifier;
+use deno_graph::ModuleGraph;
+
+use super::TestMode;
+use crate::graph_util::has_graph_root_local_dependent_changed;
+use crate::util::fs::canonicalize_path;
+use crate::util::path::specifier_to_file_path;
+
+fn run_git(cwd: &Path, args: &[&str]) -> Result<String, AnyError> {
+  let output = Command::new("git")
+    .args(args)
+    .current_dir(cwd)
+    .output()
+    .context("Failed to run git, which is required for '--changed'")?;
+  if !output.status.success() {
+    bail!(
+      "'git {}' failed: {}",
+      args.join(" "),
+      String::from_utf8_lossy(&output.stderr).trim()
+    );
+  }
+  Ok(String::from_utf8(output.stdout)?)
+}
+
+/// Get the canonicalized paths of the files which differ from the git ref,
+/// including uncommitted and untracked files. Deleted files are left out.
+pub fn changed_paths_since(
+  git_ref: &str,
+  cwd: &Path,
+) -> Result<HashSet<PathBuf>, AnyError> {
+  let root =
+    PathBuf::from(run_git(cwd, &["rev-parse", "--show-toplevel"])?.trim_end());
+  // the ref is user provided, so it must never be parsed as an option
+  let changed = run_git(
+    &root,
+    &[
+      "diff",
+      "--name-only",
+      "-z",
+      "--end-of-options",
+      git_ref,
+      "--",
+    ],
+  )
+  .with_context(|| format!("Failed to diff against '{}'", git_ref))?;
+  let untracked =
+    run_git(&root, &["ls-files", "--others", "--exclude-standard", "-z"])?;
+  Ok(
+    changed
+      .split('\0')
+      .chain(untracked.split('\0'))
+      .filter(|path| !path.is_empty())
+      .filter_map(|path| canonicalize_path(&root.join(path)).ok())
+      .collect(),
+  )
+}
+
+/// Keep the test modules which are affected by the changed paths, which are
+/// the ones that are changed themselves or that depend on a changed local
+/// module.
+pub fn select_changed(
+  specifiers_with_mode: Vec<(ModuleSpecifier, TestMode)>,
+  graph: &ModuleGraph,
+  changed_paths: &HashSet<PathBuf>,
+) -> Vec<(ModuleSpecifier, TestMode)> {
+  specifiers_with_mode
+    .into_iter()
+    .filter(|(specifier, mode)| {
+      let is_changed = specifier_to_file_path(specifier)
+        .ok()
+        .and_then(|path| canonicalize_path(&path).ok())
+        .map(|path| changed_paths.contains(&path))
+        .unwrap_or(false);
+      is_changed
+        || (*mode != TestMode::Documentation
+          && has_graph_root_local_dependent_changed(
+            graph,
+            specifier,
+            changed_paths,
+          ))
+    })
+    .collect()
+}
@@ -14,7 +14,9 @@ use crate::graph_util::graph_valid_with_cli_options;
 use crate::graph_util::has_graph_root_local_dependent_changed;
 use crate::module_loader::ModuleLoadPreparer;
 use crate::ops;
+use crate::util::file_selection::FileSelection;
 use crate::util::file_watcher;
+use crate::util::fs::canonicalize_path;
 use crate::util::fs::collect_specifiers;
 use crate::util::path::get_extension;
 use crate::util::path::is_script_ext;
@@ -63,6 +65,7 @@ use rand::seq::SliceRandom;
 use rand::SeedableRng;
 use regex::Regex;
 use serde::Deserialize;
+use serde::Serialize;
 use std::collections::BTreeMap;
 use std::collections::BTreeSet;
 use std::collections::HashMap;
@@ -84,19 +87,23 @@ use tokio::signal;
 use tokio::sync::mpsc::unbounded_channel;
 use tokio::sync::mpsc::UnboundedReceiver;
 use tokio::sync::mpsc::UnboundedSender;
-use tokio::sync::mpsc::WeakUnboundedSender;
 
+mod changed;
 pub mod fmt;
 pub mod reporters;
+mod shard;
+pub mod snapshot;
 
 use fmt::format_sanitizer_diff;
 pub use fmt::format_test_error;
+use fmt::to_relative_path_or_remote_url;
 use reporters::CompoundTestReporter;
 use reporters::DotTestReporter;
 use reporters::JunitTestReporter;
 use reporters::PrettyTestReporter;
 use reporters::TapTestReporter;
 use reporters::TestReporter;
+use snapshot::TestSnapshots;
 
 /// The test mode is used to determine how a specifier is to be tested.
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -180,6 +187,7 @@ pub struct TestDescription {
   pub location: TestLocation,
   pub sanitize_ops: bool,
   pub sanitize_resources: bool,
+  pub retry: usize,
 }
 
 /// May represent a failure of a test or test step.
@@ -298,6 +306,9 @@ pub enum TestResult {
   Ignored,
   Failed(TestFailure),
   Cancelled,
+  /// The test failed, but passed on a retry. Holds the number of the attempt
+  /// which passed.
+  Flaky(usize),
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
@@ -331,13 +342,22 @@ pub struct TestPlan {
   pub used_only: bool,
 }
 
+/// The stream of the output of a test, which is also reported to the LSP
+/// clients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TestStdioStream {
+  Stdout,
+  Stderr,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum TestEvent {
   Register(TestDescription),
   Plan(TestPlan),
   Wait(usize),
-  Output(Vec<u8>),
+  Output(TestStdioStream, Vec<u8>),
   Result(usize, TestResult, u64),
   UncaughtError(String, Box<JsError>),
   StepRegister(TestStepDescription),
@@ -353,6 +373,7 @@ pub struct TestSummary {
   pub passed: usize,
   pub failed: usize,
   pub ignored: usize,
+  pub flaky: usize,
   pub passed_steps: usize,
   pub failed_steps: usize,
   pub ignored_steps: usize,
@@ -378,6 +399,8 @@ pub struct TestSpecifierOptions {
   pub shuffle: Option<u64>,
   pub filter: TestFilter,
   pub trace_ops: bool,
+  pub retry: usize,
+  pub update_snapshots: bool,
 }
 
 impl TestSummary {
@@ -387,6 +410,7 @@ impl TestSummary {
       passed: 0,
       failed: 0,
       ignored: 0,
+      flaky: 0,
       passed_steps: 0,
       failed_steps: 0,
       ignored_steps: 0,
@@ -504,6 +528,12 @@ async fn test_specifier_inner(
   worker.execute_side_module_possibly_with_npm().await?;
 
   let mut worker = worker.into_main_worker();
+  worker
+    .js_runtime
+    .op_state()
+    .borrow_mut()
+    .borrow_mut::<TestSnapshots>()
+    .set_update(options.update_snapshots);
 
   // Ensure that there are no pending exceptions before we start running tests
   worker.run_up_to_duration(Duration::from_millis(0)).await?;
@@ -513,6 +543,26 @@ async fn test_specifier_inner(
   run_tests_for_worker(&mut worker, &specifier, &options, &fail_fast_tracker)
     .await?;
 
+  let snapshots = std::mem::take(
+    worker
+      .js_runtime
+      .op_state()
+      .borrow_mut()
+      .borrow_mut::<TestSnapshots>(),
+  );
+  for (path, count) in snapshots.save()? {
+    log::info!(
+      "{} {} {} to {}",
+      colors::green("Wrote"),
+      count,
+      if count == 1 { "snapshot" } else { "snapshots" },
+      to_relative_path_or_remote_url(
+        &Url::from_directory_path(std::env::current_dir()?).unwrap(),
+        Url::from_file_path(path).unwrap().as_str(),
+      )
+    );
+  }
+
   // Ignore `defaultPrevented` of the `beforeunload` event. We don't allow the
   // event loop to continue beyond what's needed to await results.
   worker.dispatch_beforeunload_event(located_script_name!())?;
@@ -573,13 +623,16 @@ pub async fn run_tests_for_worker(
     used_only,
   }))?;
   let mut had_uncaught_error = false;
+  let mut ran_all_tests = unfiltered == tests.len();
   let stats = worker.js_runtime.runtime_activity_stats_factory();
 
-  for (desc, function) in tests {
+  'tests: for (desc, function) in tests {
     if fail_fast_tracker.should_stop() {
+      ran_all_tests = false;
       break;
     }
     if desc.ignore {
+      ran_all_tests = false;
       sender.send(TestEvent::Result(desc.id, TestResult::Ignored, 0))?;
       continue;
     }
@@ -589,77 +642,103 @@ pub async fn run_tests_for_worker(
     }
     sender.send(TestEvent::Wait(desc.id))?;
 
-    // Poll event loop once, to allow all ops that are already resolved, but haven't
-    // responded to settle.
-    // TODO(mmastrac): we should provide an API to poll the event loop until no futher
-    // progress is made.
-    {
-      let waker = noop_waker();
-      let mut cx = Context::from_waker(&waker);
-      let _ = worker
-        .js_runtime
-        .poll_event_loop(&mut cx, PollEventLoopOptions::default());
-    }
+    let max_attempts = desc.retry.max(options.retry) + 1;
+    let mut attempt = 1;
+    let earlier = SystemTime::now();
+    let result = loop {
+      // Poll event loop once, to allow all ops that are already resolved, but haven't
+      // responded to settle.
+      // TODO(mmastrac): we should provide an API to poll the event loop until no futher
+      // progress is made.
+      {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let _ = worker
+          .js_runtime
+          .poll_event_loop(&mut cx, PollEventLoopOptions::default());
+      }
 
-    let mut filter = RuntimeActivityStatsFilter::default();
-    if desc.sanitize_resources {
-      filter = filter.with_resources();
-    }
+      let mut filter = RuntimeActivityStatsFilter::default();
+      if desc.sanitize_resources {
+        filter = filter.with_resources();
+      }
 
-    let before = if !filter.is_empty() {
-      Some(stats.clone().capture(&filter))
-    } else {
-      None
-    };
+      let before = if !filter.is_empty() {
+        Some(stats.clone().capture(&filter))
+      } else {
+        None
+      };
 
-    let earlier = SystemTime::now();
-    let call = worker.js_runtime.call(&function);
-    let result = match worker
-      .js_runtime
-      .with_event_loop_promise(call, PollEventLoopOptions::default())
-      .await
-    {
-      Ok(r) => r,
-      Err(error) => {
-        if error.is::<JsError>() {
-          sender.send(TestEvent::UncaughtError(
-            specifier.to_string(),
-            Box::new(error.downcast::<JsError>().unwrap()),
-          ))?;
-          fail_fast_tracker.add_failure();
-          sender.send(TestEvent::Result(desc.id, TestResult::Cancelled, 0))?;
-          had_uncaught_error = true;
-          continue;
-        } else {
-          return Err(error);
+      let call = worker.js_runtime.call(&function);
+      let result = match worker
+        .js_runtime
+        .with_event_loop_promise(call, PollEventLoopOptions::default())
+        .await
+      {
+        Ok(r) => r,
+        Err(error) => {
+          if error.is::<JsError>() {
+            sender.send(TestEvent::UncaughtError(
+              specifier.to_string(),
+              Box::new(error.downcast::<JsError>().unwrap()),
+            ))?;
+            fail_fast_tracker.add_failure();
+            sender.send(TestEvent::Result(
+              desc.id,
+              TestResult::Cancelled,
+              0,
+            ))?;
+            had_uncaught_error = true;
+            ran_all_tests = false;
+            continue 'tests;
+          } else {
+            return Err(error);
+          }
+        }
+      };
+
+      let mut leaked_resources = None;
+      if let Some(before) = before {
+        let after = stats.clone().capture(&filter);
+        let diff = RuntimeActivityStats::diff(&before, &after);
+        let formatted = format_sanitizer_diff(diff);
+        if !formatted.is_empty() {
+          leaked_resources = Some(formatted);
         }
       }
+      let result = match leaked_resources {
+        Some(formatted) => {
+          TestResult::Failed(TestFailure::LeakedResources(formatted))
+        }
+        None => {
+          let scope = &mut worker.js_runtime.handle_scope();
+          let result = v8::Local::new(scope, result);
+          serde_v8::from_v8::<TestResult>(scope, result)?
+        }
+      };
+      match result {
+        TestResult::Failed(_) if attempt < max_attempts => attempt += 1,
+        TestResult::Ok if attempt > 1 => break TestResult::Flaky(attempt),
+        result => break result,
+      }
     };
-    if let Some(before) = before {
-      let after = stats.clone().capture(&filter);
-      let diff = RuntimeActivityStats::diff(&before, &after);
-      let formatted = format_sanitizer_diff(diff);
-      if !formatted.is_empty() {
-        let failure = TestFailure::LeakedResources(formatted);
-        let elapsed = SystemTime::now().duration_since(earlier)?.as_millis();
-        sender.send(TestEvent::Result(
-          desc.id,
-          TestResult::Failed(failure),
-          elapsed as u64,
-        ))?;
-        continue;
+    // leaked resources don't count towards `--fail-fast`
+    if let TestResult::Failed(failure) = &result {
+      // a failed test might not have asserted all of its snapshots
+      ran_all_tests = false;
+      if !matches!(failure, TestFailure::LeakedResources(_)) {
+        fail_fast_tracker.add_failure();
       }
     }
-
-    let scope = &mut worker.js_runtime.handle_scope();
-    let result = v8::Local::new(scope, result);
-    let result = serde_v8::from_v8::<TestResult>(scope, result)?;
-    if matches!(result, TestResult::Failed(_)) {
-      fail_fast_tracker.add_failure();
-    }
     let elapsed = SystemTime::now().duration_since(earlier)?.as_millis();
     sender.send(TestEvent::Result(desc.id, result, elapsed as u64))?;
   }
+  worker
+    .js_runtime
+    .op_state()
+    .borrow_mut()
+    .borrow_mut::<TestSnapshots>()
+    .set_ran_all_tests(ran_all_tests);
   Ok(())
 }
 
@@ -929,7 +1008,6 @@ async fn test_specifiers(
   };
 
   let (sender, receiver) = unbounded_channel::<TestEvent>();
-  let sender = TestEventSender::new(sender);
   let concurrent_jobs = options.concurrent_jobs;
 
   let sender_ = sender.downgrade();
@@ -940,11 +1018,16 @@ async fn test_specifiers(
   HAS_TEST_RUN_SIGINT_HANDLER.store(true, Ordering::Relaxed);
   let reporter = get_test_reporter(&options);
   let fail_fast_tracker = FailFastTracker::new(options.fail_fast);
+  let sender = Arc::new(Mutex::new(sender));
 
   let join_handles = specifiers.into_iter().map(move |specifier| {
     let worker_factory = worker_factory.clone();
     let permissions = permissions.clone();
-    let sender = sender.clone();
+    // every module gets its own output pipes, so the output of modules which
+    // run in parallel is never attributed to a test of another module
+    let (module_sender, module_receiver) = unbounded_channel::<TestEvent>();
+    spawn_module_event_forwarder(module_receiver, sender.clone());
+    let sender = TestEventSender::new(module_sender);
     let fail_fast_tracker = fail_fast_tracker.clone();
     let specifier_options = options.specifier.clone();
     spawn_blocking(move || {
@@ -975,6 +1058,35 @@ async fn test_specifiers(
   Ok(())
 }
 
+/// Forwards the events of a single test module to the reporter. Output is held
+/// back until the next event of the module and then sent together with it, so
+/// the events of other modules can't end up in between.
+fn spawn_module_event_forwarder(
+  mut receiver: UnboundedReceiver<TestEvent>,
+  sender: Arc<Mutex<UnboundedSender<TestEvent>>>,
+) {
+  spawn(async move {
+    let mut pending_output = Vec::new();
+    while let Some(event) = receiver.recv().await {
+      if let TestEvent::Output(stream, output) = event {
+        pending_output.push((stream, output));
+        continue;
+      }
+      let sender = sender.lock();
+      for (stream, output) in pending_output.drain(..) {
+        let _ = sender.send(TestEvent::Output(stream, output));
+      }
+      if sender.send(event).is_err() {
+        return;
+      }
+    }
+    let sender = sender.lock();
+    for (stream, output) in pending_output {
+      let _ = sender.send(TestEvent::Output(stream, output));
+    }
+  });
+}
+
 /// Gives receiver back in case it was ended with `TestEvent::ForceEndReport`.
 pub async fn report_tests(
   mut receiver: UnboundedReceiver<TestEvent>,
@@ -1010,7 +1122,7 @@ pub async fn report_tests(
           reporter.report_wait(tests.get(&id).unwrap());
         }
       }
-      TestEvent::Output(output) => {
+      TestEvent::Output(_, output) => {
         reporter.report_output(&output);
       }
       TestEvent::Result(id, result, elapsed) => {
@@ -1176,29 +1288,34 @@ fn is_supported_test_ext(path: &Path) -> bool {
 /// - Specifiers matching both predicates are marked as `TestMode::Both`
 fn collect_specifiers_with_test_mode(
   files: FilePatterns,
+  file_selection: &FileSelection,
   include_inline: &bool,
 ) -> Result<Vec<(ModuleSpecifier, TestMode)>, AnyError> {
   // todo(dsherret): there's no need to collect twice as it's slow
-  let module_specifiers =
-    collect_specifiers(files.clone(), is_supported_test_path_predicate)?;
+  let module_specifiers = collect_specifiers(
+    files.clone(),
+    file_selection,
+    is_supported_test_path_predicate,
+  )?;
 
   if *include_inline {
-    return collect_specifiers(files, |p, _| is_supported_test_ext(p)).map(
-      |specifiers| {
-        specifiers
-          .into_iter()
-          .map(|specifier| {
-            let mode = if module_specifiers.contains(&specifier) {
-              TestMode::Both
-            } else {
-              TestMode::Documentation
-            };
-
-            (specifier, mode)
-          })
-          .collect()
-      },
-    );
+    return collect_specifiers(files, file_selection, |p, _| {
+      is_supported_test_ext(p)
+    })
+    .map(|specifiers| {
+      specifiers
+        .into_iter()
+        .map(|specifier| {
+          let mode = if module_specifiers.contains(&specifier) {
+            TestMode::Both
+          } else {
+            TestMode::Documentation
+          };
+
+          (specifier, mode)
+        })
+        .collect()
+    });
   }
 
   let specifiers_with_mode = module_specifiers
@@ -1220,9 +1337,11 @@ fn collect_specifiers_with_test_mode(
 async fn fetch_specifiers_with_test_mode(
   file_fetcher: &FileFetcher,
   files: FilePatterns,
+  file_selection: &FileSelection,
   doc: &bool,
 ) -> Result<Vec<(ModuleSpecifier, TestMode)>, AnyError> {
-  let mut specifiers_with_mode = collect_specifiers_with_test_mode(files, doc)?;
+  let mut specifiers_with_mode =
+    collect_specifiers_with_test_mode(files, file_selection, doc)?;
 
   for (specifier, mode) in &mut specifiers_with_mode {
     let file = file_fetcher
@@ -1244,6 +1363,10 @@ pub async fn run_tests(
 ) -> Result<(), AnyError> {
   let factory = CliFactory::from_flags(flags).await?;
   let cli_options = factory.cli_options();
+  let maybe_coverage_threshold = cli_options
+    .resolve_coverage_threshold(test_flags.coverage_threshold.clone())?;
+  let file_selection = cli_options
+    .resolve_file_selection(Some("test"), &test_flags.files.ignore)?;
   let test_options = cli_options.resolve_test_options(test_flags)?;
   let file_fetcher = factory.file_fetcher()?;
   let module_load_preparer = factory.module_load_preparer().await?;
@@ -1251,12 +1374,13 @@ pub async fn run_tests(
   // `PermissionsContainer` - otherwise granting/revoking permissions in one
   // file would have impact on other files, which is undesirable.
   let permissions =
-    Permissions::from_options(&cli_options.permissions_options())?;
+    Permissions::from_options(&cli_options.permissions_options()?)?;
   let log_level = cli_options.log_level();
 
-  let specifiers_with_mode = fetch_specifiers_with_test_mode(
+  let mut specifiers_with_mode = fetch_specifiers_with_test_mode(
     file_fetcher,
     test_options.files.clone(),
+    &file_selection,
     &test_options.doc,
   )
   .await?;
@@ -1265,6 +1389,52 @@ pub async fn run_tests(
     return Err(generic_error("No test modules found"));
   }
 
+  if let Some(git_ref) = &test_options.changed {
+    let changed_paths =
+      changed::changed_paths_since(git_ref, cli_options.initial_cwd())?;
+    let changed_config = cli_options.watch_paths().iter().any(|path| {
+      canonicalize_path(path)
+        .map(|path| changed_paths.contains(&path))
+        .unwrap_or(false)
+    });
+    if !changed_config {
+      let roots = specifiers_with_mode
+        .iter()
+        .filter(|(_, mode)| *mode != TestMode::Documentation)
+        .map(|(specifier, _)| specifier.clone())
+        .collect();
+      let graph = factory
+        .module_graph_builder()
+        .await?
+        .create_graph(cli_options.type_check_mode().as_graph_kind(), roots)
+        .await?;
+      specifiers_with_mode =
+        changed::select_changed(specifiers_with_mode, &graph, &changed_paths);
+    }
+    if specifiers_with_mode.is_empty() {
+      log::info!(
+        "No test modules are affected by the changes since '{}'",
+        git_ref
+      );
+      return Ok(());
+    }
+  }
+
+  // A shard may be empty when there are fewer modules than shards.
+  if let Some(test_shard) = &test_options.shard {
+    let maybe_timings = test_options
+      .shard_timings
+      .as_deref()
+      .map(|path| shard::read_shard_timings(path, cli_options.initial_cwd()))
+      .transpose()?;
+    specifiers_with_mode = shard::select_shard(
+      specifiers_with_mode,
+      test_shard,
+      maybe_timings.as_ref(),
+      cli_options.initial_cwd(),
+    );
+  }
+
   check_specifiers(
     cli_options,
     file_fetcher,
@@ -1301,11 +1471,24 @@ pub async fn run_tests(
         filter: TestFilter::from_flag(&test_options.filter),
         shuffle: test_options.shuffle,
         trace_ops: test_options.trace_ops,
+        retry: test_options.retry,
+        update_snapshots: test_options.update_snapshots,
       },
     },
   )
   .await?;
 
+  if let (Some(coverage_dir), Some(threshold)) =
+    (cli_options.coverage_dir(), &maybe_coverage_threshold)
+  {
+    crate::tools::coverage::check_coverage_threshold_of_dir(
+      &factory,
+      Path::new(&coverage_dir),
+      threshold,
+    )
+    .await?;
+  }
+
   Ok(())
 }
 
@@ -1343,6 +1526,8 @@ pub async fn run_tests_with_watch(
           .build_from_flags_for_watcher(flags, watcher_communicator.clone())
           .await?;
         let cli_options = factory.cli_options();
+        let file_selection = cli_options
+          .resolve_file_selection(Some("test"), &test_flags.files.ignore)?;
         let test_options = cli_options.resolve_test_options(test_flags)?;
 
         let _ = watcher_communicator.watch_paths(cli_options.watch_paths());
@@ -1359,18 +1544,21 @@ pub async fn run_tests_with_watch(
         let module_graph_builder = factory.module_graph_builder().await?;
         let file_fetcher = factory.file_fetcher()?;
         let test_modules = if test_options.doc {
-          collect_specifiers(test_options.files.clone(), |p, _| {
-            is_supported_test_ext(p)
-          })
+          collect_specifiers(
+            test_options.files.clone(),
+            &file_selection,
+            |p, _| is_supported_test_ext(p),
+          )
         } else {
           collect_specifiers(
             test_options.files.clone(),
+            &file_selection,
             is_supported_test_path_predicate,
           )
         }?;
 
         let permissions =
-          Permissions::from_options(&cli_options.permissions_options())?;
+          Permissions::from_options(&cli_options.permissions_options()?)?;
         let graph = module_graph_builder
           .create_graph(graph_kind, test_modules.clone())
           .await?;
@@ -1405,6 +1593,7 @@ pub async fn run_tests_with_watch(
         let specifiers_with_mode = fetch_specifiers_with_test_mode(
           file_fetcher,
           test_options.files.clone(),
+          &file_selection,
           &test_options.doc,
         )
         .await?
@@ -1445,6 +1634,8 @@ pub async fn run_tests_with_watch(
               filter: TestFilter::from_flag(&test_options.filter),
               shuffle: test_options.shuffle,
               trace_ops: test_options.trace_ops,
+              retry: test_options.retry,
+              update_snapshots: test_options.update_snapshots,
             },
           },
         )
@@ -1505,8 +1696,14 @@ pub struct TestEventSender {
 impl TestEventSender {
   pub fn new(sender: UnboundedSender<TestEvent>) -> Self {
     Self {
-      stdout_writer: TestOutputPipe::new(sender.clone()),
-      stderr_writer: TestOutputPipe::new(sender.clone()),
+      stdout_writer: TestOutputPipe::new(
+        sender.clone(),
+        TestStdioStream::Stdout,
+      ),
+      stderr_writer: TestOutputPipe::new(
+        sender.clone(),
+        TestStdioStream::Stderr,
+      ),
       sender,
     }
   }
@@ -1536,10 +1733,6 @@ impl TestEventSender {
     Ok(())
   }
 
-  fn downgrade(&self) -> WeakUnboundedSender<TestEvent> {
-    self.sender.downgrade()
-  }
-
   fn flush_stdout_and_stderr(&mut self) -> Result<(), AnyError> {
     self.stdout_writer.flush()?;
     self.stderr_writer.flush()?;
@@ -1566,11 +1759,14 @@ impl Clone for TestOutputPipe {
 }
 
 impl TestOutputPipe {
-  pub fn new(sender: UnboundedSender<TestEvent>) -> Self {
+  pub fn new(
+    sender: UnboundedSender<TestEvent>,
+    stream: TestStdioStream,
+  ) -> Self {
     let (reader, writer) = os_pipe::pipe().unwrap();
     let state = Arc::new(Mutex::new(None));
 
-    start_output_redirect_thread(reader, sender, state.clone());
+    start_output_redirect_thread(reader, sender, stream, state.clone());
 
     Self { writer, state }
   }
@@ -1617,6 +1813,7 @@ fn pipe_writer_to_file(writer: os_pipe::PipeWriter) -> std::fs::File {
 fn start_output_redirect_thread(
   mut pipe_reader: os_pipe::PipeReader,
   sender: UnboundedSender<TestEvent>,
+  stream: TestStdioStream,
   flush_state: Arc<Mutex<Option<std::sync::mpsc::Sender<()>>>>,
 ) {
   spawn_blocking(move || loop {
@@ -1633,7 +1830,7 @@ fn start_output_redirect_thread(
 
     if !data.is_empty()
       && sender
-        .send(TestEvent::Output(buffer[0..size].to_vec()))
+        .send(TestEvent::Output(stream, buffer[0..size].to_vec()))
         .is_err()
     {
       break;
@@ -211,6 +211,10 @@ pub(super) fn report_summary(
     .unwrap()
   }
 
+  if summary.flaky > 0 {
+    write!(summary_result, " | {} flaky", summary.flaky).unwrap();
+  }
+
   if summary.measured > 0 {
     write!(summary_result, " | {} measured", summary.measured,).unwrap();
   }
@@ -58,6 +58,7 @@ impl DotTestReporter {
       TestResult::Ignored => fmt_ignored(),
       TestResult::Failed(_failure) => fmt_failed(),
       TestResult::Cancelled => fmt_cancelled(),
+      TestResult::Flaky(_) => fmt_flaky(),
     };
 
     self.print_status(status);
@@ -68,6 +69,10 @@ fn fmt_ok() -> String {
   colors::gray(".").to_string()
 }
 
+fn fmt_flaky() -> String {
+  colors::yellow(".").to_string()
+}
+
 fn fmt_ignored() -> String {
   colors::cyan(",").to_string()
 }
@@ -105,6 +110,10 @@ impl TestReporter for DotTestReporter {
       TestResult::Ok => {
         self.summary.passed += 1;
       }
+      TestResult::Flaky(_) => {
+        self.summary.passed += 1;
+        self.summary.flaky += 1;
+      }
       TestResult::Ignored => {
         self.summary.ignored += 1;
       }
@@ -20,7 +20,9 @@ impl JunitTestReporter {
 
   fn convert_status(status: &TestResult) -> quick_junit::TestCaseStatus {
     match status {
-      TestResult::Ok => quick_junit::TestCaseStatus::success(),
+      TestResult::Ok | TestResult::Flaky(_) => {
+        quick_junit::TestCaseStatus::success()
+      }
       TestResult::Ignored => quick_junit::TestCaseStatus::skipped(),
       TestResult::Failed(failure) => quick_junit::TestCaseStatus::NonSuccess {
         kind: quick_junit::NonSuccessKind::Failure,
@@ -84,6 +86,12 @@ impl TestReporter for JunitTestReporter {
     if let Some(case) = self.cases.get_mut(&description.id) {
       case.status = Self::convert_status(result);
       case.set_time(Duration::from_millis(elapsed));
+      if let TestResult::Flaky(attempt) = result {
+        case.add_property(quick_junit::Property::new(
+          "flaky",
+          format!("passed on attempt {}", attempt),
+        ));
+      }
     }
   }
 
@@ -225,6 +225,10 @@ impl TestReporter for PrettyTestReporter {
       TestResult::Ok => {
         self.summary.passed += 1;
       }
+      TestResult::Flaky(_) => {
+        self.summary.passed += 1;
+        self.summary.flaky += 1;
+      }
       TestResult::Ignored => {
         self.summary.ignored += 1;
       }
@@ -240,11 +244,11 @@ impl TestReporter for PrettyTestReporter {
       }
     }
 
+    self.write_output_end();
     if self.parallel {
       self.force_report_wait(description);
     }
 
-    self.write_output_end();
     if self.in_new_line || self.scope_test_id != Some(description.id) {
       self.force_report_wait(description);
     }
@@ -254,6 +258,7 @@ impl TestReporter for PrettyTestReporter {
       TestResult::Ignored => colors::yellow("ignored").to_string(),
       TestResult::Failed(failure) => failure.format_label(),
       TestResult::Cancelled => colors::gray("cancelled").to_string(),
+      TestResult::Flaky(_) => colors::yellow("flaky").to_string(),
     };
     write!(&mut self.writer, " {}", status).unwrap();
     if let TestResult::Failed(failure) = result {
@@ -261,6 +266,9 @@ impl TestReporter for PrettyTestReporter {
         write!(&mut self.writer, " ({})", inline_summary).unwrap();
       }
     }
+    if let TestResult::Flaky(attempt) = result {
+      write!(&mut self.writer, " (passed on attempt {})", attempt).unwrap();
+    }
     writeln!(
       &mut self.writer,
       " {}",
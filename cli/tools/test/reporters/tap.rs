@@ -160,6 +160,7 @@ impl TestReporter for TapTestReporter {
 
     let (status, directive) = match result {
       TestResult::Ok => ("ok", ""),
+      TestResult::Flaky(_) => ("ok", " # flaky"),
       TestResult::Ignored => ("ok", " # SKIP"),
       TestResult::Failed(_failure) => ("not ok", ""),
       TestResult::Cancelled => ("not ok", ""),
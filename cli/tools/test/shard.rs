@@ -0,0 +1,292 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::url::Url;
+use deno_core::ModuleSpecifier;
+use quick_xml::events::BytesStart;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+use crate::args::TestShard;
+use crate::cache::FastInsecureHasher;
+
+/// The durations of the test modules of a previous run in milliseconds, keyed
+/// by the module path relative to the current directory.
+pub type ShardTimings = HashMap<String, f64>;
+
+/// Read the durations of the test modules from the JUnit report of a previous
+/// run, as written by `--reporter=junit --junit-path=<path>`. Every test suite
+/// of the report is a test module, whose duration is the sum of its cases.
+pub fn read_shard_timings(
+  path: &Path,
+  initial_cwd: &Path,
+) -> Result<ShardTimings, AnyError> {
+  let text = std::fs::read_to_string(path).with_context(|| {
+    format!("Failed to read shard timings from \"{}\"", path.display())
+  })?;
+  parse_junit_timings(&text, initial_cwd).with_context(|| {
+    format!(
+      "Invalid JUnit report for shard timings in \"{}\"",
+      path.display()
+    )
+  })
+}
+
+fn parse_junit_timings(
+  text: &str,
+  initial_cwd: &Path,
+) -> Result<ShardTimings, AnyError> {
+  fn attribute(
+    tag: &BytesStart,
+    name: &str,
+  ) -> Result<Option<String>, AnyError> {
+    Ok(match tag.try_get_attribute(name)? {
+      Some(attribute) => Some(attribute.unescape_value()?.into_owned()),
+      None => None,
+    })
+  }
+
+  let cwd_url = ModuleSpecifier::from_directory_path(initial_cwd).ok();
+  let mut timings = ShardTimings::new();
+  let mut maybe_suite_key = None;
+  let mut reader = Reader::from_str(text);
+  loop {
+    match reader.read_event()? {
+      Event::Start(tag) if tag.name().as_ref() == b"testsuite" => {
+        maybe_suite_key = attribute(&tag, "name")?.map(|name| {
+          match ModuleSpecifier::parse(&name) {
+            Ok(specifier) => shard_key(&specifier, cwd_url.as_ref()),
+            Err(_) => name,
+          }
+        });
+      }
+      Event::End(tag) if tag.name().as_ref() == b"testsuite" => {
+        maybe_suite_key = None;
+      }
+      Event::Start(tag) | Event::Empty(tag)
+        if tag.name().as_ref() == b"testcase" =>
+      {
+        let Some(suite_key) = &maybe_suite_key else {
+          continue;
+        };
+        let seconds = match attribute(&tag, "time")? {
+          Some(time) => time.parse::<f64>()?,
+          None => 0.0,
+        };
+        *timings.entry(suite_key.clone()).or_default() += seconds * 1000.0;
+      }
+      Event::Eof => break,
+      _ => {}
+    }
+  }
+  Ok(timings)
+}
+
+/// The key of a test module which is the same across machines, which is the
+/// path relative to the current directory for local modules.
+fn shard_key(specifier: &ModuleSpecifier, cwd_url: Option<&Url>) -> String {
+  cwd_url
+    .filter(|_| specifier.scheme() == "file")
+    .and_then(|cwd_url| cwd_url.make_relative(specifier))
+    .unwrap_or_else(|| specifier.to_string())
+}
+
+/// Select the test modules of a shard. Without timings, a module belongs to
+/// the shard of the stable hash of its key. With timings, the modules are
+/// assigned from longest to shortest to the shard with the least total
+/// duration, falling back to the average duration for unknown modules.
+pub fn select_shard<T>(
+  specifiers: Vec<(ModuleSpecifier, T)>,
+  shard: &TestShard,
+  maybe_timings: Option<&ShardTimings>,
+  initial_cwd: &Path,
+) -> Vec<(ModuleSpecifier, T)> {
+  let cwd_url = ModuleSpecifier::from_directory_path(initial_cwd).ok();
+  let keys = specifiers
+    .iter()
+    .map(|(specifier, _)| shard_key(specifier, cwd_url.as_ref()))
+    .collect::<Vec<_>>();
+  let shard_indexes = match maybe_timings {
+    Some(timings) => balanced_shard_indexes(&keys, shard.count, timings),
+    None => keys
+      .iter()
+      .map(|key| (FastInsecureHasher::hash(key) % shard.count as u64) as usize)
+      .collect(),
+  };
+  specifiers
+    .into_iter()
+    .zip(shard_indexes)
+    .filter(|(_, index)| *index == shard.index - 1)
+    .map(|(item, _)| item)
+    .collect()
+}
+
+fn balanced_shard_indexes(
+  keys: &[String],
+  count: usize,
+  timings: &ShardTimings,
+) -> Vec<usize> {
+  let known = keys
+    .iter()
+    .filter_map(|key| timings.get(key))
+    .copied()
+    .collect::<Vec<_>>();
+  let average = if known.is_empty() {
+    1.0
+  } else {
+    known.iter().sum::<f64>() / known.len() as f64
+  };
+  let durations = keys
+    .iter()
+    .map(|key| timings.get(key).copied().unwrap_or(average))
+    .collect::<Vec<_>>();
+  let mut order = (0..keys.len()).collect::<Vec<_>>();
+  order.sort_by(|a, b| {
+    durations[*b]
+      .total_cmp(&durations[*a])
+      .then_with(|| keys[*a].cmp(&keys[*b]))
+  });
+  let mut loads = vec![0.0; count];
+  let mut shard_indexes = vec![0; keys.len()];
+  for i in order {
+    let (shard_index, _) = loads
+      .iter()
+      .enumerate()
+      .min_by(|(_, a), (_, b)| a.total_cmp(b))
+      .unwrap();
+    loads[shard_index] += durations[i];
+    shard_indexes[i] = shard_index;
+  }
+  shard_indexes
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+  use test_util::TempDir;
+
+  fn specifiers(cwd: &Path, names: &[&str]) -> Vec<(ModuleSpecifier, ())> {
+    names
+      .iter()
+      .map(|name| {
+        (ModuleSpecifier::from_file_path(cwd.join(name)).unwrap(), ())
+      })
+      .collect()
+  }
+
+  fn selected(
+    cwd: &Path,
+    names: &[&str],
+    shard: TestShard,
+    maybe_timings: Option<&ShardTimings>,
+  ) -> Vec<String> {
+    select_shard(specifiers(cwd, names), &shard, maybe_timings, cwd)
+      .into_iter()
+      .map(|(specifier, _)| {
+        ModuleSpecifier::from_directory_path(cwd)
+          .unwrap()
+          .make_relative(&specifier)
+          .unwrap()
+      })
+      .collect()
+  }
+
+  #[test]
+  fn test_select_shard_partitions_modules() {
+    let names = (0..20).map(|i| format!("{i}_test.ts")).collect::<Vec<_>>();
+    let names = names.iter().map(|n| n.as_str()).collect::<Vec<_>>();
+    let temp_dir = TempDir::new();
+    let cwd = temp_dir.path().as_path();
+    let mut all = Vec::new();
+    for index in 1..=3 {
+      let shard = TestShard { index, count: 3 };
+      let modules = selected(cwd, &names, shard, None);
+      assert_eq!(modules, selected(cwd, &names, shard, None));
+      all.extend(modules);
+    }
+    all.sort();
+    let mut expected = names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+    expected.sort();
+    assert_eq!(all, expected);
+  }
+
+  #[test]
+  fn test_select_shard_with_timings() {
+    let names = ["a_test.ts", "b_test.ts", "c_test.ts", "d_test.ts"];
+    let temp_dir = TempDir::new();
+    let cwd = temp_dir.path().as_path();
+    let timings = ShardTimings::from([
+      ("a_test.ts".to_string(), 100.0),
+      ("b_test.ts".to_string(), 60.0),
+      ("c_test.ts".to_string(), 30.0),
+    ]);
+    assert_eq!(
+      selected(
+        cwd,
+        &names,
+        TestShard { index: 1, count: 2 },
+        Some(&timings)
+      ),
+      vec!["a_test.ts".to_string(), "c_test.ts".to_string()]
+    );
+    // the unknown module takes the average duration
+    assert_eq!(
+      selected(
+        cwd,
+        &names,
+        TestShard { index: 2, count: 2 },
+        Some(&timings)
+      ),
+      vec!["b_test.ts".to_string(), "d_test.ts".to_string()]
+    );
+  }
+
+  #[test]
+  fn test_parse_junit_timings() {
+    let temp_dir = TempDir::new();
+    let cwd = temp_dir.path().as_path();
+    let a_url = ModuleSpecifier::from_file_path(cwd.join("a_test.ts")).unwrap();
+    let b_url =
+      ModuleSpecifier::from_file_path(cwd.join("sub/b_test.ts")).unwrap();
+    let report = format!(
+      r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="deno test" tests="3" failures="0" errors="0" time="0.500">
+    <testsuite name="{a_url}" tests="2" disabled="0" errors="0" failures="0">
+        <testcase name="first" time="0.250">
+            <properties>
+                <property name="line" value="1"/>
+            </properties>
+        </testcase>
+        <testcase name="second" time="0.125"/>
+    </testsuite>
+    <testsuite name="{b_url}" tests="1" disabled="0" errors="0" failures="0">
+        <testcase name="third" time="0.100"/>
+    </testsuite>
+    <testsuite name="https://deno.land/x/c_test.ts" tests="1" disabled="0" errors="0" failures="0">
+        <testcase name="ignored">
+            <skipped/>
+        </testcase>
+    </testsuite>
+</testsuites>
+"#
+    );
+    assert_eq!(
+      parse_junit_timings(&report, cwd).unwrap(),
+      ShardTimings::from([
+        ("a_test.ts".to_string(), 375.0),
+        ("sub/b_test.ts".to_string(), 100.0),
+        ("https://deno.land/x/c_test.ts".to_string(), 0.0),
+      ])
+    );
+    assert!(parse_junit_timings(
+      r#"<testsuite name="a"><testcase name="b" time="c"/></testsuite>"#,
+      cwd
+    )
+    .is_err());
+  }
+}
@@ -0,0 +1,254 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::generic_error;
+use deno_core::error::AnyError;
+use deno_core::ModuleSpecifier;
+use indexmap::IndexMap;
+
+use crate::util::diff::diff;
+
+pub const UNSTABLE_FEATURE_NAME: &str = "test-snapshot";
+
+/// The snapshots of the test modules of a worker, which are asserted with
+/// `t.assertSnapshot()`.
+#[derive(Default)]
+pub struct TestSnapshots {
+  update: bool,
+  ran_all_tests: bool,
+  files: HashMap<ModuleSpecifier, SnapshotFile>,
+}
+
+struct SnapshotFile {
+  path: PathBuf,
+  snapshots: IndexMap<String, String>,
+  used: HashSet<String>,
+  changed: bool,
+}
+
+impl SnapshotFile {
+  fn load(path: PathBuf) -> Result<Self, AnyError> {
+    let snapshots = match std::fs::read_to_string(&path) {
+      Ok(text) => parse_snapshot_file(&text).with_context(|| {
+        format!("Invalid snapshot file \"{}\"", path.display())
+      })?,
+      Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+        Default::default()
+      }
+      Err(err) => {
+        return Err(err).with_context(|| {
+          format!("Failed to read snapshot file \"{}\"", path.display())
+        })
+      }
+    };
+    Ok(Self {
+      path,
+      snapshots,
+      used: Default::default(),
+      changed: false,
+    })
+  }
+}
+
+impl TestSnapshots {
+  pub fn set_update(&mut self, update: bool) {
+    self.update = update;
+  }
+
+  /// Mark that every test of the worker ran, so snapshots which weren't
+  /// asserted belong to tests which no longer exist.
+  pub fn set_ran_all_tests(&mut self, ran_all_tests: bool) {
+    self.ran_all_tests = ran_all_tests;
+  }
+
+  pub fn assert(
+    &mut self,
+    origin: &ModuleSpecifier,
+    name: &str,
+    actual: &str,
+  ) -> Result<(), AnyError> {
+    let file = match self.files.entry(origin.clone()) {
+      Entry::Occupied(entry) => entry.into_mut(),
+      Entry::Vacant(entry) => {
+        entry.insert(SnapshotFile::load(snapshot_path(origin)?)?)
+      }
+    };
+    file.used.insert(name.to_string());
+    let expected = file.snapshots.get(name);
+    if self.update {
+      if expected.map(|e| e != actual).unwrap_or(true) {
+        file.snapshots.insert(name.to_string(), actual.to_string());
+        file.changed = true;
+      }
+      return Ok(());
+    }
+    match expected {
+      Some(expected) if expected == actual => Ok(()),
+      Some(expected) => Err(generic_error(format!(
+        "Snapshot \"{}\" does not match:\n\n{}\nRun 'deno test --update-snapshots' to update it.",
+        name,
+        diff(expected, actual),
+      ))),
+      None => Err(generic_error(format!(
+        "Missing snapshot \"{}\" in \"{}\"\n\nRun 'deno test --update-snapshots' to create it.",
+        name,
+        file.path.display(),
+      ))),
+    }
+  }
+
+  /// Write the snapshot files which changed when updating snapshots. Returns
+  /// the written files with their number of snapshots.
+  pub fn save(self) -> Result<Vec<(PathBuf, usize)>, AnyError> {
+    let mut written = Vec::new();
+    if !self.update {
+      return Ok(written);
+    }
+    for (_, mut file) in self.files {
+      if self.ran_all_tests {
+        let count = file.snapshots.len();
+        let used = &file.used;
+        file.snapshots.retain(|name, _| used.contains(name));
+        file.changed |= file.snapshots.len() != count;
+      }
+      if !file.changed {
+        continue;
+      }
+      if let Some(parent) = file.path.parent() {
+        std::fs::create_dir_all(parent)?;
+      }
+      std::fs::write(&file.path, print_snapshot_file(&file.snapshots))
+        .with_context(|| {
+          format!("Failed to write snapshot file \"{}\"", file.path.display())
+        })?;
+      written.push((file.path, file.snapshots.len()));
+    }
+    Ok(written)
+  }
+}
+
+/// The snapshots of `dir/mod_test.ts` are stored in
+/// `dir/__snapshots__/mod_test.ts.snap`.
+fn snapshot_path(origin: &ModuleSpecifier) -> Result<PathBuf, AnyError> {
+  let Ok(path) = origin.to_file_path() else {
+    bail!("Snapshots are only supported for local test modules");
+  };
+  let file_name = path.file_name().unwrap().to_string_lossy();
+  Ok(
+    path
+      .parent()
+      .unwrap_or(Path::new(""))
+      .join("__snapshots__")
+      .join(format!("{}.snap", file_name)),
+  )
+}
+
+fn escape_template_literal(text: &str) -> String {
+  text
+    .replace('\\', "\\\\")
+    .replace('`', "\\`")
+    .replace("${", "\\${")
+}
+
+/// Snapshot files are JavaScript modules, in the same format as the ones of
+/// the std snapshot module.
+fn print_snapshot_file(snapshots: &IndexMap<String, String>) -> String {
+  let mut text = "export const snapshot = {};\n".to_string();
+  for (name, snapshot) in snapshots {
+    text.push_str(&format!(
+      "\nsnapshot[`{}`] = `{}`;\n",
+      escape_template_literal(name),
+      escape_template_literal(snapshot)
+    ));
+  }
+  text
+}
+
+fn parse_snapshot_file(
+  text: &str,
+) -> Result<IndexMap<String, String>, AnyError> {
+  fn template_literal(text: &str) -> Result<(String, &str), AnyError> {
+    let Some(text) = text.strip_prefix('`') else {
+      bail!("Expected a template literal");
+    };
+    let mut value = String::new();
+    let mut chars = text.char_indices();
+    while let Some((i, c)) = chars.next() {
+      match c {
+        '\\' => match chars.next() {
+          Some((_, 'n')) => value.push('\n'),
+          Some((_, 'r')) => value.push('\r'),
+          Some((_, 't')) => value.push('\t'),
+          Some((_, c)) => value.push(c),
+          None => break,
+        },
+        '`' => return Ok((value, &text[i + 1..])),
+        c => value.push(c),
+      }
+    }
+    bail!("Unterminated template literal")
+  }
+
+  let mut snapshots = IndexMap::new();
+  let mut rest = text;
+  while let Some(index) = rest.find("snapshot[") {
+    let (name, after_name) = template_literal(&rest[index + 9..])?;
+    let Some(after_name) = after_name.trim_start().strip_prefix(']') else {
+      bail!("Expected ']' after the snapshot name \"{}\"", name);
+    };
+    let Some(after_name) = after_name.trim_start().strip_prefix('=') else {
+      bail!("Expected '=' after the snapshot name \"{}\"", name);
+    };
+    let (snapshot, after_snapshot) = template_literal(after_name.trim_start())?;
+    snapshots.insert(name, snapshot);
+    rest = after_snapshot;
+  }
+  Ok(snapshots)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_snapshot_file_roundtrip() {
+    let snapshots = IndexMap::from([
+      ("a 1".to_string(), "\n{\n  a: 1,\n}\n".to_string()),
+      ("a > step `1` 1".to_string(), r#""${x} \ y""#.to_string()),
+    ]);
+    let text = print_snapshot_file(&snapshots);
+    assert_eq!(
+      text,
+      r#"export const snapshot = {};
+
+snapshot[`a 1`] = `
+{
+  a: 1,
+}
+`;
+
+snapshot[`a > step \`1\` 1`] = `"\${x} \\ y"`;
+"#
+    );
+    assert_eq!(parse_snapshot_file(&text).unwrap(), snapshots);
+  }
+
+  #[test]
+  fn test_snapshot_path() {
+    let origin =
+      ModuleSpecifier::from_file_path(std::env::temp_dir().join("mod_test.ts"))
+        .unwrap();
+    let path = snapshot_path(&origin).unwrap();
+    assert!(path.ends_with("__snapshots__/mod_test.ts.snap"));
+    let remote = ModuleSpecifier::parse("https://deno.land/x/mod_test.ts");
+    assert!(snapshot_path(&remote.unwrap()).is_err());
+  }
+}
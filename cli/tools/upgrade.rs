@@ -2,7 +2,9 @@
 
 //! This module provides feature to upgrade deno executable
 
+use crate::args::resolve_min_deno_version;
 use crate::args::Flags;
+use crate::args::UpgradeChannel;
 use crate::args::UpgradeFlags;
 use crate::colors;
 use crate::factory::CliFactory;
@@ -35,6 +37,10 @@ static ARCHIVE_NAME: Lazy<String> =
 
 const RELEASE_URL: &str = "https://github.com/denoland/deno/releases";
 
+/// The file which pins the version `deno upgrade` installs without
+/// `--version`, like the one of the Deno Version Manager.
+const PINNED_VERSION_FILE_NAME: &str = ".dvmrc";
+
 // How often query server for new version. In hours.
 const UPGRADE_CHECK_INTERVAL: i64 = 24;
 
@@ -244,7 +250,7 @@ pub fn check_for_upgrades(
   http_client: Arc<HttpClient>,
   cache_file_path: PathBuf,
 ) {
-  if !upgrade_check_enabled() {
+  if !upgrade_check_enabled() || http_client.is_offline() {
     return;
   }
 
@@ -308,7 +314,7 @@ pub struct LspVersionUpgradeInfo {
 pub async fn check_for_upgrades_for_lsp(
   http_client: Arc<HttpClient>,
 ) -> Result<Option<LspVersionUpgradeInfo>, AnyError> {
-  if !upgrade_check_enabled() {
+  if !upgrade_check_enabled() || http_client.is_offline() {
     return Ok(None);
   }
 
@@ -404,7 +410,162 @@ pub async fn upgrade(
     fs::metadata(&current_exe_path)?.permissions()
   };
 
-  let install_version = match upgrade_flags.version {
+  let backup_exe_path = factory
+    .deno_dir()?
+    .upgrade_backup_folder_path()
+    .join(current_exe_path.file_name().unwrap());
+  if upgrade_flags.rollback {
+    return rollback(
+      &current_exe_path,
+      &backup_exe_path,
+      permissions,
+      upgrade_flags.dry_run,
+    );
+  }
+
+  let cli_options = factory.cli_options();
+  let maybe_min_version =
+    resolve_min_deno_version(cli_options.maybe_config_file_ext())?;
+
+  let temp_dir = tempfile::TempDir::new()?;
+  let (new_exe_path, install_version) = match &upgrade_flags.archive {
+    Some(archive_path) => {
+      let archive_data = fs::read(archive_path).with_context(|| {
+        format!("Failed reading {}", archive_path.display())
+      })?;
+
+      log::info!("Deno is upgrading from {}", archive_path.display());
+
+      let new_exe_path =
+        unpack_into_dir(archive_data, cfg!(windows), &temp_dir)?;
+      fs::set_permissions(&new_exe_path, permissions)?;
+      let install_version = check_exe(&new_exe_path)?;
+      check_min_deno_version(
+        &install_version,
+        maybe_min_version.as_ref(),
+        upgrade_flags.force,
+      )?;
+      (new_exe_path, install_version)
+    }
+    None => {
+      let Some(install_version) = resolve_install_version(
+        client,
+        &upgrade_flags,
+        cli_options.initial_cwd(),
+      )
+      .await?
+      else {
+        return Ok(());
+      };
+      check_min_deno_version(
+        &install_version,
+        maybe_min_version.as_ref(),
+        upgrade_flags.force,
+      )?;
+
+      let download_url = if upgrade_flags.channel == UpgradeChannel::Canary {
+        format!(
+          "https://dl.deno.land/canary/{}/{}",
+          install_version, *ARCHIVE_NAME
+        )
+      } else {
+        format!(
+          "{}/download/v{}/{}",
+          RELEASE_URL, install_version, *ARCHIVE_NAME
+        )
+      };
+
+      let archive_data = download_package(client, &download_url)
+        .await
+        .with_context(|| format!("Failed downloading {download_url}. The version you requested may not have been built for the current architechture."))?;
+
+      log::info!("Deno is upgrading to version {}", &install_version);
+
+      let new_exe_path =
+        unpack_into_dir(archive_data, cfg!(windows), &temp_dir)?;
+      fs::set_permissions(&new_exe_path, permissions)?;
+      check_exe(&new_exe_path)?;
+      (new_exe_path, install_version)
+    }
+  };
+  // release notes are only published for the stable releases
+  let has_release_notes = matches!(
+    upgrade_flags.channel,
+    UpgradeChannel::Stable | UpgradeChannel::Lts
+  ) && Version::parse_standard(&install_version)
+    .is_ok_and(|version| version.pre.is_empty() && version.build.is_empty());
+
+  if upgrade_flags.dry_run {
+    fs::remove_file(&new_exe_path)?;
+    log::info!("Upgraded successfully (dry run)");
+    if has_release_notes {
+      print_release_notes(version::deno(), &install_version);
+    }
+  } else {
+    let output_exe_path =
+      upgrade_flags.output.as_ref().unwrap_or(&current_exe_path);
+    let output_result = if *output_exe_path == current_exe_path {
+      if let Err(err) = backup_exe(&current_exe_path, &backup_exe_path) {
+        log::warn!(
+          "{} Failed keeping version {} for `deno upgrade --rollback`: {:#}",
+          colors::yellow("Warning"),
+          version::deno(),
+          err
+        );
+      }
+      replace_exe(&new_exe_path, output_exe_path)
+    } else {
+      fs::rename(&new_exe_path, output_exe_path)
+        .or_else(|_| fs::copy(&new_exe_path, output_exe_path).map(|_| ()))
+    };
+    if let Err(err) = output_result {
+      const WIN_ERROR_ACCESS_DENIED: i32 = 5;
+      if cfg!(windows) && err.raw_os_error() == Some(WIN_ERROR_ACCESS_DENIED) {
+        return Err(err).with_context(|| {
+          format!(
+            concat!(
+              "Could not replace the deno executable. This may be because an ",
+              "existing deno process is running. Please ensure there are no ",
+              "running deno processes (ex. Stop-Process -Name deno ; deno {}), ",
+              "close any editors before upgrading, and ensure you have ",
+              "sufficient permission to '{}'."
+            ),
+            // skip the first argument, which is the executable path
+            std::env::args().skip(1).collect::<Vec<_>>().join(" "),
+            output_exe_path.display(),
+          )
+        });
+      } else {
+        return Err(err.into());
+      }
+    }
+    log::info!("Upgraded successfully");
+    if has_release_notes {
+      print_release_notes(version::deno(), &install_version);
+    }
+  }
+
+  drop(temp_dir); // delete the temp dir
+  Ok(())
+}
+
+/// Resolves the version to install from the `--version` flag, a `.dvmrc` file
+/// or the latest release of the channel, which is `None` when the version is
+/// already installed.
+async fn resolve_install_version(
+  client: &HttpClient,
+  upgrade_flags: &UpgradeFlags,
+  cwd: &Path,
+) -> Result<Option<String>, AnyError> {
+  let is_canary = upgrade_flags.channel == UpgradeChannel::Canary;
+  let maybe_passed_version = match &upgrade_flags.version {
+    Some(version) => Some(version.clone()),
+    None => find_pinned_version(cwd)?.map(|(path, version)| {
+      log::info!("Using version {} pinned by {}", version, path.display());
+      version
+    }),
+  };
+  match maybe_passed_version {
     Some(passed_version) => {
       let re_hash = lazy_regex::regex!("^[0-9a-f]{40}$");
       let passed_version = passed_version
@@ -412,15 +573,14 @@ pub async fn upgrade(
         .unwrap_or(&passed_version)
         .to_string();
 
-      if upgrade_flags.canary && !re_hash.is_match(&passed_version) {
+      if is_canary && !re_hash.is_match(&passed_version) {
         bail!("Invalid commit hash passed");
-      } else if !upgrade_flags.canary
-        && Version::parse_standard(&passed_version).is_err()
+      } else if !is_canary && Version::parse_standard(&passed_version).is_err()
       {
         bail!("Invalid version passed");
       }
 
-      let current_is_passed = if upgrade_flags.canary {
+      let current_is_passed = if is_canary {
         crate::version::GIT_COMMIT_HASH == passed_version
       } else if !crate::version::is_canary() {
         crate::version::deno() == passed_version
@@ -433,31 +593,39 @@ pub async fn upgrade(
         && current_is_passed
       {
         log::info!("Version {} is already installed", crate::version::deno());
-        return Ok(());
+        return Ok(None);
       }
 
-      passed_version
+      Ok(Some(passed_version))
     }
     None => {
-      let release_kind = if upgrade_flags.canary {
-        log::info!("Looking up latest canary version");
-        UpgradeReleaseKind::Canary
-      } else {
-        log::info!("Looking up latest version");
-        UpgradeReleaseKind::Stable
-      };
+      let release_kind = UpgradeReleaseKind::from(upgrade_flags.channel);
+      match release_kind {
+        UpgradeReleaseKind::Stable => log::info!("Looking up latest version"),
+        UpgradeReleaseKind::Rc => {
+          log::info!("Looking up latest release candidate")
+        }
+        UpgradeReleaseKind::Canary => {
+          log::info!("Looking up latest canary version")
+        }
+        UpgradeReleaseKind::Lts => log::info!("Looking up latest LTS version"),
+      }
 
       let latest_version =
         get_latest_version(client, release_kind, UpgradeCheckKind::Execution)
           .await?;
 
-      let current_is_most_recent = if upgrade_flags.canary {
+      let current_is_most_recent = if is_canary {
         let latest_hash = &latest_version;
         crate::version::GIT_COMMIT_HASH == latest_hash
       } else if !crate::version::is_canary() {
         let current = Version::parse_standard(crate::version::deno()).unwrap();
         let latest = Version::parse_standard(&latest_version).unwrap();
-        current >= latest
+        match release_kind {
+          // switching to the LTS channel may install an older version
+          UpgradeReleaseKind::Lts => current == latest,
+          _ => current >= latest,
+        }
       } else {
         false
       };
@@ -468,93 +636,123 @@ pub async fn upgrade(
       {
         log::info!(
           "Local deno version {} is the most recent release",
-          if upgrade_flags.canary {
+          if is_canary {
             crate::version::GIT_COMMIT_HASH
           } else {
             crate::version::deno()
           }
         );
-        return Ok(());
+        Ok(None)
       } else {
         log::info!("Found latest version {}", latest_version);
-        latest_version
+        Ok(Some(latest_version))
       }
     }
-  };
-
-  let download_url = if upgrade_flags.canary {
-    format!(
-      "https://dl.deno.land/canary/{}/{}",
-      install_version, *ARCHIVE_NAME
-    )
-  } else {
-    format!(
-      "{}/download/v{}/{}",
-      RELEASE_URL, install_version, *ARCHIVE_NAME
-    )
-  };
+  }
+}
 
-  let archive_data = download_package(client, &download_url)
-    .await
-    .with_context(|| format!("Failed downloading {download_url}. The version you requested may not have been built for the current architechture."))?;
+/// Finds the version which is pinned by a `.dvmrc` file in the directory or
+/// one of its ancestors.
+fn find_pinned_version(
+  cwd: &Path,
+) -> Result<Option<(PathBuf, String)>, AnyError> {
+  for dir in cwd.ancestors() {
+    let path = dir.join(PINNED_VERSION_FILE_NAME);
+    match fs::read_to_string(&path) {
+      Ok(text) if !text.trim().is_empty() => {
+        return Ok(Some((path, text.trim().to_string())));
+      }
+      Ok(_) => {}
+      Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+      Err(err) => {
+        return Err(err)
+          .with_context(|| format!("Failed reading {}", path.display()));
+      }
+    }
+  }
+  Ok(None)
+}
 
-  log::info!("Deno is upgrading to version {}", &install_version);
+/// Fails when the version to install is older than the `"minDenoVersion"` of
+/// the configuration file, unless the upgrade is forced. Canary builds, whose
+/// versions are commit hashes, aren't checked.
+fn check_min_deno_version(
+  version: &str,
+  maybe_min_version: Option<&Version>,
+  force: bool,
+) -> Result<(), AnyError> {
+  let Some(min_version) = maybe_min_version else {
+    return Ok(());
+  };
+  let Ok(version) = Version::parse_standard(version) else {
+    return Ok(());
+  };
+  if !force && version < *min_version {
+    bail!(
+      "Version {} is older than the \"minDenoVersion\" {} of the configuration file. Pass --force to install it anyway.",
+      version,
+      min_version
+    );
+  }
+  Ok(())
+}
 
-  let temp_dir = tempfile::TempDir::new()?;
-  let new_exe_path = unpack_into_dir(archive_data, cfg!(windows), &temp_dir)?;
-  fs::set_permissions(&new_exe_path, permissions)?;
-  check_exe(&new_exe_path)?;
+/// Keeps a copy of the executable which is replaced, so that
+/// `deno upgrade --rollback` can restore it.
+fn backup_exe(exe_path: &Path, backup_exe_path: &Path) -> Result<(), AnyError> {
+  fs::create_dir_all(backup_exe_path.parent().unwrap())?;
+  fs::copy(exe_path, backup_exe_path)?;
+  Ok(())
+}
 
-  if upgrade_flags.dry_run {
-    fs::remove_file(&new_exe_path)?;
-    log::info!("Upgraded successfully (dry run)");
-    if !upgrade_flags.canary {
-      print_release_notes(version::deno(), &install_version);
-    }
-  } else {
-    let output_exe_path =
-      upgrade_flags.output.as_ref().unwrap_or(&current_exe_path);
-    let output_result = if *output_exe_path == current_exe_path {
-      replace_exe(&new_exe_path, output_exe_path)
-    } else {
-      fs::rename(&new_exe_path, output_exe_path)
-        .or_else(|_| fs::copy(&new_exe_path, output_exe_path).map(|_| ()))
-    };
-    if let Err(err) = output_result {
-      const WIN_ERROR_ACCESS_DENIED: i32 = 5;
-      if cfg!(windows) && err.raw_os_error() == Some(WIN_ERROR_ACCESS_DENIED) {
-        return Err(err).with_context(|| {
-          format!(
-            concat!(
-              "Could not replace the deno executable. This may be because an ",
-              "existing deno process is running. Please ensure there are no ",
-              "running deno processes (ex. Stop-Process -Name deno ; deno {}), ",
-              "close any editors before upgrading, and ensure you have ",
-              "sufficient permission to '{}'."
-            ),
-            // skip the first argument, which is the executable path
-            std::env::args().skip(1).collect::<Vec<_>>().join(" "),
-            output_exe_path.display(),
-          )
-        });
-      } else {
-        return Err(err.into());
-      }
-    }
-    log::info!("Upgraded successfully");
-    if !upgrade_flags.canary {
-      print_release_notes(version::deno(), &install_version);
-    }
+fn rollback(
+  current_exe_path: &Path,
+  backup_exe_path: &Path,
+  permissions: fs::Permissions,
+  dry_run: bool,
+) -> Result<(), AnyError> {
+  if !backup_exe_path.exists() {
+    bail!("There is no previous version to roll back to. It's kept when `deno upgrade` replaces the executable.");
+  }
+  let previous_version = check_exe(backup_exe_path)?;
+  if dry_run {
+    log::info!(
+      "Rolled back successfully to version {} (dry run)",
+      previous_version
+    );
+    return Ok(());
   }
 
-  drop(temp_dir); // delete the temp dir
+  let temp_dir = tempfile::TempDir::new()?;
+  let previous_exe_path =
+    temp_dir.path().join(backup_exe_path.file_name().unwrap());
+  fs::copy(backup_exe_path, &previous_exe_path)?;
+  fs::set_permissions(&previous_exe_path, permissions)?;
+  // the version which is rolled back from is kept in turn, so that the
+  // rollback can be undone
+  backup_exe(current_exe_path, backup_exe_path)?;
+  replace_exe(&previous_exe_path, current_exe_path)?;
+  log::info!("Rolled back successfully to version {}", previous_version);
   Ok(())
 }
 
 #[derive(Debug, Clone, Copy)]
 enum UpgradeReleaseKind {
   Stable,
+  Rc,
   Canary,
+  Lts,
+}
+
+impl From<UpgradeChannel> for UpgradeReleaseKind {
+  fn from(channel: UpgradeChannel) -> Self {
+    match channel {
+      UpgradeChannel::Stable => Self::Stable,
+      UpgradeChannel::Rc => Self::Rc,
+      UpgradeChannel::Canary => Self::Canary,
+      UpgradeChannel::Lts => Self::Lts,
+    }
+  }
 }
 
 async fn get_latest_version(
@@ -573,7 +771,9 @@ fn normalize_version_from_server(
 ) -> String {
   let text = text.trim();
   match release_kind {
-    UpgradeReleaseKind::Stable => text.trim_start_matches('v').to_string(),
+    UpgradeReleaseKind::Stable
+    | UpgradeReleaseKind::Rc
+    | UpgradeReleaseKind::Lts => text.trim_start_matches('v').to_string(),
     UpgradeReleaseKind::Canary => text.to_string(),
   }
 }
@@ -585,6 +785,8 @@ fn get_url(
 ) -> String {
   let file_name = match release_kind {
     UpgradeReleaseKind::Stable => Cow::Borrowed("release-latest.txt"),
+    UpgradeReleaseKind::Rc => Cow::Borrowed("rc-latest.txt"),
+    UpgradeReleaseKind::Lts => Cow::Borrowed("lts-latest.txt"),
     UpgradeReleaseKind::Canary => {
       Cow::Owned(format!("canary-{target_tuple}-latest.txt"))
     }
@@ -707,13 +909,16 @@ fn replace_exe(from: &Path, to: &Path) -> Result<(), std::io::Error> {
   Ok(())
 }
 
-fn check_exe(exe_path: &Path) -> Result<(), AnyError> {
+/// Checks that the executable runs and gets its version.
+fn check_exe(exe_path: &Path) -> Result<String, AnyError> {
   let output = Command::new(exe_path)
     .arg("-V")
     .stderr(std::process::Stdio::inherit())
     .output()?;
   assert!(output.status.success());
-  Ok(())
+  let stdout = String::from_utf8_lossy(&output.stdout);
+  let version = stdout.trim();
+  Ok(version.strip_prefix("deno ").unwrap_or(version).to_string())
 }
 
 #[derive(Debug)]
@@ -1084,6 +1289,64 @@ mod test {
       ),
       "https://dl.deno.land/release-latest.txt?lsp"
     );
+    assert_eq!(
+      get_url(
+        UpgradeReleaseKind::Rc,
+        "aarch64-apple-darwin",
+        UpgradeCheckKind::Execution
+      ),
+      "https://dl.deno.land/rc-latest.txt"
+    );
+    assert_eq!(
+      get_url(
+        UpgradeReleaseKind::Lts,
+        "aarch64-apple-darwin",
+        UpgradeCheckKind::Execution
+      ),
+      "https://dl.deno.land/lts-latest.txt"
+    );
+  }
+
+  #[test]
+  fn test_find_pinned_version() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let project_dir = temp_dir.path().join("project");
+    let sub_dir = project_dir.join("src");
+    fs::create_dir_all(&sub_dir).unwrap();
+    assert_eq!(find_pinned_version(&sub_dir).unwrap(), None);
+
+    fs::write(project_dir.join(".dvmrc"), "\n").unwrap();
+    assert_eq!(find_pinned_version(&sub_dir).unwrap(), None);
+
+    fs::write(project_dir.join(".dvmrc"), "v1.40.2\n").unwrap();
+    assert_eq!(
+      find_pinned_version(&sub_dir).unwrap(),
+      Some((project_dir.join(".dvmrc"), "v1.40.2".to_string()))
+    );
+  }
+
+  #[test]
+  fn test_check_min_deno_version() {
+    let min_version = Version::parse_standard("1.40.0").unwrap();
+    assert!(check_min_deno_version("1.40.0", Some(&min_version), false).is_ok());
+    assert!(
+      check_min_deno_version("1.41.0-rc.1", Some(&min_version), false).is_ok()
+    );
+    assert!(check_min_deno_version("1.39.4", None, false).is_ok());
+    assert!(check_min_deno_version("1.39.4", Some(&min_version), true).is_ok());
+    // canary builds are commit hashes
+    assert!(check_min_deno_version(
+      "7e1cf9cd3df0b156f3fb823a6dfcd1a6bb4ef3b7",
+      Some(&min_version),
+      false
+    )
+    .is_ok());
+    let err =
+      check_min_deno_version("1.39.4", Some(&min_version), false).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "Version 1.39.4 is older than the \"minDenoVersion\" 1.40.0 of the configuration file. Pass --force to install it anyway."
+    );
   }
 
   #[test]
@@ -325,7 +325,9 @@ delete Object.prototype.__proto__;
     },
   };
 
-  ts.deno.setIsNodeSourceFileCallback((sourceFile) => {
+  // `ts.deno` is only in Deno's fork of TypeScript, not in the compilers of
+  // npm which are pinned by "typescriptVersion"
+  ts.deno?.setIsNodeSourceFileCallback((sourceFile) => {
     const fileName = sourceFile.fileName;
     let isNodeSourceFile = isNodeSourceFileCache.get(fileName);
     if (isNodeSourceFile == null) {
@@ -509,6 +511,12 @@ delete Object.prototype.__proto__;
   /** @type {ts.LanguageService} */
   let languageService;
 
+  /** The language service plugins of the `"plugins"` compiler option and the
+   * path of the configuration file their names are resolved from.
+   *
+   * @type {{ referrer: string | null; plugins: { name: string }[] }} */
+  let pluginsConfig = { referrer: null, plugins: [] };
+
   /** An object literal of the incremental compiler host, which provides the
    * specific "bindings" to the Deno environment that tsc needs to work.
    *
@@ -581,20 +589,21 @@ delete Object.prototype.__proto__;
         data != null,
         `"data" is unexpectedly null for "${specifier}".`,
       );
-      sourceFile = ts.createSourceFile(
-        specifier,
-        data,
-        {
-          ...getCreateSourceFileOptions(languageVersion),
-          impliedNodeFormat: isCjsCache.has(specifier)
-            ? ts.ModuleKind.CommonJS
-            : ts.ModuleKind.ESNext,
-          // no need to parse docs for `deno check`
-          jsDocParsingMode: ts.JSDocParsingMode.ParseForTypeErrors,
-        },
-        false,
-        scriptKind,
-      );
+      sourceFile = traced("parse", specifier, () =>
+        ts.createSourceFile(
+          specifier,
+          data,
+          {
+            ...getCreateSourceFileOptions(languageVersion),
+            impliedNodeFormat: isCjsCache.has(specifier)
+              ? ts.ModuleKind.CommonJS
+              : ts.ModuleKind.ESNext,
+            // no need to parse docs for `deno check`
+            jsDocParsingMode: ts.JSDocParsingMode.ParseForTypeErrors,
+          },
+          false,
+          scriptKind,
+        ));
       sourceFile.moduleName = specifier;
       sourceFile.version = version;
       if (specifier.startsWith(ASSETS_URL_PREFIX)) {
@@ -684,10 +693,11 @@ delete Object.prototype.__proto__;
         debug(`  specifiers: ${specifiers.join(", ")}`);
       }
       /** @type {Array<[string, ts.Extension] | undefined>} */
-      const resolved = ops.op_resolve({
-        specifiers,
-        base,
-      });
+      const resolved = traced("resolve", base, () =>
+        ops.op_resolve({
+          specifiers,
+          base,
+        }));
       if (resolved) {
         const result = resolved.map((item) => {
           if (item) {
@@ -845,12 +855,75 @@ delete Object.prototype.__proto__;
     return stats;
   }
 
+  /**
+   * The events of `deno check --trace`, which is `undefined` when not tracing.
+   * @type {Array<{ name: string; fileName: string; start: number; duration: number; instantiations?: number }> | undefined}
+   */
+  let traceEvents;
+  let traceStart = 0;
+
+  /**
+   * Calls the function, adding an event to the trace for the time it took.
+   * @template T
+   * @param {string} name
+   * @param {string} fileName
+   * @param {() => T} fn
+   * @param {() => number} [getInstantiationCount]
+   * @returns {T}
+   */
+  function traced(name, fileName, fn, getInstantiationCount) {
+    if (traceEvents == null) {
+      return fn();
+    }
+    const start = ts.timestamp();
+    const instantiationsStart = getInstantiationCount?.();
+    try {
+      return fn();
+    } finally {
+      const end = ts.timestamp();
+      traceEvents.push({
+        name,
+        fileName,
+        // in microseconds, like the Chrome trace format
+        start: Math.round((start - traceStart) * 1000),
+        duration: Math.round((end - start) * 1000),
+        instantiations: getInstantiationCount == null
+          ? undefined
+          : getInstantiationCount() - instantiationsStart,
+      });
+    }
+  }
+
+  /**
+   * Creates the program like `ts.createIncrementalProgram()` when there is no
+   * build info, binding every file before the type checker is created so
+   * the time it took is traced per file.
+   * @param {ts.CreateProgramOptions} createProgramOptions
+   */
+  function createTracedProgram(createProgramOptions) {
+    const program = ts.createProgram(createProgramOptions);
+    for (const sourceFile of program.getSourceFiles()) {
+      traced(
+        "bind",
+        sourceFile.fileName,
+        () => ts.bindSourceFile(sourceFile, createProgramOptions.options),
+      );
+    }
+    return ts.createEmitAndSemanticDiagnosticsBuilderProgram(
+      program,
+      createProgramOptions.host,
+      undefined,
+      createProgramOptions.configFileParsingDiagnostics,
+    );
+  }
+
   /**
    * @typedef {object} Request
    * @property {Record<string, any>} config
    * @property {boolean} debug
    * @property {string[]} rootNames
    * @property {boolean} localOnly
+   * @property {boolean} trace
    */
 
   /**
@@ -887,9 +960,11 @@ delete Object.prototype.__proto__;
   /** The API that is called by Rust when executing a request.
    * @param {Request} request
    */
-  function exec({ config, debug: debugFlag, rootNames, localOnly }) {
+  function exec({ config, debug: debugFlag, rootNames, localOnly, trace }) {
     setLogDebug(debugFlag, "TS");
     performanceStart();
+    traceEvents = trace ? [] : undefined;
+    traceStart = ts.timestamp();
 
     config = normalizeConfig(config);
 
@@ -907,12 +982,15 @@ delete Object.prototype.__proto__;
     // URLs which Deno supports. So we need to either ignore the diagnostic, or
     // inject it ourselves.
     Object.assign(options, { allowNonTsExtensions: true });
-    const program = ts.createIncrementalProgram({
+    const createProgramOptions = {
       rootNames,
       options,
       host,
       configFileParsingDiagnostics,
-    });
+    };
+    const program = traceEvents == null
+      ? ts.createIncrementalProgram(createProgramOptions)
+      : createTracedProgram(createProgramOptions);
 
     const checkFiles = localOnly
       ? rootNames
@@ -926,6 +1004,20 @@ delete Object.prototype.__proto__;
         })
       : undefined;
 
+    if (traceEvents != null) {
+      // check every file on its own first, so the diagnostics are cached by
+      // the type checker for the calls below
+      const innerProgram = program.getProgram();
+      for (const sourceFile of checkFiles ?? innerProgram.getSourceFiles()) {
+        traced(
+          "check",
+          sourceFile.fileName,
+          () => innerProgram.getSemanticDiagnostics(sourceFile),
+          () => innerProgram.getInstantiationCount(),
+        );
+      }
+    }
+
     if (checkFiles != null) {
       // When calling program.getSemanticDiagnostics(...) with a source file, we
       // need to call this code first in order to get it to invalidate cached
@@ -967,6 +1059,7 @@ delete Object.prototype.__proto__;
     ops.op_respond({
       diagnostics: fromTypeScriptDiagnostics(diagnostics),
       stats: performanceEnd(),
+      trace: traceEvents,
     });
     debug("<<< exec stop");
   }
@@ -1023,6 +1116,12 @@ delete Object.prototype.__proto__;
         }
         compilationSettings = options;
         moduleSpecifierCache.clear();
+        const plugins = args[1] ?? { referrer: null, plugins: [] };
+        if (JSON.stringify(plugins) !== JSON.stringify(pluginsConfig)) {
+          pluginsConfig = plugins;
+          pluginModuleCache.clear();
+          languageService = createLanguageService();
+        }
         return respond(id, true);
       }
       case "$getSupportedCodeFixes": {
@@ -1076,6 +1175,110 @@ delete Object.prototype.__proto__;
     }
   }
 
+  /** @type {Map<string, { exports: any }>} */
+  const pluginModuleCache = new Map();
+
+  /** A minimal CommonJS `require()` for the language service plugins, which
+   * loads the `typescript` package of the server and the modules of the
+   * project and its `node_modules` directories.
+   *
+   * @param {string} specifier
+   * @param {string} referrer */
+  function requirePluginModule(specifier, referrer) {
+    if (
+      specifier === "typescript" ||
+      specifier === "typescript/lib/tsserverlibrary"
+    ) {
+      return ts;
+    }
+    /** @type {{ fileName: string; source: string } | null} */
+    const loaded = ops.op_load_plugin_module(specifier, referrer);
+    if (!loaded) {
+      throw new Error(
+        `Cannot find module "${specifier}" required by "${referrer}".`,
+      );
+    }
+    const { fileName, source } = loaded;
+    const cached = pluginModuleCache.get(fileName);
+    if (cached) {
+      return cached.exports;
+    }
+    const module = { exports: {} };
+    pluginModuleCache.set(fileName, module);
+    if (fileName.endsWith(".json")) {
+      module.exports = JSON.parse(source);
+    } else {
+      const dirName = fileName.replace(/[\\/][^\\/]*$/, "");
+      const fn = new Function(
+        "exports",
+        "require",
+        "module",
+        "__filename",
+        "__dirname",
+        source,
+      );
+      fn(
+        module.exports,
+        (/** @type {string} */ specifier) =>
+          requirePluginModule(specifier, fileName),
+        module,
+        fileName,
+        dirName,
+      );
+    }
+    return module.exports;
+  }
+
+  /** The parts of the project of `tsserver` which are used by plugins. */
+  const pluginProject = {
+    projectService: {
+      logger: {
+        info: (/** @type {string} */ msg) => debug(msg),
+        msg: (/** @type {string} */ msg) => debug(msg),
+        loggingEnabled: () => logDebug,
+        hasLevel: () => logDebug,
+      },
+    },
+    getCompilerOptions: () => compilationSettings,
+    getCurrentDirectory: () => host.getCurrentDirectory(),
+    getProjectName: () => "deno",
+  };
+
+  /** Creates the language service, wrapped by each of the plugins in order,
+   * so their diagnostics, completions and so on are part of the results of
+   * the requests. A plugin which fails to load is skipped. */
+  function createLanguageService() {
+    let service = ts.createLanguageService(host, documentRegistry);
+    const { referrer, plugins } = pluginsConfig;
+    for (const config of plugins) {
+      try {
+        if (!referrer) {
+          throw new Error("There is no configuration file.");
+        }
+        const pluginModule = requirePluginModule(config.name, referrer);
+        const init = typeof pluginModule === "function"
+          ? pluginModule
+          : pluginModule.default;
+        const plugin = init({ typescript: ts });
+        service = plugin.create({
+          config,
+          languageService: service,
+          languageServiceHost: host,
+          project: pluginProject,
+          serverHost: undefined,
+        }) ?? service;
+        debug(`Loaded the language service plugin "${config.name}".`);
+      } catch (e) {
+        error(
+          `Failed loading the language service plugin "${config.name}": ${
+            e?.stack ?? e
+          }`,
+        );
+      }
+    }
+    return service;
+  }
+
   let hasStarted = false;
   /** @param {{ debug: boolean; }} init */
   function serverInit({ debug: debugFlag }) {
@@ -1083,13 +1286,13 @@ delete Object.prototype.__proto__;
       throw new Error("The language server has already been initialized.");
     }
     hasStarted = true;
-    languageService = ts.createLanguageService(host, documentRegistry);
+    languageService = createLanguageService();
     setLogDebug(debugFlag, "TSLS");
     debug("serverInit()");
   }
 
   function serverRestart() {
-    languageService = ts.createLanguageService(host, documentRegistry);
+    languageService = createLanguageService();
     isNodeSourceFileCache.clear();
     debug("serverRestart()");
   }
@@ -1099,10 +1302,10 @@ delete Object.prototype.__proto__;
   /** @type {{ buildSpecifier: string; libs: string[]; nodeBuiltInModuleNames: string[] }} */
   const { buildSpecifier, libs, nodeBuiltInModuleNames } = ops.op_build_info();
 
-  ts.deno.setNodeBuiltInModuleNames(nodeBuiltInModuleNames);
+  ts.deno?.setNodeBuiltInModuleNames(nodeBuiltInModuleNames);
 
   // list of globals that should be kept in Node's globalThis
-  ts.deno.setNodeOnlyGlobalNames([
+  ts.deno?.setNodeOnlyGlobalNames([
     // when bumping the @types/node version we should check if
     // anything needs to be updated here
     "NodeRequire",
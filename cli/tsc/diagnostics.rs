@@ -287,6 +287,10 @@ impl Diagnostics {
     self.0.is_empty()
   }
 
+  pub fn extend(&mut self, other: Diagnostics) {
+    self.0.extend(other.0);
+  }
+
   /// Modifies all the diagnostics to have their display positions
   /// modified to point at the original source.
   pub fn apply_fast_check_source_maps(&mut self, graph: &ModuleGraph) {
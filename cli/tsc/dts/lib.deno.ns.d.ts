@@ -840,6 +840,12 @@ declare namespace Deno {
      *
      * @default {"inherit"} */
     permissions?: PermissionOptions;
+    /** The number of times a failing test is run again before it is reported
+     * as failed. A test which only passes on a retry is reported as flaky.
+     * The `--retry` flag of `deno test` is used when it's higher.
+     *
+     * @default {0} */
+    retry?: number;
   }
 
   /** Register a test which will be run when `deno test` is used on the command
@@ -5319,6 +5325,21 @@ declare namespace Deno {
    */
   export const mainModule: string;
 
+  /** The paths of the files embedded in an executable created by
+   * `deno compile` with the `--include` flag, which can be read with
+   * {@linkcode Deno.readFile}. This is `undefined` when not running a
+   * compiled executable.
+   *
+   * ```ts
+   * for (const path of Deno.embeddedFiles ?? []) {
+   *   console.log(path);
+   * }
+   * ```
+   *
+   * @category Runtime Environment
+   */
+  export const embeddedFiles: readonly string[] | undefined;
+
   /** Options that can be used with {@linkcode symlink} and
    * {@linkcode symlinkSync}.
    *
@@ -10,6 +10,29 @@
 declare namespace Deno {
   export {}; // stop default export type behavior
 
+  /** @category Testing */
+  export interface TestContext {
+    /** **UNSTABLE**: New API, yet to be vetted.
+     *
+     * Assert that a value matches its snapshot, which is stored in a
+     * `__snapshots__` directory next to the test module. The value is
+     * serialized with `Deno.inspect()`.
+     *
+     * Run `deno test --update-snapshots` to create or update the snapshots
+     * instead of comparing against them.
+     *
+     * ```ts
+     * Deno.test("a snapshot test", (t) => {
+     *   t.assertSnapshot({ hello: "world" });
+     * });
+     * ```
+     *
+     * Requires `--unstable-test-snapshot` or `"unstable": ["test-snapshot"]`
+     * in the configuration file.
+     */
+    assertSnapshot(actual: unknown): void;
+  }
+
   /** **UNSTABLE**: New API, yet to be vetted.
    *
    * Retrieve the process umask.  If `mask` is provided, sets the process umask.
@@ -1987,6 +2010,7 @@ declare namespace Deno {
       "text/html"?: string;
       "image/svg+xml"?: string;
       "text/markdown"?: string;
+      "text/latex"?: string;
       "application/javascript"?: string;
 
       // Images (per Jupyter spec) must be base64 encoded. We could _allow_
@@ -2005,6 +2029,11 @@ declare namespace Deno {
       "application/vnd.vega.v5+json"?: VegaObject;
       "application/vnd.vegalite.v4+json"?: VegaObject;
       "application/vnd.vegalite.v5+json"?: VegaObject;
+      "application/vnd.jupyter.widget-view+json"?: {
+        model_id: string;
+        version_major: number;
+        version_minor: number;
+      };
 
       // Must support a catch all for custom media types / mimetypes
       [key: string]: string | object | undefined;
@@ -2018,16 +2047,37 @@ declare namespace Deno {
       [$display]: () => MediaBundle | Promise<MediaBundle>;
     };
 
+    /**
+     * A handle of a displayed object, which updates it in place with
+     * `update_display_data` messages.
+     *
+     * @category Jupyter
+     */
+    export interface DisplayHandle {
+      readonly displayId: string;
+      update(obj: unknown, options?: { raw?: boolean }): Promise<void>;
+    }
+
     /**
      * Display function for Jupyter Deno Kernel.
      * Mimics the behavior of IPython's `display(obj, raw=True)` function to allow
      * asynchronous displaying of objects in Jupyter.
      *
+     * @example
+     * Update a displayed object in place.
+     * ```typescript
+     * const handle = await Deno.jupyter.display(Deno.jupyter.md`Loading...`);
+     * await handle.update(Deno.jupyter.md`**Done!**`);
+     * ```
+     *
      * @param obj - The object to be displayed
      * @param options - Display options with a default { raw: true }
      * @category Jupyter
      */
-    export function display(obj: unknown, options?: DisplayOptions): void;
+    export function display(
+      obj: unknown,
+      options?: DisplayOptions,
+    ): Promise<DisplayHandle>;
 
     /**
      * Show Markdown in Jupyter frontends with a tagged template function.
@@ -2093,6 +2143,125 @@ declare namespace Deno {
       ...values: unknown[]
     ): Displayable;
 
+    /**
+     * LaTeX Tagged Template Function.
+     *
+     * Takes a template string and returns a displayable object for Jupyter frontends.
+     *
+     * Example usage:
+     *
+     * latex`$$\int_0^1 x^2 \, dx = \frac{1}{3}$$`
+     *
+     * @category Jupyter
+     */
+    export function latex(
+      strings: TemplateStringsArray,
+      ...values: unknown[]
+    ): Displayable;
+
+    /**
+     * A progress bar in the output of a cell, which is updated in place.
+     *
+     * @category Jupyter
+     */
+    export interface ProgressBar {
+      readonly total: number;
+      readonly value: number;
+      update(value: number): Promise<void>;
+      increment(step?: number): Promise<void>;
+    }
+
+    /**
+     * Display a progress bar, which is rendered as a `<progress>` element in
+     * HTML frontends.
+     *
+     * @example
+     * ```typescript
+     * const bar = await Deno.jupyter.progress(files.length, { label: "Files" });
+     * for (const file of files) {
+     *   await process(file);
+     *   await bar.increment();
+     * }
+     * ```
+     *
+     * @category Jupyter
+     */
+    export function progress(
+      total: number,
+      options?: { label?: string },
+    ): Promise<ProgressBar>;
+
+    /**
+     * A comm of the Jupyter messaging protocol, which is a channel for custom
+     * messages with the frontend.
+     *
+     * @category Jupyter
+     */
+    export interface Comm {
+      readonly id: string;
+      readonly targetName: string;
+      send(
+        data: unknown,
+        extra?: {
+          metadata?: Record<string, unknown>;
+          buffers?: Uint8Array[];
+        },
+      ): Promise<void>;
+      onMessage(listener: (data: unknown) => void | Promise<void>): void;
+      close(data?: unknown): Promise<void>;
+    }
+
+    /**
+     * Open a comm with a target of the frontend.
+     *
+     * @category Jupyter
+     */
+    export function openComm(
+      targetName: string,
+      data?: unknown,
+      extra?: {
+        metadata?: Record<string, unknown>;
+        buffers?: Uint8Array[];
+      },
+    ): Promise<Comm>;
+
+    /**
+     * A widget of ipywidgets, whose state is synced with the frontend.
+     *
+     * @category Jupyter
+     */
+    export interface Widget extends Displayable {
+      readonly id: string;
+      readonly state: Record<string, unknown>;
+      set(state: Record<string, unknown>): Promise<void>;
+      onChange(
+        listener: (state: Record<string, unknown>) => void | Promise<void>,
+      ): void;
+      close(): Promise<void>;
+    }
+
+    /**
+     * Create a widget of `@jupyter-widgets/controls`, which is shown by
+     * frontends with the ipywidgets extension.
+     *
+     * @example
+     * ```typescript
+     * const slider = await Deno.jupyter.widget("IntSliderModel", {
+     *   value: 5,
+     *   min: 0,
+     *   max: 10,
+     * });
+     * slider.onChange((state) => console.log(state.value));
+     * slider
+     * ```
+     *
+     * @category Jupyter
+     */
+    export function widget(
+      modelName: string,
+      state?: Record<string, unknown>,
+    ): Promise<Widget>;
+
     /**
      * Format an object for displaying in Deno
      *
@@ -21,6 +21,7 @@ use deno_core::serde::Deserializer;
 use deno_core::serde::Serialize;
 use deno_core::serde::Serializer;
 use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
 use deno_core::serde_v8;
 use deno_core::JsRuntime;
 use deno_core::ModuleSpecifier;
@@ -47,11 +48,13 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 mod diagnostics;
+mod pinned;
 
 pub use self::diagnostics::Diagnostic;
 pub use self::diagnostics::DiagnosticCategory;
 pub use self::diagnostics::Diagnostics;
 pub use self::diagnostics::Position;
+pub use self::pinned::PinnedTypeScript;
 
 pub static COMPILER_SNAPSHOT: Lazy<Box<[u8]>> = Lazy::new(
   #[cold]
@@ -230,6 +233,99 @@ impl fmt::Display for Stats {
   }
 }
 
+/// An event of the trace of a type check operation, which is the time it took
+/// to parse, resolve the imports of, bind or check a file.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceEvent {
+  pub name: String,
+  pub file_name: String,
+  /// The start of the event in microseconds since the start of the check.
+  pub start: u64,
+  /// The duration of the event in microseconds.
+  pub duration: u64,
+  /// The number of types the event instantiated, for the check of a file.
+  pub instantiations: Option<u32>,
+}
+
+/// The trace of a type check operation for `deno check --trace`.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct Trace(pub Vec<TraceEvent>);
+
+impl Trace {
+  /// Converts the trace to the Chrome trace event format, which can be
+  /// opened in `chrome://tracing` or https://ui.perfetto.dev.
+  pub fn to_chrome_trace(&self) -> Value {
+    let events = self
+      .0
+      .iter()
+      .map(|event| {
+        json!({
+          "name": event.name,
+          "cat": event.name,
+          "ph": "X",
+          "ts": event.start,
+          "dur": event.duration,
+          "pid": 1,
+          "tid": 1,
+          "args": {
+            "path": event.file_name,
+            "instantiations": event.instantiations,
+          },
+        })
+      })
+      .collect::<Vec<_>>();
+    json!({ "traceEvents": events, "displayTimeUnit": "ms" })
+  }
+
+  /// Gets the files which took the longest to check, with the most
+  /// instantiated types first when the times are the same.
+  pub fn hotspots(&self, count: usize) -> Vec<&TraceEvent> {
+    let mut events = self
+      .0
+      .iter()
+      .filter(|event| event.name == "check")
+      .collect::<Vec<_>>();
+    events.sort_by(|a, b| {
+      b.duration
+        .cmp(&a.duration)
+        .then_with(|| b.instantiations.cmp(&a.instantiations))
+    });
+    events.truncate(count);
+    events
+  }
+}
+
+impl fmt::Display for Trace {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    for name in ["parse", "resolve", "bind", "check"] {
+      let duration = self
+        .0
+        .iter()
+        .filter(|event| event.name == name)
+        .map(|event| event.duration)
+        .sum::<u64>();
+      writeln!(f, "Total {name} time: {:.2}ms", duration as f64 / 1000.0)?;
+    }
+    writeln!(f, "Type check hotspots:")?;
+    for event in self.hotspots(10) {
+      write!(
+        f,
+        "  {:.2}ms {}",
+        event.duration as f64 / 1000.0,
+        event.file_name
+      )?;
+      match event.instantiations {
+        Some(instantiations) => {
+          writeln!(f, " ({instantiations} instantiations)")?
+        }
+        None => writeln!(f)?,
+      }
+    }
+    Ok(())
+  }
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AssetText {
@@ -321,6 +417,11 @@ pub struct Request {
   /// program.
   pub root_names: Vec<(ModuleSpecifier, MediaType)>,
   pub check_mode: TypeCheckMode,
+  /// Whether to trace the time spent on every file.
+  pub trace: bool,
+  /// The compiler pinned by the configuration file, which is used instead of
+  /// the built-in one.
+  pub maybe_pinned_typescript: Option<Arc<PinnedTypeScript>>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -331,6 +432,8 @@ pub struct Response {
   pub maybe_tsbuildinfo: Option<String>,
   /// Statistics from the check.
   pub stats: Stats,
+  /// The trace of the check, when it was requested.
+  pub maybe_trace: Option<Trace>,
 }
 
 // TODO(bartlomieju): we have similar struct in `tsc.rs` - maybe at least change
@@ -774,6 +877,8 @@ fn op_is_node_file(state: &mut OpState, #[string] path: &str) -> bool {
 struct RespondArgs {
   pub diagnostics: Diagnostics,
   pub stats: Stats,
+  #[serde(default)]
+  pub trace: Option<Trace>,
 }
 
 // TODO(bartlomieju): this mechanism is questionable.
@@ -848,11 +953,16 @@ pub fn exec(request: Request) -> Result<Response, AnyError> {
     "debug": request.debug,
     "rootNames": root_names,
     "localOnly": request.check_mode == TypeCheckMode::Local,
+    "trace": request.trace,
   });
   let exec_source = format!("globalThis.exec({request_value})").into();
+  let startup_snapshot = match &request.maybe_pinned_typescript {
+    Some(pinned_typescript) => pinned_typescript.load_snapshot()?,
+    None => compiler_snapshot(),
+  };
 
   let mut runtime = JsRuntime::new(RuntimeOptions {
-    startup_snapshot: Some(compiler_snapshot()),
+    startup_snapshot: Some(startup_snapshot),
     extensions: vec![deno_cli_tsc::init_ops(
       request,
       root_map,
@@ -876,6 +986,7 @@ pub fn exec(request: Request) -> Result<Response, AnyError> {
       diagnostics,
       maybe_tsbuildinfo,
       stats,
+      maybe_trace: response.trace,
     })
   } else {
     Err(anyhow!("The response for the exec request was not set."))
@@ -990,6 +1101,8 @@ mod tests {
       maybe_tsbuildinfo: None,
       root_names: vec![(specifier.clone(), MediaType::TypeScript)],
       check_mode: TypeCheckMode::All,
+      trace: false,
+      maybe_pinned_typescript: None,
     };
     exec(request)
   }
@@ -1199,9 +1312,71 @@ mod tests {
           file_name: None,
           related_information: None,
         }]),
-        stats: Stats(vec![("a".to_string(), 12)])
+        stats: Stats(vec![("a".to_string(), 12)]),
+        trace: None,
+      })
+    );
+  }
+
+  #[test]
+  fn test_trace() {
+    let trace: Trace = serde_json::from_value(json!([
+      {
+        "name": "parse",
+        "fileName": "file:///a.ts",
+        "start": 0,
+        "duration": 200,
+      },
+      {
+        "name": "check",
+        "fileName": "file:///a.ts",
+        "start": 300,
+        "duration": 1500,
+        "instantiations": 20,
+      },
+      {
+        "name": "check",
+        "fileName": "file:///b.ts",
+        "start": 1800,
+        "duration": 2500,
+        "instantiations": 5,
+      },
+    ]))
+    .unwrap();
+    let hotspots = trace
+      .hotspots(1)
+      .into_iter()
+      .map(|event| event.file_name.as_str())
+      .collect::<Vec<_>>();
+    assert_eq!(hotspots, vec!["file:///b.ts"]);
+    assert_eq!(
+      trace.to_chrome_trace()["traceEvents"][1],
+      json!({
+        "name": "check",
+        "cat": "check",
+        "ph": "X",
+        "ts": 300,
+        "dur": 1500,
+        "pid": 1,
+        "tid": 1,
+        "args": {
+          "path": "file:///a.ts",
+          "instantiations": 20,
+        },
       })
     );
+    assert_eq!(
+      trace.to_string(),
+      concat!(
+        "Total parse time: 0.20ms\n",
+        "Total resolve time: 0.00ms\n",
+        "Total bind time: 0.00ms\n",
+        "Total check time: 4.00ms\n",
+        "Type check hotspots:\n",
+        "  2.50ms file:///b.ts (5 instantiations)\n",
+        "  1.50ms file:///a.ts (20 instantiations)\n",
+      )
+    );
   }
 
   #[tokio::test]
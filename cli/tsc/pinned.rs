@@ -0,0 +1,263 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::custom_error;
+use deno_core::error::AnyError;
+use deno_core::op2;
+use deno_core::serde::Serialize;
+use deno_core::JsRuntimeForSnapshot;
+use deno_core::OpState;
+use deno_core::RuntimeOptions;
+use deno_core::Snapshot;
+use deno_runtime::deno_node::SUPPORTED_BUILTIN_NODE_MODULES;
+use deno_semver::package::PackageNv;
+use deno_semver::Version;
+use deno_terminal::colors;
+
+use crate::cache::CACHE_PERM;
+use crate::npm::CliNpmResolver;
+use crate::util::fs::atomic_write_file;
+use crate::version;
+
+const BUILD_SPECIFIER: &str = "asset:///bootstrap.ts";
+
+/// A TypeScript compiler of another version than the one built into Deno,
+/// which is pinned by the `"typescriptVersion"` of the configuration file.
+///
+/// The `typescript` npm package is downloaded and snapshotted together with
+/// the type libraries of Deno on first use. The snapshot is kept in the cache,
+/// so `deno check` and the language server start it as fast as the built-in
+/// compiler. The package doesn't have the changes of Deno's fork of
+/// TypeScript, so the globals which only exist in Node.js aren't limited to
+/// the files of npm packages.
+#[derive(Debug)]
+pub struct PinnedTypeScript {
+  version: Version,
+  snapshot_path: PathBuf,
+}
+
+impl PinnedTypeScript {
+  pub fn new(snapshots_dir: &Path, version: Version) -> Self {
+    // a snapshot only works with the V8 and the compiler host it was made with
+    let snapshot_path = snapshots_dir
+      .join(version::deno())
+      .join(format!("typescript-{version}.bin"));
+    Self {
+      version,
+      snapshot_path,
+    }
+  }
+
+  pub fn version(&self) -> &Version {
+    &self.version
+  }
+
+  pub fn is_cached(&self) -> bool {
+    self.snapshot_path.exists()
+  }
+
+  /// Downloads and snapshots the compiler when it isn't cached yet.
+  pub async fn ensure_cached(
+    &self,
+    npm_resolver: &dyn CliNpmResolver,
+  ) -> Result<(), AnyError> {
+    if self.is_cached() {
+      return Ok(());
+    }
+    // the compiler host needs the APIs of TypeScript 5.3
+    if self.version < Version::parse_standard("5.3.0").unwrap() {
+      bail!(
+        "TypeScript {} is not supported by \"typescriptVersion\". Use 5.3.0 or later.",
+        self.version
+      );
+    }
+    let Some(npm_resolver) = npm_resolver.as_managed() else {
+      bail!(
+        "\"typescriptVersion\" requires the npm cache of Deno, which isn't used with a node_modules directory managed by another package manager."
+      );
+    };
+    let nv = PackageNv {
+      name: "typescript".to_string(),
+      version: self.version.clone(),
+    };
+    let package_folder = npm_resolver.cache_package_version(&nv).await?;
+    log::info!(
+      "{} TypeScript {}",
+      colors::green("Snapshotting"),
+      self.version
+    );
+    let snapshot =
+      create_snapshot(&package_folder.join("lib")).with_context(|| {
+        format!("Failed snapshotting TypeScript {}", self.version)
+      })?;
+    atomic_write_file(&self.snapshot_path, snapshot, CACHE_PERM).with_context(
+      || {
+        format!(
+          "Failed writing the snapshot of TypeScript {} to \"{}\"",
+          self.version,
+          self.snapshot_path.display()
+        )
+      },
+    )?;
+    Ok(())
+  }
+
+  pub fn load_snapshot(&self) -> Result<Snapshot, AnyError> {
+    let data = std::fs::read(&self.snapshot_path).with_context(|| {
+      format!(
+        "Failed reading the snapshot of TypeScript {} from \"{}\"",
+        self.version,
+        self.snapshot_path.display()
+      )
+    })?;
+    Ok(Snapshot::Boxed(data.into_boxed_slice()))
+  }
+}
+
+/// The texts of the type libraries to put in the snapshot by their names,
+/// like `"es2022"` for `lib.es2022.d.ts`.
+struct SnapshotLibs(HashMap<String, String>);
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BuildInfoResponse {
+  build_specifier: String,
+  libs: Vec<String>,
+  node_built_in_module_names: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LoadResponse {
+  data: String,
+  version: String,
+  script_kind: i32,
+}
+
+// these ops are the same as the ones used to build the snapshot of the
+// built-in compiler in `build.rs`
+
+#[op2]
+#[serde]
+fn op_build_info(state: &mut OpState) -> BuildInfoResponse {
+  let mut libs = state
+    .borrow::<SnapshotLibs>()
+    .0
+    .keys()
+    .cloned()
+    .collect::<Vec<_>>();
+  libs.sort();
+  BuildInfoResponse {
+    build_specifier: BUILD_SPECIFIER.to_string(),
+    libs,
+    node_built_in_module_names: SUPPORTED_BUILTIN_NODE_MODULES
+      .iter()
+      .map(|s| s.to_string())
+      .collect(),
+  }
+}
+
+#[op2(fast)]
+fn op_is_node_file() -> bool {
+  false
+}
+
+#[op2]
+#[string]
+fn op_script_version(
+  _state: &mut OpState,
+  #[string] _arg: &str,
+) -> Result<Option<String>, AnyError> {
+  Ok(Some("1".to_string()))
+}
+
+#[op2]
+#[serde]
+fn op_load(
+  state: &mut OpState,
+  #[string] load_specifier: &str,
+) -> Result<LoadResponse, AnyError> {
+  // a basic file to warm up tsc
+  let data = if load_specifier == BUILD_SPECIFIER {
+    r#"Deno.writeTextFile("hello.txt", "hello deno!");"#.to_string()
+  } else {
+    let maybe_data = load_specifier
+      .strip_prefix("asset:///lib.")
+      .and_then(|name| name.strip_suffix(".d.ts"))
+      .and_then(|lib| state.borrow::<SnapshotLibs>().0.get(lib));
+    match maybe_data {
+      Some(data) => data.clone(),
+      None => {
+        return Err(custom_error(
+          "InvalidSpecifier",
+          format!("An invalid specifier was requested: {}", load_specifier),
+        ))
+      }
+    }
+  };
+  Ok(LoadResponse {
+    data,
+    version: "1".to_string(),
+    // this corresponds to `ts.ScriptKind.TypeScript`
+    script_kind: 3,
+  })
+}
+
+deno_core::extension!(deno_tsc_pinned,
+  ops = [op_build_info, op_is_node_file, op_load, op_script_version],
+  options = {
+    libs: SnapshotLibs,
+  },
+  state = |state, options| {
+    state.put(options.libs);
+  },
+);
+
+/// Creates a snapshot of the compiler in the `lib` folder of the `typescript`
+/// package, like the one of the built-in compiler.
+fn create_snapshot(lib_dir: &Path) -> Result<Box<[u8]>, AnyError> {
+  let typescript_source =
+    std::fs::read_to_string(lib_dir.join("typescript.js"))
+      .with_context(|| format!("Failed reading \"{}\"", lib_dir.display()))?;
+  let mut libs = HashMap::new();
+  for entry in std::fs::read_dir(lib_dir)? {
+    let file_name = entry?.file_name().to_string_lossy().to_string();
+    let maybe_lib = file_name
+      .strip_prefix("lib.")
+      .and_then(|name| name.strip_suffix(".d.ts"));
+    if let Some(lib) = maybe_lib {
+      let text = std::fs::read_to_string(lib_dir.join(&file_name))?;
+      libs.insert(lib.to_string(), text);
+    }
+  }
+  // the type libraries of Deno are taken out of the built-in compiler
+  for asset in super::get_asset_texts_from_new_runtime()? {
+    let maybe_lib = asset
+      .specifier
+      .strip_prefix("asset:///lib.")
+      .and_then(|name| name.strip_suffix(".d.ts"))
+      .filter(|lib| lib.starts_with("deno."));
+    if let Some(lib) = maybe_lib {
+      libs.insert(lib.to_string(), asset.text);
+    }
+  }
+
+  let mut runtime = JsRuntimeForSnapshot::new(RuntimeOptions {
+    extensions: vec![deno_tsc_pinned::init_ops(SnapshotLibs(libs))],
+    ..Default::default()
+  });
+  runtime.execute_script(
+    "ext:deno_tsc/00_typescript.js",
+    typescript_source.into(),
+  )?;
+  runtime.execute_script(
+    "ext:deno_tsc/99_main_compiler.js",
+    include_str!("99_main_compiler.js").to_string().into(),
+  )?;
+  Ok(runtime.snapshot().to_vec().into_boxed_slice())
+}
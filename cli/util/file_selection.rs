@@ -0,0 +1,144 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use deno_config::glob::FilePatterns;
+use deno_config::glob::PathOrPattern;
+use deno_config::glob::PathOrPatternSet;
+use deno_core::error::AnyError;
+use deno_core::ModuleSpecifier;
+
+use super::gitignore::GitIgnoreTree;
+
+/// Selects the files of a subcommand on top of its `FilePatterns`, so every
+/// subcommand and the language server agree on which files are excluded.
+///
+/// An entry of `exclude` that starts with `!`, like `"!dist/keep.ts"`,
+/// includes the paths it matches again, even when another entry excludes
+/// them. The paths ignored by the `.gitignore` files can be excluded too,
+/// which is the default of `deno fmt`, `deno lint` and `deno test`.
+#[derive(Debug, Clone, Default)]
+pub struct FileSelection {
+  unexcluded: PathOrPatternSet,
+  maybe_gitignore: Option<Arc<GitIgnoreTree>>,
+}
+
+impl FileSelection {
+  /// Creates the selection with the negated entries of `exclude`, which are
+  /// relative to `base`. Entries which aren't negated are skipped, since they
+  /// are already part of the `FilePatterns`.
+  pub fn new(
+    base: &Path,
+    excludes: &[String],
+    use_gitignore: bool,
+  ) -> Result<Self, AnyError> {
+    let negated = excludes
+      .iter()
+      .filter_map(|exclude| exclude.strip_prefix('!'))
+      .map(|exclude| exclude.to_string())
+      .collect::<Vec<_>>();
+    Ok(Self {
+      unexcluded: PathOrPatternSet::from_relative_path_or_patterns(
+        base, &negated,
+      )?,
+      maybe_gitignore: use_gitignore.then(Default::default),
+    })
+  }
+
+  /// Checks if the path is excluded by the patterns, the `.gitignore` files,
+  /// or neither because a negated entry matches it.
+  pub fn is_excluded(
+    &self,
+    file_patterns: &FilePatterns,
+    path: &Path,
+    is_dir: bool,
+  ) -> bool {
+    let is_excluded = file_patterns.exclude.matches_path(path)
+      || self
+        .maybe_gitignore
+        .as_ref()
+        .map(|gitignore| gitignore.is_ignored(path, is_dir))
+        .unwrap_or(false);
+    is_excluded && !self.unexcluded.matches_path(path)
+  }
+
+  /// Checks if an excluded directory can be skipped while walking, which
+  /// isn't the case when a negated entry may match a path in it.
+  pub fn can_skip_excluded_dir(&self, dir: &Path) -> bool {
+    !self.unexcluded.inner().iter().any(|p| match p {
+      PathOrPattern::Path(path) => path.starts_with(dir),
+      PathOrPattern::RemoteUrl(_) => false,
+      PathOrPattern::Pattern(_) => true,
+    })
+  }
+
+  pub fn matches_path(
+    &self,
+    file_patterns: &FilePatterns,
+    path: &Path,
+  ) -> bool {
+    let is_included = file_patterns
+      .include
+      .as_ref()
+      .map(|include| include.matches_path(path))
+      .unwrap_or(true);
+    is_included && !self.is_excluded(file_patterns, path, path.is_dir())
+  }
+
+  pub fn matches_specifier(
+    &self,
+    file_patterns: &FilePatterns,
+    specifier: &ModuleSpecifier,
+  ) -> bool {
+    if specifier.scheme() != "file" {
+      return file_patterns.matches_specifier(specifier);
+    }
+    match specifier.to_file_path() {
+      Ok(path) => self.matches_path(file_patterns, &path),
+      Err(_) => false,
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use test_util::TempDir;
+
+  #[test]
+  fn test_file_selection() {
+    let temp_dir = TempDir::new();
+    temp_dir.create_dir_all(".git");
+    temp_dir.write(".gitignore", "generated/\n");
+    temp_dir.create_dir_all("dist");
+    temp_dir.write("dist/keep.ts", "");
+    let base = temp_dir.path().to_path_buf();
+    let path = |name: &str| base.join(name);
+    let excludes = vec!["dist".to_string(), "!dist/keep.ts".to_string()];
+    let file_patterns = FilePatterns {
+      base: base.clone(),
+      include: None,
+      exclude: PathOrPatternSet::from_relative_path_or_patterns(
+        &base, &excludes,
+      )
+      .unwrap(),
+    };
+
+    let selection = FileSelection::new(&base, &excludes, false).unwrap();
+    assert!(selection.matches_path(&file_patterns, &path("main.ts")));
+    assert!(!selection.matches_path(&file_patterns, &path("dist/mod.ts")));
+    assert!(selection.matches_path(&file_patterns, &path("dist/keep.ts")));
+    assert!(selection.matches_path(&file_patterns, &path("generated/a.ts")));
+    assert!(!selection.can_skip_excluded_dir(&path("dist")));
+    assert!(selection.can_skip_excluded_dir(&path("other")));
+    assert!(selection.matches_specifier(
+      &file_patterns,
+      &ModuleSpecifier::parse("https://deno.land/x/mod.ts").unwrap()
+    ));
+
+    let selection = FileSelection::new(&base, &excludes, true).unwrap();
+    assert!(!selection.matches_path(&file_patterns, &path("generated/a.ts")));
+    assert!(selection.matches_path(&file_patterns, &path("dist/keep.ts")));
+  }
+}
@@ -186,6 +186,38 @@ impl WatcherCommunicator {
   }
 }
 
+/// Gets the files to process again on a restart of the watcher, which are the
+/// changed files and the files which still had problems in the previous run,
+/// so that their problems are still shown after the screen is cleared
+/// (https://github.com/denoland/deno/issues/12446). All of the files are
+/// processed again when another path changed, like a config file.
+pub fn files_to_rerun(
+  files: Vec<PathBuf>,
+  changed_paths: &[PathBuf],
+  files_with_problems: &HashSet<PathBuf>,
+) -> Vec<PathBuf> {
+  let canonicalized_files = files
+    .iter()
+    .map(|path| canonicalize_path(path).unwrap_or_else(|_| path.clone()))
+    .collect::<Vec<_>>();
+  let changed_paths = changed_paths.iter().collect::<HashSet<_>>();
+  if changed_paths
+    .iter()
+    .any(|path| !canonicalized_files.contains(path))
+  {
+    return files;
+  }
+  files
+    .into_iter()
+    .zip(canonicalized_files)
+    .filter(|(path, canonicalized_path)| {
+      changed_paths.contains(canonicalized_path)
+        || files_with_problems.contains(path)
+    })
+    .map(|(path, _)| path)
+    .collect()
+}
+
 /// Creates a file watcher.
 ///
 /// - `operation` is the actual operation we want to run every time the watcher detects file
@@ -409,3 +441,40 @@ fn consume_paths_to_watch(
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use test_util::TempDir;
+
+  #[test]
+  fn test_files_to_rerun() {
+    let temp_dir = TempDir::new();
+    for name in ["a.ts", "b.ts", "c.ts", "deno.json"] {
+      temp_dir.write(name, "");
+    }
+    let path = |name: &str| temp_dir.path().join(name).to_path_buf();
+    let canonicalized = |name: &str| canonicalize_path(&path(name)).unwrap();
+    let files = vec![path("a.ts"), path("b.ts"), path("c.ts")];
+    let files_with_problems = HashSet::from([path("c.ts")]);
+
+    // the changed file and the file with problems
+    assert_eq!(
+      files_to_rerun(
+        files.clone(),
+        &[canonicalized("a.ts")],
+        &files_with_problems
+      ),
+      vec![path("a.ts"), path("c.ts")],
+    );
+    // every file when the config file changed
+    assert_eq!(
+      files_to_rerun(
+        files.clone(),
+        &[canonicalized("a.ts"), canonicalized("deno.json")],
+        &files_with_problems
+      ),
+      files,
+    );
+  }
+}
@@ -26,6 +26,7 @@ use deno_runtime::deno_crypto::rand;
 use deno_runtime::deno_fs::FileSystem;
 use deno_runtime::deno_node::PathClean;
 
+use crate::util::file_selection::FileSelection;
 use crate::util::progress_bar::ProgressBar;
 use crate::util::progress_bar::ProgressBarStyle;
 use crate::util::progress_bar::ProgressMessagePrompt;
@@ -248,6 +249,7 @@ pub fn resolve_from_cwd(path: &Path) -> Result<PathBuf, AnyError> {
 /// If the walker visits a path that is listed in `ignore`, it skips descending into the directory.
 pub struct FileCollector<TFilter: Fn(&Path, &FilePatterns) -> bool> {
   file_filter: TFilter,
+  file_selection: FileSelection,
   ignore_git_folder: bool,
   ignore_node_modules: bool,
   ignore_vendor_folder: bool,
@@ -257,6 +259,7 @@ impl<TFilter: Fn(&Path, &FilePatterns) -> bool> FileCollector<TFilter> {
   pub fn new(file_filter: TFilter) -> Self {
     Self {
       file_filter,
+      file_selection: Default::default(),
       ignore_git_folder: false,
       ignore_node_modules: false,
       ignore_vendor_folder: false,
@@ -278,6 +281,13 @@ impl<TFilter: Fn(&Path, &FilePatterns) -> bool> FileCollector<TFilter> {
     self
   }
 
+  /// Selects the files with the negated excludes and the `.gitignore` files
+  /// of the selection, on top of the file patterns.
+  pub fn file_selection(mut self, file_selection: FileSelection) -> Self {
+    self.file_selection = file_selection;
+    self
+  }
+
   pub fn collect_file_patterns(
     &self,
     file_patterns: FilePatterns,
@@ -300,7 +310,9 @@ impl<TFilter: Fn(&Path, &FilePatterns) -> bool> FileCollector<TFilter> {
         let file_type = e.file_type();
         let is_dir = file_type.is_dir();
         let c = e.path().to_path_buf();
-        if file_patterns.exclude.matches_path(&c)
+        let is_excluded =
+          self.file_selection.is_excluded(&file_patterns, &c, is_dir);
+        if is_excluded
           || !is_dir
             && !file_patterns
               .include
@@ -308,7 +320,7 @@ impl<TFilter: Fn(&Path, &FilePatterns) -> bool> FileCollector<TFilter> {
               .map(|i| i.matches_path(&c))
               .unwrap_or(true)
         {
-          if is_dir {
+          if is_dir && self.file_selection.can_skip_excluded_dir(&c) {
             iterator.skip_current_dir();
           }
         } else if is_dir {
@@ -346,6 +358,7 @@ impl<TFilter: Fn(&Path, &FilePatterns) -> bool> FileCollector<TFilter> {
 /// Note: This ignores all .git and node_modules folders.
 pub fn collect_specifiers(
   mut files: FilePatterns,
+  file_selection: &FileSelection,
   predicate: impl Fn(&Path, &FilePatterns) -> bool,
 ) -> Result<Vec<ModuleSpecifier>, AnyError> {
   let mut prepared = vec![];
@@ -360,7 +373,7 @@ pub fn collect_specifiers(
         PathOrPattern::Path(path) => {
           if path.is_dir() {
             result.push(PathOrPattern::Path(path));
-          } else if !files.exclude.matches_path(&path) {
+          } else if !file_selection.is_excluded(&files, &path, false) {
             let url = specifier_from_file_path(&path)?;
             prepared.push(url);
           }
@@ -378,6 +391,7 @@ pub fn collect_specifiers(
   }
 
   let collected_files = FileCollector::new(predicate)
+    .file_selection(file_selection.clone())
     .ignore_git_folder()
     .ignore_node_modules()
     .ignore_vendor_folder()
@@ -510,6 +524,64 @@ pub fn hard_link_dir_recursive(from: &Path, to: &Path) -> Result<(), AnyError> {
   Ok(())
 }
 
+/// Hardlinks the files in one directory to another directory, which copies
+/// the files instead once the file system doesn't support hard linking them,
+/// for example when the directories are on different devices. Copying uses
+/// copy-on-write clones of the files on the file systems supporting them.
+///
+/// Note: Does not handle symlinks.
+pub fn hard_link_or_copy_dir_recursive(
+  from: &Path,
+  to: &Path,
+) -> Result<(), AnyError> {
+  fn inner(
+    from: &Path,
+    to: &Path,
+    can_link: &mut bool,
+  ) -> Result<(), AnyError> {
+    std::fs::create_dir_all(to)
+      .with_context(|| format!("Creating {}", to.display()))?;
+    let read_dir = std::fs::read_dir(from)
+      .with_context(|| format!("Reading {}", from.display()))?;
+
+    for entry in read_dir {
+      let entry = entry?;
+      let file_type = entry.file_type()?;
+      let new_from = from.join(entry.file_name());
+      let new_to = to.join(entry.file_name());
+
+      if file_type.is_dir() {
+        inner(&new_from, &new_to, can_link).with_context(|| {
+          format!("Dir {} to {}", new_from.display(), new_to.display())
+        })?;
+      } else if file_type.is_file() {
+        if *can_link {
+          match std::fs::hard_link(&new_from, &new_to) {
+            Ok(()) => continue,
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => continue,
+            Err(err) => {
+              log::debug!(
+                "Failed hard linking {} to {}, copying instead. {:#}",
+                new_from.display(),
+                new_to.display(),
+                err
+              );
+              *can_link = false;
+            }
+          }
+        }
+        std::fs::copy(&new_from, &new_to).with_context(|| {
+          format!("Copying {} to {}", new_from.display(), new_to.display())
+        })?;
+      }
+    }
+
+    Ok(())
+  }
+
+  inner(from, to, &mut true)
+}
+
 pub fn symlink_dir(oldpath: &Path, newpath: &Path) -> Result<(), AnyError> {
   let err_mapper = |err: Error| {
     Error::new(
@@ -970,6 +1042,7 @@ mod tests {
           ignore_dir_path.to_path_buf(),
         )]),
       },
+      &Default::default(),
       predicate,
     )
     .unwrap();
@@ -1015,6 +1088,7 @@ mod tests {
         .unwrap()])),
         exclude: Default::default(),
       },
+      &Default::default(),
       predicate,
     )
     .unwrap();
@@ -1034,6 +1108,25 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_hard_link_or_copy_dir_recursive() {
+    let temp_dir = TempDir::new();
+    let from = temp_dir.path().join("from");
+    from.join("sub").create_dir_all();
+    from.join("a.js").write("a");
+    from.join("sub/b.js").write("b");
+    let to = temp_dir.path().join("to");
+    hard_link_or_copy_dir_recursive(from.as_path(), to.as_path()).unwrap();
+    assert_eq!(to.join("a.js").read_to_string(), "a");
+    assert_eq!(to.join("sub/b.js").read_to_string(), "b");
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::MetadataExt;
+      let metadata = std::fs::metadata(to.join("sub/b.js")).unwrap();
+      assert_eq!(metadata.nlink(), 2);
+    }
+  }
+
   #[tokio::test]
   async fn lax_fs_lock() {
     let temp_dir = TempDir::new();
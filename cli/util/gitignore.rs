@@ -0,0 +1,181 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use deno_core::parking_lot::Mutex;
+
+/// A rule of a `.gitignore` file.
+#[derive(Debug)]
+struct GitIgnoreRule {
+  pattern: glob::Pattern,
+  negated: bool,
+  dir_only: bool,
+}
+
+impl GitIgnoreRule {
+  fn parse(line: &str) -> Option<Self> {
+    let line = line.trim_end();
+    if line.is_empty() || line.starts_with('#') {
+      return None;
+    }
+    let (negated, line) = match line.strip_prefix('!') {
+      Some(line) => (true, line),
+      None => (false, line.strip_prefix('\\').unwrap_or(line)),
+    };
+    let (dir_only, line) = match line.strip_suffix('/') {
+      Some(line) => (true, line),
+      None => (false, line),
+    };
+    // a pattern with a slash is relative to the directory of the .gitignore,
+    // otherwise it matches a name at any depth
+    let pattern = if line.contains('/') {
+      line.trim_start_matches('/').to_string()
+    } else {
+      format!("**/{line}")
+    };
+    let pattern = glob::Pattern::new(&pattern).ok()?;
+    Some(Self {
+      pattern,
+      negated,
+      dir_only,
+    })
+  }
+
+  fn matches(&self, relative_path: &str, is_dir: bool) -> bool {
+    (is_dir || !self.dir_only)
+      && self.pattern.matches_with(
+        relative_path,
+        glob::MatchOptions {
+          case_sensitive: true,
+          require_literal_separator: true,
+          require_literal_leading_dot: false,
+        },
+      )
+  }
+}
+
+#[derive(Debug)]
+struct DirInfo {
+  rules: Vec<GitIgnoreRule>,
+  is_git_root: bool,
+}
+
+/// The `.gitignore` files of a git repository, which are read lazily for
+/// the directories of the paths being checked.
+#[derive(Debug, Default)]
+pub struct GitIgnoreTree {
+  dirs: Mutex<HashMap<PathBuf, Arc<DirInfo>>>,
+  ignored_dirs: Mutex<HashMap<PathBuf, bool>>,
+}
+
+impl GitIgnoreTree {
+  /// Checks if the path is ignored by the `.gitignore` files of its
+  /// directory and of the parent directories up to the root of the git
+  /// repository. Like git, files in an ignored directory are always ignored.
+  pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+    let Some(parent) = path.parent() else {
+      return false;
+    };
+    let mut dirs = Vec::new();
+    for dir in parent.ancestors() {
+      let info = self.dir_info(dir);
+      let is_git_root = info.is_git_root;
+      dirs.push((dir, info));
+      if is_git_root {
+        break;
+      }
+    }
+    if self.is_ignored_dir(parent) {
+      return true;
+    }
+    let mut is_ignored = false;
+    // the rules of the deeper directories take precedence
+    for (dir, info) in dirs.iter().rev() {
+      let Some(relative_path) = relative_path_text(dir, path) else {
+        continue;
+      };
+      for rule in &info.rules {
+        if rule.matches(&relative_path, is_dir) {
+          is_ignored = !rule.negated;
+        }
+      }
+    }
+    is_ignored
+  }
+
+  fn is_ignored_dir(&self, dir: &Path) -> bool {
+    if let Some(is_ignored) = self.ignored_dirs.lock().get(dir) {
+      return *is_ignored;
+    }
+    let is_ignored =
+      !self.dir_info(dir).is_git_root && self.is_ignored(dir, true);
+    self
+      .ignored_dirs
+      .lock()
+      .insert(dir.to_path_buf(), is_ignored);
+    is_ignored
+  }
+
+  fn dir_info(&self, dir: &Path) -> Arc<DirInfo> {
+    if let Some(info) = self.dirs.lock().get(dir) {
+      return info.clone();
+    }
+    let rules = std::fs::read_to_string(dir.join(".gitignore"))
+      .map(|text| text.lines().filter_map(GitIgnoreRule::parse).collect())
+      .unwrap_or_default();
+    let info = Arc::new(DirInfo {
+      rules,
+      is_git_root: dir.join(".git").exists(),
+    });
+    self.dirs.lock().insert(dir.to_path_buf(), info.clone());
+    info
+  }
+}
+
+fn relative_path_text(dir: &Path, path: &Path) -> Option<String> {
+  let relative_path = path.strip_prefix(dir).ok()?;
+  Some(
+    relative_path
+      .components()
+      .map(|component| component.as_os_str().to_string_lossy())
+      .collect::<Vec<_>>()
+      .join("/"),
+  )
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use test_util::TempDir;
+
+  #[test]
+  fn test_git_ignore_tree() {
+    let temp_dir = TempDir::new();
+    temp_dir.create_dir_all(".git");
+    temp_dir.write(
+      ".gitignore",
+      "# build output\ndist/\n*.log\n!keep.log\n/root_only.ts\n",
+    );
+    temp_dir.create_dir_all("sub");
+    temp_dir.write("sub/.gitignore", "generated.ts\n!/dist\n");
+    let path = |name: &str| temp_dir.path().join(name).to_path_buf();
+    let tree = GitIgnoreTree::default();
+
+    assert!(tree.is_ignored(&path("dist"), true));
+    assert!(tree.is_ignored(&path("dist/mod.ts"), false));
+    assert!(!tree.is_ignored(&path("dist"), false));
+    assert!(tree.is_ignored(&path("debug.log"), false));
+    assert!(tree.is_ignored(&path("sub/debug.log"), false));
+    assert!(!tree.is_ignored(&path("keep.log"), false));
+    assert!(tree.is_ignored(&path("root_only.ts"), false));
+    assert!(!tree.is_ignored(&path("sub/root_only.ts"), false));
+    assert!(tree.is_ignored(&path("sub/generated.ts"), false));
+    assert!(tree.is_ignored(&path("sub/deeper/generated.ts"), false));
+    assert!(!tree.is_ignored(&path("generated.ts"), false));
+    assert!(!tree.is_ignored(&path("sub/dist"), true));
+    assert!(!tree.is_ignored(&path("sub/mod.ts"), false));
+  }
+}
@@ -6,8 +6,10 @@ pub mod console;
 pub mod diff;
 pub mod display;
 pub mod draw_thread;
+pub mod file_selection;
 pub mod file_watcher;
 pub mod fs;
+pub mod gitignore;
 pub mod import_map;
 pub mod logger;
 pub mod path;
@@ -5,6 +5,8 @@ use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::Arc;
 
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
 use deno_ast::ModuleSpecifier;
 use deno_core::anyhow::bail;
 use deno_core::anyhow::Context;
@@ -12,6 +14,7 @@ use deno_core::error::AnyError;
 use deno_core::futures::FutureExt;
 use deno_core::located_script_name;
 use deno_core::parking_lot::Mutex;
+use deno_core::serde_json;
 use deno_core::url::Url;
 use deno_core::v8;
 use deno_core::CompiledWasmModuleStore;
@@ -48,6 +51,7 @@ use tokio::select;
 
 use crate::args::package_json::PackageJsonDeps;
 use crate::args::DenoSubcommand;
+use crate::args::ServeFlags;
 use crate::args::StorageKeyResolver;
 use crate::emit::Emitter;
 use crate::errors;
@@ -60,6 +64,10 @@ use crate::util::file_watcher::WatcherCommunicator;
 use crate::util::file_watcher::WatcherRestartMode;
 use crate::version;
 
+/// The template of the links of the error overlay of `deno serve --dev`,
+/// which open a file in VS Code.
+const DEFAULT_EDITOR_URL: &str = "vscode://file{path}:{line}:{column}";
+
 pub trait ModuleLoaderFactory: Send + Sync {
   fn create_for_main(
     &self,
@@ -91,6 +99,7 @@ pub struct CliMainWorkerOptions {
   pub enable_testing_features: bool,
   pub has_node_modules_dir: bool,
   pub hmr: bool,
+  pub hmr_control_dir: Option<PathBuf>,
   pub inspect_brk: bool,
   pub inspect_wait: bool,
   pub strace_ops: Option<Vec<String>>,
@@ -168,6 +177,11 @@ impl CliMainWorker {
       self.execute_main_module_possibly_with_npm().await?;
     }
 
+    if let DenoSubcommand::Serve(serve_flags) = &self.shared.subcommand {
+      let serve_flags = serve_flags.clone();
+      self.serve_main_module(&serve_flags).await?;
+    }
+
     self.worker.dispatch_load_event(located_script_name!())?;
 
     loop {
@@ -317,6 +331,68 @@ impl CliMainWorker {
     self.evaluate_module_possibly_with_npm(id).await
   }
 
+  /// Serves the `fetch` handler of the default export of the main module for
+  /// `deno serve`. The handler is looked up on every request, so the methods
+  /// replaced by HMR are used without restarting the server and dropping its
+  /// connections, and the `onHmr` method is called after every replacement.
+  /// With `--dev`, the errors thrown by the handler are served as an error
+  /// overlay.
+  async fn serve_main_module(
+    &mut self,
+    serve_flags: &ServeFlags,
+  ) -> Result<(), AnyError> {
+    let (error_overlay, serve_handler) = if serve_flags.dev {
+      let editor_url = serve_flags
+        .editor_url
+        .as_deref()
+        .unwrap_or(DEFAULT_EDITOR_URL);
+      (
+        format!(
+          "{}\nconst editorUrl = {};\n",
+          include_str!("tools/run/serve_error_overlay.js"),
+          serde_json::to_string(editor_url)?
+        ),
+        r#"async (request, info) => {
+    try {
+      return await handler.fetch(request, info);
+    } catch (error) {
+      console.error(error);
+      return renderErrorOverlay(error, request, editorUrl);
+    }
+  }"#,
+      )
+    } else {
+      (
+        String::new(),
+        "(request, info) => handler.fetch(request, info)",
+      )
+    };
+    let source_code = format!(
+      r#"import * as mod from {main_module};
+const handler = mod.default;
+if (typeof handler?.fetch !== "function") {{
+  throw new TypeError(
+    "The default export of the main module must be an object with a `fetch` method.",
+  );
+}}
+addEventListener("hmr", (e) => handler.onHmr?.(e.detail.path));
+{error_overlay}Deno.serve(
+  {{ port: {port}, hostname: {hostname} }},
+  {serve_handler},
+);
+"#,
+      main_module = serde_json::to_string(self.main_module.as_str())?,
+      port = serve_flags.port,
+      hostname = serde_json::to_string(&serve_flags.host)?,
+    );
+    let specifier = ModuleSpecifier::parse(&format!(
+      "data:application/javascript;base64,{}",
+      BASE64_STANDARD.encode(source_code)
+    ))?;
+    let id = self.worker.preload_side_module(&specifier).await?;
+    self.evaluate_module_possibly_with_npm(id).await
+  }
+
   async fn evaluate_module_possibly_with_npm(
     &mut self,
     id: ModuleId,
@@ -359,7 +435,12 @@ impl CliMainWorker {
     let emitter = self.shared.emitter.clone().unwrap();
 
     let session = self.worker.create_inspector_session().await;
-    let mut hmr_runner = HmrRunner::new(emitter, session, watcher_communicator);
+    let mut hmr_runner = HmrRunner::new(
+      emitter,
+      session,
+      watcher_communicator,
+      self.shared.options.hmr_control_dir.clone(),
+    );
 
     self
       .worker
@@ -383,6 +464,17 @@ impl CliMainWorker {
       .js_runtime
       .execute_script_static(name, source_code)
   }
+
+  pub fn execute_script(
+    &mut self,
+    name: &'static str,
+    source_code: String,
+  ) -> Result<v8::Global<v8::Value>, AnyError> {
+    self
+      .worker
+      .js_runtime
+      .execute_script(name, source_code.into())
+  }
 }
 
 pub struct CliMainWorkerFactory {
@@ -204,6 +204,7 @@ pub fn get_or_create_client_from_state(
           .unsafely_ignore_certificate_errors
           .clone(),
         client_cert_chain_and_key: options.client_cert_chain_and_key.clone(),
+        proxy_resolver: None,
         pool_max_idle_per_host: None,
         pool_idle_timeout: None,
         http1: true,
@@ -857,6 +858,7 @@ where
         .unsafely_ignore_certificate_errors
         .clone(),
       client_cert_chain_and_key,
+      proxy_resolver: None,
       pool_max_idle_per_host: args.pool_max_idle_per_host,
       pool_idle_timeout: args.pool_idle_timeout.and_then(
         |timeout| match timeout {
@@ -879,6 +881,29 @@ where
   Ok(rid)
 }
 
+/// Resolves the proxy of the URL of every request, where `None` connects
+/// directly.
+#[derive(Clone)]
+pub struct ProxyResolver(Arc<dyn Fn(&Url) -> Option<Url> + Send + Sync>);
+
+impl ProxyResolver {
+  pub fn new(
+    resolve: impl Fn(&Url) -> Option<Url> + Send + Sync + 'static,
+  ) -> Self {
+    Self(Arc::new(resolve))
+  }
+
+  pub fn resolve(&self, url: &Url) -> Option<Url> {
+    (self.0)(url)
+  }
+}
+
+impl std::fmt::Debug for ProxyResolver {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("ProxyResolver").finish()
+  }
+}
+
 #[derive(Debug, Clone)]
 pub struct CreateHttpClientOptions {
   pub root_cert_store: Option<RootCertStore>,
@@ -886,6 +911,9 @@ pub struct CreateHttpClientOptions {
   pub proxy: Option<Proxy>,
   pub unsafely_ignore_certificate_errors: Option<Vec<String>>,
   pub client_cert_chain_and_key: Option<(String, String)>,
+  /// Resolves the proxy of every request instead of the proxies of the
+  /// environment. It's ignored when `proxy` is set.
+  pub proxy_resolver: Option<ProxyResolver>,
   pub pool_max_idle_per_host: Option<usize>,
   pub pool_idle_timeout: Option<Option<u64>>,
   pub http1: bool,
@@ -900,6 +928,7 @@ impl Default for CreateHttpClientOptions {
       proxy: None,
       unsafely_ignore_certificate_errors: None,
       client_cert_chain_and_key: None,
+      proxy_resolver: None,
       pool_max_idle_per_host: None,
       pool_idle_timeout: None,
       http1: true,
@@ -945,6 +974,10 @@ pub fn create_http_client(
         reqwest_proxy.basic_auth(&basic_auth.username, &basic_auth.password);
     }
     builder = builder.proxy(reqwest_proxy);
+  } else if let Some(proxy_resolver) = options.proxy_resolver {
+    builder = builder.proxy(reqwest::Proxy::custom(move |url| {
+      proxy_resolver.resolve(url)
+    }));
   }
 
   if let Some(pool_max_idle_per_host) = options.pool_max_idle_per_host {
@@ -45,18 +45,39 @@ pub struct InspectorServer {
 }
 
 impl InspectorServer {
+  /// Binds the inspector server to the host. The listener is bound before
+  /// returning so that `host` holds the actual port when port `0` is used.
   pub fn new(host: SocketAddr, name: &'static str) -> Self {
     let (register_inspector_tx, register_inspector_rx) =
       mpsc::unbounded::<InspectorInfo>();
 
     let (shutdown_server_tx, shutdown_server_rx) = broadcast::channel(1);
 
+    let maybe_listener = std::net::TcpListener::bind(host)
+      .and_then(|listener| {
+        listener.set_nonblocking(true)?;
+        Ok(listener)
+      })
+      .map_err(|err| {
+        eprintln!("Cannot start inspector server: {:?}", err);
+      })
+      .ok();
+    let host = maybe_listener
+      .as_ref()
+      .and_then(|listener| listener.local_addr().ok())
+      .unwrap_or(host);
+
     let thread_handle = thread::spawn(move || {
       let rt = crate::tokio_util::create_basic_runtime();
       let local = tokio::task::LocalSet::new();
       local.block_on(
         &rt,
-        server(host, register_inspector_rx, shutdown_server_rx, name),
+        server(
+          maybe_listener,
+          register_inspector_rx,
+          shutdown_server_rx,
+          name,
+        ),
       )
     });
 
@@ -220,7 +241,7 @@ fn handle_json_version_request(
 }
 
 async fn server(
-  host: SocketAddr,
+  maybe_listener: Option<std::net::TcpListener>,
   register_inspector_rx: UnboundedReceiver<InspectorInfo>,
   shutdown_server_rx: broadcast::Receiver<()>,
   name: &str,
@@ -261,7 +282,10 @@ async fn server(
   });
 
   // Create the server manually so it can use the Local Executor
-  let listener = match TcpListener::bind(&host).await {
+  let Some(listener) = maybe_listener else {
+    return;
+  };
+  let listener = match TcpListener::from_std(listener) {
     Ok(l) => l,
     Err(err) => {
       eprintln!("Cannot start inspector server: {:?}", err);
@@ -34,11 +34,48 @@ use prompter::PromptResponse;
 use prompter::PERMISSION_EMOJI;
 
 pub use prompter::set_prompt_callbacks;
+pub use prompter::set_prompt_grant_callback;
 pub use prompter::PromptCallback;
+pub use prompter::PromptGrantCallback;
 
 static DEBUG_LOG_ENABLED: Lazy<bool> =
   Lazy::new(|| log::log_enabled!(log::Level::Debug));
 
+static MAYBE_CHECK_CALLBACK: Lazy<Mutex<Option<PermissionCheckCallback>>> =
+  Lazy::new(|| Mutex::new(None));
+
+/// A check of a permission, which is passed to the callback of
+/// `set_permission_check_callback`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionCheck<'a> {
+  /// The name of the permission, like "read".
+  pub permission: &'a str,
+  /// The checked resource, like a path, which is `None` for all of the
+  /// permission.
+  pub resource: Option<Cow<'a, str>>,
+  /// The API which checked the permission, like "Deno.readFile()".
+  pub api_name: Option<&'a str>,
+  pub granted: bool,
+  /// Whether the permission was prompted for in this check.
+  pub prompted: bool,
+}
+
+pub type PermissionCheckCallback =
+  Box<dyn FnMut(&PermissionCheck) + Send + Sync>;
+
+/// Set a callback which is called for every check of a permission, whether
+/// it's granted or not, like for an audit log of the used permissions.
+pub fn set_permission_check_callback(callback: PermissionCheckCallback) {
+  *MAYBE_CHECK_CALLBACK.lock() = Some(callback);
+}
+
+fn notify_permission_check(check: PermissionCheck) {
+  if let Some(callback) = MAYBE_CHECK_CALLBACK.lock().as_mut() {
+    callback(&check);
+  }
+}
+
 /// Quadri-state value for storing permission state
 #[derive(
   Eq, PartialEq, Default, Debug, Clone, Copy, Deserialize, PartialOrd,
@@ -115,7 +152,8 @@ impl PermissionState {
     )
   }
 
-  /// Check the permission state. bool is whether a prompt was issued.
+  /// Check the permission state. The response is set when a prompt was
+  /// issued.
   #[inline]
   fn check(
     self,
@@ -123,8 +161,8 @@ impl PermissionState {
     api_name: Option<&str>,
     info: Option<&str>,
     prompt: bool,
-  ) -> (Result<(), AnyError>, bool, bool) {
-    self.check2(name, api_name, || info.map(|s| s.to_string()), prompt)
+  ) -> (Result<(), AnyError>, Option<PromptResponse>) {
+    self.check2(name, api_name, || info.map(|s| s.to_string()), prompt, None)
   }
 
   #[inline]
@@ -134,11 +172,12 @@ impl PermissionState {
     api_name: Option<&str>,
     info: impl Fn() -> Option<String>,
     prompt: bool,
-  ) -> (Result<(), AnyError>, bool, bool) {
+    maybe_group: Option<&str>,
+  ) -> (Result<(), AnyError>, Option<PromptResponse>) {
     match self {
       PermissionState::Granted => {
         Self::log_perm_access(name, info);
-        (Ok(()), false, false)
+        (Ok(()), None)
       }
       PermissionState::Prompt if prompt => {
         let msg = format!(
@@ -148,19 +187,17 @@ impl PermissionState {
             .map(|info| { format!(" to {info}") })
             .unwrap_or_default(),
         );
-        match permission_prompt(&msg, name, api_name, true) {
-          PromptResponse::Allow => {
-            Self::log_perm_access(name, info);
-            (Ok(()), true, false)
+        match permission_prompt(&msg, name, api_name, true, maybe_group) {
+          PromptResponse::Deny => {
+            (Err(Self::error(name, info)), Some(PromptResponse::Deny))
           }
-          PromptResponse::AllowAll => {
+          response => {
             Self::log_perm_access(name, info);
-            (Ok(()), true, true)
+            (Ok(()), Some(response))
           }
-          PromptResponse::Deny => (Err(Self::error(name, info)), true, false),
         }
       }
-      _ => (Err(Self::error(name, info)), false, false),
+      _ => (Err(Self::error(name, info)), None),
     }
   }
 }
@@ -197,6 +234,7 @@ impl UnitPermission {
           self.name,
           Some("Deno.permissions.query()"),
           false,
+          None,
         )
       {
         self.state = PermissionState::Granted;
@@ -215,9 +253,16 @@ impl UnitPermission {
   }
 
   pub fn check(&mut self) -> Result<(), AnyError> {
-    let (result, prompted, _is_allow_all) =
+    let (result, maybe_response) =
       self.state.check(self.name, None, None, self.prompt);
-    if prompted {
+    notify_permission_check(PermissionCheck {
+      permission: self.name,
+      resource: None,
+      api_name: None,
+      granted: result.is_ok(),
+      prompted: maybe_response.is_some(),
+    });
+    if maybe_response.is_some() {
       if result.is_ok() {
         self.state = PermissionState::Granted;
       } else {
@@ -264,6 +309,12 @@ pub trait Descriptor: Eq + Clone {
   fn aliases(&self) -> Vec<Self> {
     vec![]
   }
+  /// The group of the descriptor, like the directory of a path, which a
+  /// prompt offers to allow at once when another descriptor of the group was
+  /// prompted for before.
+  fn group(&self) -> Option<Self> {
+    None
+  }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -275,6 +326,8 @@ pub struct UnaryPermission<T: Descriptor + Hash> {
   pub prompt_denied_global: bool,
   pub prompt_denied_list: HashSet<T>,
   pub prompt: bool,
+  /// The groups of the descriptors that were prompted for.
+  pub prompted_groups: HashSet<T>,
 }
 
 impl<T: Descriptor + Hash> Default for UnaryPermission<T> {
@@ -287,6 +340,7 @@ impl<T: Descriptor + Hash> Default for UnaryPermission<T> {
       prompt_denied_global: Default::default(),
       prompt_denied_list: Default::default(),
       prompt: Default::default(),
+      prompted_groups: Default::default(),
     }
   }
 }
@@ -299,7 +353,8 @@ impl<T: Descriptor + Hash> UnaryPermission<T> {
     api_name: Option<&str>,
     get_display_name: impl Fn() -> Option<String>,
   ) -> Result<(), AnyError> {
-    let (result, prompted, is_allow_all) = self
+    let maybe_group = self.prompted_group(desc);
+    let (result, maybe_response) = self
       .query_desc(desc, AllowPartial::from(assert_non_partial))
       .check2(
         T::flag_name(),
@@ -309,17 +364,17 @@ impl<T: Descriptor + Hash> UnaryPermission<T> {
           None => desc.as_ref().map(|d| format!("\"{}\"", d.name())),
         },
         self.prompt,
+        maybe_group.as_ref().map(|group| group.name()).as_deref(),
       );
-    if prompted {
-      if result.is_ok() {
-        if is_allow_all {
-          self.insert_granted(None);
-        } else {
-          self.insert_granted(desc.clone());
-        }
-      } else {
-        self.insert_prompt_denied(desc.clone());
-      }
+    notify_permission_check(PermissionCheck {
+      permission: T::flag_name(),
+      resource: desc.as_ref().map(|desc| desc.name()),
+      api_name,
+      granted: result.is_ok(),
+      prompted: maybe_response.is_some(),
+    });
+    if let Some(response) = maybe_response {
+      self.insert_prompt_response(desc, response, maybe_group);
     }
     result
   }
@@ -392,24 +447,56 @@ impl<T: Descriptor + Hash> UnaryPermission<T> {
         None => {}
       },
     }
-    match permission_prompt(
+    let maybe_group = self.prompted_group(desc);
+    let response = permission_prompt(
       &message,
       T::flag_name(),
       Some("Deno.permissions.request()"),
       true,
-    ) {
-      PromptResponse::Allow => {
-        self.insert_granted(desc.clone());
-        PermissionState::Granted
-      }
-      PromptResponse::Deny => {
-        self.insert_prompt_denied(desc.clone());
-        PermissionState::Denied
-      }
-      PromptResponse::AllowAll => {
-        self.insert_granted(None);
-        PermissionState::Granted
+      maybe_group.as_ref().map(|group| group.name()).as_deref(),
+    );
+    let state = if response == PromptResponse::Deny {
+      PermissionState::Denied
+    } else {
+      PermissionState::Granted
+    };
+    self.insert_prompt_response(desc, response, maybe_group);
+    state
+  }
+
+  /// Get the group of the descriptor when another descriptor of it was
+  /// prompted for before.
+  fn prompted_group(&self, desc: &Option<T>) -> Option<T> {
+    desc
+      .as_ref()
+      .and_then(|desc| desc.group())
+      .filter(|group| self.prompted_groups.contains(group))
+  }
+
+  fn insert_prompt_response(
+    &mut self,
+    desc: &Option<T>,
+    response: PromptResponse,
+    maybe_group: Option<T>,
+  ) {
+    let granted = match response {
+      PromptResponse::Allow => Some(desc.clone()),
+      PromptResponse::AllowGroup => Some(maybe_group.or_else(|| desc.clone())),
+      PromptResponse::AllowAll => Some(None),
+      PromptResponse::Deny => None,
+    };
+    match granted {
+      Some(granted) => {
+        prompter::notify_prompt_grant(
+          T::flag_name(),
+          granted.as_ref().map(|d| d.name()).as_deref(),
+        );
+        self.insert_granted(granted);
       }
+      None => self.insert_prompt_denied(desc.clone()),
+    }
+    if let Some(group) = desc.as_ref().and_then(|desc| desc.group()) {
+      self.prompted_groups.insert(group);
     }
   }
 
@@ -498,6 +585,14 @@ impl<T: Descriptor + Hash> UnaryPermission<T> {
   }
 }
 
+/// The group of a path is its directory, unless that's the root directory.
+fn path_group(path: &Path) -> Option<PathBuf> {
+  path
+    .parent()
+    .filter(|parent| parent.parent().is_some())
+    .map(|parent| parent.to_path_buf())
+}
+
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
 pub struct ReadDescriptor(pub PathBuf);
 
@@ -513,6 +608,10 @@ impl Descriptor for ReadDescriptor {
   fn stronger_than(&self, other: &Self) -> bool {
     other.0.starts_with(&self.0)
   }
+
+  fn group(&self) -> Option<Self> {
+    path_group(&self.0).map(Self)
+  }
 }
 
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
@@ -530,6 +629,10 @@ impl Descriptor for WriteDescriptor {
   fn stronger_than(&self, other: &Self) -> bool {
     other.0.starts_with(&self.0)
   }
+
+  fn group(&self) -> Option<Self> {
+    path_group(&self.0).map(Self)
+  }
 }
 
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
@@ -553,6 +656,11 @@ impl Descriptor for NetDescriptor {
   fn stronger_than(&self, other: &Self) -> bool {
     self.0 == other.0 && (self.1.is_none() || self.1 == other.1)
   }
+
+  /// The group of a host with a port is the host with any port.
+  fn group(&self) -> Option<Self> {
+    self.1.map(|_| NetDescriptor(self.0.clone(), None))
+  }
 }
 
 impl FromStr for NetDescriptor {
@@ -582,11 +690,28 @@ impl fmt::Display for NetDescriptor {
 }
 
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
-pub struct EnvDescriptor(EnvVarName);
+pub struct EnvDescriptor {
+  name: EnvVarName,
+  /// Whether all the names starting with the name are allowed, which is only
+  /// the case for the groups granted in prompts and in permissions files.
+  is_prefix: bool,
+}
 
 impl EnvDescriptor {
   pub fn new(env: impl AsRef<str>) -> Self {
-    Self(EnvVarName::new(env))
+    Self {
+      name: EnvVarName::new(env),
+      is_prefix: false,
+    }
+  }
+
+  /// A group of the names starting with the prefix, like `AWS_`, which is
+  /// shown as `AWS_*`.
+  pub fn new_prefix(prefix: impl AsRef<str>) -> Self {
+    Self {
+      name: EnvVarName::new(prefix),
+      is_prefix: true,
+    }
   }
 }
 
@@ -596,13 +721,35 @@ impl Descriptor for EnvDescriptor {
   }
 
   fn name(&self) -> Cow<str> {
-    Cow::from(self.0.as_ref())
+    if self.is_prefix {
+      Cow::from(format!("{}*", self.name.as_ref()))
+    } else {
+      Cow::from(self.name.as_ref())
+    }
+  }
+
+  fn stronger_than(&self, other: &Self) -> bool {
+    if self.is_prefix {
+      other.name.as_ref().starts_with(self.name.as_ref())
+    } else {
+      self == other
+    }
+  }
+
+  /// The group of a name like `AWS_REGION` is its prefix, `AWS_*`.
+  fn group(&self) -> Option<Self> {
+    if self.is_prefix {
+      return None;
+    }
+    let (prefix, _) = self.name.as_ref().split_once('_')?;
+    (!prefix.is_empty())
+      .then(|| EnvDescriptor::new_prefix(format!("{prefix}_")))
   }
 }
 
 impl AsRef<str> for EnvDescriptor {
   fn as_ref(&self) -> &str {
-    self.0.as_ref()
+    self.name.as_ref()
   }
 }
 
@@ -708,6 +855,10 @@ impl Descriptor for FfiDescriptor {
   fn stronger_than(&self, other: &Self) -> bool {
     other.0.starts_with(&self.0)
   }
+
+  fn group(&self) -> Option<Self> {
+    path_group(&self.0).map(Self)
+  }
 }
 
 impl UnaryPermission<ReadDescriptor> {
@@ -1069,6 +1220,10 @@ impl Default for Permissions {
 #[derive(Clone, Debug, Eq, PartialEq, Default, Serialize, Deserialize)]
 pub struct PermissionsOptions {
   pub allow_env: Option<Vec<String>>,
+  /// The prefixes of the names of the groups of environment variables, like
+  /// `AWS_` for `AWS_*`, which were allowed in a permissions file.
+  #[serde(default)]
+  pub allow_env_prefixes: Vec<String>,
   pub deny_env: Option<Vec<String>>,
   pub allow_hrtime: bool,
   pub deny_hrtime: bool,
@@ -1138,9 +1293,27 @@ impl Permissions {
     deny_list: &Option<Vec<String>>,
     prompt: bool,
   ) -> Result<UnaryPermission<EnvDescriptor>, AnyError> {
+    Self::new_env_with_prefixes(allow_list, &[], deny_list, prompt)
+  }
+
+  /// Like `new_env`, where the names starting with one of the prefixes are
+  /// allowed too.
+  pub fn new_env_with_prefixes(
+    allow_list: &Option<Vec<String>>,
+    allow_prefixes: &[String],
+    deny_list: &Option<Vec<String>>,
+    prompt: bool,
+  ) -> Result<UnaryPermission<EnvDescriptor>, AnyError> {
+    let mut granted_list = parse_env_list(allow_list)?;
+    for prefix in allow_prefixes {
+      if prefix.is_empty() {
+        return Err(AnyError::msg("Empty prefix is not allowed"));
+      }
+      granted_list.insert(EnvDescriptor::new_prefix(prefix));
+    }
     Ok(UnaryPermission::<EnvDescriptor> {
       granted_global: global_from_option(allow_list),
-      granted_list: parse_env_list(allow_list)?,
+      granted_list,
       flag_denied_global: global_from_option(deny_list),
       flag_denied_list: parse_env_list(deny_list)?,
       prompt,
@@ -1216,7 +1389,12 @@ impl Permissions {
         opts.prompt,
       )?,
       net: Permissions::new_net(&opts.allow_net, &opts.deny_net, opts.prompt)?,
-      env: Permissions::new_env(&opts.allow_env, &opts.deny_env, opts.prompt)?,
+      env: Permissions::new_env_with_prefixes(
+        &opts.allow_env,
+        &opts.allow_env_prefixes,
+        &opts.deny_env,
+        opts.prompt,
+      )?,
       sys: Permissions::new_sys(&opts.allow_sys, &opts.deny_sys, opts.prompt)?,
       run: Permissions::new_run(&opts.allow_run, &opts.deny_run, opts.prompt)?,
       ffi: Permissions::new_ffi(&opts.allow_ffi, &opts.deny_ffi, opts.prompt)?,
@@ -2764,6 +2942,47 @@ mod tests {
     };
   }
 
+  #[test]
+  fn test_prompt_groups() {
+    let mut read = Permissions::new_read(&None, &None, true).unwrap();
+    let foo = Some(ReadDescriptor(PathBuf::from("/a/b/foo")));
+    let bar = Some(ReadDescriptor(PathBuf::from("/a/b/bar")));
+    // the group is only offered after another prompt for it
+    assert_eq!(read.prompted_group(&foo), None);
+    read.insert_prompt_response(&foo, PromptResponse::Allow, None);
+    let group = read.prompted_group(&bar);
+    assert_eq!(group, Some(ReadDescriptor(PathBuf::from("/a/b"))));
+    read.insert_prompt_response(&bar, PromptResponse::AllowGroup, group);
+    assert_eq!(
+      read.query(Some(Path::new("/a/b/baz"))),
+      PermissionState::Granted
+    );
+    assert_eq!(read.query(Some(Path::new("/a/c"))), PermissionState::Prompt);
+    assert_eq!(ReadDescriptor(PathBuf::from("/a")).group(), None);
+
+    assert_eq!(
+      NetDescriptor("deno.land".to_string(), Some(443)).group(),
+      Some(NetDescriptor("deno.land".to_string(), None))
+    );
+    assert_eq!(NetDescriptor("deno.land".to_string(), None).group(), None);
+
+    let env =
+      Permissions::new_env_with_prefixes(&None, &svec!["AWS_"], &None, true)
+        .unwrap();
+    assert_eq!(env.query(Some("AWS_REGION")), PermissionState::Granted);
+    assert_eq!(env.query(Some("AWSREGION")), PermissionState::Prompt);
+    let group = EnvDescriptor::new("AWS_REGION").group();
+    assert_eq!(group, Some(EnvDescriptor::new_prefix("AWS_")));
+    assert_eq!(group.unwrap().name(), "AWS_*");
+    assert_eq!(EnvDescriptor::new_prefix("AWS_").group(), None);
+    assert_eq!(EnvDescriptor::new("HOME").group(), None);
+
+    // a name ending with `*` in the flags is only that name
+    let env = Permissions::new_env(&Some(svec!["FOO*"]), &None, true).unwrap();
+    assert_eq!(env.query(Some("FOO*")), PermissionState::Granted);
+    assert_eq!(env.query(Some("FOOBAR")), PermissionState::Prompt);
+  }
+
   #[test]
   fn test_revoke() {
     set_prompter(Box::new(TestPrompter));
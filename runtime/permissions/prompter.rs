@@ -29,6 +29,9 @@ pub enum PromptResponse {
   Allow,
   Deny,
   AllowAll,
+  /// Allow the group of the requested permission, like the directory of a
+  /// path, which is only offered when it was requested before.
+  AllowGroup,
 }
 
 static PERMISSION_PROMPTER: Lazy<Mutex<Box<dyn PermissionPrompter>>> =
@@ -40,18 +43,26 @@ static MAYBE_BEFORE_PROMPT_CALLBACK: Lazy<Mutex<Option<PromptCallback>>> =
 static MAYBE_AFTER_PROMPT_CALLBACK: Lazy<Mutex<Option<PromptCallback>>> =
   Lazy::new(|| Mutex::new(None));
 
+static MAYBE_PROMPT_GRANT_CALLBACK: Lazy<Mutex<Option<PromptGrantCallback>>> =
+  Lazy::new(|| Mutex::new(None));
+
 pub fn permission_prompt(
   message: &str,
   flag: &str,
   api_name: Option<&str>,
   is_unary: bool,
+  maybe_group: Option<&str>,
 ) -> PromptResponse {
   if let Some(before_callback) = MAYBE_BEFORE_PROMPT_CALLBACK.lock().as_mut() {
     before_callback();
   }
-  let r = PERMISSION_PROMPTER
-    .lock()
-    .prompt(message, flag, api_name, is_unary);
+  let r = PERMISSION_PROMPTER.lock().prompt(
+    message,
+    flag,
+    api_name,
+    is_unary,
+    maybe_group,
+  );
   if let Some(after_callback) = MAYBE_AFTER_PROMPT_CALLBACK.lock().as_mut() {
     after_callback();
   }
@@ -68,6 +79,21 @@ pub fn set_prompt_callbacks(
 
 pub type PromptCallback = Box<dyn FnMut() + Send + Sync>;
 
+/// Set a callback which is called when a permission is granted in a prompt,
+/// with the name of the permission and the granted value, which is `None`
+/// when all of it was granted.
+pub fn set_prompt_grant_callback(callback: PromptGrantCallback) {
+  *MAYBE_PROMPT_GRANT_CALLBACK.lock() = Some(callback);
+}
+
+pub type PromptGrantCallback = Box<dyn FnMut(&str, Option<&str>) + Send + Sync>;
+
+pub(crate) fn notify_prompt_grant(name: &str, value: Option<&str>) {
+  if let Some(callback) = MAYBE_PROMPT_GRANT_CALLBACK.lock().as_mut() {
+    callback(name, value);
+  }
+}
+
 pub trait PermissionPrompter: Send + Sync {
   fn prompt(
     &mut self,
@@ -75,6 +101,7 @@ pub trait PermissionPrompter: Send + Sync {
     name: &str,
     api_name: Option<&str>,
     is_unary: bool,
+    maybe_group: Option<&str>,
   ) -> PromptResponse;
 }
 
@@ -221,6 +248,7 @@ impl PermissionPrompter for TtyPrompter {
     name: &str,
     api_name: Option<&str>,
     is_unary: bool,
+    maybe_group: Option<&str>,
   ) -> PromptResponse {
     if !std::io::stdin().is_terminal() || !std::io::stderr().is_terminal() {
       return PromptResponse::Deny;
@@ -252,12 +280,13 @@ impl PermissionPrompter for TtyPrompter {
     let message = strip_ansi_codes_and_ascii_control(message);
     let name = strip_ansi_codes_and_ascii_control(name);
     let api_name = api_name.map(strip_ansi_codes_and_ascii_control);
+    let maybe_group = maybe_group.map(strip_ansi_codes_and_ascii_control);
 
     // print to stderr so that if stdout is piped this is still displayed.
-    let opts: String = if is_unary {
-      format!("[y/n/A] (y = yes, allow; n = no, deny; A = allow all {name} permissions)")
-    } else {
-      "[y/n] (y = yes, allow; n = no, deny)".to_string()
+    let opts: String = match &maybe_group {
+      Some(group) if is_unary => format!("[y/n/g/A] (y = yes, allow; n = no, deny; g = allow all of \"{group}\"; A = allow all {name} permissions)"),
+      _ if is_unary => format!("[y/n/A] (y = yes, allow; n = no, deny; A = allow all {name} permissions)"),
+      _ => "[y/n] (y = yes, allow; n = no, deny)".to_string(),
     };
 
     // output everything in one shot to make the tests more reliable
@@ -316,6 +345,16 @@ impl PermissionPrompter for TtyPrompter {
           writeln!(stderr_lock, "✅ {}", colors::bold(&msg)).unwrap();
           break PromptResponse::AllowAll;
         }
+        'g' if is_unary && maybe_group.is_some() => {
+          clear_n_lines(
+            &mut stderr_lock,
+            if api_name.is_some() { 4 } else { 3 },
+          );
+          let group = maybe_group.as_deref().unwrap_or_default();
+          let msg = format!("Granted {name} access to \"{group}\".");
+          writeln!(stderr_lock, "✅ {}", colors::bold(&msg)).unwrap();
+          break PromptResponse::AllowGroup;
+        }
         _ => {
           // If we don't get a recognized option try again.
           clear_n_lines(&mut stderr_lock, 1);
@@ -372,6 +411,7 @@ pub mod tests {
       _name: &str,
       _api_name: Option<&str>,
       _is_unary: bool,
+      _maybe_group: Option<&str>,
     ) -> PromptResponse {
       if STUB_PROMPT_VALUE.load(Ordering::SeqCst) {
         PromptResponse::Allow
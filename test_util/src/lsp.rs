@@ -799,11 +799,11 @@ impl LspClient {
 
   /// Reads the latest diagnostics. It's assumed that
   pub fn read_diagnostics(&mut self) -> CollectedDiagnostics {
-    // wait for three (deno, lint, and typescript diagnostics) batch
-    // notification messages for that index
+    // wait for five (deno, lint, typescript, config, and publish diagnostics)
+    // batch notification messages for that index
     let mut read = 0;
     let mut total_messages_len = 0;
-    while read < 3 {
+    while read < 5 {
       let (method, response) =
         self.read_notification::<DiagnosticBatchNotificationParams>();
       assert_eq!(method, "deno/internalTestDiagnosticBatch");
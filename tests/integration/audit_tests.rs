@@ -0,0 +1,58 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use test_util as util;
+use util::env_vars_for_npm_tests;
+use util::TestContextBuilder;
+
+itest!(audit {
+  args: "audit --quiet",
+  output: "audit/main.out",
+  envs: env_vars_for_npm_tests(),
+  http_server: true,
+  cwd: Some("audit"),
+  copy_temp_dir: Some("audit"),
+  exit_code: 1,
+});
+
+itest!(audit_json {
+  args: "audit --quiet --json",
+  output: "audit/main_json.out",
+  envs: env_vars_for_npm_tests(),
+  http_server: true,
+  cwd: Some("audit"),
+  copy_temp_dir: Some("audit"),
+  exit_code: 1,
+});
+
+itest!(audit_level {
+  args: "audit --quiet --level=critical",
+  output: "audit/critical.out",
+  envs: env_vars_for_npm_tests(),
+  http_server: true,
+  cwd: Some("audit"),
+  copy_temp_dir: Some("audit"),
+  exit_code: 0,
+});
+
+#[test]
+fn audit_cached_only() {
+  let context = TestContextBuilder::for_npm()
+    .use_copy_temp_dir("audit")
+    .cwd("audit")
+    .build();
+
+  let output = context
+    .new_command()
+    .args("audit --quiet --level=critical")
+    .run();
+  output.assert_exit_code(0);
+  output.assert_matches_file("audit/critical.out");
+
+  // the advisories are cached in the DENO_DIR
+  let output = context
+    .new_command()
+    .args("audit --quiet --cached-only --level=critical")
+    .run();
+  output.assert_exit_code(0);
+  output.assert_matches_file("audit/critical.out");
+}
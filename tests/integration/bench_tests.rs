@@ -1,5 +1,6 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use deno_core::serde_json::json;
 use deno_core::url::Url;
 use test_util as util;
 use util::assert_contains;
@@ -216,6 +217,18 @@ itest!(json_output {
   output: "bench/pass.json.out",
 });
 
+itest!(json_reporter_output {
+  args: "bench --reporter=json bench/pass.ts",
+  exit_code: 0,
+  output: "bench/pass.reporter_json.out",
+});
+
+itest!(junit_reporter_output {
+  args: "bench --reporter=junit bench/pass.ts",
+  exit_code: 0,
+  output: "bench/pass.junit.out",
+});
+
 #[test]
 fn recursive_permissions_pledge() {
   let context = TestContext::default();
@@ -283,3 +296,53 @@ fn conditionally_loads_type_graph() {
     .run();
   assert_not_contains!(output.combined_output(), "type_reference.d.ts");
 }
+
+#[test]
+fn baseline_comparison() {
+  let context = TestContext::default();
+  let temp_dir = context.temp_dir();
+  let baseline_of = |avg: f64| {
+    let benches = (0..10)
+      .map(|i| {
+        json!({
+          "origin": "file:///ci/bench/pass.ts",
+          "group": null,
+          "name": format!("bench{i}"),
+          "stats": { "iterations": 1000, "avg": avg, "sd": 0.0 },
+        })
+      })
+      .collect::<Vec<_>>();
+    json!({ "version": 1, "benches": benches }).to_string()
+  };
+
+  temp_dir.write("fast.json", baseline_of(0.001));
+  let output = context
+    .new_command()
+    .args_vec([
+      "bench".to_string(),
+      format!("--baseline={}", temp_dir.path().join("fast.json")),
+      "bench/pass.ts".to_string(),
+    ])
+    .run();
+  output.assert_exit_code(1);
+  assert_contains!(
+    output.combined_output(),
+    "10 of 10 benchmarks regressed compared to the baseline"
+  );
+
+  temp_dir.write("slow.json", baseline_of(1e12));
+  let output = context
+    .new_command()
+    .args_vec([
+      "bench".to_string(),
+      format!("--baseline={}", temp_dir.path().join("slow.json")),
+      "--regression-threshold=10".to_string(),
+      "bench/pass.ts".to_string(),
+    ])
+    .run();
+  output.assert_exit_code(0);
+  assert_contains!(
+    output.combined_output(),
+    "No regressions found in 10 benchmarks compared to the baseline"
+  );
+}
@@ -415,6 +415,56 @@ fn bundle_json_module_escape_sub() {
   );
 }
 
+#[test]
+fn bundle_code_splitting() {
+  let main = util::testdata_path().join("bundle/code_splitting/main.ts");
+  assert!(main.is_file());
+  let t = TempDir::new();
+  let bundle = t.path().join("main.bundle.js");
+  let mut deno = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("bundle")
+    .arg("--minify")
+    .arg("--source-map")
+    .arg(main)
+    .arg(&bundle)
+    .spawn()
+    .unwrap();
+  let status = deno.wait().unwrap();
+  assert!(status.success());
+  assert!(bundle.is_file());
+  assert!(t.path().join("main.bundle.js.map").is_file());
+
+  // the dynamically imported module is in a chunk next to the bundle
+  let mut file_names = std::fs::read_dir(t.path())
+    .unwrap()
+    .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+    .collect::<Vec<_>>();
+  file_names.sort();
+  assert_eq!(file_names.len(), 4, "{:?}", file_names);
+  let chunk = file_names
+    .iter()
+    .find(|name| name.starts_with("hello-") && name.ends_with(".js"))
+    .unwrap();
+  assert!(file_names.contains(&format!("{}.map", chunk)));
+  assert_contains!(
+    std::fs::read_to_string(&bundle).unwrap(),
+    &format!("import(\"./{}\")", chunk),
+  );
+
+  let output = util::deno_cmd()
+    .current_dir(t.path())
+    .arg("run")
+    .arg(&bundle)
+    .output()
+    .unwrap();
+  assert_eq!(output.stderr, b"");
+  assert_eq!(
+    std::str::from_utf8(&output.stdout).unwrap().trim(),
+    "Hello from a chunk",
+  );
+}
+
 itest!(lockfile_check_error {
   args: "bundle --lock=bundle/lockfile/check_error.json http://127.0.0.1:4545/subdir/mod1.ts",
   output: "bundle/lockfile/check_error.out",
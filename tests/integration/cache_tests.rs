@@ -184,3 +184,89 @@ fn loads_type_graph() {
     .run();
   output.assert_matches_text("[WILDCARD] - FileFetcher::fetch() - specifier: file:///[WILDCARD]/subdir/type_reference.d.ts[WILDCARD]");
 }
+
+#[test]
+fn offline() {
+  let context = TestContextBuilder::for_npm().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write(
+    "remote.ts",
+    "import 'http://localhost:4545/run/002_hello.ts';",
+  );
+  temp_dir.write(
+    "npm.ts",
+    "import { getValue } from 'npm:@denotest/esm-basic';\nconsole.log(getValue());",
+  );
+
+  // nothing is cached yet, so it fails instead of downloading
+  let output = context
+    .new_command()
+    .args("cache --offline remote.ts")
+    .run();
+  output.assert_matches_text(concat!(
+    "error: Specifier not found in cache: \"http://localhost:4545/run/002_hello.ts\", --offline is specified.\n",
+    "[WILDCARD]",
+  ));
+  output.assert_exit_code(1);
+  let output = context.new_command().args("cache --offline npm.ts").run();
+  output.assert_matches_text(concat!(
+    "[WILDCARD]An npm specifier not found in cache: \"@denotest/esm-basic\", --offline is specified.\n",
+    "[WILDCARD]",
+  ));
+  output.assert_exit_code(1);
+
+  context
+    .new_command()
+    .args("cache remote.ts npm.ts")
+    .run()
+    .skip_output_check()
+    .assert_exit_code(0);
+
+  // now the cache is used, also when enabled by the environment variable
+  let output = context.new_command().args("run --offline remote.ts").run();
+  output.assert_matches_text("Hello World\n");
+  let output = context
+    .new_command()
+    .env("DENO_OFFLINE", "1")
+    .args("run npm.ts")
+    .run();
+  output.assert_matches_text("0\n");
+}
+
+#[test]
+fn cache_prune() {
+  let context = TestContextBuilder::new()
+    .use_http_server()
+    .use_temp_cwd()
+    .build();
+  context
+    .new_command()
+    .args("cache http://localhost:4545/run/002_hello.ts")
+    .run()
+    .skip_output_check()
+    .assert_exit_code(0);
+
+  // the entry was used recently, so it's kept
+  let output = context.new_command().args("cache --prune").run();
+  output.assert_matches_text(
+    "Removed 0 of 1 entry of the HTTP cache, freeing 0B.\n",
+  );
+  output.assert_exit_code(0);
+
+  let output = context
+    .new_command()
+    .args("cache --prune --max-size=0B")
+    .run();
+  output.assert_matches_text(
+    "Removed 1 of 1 entry of the HTTP cache, freeing [WILDCARD]B.\n",
+  );
+  output.assert_exit_code(0);
+
+  let output = context
+    .new_command()
+    .args("run http://localhost:4545/run/002_hello.ts")
+    .run();
+  output.assert_matches_text(
+    "Download http://localhost:4545/run/002_hello.ts\nHello World\n",
+  );
+}
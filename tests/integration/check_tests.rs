@@ -1,5 +1,6 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use deno_core::serde_json;
 use test_util as util;
 use util::env_vars_for_npm_tests;
 use util::TestContext;
@@ -150,6 +151,20 @@ itest!(check_with_excluded_file_specified {
   output: "check/excluded_file_specified/check.out",
 });
 
+itest!(check_graph_budget {
+  args: "check main.ts",
+  cwd: Some("check/graph_budget/"),
+  output: "check/graph_budget/main.out",
+  exit_code: 1,
+});
+
+itest!(cache_graph_budget {
+  args: "cache main.ts",
+  cwd: Some("check/graph_budget/"),
+  output: "check/graph_budget/cache.out",
+  exit_code: 1,
+});
+
 #[test]
 fn cache_switching_config_then_no_config() {
   let context = TestContext::default();
@@ -341,6 +356,40 @@ fn check_error_in_dep_then_fix() {
   output.assert_exit_code(1);
 }
 
+#[test]
+fn check_error_cached() {
+  let test_context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = test_context.temp_dir();
+  temp_dir.write(
+    "main.ts",
+    "const value: number = 'hello';\nconsole.log(value);\n",
+  );
+
+  let output = test_context
+    .new_command()
+    .args("check -L debug main.ts")
+    .split_output()
+    .run();
+  output.assert_exit_code(1);
+  assert!(output.stderr().contains("Compilation statistics"));
+
+  // the diagnostics are reported again without type checking
+  let output = test_context
+    .new_command()
+    .args("check -L debug main.ts")
+    .split_output()
+    .run();
+  output.assert_exit_code(1);
+  assert!(!output.stderr().contains("Compilation statistics"));
+  assert!(output.stderr().contains("TS2322 [ERROR]"));
+
+  let output = test_context.new_command().args("check main.ts").run();
+  output.assert_matches_text(
+    "Check [WILDCARD]main.ts\nerror: TS2322 [ERROR]: Type 'string' is not assignable to type 'number'.[WILDCARD]",
+  );
+  output.assert_exit_code(1);
+}
+
 #[test]
 fn json_module_check_then_error() {
   let test_context = TestContextBuilder::new().use_temp_cwd().build();
@@ -417,3 +466,135 @@ fn npm_module_check_then_error() {
     .assert_matches_text("Check [WILDCARD]main.ts\nerror: TS2305[WILDCARD]has no exported member 'oldName'[WILDCARD]")
     .assert_exit_code(1);
 }
+
+#[test]
+fn check_workspace() {
+  let test_context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = test_context.temp_dir();
+  temp_dir.write("deno.json", r#"{ "workspaces": ["./a", "./b"] }"#);
+  temp_dir.create_dir_all("a");
+  temp_dir.write(
+    "a/deno.json",
+    r#"{
+  "name": "@scope/a",
+  "version": "1.0.0",
+  "exports": "./mod.ts",
+  "imports": { "b": "jsr:@scope/b@1" }
+}"#,
+  );
+  temp_dir.write(
+    "a/mod.ts",
+    "import { getCount } from \"b\";\n\nexport const count: string = getCount();\n",
+  );
+  temp_dir.create_dir_all("b");
+  temp_dir.write(
+    "b/deno.json",
+    r#"{ "name": "@scope/b", "version": "1.0.0", "exports": "./mod.ts" }"#,
+  );
+  temp_dir.write(
+    "b/mod.ts",
+    "export function getCount(): number {\n  return 1;\n}\n",
+  );
+
+  // the members which are depended on are checked first
+  let output = test_context.new_command().args("check --workspace").run();
+  output.assert_matches_text(
+    "Check @scope/b\nCheck @scope/a\nerror: TS2322 [ERROR]: Type 'number' is not assignable to type 'string'.[WILDCARD]a/mod.ts[WILDCARD]",
+  );
+  output.assert_exit_code(1);
+
+  temp_dir.write(
+    "a/mod.ts",
+    "import { getCount } from \"b\";\n\nexport const count: number = getCount();\n",
+  );
+  let output = test_context.new_command().args("check --workspace").run();
+  output.assert_matches_text("Check @scope/b\nCheck @scope/a\n");
+  output.assert_exit_code(0);
+
+  temp_dir.write("deno.json", "{}");
+  let output = test_context.new_command().args("check --workspace").run();
+  output.assert_matches_text(
+    "error: '--workspace' requires a configuration file with \"workspaces\"\n",
+  );
+  output.assert_exit_code(1);
+}
+
+#[test]
+fn check_trace() {
+  let test_context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = test_context.temp_dir();
+  temp_dir.write("mod.ts", "export const value: number = 1;\n");
+  temp_dir.write(
+    "main.ts",
+    "import { value } from \"./mod.ts\";\nconsole.log(value);\n",
+  );
+
+  let output = test_context.new_command().args("check main.ts").run();
+  output.assert_exit_code(0);
+
+  // the trace checks again, even though the files were already checked
+  let output = test_context
+    .new_command()
+    .args("check --trace=trace.json main.ts")
+    .run();
+  output.assert_matches_text(
+    "Check [WILDCARD]main.ts\nCompilation statistics:\n[WILDCARD]Total check time: [WILDCARD]ms\nType check hotspots:\n[WILDCARD]main.ts ([WILDCARD] instantiations)\n[WILDCARD]Trace [WILDCARD]trace.json\n",
+  );
+  output.assert_exit_code(0);
+
+  let trace = temp_dir.read_to_string("trace.json");
+  let trace: serde_json::Value = serde_json::from_str(&trace).unwrap();
+  let events = trace["traceEvents"].as_array().unwrap();
+  for name in ["parse", "resolve", "bind", "check"] {
+    assert!(
+      events.iter().any(|event| event["name"] == name
+        && event["ph"] == "X"
+        && event["args"]["path"].as_str().unwrap().ends_with("main.ts")),
+      "missing {name} event"
+    );
+  }
+}
+
+#[test]
+fn check_typescript_version_unsupported() {
+  let test_context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = test_context.temp_dir();
+  temp_dir.write("deno.json", r#"{ "typescriptVersion": "4.9.5" }"#);
+  temp_dir.write("main.ts", "const value: number = 1;\nconsole.log(value);\n");
+
+  let output = test_context.new_command().args("check main.ts").run();
+  output.assert_matches_text(
+    "error: TypeScript 4.9.5 is not supported by \"typescriptVersion\". Use 5.3.0 or later.\n",
+  );
+  output.assert_exit_code(1);
+
+  temp_dir.write("deno.json", r#"{ "typescriptVersion": "latest" }"#);
+  let output = test_context.new_command().args("check main.ts").run();
+  output.assert_matches_text(
+    "error: Invalid typescriptVersion in '[WILDCARD]deno.json'\n\nCaused by:\n    0: Invalid \"typescriptVersion\" \"latest\". Use an exact version like \"5.4.5\".\n[WILDCARD]",
+  );
+  output.assert_exit_code(1);
+}
+
+#[test]
+fn check_deno_version_req_unsatisfied() {
+  let test_context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = test_context.temp_dir();
+  temp_dir.write("deno.json", r#"{ "deno": "<1" }"#);
+  temp_dir.write("main.ts", "console.log(1);\n");
+
+  let output = test_context.new_command().args("check main.ts").run();
+  output.assert_matches_text(
+    "error: Deno [WILDCARD] doesn't satisfy the version requirement \"<1\" of '[WILDCARD]deno.json'. Run `deno upgrade` to install a version which does.\n",
+  );
+  output.assert_exit_code(1);
+  let output = test_context.new_command().args("run main.ts").run();
+  output
+    .assert_matches_text("error: Deno [WILDCARD] doesn't satisfy [WILDCARD]");
+  output.assert_exit_code(1);
+
+  temp_dir.write("deno.json", r#"{ "deno": ">=1" }"#);
+  let output = test_context.new_command().args("run main.ts").run();
+  output.assert_matches_text("1\n");
+  output.assert_exit_code(0);
+}
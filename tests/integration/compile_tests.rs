@@ -114,6 +114,54 @@ fn standalone_error() {
   assert_contains!(stderr, "standalone_error.ts:7:1");
 }
 
+#[test]
+fn standalone_error_source_map() {
+  let context = TestContextBuilder::new().build();
+  let dir = context.temp_dir();
+  let exe = if cfg!(windows) {
+    dir.path().join("error.exe")
+  } else {
+    dir.path().join("error")
+  };
+  let compile = |flags: &[&str]| {
+    let mut args = vec!["compile".to_string()];
+    args.extend(flags.iter().map(|flag| flag.to_string()));
+    args.extend([
+      "--output".to_string(),
+      exe.to_string_lossy().to_string(),
+      "./compile/standalone_error_types.ts".to_string(),
+    ]);
+    context
+      .new_command()
+      .args_vec(args)
+      .run()
+      .skip_output_check()
+      .assert_exit_code(0);
+    let output = context
+      .new_command()
+      .name(&exe)
+      .env("NO_COLOR", "1")
+      .split_output()
+      .run();
+    output.assert_exit_code(1);
+    output.stderr().to_string()
+  };
+
+  // the locations are the ones of the typescript module, not the emit
+  for args in [&[][..], &["--include-source-maps"][..]] {
+    let stderr = compile(args);
+    assert_contains!(stderr, "error: Uncaught (in promise) Error: boom!");
+    assert_contains!(stderr, "throw new Error(options.message);");
+    assert_contains!(stderr, "standalone_error_types.ts:8:9");
+    assert_contains!(stderr, "standalone_error_types.ts:11:34");
+    assert_contains!(stderr, "standalone_error_types.ts:12:1");
+  }
+
+  let stderr = compile(&["--strip"]);
+  assert_contains!(stderr, "error: Uncaught (in promise) Error: boom!");
+  assert_not_contains!(stderr, "standalone_error_types.ts:8:9");
+}
+
 #[test]
 fn standalone_error_module_with_imports() {
   let context = TestContextBuilder::new().build();
@@ -650,6 +698,52 @@ fn workers_with_include_flag() {
     .assert_matches_text("Hello from worker!\nReceived 42\nClosing\n");
 }
 
+#[test]
+fn compile_include_assets() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.create_dir_all("static/nested");
+  temp_dir.create_dir_all("templates");
+  temp_dir.write("static/hello.txt", "Hello from an asset!");
+  temp_dir.write("static/nested/data.bin", "data");
+  temp_dir.write("templates/index.html", "<h1>Index</h1>");
+  temp_dir.write("templates/ignored.txt", "");
+  temp_dir.write(
+    "main.ts",
+    concat!(
+      "const url = new URL('./static/hello.txt', import.meta.url);\n",
+      "console.log(Deno.readTextFileSync(url));\n",
+      "for (const path of Deno.embeddedFiles ?? []) {\n",
+      "  console.log(path.split(/[\\\\/]/).slice(-2).join('/'));\n",
+      "}\n",
+    ),
+  );
+  let binary_path = if cfg!(windows) {
+    temp_dir.path().join("binary.exe")
+  } else {
+    temp_dir.path().join("binary")
+  };
+
+  context
+    .new_command()
+    .args("compile --output binary --include static --include templates/*.html main.ts")
+    .run()
+    .skip_output_check()
+    .assert_exit_code(0);
+
+  // the assets are read from the executable
+  temp_dir.remove_dir_all("static");
+  temp_dir.remove_dir_all("templates");
+  context
+    .new_command()
+    .name(&binary_path)
+    .run()
+    .assert_matches_text(
+      "Hello from an asset!\nstatic/hello.txt\nnested/data.bin\ntemplates/index.html\n",
+    )
+    .assert_exit_code(0);
+}
+
 #[test]
 fn dynamic_import() {
   let context = TestContext::with_http_server();
@@ -631,8 +631,140 @@ File         | Branch % | Line % |
   );
 }
 
+#[test]
+fn test_cobertura_and_json_summary_reporters() {
+  let context = TestContext::default();
+  let tempdir = context.temp_dir();
+  let tempdir = tempdir.path().join("cov");
+
+  let output = context
+    .new_command()
+    .args_vec(vec![
+      "test".to_string(),
+      "--quiet".to_string(),
+      format!("--coverage={}", tempdir),
+      "coverage/multisource".to_string(),
+    ])
+    .run();
+
+  output.assert_exit_code(0);
+  output.skip_output_check();
+
+  let output = context
+    .new_command()
+    .args_vec(vec![
+      "coverage".to_string(),
+      "--cobertura".to_string(),
+      format!("--output={}", tempdir.join("cobertura.xml")),
+      format!("{}/", tempdir),
+    ])
+    .run();
+
+  output.assert_exit_code(0);
+  output.skip_output_check();
+
+  let cobertura_xml =
+    fs::read_to_string(tempdir.join("cobertura.xml")).unwrap();
+  assert!(cobertura_xml.starts_with("<?xml version=\"1.0\" ?>\n"));
+  assert!(cobertura_xml.contains("<package name=\".\""));
+  assert!(cobertura_xml.contains("<package name=\"baz\""));
+  assert!(cobertura_xml.contains("<class name=\"foo.ts\" filename=\"foo.ts\""));
+  assert!(cobertura_xml.contains(
+    "<class name=\"baz/qux.ts\" filename=\"baz/qux.ts\" line-rate=\"1\""
+  ));
+
+  let output = context
+    .new_command()
+    .args_vec(vec![
+      "coverage".to_string(),
+      "--json-summary".to_string(),
+      format!("{}/", tempdir),
+    ])
+    .run();
+
+  output.assert_exit_code(0);
+  let summary: serde_json::Value =
+    serde_json::from_str(&output.combined_output()).unwrap();
+  let summary = summary.as_object().unwrap();
+  assert_eq!(summary.len(), 5);
+  assert_eq!(summary["total"]["branches"]["pct"], 40.0);
+  let (_, qux) = summary
+    .iter()
+    .find(|(path, _)| path.ends_with("qux.ts"))
+    .unwrap();
+  assert_eq!(qux["lines"]["pct"], 100.0);
+  assert_eq!(qux["branches"]["pct"], 100.0);
+}
+
 itest!(no_files_found {
   args: "coverage doesnt_exist.js",
   exit_code: 1,
   output: "coverage/doesnt_exist.out",
 });
+
+#[test]
+fn test_coverage_threshold() {
+  let context = TestContext::default();
+  let tempdir = context.temp_dir();
+  let tempdir = tempdir.path().join("cov");
+
+  let output = context
+    .new_command()
+    .args_vec(vec![
+      "test".to_string(),
+      "--quiet".to_string(),
+      format!("--coverage={}", tempdir),
+      "coverage/multisource".to_string(),
+    ])
+    .run();
+
+  output.assert_exit_code(0);
+  output.skip_output_check();
+
+  let output = context
+    .new_command()
+    .args_vec(vec![
+      "coverage".to_string(),
+      "--coverage-threshold=lines:90,branches:30".to_string(),
+      format!("{}/", tempdir),
+    ])
+    .run();
+
+  output.assert_exit_code(1);
+  let combined_output = util::strip_ansi_codes(output.combined_output());
+  assert!(combined_output.contains("Files below the coverage threshold:"));
+  assert!(combined_output.contains("foo.ts"));
+  assert!(
+    combined_output.contains("Coverage threshold not met: lines 61.0% < 90%")
+  );
+  assert!(!combined_output.contains("branches 40.0%"));
+
+  let output = context
+    .new_command()
+    .args_vec(vec![
+      "coverage".to_string(),
+      "--coverage-threshold=lines:50".to_string(),
+      format!("{}/", tempdir),
+    ])
+    .run();
+
+  output.assert_exit_code(0);
+  output.skip_output_check();
+
+  let output = context
+    .new_command()
+    .args_vec(vec![
+      "test".to_string(),
+      "--quiet".to_string(),
+      format!("--coverage={}", tempdir.join("test")),
+      "--coverage-threshold=lines:90".to_string(),
+      "coverage/multisource".to_string(),
+    ])
+    .run();
+
+  output.assert_exit_code(1);
+  let combined_output = util::strip_ansi_codes(output.combined_output());
+  assert!(
+    combined_output.contains("Coverage threshold not met: lines 61.0% < 90%")
+  );
+}
@@ -80,6 +80,13 @@ itest!(deno_doc_lint_json_success {
   output: "doc/lint_success_json.out",
 });
 
+itest!(deno_doc_lint_rules_json {
+  args: "doc --lint --json mod.ts",
+  cwd: Some("doc/lint_rules"),
+  exit_code: 1,
+  output: "doc/lint_rules/main_json.out",
+});
+
 itest!(deno_doc_lint_html_success {
   args: "doc --lint --html --name=Library lint_success.ts",
   copy_temp_dir: Some("doc"),
@@ -150,3 +157,74 @@ fn deno_doc_html() {
   assert!(temp_dir.path().join("~/MyClass.html").exists());
   assert!(temp_dir.path().join("~/index.html").exists());
 }
+
+#[test]
+fn deno_doc_html_workspace() {
+  let context = TestContext::default();
+  let temp_dir = context.temp_dir();
+  let output = context
+    .new_command()
+    .env("NO_COLOR", "1")
+    .current_dir(util::testdata_path().join("doc/workspace"))
+    .args_vec(vec![
+      "doc",
+      "--html",
+      "--name=MyWorkspace",
+      &format!("--output={}", temp_dir.path().to_string_lossy()),
+      "--workspace",
+    ])
+    .split_output()
+    .run();
+
+  output.assert_exit_code(0);
+  assert_contains!(output.stderr(), "Written");
+  let index = temp_dir.read_to_string("index.html");
+  assert_contains!(index, "<title>MyWorkspace</title>");
+  assert_contains!(index, "<a href=\"@foo/foo/index.html\">@foo/foo</a>");
+  assert_contains!(index, "theme.css");
+  assert!(temp_dir.path().join("theme.css").exists());
+  assert!(temp_dir.path().join("@foo/bar/~/Bar.html").exists());
+  assert!(temp_dir.path().join("@foo/foo/~/createBar.html").exists());
+  assert_contains!(
+    temp_dir.read_to_string("workspace_search_index.js"),
+    r#""name":"createBar","kind":"function","package":"@foo/foo","href":"@foo/foo/~/createBar.html""#
+  );
+  assert_contains!(
+    temp_dir.read_to_string("@foo/foo/~/createBar.html"),
+    "../../../theme.css"
+  );
+}
+
+#[test]
+fn deno_doc_markdown() {
+  let context = TestContext::default();
+  let temp_dir = context.temp_dir();
+  let output = context
+    .new_command()
+    .env("NO_COLOR", "1")
+    .current_dir(util::testdata_path().join("doc/markdown"))
+    .args_vec(vec![
+      "doc",
+      "--markdown",
+      "--name=Greetings",
+      &format!("--output={}", temp_dir.path().to_string_lossy()),
+      "mod.ts",
+    ])
+    .split_output()
+    .run();
+
+  output.assert_exit_code(0);
+  assert_contains!(output.stderr(), "Written 2 files to");
+  assert_eq!(
+    temp_dir.read_to_string("index.md"),
+    "# Greetings\n\n- [mod.ts](mod.md): Utilities for greeting.\n"
+  );
+  let module = temp_dir.read_to_string("mod.md");
+  assert_contains!(module, "# mod.ts\n\nUtilities for greeting.");
+  assert_contains!(
+    module,
+    "## Table of contents\n\n- [Greeter](#greeter)\n- [createGreeter](#creategreeter)\n"
+  );
+  assert_contains!(module, "function createGreeter(): Greeter");
+  assert_contains!(module, "- `greet`: Greets someone.");
+}
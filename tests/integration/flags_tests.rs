@@ -2,6 +2,7 @@
 
 use test_util as util;
 use util::assert_contains;
+use util::TestContextBuilder;
 
 #[test]
 fn help_flag() {
@@ -84,3 +85,56 @@ itest!(types {
   args: "types",
   output: "types/types.out",
 });
+
+#[test]
+fn types_ffi() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write(
+    "mylib.h",
+    "#include <stdint.h>\n\ntypedef struct { double x; double y; } Vec2;\n\nint32_t add(int32_t a, int32_t b);\nVec2 vec2_add(Vec2 a, Vec2 b);\n",
+  );
+  let output = context
+    .new_command()
+    .args("types --ffi mylib.h")
+    .split_output()
+    .run();
+  output.assert_exit_code(0);
+  let bindings = output.stdout();
+  assert_contains!(
+    bindings,
+    "  add: { parameters: [\"i32\", \"i32\"], result: \"i32\" },"
+  );
+  assert_contains!(
+    bindings,
+    "  vec2_add: { parameters: [Vec2, Vec2], result: Vec2 },"
+  );
+  temp_dir.write("mylib.ts", bindings);
+  context
+    .new_command()
+    .args("check --unstable-ffi mylib.ts")
+    .run()
+    .assert_exit_code(0)
+    .skip_output_check();
+  context
+    .new_command()
+    .args("types --ffi mylib.h --check mylib.ts")
+    .run()
+    .assert_exit_code(0)
+    .assert_matches_text("");
+
+  // the signature of the header has changed
+  temp_dir.write(
+    "mylib.h",
+    "#include <stdint.h>\n\ntypedef struct { double x; double y; } Vec2;\n\nint32_t add(int32_t a, int64_t b);\nVec2 vec2_add(Vec2 a, Vec2 b);\n",
+  );
+  let output = context
+    .new_command()
+    .args("types --ffi mylib.h --check mylib.ts")
+    .run();
+  output.assert_exit_code(1);
+  assert_contains!(
+    output.combined_output(),
+    "error: The FFI bindings in \"mylib.ts\" are not the ones generated from \"mylib.h\"."
+  );
+}
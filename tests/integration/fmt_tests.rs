@@ -100,6 +100,101 @@ fn fmt_test() {
   assert_eq!(expected_ipynb, actual_ipynb);
 }
 
+#[test]
+fn fmt_css_scss_html() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write("style.css", "a{color:red}");
+  temp_dir.write("style.scss", "$c:red;a{b{color:$c}}");
+  temp_dir.write(
+    "index.html",
+    "<html><head><style>a{color:red}</style></head><body><script>let a=1</script></body></html>",
+  );
+  temp_dir.write("deno.json", r#"{ "fmt": { "exclude": ["excluded.css"] } }"#);
+  temp_dir.write("excluded.css", "a{color:red}");
+
+  let output = context.new_command().args("fmt --check").run();
+  output.assert_exit_code(1);
+  assert_contains!(output.combined_output(), "not formatted");
+
+  let output = context.new_command().args("fmt").run();
+  output.assert_exit_code(0);
+  output.skip_output_check();
+
+  let output = context.new_command().args("fmt --check").run();
+  output.assert_exit_code(0);
+  output.skip_output_check();
+  assert_eq!(temp_dir.read_to_string("excluded.css"), "a{color:red}");
+}
+
+#[test]
+fn fmt_organize_imports() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write(
+    "deno.json",
+    r#"{ "imports": { "@std/assert": "jsr:@std/assert@^0.218.0" } }"#,
+  );
+  temp_dir.write(
+    "main.ts",
+    r#"import { b } from "./b.ts";
+import { unused } from "./c.ts";
+import { assert } from "jsr:@std/assert@^0.218.0";
+
+assert(b);
+"#,
+  );
+
+  let output = context.new_command().args("fmt --organize-imports").run();
+  output.assert_exit_code(0);
+  output.skip_output_check();
+  assert_eq!(
+    temp_dir.read_to_string("main.ts"),
+    r#"import { assert } from "@std/assert";
+
+import { b } from "./b.ts";
+
+assert(b);
+"#
+  );
+
+  let output = context
+    .new_command()
+    .args("fmt --organize-imports --check")
+    .run();
+  output.assert_exit_code(0);
+  output.skip_output_check();
+}
+
+#[test]
+fn fmt_negated_exclude_and_gitignore() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write(
+    "deno.json",
+    r#"{ "exclude": ["dist"], "fmt": { "exclude": ["!dist/keep.ts"] } }"#,
+  );
+  temp_dir.write(".gitignore", "generated/\n");
+  temp_dir.create_dir_all("dist");
+  temp_dir.create_dir_all("generated");
+  for path in ["main.ts", "dist/keep.ts", "dist/other.ts", "generated/a.ts"] {
+    temp_dir.write(path, "const a=1");
+  }
+
+  let output = context.new_command().args("fmt").run();
+  output.assert_exit_code(0);
+  output.skip_output_check();
+  assert_eq!(temp_dir.read_to_string("main.ts"), "const a = 1;\n");
+  assert_eq!(temp_dir.read_to_string("dist/keep.ts"), "const a = 1;\n");
+  assert_eq!(temp_dir.read_to_string("dist/other.ts"), "const a=1");
+  assert_eq!(temp_dir.read_to_string("generated/a.ts"), "const a=1");
+
+  let output = context.new_command().args("fmt --no-gitignore").run();
+  output.assert_exit_code(0);
+  output.skip_output_check();
+  assert_eq!(temp_dir.read_to_string("generated/a.ts"), "const a = 1;\n");
+}
+
 #[test]
 fn fmt_stdin_syntax_error() {
   let output = util::deno_cmd()
@@ -50,6 +50,49 @@ itest!(info_flag_location {
   output: "info/041_info_flag_location.out",
 });
 
+itest!(info_dot {
+  args: "info --format=dot info/dot/mod.ts",
+  output: "info/dot/mod.out",
+});
+
+itest!(info_diff {
+  args: "info --diff=info/diff/old_graph.json info/diff/main.ts",
+  output: "info/diff/main.out",
+});
+
+itest!(info_diff_json {
+  args: "info --json --diff=info/diff/old_graph.json info/diff/main.ts",
+  output: "info/diff/main_json.out",
+});
+
+itest!(info_diff_unchanged {
+  args: "info --diff=info/diff/unchanged.json info/diff/dep.ts",
+  output: "info/diff/unchanged.out",
+});
+
+itest!(info_licenses {
+  args: "info --quiet --licenses info/licenses/main.ts",
+  output: "info/licenses/main.out",
+  envs: env_vars_for_npm_tests(),
+  http_server: true,
+});
+
+itest!(info_licenses_json {
+  args: "info --quiet --licenses --json info/licenses/main.ts",
+  output: "info/licenses/main_json.out",
+  envs: env_vars_for_npm_tests(),
+  http_server: true,
+});
+
+itest!(info_licenses_not_allowed {
+  args: "info --quiet --licenses main.ts",
+  output: "info/licenses/allowlist/main.out",
+  envs: env_vars_for_npm_tests(),
+  http_server: true,
+  cwd: Some("info/licenses/allowlist"),
+  exit_code: 1,
+});
+
 itest!(info_json {
   args: "info --json",
   output: "info/info_json.out",
@@ -169,3 +169,76 @@ Run these commands to get started
   output.assert_exit_code(0);
   output.assert_matches_text("Log from main.ts that already exists\n");
 }
+
+#[test]
+fn init_subcommand_with_template() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let cwd = context.temp_dir().path();
+
+  let output = context
+    .new_command()
+    .args("init --template=library --ci my_lib")
+    .split_output()
+    .run();
+
+  output.assert_exit_code(0);
+
+  let stderr = output.stderr();
+  assert_contains!(stderr, "Project initialized");
+  assert_contains!(stderr, "cd my_lib");
+  assert_contains!(stderr, "deno publish --dry-run");
+
+  assert!(cwd.join("my_lib/mod.ts").exists());
+  assert!(cwd.join("my_lib/mod_test.ts").exists());
+  assert!(cwd.join("my_lib/.github/workflows/ci.yml").exists());
+  let deno_json = cwd.join("my_lib/deno.json").read_to_string();
+  assert_contains!(deno_json, r#""name": "@scope/my-lib""#);
+  assert_contains!(deno_json, r#""$std/": "https://deno.land/std@"#);
+}
+
+#[test]
+fn init_subcommand_with_remote_template() {
+  let context = TestContextBuilder::new()
+    .use_http_server()
+    .use_temp_cwd()
+    .build();
+  let cwd = context.temp_dir().path();
+
+  let output = context
+    .new_command()
+    .args("init --template=http://localhost:4545/init/template.json")
+    .split_output()
+    .run();
+
+  output.assert_exit_code(0);
+  assert_contains!(output.stderr(), "Project initialized");
+  assert_eq!(
+    cwd.join("src/util.ts").read_to_string(),
+    "export const answer = 42;\n"
+  );
+
+  let output = context
+    .new_command()
+    .env("NO_COLOR", "1")
+    .args("run main.ts")
+    .run();
+  output.assert_exit_code(0);
+  output.assert_matches_text("Hello from the template\n");
+}
+
+#[test]
+fn init_subcommand_with_unknown_template() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+
+  let output = context
+    .new_command()
+    .args("init --template=unknown")
+    .split_output()
+    .run();
+
+  output.assert_exit_code(1);
+  assert_contains!(
+    output.stderr(),
+    "Unknown template 'unknown'. Use one of default, server, cli, library, worker, or a jsr: or https: URL."
+  );
+}
@@ -209,3 +209,50 @@ fn lint_with_glob_config_and_flags() {
   assert_contains!(output, "Found 2 problems");
   assert_contains!(output, "Checked 2 files");
 }
+
+#[test]
+fn lint_with_workspace_member_config() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write(
+    "deno.json",
+    r#"{
+  "workspaces": ["member"],
+  "lint": {
+    "rules": {
+      "exclude": ["no-var"]
+    }
+  }
+}"#,
+  );
+  temp_dir.write("main.ts", "var a = 1;\nconsole.log(a);\n");
+  temp_dir.create_dir_all("member");
+  temp_dir.write(
+    "member/deno.json",
+    r#"{
+  "name": "@scope/member",
+  "version": "0.0.0",
+  "lint": {
+    "exclude": ["ignored.ts"],
+    "rules": {
+      "exclude": []
+    }
+  }
+}"#,
+  );
+  temp_dir.write("member/mod.ts", "var b = 1;\nconsole.log(b);\n");
+  temp_dir.write("member/ignored.ts", "var c = 1;\nconsole.log(c);\n");
+
+  let cmd_output = context.new_command().args("lint").run();
+
+  cmd_output.assert_exit_code(1);
+  let output = cmd_output.combined_output();
+  assert_contains!(output, "error[no-var]");
+  if cfg!(windows) {
+    assert_contains!(output, r"member\mod.ts:1:1");
+  } else {
+    assert_contains!(output, "member/mod.ts:1:1");
+  }
+  assert_contains!(output, "Found 1 problem");
+  assert_contains!(output, "Checked 2 files");
+}
@@ -0,0 +1,87 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::serde_json::json;
+use test_util as util;
+use util::TestContextBuilder;
+
+#[test]
+fn lock_verify() {
+  let context = TestContextBuilder::for_npm().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write("deno.json", "{}");
+  temp_dir.write(
+    "main.ts",
+    "import 'http://localhost:4545/subdir/print_hello.ts';\nimport 'npm:@denotest/esm-basic';\n",
+  );
+  context
+    .new_command()
+    .args("cache main.ts")
+    .run()
+    .skip_output_check();
+
+  let output = context.new_command().args("lock --verify").run();
+  output.assert_exit_code(0);
+  output.assert_matches_text(
+    "[WILDCARD]Verified 2 entries of \"[WILDCARD]deno.lock\".\n",
+  );
+
+  // tamper with the checksums
+  let lockfile = temp_dir.path().join("deno.lock");
+  let mut lockfile_json = lockfile.read_json_value();
+  lockfile_json["remote"]["http://localhost:4545/subdir/print_hello.ts"] =
+    json!("0000");
+  lockfile_json["packages"]["npm"]["@denotest/esm-basic@1.0.0"]["integrity"] =
+    json!("sha512-tampered");
+  lockfile.write_json(&lockfile_json);
+
+  let output = context.new_command().args("lock --verify").run();
+  output.assert_exit_code(1);
+  output.assert_matches_text(
+    "[WILDCARD]error: The remote module \"http://localhost:4545/subdir/print_hello.ts\" doesn't match the checksum of the lockfile.
+  Expected: 0000
+  Actual:   [WILDCARD]
+error: The npm package \"@denotest/esm-basic@1.0.0\" doesn't match the integrity of the lockfile.
+  Expected: sha512-tampered
+  Registry: [WILDCARD]
+error: 2 entries of the lockfile failed verification.
+
+If the changes are expected, update the lockfile with 'deno cache --reload --lock-write <files>'. Otherwise, find out which dependencies were modified before running any code which uses them.
+",
+  );
+}
+
+#[test]
+fn lock_verify_missing_package() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.path().join("deno.lock").write_json(&json!({
+    "version": "3",
+    "packages": {
+      "specifiers": {
+        "npm:chalk@5": "npm:chalk@5.3.0"
+      }
+    },
+    "remote": {}
+  }));
+
+  let output = context
+    .new_command()
+    .args("lock --verify --lock=deno.lock")
+    .run();
+  output.assert_exit_code(1);
+  output.assert_matches_text(
+    "error: The npm package \"chalk@5.3.0\" which \"npm:chalk@5\" resolves to is missing from the lockfile.
+error: 1 entry of the lockfile failed verification.
+[WILDCARD]",
+  );
+}
+
+#[test]
+fn lock_verify_no_lockfile() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let output = context.new_command().args("lock --verify").run();
+  output.assert_exit_code(1);
+  output.assert_matches_text(
+    "error: No lockfile to verify. Specify one with '--lock' or a configuration file next to a \"deno.lock\".\n",
+  );
+}
@@ -836,6 +836,74 @@ fn lsp_workspace_enable_paths_no_workspace_configuration() {
   client.shutdown();
 }
 
+#[test]
+fn lsp_workspace_folders_conflicting_import_maps() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  // each folder maps "util" to its own module, which only exports the name
+  // its folder imports
+  for (folder, name) in [("project_a", "a"), ("project_b", "b")] {
+    temp_dir.create_dir_all(folder);
+    temp_dir.write(
+      format!("{folder}/deno.json"),
+      json!({ "imports": { "util": "./util.ts" } }).to_string(),
+    );
+    temp_dir.write(
+      format!("{folder}/util.ts"),
+      format!("export const {name} = 1;\n"),
+    );
+  }
+  let mut client = context.new_lsp_command().build();
+  client.initialize(|builder| {
+    builder.set_root_uri(temp_dir.uri().join("project_a/").unwrap());
+    builder.set_workspace_folders(vec![
+      lsp::WorkspaceFolder {
+        uri: temp_dir.uri().join("project_a/").unwrap(),
+        name: "project_a".to_string(),
+      },
+      lsp::WorkspaceFolder {
+        uri: temp_dir.uri().join("project_b/").unwrap(),
+        name: "project_b".to_string(),
+      },
+    ]);
+  });
+
+  let main_a = temp_dir.uri().join("project_a/main.ts").unwrap();
+  let diagnostics = client.did_open(json!({
+    "textDocument": {
+      "uri": main_a,
+      "languageId": "typescript",
+      "version": 1,
+      "text": "import { a } from \"util\";\nconsole.log(a);\n",
+    }
+  }));
+  assert_eq!(diagnostics.all(), vec![]);
+  let main_b = temp_dir.uri().join("project_b/main.ts").unwrap();
+  let diagnostics = client.did_open(json!({
+    "textDocument": {
+      "uri": main_b,
+      "languageId": "typescript",
+      "version": 1,
+      "text": "import { b } from \"util\";\nconsole.log(b);\n",
+    }
+  }));
+  assert_eq!(diagnostics.all(), vec![]);
+
+  // the specifier resolves with the import map of the folder of the referrer
+  let res = client.write_request(
+    "textDocument/definition",
+    json!({
+      "textDocument": { "uri": main_b },
+      "position": { "line": 1, "character": 12 },
+    }),
+  );
+  assert_eq!(
+    res[0]["targetUri"],
+    json!(temp_dir.uri().join("project_b/util.ts").unwrap()),
+  );
+  client.shutdown();
+}
+
 #[test]
 fn lsp_did_change_deno_configuration_notification() {
   let context = TestContextBuilder::new().use_temp_cwd().build();
@@ -1010,6 +1078,184 @@ fn lsp_deno_task() {
   );
 }
 
+#[test]
+fn lsp_publish_diagnostics() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write(
+    "deno.json",
+    r#"{ "name": "@foo/bar", "version": "1.0.0", "exports": "./mod.ts" }"#,
+  );
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+
+  let diagnostics = client.did_open(json!({
+    "textDocument": {
+      "uri": temp_dir.uri().join("mod.ts").unwrap(),
+      "languageId": "typescript",
+      "version": 1,
+      "text": "export function add(a: number, b: number) {\n  return a + b;\n}\n",
+    }
+  }));
+  let diagnostics = diagnostics.messages_with_source("deno-publish");
+  assert_eq!(
+    diagnostics
+      .diagnostics
+      .iter()
+      .map(|d| (d.range.start.line, d.code.clone().unwrap()))
+      .collect::<Vec<_>>(),
+    vec![(
+      0,
+      lsp::NumberOrString::String(
+        "zap-missing-explicit-return-type".to_string()
+      )
+    )]
+  );
+  client.shutdown();
+}
+
+#[test]
+fn lsp_deno_json_tasks() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  let config_text = r#"{
+  "tasks": {
+    "build": "deno run build.ts &&",
+    "dev": {
+      "command": "deno run main.ts",
+      "dependsOn": ["", "fetch"]
+    }
+  }
+}
+
+#[test]
+fn lsp_deno_json_deno_version() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  let config_text = "{\n  \"deno\": \"<1\"\n}\n";
+  temp_dir.write("deno.json", config_text);
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+
+  let diagnostics = client.did_open(json!({
+    "textDocument": {
+      "uri": temp_dir.uri().join("deno.json").unwrap(),
+      "languageId": "json",
+      "version": 1,
+      "text": config_text,
+    }
+  }));
+  let diagnostics = diagnostics.messages_with_source("deno-config");
+  assert_eq!(diagnostics.diagnostics.len(), 1);
+  let diagnostic = &diagnostics.diagnostics[0];
+  assert_eq!(
+    diagnostic.code,
+    Some(lsp::NumberOrString::String(
+      "deno-version-unsatisfied".to_string()
+    ))
+  );
+  assert_eq!(diagnostic.range.start, lsp::Position::new(1, 10));
+
+  let res = client.write_request(
+    "textDocument/codeAction",
+    json!({
+      "textDocument": { "uri": temp_dir.uri().join("deno.json").unwrap() },
+      "range": diagnostic.range,
+      "context": {
+        "diagnostics": [diagnostic],
+        "only": ["quickfix"],
+      },
+    }),
+  );
+  assert_eq!(
+    res,
+    json!([{
+      "title": "Upgrade Deno",
+      "kind": "quickfix",
+      "diagnostics": [diagnostic],
+      "command": {
+        "title": "Upgrade Deno",
+        "command": "deno.upgrade",
+      },
+    }])
+  );
+  client.shutdown();
+}
+"#;
+  temp_dir.write("deno.json", config_text);
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+
+  let diagnostics = client.did_open(json!({
+    "textDocument": {
+      "uri": temp_dir.uri().join("deno.json").unwrap(),
+      "languageId": "json",
+      "version": 1,
+      "text": config_text,
+    }
+  }));
+  let diagnostics = diagnostics.messages_with_source("deno-config");
+  assert_eq!(
+    diagnostics
+      .diagnostics
+      .iter()
+      .map(|d| (d.range.start.line, d.code.clone().unwrap()))
+      .collect::<Vec<_>>(),
+    vec![
+      (
+        2,
+        lsp::NumberOrString::String("invalid-task-command".to_string())
+      ),
+      (
+        5,
+        lsp::NumberOrString::String("unknown-task-dependency".to_string())
+      ),
+      (
+        5,
+        lsp::NumberOrString::String("unknown-task-dependency".to_string())
+      ),
+    ]
+  );
+
+  let list = client.get_completion_list(
+    temp_dir.uri().join("deno.json").unwrap(),
+    (5, 21),
+    json!({ "triggerKind": 1 }),
+  );
+  assert_eq!(
+    list
+      .items
+      .iter()
+      .map(|item| item.label.as_str())
+      .collect::<Vec<_>>(),
+    vec!["build"]
+  );
+
+  let res = client.write_request(
+    "textDocument/hover",
+    json!({
+      "textDocument": {
+        "uri": temp_dir.uri().join("deno.json").unwrap(),
+      },
+      "position": { "line": 1, "character": 4 }
+    }),
+  );
+  assert_eq!(
+    res,
+    json!({
+      "contents": {
+        "kind": "markdown",
+        "value": "Configuration for deno task",
+      },
+      "range": {
+        "start": { "line": 1, "character": 2 },
+        "end": { "line": 1, "character": 9 }
+      }
+    })
+  );
+  client.shutdown();
+}
+
 #[test]
 fn lsp_reload_import_registries_command() {
   let context = TestContextBuilder::new().use_temp_cwd().build();
@@ -1022,6 +1268,95 @@ fn lsp_reload_import_registries_command() {
   assert_eq!(res, json!(true));
 }
 
+#[test]
+fn lsp_reload_module_command() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write("main.ts", "console.log(1);\n");
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  // no program is running with HMR
+  let res = client.write_request(
+    "workspace/executeCommand",
+    json!({
+      "command": "deno.reloadModule",
+      "arguments": [temp_dir.uri().join("main.ts").unwrap()],
+    }),
+  );
+  assert_eq!(res, json!(0));
+  client.shutdown();
+}
+
+#[test]
+fn lsp_show_dependency_graph_command() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write("b.ts", "export const b = 1;\n");
+  temp_dir.write("c.ts", "export type C = string;\n");
+  temp_dir.write("d.ts", "export const b = 1;\n");
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": temp_dir.uri().join("main.ts").unwrap(),
+      "languageId": "typescript",
+      "version": 1,
+      "text": "import { b } from \"./b.ts\";\nimport type { C } from \"./c.ts\";\nconst c: C = \"\";\nconsole.log(b, c, import(\"./d.ts\"));\n",
+    }
+  }));
+  let specifier = |name: &str| temp_dir.uri().join(name).unwrap().to_string();
+  let res = client.write_request(
+    "workspace/executeCommand",
+    json!({
+      "command": "deno.showDependencyGraph",
+      "arguments": [temp_dir.uri().join("main.ts").unwrap()],
+    }),
+  );
+  assert_eq!(
+    res,
+    json!({
+      "roots": [specifier("main.ts")],
+      "nodes": [
+        { "specifier": specifier("main.ts"), "size": 115 },
+        { "specifier": specifier("b.ts"), "size": 20 },
+        { "specifier": specifier("c.ts"), "size": 24 },
+        { "specifier": specifier("d.ts"), "size": 20 },
+      ],
+      "edges": [
+        { "from": specifier("main.ts"), "to": specifier("b.ts"), "kind": "static" },
+        { "from": specifier("main.ts"), "to": specifier("c.ts"), "kind": "typeOnly" },
+        { "from": specifier("main.ts"), "to": specifier("d.ts"), "kind": "dynamic" },
+      ],
+    })
+  );
+  let res = client.write_request(
+    "workspace/executeCommand",
+    json!({
+      "command": "deno.showDependencyGraph",
+      "arguments": [temp_dir.uri().join("main.ts").unwrap(), "dot"],
+    }),
+  );
+  let dot = res.as_str().unwrap();
+  assert!(dot.starts_with("digraph {\n"), "{dot}");
+  assert!(
+    dot.contains(&format!(
+      "  \"{}\" -> \"{}\" [style=dotted];\n",
+      specifier("main.ts"),
+      specifier("c.ts")
+    )),
+    "{dot}"
+  );
+  assert!(
+    dot.contains(&format!(
+      "  \"{}\" -> \"{}\" [style=dashed];\n",
+      specifier("main.ts"),
+      specifier("d.ts")
+    )),
+    "{dot}"
+  );
+  client.shutdown();
+}
+
 #[test]
 fn lsp_import_attributes() {
   let context = TestContextBuilder::new().use_temp_cwd().build();
@@ -1071,7 +1406,8 @@ fn lsp_import_attributes() {
         "severity": 1,
         "code": "no-attribute-type",
         "source": "deno",
-        "message": "The module is a JSON module and not being imported with an import attribute. Consider adding `with { type: \"json\" }` to the import statement."
+        "message": "The module is a JSON module and not being imported with an import attribute. Consider adding `with { type: \"json\" }` to the import statement.",
+        "data": { "type": "json" }
       }
     ])
   );
@@ -1137,6 +1473,217 @@ fn lsp_import_attributes() {
   client.shutdown();
 }
 
+#[test]
+fn lsp_import_attributes_css_svg() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write("styles.css", "p { color: red; }\n");
+  temp_dir.write("logo.svg", "<svg xmlns=\"http://www.w3.org/2000/svg\" />\n");
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  let uri = temp_dir.uri().join("main.ts").unwrap();
+  let diagnostics = client.did_open(json!({
+    "textDocument": {
+      "uri": uri,
+      "languageId": "typescript",
+      "version": 1,
+      "text": "import sheet from \"./styles.css\" with { type: \"css\" };\nimport logo from \"./logo.svg\";\nimport data from \"./styles.css\" with { type: \"json\" };\nconst rules: number = sheet.cssRules.length;\nconst markup: number = logo;\nconsole.log(data, rules, markup);\n",
+    },
+  }));
+  let deno_diagnostics = diagnostics.messages_with_source("deno").diagnostics;
+  assert_eq!(
+    json!(deno_diagnostics),
+    json!([
+      {
+        "range": {
+          "start": { "line": 1, "character": 17 },
+          "end": { "line": 1, "character": 29 },
+        },
+        "severity": 1,
+        "code": "no-attribute-type",
+        "source": "deno",
+        "message": "The module is an SVG image and not being imported with an import attribute. Consider adding `with { type: \"text\" }` to the import statement.",
+        "data": { "type": "text" },
+      },
+      {
+        "range": {
+          "start": { "line": 2, "character": 17 },
+          "end": { "line": 2, "character": 31 },
+        },
+        "severity": 1,
+        "code": "invalid-attribute-type",
+        "source": "deno",
+        "message": "The module is a CSS module and expected an attribute type of \"css\". Instead got \"json\".",
+      },
+    ])
+  );
+  // the modules are typed by their generated declarations
+  assert_eq!(
+    json!(diagnostics.messages_with_source("deno-ts").diagnostics),
+    json!([{
+      "range": {
+        "start": { "line": 4, "character": 6 },
+        "end": { "line": 4, "character": 12 },
+      },
+      "severity": 1,
+      "code": 2322,
+      "source": "deno-ts",
+      "message": "Type 'string' is not assignable to type 'number'.",
+    }])
+  );
+  let diagnostic = json!(deno_diagnostics[0]);
+  let res = client.write_request(
+    "textDocument/codeAction",
+    json!({
+      "textDocument": { "uri": uri },
+      "range": diagnostic["range"],
+      "context": {
+        "diagnostics": [diagnostic],
+        "only": ["quickfix"],
+      },
+    }),
+  );
+  assert_eq!(
+    res[0]["edit"]["changes"][uri.as_str()],
+    json!([{
+      "range": {
+        "start": { "line": 1, "character": 29 },
+        "end": { "line": 1, "character": 29 },
+      },
+      "newText": " with { type: \"text\" }",
+    }])
+  );
+  client.shutdown();
+}
+
+#[test]
+fn lsp_wasm_module_imports() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  // a module which exports a function of (i32, i32) -> i32
+  let write_wasm = |export_name: &[u8; 3]| {
+    let mut bytes = b"\0asm\x01\0\0\0".to_vec();
+    bytes.extend([0x01, 0x07, 0x01, 0x60, 0x02, 0x7f, 0x7f, 0x01, 0x7f]);
+    bytes.extend([0x03, 0x02, 0x01, 0x00]);
+    bytes.extend([0x07, 0x07, 0x01, 0x03]);
+    bytes.extend(export_name);
+    bytes.extend([0x00, 0x00]);
+    std::fs::write(temp_dir.path().join("math.wasm"), bytes).unwrap();
+  };
+  write_wasm(b"add");
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  let uri = temp_dir.uri().join("main.ts").unwrap();
+  let diagnostics = client.did_open(json!({
+    "textDocument": {
+      "uri": uri,
+      "languageId": "typescript",
+      "version": 1,
+      "text": "import { add } from \"./math.wasm\";\nconst sum: string = add(1, 2);\nconsole.log(sum);\n",
+    },
+  }));
+  assert_eq!(
+    json!(diagnostics.all()),
+    json!([{
+      "range": {
+        "start": { "line": 1, "character": 6 },
+        "end": { "line": 1, "character": 9 },
+      },
+      "severity": 1,
+      "code": 2322,
+      "source": "deno-ts",
+      "message": "Type 'number' is not assignable to type 'string'.",
+    }])
+  );
+  let res = client.write_request(
+    "textDocument/hover",
+    json!({
+      "textDocument": { "uri": uri },
+      "position": { "line": 1, "character": 21 },
+    }),
+  );
+  assert_eq!(
+    res,
+    json!({
+      "contents": [
+        {
+          "language": "typescript",
+          "value": "(alias) function add(p0: number, p1: number): number\nimport add",
+        },
+        "",
+      ],
+      "range": {
+        "start": { "line": 1, "character": 20 },
+        "end": { "line": 1, "character": 23 },
+      },
+    })
+  );
+
+  // the export is renamed in the binary
+  write_wasm(b"sum");
+  client.did_change_watched_files(json!({
+    "changes": [{
+      "uri": temp_dir.uri().join("math.wasm").unwrap(),
+      "type": 2,
+    }],
+  }));
+  assert_eq!(
+    json!(client.read_diagnostics().all()),
+    json!([{
+      "range": {
+        "start": { "line": 0, "character": 9 },
+        "end": { "line": 0, "character": 12 },
+      },
+      "severity": 1,
+      "code": 2305,
+      "source": "deno-ts",
+      "message": "Module '\"./math.wasm\"' has no exported member 'add'.",
+    }])
+  );
+  client.shutdown();
+}
+
+#[test]
+fn lsp_ffi_call_diagnostics() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  let diagnostics = client.did_open(json!({
+    "textDocument": {
+      "uri": "file:///a/file.ts",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "const lib = Deno.dlopen(\"./libc.so\", {\n  puts: { parameters: [\"buffer\"], result: \"i32\" },\n});\nlib.symbols.puts(\"hello\", 1);\n",
+    },
+  }));
+  assert_eq!(
+    json!(diagnostics.messages_with_source("deno").diagnostics),
+    json!([
+      {
+        "range": {
+          "start": { "line": 3, "character": 12 },
+          "end": { "line": 3, "character": 16 },
+        },
+        "severity": 1,
+        "code": "ffi-arity",
+        "source": "deno",
+        "message": "The FFI symbol \"puts\" expects 1 argument, but got 2.",
+      },
+      {
+        "range": {
+          "start": { "line": 3, "character": 17 },
+          "end": { "line": 3, "character": 24 },
+        },
+        "severity": 1,
+        "code": "ffi-string-argument",
+        "source": "deno",
+        "message": "Argument 1 of the FFI symbol \"puts\" is of the native type \"buffer\", which can't be a string. Encode the string into a buffer, like `new TextEncoder().encode(\"text\\0\")`.",
+      },
+    ])
+  );
+  client.shutdown();
+}
+
 #[test]
 fn lsp_import_map_import_completions() {
   let context = TestContextBuilder::new().use_temp_cwd().build();
@@ -1510,6 +2057,68 @@ fn lsp_inlay_hints() {
   );
 }
 
+#[test]
+fn lsp_inlay_hints_npm_package() {
+  let context = TestContextBuilder::new()
+    .use_http_server()
+    .use_temp_cwd()
+    .build();
+  let mut client = context.new_lsp_command().build();
+  client.initialize(|builder| {
+    builder.enable_inlay_hints();
+  });
+  client.did_open(json!({
+    "textDocument": {
+      "uri": "file:///a/file.ts",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "import { sum } from 'npm:@denotest/inlay-hints@1.0.0';\nconsole.log(sum);\n",
+    }
+  }));
+  client.write_request(
+    "workspace/executeCommand",
+    json!({
+      "command": "deno.cache",
+      "arguments": [["npm:@denotest/inlay-hints@1.0.0"], "file:///a/file.ts"],
+    }),
+  );
+  client.read_diagnostics();
+  let res = client.write_request(
+    "textDocument/definition",
+    json!({
+      "textDocument": { "uri": "file:///a/file.ts" },
+      "position": { "line": 1, "character": 13 }
+    }),
+  );
+  let location = &res.as_array().unwrap()[0];
+  let uri = location
+    .get("targetUri")
+    .or_else(|| location.get("uri"))
+    .and_then(|u| u.as_str())
+    .unwrap();
+  assert!(uri.starts_with("deno:/npm/"), "{uri}");
+  // the package is outside of the enabled paths, but is still a dependency
+  let res = client.write_request(
+    "textDocument/inlayHint",
+    json!({
+      "textDocument": { "uri": uri },
+      "range": {
+        "start": { "line": 0, "character": 0 },
+        "end": { "line": 5, "character": 0 }
+      }
+    }),
+  );
+  let labels = res
+    .as_array()
+    .unwrap()
+    .iter()
+    .map(|hint| hint["label"].as_str().unwrap())
+    .collect::<Vec<_>>();
+  assert!(labels.contains(&"a:"), "{labels:?}");
+  assert!(labels.contains(&"b:"), "{labels:?}");
+  client.shutdown();
+}
+
 #[test]
 fn lsp_inlay_hints_not_enabled() {
   let context = TestContextBuilder::new().use_temp_cwd().build();
@@ -2542,6 +3151,68 @@ fn lsp_goto_type_definition() {
   client.shutdown();
 }
 
+#[test]
+fn lsp_type_hierarchy() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": "file:///a/file.ts",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "interface A {}\nclass B implements A {}\nclass C extends B {}\n"
+    }
+  }));
+  let res = client.write_request(
+    "textDocument/prepareTypeHierarchy",
+    json!({
+      "textDocument": { "uri": "file:///a/file.ts" },
+      "position": { "line": 1, "character": 6 }
+    }),
+  );
+  assert_eq!(
+    res,
+    json!([{
+      "name": "B",
+      "kind": 5,
+      "uri": "file:///a/file.ts",
+      "range": {
+        "start": { "line": 1, "character": 0 },
+        "end": { "line": 1, "character": 23 }
+      },
+      "selectionRange": {
+        "start": { "line": 1, "character": 6 },
+        "end": { "line": 1, "character": 7 }
+      }
+    }])
+  );
+  let item = res.as_array().unwrap()[0].clone();
+  let res = client
+    .write_request("typeHierarchy/supertypes", json!({ "item": item.clone() }));
+  assert_eq!(
+    res,
+    json!([{
+      "name": "A",
+      "kind": 11,
+      "uri": "file:///a/file.ts",
+      "range": {
+        "start": { "line": 0, "character": 0 },
+        "end": { "line": 0, "character": 14 }
+      },
+      "selectionRange": {
+        "start": { "line": 0, "character": 10 },
+        "end": { "line": 0, "character": 11 }
+      }
+    }])
+  );
+  let res =
+    client.write_request("typeHierarchy/subtypes", json!({ "item": item }));
+  assert_eq!(res.as_array().unwrap().len(), 1);
+  assert_eq!(res[0]["name"], "C");
+  client.shutdown();
+}
+
 #[test]
 fn lsp_call_hierarchy() {
   let context = TestContextBuilder::new().use_temp_cwd().build();
@@ -3021,6 +3692,51 @@ fn lsp_folding_range() {
   client.shutdown();
 }
 
+#[test]
+fn lsp_will_rename_files_deno_types() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write("lib.js", "export const a = 1;\n");
+  temp_dir.write("lib.d.ts", "export const a: number;\n");
+  temp_dir.create_dir_all("types");
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": temp_dir.uri().join("main.ts").unwrap(),
+      "languageId": "typescript",
+      "version": 1,
+      "text": "// @deno-types=\"./lib.d.ts\"\nimport { a } from \"./lib.js\";\nconsole.log(a);\n"
+    }
+  }));
+  let res = client.write_request(
+    "workspace/willRenameFiles",
+    json!({
+      "files": [{
+        "oldUri": temp_dir.uri().join("lib.d.ts").unwrap(),
+        "newUri": temp_dir.uri().join("types/lib.d.ts").unwrap(),
+      }]
+    }),
+  );
+  let edits = res["documentChanges"]
+    .as_array()
+    .unwrap()
+    .iter()
+    .filter(|change| {
+      change["textDocument"]["uri"]
+        == json!(temp_dir.uri().join("main.ts").unwrap())
+    })
+    .flat_map(|change| change["edits"].as_array().unwrap().clone())
+    .collect::<Vec<_>>();
+  assert_eq!(edits.len(), 1);
+  assert_eq!(edits[0]["range"]["start"]["line"], 0);
+  assert_eq!(
+    edits[0]["newText"].as_str().unwrap().trim_matches('"'),
+    "./types/lib.d.ts"
+  );
+  client.shutdown();
+}
+
 #[test]
 fn lsp_rename() {
   let context = TestContextBuilder::new().use_temp_cwd().build();
@@ -4968,6 +5684,63 @@ fn lsp_cache_on_save() {
   client.shutdown();
 }
 
+#[test]
+fn lsp_references_remote_module() {
+  let context = TestContextBuilder::new()
+    .use_http_server()
+    .use_temp_cwd()
+    .build();
+  let temp_dir = context.temp_dir();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": temp_dir.uri().join("file.ts").unwrap(),
+      "languageId": "typescript",
+      "version": 1,
+      "text": "import { returnsFoo } from \"http://localhost:4545/subdir/subdir2/mod2.ts\";\nimport { returnsFoo2 } from \"http://localhost:4545/subdir/mod1.ts\";\nconsole.log(returnsFoo(), returnsFoo2());\n",
+    },
+  }));
+  client.write_request(
+    "workspace/executeCommand",
+    json!({
+      "command": "deno.cache",
+      "arguments": [[], temp_dir.uri().join("file.ts").unwrap()],
+    }),
+  );
+  let res = client.write_request(
+    "textDocument/references",
+    json!({
+      "textDocument": {
+        "uri": "deno:/http/localhost%3A4545/subdir/subdir2/mod2.ts",
+      },
+      "position": { "line": 2, "character": 16 },
+      "context": {
+        "includeDeclaration": false
+      }
+    }),
+  );
+  let uris = res
+    .as_array()
+    .unwrap()
+    .iter()
+    .map(|location| location["uri"].as_str().unwrap().to_string())
+    .collect::<Vec<_>>();
+  let file_uri = temp_dir.uri().join("file.ts").unwrap().to_string();
+  assert!(uris.contains(&file_uri));
+  assert!(
+    uris.contains(&"deno:/http/localhost%3A4545/subdir/mod1.ts".to_string())
+  );
+  // each reference is only reported once
+  let locations = res.as_array().unwrap();
+  let unique_locations = locations
+    .iter()
+    .map(|location| location.to_string())
+    .collect::<std::collections::HashSet<_>>();
+  assert_eq!(unique_locations.len(), locations.len());
+  client.shutdown();
+}
+
 // Regression test for https://github.com/denoland/deno/issues/22122.
 #[test]
 fn lsp_cache_then_definition() {
@@ -6496,6 +7269,39 @@ fn lsp_npm_completions_auto_import_and_quick_fix_no_import_map() {
   );
 }
 
+#[test]
+fn lsp_semantic_tokens_import_specifiers() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": "file:///a/file.ts",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "import \"npm:chalk@5\";\nimport \"node:fs\";\nimport \"foo:bar\";\n",
+    }
+  }));
+  let res = client.write_request(
+    "textDocument/semanticTokens/full",
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts"
+      }
+    }),
+  );
+  assert_eq!(
+    res,
+    json!({
+      "data": [
+        0, 8, 4, 12, 0, 0, 4, 5, 13, 0, 0, 6, 1, 14, 64, 1, 8, 5, 12, 0, 0, 5,
+        2, 13, 0, 1, 8, 4, 12, 128
+      ]
+    })
+  );
+  client.shutdown();
+}
+
 #[test]
 fn lsp_semantic_tokens_for_disabled_module() {
   let context = TestContextBuilder::new()
@@ -6503,36 +7309,95 @@ fn lsp_semantic_tokens_for_disabled_module() {
     .use_temp_cwd()
     .build();
   let mut client = context.new_lsp_command().build();
-  client.initialize_with_config(
-    |builder| {
-      builder.set_deno_enable(false);
-    },
-    json!({ "deno": {
-      "enable": false
-    } }),
+  client.initialize_with_config(
+    |builder| {
+      builder.set_deno_enable(false);
+    },
+    json!({ "deno": {
+      "enable": false
+    } }),
+  );
+  client.did_open(json!({
+    "textDocument": {
+      "uri": "file:///a/file.ts",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "const someConst = 1; someConst"
+    }
+  }));
+  let res = client.write_request(
+    "textDocument/semanticTokens/full",
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts"
+      }
+    }),
+  );
+  assert_eq!(
+    res,
+    json!({
+      "data": [0, 6, 9, 7, 9, 0, 15, 9, 7, 8],
+    })
+  );
+}
+
+#[test]
+fn lsp_completions_auto_import_from_import_map_entry() {
+  let context = TestContextBuilder::new()
+    .use_http_server()
+    .use_temp_cwd()
+    .build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write(
+    "import_map.json",
+    r#"{
+      "imports": {
+        "mod1": "http://localhost:4545/subdir/mod1.ts"
+      }
+    }"#,
+  );
+  let mut client = context.new_lsp_command().build();
+  client.initialize(|builder| {
+    builder.set_import_map("import_map.json");
+  });
+  let file_uri = temp_dir.uri().join("file.ts").unwrap();
+  client.write_request(
+    "workspace/executeCommand",
+    json!({
+      "command": "deno.cache",
+      "arguments": [["http://localhost:4545/subdir/mod1.ts"], file_uri],
+    }),
   );
+  // the module isn't imported by any document
   client.did_open(json!({
     "textDocument": {
-      "uri": "file:///a/file.ts",
+      "uri": file_uri,
       "languageId": "typescript",
       "version": 1,
-      "text": "const someConst = 1; someConst"
+      "text": "returnsH",
     }
   }));
-  let res = client.write_request(
-    "textDocument/semanticTokens/full",
-    json!({
-      "textDocument": {
-        "uri": "file:///a/file.ts"
-      }
-    }),
-  );
+  let list =
+    client.get_completion_list(&file_uri, (0, 8), json!({ "triggerKind": 1 }));
+  let item = list
+    .items
+    .iter()
+    .find(|item| item.label == "returnsHi")
+    .unwrap();
+  let res = client.write_request("completionItem/resolve", item);
   assert_eq!(
-    res,
-    json!({
-      "data": [0, 6, 9, 7, 9, 0, 15, 9, 7, 8],
-    })
+    res["additionalTextEdits"],
+    json!([
+      {
+        "range": {
+          "start": { "line": 0, "character": 0 },
+          "end": { "line": 0, "character": 0 }
+        },
+        "newText": "import { returnsHi } from \"mod1\";\n\n"
+      }
+    ])
   );
+  client.shutdown();
 }
 
 #[test]
@@ -7255,6 +8120,91 @@ fn lsp_completions_npm() {
   client.shutdown();
 }
 
+#[test]
+fn lsp_hover_npm_package_metadata() {
+  let context = TestContextBuilder::new()
+    .use_http_server()
+    .use_temp_cwd()
+    .build();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": "file:///a/file.ts",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "import chalk from 'npm:chalk@5';\nconsole.log(chalk);\n",
+    }
+  }));
+  client.write_request(
+    "workspace/executeCommand",
+    json!({
+      "command": "deno.cache",
+      "arguments": [["npm:chalk@5"], "file:///a/file.ts"],
+    }),
+  );
+  let res = client.write_request(
+    "textDocument/hover",
+    json!({
+      "textDocument": { "uri": "file:///a/file.ts" },
+      "position": { "line": 0, "character": 22 }
+    }),
+  );
+  let value = res["contents"]["value"].as_str().unwrap();
+  assert!(
+    value.ends_with("\n\n---\n\n**chalk**\n\nTerminal string styling done right\n\n**Version**: `5`  \n**Latest**: `5.0.1`  \n**License**: MIT\n\n[https://www.npmjs.com/package/chalk](https://www.npmjs.com/package/chalk)"),
+    "{value}"
+  );
+  client.shutdown();
+}
+
+#[test]
+fn lsp_npm_definition_virtual_document() {
+  let context = TestContextBuilder::new()
+    .use_http_server()
+    .use_temp_cwd()
+    .build();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": "file:///a/file.ts",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "import chalk from 'npm:chalk@5';\nconsole.log(chalk.red);\n",
+    }
+  }));
+  client.write_request(
+    "workspace/executeCommand",
+    json!({
+      "command": "deno.cache",
+      "arguments": [["npm:chalk@5"], "file:///a/file.ts"],
+    }),
+  );
+  client.read_diagnostics();
+  let res = client.write_request(
+    "textDocument/definition",
+    json!({
+      "textDocument": { "uri": "file:///a/file.ts" },
+      "position": { "line": 1, "character": 19 }
+    }),
+  );
+  let location = &res.as_array().unwrap()[0];
+  let uri = location
+    .get("targetUri")
+    .or_else(|| location.get("uri"))
+    .and_then(|u| u.as_str())
+    .unwrap();
+  // there is no local node_modules, so the package is in the global cache
+  assert!(uri.starts_with("deno:/npm/"), "{uri}");
+  let res = client.write_request(
+    "deno/virtualTextDocument",
+    json!({ "textDocument": { "uri": uri } }),
+  );
+  assert!(res.as_str().unwrap().contains("red"));
+  client.shutdown();
+}
+
 #[test]
 fn lsp_npm_specifier_unopened_file() {
   let context = TestContextBuilder::new()
@@ -8060,6 +9010,142 @@ fn lsp_redirect_quick_fix() {
   client.shutdown();
 }
 
+#[test]
+fn lsp_diagnostics_graph_budget() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write(
+    "deno.json",
+    json!({
+      "graph": {
+        "budget": {
+          "maxDependencies": 0,
+          "banned": ["*/banned.ts"],
+        },
+      },
+    })
+    .to_string(),
+  );
+  temp_dir.write("banned.ts", "export const banned = 1;\n");
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  let diagnostics = client.did_open(json!({
+    "textDocument": {
+      "uri": temp_dir.uri().join("main.ts").unwrap(),
+      "languageId": "typescript",
+      "version": 1,
+      "text": "import { banned } from \"./banned.ts\";\n\nconsole.log(banned);\n",
+    },
+  }));
+  let range = lsp::Range {
+    start: lsp::Position {
+      line: 0,
+      character: 23,
+    },
+    end: lsp::Position {
+      line: 0,
+      character: 36,
+    },
+  };
+  assert_eq!(
+    diagnostics.messages_with_source("deno"),
+    lsp::PublishDiagnosticsParams {
+      uri: temp_dir.uri().join("main.ts").unwrap(),
+      diagnostics: vec![
+        lsp::Diagnostic {
+          range,
+          severity: Some(lsp::DiagnosticSeverity::ERROR),
+          code: Some(lsp::NumberOrString::String(
+            "banned-specifier".to_string()
+          )),
+          source: Some("deno".to_string()),
+          message: format!(
+            "\"{}\" is banned by \"*/banned.ts\" in the graph budget of the configuration file.",
+            temp_dir.uri().join("banned.ts").unwrap()
+          ),
+          ..Default::default()
+        },
+        lsp::Diagnostic {
+          range,
+          severity: Some(lsp::DiagnosticSeverity::ERROR),
+          code: Some(lsp::NumberOrString::String(
+            "graph-budget-exceeded".to_string()
+          )),
+          source: Some("deno".to_string()),
+          message: "The 1 dependencies of the module graph are more than the maximum of 0 in the graph budget of the configuration file. This import adds 1.".to_string(),
+          ..Default::default()
+        },
+      ],
+      version: Some(1),
+    }
+  );
+  client.shutdown();
+}
+
+#[test]
+fn lsp_diagnostics_npm_vulnerability() {
+  let context = TestContextBuilder::new()
+    .use_http_server()
+    .use_temp_cwd()
+    .build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write(
+    "main.ts",
+    "import { getValue } from \"npm:@denotest/esm-basic@1.0.0\";\n\nconsole.log(getValue());\n",
+  );
+  let mut client = context.new_lsp_command().build();
+  // the advisories which `deno audit` cached
+  client.deno_dir().write(
+    "npm_audit.json",
+    json!({
+      "packages": {
+        "@denotest/esm-basic@1.0.0": [{
+          "id": 1001,
+          "title": "Prototype pollution in @denotest/esm-basic",
+          "severity": "moderate",
+          "url": "https://github.com/advisories/GHSA-0000-0000-0001",
+          "vulnerableVersions": "<2.0.0",
+        }],
+      },
+    })
+    .to_string(),
+  );
+  client.initialize_default();
+  client.write_request(
+    "workspace/executeCommand",
+    json!({
+      "command": "deno.cache",
+      "arguments": [[], temp_dir.uri().join("main.ts").unwrap()],
+    }),
+  );
+  let diagnostics = client.did_open(json!({
+    "textDocument": {
+      "uri": temp_dir.uri().join("main.ts").unwrap(),
+      "languageId": "typescript",
+      "version": 1,
+      "text": temp_dir.read_to_string("main.ts"),
+    },
+  }));
+  assert_eq!(
+    json!(diagnostics.messages_with_source("deno")),
+    json!({
+      "uri": temp_dir.uri().join("main.ts").unwrap(),
+      "diagnostics": [{
+        "range": {
+          "start": { "line": 0, "character": 25 },
+          "end": { "line": 0, "character": 56 },
+        },
+        "severity": 2,
+        "code": "npm-vulnerability",
+        "source": "deno",
+        "message": "\"@denotest/esm-basic@1.0.0\" has a moderate severity vulnerability: Prototype pollution in @denotest/esm-basic (https://github.com/advisories/GHSA-0000-0000-0001)",
+      }],
+      "version": 1,
+    })
+  );
+  client.shutdown();
+}
+
 #[test]
 fn lsp_diagnostics_deprecated() {
   let context = TestContextBuilder::new().use_temp_cwd().build();
@@ -8114,6 +9200,139 @@ fn lsp_diagnostics_deprecated() {
   client.shutdown();
 }
 
+#[test]
+fn lsp_ts_plugins() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write(
+    "deno.json",
+    r#"{
+  "compilerOptions": {
+    "plugins": [{ "name": "todo-plugin", "message": "There is a TODO." }]
+  }
+}
+"#,
+  );
+  temp_dir.create_dir_all("node_modules/todo-plugin");
+  temp_dir.write(
+    "node_modules/todo-plugin/package.json",
+    r#"{ "name": "todo-plugin", "main": "./index.js" }"#,
+  );
+  temp_dir.write(
+    "node_modules/todo-plugin/index.js",
+    r#"const { findTodo } = require("./find.js");
+module.exports = function init({ typescript: ts }) {
+  return {
+    create(info) {
+      const proxy = Object.create(null);
+      for (const key of Object.keys(info.languageService)) {
+        proxy[key] = (...args) => info.languageService[key](...args);
+      }
+      proxy.getSemanticDiagnostics = (fileName) => {
+        const diagnostics = info.languageService.getSemanticDiagnostics(fileName);
+        const file = info.languageService.getProgram().getSourceFile(fileName);
+        const start = findTodo(file.text);
+        if (start < 0) {
+          return diagnostics;
+        }
+        return [...diagnostics, {
+          file,
+          start,
+          length: 4,
+          messageText: info.config.message,
+          category: ts.DiagnosticCategory.Warning,
+          code: 9999,
+        }];
+      };
+      return proxy;
+    },
+  };
+};
+"#,
+  );
+  temp_dir.write(
+    "node_modules/todo-plugin/find.js",
+    r#"exports.findTodo = (text) => text.indexOf("TODO");"#,
+  );
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  let diagnostics = client.did_open(json!({
+    "textDocument": {
+      "uri": temp_dir.uri().join("main.ts").unwrap(),
+      "languageId": "typescript",
+      "version": 1,
+      "text": "// TODO: remove\nconsole.log(1);\n",
+    },
+  }));
+  assert_eq!(
+    json!(diagnostics.all()),
+    json!([{
+      "range": {
+        "start": { "line": 0, "character": 3 },
+        "end": { "line": 0, "character": 7 },
+      },
+      "severity": 2,
+      "code": 9999,
+      "source": "deno-ts",
+      "message": "There is a TODO.",
+    }])
+  );
+  client.shutdown();
+}
+
+#[test]
+fn lsp_document_preprocessors() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write("b.ts", "export const b = \"b\";\n");
+  let mut client = context.new_lsp_command().build();
+  client.initialize_with_config(
+    |_| {},
+    json!({ "deno": {
+      "enable": true,
+      "documentPreprocessors": { "svelte": "scriptBlocks" },
+    } }),
+  );
+  let uri = temp_dir.uri().join("Counter.svelte").unwrap();
+  let diagnostics = client.did_open(json!({
+    "textDocument": {
+      "uri": uri,
+      "languageId": "svelte",
+      "version": 1,
+      "text": "<script lang=\"ts\">\n  import { b } from \"./b.ts\";\n  export let count: number = b;\n</script>\n\n<p>{count}</p>\n",
+    },
+  }));
+  assert_eq!(
+    json!(diagnostics.messages_with_source("deno-ts")),
+    json!({
+      "uri": uri,
+      "diagnostics": [{
+        "range": {
+          "start": { "line": 2, "character": 13 },
+          "end": { "line": 2, "character": 18 },
+        },
+        "severity": 1,
+        "code": 2322,
+        "source": "deno-ts",
+        "message": "Type 'string' is not assignable to type 'number'.",
+      }],
+      "version": 1,
+    })
+  );
+  let res = client.write_request(
+    "textDocument/definition",
+    json!({
+      "textDocument": { "uri": uri },
+      "position": { "line": 2, "character": 29 },
+    }),
+  );
+  assert_eq!(
+    res[0]["targetUri"],
+    json!(temp_dir.uri().join("b.ts").unwrap())
+  );
+  client.shutdown();
+}
+
 #[test]
 fn lsp_diagnostics_deno_types() {
   let context = TestContextBuilder::new().use_temp_cwd().build();
@@ -8351,6 +9570,52 @@ fn lsp_untitled_file_diagnostics() {
   client.shutdown();
 }
 
+#[test]
+fn lsp_untitled_file_relative_import() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write("mod.ts", "export const a: string = \"a\";\n");
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  // the untitled document is assumed to be in the root of the workspace
+  let diagnostics = client.did_open(json!({
+    "textDocument": {
+      "uri": "untitled:Untitled-1",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "import { a } from \"./mod.ts\";\nconst b: number = a;\nconsole.log(b);\n",
+    },
+  }));
+  assert_eq!(
+    json!(diagnostics.all_messages()),
+    json!([
+      {
+        "uri": "untitled:Untitled-1",
+        "diagnostics": [
+          {
+            "range": {
+              "start": {
+                "line": 1,
+                "character": 6,
+              },
+              "end": {
+                "line": 1,
+                "character": 7,
+              },
+            },
+            "severity": 1,
+            "code": 2322,
+            "source": "deno-ts",
+            "message": "Type 'string' is not assignable to type 'number'.",
+          },
+        ],
+        "version": 1,
+      },
+    ])
+  );
+  client.shutdown();
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PerformanceAverage {
@@ -8430,6 +9695,49 @@ fn lsp_performance() {
   client.shutdown();
 }
 
+#[test]
+fn lsp_performance_report() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": "file:///a/file.ts",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "console.log(Deno.args);\n"
+    }
+  }));
+  client.write_request(
+    "textDocument/hover",
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts"
+      },
+      "position": { "line": 0, "character": 19 }
+    }),
+  );
+  let res = client.write_request("deno/performanceReport", json!(null));
+  let hover = res["operations"]
+    .as_array()
+    .unwrap()
+    .iter()
+    .find(|p| p["name"] == "lsp.hover")
+    .unwrap();
+  assert_eq!(hover["count"], 1);
+  assert!(hover["p50"].as_f64().unwrap() <= hover["p95"].as_f64().unwrap());
+  assert!(res["tsc"]["requests"]
+    .as_array()
+    .unwrap()
+    .iter()
+    .any(|p| p["name"] == "tsc.request.getQuickInfoAtPosition"));
+  assert_eq!(res["tsc"]["pendingRequests"], 0);
+  assert_eq!(res["openDocuments"], json!({ "count": 1, "textSize": 24 }));
+  assert_eq!(res["documentCache"]["maxSize"], json!(null));
+  assert_eq!(res["documentCache"]["evictions"], 0);
+  client.shutdown();
+}
+
 #[test]
 fn lsp_format_no_changes() {
   let context = TestContextBuilder::new().use_temp_cwd().build();
@@ -8709,6 +10017,168 @@ fn lsp_format_json() {
   client.shutdown();
 }
 
+#[test]
+fn lsp_format_range_and_on_type() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": "file:///a/file.ts",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "const a=1;\nconst b=2;\nconst c=3;\n"
+    }
+  }));
+
+  let res = client.write_request(
+    "textDocument/rangeFormatting",
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts",
+      },
+      "range": {
+        "start": { "line": 1, "character": 0 },
+        "end": { "line": 1, "character": 10 }
+      },
+      "options": {
+        "tabSize": 2,
+        "insertSpaces": true
+      }
+    }),
+  );
+  assert_eq!(
+    res,
+    json!([
+      {
+        "range": {
+          "start": { "line": 1, "character": 7 },
+          "end": { "line": 1, "character": 7 }
+        },
+        "newText": " "
+      }, {
+        "range": {
+          "start": { "line": 1, "character": 8 },
+          "end": { "line": 1, "character": 8 }
+        },
+        "newText": " "
+      }
+    ])
+  );
+
+  let res = client.write_request(
+    "textDocument/onTypeFormatting",
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts",
+      },
+      "position": { "line": 2, "character": 10 },
+      "ch": ";",
+      "options": {
+        "tabSize": 2,
+        "insertSpaces": true
+      }
+    }),
+  );
+  assert_eq!(
+    res,
+    json!([
+      {
+        "range": {
+          "start": { "line": 2, "character": 7 },
+          "end": { "line": 2, "character": 7 }
+        },
+        "newText": " "
+      }, {
+        "range": {
+          "start": { "line": 2, "character": 8 },
+          "end": { "line": 2, "character": 8 }
+        },
+        "newText": " "
+      }
+    ])
+  );
+  client.shutdown();
+}
+
+#[test]
+fn lsp_format_range_syntax_error() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": "file:///a/file.ts",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "const a=1;\nconst b=2;\nconst c = {;\n"
+    }
+  }));
+
+  // the whole document is formatted, so a syntax error outside of the range
+  // means there are no edits, rather than edits of a partial parse
+  let res = client.write_request(
+    "textDocument/rangeFormatting",
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts",
+      },
+      "range": {
+        "start": { "line": 0, "character": 0 },
+        "end": { "line": 0, "character": 10 }
+      },
+      "options": {
+        "tabSize": 2,
+        "insertSpaces": true
+      }
+    }),
+  );
+  assert_eq!(res, json!(null));
+  client.shutdown();
+}
+
+#[test]
+fn lsp_code_actions_organize_imports() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": "file:///a/file.ts",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "import { b } from \"./b.ts\";\nimport { a } from \"./a.ts\";\nimport { c } from \"./c.ts\";\n\nconsole.log(a, b);\n"
+    }
+  }));
+  let res = client.write_request(
+    "textDocument/codeAction",
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts"
+      },
+      "range": {
+        "start": { "line": 0, "character": 0 },
+        "end": { "line": 0, "character": 0 }
+      },
+      "context": {
+        "diagnostics": [],
+        "only": ["source.organizeImports"]
+      }
+    }),
+  );
+  let actions = res.as_array().unwrap();
+  let action = actions
+    .iter()
+    .find(|action| action["kind"] == "source.organizeImports")
+    .unwrap();
+  assert_eq!(action["title"], "Organize imports");
+  assert!(!action["edit"]["changes"]["file:///a/file.ts"]
+    .as_array()
+    .unwrap()
+    .is_empty());
+  client.shutdown();
+}
+
 #[test]
 fn lsp_json_no_diagnostics() {
   let context = TestContextBuilder::new().use_temp_cwd().build();
@@ -8830,6 +10300,39 @@ fn lsp_format_markdown() {
   client.shutdown();
 }
 
+#[test]
+fn lsp_format_css() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let css_file = context.temp_dir().path().join("file.css");
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": css_file.uri_file(),
+      "languageId": "css",
+      "version": 1,
+      "text": "a{color:red}"
+    }
+  }));
+
+  let res = client.write_request(
+    "textDocument/formatting",
+    json!({
+      "textDocument": {
+        "uri": css_file.uri_file()
+      },
+      "options": {
+        "tabSize": 2,
+        "insertSpaces": true
+      }
+    }),
+  );
+
+  let edits = res.as_array().unwrap();
+  assert!(!edits.is_empty());
+  client.shutdown();
+}
+
 #[test]
 fn lsp_format_with_config() {
   let context = TestContextBuilder::new().use_temp_cwd().build();
@@ -9134,6 +10637,80 @@ fn lsp_completions_complete_function_calls() {
   client.shutdown();
 }
 
+#[test]
+fn lsp_workspace_symbol_include_dependencies() {
+  let context = TestContextBuilder::new()
+    .use_http_server()
+    .use_temp_cwd()
+    .build();
+  let temp_dir = context.temp_dir();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  let file_uri = temp_dir.uri().join("file.ts").unwrap();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": file_uri,
+      "languageId": "typescript",
+      "version": 1,
+      "text": "import { returnsHi } from \"http://localhost:4545/subdir/mod1.ts\";\nexport function returnsLocal() {\n  return returnsHi();\n}\n",
+    },
+  }));
+  client.write_request(
+    "workspace/executeCommand",
+    json!({
+      "command": "deno.cache",
+      "arguments": [[], file_uri],
+    }),
+  );
+  let symbols = |client: &mut LspClient| {
+    let res = client.write_request(
+      "workspace/symbol",
+      json!({
+        "query": "returns"
+      }),
+    );
+    res
+      .as_array()
+      .unwrap()
+      .iter()
+      .map(|symbol| {
+        (
+          symbol["name"].as_str().unwrap().to_string(),
+          symbol["location"]["uri"].as_str().unwrap().to_string(),
+        )
+      })
+      .collect::<Vec<_>>()
+  };
+  let mod1_uri = "deno:/http/localhost%3A4545/subdir/mod1.ts".to_string();
+  let mod2_uri = "deno:/http/localhost%3A4545/subdir/subdir2/mod2.ts";
+
+  // dependencies aren't included by default
+  let res = symbols(&mut client);
+  assert!(res.contains(&("returnsLocal".to_string(), file_uri.to_string())));
+  assert!(!res.iter().any(|(_, uri)| uri.starts_with("deno:/http/")));
+
+  client.change_configuration(json!({ "deno": {
+    "enable": true,
+    "workspaceSymbols": {
+      "includeDependencies": true,
+    },
+  } }));
+  let res = symbols(&mut client);
+  let local_index = res
+    .iter()
+    .position(|(name, _)| name == "returnsLocal")
+    .unwrap();
+  let dependency_index = res
+    .iter()
+    .position(|symbol| symbol == &("returnsHi".to_string(), mod1_uri.clone()))
+    .unwrap();
+  assert!(local_index < dependency_index);
+  assert!(res.contains(&("returnsFoo2".to_string(), mod1_uri.clone())));
+  // only direct dependencies are included
+  assert!(!res.iter().any(|(_, uri)| uri == mod2_uri));
+  client.shutdown();
+}
+
 #[test]
 fn lsp_workspace_symbol() {
   let context = TestContextBuilder::new().use_temp_cwd().build();
@@ -9896,6 +11473,316 @@ struct TestRunResponseParams {
   enqueued: Vec<EnqueuedTestModule>,
 }
 
+#[test]
+fn lsp_testing_api_code_lens_results() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  let contents = "Deno.test(\"test a\", () => {});\n";
+  temp_dir.write("./test.ts", contents);
+  temp_dir.write("./deno.jsonc", "{}");
+  let specifier = temp_dir.uri().join("test.ts").unwrap();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": specifier,
+      "languageId": "typescript",
+      "version": 1,
+      "text": contents,
+    }
+  }));
+  client.read_notification_with_method::<Value>("deno/testModule");
+  let get_test_lenses = |client: &mut LspClient| {
+    let res = client.write_request(
+      "textDocument/codeLens",
+      json!({ "textDocument": { "uri": specifier } }),
+    );
+    res
+      .as_array()
+      .unwrap()
+      .iter()
+      .filter_map(|lens| {
+        let command = lens.get("command")?;
+        let name = command["command"].as_str()?;
+        if name == "deno.client.test" || name == "deno.debugTest" {
+          Some((command["title"].as_str()?.to_string(), name.to_string()))
+        } else {
+          None
+        }
+      })
+      .collect::<Vec<_>>()
+  };
+  assert_eq!(
+    get_test_lenses(&mut client),
+    vec![
+      (
+        "▶\u{fe0e} Run Test".to_string(),
+        "deno.client.test".to_string()
+      ),
+      ("Debug Test".to_string(), "deno.debugTest".to_string()),
+    ]
+  );
+
+  client.write_request(
+    "deno/testRun",
+    json!({
+      "id": 1,
+      "kind": "run",
+    }),
+  );
+  loop {
+    let notification = client
+      .read_notification_with_method::<Value>("deno/testRunProgress")
+      .unwrap();
+    if notification["message"]["type"] == "end" {
+      break;
+    }
+  }
+  let lenses = get_test_lenses(&mut client);
+  assert_eq!(lenses.len(), 2);
+  assert!(
+    lenses[0].0.starts_with("\u{2714}\u{fe0e} Run Test ("),
+    "{lenses:?}"
+  );
+  assert_eq!(lenses[1].1, "deno.debugTest");
+  client.shutdown();
+}
+
+#[test]
+fn lsp_testing_api_run_on_save() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  let util_contents = "export const a = 1;\n";
+  let test_contents = "import { a } from \"./util.ts\";\nDeno.test(\"test a\", () => {\n  if (a !== 1) throw new Error();\n});\n";
+  temp_dir.write("./util.ts", util_contents);
+  temp_dir.write("./test.ts", test_contents);
+  temp_dir.write("./deno.jsonc", "{}");
+  let util_specifier = temp_dir.uri().join("util.ts").unwrap();
+  let test_specifier = temp_dir.uri().join("test.ts").unwrap();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.change_configuration(json!({
+    "deno": {
+      "enable": true,
+      "testing": {
+        "args": ["--allow-all", "--no-check"],
+        "runOnSave": true,
+      },
+    },
+  }));
+  client.did_open(json!({
+    "textDocument": {
+      "uri": test_specifier,
+      "languageId": "typescript",
+      "version": 1,
+      "text": test_contents,
+    }
+  }));
+  client.read_notification_with_method::<Value>("deno/testModule");
+  client.did_open(json!({
+    "textDocument": {
+      "uri": util_specifier,
+      "languageId": "typescript",
+      "version": 1,
+      "text": util_contents,
+    }
+  }));
+  client.did_save(json!({
+    "textDocument": { "uri": util_specifier },
+  }));
+  let notification = client
+    .read_notification_with_method::<Value>("deno/testRunStart")
+    .unwrap();
+  assert_eq!(notification["kind"], "run");
+  assert_eq!(
+    notification["enqueued"][0]["textDocument"]["uri"],
+    json!(test_specifier)
+  );
+  let id = notification["id"].clone();
+  loop {
+    let notification = client
+      .read_notification_with_method::<Value>("deno/testRunProgress")
+      .unwrap();
+    assert_eq!(notification["id"], id);
+    if notification["message"]["type"] == "end" {
+      break;
+    }
+  }
+  client.shutdown();
+}
+
+#[test]
+fn lsp_testing_api_coverage() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  let util_contents = "export function add(a: number, b: number) {\n  return a + b;\n}\n\nexport function sub(a: number, b: number) {\n  return a - b;\n}\n";
+  let test_contents = "import { add } from \"./util.ts\";\nDeno.test(\"add\", () => {\n  if (add(1, 2) !== 3) throw new Error();\n});\n";
+  temp_dir.write("./util.ts", util_contents);
+  temp_dir.write("./test.ts", test_contents);
+  temp_dir.write("./deno.jsonc", "{}");
+  let util_specifier = temp_dir.uri().join("util.ts").unwrap();
+  let test_specifier = temp_dir.uri().join("test.ts").unwrap();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": test_specifier,
+      "languageId": "typescript",
+      "version": 1,
+      "text": test_contents,
+    }
+  }));
+  client.read_notification_with_method::<Value>("deno/testModule");
+  client.did_open(json!({
+    "textDocument": {
+      "uri": util_specifier,
+      "languageId": "typescript",
+      "version": 1,
+      "text": util_contents,
+    }
+  }));
+  client.write_request(
+    "deno/testRun",
+    json!({
+      "id": 1,
+      "kind": "coverage",
+    }),
+  );
+  let notification = client
+    .read_notification_with_method::<Value>("deno/testCoverage")
+    .unwrap();
+  assert_eq!(notification["textDocument"]["uri"], json!(util_specifier));
+  let lines = notification["lines"].as_array().unwrap();
+  assert!(
+    lines.contains(&json!({ "line": 1, "hits": 1 })),
+    "{lines:?}"
+  );
+  assert!(
+    lines.contains(&json!({ "line": 5, "hits": 0 })),
+    "{lines:?}"
+  );
+
+  let res = client.write_request(
+    "workspace/executeCommand",
+    json!({
+      "command": "deno.coverage.show",
+    }),
+  );
+  assert_eq!(res, json!(false));
+  let notification = client
+    .read_notification_with_method::<Value>("deno/testCoverage")
+    .unwrap();
+  assert_eq!(notification["textDocument"]["uri"], json!(util_specifier));
+  assert_eq!(notification["lines"], json!([]));
+  client.shutdown();
+}
+
+#[test]
+fn lsp_testing_api_run_with_coverage() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  let util_contents = "export function add(a: number, b: number) {\n  return a + b;\n}\n\nexport function sub(a: number, b: number) {\n  return a - b;\n}\n";
+  let test_contents = "import { add } from \"./util.ts\";\nDeno.test(\"add\", () => {\n  if (add(1, 2) !== 3) throw new Error();\n});\n";
+  temp_dir.write("./util.ts", util_contents);
+  temp_dir.write("./test.ts", test_contents);
+  temp_dir.write("./deno.jsonc", "{}");
+  let util_specifier = temp_dir.uri().join("util.ts").unwrap();
+  let test_specifier = temp_dir.uri().join("test.ts").unwrap();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": test_specifier,
+      "languageId": "typescript",
+      "version": 1,
+      "text": test_contents,
+    }
+  }));
+  client.read_notification_with_method::<Value>("deno/testModule");
+  client.did_open(json!({
+    "textDocument": {
+      "uri": util_specifier,
+      "languageId": "typescript",
+      "version": 1,
+      "text": util_contents,
+    }
+  }));
+  client.write_request(
+    "deno/testRun",
+    json!({
+      "id": 1,
+      "kind": "run",
+      "coverage": true,
+    }),
+  );
+  let notification = client
+    .read_notification_with_method::<Value>("deno/testCoverage")
+    .unwrap();
+  assert_eq!(notification["textDocument"]["uri"], json!(util_specifier));
+  let lines = notification["lines"].as_array().unwrap();
+  assert!(
+    lines.contains(&json!({ "line": 1, "hits": 1 })),
+    "{lines:?}"
+  );
+  assert!(
+    lines.contains(&json!({ "line": 5, "hits": 0 })),
+    "{lines:?}"
+  );
+  client.shutdown();
+}
+
+#[test]
+fn lsp_testing_api_output_streams() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  let contents = "Deno.test(\"output\", () => {\n  console.log(\"to stdout\");\n  console.error(\"to stderr\");\n});\n";
+  temp_dir.write("./test.ts", contents);
+  temp_dir.write("./deno.jsonc", "{}");
+  let specifier = temp_dir.uri().join("test.ts").unwrap();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": specifier,
+      "languageId": "typescript",
+      "version": 1,
+      "text": contents,
+    }
+  }));
+  client.read_notification_with_method::<Value>("deno/testModule");
+  client.write_request(
+    "deno/testRun",
+    json!({
+      "id": 1,
+      "kind": "run",
+    }),
+  );
+  let mut stdout = String::new();
+  let mut stderr = String::new();
+  loop {
+    let notification = client
+      .read_notification_with_method::<Value>("deno/testRunProgress")
+      .unwrap();
+    let message = &notification["message"];
+    match message["type"].as_str().unwrap() {
+      "output" => {
+        let value = message["value"].as_str().unwrap();
+        match message["stream"].as_str().unwrap() {
+          "stdout" => stdout.push_str(value),
+          "stderr" => stderr.push_str(value),
+          stream => panic!("Unexpected stream: {stream}"),
+        }
+      }
+      "end" => break,
+      _ => {}
+    }
+  }
+  // strip the zero-width spaces which are used to synchronize the pipes
+  assert_eq!(stdout.replace('\u{200B}', ""), "to stdout\r\n");
+  assert_eq!(stderr.replace('\u{200B}', ""), "to stderr\r\n");
+  client.shutdown();
+}
+
 #[test]
 fn lsp_testing_api() {
   let context = TestContextBuilder::new().use_temp_cwd().build();
@@ -10022,6 +11909,7 @@ Deno.test({
       "message": {
         "type": "output",
         "value": notification_value,
+        "stream": "stdout",
         "test": {
           "textDocument": {
             "uri": specifier,
@@ -10482,6 +12370,75 @@ fn lsp_node_modules_dir() {
   client.shutdown();
 }
 
+#[test]
+fn lsp_node_modules_dir_workspace_member() {
+  let context = TestContextBuilder::new()
+    .use_http_server()
+    .use_temp_cwd()
+    .build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write(
+    "deno.json",
+    r#"{ "workspaces": ["member"], "nodeModulesDir": true, "lock": false }"#,
+  );
+  temp_dir.create_dir_all("member");
+  temp_dir.write(
+    "member/deno.json",
+    r#"{ "name": "@scope/member", "version": "1.0.0" }"#,
+  );
+
+  let mut client = context.new_lsp_command().build();
+  client.initialize(|builder| {
+    builder.set_config("./member/deno.json");
+  });
+  let file_uri = temp_dir.uri().join("member/file.ts").unwrap();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": file_uri,
+      "languageId": "typescript",
+      "version": 1,
+      "text": "import chalk from 'npm:chalk';\n\nconsole.log(chalk.green('a'));\n",
+    }
+  }));
+  client.write_request(
+    "workspace/executeCommand",
+    json!({
+      "command": "deno.cache",
+      "arguments": [["npm:chalk"], file_uri],
+    }),
+  );
+  let diagnostics = client.read_diagnostics();
+  assert_eq!(diagnostics.all().len(), 0, "{:#?}", diagnostics);
+
+  // the member uses the node_modules directory of the workspace root
+  assert!(temp_dir.path().join("node_modules/chalk").exists());
+  assert!(!temp_dir.path().join("member/node_modules").exists());
+
+  let res = client.write_request(
+    "textDocument/references",
+    json!({
+      "textDocument": {
+        "uri": file_uri,
+      },
+      "position": { "line": 0, "character": 7 }, // chalk
+      "context": {
+        "includeDeclaration": false
+      }
+    }),
+  );
+  let references = res.as_array().unwrap();
+  assert_eq!(references.len(), 2, "references: {:#?}", references);
+  let uri = references[1]["uri"].as_str().unwrap();
+  // canonicalize for mac
+  let path = temp_dir.path().join("node_modules").canonicalize();
+  assert_starts_with!(
+    uri,
+    ModuleSpecifier::from_file_path(&path).unwrap().as_str()
+  );
+
+  client.shutdown();
+}
+
 #[test]
 fn lsp_vendor_dir() {
   let context = TestContextBuilder::new()
@@ -89,6 +89,8 @@ macro_rules! command_step(
 // the test (ex. `lint_tests.rs`) and which is the implementation (ex. `lint.rs`)
 // when both are open, especially for two tabs in VS Code
 
+#[path = "audit_tests.rs"]
+mod audit;
 #[path = "bench_tests.rs"]
 mod bench;
 #[path = "bundle_tests.rs"]
@@ -127,6 +129,8 @@ mod jsr;
 mod jupyter;
 #[path = "lint_tests.rs"]
 mod lint;
+#[path = "lock_tests.rs"]
+mod lock;
 #[path = "lsp_tests.rs"]
 mod lsp;
 #[path = "node_compat_tests.rs"]
@@ -135,6 +139,10 @@ mod node_compat_tests;
 mod node_unit_tests;
 #[path = "npm_tests.rs"]
 mod npm;
+#[path = "outdated_tests.rs"]
+mod outdated;
+#[path = "pm_tests.rs"]
+mod pm;
 #[path = "publish_tests.rs"]
 mod publish;
 
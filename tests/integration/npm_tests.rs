@@ -1003,9 +1003,16 @@ fn ensure_registry_files_local() {
   }
 }
 
-itest!(bundle_errors {
+itest!(bundle_esm {
   args: "bundle --quiet npm/esm/main.js",
-  output_str: Some("error: npm specifiers have not yet been implemented for this subcommand (https://github.com/denoland/deno/issues/15960). Found: npm:/chalk@5.0.1\n"),
+  output: "npm/esm/bundle.out",
+  envs: env_vars_for_npm_tests(),
+  http_server: true,
+});
+
+itest!(bundle_cjs_errors {
+  args: "bundle --quiet npm/cjs_with_deps/main.js",
+  output_str: Some("error: [WILDCARD]CommonJS modules can't be bundled. Found: [WILDCARD]"),
   exit_code: 1,
   envs: env_vars_for_npm_tests(),
   http_server: true,
@@ -2748,3 +2755,144 @@ fn run_cjs_in_node_modules_folder() {
     .run()
     .assert_matches_text("hi\n");
 }
+
+#[test]
+fn node_modules_dir_allow_scripts() {
+  let test_context = TestContextBuilder::for_npm().use_temp_cwd().build();
+  let temp_dir = test_context.temp_dir();
+  temp_dir.write("deno.json", r#"{ "nodeModulesDir": true }"#);
+  temp_dir.write(
+    "main.ts",
+    "import { value } from 'npm:@denotest/install-scripts';\nconsole.log(value);\n",
+  );
+  let installed_path = temp_dir.path().join(
+    "node_modules/.deno/@denotest+install-scripts@1.0.0/node_modules/@denotest/install-scripts/installed.txt",
+  );
+
+  let output = test_context.new_command().args("cache main.ts").run();
+  output.assert_matches_text(concat!(
+    "[WILDCARD]Warning The \"postinstall\" script of npm:@denotest/install-scripts@1.0.0 was not run. ",
+    "Add \"npm:@denotest/install-scripts\" to \"allowScripts\" in the configuration file to run it.\n",
+  ));
+  assert!(!installed_path.exists());
+
+  temp_dir.write(
+    "deno.json",
+    r#"{ "nodeModulesDir": true, "allowScripts": ["npm:@denotest/install-scripts"] }"#,
+  );
+  let output = test_context.new_command().args("cache main.ts").run();
+  output.assert_matches_text(
+    "Running postinstall script of npm:@denotest/install-scripts@1.0.0: node install.mjs\n",
+  );
+  assert_eq!(installed_path.read_to_string(), "installed");
+
+  // the scripts only run once
+  let output = test_context.new_command().args("run main.ts").run();
+  output.assert_matches_text("5\n");
+}
+
+#[test]
+fn node_modules_dir_workspace() {
+  let test_context = TestContextBuilder::for_npm().use_temp_cwd().build();
+  let temp_dir = test_context.temp_dir();
+  temp_dir.write(
+    "deno.json",
+    r#"{ "workspaces": ["project-a", "project-b"], "nodeModulesDir": true }"#,
+  );
+  let project_a_dir = temp_dir.path().join("project-a");
+  project_a_dir.create_dir_all();
+  project_a_dir
+    .join("deno.json")
+    .write(r#"{ "name": "@scope/project-a", "version": "1.0.0" }"#);
+  project_a_dir.join("package.json").write_json(&json!({
+    "name": "project-a",
+    "version": "1.0.0",
+    "type": "module",
+    "scripts": {
+      "postinstall": "node setup.js"
+    },
+    "dependencies": {
+      "@denotest/esm-basic": "^1.0",
+      "project-b": "^1.0"
+    }
+  }));
+  let project_b_dir = temp_dir.path().join("project-b");
+  project_b_dir.create_dir_all();
+  project_b_dir
+    .join("deno.json")
+    .write(r#"{ "name": "@scope/project-b", "version": "1.0.0" }"#);
+  project_b_dir.join("package.json").write_json(&json!({
+    "name": "project-b",
+    "version": "1.0.0",
+    "dependencies": {
+      "@denotest/cjs-default-export": "^1.0"
+    }
+  }));
+  temp_dir.write(
+    "main.ts",
+    "import { getValue, setValue } from '@denotest/esm-basic';\nsetValue(5);\nconsole.log(getValue());\n",
+  );
+
+  let output = test_context.new_command().args("run main.ts").run();
+  output.assert_matches_text(concat!(
+    "[WILDCARD]Warning The \"postinstall\" script of the workspace member \"project-a\" was not run. ",
+    "Run it with `deno task postinstall` in [WILDCARD]project-a\n",
+    "5\n",
+  ));
+
+  // the dependencies of all members are installed once at the root, where
+  // the members are linked by their package name
+  let node_modules = temp_dir.path().join("node_modules");
+  assert!(node_modules.join("@denotest/esm-basic").exists());
+  assert!(node_modules.join("@denotest/cjs-default-export").exists());
+  assert!(node_modules.join("project-b/package.json").exists());
+  assert_eq!(
+    node_modules.join("project-a").canonicalize(),
+    project_a_dir.canonicalize()
+  );
+  assert!(!project_a_dir.join("node_modules").exists());
+  assert!(!project_b_dir.join("node_modules").exists());
+
+  // running from a member uses the node_modules directory of the root
+  let output = test_context
+    .new_command()
+    .current_dir(project_b_dir.as_path())
+    .args("run ../main.ts")
+    .run();
+  output.assert_matches_text("5\n");
+  assert!(!project_b_dir.join("node_modules").exists());
+}
+
+#[test]
+fn node_modules_dir_linked() {
+  let test_context = TestContextBuilder::for_npm().use_temp_cwd().build();
+  let temp_dir = test_context.temp_dir();
+  temp_dir.write(
+    "main.ts",
+    "import { getValue, setValue } from 'npm:@denotest/esm-basic';\nsetValue(5);\nconsole.log(getValue());\n",
+  );
+
+  let output = test_context
+    .new_command()
+    .args("run --node-modules-dir=linked main.ts")
+    .run();
+  output.assert_matches_text("[WILDCARD]5\n");
+
+  let local_file = temp_dir.path().join(
+    "node_modules/.deno/@denotest+esm-basic@1.0.0/node_modules/@denotest/esm-basic/main.mjs",
+  );
+  let global_file = test_context
+    .deno_dir()
+    .path()
+    .join("npm/localhost_4545/npm/registry/@denotest/esm-basic/1.0.0/main.mjs");
+  assert_eq!(local_file.read_to_string(), global_file.read_to_string());
+  // the file is a hard link to the global cache instead of a copy
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::MetadataExt;
+    assert_eq!(
+      std::fs::metadata(&local_file).unwrap().ino(),
+      std::fs::metadata(&global_file).unwrap().ino()
+    );
+  }
+}
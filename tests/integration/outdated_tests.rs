@@ -0,0 +1,108 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::serde_json::json;
+use test_util as util;
+use util::env_vars_for_jsr_tests;
+use util::env_vars_for_npm_tests;
+use util::TestContextBuilder;
+
+itest!(outdated {
+  args: "outdated --quiet",
+  output: "outdated/main.out",
+  envs: env_vars_for_jsr_tests()
+    .into_iter()
+    .chain(env_vars_for_npm_tests().into_iter())
+    .collect(),
+  http_server: true,
+  cwd: Some("outdated"),
+});
+
+itest!(outdated_json {
+  args: "outdated --quiet --json",
+  output: "outdated/main_json.out",
+  envs: env_vars_for_jsr_tests()
+    .into_iter()
+    .chain(env_vars_for_npm_tests().into_iter())
+    .collect(),
+  http_server: true,
+  cwd: Some("outdated"),
+});
+
+#[test]
+fn outdated_update() {
+  let context = TestContextBuilder::new()
+    .use_temp_cwd()
+    .use_http_server()
+    .add_npm_env_vars()
+    .add_jsr_env_vars()
+    .build();
+  let temp_dir = context.temp_dir().path();
+  let deno_json = temp_dir.join("deno.json");
+  deno_json.write_json(&json!({
+    "imports": {
+      "add": "jsr:@denotest/add@1",
+      "bin": "npm:@denotest/bin@^0.5.0",
+      "no_module_graph": "jsr:@denotest/no_module_graph@^0.1.0",
+    }
+  }));
+  let lockfile = temp_dir.join("deno.lock");
+  lockfile.write_json(&json!({
+    "version": "3",
+    "packages": {
+      "specifiers": {
+        "jsr:@denotest/add@1": "jsr:@denotest/add@1.0.0",
+        "jsr:@denotest/no_module_graph@^0.1.0": "jsr:@denotest/no_module_graph@0.1.0"
+      },
+      "jsr": {
+        "@denotest/add@1.0.0": {},
+        "@denotest/no_module_graph@0.1.0": {}
+      }
+    },
+    "remote": {},
+    "workspace": {
+      "dependencies": [
+        "jsr:@denotest/add@1",
+        "jsr:@denotest/no_module_graph@^0.1.0",
+        "npm:@denotest/bin@^0.5.0"
+      ]
+    }
+  }));
+
+  let output = context.new_command().args("outdated --update").run();
+  output.assert_exit_code(0);
+  output.assert_matches_text(
+    "[WILDCARD]Package                        Current  Wanted  Latest
+jsr:@denotest/no_module_graph  0.1.0    0.1.1   0.2.0
+npm:@denotest/bin              -        0.5.0   1.0.0
+Updated jsr:@denotest/no_module_graph ^0.1.0 -> ^0.1.1
+",
+  );
+  assert_eq!(
+    deno_json.read_json_value(),
+    json!({
+      "imports": {
+        "add": "jsr:@denotest/add@1",
+        "bin": "npm:@denotest/bin@^0.5.0",
+        "no_module_graph": "jsr:@denotest/no_module_graph@^0.1.1",
+      }
+    })
+  );
+  let lockfile = lockfile.read_json_value();
+  assert_eq!(
+    lockfile["packages"]["specifiers"],
+    json!({
+      "jsr:@denotest/add@1": "jsr:@denotest/add@1.0.0",
+      "jsr:@denotest/no_module_graph@^0.1.1": "jsr:@denotest/no_module_graph@0.1.1"
+    })
+  );
+
+  // everything which the version requirements allow is up to date now
+  let output = context.new_command().args("outdated --quiet").run();
+  output.assert_exit_code(0);
+  output.assert_matches_text(
+    "Package                        Current  Wanted  Latest
+jsr:@denotest/no_module_graph  0.1.1    0.1.1   0.2.0
+npm:@denotest/bin              -        0.5.0   1.0.0
+",
+  );
+}
@@ -0,0 +1,77 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::serde_json::json;
+use test_util::TestContextBuilder;
+
+#[test]
+fn add_and_remove() {
+  let context = TestContextBuilder::new()
+    .use_temp_cwd()
+    .use_http_server()
+    .add_npm_env_vars()
+    .add_jsr_env_vars()
+    .build();
+  let temp_dir = context.temp_dir().path();
+  let deno_json = temp_dir.join("deno.json");
+  deno_json.write_json(&json!({
+    "imports": {
+      "utils": "./utils.ts",
+    }
+  }));
+
+  let output = context
+    .new_command()
+    .args("add jsr:@denotest/add npm:@denotest/bin@^0.5")
+    .run();
+  output.assert_exit_code(0);
+  output.assert_matches_text(
+    "[WILDCARD]Added jsr:@denotest/add@^1.0.0
+Added npm:@denotest/bin@^0.5.0
+",
+  );
+  assert_eq!(
+    deno_json.read_json_value(),
+    json!({
+      "imports": {
+        "utils": "./utils.ts",
+        "@denotest/add": "jsr:@denotest/add@^1.0.0",
+        "@denotest/bin": "npm:@denotest/bin@^0.5.0",
+      }
+    })
+  );
+  let lockfile = temp_dir.join("deno.lock");
+  assert_eq!(
+    lockfile.read_json_value()["packages"]["specifiers"],
+    json!({
+      "jsr:@denotest/add@^1.0.0": "jsr:@denotest/add@1.0.0",
+      "npm:@denotest/bin@^0.5.0": "npm:@denotest/bin@0.5.0"
+    })
+  );
+
+  let output = context.new_command().args("add express").run();
+  output.assert_exit_code(1);
+  output.assert_matches_text(
+    "error: \"express\" is missing a prefix. Did you mean \"jsr:express\" or \"npm:express\"?\n",
+  );
+
+  let output = context
+    .new_command()
+    .args("remove @denotest/add npm:@denotest/bin")
+    .run();
+  output.assert_exit_code(0);
+  output.assert_matches_text(
+    "Removed jsr:@denotest/add@^1.0.0
+Removed npm:@denotest/bin@^0.5.0
+",
+  );
+  assert_eq!(
+    deno_json.read_json_value(),
+    json!({
+      "imports": {
+        "utils": "./utils.ts",
+      }
+    })
+  );
+  let specifiers = &lockfile.read_json_value()["packages"]["specifiers"];
+  assert!(specifiers.as_object().map(|s| s.is_empty()).unwrap_or(true));
+}
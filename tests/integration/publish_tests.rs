@@ -152,6 +152,14 @@ itest!(successful {
   http_server: true,
 });
 
+itest!(successful_registry {
+  args: "publish --token 'sadfasdf' --registry=http://127.0.0.1:4250",
+  output: "publish/successful.out",
+  cwd: Some("publish/successful"),
+  envs: vec![("NO_COLOR".to_string(), "1".to_string())],
+  http_server: true,
+});
+
 itest!(node_specifier {
   args: "publish --token 'sadfasdf'",
   output: "publish/node_specifier.out",
@@ -269,6 +277,63 @@ fn includes_directories() {
   assert_not_contains!(output, "ignored.ts");
 }
 
+#[test]
+fn bump_version() {
+  let context = publish_context_builder().build();
+  let temp_dir = context.temp_dir().path();
+  temp_dir.join("deno.json").write_json(&json!({
+    "name": "@foo/bar",
+    "version": "1.0.0",
+    "exports": "./main.ts",
+  }));
+  temp_dir.join("main.ts").write("");
+
+  let output = context
+    .new_command()
+    .arg("publish")
+    .arg("--bump=minor")
+    .arg("--token")
+    .arg("sadfasdf")
+    .run();
+  output.assert_exit_code(0);
+  let output = output.combined_output();
+  assert_contains!(output, "Bump @foo/bar from 1.0.0 to 1.1.0");
+  assert_contains!(output, "@foo/bar@1.1.0");
+  assert_contains!(
+    temp_dir.join("deno.json").read_to_string(),
+    r#""version": "1.1.0""#
+  );
+}
+
+#[test]
+fn bump_version_already_published() {
+  let context = publish_context_builder().build();
+  let temp_dir = context.temp_dir().path();
+  temp_dir.join("deno.json").write_json(&json!({
+    "name": "@denotest/add",
+    "version": "0.9.0",
+    "exports": "./main.ts",
+  }));
+  temp_dir.join("main.ts").write("");
+
+  let output = context
+    .new_command()
+    .arg("publish")
+    .arg("--bump=major")
+    .arg("--token")
+    .arg("sadfasdf")
+    .run();
+  output.assert_exit_code(1);
+  assert_contains!(
+    output.combined_output(),
+    "Failed bumping @denotest/add to 1.0.0, because the version is already published."
+  );
+  assert_contains!(
+    temp_dir.join("deno.json").read_to_string(),
+    r#""version": "0.9.0""#
+  );
+}
+
 fn publish_context_builder() -> TestContextBuilder {
   TestContextBuilder::new()
     .use_http_server()
@@ -50,6 +50,22 @@ fn pty_null() {
   });
 }
 
+#[test]
+fn pty_editor_mode() {
+  util::with_pty(&["repl"], |mut console| {
+    console.write_line(".editor");
+    console
+      .expect("// Entering editor mode (ctrl+d to finish, ctrl+c to cancel)");
+    console.write_line("const a = 1;");
+    console.write_line("a + 1");
+    // ctrl+d
+    console.write_raw("\u{4}");
+    console.expect("2");
+    console.write_line("a");
+    console.expect("1");
+  });
+}
+
 #[test]
 fn pty_unpaired_braces() {
   for right_brace in &[")", "]", "}"] {
@@ -307,6 +323,43 @@ fn let_redeclaration() {
   });
 }
 
+#[test]
+fn repl_save_load_and_transcript() {
+  let context = TestContextBuilder::default().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write("script.ts", "const b = a * 10;\nb");
+  context
+    .new_command()
+    .args_vec(["repl"])
+    .with_pty(|mut console| {
+      console.write_line(".transcript transcript.txt");
+      console.expect("Recording transcript to \"transcript.txt\"");
+      console.write_line("const a = 2;");
+      console.expect("undefined");
+      console.write_line("throw new Error('boom')");
+      console.expect("Uncaught Error: boom");
+      console.write_line(".load script.ts");
+      console.expect("20");
+      console.write_line(".save session.ts");
+      console.expect("Session saved to \"session.ts\"");
+      console.write_line(".transcript");
+      console.expect("Stopped recording transcript to \"transcript.txt\"");
+      console.write_line(".save");
+      console.expect("usage: .save <file>");
+    });
+  assert_eq!(
+    temp_dir.read_to_string("session.ts"),
+    "const a = 2;\nconst b = a * 10;\nb\n"
+  );
+  let transcript = temp_dir.read_to_string("transcript.txt");
+  assert_contains!(transcript, "> const a = 2;\nundefined\n");
+  assert_contains!(
+    transcript,
+    "> throw new Error('boom')\nUncaught Error: boom"
+  );
+  assert_contains!(transcript, "> const b = a * 10;\n... b\n20\n");
+}
+
 #[test]
 fn repl_cwd() {
   let context = TestContextBuilder::default().use_temp_cwd().build();
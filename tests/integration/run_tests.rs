@@ -749,6 +749,100 @@ fn permissions_cache() {
     });
 }
 
+#[test]
+fn permissions_file() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write("permissions.json", r#"{ "read": ["./data.txt"] }"#);
+  temp_dir.write("data.txt", "Hello");
+  temp_dir.write(
+    "main.ts",
+    r#"console.log(Deno.readTextFileSync("./data.txt"));
+await Deno.permissions.request({ name: "env", variable: "AWS_REGION" });
+await Deno.permissions.request({ name: "env", variable: "AWS_PROFILE" });
+const status = await Deno.permissions.query({ name: "env", variable: "AWS_KEY" });
+console.log(status.state);
+"#,
+  );
+  context
+    .new_command()
+    .args_vec(["run", "--quiet", "--permissions-file=permissions.json", "main.ts"])
+    .with_pty(|mut console| {
+      console.expect("Hello");
+      console.expect(concat!(
+        "┌ ⚠️  Deno requests env access to \"AWS_REGION\".\r\n",
+        "├ Requested by `Deno.permissions.request()` API.\r\n",
+        "├ Run again with --allow-env to bypass this prompt.\r\n",
+        "└ Allow? [y/n/A] (y = yes, allow; n = no, deny; A = allow all env permissions)",
+      ));
+      console.write_line_raw("y");
+      console.expect(concat!(
+        "┌ ⚠️  Deno requests env access to \"AWS_PROFILE\".\r\n",
+        "├ Requested by `Deno.permissions.request()` API.\r\n",
+        "├ Run again with --allow-env to bypass this prompt.\r\n",
+        "└ Allow? [y/n/g/A] (y = yes, allow; n = no, deny; g = allow all of \"AWS_*\"; A = allow all env permissions)",
+      ));
+      console.write_line_raw("g");
+      console.expect("✅ Granted env access to \"AWS_*\".");
+      console.expect("granted");
+    });
+  assert_eq!(
+    temp_dir.read_to_string("permissions.json"),
+    concat!(
+      "{\n",
+      "  \"env\": [\n",
+      "    \"AWS_REGION\",\n",
+      "    \"AWS_*\"\n",
+      "  ],\n",
+      "  \"read\": [\n",
+      "    \"./data.txt\"\n",
+      "  ]\n",
+      "}\n",
+    )
+  );
+
+  // the saved permissions are granted in the next runs
+  temp_dir.write("main.ts", r#"console.log(Deno.env.get("AWS_PROFILE"));"#);
+  context
+    .new_command()
+    .args("run --quiet --no-prompt --permissions-file=permissions.json main.ts")
+    .env("AWS_PROFILE", "default")
+    .run()
+    .assert_matches_text("default\n");
+}
+
+#[test]
+fn permission_log() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write("data.txt", "Hello");
+  temp_dir.write(
+    "main.ts",
+    r#"console.log(Deno.readTextFileSync("./data.txt"));
+try {
+  Deno.env.get("SECRET");
+} catch {
+  console.log("denied");
+}
+"#,
+  );
+  context
+    .new_command()
+    .args("run --quiet --no-prompt --allow-read --permission-log=permissions.jsonl main.ts")
+    .run()
+    .assert_matches_text("Hello\ndenied\n");
+  let log = temp_dir.read_to_string("permissions.jsonl");
+  let lines = log.lines().collect::<Vec<_>>();
+  assert_eq!(lines.len(), 2, "{log}");
+  assert_contains!(lines[0], r#""permission":"read","resource":""#);
+  assert_contains!(
+    lines[0],
+    r#"data.txt","apiName":"Deno.readFileSync()","granted":true,"prompted":false}"#
+  );
+  assert_contains!(lines[1], r#""permission":"env","resource":"SECRET","#);
+  assert_contains!(lines[1], r#""granted":false,"prompted":false}"#);
+}
+
 itest!(env_file {
   args: "run --env=env --allow-env run/env_file.ts",
   output: "run/env_file.out",
@@ -759,6 +853,11 @@ itest!(env_file_missing {
   output: "run/env_file_missing.out",
 });
 
+itest!(env_file_multiple {
+  args: "run --env-file=env --env-file=env_override --allow-env run/env_file_override.ts",
+  output: "run/env_file_override.out",
+});
+
 itest!(_091_use_define_for_class_fields {
   args: "run --check run/091_use_define_for_class_fields.ts",
   output: "run/091_use_define_for_class_fields.ts.out",
@@ -2995,12 +3094,14 @@ mod permissions {
           "└ Allow? [y/n/A] (y = yes, allow; n = no, deny; A = allow all read permissions)",
         ));
         console.write_line_raw("y");
+        // "bar" is in the same directory as "foo"
         console.expect(concat!(
           "┌ ⚠️  Deno requests read access to \"bar\".\r\n",
           "├ Requested by `Deno.permissions.request()` API.\r\n",
           "├ Run again with --allow-read to bypass this prompt.\r\n",
-          "└ Allow? [y/n/A] (y = yes, allow; n = no, deny; A = allow all read permissions)",
+          "└ Allow? [y/n/g/A] (y = yes, allow; n = no, deny; g = allow all of \"",
         ));
+        console.expect("\"; A = allow all read permissions)");
         console.write_line_raw("n");
         console.expect("granted");
         console.expect("prompt");
@@ -3021,12 +3122,14 @@ mod permissions {
           "└ Allow? [y/n/A] (y = yes, allow; n = no, deny; A = allow all read permissions)",
         ));
         console.write_line_raw("y");
+        // "bar" is in the same directory as "foo"
         console.expect(concat!(
           "┌ ⚠️  Deno requests read access to \"bar\".\r\n",
           "├ Requested by `Deno.permissions.request()` API.\r\n",
           "├ Run again with --allow-read to bypass this prompt.\r\n",
-          "└ Allow? [y/n/A] (y = yes, allow; n = no, deny; A = allow all read permissions)",
+          "└ Allow? [y/n/g/A] (y = yes, allow; n = no, deny; g = allow all of \"",
         ));
+        console.expect("\"; A = allow all read permissions)");
         console.write_line_raw("n");
         console.expect("granted");
         console.expect("prompt");
@@ -4276,6 +4379,61 @@ async fn http2_request_url() {
   child.wait().unwrap();
 }
 
+#[tokio::test]
+async fn serve_dev_error_overlay() {
+  let temp_dir = TempDir::new();
+  temp_dir.write(
+    "main.ts",
+    "export default {\n  fetch(_request: Request): Response {\n    throw new Error(\"boom\");\n  },\n};\n",
+  );
+  let mut child = util::deno_cmd()
+    .current_dir(temp_dir.path())
+    .arg("serve")
+    .arg("--dev")
+    .arg("--allow-net")
+    .arg("--allow-read")
+    .arg("--port")
+    .arg("4561")
+    .arg("--editor-url")
+    .arg("editor://open?file={path}&line={line}")
+    .arg("main.ts")
+    .stdout_piped()
+    .stderr(Stdio::null())
+    .spawn()
+    .unwrap();
+  let stdout = child.stdout.as_mut().unwrap();
+  let mut output = String::new();
+  let mut buffer = [0; 1024];
+  while !output.contains("Listening on") {
+    let read = stdout.read(&mut buffer).unwrap();
+    assert!(read > 0);
+    output.push_str(std::str::from_utf8(&buffer[..read]).unwrap());
+  }
+
+  let client = reqwest::Client::new();
+  let res = client
+    .get("http://127.0.0.1:4561/")
+    .header("accept", "text/html")
+    .send()
+    .await
+    .unwrap();
+  assert_eq!(res.status(), 500);
+  let body = res.text().await.unwrap();
+  assert_contains!(body, "<h1>Error: boom</h1>");
+  // the code frame of the line which threw
+  assert_contains!(body, "3 |     throw new Error(&quot;boom&quot;);");
+  assert_contains!(body, "editor://open?file=");
+  assert_contains!(body, "main.ts&amp;line=3");
+
+  let res = client.get("http://127.0.0.1:4561/").send().await.unwrap();
+  assert_eq!(res.status(), 500);
+  let body = res.text().await.unwrap();
+  assert!(body.starts_with("Error: boom\n    at Object.fetch (file://"));
+
+  child.kill().unwrap();
+  child.wait().unwrap();
+}
+
 #[cfg(not(windows))]
 #[test]
 fn set_raw_should_not_panic_on_no_tty() {
@@ -293,6 +293,70 @@ itest!(task_pre_only {
   envs: vec![("NO_COLOR".to_string(), "1".to_string())],
 });
 
+itest!(task_depends_on {
+  args: "task build",
+  cwd: Some("task/deno_json_depends_on/"),
+  output: "task/deno_json_depends_on/build.out",
+  envs: vec![("NO_COLOR".to_string(), "1".to_string())],
+  exit_code: 0,
+});
+
+itest!(task_depends_on_failure {
+  args: "task after_fail",
+  cwd: Some("task/deno_json_depends_on/"),
+  output: "task/deno_json_depends_on/fail.out",
+  envs: vec![("NO_COLOR".to_string(), "1".to_string())],
+  exit_code: 3,
+});
+
+itest!(task_depends_on_cycle {
+  args: "task cycle_a",
+  cwd: Some("task/deno_json_depends_on/"),
+  output: "task/deno_json_depends_on/cycle.out",
+  envs: vec![("NO_COLOR".to_string(), "1".to_string())],
+  exit_code: 1,
+});
+
+itest!(task_depends_on_empty_command {
+  args: "task empty",
+  cwd: Some("task/deno_json_depends_on/"),
+  output: "task/deno_json_depends_on/empty.out",
+  envs: vec![("NO_COLOR".to_string(), "1".to_string())],
+  exit_code: 1,
+});
+
+itest!(task_env_file {
+  args: "task --env-file echo",
+  cwd: Some("task/env_file/"),
+  output: "task/env_file/task.out",
+  envs: vec![("NO_COLOR".to_string(), "1".to_string())],
+  exit_code: 0,
+});
+
+itest!(task_recursive {
+  args: "task --recursive build",
+  cwd: Some("task/workspace/"),
+  output: "task/workspace/recursive.out",
+  envs: vec![("NO_COLOR".to_string(), "1".to_string())],
+  exit_code: 0,
+});
+
+itest!(task_filter {
+  args: "task --filter packages/a build",
+  cwd: Some("task/workspace/"),
+  output: "task/workspace/filter.out",
+  envs: vec![("NO_COLOR".to_string(), "1".to_string())],
+  exit_code: 0,
+});
+
+itest!(task_filter_not_found {
+  args: "task --filter @scope/c build",
+  cwd: Some("task/workspace/"),
+  output: "task/workspace/filter_not_found.out",
+  envs: vec![("NO_COLOR".to_string(), "1".to_string())],
+  exit_code: 1,
+});
+
 itest!(task_deno_no_pre_post {
   args: "task test",
   cwd: Some("task/deno_json_pre_post/"),
@@ -355,3 +419,62 @@ fn task_byonm() {
 "#,
     );
 }
+
+#[test]
+fn task_shell_syntax() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write(
+    "deno.json",
+    r#"{
+  "tasks": {
+    "shell": "export GREETING=$(echo hello) && (echo $GREETING && exit 1 || echo recovered) && echo ~/dir '~'"
+  }
+}"#,
+  );
+  context
+    .new_command()
+    .args("task -q shell")
+    .env("HOME", "/home/deno")
+    .env("USERPROFILE", "/home/deno")
+    .run()
+    .assert_matches_text("hello\nrecovered\n/home/deno/dir ~\n");
+}
+
+#[test]
+fn task_permission_set() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write(
+    "deno.json",
+    r#"{
+  "permissions": {
+    "serve": { "read": ["./data.txt"], "env": ["PORT"] }
+  },
+  "tasks": {
+    "start": "deno run --no-prompt --permission-set=serve main.ts"
+  }
+}"#,
+  );
+  temp_dir.write("data.txt", "Hello");
+  temp_dir.write(
+    "main.ts",
+    r#"console.log(Deno.readTextFileSync("./data.txt"), Deno.env.get("PORT"));"#,
+  );
+  context
+    .new_command()
+    .args("task -q start")
+    .env("PORT", "8000")
+    .run()
+    .assert_matches_text("Hello 8000\n");
+  context
+    .new_command()
+    .args("run --permission-set=dev main.ts")
+    .run()
+    .assert_matches_text(concat!(
+      "error: Invalid permissions in '[WILDCARD]deno.json'\n\n",
+      "Caused by:\n",
+      "    Unknown permission set \"dev\". Use one of \"serve\".\n",
+    ))
+    .assert_exit_code(1);
+}
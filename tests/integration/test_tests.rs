@@ -112,13 +112,13 @@ itest!(parallel_flag_with_env_variable {
 itest!(jobs_flag {
   args: "test test/short-pass.ts --jobs",
   exit_code: 0,
-  output: "test/short-pass-jobs-flag-warning.out",
+  output: "test/short-pass.out",
 });
 
 itest!(jobs_flag_with_numeric_value {
   args: "test test/short-pass.ts --jobs=2",
   exit_code: 0,
-  output: "test/short-pass-jobs-flag-warning.out",
+  output: "test/short-pass.out",
 });
 
 itest!(load_unload {
@@ -187,6 +187,26 @@ itest!(fail_fast {
   output: "test/fail_fast.out",
 });
 
+itest!(retry {
+  args: "test --retry=1 test/retry.ts",
+  exit_code: 1,
+  output: "test/retry.out",
+});
+
+itest!(retry_junit {
+  args: "test --retry=1 --reporter=junit test/retry.ts",
+  exit_code: 1,
+  output: "test/retry.junit.out",
+});
+
+itest!(retry_invalid {
+  args: "test test/retry_invalid.ts",
+  exit_code: 1,
+  output_str: Some(
+    "[WILDCARD]The 'retry' option must be a non-negative integer[WILDCARD]"
+  ),
+});
+
 itest!(only {
   args: "test test/only.ts",
   exit_code: 1,
@@ -539,6 +559,170 @@ itest!(parallel_output {
   exit_code: 1,
 });
 
+#[test]
+fn changed_flag() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write("dep.ts", "export const value = 1;\n");
+  temp_dir.write(
+    "a_test.ts",
+    "import { value } from './dep.ts';\nDeno.test('a', () => {});\n",
+  );
+  temp_dir.write("b_test.ts", "Deno.test('b', () => {});\n");
+  let git = |args: &str| {
+    let status = std::process::Command::new("git")
+      .args(args.split(' '))
+      .current_dir(temp_dir.path())
+      .status()
+      .unwrap();
+    assert!(status.success());
+  };
+  git("init -q");
+  git("add .");
+  git("-c user.name=deno -c user.email=deno@example.com commit -q -m init");
+
+  let output = context.new_command().args("test --changed").run();
+  output.assert_exit_code(0);
+  assert_contains!(
+    output.combined_output(),
+    "No test modules are affected by the changes since 'HEAD'"
+  );
+
+  temp_dir.write("dep.ts", "export const value = 2;\n");
+  temp_dir.write("c_test.ts", "Deno.test('c', () => {});\n");
+  let output = context.new_command().args("test --changed=HEAD").run();
+  output.assert_exit_code(0);
+  let output_text = output.combined_output();
+  assert_contains!(output_text, "a_test.ts");
+  assert_contains!(output_text, "c_test.ts");
+  assert_not_contains!(output_text, "b_test.ts");
+  assert_contains!(output_text, "ok | 2 passed | 0 failed");
+}
+
+#[test]
+fn snapshots() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  let test_file = |value: &str| {
+    format!(
+      r#"Deno.test("object", async (t) => {{
+  t.assertSnapshot({{ b: [1, 2], a: "{value}" }});
+  await t.step("step", (t) => {{
+    t.assertSnapshot("in step");
+  }});
+}});
+"#
+    )
+  };
+  temp_dir.write("a_test.ts", test_file("x"));
+
+  let output = context.new_command().args("test a_test.ts").run();
+  output.assert_exit_code(70);
+  assert_contains!(
+    output.combined_output(),
+    "Unstable API 't.assertSnapshot'. The `--unstable-test-snapshot` flag must be provided."
+  );
+
+  temp_dir.write("deno.json", r#"{ "unstable": ["test-snapshot"] }"#);
+  let output = context.new_command().args("test a_test.ts").run();
+  output.assert_exit_code(1);
+  assert_contains!(output.combined_output(), "Missing snapshot \"object 1\"");
+  assert_contains!(output.combined_output(), "deno test --update-snapshots");
+
+  let output = context
+    .new_command()
+    .args("test --update-snapshots a_test.ts")
+    .run();
+  output.assert_exit_code(0);
+  assert_contains!(
+    output.combined_output(),
+    "Wrote 2 snapshots to ./__snapshots__/a_test.ts.snap"
+  );
+  temp_dir
+    .path()
+    .join("__snapshots__/a_test.ts.snap")
+    .assert_matches_text(
+      r#"export const snapshot = {};
+
+snapshot[`object 1`] = `
+{
+  a: "x",
+  b: [
+    1,
+    2,
+  ],
+}
+`;
+
+snapshot[`object > step 1`] = `"in step"`;
+"#,
+    );
+
+  let output = context.new_command().args("test a_test.ts").run();
+  output.assert_exit_code(0);
+  output.skip_output_check();
+
+  temp_dir.write("a_test.ts", test_file("y"));
+  let output = context
+    .new_command()
+    .env("NO_COLOR", "1")
+    .args("test a_test.ts")
+    .run();
+  output.assert_exit_code(1);
+  assert_contains!(
+    output.combined_output(),
+    "Snapshot \"object 1\" does not match"
+  );
+  assert_contains!(output.combined_output(), "-  a: \"x\",");
+  assert_contains!(output.combined_output(), "+  a: \"y\",");
+}
+
+#[test]
+fn jobs_output_is_not_interleaved() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  for name in ["a", "b", "c"] {
+    temp_dir.write(
+      format!("{name}_test.ts"),
+      format!(
+        r#"for (let i = 0; i < 20; i++) {{
+  Deno.test("{name} " + i, async () => {{
+    console.log("start {name} " + i);
+    await new Promise((resolve) => setTimeout(resolve, 1));
+    console.log("end {name} " + i);
+  }});
+}}
+"#
+      ),
+    );
+  }
+  let output = context
+    .new_command()
+    .env("NO_COLOR", "1")
+    .args("test --jobs=3 a_test.ts b_test.ts c_test.ts")
+    .run();
+  output.assert_exit_code(0);
+  let text = output.combined_output();
+  let lines = text.lines().collect::<Vec<_>>();
+  let mut results = 0;
+  for (i, line) in lines.iter().enumerate() {
+    let Some(rest) = line.strip_prefix("./") else {
+      continue;
+    };
+    let (module, name) = rest.split_once("_test.ts => ").unwrap();
+    let (name, _) = name.split_once(" ...").unwrap();
+    assert_eq!(name.split(' ').next().unwrap(), module);
+    // every result follows the complete output of its own test
+    assert_eq!(lines[i - 1], "----- output end -----");
+    assert_eq!(lines[i - 2], format!("end {name}"));
+    assert_eq!(lines[i - 3], format!("start {name}"));
+    assert_eq!(lines[i - 4], "------- output -------");
+    results += 1;
+  }
+  assert_eq!(results, 60);
+  assert_contains!(text, "ok | 60 passed | 0 failed");
+}
+
 #[test]
 // todo(#18480): re-enable
 #[ignore]
@@ -669,3 +853,67 @@ itest!(test_include_relative_pattern_dot_slash {
   output: "test/relative_pattern_dot_slash/output.out",
   cwd: Some("test/relative_pattern_dot_slash"),
 });
+
+#[test]
+fn test_shard() {
+  let context = TestContextBuilder::new().cwd("test").build();
+  let modules_of = |args: &str| {
+    let cmd_output = context.new_command().args(args).run();
+    cmd_output.assert_exit_code(0);
+    let mut modules = cmd_output
+      .combined_output()
+      .lines()
+      .filter_map(|line| line.split_once(" from ").map(|(_, m)| m.to_string()))
+      .collect::<Vec<_>>();
+    modules.sort();
+    modules
+  };
+
+  let all_modules = modules_of("test --config deno.glob.json");
+  let first_shard = modules_of("test --config deno.glob.json --shard=1/2");
+  let second_shard = modules_of("test --config deno.glob.json --shard=2/2");
+  assert_eq!(
+    first_shard,
+    modules_of("test --config deno.glob.json --shard=1/2")
+  );
+  let mut sharded_modules = [first_shard, second_shard].concat();
+  sharded_modules.sort();
+  assert_eq!(sharded_modules, all_modules);
+
+  // the slowest module of the previous run is assigned first
+  let module_url =
+    |path: &str| util::testdata_path().join("test").join(path).uri_file();
+  context.temp_dir().write(
+    "report.xml",
+    format!(
+      r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="deno test" tests="2" failures="0" errors="0" time="100.001">
+    <testsuite name="{}" tests="1" disabled="0" errors="0" failures="0">
+        <testcase name="slow" time="100.000"/>
+    </testsuite>
+    <testsuite name="{}" tests="1" disabled="0" errors="0" failures="0">
+        <testcase name="fast" time="0.001"/>
+    </testsuite>
+</testsuites>
+"#,
+      module_url("glob/data/test1.ts"),
+      module_url("glob/data/test1.js"),
+    ),
+  );
+  let timings_arg = format!(
+    "--shard-timings={}",
+    context.temp_dir().path().join("report.xml")
+  );
+  let shard_output = |shard: &str| {
+    let cmd_output = context
+      .new_command()
+      .args_vec(["test", "--config", "deno.glob.json", shard, &timings_arg])
+      .run();
+    cmd_output.assert_exit_code(0);
+    cmd_output.combined_output().to_string()
+  };
+  let output = shard_output("--shard=1/2");
+  assert_contains!(output, "glob/data/test1.ts");
+  let output = shard_output("--shard=2/2");
+  assert_not_contains!(output, "glob/data/test1.ts");
+}
@@ -566,9 +566,10 @@ fn vendor_npm_node_specifiers() {
   // now try re-vendoring with a lockfile
   let output = context.new_command().args("vendor --force my_app.ts").run();
   output.assert_matches_text(format!(
-    "{}\n{}\n\n{}\n",
+    "{}\n{}\n{}\n\n{}\n",
     ignoring_import_map_text(),
     vendored_text("1 module", "vendor/"),
+    revendored_npm_package_text("1 npm package"),
     success_text_updated_deno_json("vendor/"),
   ));
 
@@ -597,8 +598,9 @@ fn vendor_npm_node_specifiers() {
     .args("vendor --node-modules-dir --force my_app.ts")
     .run();
   output.assert_matches_text(format!(
-    "Initialize @denotest/esm-basic@1.0.0\n{}\n\n{}\n",
+    "Initialize @denotest/esm-basic@1.0.0\n{}\n{}\n\n{}\n",
     vendored_text("1 module", "vendor/"),
+    revendored_npm_package_text("1 npm package"),
     use_import_map_text("vendor/")
   ));
 }
@@ -633,6 +635,59 @@ fn vendor_only_npm_specifiers() {
   );
 }
 
+#[test]
+fn vendor_npm_packages_offline() {
+  let context = TestContextBuilder::for_npm().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write(
+    "my_app.ts",
+    concat!(
+      "import { getValue, setValue } from 'npm:@denotest/esm-basic';\n",
+      "setValue(5);\n",
+      "console.log(getValue());",
+    ),
+  );
+  temp_dir.write("deno.json", "{}");
+
+  context
+    .new_command()
+    .args("vendor my_app.ts")
+    .run()
+    .skip_output_check()
+    .assert_exit_code(0);
+  let registry_file = temp_dir.path().join("node_modules/.deno/.registry.json");
+  let registry_infos = registry_file.read_json_value();
+  let versions = registry_infos["@denotest/esm-basic"]["versions"]
+    .as_object()
+    .unwrap();
+  assert_eq!(versions.keys().collect::<Vec<_>>(), vec!["1.0.0"]);
+
+  // a package which isn't used anymore is removed when re-vendoring
+  temp_dir.create_dir_all("node_modules/.deno/@denotest+unused@1.0.0");
+  context
+    .new_command()
+    .args("vendor --force my_app.ts")
+    .run()
+    .skip_output_check()
+    .assert_exit_code(0);
+  assert!(!temp_dir
+    .path()
+    .join("node_modules/.deno/@denotest+unused@1.0.0")
+    .exists());
+  assert!(temp_dir
+    .path()
+    .join("node_modules/.deno/@denotest+esm-basic@1.0.0")
+    .exists());
+
+  // the vendored packages work without the global npm cache
+  context.deno_dir().remove_dir_all("./npm");
+  let output = context
+    .new_command()
+    .args("run --cached-only my_app.ts")
+    .run();
+  output.assert_matches_text("5\n");
+}
+
 fn success_text(module_count: &str, dir: &str, has_import_map: bool) -> String {
   let mut text = format!("Vendored {module_count} into {dir} directory.");
   if has_import_map {
@@ -670,6 +725,10 @@ fn vendored_npm_package_text(package_count: &str) -> String {
   )
 }
 
+fn revendored_npm_package_text(package_count: &str) -> String {
+  format!("Vendored {} into node_modules directory.", package_count)
+}
+
 fn success_text_updated_deno_json(dir: &str) -> String {
   format!(
     concat!(
@@ -349,6 +349,43 @@ async fn lint_all_files_on_each_change_test() {
   drop(t);
 }
 
+#[tokio::test]
+async fn lint_watch_changed_and_failing_files_test() {
+  let t = TempDir::new();
+  let badly_linted = util::testdata_path().join("lint/watch/badly_linted.js");
+  std::fs::copy(badly_linted, t.path().join("badly_linted.js")).unwrap();
+  let clean_1 = t.path().join("clean_1.js");
+  let clean_2 = t.path().join("clean_2.js");
+  clean_1.write("export const a = 1;\n");
+  clean_2.write("export const b = 2;\n");
+
+  let mut child = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("lint")
+    .arg(t.path())
+    .arg("--watch")
+    .arg("--unstable")
+    .piped_output()
+    .spawn()
+    .unwrap();
+  let (_stdout_lines, mut stderr_lines) = child_lines(&mut child);
+
+  assert_contains!(
+    wait_contains("Checked", &mut stderr_lines).await,
+    "Checked 3 files"
+  );
+
+  // only the changed file and the file which still has problems
+  clean_1.write("export const a = 3;\n");
+
+  assert_contains!(
+    wait_contains("Checked", &mut stderr_lines).await,
+    "Checked 2 files"
+  );
+
+  check_alive_then_kill(child);
+}
+
 #[tokio::test]
 async fn fmt_watch_test() {
   let fmt_testdata_path = util::testdata_path().join("fmt");
@@ -1735,6 +1772,66 @@ export function foo() {
   check_alive_then_kill(child);
 }
 
+#[tokio::test]
+async fn serve_hmr() {
+  let t = TempDir::new();
+  let file_to_watch = t.path().join("file_to_watch.js");
+  file_to_watch.write(
+    r#"
+import { greet } from "./greet.js";
+
+export default {
+  fetch(_req) {
+    return new Response(greet());
+  },
+  onHmr(path) {
+    console.log("onHmr", path.endsWith("greet.js"));
+  },
+};
+"#,
+  );
+  let file_to_watch2 = t.path().join("greet.js");
+  file_to_watch2.write(
+    r#"
+export function greet() {
+  return "Hello";
+}
+"#,
+  );
+
+  let mut child = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("serve")
+    .arg("--watch-hmr")
+    .arg("--allow-net")
+    .arg("--port")
+    .arg("11112")
+    .arg("-L")
+    .arg("debug")
+    .arg(&file_to_watch)
+    .env("NO_COLOR", "1")
+    .piped_output()
+    .spawn()
+    .unwrap();
+  let (mut stdout_lines, mut stderr_lines) = child_lines(&mut child);
+  wait_contains("Process started", &mut stderr_lines).await;
+  wait_for_watcher("file_to_watch.js", &mut stderr_lines).await;
+  wait_contains("Listening on", &mut stdout_lines).await;
+
+  file_to_watch2.write(
+    r#"
+export function greet() {
+  return "Hello world";
+}
+"#,
+  );
+
+  wait_contains("Replaced changed module", &mut stderr_lines).await;
+  wait_contains("onHmr true", &mut stdout_lines).await;
+
+  check_alive_then_kill(child);
+}
+
 #[tokio::test]
 async fn run_hmr_uncaught_error() {
   let t = TempDir::new();
@@ -1862,3 +1959,47 @@ setInterval(() => {
 
   check_alive_then_kill(child);
 }
+
+#[tokio::test]
+async fn task_watch_restarts_on_change() {
+  let t = TempDir::new();
+  t.path().join("deno.json").write(
+    r#"{
+      "tasks": {
+        "dev": {
+          "command": "cat src/message.txt",
+          "watch": ["src/**/*.txt"]
+        }
+      }
+    }"#,
+  );
+  t.path().join("src").create_dir_all();
+  let message_file = t.path().join("src/message.txt");
+  message_file.write("first");
+
+  let mut child = util::deno_cmd()
+    .current_dir(t.path())
+    .arg("-L")
+    .arg("debug")
+    .arg("task")
+    .arg("dev")
+    .env("NO_COLOR", "1")
+    .piped_output()
+    .spawn()
+    .unwrap();
+  let (mut stdout_lines, mut stderr_lines) = child_lines(&mut child);
+
+  wait_contains("first", &mut stdout_lines).await;
+  wait_for_watcher("src", &mut stderr_lines).await;
+
+  message_file.write("second");
+  wait_contains("Restarting", &mut stderr_lines).await;
+  wait_contains("second", &mut stdout_lines).await;
+
+  // the directories of the globs are watched, so new files are seen too
+  wait_for_watcher("src", &mut stderr_lines).await;
+  t.path().join("src/other.txt").write("other");
+  wait_contains("Restarting", &mut stderr_lines).await;
+  wait_contains("second", &mut stdout_lines).await;
+  check_alive_then_kill(child);
+}
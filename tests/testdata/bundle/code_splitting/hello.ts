@@ -0,0 +1,5 @@
+import { name } from "./name.ts";
+
+export function hello(): string {
+  return `Hello from ${name}`;
+}
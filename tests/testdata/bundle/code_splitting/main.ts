@@ -0,0 +1,2 @@
+const { hello } = await import("./hello.ts");
+console.log(hello());
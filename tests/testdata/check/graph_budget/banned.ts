@@ -0,0 +1 @@
+export const banned = 1;
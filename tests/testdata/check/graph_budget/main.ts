@@ -0,0 +1,3 @@
+import { banned } from "./banned.ts";
+
+console.log(banned);
@@ -0,0 +1,12 @@
+interface Options {
+  message: string;
+}
+
+type Callback = () => void;
+
+function boom(options: Options): never {
+  throw new Error(options.message);
+}
+
+const callback: Callback = () => boom({ message: "boom!" });
+callback();
@@ -0,0 +1,23 @@
+/** Adds two numbers. */
+export function add(a: number, b: number): number {
+  return a + b;
+}
+
+/**
+ * Subtracts two numbers.
+ *
+ * @example
+ * ```ts
+ * subtract(2, 1);
+ * ```
+ */
+export function subtract(a: number, b: number): number {
+  return a - b;
+}
+
+interface Options {
+  verbose?: boolean;
+}
+
+/** Runs the program. */
+export function run(_options: Options): void {}
@@ -0,0 +1,28 @@
+/**
+ * Utilities for greeting.
+ *
+ * @module
+ */
+
+/** A greeter. */
+export class Greeter {
+  /** The greeting of the greeter. */
+  greeting = "Hello";
+
+  /** Greets someone. */
+  greet(name: string): string {
+    return `${this.greeting}, ${name}!`;
+  }
+}
+
+/**
+ * Creates a greeter.
+ *
+ * @example
+ * ```ts
+ * createGreeter().greet("Deno");
+ * ```
+ */
+export function createGreeter(): Greeter {
+  return new Greeter();
+}
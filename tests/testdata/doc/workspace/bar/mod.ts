@@ -0,0 +1,5 @@
+/** A bar. */
+export interface Bar {
+  /** The name of the bar. */
+  name: string;
+}
@@ -0,0 +1,6 @@
+import type { Bar } from "../bar/mod.ts";
+
+/** Creates a bar. */
+export function createBar(name: string): Bar {
+  return { name };
+}
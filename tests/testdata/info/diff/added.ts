@@ -0,0 +1 @@
+export const added = "added";
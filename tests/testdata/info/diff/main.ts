@@ -0,0 +1,4 @@
+import { dep } from "./dep.ts";
+import { added } from "./added.ts";
+
+console.log(dep, added);
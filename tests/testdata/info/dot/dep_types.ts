@@ -0,0 +1 @@
+export type Dep = string;
@@ -0,0 +1,5 @@
+import { dep } from "./dep.ts";
+import type { Dep } from "./dep_types.ts";
+
+const value: Dep = dep;
+console.log(value, import("./dynamic.ts"));
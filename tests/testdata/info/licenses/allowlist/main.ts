@@ -0,0 +1,5 @@
+import { getValue } from "npm:@denotest/licensed@1";
+import { printHello } from "http://localhost:4545/subdir/print_hello.ts";
+
+console.log(getValue());
+printHello();
@@ -0,0 +1,3 @@
+import { getValue } from "npm:@denotest/licensed@1";
+
+console.log(getValue());
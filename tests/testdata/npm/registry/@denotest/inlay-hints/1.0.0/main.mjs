@@ -0,0 +1,5 @@
+export function add(a, b) {
+  return a + b;
+}
+
+export const sum = add(1, 2);
@@ -0,0 +1,3 @@
+import fs from "node:fs";
+
+fs.writeFileSync("installed.txt", "installed");
@@ -0,0 +1 @@
+export { getValue } from "@denotest/esm-basic";
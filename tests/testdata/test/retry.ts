@@ -0,0 +1,21 @@
+let flakyAttempts = 0;
+Deno.test("flaky", () => {
+  flakyAttempts++;
+  if (flakyAttempts < 2) {
+    throw new Error("flaky failure");
+  }
+});
+
+let retryOptionAttempts = 0;
+Deno.test({ name: "retry option", retry: 2 }, () => {
+  retryOptionAttempts++;
+  if (retryOptionAttempts < 3) {
+    throw new Error("flaky failure");
+  }
+});
+
+Deno.test("always fails", () => {
+  throw new Error("always fails");
+});
+
+Deno.test("passes", () => {});
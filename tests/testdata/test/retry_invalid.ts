@@ -0,0 +1 @@
+Deno.test({ name: "invalid", retry: -1 }, () => {});
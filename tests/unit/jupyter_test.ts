@@ -4,6 +4,8 @@ import { assertEquals, assertThrows } from "./test_util.ts";
 
 // @ts-expect-error TypeScript (as of 3.7) does not support indexing namespaces by symbol
 const format = Deno[Deno.internal].jupyter.formatInner;
+// @ts-expect-error TypeScript (as of 3.7) does not support indexing namespaces by symbol
+const formatProgress = Deno[Deno.internal].jupyter.formatProgress;
 
 Deno.test("Deno.jupyter is not available", () => {
   assertThrows(
@@ -77,3 +79,15 @@ Deno.test(
     await assertFormattedAs(example, { "application/json": { x: 3 } });
   },
 );
+
+Deno.test("progress bars are formatted as text and HTML", () => {
+  assertEquals(formatProgress(3, 10, "Files"), {
+    "text/plain": "Files [#########                     ] 3/10 (30%)",
+    "text/html":
+      '<div><span>Files</span> <progress value="3" max="10"></progress> 3/10 (30%)</div>',
+  });
+  assertEquals(
+    formatProgress(20, 10, "")["text/plain"],
+    "[##############################] 20/10 (100%)",
+  );
+});